@@ -3,9 +3,12 @@
 //! This module contains exit codes, tracing setup, and error hints
 //! that support the main entry point.
 
-use ddns_a::config::{ConfigError, field};
+use ddns_a::config::{ConfigError, Locale, Message, field};
+use ddns_a::logging::{LogTarget, SystemLogLayer, open_system_log};
 use tracing::Level;
 use tracing_subscriber::EnvFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
 
 /// Application exit codes.
 pub mod exit_code {
@@ -23,33 +26,55 @@ pub mod exit_code {
     pub fn runtime_error() -> ExitCode {
         ExitCode::from(2)
     }
+
+    /// Changes found (exit code 3) - `check` saw the addresses differ from the state file.
+    pub fn changes_found() -> ExitCode {
+        ExitCode::from(3)
+    }
 }
 
-/// Prints helpful hints for common configuration errors.
-pub fn print_config_hint(error: &ConfigError) {
+/// Prints helpful hints for common configuration errors in `locale`.
+pub fn print_config_hint(error: &ConfigError, locale: Locale) {
     match error {
-        ConfigError::MissingRequired { field: f, .. } => {
-            if *f == field::URL || *f == field::IP_VERSION {
-                eprintln!("\nRun 'ddns-a init' to generate a configuration template.");
-            }
+        ConfigError::MissingRequired { field: f, .. }
+            if *f == field::URL || *f == field::IP_VERSION =>
+        {
+            eprintln!("\n{}", locale.text(Message::InitHint));
         }
         ConfigError::FileRead { .. } => {
-            eprintln!("\nRun 'ddns-a init' to generate a configuration template.");
+            eprintln!("\n{}", locale.text(Message::InitHint));
         }
         _ => {}
     }
 }
 
-/// Sets up the tracing subscriber for logging.
-pub fn setup_tracing(verbose: bool) {
+/// Sets up the tracing subscriber for logging to `target`.
+///
+/// Falls back to stderr, with a warning, if the system log cannot be opened.
+pub fn setup_tracing(verbose: bool, target: LogTarget) {
     let level = if verbose { Level::DEBUG } else { Level::INFO };
 
     let filter = EnvFilter::builder()
         .with_default_directive(level.into())
         .from_env_lossy();
 
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .with_target(false)
+    let (system, error) = match target {
+        LogTarget::Stderr => (None, None),
+        LogTarget::System => match open_system_log() {
+            Ok(sink) => (Some(SystemLogLayer::new(sink)), None),
+            Err(e) => (None, Some(e)),
+        },
+    };
+    let stderr = system
+        .is_none()
+        .then(|| tracing_subscriber::fmt::layer().with_target(false));
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(stderr)
+        .with(system)
         .init();
+    if let Some(e) = error {
+        tracing::warn!("{e}; logging to stderr instead");
+    }
 }
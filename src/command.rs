@@ -0,0 +1,518 @@
+//! Subcommands run by the entry point instead of the monitor.
+
+use ddns_a::config::{
+    Command, CtlCommand, InitMode, InitOutcome, Locale, Message, ServiceCommand, TemplateCommand,
+    ValidatedConfig, write_config_from_snapshot, write_default_config,
+};
+use ddns_a::logging::LogTarget;
+use ddns_a::monitor::summarize;
+use ddns_a::network::IpVersion;
+use ddns_a::report::{HistoryFilter, HistoryLog, format_record};
+use ddns_a::state::{load_snapshots, replay};
+use ddns_a::update::{UpdateOutcome, Updater, Version};
+use ddns_a::webhook::{GoldenDir, check_template, sample_changes, synthetic_changes};
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::app::{exit_code, setup_tracing};
+use crate::run;
+
+/// Runs the subcommands that need the configuration but not the monitor;
+/// `None` for the others.
+pub fn run_config_command(command: Option<&Command>, config: &ValidatedConfig) -> Option<ExitCode> {
+    let exit = match command? {
+        Command::SelfUpdate {
+            check_only,
+            reinstall,
+        } => {
+            setup_tracing(config.verbose, LogTarget::Stderr);
+            run_self_update(config, *check_only, *reinstall)
+        }
+        Command::Ctl {
+            command: CtlCommand::Restart,
+        } => run_ctl_restart(config),
+        Command::Service {
+            command: ServiceCommand::Install,
+        } => run_service_install(config),
+        Command::Replay { from, to, notify } => {
+            setup_tracing(config.verbose, LogTarget::Stderr);
+            run_replay(config, from, to, *notify)
+        }
+        Command::Template {
+            command: TemplateCommand::Verify { dir },
+        } => run_template_verify(config, dir),
+        Command::Status { as_json } => run_status(config, *as_json),
+        Command::History {
+            since,
+            adapter,
+            history_file,
+        } => {
+            setup_tracing(config.verbose, LogTarget::Stderr);
+            let filter = HistoryFilter {
+                since: since.map(|age| SystemTime::now().checked_sub(age).unwrap_or(UNIX_EPOCH)),
+                adapter: adapter.clone(),
+            };
+            run_history(config, history_file.as_deref(), &filter)
+        }
+        _ => return None,
+    };
+    Some(exit)
+}
+
+/// Handles the `init` subcommand.
+pub fn handle_init(
+    output: &std::path::Path,
+    mode: InitMode,
+    from_snapshot: Option<&std::path::Path>,
+    locale: Locale,
+) -> ExitCode {
+    let result = from_snapshot.map_or_else(
+        || write_default_config(output, mode),
+        |snapshot| write_config_from_snapshot(output, mode, snapshot),
+    );
+
+    match result {
+        Ok(InitOutcome::Created | InitOutcome::Overwritten) => {
+            if let Some(snapshot) = from_snapshot {
+                println!(
+                    "Configuration with filters from {} written to: {}",
+                    snapshot.display(),
+                    output.display()
+                );
+            } else {
+                println!("Configuration template written to: {}", output.display());
+            }
+            exit_code::SUCCESS
+        }
+        Ok(InitOutcome::Merged { added }) if added.is_empty() => {
+            println!("{} is up to date, no options added", output.display());
+            exit_code::SUCCESS
+        }
+        Ok(InitOutcome::Merged { added }) => {
+            println!("Added to {}: {}", output.display(), added.join(", "));
+            exit_code::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}: {}", locale.text(Message::Error), e.localized(locale));
+            exit_code::CONFIG_ERROR
+        }
+    }
+}
+
+/// Handles the `template check` subcommand, printing the rendered template.
+pub fn handle_template_check(
+    file: &Path,
+    sample: Option<&Path>,
+    json: bool,
+    version: IpVersion,
+    locale: Locale,
+) -> ExitCode {
+    let read = |path: &Path| {
+        std::fs::read_to_string(path).map_err(|e| format!("cannot read {}: {e}", path.display()))
+    };
+    let result = read(file).and_then(|template| {
+        let changes = match sample {
+            Some(path) => sample_changes(&read(path)?)
+                .map_err(|e| format!("invalid sample {}: {e}", path.display()))?,
+            None => synthetic_changes(version, SystemTime::now()),
+        };
+        check_template(&template, &changes, json)
+    });
+
+    match result {
+        Ok(rendered) => {
+            println!("{rendered}");
+            exit_code::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}: {e}", locale.text(Message::TemplateError));
+            exit_code::CONFIG_ERROR
+        }
+    }
+}
+
+/// Runs the `test-webhook` subcommand, exiting non-zero if any selected
+/// target failed or is not configured.
+///
+/// Excluded from coverage - requires async runtime and network access.
+#[cfg(not(tarpaulin_include))]
+pub fn run_webhook_test(config: &ValidatedConfig, selection: &run::TargetSelection) -> ExitCode {
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+
+    match runtime.block_on(run::test_webhooks(config, selection)) {
+        Ok(true) => exit_code::SUCCESS,
+        Ok(false) => exit_code::runtime_error(),
+        Err(e) => {
+            eprintln!("{}: {e}", config.locale.text(Message::Error));
+            exit_code::CONFIG_ERROR
+        }
+    }
+}
+
+/// Runs the `send-test` subcommand, exiting non-zero if the delivery failed.
+///
+/// Excluded from coverage - requires async runtime and network access.
+#[cfg(not(tarpaulin_include))]
+pub fn run_send_test(config: &ValidatedConfig) -> ExitCode {
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+
+    match runtime.block_on(run::send_test(config)) {
+        Ok(()) => exit_code::SUCCESS,
+        Err(e) => {
+            eprintln!("{}: {e}", config.locale.text(Message::Error));
+            exit_code::runtime_error()
+        }
+    }
+}
+
+/// Runs the `self-update` subcommand.
+///
+/// Excluded from coverage - requires async runtime and network access.
+#[cfg(not(tarpaulin_include))]
+fn run_self_update(config: &ValidatedConfig, check_only: bool, reinstall: bool) -> ExitCode {
+    let error = config.locale.text(Message::Error);
+    let Some(settings) = config.update.clone() else {
+        eprintln!(
+            "{error}: self-update is disabled; set enabled = true and public_key in [update]"
+        );
+        return exit_code::CONFIG_ERROR;
+    };
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            eprintln!("{error}: cannot locate the running executable: {e}");
+            return exit_code::runtime_error();
+        }
+    };
+
+    let updater = Updater::new(run::http_client(&config.http, None), settings)
+        .with_user_agent(config.http.user_agent.clone());
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+
+    match runtime.block_on(updater.run(&exe, check_only, reinstall)) {
+        Ok(UpdateOutcome::UpToDate(version)) => println!("ddns-a {version} is up to date"),
+        Ok(UpdateOutcome::Available(version)) => {
+            println!(
+                "ddns-a {version} is available (running {})",
+                Version::current()
+            );
+        }
+        Ok(UpdateOutcome::Installed(version)) => {
+            println!(
+                "Updated {} to ddns-a {version}; restart ddns-a to run it",
+                exe.display()
+            );
+        }
+        Err(e) => {
+            eprintln!("{error}: {e}");
+            return exit_code::runtime_error();
+        }
+    }
+    exit_code::SUCCESS
+}
+
+/// Runs the `replay` subcommand, printing the changes between two state
+/// files and sending them with `notify`.
+///
+/// Excluded from coverage - sending requires async runtime and network access.
+#[cfg(not(tarpaulin_include))]
+fn run_replay(config: &ValidatedConfig, from: &Path, to: &Path, notify: bool) -> ExitCode {
+    let states = load_snapshots(from).and_then(|old| Ok((old, load_snapshots(to)?)));
+    let (old, new) = match states {
+        Ok(states) => states,
+        Err(e) => {
+            eprintln!("{}: {e}", config.locale.text(Message::Error));
+            return exit_code::CONFIG_ERROR;
+        }
+    };
+
+    let changes = replay(
+        &old,
+        &new,
+        &config.monitor.filter,
+        config.ip_version,
+        SystemTime::now(),
+    );
+    if changes.is_empty() {
+        println!("No changes from {} to {}", from.display(), to.display());
+        return exit_code::SUCCESS;
+    }
+    for line in summarize(&changes) {
+        println!("{line}");
+    }
+    if !notify {
+        return exit_code::SUCCESS;
+    }
+
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+    if runtime.block_on(run::notify_replayed(config, &changes)) {
+        exit_code::SUCCESS
+    } else {
+        exit_code::runtime_error()
+    }
+}
+
+/// Runs the `history` subcommand, printing the records `filter` matches
+/// from `file`, or else the configured history file.
+fn run_history(config: &ValidatedConfig, file: Option<&Path>, filter: &HistoryFilter) -> ExitCode {
+    let error = config.locale.text(Message::Error);
+    let Some(path) = file.or(config.outputs.history.as_deref()) else {
+        eprintln!("{error}: history needs a file; set history_file in [output] or pass --file");
+        return exit_code::CONFIG_ERROR;
+    };
+
+    let records = match HistoryLog::new(path).read() {
+        Ok(records) => records,
+        Err(e) => {
+            eprintln!("{error}: cannot read {}: {e}", path.display());
+            return exit_code::runtime_error();
+        }
+    };
+    let mut matching = records
+        .iter()
+        .filter(|record| filter.matches(record))
+        .peekable();
+    if matching.peek().is_none() {
+        println!("No matching records in {}", path.display());
+    }
+    for record in matching {
+        println!("{}", format_record(record));
+    }
+    exit_code::SUCCESS
+}
+
+/// Handles the `template verify` subcommand.
+///
+/// Renders every recorded case in `dir` with the loaded configuration and
+/// fails if any payload differs from its golden, or if there are none.
+fn run_template_verify(config: &ValidatedConfig, dir: &Path) -> ExitCode {
+    let cases = match GoldenDir::new(dir).cases() {
+        Ok(cases) => cases,
+        Err(e) => {
+            eprintln!("{}: {e}", config.locale.text(Message::Error));
+            return exit_code::CONFIG_ERROR;
+        }
+    };
+    if cases.is_empty() {
+        eprintln!("No golden files in {}", dir.display());
+        return exit_code::CONFIG_ERROR;
+    }
+
+    let results = run::verify_goldens(config, &cases);
+    for (case, result) in cases.iter().zip(&results) {
+        match result {
+            Ok(()) => println!("ok     {}", case.name),
+            Err(diff) => {
+                println!("FAILED {}", case.name);
+                for line in diff.lines() {
+                    println!("    {line}");
+                }
+            }
+        }
+    }
+
+    let matching = results.iter().filter(|r| r.is_ok()).count();
+    println!(
+        "{matching} of {} payloads match their golden files",
+        cases.len()
+    );
+    if matching == cases.len() {
+        exit_code::SUCCESS
+    } else {
+        exit_code::runtime_error()
+    }
+}
+
+/// Runs the `status` subcommand, asking the running instance through its
+/// health endpoint; exits non-zero if it is unreachable or unhealthy.
+///
+/// Excluded from coverage - requires a running instance.
+#[cfg(not(tarpaulin_include))]
+fn run_status(config: &ValidatedConfig, as_json: bool) -> ExitCode {
+    let error = config.locale.text(Message::Error);
+    let Some(address) = config.health else {
+        eprintln!(
+            "{error}: status needs the health endpoint; set bind in [health] or pass --health"
+        );
+        return exit_code::CONFIG_ERROR;
+    };
+
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+    let report = match runtime.block_on(ddns_a::health::fetch_status(address)) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("{error}: {e}");
+            return exit_code::runtime_error();
+        }
+    };
+
+    if as_json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).expect("status report is always serializable")
+        );
+    } else {
+        print!("{}", ddns_a::health::format_status(&report));
+    }
+    if report.health.healthy {
+        exit_code::SUCCESS
+    } else {
+        exit_code::runtime_error()
+    }
+}
+
+/// Runs the `check` subcommand; exits with [`exit_code::changes_found`] if
+/// the addresses differ from the state file.
+///
+/// Excluded from coverage - requires platform APIs.
+#[cfg(not(tarpaulin_include))]
+pub fn run_check(config: ValidatedConfig) -> ExitCode {
+    let error = config.locale.text(Message::Error);
+    // Public address lookups block on the runtime they are called in
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+    let _guard = runtime.enter();
+
+    match run::check(config) {
+        Ok(false) => exit_code::SUCCESS,
+        Ok(true) => exit_code::changes_found(),
+        Err(e) => {
+            eprintln!("{error}: {e}");
+            exit_code::runtime_error()
+        }
+    }
+}
+
+/// Runs the `ctl restart` subcommand, signalling the instance named by
+/// `--pidfile`.
+#[cfg(unix)]
+fn run_ctl_restart(config: &ValidatedConfig) -> ExitCode {
+    let error = config.locale.text(Message::Error);
+    let Some(ref pid_file) = config.pid_file else {
+        eprintln!("{error}: ctl restart needs --pidfile to find the running instance");
+        return exit_code::CONFIG_ERROR;
+    };
+
+    match ddns_a::daemon::signal_restart(pid_file) {
+        Ok(pid) => {
+            println!("Asked ddns-a (pid {pid}) to restart");
+            exit_code::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{error}: {e}");
+            exit_code::runtime_error()
+        }
+    }
+}
+
+/// Runs the `ctl restart` subcommand; only Unix instances can be signalled.
+#[cfg(not(unix))]
+fn run_ctl_restart(config: &ValidatedConfig) -> ExitCode {
+    eprintln!(
+        "{}: ctl restart is only supported on Unix",
+        config.locale.text(Message::Error)
+    );
+    exit_code::CONFIG_ERROR
+}
+
+/// Runs the `service install` subcommand, registering this executable with
+/// the configuration file that was loaded.
+#[cfg(windows)]
+fn run_service_install(config: &ValidatedConfig) -> ExitCode {
+    let error = config.locale.text(Message::Error);
+    // Services start in the system directory, so the file must be named absolutely
+    let Some(config_file) = config.config_file.as_deref().map(std::path::absolute) else {
+        eprintln!("{error}: service install needs a configuration file; pass --config");
+        return exit_code::CONFIG_ERROR;
+    };
+    let paths = config_file.and_then(|file| Ok((std::env::current_exe()?, file)));
+    let (exe, config_file) = match paths {
+        Ok(paths) => paths,
+        Err(e) => {
+            eprintln!("{error}: {e}");
+            return exit_code::runtime_error();
+        }
+    };
+
+    match ddns_a::service::install(&exe, &config_file) {
+        Ok(()) => {
+            println!(
+                "Installed service '{}' with {}; start it with: sc start {0}",
+                ddns_a::service::SERVICE_NAME,
+                config_file.display()
+            );
+            exit_code::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{error}: {e}");
+            exit_code::runtime_error()
+        }
+    }
+}
+
+/// Runs the `service install` subcommand; services need Windows.
+#[cfg(not(windows))]
+fn run_service_install(config: &ValidatedConfig) -> ExitCode {
+    service_unsupported(config.locale)
+}
+
+/// Runs the `service uninstall` subcommand.
+#[cfg(windows)]
+pub fn run_service_uninstall(locale: Locale) -> ExitCode {
+    match ddns_a::service::uninstall() {
+        Ok(()) => {
+            println!("Removed service '{}'", ddns_a::service::SERVICE_NAME);
+            exit_code::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}: {e}", locale.text(Message::Error));
+            exit_code::runtime_error()
+        }
+    }
+}
+
+/// Runs the `service uninstall` subcommand; services need Windows.
+#[cfg(not(windows))]
+pub fn run_service_uninstall(locale: Locale) -> ExitCode {
+    service_unsupported(locale)
+}
+
+/// Runs the application under the service control manager, which stops it
+/// through the shutdown token.
+///
+/// Excluded from coverage - requires the Windows service control manager.
+#[cfg(not(tarpaulin_include))]
+#[cfg(windows)]
+pub fn run_as_service(config: ValidatedConfig) -> ExitCode {
+    let result = ddns_a::service::run(move |shutdown| {
+        let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+        runtime
+            .block_on(run::execute(config, shutdown))
+            .map(drop)
+            .map_err(|e| e.to_string())
+    });
+
+    match result {
+        Ok(()) => exit_code::SUCCESS,
+        Err(e) => {
+            tracing::error!("{e}");
+            exit_code::runtime_error()
+        }
+    }
+}
+
+/// Runs the `service run` subcommand; services need Windows.
+#[cfg(not(windows))]
+#[allow(clippy::needless_pass_by_value)] // Same signature as the Windows version
+pub fn run_as_service(config: ValidatedConfig) -> ExitCode {
+    service_unsupported(config.locale)
+}
+
+#[cfg(not(windows))]
+fn service_unsupported(locale: Locale) -> ExitCode {
+    eprintln!(
+        "{}: service is only supported on Windows",
+        locale.text(Message::Error)
+    );
+    exit_code::CONFIG_ERROR
+}
@@ -3,10 +3,14 @@
 //! Defines the command-line interface with all options and subcommands.
 
 use std::path::PathBuf;
+use std::time::Duration;
 
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
 
+use super::init::InitMode;
+use super::locale::{Locale, localize_command};
 use crate::network::AdapterKind;
+use crate::report::parse_age;
 
 /// DDNS-A: Dynamic DNS Address Monitor
 ///
@@ -45,6 +49,23 @@ pub struct Cli {
     #[arg(long = "body-template")]
     pub body_template: Option<String>,
 
+    /// DNS provider preset to update instead of a generic webhook
+    /// (duckdns, porkbun, gandi, desec, noip, dynu, freedns)
+    #[arg(long, value_name = "NAME")]
+    pub provider: Option<String>,
+
+    /// Domain of the provider preset (e.g. the `DuckDNS` subdomain or the hostname)
+    #[arg(long = "provider-domain", value_name = "DOMAIN")]
+    pub provider_domain: Option<String>,
+
+    /// API token of the provider preset
+    #[arg(long = "provider-token", value_name = "TOKEN")]
+    pub provider_token: Option<String>,
+
+    /// Secret of the provider preset (Porkbun secret API key, No-IP password)
+    #[arg(long = "provider-secret", value_name = "SECRET")]
+    pub provider_secret: Option<String>,
+
     /// Regex pattern for adapters to include (can be specified multiple times)
     #[arg(long = "include-adapter", value_name = "PATTERN")]
     pub include_adapters: Vec<String>,
@@ -77,18 +98,47 @@ pub struct Cli {
     #[arg(long = "retry-delay")]
     pub retry_delay: Option<u64>,
 
-    /// Path to configuration file
+    /// Path to configuration file (default: first found of ./ddns-a.toml,
+    /// the user config directory's ddns-a/config.toml, /etc/ddns-a/config.toml)
     #[arg(long, short)]
     pub config: Option<PathBuf>,
 
+    /// Do not search standard locations for a configuration file
+    #[arg(long = "no-config", conflicts_with = "config")]
+    pub no_config: bool,
+
     /// Path to state file for detecting changes across restarts
     #[arg(long = "state-file")]
     pub state_file: Option<PathBuf>,
 
+    /// Do not lock the state file against other instances using it
+    #[arg(long = "no-lock")]
+    pub no_lock: bool,
+
+    /// Write the process ID to this file (removed on shutdown)
+    #[arg(long = "pidfile", value_name = "PATH")]
+    pub pid_file: Option<PathBuf>,
+
+    /// Detach from the terminal and run in the background (Unix only)
+    #[arg(long)]
+    pub daemon: bool,
+
+    /// Show a tray icon with the current address and notification controls (Windows only)
+    #[arg(long)]
+    pub tray: bool,
+
+    /// Serve a /healthz health-check endpoint on ADDR (e.g. 127.0.0.1:8053)
+    #[arg(long, value_name = "ADDR")]
+    pub health: Option<String>,
+
     /// Test mode - log changes without sending webhooks
     #[arg(long)]
     pub dry_run: bool,
 
+    /// Test mode - write rendered payloads to DIR as golden files instead of sending them
+    #[arg(long, value_name = "DIR", conflicts_with = "dry_run")]
+    pub record_payloads: Option<PathBuf>,
+
     /// Enable verbose logging
     #[arg(long, short)]
     pub verbose: bool,
@@ -102,6 +152,147 @@ pub enum Command {
         /// Output path for the configuration file
         #[arg(long, short, default_value = "ddns-a.toml")]
         output: PathBuf,
+
+        /// Overwrite an existing file
+        #[arg(long, conflicts_with = "merge")]
+        force: bool,
+
+        /// Add options missing from an existing file, keeping its values
+        #[arg(long)]
+        merge: bool,
+
+        /// Include exactly the adapters in this JSON listing (adapter array or state file)
+        #[arg(long, value_name = "FILE", conflicts_with = "merge")]
+        from_snapshot: Option<PathBuf>,
+    },
+
+    /// Send a synthetic change to the main webhook (or other configured targets) and report the results
+    #[command(name = "test-webhook")]
+    TestWebhook {
+        /// Test every configured target: webhooks, chats, MQTT, email and the exec action
+        #[arg(long, conflicts_with = "targets")]
+        all: bool,
+
+        /// Test the target with this name instead of the main webhook (repeatable)
+        #[arg(long = "target", value_name = "NAME")]
+        targets: Vec<String>,
+    },
+
+    /// Send a synthetic change to the main webhook once and print each request with its response (--dry-run: only print the requests)
+    SendTest,
+
+    /// Work with webhook body templates
+    Template {
+        #[command(subcommand)]
+        command: TemplateCommand,
+    },
+
+    /// Replace this binary with the latest signed release (needs `[update] enabled = true`)
+    #[command(name = "self-update")]
+    SelfUpdate {
+        /// Only report whether a newer release is available
+        #[arg(long = "check")]
+        check_only: bool,
+
+        /// Install the latest release even if it is not newer
+        #[arg(long = "force", conflicts_with = "check_only")]
+        reinstall: bool,
+    },
+
+    /// Print the changes between two saved state files (or send them with --notify)
+    Replay {
+        /// Earlier state file, e.g. a backup
+        #[arg(long, value_name = "FILE")]
+        from: PathBuf,
+
+        /// Later state file
+        #[arg(long, value_name = "FILE")]
+        to: PathBuf,
+
+        /// Send the changes to the configured webhook
+        #[arg(long)]
+        notify: bool,
+    },
+
+    /// Show the state of the running instance through its health endpoint
+    Status {
+        /// Print the status report as JSON
+        #[arg(long = "json")]
+        as_json: bool,
+    },
+
+    /// Fetch the addresses once, print those passing the filters and compare them with the state file
+    Check,
+
+    /// Print the detected changes and delivery results recorded in the history file
+    History {
+        /// Only records from this long ago on, e.g. 90s, 30m, 24h or 7d
+        #[arg(long, value_name = "AGE", value_parser = parse_age)]
+        since: Option<Duration>,
+
+        /// Only records concerning this adapter
+        #[arg(long, value_name = "NAME")]
+        adapter: Option<String>,
+
+        /// History file to read (default: `output.history_file`)
+        #[arg(long = "file", value_name = "FILE")]
+        history_file: Option<PathBuf>,
+    },
+
+    /// Control the running instance named by --pidfile (Unix only)
+    Ctl {
+        #[command(subcommand)]
+        command: CtlCommand,
+    },
+
+    /// Run as a native Windows service (Windows only)
+    Service {
+        #[command(subcommand)]
+        command: ServiceCommand,
+    },
+}
+
+/// Subcommands of `ddns-a ctl`
+#[derive(Debug, Subcommand)]
+pub enum CtlCommand {
+    /// Restart with the binary now on disk, handing pending changes over through the state file
+    Restart,
+}
+
+/// Subcommands of `ddns-a service`
+#[derive(Debug, Subcommand)]
+pub enum ServiceCommand {
+    /// Register ddns-a with the configuration file found now as an automatically started service
+    Install,
+    /// Stop and remove the service
+    Uninstall,
+    /// Run under the service control manager (the installed service's command)
+    Run,
+}
+
+/// Subcommands of `ddns-a template`
+#[derive(Debug, Subcommand)]
+pub enum TemplateCommand {
+    /// Render a body template against sample changes and print the output
+    Check {
+        /// Handlebars template to check
+        #[arg(long, short)]
+        file: PathBuf,
+
+        /// JSON change array or captured payload (default: a synthetic change)
+        #[arg(long, value_name = "FILE")]
+        sample: Option<PathBuf>,
+
+        /// Require the rendered output to be valid JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Render recorded changes with the current configuration and diff them against their golden files
+    Verify {
+        /// Directory written by --record-payloads
+        #[arg(long, value_name = "DIR")]
+        dir: PathBuf,
     },
 }
 
@@ -159,10 +350,18 @@ impl From<AdapterKindArg> for AdapterKind {
 }
 
 impl Cli {
-    /// Parses CLI arguments from the command line.
+    /// Parses CLI arguments from the command line, with help in the
+    /// environment's [`Locale`].
     #[must_use]
     pub fn parse_args() -> Self {
-        Self::parse()
+        let matches = Self::localized_command(Locale::from_env()).get_matches();
+        Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit())
+    }
+
+    /// Returns the clap command with help translated to `locale`.
+    #[must_use]
+    pub fn localized_command(locale: Locale) -> clap::Command {
+        localize_command(Self::command(), locale)
     }
 
     /// Parses CLI arguments from an iterator (useful for testing).
@@ -174,6 +373,18 @@ impl Cli {
         Self::parse_from(iter)
     }
 
+    /// Returns how the init command treats an existing file, if this is
+    /// the init command.
+    #[must_use]
+    pub const fn init_mode(&self) -> Option<InitMode> {
+        match self.command {
+            Some(Command::Init { force: true, .. }) => Some(InitMode::Overwrite),
+            Some(Command::Init { merge: true, .. }) => Some(InitMode::Merge),
+            Some(Command::Init { .. }) => Some(InitMode::CreateNew),
+            _ => None,
+        }
+    }
+
     /// Returns true if this is the init command.
     #[must_use]
     pub const fn is_init(&self) -> bool {
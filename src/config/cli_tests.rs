@@ -1,6 +1,6 @@
 //! Tests for CLI argument parsing.
 
-use super::cli::{AdapterKindArg, Cli, Command, IpVersionArg};
+use super::cli::{AdapterKindArg, Cli, Command, CtlCommand, IpVersionArg};
 
 mod parsing {
     use super::*;
@@ -60,6 +60,31 @@ mod parsing {
         );
     }
 
+    #[test]
+    fn parse_provider_options() {
+        let cli = Cli::parse_from_iter([
+            "ddns-a",
+            "--provider",
+            "duckdns",
+            "--provider-domain",
+            "myhost",
+            "--provider-token",
+            "XYZ",
+        ]);
+
+        assert_eq!(cli.provider.as_deref(), Some("duckdns"));
+        assert_eq!(cli.provider_domain.as_deref(), Some("myhost"));
+        assert_eq!(cli.provider_token.as_deref(), Some("XYZ"));
+        assert_eq!(cli.provider_secret, None);
+    }
+
+    #[test]
+    fn parse_health_address() {
+        let cli = Cli::parse_from_iter(["ddns-a", "--health", "127.0.0.1:8053"]);
+
+        assert_eq!(cli.health.as_deref(), Some("127.0.0.1:8053"));
+    }
+
     #[test]
     fn parse_filter_options() {
         let cli = Cli::parse_from_iter([
@@ -119,6 +144,46 @@ mod parsing {
         assert!(cli.verbose);
     }
 
+    #[test]
+    fn parse_daemon_options() {
+        let cli = Cli::parse_from_iter(["ddns-a", "--pidfile", "/run/ddns-a.pid", "--daemon"]);
+
+        assert_eq!(
+            cli.pid_file.as_deref(),
+            Some(std::path::Path::new("/run/ddns-a.pid"))
+        );
+        assert!(cli.daemon);
+    }
+
+    #[test]
+    fn parse_no_lock() {
+        let cli = Cli::parse_from_iter(["ddns-a", "--state-file", "state.json", "--no-lock"]);
+
+        assert!(cli.no_lock);
+    }
+
+    #[test]
+    fn parse_tray() {
+        let cli = Cli::parse_from_iter(["ddns-a", "--tray"]);
+
+        assert!(cli.tray);
+    }
+
+    #[test]
+    fn parse_no_config() {
+        let cli = Cli::parse_from_iter(["ddns-a", "--no-config"]);
+
+        assert!(cli.no_config);
+    }
+
+    #[test]
+    fn no_config_conflicts_with_config() {
+        let result =
+            <Cli as clap::Parser>::try_parse_from(["ddns-a", "--no-config", "--config", "a.toml"]);
+
+        assert!(result.is_err());
+    }
+
     #[test]
     fn default_values() {
         let cli = Cli::parse_from_iter(["ddns-a"]);
@@ -132,6 +197,11 @@ mod parsing {
         assert!(!cli.poll_only);
         assert!(!cli.dry_run);
         assert!(!cli.verbose);
+        assert!(!cli.no_config);
+        assert!(!cli.daemon);
+        assert!(!cli.tray);
+        assert!(!cli.no_lock);
+        assert!(cli.pid_file.is_none());
         // Vec fields default to empty
         assert!(cli.include_kinds.is_empty());
         assert!(cli.exclude_kinds.is_empty());
@@ -140,6 +210,7 @@ mod parsing {
 
 mod init_command {
     use super::*;
+    use crate::config::InitMode;
     use std::path::PathBuf;
 
     #[test]
@@ -148,7 +219,7 @@ mod init_command {
 
         assert!(cli.is_init());
         match cli.command {
-            Some(Command::Init { output }) => {
+            Some(Command::Init { output, .. }) => {
                 assert_eq!(output, PathBuf::from("ddns-a.toml"));
             }
             _ => panic!("Expected Init command"),
@@ -161,7 +232,7 @@ mod init_command {
 
         assert!(cli.is_init());
         match cli.command {
-            Some(Command::Init { output }) => {
+            Some(Command::Init { output, .. }) => {
                 assert_eq!(output, PathBuf::from("/custom/path/config.toml"));
             }
             _ => panic!("Expected Init command"),
@@ -173,6 +244,358 @@ mod init_command {
         let cli = Cli::parse_from_iter(["ddns-a", "--url", "https://example.com"]);
 
         assert!(!cli.is_init());
+        assert_eq!(cli.init_mode(), None);
+    }
+
+    #[test]
+    fn init_mode_defaults_to_create_new() {
+        let cli = Cli::parse_from_iter(["ddns-a", "init"]);
+
+        assert_eq!(cli.init_mode(), Some(InitMode::CreateNew));
+    }
+
+    #[test]
+    fn init_force_overwrites() {
+        let cli = Cli::parse_from_iter(["ddns-a", "init", "--force"]);
+
+        assert_eq!(cli.init_mode(), Some(InitMode::Overwrite));
+    }
+
+    #[test]
+    fn init_merge() {
+        let cli = Cli::parse_from_iter(["ddns-a", "init", "--merge"]);
+
+        assert_eq!(cli.init_mode(), Some(InitMode::Merge));
+    }
+
+    #[test]
+    fn init_from_snapshot() {
+        let cli = Cli::parse_from_iter(["ddns-a", "init", "--from-snapshot", "adapters.json"]);
+
+        match cli.command {
+            Some(Command::Init { from_snapshot, .. }) => {
+                assert_eq!(from_snapshot, Some(PathBuf::from("adapters.json")));
+            }
+            _ => panic!("Expected Init command"),
+        }
+    }
+
+    #[test]
+    fn init_from_snapshot_conflicts_with_merge() {
+        let result = <Cli as clap::Parser>::try_parse_from([
+            "ddns-a",
+            "init",
+            "--merge",
+            "--from-snapshot",
+            "adapters.json",
+        ]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn init_force_conflicts_with_merge() {
+        let result =
+            <Cli as clap::Parser>::try_parse_from(["ddns-a", "init", "--force", "--merge"]);
+
+        assert!(result.is_err());
+    }
+}
+
+mod test_webhook_command {
+    use super::*;
+
+    #[test]
+    fn parse_test_webhook() {
+        let cli = Cli::parse_from_iter(["ddns-a", "test-webhook", "--url", "https://example.com"]);
+
+        assert!(matches!(
+            cli.command,
+            Some(Command::TestWebhook { all: false, ref targets }) if targets.is_empty()
+        ));
+        assert_eq!(cli.url.as_deref(), Some("https://example.com"));
+        assert!(!cli.is_init());
+    }
+
+    #[test]
+    fn parse_test_webhook_all() {
+        let cli = Cli::parse_from_iter(["ddns-a", "test-webhook", "--all"]);
+
+        assert!(matches!(
+            cli.command,
+            Some(Command::TestWebhook { all: true, .. })
+        ));
+    }
+
+    #[test]
+    fn parse_test_webhook_targets() {
+        let cli = Cli::parse_from_iter([
+            "ddns-a",
+            "test-webhook",
+            "--target",
+            "backup",
+            "--target",
+            "telegram",
+        ]);
+
+        assert!(matches!(
+            cli.command,
+            Some(Command::TestWebhook { all: false, ref targets }) if targets == &["backup", "telegram"]
+        ));
+    }
+
+    #[test]
+    fn all_conflicts_with_target() {
+        let result = <Cli as clap::Parser>::try_parse_from([
+            "ddns-a",
+            "test-webhook",
+            "--all",
+            "--target",
+            "a",
+        ]);
+
+        assert!(result.is_err());
+    }
+}
+
+mod template_command {
+    use super::*;
+    use crate::config::TemplateCommand;
+    use std::path::PathBuf;
+
+    #[test]
+    fn parse_template_check() {
+        let cli = Cli::parse_from_iter([
+            "ddns-a",
+            "template",
+            "check",
+            "--file",
+            "body.hbs",
+            "--sample",
+            "changes.json",
+            "--json",
+        ]);
+
+        assert!(!cli.is_init());
+        match cli.command {
+            Some(Command::Template {
+                command: TemplateCommand::Check { file, sample, json },
+            }) => {
+                assert_eq!(file, PathBuf::from("body.hbs"));
+                assert_eq!(sample, Some(PathBuf::from("changes.json")));
+                assert!(json);
+            }
+            _ => panic!("Expected template check command"),
+        }
+    }
+
+    #[test]
+    fn template_check_requires_file() {
+        let result = <Cli as clap::Parser>::try_parse_from(["ddns-a", "template", "check"]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_template_verify() {
+        let cli = Cli::parse_from_iter(["ddns-a", "template", "verify", "--dir", "goldens"]);
+
+        let Some(Command::Template {
+            command: TemplateCommand::Verify { dir },
+        }) = cli.command
+        else {
+            panic!("expected template verify command");
+        };
+        assert_eq!(dir, PathBuf::from("goldens"));
+    }
+}
+
+mod self_update_command {
+    use super::*;
+
+    #[test]
+    fn parse_self_update() {
+        let cli = Cli::parse_from_iter(["ddns-a", "self-update", "--check"]);
+
+        assert!(!cli.is_init());
+        assert!(matches!(
+            cli.command,
+            Some(Command::SelfUpdate {
+                check_only: true,
+                reinstall: false
+            })
+        ));
+    }
+
+    #[test]
+    fn check_conflicts_with_force() {
+        let result =
+            <Cli as clap::Parser>::try_parse_from(["ddns-a", "self-update", "--check", "--force"]);
+
+        assert!(result.is_err());
+    }
+}
+
+mod replay_command {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn parse_replay() {
+        let cli = Cli::parse_from_iter([
+            "ddns-a",
+            "replay",
+            "--from",
+            "state.bak",
+            "--to",
+            "state.json",
+            "--notify",
+        ]);
+
+        let Some(Command::Replay { from, to, notify }) = cli.command else {
+            panic!("expected replay command");
+        };
+        assert_eq!(from, PathBuf::from("state.bak"));
+        assert_eq!(to, PathBuf::from("state.json"));
+        assert!(notify);
+    }
+
+    #[test]
+    fn both_states_are_required() {
+        let result = <Cli as clap::Parser>::try_parse_from(["ddns-a", "replay", "--from", "a"]);
+
+        assert!(result.is_err());
+    }
+}
+
+mod status_command {
+    use super::*;
+
+    #[test]
+    fn parse_status() {
+        let cli = Cli::parse_from_iter(["ddns-a", "status"]);
+        let json = Cli::parse_from_iter(["ddns-a", "status", "--json"]);
+
+        assert!(matches!(
+            cli.command,
+            Some(Command::Status { as_json: false })
+        ));
+        assert!(matches!(
+            json.command,
+            Some(Command::Status { as_json: true })
+        ));
+    }
+}
+
+mod history_command {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn parse_history_filters() {
+        let cli = Cli::parse_from_iter([
+            "ddns-a",
+            "history",
+            "--since",
+            "24h",
+            "--adapter",
+            "eth0",
+            "--file",
+            "history.jsonl",
+        ]);
+
+        let Some(Command::History {
+            since,
+            adapter,
+            history_file,
+        }) = cli.command
+        else {
+            panic!("expected history command");
+        };
+        assert_eq!(since, Some(Duration::from_secs(86_400)));
+        assert_eq!(adapter.as_deref(), Some("eth0"));
+        assert_eq!(history_file, Some(PathBuf::from("history.jsonl")));
+    }
+
+    #[test]
+    fn rejects_unknown_age_unit() {
+        let result = <Cli as clap::Parser>::try_parse_from(["ddns-a", "history", "--since", "1w"]);
+
+        assert!(result.is_err());
+    }
+}
+
+mod send_test_command {
+    use super::*;
+
+    #[test]
+    fn parse_send_test() {
+        let cli = Cli::parse_from_iter(["ddns-a", "--dry-run", "send-test"]);
+
+        assert!(matches!(cli.command, Some(Command::SendTest)));
+        assert!(cli.dry_run);
+    }
+}
+
+mod check_command {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn parse_check() {
+        let cli = Cli::parse_from_iter(["ddns-a", "--state-file", "state.json", "check"]);
+
+        assert!(matches!(cli.command, Some(Command::Check)));
+        assert_eq!(cli.state_file, Some(PathBuf::from("state.json")));
+    }
+}
+
+mod ctl_command {
+    use super::*;
+
+    #[test]
+    fn parse_ctl_restart() {
+        let cli = Cli::parse_from_iter(["ddns-a", "--pidfile", "ddns-a.pid", "ctl", "restart"]);
+
+        assert!(matches!(
+            cli.command,
+            Some(Command::Ctl {
+                command: CtlCommand::Restart
+            })
+        ));
+    }
+
+    #[test]
+    fn action_is_required() {
+        let result = <Cli as clap::Parser>::try_parse_from(["ddns-a", "ctl"]);
+
+        assert!(result.is_err());
+    }
+}
+
+mod record_payloads {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn parse_record_payloads() {
+        let cli = Cli::parse_from_iter(["ddns-a", "--record-payloads", "goldens"]);
+
+        assert_eq!(cli.record_payloads, Some(PathBuf::from("goldens")));
+    }
+
+    #[test]
+    fn record_payloads_conflicts_with_dry_run() {
+        let result = <Cli as clap::Parser>::try_parse_from([
+            "ddns-a",
+            "--dry-run",
+            "--record-payloads",
+            "goldens",
+        ]);
+
+        assert!(result.is_err());
     }
 }
 
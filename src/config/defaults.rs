@@ -10,6 +10,16 @@ pub const METHOD: &str = "POST";
 /// Default polling interval in seconds.
 pub const POLL_INTERVAL_SECS: u64 = 60;
 
+/// Default longest adaptive polling interval, as a multiple of the shortest.
+pub const POLL_INTERVAL_MAX_FACTOR: u32 = 10;
+
+/// Default adapter fetch timeout in seconds.
+pub const FETCH_TIMEOUT_SECS: u64 = 30;
+
+/// Default quiet period ending a burst of address change notifications, in
+/// milliseconds.
+pub const EVENT_QUIET_MS: u64 = 250;
+
 /// Default maximum number of retry attempts.
 pub const RETRY_MAX_ATTEMPTS: u32 = 3;
 
@@ -22,12 +32,24 @@ pub const RETRY_MAX_DELAY_SECS: u64 = 60;
 /// Default retry backoff multiplier.
 pub const RETRY_MULTIPLIER: f64 = 2.0;
 
+/// Default interval between webhook URL lookups in seconds.
+pub const DISCOVERY_REFRESH_SECS: u64 = 3600;
+
+/// Whether `X-DDNS-A-Version` / `X-DDNS-A-Host` headers are sent by default.
+pub const METADATA_HEADERS: bool = true;
+
 /// Default polling interval as Duration.
 #[must_use]
 pub const fn poll_interval() -> Duration {
     Duration::from_secs(POLL_INTERVAL_SECS)
 }
 
+/// Default adapter fetch timeout as Duration.
+#[must_use]
+pub const fn fetch_timeout() -> Duration {
+    Duration::from_secs(FETCH_TIMEOUT_SECS)
+}
+
 /// Default initial retry delay as Duration.
 #[must_use]
 pub const fn retry_initial_delay() -> Duration {
@@ -0,0 +1,61 @@
+//! Config file auto-discovery.
+//!
+//! When `--config` is not given, standard locations are searched in order
+//! and the first existing file is used. `--no-config` skips the search.
+
+use std::path::{Path, PathBuf};
+
+use super::Cli;
+
+/// File name searched for in the working directory.
+pub(super) const LOCAL_FILE_NAME: &str = "ddns-a.toml";
+
+/// File name searched for in per-user and system config directories.
+const DIR_FILE_NAME: &str = "config.toml";
+
+/// Application directory inside a config directory.
+const APP_DIR: &str = "ddns-a";
+
+/// Returns the locations searched for a config file, in priority order.
+///
+/// - `./ddns-a.toml`
+/// - `$XDG_CONFIG_HOME/ddns-a/config.toml` (falling back to the platform
+///   config directory: `~/.config` on Linux, `%APPDATA%` on Windows)
+/// - `/etc/ddns-a/config.toml` (Unix only)
+#[must_use]
+pub(super) fn search_paths() -> Vec<PathBuf> {
+    let user_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(dirs::config_dir);
+
+    candidate_paths(Path::new("."), user_dir.as_deref())
+}
+
+/// Builds the search list from a working directory and user config directory.
+pub(super) fn candidate_paths(cwd: &Path, user_config_dir: Option<&Path>) -> Vec<PathBuf> {
+    let mut paths = vec![cwd.join(LOCAL_FILE_NAME)];
+    if let Some(dir) = user_config_dir {
+        paths.push(dir.join(APP_DIR).join(DIR_FILE_NAME));
+    }
+    if cfg!(unix) {
+        paths.push(Path::new("/etc").join(APP_DIR).join(DIR_FILE_NAME));
+    }
+    paths
+}
+
+/// Returns the config file to load for `cli`: the `--config` path, else the
+/// first file found unless `--no-config` is given.
+pub(super) fn config_path(cli: &Cli) -> Option<PathBuf> {
+    match cli.config {
+        Some(ref path) => Some(path.clone()),
+        None if cli.no_config => None,
+        None => discover_in(&search_paths()),
+    }
+}
+
+/// Returns the first of `candidates` that is an existing file.
+#[must_use]
+pub(super) fn discover_in(candidates: &[PathBuf]) -> Option<PathBuf> {
+    candidates.iter().find(|path| path.is_file()).cloned()
+}
@@ -0,0 +1,87 @@
+//! Tests for config file discovery.
+
+use std::fs;
+use std::path::Path;
+
+use tempfile::tempdir;
+
+use super::discovery::{LOCAL_FILE_NAME, candidate_paths, discover_in};
+
+mod candidates {
+    use super::*;
+
+    #[test]
+    fn working_directory_comes_first() {
+        let paths = candidate_paths(Path::new("work"), Some(Path::new("home/.config")));
+
+        assert_eq!(paths[0], Path::new("work").join(LOCAL_FILE_NAME));
+        assert_eq!(
+            paths[1],
+            Path::new("home/.config").join("ddns-a").join("config.toml")
+        );
+    }
+
+    #[test]
+    fn user_directory_is_skipped_when_unknown() {
+        let paths = candidate_paths(Path::new("work"), None);
+
+        assert_eq!(paths[0], Path::new("work").join(LOCAL_FILE_NAME));
+        assert!(paths.iter().all(|p| !p.starts_with("home")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn system_directory_is_last_on_unix() {
+        let paths = candidate_paths(Path::new("work"), Some(Path::new("home")));
+
+        assert_eq!(
+            paths.last().map(AsRef::as_ref),
+            Some(Path::new("/etc/ddns-a/config.toml"))
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn no_system_directory_on_windows() {
+        let paths = candidate_paths(Path::new("work"), Some(Path::new("appdata")));
+
+        assert_eq!(paths.len(), 2);
+    }
+}
+
+mod discover {
+    use super::*;
+
+    #[test]
+    fn returns_first_existing_file() {
+        let dir = tempdir().unwrap();
+        let local = dir.path().join(LOCAL_FILE_NAME);
+        let user_dir = dir.path().join("config");
+        let user = user_dir.join("ddns-a").join("config.toml");
+        fs::create_dir_all(user.parent().unwrap()).unwrap();
+        fs::write(&user, "").unwrap();
+
+        let paths = candidate_paths(dir.path(), Some(&user_dir));
+        assert_eq!(discover_in(&paths), Some(user));
+
+        fs::write(&local, "").unwrap();
+        assert_eq!(discover_in(&paths), Some(local));
+    }
+
+    #[test]
+    fn none_when_nothing_exists() {
+        let dir = tempdir().unwrap();
+        let paths = vec![dir.path().join("a.toml"), dir.path().join("b.toml")];
+
+        assert_eq!(discover_in(&paths), None);
+    }
+
+    #[test]
+    fn directories_are_not_config_files() {
+        let dir = tempdir().unwrap();
+        let shadow = dir.path().join(LOCAL_FILE_NAME);
+        fs::create_dir(&shadow).unwrap();
+
+        assert_eq!(discover_in(&[shadow]), None);
+    }
+}
@@ -0,0 +1,166 @@
+//! Environment-variable configuration layer.
+//!
+//! `DDNS_A_*` variables fill the CLI options not given on the command line,
+//! so values resolve CLI > environment > TOML > default. Secrets such as the
+//! bearer token or a provider token can then be injected into a container
+//! without appearing in the process arguments or a file.
+//!
+//! | Variable | Option |
+//! |----------|--------|
+//! | `DDNS_A_URL` | `--url` |
+//! | `DDNS_A_IP_VERSION` | `--ip-version` |
+//! | `DDNS_A_METHOD` | `--method` |
+//! | `DDNS_A_BEARER` | `--bearer` |
+//! | `DDNS_A_BODY_TEMPLATE` | `--body-template` |
+//! | `DDNS_A_PROVIDER` | `--provider` |
+//! | `DDNS_A_PROVIDER_DOMAIN` | `--provider-domain` |
+//! | `DDNS_A_PROVIDER_TOKEN` | `--provider-token` |
+//! | `DDNS_A_PROVIDER_SECRET` | `--provider-secret` |
+//! | `DDNS_A_POLL_INTERVAL` | `--poll-interval` |
+//! | `DDNS_A_RETRY_MAX` | `--retry-max` |
+//! | `DDNS_A_RETRY_DELAY` | `--retry-delay` |
+//! | `DDNS_A_CONFIG` | `--config` (ignored with `--no-config`) |
+//! | `DDNS_A_STATE_FILE` | `--state-file` |
+//! | `DDNS_A_HEALTH` | `--health` |
+//! | `DDNS_A_POLL_ONLY` | `--poll-only` |
+//! | `DDNS_A_DRY_RUN` | `--dry-run` |
+//! | `DDNS_A_VERBOSE` | `--verbose` |
+//!
+//! Empty variables are ignored. Like the flags, the boolean variables
+//! (`1`/`true`/`yes`/`on` or `0`/`false`/`no`/`off`) can only enable.
+
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use clap::ValueEnum;
+
+use super::cli::{Cli, IpVersionArg};
+use super::error::ConfigError;
+
+/// Prefix of the configuration variables.
+pub const ENV_PREFIX: &str = "DDNS_A_";
+
+impl Cli {
+    /// Fills the options not given on the command line from `DDNS_A_*`
+    /// environment variables.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::InvalidEnv`] if a variable used for an option
+    /// holds an invalid value.
+    pub fn apply_env(&mut self) -> Result<(), ConfigError> {
+        self.apply_env_with(|name| std::env::var(name).ok())
+    }
+
+    /// Like [`apply_env`](Self::apply_env), reading variables with `var`.
+    pub(super) fn apply_env_with(
+        &mut self,
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<(), ConfigError> {
+        let env = Env(var);
+
+        for (option, key) in [
+            (&mut self.url, "URL"),
+            (&mut self.method, "METHOD"),
+            (&mut self.bearer, "BEARER"),
+            (&mut self.body_template, "BODY_TEMPLATE"),
+            (&mut self.provider, "PROVIDER"),
+            (&mut self.provider_domain, "PROVIDER_DOMAIN"),
+            (&mut self.provider_token, "PROVIDER_TOKEN"),
+            (&mut self.provider_secret, "PROVIDER_SECRET"),
+            (&mut self.health, "HEALTH"),
+        ] {
+            fill(option, || Ok(env.string(key)))?;
+        }
+        if !self.no_config {
+            fill(&mut self.config, || Ok(env.path("CONFIG")))?;
+        }
+        fill(&mut self.state_file, || Ok(env.path("STATE_FILE")))?;
+        fill(&mut self.ip_version, || env.ip_version("IP_VERSION"))?;
+        fill(&mut self.poll_interval, || env.parse("POLL_INTERVAL"))?;
+        fill(&mut self.retry_max, || env.parse("RETRY_MAX"))?;
+        fill(&mut self.retry_delay, || env.parse("RETRY_DELAY"))?;
+
+        self.poll_only |= env.flag("POLL_ONLY")?;
+        self.dry_run |= env.flag("DRY_RUN")?;
+        self.verbose |= env.flag("VERBOSE")?;
+        Ok(())
+    }
+}
+
+/// Sets `option` from `value` unless the command line already did.
+fn fill<T>(
+    option: &mut Option<T>,
+    value: impl FnOnce() -> Result<Option<T>, ConfigError>,
+) -> Result<(), ConfigError> {
+    if option.is_none() {
+        *option = value()?;
+    }
+    Ok(())
+}
+
+/// Reads `DDNS_A_*` variables through a lookup function.
+struct Env<V>(V);
+
+impl<V: Fn(&str) -> Option<String>> Env<V> {
+    /// Returns the full name and non-empty value of the variable `key`.
+    fn get(&self, key: &str) -> Option<(String, String)> {
+        let name = format!("{ENV_PREFIX}{key}");
+        let value = (self.0)(&name).filter(|value| !value.is_empty())?;
+        Some((name, value))
+    }
+
+    fn string(&self, key: &str) -> Option<String> {
+        self.get(key).map(|(_, value)| value)
+    }
+
+    fn path(&self, key: &str) -> Option<PathBuf> {
+        self.string(key).map(PathBuf::from)
+    }
+
+    fn parse<T>(&self, key: &str) -> Result<Option<T>, ConfigError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.get(key)
+            .map(|(name, value)| {
+                value
+                    .trim()
+                    .parse()
+                    .map_err(|e: T::Err| ConfigError::InvalidEnv {
+                        name,
+                        reason: format!("'{value}' is not a number ({e})"),
+                    })
+            })
+            .transpose()
+    }
+
+    fn ip_version(&self, key: &str) -> Result<Option<IpVersionArg>, ConfigError> {
+        self.get(key)
+            .map(|(name, value)| {
+                <IpVersionArg as ValueEnum>::from_str(value.trim(), true).map_err(|_| {
+                    ConfigError::InvalidEnv {
+                        name,
+                        reason: format!("'{value}' is not ipv4, ipv6 or both"),
+                    }
+                })
+            })
+            .transpose()
+    }
+
+    fn flag(&self, key: &str) -> Result<bool, ConfigError> {
+        let Some((name, value)) = self.get(key) else {
+            return Ok(false);
+        };
+        match value.trim().to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Ok(true),
+            "0" | "false" | "no" | "off" => Ok(false),
+            _ => Err(ConfigError::InvalidEnv {
+                name,
+                reason: format!("'{value}' is not a boolean (true or false)"),
+            }),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! Tests for the `DDNS_A_*` environment-variable layer.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::cli::{Cli, IpVersionArg};
+use super::error::ConfigError;
+
+fn cli(args: &[&str]) -> Cli {
+    Cli::parse_from_iter(std::iter::once("ddns-a").chain(args.iter().copied()))
+}
+
+fn with_env(mut cli: Cli, vars: &[(&str, &str)]) -> Result<Cli, ConfigError> {
+    let vars: HashMap<_, _> = vars.iter().copied().collect();
+    cli.apply_env_with(|name| vars.get(name).map(ToString::to_string))?;
+    Ok(cli)
+}
+
+#[test]
+fn fills_options_not_on_command_line() {
+    let cli = with_env(
+        cli(&[]),
+        &[
+            ("DDNS_A_URL", "https://env.example.com"),
+            ("DDNS_A_IP_VERSION", "IPv6"),
+            ("DDNS_A_BEARER", "secret"),
+            ("DDNS_A_POLL_INTERVAL", "30"),
+            ("DDNS_A_STATE_FILE", "/var/lib/ddns-a/state.json"),
+            ("DDNS_A_DRY_RUN", "true"),
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(cli.url.as_deref(), Some("https://env.example.com"));
+    assert_eq!(cli.ip_version, Some(IpVersionArg::V6));
+    assert_eq!(cli.bearer.as_deref(), Some("secret"));
+    assert_eq!(cli.poll_interval, Some(30));
+    assert_eq!(
+        cli.state_file.as_deref(),
+        Some(Path::new("/var/lib/ddns-a/state.json"))
+    );
+    assert!(cli.dry_run);
+    assert!(!cli.poll_only);
+}
+
+#[test]
+fn command_line_wins() {
+    let cli = with_env(
+        cli(&["--url", "https://cli.example.com", "--poll-interval", "5"]),
+        &[
+            ("DDNS_A_URL", "https://env.example.com"),
+            ("DDNS_A_POLL_INTERVAL", "not a number"),
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(cli.url.as_deref(), Some("https://cli.example.com"));
+    assert_eq!(cli.poll_interval, Some(5));
+}
+
+#[test]
+fn empty_variables_are_ignored() {
+    let cli = with_env(cli(&[]), &[("DDNS_A_URL", ""), ("DDNS_A_VERBOSE", "")]).unwrap();
+
+    assert_eq!(cli.url, None);
+    assert!(!cli.verbose);
+}
+
+#[test]
+fn config_path_ignored_with_no_config() {
+    let vars = [("DDNS_A_CONFIG", "/etc/ddns-a/env.toml")];
+
+    let found = with_env(cli(&[]), &vars).unwrap();
+    let skipped = with_env(cli(&["--no-config"]), &vars).unwrap();
+
+    assert_eq!(
+        found.config.as_deref(),
+        Some(Path::new("/etc/ddns-a/env.toml"))
+    );
+    assert_eq!(skipped.config, None);
+}
+
+#[test]
+fn invalid_values_name_the_variable() {
+    for (name, value) in [
+        ("DDNS_A_IP_VERSION", "ipv5"),
+        ("DDNS_A_RETRY_MAX", "-1"),
+        ("DDNS_A_POLL_ONLY", "maybe"),
+    ] {
+        let error = with_env(cli(&[]), &[(name, value)]).unwrap_err();
+
+        assert!(
+            matches!(&error, ConfigError::InvalidEnv { name: n, .. } if n == name),
+            "{error:?}"
+        );
+        assert!(error.to_string().contains(value), "{error}");
+    }
+}
@@ -4,6 +4,9 @@ use std::path::PathBuf;
 
 use thiserror::Error;
 
+use crate::network::class::CidrError;
+use crate::webhook::RetryOverlap;
+
 /// Error type for configuration operations.
 ///
 /// Covers errors from parsing, validation, and file operations.
@@ -33,6 +36,25 @@ pub enum ConfigError {
         source: std::io::Error,
     },
 
+    /// Config file already exists (for init command without `--force`/`--merge`).
+    #[error(
+        "Config file '{}' already exists (use --force to overwrite or --merge to add new options)",
+        path.display()
+    )]
+    FileExists {
+        /// Path to the existing file
+        path: PathBuf,
+    },
+
+    /// The `init --from-snapshot` listing is not usable.
+    #[error("Invalid adapter listing '{}': {reason}", path.display())]
+    InvalidSnapshot {
+        /// Path to the listing
+        path: PathBuf,
+        /// What is wrong with it
+        reason: String,
+    },
+
     /// Missing required field that must be provided by CLI or config file.
     #[error("Missing required field: {field}. {hint}")]
     MissingRequired {
@@ -61,6 +83,16 @@ pub enum ConfigError {
         source: regex::Error,
     },
 
+    /// Invalid CIDR range for address filtering.
+    #[error("Invalid CIDR '{value}': {source}")]
+    InvalidCidr {
+        /// The invalid range
+        value: String,
+        /// Why it is invalid
+        #[source]
+        source: CidrError,
+    },
+
     /// Invalid duration value (zero or too large).
     #[error("Invalid duration for {field}: {reason}")]
     InvalidDuration {
@@ -74,6 +106,98 @@ pub enum ConfigError {
     #[error("Invalid retry configuration: {0}")]
     InvalidRetry(String),
 
+    /// Invalid webhook URL discovery configuration.
+    #[error("Invalid webhook.discovery configuration: {0}")]
+    InvalidDiscovery(String),
+
+    /// Invalid `[http] dns_servers` entry.
+    #[error("Invalid http.dns_servers: {0}")]
+    InvalidDnsServers(String),
+
+    /// Invalid `[http] local_address` or `interface` binding.
+    #[error("Invalid http binding: {0}")]
+    InvalidHttpBinding(String),
+
+    /// Invalid `[http.tls]` configuration.
+    #[error("Invalid http.tls configuration: {0}")]
+    InvalidTls(String),
+
+    /// Invalid DNS provider preset configuration.
+    #[error("Invalid webhook.provider configuration: {0}")]
+    InvalidProvider(String),
+
+    /// Invalid pre-request configuration.
+    #[error("Invalid webhook.pre_request configuration: {0}")]
+    InvalidPreRequest(String),
+
+    /// Invalid `[webhook.oauth2]` client-credentials configuration.
+    #[error("Invalid webhook.oauth2 configuration: {0}")]
+    InvalidOAuth2(String),
+
+    /// Invalid `webhook.expect_json` assertion.
+    #[error("Invalid webhook.expect_json configuration: {0}")]
+    InvalidExpectJson(String),
+
+    /// Invalid `[webhook.certificate]` tracking.
+    #[error("Invalid webhook.certificate configuration: {0}")]
+    InvalidCertificate(String),
+
+    /// Invalid `[webhook.circuit_breaker]` policy.
+    #[error("Invalid webhook.circuit_breaker configuration: {0}")]
+    InvalidCircuitBreaker(String),
+
+    /// Invalid `[monitor.address_storm]` limits.
+    #[error("Invalid monitor.address_storm configuration: {0}")]
+    InvalidAddressStorm(String),
+
+    /// Invalid `[webhook.ipv4]` or `[webhook.ipv6]` URL.
+    #[error("Invalid per-version webhook URL: {0}")]
+    InvalidVersionUrl(String),
+
+    /// Invalid `[[webhook.targets]]` entry.
+    #[error("Invalid webhook.targets configuration: {0}")]
+    InvalidTarget(String),
+
+    /// Invalid `[monitor.public]` address lookup.
+    #[error("Invalid monitor.public configuration: {0}")]
+    InvalidPublicAddress(String),
+
+    /// Invalid `monitor.state` mode, or no default state file for `auto`.
+    #[error("Invalid monitor.state configuration: {0}")]
+    InvalidState(String),
+
+    /// Invalid `[health]` endpoint address.
+    #[error("Invalid health configuration: {0}")]
+    InvalidHealth(String),
+
+    /// Invalid self-update configuration.
+    #[error("Invalid update configuration: {0}")]
+    InvalidUpdate(String),
+
+    /// Invalid `[action.exec]` command.
+    #[error("Invalid action.exec configuration: {0}")]
+    InvalidExec(String),
+
+    /// Invalid `[mqtt]` publisher.
+    #[error("Invalid mqtt configuration: {0}")]
+    InvalidMqtt(String),
+
+    /// Invalid `[email]` notifier.
+    #[error("Invalid email configuration: {0}")]
+    InvalidEmail(String),
+
+    /// Invalid `[notify]` chat service.
+    #[error("Invalid notify configuration: {0}")]
+    InvalidNotify(String),
+
+    /// Invalid `[expect]` address list.
+    #[error("Invalid expect configuration: {0}")]
+    InvalidExpect(String),
+
+    /// Invalid `monitor.adapter_priority` list.
+    #[error("Invalid monitor.adapter_priority: {0}")]
+    InvalidAdapterPriority(String),
+
     /// Invalid HTTP method.
     #[error("Invalid HTTP method '{0}'")]
     InvalidMethod(String),
@@ -85,6 +209,13 @@ pub enum ConfigError {
         value: String,
     },
 
+    /// Invalid `notify_on` entry.
+    #[error("Invalid notify_on '{value}': expected added or removed")]
+    InvalidNotifyOn {
+        /// The invalid value provided
+        value: String,
+    },
+
     /// Invalid adapter kind value.
     #[error("Invalid adapter kind '{value}': expected ethernet, wireless, virtual, or loopback")]
     InvalidAdapterKind {
@@ -92,6 +223,112 @@ pub enum ConfigError {
         value: String,
     },
 
+    /// Invalid connectivity check value.
+    #[error("Invalid connectivity check '{value}': expected none or family")]
+    InvalidConnectivityCheck {
+        /// The invalid value provided
+        value: String,
+    },
+
+    /// Invalid watchdog action value.
+    #[error("Invalid watchdog action '{value}': expected off, log, or exit")]
+    InvalidWatchdog {
+        /// The invalid value provided
+        value: String,
+    },
+
+    /// Invalid `monitor.source` value.
+    #[error("Invalid address source '{value}': expected adapter, public, or both")]
+    InvalidAddressSource {
+        /// The invalid value provided
+        value: String,
+    },
+
+    /// Invalid `retry.overlap` value.
+    #[error("Invalid retry overlap check '{value}': expected off, warn, or error")]
+    InvalidOverlapCheck {
+        /// The invalid value provided
+        value: String,
+    },
+
+    /// Invalid `retry.jitter` value.
+    #[error("Invalid retry jitter '{value}': expected none, full, or equal")]
+    InvalidJitter {
+        /// The invalid value provided
+        value: String,
+    },
+
+    /// Retries can outlast the poll interval and `retry.overlap = "error"`.
+    #[error("Retry policy outlasts the poll interval: {0}")]
+    RetryOverlap(RetryOverlap),
+
+    /// Invalid echo check value.
+    #[error("Invalid echo check '{value}': expected off, header, or body")]
+    InvalidEchoCheck {
+        /// The invalid value provided
+        value: String,
+    },
+
+    /// Invalid delivery mode value.
+    #[error("Invalid delivery mode '{value}': expected batched or per_change")]
+    InvalidDeliveryMode {
+        /// The invalid value provided
+        value: String,
+    },
+
+    /// Invalid payload encoding value.
+    #[error("Invalid payload encoding '{value}': expected json, yaml, or cbor")]
+    InvalidPayloadEncoding {
+        /// The invalid value provided
+        value: String,
+    },
+
+    /// Invalid payload format value.
+    #[error("Invalid payload format '{value}': expected json-v1 or none")]
+    InvalidPayloadFormat {
+        /// The invalid value provided
+        value: String,
+    },
+
+    /// Invalid `log.locale` value.
+    #[error("Invalid locale '{value}': expected en or zh")]
+    InvalidLocale {
+        /// The invalid value provided
+        value: String,
+    },
+
+    /// Invalid `log.target` value.
+    #[error("Invalid log target '{value}': expected stderr or system")]
+    InvalidLogTarget {
+        /// The invalid value provided
+        value: String,
+    },
+
+    /// Invalid `DDNS_A_*` environment variable.
+    #[error("Invalid environment variable {name}: {reason}")]
+    InvalidEnv {
+        /// Name of the variable
+        name: String,
+        /// Reason for invalidity
+        reason: String,
+    },
+
+    /// Option not available on the current platform.
+    #[error("{option} is not supported on this platform")]
+    UnsupportedPlatform {
+        /// The unsupported option
+        option: &'static str,
+    },
+
+    /// Option requires a cargo feature this binary was built without.
+    #[error("{option} requires the '{feature}' feature, which this build does not include")]
+    FeatureDisabled {
+        /// The cargo feature that is missing
+        feature: &'static str,
+        /// The option that requires it
+        option: &'static str,
+    },
+
     /// Invalid header format.
     #[error("Invalid header format '{value}': expected 'Key=Value' or 'Key: Value'")]
     InvalidHeader {
@@ -0,0 +1,23 @@
+//! Resolution of `--health` and the `[health]` section.
+
+use std::net::SocketAddr;
+
+use super::error::ConfigError;
+use super::toml::TomlConfig;
+
+/// Resolves the health endpoint's bind address; `None` keeps it off.
+///
+/// `--health` takes precedence over `health.bind`.
+pub(super) fn resolve_health(
+    cli_bind: Option<&str>,
+    toml: Option<&TomlConfig>,
+) -> Result<Option<SocketAddr>, ConfigError> {
+    let Some(bind) = cli_bind.or_else(|| toml.and_then(|t| t.health.bind.as_deref())) else {
+        return Ok(None);
+    };
+    bind.parse().map(Some).map_err(|_| {
+        ConfigError::InvalidHealth(format!(
+            "bind '{bind}' must be an IP address and port, e.g. 127.0.0.1:8053"
+        ))
+    })
+}
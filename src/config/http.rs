@@ -0,0 +1,175 @@
+//! HTTP client settings: the user agent, nameservers, TLS and connections.
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use http::header::HeaderValue;
+
+use crate::config::defaults;
+use crate::config::error::ConfigError;
+use crate::config::parse::{parse_header_value, parse_nameserver};
+use crate::config::tls::resolve_tls;
+use crate::config::toml::{HttpSection, TomlConfig};
+use crate::webhook::{
+    ConnectionSettings, DEFAULT_USER_AGENT, INTERFACE_BINDING_SUPPORTED, TlsSettings,
+};
+
+/// HTTP client settings resolved from the `[http]` section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpSettings {
+    /// `User-Agent` sent with every request
+    pub user_agent: HeaderValue,
+
+    /// Whether to send `X-DDNS-A-Version` / `X-DDNS-A-Host` headers
+    pub metadata_headers: bool,
+
+    /// Nameservers resolving outbound hosts, asked in order (empty: the
+    /// system resolver)
+    pub dns_servers: Vec<SocketAddr>,
+
+    /// Custom TLS roots, client certificate and insecure mode
+    pub tls: TlsSettings,
+
+    /// Timeouts and connection pool limits
+    pub connection: ConnectionSettings,
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            user_agent: HeaderValue::from_static(DEFAULT_USER_AGENT),
+            metadata_headers: defaults::METADATA_HEADERS,
+            dns_servers: Vec::new(),
+            tls: TlsSettings::default(),
+            connection: ConnectionSettings::default(),
+        }
+    }
+}
+
+impl HttpSettings {
+    /// Resolves the HTTP client settings (TOML-only).
+    pub(super) fn resolve(toml: Option<&TomlConfig>) -> Result<Self, ConfigError> {
+        let section = toml.map(|t| &t.http);
+
+        let user_agent = Self::resolve_user_agent(section.and_then(|h| h.user_agent.as_deref()))?;
+        let metadata_headers = section
+            .and_then(|h| h.metadata_headers)
+            .unwrap_or(defaults::METADATA_HEADERS);
+
+        let dns_servers = section
+            .map_or(&[][..], |h| h.dns_servers.as_slice())
+            .iter()
+            .map(|s| {
+                parse_nameserver(s).ok_or_else(|| {
+                    ConfigError::InvalidDnsServers(format!(
+                        "'{s}' must be an IP address, optionally with a port"
+                    ))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            user_agent,
+            metadata_headers,
+            dns_servers,
+            tls: resolve_tls(section.and_then(|h| h.tls.as_ref()))?,
+            connection: Self::resolve_connection(section)?,
+        })
+    }
+
+    fn resolve_connection(
+        section: Option<&HttpSection>,
+    ) -> Result<ConnectionSettings, ConfigError> {
+        let mut connection = ConnectionSettings::default();
+        let Some(section) = section else {
+            return Ok(connection);
+        };
+        let timeouts = [
+            (
+                "http.connect_timeout",
+                section.connect_timeout,
+                &mut connection.connect_timeout,
+            ),
+            (
+                "http.request_timeout",
+                section.request_timeout,
+                &mut connection.request_timeout,
+            ),
+            (
+                "http.pool_idle_timeout",
+                section.pool_idle_timeout,
+                &mut connection.pool_idle_timeout,
+            ),
+        ];
+        for (field, seconds, timeout) in timeouts {
+            match seconds {
+                Some(0) => {
+                    return Err(ConfigError::InvalidDuration {
+                        field,
+                        reason: "must be greater than 0".to_string(),
+                    });
+                }
+                Some(seconds) => *timeout = Duration::from_secs(seconds),
+                None => {}
+            }
+        }
+        if connection.connect_timeout > connection.request_timeout {
+            return Err(ConfigError::InvalidDuration {
+                field: "http.connect_timeout",
+                reason: format!(
+                    "must not exceed http.request_timeout ({}s)",
+                    connection.request_timeout.as_secs()
+                ),
+            });
+        }
+        if let Some(max_idle) = section.pool_max_idle_per_host {
+            connection.pool_max_idle_per_host = max_idle;
+        }
+        connection.local_address = section
+            .local_address
+            .as_deref()
+            .map(|address| {
+                address.parse().map_err(|_| {
+                    ConfigError::InvalidHttpBinding(format!(
+                        "local_address '{address}' must be an IP address"
+                    ))
+                })
+            })
+            .transpose()?;
+        connection.interface = Self::resolve_interface(section.interface.as_deref())?;
+        Ok(connection)
+    }
+
+    fn resolve_interface(interface: Option<&str>) -> Result<Option<String>, ConfigError> {
+        let Some(interface) = interface else {
+            return Ok(None);
+        };
+        if interface.trim().is_empty() {
+            return Err(ConfigError::InvalidHttpBinding(
+                "interface must not be empty".to_string(),
+            ));
+        }
+        if !INTERFACE_BINDING_SUPPORTED {
+            return Err(ConfigError::InvalidHttpBinding(
+                "interface binding is not supported on this platform, use local_address"
+                    .to_string(),
+            ));
+        }
+        Ok(Some(interface.to_string()))
+    }
+
+    fn resolve_user_agent(user_agent: Option<&str>) -> Result<HeaderValue, ConfigError> {
+        let Some(user_agent) = user_agent else {
+            return Ok(HeaderValue::from_static(DEFAULT_USER_AGENT));
+        };
+
+        if user_agent.trim().is_empty() {
+            return Err(ConfigError::InvalidHeaderValue {
+                name: "User-Agent".to_string(),
+                reason: "must not be empty".to_string(),
+            });
+        }
+
+        parse_header_value("User-Agent", user_agent)
+    }
+}
@@ -0,0 +1,264 @@
+//! Config file generation for the `init` subcommand.
+//!
+//! By default `init` refuses to replace an existing file. `--force`
+//! overwrites it with the template, and `--merge` adds options introduced
+//! since the file was generated while leaving everything the user wrote
+//! untouched. `--from-snapshot` fills the template's adapter filter from a
+//! captured adapter listing.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use regex::Regex;
+
+use super::ConfigError;
+use super::snapshot::{include_patterns, load_listing, with_include_filter};
+use super::toml::default_config_template;
+
+/// How `init` treats an existing config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InitMode {
+    /// Fail if the file already exists.
+    #[default]
+    CreateNew,
+    /// Replace an existing file with the template.
+    Overwrite,
+    /// Add missing template options to an existing file.
+    Merge,
+}
+
+/// Result of a successful `init`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InitOutcome {
+    /// A new file was written.
+    Created,
+    /// An existing file was replaced.
+    Overwritten,
+    /// Missing options were added to an existing file.
+    Merged {
+        /// Added options as `section.key`, in template order (empty if
+        /// the file was already up to date and left unchanged)
+        added: Vec<String>,
+    },
+}
+
+/// Writes the default configuration template to a file.
+///
+/// With [`InitMode::Merge`] and no existing file, the template is written
+/// as with [`InitMode::CreateNew`].
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The file exists and `mode` is [`InitMode::CreateNew`]
+/// - The existing file cannot be read or is not valid TOML (merge mode)
+/// - The file cannot be written
+pub fn write_default_config(path: &Path, mode: InitMode) -> Result<InitOutcome, ConfigError> {
+    let template = default_config_template();
+    let exists = path.exists();
+
+    let outcome = match (mode, exists) {
+        (_, false) => InitOutcome::Created,
+        (InitMode::CreateNew, true) => {
+            return Err(ConfigError::FileExists {
+                path: path.to_path_buf(),
+            });
+        }
+        (InitMode::Overwrite, true) => InitOutcome::Overwritten,
+        (InitMode::Merge, true) => {
+            let existing = std::fs::read_to_string(path).map_err(|e| ConfigError::FileRead {
+                path: path.to_path_buf(),
+                source: e,
+            })?;
+            existing.parse::<toml::Table>()?;
+
+            let (merged, added) = merge_template(&existing, &template);
+            if !added.is_empty() {
+                write(path, &merged)?;
+            }
+            return Ok(InitOutcome::Merged { added });
+        }
+    };
+
+    write(path, &template)?;
+    Ok(outcome)
+}
+
+/// Writes the default template with its `[filter]` section including
+/// exactly the adapters listed in `snapshot` (see [`super::snapshot`]).
+///
+/// [`InitMode::Merge`] is treated as [`InitMode::CreateNew`]: generated
+/// filters replace what the user chose, so they are never merged in.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The listing cannot be read, is not valid, or lists no adapter to include
+/// - The file exists and `mode` is not [`InitMode::Overwrite`]
+/// - The file cannot be written
+pub fn write_config_from_snapshot(
+    path: &Path,
+    mode: InitMode,
+    snapshot: &Path,
+) -> Result<InitOutcome, ConfigError> {
+    let patterns = include_patterns(&load_listing(snapshot)?);
+    if patterns.is_empty() {
+        return Err(ConfigError::InvalidSnapshot {
+            path: snapshot.to_path_buf(),
+            reason: "no non-loopback adapters listed".to_string(),
+        });
+    }
+
+    let outcome = match (mode, path.exists()) {
+        (_, false) => InitOutcome::Created,
+        (InitMode::Overwrite, true) => InitOutcome::Overwritten,
+        (InitMode::CreateNew | InitMode::Merge, true) => {
+            return Err(ConfigError::FileExists {
+                path: path.to_path_buf(),
+            });
+        }
+    };
+
+    let source = snapshot.file_name().unwrap_or(snapshot.as_os_str());
+    let content = with_include_filter(
+        &default_config_template(),
+        &patterns,
+        &source.to_string_lossy(),
+    );
+    write(path, &content)?;
+    Ok(outcome)
+}
+
+fn write(path: &Path, content: &str) -> Result<(), ConfigError> {
+    std::fs::write(path, content).map_err(|e| ConfigError::FileWrite {
+        path: path.to_path_buf(),
+        source: e,
+    })
+}
+
+/// A `[section]` of a config file and the lines that follow it.
+struct Section<'a> {
+    name: &'a str,
+    lines: Vec<&'a str>,
+}
+
+/// Splits a config file into its preamble and top-level sections.
+fn split_sections(content: &str) -> (Vec<&str>, Vec<Section<'_>>) {
+    let header = Regex::new(r"^\s*\[([^\[\]]+)\]\s*$").expect("valid regex");
+
+    let mut preamble = Vec::new();
+    let mut sections: Vec<Section<'_>> = Vec::new();
+    for line in content.lines() {
+        if let Some(caps) = header.captures(line) {
+            let name = caps.get(1).map_or("", |m| m.as_str().trim());
+            sections.push(Section {
+                name,
+                lines: vec![line],
+            });
+        } else if let Some(section) = sections.last_mut() {
+            section.lines.push(line);
+        } else {
+            preamble.push(line);
+        }
+    }
+    (preamble, sections)
+}
+
+/// Returns the key set (commented out or not) on `line`, if any.
+fn key_of(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+    let line = line.strip_prefix('#').unwrap_or(line).trim_start();
+    let end = line.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))?;
+
+    (end > 0 && line[end..].trim_start().starts_with('=')).then(|| &line[..end])
+}
+
+/// Returns true for a commented-out table header such as `# [webhook.headers]`.
+fn is_commented_header(line: &str) -> bool {
+    line.trim_start()
+        .strip_prefix('#')
+        .map(str::trim)
+        .is_some_and(|rest| rest.starts_with('[') && rest.ends_with(']'))
+}
+
+/// A blank-line separated block of template lines documenting one key.
+struct Block<'a> {
+    key: &'a str,
+    lines: Vec<&'a str>,
+}
+
+/// Splits section body lines into blocks; blocks without a simple key
+/// (notes, commented-out sub-tables) are dropped.
+fn key_blocks<'a>(lines: &[&'a str]) -> Vec<Block<'a>> {
+    lines
+        .split(|line| line.trim().is_empty())
+        .filter(|block| !block.iter().any(|l| is_commented_header(l)))
+        .filter_map(|block| {
+            let key = block.iter().find_map(|l| key_of(l))?;
+            Some(Block {
+                key,
+                lines: block.to_vec(),
+            })
+        })
+        .collect()
+}
+
+/// Adds template options missing from `existing`, preserving its content.
+///
+/// A key counts as present if the user's section mentions it at all,
+/// even commented out, so options a user deliberately disabled are not
+/// re-added. Missing keys are inserted (with the template's comments) at
+/// the end of their section; missing sections are appended whole.
+///
+/// Returns the merged content and the added options as `section.key`.
+pub(super) fn merge_template(existing: &str, template: &str) -> (String, Vec<String>) {
+    let (preamble, user_sections) = split_sections(existing);
+    let (_, template_sections) = split_sections(template);
+
+    let mut added = Vec::new();
+    let mut out: Vec<&str> = preamble;
+
+    for section in &user_sections {
+        let present: HashSet<&str> = section.lines.iter().filter_map(|l| key_of(l)).collect();
+        let missing: Vec<Block<'_>> = template_sections
+            .iter()
+            .filter(|t| t.name == section.name)
+            .flat_map(|t| key_blocks(&t.lines[1..]))
+            .filter(|block| !present.contains(block.key))
+            .collect();
+
+        // Insert before the blank lines that separate this section from the next
+        let body_end = section
+            .lines
+            .iter()
+            .rposition(|l| !l.trim().is_empty())
+            .map_or(0, |i| i + 1);
+        out.extend(&section.lines[..body_end]);
+        for block in missing {
+            added.push(format!("{}.{}", section.name, block.key));
+            out.push("");
+            out.extend(block.lines);
+        }
+        out.extend(&section.lines[body_end..]);
+    }
+
+    let user_names: HashSet<&str> = user_sections.iter().map(|s| s.name).collect();
+    for section in template_sections
+        .iter()
+        .filter(|t| !user_names.contains(t.name))
+    {
+        added.extend(
+            key_blocks(&section.lines[1..])
+                .iter()
+                .map(|block| format!("{}.{}", section.name, block.key)),
+        );
+        if out.last().is_some_and(|l| !l.trim().is_empty()) {
+            out.push("");
+        }
+        out.extend(&section.lines);
+    }
+
+    let mut merged = out.join("\n");
+    merged.push('\n');
+    (merged, added)
+}
@@ -0,0 +1,281 @@
+//! Tests for `init` file generation and template merging.
+
+use std::fs;
+
+use tempfile::tempdir;
+
+use super::ConfigError;
+use super::init::{
+    InitMode, InitOutcome, merge_template, write_config_from_snapshot, write_default_config,
+};
+use super::toml::{TomlConfig, default_config_template};
+
+const TEMPLATE: &str = r#"# Header comment
+
+[webhook]
+# Webhook URL (required)
+# url = "https://api.example.com/ddns"
+
+# HTTP headers
+# [webhook.headers]
+# X-Custom-Header = "value"
+
+# New: check connectivity
+# connectivity_check = "family"
+
+[monitor]
+# Polling interval in seconds (default: 60)
+poll_interval = 60
+
+# Every attempt carries extra headers
+
+# Watchdog for a stalled loop
+# watchdog = "log"
+
+[output]
+# Write a run summary
+# summary = true
+"#;
+
+mod write_modes {
+    use super::*;
+
+    #[test]
+    fn create_new_refuses_existing_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("ddns-a.toml");
+        fs::write(&path, "# mine\n").unwrap();
+
+        let result = write_default_config(&path, InitMode::CreateNew);
+
+        assert!(matches!(result, Err(ConfigError::FileExists { .. })));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "# mine\n");
+    }
+
+    #[test]
+    fn overwrite_replaces_existing_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("ddns-a.toml");
+        fs::write(&path, "# mine\n").unwrap();
+
+        let outcome = write_default_config(&path, InitMode::Overwrite).unwrap();
+
+        assert_eq!(outcome, InitOutcome::Overwritten);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            default_config_template()
+        );
+    }
+
+    #[test]
+    fn merge_without_existing_file_creates_it() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("ddns-a.toml");
+
+        let outcome = write_default_config(&path, InitMode::Merge).unwrap();
+
+        assert_eq!(outcome, InitOutcome::Created);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            default_config_template()
+        );
+    }
+
+    #[test]
+    fn merge_with_current_template_changes_nothing() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("ddns-a.toml");
+        fs::write(&path, default_config_template()).unwrap();
+
+        let outcome = write_default_config(&path, InitMode::Merge).unwrap();
+
+        assert_eq!(outcome, InitOutcome::Merged { added: vec![] });
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            default_config_template()
+        );
+    }
+
+    #[test]
+    fn merge_keeps_user_values_and_stays_loadable() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("ddns-a.toml");
+        let user = "[webhook]\nurl = \"https://example.com\"\nip_version = \"ipv6\"\n\n[monitor]\npoll_interval = 30\n";
+        fs::write(&path, user).unwrap();
+
+        let outcome = write_default_config(&path, InitMode::Merge).unwrap();
+
+        let InitOutcome::Merged { added } = outcome else {
+            panic!("expected merge, got {outcome:?}");
+        };
+        assert!(added.contains(&"monitor.watchdog".to_string()));
+        assert!(!added.contains(&"monitor.poll_interval".to_string()));
+
+        let merged = TomlConfig::load(&path).unwrap();
+        assert_eq!(merged.webhook.url.as_deref(), Some("https://example.com"));
+        assert_eq!(merged.monitor.poll_interval, Some(30));
+    }
+
+    #[test]
+    fn merge_rejects_invalid_toml() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("ddns-a.toml");
+        fs::write(&path, "[webhook\n").unwrap();
+
+        let result = write_default_config(&path, InitMode::Merge);
+
+        assert!(matches!(result, Err(ConfigError::TomlParse(_))));
+    }
+}
+
+mod merge {
+    use super::*;
+
+    #[test]
+    fn inserts_missing_keys_at_end_of_section() {
+        let user = "[webhook]\nurl = \"https://example.com\"\n\n[monitor]\npoll_interval = 30\n# watchdog = \"exit\"\n\n[output]\nsummary = true\n";
+
+        let (merged, added) = merge_template(user, TEMPLATE);
+
+        assert_eq!(added, vec!["webhook.connectivity_check"]);
+        assert_eq!(
+            merged,
+            "[webhook]\nurl = \"https://example.com\"\n\n# New: check connectivity\n# connectivity_check = \"family\"\n\n[monitor]\npoll_interval = 30\n# watchdog = \"exit\"\n\n[output]\nsummary = true\n"
+        );
+    }
+
+    #[test]
+    fn appends_missing_sections_whole() {
+        let user = "[webhook]\nurl = \"u\"\n# connectivity_check = \"none\"\n";
+
+        let (merged, added) = merge_template(user, TEMPLATE);
+
+        assert_eq!(
+            added,
+            vec![
+                "monitor.poll_interval",
+                "monitor.watchdog",
+                "output.summary"
+            ]
+        );
+        assert!(merged.starts_with(user));
+        assert!(merged.contains(
+            "\n[monitor]\n# Polling interval in seconds (default: 60)\npoll_interval = 60\n"
+        ));
+        assert!(merged.ends_with("[output]\n# Write a run summary\n# summary = true\n"));
+        merged.parse::<toml::Table>().unwrap();
+    }
+
+    #[test]
+    fn commented_out_keys_count_as_present() {
+        let user = "[webhook]\n# url = \"x\"\n# connectivity_check = \"family\"\n\n[monitor]\n# poll_interval = 60\n# watchdog = \"off\"\n\n[output]\n# summary = false\n";
+
+        let (merged, added) = merge_template(user, TEMPLATE);
+
+        assert!(added.is_empty());
+        assert_eq!(merged, user);
+    }
+
+    #[test]
+    fn notes_and_sub_tables_are_not_added() {
+        let (merged, _) = merge_template("# empty\n", TEMPLATE);
+
+        // Whole sections are copied verbatim...
+        assert!(merged.contains("# Every attempt carries extra headers"));
+
+        let user = "[webhook]\nurl = \"u\"\n\n[monitor]\npoll_interval = 1\n";
+        let (merged, _) = merge_template(user, TEMPLATE);
+
+        // ...but only key blocks are inserted into existing sections
+        assert!(!merged.contains("X-Custom-Header"));
+        assert!(!merged.contains("Every attempt"));
+    }
+
+    #[test]
+    fn preserves_preamble_and_user_comments() {
+        let user = "# my notes\n\n[monitor]\n# keep me\npoll_interval = 5 # inline\n";
+
+        let (merged, _) = merge_template(user, TEMPLATE);
+
+        assert!(
+            merged.starts_with("# my notes\n\n[monitor]\n# keep me\npoll_interval = 5 # inline\n")
+        );
+    }
+
+    #[test]
+    fn real_template_merges_into_minimal_file() {
+        let user = "[webhook]\nurl = \"https://example.com\"\nip_version = \"both\"\n";
+
+        let (merged, added) = merge_template(user, &default_config_template());
+
+        assert!(added.contains(&"webhook.connectivity_check".to_string()));
+        assert!(added.contains(&"output.summary_file".to_string()));
+        TomlConfig::parse(&merged).unwrap();
+    }
+}
+
+mod from_snapshot {
+    use super::*;
+
+    const LISTING: &str =
+        r#"[{"name": "Ethernet", "kind": "Ethernet", "ipv4_addresses": [], "ipv6_addresses": []}]"#;
+
+    #[test]
+    fn writes_filtered_template() {
+        let dir = tempdir().unwrap();
+        let listing = dir.path().join("adapters.json");
+        let path = dir.path().join("ddns-a.toml");
+        fs::write(&listing, LISTING).unwrap();
+
+        let outcome = write_config_from_snapshot(&path, InitMode::CreateNew, &listing).unwrap();
+
+        assert_eq!(outcome, InitOutcome::Created);
+        let config = TomlConfig::load(&path).unwrap();
+        assert_eq!(config.filter.include, ["^Ethernet$"]);
+    }
+
+    #[test]
+    fn refuses_existing_file_unless_forced() {
+        let dir = tempdir().unwrap();
+        let listing = dir.path().join("adapters.json");
+        let path = dir.path().join("ddns-a.toml");
+        fs::write(&listing, LISTING).unwrap();
+        fs::write(&path, "# mine\n").unwrap();
+
+        let result = write_config_from_snapshot(&path, InitMode::Merge, &listing);
+        assert!(matches!(result, Err(ConfigError::FileExists { .. })));
+
+        let outcome = write_config_from_snapshot(&path, InitMode::Overwrite, &listing).unwrap();
+        assert_eq!(outcome, InitOutcome::Overwritten);
+    }
+
+    #[test]
+    fn listing_without_usable_adapters_is_rejected() {
+        let dir = tempdir().unwrap();
+        let listing = dir.path().join("adapters.json");
+        let path = dir.path().join("ddns-a.toml");
+        fs::write(
+            &listing,
+            r#"[{"name": "lo", "kind": "Loopback", "ipv4_addresses": [], "ipv6_addresses": []}]"#,
+        )
+        .unwrap();
+
+        let result = write_config_from_snapshot(&path, InitMode::CreateNew, &listing);
+
+        assert!(matches!(result, Err(ConfigError::InvalidSnapshot { .. })));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn missing_listing_is_read_error() {
+        let dir = tempdir().unwrap();
+
+        let result = write_config_from_snapshot(
+            &dir.path().join("ddns-a.toml"),
+            InitMode::CreateNew,
+            &dir.path().join("missing.json"),
+        );
+
+        assert!(matches!(result, Err(ConfigError::FileRead { .. })));
+    }
+}
@@ -0,0 +1,475 @@
+//! Message catalog for user-facing CLI errors, hints and help.
+//!
+//! Messages are looked up by [`Locale`], selected with `[log] locale` or the
+//! `LC_ALL` / `LC_MESSAGES` / `LANG` environment variables; English is the
+//! default and the fallback for unsupported locales. Only the text around
+//! values is translated: paths, values and underlying error reasons are
+//! shown as they are.
+//!
+//! CLI help is printed before any config file is read, so it follows the
+//! environment only.
+
+use std::fmt;
+use std::path::Path;
+
+use super::cli::Cli;
+use super::discovery;
+use super::error::{ConfigError, field};
+use super::toml::TomlConfig;
+use crate::webhook::RetryOverlap;
+
+/// Language of user-facing messages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    /// English (default)
+    #[default]
+    En,
+    /// Simplified Chinese
+    Zh,
+}
+
+/// A catalog entry, translated by [`Locale::text`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    /// Prefix of configuration errors
+    ConfigError,
+    /// Prefix of template check errors
+    TemplateError,
+    /// Prefix of other errors
+    Error,
+    /// Hint pointing to `ddns-a init`
+    InitHint,
+}
+
+impl Locale {
+    /// All supported locales.
+    pub const ALL: [Self; 2] = [Self::En, Self::Zh];
+
+    /// Returns the name used to select this locale in configuration.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::Zh => "zh",
+        }
+    }
+
+    /// Returns the locale of a language tag such as `zh`, `zh-Hans` or
+    /// `zh_CN.UTF-8`, or `None` if the language is not supported.
+    ///
+    /// `C` and `POSIX` are English.
+    #[must_use]
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "c" | "posix" => Some(Self::En),
+            language => Self::ALL.into_iter().find(|l| l.name() == language),
+        }
+    }
+
+    /// Returns the locale of the environment, English if unset or unsupported.
+    #[must_use]
+    pub fn from_env() -> Self {
+        Self::from_env_with(|name| std::env::var(name).ok())
+    }
+
+    /// Like [`from_env`](Self::from_env), reading variables with `var`.
+    ///
+    /// The first non-empty of `LC_ALL`, `LC_MESSAGES` and `LANG` decides,
+    /// following POSIX precedence.
+    pub(super) fn from_env_with(var: impl Fn(&str) -> Option<String>) -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .find_map(|name| var(name).filter(|value| !value.is_empty()))
+            .and_then(|tag| Self::from_tag(&tag))
+            .unwrap_or_default()
+    }
+
+    /// Returns the text of `message` in this locale.
+    #[must_use]
+    pub const fn text(self, message: Message) -> &'static str {
+        match (self, message) {
+            (Self::En, Message::ConfigError) => "Configuration error",
+            (Self::Zh, Message::ConfigError) => "配置错误",
+            (Self::En, Message::TemplateError) => "Template error",
+            (Self::Zh, Message::TemplateError) => "模板错误",
+            (Self::En, Message::Error) => "Error",
+            (Self::Zh, Message::Error) => "错误",
+            (Self::En, Message::InitHint) => {
+                "Run 'ddns-a init' to generate a configuration template."
+            }
+            (Self::Zh, Message::InitHint) => "运行 'ddns-a init' 生成配置文件模板。",
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Resolves `[log] locale`, falling back to the environment.
+pub(super) fn resolve_locale(toml: Option<&TomlConfig>) -> Result<Locale, ConfigError> {
+    toml.and_then(|t| t.log.locale.as_deref()).map_or_else(
+        || Ok(Locale::from_env()),
+        |value| {
+            Locale::from_tag(value).ok_or_else(|| ConfigError::InvalidLocale {
+                value: value.to_string(),
+            })
+        },
+    )
+}
+
+/// Returns the locale for messages about `cli`'s configuration.
+///
+/// Reads `[log] locale` from the config file [`ValidatedConfig::load`]
+/// would use, without validating the rest of it, so errors in that file
+/// are still reported in the configured language.
+///
+/// [`ValidatedConfig::load`]: super::ValidatedConfig::load
+#[must_use]
+pub fn configured_locale(cli: &Cli) -> Locale {
+    discovery::config_path(cli)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| content.parse::<::toml::Table>().ok())
+        .and_then(|table| {
+            let locale = table.get("log")?.get("locale")?.as_str()?;
+            Locale::from_tag(locale)
+        })
+        .unwrap_or_else(Locale::from_env)
+}
+
+/// The Chinese text of an invalid option `value` and the `expected` ones.
+fn zh_expected(option: &str, value: &str, expected: &str) -> String {
+    format!("无效的{option} '{value}'：应为 {expected}")
+}
+
+/// The Chinese text of an invalid configuration `section` and why.
+fn zh_section(section: &str, reason: &str) -> String {
+    format!("无效的 {section} 配置：{reason}")
+}
+
+/// The Chinese text of a configuration file error: `what` of `path` failed
+/// because of `reason`.
+fn zh_file(what: &str, path: &Path, reason: impl fmt::Display) -> String {
+    format!("{what} '{}'：{reason}", path.display())
+}
+
+/// The Chinese text of a missing required `field`, translating the hints of
+/// the well-known fields.
+fn zh_missing(name: &str, hint: &str) -> String {
+    match name {
+        field::URL => "缺少必填项：url。请使用 --url 或在配置文件中设置 webhook.url".to_string(),
+        field::IP_VERSION => {
+            "缺少必填项：ip_version。请使用 --ip-version 或在配置文件中设置 webhook.ip_version"
+                .to_string()
+        }
+        _ => format!("缺少必填项：{name}。{hint}"),
+    }
+}
+
+/// The Chinese text of a retry policy outlasting the poll interval.
+fn zh_overlap(overlap: &RetryOverlap) -> String {
+    format!(
+        "重试策略超过轮询间隔：重试最多等待 {}s，长于 {}s 的轮询间隔",
+        overlap.worst_case.as_secs(),
+        overlap.poll_interval.as_secs()
+    )
+}
+
+/// The Chinese text of an `option` needing a `feature` this build lacks.
+fn zh_feature(feature: &str, option: &str) -> String {
+    format!("{option} 需要 '{feature}' 功能，但此构建未包含该功能")
+}
+
+/// Text of an existing configuration file, shown after its path.
+const ZH_FILE_EXISTS: &str = "已存在（使用 --force 覆盖，或使用 --merge 添加新选项）";
+
+/// Expected values of invalid options, for [`zh_expected`].
+const ZH_ADAPTER_KINDS: &str = "ethernet、wireless、virtual 或 loopback";
+const ZH_SOURCES: &str = "adapter、public 或 both";
+const ZH_OVERLAP: &str = "off、warn 或 error";
+const ZH_DELIVERY: &str = "batched 或 per_change";
+const ZH_ENCODINGS: &str = "json、yaml 或 cbor";
+const ZH_HEADER_FORMATS: &str = "'Key=Value' 或 'Key: Value'";
+
+impl ConfigError {
+    /// Formats the error in `locale`.
+    ///
+    /// English is the [`Display`](fmt::Display) text.
+    #[must_use]
+    pub fn localized(&self, locale: Locale) -> String {
+        match locale {
+            Locale::En => self.to_string(),
+            Locale::Zh => self.zh(),
+        }
+    }
+
+    /// The Chinese text of the error, one arm per variant.
+    fn zh(&self) -> String {
+        match self {
+            Self::FileRead { path, source } => zh_file("无法读取配置文件", path, source),
+            Self::TomlParse(e) => format!("无法解析 TOML 配置：{e}"),
+            Self::FileWrite { path, source } => zh_file("无法写入配置文件", path, source),
+            Self::FileExists { path } => format!("配置文件 '{}' {ZH_FILE_EXISTS}", path.display()),
+            Self::InvalidSnapshot { path, reason } => zh_file("无效的网卡列表", path, reason),
+            Self::MissingRequired { field, hint } => zh_missing(field, hint),
+            Self::InvalidUrl { url, reason } => format!("无效的 URL '{url}'：{reason}"),
+            Self::InvalidRegex { pattern, source } => {
+                format!("无效的正则表达式 '{pattern}'：{source}")
+            }
+            Self::InvalidCidr { value, source } => format!("无效的 CIDR '{value}'：{source}"),
+            Self::InvalidDuration { field, reason } => format!("{field} 的时长无效：{reason}"),
+            Self::InvalidRetry(reason) => format!("无效的重试配置：{reason}"),
+            Self::InvalidDiscovery(reason) => zh_section("webhook.discovery", reason),
+            Self::InvalidDnsServers(reason) => format!("无效的 http.dns_servers：{reason}"),
+            Self::InvalidHttpBinding(reason) => format!("无效的 http 绑定：{reason}"),
+            Self::InvalidTls(reason) => zh_section("http.tls", reason),
+            Self::InvalidProvider(reason) => zh_section("webhook.provider", reason),
+            Self::InvalidPreRequest(reason) => zh_section("webhook.pre_request", reason),
+            Self::InvalidOAuth2(reason) => zh_section("webhook.oauth2", reason),
+            Self::InvalidExpectJson(reason) => zh_section("webhook.expect_json", reason),
+            Self::InvalidCertificate(reason) => zh_section("webhook.certificate", reason),
+            Self::InvalidCircuitBreaker(reason) => zh_section("webhook.circuit_breaker", reason),
+            Self::InvalidAddressStorm(reason) => zh_section("monitor.address_storm", reason),
+            Self::InvalidTarget(reason) => zh_section("webhook.targets", reason),
+            Self::InvalidVersionUrl(reason) => format!("无效的按 IP 版本 webhook URL：{reason}"),
+            Self::InvalidPublicAddress(reason) => zh_section("monitor.public", reason),
+            Self::InvalidState(reason) => zh_section("monitor.state", reason),
+            Self::InvalidHealth(reason) => zh_section("health", reason),
+            Self::InvalidUpdate(reason) => zh_section("update", reason),
+            Self::InvalidExpect(reason) => zh_section("expect", reason),
+            Self::InvalidExec(reason) => zh_section("action.exec", reason),
+            Self::InvalidMqtt(reason) => zh_section("mqtt", reason),
+            Self::InvalidEmail(reason) => zh_section("email", reason),
+            Self::InvalidNotify(reason) => zh_section("notify", reason),
+            Self::InvalidAdapterPriority(reason) => zh_section("monitor.adapter_priority", reason),
+            Self::InvalidMethod(method) => format!("无效的 HTTP 方法 '{method}'"),
+            Self::InvalidIpVersion { value } => {
+                format!("无效的 IP 版本 '{value}'：应为 ipv4、ipv6 或 both")
+            }
+            Self::InvalidNotifyOn { value } => zh_expected("notify_on", value, "added 或 removed"),
+            Self::InvalidAdapterKind { value } => zh_expected("网卡类型", value, ZH_ADAPTER_KINDS),
+            Self::InvalidConnectivityCheck { value } => {
+                zh_expected("连通性检查", value, "none 或 family")
+            }
+            Self::InvalidWatchdog { value } => zh_expected("看门狗动作", value, "off、log 或 exit"),
+            Self::InvalidAddressSource { value } => zh_expected("地址来源", value, ZH_SOURCES),
+            Self::InvalidOverlapCheck { value } => zh_expected("重试重叠检查", value, ZH_OVERLAP),
+            Self::InvalidJitter { value } => zh_expected("重试抖动", value, "none、full 或 equal"),
+            Self::RetryOverlap(overlap) => zh_overlap(overlap),
+            Self::InvalidEchoCheck { value } => {
+                zh_expected("回显检查", value, "off、header 或 body")
+            }
+            Self::InvalidDeliveryMode { value } => zh_expected("投递模式", value, ZH_DELIVERY),
+            Self::InvalidPayloadEncoding { value } => zh_expected("负载编码", value, ZH_ENCODINGS),
+            Self::InvalidPayloadFormat { value } => {
+                zh_expected("负载格式", value, "json-v1 或 none")
+            }
+            Self::InvalidLocale { value } => format!("无效的语言 '{value}'：应为 en 或 zh"),
+            Self::InvalidLogTarget { value } => {
+                format!("无效的日志输出 '{value}'：应为 stderr 或 system")
+            }
+            Self::InvalidEnv { name, reason } => format!("无效的环境变量 {name}：{reason}"),
+            Self::UnsupportedPlatform { option } => format!("当前平台不支持 {option}"),
+            Self::FeatureDisabled { feature, option } => zh_feature(feature, option),
+            Self::InvalidHeader { value } => zh_expected("请求头格式", value, ZH_HEADER_FORMATS),
+            Self::InvalidHeaderName { name, reason } => {
+                format!("无效的请求头名称 '{name}'：{reason}")
+            }
+            Self::InvalidHeaderValue { name, reason } => {
+                format!("请求头 '{name}' 的值无效：{reason}")
+            }
+            Self::InvalidTemplate { reason } => format!("无效的请求体模板：{reason}"),
+        }
+    }
+}
+
+/// Help template for commands with subcommands.
+const ZH_PARENT_TEMPLATE: &str = "\
+{before-help}{about-with-newline}
+用法: {usage}
+
+命令:
+{subcommands}
+
+选项:
+{options}{after-help}";
+
+/// Help template for commands without subcommands.
+const ZH_LEAF_TEMPLATE: &str = "\
+{before-help}{about-with-newline}
+用法: {usage}
+
+选项:
+{options}{after-help}";
+
+/// Chinese help, keyed by argument id or subcommand name.
+const ZH_HELP: &[(&str, &str)] = &[
+    ("ddns-a", "DDNS-A：动态 DNS 地址监视器"),
+    ("url", "Webhook URL（运行模式必填）"),
+    ("ip_version", "要监视的 IP 版本（运行模式必填）"),
+    ("method", "Webhook 请求的 HTTP 方法"),
+    (
+        "headers",
+        "'Key=Value' 或 'Key: Value' 格式的 HTTP 请求头（可多次指定）",
+    ),
+    ("bearer", "Authorization 请求头的 Bearer 令牌"),
+    ("body_template", "Webhook 请求体的 Handlebars 模板"),
+    (
+        "provider",
+        "代替通用 webhook 更新的 DNS 服务商预设\
+         （duckdns、porkbun、gandi、desec、noip、dynu、freedns）",
+    ),
+    (
+        "provider_domain",
+        "服务商预设的域名（例如 DuckDNS 子域名或主机名）",
+    ),
+    ("provider_token", "服务商预设的 API 令牌"),
+    (
+        "provider_secret",
+        "服务商预设的密钥（Porkbun 的 secret API key、No-IP 的密码）",
+    ),
+    ("include_adapters", "要包含的网卡的正则表达式（可多次指定）"),
+    ("exclude_adapters", "要排除的网卡的正则表达式（可多次指定）"),
+    (
+        "include_kinds",
+        "要包含的网卡类型（可多次指定或以逗号分隔）",
+    ),
+    (
+        "exclude_kinds",
+        "要排除的网卡类型（可多次指定或以逗号分隔）",
+    ),
+    ("poll_interval", "轮询间隔（秒）"),
+    ("poll_only", "禁用系统事件监听，仅使用轮询"),
+    ("retry_max", "最大重试次数"),
+    ("retry_delay", "首次重试延迟（秒）"),
+    (
+        "config",
+        "配置文件路径（默认：./ddns-a.toml、用户配置目录下的 ddns-a/config.toml、\
+         /etc/ddns-a/config.toml 中第一个存在的文件）",
+    ),
+    ("no_config", "不在标准位置搜索配置文件"),
+    ("state_file", "用于检测重启前后变化的状态文件路径"),
+    ("no_lock", "不锁定状态文件（允许其他实例同时使用）"),
+    ("pid_file", "将进程 ID 写入此文件（退出时删除）"),
+    ("daemon", "脱离终端在后台运行（仅 Unix）"),
+    ("tray", "显示托盘图标，包含当前地址和通知控制（仅 Windows）"),
+    (
+        "health",
+        "在 ADDR（例如 127.0.0.1:8053）上提供 /healthz 健康检查端点",
+    ),
+    ("dry_run", "测试模式：只记录变化，不发送 webhook"),
+    (
+        "record_payloads",
+        "测试模式：把渲染后的负载作为 golden 文件写入 DIR，而不是发送",
+    ),
+    ("verbose", "启用详细日志"),
+    ("init", "生成默认配置文件"),
+    ("output", "配置文件的输出路径"),
+    ("force", "覆盖已存在的文件"),
+    ("merge", "向已存在的文件添加缺少的选项，保留原有的值"),
+    (
+        "from_snapshot",
+        "只包含此 JSON 列表（网卡数组或状态文件）中的网卡",
+    ),
+    (
+        "test-webhook",
+        "向主 webhook（或其他已配置的目标）发送一次模拟变化并报告结果",
+    ),
+    (
+        "all",
+        "测试每个已配置的目标：webhook、聊天、MQTT、邮件和 exec 动作",
+    ),
+    ("targets", "测试此名称的目标而不是主 webhook（可多次指定）"),
+    (
+        "send-test",
+        "向主 webhook 发送一次模拟变化，并打印每个请求及其响应（--dry-run：只打印请求）",
+    ),
+    ("template", "处理 webhook 请求体模板"),
+    ("template check", "用示例变化渲染请求体模板并打印输出"),
+    ("file", "要检查的 Handlebars 模板"),
+    ("sample", "JSON 变化数组或捕获的负载（默认：一次模拟变化）"),
+    ("json", "要求渲染结果是有效的 JSON"),
+    (
+        "verify",
+        "用当前配置渲染记录的变化，并与其 golden 文件比较差异",
+    ),
+    ("dir", "--record-payloads 写入的目录"),
+    (
+        "self-update",
+        "用最新的已签名版本替换此程序（需要 `[update] enabled = true`）",
+    ),
+    ("check_only", "只报告是否有更新的版本"),
+    ("reinstall", "即使最新版本不比当前版本新也安装"),
+    (
+        "replay",
+        "打印两个已保存的状态文件之间的变化（或用 --notify 发送）",
+    ),
+    ("from", "较早的状态文件，例如一份备份"),
+    ("to", "较晚的状态文件"),
+    ("notify", "把这些变化发送到已配置的 webhook"),
+    ("status", "通过健康检查端点显示正在运行的实例的状态"),
+    (
+        "check",
+        "获取一次地址，打印通过过滤器的网卡和地址，并与状态文件比较",
+    ),
+    ("as_json", "以 JSON 格式打印状态报告"),
+    ("history", "打印历史文件中记录的检测到的变化和投递结果"),
+    ("since", "只显示此时长以内的记录，例如 90s、30m、24h 或 7d"),
+    ("adapter", "只显示与此网卡有关的记录"),
+    (
+        "history_file",
+        "要读取的历史文件（默认：`output.history_file`）",
+    ),
+    ("service", "作为 Windows 原生服务运行（仅 Windows）"),
+    (
+        "install",
+        "使用当前找到的配置文件，把 ddns-a 注册为自动启动的服务",
+    ),
+    ("uninstall", "停止并删除服务"),
+    ("run", "在服务控制管理器下运行（已安装服务使用的命令）"),
+];
+
+fn zh_help(key: &str) -> Option<&'static str> {
+    ZH_HELP
+        .iter()
+        .find_map(|&(k, help)| (k == key).then_some(help))
+}
+
+/// Translates the about and argument help of `command` and its subcommands.
+pub(super) fn localize_command(command: clap::Command, locale: Locale) -> clap::Command {
+    if locale == Locale::En {
+        return command;
+    }
+    localize(command, "")
+}
+
+/// Translates `command`, a subcommand of `parent`; its about is keyed by
+/// `"parent name"` where the bare name is taken by another command.
+fn localize(command: clap::Command, parent: &str) -> clap::Command {
+    let template = if command.has_subcommands() {
+        ZH_PARENT_TEMPLATE
+    } else {
+        ZH_LEAF_TEMPLATE
+    };
+    let name = command.get_name().to_string();
+    let about = zh_help(&format!("{parent} {name}")).or_else(|| zh_help(&name));
+    let command = match about {
+        Some(about) => command.about(about).long_about(None),
+        None => command,
+    };
+    // Long help is dropped, or `--help` would show the English text
+    command
+        .help_template(template)
+        .mut_args(|arg| match zh_help(arg.get_id().as_str()) {
+            Some(help) => arg.help(help).long_help(None),
+            None => arg,
+        })
+        .mut_subcommands(|sub| localize(sub, &name))
+}
@@ -0,0 +1,259 @@
+//! Tests for the message catalog.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+use tempfile::NamedTempFile;
+
+use super::cli::Cli;
+use super::error::{ConfigError, field};
+use super::locale::{Locale, Message, configured_locale};
+
+mod selection {
+    use super::*;
+
+    fn from_vars(vars: &[(&str, &str)]) -> Locale {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        Locale::from_env_with(|name| vars.get(name).map(ToString::to_string))
+    }
+
+    #[test]
+    fn parses_language_tags() {
+        for tag in ["zh", "ZH", "zh_CN.UTF-8", "zh-Hans", "zh_TW@stroke"] {
+            assert_eq!(Locale::from_tag(tag), Some(Locale::Zh), "{tag}");
+        }
+        for tag in ["en", "en_US.UTF-8", "C", "C.UTF-8", "POSIX"] {
+            assert_eq!(Locale::from_tag(tag), Some(Locale::En), "{tag}");
+        }
+        assert_eq!(Locale::from_tag("de_DE.UTF-8"), None);
+        assert_eq!(Locale::from_tag(""), None);
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for locale in Locale::ALL {
+            assert_eq!(Locale::from_tag(locale.name()), Some(locale));
+            assert_eq!(locale.to_string(), locale.name());
+        }
+    }
+
+    #[test]
+    fn env_follows_posix_precedence() {
+        assert_eq!(
+            from_vars(&[("LC_ALL", "zh_CN.UTF-8"), ("LANG", "en_US.UTF-8")]),
+            Locale::Zh
+        );
+        assert_eq!(
+            from_vars(&[("LC_MESSAGES", "en_US"), ("LANG", "zh_CN")]),
+            Locale::En
+        );
+        assert_eq!(from_vars(&[("LC_ALL", ""), ("LANG", "zh_CN")]), Locale::Zh);
+    }
+
+    #[test]
+    fn env_defaults_to_english() {
+        assert_eq!(from_vars(&[]), Locale::En);
+        assert_eq!(from_vars(&[("LANG", "fr_FR.UTF-8")]), Locale::En);
+    }
+
+    #[test]
+    fn config_file_locale_wins() {
+        let mut file = NamedTempFile::new().unwrap();
+        // The rest of the file need not be valid for the locale to be found
+        writeln!(file, "[log]\nlocale = \"zh\"\n\n[webhook]\nunknown = 1").unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let cli = Cli::parse_from_iter(["ddns-a", "--config", path]);
+
+        assert_eq!(configured_locale(&cli), Locale::Zh);
+    }
+
+    #[test]
+    fn unreadable_config_falls_back_to_env() {
+        let cli = Cli::parse_from_iter(["ddns-a", "--config", "/nonexistent/ddns-a.toml"]);
+
+        assert_eq!(configured_locale(&cli), Locale::from_env());
+    }
+}
+
+mod messages {
+    use super::*;
+
+    const MESSAGES: [Message; 4] = [
+        Message::ConfigError,
+        Message::TemplateError,
+        Message::Error,
+        Message::InitHint,
+    ];
+
+    #[test]
+    fn every_message_is_translated() {
+        for message in MESSAGES {
+            assert!(!Locale::Zh.text(message).is_empty());
+            assert_ne!(Locale::En.text(message), Locale::Zh.text(message));
+        }
+    }
+
+    #[test]
+    fn english_error_is_display() {
+        let error = ConfigError::InvalidMethod("FETCH".to_string());
+
+        assert_eq!(error.localized(Locale::En), error.to_string());
+    }
+
+    #[test]
+    fn chinese_error_keeps_values() {
+        let error = ConfigError::InvalidIpVersion {
+            value: "ipv5".to_string(),
+        };
+
+        assert_eq!(
+            error.localized(Locale::Zh),
+            "无效的 IP 版本 'ipv5'：应为 ipv4、ipv6 或 both"
+        );
+    }
+
+    #[test]
+    fn chinese_missing_field_translates_hint() {
+        let error = ConfigError::missing(field::URL, "Use --url or set webhook.url in config file");
+
+        let message = error.localized(Locale::Zh);
+
+        assert!(message.starts_with("缺少必填项：url"));
+        assert!(!message.contains("Use --url"));
+    }
+
+    #[test]
+    fn chinese_file_errors_keep_paths() {
+        let error = ConfigError::FileExists {
+            path: PathBuf::from("ddns-a.toml"),
+        };
+
+        assert!(error.localized(Locale::Zh).contains("'ddns-a.toml'"));
+    }
+
+    #[test]
+    fn chinese_errors_match_their_variant() {
+        let cases = [
+            (
+                ConfigError::FileExists {
+                    path: PathBuf::from("ddns-a.toml"),
+                },
+                "配置文件 'ddns-a.toml' 已存在（使用 --force 覆盖，或使用 --merge 添加新选项）",
+            ),
+            (
+                ConfigError::missing("state_file", "Set monitor.state_file"),
+                "缺少必填项：state_file。Set monitor.state_file",
+            ),
+            (
+                ConfigError::InvalidAdapterKind {
+                    value: "modem".to_string(),
+                },
+                "无效的网卡类型 'modem'：应为 ethernet、wireless、virtual 或 loopback",
+            ),
+            (
+                ConfigError::InvalidTls("no CA".to_string()),
+                "无效的 http.tls 配置：no CA",
+            ),
+            (
+                ConfigError::FeatureDisabled {
+                    feature: "tray",
+                    option: "--tray",
+                },
+                "--tray 需要 'tray' 功能，但此构建未包含该功能",
+            ),
+        ];
+
+        for (error, expected) in cases {
+            assert_eq!(error.localized(Locale::Zh), expected, "{error:?}");
+        }
+    }
+}
+
+mod help {
+    use super::*;
+
+    /// Collects `(command path, argument id, help)` for every argument.
+    fn arg_helps(command: &clap::Command, path: &str) -> Vec<(String, String, String)> {
+        let path = format!("{path} {}", command.get_name());
+        let mut helps: Vec<_> = command
+            .get_arguments()
+            .map(|arg| {
+                let help = arg.get_help().map(ToString::to_string).unwrap_or_default();
+                (path.clone(), arg.get_id().to_string(), help)
+            })
+            .collect();
+        for sub in command.get_subcommands() {
+            helps.extend(arg_helps(sub, &path));
+        }
+        helps
+    }
+
+    #[test]
+    fn english_help_is_unchanged() {
+        let localized = Cli::localized_command(Locale::En);
+
+        assert_eq!(
+            arg_helps(&localized, ""),
+            arg_helps(&<Cli as clap::CommandFactory>::command(), "")
+        );
+    }
+
+    #[test]
+    fn every_argument_is_translated() {
+        let english = arg_helps(&<Cli as clap::CommandFactory>::command(), "");
+        let chinese = arg_helps(&Cli::localized_command(Locale::Zh), "");
+
+        assert_eq!(english.len(), chinese.len());
+        for (en, zh) in english.iter().zip(&chinese) {
+            assert_ne!(en.2, zh.2, "untranslated help for {} --{}", en.0, en.1);
+        }
+    }
+
+    #[test]
+    fn chinese_help_uses_translated_headings() {
+        let mut command = Cli::localized_command(Locale::Zh);
+
+        let help = command.render_help().to_string();
+
+        assert!(help.starts_with("DDNS-A：动态 DNS 地址监视器"));
+        assert!(help.contains("用法: "));
+        assert!(help.contains("命令:"));
+        assert!(help.contains("选项:"));
+        assert!(!help.contains("Options:"));
+        assert!(
+            !command
+                .render_long_help()
+                .to_string()
+                .contains("config directory")
+        );
+    }
+
+    #[test]
+    fn subcommands_are_translated() {
+        let mut command = Cli::localized_command(Locale::Zh);
+        let init = command.find_subcommand_mut("init").unwrap();
+
+        let help = init.render_help().to_string();
+
+        assert!(help.starts_with("生成默认配置文件"));
+        assert!(!help.contains("命令:"));
+    }
+
+    #[test]
+    fn nested_subcommand_keys_win_over_shared_names() {
+        let mut command = Cli::localized_command(Locale::Zh);
+
+        let check = command.find_subcommand_mut("check").unwrap().render_help();
+        let template_check = command
+            .find_subcommand_mut("template")
+            .unwrap()
+            .find_subcommand_mut("check")
+            .unwrap()
+            .render_help();
+
+        assert!(check.to_string().starts_with("获取一次地址"));
+        assert!(template_check.to_string().starts_with("用示例变化渲染"));
+    }
+}
@@ -0,0 +1,23 @@
+//! Resolution of `[log] target`.
+
+use crate::logging::LogTarget;
+
+use super::error::ConfigError;
+use super::toml::TomlConfig;
+
+/// Resolves where log output goes; standard error unless `target` says
+/// otherwise.
+pub(super) fn resolve_log_target(toml: Option<&TomlConfig>) -> Result<LogTarget, ConfigError> {
+    let Some(value) = toml.and_then(|t| t.log.target.as_deref()) else {
+        return Ok(LogTarget::Stderr);
+    };
+    let target = LogTarget::from_name(value).ok_or_else(|| ConfigError::InvalidLogTarget {
+        value: value.to_string(),
+    })?;
+    if target == LogTarget::System && !cfg!(any(unix, windows)) {
+        return Err(ConfigError::UnsupportedPlatform {
+            option: "log.target = \"system\"",
+        });
+    }
+    Ok(target)
+}
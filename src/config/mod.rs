@@ -1,21 +1,28 @@
 //! Configuration layer for DDNS-A.
 //!
 //! This module provides:
-//! - CLI argument parsing ([`Cli`], [`Command`])
+//! - CLI argument parsing ([`Cli`], [`Command`], [`TemplateCommand`], [`CtlCommand`],
+//!   [`ServiceCommand`])
 //! - TOML configuration file parsing ([`TomlConfig`])
 //! - Validated configuration ([`ValidatedConfig`])
 //! - Configuration file generation ([`write_default_config`])
+//! - Config file discovery in standard locations when `--config` is not given
 //! - Default values ([`defaults`])
+//! - Localized errors, hints and help ([`Locale`], [`ConfigError::localized`])
 //!
 //! # Priority
 //!
 //! Configuration values are resolved with the following priority (highest to lowest):
 //!
 //! 1. **Explicit CLI arguments** - Values explicitly passed via command line
-//! 2. **TOML config file** - Values from the configuration file
-//! 3. **Built-in defaults** - Hardcoded default values
+//! 2. **Environment variables** - `DDNS_A_*` variables for the CLI options
+//!    not given ([`Cli::apply_env`]), e.g. `DDNS_A_URL`, `DDNS_A_BEARER`
+//! 3. **TOML config file** - Values from the configuration file
+//! 4. **Built-in defaults** - Hardcoded default values
 //!
 //! For required fields without defaults (`url`, `ip_version`), CLI takes precedence over TOML.
+//! Environment variables only fill CLI options, so everything said about CLI
+//! values below also holds for them.
 //!
 //! For optional fields with defaults (`method`, `poll_interval`, retry settings),
 //! explicit CLI values always win, then TOML, then built-in defaults.
@@ -37,31 +44,131 @@
 //! Some retry policy options are TOML-only (not available via CLI):
 //! - `retry.max_delay` (default: 60s) - Maximum retry delay
 //! - `retry.multiplier` (default: 2.0) - Exponential backoff multiplier
+//! - `retry.overlap` (default: warn) - Check retries against the poll interval
+//! - `retry.jitter` (default: none) - Randomize retry delays (full or equal jitter)
+//! - `[retry.queue]` - Keep changes whose retries ran out in the state file
+//! - `retry.shutdown_grace` (default: 0) - Let deliveries keep retrying after a stop signal
+//!
+//! The `[audit]` section (outbound request audit log) and `[http]` section
+//! (`User-Agent`, metadata headers, timeouts, connection pool and source
+//! binding) are also TOML-only, as is `[http.tls]` (custom CA, client certificate, insecure mode),
+//! `webhook.connectivity_check` (postpone deliveries while unreachable),
+//! `webhook.min_notify_interval` (coalesce changes between notifications),
+//! `webhook.notify_on` (deliver only added or only removed addresses),
+//! `filter.exclude_link_local`, `exclude_ula`, `exclude_temporary` and `include_private` (address classes),
+//! `filter.include_cidr` and `exclude_cidr` (address ranges),
+//! `filter.default_route_only` (only the adapter holding the default route),
+//! `monitor.state` (`"auto"`: a platform default state file without `state_file`),
+//! `[webhook.discovery]` (read the webhook URL from a DNS TXT/SRV record),
+//! `[webhook.provider]` (update a DNS provider's records directly),
+//! `[webhook.pre_request]` (fetch a session or CSRF token before each attempt),
+//! `[webhook.oauth2]` (authenticate with `OAuth2` client-credentials tokens),
+//! `[webhook.circuit_breaker]` (skip deliveries to a webhook that keeps failing),
+//! `[webhook.ipv4]`/`[webhook.ipv6]` (send one IP version's changes elsewhere),
+//! `[[webhook.targets]]` (further webhooks every change is also sent to),
+//! `[action.exec]` (a local command run on every delivery),
+//! `[mqtt]` (an MQTT broker every change is also published to),
+//! `[email]` (an SMTP server every change is also emailed through),
+//! `[notify.telegram]`/`[notify.discord]`/`[notify.slack]` (chats every change
+//! is also posted to as a readable message),
+//! `monitor.fetch_timeout` (default: 30s, abandon a hung adapter fetch),
+//! `monitor.debounce_ms` (default: 2000, window merging rapid changes; 0 disables it),
+//! `monitor.event_quiet_ms` (default: 250, quiet period coalescing API
+//! notification bursts in hybrid mode; 0 disables it),
+//! `monitor.poll_interval_v6` (separate IPv6 polling interval),
+//! `monitor.adaptive` with `monitor.poll_interval_min`/`poll_interval_max`
+//! (stretch the polling interval while addresses stay stable),
+//! `monitor.watchdog` (action on a stalled monitor loop), `monitor.track_dns`
+//! with `webhook.dns_body_template` (report DNS setting changes),
+//! `monitor.track_hostname` (report hostname changes),
+//! `monitor.track_link` (report adapters going up or down),
+//! `[monitor.address_storm]` (alert on adapters with too many IPv6 addresses),
+//! `monitor.source` with `[monitor.public]` (monitor the public address seen
+//! behind NAT),
+//! `webhook.payload_encoding` (JSON, YAML or CBOR bodies without a template),
+//! `webhook.payload_format` (the versioned default address body),
+//! `webhook.echo_check` (require receivers to echo a per-attempt nonce),
+//! `webhook.delivery` (one request per address change instead of per batch),
+//! `webhook.expect_json` (require a field of JSON responses to hold a value),
+//! `webhook.expect_body_regex` (require response bodies to match a regex),
+//! `[webhook.certificate]` (record or pin the webhook's TLS certificate),
+//! `webhook.ttl` (TTL hint for payloads and provider records),
+//! `monitor.adapter_priority` (adapters preferred for effective addresses), the
+//! `[expect]` section (static addresses whose drift is alerted), the
+//! `[output]` section (JSON run summary on exit, history file), `log.locale` (language of
+//! errors and hints, otherwise taken from `LANG`), `log.target` (syslog or the
+//! Windows Event Log instead of stderr) and the `[update]` section
+//! (opt-in `self-update` with a release signing key).
+//!
+//! Process options (`--pidfile`, `--daemon`, `--tray`, `--no-lock`) are
+//! CLI-only, since they describe how a particular init script or desktop
+//! session launches ddns-a rather than what it monitors.
 //!
 //! For full configurability, use a config file.
 //!
+//! # Cargo Features
+//!
+//! Body templates (`--body-template`, `retry.body_template`,
+//! `webhook.dns_body_template`) need the
+//! `templates` feature. Builds without it reject a configured template with
+//! [`ConfigError::FeatureDisabled`] rather than sending untemplated bodies.
+//!
 //! # Internal Tuning Parameters
 //!
 //! The following parameters are intentionally not user-configurable:
-//! - **Debounce window**: The monitor module uses a fixed 2-second debounce window
-//!   to merge rapid IP change events. This is tuned for typical OS notification patterns
-//!   and is not exposed via CLI or TOML configuration.
+//! - **Time-jump tolerance**: Wall-clock steps larger than 10 seconds relative to
+//!   the monotonic clock are logged and restart an open debounce window.
 
 mod cli;
 pub mod defaults;
+mod discovery;
+mod env;
 mod error;
+mod health;
+mod http;
+mod init;
+mod locale;
+mod log;
+mod monitor;
+mod outputs;
+mod parse;
+mod snapshot;
+mod state;
+mod template;
+mod tls;
 mod toml;
+mod update;
 mod validated;
+mod webhook;
 
 #[cfg(test)]
 mod cli_tests;
 #[cfg(test)]
+mod discovery_tests;
+#[cfg(test)]
+mod env_tests;
+#[cfg(test)]
+mod init_tests;
+#[cfg(test)]
+mod locale_tests;
+#[cfg(test)]
+mod snapshot_tests;
+#[cfg(test)]
 mod toml_tests;
 #[cfg(test)]
 #[path = "validated_tests/mod.rs"]
 mod validated_tests;
 
-pub use cli::{AdapterKindArg, Cli, Command, IpVersionArg};
+pub use cli::{
+    AdapterKindArg, Cli, Command, CtlCommand, IpVersionArg, ServiceCommand, TemplateCommand,
+};
+pub use env::ENV_PREFIX;
 pub use error::{ConfigError, field};
+pub use http::HttpSettings;
+pub use init::{InitMode, InitOutcome, write_config_from_snapshot, write_default_config};
+pub use locale::{Locale, Message, configured_locale};
+pub use monitor::MonitorSettings;
+pub use outputs::{EMAIL_TARGET, EXEC_TARGET, MQTT_TARGET, OutputSettings};
 pub use toml::{TomlConfig, default_config_template};
-pub use validated::{ValidatedConfig, write_default_config};
+pub use validated::ValidatedConfig;
+pub use webhook::{MAIN_TARGET, VersionUrl, WebhookSettings, WebhookTarget};
@@ -0,0 +1,43 @@
+//! Resolution of the `[expect]` section.
+
+use std::net::IpAddr;
+
+use crate::monitor::ExpectedAddresses;
+use crate::network::IpVersion;
+
+use crate::config::error::ConfigError;
+use crate::config::toml::TomlConfig;
+
+/// Resolves the expected static addresses; empty without `[expect]`.
+///
+/// Every address must belong to a monitored family, since drift in an
+/// unmonitored family could never be observed.
+pub(super) fn resolve_expect(
+    toml: Option<&TomlConfig>,
+    ip_version: IpVersion,
+) -> Result<ExpectedAddresses, ConfigError> {
+    let addresses = toml.map_or(&[][..], |t| &t.expect.addresses);
+    let invalid = |reason: String| ConfigError::InvalidExpect(reason);
+
+    let addresses = addresses
+        .iter()
+        .map(|value| {
+            let address: IpAddr = value
+                .trim()
+                .parse()
+                .map_err(|_| invalid(format!("'{value}' is not an IP address")))?;
+            let monitored = match address {
+                IpAddr::V4(_) => ip_version.includes_v4(),
+                IpAddr::V6(_) => ip_version.includes_v6(),
+            };
+            if !monitored {
+                return Err(invalid(format!(
+                    "'{address}' is outside the monitored IP version ({ip_version})"
+                )));
+            }
+            Ok(address)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ExpectedAddresses::new(addresses))
+}
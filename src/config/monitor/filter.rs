@@ -0,0 +1,142 @@
+//! Resolution of the adapter filter chain and the address-class filter.
+
+use std::collections::HashSet;
+
+use crate::network::AdapterKind;
+use crate::network::class::{AddressClassFilter, Cidr};
+use crate::network::filter::{DefaultRouteFilter, FilterChain, KindFilter, NameRegexFilter};
+
+use crate::config::cli::{AdapterKindArg, Cli};
+use crate::config::error::ConfigError;
+use crate::config::parse::parse_adapter_kind;
+use crate::config::toml::TomlConfig;
+
+/// Builds the adapter filter chain from CLI and TOML settings.
+pub(super) fn build_filter(
+    cli: &Cli,
+    toml: Option<&TomlConfig>,
+) -> Result<FilterChain, ConfigError> {
+    let mut chain = FilterChain::new();
+
+    // Collect all kinds from CLI and TOML (CLI replaces TOML)
+    let include_kinds: HashSet<AdapterKind> = collect_kinds(
+        &cli.include_kinds,
+        toml.map(|t| &t.filter.include_kinds),
+        !cli.include_kinds.is_empty(),
+    )?;
+    let exclude_kinds: HashSet<AdapterKind> = collect_kinds(
+        &cli.exclude_kinds,
+        toml.map(|t| &t.filter.exclude_kinds),
+        !cli.exclude_kinds.is_empty(),
+    )?;
+
+    // Default: exclude loopback UNLESS explicitly included
+    if !include_kinds.contains(&AdapterKind::Loopback) {
+        chain = chain.exclude(KindFilter::new([AdapterKind::Loopback]));
+    }
+
+    // Add kind excludes
+    if !exclude_kinds.is_empty() {
+        chain = chain.exclude(KindFilter::new(exclude_kinds));
+    }
+
+    // Add kind includes
+    if !include_kinds.is_empty() {
+        chain = chain.include(KindFilter::new(include_kinds));
+    }
+
+    // Collect name patterns (CLI replaces TOML)
+    let exclude_patterns = if cli.exclude_adapters.is_empty() {
+        toml.map_or(&[][..], |t| t.filter.exclude.as_slice())
+    } else {
+        cli.exclude_adapters.as_slice()
+    };
+
+    let include_patterns = if cli.include_adapters.is_empty() {
+        toml.map_or(&[][..], |t| t.filter.include.as_slice())
+    } else {
+        cli.include_adapters.as_slice()
+    };
+
+    // Add name excludes
+    for pattern in exclude_patterns {
+        let regex_filter =
+            NameRegexFilter::new(pattern).map_err(|e| ConfigError::InvalidRegex {
+                pattern: pattern.clone(),
+                source: e,
+            })?;
+        chain = chain.exclude(regex_filter);
+    }
+
+    // Add name includes
+    for pattern in include_patterns {
+        let regex_filter =
+            NameRegexFilter::new(pattern).map_err(|e| ConfigError::InvalidRegex {
+                pattern: pattern.clone(),
+                source: e,
+            })?;
+        chain = chain.include(regex_filter);
+    }
+
+    if toml.is_some_and(|t| t.filter.default_route_only) {
+        chain = chain.require(DefaultRouteFilter);
+    }
+
+    Ok(chain)
+}
+
+/// Resolves the address classes and ranges removed from snapshots
+/// (TOML-only).
+pub(super) fn resolve_address_classes(
+    toml: Option<&TomlConfig>,
+) -> Result<AddressClassFilter, ConfigError> {
+    let defaults = AddressClassFilter::default();
+    let Some(filter) = toml.map(|t| &t.filter) else {
+        return Ok(defaults);
+    };
+    Ok(defaults
+        .with_link_local(filter.exclude_link_local.unwrap_or(true))
+        .with_unique_local(filter.exclude_ula.unwrap_or(true))
+        .with_temporary(filter.exclude_temporary.unwrap_or(true))
+        .with_private(!filter.include_private.unwrap_or(true))
+        .with_include(parse_cidrs(&filter.include_cidr)?)
+        .with_exclude(parse_cidrs(&filter.exclude_cidr)?))
+}
+
+fn parse_cidrs(values: &[String]) -> Result<Vec<Cidr>, ConfigError> {
+    values
+        .iter()
+        .map(|value| {
+            value.parse().map_err(|e| ConfigError::InvalidCidr {
+                value: value.clone(),
+                source: e,
+            })
+        })
+        .collect()
+}
+
+/// Collects adapter kinds from CLI and/or TOML.
+///
+/// If `cli_replaces` is true, only CLI kinds are used; otherwise TOML kinds are used.
+fn collect_kinds(
+    cli_kinds: &[AdapterKindArg],
+    toml_kinds: Option<&Vec<String>>,
+    cli_replaces: bool,
+) -> Result<HashSet<AdapterKind>, ConfigError> {
+    let mut kinds = HashSet::new();
+
+    if cli_replaces || toml_kinds.is_none() {
+        // Use CLI kinds
+        for kind in cli_kinds {
+            kinds.insert((*kind).into());
+        }
+    } else if let Some(toml_list) = toml_kinds {
+        // Use TOML kinds
+        for kind_str in toml_list {
+            let kind = parse_adapter_kind(kind_str)?;
+            kinds.insert(kind);
+        }
+    }
+
+    Ok(kinds)
+}
@@ -0,0 +1,318 @@
+//! Monitor settings: which addresses are watched, and how often.
+
+use std::time::Duration;
+
+use crate::config::cli::Cli;
+use crate::config::defaults;
+use crate::config::error::ConfigError;
+use crate::config::parse::{parse_adapter_priority, parse_watchdog_action};
+use crate::config::toml::TomlConfig;
+use crate::monitor::{
+    AdaptivePolicy, DebouncePolicy, ExpectedAddresses, StormPolicy, WatchdogAction,
+};
+use crate::network::IpVersion;
+use crate::network::class::AddressClassFilter;
+use crate::network::filter::FilterChain;
+use crate::network::priority::AdapterPriority;
+use crate::network::public::{AddressSource, PublicIpSettings};
+
+mod expect;
+mod filter;
+mod public;
+mod storm;
+
+use expect::resolve_expect;
+use filter::{build_filter, resolve_address_classes};
+use public::resolve_public_address;
+use storm::resolve_address_storm;
+
+/// Settings of the adapter monitor, mostly from the `[monitor]` section.
+#[derive(Debug)]
+#[allow(clippy::struct_excessive_bools)] // One flag per kind of change tracked
+pub struct MonitorSettings {
+    /// Adapter filter configuration
+    pub filter: FilterChain,
+
+    /// Address classes removed from every adapter snapshot (TOML-only)
+    pub address_classes: AddressClassFilter,
+
+    /// Polling interval
+    pub poll_interval: Duration,
+
+    /// Separate IPv6 polling interval when monitoring both versions (TOML-only).
+    /// If `None`, `poll_interval` applies to both.
+    pub poll_interval_v6: Option<Duration>,
+
+    /// Adaptive polling interval (`monitor.adaptive`, TOML-only); starts at
+    /// `poll_interval`, which is then its minimum.
+    /// If `None`, the polling interval is fixed.
+    pub adaptive: Option<AdaptivePolicy>,
+
+    /// Timeout for a single adapter fetch
+    pub fetch_timeout: Duration,
+
+    /// Window merging rapid changes (`monitor.debounce_ms`, default: 2s).
+    /// If `None`, changes are emitted as soon as they are seen.
+    pub debounce: Option<DebouncePolicy>,
+
+    /// Quiet period after which a burst of API notifications is checked
+    /// once (`monitor.event_quiet_ms`, default: 250ms).
+    /// If `None`, every notification triggers a check.
+    pub event_quiet: Option<Duration>,
+
+    /// Whether to use polling only (no API events)
+    pub poll_only: bool,
+
+    /// Action taken when the monitor loop stalls
+    pub watchdog: WatchdogAction,
+
+    /// Report adapter DNS setting changes (TOML-only)
+    pub track_dns: bool,
+
+    /// Report hostname changes (TOML-only)
+    pub track_hostname: bool,
+
+    /// Report adapters going up or down (TOML-only)
+    pub track_link: bool,
+
+    /// Adapter order for choosing effective addresses (TOML-only)
+    pub adapter_priority: AdapterPriority,
+
+    /// Per-adapter IPv6 address limits whose excess is alerted (TOML-only).
+    /// If `None`, addresses are not counted.
+    pub address_storm: Option<StormPolicy>,
+
+    /// Where monitored addresses come from (TOML-only)
+    pub address_source: AddressSource,
+
+    /// Public address lookup settings (TOML-only).
+    /// `Some` exactly if `address_source` includes public addresses.
+    pub public_address: Option<PublicIpSettings>,
+
+    /// Static addresses whose drift is alerted (TOML-only).
+    /// If empty, addresses are not checked.
+    pub expect: ExpectedAddresses,
+}
+
+impl MonitorSettings {
+    /// Resolves the monitor settings.
+    pub(super) fn resolve(
+        cli: &Cli,
+        toml: Option<&TomlConfig>,
+        ip_version: IpVersion,
+    ) -> Result<Self, ConfigError> {
+        // Merge poll interval (CLI default: 60)
+        let (poll_interval, poll_interval_v6, adaptive) =
+            Self::resolve_poll_intervals(cli, toml, ip_version)?;
+        let shortest_poll = poll_interval_v6.map_or(poll_interval, |v6| v6.min(poll_interval));
+        let (address_source, public_address) = resolve_public_address(toml, ip_version)?;
+
+        Ok(Self {
+            filter: build_filter(cli, toml)?,
+            address_classes: resolve_address_classes(toml)?,
+            poll_interval,
+            poll_interval_v6,
+            adaptive,
+            fetch_timeout: Self::resolve_fetch_timeout(toml)?,
+            debounce: Self::resolve_debounce(toml, shortest_poll)?,
+            event_quiet: Self::resolve_event_quiet(toml, shortest_poll)?,
+            // CLI wins if true
+            poll_only: cli.poll_only || toml.is_some_and(|t| t.monitor.poll_only),
+            watchdog: Self::resolve_watchdog(toml)?,
+            track_dns: toml.is_some_and(|t| t.monitor.track_dns),
+            track_hostname: toml.is_some_and(|t| t.monitor.track_hostname),
+            track_link: toml.is_some_and(|t| t.monitor.track_link),
+            adapter_priority: parse_adapter_priority(
+                toml.map_or(&[], |t| &t.monitor.adapter_priority),
+            )?,
+            address_storm: resolve_address_storm(toml, ip_version)?,
+            address_source,
+            public_address,
+            expect: resolve_expect(toml, ip_version)?,
+        })
+    }
+
+    /// Returns the shortest polling interval, that of IPv6 if it is shorter.
+    #[must_use]
+    pub fn shortest_poll_interval(&self) -> Duration {
+        self.poll_interval_v6
+            .map_or(self.poll_interval, |v6| v6.min(self.poll_interval))
+    }
+
+    /// Resolves the polling interval, the separate IPv6 one and the
+    /// adaptive policy; with an adaptive policy, the polling interval is its
+    /// minimum.
+    fn resolve_poll_intervals(
+        cli: &Cli,
+        toml: Option<&TomlConfig>,
+        ip_version: IpVersion,
+    ) -> Result<(Duration, Option<Duration>, Option<AdaptivePolicy>), ConfigError> {
+        let poll_interval = Self::resolve_poll_interval(cli, toml)?;
+        let (poll_interval, poll_interval_v6) =
+            Self::resolve_poll_interval_v6(cli, toml, ip_version, poll_interval)?;
+        let adaptive = Self::resolve_adaptive(toml, poll_interval)?;
+        let poll_interval = adaptive.map_or(poll_interval, |policy| policy.min());
+        Ok((poll_interval, poll_interval_v6, adaptive))
+    }
+
+    fn resolve_poll_interval(
+        cli: &Cli,
+        toml: Option<&TomlConfig>,
+    ) -> Result<Duration, ConfigError> {
+        // Priority: CLI explicit > TOML > default
+        let seconds = cli
+            .poll_interval
+            .or_else(|| toml.and_then(|t| t.monitor.poll_interval))
+            .unwrap_or(defaults::POLL_INTERVAL_SECS);
+
+        if seconds == 0 {
+            return Err(ConfigError::InvalidDuration {
+                field: "poll_interval",
+                reason: "must be greater than 0".to_string(),
+            });
+        }
+
+        Ok(Duration::from_secs(seconds))
+    }
+
+    /// Applies `monitor.poll_interval_v6` to the polling interval.
+    ///
+    /// IPv6-only monitoring simply polls at that interval (unless
+    /// `--poll-interval` is given); with both versions IPv6 gets its own
+    /// interval, and with IPv4 only it is ignored.
+    fn resolve_poll_interval_v6(
+        cli: &Cli,
+        toml: Option<&TomlConfig>,
+        ip_version: IpVersion,
+        poll_interval: Duration,
+    ) -> Result<(Duration, Option<Duration>), ConfigError> {
+        let Some(seconds) = toml.and_then(|t| t.monitor.poll_interval_v6) else {
+            return Ok((poll_interval, None));
+        };
+        if seconds == 0 {
+            return Err(ConfigError::InvalidDuration {
+                field: "poll_interval_v6",
+                reason: "must be greater than 0".to_string(),
+            });
+        }
+
+        let ipv6 = Duration::from_secs(seconds);
+        Ok(match ip_version {
+            IpVersion::V6 if cli.poll_interval.is_none() => (ipv6, None),
+            IpVersion::Both if ipv6 != poll_interval => (poll_interval, Some(ipv6)),
+            _ => (poll_interval, None),
+        })
+    }
+
+    /// Resolves `monitor.adaptive` with `poll_interval_min` (default: the
+    /// polling interval) and `poll_interval_max` (default: ten times the
+    /// minimum). Without `adaptive = true` both are ignored.
+    fn resolve_adaptive(
+        toml: Option<&TomlConfig>,
+        poll_interval: Duration,
+    ) -> Result<Option<AdaptivePolicy>, ConfigError> {
+        let Some(monitor) = toml.map(|t| &t.monitor).filter(|m| m.adaptive) else {
+            return Ok(None);
+        };
+
+        let min = match monitor.poll_interval_min {
+            Some(0) => {
+                return Err(ConfigError::InvalidDuration {
+                    field: "poll_interval_min",
+                    reason: "must be greater than 0".to_string(),
+                });
+            }
+            Some(seconds) => Duration::from_secs(seconds),
+            None => poll_interval,
+        };
+        let max = monitor.poll_interval_max.map_or_else(
+            || min * defaults::POLL_INTERVAL_MAX_FACTOR,
+            Duration::from_secs,
+        );
+        if max < min {
+            return Err(ConfigError::InvalidDuration {
+                field: "poll_interval_max",
+                reason: format!(
+                    "must not be shorter than poll_interval_min ({}s)",
+                    min.as_secs()
+                ),
+            });
+        }
+        Ok(Some(AdaptivePolicy::new(min, max)))
+    }
+
+    /// Resolves the action on a stalled monitor loop (TOML-only).
+    fn resolve_watchdog(toml: Option<&TomlConfig>) -> Result<WatchdogAction, ConfigError> {
+        toml.and_then(|t| t.monitor.watchdog.as_deref())
+            .map_or(Ok(WatchdogAction::Log), parse_watchdog_action)
+    }
+
+    fn resolve_fetch_timeout(toml: Option<&TomlConfig>) -> Result<Duration, ConfigError> {
+        let seconds = toml
+            .and_then(|t| t.monitor.fetch_timeout)
+            .unwrap_or(defaults::FETCH_TIMEOUT_SECS);
+
+        if seconds == 0 {
+            return Err(ConfigError::InvalidDuration {
+                field: "fetch_timeout",
+                reason: "must be greater than 0".to_string(),
+            });
+        }
+
+        Ok(Duration::from_secs(seconds))
+    }
+
+    /// Resolves `monitor.debounce_ms`, which must be shorter than the
+    /// shortest poll interval; 0 disables debouncing.
+    ///
+    /// Without the setting, the default window applies even with a shorter
+    /// poll interval, as it did before the setting existed.
+    fn resolve_debounce(
+        toml: Option<&TomlConfig>,
+        shortest_poll: Duration,
+    ) -> Result<Option<DebouncePolicy>, ConfigError> {
+        let millis = match toml.and_then(|t| t.monitor.debounce_ms) {
+            None => return Ok(Some(DebouncePolicy::default())),
+            Some(0) => return Ok(None),
+            Some(millis) => millis,
+        };
+
+        let window = Duration::from_millis(millis);
+        if window >= shortest_poll {
+            return Err(ConfigError::InvalidDuration {
+                field: "debounce_ms",
+                reason: format!(
+                    "must be shorter than the poll interval ({}s), or 0 to disable debouncing",
+                    shortest_poll.as_secs()
+                ),
+            });
+        }
+        Ok(Some(DebouncePolicy::new(window)))
+    }
+
+    /// Resolves `monitor.event_quiet_ms`, which must be shorter than the
+    /// shortest poll interval; 0 disables coalescing.
+    fn resolve_event_quiet(
+        toml: Option<&TomlConfig>,
+        shortest_poll: Duration,
+    ) -> Result<Option<Duration>, ConfigError> {
+        let millis = toml
+            .and_then(|t| t.monitor.event_quiet_ms)
+            .unwrap_or(defaults::EVENT_QUIET_MS);
+        if millis == 0 {
+            return Ok(None);
+        }
+
+        let quiet = Duration::from_millis(millis);
+        if quiet >= shortest_poll {
+            return Err(ConfigError::InvalidDuration {
+                field: "event_quiet_ms",
+                reason: format!(
+                    "must be shorter than the poll interval ({}s), or 0 to check on every notification",
+                    shortest_poll.as_secs()
+                ),
+            });
+        }
+        Ok(Some(quiet))
+    }
+}
@@ -0,0 +1,62 @@
+//! Resolution of `monitor.source` and the `[monitor.public]` section.
+
+use std::time::Duration;
+
+use crate::network::IpVersion;
+use crate::network::public::{AddressSource, DEFAULT_TIMEOUT, PublicEndpoint, PublicIpSettings};
+
+use crate::config::error::ConfigError;
+use crate::config::toml::TomlConfig;
+
+/// Resolves where addresses come from, with the lookup settings if public
+/// addresses are included.
+///
+/// `[monitor.public]` without a public source is rejected, since it would
+/// silently have no effect.
+pub(super) fn resolve_public_address(
+    toml: Option<&TomlConfig>,
+    ip_version: IpVersion,
+) -> Result<(AddressSource, Option<PublicIpSettings>), ConfigError> {
+    let source = toml
+        .and_then(|t| t.monitor.source.as_deref())
+        .map_or(Ok(AddressSource::Adapter), parse_address_source)?;
+    let section = toml.and_then(|t| t.monitor.public.as_ref());
+    let invalid = |reason: String| ConfigError::InvalidPublicAddress(reason);
+
+    if !source.includes_public() {
+        return match section {
+            Some(_) => Err(invalid(format!(
+                "public addresses are not monitored (source = {source})"
+            ))),
+            None => Ok((source, None)),
+        };
+    }
+
+    let endpoints = section.map_or(&[][..], |s| &s.endpoints);
+    let endpoints = if endpoints.is_empty() {
+        PublicEndpoint::defaults(ip_version)
+    } else {
+        endpoints
+            .iter()
+            .map(|e| e.parse().map_err(invalid))
+            .collect::<Result<_, _>>()?
+    };
+    let timeout = match section.and_then(|s| s.timeout) {
+        Some(0) => return Err(invalid("timeout must be greater than 0".to_string())),
+        Some(secs) => Duration::from_secs(secs),
+        None => DEFAULT_TIMEOUT,
+    };
+
+    Ok((source, Some(PublicIpSettings { endpoints, timeout })))
+}
+
+fn parse_address_source(s: &str) -> Result<AddressSource, ConfigError> {
+    match s.to_lowercase().as_str() {
+        "adapter" => Ok(AddressSource::Adapter),
+        "public" => Ok(AddressSource::Public),
+        "both" => Ok(AddressSource::Both),
+        _ => Err(ConfigError::InvalidAddressSource {
+            value: s.to_string(),
+        }),
+    }
+}
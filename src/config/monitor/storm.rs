@@ -0,0 +1,44 @@
+//! Resolution of the `[monitor.address_storm]` section.
+
+use crate::monitor::StormPolicy;
+use crate::network::IpVersion;
+
+use crate::config::error::ConfigError;
+use crate::config::toml::TomlConfig;
+
+/// Resolves the IPv6 address limits; `None` if the section is absent.
+///
+/// IPv6 must be monitored, since its addresses could not be counted
+/// otherwise.
+pub(super) fn resolve_address_storm(
+    toml: Option<&TomlConfig>,
+    ip_version: IpVersion,
+) -> Result<Option<StormPolicy>, ConfigError> {
+    let Some(section) = toml.and_then(|t| t.monitor.address_storm.as_ref()) else {
+        return Ok(None);
+    };
+    let invalid = |reason: String| ConfigError::InvalidAddressStorm(reason);
+
+    if !ip_version.includes_v6() {
+        return Err(invalid(format!(
+            "IPv6 is not monitored (ip_version = {ip_version})"
+        )));
+    }
+    let max_addresses = section
+        .max_addresses
+        .ok_or_else(|| invalid("max_addresses is required".to_string()))?;
+    if max_addresses == 0 {
+        return Err(invalid("max_addresses must be greater than 0".to_string()));
+    }
+
+    let mut policy = StormPolicy::new(max_addresses).with_prefix_only(section.prefix_only);
+    for (adapter, &limit) in &section.adapters {
+        if limit == 0 {
+            return Err(invalid(format!(
+                "limit of adapter '{adapter}' must be greater than 0"
+            )));
+        }
+        policy = policy.with_adapter_limit(adapter, limit);
+    }
+    Ok(Some(policy))
+}
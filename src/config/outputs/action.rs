@@ -0,0 +1,48 @@
+//! Resolution of the `[action]` section.
+
+use std::time::Duration;
+
+use crate::webhook::{DEFAULT_EXEC_TIMEOUT, ExecAction, ExecInput};
+
+use crate::config::error::ConfigError;
+use crate::config::toml::TomlConfig;
+
+/// Name of the `[action.exec]` command in logs and reports.
+pub const EXEC_TARGET: &str = "action.exec";
+
+/// Resolves `[action.exec]`; `None` unless the section is present.
+pub(super) fn resolve_exec(toml: Option<&TomlConfig>) -> Result<Option<ExecAction>, ConfigError> {
+    let Some(section) = toml.and_then(|t| t.action.exec.as_ref()) else {
+        return Ok(None);
+    };
+    let invalid = |reason: &str| ConfigError::InvalidExec(reason.to_string());
+
+    let Some((program, args)) = section.command.split_first() else {
+        return Err(invalid("command is required"));
+    };
+    if program.trim().is_empty() {
+        return Err(invalid("command must start with a program"));
+    }
+
+    let timeout = match section.timeout {
+        Some(0) => return Err(invalid("timeout must be at least 1 second")),
+        Some(secs) => Duration::from_secs(secs),
+        None => DEFAULT_EXEC_TIMEOUT,
+    };
+    let input = match section.input.as_deref().map(str::to_lowercase).as_deref() {
+        None | Some("env") => ExecInput::Env,
+        Some("stdin") => ExecInput::Stdin,
+        Some(other) => {
+            return Err(ConfigError::InvalidExec(format!(
+                "input '{other}': expected env or stdin"
+            )));
+        }
+    };
+
+    Ok(Some(
+        ExecAction::new(program)
+            .with_args(args)
+            .with_timeout(timeout)
+            .with_input(input),
+    ))
+}
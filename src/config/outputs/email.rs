@@ -0,0 +1,115 @@
+//! Resolution of the `[email]` section.
+
+use std::time::Duration;
+
+use url::Url;
+
+use crate::webhook::{DEFAULT_EMAIL_TIMEOUT, SmtpMailer};
+
+use crate::config::error::ConfigError;
+use crate::config::template::validate_template;
+use crate::config::tls::resolve_tls;
+use crate::config::toml::TomlConfig;
+
+/// Name of the `[email]` mailer in logs and reports.
+pub const EMAIL_TARGET: &str = "email";
+
+/// Resolves `[email]`; `None` unless the section is present.
+pub(super) fn resolve_email(toml: Option<&TomlConfig>) -> Result<Option<SmtpMailer>, ConfigError> {
+    let Some(section) = toml.and_then(|t| t.email.as_ref()) else {
+        return Ok(None);
+    };
+    let invalid = |reason: String| ConfigError::InvalidEmail(reason);
+
+    let Some(server) = section.smtp_server.as_deref() else {
+        return Err(invalid("smtp_server is required".to_string()));
+    };
+    let server = Url::parse(server).map_err(|e| invalid(format!("smtp_server '{server}': {e}")))?;
+    if !matches!(server.scheme(), "smtp" | "smtps") || server.host_str().is_none() {
+        return Err(invalid(format!(
+            "smtp_server '{server}': expected smtp://host or smtps://host"
+        )));
+    }
+
+    let Some(from) = section.from.as_deref() else {
+        return Err(invalid("from is required".to_string()));
+    };
+    check_address(from).map_err(|reason| invalid(format!("from '{from}': {reason}")))?;
+    if section.to.is_empty() {
+        return Err(invalid("to needs at least one address".to_string()));
+    }
+    for to in &section.to {
+        check_address(to).map_err(|reason| invalid(format!("to '{to}': {reason}")))?;
+    }
+
+    let implicit_tls = server.scheme() == "smtps";
+    let starttls = match (section.starttls, implicit_tls) {
+        (Some(_), true) => {
+            return Err(invalid(
+                "starttls cannot be set for an smtps:// server".to_string(),
+            ));
+        }
+        (starttls, _) => starttls.unwrap_or(true),
+    };
+    let encrypted = implicit_tls || starttls;
+    if section.tls.is_some() && !encrypted {
+        return Err(invalid(
+            "tls requires starttls or an smtps:// server".to_string(),
+        ));
+    }
+    let timeout = match section.timeout {
+        Some(0) => return Err(invalid("timeout must be at least 1 second".to_string())),
+        Some(secs) => Duration::from_secs(secs),
+        None => DEFAULT_EMAIL_TIMEOUT,
+    };
+    let tls = resolve_tls(section.tls.as_ref()).map_err(|e| match e {
+        ConfigError::InvalidTls(reason) => invalid(format!("tls: {reason}")),
+        other => other,
+    })?;
+
+    let mut mailer = SmtpMailer::new(server, from, &section.to)
+        .with_starttls(starttls)
+        .with_tls(tls)
+        .with_timeout(timeout);
+    match (&section.username, &section.password) {
+        // Never send the password in the clear
+        (Some(_), Some(_)) if !encrypted => {
+            return Err(invalid(
+                "username requires starttls or an smtps:// server".to_string(),
+            ));
+        }
+        (Some(username), Some(password)) => {
+            mailer = mailer.with_credentials(username, password);
+        }
+        (None, None) => {}
+        _ => {
+            return Err(invalid(
+                "username and password must be set together".to_string(),
+            ));
+        }
+    }
+    if let Some(template) = &section.subject_template {
+        validate_template(template, "email.subject_template")?;
+        mailer = mailer.with_subject_template(template);
+    }
+    if let Some(template) = &section.body_template {
+        validate_template(template, "email.body_template")?;
+        mailer = mailer.with_body_template(template);
+    }
+    Ok(Some(mailer))
+}
+
+/// Checks that `address` is a bare address the SMTP envelope can carry.
+fn check_address(address: &str) -> Result<(), &'static str> {
+    match address.split_once('@') {
+        Some((local, domain)) if !local.is_empty() && !domain.is_empty() => {}
+        _ => return Err("expected user@domain"),
+    }
+    if address
+        .chars()
+        .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '<' | '>' | ',' | '"'))
+    {
+        return Err("expected a bare address without a name");
+    }
+    Ok(())
+}
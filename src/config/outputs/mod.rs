@@ -0,0 +1,127 @@
+//! Output settings: the sinks beside the webhooks and the local records.
+
+use std::path::{Path, PathBuf};
+
+use crate::config::error::ConfigError;
+use crate::config::parse::{expand_tilde, parse_header_name};
+use crate::config::toml::TomlConfig;
+use crate::network::IpVersion;
+use crate::report::SummaryOutput;
+use crate::webhook::{
+    AdapterRoute, AuditConfig, ChatPreset, ExecAction, MqttPublisher, PayloadFormat, Redaction,
+    SmtpMailer,
+};
+
+mod action;
+mod email;
+mod mqtt;
+mod notify;
+mod route;
+
+pub use action::EXEC_TARGET;
+pub use email::EMAIL_TARGET;
+pub use mqtt::MQTT_TARGET;
+
+use action::resolve_exec;
+use email::resolve_email;
+use mqtt::resolve_mqtt;
+use notify::resolve_chats;
+use route::resolve_sink_routes;
+
+/// Settings of the sinks every change is also delivered to, the audit log,
+/// the run summary and the history file.
+#[derive(Debug)]
+pub struct OutputSettings {
+    /// Local command run on every delivery beside the webhooks, with the
+    /// main retry policy (`[action.exec]`, TOML-only)
+    pub exec: Option<ExecAction>,
+
+    /// MQTT broker every change is also published to, with the main retry
+    /// policy (`[mqtt]`, TOML-only)
+    pub mqtt: Option<MqttPublisher>,
+
+    /// SMTP server every change is also emailed through, with the main
+    /// retry policy (`[email]`, TOML-only)
+    pub email: Option<SmtpMailer>,
+
+    /// Chat services every change is also posted to, with the main retry
+    /// policy (`[notify.telegram]`, `[notify.discord]`, `[notify.slack]`,
+    /// TOML-only)
+    pub chats: Vec<ChatPreset>,
+
+    /// Changes the `[action.exec]`, `[mqtt]`, `[email]` and `[notify.*]`
+    /// sinks receive, by target name (TOML-only)
+    pub sink_routes: Vec<(&'static str, AdapterRoute)>,
+
+    /// Audit log of outbound requests.
+    /// If `None`, auditing is disabled.
+    pub audit: Option<AuditConfig>,
+
+    /// Where the JSON run summary is written on exit.
+    /// If `None`, no summary is written.
+    pub summary: Option<SummaryOutput>,
+
+    /// History file every detected change and delivery result is appended to.
+    /// If `None`, no history is kept.
+    pub history: Option<PathBuf>,
+}
+
+impl OutputSettings {
+    /// Resolves the output settings (TOML-only); MQTT messages carry
+    /// `payload_format` documents.
+    pub(super) fn resolve(
+        toml: Option<&TomlConfig>,
+        ip_version: IpVersion,
+        payload_format: PayloadFormat,
+    ) -> Result<Self, ConfigError> {
+        Ok(Self {
+            exec: resolve_exec(toml)?,
+            mqtt: resolve_mqtt(toml, payload_format)?,
+            email: resolve_email(toml)?,
+            chats: resolve_chats(toml)?,
+            sink_routes: resolve_sink_routes(toml, ip_version)?,
+            audit: Self::build_audit(toml)?,
+            summary: Self::resolve_summary(toml),
+            history: Self::resolve_history(toml),
+        })
+    }
+
+    fn build_audit(toml: Option<&TomlConfig>) -> Result<Option<AuditConfig>, ConfigError> {
+        let Some(section) = toml.map(|t| &t.audit) else {
+            return Ok(None);
+        };
+        let Some(ref file) = section.file else {
+            return Ok(None);
+        };
+
+        let mut redaction = Redaction::new();
+        for name in &section.allow_headers {
+            redaction = redaction.allow_header(parse_header_name(name)?);
+        }
+        for name in &section.allow_query {
+            redaction = redaction.allow_query_param(name);
+        }
+
+        Ok(Some(AuditConfig {
+            path: expand_tilde(Path::new(file)),
+            redaction,
+        }))
+    }
+
+    fn resolve_summary(toml: Option<&TomlConfig>) -> Option<SummaryOutput> {
+        let section = &toml?.output;
+
+        if let Some(ref file) = section.summary_file {
+            return Some(SummaryOutput::File(expand_tilde(Path::new(file))));
+        }
+        section
+            .summary
+            .unwrap_or(false)
+            .then_some(SummaryOutput::Stderr)
+    }
+
+    fn resolve_history(toml: Option<&TomlConfig>) -> Option<PathBuf> {
+        let file = toml?.output.history_file.as_ref()?;
+        Some(expand_tilde(Path::new(file)))
+    }
+}
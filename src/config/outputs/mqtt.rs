@@ -0,0 +1,93 @@
+//! Resolution of the `[mqtt]` section.
+
+use std::time::Duration;
+
+use url::Url;
+
+use crate::webhook::{DEFAULT_MQTT_TIMEOUT, MqttPublisher, MqttQos, PayloadFormat};
+
+use crate::config::error::ConfigError;
+use crate::config::template::validate_template;
+use crate::config::tls::resolve_tls;
+use crate::config::toml::TomlConfig;
+
+/// Name of the `[mqtt]` publisher in logs and reports.
+pub const MQTT_TARGET: &str = "mqtt";
+
+/// Resolves `[mqtt]`; `None` unless the section is present. Messages without
+/// a body template carry the `payload_format` document.
+pub(super) fn resolve_mqtt(
+    toml: Option<&TomlConfig>,
+    payload_format: PayloadFormat,
+) -> Result<Option<MqttPublisher>, ConfigError> {
+    let Some(section) = toml.and_then(|t| t.mqtt.as_ref()) else {
+        return Ok(None);
+    };
+    let invalid = |reason: String| ConfigError::InvalidMqtt(reason);
+
+    let Some(broker) = section.broker.as_deref() else {
+        return Err(invalid("broker is required".to_string()));
+    };
+    let broker = Url::parse(broker).map_err(|e| invalid(format!("broker '{broker}': {e}")))?;
+    if !matches!(broker.scheme(), "mqtt" | "mqtts") || broker.host_str().is_none() {
+        return Err(invalid(format!(
+            "broker '{broker}': expected mqtt://host or mqtts://host"
+        )));
+    }
+
+    let topic = match section.topic.as_deref() {
+        None | Some("") => return Err(invalid("topic is required".to_string())),
+        Some(topic) if topic.contains(['+', '#']) => {
+            return Err(invalid(format!(
+                "topic '{topic}': wildcards cannot be published to"
+            )));
+        }
+        Some(topic) => topic,
+    };
+    let qos = match section.qos {
+        Some(level) => MqttQos::from_level(level)
+            .ok_or_else(|| invalid(format!("qos {level}: expected 0, 1 or 2")))?,
+        None => MqttQos::default(),
+    };
+    let timeout = match section.timeout {
+        Some(0) => return Err(invalid("timeout must be at least 1 second".to_string())),
+        Some(secs) => Duration::from_secs(secs),
+        None => DEFAULT_MQTT_TIMEOUT,
+    };
+    if section.tls.is_some() && broker.scheme() != "mqtts" {
+        return Err(invalid("tls requires an mqtts:// broker".to_string()));
+    }
+    let tls = resolve_tls(section.tls.as_ref()).map_err(|e| match e {
+        ConfigError::InvalidTls(reason) => invalid(format!("tls: {reason}")),
+        other => other,
+    })?;
+
+    let mut publisher = MqttPublisher::new(broker, topic)
+        .with_qos(qos)
+        .with_retain(section.retain)
+        .with_tls(tls)
+        .with_payload_format(payload_format)
+        .with_timeout(timeout);
+    if let Some(client_id) = &section.client_id {
+        if client_id.is_empty() {
+            return Err(invalid("client_id must not be empty".to_string()));
+        }
+        publisher = publisher.with_client_id(client_id);
+    }
+    match (&section.username, &section.password) {
+        (Some(username), Some(password)) => {
+            publisher = publisher.with_credentials(username, password);
+        }
+        (None, None) => {}
+        _ => {
+            return Err(invalid(
+                "username and password must be set together".to_string(),
+            ));
+        }
+    }
+    if let Some(template) = &section.body_template {
+        validate_template(template, "mqtt.body_template")?;
+        publisher = publisher.with_body_template(template);
+    }
+    Ok(Some(publisher))
+}
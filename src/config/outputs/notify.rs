@@ -0,0 +1,69 @@
+//! Resolution of the `[notify]` section.
+
+use url::Url;
+
+use crate::webhook::{ChatPreset, ChatService};
+
+use crate::config::error::ConfigError;
+use crate::config::toml::{ChatWebhookSection, TomlConfig};
+
+/// Resolves the `[notify.*]` chat services, in [`ChatService::ALL`] order.
+pub(super) fn resolve_chats(toml: Option<&TomlConfig>) -> Result<Vec<ChatPreset>, ConfigError> {
+    let Some(section) = toml.map(|t| &t.notify) else {
+        return Ok(Vec::new());
+    };
+    let invalid = |service: ChatService, reason: &str| {
+        ConfigError::InvalidNotify(format!("{service}: {reason}"))
+    };
+
+    let mut chats = Vec::new();
+    if let Some(telegram) = &section.telegram {
+        let service = ChatService::Telegram;
+        let Some(token) = telegram.bot_token.as_deref() else {
+            return Err(invalid(service, "bot_token is required"));
+        };
+        if !is_bot_token(token) {
+            return Err(invalid(service, "bot_token: expected <id>:<secret>"));
+        }
+        let chat_id = match telegram.chat_id.as_deref().map(str::trim) {
+            None | Some("") => return Err(invalid(service, "chat_id is required")),
+            Some(chat_id) => chat_id,
+        };
+        chats.push(ChatPreset::telegram(token, chat_id));
+    }
+    if let Some(discord) = &section.discord {
+        let url = webhook_url(discord).map_err(|reason| invalid(ChatService::Discord, &reason))?;
+        chats.push(ChatPreset::discord(url));
+    }
+    if let Some(slack) = &section.slack {
+        let url = webhook_url(slack).map_err(|reason| invalid(ChatService::Slack, &reason))?;
+        chats.push(ChatPreset::slack(url));
+    }
+    Ok(chats)
+}
+
+/// Checks the shape of a Telegram bot token, `<numeric id>:<secret>`.
+fn is_bot_token(token: &str) -> bool {
+    token.split_once(':').is_some_and(|(id, secret)| {
+        !id.is_empty()
+            && id.chars().all(|c| c.is_ascii_digit())
+            && !secret.is_empty()
+            && secret
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+    })
+}
+
+/// Parses the `webhook_url` of a Discord or Slack section.
+fn webhook_url(section: &ChatWebhookSection) -> Result<Url, String> {
+    // The URL is a credential, so it is not echoed in errors
+    let url = section
+        .webhook_url
+        .as_deref()
+        .ok_or("webhook_url is required")?;
+    let url = Url::parse(url).map_err(|e| format!("webhook_url: {e}"))?;
+    if !matches!(url.scheme(), "https" | "http") {
+        return Err("webhook_url: expected an http(s) URL".to_string());
+    }
+    Ok(url)
+}
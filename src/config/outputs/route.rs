@@ -0,0 +1,73 @@
+//! Resolution of the sink routes: which changes `[action.exec]`, `[mqtt]`,
+//! `[email]` and `[notify.*]` receive.
+
+use crate::network::IpVersion;
+use crate::webhook::{AdapterRoute, ChatService};
+
+use super::{EMAIL_TARGET, EXEC_TARGET, MQTT_TARGET};
+use crate::config::error::ConfigError;
+use crate::config::parse::parse_route;
+use crate::config::toml::TomlConfig;
+
+/// Resolves the routes of the `[action.exec]`, `[mqtt]`, `[email]` and
+/// `[notify.*]` sinks that are configured, by target name, for the
+/// monitored `ip_version`.
+pub(super) fn resolve_sink_routes(
+    toml: Option<&TomlConfig>,
+    ip_version: IpVersion,
+) -> Result<Vec<(&'static str, AdapterRoute)>, ConfigError> {
+    let Some(toml) = toml else {
+        return Ok(Vec::new());
+    };
+    let route = |adapters: &[String], version: Option<&String>, added_only: bool| {
+        parse_route(
+            adapters,
+            version.map(String::as_str),
+            added_only,
+            ip_version,
+        )
+    };
+
+    let mut routes = Vec::new();
+    if let Some(s) = &toml.action.exec {
+        let route = route(&s.adapters, s.ip_version.as_ref(), s.added_only);
+        routes.push((EXEC_TARGET, route.map_err(ConfigError::InvalidExec)?));
+    }
+    if let Some(s) = &toml.mqtt {
+        let route = route(&s.adapters, s.ip_version.as_ref(), s.added_only);
+        routes.push((MQTT_TARGET, route.map_err(ConfigError::InvalidMqtt)?));
+    }
+    if let Some(s) = &toml.email {
+        let route = route(&s.adapters, s.ip_version.as_ref(), s.added_only);
+        routes.push((EMAIL_TARGET, route.map_err(ConfigError::InvalidEmail)?));
+    }
+    let notify = &toml.notify;
+    let chats = [
+        notify.telegram.as_ref().map(|s| {
+            (
+                ChatService::Telegram,
+                &s.adapters,
+                &s.ip_version,
+                s.added_only,
+            )
+        }),
+        notify.discord.as_ref().map(|s| {
+            (
+                ChatService::Discord,
+                &s.adapters,
+                &s.ip_version,
+                s.added_only,
+            )
+        }),
+        notify
+            .slack
+            .as_ref()
+            .map(|s| (ChatService::Slack, &s.adapters, &s.ip_version, s.added_only)),
+    ];
+    for (service, adapters, version, added_only) in chats.into_iter().flatten() {
+        let route = route(adapters, version.as_ref(), added_only)
+            .map_err(|reason| ConfigError::InvalidNotify(format!("{service}: {reason}")))?;
+        routes.push((service.target_name(), route));
+    }
+    Ok(routes)
+}
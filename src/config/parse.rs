@@ -0,0 +1,339 @@
+//! Parsing of individual configuration values.
+//!
+//! Shared by the resolvers of the settings groups; each function maps a raw
+//! CLI/TOML string to its typed form or a [`ConfigError`].
+
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+
+use http::header::{HeaderName, HeaderValue};
+use regex::Regex;
+
+use crate::dns::DNS_PORT;
+use crate::monitor::{NotifyOn, WatchdogAction};
+use crate::network::priority::AdapterPriority;
+use crate::network::{AdapterKind, IpVersion};
+use crate::webhook::{
+    AdapterRoute, ConnectivityCheck, DeliveryMode, EchoCheck, JitterMode, OverlapCheck,
+    PayloadEncoding, PayloadFormat,
+};
+
+use super::error::ConfigError;
+
+pub(super) fn parse_ip_version(s: &str) -> Result<IpVersion, ConfigError> {
+    match s.to_lowercase().as_str() {
+        "ipv4" | "v4" | "4" => Ok(IpVersion::V4),
+        "ipv6" | "v6" | "6" => Ok(IpVersion::V6),
+        "both" | "all" | "dual" => Ok(IpVersion::Both),
+        _ => Err(ConfigError::InvalidIpVersion {
+            value: s.to_string(),
+        }),
+    }
+}
+
+/// Parses `notify_on`: a list of "added" and "removed"; empty is rejected.
+pub(super) fn parse_notify_on(values: &[String]) -> Result<NotifyOn, ConfigError> {
+    if values.is_empty() {
+        return Err(ConfigError::InvalidNotifyOn {
+            value: "[]".to_string(),
+        });
+    }
+    let (mut added, mut removed) = (false, false);
+    for value in values {
+        match value.to_lowercase().as_str() {
+            "added" => added = true,
+            "removed" => removed = true,
+            _ => {
+                return Err(ConfigError::InvalidNotifyOn {
+                    value: value.clone(),
+                });
+            }
+        }
+    }
+    Ok(match (added, removed) {
+        (true, false) => NotifyOn::Added,
+        (false, true) => NotifyOn::Removed,
+        _ => NotifyOn::Both,
+    })
+}
+
+pub(super) fn parse_adapter_kind(s: &str) -> Result<AdapterKind, ConfigError> {
+    match s.to_lowercase().as_str() {
+        "ethernet" => Ok(AdapterKind::Ethernet),
+        "wireless" => Ok(AdapterKind::Wireless),
+        "virtual" => Ok(AdapterKind::Virtual),
+        "loopback" => Ok(AdapterKind::Loopback),
+        _ => Err(ConfigError::InvalidAdapterKind {
+            value: s.to_string(),
+        }),
+    }
+}
+
+pub(super) fn parse_connectivity_check(s: &str) -> Result<ConnectivityCheck, ConfigError> {
+    match s.to_lowercase().as_str() {
+        "none" | "off" => Ok(ConnectivityCheck::Disabled),
+        "family" => Ok(ConnectivityCheck::AddressFamily),
+        _ => Err(ConfigError::InvalidConnectivityCheck {
+            value: s.to_string(),
+        }),
+    }
+}
+
+pub(super) fn parse_watchdog_action(s: &str) -> Result<WatchdogAction, ConfigError> {
+    match s.to_lowercase().as_str() {
+        "off" | "none" => Ok(WatchdogAction::Disabled),
+        "log" => Ok(WatchdogAction::Log),
+        "exit" => Ok(WatchdogAction::Exit),
+        _ => Err(ConfigError::InvalidWatchdog {
+            value: s.to_string(),
+        }),
+    }
+}
+
+pub(super) fn parse_overlap_check(s: &str) -> Result<OverlapCheck, ConfigError> {
+    match s.to_lowercase().as_str() {
+        "off" | "none" => Ok(OverlapCheck::Off),
+        "warn" => Ok(OverlapCheck::Warn),
+        "error" => Ok(OverlapCheck::Error),
+        _ => Err(ConfigError::InvalidOverlapCheck {
+            value: s.to_string(),
+        }),
+    }
+}
+
+pub(super) fn parse_jitter(s: &str) -> Result<JitterMode, ConfigError> {
+    match s.to_lowercase().as_str() {
+        "none" | "off" => Ok(JitterMode::None),
+        "full" => Ok(JitterMode::Full),
+        "equal" => Ok(JitterMode::Equal),
+        _ => Err(ConfigError::InvalidJitter {
+            value: s.to_string(),
+        }),
+    }
+}
+
+pub(super) fn parse_payload_encoding(s: &str) -> Result<PayloadEncoding, ConfigError> {
+    match s.to_lowercase().as_str() {
+        "json" => Ok(PayloadEncoding::Json),
+        "yaml" | "yml" => Ok(PayloadEncoding::Yaml),
+        "cbor" => Ok(PayloadEncoding::Cbor),
+        _ => Err(ConfigError::InvalidPayloadEncoding {
+            value: s.to_string(),
+        }),
+    }
+}
+
+pub(super) fn parse_payload_format(s: &str) -> Result<PayloadFormat, ConfigError> {
+    match s.to_lowercase().as_str() {
+        "json-v1" => Ok(PayloadFormat::JsonV1),
+        "none" => Ok(PayloadFormat::None),
+        _ => Err(ConfigError::InvalidPayloadFormat {
+            value: s.to_string(),
+        }),
+    }
+}
+
+pub(super) fn parse_echo_check(s: &str) -> Result<EchoCheck, ConfigError> {
+    match s.to_lowercase().as_str() {
+        "off" | "none" => Ok(EchoCheck::Disabled),
+        "header" => Ok(EchoCheck::Header),
+        "body" => Ok(EchoCheck::Body),
+        _ => Err(ConfigError::InvalidEchoCheck {
+            value: s.to_string(),
+        }),
+    }
+}
+
+pub(super) fn parse_delivery_mode(s: &str) -> Result<DeliveryMode, ConfigError> {
+    match s.to_lowercase().replace('-', "_").as_str() {
+        "batched" => Ok(DeliveryMode::Batched),
+        "per_change" => Ok(DeliveryMode::PerChange),
+        _ => Err(ConfigError::InvalidDeliveryMode {
+            value: s.to_string(),
+        }),
+    }
+}
+
+pub(super) fn parse_adapter_priority(names: &[String]) -> Result<AdapterPriority, ConfigError> {
+    for (i, name) in names.iter().enumerate() {
+        if name.trim().is_empty() {
+            return Err(ConfigError::InvalidAdapterPriority(
+                "adapter names must not be empty".to_string(),
+            ));
+        }
+        if names[..i].contains(name) {
+            return Err(ConfigError::InvalidAdapterPriority(format!(
+                "'{name}' is listed more than once"
+            )));
+        }
+    }
+    Ok(AdapterPriority::new(names.iter().cloned()))
+}
+
+pub(super) fn parse_header_string(s: &str) -> Result<(String, String), ConfigError> {
+    // Try "Key=Value" format first
+    if let Some((name, value)) = s.split_once('=') {
+        return Ok((name.trim().to_string(), value.trim().to_string()));
+    }
+
+    // Try "Key: Value" format
+    if let Some((name, value)) = s.split_once(':') {
+        return Ok((name.trim().to_string(), value.trim().to_string()));
+    }
+
+    Err(ConfigError::InvalidHeader {
+        value: s.to_string(),
+    })
+}
+
+pub(super) fn parse_header_name(name: &str) -> Result<HeaderName, ConfigError> {
+    name.parse::<HeaderName>()
+        .map_err(|e| ConfigError::InvalidHeaderName {
+            name: name.to_string(),
+            reason: e.to_string(),
+        })
+}
+
+pub(super) fn parse_header_value(name: &str, value: &str) -> Result<HeaderValue, ConfigError> {
+    HeaderValue::from_str(value).map_err(|e| ConfigError::InvalidHeaderValue {
+        name: name.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Expands tilde (`~`) at the start of a path to the user's home directory.
+///
+/// - `~/foo` → `<home>/foo`
+/// - `~` → `<home>`
+/// - Paths not starting with `~` are returned unchanged.
+pub(super) fn expand_tilde(path: &Path) -> PathBuf {
+    let path_str = path.to_string_lossy();
+
+    // Check if path starts with ~ (tilde)
+    if !path_str.starts_with('~') {
+        return path.to_path_buf();
+    }
+
+    // Get home directory
+    let Some(home) = dirs::home_dir() else {
+        // Cannot determine home directory - return path unchanged
+        tracing::warn!("Cannot expand ~: home directory not found");
+        return path.to_path_buf();
+    };
+
+    // Handle bare ~ or ~/...
+    if path_str == "~" {
+        return home;
+    }
+
+    // Handle ~/path or ~\path (Windows)
+    if path_str.starts_with("~/") || path_str.starts_with("~\\") {
+        return home.join(&path_str[2..]);
+    }
+
+    // ~username style is not supported - return unchanged
+    path.to_path_buf()
+}
+
+/// Parses `192.0.2.53`, `192.0.2.53:5353`, `2001:db8::53` or `[2001:db8::53]:5353`.
+pub(super) fn parse_nameserver(s: &str) -> Option<SocketAddr> {
+    let s = s.trim();
+    s.parse::<SocketAddr>()
+        .ok()
+        .or_else(|| Some(SocketAddr::new(s.parse::<IpAddr>().ok()?, DNS_PORT)))
+}
+
+/// Parses the route of a target or sink receiving the changes of the `adapters` patterns,
+/// the addresses of `version` (all if unset) and, if `added_only`, no
+/// removed addresses. Only a `monitored` version can be asked for.
+pub(super) fn parse_route(
+    adapters: &[String],
+    version: Option<&str>,
+    added_only: bool,
+    monitored: IpVersion,
+) -> Result<AdapterRoute, String> {
+    let patterns = adapters
+        .iter()
+        .map(|p| Regex::new(p).map_err(|e| format!("adapters: invalid pattern '{p}': {e}")))
+        .collect::<Result<_, _>>()?;
+    let route = AdapterRoute::new(patterns).with_added_only(added_only);
+    let Some(version) = version else {
+        return Ok(route);
+    };
+    let version = parse_ip_version(version).map_err(|e| format!("ip_version: {e}"))?;
+    let (monitored_too, other) = match version {
+        IpVersion::V4 => (monitored.includes_v4(), IpVersion::V6),
+        IpVersion::V6 => (monitored.includes_v6(), IpVersion::V4),
+        IpVersion::Both => return Ok(route),
+    };
+    if !monitored_too {
+        return Err(format!(
+            "ip_version: {version} is not monitored (ip_version is {monitored})"
+        ));
+    }
+    // Changes without an address are still routed
+    Ok(route.excluding(other))
+}
+
+#[cfg(test)]
+mod tilde_tests {
+    use std::path::Path;
+
+    use super::expand_tilde;
+
+    #[test]
+    fn tilde_alone_expands_to_home() {
+        let result = expand_tilde(Path::new("~"));
+        let expected = dirs::home_dir().expect("home dir should exist");
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn tilde_slash_prefix_expands() {
+        let result = expand_tilde(Path::new("~/.ddns-a/state.json"));
+        let home = dirs::home_dir().expect("home dir should exist");
+        assert_eq!(result, home.join(".ddns-a/state.json"));
+    }
+
+    #[test]
+    fn tilde_backslash_prefix_expands() {
+        // Windows-style path separator
+        let result = expand_tilde(Path::new("~\\.ddns-a\\state.json"));
+        let home = dirs::home_dir().expect("home dir should exist");
+        assert_eq!(result, home.join(".ddns-a\\state.json"));
+    }
+
+    #[test]
+    fn absolute_path_unchanged() {
+        #[cfg(windows)]
+        let path = Path::new("C:\\Users\\test\\state.json");
+        #[cfg(not(windows))]
+        let path = Path::new("/home/test/state.json");
+
+        let result = expand_tilde(path);
+        assert_eq!(result, path);
+    }
+
+    #[test]
+    fn relative_path_unchanged() {
+        let path = Path::new("./state.json");
+        let result = expand_tilde(path);
+        assert_eq!(result, path);
+    }
+
+    #[test]
+    fn tilde_in_middle_unchanged() {
+        // Tilde not at start should not expand
+        let path = Path::new("foo/~/bar");
+        let result = expand_tilde(path);
+        assert_eq!(result, path);
+    }
+
+    #[test]
+    fn tilde_username_style_unchanged() {
+        // ~username style is not supported
+        let path = Path::new("~otheruser/file");
+        let result = expand_tilde(path);
+        assert_eq!(result, path);
+    }
+}
@@ -0,0 +1,83 @@
+//! Filter generation from a captured adapter listing (`init --from-snapshot`).
+//!
+//! Configuring a headless machine is easier from a listing captured on it
+//! than by typing adapter names by hand. The listing is JSON in one of two
+//! shapes, both using the serialized [`AdapterSnapshot`] form:
+//!
+//! - an array of adapters: `[{"name": "Ethernet", "kind": "Ethernet", ...}]`
+//! - a state file (`monitor.state_file`): `{"version": 1, "snapshots": [...]}`
+
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::network::AdapterSnapshot;
+
+use super::ConfigError;
+
+/// Accepted listing shapes.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Listing {
+    Adapters(Vec<AdapterSnapshot>),
+    StateFile { snapshots: Vec<AdapterSnapshot> },
+}
+
+/// Reads the adapter listing at `path`.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or is not a listing.
+pub(super) fn load_listing(path: &Path) -> Result<Vec<AdapterSnapshot>, ConfigError> {
+    let content = std::fs::read_to_string(path).map_err(|e| ConfigError::FileRead {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+
+    parse_listing(&content).map_err(|reason| ConfigError::InvalidSnapshot {
+        path: path.to_path_buf(),
+        reason,
+    })
+}
+
+/// Parses an adapter listing in either accepted shape.
+pub(super) fn parse_listing(content: &str) -> Result<Vec<AdapterSnapshot>, String> {
+    match serde_json::from_str(content) {
+        Ok(
+            Listing::Adapters(adapters)
+            | Listing::StateFile {
+                snapshots: adapters,
+            },
+        ) => Ok(adapters),
+        Err(_) => Err(
+            "expected a JSON array of adapters or a state file with a 'snapshots' array"
+                .to_string(),
+        ),
+    }
+}
+
+/// Returns exact-name include patterns for the listed adapters.
+///
+/// Loopback adapters are skipped since they are excluded by default, and
+/// duplicate names are listed once.
+pub(super) fn include_patterns(adapters: &[AdapterSnapshot]) -> Vec<String> {
+    let mut patterns: Vec<String> = Vec::new();
+    for adapter in adapters.iter().filter(|a| !a.kind.is_loopback()) {
+        let pattern = format!("^{}$", regex::escape(&adapter.name));
+        if !patterns.contains(&pattern) {
+            patterns.push(pattern);
+        }
+    }
+    patterns
+}
+
+/// Inserts an `include` list for `patterns` at the top of the template's
+/// `[filter]` section.
+pub(super) fn with_include_filter(template: &str, patterns: &[String], source: &str) -> String {
+    let list = toml::Value::Array(patterns.iter().cloned().map(toml::Value::String).collect());
+    let generated = format!(
+        "[filter]\n# Generated from {source}: monitor exactly these adapters\ninclude = {list}\n"
+    );
+
+    template.replacen("[filter]\n", &generated, 1)
+}
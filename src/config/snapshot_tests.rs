@@ -0,0 +1,80 @@
+//! Tests for adapter listing parsing and filter generation.
+
+use super::snapshot::{include_patterns, parse_listing, with_include_filter};
+use super::toml::{TomlConfig, default_config_template};
+use crate::network::{AdapterKind, AdapterSnapshot};
+
+const ADAPTERS: &str = r#"[
+    {"name": "Ethernet", "kind": "Ethernet", "ipv4_addresses": ["192.0.2.10"], "ipv6_addresses": []},
+    {"name": "Wi-Fi (5G)", "kind": "Wireless", "ipv4_addresses": [], "ipv6_addresses": []},
+    {"name": "Loopback Pseudo-Interface 1", "kind": "Loopback", "ipv4_addresses": ["127.0.0.1"], "ipv6_addresses": []}
+]"#;
+
+fn names(adapters: &[AdapterSnapshot]) -> Vec<&str> {
+    adapters.iter().map(|a| a.name.as_str()).collect()
+}
+
+mod listing {
+    use super::*;
+
+    #[test]
+    fn parses_adapter_array() {
+        let adapters = parse_listing(ADAPTERS).unwrap();
+
+        assert_eq!(
+            names(&adapters),
+            ["Ethernet", "Wi-Fi (5G)", "Loopback Pseudo-Interface 1"]
+        );
+        assert_eq!(adapters[1].kind, AdapterKind::Wireless);
+    }
+
+    #[test]
+    fn parses_state_file() {
+        let content = r#"{"version": 1, "saved_at": "1760400000", "snapshots": [
+            {"name": "eth0", "kind": "Ethernet", "ipv4_addresses": [], "ipv6_addresses": []}
+        ]}"#;
+
+        assert_eq!(names(&parse_listing(content).unwrap()), ["eth0"]);
+    }
+
+    #[test]
+    fn rejects_other_json() {
+        let reason = parse_listing(r#"{"adapters": 3}"#).unwrap_err();
+
+        assert!(reason.contains("snapshots"));
+    }
+
+    #[test]
+    fn rejects_non_json() {
+        assert!(parse_listing("Ethernet\nWi-Fi\n").is_err());
+    }
+}
+
+mod patterns {
+    use super::*;
+
+    #[test]
+    fn exact_escaped_names_without_loopback() {
+        let patterns = include_patterns(&parse_listing(ADAPTERS).unwrap());
+
+        assert_eq!(patterns, [r"^Ethernet$", r"^Wi\-Fi \(5G\)$"]);
+    }
+
+    #[test]
+    fn duplicates_listed_once() {
+        let eth = AdapterSnapshot::new("Ethernet", AdapterKind::Ethernet, vec![], vec![]);
+
+        assert_eq!(include_patterns(&[eth.clone(), eth]), ["^Ethernet$"]);
+    }
+
+    #[test]
+    fn generated_template_is_valid_and_filters_exactly() {
+        let patterns = include_patterns(&parse_listing(ADAPTERS).unwrap());
+
+        let content = with_include_filter(&default_config_template(), &patterns, "adapters.json");
+
+        assert!(content.contains("# Generated from adapters.json"));
+        let config = TomlConfig::parse(&content).unwrap();
+        assert_eq!(config.filter.include, patterns);
+    }
+}
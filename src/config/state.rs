@@ -0,0 +1,111 @@
+//! Resolution of `--state-file`, `monitor.state_file` and `monitor.state`.
+//!
+//! Without a state file, state persistence is disabled. `state = "auto"`
+//! picks a platform default instead:
+//!
+//! - Windows: `%ProgramData%\ddns-a\state.json`
+//! - Unix, as root: `/var/lib/ddns-a/state.json`
+//! - Unix, otherwise: `$XDG_STATE_HOME/ddns-a/state.json` (falling back to
+//!   `~/.local/state/ddns-a/state.json`)
+//!
+//! The directory is created on the first save, readable only by its owner
+//! on Unix (see [`FileStateStore`](crate::state::FileStateStore)).
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use super::Cli;
+use super::error::ConfigError;
+use super::parse::expand_tilde;
+use super::toml::TomlConfig;
+
+/// Application directory inside a state directory.
+const APP_DIR: &str = "ddns-a";
+
+/// File name of the default state file.
+const STATE_FILE_NAME: &str = "state.json";
+
+/// Resolves the state file; `None` disables state persistence.
+///
+/// `--state-file` takes precedence over `monitor.state_file`, which takes
+/// precedence over `monitor.state = "auto"`.
+pub(super) fn resolve_state_file(
+    cli: &Cli,
+    toml: Option<&TomlConfig>,
+) -> Result<Option<PathBuf>, ConfigError> {
+    if let Some(ref path) = cli.state_file {
+        return Ok(Some(expand_tilde(path)));
+    }
+    let Some(monitor) = toml.map(|t| &t.monitor) else {
+        return Ok(None);
+    };
+    if let Some(ref path) = monitor.state_file {
+        return Ok(Some(expand_tilde(Path::new(path))));
+    }
+
+    let Some(ref mode) = monitor.state else {
+        return Ok(None);
+    };
+    match mode.to_lowercase().as_str() {
+        "file" => Ok(None),
+        "auto" => default_state_file().map(Some).ok_or_else(|| {
+            ConfigError::InvalidState(
+                "auto found no home or state directory; set state_file".to_string(),
+            )
+        }),
+        _ => Err(ConfigError::InvalidState(format!(
+            "'{mode}' must be auto or file"
+        ))),
+    }
+}
+
+/// Returns the platform default state file, if a directory for it is known.
+fn default_state_file() -> Option<PathBuf> {
+    default_state_dir().map(|dir| dir.join(STATE_FILE_NAME))
+}
+
+#[cfg(windows)]
+fn default_state_dir() -> Option<PathBuf> {
+    Some(windows_state_dir(std::env::var_os("ProgramData")))
+}
+
+#[cfg(unix)]
+fn default_state_dir() -> Option<PathBuf> {
+    // SAFETY: geteuid has no preconditions and cannot fail.
+    let root = unsafe { libc::geteuid() } == 0;
+    unix_state_dir(root, std::env::var_os("XDG_STATE_HOME"), dirs::home_dir())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn default_state_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join(APP_DIR))
+}
+
+/// Returns the state directory under `program_data`, or under the usual
+/// `C:\ProgramData` if it is unset.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub(super) fn windows_state_dir(program_data: Option<OsString>) -> PathBuf {
+    program_data
+        .filter(|dir| !dir.is_empty())
+        .map_or_else(|| PathBuf::from(r"C:\ProgramData"), PathBuf::from)
+        .join(APP_DIR)
+}
+
+/// Returns the system state directory for `root`, else the user's: under
+/// `xdg_state_home` if it is an absolute path (as the XDG base directory
+/// specification requires), or else under `home`.
+#[cfg_attr(not(unix), allow(dead_code))]
+pub(super) fn unix_state_dir(
+    root: bool,
+    xdg_state_home: Option<OsString>,
+    home: Option<PathBuf>,
+) -> Option<PathBuf> {
+    if root {
+        return Some(Path::new("/var/lib").join(APP_DIR));
+    }
+    xdg_state_home
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| home.map(|home| home.join(".local").join("state")))
+        .map(|dir| dir.join(APP_DIR))
+}
@@ -0,0 +1,96 @@
+//! Body template validation.
+//!
+//! Templates are only compiled in with the `templates` feature; without it,
+//! configuring one is rejected up front instead of failing at send time.
+
+use http::HeaderMap;
+use url::Url;
+
+use crate::webhook::placeholders;
+
+use super::error::ConfigError;
+use super::toml::TomlConfig;
+
+/// Resolves and validates `webhook.dns_body_template` (TOML-only).
+pub(super) fn resolve_dns_body_template(
+    toml: Option<&TomlConfig>,
+) -> Result<Option<String>, ConfigError> {
+    let template = toml.and_then(|t| t.webhook.dns_body_template.clone());
+    if let Some(ref tmpl) = template {
+        validate_template(tmpl, "webhook.dns_body_template")?;
+    }
+    Ok(template)
+}
+
+/// Validates the header values holding Handlebars expressions, set via
+/// `option`; values with only `{{pre.<name>}}` placeholders need no
+/// templating.
+pub(super) fn validate_header_templates(
+    headers: &HeaderMap,
+    option: &'static str,
+) -> Result<(), ConfigError> {
+    for value in headers.values() {
+        let Ok(text) = value.to_str() else {
+            continue;
+        };
+        let unfilled = placeholders(text)
+            .into_iter()
+            .fold(text.to_string(), |text, name| {
+                text.replace(&format!("{{{{pre.{name}}}}}"), "")
+            });
+        if unfilled.contains("{{") {
+            validate_template(text, option)?;
+        }
+    }
+    Ok(())
+}
+
+/// Validates the Handlebars syntax and helper usage of a template set via
+/// `option`.
+#[cfg(feature = "templates")]
+pub(super) fn validate_template(template: &str, _option: &'static str) -> Result<(), ConfigError> {
+    let hbs = crate::webhook::template_registry();
+    // Render with empty context: catches syntax errors, unknown helpers and
+    // helpers given the wrong number or kind of parameters
+    hbs.render_template(template, &serde_json::json!({}))
+        .map_err(|e| ConfigError::InvalidTemplate {
+            reason: e.to_string(),
+        })?;
+    Ok(())
+}
+
+/// Validates a templated `webhook.url`, returning the URL it renders to
+/// without change data.
+#[cfg(feature = "templates")]
+pub(super) fn validate_url_template(template: &str) -> Result<Url, ConfigError> {
+    let rendered = crate::webhook::url_template_registry()
+        .render_template(template, &serde_json::json!({}))
+        .map_err(|e| ConfigError::InvalidTemplate {
+            reason: e.to_string(),
+        })?;
+    Url::parse(&rendered).map_err(|e| ConfigError::InvalidUrl {
+        url: template.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Rejects a templated `webhook.url`: templating is not compiled in.
+#[cfg(not(feature = "templates"))]
+pub(super) const fn validate_url_template(_template: &str) -> Result<Url, ConfigError> {
+    Err(ConfigError::FeatureDisabled {
+        feature: "templates",
+        option: "--url / webhook.url",
+    })
+}
+
+/// Rejects a template set via `option`: templating is not compiled in.
+#[cfg(not(feature = "templates"))]
+pub(super) const fn validate_template(
+    _template: &str,
+    option: &'static str,
+) -> Result<(), ConfigError> {
+    Err(ConfigError::FeatureDisabled {
+        feature: "templates",
+        option,
+    })
+}
@@ -0,0 +1,41 @@
+//! Resolution of the `[http.tls]` section.
+
+use std::path::Path;
+
+use crate::webhook::{ClientIdentity, TlsSettings};
+
+use super::error::ConfigError;
+use super::parse::expand_tilde;
+use super::toml::TlsSection;
+
+/// Resolves the TLS settings, reading the files they name so unusable ones
+/// fail the configuration; the default if the section is absent.
+pub(super) fn resolve_tls(section: Option<&TlsSection>) -> Result<TlsSettings, ConfigError> {
+    let Some(section) = section else {
+        return Ok(TlsSettings::default());
+    };
+    let invalid = |reason: String| ConfigError::InvalidTls(reason);
+
+    let client_identity = match (&section.client_cert, &section.client_key) {
+        (Some(cert), Some(key)) => Some(ClientIdentity {
+            cert_file: expand_tilde(Path::new(cert)),
+            key_file: expand_tilde(Path::new(key)),
+        }),
+        (None, None) => None,
+        _ => {
+            return Err(invalid(
+                "client_cert and client_key must be set together".to_string(),
+            ));
+        }
+    };
+    let settings = TlsSettings {
+        ca_file: section
+            .ca_file
+            .as_deref()
+            .map(|f| expand_tilde(Path::new(f))),
+        client_identity,
+        accept_invalid_certs: section.danger_accept_invalid_certs,
+    };
+    settings.check().map_err(invalid)?;
+    Ok(settings)
+}
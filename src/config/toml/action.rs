@@ -0,0 +1,37 @@
+//! The `[action]` section, with `[action.exec]`.
+
+use serde::Deserialize;
+
+/// Actions run beside the webhook.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ActionSection {
+    /// Local command run on every delivery
+    pub exec: Option<ExecSection>,
+}
+
+/// Local command configuration section (`[action.exec]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExecSection {
+    /// Program and arguments, run without a shell
+    #[serde(default)]
+    pub command: Vec<String>,
+
+    /// Seconds a run may take before the command is killed (default: 30)
+    pub timeout: Option<u64>,
+
+    /// How the changes are passed: "env" or "stdin" (default: env)
+    pub input: Option<String>,
+
+    /// Regex patterns of the adapters whose changes the command receives (default: all)
+    #[serde(default)]
+    pub adapters: Vec<String>,
+
+    /// IP version whose address changes the command receives (default: both)
+    pub ip_version: Option<String>,
+
+    /// Leave out removed addresses (default: false)
+    #[serde(default)]
+    pub added_only: bool,
+}
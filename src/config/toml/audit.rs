@@ -0,0 +1,19 @@
+//! The `[audit]` section.
+
+use serde::Deserialize;
+
+/// Outbound request audit log configuration section.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuditSection {
+    /// Path to the JSON-lines audit file (auditing is disabled if unset)
+    pub file: Option<String>,
+
+    /// Header names whose values are recorded unredacted
+    #[serde(default)]
+    pub allow_headers: Vec<String>,
+
+    /// Query parameter names whose values are recorded unredacted
+    #[serde(default)]
+    pub allow_query: Vec<String>,
+}
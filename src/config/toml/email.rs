@@ -0,0 +1,52 @@
+//! The `[email]` section.
+
+use serde::Deserialize;
+
+use super::http::TlsSection;
+
+/// Email notification configuration section (`[email]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EmailSection {
+    /// Server URL, `smtp://host[:port]` or `smtps://host[:port]`
+    pub smtp_server: Option<String>,
+
+    /// Sender address
+    pub from: Option<String>,
+
+    /// Recipient addresses
+    #[serde(default)]
+    pub to: Vec<String>,
+
+    /// Upgrade `smtp://` sessions with STARTTLS (default: true)
+    pub starttls: Option<bool>,
+
+    /// User name sent with `AUTH PLAIN`
+    pub username: Option<String>,
+
+    /// Password sent with `AUTH PLAIN`, with `username`
+    pub password: Option<String>,
+
+    /// Handlebars template of the subject (default: host and event)
+    pub subject_template: Option<String>,
+
+    /// Handlebars template of the body (default: one line per change)
+    pub body_template: Option<String>,
+
+    /// Seconds a send may take until the server accepts it (default: 30)
+    pub timeout: Option<u64>,
+
+    /// Custom TLS roots, client certificate and insecure mode
+    pub tls: Option<TlsSection>,
+
+    /// Regex patterns of the adapters whose changes are emailed (default: all)
+    #[serde(default)]
+    pub adapters: Vec<String>,
+
+    /// IP version whose address changes are emailed (default: both)
+    pub ip_version: Option<String>,
+
+    /// Leave out removed addresses (default: false)
+    #[serde(default)]
+    pub added_only: bool,
+}
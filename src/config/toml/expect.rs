@@ -0,0 +1,12 @@
+//! The `[expect]` section.
+
+use serde::Deserialize;
+
+/// Expected static address configuration section.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExpectSection {
+    /// Static addresses the monitored adapters must carry (default: none)
+    #[serde(default)]
+    pub addresses: Vec<String>,
+}
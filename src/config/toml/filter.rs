@@ -0,0 +1,48 @@
+//! The `[filter]` section.
+
+use serde::Deserialize;
+
+/// Adapter filter configuration section.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FilterSection {
+    /// Regex patterns for adapters to include (by name)
+    #[serde(default)]
+    pub include: Vec<String>,
+
+    /// Regex patterns for adapters to exclude (by name)
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
+    /// Adapter kinds to include (e.g., "ethernet", "wireless")
+    #[serde(default)]
+    pub include_kinds: Vec<String>,
+
+    /// Adapter kinds to exclude (e.g., "virtual", "loopback")
+    #[serde(default)]
+    pub exclude_kinds: Vec<String>,
+
+    /// Drop link-local addresses, `fe80::/10` and `169.254.0.0/16` (default: true)
+    pub exclude_link_local: Option<bool>,
+
+    /// Drop IPv6 unique local addresses, `fc00::/7` (default: true)
+    pub exclude_ula: Option<bool>,
+
+    /// Drop temporary IPv6 privacy addresses (default: true)
+    pub exclude_temporary: Option<bool>,
+
+    /// Keep private IPv4 addresses, RFC 1918 and `100.64.0.0/10` (default: true)
+    pub include_private: Option<bool>,
+
+    /// Monitor only the adapters a default route goes through (default: false)
+    #[serde(default)]
+    pub default_route_only: bool,
+
+    /// Address ranges to monitor (CIDR, e.g. "203.0.113.0/24"; empty = all)
+    #[serde(default)]
+    pub include_cidr: Vec<String>,
+
+    /// Address ranges to ignore (CIDR)
+    #[serde(default)]
+    pub exclude_cidr: Vec<String>,
+}
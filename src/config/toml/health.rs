@@ -0,0 +1,11 @@
+//! The `[health]` section.
+
+use serde::Deserialize;
+
+/// Health-check endpoint configuration section.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HealthSection {
+    /// Address and port serving `/healthz` (default: off)
+    pub bind: Option<String>,
+}
@@ -0,0 +1,58 @@
+//! The `[http]` section, with the `[http.tls]` settings that `[mqtt.tls]` and
+//! `[email.tls]` share.
+
+use serde::Deserialize;
+
+/// HTTP client configuration section.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HttpSection {
+    /// `User-Agent` sent with every request (default: `ddns-a/<version>`)
+    pub user_agent: Option<String>,
+
+    /// Send `X-DDNS-A-Version` and `X-DDNS-A-Host` headers (default: true)
+    pub metadata_headers: Option<bool>,
+
+    /// Nameservers resolving outbound hosts instead of the system resolver
+    #[serde(default)]
+    pub dns_servers: Vec<String>,
+
+    /// Seconds allowed to establish a connection (default: 10)
+    pub connect_timeout: Option<u64>,
+
+    /// Seconds allowed for a whole request (default: 60)
+    pub request_timeout: Option<u64>,
+
+    /// Seconds an unused pooled connection is kept open (default: 90)
+    pub pool_idle_timeout: Option<u64>,
+
+    /// Unused connections kept open per host, 0 disables pooling (default: 8)
+    pub pool_max_idle_per_host: Option<usize>,
+
+    /// Local address outbound connections are made from
+    pub local_address: Option<String>,
+
+    /// Network interface outbound connections go out through (Linux, macOS)
+    pub interface: Option<String>,
+
+    /// Custom TLS roots, client certificate and insecure mode
+    pub tls: Option<TlsSection>,
+}
+
+/// TLS configuration section (`[http.tls]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TlsSection {
+    /// PEM file with root certificates trusted besides the platform's
+    pub ca_file: Option<String>,
+
+    /// PEM file with the client certificate chain for mutual TLS
+    pub client_cert: Option<String>,
+
+    /// PEM file with the private key of `client_cert`
+    pub client_key: Option<String>,
+
+    /// Accept any server certificate (default: false)
+    #[serde(default)]
+    pub danger_accept_invalid_certs: bool,
+}
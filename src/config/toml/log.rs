@@ -0,0 +1,14 @@
+//! The `[log]` section.
+
+use serde::Deserialize;
+
+/// Logging and message configuration section.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LogSection {
+    /// Language of CLI errors and hints: "en" or "zh" (default: from `LANG`)
+    pub locale: Option<String>,
+
+    /// Where log output goes: "stderr" or "system" (default: stderr)
+    pub target: Option<String>,
+}
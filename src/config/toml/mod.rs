@@ -0,0 +1,137 @@
+//! TOML configuration file parsing.
+//!
+//! Defines the structure of the configuration file with serde, one module
+//! per section.
+
+use std::path::Path;
+
+use serde::Deserialize;
+
+use super::ConfigError;
+
+mod action;
+mod audit;
+mod email;
+mod expect;
+mod filter;
+mod health;
+mod http;
+mod log;
+mod monitor;
+mod mqtt;
+mod notify;
+mod output;
+mod retry;
+mod template;
+mod update;
+mod webhook;
+
+pub use action::ActionSection;
+pub use audit::AuditSection;
+pub use email::EmailSection;
+pub use expect::ExpectSection;
+pub use filter::FilterSection;
+pub use health::HealthSection;
+pub use http::{HttpSection, TlsSection};
+pub use log::LogSection;
+pub use monitor::MonitorSection;
+pub use mqtt::MqttSection;
+pub use notify::{ChatWebhookSection, NotifySection};
+pub use output::OutputSection;
+pub use retry::RetrySection;
+pub use template::default_config_template;
+pub use update::UpdateSection;
+pub use webhook::{
+    CircuitBreakerSection, DiscoverySection, OAuth2Section, PreRequestSection, ProviderSection,
+    TargetSection, WebhookSection,
+};
+
+/// Root configuration structure from TOML file.
+///
+/// All fields are optional to allow partial configuration
+/// that can be merged with CLI arguments.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TomlConfig {
+    /// Webhook configuration section
+    #[serde(default)]
+    pub webhook: WebhookSection,
+
+    /// Network adapter filter configuration
+    #[serde(default)]
+    pub filter: FilterSection,
+
+    /// Monitoring configuration
+    #[serde(default)]
+    pub monitor: MonitorSection,
+
+    /// Retry policy configuration
+    #[serde(default)]
+    pub retry: RetrySection,
+
+    /// Outbound request audit log configuration
+    #[serde(default)]
+    pub audit: AuditSection,
+
+    /// HTTP client configuration
+    #[serde(default)]
+    pub http: HttpSection,
+    /// Run summary output configuration
+    #[serde(default)]
+    pub output: OutputSection,
+
+    /// Logging and message configuration
+    #[serde(default)]
+    pub log: LogSection,
+
+    /// Self-update configuration
+    #[serde(default)]
+    pub update: UpdateSection,
+
+    /// Health-check endpoint configuration
+    #[serde(default)]
+    pub health: HealthSection,
+
+    /// Expected static address configuration
+    #[serde(default)]
+    pub expect: ExpectSection,
+
+    /// Actions run beside the webhook
+    #[serde(default)]
+    pub action: ActionSection,
+
+    /// MQTT broker every change is also published to
+    pub mqtt: Option<MqttSection>,
+
+    /// SMTP server every change is also emailed through
+    pub email: Option<EmailSection>,
+
+    /// Chat services every change is also posted to
+    #[serde(default)]
+    pub notify: NotifySection,
+}
+
+impl TomlConfig {
+    /// Loads configuration from a TOML file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let content = std::fs::read_to_string(path).map_err(|e| ConfigError::FileRead {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+
+        Self::parse(&content)
+    }
+
+    /// Parses configuration from a TOML string.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the TOML is invalid.
+    pub fn parse(content: &str) -> Result<Self, ConfigError> {
+        toml::from_str(content).map_err(ConfigError::from)
+    }
+}
@@ -0,0 +1,105 @@
+//! The `[monitor]` section, with `[monitor.address_storm]` and
+//! `[monitor.public]`.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+/// Monitoring configuration section.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[allow(clippy::struct_excessive_bools)] // One switch per tracked event
+pub struct MonitorSection {
+    /// Polling interval in seconds
+    pub poll_interval: Option<u64>,
+
+    /// Separate polling interval in seconds for IPv6 addresses
+    pub poll_interval_v6: Option<u64>,
+
+    /// Stretch the polling interval while addresses stay stable
+    #[serde(default)]
+    pub adaptive: bool,
+
+    /// Shortest adaptive polling interval in seconds
+    pub poll_interval_min: Option<u64>,
+
+    /// Longest adaptive polling interval in seconds
+    pub poll_interval_max: Option<u64>,
+
+    /// Disable API event listening, use polling only
+    #[serde(default)]
+    pub poll_only: bool,
+
+    /// Path to state file for detecting changes across restarts
+    pub state_file: Option<String>,
+
+    /// "auto" picks a platform default state file when `state_file` is unset;
+    /// "file" (default) only uses `state_file`
+    pub state: Option<String>,
+
+    /// Timeout in seconds for a single adapter fetch
+    pub fetch_timeout: Option<u64>,
+
+    /// Window in milliseconds merging rapid changes (0 disables debouncing)
+    pub debounce_ms: Option<u64>,
+
+    /// Quiet period in milliseconds ending a burst of OS change notifications
+    /// (0 fetches on every notification)
+    pub event_quiet_ms: Option<u64>,
+
+    /// Action taken when the monitor loop stalls (off, log, exit)
+    pub watchdog: Option<String>,
+
+    /// Report adapter DNS suffix and DNS server changes
+    #[serde(default)]
+    pub track_dns: bool,
+
+    /// Report changes of the machine hostname
+    #[serde(default)]
+    pub track_hostname: bool,
+
+    /// Report adapters going up or down
+    #[serde(default)]
+    pub track_link: bool,
+
+    /// Adapter names whose addresses win when choosing the effective address
+    #[serde(default)]
+    pub adapter_priority: Vec<String>,
+
+    /// Alerts on adapters carrying too many IPv6 addresses
+    pub address_storm: Option<AddressStormSection>,
+
+    /// Where addresses come from: "adapter", "public" or "both"
+    pub source: Option<String>,
+
+    /// Services reporting the public address
+    pub public: Option<PublicSection>,
+}
+
+/// IPv6 address storm section (`[monitor.address_storm]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AddressStormSection {
+    /// IPv6 addresses allowed per adapter (required)
+    pub max_addresses: Option<usize>,
+
+    /// Limits of single adapters by exact name, overriding `max_addresses`
+    #[serde(default)]
+    pub adapters: HashMap<String, usize>,
+
+    /// Report a storming adapter's IPv6 addresses as /64 prefixes
+    #[serde(default)]
+    pub prefix_only: bool,
+}
+
+/// Public address lookup section (`[monitor.public]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PublicSection {
+    /// HTTP(S) URLs or `stun:host[:port]` servers, asked in order
+    #[serde(default)]
+    pub endpoints: Vec<String>,
+
+    /// Timeout in seconds of a single lookup
+    pub timeout: Option<u64>,
+}
@@ -0,0 +1,53 @@
+//! The `[mqtt]` section.
+
+use serde::Deserialize;
+
+use super::http::TlsSection;
+
+/// MQTT broker configuration section (`[mqtt]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MqttSection {
+    /// Broker URL, `mqtt://host[:port]` or `mqtts://host[:port]`
+    pub broker: Option<String>,
+
+    /// Topic address changes are published to; other events go to subtopics
+    pub topic: Option<String>,
+
+    /// Delivery guarantee: 0, 1 or 2 (default: 1)
+    pub qos: Option<u8>,
+
+    /// Ask the broker to keep the last message for new subscribers
+    /// (default: false)
+    #[serde(default)]
+    pub retain: bool,
+
+    /// Client identifier (default: "ddns-a-" followed by the hostname)
+    pub client_id: Option<String>,
+
+    /// User name sent to the broker
+    pub username: Option<String>,
+
+    /// Password sent to the broker, with `username`
+    pub password: Option<String>,
+
+    /// Handlebars template of the message (default: the payload document)
+    pub body_template: Option<String>,
+
+    /// Seconds a publish may take until acknowledged (default: 10)
+    pub timeout: Option<u64>,
+
+    /// Custom TLS roots, client certificate and insecure mode (`mqtts://`)
+    pub tls: Option<TlsSection>,
+
+    /// Regex patterns of the adapters whose changes are published (default: all)
+    #[serde(default)]
+    pub adapters: Vec<String>,
+
+    /// IP version whose address changes are published (default: both)
+    pub ip_version: Option<String>,
+
+    /// Leave out removed addresses (default: false)
+    #[serde(default)]
+    pub added_only: bool,
+}
@@ -0,0 +1,59 @@
+//! The `[notify]` section: `[notify.telegram]`, `[notify.discord]` and
+//! `[notify.slack]`.
+
+use serde::Deserialize;
+
+/// Chat notification configuration section (`[notify]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NotifySection {
+    /// Telegram bot
+    pub telegram: Option<TelegramSection>,
+
+    /// Discord channel webhook
+    pub discord: Option<ChatWebhookSection>,
+
+    /// Slack incoming webhook
+    pub slack: Option<ChatWebhookSection>,
+}
+
+/// Telegram bot configuration section (`[notify.telegram]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TelegramSection {
+    /// Bot token from `@BotFather`, `<id>:<secret>`
+    pub bot_token: Option<String>,
+
+    /// Chat ID (numeric, negative for groups) or `@channel` name
+    pub chat_id: Option<String>,
+
+    /// Regex patterns of the adapters whose changes are posted (default: all)
+    #[serde(default)]
+    pub adapters: Vec<String>,
+
+    /// IP version whose address changes are posted (default: both)
+    pub ip_version: Option<String>,
+
+    /// Leave out removed addresses (default: false)
+    #[serde(default)]
+    pub added_only: bool,
+}
+
+/// Chat webhook configuration section (`[notify.discord]`, `[notify.slack]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChatWebhookSection {
+    /// Webhook URL issued by the service
+    pub webhook_url: Option<String>,
+
+    /// Regex patterns of the adapters whose changes are posted (default: all)
+    #[serde(default)]
+    pub adapters: Vec<String>,
+
+    /// IP version whose address changes are posted (default: both)
+    pub ip_version: Option<String>,
+
+    /// Leave out removed addresses (default: false)
+    #[serde(default)]
+    pub added_only: bool,
+}
@@ -0,0 +1,17 @@
+//! The `[output]` section.
+
+use serde::Deserialize;
+
+/// Run summary output configuration section.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutputSection {
+    /// Write a JSON run summary to stderr on exit (default: false)
+    pub summary: Option<bool>,
+
+    /// Write the JSON run summary to this file instead of stderr
+    pub summary_file: Option<String>,
+
+    /// Append every detected change and delivery result to this JSON-lines file
+    pub history_file: Option<String>,
+}
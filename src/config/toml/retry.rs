@@ -0,0 +1,49 @@
+//! The `[retry]` section, with `[retry.queue]`.
+
+use serde::Deserialize;
+
+/// Retry policy configuration section.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetrySection {
+    /// Maximum number of retry attempts
+    pub max_attempts: Option<u32>,
+
+    /// Initial retry delay in seconds
+    pub initial_delay: Option<u64>,
+
+    /// Maximum retry delay in seconds
+    pub max_delay: Option<u64>,
+
+    /// Backoff multiplier
+    pub multiplier: Option<f64>,
+
+    /// Randomization of retry delays: "none", "full" or "equal"
+    pub jitter: Option<String>,
+
+    /// Handlebars body template used on retry attempts instead of `webhook.body_template`
+    pub body_template: Option<String>,
+
+    /// Query parameter ("name=value") appended to the URL on retry attempts
+    pub query: Option<String>,
+
+    /// Check of the worst-case retry wait against the poll interval: "off", "warn" or "error"
+    pub overlap: Option<String>,
+
+    /// Seconds deliveries in progress may keep retrying after a stop signal
+    pub shutdown_grace: Option<u64>,
+
+    /// Persistent queue of changes whose retries ran out (disabled if absent)
+    pub queue: Option<RetryQueueSection>,
+}
+
+/// Persistent retry queue configuration section (`[retry.queue]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetryQueueSection {
+    /// Seconds a change is kept before being dropped
+    pub max_age: Option<u64>,
+
+    /// Number of changes kept; the oldest are dropped first
+    pub max_size: Option<usize>,
+}
@@ -0,0 +1,566 @@
+//! The commented default configuration written by `ddns-a init`.
+
+/// Generates a default configuration file with comments.
+#[must_use]
+pub fn default_config_template() -> String {
+    DEFAULT_CONFIG_TEMPLATE.to_string()
+}
+
+/// Commented default configuration written by `ddns-a init`.
+const DEFAULT_CONFIG_TEMPLATE: &str = r#"# DDNS-A Configuration File
+# Documentation: https://github.com/doraemonkeys/ddns-a
+
+[webhook]
+# Webhook URL (required unless [webhook.provider] is set)
+# A URL containing {{ is a template rendered per request, values percent-encoded:
+#   url = "https://dyn.example.com/update?myip={{first_added_v4 changes}}"
+# url = "https://api.example.com/ddns"
+
+# IP version to monitor (required)
+# Accepted values: "ipv4"/"v4"/"4", "ipv6"/"v6"/"6", or "both"/"all"/"dual"
+# ip_version = "both"
+
+# Kinds of address change to notify (default: both)
+# ["added"] suppresses removed addresses before any delivery; ["removed"]
+# suppresses new ones. The state file still tracks every change.
+# notify_on = ["added", "removed"]
+
+# HTTP method (default: POST, can be overridden by --method CLI flag)
+# method = "POST"
+
+# HTTP headers
+# [webhook.headers]
+# X-Custom-Header = "value"
+# X-Current-IP = "{{first_added changes}}"  # values with {{ are body-style templates
+
+# Bearer token for Authorization header
+# bearer = "your-token-here"
+
+# Handlebars body template
+# Available variables: {{adapter}}, {{address}}, {{timestamp}}, {{kind}}
+# Helpers: {{first_added changes}}, {{first_added_v4 changes}},
+# {{first_added_v6 changes}}, {{#each (only_added changes)}}, {{json value}},
+# {{iso8601 timestamp}}, {{urlencode value}}
+# body_template = '{"ip": "{{address}}", "adapter": "{{adapter}}"}'
+
+# Handlebars body template for DNS setting changes (see [monitor] track_dns)
+# Available variables: {{#each dns_changes}} with {{adapter}}, {{suffix}},
+# {{old_suffix}}, {{servers}}, {{old_servers}}, {{timestamp}}
+# Default: the same data as JSON
+# dns_body_template = '{"adapter": "{{#each dns_changes}}{{adapter}}{{/each}}"}'
+
+# Body of address changes without body_template: "json-v1" (default), a frozen
+# {"version": 1, "changes": [{adapter, address, kind, timestamp_rfc3339}],
+# "host": ...} document, or "none" for no body
+# payload_format = "json-v1"
+
+# Encoding of bodies sent without a template: "json", "yaml", or "cbor"
+# Once set, address changes without body_template carry the template
+# variables in this encoding instead of the payload_format document
+# (DNS changes always use JSON unless set)
+# payload_encoding = "cbor"
+
+# Require the receiver to echo a per-attempt nonce (default: "off"), to catch
+# proxies or CDNs answering with a cached 200. Each attempt sends the nonce in
+# the X-DDNS-A-Nonce header and as the {{nonce}} template variable.
+# "header": the response must carry it in X-DDNS-A-Nonce; "body": the
+# response body must contain it. Otherwise the attempt fails and is retried.
+# echo_check = "header"
+
+# Address changes per request (default: "batched", the whole batch at once)
+# "per_change": one request per added or removed address, for endpoints that
+# accept a single IP. Changes are sent one after another in batch order, each
+# with its own retries; a failed change does not stop the rest of the batch.
+# delivery = "per_change"
+
+# Minimum seconds between address notifications, for flapping adapters.
+# Changes detected sooner are held, coalesced (an address added and removed
+# again is dropped) and sent when the interval ends. Disabled by default.
+# min_notify_interval = 60
+
+# Require a field of every 2xx JSON response to hold a value, for APIs that
+# answer 200 with an error in the body. path is a JSONPath such as
+# "$.status" or "$.results[0].ok"; equals may be a string, number or boolean.
+# A mismatch fails the delivery, or is retried like a 5xx with retry = true.
+# expect_json = { path = "$.status", equals = "ok", retry = false }
+
+# Require the body of every 2xx response to match a regex, for APIs that
+# answer 200 with a plain-text error such as "KO" or "badauth". A mismatch
+# fails the delivery, or is retried like a 5xx with expect_body_retry = true.
+# expect_body_regex = "good|nochg"
+# expect_body_retry = false
+
+# TTL hint in seconds for downstream caches (default: none)
+# Address payloads carry it as ttl, and each added address an expires Unix
+# time; [webhook.provider] records use it unless the provider sets its own ttl
+# ttl = 300
+
+# Check connectivity before delivering (default: "none")
+# "family": postpone deliveries while this host and the webhook host share no
+# address in ip_version's family (e.g. no global IPv6 address yet); postponed
+# changes are sent with the next delivery once connectivity returns
+# connectivity_check = "family"
+
+# Discover the webhook URL from DNS; url above is used until the first
+# successful lookup and whenever a lookup fails. Only https URLs are accepted.
+# [webhook.discovery]
+# "txt": the record's text is the URL; "srv": https://<target>:<port><path>
+# type = "txt"
+# name = "_ddns-a.example.com"
+# path = "/ddns"             # srv only (default: "/")
+# nameserver = "192.0.2.53"  # default: first nameserver in /etc/resolv.conf
+# refresh = 3600             # seconds between lookups, 0 = startup only
+
+# Record the SHA-256 fingerprint of the TLS certificate each host presents
+# (kept in --state-file) and warn when it changes. With pins, the webhook
+# host must present one of them, or the connection fails before anything is
+# sent; list the next certificate's fingerprint before renewing.
+# [webhook.certificate]
+# track = true
+# pins = ["9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"]
+
+# Update a DNS provider's records directly instead of calling a webhook.
+# Without url the provider's API is used; body_template must not be set.
+# Responses are checked, so a rejected update counts as a failed delivery.
+# --provider, --provider-domain, --provider-token and --provider-secret
+# override name, domain, token and secret.
+# [webhook.provider]
+# name = "duckdns"        # "duckdns", "porkbun", "gandi", "desec", "noip", "dynu", or "freedns"
+# domain = "myhost"       # duckdns: subdomain; porkbun/gandi: zone; others: hostname
+# record = "home"         # porkbun/gandi/desec: name within domain (default: apex)
+# token = "your-token"    # porkbun: API key; gandi: access token; noip: username;
+#                         # dynu: IP update password; freedns: the record's update token
+# secret = "sk1_..."      # porkbun: secret API key; noip: password
+# ttl = 600               # porkbun/gandi record TTL in seconds (default: webhook.ttl, else 600)
+
+# Send a request before every attempt, e.g. to log in and fetch a fresh
+# session or CSRF token. Captured values are available to body_template as
+# {{pre.<name>}}, and "{{pre.<name>}}" in a [webhook.headers] value is replaced.
+# A failed pre-request fails the attempt; a missing capture fails the delivery.
+# [webhook.pre_request]
+# url = "https://panel.example.com/api/login"
+# method = "POST"                     # default: POST
+# body = '{"user": "me", "password": "secret"}'
+# [webhook.pre_request.headers]
+# Content-Type = "application/json"
+# [webhook.pre_request.capture]
+# csrf = "header:X-CSRF-Token"        # a response header
+# session = "json:/data/session"      # a JSON pointer into the response body
+
+# Authenticate with OAuth2 client-credentials tokens, for endpoints whose
+# bearer tokens expire. A token is fetched from token_url, cached until
+# shortly before it expires and sent as "Authorization: Bearer <token>";
+# a 401 answer fetches a new token and resends once. Cannot be combined with
+# bearer, an Authorization header or [webhook.provider].
+# [webhook.oauth2]
+# token_url = "https://auth.example.com/oauth/token"   # https unless on localhost
+# client_id = "ddns-a"
+# client_secret = "your-client-secret"
+# scope = "ddns.write"                  # space-separated (default: none)
+# audience = "https://api.example.com"  # Auth0 and similar (default: none)
+# client_auth = "basic"                 # "basic" (default) or "body"
+
+# Stop hammering a dead endpoint: after `failures` consecutive failed
+# deliveries, a webhook's circuit opens and its deliveries are skipped for
+# `cooldown` seconds, with a single warning. The next delivery after that
+# probes the endpoint and closes the circuit on success. Each of
+# [[webhook.targets]] has its own circuit, and can set its own policy in
+# [webhook.targets.circuit_breaker]. Disabled unless the section is set.
+# [webhook.circuit_breaker]
+# failures = 5                  # default: 5
+# cooldown = 300                # seconds (default: 300)
+
+# Send the address changes of one IP version to another URL, e.g. A and
+# AAAA updates to different endpoints. The URL is used with every other
+# [webhook] setting; url above keeps the other version and the changes
+# without an address (DNS servers, hostname...). The version must be
+# monitored; not with [webhook.provider].
+# [webhook.ipv4]
+# url = "https://example.com/ddns/a"
+# [webhook.ipv6]
+# url = "https://example.com/ddns/aaaa"
+
+# Further webhooks every change is also sent to, concurrently with the one
+# above. Each target retries on its own; a delivery succeeds only if every
+# target accepted it. Targets use only the settings below and
+# webhook.connectivity_check; the other [webhook] settings apply to the
+# webhook above only.
+# Route changes by adapter with `adapters` (regex patterns, default: all):
+# here and in [webhook], a webhook only receives the changes of matching
+# adapters, and is skipped for batches without any. `added_only = true`
+# leaves out removed addresses; on targets, `ip_version` limits the address
+# changes. [action.exec], [mqtt], [email] and [notify.*] take the same
+# three keys.
+# [[webhook.targets]]
+# name = "private-api"            # shown in logs and reports (default: URL host)
+# url = "https://api.example.com/ddns"
+# method = "PUT"                  # default: POST
+# bearer = "your-token"
+# body_template = '{"ip": "{{address}}"}'
+# adapters = ["^wg0$"]            # only changes of the WireGuard adapter
+# ip_version = "ipv6"             # only IPv6 address changes (default: both)
+# added_only = true               # no removed addresses (default: false)
+# delivery = "per_change"         # default: "batched"
+# connect_timeout = 5             # seconds (default: from [http])
+# request_timeout = 20            # seconds (default: from [http])
+# [webhook.targets.headers]
+# X-Api-Key = "secret"
+# [webhook.targets.retry]         # unset values are taken from [retry]
+# max_attempts = 5
+# [webhook.targets.circuit_breaker]  # unset values are taken from
+# failures = 3                       # [webhook.circuit_breaker]
+
+[filter]
+# Adapter kinds to include (empty = all kinds)
+# Valid values: ethernet, wireless, virtual, loopback
+# Note: CLI --include-kind REPLACES these entirely (not merged)
+# include_kinds = ["ethernet", "wireless"]
+
+# Adapter kinds to exclude
+# Note: Loopback is excluded by default unless explicitly included
+# Note: CLI --exclude-kind REPLACES these entirely (not merged)
+# exclude_kinds = ["virtual"]
+
+# Regex patterns for adapters to include by name (empty = all names)
+# Note: CLI --include-adapter REPLACES these entirely (not merged)
+# include = ["^eth", "^Ethernet"]
+
+# Regex patterns for adapters to exclude by name
+# Note: CLI --exclude-adapter REPLACES these entirely (not merged)
+# exclude = ["^Docker", "^vEthernet"]
+
+# Monitor only the adapter holding the default route, following it when the
+# route moves, e.g. from Wi-Fi to Ethernet (Windows and macOS; default: false)
+# default_route_only = true
+
+# Address classes dropped before changes are detected, on every adapter
+# exclude_link_local = true   # fe80::/10 and 169.254.0.0/16 (default: true)
+# exclude_ula = true          # IPv6 unique local fc00::/7, e.g. fd00::/8 (default: true)
+# exclude_temporary = true    # IPv6 privacy addresses, where reported (default: true)
+# include_private = true      # false drops 10/8, 172.16/12, 192.168/16, 100.64/10 (default: true)
+
+# Address ranges (CIDR) to monitor and to ignore, on every adapter; excludes
+# win, and with includes set only addresses in one of them are monitored
+# include_cidr = ["203.0.113.0/24", "2001:db8::/32"]
+# exclude_cidr = ["2001:db8:ffff::/48"]
+
+[monitor]
+# Polling interval in seconds (default: 60)
+poll_interval = 60
+
+# Poll IPv6 addresses at their own interval in seconds, e.g. more often since
+# router advertisements change them more often than DHCP changes IPv4 ones;
+# poll_interval then applies to IPv4 only (default: poll_interval)
+# poll_interval_v6 = 15
+
+# Adaptive polling: every poll without a change lengthens the interval by half,
+# up to poll_interval_max; a change (or, in hybrid mode, an API event) drops it
+# back to poll_interval_min at once. The IPv6 interval above is not adapted
+# adaptive = false
+# Shortest interval in seconds (default: poll_interval)
+# poll_interval_min = 60
+# Longest interval in seconds (default: 10x poll_interval_min)
+# poll_interval_max = 600
+
+# Disable API event listening, use polling only
+# poll_only = false
+
+# Path to state file for detecting changes across restarts
+# If set, the program will compare current IP addresses with the saved state
+# and trigger webhooks for any changes detected during the program restart
+# state_file = "ddns-a-state.json"
+
+# Without state_file, "auto" keeps the state in a platform default location:
+# %ProgramData%\ddns-a\state.json on Windows, /var/lib/ddns-a/state.json as
+# root on Unix, else $XDG_STATE_HOME (or ~/.local/state)/ddns-a/state.json.
+# "file" (default) only uses state_file
+# state = "auto"
+
+# Timeout in seconds for a single adapter fetch (default: 30)
+# Fetches run on a background thread; a hung OS call is abandoned after this
+# fetch_timeout = 30
+
+# Window in milliseconds merging rapid changes before they are sent (default:
+# 2000). An address added and removed again within it is not reported. Must be
+# shorter than the poll interval; 0 sends every change at once
+# debounce_ms = 2000
+
+# In hybrid mode, a burst of OS change notifications (e.g. a DHCP storm) is
+# checked once, after none has arrived for this many milliseconds (default:
+# 250). Must be shorter than the poll interval; 0 checks on every notification
+# event_quiet_ms = 250
+
+# Watchdog for a stalled monitor loop (default: "log")
+# A stall is reported when no adapter fetch completes within 2x poll_interval
+# Valid values: off, log, exit (exit lets a service manager restart ddns-a)
+# watchdog = "log"
+
+# Report adapter DNS suffix and DNS server changes (default: false)
+# Sent as separate deliveries with an X-DDNS-A-Event: dns header, using
+# webhook.dns_body_template; Windows VPN connects often change only these
+# track_dns = false
+
+# Report changes of the machine hostname (default: false)
+# Sent as separate deliveries with an X-DDNS-A-Event: hostname header, for
+# records derived from the hostname
+# track_hostname = false
+
+# Report adapters going up or down (default: false)
+# Sent as separate deliveries with an X-DDNS-A-Event: link header, whose
+# link_changes carry state (up or down) and the operational status
+# track_link = false
+
+# Adapters whose addresses win when choosing the effective address per family
+# (exact names, highest priority first; other adapters follow in system order)
+# Reported as effective_addresses in the run summary
+# adapter_priority = ["Ethernet", "Wi-Fi"]
+
+# Where monitored addresses come from (default: "adapter")
+# Valid values: adapter (local adapters), public (the address the internet
+# sees, for hosts behind NAT), both (public reported as adapter "public")
+# Public addresses are looked up every poll_interval; no OS event announces
+# a change of the router's address
+# source = "adapter"
+
+# Alert when an adapter carries more IPv6 addresses than allowed, e.g. from
+# privacy extensions churning too fast or a router flooding advertisements.
+# Link-local addresses are not counted. Sent as separate deliveries with an
+# X-DDNS-A-Event: storm header when a storm starts and when it ends.
+# [monitor.address_storm]
+# max_addresses = 16        # per adapter
+# prefix_only = false       # report a storming adapter's IPv6 /64 prefixes
+#                           # instead of its addresses, until restart
+# [monitor.address_storm.adapters]
+# "Wi-Fi" = 32              # exact adapter name = its own limit
+
+# Services reporting the public address (source = "public" or "both"), asked
+# in order until every monitored family has an address. HTTP(S) endpoints
+# answer with the address as plain text; STUN servers are written
+# stun:host[:port] (default port 3478)
+# [monitor.public]
+# endpoints = ["https://api4.ipify.org", "https://api6.ipify.org"]  # default
+# timeout = 5               # seconds per lookup
+
+[retry]
+# Maximum number of retry attempts (default: 3)
+# max_attempts = 3
+
+# Initial retry delay in seconds (default: 5)
+# initial_delay = 5
+
+# Maximum retry delay in seconds (default: 60)
+# A 429 or 503 answer's Retry-After header lengthens the delay, up to this
+# max_delay = 60
+
+# Backoff multiplier (default: 2.0)
+# multiplier = 2.0
+
+# Randomize retry delays so instances failing at the same time do not retry
+# in lockstep (default: "none"). "full": between zero and the delay;
+# "equal": between half the delay and the delay. Also used by targets.
+# jitter = "full"
+
+# Every attempt carries X-Attempt and X-Attempt-Elapsed-Ms headers, and
+# templates can use {{attempt}}, {{elapsed_ms}} and {{is_retry}}
+
+# Body template used on retry attempts (default: webhook.body_template)
+# body_template = '{"ip": "{{#each changes}}{{address}}{{/each}}", "retry": {{attempt}}}'
+
+# Query parameter appended to the URL on retry attempts
+# query = "retry=true"
+
+# What to do when the waits between retries (max_attempts, initial_delay,
+# max_delay, multiplier) add up to more than the poll interval: "off",
+# "warn" (log on startup) or "error" (reject the configuration). Deliveries
+# never overlap; changes made while retrying are delivered afterwards.
+# overlap = "warn"
+
+# Seconds a delivery in progress may keep retrying after Ctrl+C, SIGTERM or a
+# service stop before it is cut short (default: 0). No new changes are taken
+# meanwhile; a second Ctrl+C or SIGTERM cuts it short at once.
+# shutdown_grace = 30
+
+# Keep changes whose retries ran out in the state file (requires state_file)
+# and send them again every poll interval and on the next start, ahead of
+# newer changes, until delivered or dropped by these limits
+# [retry.queue]
+# max_age = 86400           # seconds a change is kept (default: 1 day)
+# max_size = 1000           # changes kept, oldest dropped first (default: 1000)
+
+[audit]
+# Append a JSON line for every outbound request (method, URL, header names,
+# body SHA-256, response status). Disabled unless a file is set.
+# Header and query parameter values are redacted unless allow-listed below.
+# file = "ddns-a-audit.jsonl"
+
+# Header names whose values are recorded as-is
+# allow_headers = ["Content-Type"]
+
+# Query parameter names whose values are recorded as-is
+# allow_query = ["ip"]
+
+[http]
+# User-Agent sent with every request (default: "ddns-a/<version>")
+# A User-Agent set in [webhook.headers] takes precedence
+# user_agent = "ddns-a"
+
+# Send X-DDNS-A-Version and X-DDNS-A-Host headers identifying this machine
+# metadata_headers = true
+
+# Resolve the webhook, provider and update hosts through these nameservers,
+# asked in order, instead of the system resolver, which may still be broken
+# right after a reconnect. Also used by the connectivity check and, without
+# its own nameserver, by webhook URL discovery.
+# dns_servers = ["1.1.1.1", "[2606:4700:4700::1111]:53"]
+
+# Timeouts of every outbound request, in seconds, so a slow provider cannot
+# stall the monitor. request_timeout covers the whole exchange (including
+# self-update downloads); connect_timeout must not exceed it.
+# connect_timeout = 10
+# request_timeout = 60
+
+# Connection pool: seconds an unused connection is kept open, and how many
+# are kept per host (0 disables pooling)
+# pool_idle_timeout = 90
+# pool_max_idle_per_host = 8
+
+# Send every outbound request through a given link: local_address binds the
+# source address ("::" or "0.0.0.0" only pins the address family), interface
+# the network interface (Linux and macOS; not Windows)
+# local_address = "2001:db8::10"
+# interface = "eth0"
+
+# TLS settings of every outbound request. ca_file adds the roots of a
+# private CA to the platform's; client_cert and client_key (PEM, set both)
+# are presented to endpoints requiring mutual TLS.
+# danger_accept_invalid_certs accepts ANY server certificate, so anyone on
+# the path can read the credentials sent: for lab environments only.
+# [http.tls]
+# ca_file = "/etc/ddns-a/ca.pem"
+# client_cert = "/etc/ddns-a/client.pem"
+# client_key = "/etc/ddns-a/client.key"
+# danger_accept_invalid_certs = false
+
+[output]
+# Write a one-line JSON run summary on exit (changes detected, notifications
+# sent/failed/postponed, uptime, last addresses) to stderr
+# summary = true
+
+# Write the summary to this file instead (implies summary = true)
+# summary_file = "ddns-a-summary.json"
+
+# Append a JSON line for every detected change and delivery result;
+# read it back with `ddns-a history --since 24h --adapter eth0`
+# history_file = "ddns-a-history.jsonl"
+
+[log]
+# Language of CLI errors and hints: "en" or "zh"
+# (default: from LC_ALL / LC_MESSAGES / LANG, falling back to English)
+# locale = "zh"
+# Where log output goes: "stderr" (default) or "system", i.e. syslog (which
+# journald collects under systemd) on Unix and the Application event log
+# (source "ddns-a") on Windows. Interactive commands keep logging to stderr.
+# target = "system"
+
+[health]
+# Serve GET /healthz on this address: 200 while the latest adapter fetch
+# succeeded, 503 otherwise, with the last delivery's age and the known
+# addresses as JSON. Use 0.0.0.0 to reach it from outside a container
+# bind = "127.0.0.1:8053"
+
+[update]
+# Allow `ddns-a self-update` to replace this binary with the latest release
+# enabled = true
+
+# Ed25519 public key (64 hex digits) that release binaries must be signed
+# with; required when enabled
+# public_key = "..."
+
+# Latest-release URL in GitHub's API format (default: the ddns-a releases)
+# feed = "https://api.github.com/repos/doraemonkeys/ddns-a/releases/latest"
+
+[expect]
+# Static addresses the monitored adapters must carry. An alert is logged
+# and sent (X-DDNS-A-Event: drift) when one disappears, or when an address
+# of the same family appears that is not listed, and again once resolved
+# addresses = ["203.0.113.10"]
+
+[action.exec]
+# Run a local command on every delivery, beside the webhook and with its
+# retry policy, e.g. nsupdate or a shell script. The command is run without
+# a shell and gets DDNS_A_EVENT, DDNS_A_ADDED, DDNS_A_REMOVED,
+# DDNS_A_ADDED_V4, DDNS_A_ADDED_V6 and the JSON payload in DDNS_A_PAYLOAD
+# command = ["/usr/local/bin/update-dns.sh", "--zone", "example.com"]
+
+# Seconds a run may take before the command is killed
+# timeout = 30
+
+# Pass the JSON payload on standard input instead of DDNS_A_PAYLOAD
+# input = "stdin"
+
+# [mqtt]
+# Also publish every change to an MQTT broker, with the webhook's retry
+# policy, e.g. for Home Assistant. Address changes go to the topic, other
+# events to a subtopic named after them (e.g. "ddns-a/dns")
+# broker = "mqtts://broker.example.com:8883"
+# topic = "ddns-a"
+
+# Delivery guarantee (0, 1 or 2) and whether the broker keeps the last
+# message for new subscribers
+# qos = 1
+# retain = true
+
+# client_id = "ddns-a-nas"
+# username = "ddns-a"
+# password = "..."
+
+# Handlebars template of address change messages (default: the JSON
+# document of webhook.payload_format)
+# body_template = "{{first_added_v4 changes}}"
+
+# Seconds a publish may take until the broker acknowledges it
+# timeout = 10
+
+# [mqtt.tls]
+# ca_file = "/etc/ssl/broker-ca.pem"
+
+# [email]
+# Also email every change, with the webhook's retry policy. smtp:// servers
+# (port 587) are upgraded with STARTTLS, smtps:// servers (port 465) use TLS
+# from the start. With [email], [mqtt] or [action.exec], webhook.url may
+# be left out
+# smtp_server = "smtp://smtp.example.com:587"
+# from = "ddns-a@example.com"
+# to = ["admin@example.com"]
+
+# Credentials, sent with AUTH PLAIN over TLS only
+# username = "ddns-a@example.com"
+# password = "..."
+
+# Send without TLS to a trusted local relay (no credentials then)
+# starttls = false
+
+# Handlebars templates of the subject and body (default: the host and
+# event, and one line per address change)
+# subject_template = "New address: {{first_added changes}}"
+# body_template = "{{json changes}}"
+
+# Seconds a send may take until the server accepts the message
+# timeout = 30
+
+# [email.tls]
+# ca_file = "/etc/ssl/smtp-ca.pem"
+
+# [notify.telegram]
+# Also post every change as a readable chat message ("eth0 got new IPv4
+# 203.0.113.5"), with the webhook's retry policy. With [notify.*],
+# webhook.url may be left out
+# bot_token = "123456:ABC-DEF..."
+# chat_id = "-1001234567890"
+
+# [notify.discord]
+# webhook_url = "https://discord.com/api/webhooks/..."
+
+# [notify.slack]
+# webhook_url = "https://hooks.slack.com/services/..."
+"#;
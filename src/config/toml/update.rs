@@ -0,0 +1,18 @@
+//! The `[update]` section.
+
+use serde::Deserialize;
+
+/// Self-update configuration section.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateSection {
+    /// Allow `ddns-a self-update` (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Ed25519 key release binaries must be signed with (64 hex digits)
+    pub public_key: Option<String>,
+
+    /// Latest-release URL in GitHub's API format (default: this project)
+    pub feed: Option<String>,
+}
@@ -0,0 +1,312 @@
+//! The `[webhook]` section and its subtables: the alternative targets,
+//! response checks, certificate pins, endpoint discovery, provider preset,
+//! pre-request and `OAuth2` client.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+/// Webhook configuration section.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookSection {
+    /// Webhook URL
+    pub url: Option<String>,
+
+    /// IP version to monitor: "ipv4", "ipv6", or "both"
+    pub ip_version: Option<String>,
+
+    /// Kinds of address change notified: "added", "removed", or both
+    /// (default: both)
+    pub notify_on: Option<Vec<String>>,
+
+    /// HTTP method (default: POST)
+    pub method: Option<String>,
+
+    /// HTTP headers as key-value pairs
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+
+    /// Bearer token for Authorization header
+    pub bearer: Option<String>,
+
+    /// Handlebars body template
+    pub body_template: Option<String>,
+
+    /// Handlebars body template for DNS setting changes (`monitor.track_dns`)
+    pub dns_body_template: Option<String>,
+
+    /// Encoding of non-templated bodies: "json", "yaml", or "cbor"
+    pub payload_encoding: Option<String>,
+
+    /// Default body of address changes without a template or encoding:
+    /// "json-v1" (default) or "none"
+    pub payload_format: Option<String>,
+
+    /// Nonce the receiver must echo: "off" (default), "header", or "body"
+    pub echo_check: Option<String>,
+
+    /// Address changes per request: `batched` (default) or `per_change`
+    pub delivery: Option<String>,
+
+    /// Minimum seconds between address notifications; changes in between
+    /// are coalesced and sent when the interval ends
+    pub min_notify_interval: Option<u64>,
+
+    /// Assertion on a field of JSON delivery responses
+    pub expect_json: Option<ExpectJsonSection>,
+
+    /// Regex the bodies of delivery responses must match
+    pub expect_body_regex: Option<String>,
+
+    /// Whether a body not matching `expect_body_regex` is retried (default: false)
+    pub expect_body_retry: Option<bool>,
+
+    /// TTL hint in seconds for address payloads and provider records
+    pub ttl: Option<u32>,
+
+    /// Connectivity check before delivery: "none" (default) or "family"
+    pub connectivity_check: Option<String>,
+
+    /// Webhook URL discovery from DNS
+    pub discovery: Option<DiscoverySection>,
+
+    /// Built-in DNS provider preset
+    pub provider: Option<ProviderSection>,
+
+    /// Request sent before every attempt, feeding its response to it
+    pub pre_request: Option<PreRequestSection>,
+
+    /// `OAuth2` client-credentials authentication
+    pub oauth2: Option<OAuth2Section>,
+
+    /// TLS certificate fingerprint tracking and pinning
+    pub certificate: Option<CertificateSection>,
+
+    /// Circuit breaker skipping deliveries to a failing webhook (disabled if absent)
+    pub circuit_breaker: Option<CircuitBreakerSection>,
+
+    /// URL receiving the IPv4 address changes instead of `url`
+    pub ipv4: Option<VersionUrlSection>,
+
+    /// URL receiving the IPv6 address changes instead of `url`
+    pub ipv6: Option<VersionUrlSection>,
+
+    /// Regex patterns of the adapters whose changes this webhook receives
+    /// (default: all)
+    #[serde(default)]
+    pub adapters: Vec<String>,
+
+    /// Leave out removed addresses (default: false)
+    #[serde(default)]
+    pub added_only: bool,
+
+    /// Further webhooks every change is also sent to
+    #[serde(default)]
+    pub targets: Vec<TargetSection>,
+}
+
+/// Per-version webhook URL (`[webhook.ipv4]`, `[webhook.ipv6]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VersionUrlSection {
+    /// Target URL (required)
+    pub url: Option<String>,
+}
+
+/// Additional webhook target (`[[webhook.targets]]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TargetSection {
+    /// Name shown in logs and reports (default: the URL's host)
+    pub name: Option<String>,
+
+    /// Target URL (required)
+    pub url: Option<String>,
+
+    /// HTTP method (default: POST)
+    pub method: Option<String>,
+
+    /// HTTP headers as key-value pairs
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+
+    /// Bearer token for Authorization header
+    pub bearer: Option<String>,
+
+    /// Handlebars body template (default: the JSON payload)
+    pub body_template: Option<String>,
+
+    /// Address changes per request: `batched` (default) or `per_change`
+    pub delivery: Option<String>,
+
+    /// Regex patterns of the adapters whose changes this target receives
+    /// (default: all)
+    #[serde(default)]
+    pub adapters: Vec<String>,
+
+    /// IP version whose address changes this target receives (default: both)
+    pub ip_version: Option<String>,
+
+    /// Leave out removed addresses (default: false)
+    #[serde(default)]
+    pub added_only: bool,
+
+    /// Retry policy of this target; unset values are taken from `[retry]`
+    pub retry: Option<TargetRetrySection>,
+
+    /// Seconds allowed to establish a connection (default: from `[http]`)
+    pub connect_timeout: Option<u64>,
+
+    /// Seconds allowed for a whole request (default: from `[http]`)
+    pub request_timeout: Option<u64>,
+
+    /// Circuit breaker of this target; unset values are taken from
+    /// `[webhook.circuit_breaker]`
+    pub circuit_breaker: Option<CircuitBreakerSection>,
+}
+
+/// Retry policy of an additional target (`[webhook.targets.retry]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TargetRetrySection {
+    /// Maximum number of retry attempts
+    pub max_attempts: Option<u32>,
+
+    /// Initial retry delay in seconds
+    pub initial_delay: Option<u64>,
+
+    /// Maximum retry delay in seconds
+    pub max_delay: Option<u64>,
+
+    /// Backoff multiplier
+    pub multiplier: Option<f64>,
+}
+
+/// Response assertion (`webhook.expect_json`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExpectJsonSection {
+    /// `JSONPath` of the checked field, e.g. "$.status" (required)
+    pub path: Option<String>,
+
+    /// Value the field must hold (required)
+    pub equals: Option<serde_json::Value>,
+
+    /// Whether a mismatch is retried (default: false)
+    pub retry: Option<bool>,
+}
+
+/// Certificate tracking section (`[webhook.certificate]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CertificateSection {
+    /// Record fingerprints and warn when they change (default: true if pins are set)
+    pub track: Option<bool>,
+
+    /// SHA-256 fingerprints the webhook host must present one of
+    #[serde(default)]
+    pub pins: Vec<String>,
+}
+
+/// Circuit breaker section (`[webhook.circuit_breaker]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CircuitBreakerSection {
+    /// Consecutive failed deliveries opening the circuit
+    pub failures: Option<u32>,
+
+    /// Seconds the open circuit skips deliveries before probing
+    pub cooldown: Option<u64>,
+}
+
+/// Webhook URL discovery section (`[webhook.discovery]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DiscoverySection {
+    /// Record type: "txt" or "srv"
+    #[serde(rename = "type")]
+    pub record_type: Option<String>,
+
+    /// Record name to look up
+    pub name: Option<String>,
+
+    /// URL path appended to an SRV target (default: "/")
+    pub path: Option<String>,
+
+    /// Nameserver address, with optional port (default: system nameserver)
+    pub nameserver: Option<String>,
+
+    /// Seconds between lookups; 0 looks up once at startup
+    pub refresh: Option<u64>,
+}
+
+/// DNS provider preset section (`[webhook.provider]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProviderSection {
+    /// Provider: "duckdns", "porkbun", "gandi", "desec", "noip", "dynu", or
+    /// "freedns"
+    pub name: Option<String>,
+
+    /// Domain (`DuckDNS` subdomain, zone, or hostname)
+    pub domain: Option<String>,
+
+    /// Record name within the domain (Porkbun, Gandi and deSEC; default: the apex)
+    pub record: Option<String>,
+
+    /// API token (Porkbun: the API key; No-IP: the username; `FreeDNS`: the
+    /// update token)
+    pub token: Option<String>,
+
+    /// Secret API key (Porkbun) or password (No-IP)
+    pub secret: Option<String>,
+
+    /// Record TTL in seconds (Porkbun and Gandi)
+    pub ttl: Option<u32>,
+}
+
+/// Pre-request section (`[webhook.pre_request]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PreRequestSection {
+    /// Pre-request URL (required)
+    pub url: Option<String>,
+
+    /// HTTP method (default: POST)
+    pub method: Option<String>,
+
+    /// HTTP headers as key-value pairs
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+
+    /// Request body, sent as is
+    pub body: Option<String>,
+
+    /// Captured values by name: "header:<name>" or "json:<pointer>"
+    #[serde(default)]
+    pub capture: HashMap<String, String>,
+}
+
+/// `OAuth2` client-credentials section (`[webhook.oauth2]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OAuth2Section {
+    /// Token endpoint URL (required)
+    pub token_url: Option<String>,
+
+    /// Client identifier (required)
+    pub client_id: Option<String>,
+
+    /// Client secret (required)
+    pub client_secret: Option<String>,
+
+    /// Space-separated scopes requested
+    pub scope: Option<String>,
+
+    /// Audience requested, for providers such as Auth0
+    pub audience: Option<String>,
+
+    /// Client authentication: "basic" (default) or "body"
+    pub client_auth: Option<String>,
+}
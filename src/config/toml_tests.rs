@@ -124,6 +124,23 @@ mod parsing {
         assert_eq!(retry.multiplier, Some(1.5));
     }
 
+    #[test]
+    fn parse_retry_overrides() {
+        let toml = r#"
+            [retry]
+            body_template = "retry {{attempt}}"
+            query = "retry=true"
+        "#;
+
+        let config = TomlConfig::parse(toml).unwrap();
+
+        assert_eq!(
+            config.retry.body_template.as_deref(),
+            Some("retry {{attempt}}")
+        );
+        assert_eq!(config.retry.query.as_deref(), Some("retry=true"));
+    }
+
     #[test]
     fn parse_empty_config() {
         let toml = "";
@@ -136,6 +153,81 @@ mod parsing {
         assert!(config.filter.exclude_kinds.is_empty());
     }
 
+    #[test]
+    fn parse_audit_section() {
+        let toml = r#"
+            [audit]
+            file = "audit.jsonl"
+            allow_headers = ["Content-Type"]
+            allow_query = ["ip"]
+        "#;
+
+        let config = TomlConfig::parse(toml).unwrap();
+        assert_eq!(config.audit.file.as_deref(), Some("audit.jsonl"));
+        assert_eq!(config.audit.allow_headers, vec!["Content-Type"]);
+        assert_eq!(config.audit.allow_query, vec!["ip"]);
+    }
+
+    #[test]
+    fn audit_section_defaults_to_disabled() {
+        let config = TomlConfig::parse("").unwrap();
+
+        assert!(config.audit.file.is_none());
+        assert!(config.audit.allow_headers.is_empty());
+    }
+
+    #[test]
+    fn parse_http_section() {
+        let toml = r#"
+            [http]
+            user_agent = "my-router/1.0"
+            metadata_headers = false
+        "#;
+
+        let config = TomlConfig::parse(toml).unwrap();
+        assert_eq!(config.http.user_agent.as_deref(), Some("my-router/1.0"));
+        assert_eq!(config.http.metadata_headers, Some(false));
+    }
+
+    #[test]
+    fn parse_discovery_section() {
+        let toml = r#"
+            [webhook.discovery]
+            type = "srv"
+            name = "_ddns-a._tcp.example.com"
+            path = "/ddns"
+            refresh = 600
+        "#;
+
+        let config = TomlConfig::parse(toml).unwrap();
+        let discovery = config.webhook.discovery.unwrap();
+        assert_eq!(discovery.record_type.as_deref(), Some("srv"));
+        assert_eq!(discovery.name.as_deref(), Some("_ddns-a._tcp.example.com"));
+        assert_eq!(discovery.path.as_deref(), Some("/ddns"));
+        assert_eq!(discovery.nameserver, None);
+        assert_eq!(discovery.refresh, Some(600));
+    }
+
+    #[test]
+    fn parse_dns_tracking() {
+        let toml = r#"
+            [webhook]
+            dns_body_template = "{{#each dns_changes}}{{adapter}}{{/each}}"
+
+            [monitor]
+            track_dns = true
+        "#;
+
+        let config = TomlConfig::parse(toml).unwrap();
+        assert!(config.monitor.track_dns);
+        assert!(!config.monitor.track_hostname);
+        assert!(!config.monitor.track_link);
+        assert_eq!(
+            config.webhook.dns_body_template.as_deref(),
+            Some("{{#each dns_changes}}{{adapter}}{{/each}}")
+        );
+    }
+
     #[test]
     fn reject_unknown_fields() {
         let toml = r#"
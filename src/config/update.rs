@@ -0,0 +1,33 @@
+//! Resolution of the `[update]` section.
+
+use url::Url;
+
+use crate::update::{DEFAULT_RELEASE_FEED, PublicKey, UpdateSettings};
+
+use super::error::ConfigError;
+use super::toml::TomlConfig;
+
+/// Resolves self-update settings; `None` unless `enabled = true`.
+pub(super) fn resolve_update(
+    toml: Option<&TomlConfig>,
+) -> Result<Option<UpdateSettings>, ConfigError> {
+    let Some(section) = toml.map(|t| &t.update).filter(|s| s.enabled) else {
+        return Ok(None);
+    };
+    let invalid = |reason: String| ConfigError::InvalidUpdate(reason);
+
+    let public_key = section
+        .public_key
+        .as_deref()
+        .ok_or_else(|| invalid("public_key is required when enabled".to_string()))?;
+    let public_key = PublicKey::from_hex(public_key)
+        .ok_or_else(|| invalid("public_key must be 64 hex digits (Ed25519)".to_string()))?;
+
+    let feed = section.feed.as_deref().unwrap_or(DEFAULT_RELEASE_FEED);
+    let feed = Url::parse(feed).map_err(|e| invalid(format!("feed '{feed}': {e}")))?;
+    if feed.scheme() != "https" {
+        return Err(invalid(format!("feed '{feed}' must use https")));
+    }
+
+    Ok(Some(UpdateSettings { feed, public_key }))
+}
@@ -1,26 +1,29 @@
 //! Validated configuration after merging CLI and TOML sources.
 //!
 //! This module contains the final, validated configuration that is used
-//! by the application. All validation is performed during construction.
+//! by the application. All validation is performed during construction;
+//! each group of settings ([`WebhookSettings`], [`MonitorSettings`],
+//! [`HttpSettings`], [`OutputSettings`]) is resolved by its own module.
 
-use std::collections::HashSet;
 use std::fmt;
-use std::path::{Path, PathBuf};
-use std::time::Duration;
+use std::net::SocketAddr;
+use std::path::PathBuf;
 
-use handlebars::Handlebars;
-use http::header::{AUTHORIZATION, HeaderName, HeaderValue};
-use http::{HeaderMap, Method};
-use url::Url;
+use crate::logging::LogTarget;
+use crate::network::IpVersion;
+use crate::update::UpdateSettings;
 
-use crate::network::filter::{FilterChain, KindFilter, NameRegexFilter};
-use crate::network::{AdapterKind, IpVersion};
-use crate::webhook::RetryPolicy;
-
-use super::cli::{AdapterKindArg, Cli};
-use super::defaults;
+use super::cli::Cli;
+use super::discovery;
 use super::error::{ConfigError, field};
+use super::health::resolve_health;
+use super::locale::{Locale, resolve_locale};
+use super::log::resolve_log_target;
+use super::parse::{expand_tilde, parse_ip_version};
+use super::state::resolve_state_file;
 use super::toml::TomlConfig;
+use super::update::resolve_update;
+use super::{HttpSettings, MonitorSettings, OutputSettings, WebhookSettings};
 
 /// Fully validated configuration ready for use by the application.
 ///
@@ -32,43 +35,66 @@ use super::toml::TomlConfig;
 /// Use [`ValidatedConfig::from_raw`] to create from CLI args and optional TOML config.
 /// The function validates all inputs and returns errors for invalid configurations.
 #[derive(Debug)]
+#[allow(clippy::struct_excessive_bools)] // Mirrors the CLI's boolean flags
 pub struct ValidatedConfig {
     /// IP version to monitor (required)
     pub ip_version: IpVersion,
 
-    /// Webhook URL (required)
-    pub url: Url,
+    /// Main webhook, its targets and their delivery
+    pub webhook: WebhookSettings,
 
-    /// HTTP method for webhook requests
-    pub method: Method,
+    /// Adapter monitoring and polling
+    pub monitor: MonitorSettings,
 
-    /// HTTP headers for webhook requests
-    pub headers: HeaderMap,
+    /// HTTP client settings
+    pub http: HttpSettings,
 
-    /// Handlebars body template (optional)
-    pub body_template: Option<String>,
+    /// Other sinks, the audit log, run summary and history
+    pub outputs: OutputSettings,
 
-    /// Adapter filter configuration
-    pub filter: FilterChain,
+    /// Path to state file for detecting changes across restarts.
+    /// If `None`, state persistence is disabled.
+    pub state_file: Option<PathBuf>,
 
-    /// Polling interval
-    pub poll_interval: Duration,
+    /// Language of CLI errors and hints (`[log] locale` or `LANG`)
+    pub locale: Locale,
 
-    /// Whether to use polling only (no API events)
-    pub poll_only: bool,
+    /// Where log output goes (`[log] target`, default: stderr)
+    pub log_target: LogTarget,
 
-    /// Retry policy for failed webhook requests
-    pub retry_policy: RetryPolicy,
+    /// Address of the `/healthz` endpoint (`--health` or `health.bind`).
+    /// If `None`, no endpoint is served.
+    pub health: Option<SocketAddr>,
 
-    /// Path to state file for detecting changes across restarts.
-    /// If `None`, state persistence is disabled.
-    pub state_file: Option<PathBuf>,
+    /// Self-update settings (TOML-only).
+    /// If `None`, `ddns-a self-update` is disabled.
+    pub update: Option<UpdateSettings>,
+
+    /// Skip the lock keeping other instances from using the state file
+    /// (CLI-only)
+    pub no_lock: bool,
+
+    /// PID file removed on shutdown (CLI-only)
+    pub pid_file: Option<PathBuf>,
+
+    /// Detach from the terminal before starting (Unix only, CLI-only)
+    pub daemonize: bool,
+
+    /// Show the tray icon (Windows only, `tray` feature, CLI-only)
+    pub tray: bool,
 
     /// Dry-run mode (log changes without sending webhooks)
     pub dry_run: bool,
 
+    /// Directory payloads are recorded to instead of sent (CLI-only).
+    /// If `None`, payloads are sent (unless `dry_run`).
+    pub record_payloads: Option<PathBuf>,
+
     /// Verbose logging enabled
     pub verbose: bool,
+
+    /// Config file the TOML values were loaded from, if any
+    pub config_file: Option<PathBuf>,
 }
 
 impl fmt::Display for ValidatedConfig {
@@ -77,22 +103,33 @@ impl fmt::Display for ValidatedConfig {
             .state_file
             .as_ref()
             .map_or_else(|| "none".to_string(), |p| p.display().to_string());
+        let audit_str = self
+            .outputs
+            .audit
+            .as_ref()
+            .map_or_else(|| "none".to_string(), |a| a.path.display().to_string());
+        let url_str = self
+            .webhook
+            .url
+            .as_ref()
+            .map_or_else(|| "none".to_string(), ToString::to_string);
 
         write!(
             f,
             "Config {{ url: {}, ip_version: {}, method: {}, poll_interval: {}s, poll_only: {}, \
-             retry: {}x/{}s, state_file: {}, dry_run: {}, filters: inc={}/exc={} }}",
-            self.url,
+             retry: {}x/{}s, state_file: {}, audit: {}, dry_run: {}, filters: inc={}/exc={} }}",
+            url_str,
             self.ip_version,
-            self.method,
-            self.poll_interval.as_secs(),
-            self.poll_only,
-            self.retry_policy.max_attempts,
-            self.retry_policy.initial_delay.as_secs(),
+            self.webhook.method,
+            self.monitor.poll_interval.as_secs(),
+            self.monitor.poll_only,
+            self.webhook.retry_policy.max_attempts,
+            self.webhook.retry_policy.initial_delay.as_secs(),
             state_file_str,
+            audit_str,
             self.dry_run,
-            self.filter.include_count(),
-            self.filter.exclude_count(),
+            self.monitor.filter.include_count(),
+            self.monitor.filter.exclude_count(),
         )
     }
 }
@@ -114,52 +151,45 @@ impl ValidatedConfig {
         // Merge and validate IP version (required)
         let ip_version = Self::resolve_ip_version(cli, toml)?;
 
-        // Merge and validate URL (required)
-        let url = Self::resolve_url(cli, toml)?;
-
-        // Merge HTTP method (CLI default: POST)
-        let method = Self::resolve_method(cli, toml)?;
+        let http = HttpSettings::resolve(toml)?;
+        let monitor = MonitorSettings::resolve(cli, toml, ip_version)?;
 
-        // Merge headers
-        let headers = Self::resolve_headers(cli, toml)?;
-
-        // Merge and validate body template
-        let body_template = Self::resolve_body_template(cli, toml)?;
-
-        // Build adapter filter
-        let filter = Self::build_filter(cli, toml)?;
-
-        // Merge poll interval (CLI default: 60)
-        let poll_interval = Self::resolve_poll_interval(cli, toml)?;
+        // Resolve state file path (CLI takes precedence over TOML)
+        let state_file = resolve_state_file(cli, toml)?;
 
-        // Merge poll_only (CLI wins if true)
-        let poll_only = cli.poll_only || toml.is_some_and(|t| t.monitor.poll_only);
+        let webhook =
+            WebhookSettings::resolve(cli, toml, ip_version, &http, &monitor, state_file.is_some())?;
+        let outputs = OutputSettings::resolve(toml, ip_version, webhook.payload_format)?;
 
-        // Build retry policy
-        let retry_policy = Self::build_retry_policy(cli, toml)?;
-
-        // Resolve state file path (CLI takes precedence over TOML)
-        let state_file = Self::resolve_state_file(cli, toml);
+        Self::check_platform(cli)?;
 
         Ok(Self {
             ip_version,
-            url,
-            method,
-            headers,
-            body_template,
-            filter,
-            poll_interval,
-            poll_only,
-            retry_policy,
+            webhook,
+            monitor,
+            http,
+            outputs,
             state_file,
+            locale: resolve_locale(toml)?,
+            log_target: resolve_log_target(toml)?,
+            update: resolve_update(toml)?,
+            health: resolve_health(cli.health.as_deref(), toml)?,
+            no_lock: cli.no_lock,
+            pid_file: cli.pid_file.as_deref().map(expand_tilde),
+            daemonize: cli.daemon,
+            tray: cli.tray,
             dry_run: cli.dry_run,
+            record_payloads: cli.record_payloads.as_deref().map(expand_tilde),
             verbose: cli.verbose,
+            config_file: None,
         })
     }
 
     /// Loads and merges configuration from CLI and optional config file.
     ///
-    /// If `cli.config` is set, loads the TOML file from that path.
+    /// If `cli.config` is set, loads the TOML file from that path. Otherwise
+    /// the standard locations are searched unless `--no-config` is given;
+    /// the file used is recorded in [`config_file`](Self::config_file).
     ///
     /// # Errors
     ///
@@ -167,13 +197,12 @@ impl ValidatedConfig {
     /// - The config file cannot be read or parsed
     /// - The merged configuration is invalid
     pub fn load(cli: &Cli) -> Result<Self, ConfigError> {
-        let toml = if let Some(ref path) = cli.config {
-            Some(TomlConfig::load(path)?)
-        } else {
-            None
-        };
+        let path = discovery::config_path(cli);
+        let toml = path.as_deref().map(TomlConfig::load).transpose()?;
 
-        Self::from_raw(cli, toml.as_ref())
+        let mut config = Self::from_raw(cli, toml.as_ref())?;
+        config.config_file = path;
+        Ok(config)
     }
 
     fn resolve_ip_version(cli: &Cli, toml: Option<&TomlConfig>) -> Result<IpVersion, ConfigError> {
@@ -195,443 +224,20 @@ impl ValidatedConfig {
         ))
     }
 
-    fn resolve_url(cli: &Cli, toml: Option<&TomlConfig>) -> Result<Url, ConfigError> {
-        // CLI takes precedence
-        let url_str = cli
-            .url
-            .as_deref()
-            .or_else(|| toml.and_then(|t| t.webhook.url.as_deref()))
-            .ok_or_else(|| {
-                ConfigError::missing(field::URL, "Use --url or set webhook.url in config file")
-            })?;
-
-        Url::parse(url_str).map_err(|e| ConfigError::InvalidUrl {
-            url: url_str.to_string(),
-            reason: e.to_string(),
-        })
-    }
-
-    fn resolve_method(cli: &Cli, toml: Option<&TomlConfig>) -> Result<Method, ConfigError> {
-        // Priority: CLI explicit > TOML > default
-        let method_str = cli
-            .method
-            .as_deref()
-            .or_else(|| toml.and_then(|t| t.webhook.method.as_deref()))
-            .unwrap_or(defaults::METHOD);
-
-        method_str
-            .parse::<Method>()
-            .map_err(|_| ConfigError::InvalidMethod(method_str.to_string()))
-    }
-
-    fn resolve_headers(cli: &Cli, toml: Option<&TomlConfig>) -> Result<HeaderMap, ConfigError> {
-        let mut headers = HeaderMap::new();
-
-        // Add TOML headers first (CLI can override)
-        if let Some(toml) = toml {
-            for (name, value) in &toml.webhook.headers {
-                let header_name = parse_header_name(name)?;
-                let header_value = parse_header_value(name, value)?;
-                headers.insert(header_name, header_value);
-            }
-        }
-
-        // Add CLI headers (override TOML)
-        for header_str in &cli.headers {
-            let (name, value) = parse_header_string(header_str)?;
-            let header_name = parse_header_name(&name)?;
-            let header_value = parse_header_value(&name, &value)?;
-            headers.insert(header_name, header_value);
-        }
-
-        // Handle bearer token (CLI wins, then TOML)
-        let bearer = cli
-            .bearer
-            .as_deref()
-            .or_else(|| toml.and_then(|t| t.webhook.bearer.as_deref()));
-
-        if let Some(token) = bearer {
-            let auth_value = format!("Bearer {token}");
-            let header_value = parse_header_value("Authorization", &auth_value)?;
-            headers.insert(AUTHORIZATION, header_value);
-        }
-
-        Ok(headers)
-    }
-
-    fn resolve_body_template(
-        cli: &Cli,
-        toml: Option<&TomlConfig>,
-    ) -> Result<Option<String>, ConfigError> {
-        let template = cli
-            .body_template
-            .clone()
-            .or_else(|| toml.and_then(|t| t.webhook.body_template.clone()));
-
-        // Validate Handlebars syntax if template is provided
-        if let Some(ref tmpl) = template {
-            Self::validate_template(tmpl)?;
-        }
-
-        Ok(template)
-    }
-
-    fn validate_template(template: &str) -> Result<(), ConfigError> {
-        let hbs = Handlebars::new();
-        // Compile-check only; render with empty context to validate syntax
-        hbs.render_template(template, &serde_json::json!({}))
-            .map_err(|e| ConfigError::InvalidTemplate {
-                reason: e.to_string(),
-            })?;
-        Ok(())
-    }
-
-    fn build_filter(cli: &Cli, toml: Option<&TomlConfig>) -> Result<FilterChain, ConfigError> {
-        let mut chain = FilterChain::new();
-
-        // Collect all kinds from CLI and TOML (CLI replaces TOML)
-        let include_kinds: HashSet<AdapterKind> = Self::collect_kinds(
-            &cli.include_kinds,
-            toml.map(|t| &t.filter.include_kinds),
-            !cli.include_kinds.is_empty(),
-        )?;
-        let exclude_kinds: HashSet<AdapterKind> = Self::collect_kinds(
-            &cli.exclude_kinds,
-            toml.map(|t| &t.filter.exclude_kinds),
-            !cli.exclude_kinds.is_empty(),
-        )?;
-
-        // Default: exclude loopback UNLESS explicitly included
-        if !include_kinds.contains(&AdapterKind::Loopback) {
-            chain = chain.exclude(KindFilter::new([AdapterKind::Loopback]));
-        }
-
-        // Add kind excludes
-        if !exclude_kinds.is_empty() {
-            chain = chain.exclude(KindFilter::new(exclude_kinds));
+    /// Rejects CLI options this platform or build does not support.
+    const fn check_platform(cli: &Cli) -> Result<(), ConfigError> {
+        if cli.daemon && !cfg!(unix) {
+            return Err(ConfigError::UnsupportedPlatform { option: "--daemon" });
         }
-
-        // Add kind includes
-        if !include_kinds.is_empty() {
-            chain = chain.include(KindFilter::new(include_kinds));
-        }
-
-        // Collect name patterns (CLI replaces TOML)
-        let exclude_patterns = if cli.exclude_adapters.is_empty() {
-            toml.map_or(&[][..], |t| t.filter.exclude.as_slice())
-        } else {
-            cli.exclude_adapters.as_slice()
-        };
-
-        let include_patterns = if cli.include_adapters.is_empty() {
-            toml.map_or(&[][..], |t| t.filter.include.as_slice())
-        } else {
-            cli.include_adapters.as_slice()
-        };
-
-        // Add name excludes
-        for pattern in exclude_patterns {
-            let regex_filter =
-                NameRegexFilter::new(pattern).map_err(|e| ConfigError::InvalidRegex {
-                    pattern: pattern.clone(),
-                    source: e,
-                })?;
-            chain = chain.exclude(regex_filter);
-        }
-
-        // Add name includes
-        for pattern in include_patterns {
-            let regex_filter =
-                NameRegexFilter::new(pattern).map_err(|e| ConfigError::InvalidRegex {
-                    pattern: pattern.clone(),
-                    source: e,
-                })?;
-            chain = chain.include(regex_filter);
+        if cli.tray && !cfg!(windows) {
+            return Err(ConfigError::UnsupportedPlatform { option: "--tray" });
         }
-
-        Ok(chain)
-    }
-
-    /// Collects adapter kinds from CLI and/or TOML.
-    ///
-    /// If `cli_replaces` is true, only CLI kinds are used; otherwise TOML kinds are used.
-    fn collect_kinds(
-        cli_kinds: &[AdapterKindArg],
-        toml_kinds: Option<&Vec<String>>,
-        cli_replaces: bool,
-    ) -> Result<HashSet<AdapterKind>, ConfigError> {
-        let mut kinds = HashSet::new();
-
-        if cli_replaces || toml_kinds.is_none() {
-            // Use CLI kinds
-            for kind in cli_kinds {
-                kinds.insert((*kind).into());
-            }
-        } else if let Some(toml_list) = toml_kinds {
-            // Use TOML kinds
-            for kind_str in toml_list {
-                let kind = parse_adapter_kind(kind_str)?;
-                kinds.insert(kind);
-            }
-        }
-
-        Ok(kinds)
-    }
-
-    fn resolve_poll_interval(
-        cli: &Cli,
-        toml: Option<&TomlConfig>,
-    ) -> Result<Duration, ConfigError> {
-        // Priority: CLI explicit > TOML > default
-        let seconds = cli
-            .poll_interval
-            .or_else(|| toml.and_then(|t| t.monitor.poll_interval))
-            .unwrap_or(defaults::POLL_INTERVAL_SECS);
-
-        if seconds == 0 {
-            return Err(ConfigError::InvalidDuration {
-                field: "poll_interval",
-                reason: "must be greater than 0".to_string(),
+        if cli.tray && !cfg!(feature = "tray") {
+            return Err(ConfigError::FeatureDisabled {
+                feature: "tray",
+                option: "--tray",
             });
         }
-
-        Ok(Duration::from_secs(seconds))
-    }
-
-    fn build_retry_policy(
-        cli: &Cli,
-        toml: Option<&TomlConfig>,
-    ) -> Result<RetryPolicy, ConfigError> {
-        let retry = toml.map(|t| &t.retry);
-
-        // Priority: CLI explicit > TOML > default
-        let max_attempts = cli
-            .retry_max
-            .or_else(|| retry.and_then(|r| r.max_attempts))
-            .unwrap_or(defaults::RETRY_MAX_ATTEMPTS);
-
-        let initial_delay_secs = cli
-            .retry_delay
-            .or_else(|| retry.and_then(|r| r.initial_delay))
-            .unwrap_or(defaults::RETRY_INITIAL_DELAY_SECS);
-
-        let max_delay_secs = retry
-            .and_then(|r| r.max_delay)
-            .unwrap_or(defaults::RETRY_MAX_DELAY_SECS);
-
-        let multiplier = retry
-            .and_then(|r| r.multiplier)
-            .unwrap_or(defaults::RETRY_MULTIPLIER);
-
-        if max_attempts == 0 {
-            return Err(ConfigError::InvalidRetry(
-                "max_attempts must be greater than 0".to_string(),
-            ));
-        }
-
-        if initial_delay_secs == 0 {
-            return Err(ConfigError::InvalidRetry(
-                "initial_delay must be greater than 0".to_string(),
-            ));
-        }
-
-        if multiplier <= 0.0 || !multiplier.is_finite() {
-            return Err(ConfigError::InvalidRetry(
-                "multiplier must be a positive finite number".to_string(),
-            ));
-        }
-
-        if max_delay_secs < initial_delay_secs {
-            return Err(ConfigError::InvalidRetry(format!(
-                "max_delay ({max_delay_secs}s) must be >= initial_delay ({initial_delay_secs}s)"
-            )));
-        }
-
-        Ok(RetryPolicy::new()
-            .with_max_attempts(max_attempts)
-            .with_initial_delay(Duration::from_secs(initial_delay_secs))
-            .with_max_delay(Duration::from_secs(max_delay_secs))
-            .with_multiplier(multiplier))
-    }
-
-    fn resolve_state_file(cli: &Cli, toml: Option<&TomlConfig>) -> Option<PathBuf> {
-        // CLI takes precedence
-        if let Some(ref path) = cli.state_file {
-            return Some(expand_tilde(path));
-        }
-
-        // Fall back to TOML
-        toml.and_then(|t| {
-            t.monitor
-                .state_file
-                .as_ref()
-                .map(|s| expand_tilde(Path::new(s)))
-        })
-    }
-}
-
-/// Expands tilde (`~`) at the start of a path to the user's home directory.
-///
-/// - `~/foo` → `<home>/foo`
-/// - `~` → `<home>`
-/// - Paths not starting with `~` are returned unchanged.
-fn expand_tilde(path: &Path) -> PathBuf {
-    let path_str = path.to_string_lossy();
-
-    // Check if path starts with ~ (tilde)
-    if !path_str.starts_with('~') {
-        return path.to_path_buf();
-    }
-
-    // Get home directory
-    let Some(home) = dirs::home_dir() else {
-        // Cannot determine home directory - return path unchanged
-        tracing::warn!("Cannot expand ~: home directory not found");
-        return path.to_path_buf();
-    };
-
-    // Handle bare ~ or ~/...
-    if path_str == "~" {
-        return home;
-    }
-
-    // Handle ~/path or ~\path (Windows)
-    if path_str.starts_with("~/") || path_str.starts_with("~\\") {
-        return home.join(&path_str[2..]);
-    }
-
-    // ~username style is not supported - return unchanged
-    path.to_path_buf()
-}
-
-/// Writes the default configuration template to a file.
-///
-/// # Errors
-///
-/// Returns an error if the file cannot be written.
-pub fn write_default_config(path: &Path) -> Result<(), ConfigError> {
-    let template = super::toml::default_config_template();
-    std::fs::write(path, template).map_err(|e| ConfigError::FileWrite {
-        path: path.to_path_buf(),
-        source: e,
-    })
-}
-
-// Helper functions
-
-fn parse_ip_version(s: &str) -> Result<IpVersion, ConfigError> {
-    match s.to_lowercase().as_str() {
-        "ipv4" | "v4" | "4" => Ok(IpVersion::V4),
-        "ipv6" | "v6" | "6" => Ok(IpVersion::V6),
-        "both" | "all" | "dual" => Ok(IpVersion::Both),
-        _ => Err(ConfigError::InvalidIpVersion {
-            value: s.to_string(),
-        }),
-    }
-}
-
-fn parse_adapter_kind(s: &str) -> Result<AdapterKind, ConfigError> {
-    match s.to_lowercase().as_str() {
-        "ethernet" => Ok(AdapterKind::Ethernet),
-        "wireless" => Ok(AdapterKind::Wireless),
-        "virtual" => Ok(AdapterKind::Virtual),
-        "loopback" => Ok(AdapterKind::Loopback),
-        _ => Err(ConfigError::InvalidAdapterKind {
-            value: s.to_string(),
-        }),
-    }
-}
-
-fn parse_header_string(s: &str) -> Result<(String, String), ConfigError> {
-    // Try "Key=Value" format first
-    if let Some((name, value)) = s.split_once('=') {
-        return Ok((name.trim().to_string(), value.trim().to_string()));
-    }
-
-    // Try "Key: Value" format
-    if let Some((name, value)) = s.split_once(':') {
-        return Ok((name.trim().to_string(), value.trim().to_string()));
-    }
-
-    Err(ConfigError::InvalidHeader {
-        value: s.to_string(),
-    })
-}
-
-fn parse_header_name(name: &str) -> Result<HeaderName, ConfigError> {
-    name.parse::<HeaderName>()
-        .map_err(|e| ConfigError::InvalidHeaderName {
-            name: name.to_string(),
-            reason: e.to_string(),
-        })
-}
-
-fn parse_header_value(name: &str, value: &str) -> Result<HeaderValue, ConfigError> {
-    HeaderValue::from_str(value).map_err(|e| ConfigError::InvalidHeaderValue {
-        name: name.to_string(),
-        reason: e.to_string(),
-    })
-}
-
-#[cfg(test)]
-mod tilde_tests {
-    use std::path::Path;
-
-    use super::expand_tilde;
-
-    #[test]
-    fn tilde_alone_expands_to_home() {
-        let result = expand_tilde(Path::new("~"));
-        let expected = dirs::home_dir().expect("home dir should exist");
-        assert_eq!(result, expected);
-    }
-
-    #[test]
-    fn tilde_slash_prefix_expands() {
-        let result = expand_tilde(Path::new("~/.ddns-a/state.json"));
-        let home = dirs::home_dir().expect("home dir should exist");
-        assert_eq!(result, home.join(".ddns-a/state.json"));
-    }
-
-    #[test]
-    fn tilde_backslash_prefix_expands() {
-        // Windows-style path separator
-        let result = expand_tilde(Path::new("~\\.ddns-a\\state.json"));
-        let home = dirs::home_dir().expect("home dir should exist");
-        assert_eq!(result, home.join(".ddns-a\\state.json"));
-    }
-
-    #[test]
-    fn absolute_path_unchanged() {
-        #[cfg(windows)]
-        let path = Path::new("C:\\Users\\test\\state.json");
-        #[cfg(not(windows))]
-        let path = Path::new("/home/test/state.json");
-
-        let result = expand_tilde(path);
-        assert_eq!(result, path);
-    }
-
-    #[test]
-    fn relative_path_unchanged() {
-        let path = Path::new("./state.json");
-        let result = expand_tilde(path);
-        assert_eq!(result, path);
-    }
-
-    #[test]
-    fn tilde_in_middle_unchanged() {
-        // Tilde not at start should not expand
-        let path = Path::new("foo/~/bar");
-        let result = expand_tilde(path);
-        assert_eq!(result, path);
-    }
-
-    #[test]
-    fn tilde_username_style_unchanged() {
-        // ~username style is not supported
-        let path = Path::new("~otheruser/file");
-        let result = expand_tilde(path);
-        assert_eq!(result, path);
+        Ok(())
     }
 }
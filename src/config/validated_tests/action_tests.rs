@@ -0,0 +1,68 @@
+//! Tests for the `[action.exec]` section.
+
+use std::time::Duration;
+
+use super::*;
+use crate::webhook::{DEFAULT_EXEC_TIMEOUT, ExecInput};
+
+fn exec(section: Option<&str>) -> Result<ValidatedConfig, ConfigError> {
+    let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+    let toml = section.map(|s| toml(&format!("[action.exec]\n{s}")));
+    ValidatedConfig::from_raw(&cli, toml.as_ref())
+}
+
+fn invalid_reason(section: &str) -> String {
+    match exec(Some(section)) {
+        Err(ConfigError::InvalidExec(reason)) => reason,
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn off_by_default() {
+    assert!(exec(None).unwrap().outputs.exec.is_none());
+}
+
+#[test]
+fn splits_program_and_arguments() {
+    let config = exec(Some(r#"command = ["nsupdate", "-k", "/etc/ddns.key"]"#)).unwrap();
+
+    let action = config.outputs.exec.unwrap();
+    assert_eq!(action.program(), "nsupdate");
+    assert_eq!(action.args(), ["-k", "/etc/ddns.key"]);
+    assert_eq!(action.timeout(), DEFAULT_EXEC_TIMEOUT);
+    assert_eq!(action.input(), ExecInput::Env);
+}
+
+#[test]
+fn reads_timeout_and_input() {
+    let config = exec(Some(
+        "command = [\"./hook.sh\"]\ntimeout = 5\ninput = \"STDIN\"",
+    ))
+    .unwrap();
+
+    let action = config.outputs.exec.unwrap();
+    assert_eq!(action.timeout(), Duration::from_secs(5));
+    assert_eq!(action.input(), ExecInput::Stdin);
+}
+
+#[test]
+fn command_is_required() {
+    assert_eq!(invalid_reason(""), "command is required");
+    assert_eq!(
+        invalid_reason("command = [\" \"]"),
+        "command must start with a program"
+    );
+}
+
+#[test]
+fn invalid_settings_are_rejected() {
+    assert_eq!(
+        invalid_reason("command = [\"true\"]\ntimeout = 0"),
+        "timeout must be at least 1 second"
+    );
+    assert_eq!(
+        invalid_reason("command = [\"true\"]\ninput = \"file\""),
+        "input 'file': expected env or stdin"
+    );
+}
@@ -0,0 +1,62 @@
+//! Tests for the webhook circuit breaker (`[webhook.circuit_breaker]`).
+
+use std::time::Duration;
+
+use super::*;
+use crate::webhook::CircuitPolicy;
+
+fn load(section: &str) -> Result<ValidatedConfig, ConfigError> {
+    let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+    let toml = toml(&format!("[webhook.circuit_breaker]\n{section}"));
+    ValidatedConfig::from_raw(&cli, Some(&toml))
+}
+
+#[test]
+fn disabled_without_section() {
+    let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+    let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+    assert_eq!(config.webhook.circuit_breaker, None);
+}
+
+#[test]
+fn empty_section_uses_default_policy() {
+    let config = load("").unwrap();
+
+    assert_eq!(
+        config.webhook.circuit_breaker,
+        Some(CircuitPolicy::default())
+    );
+}
+
+#[test]
+fn policy_is_read() {
+    let config = load("failures = 3\ncooldown = 60").unwrap();
+
+    assert_eq!(
+        config.webhook.circuit_breaker,
+        Some(CircuitPolicy {
+            failure_threshold: 3,
+            cooldown: Duration::from_secs(60),
+        })
+    );
+}
+
+#[test]
+fn zero_values_are_rejected() {
+    for section in ["failures = 0", "cooldown = 0"] {
+        let result = load(section);
+
+        assert!(
+            matches!(result, Err(ConfigError::InvalidCircuitBreaker(_))),
+            "{section}"
+        );
+    }
+}
+
+#[test]
+fn unknown_fields_are_rejected() {
+    let result = TomlConfig::parse("[webhook.circuit_breaker]\nthreshold = 3");
+
+    assert!(matches!(result, Err(ConfigError::TomlParse(_))));
+}
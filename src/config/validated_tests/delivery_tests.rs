@@ -0,0 +1,550 @@
+//! Tests for webhook delivery configuration: connectivity check, payload encoding and
+//! format, TTL, echo check, delivery mode, response assertions, certificate tracking,
+//! endpoint discovery.
+
+use super::*;
+
+mod connectivity_check {
+    use super::*;
+    use crate::webhook::ConnectivityCheck;
+
+    #[test]
+    fn disabled_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv6"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(
+            config.webhook.connectivity_check,
+            ConnectivityCheck::Disabled
+        );
+    }
+
+    #[test]
+    fn family_from_toml() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv6"]);
+        let toml = toml(
+            r#"
+            [webhook]
+            connectivity_check = "family"
+        "#,
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(
+            config.webhook.connectivity_check,
+            ConnectivityCheck::AddressFamily
+        );
+    }
+
+    #[test]
+    fn none_is_case_insensitive() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv6"]);
+        let toml = toml(
+            r#"
+            [webhook]
+            connectivity_check = "None"
+        "#,
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(
+            config.webhook.connectivity_check,
+            ConnectivityCheck::Disabled
+        );
+    }
+
+    #[test]
+    fn invalid_value_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv6"]);
+        let toml = toml(
+            r#"
+            [webhook]
+            connectivity_check = "ping"
+        "#,
+        );
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidConnectivityCheck { value }) if value == "ping"
+        ));
+    }
+}
+
+mod payload_encoding {
+    use super::*;
+    use crate::webhook::PayloadEncoding;
+
+    #[test]
+    fn unset_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.webhook.payload_encoding, None);
+    }
+
+    #[test]
+    fn parses_each_encoding() {
+        let cases = [
+            ("json", PayloadEncoding::Json),
+            ("YAML", PayloadEncoding::Yaml),
+            ("yml", PayloadEncoding::Yaml),
+            ("cbor", PayloadEncoding::Cbor),
+        ];
+        for (value, expected) in cases {
+            let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+            let toml = toml(&format!("[webhook]\npayload_encoding = \"{value}\"\n"));
+            let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+            assert_eq!(config.webhook.payload_encoding, Some(expected), "{value}");
+        }
+    }
+
+    #[test]
+    fn invalid_value_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [webhook]
+            payload_encoding = "xml"
+        "#,
+        );
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidPayloadEncoding { value }) if value == "xml"
+        ));
+    }
+}
+
+mod payload_format {
+    use super::*;
+    use crate::webhook::PayloadFormat;
+
+    #[test]
+    fn json_v1_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.webhook.payload_format, PayloadFormat::JsonV1);
+    }
+
+    #[test]
+    fn parses_each_format() {
+        for (value, expected) in [
+            ("json-v1", PayloadFormat::JsonV1),
+            ("None", PayloadFormat::None),
+        ] {
+            let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+            let toml = toml(&format!("[webhook]\npayload_format = \"{value}\"\n"));
+            let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+            assert_eq!(config.webhook.payload_format, expected, "{value}");
+        }
+    }
+
+    #[test]
+    fn invalid_value_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [webhook]
+            payload_format = "json-v2"
+        "#,
+        );
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidPayloadFormat { value }) if value == "json-v2"
+        ));
+    }
+}
+
+mod ttl {
+    use super::*;
+
+    #[test]
+    fn unset_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.webhook.ttl, None);
+    }
+
+    #[test]
+    fn parses_seconds() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml("[webhook]\nttl = 300\n");
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(config.webhook.ttl, Some(300));
+    }
+
+    #[test]
+    fn zero_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml("[webhook]\nttl = 0\n");
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidDuration { field, .. }) if field == "webhook.ttl"
+        ));
+    }
+}
+
+mod echo_check {
+    use super::*;
+    use crate::webhook::EchoCheck;
+
+    #[test]
+    fn disabled_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.webhook.echo_check, EchoCheck::Disabled);
+    }
+
+    #[test]
+    fn parses_each_mode() {
+        let cases = [
+            ("off", EchoCheck::Disabled),
+            ("Header", EchoCheck::Header),
+            ("body", EchoCheck::Body),
+        ];
+        for (value, expected) in cases {
+            let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+            let toml = toml(&format!("[webhook]\necho_check = \"{value}\"\n"));
+            let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+            assert_eq!(config.webhook.echo_check, expected, "{value}");
+        }
+    }
+
+    #[test]
+    fn invalid_value_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml("[webhook]\necho_check = \"status\"\n");
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidEchoCheck { value }) if value == "status"
+        ));
+    }
+}
+
+mod delivery_mode {
+    use super::*;
+    use crate::webhook::DeliveryMode;
+
+    #[test]
+    fn batched_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.webhook.delivery_mode, DeliveryMode::Batched);
+    }
+
+    #[test]
+    fn parses_each_mode() {
+        let cases = [
+            ("batched", DeliveryMode::Batched),
+            ("per_change", DeliveryMode::PerChange),
+            ("Per-Change", DeliveryMode::PerChange),
+        ];
+        for (value, expected) in cases {
+            let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+            let toml = toml(&format!("[webhook]\ndelivery = \"{value}\"\n"));
+            let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+            assert_eq!(config.webhook.delivery_mode, expected, "{value}");
+        }
+    }
+
+    #[test]
+    fn invalid_value_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml("[webhook]\ndelivery = \"single\"\n");
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidDeliveryMode { value }) if value == "single"
+        ));
+    }
+}
+
+mod expect_json {
+    use super::*;
+    use crate::webhook::{JsonAssertion, JsonPath};
+
+    fn assertion(value: &str) -> Result<ValidatedConfig, ConfigError> {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(&format!("[webhook]\nexpect_json = {value}\n"));
+        ValidatedConfig::from_raw(&cli, Some(&toml))
+    }
+
+    fn path(s: &str) -> JsonPath {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn absent_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert!(config.webhook.json_assertion.is_none());
+    }
+
+    #[test]
+    fn parses_path_value_and_retry() {
+        let config = assertion(r#"{ path = "$.status", equals = "ok" }"#).unwrap();
+        assert_eq!(
+            config.webhook.json_assertion,
+            Some(JsonAssertion::new(path("$.status"), "ok".into()))
+        );
+
+        let config =
+            assertion(r#"{ path = "$.result[0].code", equals = 0, retry = true }"#).unwrap();
+        assert_eq!(
+            config.webhook.json_assertion,
+            Some(JsonAssertion::new(path("$.result[0].code"), 0.into()).retried(true))
+        );
+    }
+
+    #[test]
+    fn invalid_settings_return_error() {
+        let invalid = [
+            r#"{ equals = "ok" }"#,
+            r#"{ path = "$.status" }"#,
+            r#"{ path = "status", equals = "ok" }"#,
+            r#"{ path = "$.status", equals = ["ok"] }"#,
+        ];
+        for value in invalid {
+            assert!(
+                matches!(assertion(value), Err(ConfigError::InvalidExpectJson(_))),
+                "{value}"
+            );
+        }
+    }
+}
+
+mod expect_body_regex {
+    use super::*;
+
+    fn assertion(lines: &str) -> Result<ValidatedConfig, ConfigError> {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(&format!("[webhook]\n{lines}\n"));
+        ValidatedConfig::from_raw(&cli, Some(&toml))
+    }
+
+    #[test]
+    fn absent_by_default() {
+        let config = assertion("").unwrap();
+
+        assert!(config.webhook.body_assertion.is_none());
+    }
+
+    #[test]
+    fn parses_pattern_and_retry() {
+        let config = assertion(r#"expect_body_regex = "good|nochg""#).unwrap();
+        let body = config.webhook.body_assertion.unwrap();
+        assert_eq!(body.pattern(), "good|nochg");
+        assert!(!body.is_retryable());
+
+        let config = assertion("expect_body_regex = \"^OK\"\nexpect_body_retry = true").unwrap();
+        assert!(config.webhook.body_assertion.unwrap().is_retryable());
+    }
+
+    #[test]
+    fn invalid_pattern_returns_error() {
+        let result = assertion(r#"expect_body_regex = "good(""#);
+
+        assert!(
+            matches!(result, Err(ConfigError::InvalidRegex { ref pattern, .. }) if pattern == "good("),
+            "{result:?}"
+        );
+    }
+}
+
+mod certificate {
+    use super::*;
+    use crate::webhook::Fingerprint;
+
+    const PIN: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    fn pins(url: &str, section: &str) -> Result<Option<Vec<Fingerprint>>, ConfigError> {
+        let cli = cli(&["--url", url, "--ip-version", "ipv4"]);
+        let toml = toml(&format!("[webhook.certificate]\n{section}"));
+        ValidatedConfig::from_raw(&cli, Some(&toml)).map(|c| c.webhook.certificate_pins)
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert!(config.webhook.certificate_pins.is_none());
+        assert_eq!(pins("https://example.com", "").unwrap(), None);
+    }
+
+    #[test]
+    fn track_without_pins_records_only() {
+        assert_eq!(
+            pins("https://example.com", "track = true").unwrap(),
+            Some(Vec::new())
+        );
+    }
+
+    #[test]
+    fn pins_imply_tracking() {
+        let found = pins("https://example.com", &format!("pins = [\"{PIN}\"]"));
+
+        assert_eq!(found.unwrap(), Some(vec![PIN.parse().unwrap()]));
+    }
+
+    #[test]
+    fn invalid_settings_return_error() {
+        let invalid = [
+            ("https://example.com", "pins = [\"abc\"]".to_string()),
+            (
+                "https://example.com",
+                format!("track = false\npins = [\"{PIN}\"]"),
+            ),
+            ("http://example.com", "track = true".to_string()),
+        ];
+        for (url, section) in invalid {
+            assert!(
+                matches!(pins(url, &section), Err(ConfigError::InvalidCertificate(_))),
+                "{section}"
+            );
+        }
+    }
+}
+
+mod endpoint_discovery {
+    use super::*;
+    use crate::webhook::{EndpointDiscovery, EndpointRecord};
+    use std::time::Duration;
+
+    fn discovery(section: &str) -> Result<Option<EndpointDiscovery>, ConfigError> {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(&format!("[webhook.discovery]\n{section}"));
+        ValidatedConfig::from_raw(&cli, Some(&toml)).map(|c| c.webhook.endpoint_discovery)
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert!(config.webhook.endpoint_discovery.is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn txt_with_defaults() {
+        let found = discovery(
+            r#"
+            type = "txt"
+            name = "_ddns-a.example.com"
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            found,
+            Some(EndpointDiscovery {
+                record: EndpointRecord::Txt {
+                    name: "_ddns-a.example.com".to_string()
+                },
+                nameserver: None,
+                refresh: Duration::from_secs(3600),
+            })
+        );
+    }
+
+    #[cfg(not(unix))]
+    #[test]
+    fn nameserver_required_without_resolv_conf() {
+        let result = discovery(
+            r#"
+            type = "txt"
+            name = "_ddns-a.example.com"
+        "#,
+        );
+
+        assert!(matches!(result, Err(ConfigError::InvalidDiscovery(_))));
+    }
+
+    #[test]
+    fn srv_with_all_options() {
+        let found = discovery(
+            r#"
+            type = "SRV"
+            name = "_ddns-a._tcp.example.com"
+            path = "/ddns"
+            nameserver = "[2001:db8::53]:5353"
+            refresh = 0
+        "#,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(
+            found.record,
+            EndpointRecord::Srv {
+                name: "_ddns-a._tcp.example.com".to_string(),
+                path: "/ddns".to_string(),
+            }
+        );
+        assert_eq!(
+            found.nameserver,
+            Some("[2001:db8::53]:5353".parse().unwrap())
+        );
+        assert_eq!(found.refresh, Duration::ZERO);
+    }
+
+    #[test]
+    fn srv_path_defaults_to_root() {
+        let found = discovery(
+            r#"
+            type = "srv"
+            name = "_ddns-a._tcp.example.com"
+            nameserver = "192.0.2.53"
+        "#,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert!(matches!(found.record, EndpointRecord::Srv { ref path, .. } if path == "/"));
+        assert_eq!(found.nameserver, Some("192.0.2.53:53".parse().unwrap()));
+    }
+
+    #[test]
+    fn invalid_settings_return_error() {
+        for section in [
+            r#"name = "example.com""#,
+            r#"type = "txt""#,
+            r#"type = "a"
+               name = "example.com""#,
+            r#"type = "txt"
+               name = "example.com"
+               path = "/x""#,
+            r#"type = "srv"
+               name = "example.com"
+               path = "x""#,
+            r#"type = "txt"
+               name = "example.com"
+               nameserver = "dns.example.com""#,
+        ] {
+            assert!(
+                matches!(discovery(section), Err(ConfigError::InvalidDiscovery(_))),
+                "accepted: {section}"
+            );
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Tests for the `[email]` section.
+
+use std::time::Duration;
+
+use super::*;
+use crate::webhook::DEFAULT_EMAIL_TIMEOUT;
+
+const SERVER: &str = "smtp_server = \"smtp://smtp.example.com\"\n";
+const ADDRESSES: &str = "from = \"ddns@example.com\"\nto = [\"admin@example.com\"]\n";
+
+fn email(section: Option<&str>) -> Result<ValidatedConfig, ConfigError> {
+    let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+    let toml = section.map(|s| toml(&format!("[email]\n{s}")));
+    ValidatedConfig::from_raw(&cli, toml.as_ref())
+}
+
+fn invalid_reason(section: &str) -> String {
+    match email(Some(section)) {
+        Err(ConfigError::InvalidEmail(reason)) => reason,
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+/// The reason `extra` is rejected beside a valid server and addresses.
+fn invalid_with(extra: &str) -> String {
+    invalid_reason(&format!("{SERVER}{ADDRESSES}{extra}"))
+}
+
+#[test]
+fn off_by_default() {
+    assert!(email(None).unwrap().outputs.email.is_none());
+}
+
+#[test]
+fn reads_server_and_addresses() {
+    let config = email(Some(&format!("{SERVER}{ADDRESSES}"))).unwrap();
+
+    let mailer = config.outputs.email.unwrap();
+    assert_eq!(mailer.server().as_str(), "smtp://smtp.example.com");
+    assert_eq!(mailer.from(), "ddns@example.com");
+    assert_eq!(mailer.to(), ["admin@example.com"]);
+    assert!(mailer.starttls());
+    assert_eq!(mailer.timeout(), DEFAULT_EMAIL_TIMEOUT);
+}
+
+#[test]
+fn reads_delivery_settings() {
+    let config = email(Some(&format!(
+        "{SERVER}{ADDRESSES}starttls = false\ntimeout = 5"
+    )))
+    .unwrap();
+
+    let mailer = config.outputs.email.unwrap();
+    assert!(!mailer.starttls());
+    assert_eq!(mailer.timeout(), Duration::from_secs(5));
+}
+
+#[test]
+fn server_and_addresses_are_required() {
+    assert_eq!(invalid_reason(ADDRESSES), "smtp_server is required");
+    assert_eq!(
+        invalid_reason(&format!("{SERVER}to = [\"a@example.com\"]")),
+        "from is required"
+    );
+    assert_eq!(
+        invalid_reason(&format!("{SERVER}from = \"a@example.com\"")),
+        "to needs at least one address"
+    );
+}
+
+#[test]
+fn invalid_settings_are_rejected() {
+    assert_eq!(
+        invalid_reason(&format!("smtp_server = \"https://mx\"\n{ADDRESSES}")),
+        "smtp_server 'https://mx/': expected smtp://host or smtps://host"
+    );
+    assert_eq!(
+        invalid_reason(&format!(
+            "{SERVER}from = \"DDNS <d@example.com>\"\nto = [\"a@b\"]"
+        )),
+        "from 'DDNS <d@example.com>': expected a bare address without a name"
+    );
+    assert_eq!(
+        invalid_reason(&format!(
+            "{SERVER}from = \"d@example.com\"\nto = [\"admin\"]"
+        )),
+        "to 'admin': expected user@domain"
+    );
+    assert_eq!(
+        invalid_with("timeout = 0"),
+        "timeout must be at least 1 second"
+    );
+    assert_eq!(
+        invalid_with("username = \"u\""),
+        "username and password must be set together"
+    );
+}
+
+#[test]
+fn credentials_need_tls() {
+    assert_eq!(
+        invalid_with("starttls = false\nusername = \"u\"\npassword = \"p\""),
+        "username requires starttls or an smtps:// server"
+    );
+    assert_eq!(
+        invalid_with("starttls = false\n[email.tls]\nca_file = \"ca.pem\""),
+        "tls requires starttls or an smtps:// server"
+    );
+    assert_eq!(
+        invalid_reason(&format!(
+            "smtp_server = \"smtps://mx.example.com\"\n{ADDRESSES}starttls = true"
+        )),
+        "starttls cannot be set for an smtps:// server"
+    );
+
+    let config = email(Some(&format!(
+        "smtp_server = \"smtps://mx.example.com\"\n{ADDRESSES}username = \"u\"\npassword = \"p\""
+    )));
+    assert!(config.unwrap().outputs.email.is_some());
+}
+
+#[cfg(feature = "templates")]
+#[test]
+fn templates_are_validated() {
+    for template in ["subject_template", "body_template"] {
+        let result = email(Some(&format!(
+            "{SERVER}{ADDRESSES}{template} = \"{{{{#if}}}}\""
+        )));
+
+        assert!(
+            matches!(result, Err(ConfigError::InvalidTemplate { .. })),
+            "{template}: {result:?}"
+        );
+    }
+}
+
+#[test]
+fn replaces_the_webhook_without_url() {
+    let cli = cli(&["--ip-version", "ipv4"]);
+    let toml = toml(&format!("[email]\n{SERVER}{ADDRESSES}"));
+
+    let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+    assert_eq!(config.webhook.url, None);
+    assert!(config.outputs.email.is_some());
+}
+
+#[test]
+fn webhook_features_still_need_url() {
+    let cli = cli(&["--ip-version", "ipv4"]);
+    let discovery = toml(&format!(
+        "[webhook.discovery]\nname = \"_ddns.example.com\"\ntype = \"txt\"\n[email]\n{SERVER}{ADDRESSES}"
+    ));
+    let certificate = toml(&format!(
+        "[webhook.certificate]\ntrack = true\n[email]\n{SERVER}{ADDRESSES}"
+    ));
+
+    assert!(matches!(
+        ValidatedConfig::from_raw(&cli, Some(&discovery)),
+        Err(ConfigError::MissingRequired { field: "url", .. })
+    ));
+    assert!(matches!(
+        ValidatedConfig::from_raw(&cli, Some(&certificate)),
+        Err(ConfigError::InvalidCertificate(_))
+    ));
+}
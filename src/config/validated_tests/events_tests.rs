@@ -0,0 +1,186 @@
+//! Tests for the events reported beside address changes.
+
+use super::*;
+
+mod track_dns {
+    use super::*;
+
+    #[test]
+    fn disabled_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert!(!config.monitor.track_dns);
+        assert!(config.webhook.dns_body_template.is_none());
+    }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn enabled_with_template_from_toml() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [webhook]
+            dns_body_template = "{{#each dns_changes}}{{suffix}}{{/each}}"
+
+            [monitor]
+            track_dns = true
+        "#,
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert!(config.monitor.track_dns);
+        assert_eq!(
+            config.webhook.dns_body_template.as_deref(),
+            Some("{{#each dns_changes}}{{suffix}}{{/each}}")
+        );
+    }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn invalid_template_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [webhook]
+            dns_body_template = "{{#each dns_changes}"
+        "#,
+        );
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(result, Err(ConfigError::InvalidTemplate { .. })));
+    }
+
+    #[cfg(not(feature = "templates"))]
+    #[test]
+    fn template_without_feature_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [webhook]
+            dns_body_template = "{{suffix}}"
+        "#,
+        );
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::FeatureDisabled {
+                option: "webhook.dns_body_template",
+                ..
+            })
+        ));
+    }
+}
+
+mod track_hostname {
+    use super::*;
+
+    #[test]
+    fn disabled_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert!(!config.monitor.track_hostname);
+    }
+
+    #[test]
+    fn enabled_from_toml() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r"
+            [monitor]
+            track_hostname = true
+        ",
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert!(config.monitor.track_hostname);
+    }
+}
+
+mod track_link {
+    use super::*;
+
+    #[test]
+    fn disabled_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert!(!config.monitor.track_link);
+    }
+
+    #[test]
+    fn enabled_from_toml() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r"
+            [monitor]
+            track_link = true
+        ",
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert!(config.monitor.track_link);
+    }
+}
+
+mod address_storm {
+    use super::*;
+
+    #[test]
+    fn disabled_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "both"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert!(config.monitor.address_storm.is_none());
+    }
+
+    #[test]
+    fn parses_limits() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "both"]);
+        let toml = toml(
+            r#"
+            [monitor.address_storm]
+            max_addresses = 16
+            prefix_only = true
+
+            [monitor.address_storm.adapters]
+            "Wi-Fi" = 32
+        "#,
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        let policy = config.monitor.address_storm.unwrap();
+        assert_eq!(policy.limit_for("Ethernet"), 16);
+        assert_eq!(policy.limit_for("Wi-Fi"), 32);
+        assert!(policy.prefix_only());
+    }
+
+    #[test]
+    fn rejects_missing_or_zero_limit() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "both"]);
+        for section in [
+            "[monitor.address_storm]\nprefix_only = true",
+            "[monitor.address_storm]\nmax_addresses = 0",
+            "[monitor.address_storm]\nmax_addresses = 4\nadapters = { eth0 = 0 }",
+        ] {
+            let result = ValidatedConfig::from_raw(&cli, Some(&toml(section)));
+
+            assert!(
+                matches!(result, Err(ConfigError::InvalidAddressStorm(_))),
+                "{section}"
+            );
+        }
+    }
+
+    #[test]
+    fn requires_ipv6() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml("[monitor.address_storm]\nmax_addresses = 16");
+
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(result, Err(ConfigError::InvalidAddressStorm(_))));
+    }
+}
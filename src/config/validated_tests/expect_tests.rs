@@ -0,0 +1,58 @@
+//! Tests for the `[expect]` section.
+
+use std::net::IpAddr;
+
+use super::*;
+
+fn expect(ip_version: &str, addresses: &str) -> Result<ValidatedConfig, ConfigError> {
+    let cli = cli(&["--url", "https://example.com", "--ip-version", ip_version]);
+    let toml = toml(&format!("[expect]\naddresses = {addresses}"));
+    ValidatedConfig::from_raw(&cli, Some(&toml))
+}
+
+fn rejection(ip_version: &str, addresses: &str) -> String {
+    match expect(ip_version, addresses) {
+        Err(ConfigError::InvalidExpect(reason)) => reason,
+        other => panic!("expected InvalidExpect for {addresses}, got {other:?}"),
+    }
+}
+
+#[test]
+fn empty_by_default() {
+    let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+    let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+    assert!(config.monitor.expect.is_empty());
+}
+
+#[test]
+fn addresses_are_parsed() {
+    let config = expect("both", r#"["203.0.113.10", " 2001:db8::10 "]"#).unwrap();
+
+    assert_eq!(
+        config.monitor.expect.addresses(),
+        [
+            "203.0.113.10".parse::<IpAddr>().unwrap(),
+            "2001:db8::10".parse().unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn invalid_address_is_rejected() {
+    let reason = rejection("ipv4", r#"["203.0.113.300"]"#);
+
+    assert!(reason.contains("203.0.113.300"), "{reason}");
+}
+
+#[test]
+fn unmonitored_family_is_rejected() {
+    let reason = rejection("ipv4", r#"["2001:db8::10"]"#);
+
+    assert!(reason.contains("IPv4"), "{reason}");
+}
+
+#[test]
+fn unknown_field_is_rejected() {
+    assert!(TomlConfig::parse("[expect]\naddress = [\"203.0.113.10\"]").is_err());
+}
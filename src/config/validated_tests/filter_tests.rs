@@ -14,11 +14,11 @@ mod filter_building {
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
         // Default: loopback is excluded
-        assert!(config.filter.exclude_count() >= 1);
+        assert!(config.monitor.filter.exclude_count() >= 1);
 
         // Verify loopback adapter is rejected
         let loopback = AdapterSnapshot::new("lo", AdapterKind::Loopback, vec![], vec![]);
-        assert!(!config.filter.matches(&loopback));
+        assert!(!config.monitor.filter.matches(&loopback));
     }
 
     #[test]
@@ -34,12 +34,12 @@ mod filter_building {
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
         // Should have loopback + virtual excludes
-        assert!(config.filter.exclude_count() >= 2);
+        assert!(config.monitor.filter.exclude_count() >= 2);
 
         // Verify virtual adapter is rejected
         let virtual_adapter =
             AdapterSnapshot::new("vEthernet", AdapterKind::Virtual, vec![], vec![]);
-        assert!(!config.filter.matches(&virtual_adapter));
+        assert!(!config.monitor.filter.matches(&virtual_adapter));
     }
 
     #[test]
@@ -59,9 +59,9 @@ mod filter_building {
         let virtual_adapter =
             AdapterSnapshot::new("vEthernet", AdapterKind::Virtual, vec![], vec![]);
 
-        assert!(config.filter.matches(&ethernet));
-        assert!(config.filter.matches(&wireless));
-        assert!(!config.filter.matches(&virtual_adapter)); // Not in include list
+        assert!(config.monitor.filter.matches(&ethernet));
+        assert!(config.monitor.filter.matches(&wireless));
+        assert!(!config.monitor.filter.matches(&virtual_adapter)); // Not in include list
     }
 
     #[test]
@@ -78,7 +78,7 @@ mod filter_building {
 
         // Loopback should NOT be excluded when explicitly included
         let loopback = AdapterSnapshot::new("lo", AdapterKind::Loopback, vec![], vec![]);
-        assert!(config.filter.matches(&loopback));
+        assert!(config.monitor.filter.matches(&loopback));
     }
 
     #[test]
@@ -94,8 +94,8 @@ mod filter_building {
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
         // loopback exclude + include pattern
-        assert!(config.filter.exclude_count() >= 1);
-        assert!(config.filter.include_count() >= 1);
+        assert!(config.monitor.filter.exclude_count() >= 1);
+        assert!(config.monitor.filter.include_count() >= 1);
     }
 
     #[test]
@@ -129,7 +129,7 @@ mod filter_toml_patterns {
         let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
 
         // 2 include patterns
-        assert_eq!(config.filter.include_count(), 2);
+        assert_eq!(config.monitor.filter.include_count(), 2);
     }
 
     #[test]
@@ -147,7 +147,7 @@ mod filter_toml_patterns {
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
         // loopback + 2 exclude patterns
-        assert_eq!(config.filter.exclude_count(), 3);
+        assert_eq!(config.monitor.filter.exclude_count(), 3);
     }
 
     #[test]
@@ -162,7 +162,7 @@ mod filter_toml_patterns {
         let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
 
         // loopback + 2 exclude patterns
-        assert_eq!(config.filter.exclude_count(), 3);
+        assert_eq!(config.monitor.filter.exclude_count(), 3);
     }
 
     #[test]
@@ -224,13 +224,13 @@ mod filter_toml_kinds {
         let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
 
         // 1 include filter (KindFilter for ethernet+wireless)
-        assert!(config.filter.include_count() >= 1);
+        assert!(config.monitor.filter.include_count() >= 1);
 
         let ethernet = AdapterSnapshot::new("eth0", AdapterKind::Ethernet, vec![], vec![]);
         let virtual_adapter = AdapterSnapshot::new("vm0", AdapterKind::Virtual, vec![], vec![]);
 
-        assert!(config.filter.matches(&ethernet));
-        assert!(!config.filter.matches(&virtual_adapter));
+        assert!(config.monitor.filter.matches(&ethernet));
+        assert!(!config.monitor.filter.matches(&virtual_adapter));
     }
 
     #[test]
@@ -245,10 +245,10 @@ mod filter_toml_kinds {
         let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
 
         // loopback + virtual excludes
-        assert!(config.filter.exclude_count() >= 2);
+        assert!(config.monitor.filter.exclude_count() >= 2);
 
         let virtual_adapter = AdapterSnapshot::new("vm0", AdapterKind::Virtual, vec![], vec![]);
-        assert!(!config.filter.matches(&virtual_adapter));
+        assert!(!config.monitor.filter.matches(&virtual_adapter));
     }
 
     #[test]
@@ -273,8 +273,8 @@ mod filter_toml_kinds {
         let wireless = AdapterSnapshot::new("wlan0", AdapterKind::Wireless, vec![], vec![]);
 
         // CLI replaces TOML, so only wireless is included
-        assert!(!config.filter.matches(&ethernet));
-        assert!(config.filter.matches(&wireless));
+        assert!(!config.monitor.filter.matches(&ethernet));
+        assert!(config.monitor.filter.matches(&wireless));
     }
 
     #[test]
@@ -302,15 +302,135 @@ mod filter_toml_kinds {
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
         // No include kind filter added (only default loopback exclude)
-        assert_eq!(config.filter.include_count(), 0);
+        assert_eq!(config.monitor.filter.include_count(), 0);
 
         // All non-loopback kinds should match (no include restriction)
         let ethernet = AdapterSnapshot::new("eth0", AdapterKind::Ethernet, vec![], vec![]);
         let wireless = AdapterSnapshot::new("wlan0", AdapterKind::Wireless, vec![], vec![]);
         let virtual_adapter = AdapterSnapshot::new("vm0", AdapterKind::Virtual, vec![], vec![]);
 
-        assert!(config.filter.matches(&ethernet));
-        assert!(config.filter.matches(&wireless));
-        assert!(config.filter.matches(&virtual_adapter));
+        assert!(config.monitor.filter.matches(&ethernet));
+        assert!(config.monitor.filter.matches(&wireless));
+        assert!(config.monitor.filter.matches(&virtual_adapter));
+    }
+}
+
+mod default_route_only {
+    use super::*;
+
+    #[test]
+    fn off_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.monitor.filter.require_count(), 0);
+    }
+
+    #[test]
+    fn requires_the_default_route_besides_other_filters() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [filter]
+            default_route_only = true
+            include_kinds = ["ethernet", "wireless"]
+        "#,
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        let eth0 = AdapterSnapshot::new("eth0", AdapterKind::Ethernet, vec![], vec![]);
+        let wlan0 = AdapterSnapshot::new("wlan0", AdapterKind::Wireless, vec![], vec![]);
+        let vm0 = AdapterSnapshot::new("vm0", AdapterKind::Virtual, vec![], vec![]);
+
+        assert!(
+            config
+                .monitor
+                .filter
+                .matches(&eth0.with_default_route(true))
+        );
+        assert!(!config.monitor.filter.matches(&wlan0));
+        assert!(!config.monitor.filter.matches(&vm0.with_default_route(true)));
+    }
+}
+
+mod address_classes {
+    use super::*;
+    use crate::network::class::AddressClassFilter;
+
+    fn config(filter: &str) -> ValidatedConfig {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "both"]);
+        ValidatedConfig::from_raw(&cli, Some(&toml(&format!("[filter]\n{filter}")))).unwrap()
+    }
+
+    #[test]
+    fn defaults_without_config_file() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "both"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(
+            config.monitor.address_classes,
+            AddressClassFilter::default()
+        );
+    }
+
+    #[test]
+    fn defaults_with_empty_section() {
+        assert_eq!(
+            config("").monitor.address_classes,
+            AddressClassFilter::default()
+        );
+    }
+
+    #[test]
+    fn every_class_can_be_kept() {
+        let config =
+            config("exclude_link_local = false\nexclude_ula = false\nexclude_temporary = false\n");
+
+        assert_eq!(config.monitor.address_classes, AddressClassFilter::none());
+    }
+
+    #[test]
+    fn cidr_lists_from_toml() {
+        let config = config(
+            r#"
+            include_cidr = ["203.0.113.0/24", "2001:db8::/32"]
+            exclude_cidr = ["203.0.113.128/25"]
+        "#,
+        );
+
+        let classes = &config.monitor.address_classes;
+        assert_eq!(classes.include().len(), 2);
+        assert_eq!(classes.exclude()[0].to_string(), "203.0.113.128/25");
+        assert!(classes.allows("203.0.113.5".parse().unwrap()));
+        assert!(!classes.allows("203.0.113.200".parse().unwrap()));
+        assert!(!classes.allows("198.51.100.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn invalid_cidr_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "both"]);
+        let toml = toml("[filter]\nexclude_cidr = [\"10.0.0.0/40\"]");
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidCidr { value, .. }) if value == "10.0.0.0/40"
+        ));
+    }
+
+    #[test]
+    fn private_addresses_can_be_dropped() {
+        let config = config("include_private = false");
+
+        assert_eq!(
+            config.monitor.address_classes,
+            AddressClassFilter::default().with_private(true)
+        );
+        assert!(
+            !config
+                .monitor
+                .address_classes
+                .allows("10.1.2.3".parse().unwrap())
+        );
     }
 }
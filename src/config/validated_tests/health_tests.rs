@@ -0,0 +1,44 @@
+//! Tests for `--health` and the `[health]` section.
+
+use super::*;
+
+fn health(args: &[&str], section: Option<&str>) -> Result<ValidatedConfig, ConfigError> {
+    let mut full = vec!["--url", "https://example.com", "--ip-version", "ipv4"];
+    full.extend(args);
+    let toml = section.map(|s| toml(&format!("[health]\n{s}")));
+    ValidatedConfig::from_raw(&cli(&full), toml.as_ref())
+}
+
+#[test]
+fn off_by_default() {
+    assert!(health(&[], None).unwrap().health.is_none());
+    assert!(health(&[], Some("")).unwrap().health.is_none());
+}
+
+#[test]
+fn bind_from_section() {
+    let config = health(&[], Some("bind = \"127.0.0.1:8053\"")).unwrap();
+
+    assert_eq!(config.health, Some("127.0.0.1:8053".parse().unwrap()));
+}
+
+#[test]
+fn flag_overrides_section() {
+    let config = health(
+        &["--health", "[::1]:9000"],
+        Some("bind = \"127.0.0.1:8053\""),
+    )
+    .unwrap();
+
+    assert_eq!(config.health, Some("[::1]:9000".parse().unwrap()));
+}
+
+#[test]
+fn address_without_port_is_rejected() {
+    let result = health(&["--health", "127.0.0.1"], None);
+
+    assert!(
+        matches!(result, Err(ConfigError::InvalidHealth(ref reason)) if reason.contains("127.0.0.1")),
+        "{result:?}"
+    );
+}
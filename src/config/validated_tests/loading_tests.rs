@@ -42,7 +42,10 @@ mod required_fields {
         let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
-        assert_eq!(config.url.as_str(), "https://example.com/");
+        assert_eq!(
+            config.webhook.url.as_ref().unwrap().as_str(),
+            "https://example.com/"
+        );
         assert_eq!(config.ip_version, IpVersion::V4);
     }
 
@@ -59,7 +62,10 @@ mod required_fields {
 
         let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
 
-        assert_eq!(config.url.as_str(), "https://example.com/webhook");
+        assert_eq!(
+            config.webhook.url.as_ref().unwrap().as_str(),
+            "https://example.com/webhook"
+        );
         assert_eq!(config.ip_version, IpVersion::Both);
     }
 }
@@ -83,17 +89,31 @@ mod config_load {
         let cli = cli(&["--config", file.path().to_str().unwrap()]);
         let config = ValidatedConfig::load(&cli).unwrap();
 
-        assert_eq!(config.url.as_str(), "https://example.com/webhook");
+        assert_eq!(
+            config.webhook.url.as_ref().unwrap().as_str(),
+            "https://example.com/webhook"
+        );
         assert_eq!(config.ip_version, IpVersion::V4);
+        assert_eq!(config.config_file.as_deref(), Some(file.path()));
     }
 
     #[test]
     fn load_without_config_file() {
-        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv6"]);
+        let cli = cli(&[
+            "--url",
+            "https://example.com",
+            "--ip-version",
+            "ipv6",
+            "--no-config",
+        ]);
         let config = ValidatedConfig::load(&cli).unwrap();
 
-        assert_eq!(config.url.as_str(), "https://example.com/");
+        assert_eq!(
+            config.webhook.url.as_ref().unwrap().as_str(),
+            "https://example.com/"
+        );
         assert_eq!(config.ip_version, IpVersion::V6);
+        assert!(config.config_file.is_none());
     }
 
     #[test]
@@ -106,7 +126,7 @@ mod config_load {
 }
 
 mod write_config {
-    use super::super::super::validated::write_default_config;
+    use super::super::super::init::{InitMode, InitOutcome, write_default_config};
     use super::*;
 
     #[test]
@@ -114,7 +134,9 @@ mod write_config {
         let dir = tempdir().unwrap();
         let path = dir.path().join("test-config.toml");
 
-        write_default_config(&path).unwrap();
+        let outcome = write_default_config(&path, InitMode::CreateNew).unwrap();
+
+        assert_eq!(outcome, InitOutcome::Created);
 
         let content = fs::read_to_string(&path).unwrap();
         assert!(content.contains("[webhook]"));
@@ -127,8 +149,82 @@ mod write_config {
     fn write_default_config_to_invalid_path_returns_error() {
         // Try writing to an invalid path (directory that doesn't exist)
         let path = Path::new("/nonexistent_dir_12345/config.toml");
-        let result = write_default_config(path);
+        let result = write_default_config(path, InitMode::CreateNew);
 
         assert!(matches!(result, Err(ConfigError::FileWrite { .. })));
     }
 }
+
+mod locale {
+    use super::super::super::Locale;
+    use super::*;
+
+    #[test]
+    fn log_locale_is_applied() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml("[log]\nlocale = \"zh_CN\"");
+
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(config.locale, Locale::Zh);
+    }
+
+    #[test]
+    fn invalid_log_locale_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml("[log]\nlocale = \"klingon\"");
+
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidLocale { value }) if value == "klingon"
+        ));
+    }
+
+    #[test]
+    fn missing_log_locale_follows_env() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.locale, Locale::from_env());
+    }
+}
+
+mod log_target {
+    use super::*;
+    use crate::logging::LogTarget;
+
+    #[test]
+    fn defaults_to_stderr() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.log_target, LogTarget::Stderr);
+    }
+
+    #[test]
+    fn system_target_is_applied() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml("[log]\ntarget = \"system\"");
+
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(config.log_target, LogTarget::System);
+    }
+
+    #[test]
+    fn invalid_target_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml("[log]\ntarget = \"journald\"");
+
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidLogTarget { value }) if value == "journald"
+        ));
+    }
+}
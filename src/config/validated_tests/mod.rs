@@ -19,8 +19,28 @@ fn toml(content: &str) -> TomlConfig {
     TomlConfig::parse(content).unwrap()
 }
 
+mod action_tests;
+mod circuit_breaker_tests;
+mod delivery_tests;
+mod email_tests;
+mod events_tests;
+mod expect_tests;
 mod filter_tests;
+mod health_tests;
 mod loading_tests;
+mod mqtt_tests;
+mod notify_tests;
+mod oauth2_tests;
+mod pre_request_tests;
 mod precedence_tests;
+mod process_tests;
+mod provider_tests;
+mod retry_overlap_tests;
+mod retry_queue_tests;
+mod retry_tests;
+mod routing_tests;
 mod runtime_tests;
+mod state_tests;
+mod targets_tests;
+mod update_tests;
 mod webhook_tests;
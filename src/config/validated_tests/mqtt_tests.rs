@@ -0,0 +1,126 @@
+//! Tests for the `[mqtt]` section.
+
+use std::time::Duration;
+
+use super::*;
+use crate::webhook::{DEFAULT_MQTT_TIMEOUT, MqttQos};
+
+fn mqtt(section: Option<&str>) -> Result<ValidatedConfig, ConfigError> {
+    let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+    let toml = section.map(|s| toml(&format!("[mqtt]\n{s}")));
+    ValidatedConfig::from_raw(&cli, toml.as_ref())
+}
+
+fn invalid_reason(section: &str) -> String {
+    match mqtt(Some(section)) {
+        Err(ConfigError::InvalidMqtt(reason)) => reason,
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn off_by_default() {
+    assert!(mqtt(None).unwrap().outputs.mqtt.is_none());
+}
+
+#[test]
+fn reads_broker_and_topic() {
+    let config = mqtt(Some(
+        "broker = \"mqtt://broker.lan\"\ntopic = \"home/ddns\"",
+    ))
+    .unwrap();
+
+    let publisher = config.outputs.mqtt.unwrap();
+    assert_eq!(publisher.broker().as_str(), "mqtt://broker.lan");
+    assert_eq!(publisher.topic(), "home/ddns");
+    assert_eq!(publisher.qos(), MqttQos::AtLeastOnce);
+    assert!(!publisher.retain());
+    assert!(publisher.client_id().starts_with("ddns-a-"));
+    assert_eq!(publisher.timeout(), DEFAULT_MQTT_TIMEOUT);
+}
+
+#[test]
+fn reads_delivery_settings() {
+    let config = mqtt(Some(
+        r#"
+        broker = "mqtts://broker.example.com:8883"
+        topic = "ddns"
+        qos = 2
+        retain = true
+        client_id = "nas"
+        username = "ha"
+        password = "secret"
+        timeout = 3
+        "#,
+    ))
+    .unwrap();
+
+    let publisher = config.outputs.mqtt.unwrap();
+    assert_eq!(publisher.qos(), MqttQos::ExactlyOnce);
+    assert!(publisher.retain());
+    assert_eq!(publisher.client_id(), "nas");
+    assert_eq!(publisher.timeout(), Duration::from_secs(3));
+}
+
+#[test]
+fn broker_and_topic_are_required() {
+    assert_eq!(invalid_reason("topic = \"t\""), "broker is required");
+    assert_eq!(
+        invalid_reason("broker = \"mqtt://broker.lan\""),
+        "topic is required"
+    );
+}
+
+#[test]
+fn invalid_settings_are_rejected() {
+    let with = |extra: &str| {
+        invalid_reason(&format!(
+            "broker = \"mqtt://broker.lan\"\ntopic = \"t\"\n{extra}"
+        ))
+    };
+
+    assert_eq!(
+        invalid_reason("broker = \"https://broker.lan\"\ntopic = \"t\""),
+        "broker 'https://broker.lan/': expected mqtt://host or mqtts://host"
+    );
+    assert_eq!(
+        invalid_reason("broker = \"mqtt://broker.lan\"\ntopic = \"home/+\""),
+        "topic 'home/+': wildcards cannot be published to"
+    );
+    assert_eq!(with("qos = 3"), "qos 3: expected 0, 1 or 2");
+    assert_eq!(with("timeout = 0"), "timeout must be at least 1 second");
+    assert_eq!(
+        with("username = \"ha\""),
+        "username and password must be set together"
+    );
+    assert_eq!(with("client_id = \"\""), "client_id must not be empty");
+    assert_eq!(
+        with("[mqtt.tls]\nca_file = \"ca.pem\""),
+        "tls requires an mqtts:// broker"
+    );
+}
+
+#[test]
+fn tls_errors_name_the_section() {
+    let reason = invalid_reason(
+        "broker = \"mqtts://broker.lan\"\ntopic = \"t\"\n[mqtt.tls]\nclient_cert = \"c.pem\"",
+    );
+
+    assert_eq!(
+        reason,
+        "tls: client_cert and client_key must be set together"
+    );
+}
+
+#[cfg(feature = "templates")]
+#[test]
+fn body_template_is_validated() {
+    let result = mqtt(Some(
+        "broker = \"mqtt://broker.lan\"\ntopic = \"t\"\nbody_template = \"{{#if}}\"",
+    ));
+
+    assert!(
+        matches!(result, Err(ConfigError::InvalidTemplate { .. })),
+        "{result:?}"
+    );
+}
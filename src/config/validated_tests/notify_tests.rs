@@ -0,0 +1,100 @@
+//! Tests for the `[notify]` chat services.
+
+use super::*;
+use crate::webhook::{ChatPreset, ChatService};
+
+fn notify(section: &str) -> Result<ValidatedConfig, ConfigError> {
+    let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+    ValidatedConfig::from_raw(&cli, Some(&toml(section)))
+}
+
+fn invalid_reason(section: &str) -> String {
+    match notify(section) {
+        Err(ConfigError::InvalidNotify(reason)) => reason,
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn off_by_default() {
+    assert!(notify("").unwrap().outputs.chats.is_empty());
+}
+
+#[test]
+fn reads_every_service() {
+    let config = notify(
+        r#"
+        [notify.slack]
+        webhook_url = "https://hooks.slack.com/services/T0/B0/x"
+        [notify.telegram]
+        bot_token = "123456:ABC-def_9"
+        chat_id = "@updates"
+        [notify.discord]
+        webhook_url = "https://discord.com/api/webhooks/1/x"
+        "#,
+    )
+    .unwrap();
+
+    let services: Vec<_> = config
+        .outputs
+        .chats
+        .iter()
+        .map(ChatPreset::service)
+        .collect();
+    assert_eq!(services, ChatService::ALL);
+    assert_eq!(
+        config.outputs.chats[0].url().as_str(),
+        "https://api.telegram.org/bot123456:ABC-def_9/sendMessage"
+    );
+    assert_eq!(
+        config.outputs.chats[1].url().as_str(),
+        "https://discord.com/api/webhooks/1/x"
+    );
+}
+
+#[test]
+fn telegram_needs_token_and_chat() {
+    assert_eq!(
+        invalid_reason("[notify.telegram]\nchat_id = \"42\""),
+        "telegram: bot_token is required"
+    );
+    assert_eq!(
+        invalid_reason("[notify.telegram]\nbot_token = \"123:abc\"\nchat_id = \" \""),
+        "telegram: chat_id is required"
+    );
+    for token in ["abc", "abc:def", "123:", "123:a/b"] {
+        assert_eq!(
+            invalid_reason(&format!(
+                "[notify.telegram]\nbot_token = \"{token}\"\nchat_id = \"42\""
+            )),
+            "telegram: bot_token: expected <id>:<secret>"
+        );
+    }
+}
+
+#[test]
+fn webhook_url_is_checked() {
+    assert_eq!(
+        invalid_reason("[notify.discord]"),
+        "discord: webhook_url is required"
+    );
+    assert_eq!(
+        invalid_reason("[notify.slack]\nwebhook_url = \"ftp://hooks.example.com/x\""),
+        "slack: webhook_url: expected an http(s) URL"
+    );
+    assert!(
+        invalid_reason("[notify.slack]\nwebhook_url = \"hooks\"")
+            .starts_with("slack: webhook_url: ")
+    );
+}
+
+#[test]
+fn replaces_the_webhook_without_url() {
+    let cli = cli(&["--ip-version", "ipv4"]);
+    let toml = toml("[notify.discord]\nwebhook_url = \"https://discord.com/api/webhooks/1/x\"");
+
+    let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+    assert_eq!(config.webhook.url, None);
+    assert_eq!(config.outputs.chats.len(), 1);
+}
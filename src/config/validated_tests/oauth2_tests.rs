@@ -0,0 +1,83 @@
+//! Tests for the `[webhook.oauth2]` section.
+
+use super::*;
+
+fn oauth2(args: &[&str], section: &str) -> Result<ValidatedConfig, ConfigError> {
+    let mut full = vec!["--url", "https://example.com", "--ip-version", "ipv4"];
+    full.extend(args);
+    ValidatedConfig::from_raw(&cli(&full), Some(&toml(section)))
+}
+
+fn rejection(args: &[&str], section: &str) -> String {
+    match oauth2(args, section) {
+        Err(ConfigError::InvalidOAuth2(reason)) => reason,
+        other => panic!("expected InvalidOAuth2 for {section}, got {other:?}"),
+    }
+}
+
+const GRANT: &str = r#"[webhook.oauth2]
+token_url = "https://auth.example.com/oauth/token"
+client_id = "ddns-a"
+client_secret = "secret"
+"#;
+
+#[test]
+fn absent_by_default() {
+    let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+    let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+    assert!(config.webhook.oauth2.is_none());
+}
+
+#[test]
+fn parses_grant() {
+    let section = format!("{GRANT}scope = \"ddns.write\"\nclient_auth = \"body\"\n");
+    let config = oauth2(&[], &section).unwrap();
+
+    let request = config.webhook.oauth2.unwrap().token_request();
+    assert_eq!(request.url.as_str(), "https://auth.example.com/oauth/token");
+    assert_eq!(
+        request.body.as_deref(),
+        Some(
+            b"grant_type=client_credentials&scope=ddns.write&client_id=ddns-a&client_secret=secret"
+                .as_slice()
+        )
+    );
+}
+
+#[test]
+fn requires_url_and_credentials() {
+    for field in ["token_url", "client_id", "client_secret"] {
+        let section = GRANT
+            .lines()
+            .filter(|line| !line.starts_with(field))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_eq!(rejection(&[], &section), format!("{field} is required"));
+    }
+}
+
+#[test]
+fn rejects_plain_http_token_url_except_on_loopback() {
+    let remote = GRANT.replace("https://auth.example.com", "http://auth.example.com");
+    let local = GRANT.replace("https://auth.example.com", "http://127.0.0.1:8080");
+
+    assert!(rejection(&[], &remote).contains("must use https"));
+    assert!(oauth2(&[], &local).unwrap().webhook.oauth2.is_some());
+}
+
+#[test]
+fn rejects_unknown_client_auth() {
+    let section = format!("{GRANT}client_auth = \"jwt\"\n");
+
+    assert!(rejection(&[], &section).contains("'jwt'"));
+}
+
+#[test]
+fn rejects_other_authorization() {
+    let header = format!("{GRANT}[webhook.headers]\nAuthorization = \"Basic eDp5\"\n");
+
+    assert!(rejection(&["--bearer", "token"], GRANT).contains("bearer"));
+    assert!(rejection(&[], &header).contains("Authorization"));
+}
@@ -0,0 +1,136 @@
+//! Tests for the `[webhook.pre_request]` section.
+
+use super::*;
+use crate::webhook::CaptureSource;
+
+fn pre_request(section: &str) -> Result<ValidatedConfig, ConfigError> {
+    let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+    let toml = toml(section);
+    ValidatedConfig::from_raw(&cli, Some(&toml))
+}
+
+fn rejection(section: &str) -> String {
+    match pre_request(section) {
+        Err(ConfigError::InvalidPreRequest(reason)) => reason,
+        other => panic!("expected InvalidPreRequest for {section}, got {other:?}"),
+    }
+}
+
+#[test]
+fn absent_by_default() {
+    let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+    let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+    assert!(config.webhook.pre_request.is_none());
+}
+
+#[test]
+fn parses_request_and_sorted_captures() {
+    let config = pre_request(
+        r#"[webhook.pre_request]
+           url = "https://panel.example.com/login"
+           method = "PUT"
+           body = "user=me"
+           [webhook.pre_request.headers]
+           Content-Type = "application/x-www-form-urlencoded"
+           [webhook.pre_request.capture]
+           session = "json:/data/session"
+           csrf = "header:X-CSRF-Token""#,
+    )
+    .unwrap();
+
+    let pre_request = config.webhook.pre_request.unwrap();
+    let request = pre_request.request();
+    assert_eq!(request.method, Method::PUT);
+    assert_eq!(request.url.as_str(), "https://panel.example.com/login");
+    assert_eq!(request.body.as_deref(), Some(b"user=me".as_slice()));
+    assert_eq!(
+        request.headers.get("content-type").unwrap(),
+        "application/x-www-form-urlencoded"
+    );
+
+    let captures: Vec<_> = pre_request
+        .captures()
+        .iter()
+        .map(|c| (c.name.as_str(), c.source.clone()))
+        .collect();
+    assert_eq!(
+        captures,
+        [
+            (
+                "csrf",
+                CaptureSource::Header(http::HeaderName::from_static("x-csrf-token"))
+            ),
+            ("session", CaptureSource::Json("/data/session".to_string())),
+        ]
+    );
+}
+
+#[test]
+fn method_defaults_to_post() {
+    let config = pre_request(
+        r#"[webhook.pre_request]
+           url = "https://panel.example.com/login""#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.webhook.pre_request.unwrap().request().method,
+        Method::POST
+    );
+}
+
+#[test]
+fn rejects_missing_url() {
+    let reason = rejection("[webhook.pre_request]\nmethod = \"GET\"");
+
+    assert!(reason.contains("url is required"), "{reason}");
+}
+
+#[test]
+fn rejects_invalid_capture_name_and_source() {
+    let reason = rejection(
+        r#"[webhook.pre_request]
+           url = "https://panel.example.com/login"
+           [webhook.pre_request.capture]
+           "my-token" = "header:X-Token""#,
+    );
+    assert!(reason.contains("my-token"), "{reason}");
+
+    let reason = rejection(
+        r#"[webhook.pre_request]
+           url = "https://panel.example.com/login"
+           [webhook.pre_request.capture]
+           token = "cookie:session""#,
+    );
+    assert!(reason.starts_with("capture.token:"), "{reason}");
+}
+
+#[test]
+fn rejects_header_placeholder_without_capture() {
+    let reason = rejection(
+        r#"[webhook.headers]
+           X-CSRF-Token = "{{pre.csrf}}"
+           [webhook.pre_request]
+           url = "https://panel.example.com/login"
+           [webhook.pre_request.capture]
+           session = "json:/session""#,
+    );
+
+    assert!(reason.contains("{{pre.csrf}}"), "{reason}");
+}
+
+#[test]
+fn accepts_header_placeholder_with_capture() {
+    let config = pre_request(
+        r#"[webhook.headers]
+           X-CSRF-Token = "{{pre.csrf}}"
+           [webhook.pre_request]
+           url = "https://panel.example.com/login"
+           [webhook.pre_request.capture]
+           csrf = "header:X-CSRF-Token""#,
+    )
+    .unwrap();
+
+    assert!(config.webhook.pre_request.is_some());
+}
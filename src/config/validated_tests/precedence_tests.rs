@@ -21,7 +21,10 @@ mod cli_precedence {
 
         let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
 
-        assert_eq!(config.url.as_str(), "https://cli.example.com/");
+        assert_eq!(
+            config.webhook.url.as_ref().unwrap().as_str(),
+            "https://cli.example.com/"
+        );
     }
 
     #[test]
@@ -65,8 +68,8 @@ mod cli_precedence {
         let virtual_adapter = AdapterSnapshot::new("vm0", AdapterKind::Virtual, vec![], vec![]);
         let wireless = AdapterSnapshot::new("wlan0", AdapterKind::Wireless, vec![], vec![]);
 
-        assert!(!config.filter.matches(&virtual_adapter)); // Excluded by CLI
-        assert!(config.filter.matches(&wireless)); // Not excluded (CLI replaced TOML)
+        assert!(!config.monitor.filter.matches(&virtual_adapter)); // Excluded by CLI
+        assert!(config.monitor.filter.matches(&wireless)); // Not excluded (CLI replaced TOML)
     }
 }
 
@@ -91,7 +94,7 @@ mod retry_cli_overrides_toml {
         );
         let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
 
-        assert_eq!(config.retry_policy.max_attempts, 10);
+        assert_eq!(config.webhook.retry_policy.max_attempts, 10);
     }
 
     #[test]
@@ -112,6 +115,59 @@ mod retry_cli_overrides_toml {
         );
         let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
 
-        assert_eq!(config.retry_policy.initial_delay, Duration::from_secs(30));
+        assert_eq!(
+            config.webhook.retry_policy.initial_delay,
+            Duration::from_secs(30)
+        );
+    }
+}
+
+mod env_precedence {
+    use super::*;
+
+    fn env_cli(args: &[&str]) -> crate::config::Cli {
+        let mut cli = cli(args);
+        cli.apply_env_with(|name| match name {
+            "DDNS_A_URL" => Some("https://env.example.com".to_string()),
+            "DDNS_A_POLL_INTERVAL" => Some("45".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        cli
+    }
+
+    #[test]
+    fn env_overrides_toml() {
+        let toml = toml(
+            r#"
+            [webhook]
+            url = "https://toml.example.com"
+            ip_version = "ipv4"
+
+            [monitor]
+            poll_interval = 120
+        "#,
+        );
+
+        let config = ValidatedConfig::from_raw(&env_cli(&[]), Some(&toml)).unwrap();
+
+        assert_eq!(
+            config.webhook.url.as_ref().unwrap().as_str(),
+            "https://env.example.com/"
+        );
+        assert_eq!(config.monitor.poll_interval, Duration::from_secs(45));
+    }
+
+    #[test]
+    fn cli_overrides_env() {
+        let cli = env_cli(&["--url", "https://cli.example.com", "--ip-version", "ipv4"]);
+
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(
+            config.webhook.url.as_ref().unwrap().as_str(),
+            "https://cli.example.com/"
+        );
+        assert_eq!(config.monitor.poll_interval, Duration::from_secs(45));
     }
 }
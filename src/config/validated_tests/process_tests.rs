@@ -0,0 +1,556 @@
+//! Tests for process and output configuration: dry run, verbose, audit log, request
+//! metadata, run summary, daemon, tray.
+
+use std::time::Duration;
+
+use super::*;
+
+mod dry_run_and_verbose {
+    use super::*;
+
+    #[test]
+    fn dry_run_flag() {
+        let cli = cli(&[
+            "--url",
+            "https://example.com",
+            "--ip-version",
+            "ipv4",
+            "--dry-run",
+        ]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert!(config.dry_run);
+    }
+
+    #[test]
+    fn verbose_flag() {
+        let cli = cli(&[
+            "--url",
+            "https://example.com",
+            "--ip-version",
+            "ipv4",
+            "--verbose",
+        ]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert!(config.verbose);
+    }
+}
+
+mod audit {
+    use super::*;
+
+    #[test]
+    fn disabled_without_toml() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert!(config.outputs.audit.is_none());
+    }
+
+    #[test]
+    fn disabled_without_file() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [audit]
+            allow_headers = ["Content-Type"]
+        "#,
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert!(config.outputs.audit.is_none());
+    }
+
+    #[test]
+    fn enabled_with_file_and_allow_lists() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [audit]
+            file = "audit.jsonl"
+            allow_headers = ["Content-Type"]
+            allow_query = ["ip"]
+        "#,
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        let audit = config.outputs.audit.expect("audit should be enabled");
+        assert_eq!(audit.path, std::path::PathBuf::from("audit.jsonl"));
+        assert_eq!(
+            audit.redaction,
+            crate::webhook::Redaction::new()
+                .allow_header(http::header::CONTENT_TYPE)
+                .allow_query_param("ip")
+        );
+    }
+
+    #[test]
+    fn invalid_allow_header_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [audit]
+            file = "audit.jsonl"
+            allow_headers = ["Bad Header"]
+        "#,
+        );
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(result, Err(ConfigError::InvalidHeaderName { .. })));
+    }
+
+    #[test]
+    fn display_shows_audit_path() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [audit]
+            file = "audit.jsonl"
+        "#,
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert!(format!("{config}").contains("audit: audit.jsonl"));
+    }
+}
+
+mod request_metadata {
+    use super::*;
+    use crate::config::HttpSettings;
+    use crate::webhook::{ConnectionSettings, DEFAULT_USER_AGENT, INTERFACE_BINDING_SUPPORTED};
+
+    #[test]
+    fn defaults_without_toml() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.http, HttpSettings::default());
+        assert_eq!(config.http.user_agent, DEFAULT_USER_AGENT);
+        assert!(config.http.metadata_headers);
+    }
+
+    #[test]
+    fn user_agent_from_toml() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [http]
+            user_agent = "my-router/1.0"
+        "#,
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(config.http.user_agent, "my-router/1.0");
+    }
+
+    #[test]
+    fn metadata_headers_can_be_disabled() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r"
+            [http]
+            metadata_headers = false
+        ",
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert!(!config.http.metadata_headers);
+    }
+
+    #[test]
+    fn empty_user_agent_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [http]
+            user_agent = "  "
+        "#,
+        );
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidHeaderValue { .. })
+        ));
+    }
+
+    #[test]
+    fn dns_servers_from_toml() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [http]
+            dns_servers = ["1.1.1.1", "[2606:4700:4700::1111]:5353"]
+        "#,
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(
+            config.http.dns_servers,
+            vec![
+                "1.1.1.1:53".parse().unwrap(),
+                "[2606:4700:4700::1111]:5353".parse().unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn tls_defaults_without_section() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml("[http]"))).unwrap();
+
+        assert!(config.http.tls.is_default());
+    }
+
+    #[test]
+    fn tls_accept_invalid_certs_from_toml() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml("[http.tls]\ndanger_accept_invalid_certs = true");
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert!(config.http.tls.accept_invalid_certs);
+        assert!(config.http.tls.ca_file.is_none());
+    }
+
+    #[test]
+    fn invalid_tls_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        for section in [
+            "client_cert = \"client.pem\"",
+            "client_key = \"client.key\"",
+            "ca_file = \"/nonexistent/ca.pem\"",
+            "client_cert = \"/nonexistent/c.pem\"\nclient_key = \"/nonexistent/c.key\"",
+        ] {
+            let toml = toml(&format!("[http.tls]\n{section}"));
+            let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+            assert!(
+                matches!(result, Err(ConfigError::InvalidTls(_))),
+                "{section}"
+            );
+        }
+    }
+
+    #[test]
+    fn connection_defaults_without_section() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.http.connection, ConnectionSettings::default());
+    }
+
+    #[test]
+    fn connection_settings_from_toml() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r"
+            [http]
+            connect_timeout = 3
+            request_timeout = 15
+            pool_idle_timeout = 30
+            pool_max_idle_per_host = 0
+            ",
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(
+            config.http.connection,
+            ConnectionSettings {
+                connect_timeout: Duration::from_secs(3),
+                request_timeout: Duration::from_secs(15),
+                pool_idle_timeout: Duration::from_secs(30),
+                pool_max_idle_per_host: 0,
+                ..ConnectionSettings::default()
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_connection_timeouts_return_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        for (section, expected) in [
+            ("connect_timeout = 0", "http.connect_timeout"),
+            ("request_timeout = 0", "http.request_timeout"),
+            ("pool_idle_timeout = 0", "http.pool_idle_timeout"),
+            (
+                "connect_timeout = 30\nrequest_timeout = 20",
+                "http.connect_timeout",
+            ),
+            ("connect_timeout = 90", "http.connect_timeout"),
+        ] {
+            let toml = toml(&format!("[http]\n{section}"));
+            let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+            assert!(
+                matches!(result, Err(ConfigError::InvalidDuration { field, .. }) if field == expected),
+                "{section}"
+            );
+        }
+    }
+
+    #[test]
+    fn local_address_from_toml() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv6"]);
+        let toml = toml("[http]\nlocal_address = \"2001:db8::10\"");
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(
+            config.http.connection.local_address,
+            Some("2001:db8::10".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn invalid_local_address_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml("[http]\nlocal_address = \"eth0\"");
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(result, Err(ConfigError::InvalidHttpBinding(_))));
+    }
+
+    #[test]
+    fn interface_binding_depends_on_platform() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml("[http]\ninterface = \"eth0\"");
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        if INTERFACE_BINDING_SUPPORTED {
+            let config = result.unwrap();
+            assert_eq!(config.http.connection.interface.as_deref(), Some("eth0"));
+        } else {
+            assert!(matches!(result, Err(ConfigError::InvalidHttpBinding(_))));
+        }
+    }
+
+    #[test]
+    fn empty_interface_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml("[http]\ninterface = \" \"");
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(result, Err(ConfigError::InvalidHttpBinding(_))));
+    }
+
+    #[test]
+    fn invalid_dns_server_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [http]
+            dns_servers = ["dns.example.com"]
+        "#,
+        );
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(result, Err(ConfigError::InvalidDnsServers(_))));
+    }
+
+    #[test]
+    fn invalid_user_agent_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [http]
+            user_agent = "bad\nagent"
+        "#,
+        );
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidHeaderValue { .. })
+        ));
+    }
+}
+
+mod summary {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::report::SummaryOutput;
+
+    #[test]
+    fn disabled_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert!(config.outputs.summary.is_none());
+    }
+
+    #[test]
+    fn enabled_writes_to_stderr() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r"
+            [output]
+            summary = true
+        ",
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(config.outputs.summary, Some(SummaryOutput::Stderr));
+    }
+
+    #[test]
+    fn summary_file_takes_precedence() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [output]
+            summary = false
+            summary_file = "out/summary.json"
+        "#,
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(
+            config.outputs.summary,
+            Some(SummaryOutput::File(PathBuf::from("out/summary.json")))
+        );
+    }
+
+    #[test]
+    fn history_disabled_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.outputs.history, None);
+    }
+
+    #[test]
+    fn history_file_is_read() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [output]
+            history_file = "out/history.jsonl"
+        "#,
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(
+            config.outputs.history,
+            Some(PathBuf::from("out/history.jsonl"))
+        );
+    }
+}
+
+mod daemon {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn disabled_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert!(config.pid_file.is_none());
+        assert!(!config.daemonize);
+    }
+
+    #[test]
+    fn pid_file_from_cli() {
+        let cli = cli(&[
+            "--url",
+            "https://example.com",
+            "--ip-version",
+            "ipv4",
+            "--pidfile",
+            "run/ddns-a.pid",
+        ]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.pid_file, Some(PathBuf::from("run/ddns-a.pid")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn daemon_flag_on_unix() {
+        let cli = cli(&[
+            "--url",
+            "https://example.com",
+            "--ip-version",
+            "ipv4",
+            "--daemon",
+        ]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert!(config.daemonize);
+    }
+
+    #[cfg(not(unix))]
+    #[test]
+    fn daemon_flag_rejected_elsewhere() {
+        let cli = cli(&[
+            "--url",
+            "https://example.com",
+            "--ip-version",
+            "ipv4",
+            "--daemon",
+        ]);
+        let result = ValidatedConfig::from_raw(&cli, None);
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::UnsupportedPlatform { option: "--daemon" })
+        ));
+    }
+}
+
+mod tray {
+    use super::*;
+
+    fn tray_cli() -> Cli {
+        cli(&[
+            "--url",
+            "https://example.com",
+            "--ip-version",
+            "ipv4",
+            "--tray",
+        ])
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert!(!config.tray);
+    }
+
+    #[cfg(all(windows, feature = "tray"))]
+    #[test]
+    fn tray_flag_on_windows() {
+        let config = ValidatedConfig::from_raw(&tray_cli(), None).unwrap();
+
+        assert!(config.tray);
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn tray_flag_rejected_elsewhere() {
+        let result = ValidatedConfig::from_raw(&tray_cli(), None);
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::UnsupportedPlatform { option: "--tray" })
+        ));
+    }
+
+    #[cfg(all(windows, not(feature = "tray")))]
+    #[test]
+    fn tray_flag_needs_feature() {
+        let result = ValidatedConfig::from_raw(&tray_cli(), None);
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::FeatureDisabled {
+                feature: "tray",
+                option: "--tray"
+            })
+        ));
+    }
+}
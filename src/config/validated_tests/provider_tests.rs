@@ -0,0 +1,367 @@
+//! Tests for the `[webhook.provider]` section.
+
+use super::*;
+use crate::webhook::{Provider, ProviderKind};
+
+fn provider(section: &str) -> Result<ValidatedConfig, ConfigError> {
+    let cli = cli(&["--ip-version", "ipv4"]);
+    let toml = toml(&format!("[webhook.provider]\n{section}"));
+    ValidatedConfig::from_raw(&cli, Some(&toml))
+}
+
+fn rejection(section: &str) -> String {
+    match provider(section) {
+        Err(ConfigError::InvalidProvider(reason)) => reason,
+        other => panic!("expected InvalidProvider for {section}, got {other:?}"),
+    }
+}
+
+#[test]
+fn absent_by_default() {
+    let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+    let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+    assert!(config.webhook.provider.is_none());
+}
+
+#[test]
+fn url_defaults_to_provider_endpoint() {
+    let config = provider(
+        r#"name = "duckdns"
+           domain = "myhost"
+           token = "abc""#,
+    )
+    .unwrap();
+
+    assert_eq!(config.webhook.url, Some(ProviderKind::DuckDns.endpoint()));
+    assert_eq!(
+        config.webhook.provider,
+        Some(Provider::new(ProviderKind::DuckDns, "myhost", "abc"))
+    );
+}
+
+#[test]
+fn explicit_url_wins() {
+    let cli = cli(&["--url", "https://mock.test/api", "--ip-version", "ipv4"]);
+    let toml = toml(
+        r#"[webhook.provider]
+           name = "desec"
+           domain = "myhost.dedyn.io"
+           token = "abc""#,
+    );
+    let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+    assert_eq!(
+        config.webhook.url.as_ref().unwrap().as_str(),
+        "https://mock.test/api"
+    );
+}
+
+#[test]
+fn parses_porkbun_with_all_settings() {
+    let config = provider(
+        r#"name = "Porkbun"
+           domain = "example.com"
+           record = "home"
+           token = "pk1_key"
+           secret = "sk1_secret"
+           ttl = 900"#,
+    )
+    .unwrap();
+
+    let expected = Provider::new(ProviderKind::Porkbun, "example.com", "pk1_key")
+        .with_secret("sk1_secret")
+        .with_record("home")
+        .with_ttl(900);
+    assert_eq!(config.webhook.provider, Some(expected));
+}
+
+#[test]
+fn apex_record_is_unset() {
+    let config = provider(
+        r#"name = "gandi"
+           domain = "example.com"
+           record = "@"
+           token = "pat""#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.webhook.provider,
+        Some(Provider::new(ProviderKind::Gandi, "example.com", "pat"))
+    );
+}
+
+#[test]
+fn rejects_unknown_name() {
+    let reason = rejection(
+        r#"name = "cloudflare"
+           domain = "example.com"
+           token = "t""#,
+    );
+
+    assert!(
+        reason.contains("duckdns, porkbun, gandi, desec, noip, dynu, freedns"),
+        "{reason}"
+    );
+}
+
+#[test]
+fn rejects_missing_fields() {
+    for (section, field) in [
+        (
+            r#"domain = "d"
+            token = "t""#,
+            "name",
+        ),
+        (
+            r#"name = "duckdns"
+            token = "t""#,
+            "domain",
+        ),
+        (
+            r#"name = "duckdns"
+            domain = "  ""#,
+            "domain",
+        ),
+        (
+            r#"name = "desec"
+            domain = "d""#,
+            "token",
+        ),
+        (
+            r#"name = "porkbun"
+            domain = "d"
+            token = "t""#,
+            "secret",
+        ),
+    ] {
+        assert_eq!(rejection(section), format!("{field} is required"));
+    }
+}
+
+#[test]
+fn rejects_settings_the_provider_does_not_use() {
+    for section in [
+        r#"name = "gandi"
+           domain = "d"
+           token = "t"
+           secret = "s""#,
+        r#"name = "duckdns"
+           domain = "d"
+           token = "t"
+           record = "www""#,
+        r#"name = "desec"
+           domain = "d"
+           token = "t"
+           ttl = 60"#,
+        r#"name = "freedns"
+           domain = "d"
+           token = "t"
+           record = "www""#,
+    ] {
+        assert!(
+            rejection(section).contains("apply to"),
+            "accepted: {section}"
+        );
+    }
+}
+
+#[test]
+fn rejects_zero_ttl_and_unsafe_credentials() {
+    assert_eq!(
+        rejection(
+            r#"name = "gandi"
+               domain = "d"
+               token = "t"
+               ttl = 0"#
+        ),
+        "ttl must be greater than 0"
+    );
+    assert!(
+        rejection(
+            r#"name = "desec"
+           domain = "d"
+           token = "a b""#
+        )
+        .starts_with("token")
+    );
+}
+
+#[test]
+fn rejects_body_template() {
+    let cli = cli(&["--ip-version", "ipv4", "--body-template", "{}"]);
+    let toml = toml(
+        r#"[webhook.provider]
+           name = "duckdns"
+           domain = "myhost"
+           token = "t""#,
+    );
+
+    assert!(matches!(
+        ValidatedConfig::from_raw(&cli, Some(&toml)),
+        Err(ConfigError::InvalidProvider(_))
+    ));
+}
+
+#[test]
+fn rejects_echo_check() {
+    let cli = cli(&["--ip-version", "ipv4"]);
+    let toml = toml(
+        r#"[webhook]
+           echo_check = "header"
+
+           [webhook.provider]
+           name = "duckdns"
+           domain = "myhost"
+           token = "t""#,
+    );
+
+    assert!(matches!(
+        ValidatedConfig::from_raw(&cli, Some(&toml)),
+        Err(ConfigError::InvalidProvider(_))
+    ));
+}
+
+#[test]
+fn ttl_defaults_to_webhook_ttl() {
+    let cli = cli(&["--ip-version", "ipv4"]);
+    let config = |provider: &str| {
+        let toml = toml(&format!(
+            "[webhook]\nttl = 1200\n\n[webhook.provider]\ndomain = \"d\"\ntoken = \"t\"\n{provider}"
+        ));
+        ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap()
+    };
+
+    let gandi = config("name = \"gandi\"");
+    assert_eq!(
+        gandi.webhook.provider,
+        Some(Provider::new(ProviderKind::Gandi, "d", "t").with_ttl(1200))
+    );
+    // The provider's own ttl wins
+    let gandi = config("name = \"gandi\"\nttl = 900");
+    assert_eq!(
+        gandi.webhook.provider,
+        Some(Provider::new(ProviderKind::Gandi, "d", "t").with_ttl(900))
+    );
+    // A hint, so providers without record TTLs accept it
+    let desec = config("name = \"desec\"");
+    assert_eq!(
+        desec.webhook.provider,
+        Some(Provider::new(ProviderKind::Desec, "d", "t"))
+    );
+    assert_eq!(desec.webhook.ttl, Some(1200));
+}
+
+#[test]
+fn rejects_expect_json() {
+    let reason = rejection(
+        r#"name = "duckdns"
+           domain = "myhost"
+           token = "t"
+
+           [webhook]
+           expect_json = { path = "$.status", equals = "ok" }"#,
+    );
+
+    assert!(reason.contains("expect_json"), "{reason}");
+}
+
+#[test]
+fn rejects_expect_body_regex() {
+    let reason = rejection(
+        r#"name = "duckdns"
+           domain = "myhost"
+           token = "t"
+
+           [webhook]
+           expect_body_regex = "OK""#,
+    );
+
+    assert!(reason.contains("expect_body_regex"), "{reason}");
+}
+
+#[test]
+fn selectable_by_flags_alone() {
+    let cli = cli(&[
+        "--ip-version",
+        "ipv4",
+        "--provider",
+        "duckdns",
+        "--provider-domain",
+        "myhost",
+        "--provider-token",
+        "XYZ",
+    ]);
+    let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+    assert_eq!(config.webhook.url, Some(ProviderKind::DuckDns.endpoint()));
+    assert_eq!(
+        config.webhook.provider,
+        Some(Provider::new(ProviderKind::DuckDns, "myhost", "XYZ"))
+    );
+}
+
+#[test]
+fn flags_override_section() {
+    let cli = cli(&[
+        "--ip-version",
+        "ipv4",
+        "--provider",
+        "noip",
+        "--provider-token",
+        "user",
+        "--provider-secret",
+        "pass",
+    ]);
+    let toml = toml(
+        r#"[webhook.provider]
+           name = "dynu"
+           domain = "myhost.ddns.net"
+           token = "pw""#,
+    );
+    let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+    assert_eq!(
+        config.webhook.provider,
+        Some(Provider::new(ProviderKind::NoIp, "myhost.ddns.net", "user").with_secret("pass"))
+    );
+}
+
+#[test]
+fn flags_without_name_are_rejected() {
+    let cli = cli(&["--ip-version", "ipv4", "--provider-token", "XYZ"]);
+
+    assert!(matches!(
+        ValidatedConfig::from_raw(&cli, None),
+        Err(ConfigError::InvalidProvider(reason)) if reason == "name is required"
+    ));
+}
+
+#[test]
+fn noip_requires_password() {
+    assert_eq!(
+        rejection(
+            r#"name = "noip"
+               domain = "myhost.ddns.net"
+               token = "user""#
+        ),
+        "secret is required"
+    );
+}
+
+#[test]
+fn rejects_oauth2() {
+    let reason = rejection(
+        r#"name = "duckdns"
+           domain = "myhost"
+           token = "t"
+
+           [webhook.oauth2]
+           token_url = "https://auth.example.com/token"
+           client_id = "id"
+           client_secret = "secret""#,
+    );
+
+    assert!(reason.contains("oauth2"), "{reason}");
+}
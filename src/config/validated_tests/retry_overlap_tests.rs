@@ -0,0 +1,79 @@
+//! Tests for checking retries against the poll interval (`retry.overlap`).
+
+use std::time::Duration;
+
+use super::*;
+use crate::webhook::RetryOverlap;
+
+/// Default retries wait 5s + 10s; a 10s poll interval is shorter.
+fn load(monitor: &str, retry: &str) -> Result<ValidatedConfig, ConfigError> {
+    let cli = cli(&["--url", "https://example.com", "--ip-version", "both"]);
+    let toml = toml(&format!("[monitor]\n{monitor}\n[retry]\n{retry}"));
+    ValidatedConfig::from_raw(&cli, Some(&toml))
+}
+
+#[test]
+fn default_policy_fits_default_interval() {
+    let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+    let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+    assert_eq!(config.webhook.retry_overlap, None);
+}
+
+#[test]
+fn warns_by_default() {
+    let config = load("poll_interval = 10", "").unwrap();
+
+    assert_eq!(
+        config.webhook.retry_overlap,
+        Some(RetryOverlap {
+            worst_case: Duration::from_secs(15),
+            poll_interval: Duration::from_secs(10),
+        })
+    );
+}
+
+#[test]
+fn compares_with_shorter_ipv6_interval() {
+    let config = load("poll_interval = 60\npoll_interval_v6 = 10", "").unwrap();
+
+    assert_eq!(
+        config.webhook.retry_overlap.map(|o| o.poll_interval),
+        Some(Duration::from_secs(10))
+    );
+}
+
+#[test]
+fn off_disables_the_check() {
+    let config = load("poll_interval = 10", "overlap = \"off\"").unwrap();
+
+    assert_eq!(config.webhook.retry_overlap, None);
+}
+
+#[test]
+fn error_rejects_the_config() {
+    let result = load("poll_interval = 10", "overlap = \"error\"");
+
+    assert!(matches!(result, Err(ConfigError::RetryOverlap(_))));
+}
+
+#[test]
+fn error_accepts_a_fitting_policy() {
+    let config = load(
+        "poll_interval = 10",
+        "overlap = \"error\"\nmax_attempts = 2",
+    )
+    .unwrap();
+
+    assert_eq!(config.webhook.retry_overlap, None);
+}
+
+#[test]
+fn invalid_value_returns_error() {
+    let result = load("poll_interval = 10", "overlap = \"queue\"");
+
+    assert!(matches!(
+        result,
+        Err(ConfigError::InvalidOverlapCheck { value }) if value == "queue"
+    ));
+}
@@ -0,0 +1,72 @@
+//! Tests for the persistent retry queue (`[retry.queue]`).
+
+use std::time::Duration;
+
+use super::*;
+use crate::state::QueueLimits;
+
+fn load(queue: &str) -> Result<ValidatedConfig, ConfigError> {
+    let cli = cli(&[
+        "--url",
+        "https://example.com",
+        "--ip-version",
+        "ipv4",
+        "--state-file",
+        "state.json",
+    ]);
+    let toml = toml(&format!("[retry.queue]\n{queue}"));
+    ValidatedConfig::from_raw(&cli, Some(&toml))
+}
+
+#[test]
+fn disabled_without_section() {
+    let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+    let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+    assert_eq!(config.webhook.retry_queue, None);
+}
+
+#[test]
+fn empty_section_uses_default_limits() {
+    let config = load("").unwrap();
+
+    assert_eq!(config.webhook.retry_queue, Some(QueueLimits::default()));
+}
+
+#[test]
+fn limits_are_read() {
+    let config = load("max_age = 3600\nmax_size = 10").unwrap();
+
+    assert_eq!(
+        config.webhook.retry_queue,
+        Some(QueueLimits {
+            max_age: Duration::from_secs(3600),
+            max_size: 10,
+        })
+    );
+}
+
+#[test]
+fn requires_state_file() {
+    let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+    let toml = toml("[retry.queue]\nmax_size = 10");
+
+    let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+    assert!(
+        matches!(&result, Err(ConfigError::InvalidRetry(reason)) if reason.contains("state file")),
+        "{result:?}"
+    );
+}
+
+#[test]
+fn zero_limits_are_rejected() {
+    for queue in ["max_age = 0", "max_size = 0"] {
+        let result = load(queue);
+
+        assert!(
+            matches!(result, Err(ConfigError::InvalidRetry(_))),
+            "{queue}"
+        );
+    }
+}
@@ -0,0 +1,498 @@
+//! Tests for delivery policy configuration: `notify_on`, `min_notify_interval`, retry policy
+//! and overrides, shutdown grace.
+
+use std::time::Duration;
+
+use super::*;
+
+mod min_notify_interval {
+    use super::*;
+
+    #[test]
+    fn disabled_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.webhook.min_notify_interval, None);
+    }
+
+    #[test]
+    fn from_toml() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r"
+            [webhook]
+            min_notify_interval = 120
+        ",
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(
+            config.webhook.min_notify_interval,
+            Some(Duration::from_secs(120))
+        );
+    }
+
+    #[test]
+    fn zero_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r"
+            [webhook]
+            min_notify_interval = 0
+        ",
+        );
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidDuration {
+                field: "min_notify_interval",
+                ..
+            })
+        ));
+    }
+}
+
+mod notify_on {
+    use super::*;
+    use crate::monitor::NotifyOn;
+
+    fn config(notify_on: &str) -> Result<ValidatedConfig, ConfigError> {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(&format!("[webhook]\nnotify_on = {notify_on}"));
+        ValidatedConfig::from_raw(&cli, Some(&toml))
+    }
+
+    #[test]
+    fn both_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.webhook.notify_on, NotifyOn::Both);
+    }
+
+    #[test]
+    fn from_toml() {
+        assert_eq!(
+            config(r#"["added"]"#).unwrap().webhook.notify_on,
+            NotifyOn::Added
+        );
+        assert_eq!(
+            config(r#"["Removed"]"#).unwrap().webhook.notify_on,
+            NotifyOn::Removed
+        );
+        assert_eq!(
+            config(r#"["removed", "added"]"#).unwrap().webhook.notify_on,
+            NotifyOn::Both
+        );
+    }
+
+    #[test]
+    fn unknown_kind_returns_error() {
+        let result = config(r#"["added", "changed"]"#);
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidNotifyOn { value }) if value == "changed"
+        ));
+    }
+
+    #[test]
+    fn empty_list_returns_error() {
+        let result = config("[]");
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidNotifyOn { value }) if value == "[]"
+        ));
+    }
+}
+
+mod retry_policy {
+    use super::*;
+
+    #[test]
+    fn default_values() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.webhook.retry_policy.max_attempts, 3);
+        assert_eq!(
+            config.webhook.retry_policy.initial_delay,
+            Duration::from_secs(5)
+        );
+    }
+
+    #[test]
+    fn custom_values_from_cli() {
+        let cli = cli(&[
+            "--url",
+            "https://example.com",
+            "--ip-version",
+            "ipv4",
+            "--retry-max",
+            "5",
+            "--retry-delay",
+            "10",
+        ]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.webhook.retry_policy.max_attempts, 5);
+        assert_eq!(
+            config.webhook.retry_policy.initial_delay,
+            Duration::from_secs(10)
+        );
+    }
+
+    #[test]
+    fn custom_values_from_toml() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r"
+            [retry]
+            max_attempts = 7
+            initial_delay = 15
+            max_delay = 180
+            multiplier = 1.5
+        ",
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(config.webhook.retry_policy.max_attempts, 7);
+        assert_eq!(
+            config.webhook.retry_policy.initial_delay,
+            Duration::from_secs(15)
+        );
+        assert_eq!(
+            config.webhook.retry_policy.max_delay,
+            Duration::from_secs(180)
+        );
+        // Use approximate comparison for floats
+        assert!((config.webhook.retry_policy.multiplier - 1.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn zero_attempts_returns_error() {
+        let cli = cli(&[
+            "--url",
+            "https://example.com",
+            "--ip-version",
+            "ipv4",
+            "--retry-max",
+            "0",
+        ]);
+        let result = ValidatedConfig::from_raw(&cli, None);
+
+        assert!(matches!(result, Err(ConfigError::InvalidRetry(_))));
+    }
+
+    #[test]
+    fn zero_delay_returns_error() {
+        let cli = cli(&[
+            "--url",
+            "https://example.com",
+            "--ip-version",
+            "ipv4",
+            "--retry-delay",
+            "0",
+        ]);
+        let result = ValidatedConfig::from_raw(&cli, None);
+
+        assert!(matches!(result, Err(ConfigError::InvalidRetry(_))));
+    }
+}
+
+mod retry_policy_validation {
+    use super::*;
+    use crate::webhook::JitterMode;
+
+    #[test]
+    fn jitter_defaults_to_none() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.webhook.retry_policy.jitter, JitterMode::None);
+    }
+
+    #[test]
+    fn jitter_is_parsed() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        for (value, expected) in [
+            ("full", JitterMode::Full),
+            ("Equal", JitterMode::Equal),
+            ("none", JitterMode::None),
+        ] {
+            let toml = toml(&format!("[retry]\njitter = \"{value}\""));
+            let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+            assert_eq!(config.webhook.retry_policy.jitter, expected, "{value}");
+        }
+    }
+
+    #[test]
+    fn unknown_jitter_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml("[retry]\njitter = \"random\"");
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidJitter { value }) if value == "random"
+        ));
+    }
+
+    #[test]
+    fn zero_multiplier_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r"
+            [retry]
+            multiplier = 0.0
+        ",
+        );
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(result, Err(ConfigError::InvalidRetry(_))));
+    }
+
+    #[test]
+    fn negative_multiplier_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r"
+            [retry]
+            multiplier = -1.5
+        ",
+        );
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(result, Err(ConfigError::InvalidRetry(_))));
+    }
+
+    #[test]
+    fn nan_multiplier_returns_error() {
+        // NaN values must be rejected
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        // Cannot specify NaN directly in TOML; test via manual construction
+        let mut toml_config = TomlConfig::parse("[webhook]").unwrap();
+        // Use a special value that would pass <= 0.0 check but is not finite
+        toml_config.retry.multiplier = Some(f64::NAN);
+
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml_config));
+
+        assert!(matches!(result, Err(ConfigError::InvalidRetry(_))));
+    }
+
+    #[test]
+    fn infinity_multiplier_returns_error() {
+        // Infinity values must be rejected
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let mut toml_config = TomlConfig::parse("[webhook]").unwrap();
+        toml_config.retry.multiplier = Some(f64::INFINITY);
+
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml_config));
+
+        assert!(matches!(result, Err(ConfigError::InvalidRetry(_))));
+    }
+
+    #[test]
+    fn neg_infinity_multiplier_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let mut toml_config = TomlConfig::parse("[webhook]").unwrap();
+        toml_config.retry.multiplier = Some(f64::NEG_INFINITY);
+
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml_config));
+
+        assert!(matches!(result, Err(ConfigError::InvalidRetry(_))));
+    }
+}
+
+mod retry_delay_validation {
+    use super::*;
+
+    #[test]
+    fn max_delay_less_than_initial_delay_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r"
+            [retry]
+            initial_delay = 30
+            max_delay = 10
+        ",
+        );
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(result, Err(ConfigError::InvalidRetry(msg)) if msg.contains("max_delay")));
+    }
+
+    #[test]
+    fn max_delay_equal_to_initial_delay_is_valid() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r"
+            [retry]
+            initial_delay = 30
+            max_delay = 30
+        ",
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(
+            config.webhook.retry_policy.initial_delay,
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            config.webhook.retry_policy.max_delay,
+            Duration::from_secs(30)
+        );
+    }
+
+    #[test]
+    fn max_delay_greater_than_initial_delay_is_valid() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r"
+            [retry]
+            initial_delay = 5
+            max_delay = 120
+        ",
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(
+            config.webhook.retry_policy.initial_delay,
+            Duration::from_secs(5)
+        );
+        assert_eq!(
+            config.webhook.retry_policy.max_delay,
+            Duration::from_secs(120)
+        );
+    }
+}
+
+mod retry_overrides {
+    use super::*;
+    use crate::webhook::RetryOverrides;
+
+    #[test]
+    fn none_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.webhook.retry_overrides, RetryOverrides::new());
+    }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn body_template_and_query_from_toml() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [retry]
+            body_template = "retry {{attempt}}"
+            query = "retry=true"
+        "#,
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(
+            config.webhook.retry_overrides,
+            RetryOverrides::new()
+                .with_body_template("retry {{attempt}}")
+                .with_query("retry", "true")
+        );
+    }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn invalid_retry_template_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [retry]
+            body_template = "{{#if}}"
+        "#,
+        );
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(result, Err(ConfigError::InvalidTemplate { .. })));
+    }
+
+    #[cfg(not(feature = "templates"))]
+    #[test]
+    fn template_without_feature_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [retry]
+            body_template = "retry {{attempt}}"
+        "#,
+        );
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::FeatureDisabled {
+                feature: "templates",
+                option: "retry.body_template",
+            })
+        ));
+    }
+
+    #[test]
+    fn query_without_equals_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [retry]
+            query = "retry"
+        "#,
+        );
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(
+            matches!(result, Err(ConfigError::InvalidRetry(msg)) if msg.contains("name=value"))
+        );
+    }
+
+    #[test]
+    fn query_with_empty_name_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [retry]
+            query = "=true"
+        "#,
+        );
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(result, Err(ConfigError::InvalidRetry(_))));
+    }
+}
+
+mod shutdown_grace {
+    use super::*;
+
+    #[test]
+    fn default_is_zero() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.webhook.shutdown_grace, Duration::ZERO);
+    }
+
+    #[test]
+    fn read_from_retry_section() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r"
+            [retry]
+            shutdown_grace = 30
+        ",
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(config.webhook.shutdown_grace, Duration::from_secs(30));
+    }
+}
@@ -0,0 +1,114 @@
+//! Tests for the change filters of every sink.
+
+use std::net::IpAddr;
+
+use super::*;
+
+const EXEC: &str = "[action.exec]\ncommand = [\"true\"]\n";
+const SLACK: &str = "[notify.slack]\nwebhook_url = \"https://hooks.slack.com/services/x\"\n";
+
+fn routing(ip_version: &str, content: &str) -> Result<ValidatedConfig, ConfigError> {
+    let cli = cli(&["--url", "https://example.com", "--ip-version", ip_version]);
+    ValidatedConfig::from_raw(&cli, Some(&toml(content)))
+}
+
+fn address(address: &str) -> IpAddr {
+    address.parse().unwrap()
+}
+
+#[test]
+fn sinks_receive_everything_by_default() {
+    let config = routing("both", &format!("{EXEC}{SLACK}")).unwrap();
+
+    let names: Vec<_> = config
+        .outputs
+        .sink_routes
+        .iter()
+        .map(|(name, _)| *name)
+        .collect();
+    assert_eq!(names, ["action.exec", "notify.slack"]);
+    for (_, route) in &config.outputs.sink_routes {
+        assert!(route.is_all() && !route.is_version_split() && !route.is_added_only());
+    }
+    assert!(routing("both", "").unwrap().outputs.sink_routes.is_empty());
+}
+
+#[test]
+fn sinks_are_filtered_by_adapter_version_and_removal() {
+    let config = routing(
+        "both",
+        &format!("{SLACK}adapters = [\"^eth\"]\nip_version = \"ipv4\"\nadded_only = true"),
+    )
+    .unwrap();
+
+    let (name, route) = &config.outputs.sink_routes[0];
+    assert_eq!(*name, "notify.slack");
+    assert!(route.matches("eth0") && !route.matches("wg0"));
+    assert!(route.matches_address(Some(address("192.0.2.1"))));
+    assert!(!route.matches_address(Some(address("2001:db8::1"))));
+    // Hostname and DNS events still reach the chat
+    assert!(route.matches_address(None));
+    assert!(route.is_added_only());
+}
+
+#[test]
+fn webhooks_take_the_same_filters() {
+    let config = routing(
+        "both",
+        r#"
+        [webhook]
+        added_only = true
+
+        [[webhook.targets]]
+        url = "https://a.example/"
+        ip_version = "ipv6"
+        added_only = true
+        "#,
+    )
+    .unwrap();
+
+    assert!(config.webhook.route.is_added_only());
+    let route = &config.webhook.targets[0].route;
+    assert!(route.is_added_only());
+    assert!(!route.matches_address(Some(address("192.0.2.1"))));
+    assert!(route.matches_address(Some(address("2001:db8::1"))));
+}
+
+#[test]
+fn version_must_be_monitored() {
+    let result = routing("ipv4", &format!("{EXEC}ip_version = \"ipv6\""));
+
+    assert!(
+        matches!(result, Err(ConfigError::InvalidExec(ref reason))
+            if reason == "ip_version: IPv6 is not monitored (ip_version is IPv4)"),
+        "{result:?}"
+    );
+}
+
+#[test]
+fn errors_name_their_section() {
+    let result = routing("both", &format!("{SLACK}adapters = [\"(\"]"));
+    assert!(
+        matches!(result, Err(ConfigError::InvalidNotify(ref reason))
+            if reason.starts_with("slack: adapters: invalid pattern '('")),
+        "{result:?}"
+    );
+
+    let result = routing(
+        "both",
+        "[mqtt]\nbroker = \"mqtt://broker\"\ntopic = \"t\"\nip_version = \"ipv5\"",
+    );
+    assert!(
+        matches!(result, Err(ConfigError::InvalidMqtt(ref reason)) if reason.starts_with("ip_version: ")),
+        "{result:?}"
+    );
+
+    let result = routing(
+        "both",
+        "[[webhook.targets]]\nurl = \"https://a.example/\"\nip_version = \"ipv5\"",
+    );
+    assert!(
+        matches!(result, Err(ConfigError::InvalidTarget(ref reason)) if reason.contains("ip_version: ")),
+        "{result:?}"
+    );
+}
@@ -1,4 +1,5 @@
-//! Tests for runtime behavior configuration: retry policy, poll interval, flags.
+//! Tests for monitor runtime configuration: poll intervals, fetch timeout, debounce,
+//! adaptive polling, poll-only mode, watchdog, adapter priority, address source.
 
 use std::time::Duration;
 
@@ -12,7 +13,7 @@ mod poll_interval {
         let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
-        assert_eq!(config.poll_interval, Duration::from_secs(60));
+        assert_eq!(config.monitor.poll_interval, Duration::from_secs(60));
     }
 
     #[test]
@@ -27,7 +28,7 @@ mod poll_interval {
         ]);
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
-        assert_eq!(config.poll_interval, Duration::from_secs(120));
+        assert_eq!(config.monitor.poll_interval, Duration::from_secs(120));
     }
 
     #[test]
@@ -41,7 +42,7 @@ mod poll_interval {
         );
         let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
 
-        assert_eq!(config.poll_interval, Duration::from_secs(300));
+        assert_eq!(config.monitor.poll_interval, Duration::from_secs(300));
     }
 
     #[test]
@@ -63,7 +64,7 @@ mod poll_interval {
         );
         let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
 
-        assert_eq!(config.poll_interval, Duration::from_secs(120));
+        assert_eq!(config.monitor.poll_interval, Duration::from_secs(120));
     }
 
     #[test]
@@ -88,237 +89,329 @@ mod poll_interval {
     }
 }
 
-mod retry_policy {
+mod poll_interval_v6 {
     use super::*;
 
+    fn config(ip_version: &str, extra: &[&str], v6_seconds: u64) -> ValidatedConfig {
+        let mut args = vec!["--url", "https://example.com", "--ip-version", ip_version];
+        args.extend_from_slice(extra);
+        let toml = toml(&format!(
+            "[monitor]\npoll_interval = 60\npoll_interval_v6 = {v6_seconds}"
+        ));
+        ValidatedConfig::from_raw(&cli(&args), Some(&toml)).unwrap()
+    }
+
     #[test]
-    fn default_values() {
-        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+    fn default_is_none() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "both"]);
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
-        assert_eq!(config.retry_policy.max_attempts, 3);
-        assert_eq!(config.retry_policy.initial_delay, Duration::from_secs(5));
+        assert_eq!(config.monitor.poll_interval_v6, None);
     }
 
     #[test]
-    fn custom_values_from_cli() {
-        let cli = cli(&[
-            "--url",
-            "https://example.com",
-            "--ip-version",
-            "ipv4",
-            "--retry-max",
-            "5",
-            "--retry-delay",
-            "10",
-        ]);
-        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+    fn both_versions_get_separate_interval() {
+        let config = config("both", &[], 15);
 
-        assert_eq!(config.retry_policy.max_attempts, 5);
-        assert_eq!(config.retry_policy.initial_delay, Duration::from_secs(10));
+        assert_eq!(config.monitor.poll_interval, Duration::from_secs(60));
+        assert_eq!(
+            config.monitor.poll_interval_v6,
+            Some(Duration::from_secs(15))
+        );
     }
 
     #[test]
-    fn custom_values_from_toml() {
-        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
-        let toml = toml(
-            r"
-            [retry]
-            max_attempts = 7
-            initial_delay = 15
-            max_delay = 180
-            multiplier = 1.5
-        ",
-        );
-        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+    fn equal_interval_is_not_separate() {
+        let config = config("both", &[], 60);
 
-        assert_eq!(config.retry_policy.max_attempts, 7);
-        assert_eq!(config.retry_policy.initial_delay, Duration::from_secs(15));
-        assert_eq!(config.retry_policy.max_delay, Duration::from_secs(180));
-        // Use approximate comparison for floats
-        assert!((config.retry_policy.multiplier - 1.5).abs() < f64::EPSILON);
+        assert_eq!(config.monitor.poll_interval_v6, None);
     }
 
     #[test]
-    fn zero_attempts_returns_error() {
-        let cli = cli(&[
-            "--url",
-            "https://example.com",
-            "--ip-version",
-            "ipv4",
-            "--retry-max",
-            "0",
-        ]);
-        let result = ValidatedConfig::from_raw(&cli, None);
+    fn ipv6_only_polls_at_ipv6_interval() {
+        let config = config("ipv6", &[], 15);
 
-        assert!(matches!(result, Err(ConfigError::InvalidRetry(_))));
+        assert_eq!(config.monitor.poll_interval, Duration::from_secs(15));
+        assert_eq!(config.monitor.poll_interval_v6, None);
     }
 
     #[test]
-    fn zero_delay_returns_error() {
-        let cli = cli(&[
-            "--url",
-            "https://example.com",
-            "--ip-version",
-            "ipv4",
-            "--retry-delay",
-            "0",
-        ]);
-        let result = ValidatedConfig::from_raw(&cli, None);
+    fn cli_interval_overrides_for_ipv6_only() {
+        let config = config("ipv6", &["--poll-interval", "30"], 15);
+
+        assert_eq!(config.monitor.poll_interval, Duration::from_secs(30));
+        assert_eq!(config.monitor.poll_interval_v6, None);
+    }
+
+    #[test]
+    fn ignored_for_ipv4_only() {
+        let config = config("ipv4", &[], 15);
 
-        assert!(matches!(result, Err(ConfigError::InvalidRetry(_))));
+        assert_eq!(config.monitor.poll_interval, Duration::from_secs(60));
+        assert_eq!(config.monitor.poll_interval_v6, None);
+    }
+
+    #[test]
+    fn zero_interval_returns_error() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "both"]);
+        let toml = toml("[monitor]\npoll_interval_v6 = 0");
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidDuration {
+                field: "poll_interval_v6",
+                ..
+            })
+        ));
     }
 }
 
-mod retry_policy_validation {
+mod fetch_timeout {
     use super::*;
 
     #[test]
-    fn zero_multiplier_returns_error() {
+    fn default_is_30_seconds() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.monitor.fetch_timeout, Duration::from_secs(30));
+    }
+
+    #[test]
+    fn from_toml() {
         let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
         let toml = toml(
             r"
-            [retry]
-            multiplier = 0.0
+            [monitor]
+            fetch_timeout = 5
         ",
         );
-        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
 
-        assert!(matches!(result, Err(ConfigError::InvalidRetry(_))));
+        assert_eq!(config.monitor.fetch_timeout, Duration::from_secs(5));
     }
 
     #[test]
-    fn negative_multiplier_returns_error() {
+    fn zero_returns_error() {
         let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
         let toml = toml(
             r"
-            [retry]
-            multiplier = -1.5
+            [monitor]
+            fetch_timeout = 0
         ",
         );
         let result = ValidatedConfig::from_raw(&cli, Some(&toml));
 
-        assert!(matches!(result, Err(ConfigError::InvalidRetry(_))));
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidDuration {
+                field: "fetch_timeout",
+                ..
+            })
+        ));
     }
+}
 
-    #[test]
-    fn nan_multiplier_returns_error() {
-        // NaN values must be rejected
-        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
-        // Cannot specify NaN directly in TOML; test via manual construction
-        let mut toml_config = TomlConfig::parse("[webhook]").unwrap();
-        // Use a special value that would pass <= 0.0 check but is not finite
-        toml_config.retry.multiplier = Some(f64::NAN);
-
-        let result = ValidatedConfig::from_raw(&cli, Some(&toml_config));
+mod debounce {
+    use super::*;
+    use crate::monitor::DebouncePolicy;
 
-        assert!(matches!(result, Err(ConfigError::InvalidRetry(_))));
+    fn config_with(monitor: &str) -> Result<ValidatedConfig, ConfigError> {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(&format!("[monitor]\n{monitor}"));
+        ValidatedConfig::from_raw(&cli, Some(&toml))
     }
 
     #[test]
-    fn infinity_multiplier_returns_error() {
-        // Infinity values must be rejected
+    fn default_is_two_seconds() {
         let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
-        let mut toml_config = TomlConfig::parse("[webhook]").unwrap();
-        toml_config.retry.multiplier = Some(f64::INFINITY);
-
-        let result = ValidatedConfig::from_raw(&cli, Some(&toml_config));
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
-        assert!(matches!(result, Err(ConfigError::InvalidRetry(_))));
+        assert_eq!(config.monitor.debounce, Some(DebouncePolicy::default()));
     }
 
     #[test]
-    fn neg_infinity_multiplier_returns_error() {
-        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
-        let mut toml_config = TomlConfig::parse("[webhook]").unwrap();
-        toml_config.retry.multiplier = Some(f64::NEG_INFINITY);
+    fn from_toml() {
+        let config = config_with("debounce_ms = 500").unwrap();
+
+        assert_eq!(
+            config.monitor.debounce,
+            Some(DebouncePolicy::new(Duration::from_millis(500)))
+        );
+    }
 
-        let result = ValidatedConfig::from_raw(&cli, Some(&toml_config));
+    #[test]
+    fn zero_disables_debouncing() {
+        let config = config_with("debounce_ms = 0").unwrap();
 
-        assert!(matches!(result, Err(ConfigError::InvalidRetry(_))));
+        assert_eq!(config.monitor.debounce, None);
     }
-}
 
-mod retry_delay_validation {
-    use super::*;
+    #[test]
+    fn must_be_shorter_than_poll_interval() {
+        let result = config_with("poll_interval = 5\ndebounce_ms = 5000");
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidDuration {
+                field: "debounce_ms",
+                ..
+            })
+        ));
+    }
 
     #[test]
-    fn max_delay_less_than_initial_delay_returns_error() {
-        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+    fn checked_against_ipv6_interval() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "both"]);
         let toml = toml(
             r"
-            [retry]
-            initial_delay = 30
-            max_delay = 10
+            [monitor]
+            poll_interval = 60
+            poll_interval_v6 = 3
+            debounce_ms = 3000
         ",
         );
+
         let result = ValidatedConfig::from_raw(&cli, Some(&toml));
 
-        assert!(matches!(result, Err(ConfigError::InvalidRetry(msg)) if msg.contains("max_delay")));
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidDuration {
+                field: "debounce_ms",
+                ..
+            })
+        ));
     }
 
     #[test]
-    fn max_delay_equal_to_initial_delay_is_valid() {
-        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
-        let toml = toml(
-            r"
-            [retry]
-            initial_delay = 30
-            max_delay = 30
-        ",
-        );
-        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+    fn default_kept_with_short_poll_interval() {
+        let config = config_with("poll_interval = 1").unwrap();
 
-        assert_eq!(config.retry_policy.initial_delay, Duration::from_secs(30));
-        assert_eq!(config.retry_policy.max_delay, Duration::from_secs(30));
+        assert_eq!(config.monitor.debounce, Some(DebouncePolicy::default()));
+    }
+}
+
+mod event_quiet {
+    use super::*;
+
+    fn config_with(monitor: &str) -> Result<ValidatedConfig, ConfigError> {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(&format!("[monitor]\n{monitor}"));
+        ValidatedConfig::from_raw(&cli, Some(&toml))
     }
 
     #[test]
-    fn max_delay_greater_than_initial_delay_is_valid() {
+    fn default_is_250_milliseconds() {
         let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
-        let toml = toml(
-            r"
-            [retry]
-            initial_delay = 5
-            max_delay = 120
-        ",
-        );
-        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.monitor.event_quiet, Some(Duration::from_millis(250)));
+    }
 
-        assert_eq!(config.retry_policy.initial_delay, Duration::from_secs(5));
-        assert_eq!(config.retry_policy.max_delay, Duration::from_secs(120));
+    #[test]
+    fn from_toml() {
+        let config = config_with("event_quiet_ms = 1000").unwrap();
+
+        assert_eq!(config.monitor.event_quiet, Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn zero_disables_coalescing() {
+        let config = config_with("event_quiet_ms = 0").unwrap();
+
+        assert_eq!(config.monitor.event_quiet, None);
+    }
+
+    #[test]
+    fn must_be_shorter_than_poll_interval() {
+        let result = config_with("poll_interval = 2\nevent_quiet_ms = 2000");
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidDuration {
+                field: "event_quiet_ms",
+                ..
+            })
+        ));
     }
 }
 
-mod dry_run_and_verbose {
+mod adaptive {
     use super::*;
 
+    fn config_with(monitor: &str) -> Result<ValidatedConfig, ConfigError> {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(&format!("[monitor]\n{monitor}"));
+        ValidatedConfig::from_raw(&cli, Some(&toml))
+    }
+
     #[test]
-    fn dry_run_flag() {
-        let cli = cli(&[
-            "--url",
-            "https://example.com",
-            "--ip-version",
-            "ipv4",
-            "--dry-run",
-        ]);
-        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+    fn disabled_by_default() {
+        let config = config_with("poll_interval_min = 10").unwrap();
 
-        assert!(config.dry_run);
+        assert_eq!(config.monitor.adaptive, None);
+        assert_eq!(config.monitor.poll_interval, Duration::from_secs(60));
     }
 
     #[test]
-    fn verbose_flag() {
-        let cli = cli(&[
-            "--url",
-            "https://example.com",
-            "--ip-version",
-            "ipv4",
-            "--verbose",
-        ]);
-        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+    fn defaults_to_poll_interval_and_ten_times_it() {
+        let config = config_with("adaptive = true\npoll_interval = 30").unwrap();
 
-        assert!(config.verbose);
+        let policy = config.monitor.adaptive.unwrap();
+        assert_eq!(policy.min(), Duration::from_secs(30));
+        assert_eq!(policy.max(), Duration::from_secs(300));
+    }
+
+    #[test]
+    fn min_replaces_poll_interval() {
+        let config =
+            config_with("adaptive = true\npoll_interval_min = 10\npoll_interval_max = 120")
+                .unwrap();
+
+        let policy = config.monitor.adaptive.unwrap();
+        assert_eq!(policy.min(), Duration::from_secs(10));
+        assert_eq!(policy.max(), Duration::from_secs(120));
+        assert_eq!(config.monitor.poll_interval, Duration::from_secs(10));
+    }
+
+    #[test]
+    fn debounce_must_be_shorter_than_min() {
+        let result = config_with("adaptive = true\npoll_interval_min = 2\ndebounce_ms = 2000");
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidDuration {
+                field: "debounce_ms",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn zero_min_returns_error() {
+        let result = config_with("adaptive = true\npoll_interval_min = 0");
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidDuration {
+                field: "poll_interval_min",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn max_below_min_returns_error() {
+        let result = config_with("adaptive = true\npoll_interval_min = 60\npoll_interval_max = 30");
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidDuration {
+                field: "poll_interval_max",
+                ..
+            })
+        ));
     }
 }
 
@@ -336,7 +429,7 @@ mod poll_only {
         );
         let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
 
-        assert!(config.poll_only);
+        assert!(config.monitor.poll_only);
     }
 
     #[test]
@@ -356,6 +449,211 @@ mod poll_only {
         );
         let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
 
-        assert!(config.poll_only);
+        assert!(config.monitor.poll_only);
+    }
+}
+
+mod watchdog {
+    use super::*;
+    use crate::monitor::WatchdogAction;
+
+    #[test]
+    fn logs_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.monitor.watchdog, WatchdogAction::Log);
+    }
+
+    #[test]
+    fn exit_from_toml() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [monitor]
+            watchdog = "exit"
+        "#,
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(config.monitor.watchdog, WatchdogAction::Exit);
+    }
+
+    #[test]
+    fn off_disables() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [monitor]
+            watchdog = "OFF"
+        "#,
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(config.monitor.watchdog, WatchdogAction::Disabled);
+    }
+
+    #[test]
+    fn invalid_value_rejected() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [monitor]
+            watchdog = "restart"
+        "#,
+        );
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidWatchdog { value }) if value == "restart"
+        ));
+    }
+}
+
+mod adapter_priority {
+    use super::*;
+
+    #[test]
+    fn empty_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert!(config.monitor.adapter_priority.is_empty());
+    }
+
+    #[test]
+    fn keeps_toml_order() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [monitor]
+            adapter_priority = ["Ethernet", "Wi-Fi"]
+        "#,
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        assert_eq!(
+            config.monitor.adapter_priority.names(),
+            ["Ethernet", "Wi-Fi"]
+        );
+    }
+
+    #[test]
+    fn duplicate_name_rejected() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [monitor]
+            adapter_priority = ["Ethernet", "Wi-Fi", "Ethernet"]
+        "#,
+        );
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidAdapterPriority(reason)) if reason.contains("'Ethernet'")
+        ));
+    }
+
+    #[test]
+    fn empty_name_rejected() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml(
+            r#"
+            [monitor]
+            adapter_priority = [" "]
+        "#,
+        );
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidAdapterPriority(_))
+        ));
+    }
+}
+
+mod address_source {
+    use super::*;
+    use crate::network::IpVersion;
+    use crate::network::public::{AddressSource, PublicEndpoint};
+
+    #[test]
+    fn adapters_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.monitor.address_source, AddressSource::Adapter);
+        assert!(config.monitor.public_address.is_none());
+    }
+
+    #[test]
+    fn public_uses_default_endpoints_of_ip_version() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv6"]);
+        let toml = toml("[monitor]\nsource = \"public\"");
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        let public = config.monitor.public_address.unwrap();
+        assert_eq!(config.monitor.address_source, AddressSource::Public);
+        assert_eq!(public.endpoints, PublicEndpoint::defaults(IpVersion::V6));
+        assert_eq!(public.timeout, Duration::from_secs(5));
+    }
+
+    #[test]
+    fn both_with_endpoints_and_timeout() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "both"]);
+        let toml = toml(
+            r#"
+            [monitor]
+            source = "Both"
+
+            [monitor.public]
+            endpoints = ["stun:stun.example.com:19302", "https://ip.example.com/"]
+            timeout = 2
+        "#,
+        );
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+        let public = config.monitor.public_address.unwrap();
+        assert_eq!(config.monitor.address_source, AddressSource::Both);
+        assert_eq!(
+            public
+                .endpoints
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["stun:stun.example.com:19302", "https://ip.example.com/"]
+        );
+        assert_eq!(public.timeout, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn invalid_source_rejected() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let toml = toml("[monitor]\nsource = \"router\"");
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidAddressSource { value }) if value == "router"
+        ));
+    }
+
+    #[test]
+    fn invalid_public_section_rejected() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        for content in [
+            "[monitor.public]\ntimeout = 2",
+            "[monitor]\nsource = \"public\"\n[monitor.public]\ntimeout = 0",
+            "[monitor]\nsource = \"public\"\n[monitor.public]\nendpoints = [\"ftp://x\"]",
+        ] {
+            let result = ValidatedConfig::from_raw(&cli, Some(&toml(content)));
+
+            assert!(
+                matches!(result, Err(ConfigError::InvalidPublicAddress(_))),
+                "{content}"
+            );
+        }
     }
 }
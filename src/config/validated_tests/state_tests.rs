@@ -0,0 +1,173 @@
+//! Tests for the state file location (`monitor.state`).
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use super::*;
+use crate::config::state::{unix_state_dir, windows_state_dir};
+
+fn base() -> Cli {
+    cli(&["--url", "https://example.com", "--ip-version", "ipv4"])
+}
+
+#[test]
+fn disabled_by_default() {
+    let config = ValidatedConfig::from_raw(&base(), None).unwrap();
+
+    assert_eq!(config.state_file, None);
+    assert!(!config.no_lock);
+}
+
+#[test]
+fn no_lock_from_cli() {
+    let cli = cli(&[
+        "--url",
+        "https://example.com",
+        "--ip-version",
+        "ipv4",
+        "--state-file",
+        "state.json",
+        "--no-lock",
+    ]);
+    let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+    assert!(config.no_lock);
+}
+
+#[test]
+fn file_mode_needs_state_file() {
+    let toml = toml(
+        r#"
+        [monitor]
+        state = "file"
+    "#,
+    );
+    let config = ValidatedConfig::from_raw(&base(), Some(&toml)).unwrap();
+
+    assert_eq!(config.state_file, None);
+}
+
+#[test]
+fn auto_picks_a_default_file() {
+    let toml = toml(
+        r#"
+        [monitor]
+        state = "auto"
+    "#,
+    );
+    let config = ValidatedConfig::from_raw(&base(), Some(&toml)).unwrap();
+
+    let path = config.state_file.unwrap();
+    assert!(
+        path.ends_with(Path::new("ddns-a").join("state.json")),
+        "{}",
+        path.display()
+    );
+}
+
+#[test]
+fn state_file_wins_over_auto() {
+    let toml = toml(
+        r#"
+        [monitor]
+        state = "auto"
+        state_file = "mine.json"
+    "#,
+    );
+    let config = ValidatedConfig::from_raw(&base(), Some(&toml)).unwrap();
+
+    assert_eq!(config.state_file, Some(PathBuf::from("mine.json")));
+}
+
+#[test]
+fn cli_state_file_wins_over_auto() {
+    let cli = cli(&[
+        "--url",
+        "https://example.com",
+        "--ip-version",
+        "ipv4",
+        "--state-file",
+        "cli.json",
+    ]);
+    let toml = toml(
+        r#"
+        [monitor]
+        state = "auto"
+    "#,
+    );
+    let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+    assert_eq!(config.state_file, Some(PathBuf::from("cli.json")));
+}
+
+#[test]
+fn unknown_mode_is_rejected() {
+    let toml = toml(
+        r#"
+        [monitor]
+        state = "default"
+    "#,
+    );
+    let result = ValidatedConfig::from_raw(&base(), Some(&toml));
+
+    assert!(
+        matches!(result, Err(ConfigError::InvalidState(reason)) if reason.contains("'default'"))
+    );
+}
+
+mod unix_dir {
+    use super::*;
+
+    #[test]
+    fn root_uses_var_lib() {
+        let dir = unix_state_dir(true, Some(OsString::from("/state")), None);
+
+        assert_eq!(dir, Some(PathBuf::from("/var/lib/ddns-a")));
+    }
+
+    #[test]
+    fn users_use_xdg_state_home() {
+        let dir = unix_state_dir(
+            false,
+            Some(OsString::from("/home/me/state")),
+            Some(PathBuf::from("/home/me")),
+        );
+
+        assert_eq!(dir, Some(PathBuf::from("/home/me/state/ddns-a")));
+    }
+
+    #[test]
+    fn relative_or_missing_xdg_state_home_falls_back_to_home() {
+        for xdg in [None, Some(OsString::new()), Some(OsString::from("state"))] {
+            let dir = unix_state_dir(false, xdg, Some(PathBuf::from("/home/me")));
+
+            assert_eq!(dir, Some(PathBuf::from("/home/me/.local/state/ddns-a")));
+        }
+    }
+
+    #[test]
+    fn no_directory_without_home() {
+        assert_eq!(unix_state_dir(false, None, None), None);
+    }
+}
+
+mod windows_dir {
+    use super::*;
+
+    #[test]
+    fn uses_program_data() {
+        let dir = windows_state_dir(Some(OsString::from("D:\\Data")));
+
+        assert_eq!(dir, PathBuf::from("D:\\Data").join("ddns-a"));
+    }
+
+    #[test]
+    fn defaults_to_c_program_data() {
+        for program_data in [None, Some(OsString::new())] {
+            assert_eq!(
+                windows_state_dir(program_data),
+                PathBuf::from(r"C:\ProgramData").join("ddns-a")
+            );
+        }
+    }
+}
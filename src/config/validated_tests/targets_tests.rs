@@ -0,0 +1,403 @@
+//! Tests for the additional webhook targets and the per-version URLs.
+
+use super::*;
+
+mod targets {
+    use super::*;
+    use std::time::Duration;
+
+    fn targets(content: &str) -> Result<ValidatedConfig, ConfigError> {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        ValidatedConfig::from_raw(&cli, Some(&toml(content)))
+    }
+
+    #[test]
+    fn none_by_default() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert!(config.webhook.targets.is_empty());
+    }
+
+    #[test]
+    fn parses_target_settings() {
+        let config = targets(
+            r#"
+            [[webhook.targets]]
+            name = "private-api"
+            url = "https://api.example.com/ddns"
+            method = "PUT"
+            bearer = "secret"
+            headers = { X-Api-Key = "key" }
+        "#,
+        )
+        .unwrap();
+
+        let target = &config.webhook.targets[0];
+        assert_eq!(target.name, "private-api");
+        assert_eq!(target.url.as_str(), "https://api.example.com/ddns");
+        assert_eq!(target.method, Method::PUT);
+        assert_eq!(target.headers["authorization"], "Bearer secret");
+        assert_eq!(target.headers["x-api-key"], "key");
+        assert!(target.body_template.is_none());
+        assert_eq!(target.delivery_mode, crate::webhook::DeliveryMode::Batched);
+    }
+
+    #[test]
+    fn parses_delivery_mode() {
+        let config = targets(
+            r#"
+            [[webhook.targets]]
+            url = "https://api.example.com/ddns"
+            delivery = "per_change"
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.webhook.targets[0].delivery_mode,
+            crate::webhook::DeliveryMode::PerChange
+        );
+    }
+
+    #[test]
+    fn defaults_to_host_name_and_post() {
+        let config = targets(
+            r#"
+            [[webhook.targets]]
+            url = "https://hooks.example.net/a"
+
+            [[webhook.targets]]
+            url = "https://other.example.net/b"
+        "#,
+        )
+        .unwrap();
+
+        let names: Vec<_> = config
+            .webhook
+            .targets
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect();
+        assert_eq!(names, ["hooks.example.net", "other.example.net"]);
+        assert_eq!(config.webhook.targets[0].method, Method::POST);
+    }
+
+    #[test]
+    fn retry_falls_back_to_main_policy() {
+        let config = targets(
+            r#"
+            [retry]
+            max_attempts = 4
+            initial_delay = 2
+            jitter = "equal"
+
+            [[webhook.targets]]
+            url = "https://api.example.com/"
+            [webhook.targets.retry]
+            max_attempts = 8
+        "#,
+        )
+        .unwrap();
+
+        let policy = &config.webhook.targets[0].retry_policy;
+        assert_eq!(policy.max_attempts, 8);
+        assert_eq!(policy.initial_delay, Duration::from_secs(2));
+        assert_eq!(policy.jitter, crate::webhook::JitterMode::Equal);
+        assert_eq!(config.webhook.retry_policy.max_attempts, 4);
+    }
+
+    #[test]
+    fn timeouts_fall_back_to_http() {
+        let config = targets(
+            r#"
+            [http]
+            connect_timeout = 4
+            request_timeout = 30
+            pool_max_idle_per_host = 2
+
+            [[webhook.targets]]
+            url = "https://api.example.com/"
+            request_timeout = 8
+
+            [[webhook.targets]]
+            url = "https://other.example.com/"
+        "#,
+        )
+        .unwrap();
+
+        let own = &config.webhook.targets[0].connection;
+        assert_eq!(own.connect_timeout, Duration::from_secs(4));
+        assert_eq!(own.request_timeout, Duration::from_secs(8));
+        assert_eq!(own.pool_max_idle_per_host, 2);
+        assert_eq!(config.webhook.targets[1].connection, config.http.connection);
+        assert_eq!(
+            config.http.connection.request_timeout,
+            Duration::from_secs(30)
+        );
+    }
+
+    #[test]
+    fn circuit_breaker_falls_back_to_main_policy() {
+        let config = targets(
+            r#"
+            [webhook.circuit_breaker]
+            failures = 4
+            cooldown = 60
+
+            [[webhook.targets]]
+            url = "https://api.example.com/"
+            [webhook.targets.circuit_breaker]
+            failures = 2
+
+            [[webhook.targets]]
+            url = "https://other.example.com/"
+        "#,
+        )
+        .unwrap();
+
+        let own = config.webhook.targets[0].circuit_breaker.unwrap();
+        assert_eq!(own.failure_threshold, 2);
+        assert_eq!(own.cooldown, Duration::from_secs(60));
+        assert_eq!(
+            config.webhook.targets[1].circuit_breaker,
+            config.webhook.circuit_breaker
+        );
+    }
+
+    #[test]
+    fn circuit_breaker_can_be_enabled_for_one_target() {
+        let config = targets(
+            r#"
+            [[webhook.targets]]
+            url = "https://api.example.com/"
+            [webhook.targets.circuit_breaker]
+            cooldown = 30
+
+            [[webhook.targets]]
+            url = "https://other.example.com/"
+        "#,
+        )
+        .unwrap();
+
+        let own = config.webhook.targets[0].circuit_breaker.unwrap();
+        assert_eq!(
+            own.failure_threshold,
+            crate::webhook::DEFAULT_FAILURE_THRESHOLD
+        );
+        assert_eq!(own.cooldown, Duration::from_secs(30));
+        assert_eq!(config.webhook.targets[1].circuit_breaker, None);
+        assert_eq!(config.webhook.circuit_breaker, None);
+    }
+
+    #[test]
+    fn invalid_targets_return_error() {
+        for content in [
+            "[[webhook.targets]]\nname = \"a\"",
+            "[[webhook.targets]]\nurl = \"not a url\"",
+            "[[webhook.targets]]\nurl = \"https://a.example/\"\nname = \" \"",
+            "[[webhook.targets]]\nurl = \"https://a.example/\"\nmethod = \"GE T\"",
+            "[[webhook.targets]]\nurl = \"https://a.example/\"\nheaders = { \"Bad Name\" = \"x\" }",
+            "[[webhook.targets]]\nurl = \"https://a.example/\"\nheaders = { \"X-Ip\" = \"{{json}}\" }",
+            "[[webhook.targets]]\nurl = \"https://a.example/\"\nretry = { max_attempts = 0 }",
+            "[[webhook.targets]]\nurl = \"https://a.example/\"\nretry = { max_delay = 1 }",
+            "[[webhook.targets]]\nurl = \"https://a.example/\"\nadapters = [\"(\"]",
+            "[[webhook.targets]]\nurl = \"https://a.example/\"\ndelivery = \"each\"",
+            "[[webhook.targets]]\nurl = \"https://a.example/\"\nrequest_timeout = 0",
+            "[[webhook.targets]]\nurl = \"https://a.example/\"\nconnect_timeout = 0",
+            "[[webhook.targets]]\nurl = \"https://a.example/\"\nconnect_timeout = 90",
+            "[[webhook.targets]]\nurl = \"https://a.example/\"\ncircuit_breaker = { failures = 0 }",
+        ] {
+            assert!(
+                matches!(targets(content), Err(ConfigError::InvalidTarget(_))),
+                "accepted: {content}"
+            );
+        }
+    }
+
+    #[test]
+    fn error_names_the_target() {
+        let result = targets(
+            r#"
+            [[webhook.targets]]
+            url = "https://a.example/"
+
+            [[webhook.targets]]
+            name = "b"
+            method = "GE T"
+            url = "https://b.example/"
+
+            [[webhook.targets]]
+        "#,
+        );
+
+        let Err(ConfigError::InvalidTarget(reason)) = result else {
+            panic!("unexpected result: {result:?}");
+        };
+        assert!(reason.starts_with("target 'b': "), "{reason}");
+    }
+
+    #[test]
+    fn names_must_be_unique() {
+        for content in [
+            "[[webhook.targets]]\nurl = \"https://a.example/\"\n[[webhook.targets]]\nurl = \"https://a.example/b\"",
+            "[[webhook.targets]]\nurl = \"https://a.example/\"\nname = \"webhook\"",
+            "[[webhook.targets]]\nurl = \"https://a.example/\"\nname = \"action.exec\"",
+            "[[webhook.targets]]\nurl = \"https://a.example/\"\nname = \"mqtt\"",
+            "[[webhook.targets]]\nurl = \"https://a.example/\"\nname = \"email\"",
+            "[[webhook.targets]]\nurl = \"https://a.example/\"\nname = \"notify.slack\"",
+        ] {
+            let result = targets(content);
+
+            assert!(
+                matches!(result, Err(ConfigError::InvalidTarget(ref reason)) if reason.contains("used twice")),
+                "{result:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn routes_by_adapter() {
+        let config = targets(
+            r#"
+            [webhook]
+            adapters = ["^eth0$"]
+
+            [[webhook.targets]]
+            url = "https://a.example/"
+            adapters = ["^wg", "^tun"]
+
+            [[webhook.targets]]
+            url = "https://b.example/"
+        "#,
+        )
+        .unwrap();
+
+        assert!(config.webhook.route.matches("eth0"));
+        assert!(!config.webhook.route.matches("wg0"));
+        let patterns: Vec<_> = config.webhook.targets[0].route.patterns().collect();
+        assert_eq!(patterns, ["^wg", "^tun"]);
+        assert!(config.webhook.targets[1].route.is_all());
+    }
+
+    #[test]
+    fn routes_every_adapter_by_default() {
+        let config = targets("[[webhook.targets]]\nurl = \"https://a.example/\"").unwrap();
+
+        assert!(config.webhook.route.is_all());
+        assert!(config.webhook.targets[0].route.is_all());
+    }
+
+    #[test]
+    fn invalid_main_route_returns_error() {
+        let result = targets("[webhook]\nadapters = [\"(\"]");
+
+        assert!(
+            matches!(result, Err(ConfigError::InvalidRegex { ref pattern, .. }) if pattern == "("),
+            "{result:?}"
+        );
+    }
+}
+
+mod version_urls {
+    use super::*;
+    use crate::network::IpVersion;
+
+    fn config(ip_version: &str, content: &str) -> Result<ValidatedConfig, ConfigError> {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", ip_version]);
+        ValidatedConfig::from_raw(&cli, Some(&toml(content)))
+    }
+
+    fn rejection(ip_version: &str, content: &str) -> String {
+        match config(ip_version, content) {
+            Err(ConfigError::InvalidVersionUrl(reason)) => reason,
+            other => panic!("expected InvalidVersionUrl for {content}, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn none_by_default() {
+        let config = config("both", "").unwrap();
+
+        assert!(config.webhook.version_urls.is_empty());
+    }
+
+    #[test]
+    fn parses_both_versions_ipv4_first() {
+        let config = config(
+            "both",
+            r#"
+            [webhook.ipv6]
+            url = "https://example.com/aaaa"
+            [webhook.ipv4]
+            url = "https://example.com/a"
+            "#,
+        )
+        .unwrap();
+
+        let urls: Vec<_> = config
+            .webhook
+            .version_urls
+            .iter()
+            .map(|v| (v.version, v.name(), v.url.as_str()))
+            .collect();
+        assert_eq!(
+            urls,
+            [
+                (IpVersion::V4, "webhook.ipv4", "https://example.com/a"),
+                (IpVersion::V6, "webhook.ipv6", "https://example.com/aaaa"),
+            ]
+        );
+    }
+
+    #[test]
+    fn version_must_be_monitored() {
+        let reason = rejection("ipv4", "[webhook.ipv6]\nurl = \"https://example.com/aaaa\"");
+
+        assert!(
+            reason.starts_with("webhook.ipv6: IPv6 is not monitored"),
+            "{reason}"
+        );
+    }
+
+    #[test]
+    fn url_is_required_and_valid() {
+        assert_eq!(
+            rejection("both", "[webhook.ipv4]"),
+            "webhook.ipv4: url is required"
+        );
+        assert!(rejection("both", "[webhook.ipv4]\nurl = \"not a url\"").contains("'not a url'"));
+    }
+
+    #[test]
+    fn provider_cannot_be_combined() {
+        let cli = cli(&["--ip-version", "both"]);
+        let toml = toml(
+            r#"
+            [webhook.provider]
+            name = "duckdns"
+            domain = "myhost"
+            token = "abc"
+            [webhook.ipv4]
+            url = "https://example.com/a"
+            "#,
+        );
+
+        let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+        assert!(
+            matches!(result, Err(ConfigError::InvalidVersionUrl(ref r)) if r.contains("provider")),
+            "{result:?}"
+        );
+    }
+
+    #[test]
+    fn target_names_are_reserved() {
+        let result = config(
+            "both",
+            "[[webhook.targets]]\nname = \"webhook.ipv6\"\nurl = \"https://a.example/\"",
+        );
+
+        assert!(matches!(result, Err(ConfigError::InvalidTarget(_))));
+    }
+}
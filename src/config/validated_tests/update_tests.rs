@@ -0,0 +1,82 @@
+//! Tests for the `[update]` section.
+
+use super::*;
+use crate::update::{DEFAULT_RELEASE_FEED, PublicKey};
+
+const KEY: &str = "ea4a6c63e29c520abef5507b132ec5f9954776aebebe7b92421eea691446d22c";
+
+fn update(section: &str) -> Result<ValidatedConfig, ConfigError> {
+    let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+    let toml = toml(&format!("[update]\n{section}"));
+    ValidatedConfig::from_raw(&cli, Some(&toml))
+}
+
+fn rejection(section: &str) -> String {
+    match update(section) {
+        Err(ConfigError::InvalidUpdate(reason)) => reason,
+        other => panic!("expected InvalidUpdate for {section}, got {other:?}"),
+    }
+}
+
+#[test]
+fn disabled_by_default() {
+    let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+    let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+    assert!(config.update.is_none());
+    assert!(
+        update(&format!("public_key = \"{KEY}\""))
+            .unwrap()
+            .update
+            .is_none()
+    );
+}
+
+#[test]
+fn enabled_with_key_uses_project_feed() {
+    let config = update(&format!("enabled = true\npublic_key = \"{KEY}\"")).unwrap();
+
+    let settings = config.update.unwrap();
+    assert_eq!(settings.feed.as_str(), DEFAULT_RELEASE_FEED);
+    assert_eq!(Some(settings.public_key), PublicKey::from_hex(KEY));
+}
+
+#[test]
+fn custom_feed() {
+    let config = update(&format!(
+        "enabled = true\npublic_key = \"{KEY}\"\nfeed = \"https://mirror.example.com/latest.json\""
+    ))
+    .unwrap();
+
+    assert_eq!(
+        config.update.unwrap().feed.as_str(),
+        "https://mirror.example.com/latest.json"
+    );
+}
+
+#[test]
+fn requires_valid_key() {
+    assert_eq!(
+        rejection("enabled = true"),
+        "public_key is required when enabled"
+    );
+    assert_eq!(
+        rejection("enabled = true\npublic_key = \"abcd\""),
+        "public_key must be 64 hex digits (Ed25519)"
+    );
+}
+
+#[test]
+fn requires_https_feed() {
+    let reason = rejection(&format!(
+        "enabled = true\npublic_key = \"{KEY}\"\nfeed = \"http://mirror.example.com/latest\""
+    ));
+
+    assert!(reason.ends_with("must use https"), "{reason}");
+    assert!(
+        rejection(&format!(
+            "enabled = true\npublic_key = \"{KEY}\"\nfeed = \"nope\""
+        ))
+        .starts_with("feed 'nope'")
+    );
+}
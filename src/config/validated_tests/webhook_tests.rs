@@ -112,8 +112,11 @@ mod url_validation {
         ]);
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
-        assert_eq!(config.url.scheme(), "https");
-        assert_eq!(config.url.host_str(), Some("api.example.com"));
+        assert_eq!(config.webhook.url.as_ref().unwrap().scheme(), "https");
+        assert_eq!(
+            config.webhook.url.as_ref().unwrap().host_str(),
+            Some("api.example.com")
+        );
     }
 
     #[test]
@@ -126,8 +129,8 @@ mod url_validation {
         ]);
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
-        assert_eq!(config.url.scheme(), "http");
-        assert_eq!(config.url.port(), Some(8080));
+        assert_eq!(config.webhook.url.as_ref().unwrap().scheme(), "http");
+        assert_eq!(config.webhook.url.as_ref().unwrap().port(), Some(8080));
     }
 
     #[test]
@@ -137,6 +140,80 @@ mod url_validation {
 
         assert!(matches!(result, Err(ConfigError::InvalidUrl { .. })));
     }
+
+    #[test]
+    fn plain_url_has_no_template() {
+        let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert!(config.webhook.url_template.is_none());
+    }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn templated_url_is_kept_as_template() {
+        let template = "https://dyn.example.com/update?myip={{first_added_v4 changes}}";
+        let cli = cli(&["--url", template, "--ip-version", "ipv4"]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(config.webhook.url_template.as_deref(), Some(template));
+        assert_eq!(
+            config.webhook.url.as_ref().unwrap().as_str(),
+            "https://dyn.example.com/update?myip="
+        );
+    }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn invalid_url_template_returns_error() {
+        let cli = cli(&[
+            "--url",
+            "https://example.com/?ip={{#if}}x{{/if}}",
+            "--ip-version",
+            "ipv4",
+        ]);
+        let result = ValidatedConfig::from_raw(&cli, None);
+
+        assert!(matches!(result, Err(ConfigError::InvalidTemplate { .. })));
+    }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn url_template_must_render_a_url() {
+        let cli = cli(&[
+            "--url",
+            "{{changes.0.address}}not-a-url",
+            "--ip-version",
+            "ipv4",
+        ]);
+        let result = ValidatedConfig::from_raw(&cli, None);
+
+        assert!(matches!(result, Err(ConfigError::InvalidUrl { .. })));
+    }
+
+    #[test]
+    fn templated_url_conflicts_with_provider_and_discovery() {
+        let cli = cli(&["--ip-version", "ipv4"]);
+        for section in [
+            r#"[webhook.provider]
+               name = "duckdns"
+               domain = "myhost"
+               token = "abc""#,
+            r#"[webhook.discovery]
+               type = "txt"
+               name = "_ddns-a.example.com""#,
+        ] {
+            let toml = toml(&format!(
+                "[webhook]\nurl = \"https://example.com/?ip={{{{changes.0.address}}}}\"\n{section}"
+            ));
+            let result = ValidatedConfig::from_raw(&cli, Some(&toml));
+
+            let Err(ConfigError::InvalidUrl { reason, .. }) = result else {
+                panic!("expected InvalidUrl for {section}, got {result:?}");
+            };
+            assert!(reason.contains("cannot be combined"), "{reason}");
+        }
+    }
 }
 
 mod http_method {
@@ -147,7 +224,7 @@ mod http_method {
         let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
-        assert_eq!(config.method, Method::POST);
+        assert_eq!(config.webhook.method, Method::POST);
     }
 
     #[test]
@@ -161,7 +238,7 @@ mod http_method {
         );
         let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
 
-        assert_eq!(config.method, Method::PUT);
+        assert_eq!(config.webhook.method, Method::PUT);
     }
 
     #[test]
@@ -183,7 +260,7 @@ mod http_method {
         );
         let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
 
-        assert_eq!(config.method, Method::DELETE);
+        assert_eq!(config.webhook.method, Method::DELETE);
     }
 
     #[test]
@@ -198,7 +275,7 @@ mod http_method {
         ]);
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
-        assert_eq!(config.method, Method::PUT);
+        assert_eq!(config.webhook.method, Method::PUT);
     }
 
     #[test]
@@ -214,7 +291,7 @@ mod http_method {
         ]);
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
-        assert_eq!(config.method.as_str(), "CUSTOM");
+        assert_eq!(config.webhook.method.as_str(), "CUSTOM");
     }
 
     #[test]
@@ -249,7 +326,7 @@ mod headers {
         ]);
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
-        let value = config.headers.get("X-Api-Key").unwrap();
+        let value = config.webhook.headers.get("X-Api-Key").unwrap();
         assert_eq!(value.to_str().unwrap(), "secret123");
     }
 
@@ -265,7 +342,7 @@ mod headers {
         ]);
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
-        let value = config.headers.get("Content-Type").unwrap();
+        let value = config.webhook.headers.get("Content-Type").unwrap();
         assert_eq!(value.to_str().unwrap(), "application/json");
     }
 
@@ -281,7 +358,7 @@ mod headers {
         ]);
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
-        let auth = config.headers.get("Authorization").unwrap();
+        let auth = config.webhook.headers.get("Authorization").unwrap();
         assert_eq!(auth.to_str().unwrap(), "Bearer my-token");
     }
 
@@ -303,7 +380,7 @@ mod headers {
         );
         let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
 
-        let value = config.headers.get("X-Key").unwrap();
+        let value = config.webhook.headers.get("X-Key").unwrap();
         assert_eq!(value.to_str().unwrap(), "cli-value");
     }
 
@@ -335,7 +412,7 @@ mod headers {
         ]);
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
-        let value = config.headers.get("X-Token").unwrap();
+        let value = config.webhook.headers.get("X-Token").unwrap();
         assert_eq!(value.to_str().unwrap(), "abc=def=123");
     }
 
@@ -352,7 +429,7 @@ mod headers {
         ]);
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
-        let value = config.headers.get("X-Time").unwrap();
+        let value = config.webhook.headers.get("X-Time").unwrap();
         assert_eq!(value.to_str().unwrap(), "12:34:56");
     }
 }
@@ -432,8 +509,68 @@ mod header_validation {
             Err(ConfigError::InvalidHeaderValue { .. })
         ));
     }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn templated_header_value_is_accepted() {
+        let cli = cli(&[
+            "--url",
+            "https://example.com",
+            "--ip-version",
+            "ipv4",
+            "--header",
+            "X-Current-IP: {{first_added changes}}",
+        ]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+
+        assert_eq!(
+            config.webhook.headers["x-current-ip"],
+            "{{first_added changes}}"
+        );
+    }
+
+    #[test]
+    fn invalid_header_template_returns_error() {
+        let cli = cli(&[
+            "--url",
+            "https://example.com",
+            "--ip-version",
+            "ipv4",
+            "--header",
+            "X-Current-IP: {{first_added}}",
+        ]);
+        let result = ValidatedConfig::from_raw(&cli, None);
+
+        #[cfg(feature = "templates")]
+        assert!(matches!(result, Err(ConfigError::InvalidTemplate { .. })));
+        #[cfg(not(feature = "templates"))]
+        assert!(matches!(result, Err(ConfigError::FeatureDisabled { .. })));
+    }
+}
+
+#[cfg(not(feature = "templates"))]
+#[test]
+fn body_template_without_feature_returns_error() {
+    let cli = cli(&[
+        "--url",
+        "https://example.com",
+        "--ip-version",
+        "ipv4",
+        "--body-template",
+        "{}",
+    ]);
+    let result = ValidatedConfig::from_raw(&cli, None);
+
+    assert!(matches!(
+        result,
+        Err(ConfigError::FeatureDisabled {
+            feature: "templates",
+            ..
+        })
+    ));
 }
 
+#[cfg(feature = "templates")]
 mod body_template {
     use super::*;
 
@@ -450,7 +587,7 @@ mod body_template {
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
         assert_eq!(
-            config.body_template.as_deref(),
+            config.webhook.body_template.as_deref(),
             Some(r#"{"ip":"{{address}}"}"#)
         );
     }
@@ -466,9 +603,10 @@ mod body_template {
         );
         let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
 
-        assert!(config.body_template.is_some());
+        assert!(config.webhook.body_template.is_some());
         assert!(
             config
+                .webhook
                 .body_template
                 .as_ref()
                 .unwrap()
@@ -494,7 +632,10 @@ mod body_template {
         );
         let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
 
-        assert_eq!(config.body_template.as_deref(), Some("cli-template"));
+        assert_eq!(
+            config.webhook.body_template.as_deref(),
+            Some("cli-template")
+        );
     }
 
     #[test]
@@ -502,7 +643,7 @@ mod body_template {
         let cli = cli(&["--url", "https://example.com", "--ip-version", "ipv4"]);
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
-        assert!(config.body_template.is_none());
+        assert!(config.webhook.body_template.is_none());
     }
 
     #[test]
@@ -535,6 +676,35 @@ mod body_template {
         assert!(matches!(result, Err(ConfigError::InvalidTemplate { .. })));
     }
 
+    #[test]
+    fn helper_misuse_returns_error() {
+        let cli = cli(&[
+            "--url",
+            "https://example.com",
+            "--ip-version",
+            "ipv4",
+            "--body-template",
+            r#"{"changes": {{json}}}"#,
+        ]);
+        let result = ValidatedConfig::from_raw(&cli, None);
+
+        assert!(matches!(result, Err(ConfigError::InvalidTemplate { .. })));
+    }
+
+    #[test]
+    fn template_with_helpers_is_accepted() {
+        let cli = cli(&[
+            "--url",
+            "https://example.com",
+            "--ip-version",
+            "both",
+            "--body-template",
+            r#"{"ip": "{{first_added_v6 changes}}", "changes": {{json (only_added changes)}}}"#,
+        ]);
+
+        ValidatedConfig::from_raw(&cli, None).unwrap();
+    }
+
     #[test]
     fn valid_complex_template() {
         let cli = cli(&[
@@ -547,7 +717,7 @@ mod body_template {
         ]);
         let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
-        assert!(config.body_template.is_some());
+        assert!(config.webhook.body_template.is_some());
     }
 }
 
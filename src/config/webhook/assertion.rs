@@ -0,0 +1,60 @@
+//! Resolution of the `webhook.expect_json` and `webhook.expect_body_regex`
+//! response assertions.
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::webhook::{BodyAssertion, JsonAssertion, JsonPath};
+
+use crate::config::error::ConfigError;
+use crate::config::toml::TomlConfig;
+
+/// Resolves the response assertion; `None` if unset.
+pub(super) fn resolve_json_assertion(
+    toml: Option<&TomlConfig>,
+) -> Result<Option<JsonAssertion>, ConfigError> {
+    let Some(section) = toml.and_then(|t| t.webhook.expect_json.as_ref()) else {
+        return Ok(None);
+    };
+    let invalid = |reason: String| ConfigError::InvalidExpectJson(reason);
+
+    let path = section
+        .path
+        .as_deref()
+        .ok_or_else(|| invalid("path is required".to_string()))?;
+    let path: JsonPath = path.parse().map_err(invalid)?;
+    let equals = section
+        .equals
+        .clone()
+        .ok_or_else(|| invalid("equals is required".to_string()))?;
+    // A field is a single value; arrays and tables would never be meant
+    if matches!(equals, Value::Array(_) | Value::Object(_)) {
+        return Err(invalid(
+            "equals must be a string, number or boolean".to_string(),
+        ));
+    }
+
+    Ok(Some(
+        JsonAssertion::new(path, equals).retried(section.retry.unwrap_or(false)),
+    ))
+}
+
+/// Resolves the response body regex; `None` if unset.
+pub(super) fn resolve_body_assertion(
+    toml: Option<&TomlConfig>,
+) -> Result<Option<BodyAssertion>, ConfigError> {
+    let Some(webhook) = toml.map(|t| &t.webhook) else {
+        return Ok(None);
+    };
+    let Some(pattern) = webhook.expect_body_regex.as_deref() else {
+        return Ok(None);
+    };
+    let regex = Regex::new(pattern).map_err(|source| ConfigError::InvalidRegex {
+        pattern: pattern.to_string(),
+        source,
+    })?;
+
+    Ok(Some(
+        BodyAssertion::new(regex).retried(webhook.expect_body_retry.unwrap_or(false)),
+    ))
+}
@@ -0,0 +1,48 @@
+//! Resolution of the `[webhook.certificate]` fingerprint tracking.
+
+use url::Url;
+
+use crate::webhook::Fingerprint;
+
+use crate::config::error::ConfigError;
+use crate::config::toml::TomlConfig;
+
+/// Resolves the pinned fingerprints; `None` if certificates are not tracked.
+pub(super) fn resolve_certificate_pins(
+    toml: Option<&TomlConfig>,
+    url: Option<&Url>,
+) -> Result<Option<Vec<Fingerprint>>, ConfigError> {
+    let Some(section) = toml.and_then(|t| t.webhook.certificate.as_ref()) else {
+        return Ok(None);
+    };
+    let invalid = |reason: String| ConfigError::InvalidCertificate(reason);
+
+    let pins = section
+        .pins
+        .iter()
+        .map(|pin| pin.parse::<Fingerprint>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(invalid)?;
+    // Pins imply tracking unless it is turned off explicitly
+    if !section.track.unwrap_or(!pins.is_empty()) {
+        if pins.is_empty() {
+            return Ok(None);
+        }
+        return Err(invalid(
+            "pins cannot be combined with track = false".to_string(),
+        ));
+    }
+    let Some(url) = url else {
+        return Err(invalid(
+            "certificates are tracked for webhook.url, which is not set".to_string(),
+        ));
+    };
+    if url.scheme() != "https" {
+        return Err(invalid(format!(
+            "the webhook url must use https to check its certificate, got {}",
+            url.scheme()
+        )));
+    }
+
+    Ok(Some(pins))
+}
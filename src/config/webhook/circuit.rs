@@ -0,0 +1,41 @@
+//! Resolution of the `[webhook.circuit_breaker]` section.
+
+use std::time::Duration;
+
+use crate::webhook::CircuitPolicy;
+
+use crate::config::error::ConfigError;
+use crate::config::toml::{CircuitBreakerSection, TomlConfig};
+
+/// Resolves the circuit breaker policy; `None` if the section is absent.
+pub(super) fn resolve_circuit_breaker(
+    toml: Option<&TomlConfig>,
+) -> Result<Option<CircuitPolicy>, ConfigError> {
+    let Some(section) = toml.and_then(|t| t.webhook.circuit_breaker.as_ref()) else {
+        return Ok(None);
+    };
+    circuit_policy(section, CircuitPolicy::default())
+        .map(Some)
+        .map_err(ConfigError::InvalidCircuitBreaker)
+}
+
+/// Applies the values set in `section` to `policy`.
+pub(super) fn circuit_policy(
+    section: &CircuitBreakerSection,
+    mut policy: CircuitPolicy,
+) -> Result<CircuitPolicy, String> {
+    let invalid = |field: &str| format!("{field} must be greater than 0");
+    if let Some(failures) = section.failures {
+        if failures == 0 {
+            return Err(invalid("failures"));
+        }
+        policy.failure_threshold = failures;
+    }
+    if let Some(cooldown) = section.cooldown {
+        if cooldown == 0 {
+            return Err(invalid("cooldown"));
+        }
+        policy.cooldown = Duration::from_secs(cooldown);
+    }
+    Ok(policy)
+}
@@ -0,0 +1,82 @@
+//! Resolution of the `[webhook.discovery]` section.
+
+use std::time::Duration;
+
+use crate::webhook::{EndpointDiscovery, EndpointRecord};
+
+use crate::config::defaults;
+use crate::config::error::ConfigError;
+use crate::config::parse::parse_nameserver;
+use crate::config::toml::{DiscoverySection, TomlConfig};
+
+/// Resolves endpoint discovery settings; `None` if the section is absent.
+pub(super) fn resolve_discovery(
+    toml: Option<&TomlConfig>,
+) -> Result<Option<EndpointDiscovery>, ConfigError> {
+    let has_dns_servers = toml.is_some_and(|t| !t.http.dns_servers.is_empty());
+    toml.and_then(|t| t.webhook.discovery.as_ref())
+        .map(|section| parse_discovery(section, has_dns_servers))
+        .transpose()
+}
+
+fn parse_discovery(
+    section: &DiscoverySection,
+    has_dns_servers: bool,
+) -> Result<EndpointDiscovery, ConfigError> {
+    let invalid = |reason: &str| ConfigError::InvalidDiscovery(reason.to_string());
+
+    let name = section
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| invalid("name is required"))?
+        .to_string();
+
+    let record_type = section
+        .record_type
+        .as_deref()
+        .ok_or_else(|| invalid("type is required (\"txt\" or \"srv\")"))?;
+    let record = match record_type.to_ascii_lowercase().as_str() {
+        "txt" if section.path.is_some() => {
+            return Err(invalid("path only applies to type = \"srv\""));
+        }
+        "txt" => EndpointRecord::Txt { name },
+        "srv" => {
+            let path = section.path.clone().unwrap_or_else(|| "/".to_string());
+            if !path.starts_with('/') {
+                return Err(invalid("path must start with '/'"));
+            }
+            EndpointRecord::Srv { name, path }
+        }
+        other => {
+            return Err(ConfigError::InvalidDiscovery(format!(
+                "type '{other}' must be \"txt\" or \"srv\""
+            )));
+        }
+    };
+
+    let nameserver = section
+        .nameserver
+        .as_deref()
+        .map(|s| {
+            parse_nameserver(s).ok_or_else(|| {
+                ConfigError::InvalidDiscovery(format!(
+                    "nameserver '{s}' must be an IP address, optionally with a port"
+                ))
+            })
+        })
+        .transpose()?;
+    // Only Unix has a resolv.conf to take the system nameserver from
+    if nameserver.is_none() && !has_dns_servers && !cfg!(unix) {
+        return Err(invalid(
+            "nameserver (or http.dns_servers) is required on this platform",
+        ));
+    }
+
+    Ok(EndpointDiscovery {
+        record,
+        nameserver,
+        refresh: Duration::from_secs(section.refresh.unwrap_or(defaults::DISCOVERY_REFRESH_SECS)),
+    })
+}
@@ -0,0 +1,413 @@
+//! Webhook settings: the main webhook's request, retries and delivery.
+
+use std::time::Duration;
+
+use http::header::AUTHORIZATION;
+use http::{HeaderMap, Method};
+use url::Url;
+
+use crate::config::cli::Cli;
+use crate::config::defaults;
+use crate::config::error::{ConfigError, field};
+use crate::config::parse::{
+    parse_connectivity_check, parse_delivery_mode, parse_echo_check, parse_header_name,
+    parse_header_string, parse_header_value, parse_notify_on, parse_payload_encoding,
+    parse_payload_format,
+};
+use crate::config::template::{
+    resolve_dns_body_template, validate_header_templates, validate_template, validate_url_template,
+};
+use crate::config::toml::TomlConfig;
+use crate::monitor::NotifyOn;
+use crate::network::IpVersion;
+use crate::state::QueueLimits;
+use crate::webhook::{
+    AdapterRoute, BodyAssertion, CircuitPolicy, ConnectivityCheck, DeliveryMode, EchoCheck,
+    EndpointDiscovery, Fingerprint, JsonAssertion, OAuth2, PayloadEncoding, PayloadFormat,
+    PreRequest, Provider, RetryOverlap, RetryOverrides, RetryPolicy,
+};
+
+use crate::config::{HttpSettings, MonitorSettings};
+
+mod assertion;
+mod certificate;
+mod circuit;
+mod endpoint;
+mod oauth2;
+mod pre_request;
+mod provider;
+mod retry;
+mod target;
+
+use assertion::{resolve_body_assertion, resolve_json_assertion};
+use certificate::resolve_certificate_pins;
+use circuit::resolve_circuit_breaker;
+use endpoint::resolve_discovery;
+use oauth2::resolve_oauth2;
+use pre_request::resolve_pre_request;
+use provider::{resolve_provider, resolve_ttl};
+use retry::{
+    build_retry_overrides, build_retry_policy, check_retry_overlap, resolve_retry_queue,
+    resolve_shutdown_grace,
+};
+pub use target::{MAIN_TARGET, VersionUrl, WebhookTarget};
+use target::{resolve_route, resolve_targets, resolve_version_urls};
+
+/// Settings of the main webhook and the targets beside it.
+#[derive(Debug)]
+pub struct WebhookSettings {
+    /// Webhook URL (required unless a provider preset supplies it).
+    /// If `None`, only `[action.exec]`, `[mqtt]`, `[email]` or `[notify]`
+    /// deliver the changes.
+    pub url: Option<Url>,
+
+    /// Handlebars template the webhook URL is rendered from per delivery,
+    /// if `url` contains one (`url` is then its rendering without data)
+    pub url_template: Option<String>,
+
+    /// Kinds of address change delivered; the others are suppressed
+    /// before any delivery (TOML-only)
+    pub notify_on: NotifyOn,
+
+    /// HTTP method for webhook requests
+    pub method: Method,
+
+    /// HTTP headers for webhook requests
+    pub headers: HeaderMap,
+
+    /// Handlebars body template (optional)
+    pub body_template: Option<String>,
+
+    /// Body template for DNS setting changes (TOML-only)
+    pub dns_body_template: Option<String>,
+
+    /// Encoding of non-templated bodies (TOML-only; `None`: address
+    /// deliveries without a template carry the `payload_format` document)
+    pub payload_encoding: Option<PayloadEncoding>,
+
+    /// Default body of address deliveries without a template or encoding
+    /// (TOML-only; default: `json-v1`)
+    pub payload_format: PayloadFormat,
+
+    /// Where responses must echo each attempt's nonce (TOML-only)
+    pub echo_check: EchoCheck,
+
+    /// How address changes are split into requests (TOML-only)
+    pub delivery_mode: DeliveryMode,
+
+    /// Assertion 2xx JSON responses must pass (TOML-only)
+    pub json_assertion: Option<JsonAssertion>,
+
+    /// Regex the bodies of 2xx responses must match (TOML-only)
+    pub body_assertion: Option<BodyAssertion>,
+
+    /// Fingerprints the webhook host must present one of (TOML-only).
+    /// If `None`, certificates are not tracked; an empty list only records them.
+    pub certificate_pins: Option<Vec<Fingerprint>>,
+
+    /// TTL hint in seconds for address payloads (TOML-only).
+    /// If `None`, payloads carry no `ttl` or `expires`.
+    pub ttl: Option<u32>,
+
+    /// Minimum interval between address notifications (TOML-only).
+    /// If `None`, every batch is sent at once.
+    pub min_notify_interval: Option<Duration>,
+
+    /// Retry policy for failed webhook requests
+    pub retry_policy: RetryPolicy,
+
+    /// Adapters whose changes the main webhook receives (TOML-only)
+    pub route: AdapterRoute,
+
+    /// Further webhooks every change is also sent to (TOML-only)
+    pub targets: Vec<WebhookTarget>,
+
+    /// URLs receiving the address changes of one IP version instead of
+    /// [`url`](Self::url), IPv4 first (TOML-only)
+    pub version_urls: Vec<VersionUrl>,
+
+    /// Request changes applied to retry attempts
+    pub retry_overrides: RetryOverrides,
+
+    /// Set if retries can outlast the poll interval (`retry.overlap = "warn"`)
+    pub retry_overlap: Option<RetryOverlap>,
+
+    /// Circuit breaker policy of every webhook (TOML-only).
+    /// If `None`, failing webhooks are always attempted.
+    pub circuit_breaker: Option<CircuitPolicy>,
+
+    /// Limits of the persistent retry queue (TOML-only).
+    /// If `None`, changes whose retries ran out are dropped.
+    pub retry_queue: Option<QueueLimits>,
+
+    /// How long deliveries in progress may keep retrying after a stop
+    /// signal (TOML-only). Zero cuts them short at once.
+    pub shutdown_grace: Duration,
+
+    /// Connectivity check performed before each delivery
+    pub connectivity_check: ConnectivityCheck,
+
+    /// Webhook URL discovery from DNS (TOML-only).
+    /// If `None`, `url` is always used.
+    pub endpoint_discovery: Option<EndpointDiscovery>,
+
+    /// DNS provider preset (TOML-only).
+    /// If set, address changes update the provider's records instead of
+    /// calling a generic webhook.
+    pub provider: Option<Provider>,
+
+    /// Request sent before every attempt, whose captured response values
+    /// feed its template and headers (TOML-only).
+    pub pre_request: Option<PreRequest>,
+
+    /// `OAuth2` client-credentials grant authenticating the webhook's
+    /// requests (TOML-only).
+    pub oauth2: Option<OAuth2>,
+}
+
+impl WebhookSettings {
+    /// Resolves the webhook settings; the targets inherit the connection
+    /// settings of `http`, and retries are checked against the shortest
+    /// poll interval of `monitor`.
+    pub(super) fn resolve(
+        cli: &Cli,
+        toml: Option<&TomlConfig>,
+        ip_version: IpVersion,
+        http: &HttpSettings,
+        monitor: &MonitorSettings,
+        has_state_file: bool,
+    ) -> Result<Self, ConfigError> {
+        // Resolve the TTL hint and DNS provider preset (TOML-only)
+        let ttl = resolve_ttl(toml)?;
+        let provider = resolve_provider(cli, toml, ttl)?;
+
+        // Merge and validate URL (required unless something else delivers)
+        let (url, url_template) = Self::resolve_url(cli, toml, provider.as_ref())?;
+
+        // Merge HTTP method (CLI default: POST) and headers
+        let method = Self::resolve_method(cli, toml)?;
+        let headers = Self::resolve_headers(cli, toml)?;
+
+        // Merge and validate body template
+        let body_template = Self::resolve_body_template(cli, toml)?;
+        let dns_body_template = resolve_dns_body_template(toml)?;
+        let (payload_encoding, payload_format) = Self::resolve_default_body(toml)?;
+
+        // Build retry policy
+        let retry_policy = build_retry_policy(cli, toml)?;
+        let retry_overlap =
+            check_retry_overlap(toml, &retry_policy, monitor.shortest_poll_interval())?;
+
+        // Resolve the circuit breaker, the default of the targets (TOML-only)
+        let circuit_breaker = resolve_circuit_breaker(toml)?;
+
+        Ok(Self {
+            certificate_pins: resolve_certificate_pins(toml, url.as_ref())?,
+            url,
+            url_template,
+            notify_on: Self::resolve_notify_on(toml)?,
+            method,
+            // The pre-request and OAuth2 grant reuse the webhook headers
+            pre_request: resolve_pre_request(toml, &headers)?,
+            oauth2: resolve_oauth2(toml, &headers)?,
+            headers,
+            body_template,
+            dns_body_template,
+            payload_encoding,
+            payload_format,
+            echo_check: toml
+                .and_then(|t| t.webhook.echo_check.as_deref())
+                .map_or(Ok(EchoCheck::Disabled), parse_echo_check)?,
+            delivery_mode: toml
+                .and_then(|t| t.webhook.delivery.as_deref())
+                .map_or(Ok(DeliveryMode::Batched), parse_delivery_mode)?,
+            json_assertion: resolve_json_assertion(toml)?,
+            body_assertion: resolve_body_assertion(toml)?,
+            ttl,
+            min_notify_interval: Self::resolve_min_notify_interval(toml)?,
+            route: resolve_route(toml)?,
+            targets: resolve_targets(
+                toml,
+                &retry_policy,
+                &http.connection,
+                circuit_breaker,
+                ip_version,
+            )?,
+            version_urls: resolve_version_urls(toml, ip_version, provider.is_some())?,
+            retry_policy,
+            retry_overrides: build_retry_overrides(toml)?,
+            retry_overlap,
+            circuit_breaker,
+            retry_queue: resolve_retry_queue(toml, has_state_file)?,
+            shutdown_grace: resolve_shutdown_grace(toml),
+            connectivity_check: Self::resolve_connectivity_check(toml)?,
+            endpoint_discovery: resolve_discovery(toml)?,
+            provider,
+        })
+    }
+
+    fn resolve_url(
+        cli: &Cli,
+        toml: Option<&TomlConfig>,
+        provider: Option<&Provider>,
+    ) -> Result<(Option<Url>, Option<String>), ConfigError> {
+        // CLI takes precedence
+        let Some(url_str) = cli
+            .url
+            .as_deref()
+            .or_else(|| toml.and_then(|t| t.webhook.url.as_deref()))
+        else {
+            if let Some(provider) = provider {
+                return Ok((Some(provider.kind().endpoint()), None));
+            }
+            // Other backends can replace the webhook, but discovery needs it
+            let replaced = toml.is_some_and(|t| {
+                let notify = &t.notify;
+                let chats =
+                    notify.telegram.is_some() || notify.discord.is_some() || notify.slack.is_some();
+                (t.action.exec.is_some() || t.mqtt.is_some() || t.email.is_some() || chats)
+                    && t.webhook.discovery.is_none()
+            });
+            if replaced {
+                return Ok((None, None));
+            }
+            return Err(ConfigError::missing(
+                field::URL,
+                "Use --url or set webhook.url in config file",
+            ));
+        };
+
+        if !url_str.contains("{{") {
+            let url = Url::parse(url_str).map_err(|e| ConfigError::InvalidUrl {
+                url: url_str.to_string(),
+                reason: e.to_string(),
+            })?;
+            return Ok((Some(url), None));
+        }
+
+        // A templated URL is rendered per delivery, so nothing may replace it
+        let conflict = if provider.is_some() {
+            Some("[webhook.provider]")
+        } else if toml.is_some_and(|t| t.webhook.discovery.is_some()) {
+            Some("[webhook.discovery]")
+        } else {
+            None
+        };
+        if let Some(section) = conflict {
+            return Err(ConfigError::InvalidUrl {
+                url: url_str.to_string(),
+                reason: format!("a templated URL cannot be combined with {section}"),
+            });
+        }
+        Ok((
+            Some(validate_url_template(url_str)?),
+            Some(url_str.to_string()),
+        ))
+    }
+
+    fn resolve_method(cli: &Cli, toml: Option<&TomlConfig>) -> Result<Method, ConfigError> {
+        // Priority: CLI explicit > TOML > default
+        let method_str = cli
+            .method
+            .as_deref()
+            .or_else(|| toml.and_then(|t| t.webhook.method.as_deref()))
+            .unwrap_or(defaults::METHOD);
+
+        method_str
+            .parse::<Method>()
+            .map_err(|_| ConfigError::InvalidMethod(method_str.to_string()))
+    }
+
+    fn resolve_headers(cli: &Cli, toml: Option<&TomlConfig>) -> Result<HeaderMap, ConfigError> {
+        let mut headers = HeaderMap::new();
+
+        // Add TOML headers first (CLI can override)
+        if let Some(toml) = toml {
+            for (name, value) in &toml.webhook.headers {
+                let header_name = parse_header_name(name)?;
+                let header_value = parse_header_value(name, value)?;
+                headers.insert(header_name, header_value);
+            }
+        }
+
+        // Add CLI headers (override TOML)
+        for header_str in &cli.headers {
+            let (name, value) = parse_header_string(header_str)?;
+            let header_name = parse_header_name(&name)?;
+            let header_value = parse_header_value(&name, &value)?;
+            headers.insert(header_name, header_value);
+        }
+
+        // Handle bearer token (CLI wins, then TOML)
+        let bearer = cli
+            .bearer
+            .as_deref()
+            .or_else(|| toml.and_then(|t| t.webhook.bearer.as_deref()));
+
+        if let Some(token) = bearer {
+            let auth_value = format!("Bearer {token}");
+            let header_value = parse_header_value("Authorization", &auth_value)?;
+            headers.insert(AUTHORIZATION, header_value);
+        }
+
+        validate_header_templates(&headers, "--header / webhook.headers")?;
+        Ok(headers)
+    }
+
+    /// Resolves the encoding and format of non-templated bodies (TOML-only).
+    fn resolve_default_body(
+        toml: Option<&TomlConfig>,
+    ) -> Result<(Option<PayloadEncoding>, PayloadFormat), ConfigError> {
+        let webhook = toml.map(|t| &t.webhook);
+        let encoding = webhook
+            .and_then(|w| w.payload_encoding.as_deref())
+            .map(parse_payload_encoding)
+            .transpose()?;
+        let format = webhook
+            .and_then(|w| w.payload_format.as_deref())
+            .map_or_else(|| Ok(PayloadFormat::default()), parse_payload_format)?;
+        Ok((encoding, format))
+    }
+
+    fn resolve_body_template(
+        cli: &Cli,
+        toml: Option<&TomlConfig>,
+    ) -> Result<Option<String>, ConfigError> {
+        let template = cli
+            .body_template
+            .clone()
+            .or_else(|| toml.and_then(|t| t.webhook.body_template.clone()));
+
+        if let Some(ref tmpl) = template {
+            validate_template(tmpl, "--body-template / webhook.body_template")?;
+        }
+
+        Ok(template)
+    }
+
+    /// Resolves the connectivity check before delivery (TOML-only).
+    fn resolve_connectivity_check(
+        toml: Option<&TomlConfig>,
+    ) -> Result<ConnectivityCheck, ConfigError> {
+        toml.and_then(|t| t.webhook.connectivity_check.as_deref())
+            .map_or(Ok(ConnectivityCheck::Disabled), parse_connectivity_check)
+    }
+
+    /// Resolves the kinds of address change notified (TOML-only).
+    fn resolve_notify_on(toml: Option<&TomlConfig>) -> Result<NotifyOn, ConfigError> {
+        toml.and_then(|t| t.webhook.notify_on.as_deref())
+            .map_or(Ok(NotifyOn::Both), parse_notify_on)
+    }
+
+    fn resolve_min_notify_interval(
+        toml: Option<&TomlConfig>,
+    ) -> Result<Option<Duration>, ConfigError> {
+        match toml.and_then(|t| t.webhook.min_notify_interval) {
+            Some(0) => Err(ConfigError::InvalidDuration {
+                field: "min_notify_interval",
+                reason: "must be greater than 0".to_string(),
+            }),
+            seconds => Ok(seconds.map(Duration::from_secs)),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! Resolution of the `[webhook.oauth2]` section.
+
+use http::HeaderMap;
+use http::header::AUTHORIZATION;
+use url::Url;
+
+use crate::webhook::{ClientAuth, OAuth2};
+
+use crate::config::error::ConfigError;
+use crate::config::toml::{OAuth2Section, TomlConfig};
+
+/// Resolves the `OAuth2` grant; `None` if the section is absent.
+///
+/// `headers` are the resolved webhook headers, which must not set
+/// `Authorization` themselves (directly or through `bearer`).
+pub(super) fn resolve_oauth2(
+    toml: Option<&TomlConfig>,
+    headers: &HeaderMap,
+) -> Result<Option<OAuth2>, ConfigError> {
+    let Some(section) = toml.and_then(|t| t.webhook.oauth2.as_ref()) else {
+        return Ok(None);
+    };
+    if headers.contains_key(AUTHORIZATION) {
+        return Err(ConfigError::InvalidOAuth2(
+            "cannot be combined with bearer or an Authorization header".to_string(),
+        ));
+    }
+    parse_oauth2(section).map(Some)
+}
+
+fn parse_oauth2(section: &OAuth2Section) -> Result<OAuth2, ConfigError> {
+    let required = |value: Option<&String>, field: &str| {
+        value
+            .filter(|value| !value.is_empty())
+            .cloned()
+            .ok_or_else(|| ConfigError::InvalidOAuth2(format!("{field} is required")))
+    };
+    let token_url = required(section.token_url.as_ref(), "token_url")?;
+    let token_url = Url::parse(&token_url).map_err(|e| ConfigError::InvalidUrl {
+        url: token_url.clone(),
+        reason: e.to_string(),
+    })?;
+    // The client secret must not cross the network in clear text
+    if token_url.scheme() != "https" && !is_loopback(&token_url) {
+        return Err(ConfigError::InvalidOAuth2(format!(
+            "token_url '{token_url}' must use https"
+        )));
+    }
+    let client_id = required(section.client_id.as_ref(), "client_id")?;
+    let client_secret = required(section.client_secret.as_ref(), "client_secret")?;
+    let client_auth = match section.client_auth.as_deref() {
+        None => ClientAuth::default(),
+        Some(value) if value.eq_ignore_ascii_case("basic") => ClientAuth::Basic,
+        Some(value) if value.eq_ignore_ascii_case("body") => ClientAuth::Body,
+        Some(other) => {
+            return Err(ConfigError::InvalidOAuth2(format!(
+                "client_auth '{other}' must be \"basic\" or \"body\""
+            )));
+        }
+    };
+
+    let mut oauth2 = OAuth2::new(token_url, client_id, client_secret).with_client_auth(client_auth);
+    if let Some(scope) = &section.scope {
+        oauth2 = oauth2.with_scope(scope);
+    }
+    if let Some(audience) = &section.audience {
+        oauth2 = oauth2.with_audience(audience);
+    }
+    Ok(oauth2)
+}
+
+/// Whether `url` points at this host, where plain http is acceptable.
+fn is_loopback(url: &Url) -> bool {
+    match url.host() {
+        Some(url::Host::Domain(domain)) => domain.eq_ignore_ascii_case("localhost"),
+        Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+        None => false,
+    }
+}
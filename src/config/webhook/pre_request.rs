@@ -0,0 +1,79 @@
+//! Resolution of the `[webhook.pre_request]` section.
+
+use http::{HeaderMap, Method};
+use url::Url;
+
+use crate::webhook::{CaptureSource, PreRequest, placeholders};
+
+use crate::config::error::ConfigError;
+use crate::config::parse::{parse_header_name, parse_header_value};
+use crate::config::toml::{PreRequestSection, TomlConfig};
+
+/// Resolves the pre-request; `None` if the section is absent.
+///
+/// `headers` are the resolved webhook headers, whose `{{pre.<name>}}`
+/// placeholders must each name a capture.
+pub(super) fn resolve_pre_request(
+    toml: Option<&TomlConfig>,
+    headers: &HeaderMap,
+) -> Result<Option<PreRequest>, ConfigError> {
+    let Some(section) = toml.and_then(|t| t.webhook.pre_request.as_ref()) else {
+        return Ok(None);
+    };
+    let pre_request = parse_pre_request(section)?;
+
+    for (name, value) in headers {
+        let unknown = value.to_str().ok().and_then(|value| {
+            placeholders(value)
+                .into_iter()
+                .find(|p| !pre_request.captures().iter().any(|c| c.name == *p))
+        });
+        if let Some(placeholder) = unknown {
+            return Err(ConfigError::InvalidPreRequest(format!(
+                "header '{name}' uses {{{{pre.{placeholder}}}}}, which no capture defines"
+            )));
+        }
+    }
+    Ok(Some(pre_request))
+}
+
+fn parse_pre_request(section: &PreRequestSection) -> Result<PreRequest, ConfigError> {
+    let url = section
+        .url
+        .as_deref()
+        .ok_or_else(|| ConfigError::InvalidPreRequest("url is required".to_string()))?;
+    let url = Url::parse(url).map_err(|e| ConfigError::InvalidUrl {
+        url: url.to_string(),
+        reason: e.to_string(),
+    })?;
+    let method = section.method.as_deref().unwrap_or("POST");
+    let method = method
+        .parse::<Method>()
+        .map_err(|_| ConfigError::InvalidMethod(method.to_string()))?;
+
+    let mut headers = HeaderMap::new();
+    for (name, value) in &section.headers {
+        headers.insert(parse_header_name(name)?, parse_header_value(name, value)?);
+    }
+    let mut pre_request = PreRequest::new(method, url).with_headers(headers);
+    if let Some(body) = &section.body {
+        pre_request = pre_request.with_body(body);
+    }
+
+    // Sorted, so errors and the capture order do not depend on hashing
+    let mut captures: Vec<_> = section.capture.iter().collect();
+    captures.sort();
+    for (name, source) in captures {
+        // Names become template variables
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(ConfigError::InvalidPreRequest(format!(
+                "capture name '{name}' may only hold letters, digits and '_'"
+            )));
+        }
+        let source = CaptureSource::parse(source).map_err(|reason| {
+            ConfigError::InvalidPreRequest(format!("capture.{name}: {reason}"))
+        })?;
+        pre_request = pre_request.with_capture(name, source);
+    }
+    Ok(pre_request)
+}
@@ -0,0 +1,155 @@
+//! Resolution of the `[webhook.provider]` section and the `webhook.ttl`
+//! hint its records default to.
+
+use http::HeaderValue;
+
+use crate::webhook::{Provider, ProviderKind};
+
+use crate::config::cli::Cli;
+use crate::config::error::ConfigError;
+use crate::config::toml::{ProviderSection, TomlConfig};
+
+/// Resolves the `webhook.ttl` hint; `None` if unset.
+pub(super) fn resolve_ttl(toml: Option<&TomlConfig>) -> Result<Option<u32>, ConfigError> {
+    match toml.and_then(|t| t.webhook.ttl) {
+        Some(0) => Err(ConfigError::InvalidDuration {
+            field: "webhook.ttl",
+            reason: "must be greater than 0".to_string(),
+        }),
+        ttl => Ok(ttl),
+    }
+}
+
+/// Resolves the provider preset; `None` if neither the section nor any
+/// `--provider*` flag is set.
+///
+/// The flags override the section's `name`, `domain`, `token` and `secret`.
+/// Records use the provider's `ttl`, else the `ttl` hint, where the
+/// provider supports one.
+pub(super) fn resolve_provider(
+    cli: &Cli,
+    toml: Option<&TomlConfig>,
+    ttl: Option<u32>,
+) -> Result<Option<Provider>, ConfigError> {
+    let file = toml.and_then(|t| t.webhook.provider.as_ref());
+    let flagged = cli.provider.is_some()
+        || cli.provider_domain.is_some()
+        || cli.provider_token.is_some()
+        || cli.provider_secret.is_some();
+    if file.is_none() && !flagged {
+        return Ok(None);
+    }
+
+    // Presets build their own bodies
+    let templated =
+        cli.body_template.is_some() || toml.is_some_and(|t| t.webhook.body_template.is_some());
+    if templated {
+        return Err(invalid("body_template cannot be combined with a provider"));
+    }
+    // Provider APIs never echo the nonce
+    if toml.is_some_and(|t| t.webhook.echo_check.is_some()) {
+        return Err(invalid("echo_check cannot be combined with a provider"));
+    }
+    // Providers check their own answers, which are not all JSON
+    if toml.is_some_and(|t| t.webhook.expect_json.is_some()) {
+        return Err(invalid("expect_json cannot be combined with a provider"));
+    }
+    if toml.is_some_and(|t| t.webhook.expect_body_regex.is_some()) {
+        return Err(invalid(
+            "expect_body_regex cannot be combined with a provider",
+        ));
+    }
+    // Providers authenticate with their own credentials
+    if toml.is_some_and(|t| t.webhook.oauth2.is_some()) {
+        return Err(invalid("oauth2 cannot be combined with a provider"));
+    }
+
+    let unset = ProviderSection::default();
+    let file = file.unwrap_or(&unset);
+    let section = ProviderSection {
+        name: cli.provider.clone().or_else(|| file.name.clone()),
+        domain: cli.provider_domain.clone().or_else(|| file.domain.clone()),
+        record: file.record.clone(),
+        token: cli.provider_token.clone().or_else(|| file.token.clone()),
+        secret: cli.provider_secret.clone().or_else(|| file.secret.clone()),
+        ttl: file.ttl,
+    };
+    parse_provider(&section, ttl).map(Some)
+}
+
+fn parse_provider(section: &ProviderSection, ttl: Option<u32>) -> Result<Provider, ConfigError> {
+    let name = required(section.name.as_deref(), "name")?;
+    let kind = ProviderKind::from_name(name).ok_or_else(|| {
+        let names: Vec<_> = ProviderKind::ALL.iter().map(|k| k.name()).collect();
+        ConfigError::InvalidProvider(format!(
+            "name '{name}' must be one of: {}",
+            names.join(", ")
+        ))
+    })?;
+
+    let domain = required(section.domain.as_deref(), "domain")?;
+    let token = credential(section.token.as_deref(), "token")?;
+    let mut provider = Provider::new(kind, domain, token);
+
+    match (kind.needs_secret(), section.secret.as_deref()) {
+        (true, secret) => provider = provider.with_secret(credential(secret, "secret")?),
+        (false, Some(_)) => {
+            return Err(ConfigError::InvalidProvider(format!(
+                "secret does not apply to name = \"{kind}\""
+            )));
+        }
+        (false, None) => {}
+    }
+
+    if let Some(record) = section.record.as_deref() {
+        if !kind.has_records() {
+            return Err(ConfigError::InvalidProvider(format!(
+                "record does not apply to name = \"{kind}\""
+            )));
+        }
+        let record = record.trim();
+        // "@" and "" both name the apex
+        if !record.is_empty() && record != "@" {
+            provider = provider.with_record(record);
+        }
+    }
+
+    let has_ttl = matches!(kind, ProviderKind::Porkbun | ProviderKind::Gandi);
+    if let Some(ttl) = section.ttl {
+        if !has_ttl {
+            return Err(ConfigError::InvalidProvider(format!(
+                "ttl does not apply to name = \"{kind}\""
+            )));
+        }
+        if ttl == 0 {
+            return Err(invalid("ttl must be greater than 0"));
+        }
+        provider = provider.with_ttl(ttl);
+    } else if let Some(ttl) = ttl.filter(|_| has_ttl) {
+        provider = provider.with_ttl(ttl);
+    }
+
+    Ok(provider)
+}
+
+fn invalid(reason: &str) -> ConfigError {
+    ConfigError::InvalidProvider(reason.to_string())
+}
+
+fn required<'a>(value: Option<&'a str>, key: &str) -> Result<&'a str, ConfigError> {
+    value
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| ConfigError::InvalidProvider(format!("{key} is required")))
+}
+
+/// A required value that is also sent in a header or URL as is.
+fn credential<'a>(value: Option<&'a str>, key: &str) -> Result<&'a str, ConfigError> {
+    let value = required(value, key)?;
+    if HeaderValue::from_str(value).is_err() || value.contains(char::is_whitespace) {
+        return Err(ConfigError::InvalidProvider(format!(
+            "{key} must not contain whitespace or control characters"
+        )));
+    }
+    Ok(value)
+}
@@ -0,0 +1,167 @@
+//! Resolution of the `[retry]` section.
+
+use std::time::Duration;
+
+use crate::state::QueueLimits;
+use crate::webhook::{JitterMode, OverlapCheck, RetryOverlap, RetryOverrides, RetryPolicy};
+
+use crate::config::cli::Cli;
+use crate::config::defaults;
+use crate::config::error::ConfigError;
+use crate::config::parse::{parse_jitter, parse_overlap_check};
+use crate::config::template::validate_template;
+use crate::config::toml::TomlConfig;
+
+/// Builds the retry policy; CLI values take precedence over `[retry]`.
+pub(super) fn build_retry_policy(
+    cli: &Cli,
+    toml: Option<&TomlConfig>,
+) -> Result<RetryPolicy, ConfigError> {
+    let retry = toml.map(|t| &t.retry);
+
+    // Priority: CLI explicit > TOML > default
+    let max_attempts = cli
+        .retry_max
+        .or_else(|| retry.and_then(|r| r.max_attempts))
+        .unwrap_or(defaults::RETRY_MAX_ATTEMPTS);
+
+    let initial_delay_secs = cli
+        .retry_delay
+        .or_else(|| retry.and_then(|r| r.initial_delay))
+        .unwrap_or(defaults::RETRY_INITIAL_DELAY_SECS);
+
+    let max_delay_secs = retry
+        .and_then(|r| r.max_delay)
+        .unwrap_or(defaults::RETRY_MAX_DELAY_SECS);
+
+    let multiplier = retry
+        .and_then(|r| r.multiplier)
+        .unwrap_or(defaults::RETRY_MULTIPLIER);
+
+    let jitter = retry
+        .and_then(|r| r.jitter.as_deref())
+        .map_or(Ok(JitterMode::None), parse_jitter)?;
+
+    retry_policy(max_attempts, initial_delay_secs, max_delay_secs, multiplier)
+        .map(|policy| policy.with_jitter(jitter))
+        .map_err(ConfigError::InvalidRetry)
+}
+
+/// Validates retry values and builds their policy.
+pub(super) fn retry_policy(
+    max_attempts: u32,
+    initial_delay_secs: u64,
+    max_delay_secs: u64,
+    multiplier: f64,
+) -> Result<RetryPolicy, String> {
+    if max_attempts == 0 {
+        return Err("max_attempts must be greater than 0".to_string());
+    }
+
+    if initial_delay_secs == 0 {
+        return Err("initial_delay must be greater than 0".to_string());
+    }
+
+    if multiplier <= 0.0 || !multiplier.is_finite() {
+        return Err("multiplier must be a positive finite number".to_string());
+    }
+
+    if max_delay_secs < initial_delay_secs {
+        return Err(format!(
+            "max_delay ({max_delay_secs}s) must be >= initial_delay ({initial_delay_secs}s)"
+        ));
+    }
+
+    Ok(RetryPolicy::new()
+        .with_max_attempts(max_attempts)
+        .with_initial_delay(Duration::from_secs(initial_delay_secs))
+        .with_max_delay(Duration::from_secs(max_delay_secs))
+        .with_multiplier(multiplier))
+}
+
+/// Builds the request changes applied to retry attempts.
+pub(super) fn build_retry_overrides(
+    toml: Option<&TomlConfig>,
+) -> Result<RetryOverrides, ConfigError> {
+    let Some(retry) = toml.map(|t| &t.retry) else {
+        return Ok(RetryOverrides::new());
+    };
+
+    let mut overrides = RetryOverrides::new();
+
+    if let Some(ref template) = retry.body_template {
+        validate_template(template, "retry.body_template")?;
+        overrides = overrides.with_body_template(template);
+    }
+
+    if let Some(ref query) = retry.query {
+        let (name, value) = query
+            .split_once('=')
+            .filter(|(name, _)| !name.trim().is_empty())
+            .ok_or_else(|| {
+                ConfigError::InvalidRetry(format!("query '{query}' must be in 'name=value' format"))
+            })?;
+        overrides = overrides.with_query(name.trim(), value.trim());
+    }
+
+    Ok(overrides)
+}
+
+/// Compares the worst-case wait between retries with the shortest poll
+/// interval, as `retry.overlap` asks (default: warn).
+pub(super) fn check_retry_overlap(
+    toml: Option<&TomlConfig>,
+    policy: &RetryPolicy,
+    poll_interval: Duration,
+) -> Result<Option<RetryOverlap>, ConfigError> {
+    let check = toml
+        .and_then(|t| t.retry.overlap.as_deref())
+        .map_or(Ok(OverlapCheck::Warn), parse_overlap_check)?;
+
+    match (check, RetryOverlap::check(policy, poll_interval)) {
+        (OverlapCheck::Off, _) | (_, None) => Ok(None),
+        (OverlapCheck::Warn, overlap) => Ok(overlap),
+        (OverlapCheck::Error, Some(overlap)) => Err(ConfigError::RetryOverlap(overlap)),
+    }
+}
+
+/// Resolves how long deliveries may keep retrying after a stop signal.
+pub(super) fn resolve_shutdown_grace(toml: Option<&TomlConfig>) -> Duration {
+    toml.and_then(|t| t.retry.shutdown_grace)
+        .map_or(Duration::ZERO, Duration::from_secs)
+}
+
+/// Resolves the `[retry.queue]` limits; `None` if the section is absent.
+pub(super) fn resolve_retry_queue(
+    toml: Option<&TomlConfig>,
+    has_state_file: bool,
+) -> Result<Option<QueueLimits>, ConfigError> {
+    let Some(queue) = toml.and_then(|t| t.retry.queue.as_ref()) else {
+        return Ok(None);
+    };
+    // The queue lives in the state file
+    if !has_state_file {
+        return Err(ConfigError::InvalidRetry(
+            "queue requires a state file (--state-file or state_file)".to_string(),
+        ));
+    }
+
+    let mut limits = QueueLimits::default();
+    if let Some(max_age) = queue.max_age {
+        if max_age == 0 {
+            return Err(ConfigError::InvalidRetry(
+                "queue.max_age must be greater than 0".to_string(),
+            ));
+        }
+        limits.max_age = Duration::from_secs(max_age);
+    }
+    if let Some(max_size) = queue.max_size {
+        if max_size == 0 {
+            return Err(ConfigError::InvalidRetry(
+                "queue.max_size must be greater than 0".to_string(),
+            ));
+        }
+        limits.max_size = max_size;
+    }
+    Ok(Some(limits))
+}
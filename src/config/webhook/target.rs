@@ -0,0 +1,336 @@
+//! Resolution of the `[[webhook.targets]]` array.
+
+use std::time::Duration;
+
+use http::header::AUTHORIZATION;
+use http::{HeaderMap, Method};
+use regex::Regex;
+use url::Url;
+
+use crate::network::IpVersion;
+use crate::webhook::{
+    AdapterRoute, ChatService, CircuitPolicy, ConnectionSettings, DeliveryMode, RetryPolicy,
+};
+
+use super::circuit::circuit_policy;
+use super::retry::retry_policy;
+use crate::config::defaults;
+use crate::config::error::ConfigError;
+use crate::config::parse::{
+    parse_delivery_mode, parse_header_name, parse_header_value, parse_route,
+};
+use crate::config::template::{validate_header_templates, validate_template};
+use crate::config::toml::{TargetSection, TomlConfig};
+use crate::config::{EMAIL_TARGET, EXEC_TARGET, MQTT_TARGET};
+
+/// Name of the main `[webhook]` target in logs and reports.
+pub const MAIN_TARGET: &str = "webhook";
+
+/// A URL receiving the address changes of one IP version instead of the
+/// main webhook (`[webhook.ipv4]`, `[webhook.ipv6]`).
+///
+/// It is sent to like the main webhook, with all of its settings; the main
+/// webhook keeps the other version and the changes without an address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionUrl {
+    /// IP version of the routed addresses, never [`IpVersion::Both`]
+    pub version: IpVersion,
+
+    /// Target URL
+    pub url: Url,
+}
+
+impl VersionUrl {
+    /// Returns the name of the target in logs and reports, e.g. `webhook.ipv4`.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        version_target(self.version)
+    }
+}
+
+/// Name of the target receiving the addresses of `version`.
+const fn version_target(version: IpVersion) -> &'static str {
+    match version {
+        IpVersion::V6 => "webhook.ipv6",
+        IpVersion::V4 | IpVersion::Both => "webhook.ipv4",
+    }
+}
+
+/// A further webhook every change is also sent to.
+#[derive(Debug, Clone)]
+pub struct WebhookTarget {
+    /// Name shown in logs and reports
+    pub name: String,
+
+    /// Target URL
+    pub url: Url,
+
+    /// HTTP method
+    pub method: Method,
+
+    /// Request headers, including `Authorization` for a bearer token
+    pub headers: HeaderMap,
+
+    /// Handlebars body template; `None` sends the JSON payload
+    pub body_template: Option<String>,
+
+    /// How address changes are split into requests
+    pub delivery_mode: DeliveryMode,
+
+    /// Retry policy of this target alone
+    pub retry_policy: RetryPolicy,
+
+    /// Connection settings of this target alone, `[http]` with its own
+    /// timeouts
+    pub connection: ConnectionSettings,
+
+    /// Circuit breaker policy of this target alone; `None` if circuit
+    /// breaking is disabled for it
+    pub circuit_breaker: Option<CircuitPolicy>,
+
+    /// Adapters whose changes this target receives
+    pub route: AdapterRoute,
+}
+
+/// Resolves `webhook.adapters` and `webhook.added_only`, the route of the
+/// main webhook.
+pub(super) fn resolve_route(toml: Option<&TomlConfig>) -> Result<AdapterRoute, ConfigError> {
+    let patterns = toml.map_or(&[][..], |t| &t.webhook.adapters);
+    let added_only = toml.is_some_and(|t| t.webhook.added_only);
+    patterns
+        .iter()
+        .map(|pattern| {
+            Regex::new(pattern).map_err(|source| ConfigError::InvalidRegex {
+                pattern: pattern.clone(),
+                source,
+            })
+        })
+        .collect::<Result<_, _>>()
+        .map(|patterns| AdapterRoute::new(patterns).with_added_only(added_only))
+}
+
+/// Resolves the additional targets; unset retry values are taken from
+/// `retry`, the main webhook's policy, unset timeouts from `connection`,
+/// the `[http]` settings, and unset circuit breaker values from
+/// `circuit_breaker`, the `[webhook.circuit_breaker]` policy.
+///
+/// Names must be unique (including [`MAIN_TARGET`], [`EXEC_TARGET`],
+/// [`MQTT_TARGET`] and [`EMAIL_TARGET`]), since failures are reported by name.
+pub(super) fn resolve_targets(
+    toml: Option<&TomlConfig>,
+    retry: &RetryPolicy,
+    connection: &ConnectionSettings,
+    circuit_breaker: Option<CircuitPolicy>,
+    ip_version: IpVersion,
+) -> Result<Vec<WebhookTarget>, ConfigError> {
+    let sections = toml.map_or(&[][..], |t| &t.webhook.targets);
+    let mut targets: Vec<WebhookTarget> = Vec::with_capacity(sections.len());
+    for (index, section) in sections.iter().enumerate() {
+        let target = resolve_target(section, retry, connection, circuit_breaker, ip_version)
+            .map_err(|reason| with_context(&reason, section.name.as_deref(), index))?;
+        let reserved = [
+            MAIN_TARGET,
+            EXEC_TARGET,
+            MQTT_TARGET,
+            EMAIL_TARGET,
+            version_target(IpVersion::V4),
+            version_target(IpVersion::V6),
+        ];
+        let chat = ChatService::ALL
+            .iter()
+            .any(|service| service.target_name() == target.name);
+        if reserved.contains(&target.name.as_str())
+            || chat
+            || targets.iter().any(|t| t.name == target.name)
+        {
+            return Err(ConfigError::InvalidTarget(format!(
+                "name '{}' is used twice",
+                target.name
+            )));
+        }
+        targets.push(target);
+    }
+    Ok(targets)
+}
+
+fn resolve_target(
+    section: &TargetSection,
+    retry: &RetryPolicy,
+    connection: &ConnectionSettings,
+    circuit_breaker: Option<CircuitPolicy>,
+    ip_version: IpVersion,
+) -> Result<WebhookTarget, String> {
+    let url = section.url.as_deref().ok_or("url is required")?;
+    let url = Url::parse(url).map_err(|e| format!("url '{url}': {e}"))?;
+    let name = match section.name.as_deref().map(str::trim) {
+        Some("") => return Err("name must not be empty".to_string()),
+        Some(name) => name.to_string(),
+        None => url.host_str().unwrap_or(url.as_str()).to_string(),
+    };
+
+    let method = section.method.as_deref().unwrap_or(defaults::METHOD);
+    let method = method
+        .parse::<Method>()
+        .map_err(|_| format!("invalid HTTP method '{method}'"))?;
+
+    let mut headers = HeaderMap::new();
+    for (header, value) in &section.headers {
+        let name = parse_header_name(header).map_err(|e| e.to_string())?;
+        let value = parse_header_value(header, value).map_err(|e| e.to_string())?;
+        headers.insert(name, value);
+    }
+    if let Some(ref token) = section.bearer {
+        let value = parse_header_value("Authorization", &format!("Bearer {token}"))
+            .map_err(|e| e.to_string())?;
+        headers.insert(AUTHORIZATION, value);
+    }
+    validate_header_templates(&headers, "webhook.targets headers").map_err(|e| e.to_string())?;
+
+    if let Some(ref template) = section.body_template {
+        validate_template(template, "webhook.targets body_template").map_err(|e| e.to_string())?;
+    }
+
+    let delivery_mode = section
+        .delivery
+        .as_deref()
+        .map_or(Ok(DeliveryMode::Batched), parse_delivery_mode)
+        .map_err(|e| e.to_string())?;
+
+    let overrides = section.retry.as_ref();
+    let retry_policy = retry_policy(
+        overrides
+            .and_then(|r| r.max_attempts)
+            .unwrap_or(retry.max_attempts),
+        overrides
+            .and_then(|r| r.initial_delay)
+            .unwrap_or(retry.initial_delay.as_secs()),
+        overrides
+            .and_then(|r| r.max_delay)
+            .unwrap_or(retry.max_delay.as_secs()),
+        overrides
+            .and_then(|r| r.multiplier)
+            .unwrap_or(retry.multiplier),
+    )
+    .map_err(|reason| format!("retry: {reason}"))?
+    .with_jitter(retry.jitter);
+
+    let connection = target_connection(section, connection)?;
+    let circuit_breaker = section
+        .circuit_breaker
+        .as_ref()
+        .map(|overrides| circuit_policy(overrides, circuit_breaker.unwrap_or_default()))
+        .transpose()
+        .map_err(|reason| format!("circuit_breaker: {reason}"))?
+        .or(circuit_breaker);
+
+    let route = parse_route(
+        &section.adapters,
+        section.ip_version.as_deref(),
+        section.added_only,
+        ip_version,
+    )?;
+
+    Ok(WebhookTarget {
+        name,
+        url,
+        method,
+        headers,
+        body_template: section.body_template.clone(),
+        delivery_mode,
+        retry_policy,
+        connection,
+        circuit_breaker,
+        route,
+    })
+}
+
+/// Returns `connection` with the timeouts set in `section`.
+fn target_connection(
+    section: &TargetSection,
+    connection: &ConnectionSettings,
+) -> Result<ConnectionSettings, String> {
+    let mut connection = connection.clone();
+    let timeouts = [
+        (
+            "connect_timeout",
+            section.connect_timeout,
+            &mut connection.connect_timeout,
+        ),
+        (
+            "request_timeout",
+            section.request_timeout,
+            &mut connection.request_timeout,
+        ),
+    ];
+    for (field, seconds, timeout) in timeouts {
+        match seconds {
+            Some(0) => return Err(format!("{field} must be greater than 0")),
+            Some(seconds) => *timeout = Duration::from_secs(seconds),
+            None => {}
+        }
+    }
+    if connection.connect_timeout > connection.request_timeout {
+        return Err(format!(
+            "connect_timeout must not exceed request_timeout ({}s)",
+            connection.request_timeout.as_secs()
+        ));
+    }
+    Ok(connection)
+}
+
+/// Resolves `[webhook.ipv4]` and `[webhook.ipv6]`, in that order.
+///
+/// A version must be monitored to get its own URL. A provider preset
+/// updates both record types itself, so it cannot be combined with them.
+pub(super) fn resolve_version_urls(
+    toml: Option<&TomlConfig>,
+    ip_version: IpVersion,
+    has_provider: bool,
+) -> Result<Vec<VersionUrl>, ConfigError> {
+    let Some(webhook) = toml.map(|t| &t.webhook) else {
+        return Ok(Vec::new());
+    };
+    let sections = [
+        (
+            IpVersion::V4,
+            webhook.ipv4.as_ref(),
+            ip_version.includes_v4(),
+        ),
+        (
+            IpVersion::V6,
+            webhook.ipv6.as_ref(),
+            ip_version.includes_v6(),
+        ),
+    ];
+    let mut urls = Vec::new();
+    for (version, section, monitored) in sections {
+        let Some(section) = section else {
+            continue;
+        };
+        let name = version_target(version);
+        let invalid = |reason: String| ConfigError::InvalidVersionUrl(format!("{name}: {reason}"));
+        if !monitored {
+            return Err(invalid(format!(
+                "{version} is not monitored (ip_version is {ip_version})"
+            )));
+        }
+        if has_provider {
+            return Err(invalid(
+                "cannot be combined with [webhook.provider]".to_string(),
+            ));
+        }
+        let url = section
+            .url
+            .as_deref()
+            .ok_or_else(|| invalid("url is required".to_string()))?;
+        let url = Url::parse(url).map_err(|e| invalid(format!("url '{url}': {e}")))?;
+        urls.push(VersionUrl { version, url });
+    }
+    Ok(urls)
+}
+
+/// Names the target a `reason` is about: by name if set, else by position.
+fn with_context(reason: &str, name: Option<&str>, index: usize) -> ConfigError {
+    let target = name.map_or_else(|| format!("#{}", index + 1), |name| format!("'{name}'"));
+    ConfigError::InvalidTarget(format!("target {target}: {reason}"))
+}
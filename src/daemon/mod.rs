@@ -0,0 +1,85 @@
+//! Process management for init systems without native service supervision.
+//!
+//! This module provides a [`PidFile`] guard that records the running
+//! process and removes the file on shutdown, and (on Unix) [`daemonize`]
+//! for init systems such as `OpenWrt` procd or BSD rc scripts that expect
+//! the program to detach itself, and [`signal_restart`]/[`exec_restart`]
+//! for restarts that hand pending state over to the new binary. Under
+//! systemd, [`NotifyFetcher`] reports readiness and feeds the watchdog.
+
+mod pidfile;
+#[cfg(unix)]
+mod restart;
+#[cfg(unix)]
+mod systemd;
+#[cfg(unix)]
+mod unix;
+
+#[cfg(test)]
+#[path = "mod_tests.rs"]
+mod tests;
+
+pub use pidfile::PidFile;
+#[cfg(unix)]
+pub use restart::{HANDOFF_ENV, exec_restart, is_handoff, signal_restart};
+#[cfg(unix)]
+pub use systemd::{NOTIFY_SOCKET_ENV, Notifier, NotifyFetcher};
+#[cfg(unix)]
+pub use unix::daemonize;
+
+use std::io;
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// Errors that can occur while preparing the process to run as a daemon.
+#[derive(Debug, Error)]
+pub enum DaemonError {
+    /// The PID file names a process that is still running.
+    #[error("Another instance is already running (pid {pid}, pid file '{}')", path.display())]
+    AlreadyRunning {
+        /// PID recorded in the file
+        pid: u32,
+        /// Path to the PID file
+        path: PathBuf,
+    },
+
+    /// Failed to write the PID file.
+    #[error("Failed to write pid file '{}': {source}", path.display())]
+    PidFile {
+        /// Path to the PID file
+        path: PathBuf,
+        /// Underlying I/O error
+        #[source]
+        source: io::Error,
+    },
+
+    /// A fork, `setsid` or stdio redirection step failed.
+    #[error("Failed to daemonize: {0}")]
+    Daemonize(#[source] io::Error),
+
+    /// The PID file is missing or names no running process.
+    #[error("No running instance found (pid file '{}')", path.display())]
+    NotRunning {
+        /// Path to the PID file
+        path: PathBuf,
+    },
+
+    /// Failed to signal the running instance.
+    #[error("Failed to signal pid {pid}: {source}")]
+    Signal {
+        /// PID of the instance
+        pid: u32,
+        /// Underlying I/O error
+        #[source]
+        source: io::Error,
+    },
+
+    /// Failed to start the executable in place of this process.
+    #[error("Failed to restart: {0}")]
+    Exec(#[source] io::Error),
+
+    /// Failed to send a notification to systemd.
+    #[error("Failed to notify systemd: {0}")]
+    Notify(#[source] io::Error),
+}
@@ -0,0 +1,265 @@
+//! Tests for the daemon module.
+
+use std::fs;
+
+use tempfile::TempDir;
+
+use super::DaemonError;
+use super::pidfile::PidFile;
+
+mod pid_file {
+    use super::*;
+
+    #[test]
+    fn writes_current_pid() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("ddns-a.pid");
+
+        let pid_file = PidFile::create(&path).unwrap();
+
+        assert_eq!(pid_file.pid(), std::process::id());
+        assert_eq!(pid_file.path(), path);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("{}\n", std::process::id())
+        );
+    }
+
+    #[test]
+    fn creates_parent_directories() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("run").join("ddns-a.pid");
+
+        let _pid_file = PidFile::create(&path).unwrap();
+
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn removed_on_drop() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("ddns-a.pid");
+
+        drop(PidFile::create(&path).unwrap());
+
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn not_removed_after_takeover() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("ddns-a.pid");
+        let pid_file = PidFile::create(&path).unwrap();
+
+        fs::write(&path, "999999999\n").unwrap();
+        drop(pid_file);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "999999999\n");
+    }
+
+    #[test]
+    fn replaces_garbage_contents() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("ddns-a.pid");
+        fs::write(&path, "not a pid").unwrap();
+
+        let pid_file = PidFile::create(&path).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap().trim(),
+            pid_file.pid().to_string()
+        );
+    }
+
+    #[test]
+    fn replaces_own_pid() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("ddns-a.pid");
+        fs::write(&path, format!("{}\n", std::process::id())).unwrap();
+
+        assert!(PidFile::create(&path).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_live_process() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("ddns-a.pid");
+        // PID 1 (init) always exists
+        fs::write(&path, "1\n").unwrap();
+
+        let result = PidFile::create_for(path.clone(), 4242);
+
+        assert!(matches!(
+            result,
+            Err(DaemonError::AlreadyRunning { pid: 1, .. })
+        ));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn replaces_stale_pid() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("ddns-a.pid");
+        // Above the Linux pid_max ceiling, so no such process can exist
+        fs::write(&path, "999999999\n").unwrap();
+
+        let pid_file = PidFile::create_for(path.clone(), 4242).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "4242\n");
+        drop(pid_file);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn unwritable_path_is_error() {
+        let dir = TempDir::new().unwrap();
+        // A regular file cannot be a parent directory
+        let blocker = dir.path().join("file");
+        fs::write(&blocker, "").unwrap();
+
+        let result = PidFile::create(blocker.join("ddns-a.pid"));
+
+        assert!(matches!(result, Err(DaemonError::PidFile { .. })));
+    }
+}
+
+#[cfg(unix)]
+mod restart {
+    use super::*;
+    use crate::daemon::restart::{signal_restart, undeleted};
+
+    #[test]
+    fn missing_pid_file_is_not_running() {
+        let dir = TempDir::new().unwrap();
+
+        let result = signal_restart(&dir.path().join("ddns-a.pid"));
+
+        assert!(matches!(result, Err(DaemonError::NotRunning { .. })));
+    }
+
+    #[test]
+    fn stale_pid_is_not_running() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("ddns-a.pid");
+        fs::write(&path, "999999999\n").unwrap();
+
+        let result = signal_restart(&path);
+
+        assert!(matches!(result, Err(DaemonError::NotRunning { .. })));
+    }
+
+    #[test]
+    fn replaced_executable_is_started_from_its_path() {
+        let dir = TempDir::new().unwrap();
+        let exe = dir.path().join("ddns-a");
+        fs::write(&exe, "").unwrap();
+        let deleted = dir.path().join("ddns-a (deleted)");
+
+        assert_eq!(undeleted(deleted), exe);
+        assert_eq!(undeleted(exe.clone()), exe);
+    }
+}
+
+#[cfg(unix)]
+mod systemd {
+    use std::os::unix::net::UnixDatagram;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+    use crate::daemon::systemd::{Notifier, NotifyFetcher, watchdog_period};
+    use crate::network::{AdapterSnapshot, AddressFetcher, FetchError};
+
+    /// Fetcher failing until told otherwise.
+    struct Toggle(AtomicBool);
+
+    impl AddressFetcher for Toggle {
+        fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+            if self.0.load(Ordering::SeqCst) {
+                Ok(vec![])
+            } else {
+                Err(FetchError::Platform {
+                    message: "boom".to_string(),
+                })
+            }
+        }
+    }
+
+    fn listen(dir: &TempDir) -> (UnixDatagram, String) {
+        let path = dir.path().join("notify.sock");
+        let socket = UnixDatagram::bind(&path).unwrap();
+        socket.set_nonblocking(true).unwrap();
+        (socket, path.to_string_lossy().into_owned())
+    }
+
+    fn received(socket: &UnixDatagram) -> Vec<String> {
+        let mut buf = [0; 64];
+        std::iter::from_fn(|| {
+            let n = socket.recv(&mut buf).ok()?;
+            Some(String::from_utf8_lossy(&buf[..n]).into_owned())
+        })
+        .collect()
+    }
+
+    #[test]
+    fn ready_after_first_successful_fetch() {
+        let dir = TempDir::new().unwrap();
+        let (socket, path) = listen(&dir);
+        let notifier = Notifier::new(&path, None).unwrap();
+        let fetcher = NotifyFetcher::new(Toggle(false.into()), Some(notifier));
+
+        assert!(fetcher.fetch().is_err());
+        assert!(received(&socket).is_empty());
+
+        fetcher.inner().0.store(true, Ordering::SeqCst);
+        fetcher.fetch().unwrap();
+        fetcher.fetch().unwrap();
+        assert_eq!(received(&socket), ["READY=1"]);
+    }
+
+    #[test]
+    fn watchdog_fed_on_every_fetch() {
+        let dir = TempDir::new().unwrap();
+        let (socket, path) = listen(&dir);
+        let notifier = Notifier::new(&path, Some(Duration::from_secs(30))).unwrap();
+        let fetcher = NotifyFetcher::new(Toggle(false.into()), Some(notifier));
+
+        assert!(fetcher.fetch().is_err());
+        fetcher.inner().0.store(true, Ordering::SeqCst);
+        fetcher.fetch().unwrap();
+
+        assert_eq!(received(&socket), ["WATCHDOG=1", "READY=1", "WATCHDOG=1"]);
+    }
+
+    #[test]
+    fn send_failures_do_not_fail_fetches() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("missing.sock");
+        let notifier = Notifier::new(&path.to_string_lossy(), None).unwrap();
+
+        assert!(matches!(
+            notifier.notify("READY=1"),
+            Err(DaemonError::Notify(_))
+        ));
+        let fetcher = NotifyFetcher::new(Toggle(true.into()), Some(notifier));
+        assert!(fetcher.fetch().is_ok());
+    }
+
+    #[test]
+    fn watchdog_period_for_this_process_only() {
+        assert_eq!(
+            watchdog_period(Some("30000000"), None, 7),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            watchdog_period(Some("30000000"), Some("7"), 7),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(watchdog_period(Some("30000000"), Some("8"), 7), None);
+        assert_eq!(watchdog_period(Some("0"), None, 7), None);
+        assert_eq!(watchdog_period(Some("soon"), None, 7), None);
+        assert_eq!(watchdog_period(None, None, 7), None);
+    }
+}
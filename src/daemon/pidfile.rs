@@ -0,0 +1,89 @@
+//! PID file guard.
+
+use std::path::{Path, PathBuf};
+
+use super::DaemonError;
+
+/// A PID file holding the current process ID, removed when dropped.
+///
+/// A file left behind by a crashed instance is replaced. On Unix, a file
+/// naming a process that is still running is rejected so two instances
+/// cannot share one PID file; other platforms always replace it.
+#[derive(Debug)]
+pub struct PidFile {
+    path: PathBuf,
+    pid: u32,
+}
+
+impl PidFile {
+    /// Writes the current process ID to `path`, creating parent directories.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DaemonError::AlreadyRunning`] if the file names another
+    /// live process, or [`DaemonError::PidFile`] if it cannot be written.
+    pub fn create(path: impl Into<PathBuf>) -> Result<Self, DaemonError> {
+        Self::create_for(path.into(), std::process::id())
+    }
+
+    /// Writes `pid` to `path` (split out so tests can use other PIDs).
+    pub(super) fn create_for(path: PathBuf, pid: u32) -> Result<Self, DaemonError> {
+        if let Some(existing) = read_pid(&path) {
+            if existing != pid && process_exists(existing) {
+                return Err(DaemonError::AlreadyRunning {
+                    pid: existing,
+                    path,
+                });
+            }
+        }
+
+        let write = || -> std::io::Result<()> {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, format!("{pid}\n"))
+        };
+        match write() {
+            Ok(()) => Ok(Self { path, pid }),
+            Err(source) => Err(DaemonError::PidFile { path, source }),
+        }
+    }
+
+    /// Returns the path of the PID file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the PID written to the file.
+    #[must_use]
+    pub const fn pid(&self) -> u32 {
+        self.pid
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        // Leave the file alone if another instance has since taken it over
+        if read_pid(&self.path) == Some(self.pid) {
+            if let Err(e) = std::fs::remove_file(&self.path) {
+                tracing::warn!("Failed to remove pid file {}: {e}", self.path.display());
+            }
+        }
+    }
+}
+
+/// Reads the PID stored in `path`, if the file exists and holds one.
+pub(super) fn read_pid(path: &Path) -> Option<u32> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+#[cfg(unix)]
+fn process_exists(pid: u32) -> bool {
+    super::unix::process_exists(pid)
+}
+
+#[cfg(not(unix))]
+const fn process_exists(_pid: u32) -> bool {
+    false
+}
@@ -0,0 +1,94 @@
+//! Exec-based restart with state handoff.
+//!
+//! `ddns-a ctl restart` sends `SIGUSR2` to the instance named by the PID
+//! file. That instance stops as on shutdown, which already leaves nothing
+//! pending behind: deliveries cut short are kept in the state file's outbox,
+//! and changes still in the debounce window are detected again against the
+//! snapshot saved there. It then replaces itself with the executable now on
+//! disk through `exec`, keeping its PID (so the PID file and any supervisor
+//! stay valid) and telling the new image about the handoff via
+//! [`HANDOFF_ENV`].
+
+use std::ffi::OsString;
+use std::io;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::DaemonError;
+use super::pidfile::read_pid;
+use super::unix::process_exists;
+
+/// Environment variable set for the image started by [`exec_restart`].
+pub const HANDOFF_ENV: &str = "DDNS_A_HANDOFF";
+
+/// Returns true if this process was started by [`exec_restart`].
+///
+/// Such a process is already detached, so `--daemon` is skipped.
+#[must_use]
+pub fn is_handoff() -> bool {
+    std::env::var_os(HANDOFF_ENV).is_some()
+}
+
+/// Asks the instance whose PID is in `pid_file` to restart.
+///
+/// Returns the PID that was signalled.
+///
+/// # Errors
+///
+/// Returns [`DaemonError::NotRunning`] if the file is missing or names no
+/// live process, or [`DaemonError::Signal`] if `SIGUSR2` cannot be sent.
+pub fn signal_restart(pid_file: &Path) -> Result<u32, DaemonError> {
+    let not_running = || DaemonError::NotRunning {
+        path: pid_file.to_path_buf(),
+    };
+    let pid = read_pid(pid_file).ok_or_else(not_running)?;
+    if !process_exists(pid) {
+        return Err(not_running());
+    }
+    let target = libc::pid_t::try_from(pid).map_err(|_| not_running())?;
+
+    // SAFETY: kill has no memory-safety preconditions.
+    if unsafe { libc::kill(target, libc::SIGUSR2) } < 0 {
+        return Err(DaemonError::Signal {
+            pid,
+            source: io::Error::last_os_error(),
+        });
+    }
+    Ok(pid)
+}
+
+/// Replaces the process with the current executable, run with the same
+/// arguments and [`HANDOFF_ENV`] set.
+///
+/// Destructors do not run, so a [`PidFile`](super::PidFile) keeps naming
+/// this PID, which the new image takes over.
+///
+/// Only returns if the executable cannot be started.
+#[must_use]
+pub fn exec_restart() -> DaemonError {
+    let executable = match std::env::current_exe() {
+        Ok(path) => undeleted(path),
+        Err(e) => return DaemonError::Exec(e),
+    };
+    let args: Vec<OsString> = std::env::args_os().skip(1).collect();
+
+    let error = Command::new(executable)
+        .args(args)
+        .env(HANDOFF_ENV, "1")
+        .exec();
+    DaemonError::Exec(error)
+}
+
+/// Strips the ` (deleted)` suffix Linux reports for an executable that was
+/// replaced on disk (e.g. by `self-update`), so the new file is started.
+pub(super) fn undeleted(path: PathBuf) -> PathBuf {
+    if path.exists() {
+        return path;
+    }
+    let original = path
+        .to_str()
+        .and_then(|s| s.strip_suffix(" (deleted)"))
+        .map(PathBuf::from);
+    original.unwrap_or(path)
+}
@@ -0,0 +1,167 @@
+//! systemd service notifications (`Type=notify`).
+//!
+//! systemd passes a datagram socket in `NOTIFY_SOCKET` to services started
+//! with `Type=notify`, and the watchdog period in `WATCHDOG_USEC` when
+//! `WatchdogSec=` is set. [`NotifyFetcher`] reports `READY=1` after the
+//! first successful fetch and feeds the watchdog with `WATCHDOG=1` after
+//! every fetch, so a hung adapter query gets the service restarted.
+
+use std::io;
+use std::os::unix::net::{SocketAddr, UnixDatagram};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::network::{AdapterSnapshot, AddressFetcher, FetchError};
+
+use super::DaemonError;
+
+/// Socket path variable set by systemd for `Type=notify` services.
+pub const NOTIFY_SOCKET_ENV: &str = "NOTIFY_SOCKET";
+
+/// Sends state messages to the systemd service manager.
+///
+/// Clones share the socket.
+#[derive(Debug, Clone)]
+pub struct Notifier {
+    socket: Arc<UnixDatagram>,
+    address: SocketAddr,
+    watchdog: Option<Duration>,
+}
+
+impl Notifier {
+    /// Connects to `$NOTIFY_SOCKET`, with the watchdog period from
+    /// `$WATCHDOG_USEC` if it is meant for this process.
+    ///
+    /// Returns `None` when not started by systemd as a `Type=notify` service.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DaemonError::Notify`] if the socket cannot be used.
+    pub fn from_env() -> Result<Option<Self>, DaemonError> {
+        let Some(path) = std::env::var_os(NOTIFY_SOCKET_ENV) else {
+            return Ok(None);
+        };
+        let watchdog = watchdog_period(
+            std::env::var("WATCHDOG_USEC").ok().as_deref(),
+            std::env::var("WATCHDOG_PID").ok().as_deref(),
+            std::process::id(),
+        );
+        Self::new(&path.to_string_lossy(), watchdog).map(Some)
+    }
+
+    /// Creates a notifier for the socket at `path`; a leading `@` names a
+    /// Linux abstract socket.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DaemonError::Notify`] if the socket cannot be created or
+    /// the path is invalid.
+    pub fn new(path: &str, watchdog: Option<Duration>) -> Result<Self, DaemonError> {
+        let address = socket_address(path).map_err(DaemonError::Notify)?;
+        let socket = UnixDatagram::unbound().map_err(DaemonError::Notify)?;
+        Ok(Self {
+            socket: Arc::new(socket),
+            address,
+            watchdog,
+        })
+    }
+
+    /// Returns the watchdog period, if systemd expects `WATCHDOG=1` pings.
+    #[must_use]
+    pub const fn watchdog(&self) -> Option<Duration> {
+        self.watchdog
+    }
+
+    /// Sends `state`, newline-separated `KEY=VALUE` assignments.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DaemonError::Notify`] if the message could not be sent.
+    pub fn notify(&self, state: &str) -> Result<(), DaemonError> {
+        self.socket
+            .send_to_addr(state.as_bytes(), &self.address)
+            .map(drop)
+            .map_err(DaemonError::Notify)
+    }
+}
+
+/// The watchdog period from `WATCHDOG_USEC`, unless `WATCHDOG_PID` names
+/// another process (the variables are inherited by children).
+pub(super) fn watchdog_period(
+    usec: Option<&str>,
+    pid: Option<&str>,
+    own_pid: u32,
+) -> Option<Duration> {
+    if pid.is_some_and(|pid| pid.trim().parse() != Ok(own_pid)) {
+        return None;
+    }
+    let usec: u64 = usec?.trim().parse().ok()?;
+    (usec > 0).then(|| Duration::from_micros(usec))
+}
+
+#[cfg(target_os = "linux")]
+fn socket_address(path: &str) -> io::Result<SocketAddr> {
+    use std::os::linux::net::SocketAddrExt;
+
+    path.strip_prefix('@').map_or_else(
+        || SocketAddr::from_pathname(path),
+        SocketAddr::from_abstract_name,
+    )
+}
+
+#[cfg(not(target_os = "linux"))]
+fn socket_address(path: &str) -> io::Result<SocketAddr> {
+    SocketAddr::from_pathname(path)
+}
+
+/// Fetcher decorator reporting readiness and liveness to systemd.
+///
+/// Without a notifier (not started by systemd), fetches pass through.
+#[derive(Debug)]
+pub struct NotifyFetcher<F> {
+    inner: F,
+    notifier: Option<Notifier>,
+    ready: AtomicBool,
+}
+
+impl<F> NotifyFetcher<F> {
+    /// Wraps `inner`, notifying through `notifier` if given.
+    #[must_use]
+    pub const fn new(inner: F, notifier: Option<Notifier>) -> Self {
+        Self {
+            inner,
+            notifier,
+            ready: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns the wrapped fetcher.
+    #[must_use]
+    pub const fn inner(&self) -> &F {
+        &self.inner
+    }
+
+    fn send(notifier: &Notifier, state: &str) {
+        if let Err(e) = notifier.notify(state) {
+            tracing::debug!("{e}");
+        }
+    }
+}
+
+impl<F: AddressFetcher> AddressFetcher for NotifyFetcher<F> {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        let result = self.inner.fetch();
+        if let Some(ref notifier) = self.notifier {
+            if result.is_ok() && !self.ready.swap(true, Ordering::Relaxed) {
+                tracing::debug!("Reporting readiness to systemd");
+                Self::send(notifier, "READY=1");
+            }
+            // Failed fetches still show the loop is alive
+            if notifier.watchdog().is_some() {
+                Self::send(notifier, "WATCHDOG=1");
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,78 @@
+//! Unix self-daemonization.
+
+use std::fs::OpenOptions;
+use std::io;
+use std::os::fd::AsRawFd;
+
+use super::DaemonError;
+
+/// Detaches the process from its terminal and parent.
+///
+/// Performs the classic double fork: the first fork lets the invoking
+/// shell or init script return, `setsid` starts a new session without a
+/// controlling terminal, and the second fork ensures the daemon can never
+/// reacquire one. Standard input, output and error are redirected to
+/// `/dev/null`. The working directory is kept so relative paths in the
+/// configuration still resolve.
+///
+/// Must be called before any threads are started (in particular before
+/// the tokio runtime is created): `fork` only carries the calling thread
+/// into the child.
+///
+/// # Errors
+///
+/// Returns [`DaemonError::Daemonize`] if any step fails. The original
+/// parent process exits with status 0 and never returns.
+pub fn daemonize() -> Result<(), DaemonError> {
+    fork_and_exit_parent().map_err(DaemonError::Daemonize)?;
+
+    // SAFETY: setsid has no memory-safety preconditions.
+    if unsafe { libc::setsid() } < 0 {
+        return Err(DaemonError::Daemonize(io::Error::last_os_error()));
+    }
+
+    fork_and_exit_parent().map_err(DaemonError::Daemonize)?;
+    redirect_stdio().map_err(DaemonError::Daemonize)
+}
+
+/// Forks; the parent exits immediately and the child continues.
+fn fork_and_exit_parent() -> io::Result<()> {
+    // SAFETY: called while the process is single-threaded (see `daemonize`).
+    match unsafe { libc::fork() } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => Ok(()),
+        // SAFETY: _exit skips destructors and atexit handlers, which belong to the child now.
+        _ => unsafe { libc::_exit(0) },
+    }
+}
+
+/// Points stdin, stdout and stderr at `/dev/null`.
+fn redirect_stdio() -> io::Result<()> {
+    let null = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/null")?;
+
+    for fd in [libc::STDIN_FILENO, libc::STDOUT_FILENO, libc::STDERR_FILENO] {
+        // SAFETY: both descriptors are valid for the duration of the call.
+        if unsafe { libc::dup2(null.as_raw_fd(), fd) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// Returns true if a process with `pid` exists.
+///
+/// Signal 0 performs the permission and existence checks without sending
+/// anything; `EPERM` means the process exists but belongs to another user.
+pub(super) fn process_exists(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: kill with signal 0 has no side effects.
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return true;
+    }
+    io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
@@ -0,0 +1,250 @@
+//! DNS wire format (RFC 1035) for single-question queries.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use super::{DnsError, SrvRecord};
+
+/// Record types this client queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum RecordType {
+    A,
+    Aaaa,
+    Txt,
+    Srv,
+}
+
+impl RecordType {
+    const fn code(self) -> u16 {
+        match self {
+            Self::A => 1,
+            Self::Aaaa => 28,
+            Self::Txt => 16,
+            Self::Srv => 33,
+        }
+    }
+}
+
+/// Decoded answer data for a [`RecordType`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum RecordData {
+    Address(IpAddr),
+    Txt(String),
+    Srv(SrvRecord),
+}
+
+const CLASS_IN: u16 = 1;
+const HEADER_LEN: usize = 12;
+const FLAG_RESPONSE: u16 = 0x8000;
+const FLAG_TRUNCATED: u16 = 0x0200;
+const FLAG_RECURSION_DESIRED: u16 = 0x0100;
+const RCODE_NXDOMAIN: u8 = 3;
+const MAX_NAME_LEN: usize = 255;
+const MAX_LABEL_LEN: usize = 63;
+/// Upper bound on compression pointers followed while reading one name.
+const MAX_POINTER_JUMPS: usize = 16;
+
+/// Encodes a recursive query for `name` with transaction id `id`.
+pub(super) fn encode_query(id: u16, name: &str, kind: RecordType) -> Result<Vec<u8>, DnsError> {
+    let invalid = || DnsError::InvalidName {
+        name: name.to_string(),
+    };
+    let trimmed = name.strip_suffix('.').unwrap_or(name);
+    if trimmed.is_empty() || trimmed.len() > MAX_NAME_LEN - 2 {
+        return Err(invalid());
+    }
+
+    let mut msg = Vec::with_capacity(HEADER_LEN + trimmed.len() + 6);
+    msg.extend_from_slice(&id.to_be_bytes());
+    msg.extend_from_slice(&FLAG_RECURSION_DESIRED.to_be_bytes());
+    msg.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0]);
+    for label in trimmed.split('.') {
+        let len = u8::try_from(label.len())
+            .ok()
+            .filter(|&len| len > 0 && usize::from(len) <= MAX_LABEL_LEN)
+            .ok_or_else(invalid)?;
+        msg.push(len);
+        msg.extend_from_slice(label.as_bytes());
+    }
+    msg.push(0);
+    msg.extend_from_slice(&kind.code().to_be_bytes());
+    msg.extend_from_slice(&CLASS_IN.to_be_bytes());
+    Ok(msg)
+}
+
+/// Decodes the answers of type `kind` from a response to query `id`.
+///
+/// Answers of other types (e.g. the CNAME chain leading to the records) are
+/// skipped. NXDOMAIN yields an empty list.
+pub(super) fn decode_response(
+    id: u16,
+    msg: &[u8],
+    kind: RecordType,
+) -> Result<Vec<RecordData>, DnsError> {
+    let mut reader = Reader { msg, pos: 0 };
+    if reader.u16()? != id {
+        return Err(DnsError::Malformed("transaction id mismatch"));
+    }
+    let flags = reader.u16()?;
+    if flags & FLAG_RESPONSE == 0 {
+        return Err(DnsError::Malformed("not a response"));
+    }
+    if flags & FLAG_TRUNCATED != 0 {
+        return Err(DnsError::Truncated);
+    }
+    match (flags & 0x000f) as u8 {
+        0 => {}
+        RCODE_NXDOMAIN => return Ok(Vec::new()),
+        rcode => return Err(DnsError::ServerFailure { rcode }),
+    }
+
+    let questions = reader.u16()?;
+    let answers = reader.u16()?;
+    reader.skip(4)?; // authority and additional counts
+
+    for _ in 0..questions {
+        reader.name()?;
+        reader.skip(4)?;
+    }
+
+    let mut records = Vec::new();
+    for _ in 0..answers {
+        reader.name()?;
+        let rtype = reader.u16()?;
+        let class = reader.u16()?;
+        reader.skip(4)?; // TTL
+        let len = usize::from(reader.u16()?);
+        let end = reader.pos + len;
+        if end > msg.len() {
+            return Err(DnsError::Malformed("record data out of bounds"));
+        }
+
+        if rtype == kind.code() && class == CLASS_IN {
+            let data = &msg[reader.pos..end];
+            records.push(match kind {
+                RecordType::A => RecordData::Address(decode_a(data)?),
+                RecordType::Aaaa => RecordData::Address(decode_aaaa(data)?),
+                RecordType::Txt => RecordData::Txt(decode_txt(data)?),
+                RecordType::Srv => RecordData::Srv(reader.srv()?),
+            });
+        }
+        reader.pos = end;
+    }
+    Ok(records)
+}
+
+/// Decodes A record data.
+fn decode_a(data: &[u8]) -> Result<IpAddr, DnsError> {
+    let octets: [u8; 4] = data
+        .try_into()
+        .map_err(|_| DnsError::Malformed("A record is not 4 bytes"))?;
+    Ok(Ipv4Addr::from(octets).into())
+}
+
+/// Decodes AAAA record data.
+fn decode_aaaa(data: &[u8]) -> Result<IpAddr, DnsError> {
+    let octets: [u8; 16] = data
+        .try_into()
+        .map_err(|_| DnsError::Malformed("AAAA record is not 16 bytes"))?;
+    Ok(Ipv6Addr::from(octets).into())
+}
+
+/// Concatenates the character-strings of TXT record data.
+fn decode_txt(data: &[u8]) -> Result<String, DnsError> {
+    let mut text = Vec::with_capacity(data.len());
+    let mut rest = data;
+    while let Some((&len, tail)) = rest.split_first() {
+        let len = usize::from(len);
+        if len > tail.len() {
+            return Err(DnsError::Malformed("TXT string out of bounds"));
+        }
+        text.extend_from_slice(&tail[..len]);
+        rest = &tail[len..];
+    }
+    Ok(String::from_utf8_lossy(&text).into_owned())
+}
+
+/// Cursor over a DNS message.
+struct Reader<'a> {
+    msg: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, n: usize) -> Result<&[u8], DnsError> {
+        let bytes = self
+            .msg
+            .get(self.pos..self.pos + n)
+            .ok_or(DnsError::Malformed("unexpected end of message"))?;
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, n: usize) -> Result<(), DnsError> {
+        self.take(n).map(|_| ())
+    }
+
+    fn u16(&mut self) -> Result<u16, DnsError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn srv(&mut self) -> Result<SrvRecord, DnsError> {
+        Ok(SrvRecord {
+            priority: self.u16()?,
+            weight: self.u16()?,
+            port: self.u16()?,
+            target: self.name()?,
+        })
+    }
+
+    /// Reads a possibly compressed name, leaving the cursor after it.
+    fn name(&mut self) -> Result<String, DnsError> {
+        let mut labels: Vec<String> = Vec::new();
+        let mut pos = self.pos;
+        let mut resume = None;
+        let mut jumps = 0;
+
+        loop {
+            let len = *self
+                .msg
+                .get(pos)
+                .ok_or(DnsError::Malformed("name out of bounds"))?;
+            match len {
+                0 => {
+                    pos += 1;
+                    break;
+                }
+                len if len & 0xc0 == 0xc0 => {
+                    let low = *self
+                        .msg
+                        .get(pos + 1)
+                        .ok_or(DnsError::Malformed("name out of bounds"))?;
+                    jumps += 1;
+                    if jumps > MAX_POINTER_JUMPS {
+                        return Err(DnsError::Malformed("compression loop"));
+                    }
+                    resume.get_or_insert(pos + 2);
+                    pos = usize::from(u16::from_be_bytes([len & 0x3f, low]));
+                }
+                len if len & 0xc0 == 0 => {
+                    let start = pos + 1;
+                    let end = start + usize::from(len);
+                    let label = self
+                        .msg
+                        .get(start..end)
+                        .ok_or(DnsError::Malformed("label out of bounds"))?;
+                    labels.push(String::from_utf8_lossy(label).into_owned());
+                    pos = end;
+                }
+                _ => return Err(DnsError::Malformed("unsupported label type")),
+            }
+        }
+
+        self.pos = resume.unwrap_or(pos);
+        if labels.is_empty() {
+            Ok(".".to_string())
+        } else {
+            Ok(labels.join("."))
+        }
+    }
+}
@@ -0,0 +1,275 @@
+//! Tests for DNS message encoding and decoding.
+
+use super::message::{RecordData, RecordType, decode_response, encode_query};
+use super::{DnsError, SrvRecord};
+
+const ID: u16 = 0xbeef;
+
+/// Builds a response to `query` with the given flags and raw answer records.
+fn response(query: &[u8], flags: u16, answers: &[Vec<u8>]) -> Vec<u8> {
+    let mut msg = query.to_vec();
+    msg[2..4].copy_from_slice(&flags.to_be_bytes());
+    msg[6..8].copy_from_slice(&u16::try_from(answers.len()).unwrap().to_be_bytes());
+    for answer in answers {
+        msg.extend_from_slice(answer);
+    }
+    msg
+}
+
+/// Builds an answer whose name points back at the question (offset 12).
+fn answer(rtype: u16, rdata: &[u8]) -> Vec<u8> {
+    let mut rr = vec![0xc0, 12];
+    rr.extend_from_slice(&rtype.to_be_bytes());
+    rr.extend_from_slice(&1u16.to_be_bytes());
+    rr.extend_from_slice(&300u32.to_be_bytes());
+    rr.extend_from_slice(&u16::try_from(rdata.len()).unwrap().to_be_bytes());
+    rr.extend_from_slice(rdata);
+    rr
+}
+
+fn txt_rdata(parts: &[&str]) -> Vec<u8> {
+    parts
+        .iter()
+        .flat_map(|p| std::iter::once(u8::try_from(p.len()).unwrap()).chain(p.bytes()))
+        .collect()
+}
+
+fn encoded_name(name: &str) -> Vec<u8> {
+    let mut out = Vec::new();
+    for label in name.split('.') {
+        out.push(u8::try_from(label.len()).unwrap());
+        out.extend_from_slice(label.as_bytes());
+    }
+    out.push(0);
+    out
+}
+
+mod encode {
+    use super::*;
+
+    #[test]
+    fn encodes_header_and_question() {
+        let query = encode_query(ID, "_ddns.example.com", RecordType::Txt).unwrap();
+
+        assert_eq!(&query[..12], &[0xbe, 0xef, 1, 0, 0, 1, 0, 0, 0, 0, 0, 0]);
+        let mut question = encoded_name("_ddns.example.com");
+        question.extend_from_slice(&[0, 16, 0, 1]);
+        assert_eq!(&query[12..], question.as_slice());
+    }
+
+    #[test]
+    fn trailing_dot_is_ignored() {
+        assert_eq!(
+            encode_query(ID, "example.com.", RecordType::Srv).unwrap(),
+            encode_query(ID, "example.com", RecordType::Srv).unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_empty_label() {
+        assert!(matches!(
+            encode_query(ID, "example..com", RecordType::Txt),
+            Err(DnsError::InvalidName { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_overlong_label() {
+        let name = format!("{}.com", "a".repeat(64));
+        assert!(matches!(
+            encode_query(ID, &name, RecordType::Txt),
+            Err(DnsError::InvalidName { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_empty_name() {
+        assert!(encode_query(ID, "", RecordType::Txt).is_err());
+    }
+}
+
+mod decode {
+    use super::*;
+
+    #[test]
+    fn txt_strings_are_concatenated() {
+        let query = encode_query(ID, "example.com", RecordType::Txt).unwrap();
+        let msg = response(
+            &query,
+            0x8180,
+            &[answer(
+                16,
+                &txt_rdata(&["https://hooks.", "example.com/ddns"]),
+            )],
+        );
+
+        let records = decode_response(ID, &msg, RecordType::Txt).unwrap();
+
+        assert_eq!(
+            records,
+            [RecordData::Txt(
+                "https://hooks.example.com/ddns".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn srv_target_is_decoded() {
+        let query = encode_query(ID, "_ddns._tcp.example.com", RecordType::Srv).unwrap();
+        let mut rdata = vec![0, 10, 0, 5, 0x01, 0xbb];
+        rdata.extend(encoded_name("hooks.example.com"));
+        let msg = response(&query, 0x8180, &[answer(33, &rdata)]);
+
+        let records = decode_response(ID, &msg, RecordType::Srv).unwrap();
+
+        assert_eq!(
+            records,
+            [RecordData::Srv(SrvRecord {
+                priority: 10,
+                weight: 5,
+                port: 443,
+                target: "hooks.example.com".to_string(),
+            })]
+        );
+    }
+
+    #[test]
+    fn compressed_srv_target_is_followed() {
+        let query = encode_query(ID, "example.com", RecordType::Srv).unwrap();
+        // Target "hooks" + pointer to "example.com" in the question
+        let mut rdata = vec![0, 1, 0, 1, 0, 80, 5];
+        rdata.extend_from_slice(b"hooks");
+        rdata.extend_from_slice(&[0xc0, 12]);
+        let msg = response(&query, 0x8180, &[answer(33, &rdata)]);
+
+        let records = decode_response(ID, &msg, RecordType::Srv).unwrap();
+
+        let RecordData::Srv(srv) = &records[0] else {
+            panic!("expected SRV record");
+        };
+        assert_eq!(srv.target, "hooks.example.com");
+    }
+
+    #[test]
+    fn addresses_are_decoded() {
+        let query = encode_query(ID, "example.com", RecordType::A).unwrap();
+        let msg = response(&query, 0x8180, &[answer(1, &[192, 0, 2, 1])]);
+        assert_eq!(
+            decode_response(ID, &msg, RecordType::A).unwrap(),
+            [RecordData::Address("192.0.2.1".parse().unwrap())]
+        );
+
+        let query = encode_query(ID, "example.com", RecordType::Aaaa).unwrap();
+        let v6: std::net::Ipv6Addr = "2001:db8::1".parse().unwrap();
+        let msg = response(&query, 0x8180, &[answer(28, &v6.octets())]);
+        assert_eq!(
+            decode_response(ID, &msg, RecordType::Aaaa).unwrap(),
+            [RecordData::Address(v6.into())]
+        );
+    }
+
+    #[test]
+    fn address_of_wrong_length_is_error() {
+        let query = encode_query(ID, "example.com", RecordType::A).unwrap();
+        let msg = response(&query, 0x8180, &[answer(1, &[192, 0, 2])]);
+
+        assert!(matches!(
+            decode_response(ID, &msg, RecordType::A),
+            Err(DnsError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn other_record_types_are_skipped() {
+        let query = encode_query(ID, "example.com", RecordType::Txt).unwrap();
+        let cname = answer(5, &encoded_name("alias.example.com"));
+        let txt = answer(16, &txt_rdata(&["value"]));
+        let msg = response(&query, 0x8180, &[cname, txt]);
+
+        let records = decode_response(ID, &msg, RecordType::Txt).unwrap();
+
+        assert_eq!(records, [RecordData::Txt("value".to_string())]);
+    }
+
+    #[test]
+    fn nxdomain_is_empty() {
+        let query = encode_query(ID, "missing.example.com", RecordType::Txt).unwrap();
+        let msg = response(&query, 0x8183, &[]);
+
+        assert!(
+            decode_response(ID, &msg, RecordType::Txt)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn server_failure_is_error() {
+        let query = encode_query(ID, "example.com", RecordType::Txt).unwrap();
+        let msg = response(&query, 0x8182, &[]);
+
+        assert!(matches!(
+            decode_response(ID, &msg, RecordType::Txt),
+            Err(DnsError::ServerFailure { rcode: 2 })
+        ));
+    }
+
+    #[test]
+    fn truncated_is_error() {
+        let query = encode_query(ID, "example.com", RecordType::Txt).unwrap();
+        let msg = response(&query, 0x8380, &[]);
+
+        assert!(matches!(
+            decode_response(ID, &msg, RecordType::Txt),
+            Err(DnsError::Truncated)
+        ));
+    }
+
+    #[test]
+    fn id_mismatch_is_error() {
+        let query = encode_query(ID, "example.com", RecordType::Txt).unwrap();
+        let msg = response(&query, 0x8180, &[]);
+
+        assert!(matches!(
+            decode_response(ID + 1, &msg, RecordType::Txt),
+            Err(DnsError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn query_is_not_a_response() {
+        let query = encode_query(ID, "example.com", RecordType::Txt).unwrap();
+
+        assert!(matches!(
+            decode_response(ID, &query, RecordType::Txt),
+            Err(DnsError::Malformed("not a response"))
+        ));
+    }
+
+    #[test]
+    fn truncated_record_data_is_error() {
+        let query = encode_query(ID, "example.com", RecordType::Txt).unwrap();
+        let mut msg = response(&query, 0x8180, &[answer(16, &txt_rdata(&["value"]))]);
+        msg.truncate(msg.len() - 2);
+
+        assert!(matches!(
+            decode_response(ID, &msg, RecordType::Txt),
+            Err(DnsError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn compression_loop_is_error() {
+        let query = encode_query(ID, "example.com", RecordType::Txt).unwrap();
+        let mut msg = response(&query, 0x8180, &[answer(16, &[])]);
+        // Point the answer name at itself
+        let offset = u8::try_from(query.len()).unwrap();
+        let pos = query.len();
+        msg[pos..pos + 2].copy_from_slice(&[0xc0, offset]);
+
+        assert!(matches!(
+            decode_response(ID, &msg, RecordType::Txt),
+            Err(DnsError::Malformed("compression loop"))
+        ));
+    }
+}
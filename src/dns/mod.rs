@@ -0,0 +1,106 @@
+//! Minimal DNS client for TXT, SRV and address lookups.
+//!
+//! Used to discover the webhook endpoint from DNS, so fleets can re-point
+//! notifications by changing a record instead of every device's config,
+//! and to resolve outbound hosts through the `[http] dns_servers` instead of
+//! the system resolver. Only what that needs is implemented: single-question
+//! queries over UDP to recursive nameservers, with no caching and no TCP
+//! fallback.
+//!
+//! - [`DnsResolver`]: trait abstracting record lookups (mockable in tests)
+//! - [`UdpResolver`]: production implementation speaking RFC 1035 over UDP
+//! - [`NameserverList`]: asks several [`UdpResolver`]s in turn
+//! - [`SrvRecord`]: decoded SRV record data
+
+mod message;
+mod servers;
+mod udp;
+
+#[cfg(test)]
+mod message_tests;
+#[cfg(test)]
+mod servers_tests;
+#[cfg(test)]
+mod udp_tests;
+
+use std::future::Future;
+use std::io;
+use std::net::IpAddr;
+
+use thiserror::Error;
+
+pub use servers::NameserverList;
+pub use udp::{DEFAULT_TIMEOUT, UdpResolver};
+
+/// Default DNS server port.
+pub const DNS_PORT: u16 = 53;
+
+/// Decoded SRV record (RFC 2782).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SrvRecord {
+    /// Lower values are preferred.
+    pub priority: u16,
+    /// Relative weight among records with the same priority.
+    pub weight: u16,
+    /// Port the service listens on.
+    pub port: u16,
+    /// Target host name, without the trailing dot (`.` means "no service").
+    pub target: String,
+}
+
+/// Error type for DNS lookups.
+#[derive(Debug, Error)]
+pub enum DnsError {
+    /// The queried name is not a valid DNS name.
+    #[error("Invalid DNS name '{name}'")]
+    InvalidName {
+        /// The invalid name
+        name: String,
+    },
+
+    /// Sending the query or receiving the response failed.
+    #[error("DNS request failed: {0}")]
+    Io(#[from] io::Error),
+
+    /// No response arrived within the timeout.
+    #[error("DNS request timed out")]
+    Timeout,
+
+    /// The response could not be decoded.
+    #[error("Malformed DNS response: {0}")]
+    Malformed(&'static str),
+
+    /// The response did not fit in a UDP datagram.
+    #[error("DNS response truncated (TCP fallback is not supported)")]
+    Truncated,
+
+    /// The server answered with an error code other than NXDOMAIN.
+    #[error("DNS server returned error code {rcode}")]
+    ServerFailure {
+        /// Response code from the header
+        rcode: u8,
+    },
+
+    /// No nameserver was configured and none could be found on the system.
+    #[error("No DNS nameserver configured and none found in /etc/resolv.conf")]
+    NoNameserver,
+}
+
+/// Looks up DNS records.
+///
+/// A name that does not exist (NXDOMAIN) or has no records of the requested
+/// type yields an empty list rather than an error.
+pub trait DnsResolver: Send + Sync {
+    /// Returns the text of each TXT record for `name`, with its
+    /// character-strings concatenated.
+    fn lookup_txt(&self, name: &str) -> impl Future<Output = Result<Vec<String>, DnsError>> + Send;
+
+    /// Returns the SRV records for `name`.
+    fn lookup_srv(
+        &self,
+        name: &str,
+    ) -> impl Future<Output = Result<Vec<SrvRecord>, DnsError>> + Send;
+
+    /// Returns the addresses of the A and AAAA records for `name`.
+    fn lookup_ip(&self, name: &str) -> impl Future<Output = Result<Vec<IpAddr>, DnsError>> + Send;
+}
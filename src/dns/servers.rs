@@ -0,0 +1,63 @@
+//! [`DnsResolver`] asking several nameservers in turn.
+
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+
+use super::{DnsError, DnsResolver, SrvRecord, UdpResolver};
+
+/// Resolver trying each of its nameservers in order until one answers.
+///
+/// An answer without records (including NXDOMAIN) is final; only failures
+/// such as timeouts move on to the next server.
+#[derive(Debug, Clone)]
+pub struct NameserverList {
+    servers: Vec<UdpResolver>,
+}
+
+impl NameserverList {
+    /// Creates a resolver asking `servers` in order.
+    #[must_use]
+    pub fn new(servers: impl IntoIterator<Item = SocketAddr>) -> Self {
+        Self {
+            servers: servers.into_iter().map(UdpResolver::new).collect(),
+        }
+    }
+
+    /// Returns the nameserver addresses, in the order they are asked.
+    pub fn servers(&self) -> impl Iterator<Item = SocketAddr> + '_ {
+        self.servers.iter().map(UdpResolver::server)
+    }
+
+    /// Returns the first successful answer of `lookup`, or the last error.
+    async fn first_answer<'a, T, F, Fut>(&'a self, lookup: F) -> Result<T, DnsError>
+    where
+        F: Fn(&'a UdpResolver) -> Fut,
+        Fut: Future<Output = Result<T, DnsError>>,
+    {
+        let mut last = DnsError::NoNameserver;
+        for server in &self.servers {
+            match lookup(server).await {
+                Ok(answer) => return Ok(answer),
+                Err(e) => {
+                    tracing::debug!("DNS server {} failed: {e}", server.server());
+                    last = e;
+                }
+            }
+        }
+        Err(last)
+    }
+}
+
+impl DnsResolver for NameserverList {
+    async fn lookup_txt(&self, name: &str) -> Result<Vec<String>, DnsError> {
+        self.first_answer(|server| server.lookup_txt(name)).await
+    }
+
+    async fn lookup_srv(&self, name: &str) -> Result<Vec<SrvRecord>, DnsError> {
+        self.first_answer(|server| server.lookup_srv(name)).await
+    }
+
+    async fn lookup_ip(&self, name: &str) -> Result<Vec<IpAddr>, DnsError> {
+        self.first_answer(|server| server.lookup_ip(name)).await
+    }
+}
@@ -0,0 +1,101 @@
+//! Tests for the nameserver list against local fake nameservers.
+
+use std::net::{IpAddr, SocketAddr};
+
+use tokio::net::UdpSocket;
+
+use super::{DnsError, DnsResolver, NameserverList};
+
+/// Spawns a server answering A queries with `192.0.2.1` and AAAA queries
+/// with `2001:db8::1`.
+async fn address_server() -> SocketAddr {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = socket.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let mut buf = [0u8; 512];
+        loop {
+            let (len, peer) = socket.recv_from(&mut buf).await.unwrap();
+            let qtype = u16::from_be_bytes([buf[len - 4], buf[len - 3]]);
+            let rdata: Vec<u8> = match qtype {
+                1 => vec![192, 0, 2, 1],
+                _ => "2001:db8::1"
+                    .parse::<std::net::Ipv6Addr>()
+                    .unwrap()
+                    .octets()
+                    .to_vec(),
+            };
+
+            let mut reply = buf[..len].to_vec();
+            reply[2..4].copy_from_slice(&0x8180u16.to_be_bytes());
+            reply[6..8].copy_from_slice(&1u16.to_be_bytes());
+            reply.extend_from_slice(&[0xc0, 12]);
+            reply.extend_from_slice(&qtype.to_be_bytes());
+            reply.extend_from_slice(&[0, 1, 0, 0, 0, 60]);
+            reply.extend_from_slice(&u16::try_from(rdata.len()).unwrap().to_be_bytes());
+            reply.extend_from_slice(&rdata);
+            socket.send_to(&reply, peer).await.unwrap();
+        }
+    });
+
+    addr
+}
+
+/// Spawns a server answering every query with SERVFAIL.
+async fn failing_server() -> SocketAddr {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = socket.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let mut buf = [0u8; 512];
+        loop {
+            let (len, peer) = socket.recv_from(&mut buf).await.unwrap();
+            let mut reply = buf[..len].to_vec();
+            reply[2..4].copy_from_slice(&0x8182u16.to_be_bytes());
+            socket.send_to(&reply, peer).await.unwrap();
+        }
+    });
+
+    addr
+}
+
+fn expected() -> Vec<IpAddr> {
+    vec!["192.0.2.1".parse().unwrap(), "2001:db8::1".parse().unwrap()]
+}
+
+#[tokio::test]
+async fn resolves_a_and_aaaa() {
+    let resolver = NameserverList::new([address_server().await]);
+
+    let addresses = resolver.lookup_ip("hooks.example.com").await.unwrap();
+
+    assert_eq!(addresses, expected());
+}
+
+#[tokio::test]
+async fn failing_server_falls_back_to_next() {
+    let resolver = NameserverList::new([failing_server().await, address_server().await]);
+
+    let addresses = resolver.lookup_ip("hooks.example.com").await.unwrap();
+
+    assert_eq!(addresses, expected());
+}
+
+#[tokio::test]
+async fn reports_last_error_when_all_fail() {
+    let resolver = NameserverList::new([failing_server().await]);
+
+    let result = resolver.lookup_ip("hooks.example.com").await;
+
+    assert!(matches!(result, Err(DnsError::ServerFailure { rcode: 2 })));
+}
+
+#[tokio::test]
+async fn empty_list_has_no_nameserver() {
+    let resolver = NameserverList::new([]);
+
+    assert!(matches!(
+        resolver.lookup_txt("example.com").await,
+        Err(DnsError::NoNameserver)
+    ));
+}
@@ -0,0 +1,164 @@
+//! [`DnsResolver`] over UDP to a single recursive nameserver.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+use tokio::net::UdpSocket;
+
+use super::message::{RecordData, RecordType, decode_response, encode_query};
+use super::{DNS_PORT, DnsError, DnsResolver, SrvRecord};
+
+/// Default time to wait for a response.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Largest response accepted (EDNS is not used, so servers stay within 512 bytes).
+const MAX_RESPONSE_LEN: usize = 512;
+
+/// Resolver sending each query as one UDP datagram.
+///
+/// Every lookup uses a fresh socket bound to an OS-chosen port and a random
+/// transaction id, and only accepts a datagram from the configured server.
+#[derive(Debug, Clone)]
+pub struct UdpResolver {
+    server: SocketAddr,
+    timeout: Duration,
+}
+
+impl UdpResolver {
+    /// Creates a resolver querying `server`.
+    #[must_use]
+    pub const fn new(server: SocketAddr) -> Self {
+        Self {
+            server,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Creates a resolver for the first nameserver in `/etc/resolv.conf`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DnsError::NoNameserver`] if the file is missing or lists no
+    /// usable nameserver (always the case on non-Unix platforms).
+    pub fn from_system() -> Result<Self, DnsError> {
+        let content = if cfg!(unix) {
+            std::fs::read_to_string("/etc/resolv.conf").unwrap_or_default()
+        } else {
+            String::new()
+        };
+        parse_resolv_conf(&content)
+            .map(Self::new)
+            .ok_or(DnsError::NoNameserver)
+    }
+
+    /// Sets the response timeout.
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the nameserver address.
+    #[must_use]
+    pub const fn server(&self) -> SocketAddr {
+        self.server
+    }
+
+    async fn query(&self, name: &str, kind: RecordType) -> Result<Vec<RecordData>, DnsError> {
+        let id = random_id();
+        let query = encode_query(id, name, kind)?;
+
+        let exchange = async {
+            let bind: SocketAddr = if self.server.is_ipv4() {
+                (Ipv4Addr::UNSPECIFIED, 0).into()
+            } else {
+                (Ipv6Addr::UNSPECIFIED, 0).into()
+            };
+            let socket = UdpSocket::bind(bind).await?;
+            socket.connect(self.server).await?;
+            socket.send(&query).await?;
+
+            let mut buf = [0u8; MAX_RESPONSE_LEN];
+            loop {
+                let len = socket.recv(&mut buf).await?;
+                // A late answer to an earlier query: keep waiting for ours
+                if len >= 2 && buf[..2] != id.to_be_bytes() {
+                    continue;
+                }
+                return decode_response(id, &buf[..len], kind);
+            }
+        };
+
+        tokio::time::timeout(self.timeout, exchange)
+            .await
+            .map_err(|_| DnsError::Timeout)?
+    }
+}
+
+impl DnsResolver for UdpResolver {
+    async fn lookup_txt(&self, name: &str) -> Result<Vec<String>, DnsError> {
+        let records = self.query(name, RecordType::Txt).await?;
+        Ok(records
+            .into_iter()
+            .filter_map(|record| match record {
+                RecordData::Txt(text) => Some(text),
+                RecordData::Address(_) | RecordData::Srv(_) => None,
+            })
+            .collect())
+    }
+
+    async fn lookup_srv(&self, name: &str) -> Result<Vec<SrvRecord>, DnsError> {
+        let records = self.query(name, RecordType::Srv).await?;
+        Ok(records
+            .into_iter()
+            .filter_map(|record| match record {
+                RecordData::Srv(srv) => Some(srv),
+                RecordData::Address(_) | RecordData::Txt(_) => None,
+            })
+            .collect())
+    }
+
+    async fn lookup_ip(&self, name: &str) -> Result<Vec<IpAddr>, DnsError> {
+        let mut records = self.query(name, RecordType::A).await?;
+        records.extend(self.query(name, RecordType::Aaaa).await?);
+        Ok(records
+            .into_iter()
+            .filter_map(|record| match record {
+                RecordData::Address(ip) => Some(ip),
+                RecordData::Txt(_) | RecordData::Srv(_) => None,
+            })
+            .collect())
+    }
+}
+
+/// Returns the first `nameserver` entry of a `resolv.conf` file.
+///
+/// Scoped IPv6 addresses (`fe80::1%eth0`) are skipped since the scope
+/// cannot be expressed in a [`SocketAddr`] parsed from text.
+pub(super) fn parse_resolv_conf(content: &str) -> Option<SocketAddr> {
+    content.lines().find_map(|line| {
+        let mut words = line.split_whitespace();
+        if words.next() != Some("nameserver") {
+            return None;
+        }
+        let ip: IpAddr = words.next()?.parse().ok()?;
+        Some(SocketAddr::new(ip, DNS_PORT))
+    })
+}
+
+/// Returns an unpredictable transaction id.
+fn random_id() -> u16 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    // Truncation intended: any 16 bits of the hash are equally random
+    #[allow(clippy::cast_possible_truncation)]
+    let id = hasher.finish() as u16;
+    id
+}
@@ -0,0 +1,128 @@
+//! Tests for the UDP resolver against a local fake nameserver.
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use tokio::net::UdpSocket;
+
+use super::udp::parse_resolv_conf;
+use super::{DnsError, DnsResolver, UdpResolver};
+
+/// Spawns a server answering one query with a single TXT record `text`,
+/// after first sending `stray` datagrams with a different id.
+async fn fake_server(text: &'static str, stray: usize) -> SocketAddr {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = socket.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let mut buf = [0u8; 512];
+        let (len, peer) = socket.recv_from(&mut buf).await.unwrap();
+
+        let mut reply = buf[..len].to_vec();
+        reply[2..4].copy_from_slice(&0x8180u16.to_be_bytes());
+        reply[6..8].copy_from_slice(&1u16.to_be_bytes());
+        reply.extend_from_slice(&[0xc0, 12, 0, 16, 0, 1, 0, 0, 0, 60]);
+        reply.extend_from_slice(&u16::try_from(text.len() + 1).unwrap().to_be_bytes());
+        reply.push(u8::try_from(text.len()).unwrap());
+        reply.extend_from_slice(text.as_bytes());
+
+        for _ in 0..stray {
+            let mut other = reply.clone();
+            other[0] ^= 0xff;
+            socket.send_to(&other, peer).await.unwrap();
+        }
+        socket.send_to(&reply, peer).await.unwrap();
+    });
+
+    addr
+}
+
+#[tokio::test]
+async fn resolves_txt_from_server() {
+    let resolver = UdpResolver::new(fake_server("https://example.com/hook", 0).await);
+
+    let records = resolver.lookup_txt("_ddns.example.com").await.unwrap();
+
+    assert_eq!(records, ["https://example.com/hook"]);
+}
+
+#[tokio::test]
+async fn ignores_datagrams_for_other_queries() {
+    let resolver = UdpResolver::new(fake_server("ours", 2).await);
+
+    let records = resolver.lookup_txt("example.com").await.unwrap();
+
+    assert_eq!(records, ["ours"]);
+}
+
+#[tokio::test]
+async fn srv_lookup_skips_txt_answers() {
+    let resolver = UdpResolver::new(fake_server("not srv", 0).await);
+
+    // The fake server always answers with TXT data
+    let records = resolver.lookup_srv("example.com").await.unwrap();
+
+    assert!(records.is_empty());
+}
+
+#[tokio::test]
+async fn silent_server_times_out() {
+    let silent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let resolver =
+        UdpResolver::new(silent.local_addr().unwrap()).with_timeout(Duration::from_millis(50));
+
+    assert!(matches!(
+        resolver.lookup_txt("example.com").await,
+        Err(DnsError::Timeout)
+    ));
+}
+
+#[tokio::test]
+async fn invalid_name_fails_before_sending() {
+    let resolver = UdpResolver::new("127.0.0.1:9".parse().unwrap());
+
+    assert!(matches!(
+        resolver.lookup_txt("bad..name").await,
+        Err(DnsError::InvalidName { .. })
+    ));
+}
+
+mod resolv_conf {
+    use super::*;
+
+    #[test]
+    fn first_nameserver_wins() {
+        let content = "# generated\nsearch lan\nnameserver 192.0.2.53\nnameserver 192.0.2.54\n";
+
+        assert_eq!(
+            parse_resolv_conf(content),
+            Some("192.0.2.53:53".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn ipv6_nameserver() {
+        assert_eq!(
+            parse_resolv_conf("nameserver 2001:db8::53"),
+            Some("[2001:db8::53]:53".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn scoped_address_is_skipped() {
+        let content = "nameserver fe80::1%eth0\nnameserver 192.0.2.1";
+
+        assert_eq!(
+            parse_resolv_conf(content),
+            Some("192.0.2.1:53".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn no_nameserver() {
+        assert_eq!(
+            parse_resolv_conf("search lan\n# nameserver 192.0.2.1"),
+            None
+        );
+    }
+}
@@ -0,0 +1,216 @@
+//! Built-in health-check endpoint (`[health] bind`).
+//!
+//! [`serve`] answers `GET /healthz` with a [`HealthReport`] as JSON: whether
+//! the latest adapter fetch succeeded, how long ago a webhook delivery last
+//! succeeded, and the known addresses. The status is 200 while the latest
+//! fetch succeeded and 503 otherwise, so container orchestrators can use it
+//! as a liveness probe. `GET /status` answers with the more detailed
+//! [`StatusReport`] read by `ddns-a status`. It is a minimal HTTP/1.1
+//! responder that closes the connection after every response.
+
+mod status;
+
+#[cfg(test)]
+#[path = "mod_tests.rs"]
+mod tests;
+
+pub use status::{StatusReport, fetch_status, format_status};
+
+use std::collections::BTreeMap;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::network::priority::EffectiveAddresses;
+use crate::report::RunStats;
+
+/// Path of the health report.
+pub const HEALTH_PATH: &str = "/healthz";
+
+/// Path of the status report.
+pub const STATUS_PATH: &str = "/status";
+
+/// Longest request head read before answering 400.
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+
+/// Time a client has to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Errors of the health endpoint.
+#[derive(Debug, Error)]
+pub enum HealthError {
+    /// Failed to listen on the configured address.
+    #[error("Failed to listen for health checks on {address}: {source}")]
+    Bind {
+        /// Configured bind address
+        address: SocketAddr,
+        /// Underlying I/O error
+        #[source]
+        source: io::Error,
+    },
+
+    /// Failed to reach the running instance.
+    #[error("No running instance answered on {address}: {source}")]
+    Connect {
+        /// Address of the health endpoint
+        address: SocketAddr,
+        /// Underlying I/O error
+        #[source]
+        source: io::Error,
+    },
+
+    /// The running instance sent an unexpected answer.
+    #[error("Unexpected answer from {address}: {reason}")]
+    Response {
+        /// Address of the health endpoint
+        address: SocketAddr,
+        /// What was wrong with the answer
+        reason: String,
+    },
+}
+
+/// State reported by the health endpoint, serialized as JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthReport {
+    /// Whether the latest adapter fetch succeeded.
+    pub healthy: bool,
+    /// Seconds since the latest adapter fetch; `None` before the first.
+    pub last_poll_age_secs: Option<u64>,
+    /// Seconds since the latest successful webhook delivery; `None` before the first.
+    pub last_delivery_age_secs: Option<u64>,
+    /// Last known monitored addresses per adapter.
+    pub addresses: BTreeMap<String, Vec<IpAddr>>,
+    /// Effective address per monitored family, by adapter priority.
+    pub effective_addresses: EffectiveAddresses,
+}
+
+impl HealthReport {
+    /// Builds the report from `stats` as of `now`.
+    #[must_use]
+    pub fn new(stats: &RunStats, now: SystemTime) -> Self {
+        let age = |at: SystemTime| now.duration_since(at).unwrap_or_default().as_secs();
+        let last_poll = stats.last_poll();
+        let summary = stats.summary(now);
+        Self {
+            healthy: last_poll.as_ref().is_some_and(|poll| poll.succeeded),
+            last_poll_age_secs: last_poll.map(|poll| age(poll.finished_at)),
+            last_delivery_age_secs: stats.last_sent().map(age),
+            addresses: summary.last_addresses,
+            effective_addresses: summary.effective_addresses,
+        }
+    }
+
+    /// Returns the HTTP status reporting this state.
+    #[must_use]
+    pub const fn status(&self) -> StatusCode {
+        if self.healthy {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+    }
+}
+
+/// Listens on `address` for health checks.
+///
+/// # Errors
+///
+/// Returns [`HealthError::Bind`] if the address cannot be bound.
+pub async fn bind(address: SocketAddr) -> Result<TcpListener, HealthError> {
+    TcpListener::bind(address)
+        .await
+        .map_err(|source| HealthError::Bind { address, source })
+}
+
+/// Answers health and status checks on `listener` with the state of
+/// `stats`, until the returned future is dropped.
+///
+/// Each connection is handled on its own task; failures only end that
+/// connection.
+pub async fn serve(listener: TcpListener, stats: Arc<RunStats>) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                tracing::debug!("Failed to accept health check connection: {e}");
+                continue;
+            }
+        };
+        let stats = Arc::clone(&stats);
+        tokio::spawn(async move {
+            if let Err(e) = answer(stream, &stats).await {
+                tracing::debug!("Health check connection failed: {e}");
+            }
+        });
+    }
+}
+
+async fn answer(mut stream: TcpStream, stats: &RunStats) -> io::Result<()> {
+    let head = tokio::time::timeout(REQUEST_TIMEOUT, read_head(&mut stream))
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "request timed out"))??;
+    let response = respond(head.as_deref(), stats, SystemTime::now());
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Reads the request line and headers; `None` if they are too long or the
+/// connection closed first.
+async fn read_head(stream: &mut TcpStream) -> io::Result<Option<String>> {
+    let mut head = Vec::new();
+    let mut buf = [0; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 || head.len() + n > MAX_REQUEST_HEAD {
+            return Ok(None);
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    Ok(String::from_utf8(head).ok())
+}
+
+/// Builds the full response to a request `head` from `stats` as of `now`.
+pub(crate) fn respond(head: Option<&str>, stats: &RunStats, now: SystemTime) -> String {
+    let request_line = head
+        .and_then(|head| head.lines().next())
+        .unwrap_or_default();
+    let mut parts = request_line.split(' ');
+    let (Some(method), Some(target), Some(_version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return response(StatusCode::BAD_REQUEST, "", None);
+    };
+    let path = target.split('?').next().unwrap_or_default();
+
+    if path != HEALTH_PATH && path != STATUS_PATH {
+        return response(StatusCode::NOT_FOUND, "", None);
+    }
+    if method != "GET" {
+        return response(StatusCode::METHOD_NOT_ALLOWED, "", Some("Allow: GET"));
+    }
+    let json = Some("Content-Type: application/json");
+    let health = HealthReport::new(stats, now);
+    if path == HEALTH_PATH {
+        let body = serde_json::to_string(&health).expect("health report is always serializable");
+        return response(health.status(), &body, json);
+    }
+    let report = StatusReport::new(health, stats, now);
+    let body = serde_json::to_string(&report).expect("status report is always serializable");
+    response(StatusCode::OK, &body, json)
+}
+
+fn response(status: StatusCode, body: &str, header: Option<&str>) -> String {
+    let header = header.map(|h| format!("{h}\r\n")).unwrap_or_default();
+    format!(
+        "HTTP/1.1 {} {}\r\n{header}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        status.as_u16(),
+        status.canonical_reason().unwrap_or_default(),
+        body.len()
+    )
+}
@@ -0,0 +1,218 @@
+//! Tests for the health endpoint.
+
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use http::StatusCode;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use super::{HealthError, HealthReport, bind, respond, serve};
+use crate::monitor::{PollMetrics, PollSample};
+use crate::network::{AdapterKind, AdapterSnapshot, IpVersion};
+use crate::report::RunStats;
+
+fn at(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+fn poll(finished: u64, succeeded: bool) -> PollSample {
+    PollSample {
+        finished_at: at(finished),
+        duration: Duration::from_millis(5),
+        adapters: 1,
+        addresses: 1,
+        succeeded,
+    }
+}
+
+fn stats_after(poll: Option<PollSample>) -> RunStats {
+    let metrics = PollMetrics::default();
+    if let Some(poll) = poll {
+        metrics.record(poll);
+    }
+    let stats = RunStats::new(at(0)).with_poll_metrics(metrics);
+    let eth0 = AdapterSnapshot::new(
+        "eth0",
+        AdapterKind::Ethernet,
+        vec!["192.0.2.5".parse().unwrap()],
+        vec![],
+    );
+    stats.record_snapshot(&[eth0], IpVersion::V4);
+    stats
+}
+
+fn status_line(response: &str) -> &str {
+    response.lines().next().unwrap()
+}
+
+mod report {
+    use super::*;
+
+    #[test]
+    fn healthy_after_successful_poll() {
+        let report = HealthReport::new(&stats_after(Some(poll(100, true))), at(130));
+
+        assert!(report.healthy);
+        assert_eq!(report.status(), StatusCode::OK);
+        assert_eq!(report.last_poll_age_secs, Some(30));
+        assert_eq!(report.last_delivery_age_secs, None);
+        assert_eq!(
+            report.addresses["eth0"],
+            ["192.0.2.5".parse::<IpAddr>().unwrap()]
+        );
+    }
+
+    #[test]
+    fn unhealthy_after_failed_poll() {
+        let report = HealthReport::new(&stats_after(Some(poll(100, false))), at(100));
+
+        assert!(!report.healthy);
+        assert_eq!(report.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn unhealthy_before_first_poll() {
+        let report = HealthReport::new(&stats_after(None), at(100));
+
+        assert!(!report.healthy);
+        assert_eq!(report.last_poll_age_secs, None);
+    }
+
+    #[test]
+    fn delivery_age_after_success() {
+        let stats = stats_after(Some(poll(100, true)));
+        stats.record_delivery(&Ok(()));
+
+        let report = HealthReport::new(&stats, SystemTime::now() + Duration::from_secs(20));
+
+        assert!(report.last_delivery_age_secs.is_some_and(|age| age >= 20));
+    }
+}
+
+mod respond {
+    use super::*;
+
+    fn healthy() -> RunStats {
+        stats_after(Some(poll(100, true)))
+    }
+
+    fn body(response: &str) -> serde_json::Value {
+        serde_json::from_str(response.split("\r\n\r\n").nth(1).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn health_path_returns_json() {
+        let response = respond(
+            Some("GET /healthz HTTP/1.1\r\nHost: x\r\n\r\n"),
+            &healthy(),
+            at(100),
+        );
+
+        assert_eq!(status_line(&response), "HTTP/1.1 200 OK");
+        assert!(response.contains("Content-Type: application/json\r\n"));
+        let json = body(&response);
+        assert_eq!(json["healthy"], true);
+        assert_eq!(json["addresses"]["eth0"], serde_json::json!(["192.0.2.5"]));
+        let length = response.split("\r\n\r\n").nth(1).unwrap().len();
+        assert!(response.contains(&format!("Content-Length: {length}\r\n")));
+    }
+
+    #[test]
+    fn query_is_ignored() {
+        let response = respond(
+            Some("GET /healthz?probe=1 HTTP/1.1\r\n\r\n"),
+            &healthy(),
+            at(100),
+        );
+
+        assert_eq!(status_line(&response), "HTTP/1.1 200 OK");
+    }
+
+    #[test]
+    fn unhealthy_is_503() {
+        let response = respond(
+            Some("GET /healthz HTTP/1.1\r\n\r\n"),
+            &stats_after(None),
+            at(0),
+        );
+
+        assert_eq!(status_line(&response), "HTTP/1.1 503 Service Unavailable");
+    }
+
+    #[test]
+    fn status_path_returns_report_even_if_unhealthy() {
+        let response = respond(
+            Some("GET /status HTTP/1.1\r\n\r\n"),
+            &stats_after(Some(poll(100, false))),
+            at(100),
+        );
+
+        assert_eq!(status_line(&response), "HTTP/1.1 200 OK");
+        let json = body(&response);
+        assert_eq!(json["health"]["healthy"], false);
+        assert_eq!(json["summary"]["started_at"], 0);
+    }
+
+    #[test]
+    fn other_paths_are_404() {
+        let response = respond(Some("GET / HTTP/1.1\r\n\r\n"), &healthy(), at(100));
+
+        assert_eq!(status_line(&response), "HTTP/1.1 404 Not Found");
+    }
+
+    #[test]
+    fn other_methods_are_405() {
+        let response = respond(Some("POST /healthz HTTP/1.1\r\n\r\n"), &healthy(), at(100));
+
+        assert_eq!(status_line(&response), "HTTP/1.1 405 Method Not Allowed");
+        assert!(response.contains("Allow: GET\r\n"));
+    }
+
+    #[test]
+    fn malformed_requests_are_400() {
+        for head in [None, Some("garbage\r\n\r\n")] {
+            let response = respond(head, &healthy(), at(100));
+
+            assert_eq!(status_line(&response), "HTTP/1.1 400 Bad Request");
+        }
+    }
+}
+
+mod server {
+    use super::*;
+
+    #[tokio::test]
+    async fn answers_over_tcp() {
+        let listener = bind("127.0.0.1:0".parse().unwrap()).await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(serve(
+            listener,
+            Arc::new(stats_after(Some(poll(100, true)))),
+        ));
+
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream
+            .write_all(b"GET /healthz HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        server.abort();
+
+        assert_eq!(status_line(&response), "HTTP/1.1 200 OK");
+        assert!(response.contains("\"healthy\":true"), "{response}");
+    }
+
+    #[tokio::test]
+    async fn bind_failure_names_address() {
+        let taken = bind("127.0.0.1:0".parse().unwrap()).await.unwrap();
+        let address = taken.local_addr().unwrap();
+
+        let error = bind(address).await.unwrap_err();
+
+        assert!(matches!(error, HealthError::Bind { address: a, .. } if a == address));
+        assert!(error.to_string().contains(&address.to_string()));
+    }
+}
@@ -0,0 +1,196 @@
+//! The status report behind `ddns-a status`.
+
+#[cfg(test)]
+#[path = "status_tests.rs"]
+mod tests;
+
+use std::fmt::Write;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::network::priority::EffectiveAddress;
+use crate::report::{DeliveryOutcome, RunStats, RunSummary};
+use crate::webhook::CircuitState;
+
+use super::{HealthError, HealthReport, REQUEST_TIMEOUT, STATUS_PATH};
+
+/// Detailed state of a running instance, served on [`STATUS_PATH`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusReport {
+    /// The health report, including the known addresses.
+    pub health: HealthReport,
+    /// Seconds since the latest detected change; `None` before the first.
+    pub last_change_age_secs: Option<u64>,
+    /// Counters of the run so far, as in the run summary.
+    pub summary: RunSummary,
+}
+
+impl StatusReport {
+    /// Builds the report from `health` and `stats` as of `now`.
+    #[must_use]
+    pub fn new(health: HealthReport, stats: &RunStats, now: SystemTime) -> Self {
+        Self {
+            health,
+            last_change_age_secs: stats
+                .last_change()
+                .map(|at| now.duration_since(at).unwrap_or_default().as_secs()),
+            summary: stats.summary(now),
+        }
+    }
+}
+
+/// Fetches the status of the instance whose health endpoint is bound to
+/// `address`; an unspecified address (`0.0.0.0`) is reached over loopback.
+///
+/// # Errors
+///
+/// Returns [`HealthError::Connect`] if nothing answers, or
+/// [`HealthError::Response`] if the answer is not a status report.
+pub async fn fetch_status(address: SocketAddr) -> Result<StatusReport, HealthError> {
+    let address = reachable(address);
+    let connect = |source| HealthError::Connect { address, source };
+    let invalid = |reason: String| HealthError::Response { address, reason };
+
+    let response = tokio::time::timeout(REQUEST_TIMEOUT, request(address))
+        .await
+        .map_err(|_| connect(std::io::ErrorKind::TimedOut.into()))?
+        .map_err(connect)?;
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| invalid("incomplete HTTP response".to_string()))?;
+    let status_line = head.lines().next().unwrap_or_default();
+    if status_line.split(' ').nth(1) != Some("200") {
+        return Err(invalid(status_line.to_string()));
+    }
+    serde_json::from_str(body).map_err(|e| invalid(e.to_string()))
+}
+
+const fn reachable(address: SocketAddr) -> SocketAddr {
+    let ip = match address.ip() {
+        IpAddr::V4(ip) if ip.is_unspecified() => IpAddr::V4(Ipv4Addr::LOCALHOST),
+        IpAddr::V6(ip) if ip.is_unspecified() => IpAddr::V6(Ipv6Addr::LOCALHOST),
+        ip => ip,
+    };
+    SocketAddr::new(ip, address.port())
+}
+
+async fn request(address: SocketAddr) -> std::io::Result<String> {
+    let mut stream = TcpStream::connect(address).await?;
+    let request =
+        format!("GET {STATUS_PATH} HTTP/1.1\r\nHost: {address}\r\nConnection: close\r\n\r\n");
+    stream.write_all(request.as_bytes()).await?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await?;
+    Ok(String::from_utf8_lossy(&response).into_owned())
+}
+
+/// Formats `report` as an aligned, human-readable table.
+#[must_use]
+pub fn format_status(report: &StatusReport) -> String {
+    const ADAPTER_HEADER: &str = "ADAPTER";
+
+    let health = &report.health;
+    let summary = &report.summary;
+    let never = || "never".to_string();
+    let effective = |address: Option<&EffectiveAddress>| {
+        address.map_or_else(
+            || "-".to_string(),
+            |a| format!("{} ({})", a.address, a.adapter),
+        )
+    };
+
+    let rows = [
+        (
+            "Status",
+            if health.healthy {
+                "healthy"
+            } else {
+                "UNHEALTHY"
+            }
+            .to_string(),
+        ),
+        (
+            "Last poll",
+            health.last_poll_age_secs.map_or_else(never, ago),
+        ),
+        (
+            "Last change",
+            report.last_change_age_secs.map_or_else(never, ago),
+        ),
+        (
+            "Last delivery",
+            match (summary.last_delivery, health.last_delivery_age_secs) {
+                (None, _) => never(),
+                (Some(outcome), None) => outcome.as_str().to_string(),
+                (Some(DeliveryOutcome::Sent), Some(age)) => format!("sent {}", ago(age)),
+                (Some(outcome), Some(age)) => {
+                    format!("{} (last sent {})", outcome.as_str(), ago(age))
+                }
+            },
+        ),
+        (
+            "Deliveries",
+            format!(
+                "{} sent, {} failed, {} postponed",
+                summary.notifications_sent,
+                summary.notifications_failed,
+                summary.notifications_postponed
+            ),
+        ),
+        ("Changes", summary.changes_detected.to_string()),
+        ("Uptime", duration(summary.uptime_secs)),
+        ("IPv4", effective(health.effective_addresses.ipv4.as_ref())),
+        ("IPv6", effective(health.effective_addresses.ipv6.as_ref())),
+    ];
+
+    let circuits = (!summary.circuits.is_empty()).then(|| {
+        let states: Vec<_> = summary
+            .circuits
+            .iter()
+            .map(|(name, state)| match state {
+                CircuitState::Open { retry_in_secs } => {
+                    format!("{name} open (retry in {})", duration(*retry_in_secs))
+                }
+                state => format!("{name} {}", state.as_str()),
+            })
+            .collect();
+        ("Circuits", states.join(", "))
+    });
+
+    let mut table = String::new();
+    for (name, value) in rows.into_iter().chain(circuits) {
+        let _ = writeln!(table, "{:<14} {value}", format!("{name}:"));
+    }
+
+    let width = health
+        .addresses
+        .keys()
+        .map(String::len)
+        .max()
+        .unwrap_or(0)
+        .max(ADAPTER_HEADER.len());
+    let _ = write!(table, "\n{ADAPTER_HEADER:<width$}  ADDRESSES\n");
+    for (adapter, addresses) in &health.addresses {
+        let addresses: Vec<_> = addresses.iter().map(ToString::to_string).collect();
+        let _ = writeln!(table, "{adapter:<width$}  {}", addresses.join(", "));
+    }
+    table
+}
+
+fn ago(secs: u64) -> String {
+    format!("{} ago", duration(secs))
+}
+
+/// Formats `secs` with its two largest units, e.g. `2h 5m`.
+fn duration(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m {}s", secs / 60, secs % 60),
+        3600..86400 => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+        _ => format!("{}d {}h", secs / 86400, secs % 86400 / 3600),
+    }
+}
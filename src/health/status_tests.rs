@@ -0,0 +1,163 @@
+//! Tests for the status report.
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::{StatusReport, duration, fetch_status, format_status, reachable};
+use crate::health::{HealthError, HealthReport, bind, serve};
+use crate::monitor::{IpChange, PollMetrics, PollSample};
+use crate::network::priority::AdapterPriority;
+use crate::network::{AdapterKind, AdapterSnapshot, IpVersion};
+use crate::report::RunStats;
+use crate::webhook::{CircuitBreakers, CircuitPolicy, RetryableError, WebhookError};
+
+fn at(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+fn stats() -> RunStats {
+    let metrics = PollMetrics::default();
+    metrics.record(PollSample {
+        finished_at: at(990),
+        duration: Duration::from_millis(5),
+        adapters: 1,
+        addresses: 1,
+        succeeded: true,
+    });
+    let stats = RunStats::new(at(0))
+        .with_adapter_priority(AdapterPriority::default())
+        .with_poll_metrics(metrics);
+    let eth0 = AdapterSnapshot::new(
+        "eth0",
+        AdapterKind::Ethernet,
+        vec!["192.0.2.5".parse().unwrap()],
+        vec!["2001:db8::5".parse().unwrap()],
+    );
+    stats.record_snapshot(&[eth0], IpVersion::Both);
+    stats.record_changes(&[IpChange::added(
+        "eth0",
+        "192.0.2.5".parse().unwrap(),
+        at(700),
+    )]);
+    stats
+}
+
+fn report(stats: &RunStats, now: SystemTime) -> StatusReport {
+    StatusReport::new(HealthReport::new(stats, now), stats, now)
+}
+
+#[test]
+fn last_change_age_from_change_timestamps() {
+    let status = report(&stats(), at(1000));
+
+    assert_eq!(status.last_change_age_secs, Some(300));
+    assert_eq!(status.summary.changes_detected, 1);
+    assert_eq!(
+        report(&RunStats::new(at(0)), at(5)).last_change_age_secs,
+        None
+    );
+}
+
+#[test]
+fn table_lists_state_and_adapters() {
+    let table = format_status(&report(&stats(), at(1000)));
+
+    assert!(table.contains("Status:        healthy\n"), "{table}");
+    assert!(table.contains("Last poll:     10s ago\n"), "{table}");
+    assert!(table.contains("Last change:   5m 0s ago\n"), "{table}");
+    assert!(table.contains("Last delivery: never\n"), "{table}");
+    assert!(
+        table.contains("Deliveries:    0 sent, 0 failed, 0 postponed\n"),
+        "{table}"
+    );
+    assert!(
+        table.contains("IPv4:          192.0.2.5 (eth0)\n"),
+        "{table}"
+    );
+    assert!(
+        table.contains("ADAPTER  ADDRESSES\neth0     192.0.2.5, 2001:db8::5\n"),
+        "{table}"
+    );
+}
+
+#[test]
+fn table_shows_last_successful_delivery() {
+    let stats = stats();
+    stats.record_delivery(&Ok(()));
+
+    let table = format_status(&report(&stats, SystemTime::now() + Duration::from_secs(90)));
+
+    assert!(table.contains("Last delivery: sent 1m 3"), "{table}");
+}
+
+#[test]
+fn table_lists_circuits_if_enabled() {
+    let circuits = CircuitBreakers::new(CircuitPolicy {
+        failure_threshold: 1,
+        cooldown: Duration::from_secs(120),
+    });
+    let tracked = stats().with_circuit_breakers(circuits.clone());
+    let _ = circuits.circuit("backup");
+    let failure = WebhookError::Retryable(RetryableError::Template("x".to_string()));
+    circuits
+        .circuit("main")
+        .record(&Err(failure), std::time::Instant::now());
+
+    let table = format_status(&report(&tracked, at(1000)));
+
+    // The cooldown may have started a second ago
+    assert!(
+        table.contains("Circuits:      backup closed, main open (retry in "),
+        "{table}"
+    );
+    assert!(!format_status(&report(&stats(), at(1000))).contains("Circuits:"));
+}
+
+#[test]
+fn durations_use_two_largest_units() {
+    assert_eq!(duration(59), "59s");
+    assert_eq!(duration(61), "1m 1s");
+    assert_eq!(duration(7500), "2h 5m");
+    assert_eq!(duration(90_000), "1d 1h");
+}
+
+#[test]
+fn unspecified_address_is_reached_over_loopback() {
+    assert_eq!(
+        reachable("0.0.0.0:8053".parse().unwrap()),
+        "127.0.0.1:8053".parse().unwrap()
+    );
+    assert_eq!(
+        reachable("[::]:8053".parse().unwrap()),
+        "[::1]:8053".parse().unwrap()
+    );
+    assert_eq!(
+        reachable("192.0.2.1:8053".parse().unwrap()),
+        "192.0.2.1:8053".parse().unwrap()
+    );
+}
+
+#[tokio::test]
+async fn fetched_from_running_endpoint() {
+    let listener = bind("127.0.0.1:0".parse().unwrap()).await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = tokio::spawn(serve(listener, Arc::new(stats())));
+
+    let status = fetch_status(address).await;
+    server.abort();
+
+    let status = status.unwrap();
+    assert!(status.health.healthy);
+    assert_eq!(status.summary.changes_detected, 1);
+}
+
+#[tokio::test]
+async fn nothing_listening_is_connect_error() {
+    let listener = bind("127.0.0.1:0".parse().unwrap()).await.unwrap();
+    let address = listener.local_addr().unwrap();
+    drop(listener);
+
+    let error = fetch_status(address).await.unwrap_err();
+
+    assert!(matches!(error, HealthError::Connect { .. }), "{error:?}");
+}
@@ -4,8 +4,16 @@
 //! and notifying external services via webhooks.
 
 pub mod config;
+pub mod daemon;
+pub mod dns;
+pub mod health;
+pub mod logging;
 pub mod monitor;
 pub mod network;
+pub mod report;
+pub mod service;
 pub mod state;
 pub mod time;
+pub mod tray;
+pub mod update;
 pub mod webhook;
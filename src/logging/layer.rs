@@ -0,0 +1,109 @@
+//! Tracing layer forwarding events to a [`LogSink`].
+
+use std::fmt::{self, Write};
+
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::Context;
+
+/// A destination for formatted log lines.
+pub trait LogSink: Send + Sync + 'static {
+    /// Writes `message`, logged at `level`.
+    fn write(&self, level: Level, message: &str);
+}
+
+impl LogSink for Box<dyn LogSink> {
+    fn write(&self, level: Level, message: &str) {
+        (**self).write(level, message);
+    }
+}
+
+/// A tracing layer writing each event to a [`LogSink`].
+///
+/// An event becomes one line: its message followed by its other fields as
+/// `key=value`. Timestamps and levels are left to the system log, which
+/// records both.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::{Arc, Mutex};
+///
+/// use ddns_a::logging::{LogSink, SystemLogLayer};
+/// use tracing::Level;
+/// use tracing_subscriber::layer::SubscriberExt;
+///
+/// #[derive(Clone, Default)]
+/// struct Lines(Arc<Mutex<Vec<String>>>);
+///
+/// impl LogSink for Lines {
+///     fn write(&self, _level: Level, message: &str) {
+///         self.0.lock().unwrap().push(message.to_string());
+///     }
+/// }
+///
+/// let lines = Lines::default();
+/// let subscriber = tracing_subscriber::registry().with(SystemLogLayer::new(lines.clone()));
+/// tracing::subscriber::with_default(subscriber, || {
+///     tracing::info!(adapter = "eth0", "Address added");
+/// });
+///
+/// assert_eq!(*lines.0.lock().unwrap(), ["Address added adapter=eth0"]);
+/// ```
+#[derive(Debug)]
+pub struct SystemLogLayer<S> {
+    sink: S,
+}
+
+impl<S: LogSink> SystemLogLayer<S> {
+    /// Creates a layer writing to `sink`.
+    #[must_use]
+    pub const fn new(sink: S) -> Self {
+        Self { sink }
+    }
+}
+
+impl<S: LogSink, C: Subscriber> Layer<C> for SystemLogLayer<S> {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, C>) {
+        let mut visitor = LineVisitor::default();
+        event.record(&mut visitor);
+        self.sink
+            .write(*event.metadata().level(), &visitor.into_line());
+    }
+}
+
+/// Collects an event's message and its other fields.
+#[derive(Default)]
+struct LineVisitor {
+    message: String,
+    fields: String,
+}
+
+impl LineVisitor {
+    fn into_line(self) -> String {
+        if self.message.is_empty() {
+            self.fields.trim_start().to_string()
+        } else {
+            self.message + &self.fields
+        }
+    }
+}
+
+impl Visit for LineVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={value}", field.name());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+}
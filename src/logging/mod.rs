@@ -0,0 +1,94 @@
+//! System log output (`[log] target = "system"`).
+//!
+//! [`SystemLogLayer`] is a tracing layer writing each event to a
+//! [`LogSink`]. [`open_system_log`] opens the platform's own: `syslog(3)` on
+//! Unix, which journald collects under systemd, and the Application event
+//! log on Windows, under the source [`SOURCE`]. Other platforms have none.
+
+mod layer;
+#[cfg(unix)]
+mod syslog;
+#[cfg(windows)]
+mod windows;
+
+#[cfg(test)]
+#[path = "mod_tests.rs"]
+mod tests;
+
+pub use layer::{LogSink, SystemLogLayer};
+#[cfg(unix)]
+pub use syslog::Syslog;
+#[cfg(windows)]
+pub use windows::EventLog;
+
+use std::io;
+
+use thiserror::Error;
+
+/// Name ddns-a logs under: the syslog identifier and the event source.
+pub const SOURCE: &str = "ddns-a";
+
+/// Where log output goes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogTarget {
+    /// Standard error (default)
+    #[default]
+    Stderr,
+    /// The system log: syslog/journald on Unix, the Event Log on Windows
+    System,
+}
+
+impl LogTarget {
+    /// Returns the name used to select this target in configuration.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Stderr => "stderr",
+            Self::System => "system",
+        }
+    }
+
+    /// Parses a target name, ignoring case.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "stderr" => Some(Self::Stderr),
+            "system" => Some(Self::System),
+            _ => None,
+        }
+    }
+}
+
+/// Errors that can occur while opening the system log.
+#[derive(Debug, Error)]
+pub enum LogError {
+    /// The system log could not be opened.
+    #[error("Failed to open the system log: {0}")]
+    Open(#[source] io::Error),
+
+    /// This platform has no system log.
+    #[error("No system log on this platform")]
+    Unsupported,
+}
+
+/// Opens the platform's system log.
+///
+/// # Errors
+///
+/// Returns [`LogError::Open`] if the Windows event source cannot be
+/// registered, or [`LogError::Unsupported`] on platforms without a system
+/// log. Opening syslog cannot fail.
+pub fn open_system_log() -> Result<Box<dyn LogSink>, LogError> {
+    #[cfg(unix)]
+    {
+        Ok(Box::new(Syslog::open()))
+    }
+    #[cfg(windows)]
+    {
+        EventLog::open().map(|log| Box::new(log) as Box<dyn LogSink>)
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        Err(LogError::Unsupported)
+    }
+}
@@ -0,0 +1,107 @@
+//! Tests for the logging module.
+
+use std::sync::{Arc, Mutex};
+
+use tracing::Level;
+use tracing_subscriber::layer::SubscriberExt;
+
+use super::{LogSink, LogTarget, SystemLogLayer};
+
+/// Sink keeping what it was given.
+#[derive(Clone, Default)]
+struct Recorded(Arc<Mutex<Vec<(Level, String)>>>);
+
+impl LogSink for Recorded {
+    fn write(&self, level: Level, message: &str) {
+        self.0.lock().unwrap().push((level, message.to_string()));
+    }
+}
+
+/// Runs `log` with a [`SystemLogLayer`] and returns what reached the sink.
+fn record(log: impl FnOnce()) -> Vec<(Level, String)> {
+    let sink = Recorded::default();
+    let subscriber = tracing_subscriber::registry().with(SystemLogLayer::new(sink.clone()));
+    tracing::subscriber::with_default(subscriber, log);
+    sink.0.lock().map(|lines| lines.clone()).unwrap()
+}
+
+mod system_log_layer {
+    use super::*;
+
+    #[test]
+    fn keeps_the_level() {
+        let lines = record(|| {
+            tracing::error!("failed");
+            tracing::warn!("warned");
+            tracing::info!("informed");
+        });
+
+        assert_eq!(
+            lines,
+            [
+                (Level::ERROR, "failed".to_string()),
+                (Level::WARN, "warned".to_string()),
+                (Level::INFO, "informed".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn formats_the_message() {
+        let lines = record(|| tracing::info!("Detected {} change(s)", 2));
+
+        assert_eq!(lines[0].1, "Detected 2 change(s)");
+    }
+
+    #[test]
+    fn appends_other_fields() {
+        let lines = record(|| tracing::warn!(adapter = "eth0", attempts = 3, "Delivery failed"));
+
+        assert_eq!(lines[0].1, "Delivery failed adapter=eth0 attempts=3");
+    }
+
+    #[test]
+    fn fields_without_message() {
+        let lines = record(|| tracing::info!(adapter = "eth0"));
+
+        assert_eq!(lines[0].1, "adapter=eth0");
+    }
+}
+
+mod log_target {
+    use super::*;
+
+    #[test]
+    fn default_is_stderr() {
+        assert_eq!(LogTarget::default(), LogTarget::Stderr);
+    }
+
+    #[test]
+    fn names_roundtrip() {
+        for target in [LogTarget::Stderr, LogTarget::System] {
+            assert_eq!(LogTarget::from_name(target.name()), Some(target));
+        }
+    }
+
+    #[test]
+    fn from_name_ignores_case() {
+        assert_eq!(LogTarget::from_name("System"), Some(LogTarget::System));
+    }
+
+    #[test]
+    fn from_name_rejects_unknown() {
+        assert_eq!(LogTarget::from_name("journald"), None);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn syslog_priorities() {
+    use super::syslog::priority;
+
+    assert_eq!(priority(Level::ERROR), libc::LOG_ERR);
+    assert_eq!(priority(Level::WARN), libc::LOG_WARNING);
+    assert_eq!(priority(Level::INFO), libc::LOG_INFO);
+    assert_eq!(priority(Level::DEBUG), libc::LOG_DEBUG);
+    assert_eq!(priority(Level::TRACE), libc::LOG_DEBUG);
+}
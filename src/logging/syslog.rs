@@ -0,0 +1,48 @@
+//! `syslog(3)` sink.
+
+use std::ffi::CString;
+
+use tracing::Level;
+
+use super::LogSink;
+
+/// Writes to the system logger through `syslog(3)`, with the `daemon`
+/// facility and the process ID.
+///
+/// Under systemd, journald receives these messages as well.
+#[derive(Debug)]
+pub struct Syslog {
+    _private: (),
+}
+
+impl Syslog {
+    /// Opens the system logger with [`SOURCE`](super::SOURCE) as identifier.
+    #[must_use]
+    pub fn open() -> Self {
+        // SAFETY: the identifier is a static C string, so it outlives every
+        // later syslog call as openlog requires.
+        unsafe { libc::openlog(c"ddns-a".as_ptr(), libc::LOG_PID, libc::LOG_DAEMON) };
+        Self { _private: () }
+    }
+}
+
+impl LogSink for Syslog {
+    fn write(&self, level: Level, message: &str) {
+        let Ok(message) = CString::new(message.replace('\0', "")) else {
+            return;
+        };
+        // SAFETY: the format takes exactly one string, which is a valid
+        // C string.
+        unsafe { libc::syslog(priority(level), c"%s".as_ptr(), message.as_ptr()) };
+    }
+}
+
+/// Returns the syslog priority of `level`.
+pub(super) const fn priority(level: Level) -> libc::c_int {
+    match level {
+        Level::ERROR => libc::LOG_ERR,
+        Level::WARN => libc::LOG_WARNING,
+        Level::INFO => libc::LOG_INFO,
+        _ => libc::LOG_DEBUG,
+    }
+}
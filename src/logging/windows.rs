@@ -0,0 +1,80 @@
+//! Windows Event Log sink.
+
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::System::EventLog::{
+    DeregisterEventSource, EVENTLOG_ERROR_TYPE, EVENTLOG_INFORMATION_TYPE, EVENTLOG_WARNING_TYPE,
+    REPORT_EVENT_TYPE, RegisterEventSourceW, ReportEventW,
+};
+use windows::core::{HSTRING, PCWSTR, w};
+
+use tracing::Level;
+
+use super::{LogError, LogSink};
+
+/// Writes to the Application event log under the source
+/// [`SOURCE`](super::SOURCE).
+///
+/// The source has no message file registered, so Event Viewer prefixes
+/// each entry with a note that its description is missing; the logged
+/// text follows it.
+#[derive(Debug)]
+pub struct EventLog {
+    handle: HANDLE,
+}
+
+// SAFETY: event log handles may be used from any thread.
+unsafe impl Send for EventLog {}
+// SAFETY: ReportEventW is safe to call concurrently on one handle.
+unsafe impl Sync for EventLog {}
+
+impl EventLog {
+    /// Registers the event source on the local computer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LogError::Open`] if the source cannot be registered.
+    pub fn open() -> Result<Self, LogError> {
+        // SAFETY: a null server name means the local computer and the
+        // source name is a static wide string.
+        let handle = unsafe { RegisterEventSourceW(PCWSTR::null(), w!("ddns-a")) }
+            .map_err(|e| LogError::Open(e.into()))?;
+        Ok(Self { handle })
+    }
+}
+
+impl LogSink for EventLog {
+    fn write(&self, level: Level, message: &str) {
+        let text = HSTRING::from(message);
+        // SAFETY: the handle stays registered until drop, and the one
+        // string outlives the call.
+        let _ = unsafe {
+            ReportEventW(
+                self.handle,
+                event_type(level),
+                0,
+                0,
+                None,
+                0,
+                Some(&[PCWSTR(text.as_ptr())]),
+                None,
+            )
+        };
+    }
+}
+
+impl Drop for EventLog {
+    fn drop(&mut self) {
+        // SAFETY: the handle was registered by open and is not used again.
+        let _ = unsafe { DeregisterEventSource(self.handle) };
+    }
+}
+
+/// Returns the event type of `level`; debug and trace output is logged as
+/// information.
+fn event_type(level: Level) -> REPORT_EVENT_TYPE {
+    match level {
+        Level::ERROR => EVENTLOG_ERROR_TYPE,
+        Level::WARN => EVENTLOG_WARNING_TYPE,
+        _ => EVENTLOG_INFORMATION_TYPE,
+    }
+}
@@ -2,55 +2,150 @@
 //!
 //! Entry point for the ddns-a application.
 
-use ddns_a::config::{Cli, Command, ValidatedConfig, write_default_config};
+use ddns_a::config::{
+    Cli, Command, Locale, Message, ServiceCommand, TemplateCommand, ValidatedConfig,
+    configured_locale,
+};
+use ddns_a::daemon::PidFile;
+use ddns_a::logging::LogTarget;
+use ddns_a::network::IpVersion;
+use ddns_a::time::ShutdownToken;
 use std::process::ExitCode;
+use std::time::Duration;
 
 mod app;
+mod command;
 mod run;
 
 use app::{exit_code, print_config_hint, setup_tracing};
+use command::{
+    handle_init, handle_template_check, run_as_service, run_check, run_config_command,
+    run_send_test, run_service_uninstall, run_webhook_test,
+};
 
 /// Main entry point.
 ///
 /// Excluded from coverage as it's the thin wrapper around testable components.
 #[cfg(not(tarpaulin_include))]
 fn main() -> ExitCode {
-    let cli = Cli::parse_args();
+    let mut cli = Cli::parse_args();
 
     // Handle init subcommand
-    if let Some(Command::Init { output }) = &cli.command {
-        return handle_init(output);
+    if let (
+        Some(Command::Init {
+            output,
+            from_snapshot,
+            ..
+        }),
+        Some(mode),
+    ) = (&cli.command, cli.init_mode())
+    {
+        return handle_init(output, mode, from_snapshot.as_deref(), Locale::from_env());
     }
 
-    // Load and validate configuration
-    let config = match ValidatedConfig::load(&cli) {
+    // Template checks need no configuration
+    if let Some(Command::Template {
+        command: TemplateCommand::Check { file, sample, json },
+    }) = &cli.command
+    {
+        let version = cli.ip_version.map_or(IpVersion::Both, Into::into);
+        return handle_template_check(file, sample.as_deref(), *json, version, Locale::from_env());
+    }
+
+    // Removing the service needs no configuration
+    if matches!(
+        cli.command,
+        Some(Command::Service {
+            command: ServiceCommand::Uninstall
+        })
+    ) {
+        return run_service_uninstall(Locale::from_env());
+    }
+
+    // Load and validate configuration, with DDNS_A_* variables filling CLI options
+    let config = match cli.apply_env().and_then(|()| ValidatedConfig::load(&cli)) {
         Ok(config) => config,
         Err(e) => {
-            eprintln!("Configuration error: {e}");
-            print_config_hint(&e);
+            let locale = configured_locale(&cli);
+            eprintln!(
+                "{}: {}",
+                locale.text(Message::ConfigError),
+                e.localized(locale)
+            );
+            print_config_hint(&e, locale);
             return exit_code::CONFIG_ERROR;
         }
     };
 
-    // Setup logging and run
-    setup_tracing(config.verbose);
-    tracing::info!("{config}");
+    if let Some(exit) = run_config_command(cli.command.as_ref(), &config) {
+        return exit;
+    }
+    if matches!(cli.command, Some(Command::Check)) {
+        setup_tracing(config.verbose, LogTarget::Stderr);
+        return run_check(config);
+    }
 
-    run_application(config)
-}
+    let webhook_test = matches!(
+        cli.command,
+        Some(Command::TestWebhook { .. } | Command::SendTest)
+    );
 
-/// Handles the `init` subcommand.
-fn handle_init(output: &std::path::Path) -> ExitCode {
-    match write_default_config(output) {
-        Ok(()) => {
-            println!("Configuration template written to: {}", output.display());
-            exit_code::SUCCESS
+    // Detach before the runtime starts: fork only carries over the calling thread.
+    // A restarted process is still detached.
+    #[cfg(unix)]
+    if config.daemonize && !webhook_test && !ddns_a::daemon::is_handoff() {
+        if let Err(e) = ddns_a::daemon::daemonize() {
+            eprintln!("Error: {e}");
+            return exit_code::runtime_error();
         }
+    }
+
+    // Setup logging and run; webhook tests are interactive and keep stderr
+    let log_target = if webhook_test {
+        LogTarget::Stderr
+    } else {
+        config.log_target
+    };
+    setup_tracing(config.verbose, log_target);
+    if let Some(ref path) = config.config_file {
+        tracing::info!("Using config file: {}", path.display());
+    } else {
+        tracing::debug!("No config file loaded");
+    }
+    tracing::info!("{config}");
+    #[cfg(unix)]
+    if ddns_a::daemon::is_handoff() {
+        tracing::info!("Restarted, resuming from the state handed over");
+    }
+    if let Some(overlap) = config.webhook.retry_overlap {
+        tracing::warn!("{overlap} (set retry.overlap = \"off\" to silence)");
+    }
+
+    if matches!(cli.command, Some(Command::SendTest)) {
+        return run_send_test(&config);
+    }
+    if let Some(Command::TestWebhook { all, ref targets }) = cli.command {
+        return run_webhook_test(&config, &run::TargetSelection::new(all, targets));
+    }
+
+    // Held until run_application returns, then removed
+    let _pid_file = match config.pid_file.as_ref().map(PidFile::create).transpose() {
+        Ok(pid_file) => pid_file,
         Err(e) => {
-            eprintln!("Error: {e}");
-            exit_code::CONFIG_ERROR
+            tracing::error!("{e}");
+            return exit_code::runtime_error();
         }
+    };
+
+    if matches!(
+        cli.command,
+        Some(Command::Service {
+            command: ServiceCommand::Run
+        })
+    ) {
+        return run_as_service(config);
     }
+    run_application(config)
 }
 
 /// Runs the main application with the given configuration.
@@ -60,11 +155,34 @@ fn handle_init(output: &std::path::Path) -> ExitCode {
 fn run_application(config: ValidatedConfig) -> ExitCode {
     let runtime = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
 
-    match runtime.block_on(run::execute(config)) {
-        Ok(()) => exit_code::SUCCESS,
+    match runtime.block_on(run::execute(config, ShutdownToken::new())) {
+        Ok(run::Exit::Shutdown) => exit_code::SUCCESS,
+        Ok(run::Exit::Restart) => {
+            // Blocking fetches may still hold runtime threads; exec ends them anyway
+            runtime.shutdown_timeout(Duration::from_secs(1));
+            restart()
+        }
         Err(e) => {
             tracing::error!("Application error: {e}");
             exit_code::runtime_error()
         }
     }
 }
+
+/// Replaces the process with the binary now on disk; only returns on failure.
+///
+/// Excluded from coverage - replaces the test process.
+#[cfg(not(tarpaulin_include))]
+#[cfg(unix)]
+fn restart() -> ExitCode {
+    let error = ddns_a::daemon::exec_restart();
+    tracing::error!("{error}");
+    exit_code::runtime_error()
+}
+
+/// Restarts are only requested by a Unix signal.
+#[cfg(not(unix))]
+fn restart() -> ExitCode {
+    tracing::error!("Restart is not supported on this platform");
+    exit_code::runtime_error()
+}
@@ -0,0 +1,61 @@
+//! Adaptive polling interval.
+
+use std::time::Duration;
+
+/// Policy stretching the poll interval while addresses stay stable.
+///
+/// Every poll without a change lengthens the interval by half, up to
+/// `max`; a detected change (or, in hybrid mode, an API event) drops it
+/// back to `min` at once, so follow-up changes are seen quickly.
+///
+/// # Examples
+///
+/// ```
+/// use ddns_a::monitor::AdaptivePolicy;
+/// use std::time::Duration;
+///
+/// let policy = AdaptivePolicy::new(Duration::from_secs(10), Duration::from_secs(20));
+///
+/// assert_eq!(policy.next(Duration::from_secs(10), false), Duration::from_secs(15));
+/// assert_eq!(policy.next(Duration::from_secs(15), false), Duration::from_secs(20));
+/// assert_eq!(policy.next(Duration::from_secs(20), true), Duration::from_secs(10));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdaptivePolicy {
+    min: Duration,
+    max: Duration,
+}
+
+impl AdaptivePolicy {
+    /// Creates a policy polling between `min` and `max`; a `max` below
+    /// `min` is raised to it.
+    #[must_use]
+    pub fn new(min: Duration, max: Duration) -> Self {
+        Self {
+            min,
+            max: max.max(min),
+        }
+    }
+
+    /// Returns the shortest interval, used after a change.
+    #[must_use]
+    pub const fn min(&self) -> Duration {
+        self.min
+    }
+
+    /// Returns the longest interval, reached while addresses stay stable.
+    #[must_use]
+    pub const fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// Returns the interval following a poll at `current`.
+    #[must_use]
+    pub fn next(&self, current: Duration, changed: bool) -> Duration {
+        if changed {
+            self.min
+        } else {
+            (current + current / 2).clamp(self.min, self.max)
+        }
+    }
+}
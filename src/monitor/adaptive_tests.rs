@@ -0,0 +1,52 @@
+//! Tests for the adaptive polling interval.
+
+use super::adaptive::AdaptivePolicy;
+use std::time::Duration;
+
+const MIN: Duration = Duration::from_secs(10);
+const MAX: Duration = Duration::from_secs(60);
+
+#[test]
+fn stable_polls_stretch_by_half() {
+    let policy = AdaptivePolicy::new(MIN, MAX);
+
+    assert_eq!(policy.next(MIN, false), Duration::from_secs(15));
+    assert_eq!(
+        policy.next(Duration::from_secs(15), false),
+        Duration::from_millis(22_500)
+    );
+}
+
+#[test]
+fn stretching_stops_at_max() {
+    let policy = AdaptivePolicy::new(MIN, MAX);
+
+    let mut interval = MIN;
+    for _ in 0..20 {
+        interval = policy.next(interval, false);
+    }
+
+    assert_eq!(interval, MAX);
+}
+
+#[test]
+fn change_drops_to_min() {
+    let policy = AdaptivePolicy::new(MIN, MAX);
+
+    assert_eq!(policy.next(MAX, true), MIN);
+}
+
+#[test]
+fn interval_below_min_is_raised() {
+    let policy = AdaptivePolicy::new(MIN, MAX);
+
+    assert_eq!(policy.next(Duration::from_secs(1), false), MIN);
+}
+
+#[test]
+fn max_below_min_is_raised() {
+    let policy = AdaptivePolicy::new(MIN, Duration::from_secs(5));
+
+    assert_eq!(policy.max(), MIN);
+    assert_eq!(policy.next(MIN, false), MIN);
+}
@@ -1,12 +1,14 @@
 //! IP change detection types and functions.
 
-use crate::network::{AdapterSnapshot, IpVersion};
+use crate::network::{AdapterMetadata, AdapterSnapshot, AddressInfo, AddressScope, IpVersion};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::net::IpAddr;
 use std::time::SystemTime;
 
-/// The kind of IP address change.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The kind of IP address change, serialized as `added` or `removed`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum IpChangeKind {
     /// An IP address was added to an adapter.
     Added,
@@ -14,19 +16,72 @@ pub enum IpChangeKind {
     Removed,
 }
 
+/// Which kinds of address change are notified (`webhook.notify_on`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NotifyOn {
+    /// Added and removed addresses.
+    #[default]
+    Both,
+    /// Only added addresses.
+    Added,
+    /// Only removed addresses.
+    Removed,
+}
+
+impl NotifyOn {
+    /// Returns true if changes of `kind` are notified.
+    #[must_use]
+    pub const fn includes(self, kind: IpChangeKind) -> bool {
+        match self {
+            Self::Both => true,
+            Self::Added => matches!(kind, IpChangeKind::Added),
+            Self::Removed => matches!(kind, IpChangeKind::Removed),
+        }
+    }
+}
+
 /// An IP address change event.
 ///
 /// Represents a single IP address being added or removed from a network adapter.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// # Serialization
+///
+/// Changes serialize with the field names below, the timestamp as an RFC
+/// 3339 date-time (see [`time::rfc3339`](crate::time::rfc3339)) and the
+/// address details flattened; unknown metadata and details are omitted:
+///
+/// ```
+/// use ddns_a::monitor::IpChange;
+/// use std::time::{Duration, SystemTime};
+///
+/// let at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_714_564_800);
+/// let change = IpChange::added("eth0", "192.0.2.1".parse().unwrap(), at);
+///
+/// let json = serde_json::to_string(&change).unwrap();
+/// assert_eq!(
+///     json,
+///     r#"{"adapter":"eth0","address":"192.0.2.1","timestamp":"2024-05-01T12:00:00Z","kind":"added"}"#
+/// );
+/// assert_eq!(serde_json::from_str::<IpChange>(&json).unwrap(), change);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct IpChange {
     /// The name of the adapter where the change occurred.
     pub adapter: String,
     /// The IP address that was added or removed.
     pub address: IpAddr,
     /// The timestamp when the change was detected.
+    #[serde(with = "crate::time::rfc3339")]
     pub timestamp: SystemTime,
     /// Whether the address was added or removed.
     pub kind: IpChangeKind,
+    /// Link-layer details of the adapter, if its snapshot carried them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<AdapterMetadata>,
+    /// Prefix length and preferred/deprecated state of the address, as far
+    /// as its snapshot knew them.
+    #[serde(flatten)]
+    pub info: AddressInfo,
 }
 
 impl IpChange {
@@ -43,9 +98,31 @@ impl IpChange {
             address,
             timestamp,
             kind,
+            metadata: None,
+            info: AddressInfo::default(),
         }
     }
 
+    /// Attaches the adapter's link-layer details, if any.
+    #[must_use]
+    pub fn with_metadata(mut self, metadata: Option<AdapterMetadata>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Attaches the address details.
+    #[must_use]
+    pub const fn with_info(mut self, info: AddressInfo) -> Self {
+        self.info = info;
+        self
+    }
+
+    /// Returns the scope of the address.
+    #[must_use]
+    pub const fn scope(&self) -> AddressScope {
+        AddressScope::of(self.address)
+    }
+
     /// Creates an "added" change event.
     #[must_use]
     pub fn added(adapter: impl Into<String>, address: IpAddr, timestamp: SystemTime) -> Self {
@@ -115,6 +192,21 @@ pub fn filter_by_version(changes: Vec<IpChange>, version: IpVersion) -> Vec<IpCh
     }
 }
 
+/// Filters IP changes by the kinds that are notified.
+///
+/// Returns only added addresses for `Added`, only removed ones for
+/// `Removed`, and all changes for `Both`.
+#[must_use]
+pub fn filter_by_kind(changes: Vec<IpChange>, notify_on: NotifyOn) -> Vec<IpChange> {
+    match notify_on {
+        NotifyOn::Both => changes,
+        NotifyOn::Added | NotifyOn::Removed => changes
+            .into_iter()
+            .filter(|c| notify_on.includes(c.kind))
+            .collect(),
+    }
+}
+
 /// Compares two adapter snapshots and returns a list of IP changes.
 ///
 /// This is a pure function that detects which IP addresses were added or removed
@@ -138,6 +230,10 @@ pub fn filter_by_version(changes: Vec<IpChange>, version: IpVersion) -> Vec<IpCh
 ///
 /// Adapters that exist only in `old` have all their addresses marked as `Removed`.
 /// Adapters that exist only in `new` have all their addresses marked as `Added`.
+///
+/// Each change carries the adapter's metadata from `new`, or from `old` for
+/// adapters that are gone, and the address details from the snapshot that
+/// holds the address: `new` for added addresses, `old` for removed ones.
 #[must_use]
 pub fn diff(
     old: &[AdapterSnapshot],
@@ -173,6 +269,19 @@ pub fn diff(
         }
     }
 
+    for change in &mut changes {
+        let adapter = new_by_name.get(change.adapter.as_str());
+        let adapter = adapter.or_else(|| old_by_name.get(change.adapter.as_str()));
+        change.metadata = adapter.and_then(|a| a.metadata.clone());
+        let holder = if change.is_added() {
+            &new_by_name
+        } else {
+            &old_by_name
+        };
+        if let Some(holder) = holder.get(change.adapter.as_str()) {
+            change.info = holder.info_of(change.address);
+        }
+    }
     changes
 }
 
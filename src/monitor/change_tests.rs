@@ -1,7 +1,7 @@
 //! Tests for IP change detection types and functions.
 
 use super::*;
-use crate::network::AdapterKind;
+use crate::network::{AdapterKind, AddressState};
 
 fn make_snapshot(name: &str, ipv4: Vec<&str>, ipv6: Vec<&str>) -> AdapterSnapshot {
     AdapterSnapshot::new(
@@ -362,6 +362,97 @@ mod diff_function {
         assert_eq!(removed_count, 2);
         assert_eq!(added_count, 2);
     }
+
+    fn with_mtu(snapshot: AdapterSnapshot, mtu: u32) -> AdapterSnapshot {
+        snapshot.with_metadata(AdapterMetadata {
+            mtu: Some(mtu),
+            ..AdapterMetadata::default()
+        })
+    }
+
+    #[test]
+    fn changes_carry_metadata_of_current_snapshot() {
+        let old = vec![with_mtu(
+            make_snapshot("eth0", vec!["192.168.1.1"], vec![]),
+            1500,
+        )];
+        let new = vec![with_mtu(
+            make_snapshot("eth0", vec!["192.168.1.2"], vec![]),
+            9000,
+        )];
+
+        let changes = diff(&old, &new, timestamp());
+
+        assert_eq!(changes.len(), 2);
+        for change in &changes {
+            assert_eq!(change.metadata.as_ref().and_then(|m| m.mtu), Some(9000));
+        }
+    }
+
+    #[test]
+    fn removed_adapter_changes_carry_old_metadata() {
+        let old = vec![with_mtu(
+            make_snapshot("eth0", vec!["192.168.1.1"], vec![]),
+            1500,
+        )];
+
+        let changes = diff(&old, &[], timestamp());
+
+        assert_eq!(changes[0].metadata.as_ref().and_then(|m| m.mtu), Some(1500));
+    }
+
+    #[test]
+    fn changes_without_metadata_carry_none() {
+        let new = vec![make_snapshot("eth0", vec!["192.168.1.1"], vec![])];
+
+        let changes = diff(&[], &new, timestamp());
+
+        assert_eq!(changes[0].metadata, None);
+    }
+
+    fn with_prefix(snapshot: AdapterSnapshot, address: &str, prefix_len: u8) -> AdapterSnapshot {
+        let info = AddressInfo {
+            prefix_len: Some(prefix_len),
+            state: Some(AddressState::Preferred),
+        };
+        snapshot.with_address_info(address.parse().unwrap(), info)
+    }
+
+    #[test]
+    fn changes_carry_info_of_the_snapshot_holding_the_address() {
+        let old = vec![with_prefix(
+            make_snapshot("eth0", vec!["192.168.1.1"], vec![]),
+            "192.168.1.1",
+            24,
+        )];
+        let new = vec![with_prefix(
+            make_snapshot("eth0", vec!["10.0.0.2"], vec![]),
+            "10.0.0.2",
+            8,
+        )];
+
+        let changes = diff(&old, &new, timestamp());
+
+        let prefix_of = |address: &str| {
+            let address: IpAddr = address.parse().unwrap();
+            changes
+                .iter()
+                .find(|c| c.address == address)
+                .and_then(|c| c.info.prefix_len)
+        };
+        assert_eq!(prefix_of("192.168.1.1"), Some(24));
+        assert_eq!(prefix_of("10.0.0.2"), Some(8));
+    }
+
+    #[test]
+    fn changes_without_info_carry_empty_info() {
+        let new = vec![make_snapshot("eth0", vec![], vec!["fe80::1"])];
+
+        let changes = diff(&[], &new, timestamp());
+
+        assert!(changes[0].info.is_empty());
+        assert_eq!(changes[0].scope(), AddressScope::LinkLocal);
+    }
 }
 
 mod filter_by_version_function {
@@ -451,6 +542,56 @@ mod filter_by_version_function {
     }
 }
 
+mod filter_by_kind_function {
+    use super::*;
+
+    fn changes() -> Vec<IpChange> {
+        vec![
+            IpChange::added("eth0", "192.168.1.2".parse().unwrap(), timestamp()),
+            IpChange::removed("eth0", "192.168.1.1".parse().unwrap(), timestamp()),
+            IpChange::added("eth0", "fe80::2".parse().unwrap(), timestamp()),
+        ]
+    }
+
+    #[test]
+    fn added_keeps_only_added() {
+        let filtered = filter_by_kind(changes(), NotifyOn::Added);
+
+        assert_eq!(filtered.len(), 2);
+        assert!(filtered.iter().all(IpChange::is_added));
+    }
+
+    #[test]
+    fn removed_keeps_only_removed() {
+        let filtered = filter_by_kind(changes(), NotifyOn::Removed);
+
+        assert_eq!(filtered.len(), 1);
+        assert!(filtered[0].is_removed());
+    }
+
+    #[test]
+    fn both_keeps_all() {
+        let filtered = filter_by_kind(changes(), NotifyOn::Both);
+
+        assert_eq!(filtered, changes());
+    }
+
+    #[test]
+    fn default_is_both() {
+        assert_eq!(NotifyOn::default(), NotifyOn::Both);
+    }
+
+    #[test]
+    fn includes_matches_kinds() {
+        assert!(NotifyOn::Added.includes(IpChangeKind::Added));
+        assert!(!NotifyOn::Added.includes(IpChangeKind::Removed));
+        assert!(NotifyOn::Removed.includes(IpChangeKind::Removed));
+        assert!(!NotifyOn::Removed.includes(IpChangeKind::Added));
+        assert!(NotifyOn::Both.includes(IpChangeKind::Added));
+        assert!(NotifyOn::Both.includes(IpChangeKind::Removed));
+    }
+}
+
 mod ip_change_version_methods {
     use super::*;
 
@@ -492,3 +633,78 @@ mod ip_change_version_methods {
         assert!(change.matches_version(IpVersion::Both));
     }
 }
+
+mod serialization {
+    use super::*;
+    use crate::network::OperStatus;
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn at(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn kind_is_lowercase() {
+        assert_eq!(serde_json::to_value(IpChangeKind::Added).unwrap(), "added");
+        assert_eq!(
+            serde_json::from_value::<IpChangeKind>(json!("removed")).unwrap(),
+            IpChangeKind::Removed
+        );
+    }
+
+    #[test]
+    fn details_are_flattened_and_round_trip() {
+        let change = IpChange::removed("eth0", "2001:db8::5".parse().unwrap(), at(1_714_564_800))
+            .with_metadata(Some(AdapterMetadata {
+                oper_status: Some(OperStatus::Up),
+                ..AdapterMetadata::default()
+            }))
+            .with_info(AddressInfo {
+                prefix_len: Some(64),
+                state: Some(AddressState::Deprecated),
+            });
+
+        let json = serde_json::to_value(&change).unwrap();
+
+        assert_eq!(
+            json,
+            json!({
+                "adapter": "eth0",
+                "address": "2001:db8::5",
+                "timestamp": "2024-05-01T12:00:00Z",
+                "kind": "removed",
+                "metadata": {"oper_status": "up"},
+                "prefix_len": 64,
+                "state": "deprecated",
+            })
+        );
+        assert_eq!(serde_json::from_value::<IpChange>(json).unwrap(), change);
+    }
+
+    #[test]
+    fn sub_second_timestamps_round_trip() {
+        let change = IpChange::added(
+            "eth0",
+            "192.0.2.1".parse().unwrap(),
+            at(100) + Duration::from_micros(1_500),
+        );
+
+        let json = serde_json::to_value(&change).unwrap();
+
+        assert_eq!(json["timestamp"], "1970-01-01T00:01:40.0015Z");
+        assert_eq!(serde_json::from_value::<IpChange>(json).unwrap(), change);
+    }
+
+    #[test]
+    fn rejects_unix_timestamps() {
+        let json = json!({
+            "adapter": "eth0",
+            "address": "192.0.2.1",
+            "timestamp": 100,
+            "kind": "added",
+        });
+
+        assert!(serde_json::from_value::<IpChange>(json).is_err());
+    }
+}
@@ -0,0 +1,120 @@
+//! DNS configuration change detection.
+//!
+//! Corporate VPN connects often switch an adapter's resolvers or DNS suffix
+//! without touching its addresses. [`DnsTrackingFetcher`] compares the
+//! [`DnsSettings`] of consecutive fetches and reports each difference as a
+//! [`DnsChange`], separately from the address changes the streams yield.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Mutex, PoisonError};
+use std::time::SystemTime;
+
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::network::{AdapterSnapshot, AddressFetcher, DnsSettings, FetchError};
+use crate::time::{Clock, SystemClock};
+
+/// A change to an adapter's DNS settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DnsChange {
+    /// The name of the adapter whose settings changed.
+    pub adapter: String,
+    /// Settings before the change.
+    pub old: DnsSettings,
+    /// Settings after the change.
+    pub new: DnsSettings,
+    /// The timestamp when the change was detected.
+    pub timestamp: SystemTime,
+}
+
+impl fmt::Display for DnsChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: DNS {} → {}", self.adapter, self.old, self.new)
+    }
+}
+
+/// Compares DNS settings of adapters present in both snapshots.
+///
+/// Adapters without DNS settings, or that appear or disappear between the
+/// snapshots, are ignored: address changes already cover the latter.
+#[must_use]
+pub fn diff_dns(
+    old: &[AdapterSnapshot],
+    new: &[AdapterSnapshot],
+    timestamp: SystemTime,
+) -> Vec<DnsChange> {
+    let previous: HashMap<&str, &DnsSettings> = old
+        .iter()
+        .filter_map(|a| Some((a.name.as_str(), a.dns.as_ref()?)))
+        .collect();
+
+    new.iter()
+        .filter_map(|adapter| {
+            let current = adapter.dns.as_ref()?;
+            let before = *previous.get(adapter.name.as_str())?;
+            (before != current).then(|| DnsChange {
+                adapter: adapter.name.clone(),
+                old: before.clone(),
+                new: current.clone(),
+                timestamp,
+            })
+        })
+        .collect()
+}
+
+/// An [`AddressFetcher`] decorator that reports DNS setting changes.
+///
+/// Every successful fetch is compared with the previous one; changes are sent
+/// as one batch on `events`. The first fetch only records a baseline. If the
+/// inner fetcher does not collect DNS settings, nothing is ever sent.
+#[derive(Debug)]
+pub struct DnsTrackingFetcher<F, C = SystemClock> {
+    inner: F,
+    events: UnboundedSender<Vec<DnsChange>>,
+    clock: C,
+    previous: Mutex<Option<Vec<AdapterSnapshot>>>,
+}
+
+impl<F> DnsTrackingFetcher<F, SystemClock> {
+    /// Wraps `inner`, sending detected changes on `events`.
+    #[must_use]
+    pub const fn new(inner: F, events: UnboundedSender<Vec<DnsChange>>) -> Self {
+        Self::with_clock(inner, events, SystemClock)
+    }
+}
+
+impl<F, C> DnsTrackingFetcher<F, C> {
+    /// Wraps `inner` with a custom clock for change timestamps.
+    #[must_use]
+    pub const fn with_clock(inner: F, events: UnboundedSender<Vec<DnsChange>>, clock: C) -> Self {
+        Self {
+            inner,
+            events,
+            clock,
+            previous: Mutex::new(None),
+        }
+    }
+}
+
+impl<F: AddressFetcher, C: Clock> AddressFetcher for DnsTrackingFetcher<F, C> {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        let current = self.inner.fetch()?;
+
+        let previous = self
+            .previous
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .replace(current.clone());
+
+        if let Some(prev) = previous {
+            let changes = diff_dns(&prev, &current, self.clock.now());
+            if !changes.is_empty() {
+                // A closed receiver means nobody listens any more; drop the batch
+                let _ = self.events.send(changes);
+            }
+        }
+
+        Ok(current)
+    }
+}
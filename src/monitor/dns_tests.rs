@@ -0,0 +1,203 @@
+//! Tests for DNS configuration change detection.
+
+use super::dns::{DnsChange, DnsTrackingFetcher, diff_dns};
+use crate::network::{AdapterKind, AdapterSnapshot, AddressFetcher, DnsSettings, FetchError};
+use crate::time::Clock;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+
+struct FixedClock;
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(100)
+    }
+}
+
+/// Fetcher returning queued results in order.
+struct SequenceFetcher {
+    results: Mutex<VecDeque<Result<Vec<AdapterSnapshot>, FetchError>>>,
+}
+
+impl SequenceFetcher {
+    fn new(results: Vec<Result<Vec<AdapterSnapshot>, FetchError>>) -> Self {
+        Self {
+            results: Mutex::new(results.into()),
+        }
+    }
+}
+
+impl AddressFetcher for SequenceFetcher {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        self.results.lock().unwrap().pop_front().unwrap()
+    }
+}
+
+fn dns(suffix: &str, servers: &[&str]) -> DnsSettings {
+    DnsSettings {
+        suffix: suffix.to_string(),
+        servers: servers.iter().map(|s| s.parse().unwrap()).collect(),
+    }
+}
+
+fn adapter(name: &str, settings: Option<DnsSettings>) -> AdapterSnapshot {
+    let snapshot = AdapterSnapshot::new(
+        name,
+        AdapterKind::Ethernet,
+        vec!["192.0.2.1".parse().unwrap()],
+        vec![],
+    );
+    match settings {
+        Some(settings) => snapshot.with_dns(settings),
+        None => snapshot,
+    }
+}
+
+fn at(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+mod diff {
+    use super::*;
+
+    #[test]
+    fn unchanged_settings_yield_nothing() {
+        let old = [adapter("eth0", Some(dns("lan", &["192.0.2.53"])))];
+
+        assert!(diff_dns(&old, &old, at(0)).is_empty());
+    }
+
+    #[test]
+    fn server_change_is_reported() {
+        let old = [adapter("eth0", Some(dns("lan", &["192.0.2.53"])))];
+        let new = [adapter("eth0", Some(dns("lan", &["198.51.100.53"])))];
+
+        let changes = diff_dns(&old, &new, at(5));
+
+        assert_eq!(
+            changes,
+            [DnsChange {
+                adapter: "eth0".to_string(),
+                old: dns("lan", &["192.0.2.53"]),
+                new: dns("lan", &["198.51.100.53"]),
+                timestamp: at(5),
+            }]
+        );
+    }
+
+    #[test]
+    fn suffix_change_is_reported() {
+        let old = [adapter("vpn", Some(dns("", &[])))];
+        let new = [adapter("vpn", Some(dns("corp.example.com", &[])))];
+
+        assert_eq!(diff_dns(&old, &new, at(0)).len(), 1);
+    }
+
+    #[test]
+    fn server_order_matters() {
+        let old = [adapter("eth0", Some(dns("", &["192.0.2.1", "192.0.2.2"])))];
+        let new = [adapter("eth0", Some(dns("", &["192.0.2.2", "192.0.2.1"])))];
+
+        assert_eq!(diff_dns(&old, &new, at(0)).len(), 1);
+    }
+
+    #[test]
+    fn new_and_removed_adapters_are_ignored() {
+        let old = [adapter("eth0", Some(dns("lan", &[])))];
+        let new = [adapter("wlan0", Some(dns("home", &[])))];
+
+        assert!(diff_dns(&old, &new, at(0)).is_empty());
+    }
+
+    #[test]
+    fn adapters_without_settings_are_ignored() {
+        let old = [adapter("eth0", None)];
+        let new = [adapter("eth0", Some(dns("lan", &[])))];
+
+        assert!(diff_dns(&old, &new, at(0)).is_empty());
+    }
+
+    #[test]
+    fn display_shows_both_sides() {
+        let change = DnsChange {
+            adapter: "vpn".to_string(),
+            old: dns("", &["192.0.2.53"]),
+            new: dns("corp.example.com", &["10.0.0.53"]),
+            timestamp: at(0),
+        };
+
+        assert_eq!(
+            change.to_string(),
+            "vpn: DNS suffix (none), servers [192.0.2.53] → suffix corp.example.com, servers [10.0.0.53]"
+        );
+    }
+}
+
+mod tracking_fetcher {
+    use super::*;
+
+    #[test]
+    fn first_fetch_is_baseline_then_changes_are_sent() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let fetcher = DnsTrackingFetcher::with_clock(
+            SequenceFetcher::new(vec![
+                Ok(vec![adapter("eth0", Some(dns("lan", &["192.0.2.53"])))]),
+                Ok(vec![adapter("eth0", Some(dns("lan", &["192.0.2.53"])))]),
+                Ok(vec![adapter("eth0", Some(dns("vpn", &["10.0.0.53"])))]),
+            ]),
+            tx,
+            FixedClock,
+        );
+
+        fetcher.fetch().unwrap();
+        fetcher.fetch().unwrap();
+        assert!(rx.try_recv().is_err());
+
+        let snapshot = fetcher.fetch().unwrap();
+        assert_eq!(snapshot[0].dns, Some(dns("vpn", &["10.0.0.53"])));
+
+        let batch = rx.try_recv().unwrap();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].new.suffix, "vpn");
+        assert_eq!(batch[0].timestamp, at(100));
+    }
+
+    #[test]
+    fn failed_fetch_keeps_baseline() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let fetcher = DnsTrackingFetcher::new(
+            SequenceFetcher::new(vec![
+                Ok(vec![adapter("eth0", Some(dns("lan", &[])))]),
+                Err(FetchError::Platform {
+                    message: "boom".to_string(),
+                }),
+                Ok(vec![adapter("eth0", Some(dns("vpn", &[])))]),
+            ]),
+            tx,
+        );
+
+        fetcher.fetch().unwrap();
+        assert!(fetcher.fetch().is_err());
+        fetcher.fetch().unwrap();
+
+        assert_eq!(rx.try_recv().unwrap()[0].old.suffix, "lan");
+    }
+
+    #[test]
+    fn closed_receiver_does_not_fail_fetch() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        drop(rx);
+        let fetcher = DnsTrackingFetcher::new(
+            SequenceFetcher::new(vec![
+                Ok(vec![adapter("eth0", Some(dns("a", &[])))]),
+                Ok(vec![adapter("eth0", Some(dns("b", &[])))]),
+            ]),
+            tx,
+        );
+
+        fetcher.fetch().unwrap();
+        assert!(fetcher.fetch().is_ok());
+    }
+}
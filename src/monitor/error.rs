@@ -14,6 +14,16 @@ pub enum ApiError {
     #[error("Windows API error: {0}")]
     WindowsApi(#[from] windows::core::Error),
 
+    /// The rtnetlink socket failed.
+    #[cfg(target_os = "linux")]
+    #[error("Netlink error: {0}")]
+    Netlink(#[source] std::io::Error),
+
+    /// The `PF_ROUTE` routing socket failed.
+    #[cfg(target_os = "macos")]
+    #[error("Routing socket error: {0}")]
+    RoutingSocket(#[source] std::io::Error),
+
     /// The API listener stopped unexpectedly.
     ///
     /// This can happen when the underlying event stream terminates
@@ -0,0 +1,85 @@
+//! Broadcasting observed address changes.
+//!
+//! A [`ChangeBus`] hands every batch of [`IpChange`]s the monitor observes
+//! to any number of subscribers, e.g. a UI or an extra sink, next to the
+//! regular webhook delivery. Publishing never waits: a subscriber that falls
+//! more than the capacity behind misses the oldest batches and is told how
+//! many with [`RecvError::Lagged`](tokio::sync::broadcast::error::RecvError).
+
+use std::sync::Arc;
+
+use tokio::sync::broadcast;
+
+use super::IpChange;
+
+/// A batch of changes as subscribers receive it, shared between them.
+pub type ChangeBatch = Arc<[IpChange]>;
+
+/// Batches a [`ChangeBus`] keeps for slow subscribers by default.
+pub const DEFAULT_CAPACITY: usize = 64;
+
+/// A broadcast channel of observed change batches.
+///
+/// Clones publish to the same subscribers.
+///
+/// # Examples
+///
+/// ```
+/// use ddns_a::monitor::IpChange;
+/// use ddns_a::monitor::events::ChangeBus;
+/// use std::time::SystemTime;
+///
+/// let bus = ChangeBus::default();
+/// let mut changes = bus.subscribe();
+///
+/// let change = IpChange::added("eth0", "192.0.2.1".parse().unwrap(), SystemTime::UNIX_EPOCH);
+/// bus.publish(&[change.clone()]);
+///
+/// assert_eq!(&*changes.try_recv().unwrap(), &[change]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChangeBus {
+    sender: broadcast::Sender<ChangeBatch>,
+}
+
+impl Default for ChangeBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl ChangeBus {
+    /// Creates a bus keeping up to `capacity` batches for slow subscribers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            sender: broadcast::channel(capacity).0,
+        }
+    }
+
+    /// Subscribes to the batches published from now on.
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<ChangeBatch> {
+        self.sender.subscribe()
+    }
+
+    /// Returns the number of subscribers.
+    #[must_use]
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Publishes `changes` to every subscriber. Empty batches and buses
+    /// without subscribers publish nothing.
+    pub fn publish(&self, changes: &[IpChange]) {
+        if changes.is_empty() || self.sender.receiver_count() == 0 {
+            return;
+        }
+        // The last subscriber may have gone since; nobody misses the batch
+        let _ = self.sender.send(Arc::from(changes));
+    }
+}
@@ -0,0 +1,76 @@
+//! Tests for the change bus.
+
+use super::IpChange;
+use super::events::ChangeBus;
+use std::time::SystemTime;
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+
+fn change(address: &str) -> IpChange {
+    IpChange::added("eth0", address.parse().unwrap(), SystemTime::UNIX_EPOCH)
+}
+
+#[test]
+fn publishes_to_every_subscriber() {
+    let bus = ChangeBus::default();
+    let mut first = bus.subscribe();
+    let mut second = bus.subscribe();
+
+    bus.publish(&[change("192.0.2.1")]);
+
+    assert_eq!(&*first.try_recv().unwrap(), &[change("192.0.2.1")]);
+    assert_eq!(&*second.try_recv().unwrap(), &[change("192.0.2.1")]);
+}
+
+#[test]
+fn clones_publish_to_the_same_subscribers() {
+    let bus = ChangeBus::default();
+    let mut changes = bus.subscribe();
+
+    #[allow(clippy::redundant_clone)]
+    bus.clone().publish(&[change("192.0.2.1")]);
+
+    assert_eq!(changes.try_recv().unwrap().len(), 1);
+    assert_eq!(bus.subscriber_count(), 1);
+}
+
+#[test]
+fn publishing_without_subscribers_is_a_no_op() {
+    let bus = ChangeBus::default();
+
+    bus.publish(&[change("192.0.2.1")]);
+
+    let mut late = bus.subscribe();
+    assert_eq!(late.try_recv().unwrap_err(), TryRecvError::Empty);
+}
+
+#[test]
+fn empty_batches_are_not_published() {
+    let bus = ChangeBus::default();
+    let mut changes = bus.subscribe();
+
+    bus.publish(&[]);
+
+    assert_eq!(changes.try_recv().unwrap_err(), TryRecvError::Empty);
+}
+
+#[test]
+fn dropped_subscribers_are_not_counted() {
+    let bus = ChangeBus::default();
+    let changes = bus.subscribe();
+    drop(changes);
+
+    assert_eq!(bus.subscriber_count(), 0);
+}
+
+#[tokio::test]
+async fn slow_subscribers_miss_the_oldest_batches() {
+    let bus = ChangeBus::new(2);
+    let mut changes = bus.subscribe();
+
+    bus.publish(&[change("192.0.2.1")]);
+    bus.publish(&[change("192.0.2.2")]);
+    bus.publish(&[change("192.0.2.3")]);
+
+    assert!(matches!(changes.recv().await, Err(RecvError::Lagged(1))));
+    assert_eq!(&*changes.recv().await.unwrap(), &[change("192.0.2.2")]);
+}
@@ -0,0 +1,267 @@
+//! Expected static address monitoring.
+//!
+//! Servers with static addresses can list them as [`ExpectedAddresses`].
+//! [`ExpectationFetcher`] checks every fetch against that list and reports
+//! [`AddressDrift`] — an expected address missing from the monitored
+//! adapters, or an address on them that is not expected — once when it
+//! starts and once when it is resolved, as [`DriftChange`]s separate from the
+//! address changes the streams yield.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::net::IpAddr;
+use std::sync::{Mutex, PoisonError};
+use std::time::SystemTime;
+
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::network::{AdapterSnapshot, AddressFetcher, FetchError};
+use crate::time::{Clock, SystemClock};
+
+/// How the monitored addresses differ from the expected ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DriftKind {
+    /// An expected address is on none of the monitored adapters.
+    Missing,
+    /// A monitored adapter has an address that is not expected.
+    Unexpected,
+}
+
+impl DriftKind {
+    /// Returns the lowercase name used in payloads.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Missing => "missing",
+            Self::Unexpected => "unexpected",
+        }
+    }
+}
+
+/// A single difference between the monitored and the expected addresses.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AddressDrift {
+    /// Whether the address is missing or unexpected.
+    pub kind: DriftKind,
+    /// The address concerned.
+    pub address: IpAddr,
+    /// The adapter carrying an unexpected address; `None` when missing.
+    pub adapter: Option<String>,
+}
+
+impl fmt::Display for AddressDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.kind, &self.adapter) {
+            (DriftKind::Unexpected, Some(adapter)) => {
+                write!(f, "{adapter}: unexpected address {}", self.address)
+            }
+            (DriftKind::Unexpected, None) => write!(f, "unexpected address {}", self.address),
+            (DriftKind::Missing, _) => write!(f, "expected address {} missing", self.address),
+        }
+    }
+}
+
+/// Drift that started or was resolved between two checks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DriftChange {
+    /// The drift concerned.
+    pub drift: AddressDrift,
+    /// Whether the drift ended (`false` when it started).
+    pub resolved: bool,
+    /// The timestamp when the change was detected.
+    pub timestamp: SystemTime,
+}
+
+impl fmt::Display for DriftChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.resolved {
+            write!(f, "{} (resolved)", self.drift)
+        } else {
+            write!(f, "{}", self.drift)
+        }
+    }
+}
+
+/// The static addresses the monitored adapters are expected to carry.
+///
+/// Unexpected addresses are only looked for in the families that have
+/// expected addresses, and loopback, link-local and unspecified addresses
+/// are never unexpected.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExpectedAddresses {
+    addresses: Vec<IpAddr>,
+}
+
+impl ExpectedAddresses {
+    /// Creates the list from `addresses`, ignoring duplicates.
+    #[must_use]
+    pub fn new(addresses: impl IntoIterator<Item = IpAddr>) -> Self {
+        let mut addresses: Vec<_> = addresses.into_iter().collect();
+        addresses.sort_unstable();
+        addresses.dedup();
+        Self { addresses }
+    }
+
+    /// Returns the expected addresses, sorted.
+    #[must_use]
+    pub fn addresses(&self) -> &[IpAddr] {
+        &self.addresses
+    }
+
+    /// Returns true if no address is expected, which disables the check.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty()
+    }
+
+    /// Returns the current drift of `adapters`, sorted.
+    #[must_use]
+    pub fn check(&self, adapters: &[AdapterSnapshot]) -> Vec<AddressDrift> {
+        let checks_v4 = self.addresses.iter().any(IpAddr::is_ipv4);
+        let checks_v6 = self.addresses.iter().any(IpAddr::is_ipv6);
+        let mut present = HashSet::new();
+        let mut drift = Vec::new();
+
+        for adapter in adapters.iter().filter(|a| !a.kind.is_loopback()) {
+            let v4 = adapter.ipv4_addresses.iter().map(|&a| IpAddr::V4(a));
+            let v6 = adapter.ipv6_addresses.iter().map(|&a| IpAddr::V6(a));
+            for address in v4.chain(v6) {
+                present.insert(address);
+                let checked = if address.is_ipv4() {
+                    checks_v4
+                } else {
+                    checks_v6
+                };
+                if checked && is_assigned(address) && !self.addresses.contains(&address) {
+                    drift.push(AddressDrift {
+                        kind: DriftKind::Unexpected,
+                        address,
+                        adapter: Some(adapter.name.clone()),
+                    });
+                }
+            }
+        }
+
+        drift.extend(
+            self.addresses
+                .iter()
+                .filter(|address| !present.contains(address))
+                .map(|&address| AddressDrift {
+                    kind: DriftKind::Missing,
+                    address,
+                    adapter: None,
+                }),
+        );
+        drift.sort();
+        drift.dedup();
+        drift
+    }
+}
+
+/// Returns true for addresses an administrator assigns, as opposed to the
+/// loopback, link-local and unspecified addresses every host has.
+const fn is_assigned(address: IpAddr) -> bool {
+    match address {
+        IpAddr::V4(a) => !a.is_loopback() && !a.is_link_local() && !a.is_unspecified(),
+        IpAddr::V6(a) => !a.is_loopback() && !a.is_unicast_link_local() && !a.is_unspecified(),
+    }
+}
+
+/// Compares the drift of two checks.
+///
+/// Drift only in `new` has started; drift only in `old` has been resolved.
+#[must_use]
+pub fn diff_drift(
+    old: &[AddressDrift],
+    new: &[AddressDrift],
+    timestamp: SystemTime,
+) -> Vec<DriftChange> {
+    let change = |resolved| {
+        move |drift: &AddressDrift| DriftChange {
+            drift: drift.clone(),
+            resolved,
+            timestamp,
+        }
+    };
+
+    old.iter()
+        .filter(|drift| !new.contains(drift))
+        .map(change(true))
+        .chain(
+            new.iter()
+                .filter(|drift| !old.contains(drift))
+                .map(change(false)),
+        )
+        .collect()
+}
+
+/// An [`AddressFetcher`] decorator that reports drift from the expected
+/// addresses.
+///
+/// Every successful fetch is checked; drift that started or was resolved
+/// since the previous fetch is sent as one batch on `events`. Drift found by
+/// the first fetch is reported too, so a server that starts without its
+/// static address alerts at once. Without expected addresses nothing is ever
+/// sent.
+#[derive(Debug)]
+pub struct ExpectationFetcher<F, C = SystemClock> {
+    inner: F,
+    expected: ExpectedAddresses,
+    events: UnboundedSender<Vec<DriftChange>>,
+    clock: C,
+    previous: Mutex<Vec<AddressDrift>>,
+}
+
+impl<F> ExpectationFetcher<F, SystemClock> {
+    /// Wraps `inner`, sending drift changes from `expected` on `events`.
+    #[must_use]
+    pub const fn new(
+        inner: F,
+        expected: ExpectedAddresses,
+        events: UnboundedSender<Vec<DriftChange>>,
+    ) -> Self {
+        Self::with_clock(inner, expected, events, SystemClock)
+    }
+}
+
+impl<F, C> ExpectationFetcher<F, C> {
+    /// Wraps `inner` with a custom clock for change timestamps.
+    #[must_use]
+    pub const fn with_clock(
+        inner: F,
+        expected: ExpectedAddresses,
+        events: UnboundedSender<Vec<DriftChange>>,
+        clock: C,
+    ) -> Self {
+        Self {
+            inner,
+            expected,
+            events,
+            clock,
+            previous: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl<F: AddressFetcher, C: Clock> AddressFetcher for ExpectationFetcher<F, C> {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        let current = self.inner.fetch()?;
+        if self.expected.is_empty() {
+            return Ok(current);
+        }
+
+        let drift = self.expected.check(&current);
+        let previous = std::mem::replace(
+            &mut *self.previous.lock().unwrap_or_else(PoisonError::into_inner),
+            drift.clone(),
+        );
+
+        let changes = diff_drift(&previous, &drift, self.clock.now());
+        if !changes.is_empty() {
+            // A closed receiver means nobody listens any more; drop the batch
+            let _ = self.events.send(changes);
+        }
+
+        Ok(current)
+    }
+}
@@ -0,0 +1,312 @@
+//! Tests for expected static address monitoring.
+
+use super::expect::{
+    AddressDrift, DriftChange, DriftKind, ExpectationFetcher, ExpectedAddresses, diff_drift,
+};
+use crate::network::{AdapterKind, AdapterSnapshot, AddressFetcher, FetchError};
+use crate::time::Clock;
+use std::collections::VecDeque;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+
+struct FixedClock;
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(100)
+    }
+}
+
+/// Fetcher returning queued results in order.
+struct SequenceFetcher {
+    results: Mutex<VecDeque<Result<Vec<AdapterSnapshot>, FetchError>>>,
+}
+
+impl SequenceFetcher {
+    fn new(results: Vec<Result<Vec<AdapterSnapshot>, FetchError>>) -> Self {
+        Self {
+            results: Mutex::new(results.into()),
+        }
+    }
+}
+
+impl AddressFetcher for SequenceFetcher {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        self.results.lock().unwrap().pop_front().unwrap()
+    }
+}
+
+fn ip(s: &str) -> IpAddr {
+    s.parse().unwrap()
+}
+
+fn expected(addresses: &[&str]) -> ExpectedAddresses {
+    ExpectedAddresses::new(addresses.iter().map(|a| ip(a)))
+}
+
+fn adapter(name: &str, ipv4: &[&str], ipv6: &[&str]) -> AdapterSnapshot {
+    AdapterSnapshot::new(
+        name,
+        AdapterKind::Ethernet,
+        ipv4.iter().map(|a| a.parse().unwrap()).collect(),
+        ipv6.iter().map(|a| a.parse().unwrap()).collect(),
+    )
+}
+
+fn missing(address: &str) -> AddressDrift {
+    AddressDrift {
+        kind: DriftKind::Missing,
+        address: ip(address),
+        adapter: None,
+    }
+}
+
+fn unexpected(adapter: &str, address: &str) -> AddressDrift {
+    AddressDrift {
+        kind: DriftKind::Unexpected,
+        address: ip(address),
+        adapter: Some(adapter.to_string()),
+    }
+}
+
+mod check {
+    use super::*;
+
+    #[test]
+    fn expected_addresses_present_is_no_drift() {
+        let adapters = [adapter("eth0", &["203.0.113.10"], &["2001:db8::10"])];
+
+        let drift = expected(&["203.0.113.10", "2001:db8::10"]).check(&adapters);
+
+        assert!(drift.is_empty());
+    }
+
+    #[test]
+    fn expected_address_on_any_adapter_counts() {
+        let adapters = [
+            adapter("eth0", &["203.0.113.10"], &[]),
+            adapter("eth1", &["203.0.113.11"], &[]),
+        ];
+
+        let drift = expected(&["203.0.113.10", "203.0.113.11"]).check(&adapters);
+
+        assert!(drift.is_empty());
+    }
+
+    #[test]
+    fn missing_address_is_reported() {
+        let adapters = [adapter("eth0", &["203.0.113.10"], &[])];
+
+        let drift = expected(&["203.0.113.10", "203.0.113.11"]).check(&adapters);
+
+        assert_eq!(drift, [missing("203.0.113.11")]);
+    }
+
+    #[test]
+    fn unexpected_address_is_reported_with_adapter() {
+        let adapters = [adapter("eth0", &["203.0.113.10", "198.51.100.7"], &[])];
+
+        let drift = expected(&["203.0.113.10"]).check(&adapters);
+
+        assert_eq!(drift, [unexpected("eth0", "198.51.100.7")]);
+    }
+
+    #[test]
+    fn replaced_address_is_missing_and_unexpected() {
+        let adapters = [adapter("eth0", &["198.51.100.7"], &[])];
+
+        let drift = expected(&["203.0.113.10"]).check(&adapters);
+
+        assert_eq!(
+            drift,
+            [missing("203.0.113.10"), unexpected("eth0", "198.51.100.7")]
+        );
+    }
+
+    #[test]
+    fn families_without_expectations_are_not_checked() {
+        let adapters = [adapter("eth0", &["203.0.113.10"], &["2001:db8::99"])];
+
+        let drift = expected(&["203.0.113.10"]).check(&adapters);
+
+        assert!(drift.is_empty());
+    }
+
+    #[test]
+    fn link_local_and_loopback_are_never_unexpected() {
+        let adapters = [
+            adapter(
+                "eth0",
+                &["203.0.113.10", "169.254.1.1"],
+                &["2001:db8::10", "fe80::1"],
+            ),
+            AdapterSnapshot::new(
+                "lo",
+                AdapterKind::Loopback,
+                vec!["127.0.0.1".parse().unwrap(), "192.0.2.1".parse().unwrap()],
+                vec!["::1".parse().unwrap()],
+            ),
+        ];
+
+        let drift = expected(&["203.0.113.10", "2001:db8::10"]).check(&adapters);
+
+        assert!(drift.is_empty());
+    }
+
+    #[test]
+    fn duplicates_are_ignored() {
+        let list = expected(&["203.0.113.10", "203.0.113.10"]);
+
+        assert_eq!(list.addresses(), [ip("203.0.113.10")]);
+        assert_eq!(list.check(&[]), [missing("203.0.113.10")]);
+    }
+
+    #[test]
+    fn empty_list_has_no_drift() {
+        let adapters = [adapter("eth0", &["198.51.100.7"], &[])];
+
+        assert!(ExpectedAddresses::default().check(&adapters).is_empty());
+    }
+}
+
+mod diff {
+    use super::*;
+
+    #[test]
+    fn new_drift_has_started_and_old_is_resolved() {
+        let old = [missing("203.0.113.10")];
+        let new = [unexpected("eth0", "198.51.100.7")];
+
+        let changes = diff_drift(&old, &new, UNIX_EPOCH);
+
+        assert_eq!(
+            changes,
+            [
+                DriftChange {
+                    drift: missing("203.0.113.10"),
+                    resolved: true,
+                    timestamp: UNIX_EPOCH,
+                },
+                DriftChange {
+                    drift: unexpected("eth0", "198.51.100.7"),
+                    resolved: false,
+                    timestamp: UNIX_EPOCH,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn ongoing_drift_is_not_repeated() {
+        let drift = [missing("203.0.113.10")];
+
+        assert!(diff_drift(&drift, &drift, UNIX_EPOCH).is_empty());
+    }
+
+    #[test]
+    fn display_describes_the_drift() {
+        let change = |drift, resolved| DriftChange {
+            drift,
+            resolved,
+            timestamp: UNIX_EPOCH,
+        };
+
+        assert_eq!(
+            change(missing("203.0.113.10"), false).to_string(),
+            "expected address 203.0.113.10 missing"
+        );
+        assert_eq!(
+            change(unexpected("eth0", "198.51.100.7"), true).to_string(),
+            "eth0: unexpected address 198.51.100.7 (resolved)"
+        );
+    }
+}
+
+mod expectation_fetcher {
+    use super::*;
+
+    #[test]
+    fn first_fetch_reports_drift_then_only_transitions() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let fetcher = ExpectationFetcher::with_clock(
+            SequenceFetcher::new(vec![
+                Ok(vec![adapter("eth0", &["198.51.100.7"], &[])]),
+                Ok(vec![adapter("eth0", &["198.51.100.7"], &[])]),
+                Ok(vec![adapter("eth0", &["203.0.113.10"], &[])]),
+            ]),
+            expected(&["203.0.113.10"]),
+            tx,
+            FixedClock,
+        );
+
+        fetcher.fetch().unwrap();
+        let started = rx.try_recv().unwrap();
+        assert_eq!(started.len(), 2);
+        assert!(started.iter().all(|c| !c.resolved));
+        assert_eq!(started[0].timestamp, UNIX_EPOCH + Duration::from_secs(100));
+
+        fetcher.fetch().unwrap();
+        assert!(rx.try_recv().is_err());
+
+        let snapshot = fetcher.fetch().unwrap();
+        assert_eq!(
+            snapshot[0].ipv4_addresses,
+            ["203.0.113.10".parse::<std::net::Ipv4Addr>().unwrap()]
+        );
+        let resolved = rx.try_recv().unwrap();
+        assert_eq!(resolved.len(), 2);
+        assert!(resolved.iter().all(|c| c.resolved));
+    }
+
+    #[test]
+    fn matching_first_fetch_sends_nothing() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let fetcher = ExpectationFetcher::new(
+            SequenceFetcher::new(vec![Ok(vec![adapter("eth0", &["203.0.113.10"], &[])])]),
+            expected(&["203.0.113.10"]),
+            tx,
+        );
+
+        fetcher.fetch().unwrap();
+
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn failed_fetch_keeps_previous_drift() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let fetcher = ExpectationFetcher::new(
+            SequenceFetcher::new(vec![
+                Ok(vec![]),
+                Err(FetchError::Platform {
+                    message: "boom".to_string(),
+                }),
+                Ok(vec![]),
+            ]),
+            expected(&["203.0.113.10"]),
+            tx,
+        );
+
+        fetcher.fetch().unwrap();
+        assert!(fetcher.fetch().is_err());
+        fetcher.fetch().unwrap();
+
+        assert_eq!(rx.try_recv().unwrap().len(), 1);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn closed_receiver_does_not_fail_fetch() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        drop(rx);
+        let fetcher = ExpectationFetcher::new(
+            SequenceFetcher::new(vec![Ok(vec![])]),
+            expected(&["203.0.113.10"]),
+            tx,
+        );
+
+        assert!(fetcher.fetch().is_ok());
+    }
+}
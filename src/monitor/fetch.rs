@@ -0,0 +1,101 @@
+//! Fetch execution for monitor streams.
+//!
+//! [`AddressFetcher::fetch`] is synchronous. Called directly inside a stream's
+//! `poll_next`, a hung OS call blocks the runtime worker it runs on. With a
+//! timeout configured, [`FetchRunner`] instead runs each fetch on tokio's
+//! blocking thread pool and gives up once the timeout elapses.
+
+use crate::network::{AdapterSnapshot, AddressFetcher, FetchError};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Sleep;
+
+type FetchResult = Result<Vec<AdapterSnapshot>, FetchError>;
+
+/// A fetch running on the blocking pool, with its deadline.
+#[derive(Debug)]
+struct InFlight {
+    handle: JoinHandle<FetchResult>,
+    deadline: Pin<Box<Sleep>>,
+}
+
+/// Runs fetches for a monitor stream, inline or on the blocking pool.
+///
+/// Without a timeout, fetches run inline exactly as before. With one, a
+/// fetch that misses its deadline is abandoned (the OS call cannot be
+/// cancelled) and reported as [`FetchError::Timeout`]. No new fetch is
+/// started until the abandoned one returns, so a permanently hung call
+/// occupies at most one blocking thread.
+#[derive(Debug)]
+pub(super) struct FetchRunner<F> {
+    fetcher: Arc<F>,
+    timeout: Option<Duration>,
+    in_flight: Option<InFlight>,
+    abandoned: Option<JoinHandle<FetchResult>>,
+}
+
+impl<F> FetchRunner<F>
+where
+    F: AddressFetcher + 'static,
+{
+    pub(super) fn new(fetcher: F, timeout: Option<Duration>) -> Self {
+        Self {
+            fetcher: Arc::new(fetcher),
+            timeout,
+            in_flight: None,
+            abandoned: None,
+        }
+    }
+
+    /// Returns true while a fetch started by [`Self::poll_fetch`] is still running.
+    pub(super) const fn is_in_flight(&self) -> bool {
+        self.in_flight.is_some()
+    }
+
+    /// Starts a fetch if none is running and polls it to completion.
+    ///
+    /// Inline fetches are always ready on the first poll.
+    pub(super) fn poll_fetch(&mut self, cx: &mut Context<'_>) -> Poll<FetchResult> {
+        let Some(timeout) = self.timeout else {
+            return Poll::Ready(self.fetcher.fetch());
+        };
+
+        if self.in_flight.is_none() {
+            if self.abandoned.as_ref().is_some_and(|h| !h.is_finished()) {
+                tracing::debug!("Previous adapter fetch still running, skipping this one");
+                return Poll::Ready(Err(FetchError::Timeout { timeout }));
+            }
+            self.abandoned = None;
+
+            let fetcher = Arc::clone(&self.fetcher);
+            self.in_flight = Some(InFlight {
+                handle: tokio::task::spawn_blocking(move || fetcher.fetch()),
+                deadline: Box::pin(tokio::time::sleep(timeout)),
+            });
+        }
+
+        let in_flight = self.in_flight.as_mut().expect("fetch in flight");
+
+        if let Poll::Ready(joined) = Pin::new(&mut in_flight.handle).poll(cx) {
+            self.in_flight = None;
+            return Poll::Ready(joined.unwrap_or_else(|e| {
+                Err(FetchError::Platform {
+                    message: format!("fetch task failed: {e}"),
+                })
+            }));
+        }
+
+        if in_flight.deadline.as_mut().poll(cx).is_ready() {
+            let InFlight { handle, .. } = self.in_flight.take().expect("fetch in flight");
+            self.abandoned = Some(handle);
+            tracing::warn!("Adapter fetch timed out after {}s", timeout.as_secs());
+            return Poll::Ready(Err(FetchError::Timeout { timeout }));
+        }
+
+        Poll::Pending
+    }
+}
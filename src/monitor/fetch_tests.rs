@@ -0,0 +1,118 @@
+//! Tests for inline and blocking-pool fetch execution.
+
+use super::fetch::FetchRunner;
+use crate::network::{AdapterKind, AdapterSnapshot, AddressFetcher, FetchError};
+use std::future::poll_fn;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Fetcher that blocks until released, counting calls.
+struct GatedFetcher {
+    release: Mutex<Receiver<()>>,
+    calls: Arc<AtomicUsize>,
+}
+
+impl GatedFetcher {
+    fn new() -> (Self, Sender<()>, Arc<AtomicUsize>) {
+        let (tx, rx) = mpsc::channel();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let fetcher = Self {
+            release: Mutex::new(rx),
+            calls: Arc::clone(&calls),
+        };
+        (fetcher, tx, calls)
+    }
+}
+
+impl AddressFetcher for GatedFetcher {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        let _ = self.release.lock().unwrap().recv();
+        Ok(vec![snapshot()])
+    }
+}
+
+/// Fetcher that returns immediately.
+struct ReadyFetcher;
+
+impl AddressFetcher for ReadyFetcher {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        Ok(vec![snapshot()])
+    }
+}
+
+fn snapshot() -> AdapterSnapshot {
+    AdapterSnapshot::new("eth0", AdapterKind::Ethernet, vec![], vec![])
+}
+
+async fn fetch<F: AddressFetcher + 'static>(
+    runner: &mut FetchRunner<F>,
+) -> Result<Vec<AdapterSnapshot>, FetchError> {
+    poll_fn(|cx| runner.poll_fetch(cx)).await
+}
+
+#[tokio::test]
+async fn without_timeout_fetches_inline() {
+    let mut runner = FetchRunner::new(ReadyFetcher, None);
+    let waker = std::task::Waker::noop();
+    let mut cx = std::task::Context::from_waker(waker);
+
+    assert!(runner.poll_fetch(&mut cx).is_ready());
+    assert!(!runner.is_in_flight());
+}
+
+#[tokio::test]
+async fn with_timeout_returns_fetch_result() {
+    let mut runner = FetchRunner::new(ReadyFetcher, Some(Duration::from_secs(5)));
+
+    let result = fetch(&mut runner).await.unwrap();
+
+    assert_eq!(result, vec![snapshot()]);
+    assert!(!runner.is_in_flight());
+}
+
+#[tokio::test]
+async fn hung_fetch_times_out() {
+    let (fetcher, release, _calls) = GatedFetcher::new();
+    let mut runner = FetchRunner::new(fetcher, Some(TIMEOUT));
+
+    let result = fetch(&mut runner).await;
+
+    assert!(matches!(result, Err(FetchError::Timeout { timeout }) if timeout == TIMEOUT));
+    assert!(!runner.is_in_flight());
+    release.send(()).unwrap();
+}
+
+#[tokio::test]
+async fn no_new_fetch_while_abandoned_one_runs() {
+    let (fetcher, release, calls) = GatedFetcher::new();
+    let mut runner = FetchRunner::new(fetcher, Some(TIMEOUT));
+
+    let _ = fetch(&mut runner).await;
+    let second = fetch(&mut runner).await;
+
+    assert!(matches!(second, Err(FetchError::Timeout { .. })));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    release.send(()).unwrap();
+}
+
+#[tokio::test]
+async fn fetches_again_once_abandoned_one_returns() {
+    let (fetcher, release, calls) = GatedFetcher::new();
+    let mut runner = FetchRunner::new(fetcher, Some(TIMEOUT));
+
+    let _ = fetch(&mut runner).await;
+    release.send(()).unwrap();
+    release.send(()).unwrap();
+    // Give the abandoned call time to observe its release and finish
+    tokio::time::sleep(TIMEOUT).await;
+
+    let result = fetch(&mut runner).await;
+
+    assert!(result.is_ok());
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
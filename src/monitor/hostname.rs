@@ -0,0 +1,130 @@
+//! Hostname change detection.
+//!
+//! Some DDNS records are named after the machine, so renaming it should move
+//! the record. [`HostnameTrackingFetcher`] reads the hostname on every fetch
+//! and reports each rename as a [`HostnameChange`], separately from the
+//! address changes the streams yield.
+
+use std::fmt;
+use std::sync::{Mutex, PoisonError};
+use std::time::SystemTime;
+
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::network::{AdapterSnapshot, AddressFetcher, FetchError};
+use crate::time::{Clock, SystemClock};
+
+/// A change of the machine hostname.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostnameChange {
+    /// Hostname before the change.
+    pub old: String,
+    /// Hostname after the change.
+    pub new: String,
+    /// The timestamp when the change was detected.
+    pub timestamp: SystemTime,
+}
+
+impl fmt::Display for HostnameChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Hostname {} → {}", self.old, self.new)
+    }
+}
+
+/// Source of the machine hostname.
+pub trait HostnameSource: Send + Sync {
+    /// Returns the current hostname, or `None` if it cannot be read.
+    fn hostname(&self) -> Option<String>;
+}
+
+/// `None` disables tracking, allowing the fetcher to be applied
+/// unconditionally.
+impl<S: HostnameSource> HostnameSource for Option<S> {
+    fn hostname(&self) -> Option<String> {
+        self.as_ref()?.hostname()
+    }
+}
+
+/// The hostname reported by the operating system.
+///
+/// On Windows this is the DNS host name, which is also the `NetBIOS` name
+/// unless the two were set apart.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemHostname;
+
+impl HostnameSource for SystemHostname {
+    fn hostname(&self) -> Option<String> {
+        gethostname::gethostname().into_string().ok()
+    }
+}
+
+/// An [`AddressFetcher`] decorator that reports hostname changes.
+///
+/// Every successful fetch reads the hostname and compares it with the one
+/// read last; a rename is sent on `events`. The first read only records a
+/// baseline, and unreadable hostnames are skipped, so a transient failure
+/// is not reported as a rename.
+#[derive(Debug)]
+pub struct HostnameTrackingFetcher<F, S = SystemHostname, C = SystemClock> {
+    inner: F,
+    source: S,
+    events: UnboundedSender<Vec<HostnameChange>>,
+    clock: C,
+    previous: Mutex<Option<String>>,
+}
+
+impl<F, S> HostnameTrackingFetcher<F, S, SystemClock> {
+    /// Wraps `inner`, sending renames read from `source` on `events`.
+    #[must_use]
+    pub const fn new(inner: F, source: S, events: UnboundedSender<Vec<HostnameChange>>) -> Self {
+        Self::with_clock(inner, source, events, SystemClock)
+    }
+}
+
+impl<F, S, C> HostnameTrackingFetcher<F, S, C> {
+    /// Wraps `inner` with a custom clock for change timestamps.
+    #[must_use]
+    pub const fn with_clock(
+        inner: F,
+        source: S,
+        events: UnboundedSender<Vec<HostnameChange>>,
+        clock: C,
+    ) -> Self {
+        Self {
+            inner,
+            source,
+            events,
+            clock,
+            previous: Mutex::new(None),
+        }
+    }
+}
+
+impl<F: AddressFetcher, S: HostnameSource, C: Clock> AddressFetcher
+    for HostnameTrackingFetcher<F, S, C>
+{
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        let current = self.inner.fetch()?;
+        let Some(hostname) = self.source.hostname() else {
+            return Ok(current);
+        };
+
+        let previous = self
+            .previous
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .replace(hostname.clone());
+
+        if let Some(old) = previous.filter(|old| *old != hostname) {
+            let change = HostnameChange {
+                old,
+                new: hostname,
+                timestamp: self.clock.now(),
+            };
+            // A closed receiver means nobody listens any more; drop the change
+            let _ = self.events.send(vec![change]);
+        }
+
+        Ok(current)
+    }
+}
@@ -0,0 +1,170 @@
+//! Tests for hostname change detection.
+
+use super::hostname::{HostnameChange, HostnameSource, HostnameTrackingFetcher, SystemHostname};
+use crate::network::{AdapterKind, AdapterSnapshot, AddressFetcher, FetchError};
+use crate::time::Clock;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+
+struct FixedClock;
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(100)
+    }
+}
+
+/// Fetcher returning one adapter, failing the fetches queued as `true`.
+#[derive(Default)]
+struct StaticFetcher {
+    failures: Mutex<VecDeque<bool>>,
+}
+
+impl StaticFetcher {
+    fn failing(failures: impl IntoIterator<Item = bool>) -> Self {
+        Self {
+            failures: Mutex::new(failures.into_iter().collect()),
+        }
+    }
+}
+
+impl AddressFetcher for StaticFetcher {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        if self.failures.lock().unwrap().pop_front() == Some(true) {
+            return Err(FetchError::Platform {
+                message: "boom".to_string(),
+            });
+        }
+        Ok(vec![AdapterSnapshot::new(
+            "eth0",
+            AdapterKind::Ethernet,
+            vec!["192.0.2.1".parse().unwrap()],
+            vec![],
+        )])
+    }
+}
+
+/// Source returning queued hostnames in order (`None` for unreadable).
+struct SequenceSource(Mutex<VecDeque<Option<&'static str>>>);
+
+impl SequenceSource {
+    fn new(names: impl IntoIterator<Item = Option<&'static str>>) -> Self {
+        Self(Mutex::new(names.into_iter().collect()))
+    }
+}
+
+impl HostnameSource for SequenceSource {
+    fn hostname(&self) -> Option<String> {
+        self.0
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap()
+            .map(str::to_string)
+    }
+}
+
+fn rename(old: &str, new: &str) -> HostnameChange {
+    HostnameChange {
+        old: old.to_string(),
+        new: new.to_string(),
+        timestamp: UNIX_EPOCH + Duration::from_secs(100),
+    }
+}
+
+#[test]
+fn first_fetch_records_baseline_then_reports_renames() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let fetcher = HostnameTrackingFetcher::with_clock(
+        StaticFetcher::default(),
+        SequenceSource::new([Some("desk-01"), Some("desk-01"), Some("desk-02")]),
+        tx,
+        FixedClock,
+    );
+
+    let snapshot = fetcher.fetch().unwrap();
+    assert_eq!(snapshot[0].name, "eth0");
+    fetcher.fetch().unwrap();
+    assert!(rx.try_recv().is_err());
+
+    fetcher.fetch().unwrap();
+    assert_eq!(rx.try_recv().unwrap(), [rename("desk-01", "desk-02")]);
+}
+
+#[test]
+fn unreadable_hostname_is_not_a_rename() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let fetcher = HostnameTrackingFetcher::new(
+        StaticFetcher::default(),
+        SequenceSource::new([Some("desk-01"), None, Some("desk-01")]),
+        tx,
+    );
+
+    for _ in 0..3 {
+        fetcher.fetch().unwrap();
+    }
+
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn failed_fetch_skips_hostname() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    // An empty queue panics if the failed fetch reads the hostname
+    let fetcher = HostnameTrackingFetcher::new(
+        StaticFetcher::failing([false, true, false]),
+        SequenceSource::new([Some("desk-01"), Some("desk-02")]),
+        tx,
+    );
+
+    fetcher.fetch().unwrap();
+    assert!(fetcher.fetch().is_err());
+    fetcher.fetch().unwrap();
+
+    assert_eq!(rx.try_recv().unwrap().len(), 1);
+}
+
+#[test]
+fn disabled_source_never_reports() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let fetcher =
+        HostnameTrackingFetcher::new(StaticFetcher::default(), None::<SystemHostname>, tx);
+
+    fetcher.fetch().unwrap();
+    fetcher.fetch().unwrap();
+
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn closed_receiver_does_not_fail_fetch() {
+    let (tx, rx) = mpsc::unbounded_channel();
+    drop(rx);
+    let fetcher = HostnameTrackingFetcher::new(
+        StaticFetcher::default(),
+        SequenceSource::new([Some("desk-01"), Some("desk-02")]),
+        tx,
+    );
+
+    fetcher.fetch().unwrap();
+    assert!(fetcher.fetch().is_ok());
+}
+
+#[test]
+fn system_hostname_is_readable() {
+    assert!(
+        SystemHostname
+            .hostname()
+            .is_some_and(|name| !name.is_empty())
+    );
+}
+
+#[test]
+fn display_shows_old_and_new() {
+    assert_eq!(
+        rename("desk-01", "desk-02").to_string(),
+        "Hostname desk-01 → desk-02"
+    );
+}
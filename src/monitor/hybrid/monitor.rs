@@ -3,8 +3,8 @@
 //! This module provides [`HybridMonitor`], the builder/configuration struct
 //! for creating hybrid IP address monitors that combine API events with polling.
 
-use super::super::DebouncePolicy;
 use super::super::listener::ApiListener;
+use super::super::{AdaptivePolicy, DebouncePolicy};
 use super::stream::HybridStream;
 use crate::network::AddressFetcher;
 use crate::time::{Clock, SystemClock};
@@ -53,6 +53,9 @@ pub struct HybridMonitor<F, L, C = SystemClock> {
     clock: C,
     poll_interval: Duration,
     debounce: Option<DebouncePolicy>,
+    quiet_period: Option<Duration>,
+    adaptive: Option<AdaptivePolicy>,
+    fetch_timeout: Option<Duration>,
 }
 
 impl<F, L> HybridMonitor<F, L, SystemClock>
@@ -102,6 +105,9 @@ where
             clock,
             poll_interval,
             debounce: None,
+            quiet_period: None,
+            adaptive: None,
+            fetch_timeout: None,
         }
     }
 
@@ -123,6 +129,40 @@ where
         self
     }
 
+    /// Coalesces bursts of API notifications into one fetch.
+    ///
+    /// Each notification restarts a `quiet_period` timer, and the fetch
+    /// runs once it expires, so a burst costs a single fetch and diff
+    /// instead of one per notification. Without this, every notification
+    /// triggers a fetch at once.
+    #[must_use]
+    pub const fn with_quiet_period(mut self, quiet_period: Duration) -> Self {
+        self.quiet_period = Some(quiet_period);
+        self
+    }
+
+    /// Adapts the polling interval to how often addresses change.
+    ///
+    /// The fallback poll stretches towards the policy's maximum while
+    /// nothing changes, and drops to its minimum after an API event or a
+    /// change, catching follow-up changes the API might miss.
+    #[must_use]
+    pub const fn with_adaptive(mut self, policy: AdaptivePolicy) -> Self {
+        self.adaptive = Some(policy);
+        self
+    }
+
+    /// Runs each fetch on the blocking thread pool, abandoning it after `timeout`.
+    ///
+    /// Without this, fetches run inline on the runtime thread polling the
+    /// stream, which a hung OS call would block indefinitely. Timed-out
+    /// fetches are treated like fetch errors and retried on the next trigger.
+    #[must_use]
+    pub const fn with_fetch_timeout(mut self, timeout: Duration) -> Self {
+        self.fetch_timeout = Some(timeout);
+        self
+    }
+
     /// Returns the configured polling interval.
     #[must_use]
     pub const fn poll_interval(&self) -> Duration {
@@ -135,6 +175,24 @@ where
         self.debounce.as_ref()
     }
 
+    /// Returns the quiet period ending a burst of notifications, if coalescing.
+    #[must_use]
+    pub const fn quiet_period(&self) -> Option<Duration> {
+        self.quiet_period
+    }
+
+    /// Returns the adaptive interval policy, if any.
+    #[must_use]
+    pub const fn adaptive(&self) -> Option<&AdaptivePolicy> {
+        self.adaptive.as_ref()
+    }
+
+    /// Returns the configured fetch timeout, if fetches run on the blocking pool.
+    #[must_use]
+    pub const fn fetch_timeout(&self) -> Option<Duration> {
+        self.fetch_timeout
+    }
+
     /// Converts this monitor into a stream of IP changes.
     ///
     /// The returned stream will:
@@ -148,14 +206,21 @@ where
     /// The stream never terminates on its own; use `take_until` with
     /// a shutdown signal to stop it gracefully.
     #[must_use]
-    pub fn into_stream(self) -> HybridStream<F, L::Stream, C> {
+    pub fn into_stream(self) -> HybridStream<F, L::Stream, C>
+    where
+        F: 'static,
+    {
         let api_stream = self.api_listener.into_stream();
-        HybridStream::new(
+        let mut stream = HybridStream::new(
             self.fetcher,
             api_stream,
             self.clock,
             self.poll_interval,
             self.debounce,
-        )
+            self.quiet_period,
+            self.fetch_timeout,
+        );
+        stream.set_adaptive(self.adaptive);
+        stream
     }
 }
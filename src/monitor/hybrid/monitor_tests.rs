@@ -2,7 +2,7 @@
 
 use super::test_fixtures::{MockApiListener, MockClock, MockFetcher};
 use super::*;
-use crate::monitor::DebouncePolicy;
+use crate::monitor::{AdaptivePolicy, DebouncePolicy};
 use std::time::Duration;
 
 #[test]
@@ -36,6 +36,31 @@ fn with_debounce_sets_policy() {
     assert_eq!(monitor.debounce(), Some(&policy));
 }
 
+#[test]
+fn with_quiet_period_enables_coalescing() {
+    let fetcher = MockFetcher::returning_snapshots(vec![]);
+    let listener = MockApiListener::pending();
+    let monitor = HybridMonitor::new(fetcher, listener, Duration::from_secs(60));
+    assert_eq!(monitor.quiet_period(), None);
+
+    let monitor = monitor.with_quiet_period(Duration::from_millis(250));
+
+    assert_eq!(monitor.quiet_period(), Some(Duration::from_millis(250)));
+}
+
+#[test]
+fn with_adaptive_sets_policy() {
+    let fetcher = MockFetcher::returning_snapshots(vec![]);
+    let listener = MockApiListener::pending();
+    let monitor = HybridMonitor::new(fetcher, listener, Duration::from_secs(60));
+    assert_eq!(monitor.adaptive(), None);
+
+    let policy = AdaptivePolicy::new(Duration::from_secs(60), Duration::from_secs(600));
+    let monitor = monitor.with_adaptive(policy);
+
+    assert_eq!(monitor.adaptive(), Some(&policy));
+}
+
 #[test]
 fn poll_interval_accessor() {
     let fetcher = MockFetcher::returning_snapshots(vec![]);
@@ -45,6 +70,17 @@ fn poll_interval_accessor() {
     assert_eq!(monitor.poll_interval(), Duration::from_secs(120));
 }
 
+#[test]
+fn with_fetch_timeout_sets_timeout() {
+    let fetcher = MockFetcher::returning_snapshots(vec![]);
+    let listener = MockApiListener::pending();
+    let monitor = HybridMonitor::new(fetcher, listener, Duration::from_secs(60));
+    assert_eq!(monitor.fetch_timeout(), None);
+
+    let monitor = monitor.with_fetch_timeout(Duration::from_secs(10));
+    assert_eq!(monitor.fetch_timeout(), Some(Duration::from_secs(10)));
+}
+
 #[tokio::test]
 async fn into_stream_consumes_listener() {
     let fetcher = MockFetcher::returning_snapshots(vec![]);
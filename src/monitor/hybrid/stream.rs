@@ -3,15 +3,17 @@
 //! This module provides [`HybridStream`], a stream that combines API event
 //! notifications with periodic polling for IP address change detection.
 
-use crate::monitor::DebouncePolicy;
 use crate::monitor::change::{IpChange, diff};
 use crate::monitor::error::ApiError;
-use crate::network::{AdapterSnapshot, AddressFetcher, FetchError};
-use crate::time::Clock;
+use crate::monitor::fetch::FetchRunner;
+use crate::monitor::{AdaptivePolicy, DebouncePolicy};
+use crate::network::{AdapterSnapshot, AddressFetcher};
+use crate::time::{Clock, TimeJumpDetector};
+use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use std::time::Duration;
-use tokio::time::{Interval, interval};
+use tokio::time::{Instant, Interval, Sleep, interval, interval_at, sleep};
 use tokio_stream::Stream;
 
 /// Internal state of the hybrid stream.
@@ -27,9 +29,9 @@ enum StreamState<S> {
 }
 
 /// What triggered the current poll iteration.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 enum PollTrigger {
-    /// API notification received
+    /// API notification received, or a burst of them ended
     ApiEvent,
     /// API stream ended or errored - degrade
     ApiDegraded,
@@ -41,7 +43,7 @@ enum PollTrigger {
 
 impl PollTrigger {
     /// Returns a human-readable label for logging.
-    const fn label(&self) -> &'static str {
+    const fn label(self) -> &'static str {
         match self {
             Self::ApiEvent => "API event",
             Self::ApiDegraded => "API degradation",
@@ -62,12 +64,30 @@ impl PollTrigger {
 ///
 /// Degradation from hybrid to polling-only is automatic and permanent
 /// for the lifetime of this stream.
+///
+/// With a quiet period, a burst of API notifications (e.g. during a DHCP
+/// storm) triggers a single fetch once no notification has arrived for
+/// that long, instead of one fetch per notification. Polls at the interval
+/// go on during a burst.
+///
+/// With an [`AdaptivePolicy`], the polling interval stretches while nothing
+/// happens and drops back to the minimum after an API event or a change.
 #[derive(Debug)]
 pub struct HybridStream<F, S, C> {
-    fetcher: F,
+    fetcher: FetchRunner<F>,
     clock: C,
     interval: Interval,
+    /// Current period of `interval`.
+    period: Duration,
+    /// Stretches `period` while stable, if adaptive.
+    adaptive: Option<AdaptivePolicy>,
     debounce: Option<DebouncePolicy>,
+    /// Quiet period ending a burst of API notifications, if coalescing.
+    quiet_period: Option<Duration>,
+    /// Fires once the current burst has been quiet for `quiet_period`.
+    quiet_timer: Option<Pin<Box<Sleep>>>,
+    /// Notifications in the current burst.
+    burst_events: usize,
     state: StreamState<S>,
     /// Previous snapshot for comparison.
     prev_snapshot: Option<Vec<AdapterSnapshot>>,
@@ -75,11 +95,17 @@ pub struct HybridStream<F, S, C> {
     debounce_start: Option<tokio::time::Instant>,
     /// Snapshot taken at debounce start for final comparison.
     debounce_baseline: Option<Vec<AdapterSnapshot>>,
+    /// Trigger of the fetch still running on the blocking pool, if any.
+    fetch_trigger: Option<PollTrigger>,
+    /// Snapshot captured before the in-flight fetch (debounce baseline candidate).
+    pre_fetch_snapshot: Option<Vec<AdapterSnapshot>>,
+    /// Detects wall-clock steps between fetches.
+    time_jumps: TimeJumpDetector,
 }
 
 impl<F, S, C> HybridStream<F, S, C>
 where
-    F: AddressFetcher,
+    F: AddressFetcher + 'static,
     S: Stream<Item = Result<(), ApiError>> + Unpin,
     C: Clock,
 {
@@ -89,16 +115,26 @@ where
         clock: C,
         poll_interval: Duration,
         debounce: Option<DebouncePolicy>,
+        quiet_period: Option<Duration>,
+        fetch_timeout: Option<Duration>,
     ) -> Self {
         Self {
-            fetcher,
+            fetcher: FetchRunner::new(fetcher, fetch_timeout),
             clock,
             interval: interval(poll_interval),
+            period: poll_interval,
+            adaptive: None,
             debounce,
+            quiet_period,
+            quiet_timer: None,
+            burst_events: 0,
             state: StreamState::Hybrid { api_stream },
             prev_snapshot: None,
             debounce_start: None,
             debounce_baseline: None,
+            fetch_trigger: None,
+            pre_fetch_snapshot: None,
+            time_jumps: TimeJumpDetector::default(),
         }
     }
 
@@ -116,9 +152,49 @@ where
         self.prev_snapshot.as_deref()
     }
 
-    /// Performs a single fetch and returns changes if any.
-    fn fetch_changes(&mut self) -> Result<Vec<IpChange>, FetchError> {
-        let current = self.fetcher.fetch()?;
+    /// Replaces the poll interval, e.g. on a configuration reload.
+    ///
+    /// The next poll is due one new interval from now; the snapshot and an
+    /// open debounce window are kept.
+    pub fn set_poll_interval(&mut self, poll_interval: Duration) {
+        self.interval = interval_at(Instant::now() + poll_interval, poll_interval);
+        self.period = poll_interval;
+    }
+
+    /// Replaces the adaptive interval policy; `None` keeps the current
+    /// interval from now on.
+    pub const fn set_adaptive(&mut self, adaptive: Option<AdaptivePolicy>) {
+        self.adaptive = adaptive;
+    }
+
+    /// Returns the current polling interval, which changes over time when
+    /// adaptive.
+    #[must_use]
+    pub const fn poll_interval(&self) -> Duration {
+        self.period
+    }
+
+    /// Moves the polling interval along the adaptive policy after a fetch.
+    ///
+    /// `active` is set after an API event or a change, which drop the
+    /// interval to the minimum; an open debounce window keeps it there.
+    /// Otherwise it is stretched.
+    fn adapt_interval(&mut self, active: bool) {
+        let Some(adaptive) = self.adaptive else {
+            return;
+        };
+        let active = active || self.debounce_start.is_some();
+
+        let period = adaptive.next(self.period, active);
+        if period != self.period {
+            tracing::debug!("Poll interval adjusted to {}s", period.as_secs_f64());
+            self.set_poll_interval(period);
+        }
+    }
+
+    /// Records a fetched snapshot and returns changes since the previous one.
+    fn apply_snapshot(&mut self, current: Vec<AdapterSnapshot>) -> Vec<IpChange> {
+        self.check_time_jump();
         let timestamp = self.clock.now();
 
         let changes = self
@@ -127,7 +203,23 @@ where
             .map_or_else(Vec::new, |prev| diff(prev, &current, timestamp));
 
         self.prev_snapshot = Some(current);
-        Ok(changes)
+        changes
+    }
+
+    /// Logs a wall-clock jump and restarts any open debounce window.
+    ///
+    /// Timestamps around a jump are misleading, so the window is measured
+    /// afresh from the first fetch after it.
+    fn check_time_jump(&mut self) {
+        let now = tokio::time::Instant::now();
+        let Some(jump) = self.time_jumps.observe(&self.clock, now) else {
+            return;
+        };
+
+        tracing::warn!("{jump}; change timestamps around this point may be misleading");
+        if self.debounce_start.is_some() {
+            self.debounce_start = Some(now);
+        }
     }
 
     /// Handles debounce logic, returning changes to emit (if any).
@@ -208,11 +300,57 @@ where
     fn degrade_to_polling(&mut self) {
         self.state = StreamState::PollingOnly;
     }
+
+    /// Polls the API stream and interval for the next fetch trigger.
+    fn next_trigger(&mut self, cx: &mut Context<'_>) -> PollTrigger {
+        // Check API stream first (higher priority for responsiveness)
+        if let StreamState::Hybrid { api_stream } = &mut self.state {
+            loop {
+                match Pin::new(&mut *api_stream).poll_next(cx) {
+                    Poll::Ready(Some(Ok(()))) => {
+                        let Some(quiet_period) = self.quiet_period else {
+                            return PollTrigger::ApiEvent;
+                        };
+                        // Wait for the burst to end, draining what is ready
+                        self.burst_events += 1;
+                        let deadline = Instant::now() + quiet_period;
+                        match self.quiet_timer {
+                            Some(ref mut timer) => timer.as_mut().reset(deadline),
+                            None => self.quiet_timer = Some(Box::pin(sleep(quiet_period))),
+                        }
+                    }
+                    Poll::Ready(Some(Err(_)) | None) => {
+                        // API failed or ended - will degrade
+                        return PollTrigger::ApiDegraded;
+                    }
+                    Poll::Pending => break,
+                }
+            }
+        }
+
+        // A burst still pending when the API degraded is fetched as well
+        if let Some(ref mut timer) = self.quiet_timer {
+            if timer.as_mut().poll(cx).is_ready() {
+                self.quiet_timer = None;
+                tracing::trace!(
+                    "Coalesced {} API notification(s) into one check",
+                    std::mem::take(&mut self.burst_events)
+                );
+                return PollTrigger::ApiEvent;
+            }
+        }
+
+        if Pin::new(&mut self.interval).poll_tick(cx).is_ready() {
+            PollTrigger::Interval
+        } else {
+            PollTrigger::Pending
+        }
+    }
 }
 
 impl<F, S, C> Stream for HybridStream<F, S, C>
 where
-    F: AddressFetcher + Unpin,
+    F: AddressFetcher + Unpin + 'static,
     S: Stream<Item = Result<(), ApiError>> + Unpin,
     C: Clock + Unpin,
 {
@@ -220,34 +358,11 @@ where
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         loop {
-            let trigger = match &mut self.state {
-                StreamState::Hybrid { api_stream } => {
-                    // Check API stream first (higher priority for responsiveness)
-                    match Pin::new(api_stream).poll_next(cx) {
-                        Poll::Ready(Some(Ok(()))) => PollTrigger::ApiEvent,
-                        Poll::Ready(Some(Err(_)) | None) => {
-                            // API failed or ended - will degrade
-                            PollTrigger::ApiDegraded
-                        }
-                        Poll::Pending => {
-                            // API not ready - check interval
-                            if Pin::new(&mut self.interval).poll_tick(cx).is_ready() {
-                                PollTrigger::Interval
-                            } else {
-                                PollTrigger::Pending
-                            }
-                        }
-                    }
-                }
-                StreamState::PollingOnly => {
-                    // Only check interval in polling-only mode
-                    if Pin::new(&mut self.interval).poll_tick(cx).is_ready() {
-                        PollTrigger::Interval
-                    } else {
-                        PollTrigger::Pending
-                    }
-                }
-            };
+            // A fetch running on the blocking pool resumes with its original trigger
+            let trigger = self
+                .fetch_trigger
+                .take()
+                .unwrap_or_else(|| self.next_trigger(cx));
 
             match trigger {
                 PollTrigger::Pending => return Poll::Pending,
@@ -257,30 +372,40 @@ where
                     // Continue loop to check interval
                 }
                 PollTrigger::ApiEvent | PollTrigger::Interval => {
-                    tracing::debug!("Check triggered by {}", trigger.label());
-
-                    // Capture snapshot BEFORE fetch (needed for debounce baseline)
-                    // Only clone when we might start debouncing
-                    let pre_fetch_snapshot =
-                        if self.debounce.is_some() && self.debounce_start.is_none() {
-                            self.prev_snapshot.clone()
-                        } else {
-                            None
-                        };
+                    if !self.fetcher.is_in_flight() {
+                        tracing::debug!("Check triggered by {}", trigger.label());
+
+                        // Capture snapshot BEFORE fetch (needed for debounce baseline)
+                        // Only clone when we might start debouncing
+                        self.pre_fetch_snapshot =
+                            if self.debounce.is_some() && self.debounce_start.is_none() {
+                                self.prev_snapshot.clone()
+                            } else {
+                                None
+                            };
+                    }
 
                     // Fetch and process changes
-                    let Ok(changes) = self.fetch_changes() else {
+                    let Poll::Ready(result) = self.fetcher.poll_fetch(cx) else {
+                        self.fetch_trigger = Some(trigger);
+                        return Poll::Pending;
+                    };
+                    let pre_fetch_snapshot = self.pre_fetch_snapshot.take();
+                    let Ok(current) = result else {
                         // Fetch error - continue waiting for next trigger
                         continue;
                     };
+                    let changes = self.apply_snapshot(current);
+                    let has_changes = !changes.is_empty();
 
                     // API events start debounce even without detected changes,
                     // because Windows may notify before IP is visible
                     let triggered_by_api = matches!(trigger, PollTrigger::ApiEvent);
 
-                    if let Some(result) =
-                        self.process_with_debounce(changes, pre_fetch_snapshot, triggered_by_api)
-                    {
+                    let result =
+                        self.process_with_debounce(changes, pre_fetch_snapshot, triggered_by_api);
+                    self.adapt_interval(triggered_by_api || has_changes);
+                    if let Some(result) = result {
                         tracing::debug!(
                             "Emitting {} change(s) triggered by {}",
                             result.len(),
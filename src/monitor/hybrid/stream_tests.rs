@@ -2,7 +2,7 @@
 
 use super::test_fixtures::{MockApiListener, MockClock, MockFetcher, make_snapshot};
 use super::*;
-use crate::monitor::{DebouncePolicy, IpChange};
+use crate::monitor::{AdaptivePolicy, DebouncePolicy, IpChange};
 use crate::network::FetchError;
 use std::time::{Duration, SystemTime};
 use tokio_stream::StreamExt;
@@ -386,3 +386,160 @@ async fn api_event_without_visible_changes_starts_debounce() {
     assert!(batch[0].is_added());
     assert_eq!(batch[0].address.to_string(), "192.168.1.2");
 }
+
+#[tokio::test]
+async fn api_event_triggers_fetch_with_fetch_timeout() {
+    let fetcher = MockFetcher::returning_snapshots(vec![
+        vec![make_snapshot("eth0", vec!["192.168.1.1"], vec![])],
+        vec![make_snapshot("eth0", vec!["192.168.1.2"], vec![])],
+    ]);
+    let clock = MockClock::new(1000);
+    let listener = MockApiListener::new(vec![Some(Ok(())), Some(Ok(()))]);
+
+    let monitor = HybridMonitor::with_clock(fetcher, listener, clock, Duration::from_secs(60))
+        .with_fetch_timeout(Duration::from_secs(5));
+    let stream = monitor.into_stream();
+
+    let changes: Vec<_> = stream.take(1).collect().await;
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].len(), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn set_poll_interval_reschedules_polling() {
+    let before = make_snapshot("eth0", vec!["192.168.1.1"], vec![]);
+    let after = make_snapshot("eth0", vec!["192.168.1.2"], vec![]);
+    let fetcher = MockFetcher::returning_snapshots(vec![vec![before], vec![after]]);
+    let listener = MockApiListener::pending();
+
+    let monitor = HybridMonitor::with_clock(
+        fetcher,
+        listener,
+        MockClock::new(1000),
+        Duration::from_secs(3600),
+    );
+    let mut stream = monitor.into_stream();
+    let start = tokio::time::Instant::now();
+    assert!(
+        tokio::time::timeout(Duration::from_millis(1), stream.next())
+            .await
+            .is_err()
+    );
+
+    stream.set_poll_interval(Duration::from_millis(50));
+    let changes = stream.next().await.unwrap();
+
+    assert_eq!(changes.len(), 2);
+    assert_eq!(start.elapsed(), Duration::from_millis(51));
+}
+
+#[tokio::test(start_paused = true)]
+async fn quiet_period_coalesces_a_burst_into_one_fetch() {
+    let snapshot1 = make_snapshot("eth0", vec!["192.168.1.1"], vec![]);
+    let snapshot2 = make_snapshot("eth0", vec!["192.168.1.2"], vec![]);
+    let snapshot3 = make_snapshot("eth0", vec!["192.168.1.3"], vec![]);
+
+    // One fetch per notification would see .2 at once and .3 right after
+    let fetcher =
+        MockFetcher::returning_snapshots(vec![vec![snapshot1], vec![snapshot2], vec![snapshot3]]);
+    let listener = MockApiListener::new((0..5).map(|_| Some(Ok(()))).collect());
+
+    let monitor = HybridMonitor::with_clock(
+        fetcher,
+        listener,
+        MockClock::new(1000),
+        Duration::from_secs(3600),
+    )
+    .with_quiet_period(Duration::from_millis(100));
+    let mut stream = monitor.into_stream();
+    let start = tokio::time::Instant::now();
+
+    let changes = stream.next().await.unwrap();
+
+    assert_eq!(start.elapsed(), Duration::from_millis(100));
+    assert!(
+        changes
+            .iter()
+            .any(|c| c.address.to_string() == "192.168.1.2")
+    );
+    assert!(
+        tokio::time::timeout(Duration::from_secs(1), stream.next())
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn quiet_period_burst_survives_degradation() {
+    let snapshot1 = make_snapshot("eth0", vec!["192.168.1.1"], vec![]);
+    let snapshot2 = make_snapshot("eth0", vec!["192.168.1.2"], vec![]);
+
+    let fetcher = MockFetcher::returning_snapshots(vec![vec![snapshot1], vec![snapshot2]]);
+    let listener = MockApiListener::new(vec![Some(Ok(())), None]);
+
+    let monitor = HybridMonitor::with_clock(
+        fetcher,
+        listener,
+        MockClock::new(1000),
+        Duration::from_secs(3600),
+    )
+    .with_quiet_period(Duration::from_millis(100));
+    let mut stream = monitor.into_stream();
+    let start = tokio::time::Instant::now();
+
+    let changes = stream.next().await.unwrap();
+
+    assert!(stream.is_polling_only());
+    assert_eq!(start.elapsed(), Duration::from_millis(100));
+    assert_eq!(changes.len(), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn adaptive_interval_stretches_while_api_is_quiet() {
+    let fetcher = MockFetcher::returning_snapshots(vec![]);
+    let listener = MockApiListener::pending();
+
+    let monitor = HybridMonitor::with_clock(
+        fetcher,
+        listener,
+        MockClock::new(1000),
+        Duration::from_millis(10),
+    )
+    .with_adaptive(AdaptivePolicy::new(
+        Duration::from_millis(10),
+        Duration::from_millis(40),
+    ));
+    let mut stream = monitor.into_stream();
+
+    assert!(
+        tokio::time::timeout(Duration::from_secs(1), stream.next())
+            .await
+            .is_err()
+    );
+    assert_eq!(stream.poll_interval(), Duration::from_millis(40));
+}
+
+#[tokio::test(start_paused = true)]
+async fn api_event_drops_adaptive_interval_to_min() {
+    let fetcher = MockFetcher::returning_snapshots(vec![]);
+    let listener = MockApiListener::new(vec![Some(Ok(()))]);
+
+    let monitor = HybridMonitor::with_clock(
+        fetcher,
+        listener,
+        MockClock::new(1000),
+        Duration::from_millis(400),
+    )
+    .with_adaptive(AdaptivePolicy::new(
+        Duration::from_millis(100),
+        Duration::from_millis(400),
+    ));
+    let mut stream = monitor.into_stream();
+
+    assert!(
+        tokio::time::timeout(Duration::from_millis(1), stream.next())
+            .await
+            .is_err()
+    );
+    assert_eq!(stream.poll_interval(), Duration::from_millis(100));
+}
@@ -0,0 +1,179 @@
+//! Adapter link state detection.
+//!
+//! A cable pulled or a Wi-Fi connection dropped takes an adapter down long
+//! before its addresses expire. [`LinkTrackingFetcher`] compares the
+//! operational status of consecutive fetches (see
+//! [`AdapterMetadata::oper_status`](crate::network::AdapterMetadata)) and
+//! reports each adapter going up or down as a [`LinkChange`], separately
+//! from the address changes the streams yield.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Mutex, PoisonError};
+use std::time::SystemTime;
+
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::network::{AdapterSnapshot, AddressFetcher, FetchError, OperStatus};
+use crate::time::{Clock, SystemClock};
+
+/// Whether an adapter's link is up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LinkState {
+    /// The adapter went up: it can pass packets again.
+    Up,
+    /// The adapter went down, in any of the states other than up.
+    Down,
+}
+
+impl LinkState {
+    /// Returns the state as rendered in payloads: `up` or `down`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Up => "up",
+            Self::Down => "down",
+        }
+    }
+
+    /// Returns the link state of an operational status.
+    #[must_use]
+    pub const fn of(status: OperStatus) -> Self {
+        match status {
+            OperStatus::Up => Self::Up,
+            _ => Self::Down,
+        }
+    }
+}
+
+impl fmt::Display for LinkState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An adapter going up or down.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkChange {
+    /// The name of the adapter whose link changed.
+    pub adapter: String,
+    /// The link state after the change.
+    pub state: LinkState,
+    /// The operational status after the change, e.g. `lower_layer_down`.
+    pub status: OperStatus,
+    /// The timestamp when the change was detected.
+    pub timestamp: SystemTime,
+}
+
+impl fmt::Display for LinkChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: link {}", self.adapter, self.state)?;
+        if self.status.as_str() != self.state.as_str() {
+            write!(f, " ({})", self.status)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares the link states of adapters present in both snapshots.
+///
+/// Adapters without an operational status, or that appear or disappear
+/// between the snapshots, are ignored: address changes already cover the
+/// latter. Moving between two down states (e.g. `down` to `not_present`) is
+/// no change.
+#[must_use]
+pub fn diff_links(
+    old: &[AdapterSnapshot],
+    new: &[AdapterSnapshot],
+    timestamp: SystemTime,
+) -> Vec<LinkChange> {
+    let previous: HashMap<&str, OperStatus> = old
+        .iter()
+        .filter_map(|a| Some((a.name.as_str(), oper_status(a)?)))
+        .collect();
+
+    new.iter()
+        .filter_map(|adapter| {
+            let status = oper_status(adapter)?;
+            let before = *previous.get(adapter.name.as_str())?;
+            let state = LinkState::of(status);
+            (LinkState::of(before) != state).then(|| LinkChange {
+                adapter: adapter.name.clone(),
+                state,
+                status,
+                timestamp,
+            })
+        })
+        .collect()
+}
+
+fn oper_status(adapter: &AdapterSnapshot) -> Option<OperStatus> {
+    adapter.metadata.as_ref()?.oper_status
+}
+
+/// An [`AddressFetcher`] decorator that reports adapters going up or down.
+///
+/// Every successful fetch is compared with the previous one; changes are sent
+/// as one batch on `events`. The first fetch only records a baseline. Unless
+/// enabled, or if the inner fetcher reports no operational status, nothing is
+/// ever sent.
+#[derive(Debug)]
+pub struct LinkTrackingFetcher<F, C = SystemClock> {
+    inner: F,
+    enabled: bool,
+    events: UnboundedSender<Vec<LinkChange>>,
+    clock: C,
+    previous: Mutex<Option<Vec<AdapterSnapshot>>>,
+}
+
+impl<F> LinkTrackingFetcher<F, SystemClock> {
+    /// Wraps `inner`, sending detected changes on `events` if `enabled`.
+    #[must_use]
+    pub const fn new(inner: F, enabled: bool, events: UnboundedSender<Vec<LinkChange>>) -> Self {
+        Self::with_clock(inner, enabled, events, SystemClock)
+    }
+}
+
+impl<F, C> LinkTrackingFetcher<F, C> {
+    /// Wraps `inner` with a custom clock for change timestamps.
+    #[must_use]
+    pub const fn with_clock(
+        inner: F,
+        enabled: bool,
+        events: UnboundedSender<Vec<LinkChange>>,
+        clock: C,
+    ) -> Self {
+        Self {
+            inner,
+            enabled,
+            events,
+            clock,
+            previous: Mutex::new(None),
+        }
+    }
+}
+
+impl<F: AddressFetcher, C: Clock> AddressFetcher for LinkTrackingFetcher<F, C> {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        let current = self.inner.fetch()?;
+        if !self.enabled {
+            return Ok(current);
+        }
+
+        let previous = self
+            .previous
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .replace(current.clone());
+
+        if let Some(prev) = previous {
+            let changes = diff_links(&prev, &current, self.clock.now());
+            if !changes.is_empty() {
+                // A closed receiver means nobody listens any more; drop the batch
+                let _ = self.events.send(changes);
+            }
+        }
+
+        Ok(current)
+    }
+}
@@ -0,0 +1,218 @@
+//! Tests for adapter link state detection.
+
+use super::link::{LinkChange, LinkState, LinkTrackingFetcher, diff_links};
+use crate::network::{
+    AdapterKind, AdapterMetadata, AdapterSnapshot, AddressFetcher, FetchError, OperStatus,
+};
+use crate::time::Clock;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+
+struct FixedClock;
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(100)
+    }
+}
+
+/// Fetcher returning queued results in order.
+struct SequenceFetcher {
+    results: Mutex<VecDeque<Result<Vec<AdapterSnapshot>, FetchError>>>,
+}
+
+impl SequenceFetcher {
+    fn new(results: Vec<Result<Vec<AdapterSnapshot>, FetchError>>) -> Self {
+        Self {
+            results: Mutex::new(results.into()),
+        }
+    }
+}
+
+impl AddressFetcher for SequenceFetcher {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        self.results.lock().unwrap().pop_front().unwrap()
+    }
+}
+
+fn adapter(name: &str, status: Option<OperStatus>) -> AdapterSnapshot {
+    AdapterSnapshot::new(
+        name,
+        AdapterKind::Ethernet,
+        vec!["192.0.2.1".parse().unwrap()],
+        vec![],
+    )
+    .with_metadata(AdapterMetadata {
+        oper_status: status,
+        ..AdapterMetadata::default()
+    })
+}
+
+fn at(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+mod diff {
+    use super::*;
+
+    #[test]
+    fn unchanged_status_yields_nothing() {
+        let old = [adapter("eth0", Some(OperStatus::Up))];
+
+        assert!(diff_links(&old, &old, at(0)).is_empty());
+    }
+
+    #[test]
+    fn going_down_is_reported() {
+        let old = [adapter("eth0", Some(OperStatus::Up))];
+        let new = [adapter("eth0", Some(OperStatus::LowerLayerDown))];
+
+        assert_eq!(
+            diff_links(&old, &new, at(5)),
+            [LinkChange {
+                adapter: "eth0".to_string(),
+                state: LinkState::Down,
+                status: OperStatus::LowerLayerDown,
+                timestamp: at(5),
+            }]
+        );
+    }
+
+    #[test]
+    fn going_up_is_reported() {
+        let old = [adapter("wlan0", Some(OperStatus::Dormant))];
+        let new = [adapter("wlan0", Some(OperStatus::Up))];
+
+        let changes = diff_links(&old, &new, at(0));
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].state, LinkState::Up);
+    }
+
+    #[test]
+    fn moving_between_down_states_is_ignored() {
+        let old = [adapter("eth0", Some(OperStatus::Down))];
+        let new = [adapter("eth0", Some(OperStatus::NotPresent))];
+
+        assert!(diff_links(&old, &new, at(0)).is_empty());
+    }
+
+    #[test]
+    fn new_and_removed_adapters_are_ignored() {
+        let old = [adapter("eth0", Some(OperStatus::Up))];
+        let new = [adapter("wlan0", Some(OperStatus::Down))];
+
+        assert!(diff_links(&old, &new, at(0)).is_empty());
+    }
+
+    #[test]
+    fn adapters_without_status_are_ignored() {
+        let old = [adapter("eth0", None)];
+        let new = [adapter("eth0", Some(OperStatus::Down))];
+
+        assert!(diff_links(&old, &new, at(0)).is_empty());
+    }
+
+    #[test]
+    fn display_adds_status_other_than_state() {
+        let mut change = LinkChange {
+            adapter: "eth0".to_string(),
+            state: LinkState::Down,
+            status: OperStatus::Down,
+            timestamp: at(0),
+        };
+        assert_eq!(change.to_string(), "eth0: link down");
+
+        change.status = OperStatus::NotPresent;
+        assert_eq!(change.to_string(), "eth0: link down (not_present)");
+    }
+}
+
+mod tracking_fetcher {
+    use super::*;
+
+    #[test]
+    fn first_fetch_is_baseline_then_changes_are_sent() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let fetcher = LinkTrackingFetcher::with_clock(
+            SequenceFetcher::new(vec![
+                Ok(vec![adapter("eth0", Some(OperStatus::Up))]),
+                Ok(vec![adapter("eth0", Some(OperStatus::Up))]),
+                Ok(vec![adapter("eth0", Some(OperStatus::Down))]),
+            ]),
+            true,
+            tx,
+            FixedClock,
+        );
+
+        fetcher.fetch().unwrap();
+        fetcher.fetch().unwrap();
+        assert!(rx.try_recv().is_err());
+
+        fetcher.fetch().unwrap();
+
+        let batch = rx.try_recv().unwrap();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].state, LinkState::Down);
+        assert_eq!(batch[0].timestamp, at(100));
+    }
+
+    #[test]
+    fn disabled_sends_nothing() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let fetcher = LinkTrackingFetcher::new(
+            SequenceFetcher::new(vec![
+                Ok(vec![adapter("eth0", Some(OperStatus::Up))]),
+                Ok(vec![adapter("eth0", Some(OperStatus::Down))]),
+            ]),
+            false,
+            tx,
+        );
+
+        fetcher.fetch().unwrap();
+        fetcher.fetch().unwrap();
+
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn failed_fetch_keeps_baseline() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let fetcher = LinkTrackingFetcher::new(
+            SequenceFetcher::new(vec![
+                Ok(vec![adapter("eth0", Some(OperStatus::Up))]),
+                Err(FetchError::Platform {
+                    message: "boom".to_string(),
+                }),
+                Ok(vec![adapter("eth0", Some(OperStatus::Down))]),
+            ]),
+            true,
+            tx,
+        );
+
+        fetcher.fetch().unwrap();
+        assert!(fetcher.fetch().is_err());
+        fetcher.fetch().unwrap();
+
+        assert_eq!(rx.try_recv().unwrap()[0].state, LinkState::Down);
+    }
+
+    #[test]
+    fn closed_receiver_does_not_fail_fetch() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        drop(rx);
+        let fetcher = LinkTrackingFetcher::new(
+            SequenceFetcher::new(vec![
+                Ok(vec![adapter("eth0", Some(OperStatus::Up))]),
+                Ok(vec![adapter("eth0", Some(OperStatus::Down))]),
+            ]),
+            true,
+            tx,
+        );
+
+        fetcher.fetch().unwrap();
+        assert!(fetcher.fetch().is_ok());
+    }
+}
@@ -0,0 +1,241 @@
+//! Self metrics for adapter fetches.
+//!
+//! A fetch that slowly grows towards the poll interval is an early sign of an
+//! overloaded WMI/netlink stack, well before the [`Watchdog`](super::Watchdog)
+//! sees a stall. [`MetricsFetcher`] times every fetch and records it in
+//! [`PollMetrics`]: running totals plus a ring buffer of recent samples.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::network::{AdapterSnapshot, AddressFetcher, FetchError};
+use crate::time::{Clock, SystemClock};
+
+/// Number of recent samples kept by [`PollMetrics::default`].
+pub const DEFAULT_HISTORY_LEN: usize = 60;
+
+/// Fraction of the poll interval above which a fetch counts as slow.
+const SLOW_FETCH_RATIO: f64 = 0.8;
+
+/// Measurement of a single adapter fetch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PollSample {
+    /// When the fetch returned.
+    pub finished_at: SystemTime,
+    /// How long the fetch took.
+    pub duration: Duration,
+    /// Adapters returned (0 for a failed fetch).
+    pub adapters: usize,
+    /// IPv4 and IPv6 addresses across those adapters.
+    pub addresses: usize,
+    /// Whether the fetch succeeded.
+    pub succeeded: bool,
+}
+
+/// Aggregated fetch metrics, serialized into the run summary.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PollSummary {
+    /// Fetches recorded since startup.
+    pub polls: u64,
+    /// Fetches that returned an error.
+    pub failed: u64,
+    /// Duration of the latest fetch in milliseconds.
+    pub last_duration_ms: Option<u64>,
+    /// Mean duration over the recent history in milliseconds.
+    pub mean_duration_ms: Option<u64>,
+    /// Longest fetch since startup in milliseconds.
+    pub max_duration_ms: Option<u64>,
+    /// Adapters returned by the latest successful fetch.
+    pub adapters: Option<usize>,
+    /// Addresses returned by the latest successful fetch.
+    pub addresses: Option<usize>,
+}
+
+#[derive(Debug, Default)]
+struct MetricsState {
+    history: VecDeque<PollSample>,
+    polls: u64,
+    failed: u64,
+    max_duration: Option<Duration>,
+    last_success: Option<(usize, usize)>,
+}
+
+/// Shared fetch metrics with a bounded history of recent samples.
+///
+/// Clones share the same data, so one clone can be handed to the fetcher
+/// and another to whatever reports the metrics.
+#[derive(Debug, Clone)]
+pub struct PollMetrics {
+    state: Arc<Mutex<MetricsState>>,
+    capacity: usize,
+}
+
+impl Default for PollMetrics {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_LEN)
+    }
+}
+
+impl PollMetrics {
+    /// Creates empty metrics keeping the latest `capacity` samples (at least one).
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            state: Arc::new(Mutex::new(MetricsState {
+                history: VecDeque::with_capacity(capacity),
+                ..MetricsState::default()
+            })),
+            capacity,
+        }
+    }
+
+    /// Records a sample, evicting the oldest once the history is full.
+    pub fn record(&self, sample: PollSample) {
+        let mut state = self.lock();
+        state.polls += 1;
+        if sample.succeeded {
+            state.last_success = Some((sample.adapters, sample.addresses));
+        } else {
+            state.failed += 1;
+        }
+        state.max_duration = state.max_duration.max(Some(sample.duration));
+
+        if state.history.len() == self.capacity {
+            state.history.pop_front();
+        }
+        state.history.push_back(sample);
+    }
+
+    /// Returns the recent samples, oldest first.
+    #[must_use]
+    pub fn recent(&self) -> Vec<PollSample> {
+        self.lock().history.iter().cloned().collect()
+    }
+
+    /// Returns the most recent sample, if any.
+    #[must_use]
+    pub fn latest(&self) -> Option<PollSample> {
+        self.lock().history.back().cloned()
+    }
+
+    /// Summarizes the metrics recorded so far.
+    #[must_use]
+    pub fn summary(&self) -> PollSummary {
+        let state = self.lock();
+        let total: Duration = state.history.iter().map(|s| s.duration).sum();
+        let mean = u32::try_from(state.history.len())
+            .ok()
+            .filter(|&n| n > 0)
+            .map(|n| total / n);
+
+        PollSummary {
+            polls: state.polls,
+            failed: state.failed,
+            last_duration_ms: state.history.back().map(|s| millis(s.duration)),
+            mean_duration_ms: mean.map(millis),
+            max_duration_ms: state.max_duration.map(millis),
+            adapters: state.last_success.map(|(adapters, _)| adapters),
+            addresses: state.last_success.map(|(_, addresses)| addresses),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MetricsState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// An [`AddressFetcher`] decorator that records [`PollMetrics`].
+///
+/// Logs a warning when a fetch takes more than 80% of the poll interval, and
+/// once more when fetches are fast again.
+#[derive(Debug)]
+pub struct MetricsFetcher<F, C = SystemClock> {
+    inner: F,
+    metrics: PollMetrics,
+    poll_interval: Duration,
+    clock: C,
+    slow: AtomicBool,
+}
+
+impl<F> MetricsFetcher<F, SystemClock> {
+    /// Wraps `inner`, recording into `metrics`; `poll_interval` sets the
+    /// slow-fetch warning threshold.
+    #[must_use]
+    pub const fn new(inner: F, metrics: PollMetrics, poll_interval: Duration) -> Self {
+        Self::with_clock(inner, metrics, poll_interval, SystemClock)
+    }
+}
+
+impl<F, C> MetricsFetcher<F, C> {
+    /// Wraps `inner` with a custom clock for timing fetches.
+    #[must_use]
+    pub const fn with_clock(
+        inner: F,
+        metrics: PollMetrics,
+        poll_interval: Duration,
+        clock: C,
+    ) -> Self {
+        Self {
+            inner,
+            metrics,
+            poll_interval,
+            clock,
+            slow: AtomicBool::new(false),
+        }
+    }
+
+    fn check_duration(&self, duration: Duration) {
+        let slow = duration.as_secs_f64() > self.poll_interval.as_secs_f64() * SLOW_FETCH_RATIO;
+        if self.slow.swap(slow, Ordering::Relaxed) == slow {
+            return;
+        }
+
+        if slow {
+            tracing::warn!(
+                "Adapter fetch took {}ms, close to the {}s poll interval; the OS network stack may be overloaded",
+                millis(duration),
+                self.poll_interval.as_secs()
+            );
+        } else {
+            tracing::info!("Adapter fetch time back to normal ({}ms)", millis(duration));
+        }
+    }
+}
+
+impl<F: AddressFetcher, C: Clock> AddressFetcher for MetricsFetcher<F, C> {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        let started = self.clock.now();
+        let result = self.inner.fetch();
+        let finished_at = self.clock.now();
+        // A clock stepping backwards reports zero rather than a bogus duration
+        let duration = finished_at.duration_since(started).unwrap_or_default();
+
+        let (adapters, addresses) = result.as_ref().map_or((0, 0), |snapshot| {
+            let addresses = snapshot
+                .iter()
+                .map(|a| a.ipv4_addresses.len() + a.ipv6_addresses.len())
+                .sum();
+            (snapshot.len(), addresses)
+        });
+        self.metrics.record(PollSample {
+            finished_at,
+            duration,
+            adapters,
+            addresses,
+            succeeded: result.is_ok(),
+        });
+        self.check_duration(duration);
+
+        result
+    }
+}
+
+/// Whole milliseconds, saturating on overflow.
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
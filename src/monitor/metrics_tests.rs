@@ -0,0 +1,240 @@
+//! Tests for fetch self metrics.
+
+use super::metrics::{MetricsFetcher, PollMetrics, PollSample, PollSummary};
+use crate::network::{AdapterKind, AdapterSnapshot, AddressFetcher, FetchError};
+use crate::time::Clock;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Clock returning queued instants in order.
+struct SequenceClock {
+    instants: Mutex<VecDeque<SystemTime>>,
+}
+
+impl SequenceClock {
+    /// Clock for fetches taking `durations` milliseconds each, back to back.
+    fn for_durations(durations: &[u64]) -> Self {
+        let mut now = 0;
+        let mut instants = VecDeque::new();
+        for &duration in durations {
+            instants.push_back(at_ms(now));
+            now += duration;
+            instants.push_back(at_ms(now));
+        }
+        Self {
+            instants: Mutex::new(instants),
+        }
+    }
+}
+
+impl Clock for SequenceClock {
+    fn now(&self) -> SystemTime {
+        self.instants.lock().unwrap().pop_front().unwrap()
+    }
+}
+
+/// Fetcher returning queued results in order.
+struct SequenceFetcher {
+    results: Mutex<VecDeque<Result<Vec<AdapterSnapshot>, FetchError>>>,
+}
+
+impl SequenceFetcher {
+    fn new(results: Vec<Result<Vec<AdapterSnapshot>, FetchError>>) -> Self {
+        Self {
+            results: Mutex::new(results.into()),
+        }
+    }
+}
+
+impl AddressFetcher for SequenceFetcher {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        self.results.lock().unwrap().pop_front().unwrap()
+    }
+}
+
+fn at_ms(ms: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(ms)
+}
+
+fn adapter(name: &str) -> AdapterSnapshot {
+    AdapterSnapshot::new(
+        name,
+        AdapterKind::Ethernet,
+        vec!["192.0.2.1".parse().unwrap()],
+        vec!["2001:db8::1".parse().unwrap()],
+    )
+}
+
+fn failure() -> FetchError {
+    FetchError::Platform {
+        message: "boom".to_string(),
+    }
+}
+
+fn sample(duration_ms: u64, adapters: usize, succeeded: bool) -> PollSample {
+    PollSample {
+        finished_at: at_ms(duration_ms),
+        duration: Duration::from_millis(duration_ms),
+        adapters,
+        addresses: adapters * 2,
+        succeeded,
+    }
+}
+
+mod poll_metrics {
+    use super::*;
+
+    #[test]
+    fn empty_summary() {
+        assert_eq!(PollMetrics::default().summary(), PollSummary::default());
+    }
+
+    #[test]
+    fn history_keeps_latest_samples() {
+        let metrics = PollMetrics::new(2);
+
+        metrics.record(sample(10, 1, true));
+        metrics.record(sample(20, 1, true));
+        metrics.record(sample(30, 1, true));
+
+        let durations: Vec<_> = metrics.recent().iter().map(|s| s.duration).collect();
+        assert_eq!(
+            durations,
+            [Duration::from_millis(20), Duration::from_millis(30)]
+        );
+    }
+
+    #[test]
+    fn zero_capacity_keeps_one_sample() {
+        let metrics = PollMetrics::new(0);
+
+        metrics.record(sample(10, 1, true));
+        metrics.record(sample(20, 1, true));
+
+        assert_eq!(metrics.recent().len(), 1);
+    }
+
+    #[test]
+    fn summary_aggregates_samples() {
+        let metrics = PollMetrics::new(2);
+
+        metrics.record(sample(90, 3, true));
+        metrics.record(sample(10, 1, true));
+        metrics.record(sample(30, 0, false));
+
+        assert_eq!(
+            metrics.summary(),
+            PollSummary {
+                polls: 3,
+                failed: 1,
+                last_duration_ms: Some(30),
+                // Mean over the history only; max since startup
+                mean_duration_ms: Some(20),
+                max_duration_ms: Some(90),
+                adapters: Some(1),
+                addresses: Some(2),
+            }
+        );
+    }
+
+    #[test]
+    fn latest_is_last_recorded() {
+        let metrics = PollMetrics::new(2);
+        assert_eq!(metrics.latest(), None);
+
+        metrics.record(sample(10, 1, true));
+        metrics.record(sample(20, 0, false));
+
+        assert_eq!(metrics.latest(), Some(sample(20, 0, false)));
+    }
+
+    #[test]
+    fn clones_share_data() {
+        let metrics = PollMetrics::default();
+        let handle = metrics.clone();
+
+        handle.record(sample(10, 1, true));
+
+        assert_eq!(metrics.summary().polls, 1);
+    }
+}
+
+mod metrics_fetcher {
+    use super::*;
+
+    #[test]
+    fn records_duration_and_counts() {
+        let metrics = PollMetrics::default();
+        let fetcher = MetricsFetcher::with_clock(
+            SequenceFetcher::new(vec![Ok(vec![adapter("eth0"), adapter("wlan0")])]),
+            metrics.clone(),
+            Duration::from_secs(60),
+            SequenceClock::for_durations(&[250]),
+        );
+
+        assert_eq!(fetcher.fetch().unwrap().len(), 2);
+
+        assert_eq!(
+            metrics.recent(),
+            [PollSample {
+                finished_at: at_ms(250),
+                duration: Duration::from_millis(250),
+                adapters: 2,
+                addresses: 4,
+                succeeded: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn records_failed_fetch() {
+        let metrics = PollMetrics::default();
+        let fetcher = MetricsFetcher::with_clock(
+            SequenceFetcher::new(vec![Err(failure())]),
+            metrics.clone(),
+            Duration::from_secs(60),
+            SequenceClock::for_durations(&[5]),
+        );
+
+        assert!(fetcher.fetch().is_err());
+
+        let summary = metrics.summary();
+        assert_eq!((summary.polls, summary.failed), (1, 1));
+        assert_eq!(summary.adapters, None);
+    }
+
+    #[test]
+    fn backwards_clock_records_zero_duration() {
+        let metrics = PollMetrics::default();
+        let clock = SequenceClock {
+            instants: Mutex::new(VecDeque::from([at_ms(100), at_ms(50)])),
+        };
+        let fetcher = MetricsFetcher::with_clock(
+            SequenceFetcher::new(vec![Ok(vec![])]),
+            metrics.clone(),
+            Duration::from_secs(60),
+            clock,
+        );
+
+        fetcher.fetch().unwrap();
+
+        assert_eq!(metrics.recent()[0].duration, Duration::ZERO);
+    }
+
+    #[test]
+    fn slow_fetches_are_still_returned() {
+        let metrics = PollMetrics::default();
+        let fetcher = MetricsFetcher::with_clock(
+            SequenceFetcher::new(vec![Ok(vec![adapter("eth0")]), Ok(vec![adapter("eth0")])]),
+            metrics.clone(),
+            Duration::from_secs(1),
+            SequenceClock::for_durations(&[900, 100]),
+        );
+
+        fetcher.fetch().unwrap();
+        fetcher.fetch().unwrap();
+
+        assert_eq!(metrics.summary().max_duration_ms, Some(900));
+    }
+}
@@ -3,26 +3,91 @@
 //! This module provides types and functions for:
 //! - Representing IP change events ([`IpChange`], [`IpChangeKind`])
 //! - Detecting changes between snapshots ([`diff`])
+//! - Suppressing added or removed addresses ([`NotifyOn`], [`filter_by_kind`])
+//! - Summarizing change batches for logs ([`summarize`])
 //! - Debouncing rapid changes ([`DebouncePolicy`])
+//! - Stretching the poll interval while stable ([`AdaptivePolicy`])
+//! - Spacing notifications apart ([`NotifyThrottle`])
+//! - DNS setting changes ([`DnsChange`], [`diff_dns`], [`DnsTrackingFetcher`])
+//! - Hostname changes ([`HostnameChange`], [`HostnameTrackingFetcher`])
+//! - Adapters going up or down ([`LinkChange`], [`diff_links`], [`LinkTrackingFetcher`])
+//! - Expected address drift ([`ExpectedAddresses`], [`DriftChange`], [`ExpectationFetcher`])
+//! - IPv6 address storms ([`StormPolicy`], [`StormChange`], [`AddressStormFetcher`])
 //! - Error handling ([`MonitorError`], [`ApiError`])
 //! - Polling-based monitoring ([`PollingMonitor`], [`PollingStream`])
-//! - API-based notifications ([`ApiListener`], [`platform`])
-//! - Hybrid monitoring ([`HybridMonitor`], [`HybridStream`])
+//! - API-based notifications ([`ApiListener`], [`platform`]; `hybrid` feature)
+//! - Hybrid monitoring ([`HybridMonitor`], [`HybridStream`]; `hybrid` feature)
+//! - Stall detection ([`Watchdog`], [`HeartbeatFetcher`])
+//! - Fetch self metrics ([`PollMetrics`], [`MetricsFetcher`])
+//! - Broadcasting observed changes to subscribers ([`events::ChangeBus`])
 
+mod adaptive;
 mod change;
 mod debounce;
+mod dns;
 mod error;
+pub mod events;
+mod expect;
+mod fetch;
+mod hostname;
+#[cfg(feature = "hybrid")]
 mod hybrid;
+mod link;
 mod listener;
+mod metrics;
+#[cfg(feature = "hybrid")]
 pub mod platform;
 mod poller;
+mod storm;
+mod summary;
+mod throttle;
+mod watchdog;
 
+#[cfg(test)]
+mod adaptive_tests;
+#[cfg(test)]
+mod dns_tests;
+#[cfg(test)]
+mod events_tests;
+#[cfg(test)]
+mod expect_tests;
+#[cfg(test)]
+mod fetch_tests;
+#[cfg(test)]
+mod hostname_tests;
+#[cfg(test)]
+mod link_tests;
+#[cfg(test)]
+mod metrics_tests;
 #[cfg(test)]
 mod poller_tests;
+#[cfg(test)]
+mod storm_tests;
+#[cfg(test)]
+mod summary_tests;
+#[cfg(test)]
+mod throttle_tests;
+#[cfg(test)]
+mod watchdog_tests;
 
-pub use change::{IpChange, IpChangeKind, diff, filter_by_version};
+pub use adaptive::AdaptivePolicy;
+pub use change::{IpChange, IpChangeKind, NotifyOn, diff, filter_by_kind, filter_by_version};
 pub use debounce::DebouncePolicy;
+pub use dns::{DnsChange, DnsTrackingFetcher, diff_dns};
 pub use error::{ApiError, MonitorError};
+pub use expect::{
+    AddressDrift, DriftChange, DriftKind, ExpectationFetcher, ExpectedAddresses, diff_drift,
+};
+pub use hostname::{HostnameChange, HostnameSource, HostnameTrackingFetcher, SystemHostname};
+#[cfg(feature = "hybrid")]
 pub use hybrid::{HybridMonitor, HybridStream};
+pub use link::{LinkChange, LinkState, LinkTrackingFetcher, diff_links};
 pub use listener::ApiListener;
+pub use metrics::{DEFAULT_HISTORY_LEN, MetricsFetcher, PollMetrics, PollSample, PollSummary};
 pub use poller::{PollingMonitor, PollingStream, merge_changes};
+pub use storm::{AddressStorm, AddressStormFetcher, StormChange, StormPolicy, count_addresses};
+pub use summary::summarize;
+pub use throttle::NotifyThrottle;
+pub use watchdog::{
+    Heartbeat, HeartbeatFetcher, SharedInterval, Watchdog, WatchdogAction, WatchdogStatus,
+};
@@ -0,0 +1,228 @@
+//! Linux-specific IP address change listener using rtnetlink.
+
+use crate::monitor::{ApiError, ApiListener};
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+use tokio::io::unix::AsyncFd;
+use tokio_stream::Stream;
+
+/// Multicast group of IPv4 address notifications (`RTMGRP_IPV4_IFADDR`).
+const RTMGRP_IPV4_IFADDR: u32 = 0x10;
+/// Multicast group of IPv6 address notifications (`RTMGRP_IPV6_IFADDR`).
+const RTMGRP_IPV6_IFADDR: u32 = 0x100;
+/// Size of `struct nlmsghdr`.
+const NLMSG_HDRLEN: usize = 16;
+/// Receive buffer size; a datagram holds one or more whole messages.
+const RECV_BUFFER_LEN: usize = 8192;
+
+/// Linux implementation of [`ApiListener`] using an rtnetlink socket.
+///
+/// The socket subscribes to the `RTMGRP_IPV4_IFADDR` and
+/// `RTMGRP_IPV6_IFADDR` multicast groups, so the kernel pushes a message
+/// whenever an address is added to or removed from an interface. Every
+/// datagram carrying such a message yields one notification; the monitor
+/// re-fetches and diffs the addresses itself.
+///
+/// # One-time Semantics
+///
+/// Once `into_stream` is called, the listener is consumed. If the stream
+/// encounters an error, callers should fall back to polling-only mode
+/// rather than attempting to recreate the listener.
+///
+/// # Example
+///
+/// ```no_run
+/// use ddns_a::monitor::platform::LinuxApiListener;
+/// use ddns_a::monitor::ApiListener;
+/// use tokio_stream::StreamExt;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let listener = LinuxApiListener::new()?;
+/// let mut stream = listener.into_stream();
+///
+/// while let Some(result) = stream.next().await {
+///     match result {
+///         Ok(()) => println!("IP address changed"),
+///         Err(e) => {
+///             eprintln!("Listener error: {e}");
+///             break; // Fall back to polling
+///         }
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct LinuxApiListener {
+    socket: OwnedFd,
+}
+
+impl LinuxApiListener {
+    /// Creates a listener subscribed to address notifications.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ApiError::Netlink`] if the netlink socket cannot be created
+    /// or bound, e.g. inside a sandbox that forbids `AF_NETLINK`.
+    pub fn new() -> Result<Self, ApiError> {
+        open_socket(RTMGRP_IPV4_IFADDR | RTMGRP_IPV6_IFADDR)
+            .map(|socket| Self { socket })
+            .map_err(ApiError::Netlink)
+    }
+}
+
+impl ApiListener for LinuxApiListener {
+    type Stream = LinuxApiStream;
+
+    fn into_stream(self) -> Self::Stream {
+        LinuxApiStream {
+            socket: Some(self.socket),
+            registered: None,
+            terminated: false,
+        }
+    }
+}
+
+/// Stream of address change notifications from an rtnetlink socket.
+///
+/// The socket is registered with the tokio reactor on the first poll, so
+/// the stream can be created outside of a runtime.
+pub struct LinuxApiStream {
+    /// The socket until the first poll registers it
+    socket: Option<OwnedFd>,
+    /// The socket registered with the reactor
+    registered: Option<AsyncFd<OwnedFd>>,
+    /// Whether the stream has terminated due to error
+    terminated: bool,
+}
+
+impl std::fmt::Debug for LinuxApiStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LinuxApiStream")
+            .field("terminated", &self.terminated)
+            .field("registered", &self.registered.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl LinuxApiStream {
+    /// Ends the stream with `error`.
+    fn fail(&mut self, error: io::Error) -> Poll<Option<Result<(), ApiError>>> {
+        self.terminated = true;
+        self.registered = None;
+        Poll::Ready(Some(Err(ApiError::Netlink(error))))
+    }
+}
+
+impl Stream for LinuxApiStream {
+    type Item = Result<(), ApiError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.terminated {
+            return Poll::Ready(None);
+        }
+        if let Some(socket) = self.socket.take() {
+            match AsyncFd::new(socket) {
+                Ok(registered) => self.registered = Some(registered),
+                Err(e) => return self.fail(e),
+            }
+        }
+
+        let Some(registered) = &self.registered else {
+            return Poll::Ready(None);
+        };
+        match ready!(poll_change(registered, cx)) {
+            Ok(()) => Poll::Ready(Some(Ok(()))),
+            Err(e) => self.fail(e),
+        }
+    }
+}
+
+/// Waits for a datagram reporting an address change on `socket`.
+fn poll_change(socket: &AsyncFd<OwnedFd>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+    let mut buf = [0u8; RECV_BUFFER_LEN];
+    loop {
+        let mut guard = ready!(socket.poll_read_ready(cx))?;
+        match guard.try_io(|socket| recv(socket.get_ref(), &mut buf)) {
+            Ok(Ok(len)) if has_address_change(&buf[..len]) => return Poll::Ready(Ok(())),
+            // The receive buffer overflowed: notifications were lost, so
+            // treat it as a change and let the monitor re-fetch
+            Ok(Err(e)) if e.raw_os_error() == Some(libc::ENOBUFS) => return Poll::Ready(Ok(())),
+            Ok(Err(e)) if e.kind() != io::ErrorKind::Interrupted => return Poll::Ready(Err(e)),
+            // Other messages, interruptions and spurious wake-ups
+            Ok(_) | Err(_) => {}
+        }
+    }
+}
+
+/// Opens a non-blocking `NETLINK_ROUTE` socket bound to `groups`.
+///
+/// Excluded from coverage - the failure paths require a restricted kernel.
+#[cfg(not(tarpaulin_include))]
+fn open_socket(groups: u32) -> io::Result<OwnedFd> {
+    // SAFETY: socket() has no memory-safety preconditions
+    let fd = unsafe {
+        libc::socket(
+            libc::AF_NETLINK,
+            libc::SOCK_RAW | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC,
+            libc::NETLINK_ROUTE,
+        )
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: fd is a freshly created descriptor nothing else owns
+    let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    // SAFETY: sockaddr_nl is plain data, for which all zeroes is valid
+    let mut addr: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+    // AF_NETLINK (16) fits sa_family_t
+    #[allow(clippy::cast_possible_truncation)]
+    let family = libc::AF_NETLINK as libc::sa_family_t;
+    addr.nl_family = family;
+    addr.nl_groups = groups;
+    let len = libc::socklen_t::try_from(size_of::<libc::sockaddr_nl>()).unwrap_or(0);
+    // SAFETY: addr is a valid sockaddr_nl of the given length
+    let result = unsafe {
+        libc::bind(
+            socket.as_raw_fd(),
+            (&raw const addr).cast::<libc::sockaddr>(),
+            len,
+        )
+    };
+    if result < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(socket)
+}
+
+/// Receives one datagram from `socket` into `buf`.
+fn recv(socket: &OwnedFd, buf: &mut [u8]) -> io::Result<usize> {
+    // SAFETY: buf is valid for writes of buf.len() bytes
+    let len = unsafe { libc::recv(socket.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len(), 0) };
+    usize::try_from(len).map_err(|_| io::Error::last_os_error())
+}
+
+/// Returns true if the netlink messages in `datagram` include an address
+/// being added (`RTM_NEWADDR`) or removed (`RTM_DELADDR`).
+///
+/// Parsing stops at the first malformed header, keeping what was found.
+pub(super) fn has_address_change(datagram: &[u8]) -> bool {
+    let mut rest = datagram;
+    while rest.len() >= NLMSG_HDRLEN {
+        let len = u32::from_ne_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        let kind = u16::from_ne_bytes([rest[4], rest[5]]);
+        if kind == libc::RTM_NEWADDR || kind == libc::RTM_DELADDR {
+            return true;
+        }
+        if len < NLMSG_HDRLEN {
+            return false;
+        }
+        // Messages are aligned to 4 bytes (NLMSG_ALIGN)
+        let aligned = (len + 3) & !3;
+        rest = rest.get(aligned..).unwrap_or_default();
+    }
+    false
+}
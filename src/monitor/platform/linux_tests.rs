@@ -0,0 +1,74 @@
+//! Tests for the Linux rtnetlink listener.
+
+use super::linux::{LinuxApiListener, LinuxApiStream, has_address_change};
+use crate::monitor::ApiListener;
+
+/// A netlink message header of `kind` followed by `payload` bytes.
+fn message(kind: u16, payload: usize) -> Vec<u8> {
+    let len = u32::try_from(16 + payload).unwrap();
+    let mut msg = Vec::new();
+    msg.extend_from_slice(&len.to_ne_bytes());
+    msg.extend_from_slice(&kind.to_ne_bytes());
+    msg.extend_from_slice(&[0; 10]);
+    msg.resize(16 + payload, 0);
+    // Pad to the 4-byte alignment of the next message
+    msg.resize(msg.len().next_multiple_of(4), 0);
+    msg
+}
+
+#[test]
+fn new_and_deleted_addresses_are_changes() {
+    assert!(has_address_change(&message(libc::RTM_NEWADDR, 24)));
+    assert!(has_address_change(&message(libc::RTM_DELADDR, 24)));
+}
+
+#[test]
+fn other_messages_are_not_changes() {
+    assert!(!has_address_change(&message(libc::RTM_NEWLINK, 32)));
+    assert!(!has_address_change(&message(libc::RTM_NEWROUTE, 28)));
+    assert!(!has_address_change(&[]));
+}
+
+#[test]
+fn finds_change_after_other_messages() {
+    let mut datagram = message(libc::RTM_NEWLINK, 21);
+    datagram.extend(message(libc::RTM_NEWADDR, 24));
+
+    assert!(has_address_change(&datagram));
+}
+
+#[test]
+fn stops_at_malformed_header() {
+    let mut datagram = message(libc::RTM_NEWLINK, 0);
+    datagram[..4].copy_from_slice(&4u32.to_ne_bytes());
+    datagram.extend(message(libc::RTM_NEWADDR, 24));
+
+    assert!(!has_address_change(&datagram));
+}
+
+#[test]
+fn linux_api_stream_is_send_and_unpin() {
+    fn assert_send<T: Send>() {}
+    fn assert_unpin<T: Unpin>() {}
+    assert_send::<LinuxApiStream>();
+    assert_unpin::<LinuxApiStream>();
+}
+
+#[tokio::test]
+async fn stream_waits_for_notifications() {
+    // Sandboxes may forbid netlink sockets; nothing to test then
+    let Ok(listener) = LinuxApiListener::new() else {
+        return;
+    };
+    let mut stream = listener.into_stream();
+
+    let next = tokio::time::timeout(
+        std::time::Duration::from_millis(50),
+        tokio_stream::StreamExt::next(&mut stream),
+    )
+    .await;
+
+    // No address changes while the test runs
+    assert!(next.is_err());
+    assert!(format!("{stream:?}").contains("registered: true"));
+}
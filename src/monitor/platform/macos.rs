@@ -0,0 +1,240 @@
+//! macOS-specific IP address change listener using a routing socket.
+//!
+//! # Why not `SCDynamicStore` or `NWPathMonitor`
+//!
+//! `SCDynamicStore` notifications for `State:/Network/Interface/.*/IPv[46]`
+//! are published by `configd` from the kernel's `RTM_NEWADDR` and
+//! `RTM_DELADDR` routing messages, which this listener reads directly from
+//! a `PF_ROUTE` socket. Reading them here
+//!
+//! - fits the tokio reactor: the socket is a file descriptor driven by
+//!   [`AsyncFd`], while `SCDynamicStore` (through `CFRunLoop`) and
+//!   `NWPathMonitor` (through a dispatch queue) need a thread of their own
+//!   and a channel back to the runtime;
+//! - needs no framework bindings: only `libc`, already used by the macOS
+//!   fetcher, instead of `core-foundation`/`system-configuration` or
+//!   Objective-C blocks for `Network.framework`;
+//! - reports every address change, where `NWPathMonitor` only reports
+//!   changes of the path's status and interfaces (a new IPv6 temporary
+//!   address on the same interface does not update the path).
+//!
+//! The monitor only needs to know *that* something changed, since it
+//! re-fetches and diffs the addresses itself, so the extra detail of the
+//! `SCDynamicStore` keys would go unused.
+
+use crate::monitor::{ApiError, ApiListener};
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+use tokio::io::unix::AsyncFd;
+use tokio_stream::Stream;
+
+/// Message type of an address added to an interface (`RTM_NEWADDR`).
+const RTM_NEWADDR: u8 = 0xc;
+/// Message type of an address removed from an interface (`RTM_DELADDR`).
+const RTM_DELADDR: u8 = 0xd;
+/// Length of the header shared by all routing messages: `rtm_msglen`,
+/// `rtm_version` and `rtm_type`.
+const RTM_HDRLEN: usize = 4;
+/// Receive buffer size; a read returns one or more whole messages.
+const RECV_BUFFER_LEN: usize = 8192;
+
+/// macOS implementation of [`ApiListener`] using a `PF_ROUTE` socket.
+///
+/// The kernel writes a message to every routing socket whenever an address
+/// is added to or removed from an interface. These are the same events
+/// `configd` turns into `SCDynamicStore` notifications, without a
+/// `CFRunLoop` thread to drive them. Every read carrying such a message
+/// yields one notification; the monitor re-fetches and diffs the addresses
+/// itself.
+///
+/// # One-time Semantics
+///
+/// Once `into_stream` is called, the listener is consumed. If the stream
+/// encounters an error, callers should fall back to polling-only mode
+/// rather than attempting to recreate the listener.
+///
+/// # Example
+///
+/// ```no_run
+/// use ddns_a::monitor::platform::MacApiListener;
+/// use ddns_a::monitor::ApiListener;
+/// use tokio_stream::StreamExt;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let listener = MacApiListener::new()?;
+/// let mut stream = listener.into_stream();
+///
+/// while let Some(result) = stream.next().await {
+///     match result {
+///         Ok(()) => println!("IP address changed"),
+///         Err(e) => {
+///             eprintln!("Listener error: {e}");
+///             break; // Fall back to polling
+///         }
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct MacApiListener {
+    socket: OwnedFd,
+}
+
+impl MacApiListener {
+    /// Creates a listener for routing messages.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ApiError::RoutingSocket`] if the routing socket cannot be
+    /// created, e.g. inside a sandbox that forbids `PF_ROUTE`.
+    pub fn new() -> Result<Self, ApiError> {
+        open_socket()
+            .map(|socket| Self { socket })
+            .map_err(ApiError::RoutingSocket)
+    }
+}
+
+impl ApiListener for MacApiListener {
+    type Stream = MacApiStream;
+
+    fn into_stream(self) -> Self::Stream {
+        MacApiStream {
+            socket: Some(self.socket),
+            registered: None,
+            terminated: false,
+        }
+    }
+}
+
+/// Stream of address change notifications from a routing socket.
+///
+/// The socket is registered with the tokio reactor on the first poll, so
+/// the stream can be created outside of a runtime.
+pub struct MacApiStream {
+    /// The socket until the first poll registers it
+    socket: Option<OwnedFd>,
+    /// The socket registered with the reactor
+    registered: Option<AsyncFd<OwnedFd>>,
+    /// Whether the stream has terminated due to error
+    terminated: bool,
+}
+
+impl std::fmt::Debug for MacApiStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MacApiStream")
+            .field("terminated", &self.terminated)
+            .field("registered", &self.registered.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl MacApiStream {
+    /// Ends the stream with `error`.
+    fn fail(&mut self, error: io::Error) -> Poll<Option<Result<(), ApiError>>> {
+        self.terminated = true;
+        self.registered = None;
+        Poll::Ready(Some(Err(ApiError::RoutingSocket(error))))
+    }
+}
+
+impl Stream for MacApiStream {
+    type Item = Result<(), ApiError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.terminated {
+            return Poll::Ready(None);
+        }
+        if let Some(socket) = self.socket.take() {
+            match AsyncFd::new(socket) {
+                Ok(registered) => self.registered = Some(registered),
+                Err(e) => return self.fail(e),
+            }
+        }
+
+        let Some(registered) = &self.registered else {
+            return Poll::Ready(None);
+        };
+        match ready!(poll_change(registered, cx)) {
+            Ok(()) => Poll::Ready(Some(Ok(()))),
+            Err(e) => self.fail(e),
+        }
+    }
+}
+
+/// Waits for a read reporting an address change on `socket`.
+fn poll_change(socket: &AsyncFd<OwnedFd>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+    let mut buf = [0u8; RECV_BUFFER_LEN];
+    loop {
+        let mut guard = ready!(socket.poll_read_ready(cx))?;
+        match guard.try_io(|socket| recv(socket.get_ref(), &mut buf)) {
+            Ok(Ok(len)) if has_address_change(&buf[..len]) => return Poll::Ready(Ok(())),
+            // The socket buffer overflowed: messages were lost, so treat it
+            // as a change and let the monitor re-fetch
+            Ok(Err(e)) if e.raw_os_error() == Some(libc::ENOBUFS) => return Poll::Ready(Ok(())),
+            Ok(Err(e)) if e.kind() != io::ErrorKind::Interrupted => return Poll::Ready(Err(e)),
+            // Other messages, interruptions and spurious wake-ups
+            Ok(_) | Err(_) => {}
+        }
+    }
+}
+
+/// Opens a non-blocking `PF_ROUTE` socket for all address families.
+///
+/// Excluded from coverage - the failure paths require a restricted kernel.
+#[cfg(not(tarpaulin_include))]
+fn open_socket() -> io::Result<OwnedFd> {
+    // SAFETY: socket() has no memory-safety preconditions
+    let fd = unsafe { libc::socket(libc::PF_ROUTE, libc::SOCK_RAW, libc::AF_UNSPEC) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: fd is a freshly created descriptor nothing else owns
+    let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    // macOS has no SOCK_NONBLOCK / SOCK_CLOEXEC, so set both afterwards
+    // SAFETY: fcntl on a valid descriptor with integer arguments
+    let result = unsafe {
+        let flags = libc::fcntl(socket.as_raw_fd(), libc::F_GETFL);
+        if flags < 0 {
+            flags
+        } else if libc::fcntl(socket.as_raw_fd(), libc::F_SETFL, flags | libc::O_NONBLOCK) < 0 {
+            -1
+        } else {
+            libc::fcntl(socket.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC)
+        }
+    };
+    if result < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(socket)
+}
+
+/// Reads from `socket` into `buf`.
+fn recv(socket: &OwnedFd, buf: &mut [u8]) -> io::Result<usize> {
+    // SAFETY: buf is valid for writes of buf.len() bytes
+    let len = unsafe { libc::recv(socket.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len(), 0) };
+    usize::try_from(len).map_err(|_| io::Error::last_os_error())
+}
+
+/// Returns true if the routing messages in `buf` include an address being
+/// added (`RTM_NEWADDR`) or removed (`RTM_DELADDR`).
+///
+/// Parsing stops at the first malformed header, keeping what was found.
+pub(super) fn has_address_change(buf: &[u8]) -> bool {
+    let mut rest = buf;
+    while rest.len() >= RTM_HDRLEN {
+        let len = usize::from(u16::from_ne_bytes([rest[0], rest[1]]));
+        let kind = rest[3];
+        if kind == RTM_NEWADDR || kind == RTM_DELADDR {
+            return true;
+        }
+        if len < RTM_HDRLEN {
+            return false;
+        }
+        rest = rest.get(len..).unwrap_or_default();
+    }
+    false
+}
@@ -0,0 +1,79 @@
+//! Tests for the macOS routing socket listener.
+
+use super::macos::{MacApiListener, MacApiStream, has_address_change};
+use crate::monitor::ApiListener;
+
+/// A routing message header of `kind` followed by `payload` bytes.
+fn message(kind: u8, payload: usize) -> Vec<u8> {
+    let len = u16::try_from(4 + payload).unwrap();
+    let mut msg = Vec::new();
+    msg.extend_from_slice(&len.to_ne_bytes());
+    // rtm_version (RTM_VERSION)
+    msg.push(5);
+    msg.push(kind);
+    msg.resize(4 + payload, 0);
+    msg
+}
+
+// RTM_NEWADDR, RTM_DELADDR, RTM_IFINFO and RTM_ADD
+const NEWADDR: u8 = 0xc;
+const DELADDR: u8 = 0xd;
+const IFINFO: u8 = 0xe;
+const ADD: u8 = 0x1;
+
+#[test]
+fn new_and_deleted_addresses_are_changes() {
+    assert!(has_address_change(&message(NEWADDR, 16)));
+    assert!(has_address_change(&message(DELADDR, 16)));
+}
+
+#[test]
+fn other_messages_are_not_changes() {
+    assert!(!has_address_change(&message(IFINFO, 108)));
+    assert!(!has_address_change(&message(ADD, 88)));
+    assert!(!has_address_change(&[]));
+}
+
+#[test]
+fn finds_change_after_other_messages() {
+    let mut buf = message(IFINFO, 108);
+    buf.extend(message(NEWADDR, 16));
+
+    assert!(has_address_change(&buf));
+}
+
+#[test]
+fn stops_at_malformed_header() {
+    let mut buf = message(IFINFO, 0);
+    buf[..2].copy_from_slice(&2u16.to_ne_bytes());
+    buf.extend(message(NEWADDR, 16));
+
+    assert!(!has_address_change(&buf));
+}
+
+#[test]
+fn mac_api_stream_is_send_and_unpin() {
+    fn assert_send<T: Send>() {}
+    fn assert_unpin<T: Unpin>() {}
+    assert_send::<MacApiStream>();
+    assert_unpin::<MacApiStream>();
+}
+
+#[tokio::test]
+async fn stream_waits_for_notifications() {
+    // Sandboxes may forbid routing sockets; nothing to test then
+    let Ok(listener) = MacApiListener::new() else {
+        return;
+    };
+    let mut stream = listener.into_stream();
+
+    let next = tokio::time::timeout(
+        std::time::Duration::from_millis(50),
+        tokio_stream::StreamExt::next(&mut stream),
+    )
+    .await;
+
+    // No address changes while the test runs
+    assert!(next.is_err());
+    assert!(format!("{stream:?}").contains("registered: true"));
+}
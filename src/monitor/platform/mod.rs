@@ -6,12 +6,22 @@
 //! # Platform Support
 //!
 //! - **Windows**: Uses `NotifyIpInterfaceChange` API via the `windows` crate.
-//! - **Linux**: Planned for future (netlink).
-//! - **macOS**: Planned for future (Network.framework).
+//! - **Linux**: Subscribes to address notifications on an rtnetlink socket.
+//! - **macOS**: Reads address messages from a `PF_ROUTE` routing socket.
 
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "macos")]
+mod macos;
 #[cfg(windows)]
 mod windows;
 
+#[cfg(all(target_os = "linux", test))]
+mod linux_tests;
+
+#[cfg(all(target_os = "macos", test))]
+mod macos_tests;
+
 #[cfg(all(windows, test))]
 mod windows_tests;
 
@@ -21,6 +31,18 @@ pub use windows::WindowsApiListener;
 #[cfg(windows)]
 pub use windows::WindowsApiStream;
 
+#[cfg(target_os = "linux")]
+pub use linux::{LinuxApiListener, LinuxApiStream};
+
+#[cfg(target_os = "macos")]
+pub use macos::{MacApiListener, MacApiStream};
+
 // Re-export platform-specific listener as PlatformListener for convenience
 #[cfg(windows)]
 pub use windows::WindowsApiListener as PlatformListener;
+
+#[cfg(target_os = "linux")]
+pub use linux::LinuxApiListener as PlatformListener;
+
+#[cfg(target_os = "macos")]
+pub use macos::MacApiListener as PlatformListener;
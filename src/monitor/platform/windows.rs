@@ -1,8 +1,10 @@
 //! Windows-specific IP address change listener using `NotifyIpInterfaceChange`.
 
 use crate::monitor::{ApiError, ApiListener};
+use std::ffi::c_void;
+use std::fmt;
 use std::pin::Pin;
-use std::sync::mpsc;
+use std::sync::{Arc, mpsc};
 use std::task::{Context, Poll};
 use tokio::sync::mpsc as tokio_mpsc;
 use tokio_stream::Stream;
@@ -47,10 +49,10 @@ use windows::Win32::Networking::WinSock::AF_UNSPEC;
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct WindowsApiListener {
-    // Currently no configuration needed, but struct allows future extension
-    _private: (),
+    /// Registration calls; the IP Helper API outside of tests
+    api: Arc<dyn NotifyApi>,
 }
 
 impl WindowsApiListener {
@@ -60,8 +62,20 @@ impl WindowsApiListener {
     ///
     /// This constructor cannot fail, but returns `Result` for API consistency
     /// and future extensibility.
-    pub const fn new() -> Result<Self, ApiError> {
-        Ok(Self { _private: () })
+    pub fn new() -> Result<Self, ApiError> {
+        Ok(Self::default())
+    }
+
+    /// Creates a listener that registers through `api` instead of the
+    /// IP Helper API.
+    pub(super) fn with_api(api: Arc<dyn NotifyApi>) -> Self {
+        Self { api }
+    }
+}
+
+impl Default for WindowsApiListener {
+    fn default() -> Self {
+        Self::with_api(Arc::new(IpHelperApi))
     }
 }
 
@@ -69,7 +83,70 @@ impl ApiListener for WindowsApiListener {
     type Stream = WindowsApiStream;
 
     fn into_stream(self) -> Self::Stream {
-        WindowsApiStream::new()
+        WindowsApiStream::new(self.api)
+    }
+}
+
+/// Signature of the `NotifyIpInterfaceChange` callback.
+pub(super) type ChangeCallback =
+    unsafe extern "system" fn(*const c_void, *const MIB_IPINTERFACE_ROW, MIB_NOTIFICATION_TYPE);
+
+/// The two IP Helper calls the stream is built on.
+///
+/// Context ownership, cancellation on drop and callback dispatch are
+/// implemented on top of these, so they can be tested with a fake that
+/// invokes the callback itself.
+pub(super) trait NotifyApi: fmt::Debug + Send + Sync {
+    /// Registers `callback` to be called with `context` on every change.
+    fn register(
+        &self,
+        callback: ChangeCallback,
+        context: *const c_void,
+    ) -> Result<HANDLE, ApiError>;
+
+    /// Cancels the registration `handle`.
+    ///
+    /// Like `CancelMibChangeNotify2`, this must not return while a callback
+    /// is still running, and no callback may start after it returns.
+    fn cancel(&self, handle: HANDLE);
+}
+
+/// [`NotifyApi`] backed by `NotifyIpInterfaceChange`.
+#[derive(Debug)]
+struct IpHelperApi;
+
+impl NotifyApi for IpHelperApi {
+    /// Excluded from coverage - requires actual Windows API interaction.
+    #[cfg(not(tarpaulin_include))]
+    fn register(
+        &self,
+        callback: ChangeCallback,
+        context: *const c_void,
+    ) -> Result<HANDLE, ApiError> {
+        let mut handle = HANDLE::default();
+
+        // SAFETY: The caller keeps `context` valid until `cancel` returns.
+        // InitialNotification = false means no callback on registration.
+        let result = unsafe {
+            NotifyIpInterfaceChange(
+                AF_UNSPEC,
+                Some(callback),
+                Some(context),
+                false, // InitialNotification
+                &raw mut handle,
+            )
+        };
+
+        if result != NO_ERROR {
+            return Err(windows::core::Error::from(WIN32_ERROR(result.0)).into());
+        }
+        Ok(handle)
+    }
+
+    fn cancel(&self, handle: HANDLE) {
+        // SAFETY: `handle` was returned by NotifyIpInterfaceChange and is
+        // cancelled only once, by the NotificationHandle owning it.
+        let _ = unsafe { CancelMibChangeNotify2(handle) };
     }
 }
 
@@ -81,8 +158,8 @@ pub struct WindowsApiStream {
     /// Receiver for notification events
     receiver: tokio_mpsc::UnboundedReceiver<Result<(), ApiError>>,
     /// Handle for cancelling the notification registration.
-    /// This field is used implicitly through its `Drop` impl which calls
-    /// `CancelMibChangeNotify2` to clean up the Windows notification.
+    /// This field is used implicitly through its `Drop` impl which cancels
+    /// the registration to clean up the Windows notification.
     #[allow(dead_code)]
     handle: Option<NotificationHandle>,
     /// Whether the stream has terminated due to error
@@ -103,6 +180,7 @@ impl std::fmt::Debug for WindowsApiStream {
 /// Automatically cancels the notification registration when dropped,
 /// and reclaims the leaked `CallbackContext` to prevent memory/thread leaks.
 struct NotificationHandle {
+    api: Arc<dyn NotifyApi>,
     handle: HANDLE,
     /// Raw pointer to reclaim the leaked `CallbackContext` after cancellation.
     /// Dropping the context closes the channel, allowing the bridge thread to exit.
@@ -111,11 +189,9 @@ struct NotificationHandle {
 
 impl Drop for NotificationHandle {
     fn drop(&mut self) {
-        // SAFETY: We own this handle and it was returned by NotifyIpInterfaceChange.
-        // CancelMibChangeNotify2 is safe to call once per handle.
-        let _ = unsafe { CancelMibChangeNotify2(self.handle) };
+        self.api.cancel(self.handle);
 
-        // SAFETY: After CancelMibChangeNotify2 returns, Windows guarantees the
+        // SAFETY: After cancellation returns, the API guarantees the
         // callback won't fire again, so we can safely reclaim the context.
         // Dropping the context drops the sender, which closes the channel and
         // allows the bridge thread to exit cleanly.
@@ -139,7 +215,7 @@ impl WindowsApiStream {
     /// Creates a new Windows API stream.
     ///
     /// Registers for IP interface change notifications using the Windows API.
-    fn new() -> Self {
+    fn new(api: Arc<dyn NotifyApi>) -> Self {
         // Create a sync channel for the callback (called from Windows thread pool)
         let (sync_tx, sync_rx) = mpsc::channel::<()>();
 
@@ -158,26 +234,20 @@ impl WindowsApiStream {
             }
         });
 
-        // Register for notifications
-        let (handle, terminated) = match register_notification(sync_tx) {
-            Ok((h, ctx_ptr)) => (
-                Some(NotificationHandle {
-                    handle: h,
-                    context_ptr: ctx_ptr,
-                }),
-                false,
-            ),
+        // Register for notifications; on failure the stream yields the
+        // error and then ends
+        let handle = match register_notification(api, sync_tx) {
+            Ok(handle) => Some(handle),
             Err(e) => {
-                // Send the error and mark as terminated
                 let _ = async_tx.send(Err(e));
-                (None, true)
+                None
             }
         };
 
         Self {
             receiver: async_rx,
             handle,
-            terminated,
+            terminated: false,
         }
     }
 }
@@ -206,72 +276,43 @@ impl Stream for WindowsApiStream {
     }
 }
 
-/// Registers for IP interface change notifications.
-///
-/// Returns both the notification handle and the context pointer, so the caller
-/// can store them together and reclaim the context when cancelling.
-///
-/// # Safety
+/// Registers for IP interface change notifications through `api`.
 ///
-/// This function uses unsafe to call Windows API and manage raw pointers.
-/// The callback context is leaked intentionally and must be reclaimed by the
-/// caller after calling `CancelMibChangeNotify2`.
-///
-/// # Coverage Note
-///
-/// This function is excluded from coverage because:
-/// - It requires actual Windows API interaction
-/// - Callback testing requires triggering real network changes
-#[cfg(not(tarpaulin_include))]
+/// The callback context is leaked for the lifetime of the registration and
+/// reclaimed by the returned handle after it cancels the registration, or
+/// right away if registration fails.
 fn register_notification(
+    api: Arc<dyn NotifyApi>,
     sender: mpsc::Sender<()>,
-) -> Result<(HANDLE, *mut CallbackContext), ApiError> {
-    // Leak the context so it lives for the lifetime of the notification.
-    // The caller is responsible for reclaiming it after cancellation.
+) -> Result<NotificationHandle, ApiError> {
     let context_ptr = Box::into_raw(Box::new(CallbackContext { sender }));
-    let void_ptr = context_ptr.cast::<std::ffi::c_void>();
-
-    let mut handle = HANDLE::default();
-
-    // SAFETY: We provide valid callback and context. The callback will be called
-    // from the Windows thread pool when IP interface changes occur.
-    // InitialNotification = false means no callback on registration.
-    let result = unsafe {
-        NotifyIpInterfaceChange(
-            AF_UNSPEC,
-            Some(ip_interface_change_callback),
-            Some(void_ptr),
-            false, // InitialNotification
-            &raw mut handle,
-        )
-    };
-
-    if result != NO_ERROR {
-        // Clean up leaked context on error
-        // SAFETY: Registration failed, so Windows won't call the callback
-        drop(unsafe { Box::from_raw(context_ptr) });
-        return Err(windows::core::Error::from(WIN32_ERROR(result.0)).into());
-    }
 
-    Ok((handle, context_ptr))
+    match api.register(ip_interface_change_callback, context_ptr.cast::<c_void>()) {
+        Ok(handle) => Ok(NotificationHandle {
+            api,
+            handle,
+            context_ptr,
+        }),
+        Err(e) => {
+            // SAFETY: Registration failed, so the callback will never run
+            drop(unsafe { Box::from_raw(context_ptr) });
+            Err(e)
+        }
+    }
 }
 
 /// Callback function for `NotifyIpInterfaceChange`.
 ///
-/// This function is called by Windows when IP interface changes occur.
-/// It sends a notification through the channel to wake up the async stream.
+/// This function is called by Windows (or a fake [`NotifyApi`] in tests)
+/// when IP interface changes occur. It sends a notification through the
+/// channel to wake up the async stream.
 ///
 /// # Safety
 ///
 /// - `caller_context` must be a valid pointer to `CallbackContext`
 /// - `row` may be null and is not used
-///
-/// # Coverage Note
-///
-/// This function is excluded from coverage because it's only called by Windows.
-#[cfg(not(tarpaulin_include))]
 unsafe extern "system" fn ip_interface_change_callback(
-    caller_context: *const std::ffi::c_void,
+    caller_context: *const c_void,
     _row: *const MIB_IPINTERFACE_ROW,
     _notification_type: MIB_NOTIFICATION_TYPE,
 ) {
@@ -1,7 +1,15 @@
 //! Tests for Windows-specific IP address change listener.
 
-use super::windows::{WindowsApiListener, WindowsApiStream};
-use crate::monitor::ApiListener;
+use std::ffi::c_void;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio_stream::StreamExt;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::NetworkManagement::IpHelper::MIB_NOTIFICATION_TYPE;
+
+use super::windows::{ChangeCallback, NotifyApi, WindowsApiListener, WindowsApiStream};
+use crate::monitor::{ApiError, ApiListener};
 
 #[test]
 fn windows_api_listener_new_succeeds() {
@@ -58,3 +66,188 @@ async fn windows_api_stream_can_be_created() {
     // Stream created successfully - actual notification testing would
     // require triggering real network changes
 }
+
+/// A registration recorded by [`FakeApi`].
+///
+/// The context pointer is kept as an address so the fake stays `Send`.
+#[derive(Debug, Clone, Copy)]
+struct Registration {
+    callback: ChangeCallback,
+    context: usize,
+    cancelled: bool,
+}
+
+/// [`NotifyApi`] that records registrations and invokes callbacks on demand.
+#[derive(Debug, Default)]
+struct FakeApi {
+    registrations: Mutex<Vec<Registration>>,
+    /// Fail every registration
+    fail: bool,
+    /// Run the callback once more while cancelling, like a change that
+    /// races with dropping the stream
+    notify_on_cancel: bool,
+}
+
+impl FakeApi {
+    /// Invokes the callback of every active registration, as Windows does on a change.
+    fn notify(&self) {
+        let active: Vec<_> = self
+            .registrations
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| !r.cancelled)
+            .map(|r| (r.callback, r.context))
+            .collect();
+        for (callback, context) in active {
+            invoke(callback, context);
+        }
+    }
+
+    fn active(&self) -> usize {
+        let registrations = self.registrations.lock().unwrap();
+        registrations.iter().filter(|r| !r.cancelled).count()
+    }
+
+    fn registered(&self) -> usize {
+        self.registrations.lock().unwrap().len()
+    }
+}
+
+fn invoke(callback: ChangeCallback, context: usize) {
+    // SAFETY: `context` is the live context of a registration that has not
+    // been cancelled yet
+    unsafe {
+        callback(
+            context as *const c_void,
+            std::ptr::null(),
+            MIB_NOTIFICATION_TYPE(0),
+        );
+    }
+}
+
+impl NotifyApi for FakeApi {
+    fn register(
+        &self,
+        callback: ChangeCallback,
+        context: *const c_void,
+    ) -> Result<HANDLE, ApiError> {
+        if self.fail {
+            return Err(ApiError::Stopped);
+        }
+        let mut registrations = self.registrations.lock().unwrap();
+        registrations.push(Registration {
+            callback,
+            context: context as usize,
+            cancelled: false,
+        });
+        // Handles are 1-based indexes into the registrations
+        Ok(HANDLE(registrations.len() as *mut c_void))
+    }
+
+    fn cancel(&self, handle: HANDLE) {
+        let index = handle.0 as usize - 1;
+        let registration = self.registrations.lock().unwrap()[index];
+        assert!(!registration.cancelled, "registration cancelled twice");
+        if self.notify_on_cancel {
+            invoke(registration.callback, registration.context);
+        }
+        self.registrations.lock().unwrap()[index].cancelled = true;
+    }
+}
+
+fn stream_with(api: &Arc<FakeApi>) -> WindowsApiStream {
+    WindowsApiListener::with_api(Arc::clone(api) as Arc<dyn NotifyApi>).into_stream()
+}
+
+async fn next_event(stream: &mut WindowsApiStream) -> Option<Result<(), ApiError>> {
+    tokio::time::timeout(Duration::from_secs(5), stream.next())
+        .await
+        .expect("no event within 5s")
+}
+
+mod fake_api {
+    use super::*;
+
+    #[tokio::test]
+    async fn callback_is_dispatched_to_stream() {
+        let api = Arc::new(FakeApi::default());
+        let mut stream = stream_with(&api);
+
+        api.notify();
+
+        assert!(matches!(next_event(&mut stream).await, Some(Ok(()))));
+        assert_eq!(api.active(), 1);
+    }
+
+    #[tokio::test]
+    async fn failed_registration_ends_stream() {
+        let api = Arc::new(FakeApi {
+            fail: true,
+            ..FakeApi::default()
+        });
+        let mut stream = stream_with(&api);
+
+        assert!(matches!(
+            next_event(&mut stream).await,
+            Some(Err(ApiError::Stopped))
+        ));
+        assert!(next_event(&mut stream).await.is_none());
+        assert_eq!(api.registered(), 0);
+    }
+
+    #[test]
+    fn drop_while_registered_cancels() {
+        let api = Arc::new(FakeApi::default());
+        let stream = stream_with(&api);
+        assert_eq!(api.active(), 1);
+
+        drop(stream);
+
+        assert_eq!(api.active(), 0);
+        assert_eq!(api.registered(), 1);
+    }
+
+    #[test]
+    fn change_after_drop_is_not_dispatched() {
+        let api = Arc::new(FakeApi::default());
+        drop(stream_with(&api));
+
+        // Would use the reclaimed context if the registration were still active
+        api.notify();
+
+        assert_eq!(api.active(), 0);
+    }
+
+    #[test]
+    fn callback_racing_with_drop_sees_live_context() {
+        let api = Arc::new(FakeApi {
+            notify_on_cancel: true,
+            ..FakeApi::default()
+        });
+        let stream = stream_with(&api);
+
+        // The context is only reclaimed once cancellation has returned
+        drop(stream);
+
+        assert_eq!(api.active(), 0);
+    }
+
+    #[tokio::test]
+    async fn second_start_registers_independently() {
+        let api = Arc::new(FakeApi::default());
+        let mut first = stream_with(&api);
+        let mut second = stream_with(&api);
+        assert_eq!(api.active(), 2);
+
+        api.notify();
+        assert!(matches!(next_event(&mut first).await, Some(Ok(()))));
+        assert!(matches!(next_event(&mut second).await, Some(Ok(()))));
+
+        drop(first);
+        assert_eq!(api.active(), 1);
+
+        api.notify();
+        assert!(matches!(next_event(&mut second).await, Some(Ok(()))));
+    }
+}
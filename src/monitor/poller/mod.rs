@@ -6,12 +6,14 @@
 //! - [`merge_changes`]: Utility for computing net effect of changes
 
 mod monitor;
+mod schedule;
 mod stream;
 
 pub use monitor::PollingMonitor;
 pub use stream::PollingStream;
 
 use super::change::{IpChange, IpChangeKind};
+use crate::network::AdapterMetadata;
 use std::collections::HashMap;
 use std::net::IpAddr;
 
@@ -28,6 +30,9 @@ use std::net::IpAddr;
 /// - Multiple `Added` for same IP = single `Added`
 /// - Multiple `Removed` for same IP = single `Removed`
 ///
+/// A surviving change keeps the adapter metadata of the last change it
+/// merges.
+///
 /// # Arguments
 ///
 /// * `changes` - The changes to merge
@@ -38,8 +43,8 @@ use std::net::IpAddr;
 /// A vector of merged changes with net effect only.
 #[must_use]
 pub fn merge_changes(changes: &[IpChange], timestamp: std::time::SystemTime) -> Vec<IpChange> {
-    // Count net changes per (adapter, address)
-    let mut net_changes: HashMap<(&str, IpAddr), i32> = HashMap::new();
+    // Count net changes per (adapter, address), with the latest metadata
+    let mut net_changes: HashMap<(&str, IpAddr), (i32, Option<&AdapterMetadata>)> = HashMap::new();
 
     for change in changes {
         let key = (change.adapter.as_str(), change.address);
@@ -47,23 +52,23 @@ pub fn merge_changes(changes: &[IpChange], timestamp: std::time::SystemTime) ->
             IpChangeKind::Added => 1,
             IpChangeKind::Removed => -1,
         };
-        *net_changes.entry(key).or_insert(0) += delta;
+        let entry = net_changes.entry(key).or_insert((0, None));
+        entry.0 += delta;
+        entry.1 = change.metadata.as_ref();
     }
 
     // Convert net changes back to IpChange events
     let mut result = Vec::new();
-    for ((adapter, address), net) in net_changes {
-        match net.cmp(&0) {
-            std::cmp::Ordering::Greater => {
-                result.push(IpChange::added(adapter, address, timestamp));
-            }
-            std::cmp::Ordering::Less => {
-                result.push(IpChange::removed(adapter, address, timestamp));
-            }
+    for ((adapter, address), (net, metadata)) in net_changes {
+        let change = match net.cmp(&0) {
+            std::cmp::Ordering::Greater => IpChange::added(adapter, address, timestamp),
+            std::cmp::Ordering::Less => IpChange::removed(adapter, address, timestamp),
             std::cmp::Ordering::Equal => {
                 // Cancelled out - no change
+                continue;
             }
-        }
+        };
+        result.push(change.with_metadata(metadata.cloned()));
     }
 
     result
@@ -74,4 +79,6 @@ mod mod_tests;
 #[cfg(test)]
 mod monitor_tests;
 #[cfg(test)]
+mod schedule_tests;
+#[cfg(test)]
 mod stream_tests;
@@ -150,3 +150,23 @@ fn uses_provided_timestamp() {
 
     assert_eq!(result[0].timestamp, new_ts);
 }
+
+#[test]
+fn keeps_metadata_of_last_merged_change() {
+    let addr: IpAddr = "192.168.1.1".parse().unwrap();
+    let mtu = |mtu| {
+        Some(AdapterMetadata {
+            mtu: Some(mtu),
+            ..AdapterMetadata::default()
+        })
+    };
+    let changes = vec![
+        IpChange::added("eth0", addr, timestamp()).with_metadata(mtu(1500)),
+        IpChange::added("eth0", addr, timestamp()).with_metadata(mtu(9000)),
+    ];
+
+    let result = merge_changes(&changes, timestamp());
+
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].metadata, mtu(9000));
+}
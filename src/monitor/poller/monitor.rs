@@ -3,7 +3,7 @@
 //! This module provides [`PollingMonitor`], the builder/configuration struct
 //! for creating polling-based IP address monitors.
 
-use super::super::DebouncePolicy;
+use super::super::{AdaptivePolicy, DebouncePolicy};
 use super::stream::PollingStream;
 use crate::network::AddressFetcher;
 use crate::time::{Clock, SystemClock};
@@ -40,7 +40,10 @@ pub struct PollingMonitor<F, C = SystemClock> {
     fetcher: F,
     clock: C,
     interval: Duration,
+    ipv6_interval: Option<Duration>,
     debounce: Option<DebouncePolicy>,
+    adaptive: Option<AdaptivePolicy>,
+    fetch_timeout: Option<Duration>,
 }
 
 impl<F> PollingMonitor<F, SystemClock>
@@ -79,7 +82,10 @@ where
             fetcher,
             clock,
             interval,
+            ipv6_interval: None,
             debounce: None,
+            adaptive: None,
+            fetch_timeout: None,
         }
     }
 
@@ -98,18 +104,69 @@ where
         self
     }
 
+    /// Polls IPv6 addresses every `interval` instead of the polling interval.
+    ///
+    /// The polling interval then only applies to IPv4 addresses. A fetch on
+    /// one family's tick leaves the other family's addresses as they were
+    /// at its own last tick, so its changes are reported on that schedule.
+    #[must_use]
+    pub const fn with_ipv6_interval(mut self, interval: Duration) -> Self {
+        self.ipv6_interval = Some(interval);
+        self
+    }
+
+    /// Adapts the polling interval to how often addresses change.
+    ///
+    /// Polling starts at the polling interval, stretches towards the
+    /// policy's maximum while nothing changes and drops to its minimum after
+    /// a change. A separate IPv6 interval is not adapted.
+    #[must_use]
+    pub const fn with_adaptive(mut self, policy: AdaptivePolicy) -> Self {
+        self.adaptive = Some(policy);
+        self
+    }
+
+    /// Runs each fetch on the blocking thread pool, abandoning it after `timeout`.
+    ///
+    /// Without this, fetches run inline on the runtime thread polling the
+    /// stream, which a hung OS call would block indefinitely. Timed-out
+    /// fetches are treated like fetch errors and retried on the next tick.
+    #[must_use]
+    pub const fn with_fetch_timeout(mut self, timeout: Duration) -> Self {
+        self.fetch_timeout = Some(timeout);
+        self
+    }
+
     /// Returns the configured polling interval.
     #[must_use]
     pub const fn interval(&self) -> Duration {
         self.interval
     }
 
+    /// Returns the separate IPv6 polling interval, if configured.
+    #[must_use]
+    pub const fn ipv6_interval(&self) -> Option<Duration> {
+        self.ipv6_interval
+    }
+
     /// Returns the configured debounce policy, if any.
     #[must_use]
     pub const fn debounce(&self) -> Option<&DebouncePolicy> {
         self.debounce.as_ref()
     }
 
+    /// Returns the adaptive interval policy, if any.
+    #[must_use]
+    pub const fn adaptive(&self) -> Option<&AdaptivePolicy> {
+        self.adaptive.as_ref()
+    }
+
+    /// Returns the configured fetch timeout, if fetches run on the blocking pool.
+    #[must_use]
+    pub const fn fetch_timeout(&self) -> Option<Duration> {
+        self.fetch_timeout
+    }
+
     /// Converts this monitor into a stream of IP changes.
     ///
     /// The returned stream will poll at the configured interval and
@@ -118,7 +175,18 @@ where
     /// The stream never terminates on its own; use `take_until` with
     /// a shutdown signal to stop it gracefully.
     #[must_use]
-    pub fn into_stream(self) -> PollingStream<F, C> {
-        PollingStream::new(self.fetcher, self.clock, self.interval, self.debounce)
+    pub fn into_stream(self) -> PollingStream<F, C>
+    where
+        F: 'static,
+    {
+        PollingStream::new(
+            self.fetcher,
+            self.clock,
+            self.interval,
+            self.ipv6_interval,
+            self.debounce,
+            self.adaptive,
+            self.fetch_timeout,
+        )
     }
 }
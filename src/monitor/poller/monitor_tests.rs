@@ -1,7 +1,7 @@
 //! Tests for `PollingMonitor` configuration.
 
 use super::*;
-use crate::monitor::DebouncePolicy;
+use crate::monitor::{AdaptivePolicy, DebouncePolicy};
 use crate::network::{AdapterSnapshot, AddressFetcher, FetchError};
 use crate::time::Clock;
 use std::collections::VecDeque;
@@ -86,3 +86,44 @@ fn interval_accessor() {
 
     assert_eq!(monitor.interval(), Duration::from_secs(120));
 }
+
+#[test]
+fn fetch_timeout_none_by_default() {
+    let fetcher = MockFetcher::returning_snapshots(vec![]);
+    let monitor = PollingMonitor::new(fetcher, Duration::from_secs(60));
+
+    assert_eq!(monitor.fetch_timeout(), None);
+}
+
+#[test]
+fn with_fetch_timeout_sets_timeout() {
+    let fetcher = MockFetcher::returning_snapshots(vec![]);
+    let monitor = PollingMonitor::new(fetcher, Duration::from_secs(60))
+        .with_fetch_timeout(Duration::from_secs(10));
+
+    assert_eq!(monitor.fetch_timeout(), Some(Duration::from_secs(10)));
+}
+
+#[test]
+fn with_ipv6_interval_sets_interval() {
+    let fetcher = MockFetcher::returning_snapshots(vec![]);
+    let monitor = PollingMonitor::new(fetcher, Duration::from_secs(60));
+    assert_eq!(monitor.ipv6_interval(), None);
+
+    let monitor = monitor.with_ipv6_interval(Duration::from_secs(15));
+
+    assert_eq!(monitor.ipv6_interval(), Some(Duration::from_secs(15)));
+    assert_eq!(monitor.interval(), Duration::from_secs(60));
+}
+
+#[test]
+fn with_adaptive_sets_policy() {
+    let fetcher = MockFetcher::returning_snapshots(vec![]);
+    let monitor = PollingMonitor::new(fetcher, Duration::from_secs(60));
+    assert_eq!(monitor.adaptive(), None);
+
+    let policy = AdaptivePolicy::new(Duration::from_secs(60), Duration::from_secs(600));
+    let monitor = monitor.with_adaptive(policy);
+
+    assert_eq!(monitor.adaptive(), Some(&policy));
+}
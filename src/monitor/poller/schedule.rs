@@ -0,0 +1,51 @@
+//! Per-version refresh of polled snapshots.
+//!
+//! With a separate IPv6 poll interval, a fetch on one family's tick only
+//! refreshes that family: the other family's addresses are carried over
+//! from the previous snapshot, so each family changes at its own pace.
+
+use crate::network::{AdapterSnapshot, IpVersion};
+
+/// Combines a fetch that refreshes `refreshed` addresses with `previous`.
+///
+/// Addresses of the other family come from the same adapter in `previous`
+/// (none for new adapters). Adapters missing from `current` are kept for as
+/// long as they still hold addresses of the other family.
+pub(super) fn refresh(
+    previous: &[AdapterSnapshot],
+    current: Vec<AdapterSnapshot>,
+    refreshed: IpVersion,
+) -> Vec<AdapterSnapshot> {
+    let find = |name: &str| previous.iter().find(|adapter| adapter.name == name);
+
+    let mut merged: Vec<AdapterSnapshot> = current
+        .into_iter()
+        .map(|mut adapter| {
+            let before = find(&adapter.name);
+            if !refreshed.includes_v4() {
+                adapter.ipv4_addresses = before.map_or_else(Vec::new, |a| a.ipv4_addresses.clone());
+            }
+            if !refreshed.includes_v6() {
+                adapter.ipv6_addresses = before.map_or_else(Vec::new, |a| a.ipv6_addresses.clone());
+            }
+            adapter
+        })
+        .collect();
+
+    for adapter in previous {
+        if merged.iter().any(|a| a.name == adapter.name) {
+            continue;
+        }
+        let mut kept = adapter.clone();
+        if refreshed.includes_v4() {
+            kept.ipv4_addresses.clear();
+        }
+        if refreshed.includes_v6() {
+            kept.ipv6_addresses.clear();
+        }
+        if !kept.ipv4_addresses.is_empty() || !kept.ipv6_addresses.is_empty() {
+            merged.push(kept);
+        }
+    }
+    merged
+}
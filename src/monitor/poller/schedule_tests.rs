@@ -0,0 +1,51 @@
+//! Tests for per-version snapshot refresh.
+
+use super::schedule::refresh;
+use crate::network::{AdapterKind, AdapterSnapshot, IpVersion};
+
+fn snapshot(name: &str, ipv4: &[&str], ipv6: &[&str]) -> AdapterSnapshot {
+    AdapterSnapshot::new(
+        name,
+        AdapterKind::Ethernet,
+        ipv4.iter().map(|s| s.parse().unwrap()).collect(),
+        ipv6.iter().map(|s| s.parse().unwrap()).collect(),
+    )
+}
+
+#[test]
+fn keeps_other_family_from_previous() {
+    let previous = vec![snapshot("eth0", &["10.0.0.1"], &["2001:db8::1"])];
+    let current = vec![snapshot("eth0", &["10.0.0.2"], &["2001:db8::2"])];
+
+    assert_eq!(
+        refresh(&previous, current.clone(), IpVersion::V6),
+        vec![snapshot("eth0", &["10.0.0.1"], &["2001:db8::2"])]
+    );
+    assert_eq!(
+        refresh(&previous, current, IpVersion::V4),
+        vec![snapshot("eth0", &["10.0.0.2"], &["2001:db8::1"])]
+    );
+}
+
+#[test]
+fn new_adapter_waits_for_other_family() {
+    let current = vec![snapshot("eth1", &["10.0.0.1"], &["2001:db8::1"])];
+
+    assert_eq!(
+        refresh(&[], current, IpVersion::V6),
+        vec![snapshot("eth1", &[], &["2001:db8::1"])]
+    );
+}
+
+#[test]
+fn vanished_adapter_keeps_other_family() {
+    let previous = vec![
+        snapshot("eth0", &["10.0.0.1"], &["2001:db8::1"]),
+        snapshot("eth1", &[], &["2001:db8::2"]),
+    ];
+
+    assert_eq!(
+        refresh(&previous, Vec::new(), IpVersion::V6),
+        vec![snapshot("eth0", &["10.0.0.1"], &[])]
+    );
+}
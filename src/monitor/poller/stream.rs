@@ -3,24 +3,37 @@
 //! This module provides [`PollingStream`], a stream that periodically
 //! fetches network adapter snapshots and yields IP address changes.
 
-use super::super::DebouncePolicy;
 use super::super::change::{IpChange, diff};
-use crate::network::{AdapterSnapshot, AddressFetcher, FetchError};
-use crate::time::Clock;
+use super::super::fetch::FetchRunner;
+use super::super::{AdaptivePolicy, DebouncePolicy};
+use super::schedule;
+use crate::network::{AdapterSnapshot, AddressFetcher, IpVersion};
+use crate::time::{Clock, TimeJumpDetector};
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use std::time::Duration;
-use tokio::time::{Interval, interval};
+use tokio::time::{Instant, Interval, interval, interval_at};
 use tokio_stream::Stream;
 
 /// A stream of IP address changes produced by polling.
 ///
 /// This type is returned by [`super::PollingMonitor::into_stream`] and yields
 /// batches of [`IpChange`] events whenever changes are detected.
+///
+/// With an [`AdaptivePolicy`], the interval stretches while polls find no
+/// change and drops back to the minimum after one.
 pub struct PollingStream<F, C> {
-    fetcher: F,
+    fetcher: FetchRunner<F>,
     clock: C,
     interval: Interval,
+    /// Current period of `interval`
+    period: Duration,
+    /// Stretches `period` while stable, if adaptive
+    adaptive: Option<AdaptivePolicy>,
+    /// Separate IPv6 timer; `interval` then only refreshes IPv4
+    ipv6_interval: Option<Interval>,
+    /// Address families refreshed by the in-flight fetch
+    refreshing: IpVersion,
     debounce: Option<DebouncePolicy>,
     /// Previous snapshot for comparison
     prev_snapshot: Option<Vec<AdapterSnapshot>>,
@@ -28,27 +41,40 @@ pub struct PollingStream<F, C> {
     debounce_start: Option<tokio::time::Instant>,
     /// Snapshot taken at debounce start for final comparison
     debounce_baseline: Option<Vec<AdapterSnapshot>>,
+    /// Snapshot captured before the in-flight fetch (debounce baseline candidate)
+    pre_poll_snapshot: Option<Vec<AdapterSnapshot>>,
+    /// Detects wall-clock steps between fetches
+    time_jumps: TimeJumpDetector,
 }
 
 impl<F, C> PollingStream<F, C>
 where
-    F: AddressFetcher,
+    F: AddressFetcher + 'static,
     C: Clock,
 {
     pub(super) fn new(
         fetcher: F,
         clock: C,
         poll_interval: Duration,
+        ipv6_interval: Option<Duration>,
         debounce: Option<DebouncePolicy>,
+        adaptive: Option<AdaptivePolicy>,
+        fetch_timeout: Option<Duration>,
     ) -> Self {
         Self {
-            fetcher,
+            fetcher: FetchRunner::new(fetcher, fetch_timeout),
             clock,
             interval: interval(poll_interval),
+            period: poll_interval,
+            adaptive,
+            ipv6_interval: ipv6_interval.map(interval),
+            refreshing: IpVersion::Both,
             debounce,
             prev_snapshot: None,
             debounce_start: None,
             debounce_baseline: None,
+            pre_poll_snapshot: None,
+            time_jumps: TimeJumpDetector::default(),
         }
     }
 
@@ -60,18 +86,106 @@ where
         self.prev_snapshot.as_deref()
     }
 
-    /// Performs a single poll and returns changes if any.
-    fn poll_once(&mut self) -> Result<Vec<IpChange>, FetchError> {
-        let current = self.fetcher.fetch()?;
+    /// Replaces the poll interval, e.g. on a configuration reload.
+    ///
+    /// The next poll is due one new interval from now; the snapshot and an
+    /// open debounce window are kept.
+    pub fn set_poll_interval(&mut self, poll_interval: Duration) {
+        self.interval = interval_at(Instant::now() + poll_interval, poll_interval);
+        self.period = poll_interval;
+    }
+
+    /// Replaces the adaptive interval policy; `None` keeps the current
+    /// interval from now on.
+    pub const fn set_adaptive(&mut self, adaptive: Option<AdaptivePolicy>) {
+        self.adaptive = adaptive;
+    }
+
+    /// Returns the current polling interval, which changes over time when
+    /// adaptive.
+    #[must_use]
+    pub const fn poll_interval(&self) -> Duration {
+        self.period
+    }
+
+    /// Replaces the separate IPv6 poll interval; `None` refreshes both
+    /// families on the main interval.
+    pub fn set_ipv6_interval(&mut self, ipv6_interval: Option<Duration>) {
+        self.ipv6_interval =
+            ipv6_interval.map(|period| interval_at(Instant::now() + period, period));
+    }
+
+    /// Polls the timers, returning the address families due for a refresh.
+    fn poll_due(&mut self, cx: &mut Context<'_>) -> Option<IpVersion> {
+        let main = self.interval.poll_tick(cx).is_ready();
+        let Some(ipv6_interval) = self.ipv6_interval.as_mut() else {
+            return main.then_some(IpVersion::Both);
+        };
+
+        match (main, ipv6_interval.poll_tick(cx).is_ready()) {
+            (true, true) => Some(IpVersion::Both),
+            (true, false) => Some(IpVersion::V4),
+            (false, true) => Some(IpVersion::V6),
+            (false, false) => None,
+        }
+    }
+
+    /// Moves the main interval along the adaptive policy after a fetch.
+    ///
+    /// Only a poll on the main tick stretches it; a change seen on either
+    /// tick drops it to the minimum, where an open debounce window keeps it
+    /// until the window closes.
+    fn adapt_interval(&mut self, changed: bool) {
+        let Some(adaptive) = self.adaptive else {
+            return;
+        };
+        let changed = changed || self.debounce_start.is_some();
+        if !changed && self.refreshing == IpVersion::V6 {
+            return;
+        }
+
+        let period = adaptive.next(self.period, changed);
+        if period != self.period {
+            tracing::debug!("Poll interval adjusted to {}s", period.as_secs_f64());
+            self.set_poll_interval(period);
+        }
+    }
+
+    /// Records a fetched snapshot and returns changes since the previous one.
+    fn apply_snapshot(&mut self, current: Vec<AdapterSnapshot>) -> Vec<IpChange> {
+        self.check_time_jump();
         let timestamp = self.clock.now();
 
+        let current = match (&self.prev_snapshot, self.refreshing) {
+            (Some(prev), IpVersion::V4 | IpVersion::V6) => {
+                schedule::refresh(prev, current, self.refreshing)
+            }
+            _ => current,
+        };
+
         let changes = self
             .prev_snapshot
             .as_ref()
             .map_or_else(Vec::new, |prev| diff(prev, &current, timestamp));
 
         self.prev_snapshot = Some(current);
-        Ok(changes)
+        changes
+    }
+
+    /// Logs a wall-clock jump and restarts any open debounce window.
+    ///
+    /// Timestamps around a jump are misleading, so the window is measured
+    /// afresh from the first fetch after it.
+    fn check_time_jump(&mut self) {
+        let now = tokio::time::Instant::now();
+        let Some(jump) = self.time_jumps.observe(&self.clock, now) else {
+            return;
+        };
+
+        tracing::warn!("{jump}; change timestamps around this point may be misleading");
+        if self.debounce_start.is_some() {
+            self.debounce_start = Some(now);
+        }
     }
 
     /// Handles debounce logic, returning changes to emit (if any).
@@ -135,35 +249,49 @@ where
 
 impl<F, C> Stream for PollingStream<F, C>
 where
-    F: AddressFetcher + Unpin,
+    F: AddressFetcher + Unpin + 'static,
     C: Clock + Unpin,
 {
     type Item = Vec<IpChange>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         loop {
-            // Poll the interval timer - registers waker for next tick when Pending
-            if Pin::new(&mut self.interval).poll_tick(cx).is_pending() {
-                return Poll::Pending;
+            // A fetch already running on the blocking pool resumes without a new tick
+            if !self.fetcher.is_in_flight() {
+                // Poll the interval timers - registers wakers for next ticks when Pending
+                let Some(refreshing) = self.poll_due(cx) else {
+                    return Poll::Pending;
+                };
+                self.refreshing = refreshing;
+
+                // Capture snapshot BEFORE the fetch updates it (needed for debounce baseline)
+                // Only clone when we might start debouncing (entering debounce mode)
+                self.pre_poll_snapshot = if self.debounce.is_some() && self.debounce_start.is_none()
+                {
+                    self.prev_snapshot.clone()
+                } else {
+                    None
+                };
             }
 
-            // Capture snapshot BEFORE poll_once updates it (needed for debounce baseline)
-            // Only clone when we might start debouncing (entering debounce mode)
-            let pre_poll_snapshot = if self.debounce.is_some() && self.debounce_start.is_none() {
-                self.prev_snapshot.clone()
-            } else {
-                None
+            // Interval ticked - perform a poll
+            let Poll::Ready(result) = self.fetcher.poll_fetch(cx) else {
+                return Poll::Pending;
             };
+            let pre_poll_snapshot = self.pre_poll_snapshot.take();
 
-            // Interval ticked - perform a poll
             // Fetch errors are intentionally swallowed for resilient polling:
             // transient network/system errors should not terminate the stream.
-            let Ok(changes) = self.poll_once() else {
+            let Ok(current) = result else {
                 // Error occurred - loop back to re-register waker via poll_tick
                 continue;
             };
+            let changes = self.apply_snapshot(current);
+            let has_changes = !changes.is_empty();
 
-            if let Some(result) = self.process_with_debounce(changes, pre_poll_snapshot) {
+            let result = self.process_with_debounce(changes, pre_poll_snapshot);
+            self.adapt_interval(has_changes);
+            if let Some(result) = result {
                 return Poll::Ready(Some(result));
             }
             // No changes to emit - loop back to re-register waker via poll_tick
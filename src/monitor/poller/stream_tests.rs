@@ -1,7 +1,7 @@
 //! Tests for `PollingStream` behavior.
 
 use super::*;
-use crate::monitor::{DebouncePolicy, IpChange};
+use crate::monitor::{AdaptivePolicy, DebouncePolicy, IpChange};
 use crate::network::{AdapterKind, AdapterSnapshot, AddressFetcher, FetchError};
 use crate::time::Clock;
 use std::collections::VecDeque;
@@ -316,3 +316,219 @@ async fn debounce_no_changes_no_emit() {
             .any(|c| c.address.to_string() == "10.0.0.1")
     );
 }
+
+#[tokio::test]
+async fn emits_changes_with_fetch_timeout() {
+    let fetcher = MockFetcher::returning_snapshots(vec![
+        vec![make_snapshot("eth0", vec!["192.168.1.1"], vec![])],
+        vec![make_snapshot("eth0", vec!["192.168.1.2"], vec![])],
+    ]);
+    let clock = MockClock::new(0);
+
+    let monitor = PollingMonitor::with_clock(fetcher, clock, Duration::from_millis(5))
+        .with_fetch_timeout(Duration::from_secs(5));
+    let stream = monitor.into_stream();
+
+    let changes: Vec<_> = stream.take(1).collect().await;
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].len(), 2);
+}
+
+#[tokio::test]
+async fn continues_after_fetch_timeout() {
+    /// Blocks past the timeout on its second call only.
+    struct SlowSecondFetch(std::sync::atomic::AtomicUsize);
+
+    impl AddressFetcher for SlowSecondFetch {
+        fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+            let call = self.0.fetch_add(1, Ordering::SeqCst);
+            if call == 1 {
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            let ip = if call == 0 {
+                "192.168.1.1"
+            } else {
+                "192.168.1.2"
+            };
+            Ok(vec![make_snapshot("eth0", vec![ip], vec![])])
+        }
+    }
+
+    let fetcher = SlowSecondFetch(std::sync::atomic::AtomicUsize::new(0));
+    let monitor = PollingMonitor::with_clock(fetcher, MockClock::new(0), Duration::from_millis(5))
+        .with_fetch_timeout(Duration::from_millis(20));
+    let stream = monitor.into_stream();
+
+    // The timed-out call is abandoned; a later fetch reports the change
+    let changes: Vec<_> = stream.take(1).collect().await;
+    assert_eq!(changes.len(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn time_jump_restarts_debounce_window() {
+    /// Wall clock shared with the fetcher so a fetch can step it.
+    struct SharedClock(std::sync::Arc<AtomicU64>);
+
+    impl Clock for SharedClock {
+        fn now(&self) -> SystemTime {
+            SystemTime::UNIX_EPOCH + Duration::from_secs(self.0.load(Ordering::SeqCst))
+        }
+    }
+
+    /// Steps the wall clock forward by an hour during its third fetch.
+    struct JumpingFetcher {
+        inner: MockFetcher,
+        calls: std::sync::atomic::AtomicUsize,
+        secs: std::sync::Arc<AtomicU64>,
+    }
+
+    impl AddressFetcher for JumpingFetcher {
+        fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) == 2 {
+                self.secs.fetch_add(3600, Ordering::SeqCst);
+            }
+            self.inner.fetch()
+        }
+    }
+
+    let before = make_snapshot("eth0", vec!["192.168.1.1"], vec![]);
+    let after = make_snapshot("eth0", vec!["192.168.1.2"], vec![]);
+    let secs = std::sync::Arc::new(AtomicU64::new(0));
+    let fetcher = JumpingFetcher {
+        inner: MockFetcher::returning_snapshots(vec![
+            vec![before],        // t=0: baseline
+            vec![after.clone()], // t=100: change, debounce window opens
+            vec![after.clone()], // t=200: clock jumps, window restarts
+            vec![after.clone()],
+            vec![after.clone()],
+            vec![after],
+        ]),
+        calls: std::sync::atomic::AtomicUsize::new(0),
+        secs: secs.clone(),
+    };
+
+    let monitor =
+        PollingMonitor::with_clock(fetcher, SharedClock(secs), Duration::from_millis(100))
+            .with_debounce(DebouncePolicy::new(Duration::from_millis(250)));
+    let start = tokio::time::Instant::now();
+    let changes: Vec<_> = monitor.into_stream().take(1).collect().await;
+
+    // Without the jump the window would close at t=400
+    assert_eq!(changes[0].len(), 2);
+    assert_eq!(start.elapsed(), Duration::from_millis(500));
+}
+
+#[tokio::test(start_paused = true)]
+async fn ipv6_interval_reports_each_family_on_its_own_tick() {
+    let before = make_snapshot("eth0", vec!["192.168.1.1"], vec!["2001:db8::1"]);
+    let after = make_snapshot("eth0", vec!["192.168.1.2"], vec!["2001:db8::2"]);
+    // Fetches at 0ms (both), 10..=90ms (IPv6 only) and 100ms (both)
+    let mut snapshots = vec![vec![before]];
+    snapshots.extend(std::iter::repeat_n(vec![after], 10));
+    let fetcher = MockFetcher::returning_snapshots(snapshots);
+
+    let monitor =
+        PollingMonitor::with_clock(fetcher, MockClock::new(0), Duration::from_millis(100))
+            .with_ipv6_interval(Duration::from_millis(10));
+    let mut stream = monitor.into_stream();
+    let start = tokio::time::Instant::now();
+
+    let first = stream.next().await.unwrap();
+    assert_eq!(start.elapsed(), Duration::from_millis(10));
+    assert!(first.iter().all(|c| c.address.is_ipv6()));
+    assert_eq!(first.len(), 2);
+
+    let second = stream.next().await.unwrap();
+    assert_eq!(start.elapsed(), Duration::from_millis(100));
+    assert!(second.iter().all(|c| c.address.is_ipv4()));
+    assert_eq!(second.len(), 2);
+    assert_eq!(
+        stream.current_snapshot().unwrap()[0],
+        make_snapshot("eth0", vec!["192.168.1.2"], vec!["2001:db8::2"])
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn set_poll_interval_reschedules_and_keeps_snapshot() {
+    let before = make_snapshot("eth0", vec!["192.168.1.1"], vec![]);
+    let after = make_snapshot("eth0", vec!["192.168.1.2"], vec![]);
+    let fetcher = MockFetcher::returning_snapshots(vec![vec![before.clone()], vec![after]]);
+
+    let monitor = PollingMonitor::with_clock(fetcher, MockClock::new(0), Duration::from_secs(3600));
+    let mut stream = monitor.into_stream();
+    let start = tokio::time::Instant::now();
+    // The first tick fetches the baseline at once
+    assert!(
+        tokio::time::timeout(Duration::from_millis(1), stream.next())
+            .await
+            .is_err()
+    );
+    assert_eq!(stream.current_snapshot(), Some(&[before][..]));
+
+    stream.set_poll_interval(Duration::from_millis(50));
+    let changes = stream.next().await.unwrap();
+
+    assert_eq!(changes.len(), 2);
+    assert_eq!(start.elapsed(), Duration::from_millis(51));
+}
+
+#[tokio::test(start_paused = true)]
+async fn adaptive_interval_stretches_while_stable_and_drops_after_a_change() {
+    let before = make_snapshot("eth0", vec!["192.168.1.1"], vec![]);
+    let after = make_snapshot("eth0", vec!["192.168.1.2"], vec![]);
+    // Fetches at 0ms, 150ms, 375ms (change) and 475ms (change back)
+    let fetcher = MockFetcher::returning_snapshots(vec![
+        vec![before.clone()],
+        vec![before.clone()],
+        vec![after],
+        vec![before],
+    ]);
+
+    let policy = AdaptivePolicy::new(Duration::from_millis(100), Duration::from_millis(400));
+    let monitor =
+        PollingMonitor::with_clock(fetcher, MockClock::new(0), Duration::from_millis(100))
+            .with_adaptive(policy);
+    let mut stream = monitor.into_stream();
+    let start = tokio::time::Instant::now();
+
+    let first = stream.next().await.unwrap();
+    assert_eq!(start.elapsed(), Duration::from_millis(375));
+    assert_eq!(first.len(), 2);
+    assert_eq!(stream.poll_interval(), Duration::from_millis(100));
+
+    let second = stream.next().await.unwrap();
+    assert_eq!(start.elapsed(), Duration::from_millis(475));
+    assert_eq!(second.len(), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn adaptive_interval_stops_at_max() {
+    let fetcher = MockFetcher::returning_snapshots(vec![]);
+
+    let policy = AdaptivePolicy::new(Duration::from_millis(10), Duration::from_millis(40));
+    let monitor = PollingMonitor::with_clock(fetcher, MockClock::new(0), Duration::from_millis(10))
+        .with_adaptive(policy);
+    let mut stream = monitor.into_stream();
+
+    assert!(
+        tokio::time::timeout(Duration::from_secs(1), stream.next())
+            .await
+            .is_err()
+    );
+    assert_eq!(stream.poll_interval(), Duration::from_millis(40));
+}
+
+#[tokio::test(start_paused = true)]
+async fn without_adaptive_interval_stays_fixed() {
+    let fetcher = MockFetcher::returning_snapshots(vec![]);
+
+    let monitor = PollingMonitor::with_clock(fetcher, MockClock::new(0), Duration::from_millis(10));
+    let mut stream = monitor.into_stream();
+
+    assert!(
+        tokio::time::timeout(Duration::from_secs(1), stream.next())
+            .await
+            .is_err()
+    );
+    assert_eq!(stream.poll_interval(), Duration::from_millis(10));
+}
@@ -0,0 +1,270 @@
+//! IPv6 address storm detection.
+//!
+//! Privacy extensions that churn too fast or a misbehaving router sending
+//! bursts of Router Advertisements can pile dozens of SLAAC addresses onto
+//! an adapter, each one a change to deliver. [`AddressStormFetcher`] counts
+//! the IPv6 addresses of every adapter and reports an [`AddressStorm`] once
+//! the count exceeds the adapter's limit and once it is back within it, as
+//! [`StormChange`]s separate from the address changes the streams yield.
+//! It can also switch a storming adapter to prefix-only mode, in which its
+//! IPv6 addresses are reported as their /64 prefixes.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::net::Ipv6Addr;
+use std::sync::{Mutex, PoisonError};
+use std::time::SystemTime;
+
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::network::{AdapterSnapshot, AddressFetcher, FetchError};
+use crate::time::{Clock, SystemClock};
+
+/// Per-adapter limits on the number of IPv6 addresses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StormPolicy {
+    max_addresses: usize,
+    adapter_limits: BTreeMap<String, usize>,
+    prefix_only: bool,
+}
+
+impl StormPolicy {
+    /// Creates a policy allowing `max_addresses` IPv6 addresses per adapter.
+    #[must_use]
+    pub const fn new(max_addresses: usize) -> Self {
+        Self {
+            max_addresses,
+            adapter_limits: BTreeMap::new(),
+            prefix_only: false,
+        }
+    }
+
+    /// Allows the adapter named `adapter` (exact name) `limit` addresses
+    /// instead.
+    #[must_use]
+    pub fn with_adapter_limit(mut self, adapter: impl Into<String>, limit: usize) -> Self {
+        self.adapter_limits.insert(adapter.into(), limit);
+        self
+    }
+
+    /// Sets whether a storming adapter switches to prefix-only mode.
+    #[must_use]
+    pub const fn with_prefix_only(mut self, prefix_only: bool) -> Self {
+        self.prefix_only = prefix_only;
+        self
+    }
+
+    /// Returns the limit of the adapter named `adapter`.
+    #[must_use]
+    pub fn limit_for(&self, adapter: &str) -> usize {
+        self.adapter_limits
+            .get(adapter)
+            .copied()
+            .unwrap_or(self.max_addresses)
+    }
+
+    /// Returns true if a storming adapter switches to prefix-only mode.
+    #[must_use]
+    pub const fn prefix_only(&self) -> bool {
+        self.prefix_only
+    }
+}
+
+/// An adapter carrying more IPv6 addresses than its limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressStorm {
+    /// The adapter concerned.
+    pub adapter: String,
+    /// Its number of counted IPv6 addresses.
+    pub count: usize,
+    /// The limit that applies to it.
+    pub limit: usize,
+}
+
+/// A storm that started or was resolved between two fetches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StormChange {
+    /// The storm concerned; when resolved, with the count that ended it.
+    pub storm: AddressStorm,
+    /// Whether the storm ended (`false` when it started).
+    pub resolved: bool,
+    /// Whether the adapter's IPv6 addresses are reported as /64 prefixes.
+    pub prefix_only: bool,
+    /// The timestamp when the change was detected.
+    pub timestamp: SystemTime,
+}
+
+impl fmt::Display for StormChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let AddressStorm {
+            adapter,
+            count,
+            limit,
+        } = &self.storm;
+        if self.resolved {
+            write!(
+                f,
+                "{adapter}: {count} IPv6 addresses, back within the limit of {limit}"
+            )?;
+        } else {
+            write!(
+                f,
+                "{adapter}: {count} IPv6 addresses exceed the limit of {limit}"
+            )?;
+        }
+        if self.prefix_only {
+            write!(f, " (reporting /64 prefixes only)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the number of IPv6 addresses of `adapter` counted against its
+/// limit: all but loopback, link-local and unspecified addresses.
+#[must_use]
+pub fn count_addresses(adapter: &AdapterSnapshot) -> usize {
+    adapter
+        .ipv6_addresses
+        .iter()
+        .filter(|&&address| is_counted(address))
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+const fn is_counted(address: Ipv6Addr) -> bool {
+    !address.is_loopback() && !address.is_unicast_link_local() && !address.is_unspecified()
+}
+
+/// Replaces the counted IPv6 addresses of `adapter` by their /64 prefixes,
+/// in order of first appearance.
+fn reduce_to_prefixes(adapter: &mut AdapterSnapshot) {
+    let mut seen = HashSet::new();
+    let addresses = std::mem::take(&mut adapter.ipv6_addresses);
+    adapter.ipv6_addresses = addresses
+        .into_iter()
+        .map(|address| {
+            if is_counted(address) {
+                Ipv6Addr::from(address.to_bits() & !u128::from(u64::MAX))
+            } else {
+                address
+            }
+        })
+        .filter(|address| seen.insert(*address))
+        .collect();
+}
+
+/// Storms in progress and the adapters in prefix-only mode.
+#[derive(Debug, Default)]
+struct StormState {
+    storms: HashSet<String>,
+    prefix_only: HashSet<String>,
+}
+
+/// An [`AddressFetcher`] decorator that reports IPv6 address storms.
+///
+/// Every successful fetch counts the IPv6 addresses of each adapter; storms
+/// that started or were resolved since the previous fetch are sent as one
+/// batch on `events`. An adapter that disappears ends its storm. With
+/// [`StormPolicy::with_prefix_only`], an adapter stays in prefix-only mode
+/// from its first storm on, so the end of the storm does not flood the
+/// streams with its addresses again. Without a policy nothing is counted.
+#[derive(Debug)]
+pub struct AddressStormFetcher<F, C = SystemClock> {
+    inner: F,
+    policy: Option<StormPolicy>,
+    events: UnboundedSender<Vec<StormChange>>,
+    clock: C,
+    state: Mutex<StormState>,
+}
+
+impl<F> AddressStormFetcher<F, SystemClock> {
+    /// Wraps `inner`, sending storms beyond `policy`'s limits on `events`.
+    #[must_use]
+    pub fn new(
+        inner: F,
+        policy: Option<StormPolicy>,
+        events: UnboundedSender<Vec<StormChange>>,
+    ) -> Self {
+        Self::with_clock(inner, policy, events, SystemClock)
+    }
+}
+
+impl<F, C> AddressStormFetcher<F, C> {
+    /// Wraps `inner` with a custom clock for change timestamps.
+    #[must_use]
+    pub fn with_clock(
+        inner: F,
+        policy: Option<StormPolicy>,
+        events: UnboundedSender<Vec<StormChange>>,
+        clock: C,
+    ) -> Self {
+        Self {
+            inner,
+            policy,
+            events,
+            clock,
+            state: Mutex::new(StormState::default()),
+        }
+    }
+}
+
+impl<F: AddressFetcher, C: Clock> AddressFetcher for AddressStormFetcher<F, C> {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        let mut current = self.inner.fetch()?;
+        let Some(policy) = &self.policy else {
+            return Ok(current);
+        };
+
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let timestamp = self.clock.now();
+        let change = |adapter: &str, count, resolved| StormChange {
+            storm: AddressStorm {
+                adapter: adapter.to_string(),
+                count,
+                limit: policy.limit_for(adapter),
+            },
+            resolved,
+            prefix_only: policy.prefix_only(),
+            timestamp,
+        };
+
+        let mut previous = std::mem::take(&mut state.storms);
+        let mut changes = Vec::new();
+        for adapter in &current {
+            let count = count_addresses(adapter);
+            let was_storming = previous.remove(&adapter.name);
+            if count > policy.limit_for(&adapter.name) {
+                state.storms.insert(adapter.name.clone());
+                if !was_storming {
+                    changes.push(change(&adapter.name, count, false));
+                }
+            } else if was_storming {
+                changes.push(change(&adapter.name, count, true));
+            }
+        }
+        // Storms of adapters that disappeared are over
+        let mut gone: Vec<_> = previous.into_iter().collect();
+        gone.sort();
+        changes.extend(gone.iter().map(|adapter| change(adapter, 0, true)));
+
+        if policy.prefix_only() {
+            let state = &mut *state;
+            state.prefix_only.extend(state.storms.iter().cloned());
+            for adapter in current
+                .iter_mut()
+                .filter(|a| state.prefix_only.contains(&a.name))
+            {
+                reduce_to_prefixes(adapter);
+            }
+        }
+
+        drop(state);
+
+        if !changes.is_empty() {
+            // A closed receiver means nobody listens any more; drop the batch
+            let _ = self.events.send(changes);
+        }
+
+        Ok(current)
+    }
+}
@@ -0,0 +1,202 @@
+//! Tests for IPv6 address storm detection.
+
+use super::storm::{AddressStorm, AddressStormFetcher, StormChange, StormPolicy, count_addresses};
+use crate::network::{AdapterKind, AdapterSnapshot, AddressFetcher, FetchError};
+use crate::time::Clock;
+use std::collections::VecDeque;
+use std::net::Ipv6Addr;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+
+struct FixedClock;
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(100)
+    }
+}
+
+/// Fetcher returning queued snapshots in order.
+struct SequenceFetcher(Mutex<VecDeque<Vec<AdapterSnapshot>>>);
+
+impl SequenceFetcher {
+    fn new(snapshots: impl IntoIterator<Item = Vec<AdapterSnapshot>>) -> Self {
+        Self(Mutex::new(snapshots.into_iter().collect()))
+    }
+}
+
+impl AddressFetcher for SequenceFetcher {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        Ok(self.0.lock().unwrap().pop_front().unwrap())
+    }
+}
+
+/// `name` with `count` SLAAC addresses in `2001:db8:1::/64` and a link-local one.
+fn adapter(name: &str, count: u16) -> AdapterSnapshot {
+    let mut v6: Vec<Ipv6Addr> = (1..=count)
+        .map(|i| Ipv6Addr::new(0x2001, 0xdb8, 1, 0, 0, 0, 0, i))
+        .collect();
+    v6.push("fe80::1".parse().unwrap());
+    AdapterSnapshot::new(name, AdapterKind::Ethernet, vec![], v6)
+}
+
+fn change(adapter: &str, count: usize, limit: usize, resolved: bool) -> StormChange {
+    StormChange {
+        storm: AddressStorm {
+            adapter: adapter.to_string(),
+            count,
+            limit,
+        },
+        resolved,
+        prefix_only: false,
+        timestamp: UNIX_EPOCH + Duration::from_secs(100),
+    }
+}
+
+fn fetcher(
+    policy: Option<StormPolicy>,
+    snapshots: impl IntoIterator<Item = Vec<AdapterSnapshot>>,
+) -> (
+    AddressStormFetcher<SequenceFetcher, FixedClock>,
+    mpsc::UnboundedReceiver<Vec<StormChange>>,
+) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let fetcher =
+        AddressStormFetcher::with_clock(SequenceFetcher::new(snapshots), policy, tx, FixedClock);
+    (fetcher, rx)
+}
+
+#[test]
+fn counts_only_assigned_addresses_once() {
+    let mut snapshot = adapter("eth0", 3);
+    snapshot.ipv6_addresses.push(snapshot.ipv6_addresses[0]);
+    snapshot.ipv6_addresses.push(Ipv6Addr::LOCALHOST);
+
+    assert_eq!(count_addresses(&snapshot), 3);
+}
+
+#[test]
+fn adapter_limit_overrides_default() {
+    let policy = StormPolicy::new(8).with_adapter_limit("wlan0", 32);
+
+    assert_eq!(policy.limit_for("eth0"), 8);
+    assert_eq!(policy.limit_for("wlan0"), 32);
+}
+
+#[test]
+fn reports_storm_once_when_it_starts_and_ends() {
+    let (fetcher, mut rx) = fetcher(
+        Some(StormPolicy::new(4)),
+        [
+            vec![adapter("eth0", 2)],
+            vec![adapter("eth0", 6)],
+            vec![adapter("eth0", 9)],
+            vec![adapter("eth0", 4)],
+        ],
+    );
+
+    fetcher.fetch().unwrap();
+    assert!(rx.try_recv().is_err());
+
+    fetcher.fetch().unwrap();
+    assert_eq!(rx.try_recv().unwrap(), vec![change("eth0", 6, 4, false)]);
+
+    fetcher.fetch().unwrap();
+    assert!(rx.try_recv().is_err());
+
+    fetcher.fetch().unwrap();
+    assert_eq!(rx.try_recv().unwrap(), vec![change("eth0", 4, 4, true)]);
+}
+
+#[test]
+fn storm_found_by_first_fetch_is_reported() {
+    let (fetcher, mut rx) = fetcher(Some(StormPolicy::new(4)), [vec![adapter("eth0", 5)]]);
+
+    fetcher.fetch().unwrap();
+
+    assert_eq!(rx.try_recv().unwrap(), vec![change("eth0", 5, 4, false)]);
+}
+
+#[test]
+fn tracks_adapters_separately() {
+    let policy = StormPolicy::new(4).with_adapter_limit("wlan0", 8);
+    let (fetcher, mut rx) = fetcher(
+        Some(policy),
+        [vec![adapter("eth0", 5), adapter("wlan0", 5)]],
+    );
+
+    fetcher.fetch().unwrap();
+
+    assert_eq!(rx.try_recv().unwrap(), vec![change("eth0", 5, 4, false)]);
+}
+
+#[test]
+fn disappeared_adapter_ends_storm() {
+    let (fetcher, mut rx) = fetcher(
+        Some(StormPolicy::new(4)),
+        [vec![adapter("eth0", 5)], vec![]],
+    );
+
+    fetcher.fetch().unwrap();
+    rx.try_recv().unwrap();
+    fetcher.fetch().unwrap();
+
+    assert_eq!(rx.try_recv().unwrap(), vec![change("eth0", 0, 4, true)]);
+}
+
+#[test]
+fn without_policy_passes_snapshots_through() {
+    let (fetcher, mut rx) = fetcher(None, [vec![adapter("eth0", 50)]]);
+
+    assert_eq!(fetcher.fetch().unwrap(), vec![adapter("eth0", 50)]);
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn prefix_only_reduces_storming_adapter_until_restart() {
+    let policy = StormPolicy::new(4).with_prefix_only(true);
+    let (fetcher, mut rx) = fetcher(
+        Some(policy),
+        [
+            vec![adapter("eth0", 2), adapter("eth1", 2)],
+            vec![adapter("eth0", 6), adapter("eth1", 2)],
+            vec![adapter("eth0", 3), adapter("eth1", 2)],
+        ],
+    );
+    let prefix: Ipv6Addr = "2001:db8:1::".parse().unwrap();
+    let link_local: Ipv6Addr = "fe80::1".parse().unwrap();
+
+    // Before the storm, addresses are passed through
+    assert_eq!(fetcher.fetch().unwrap()[0].ipv6_addresses.len(), 3);
+
+    let during = fetcher.fetch().unwrap();
+    assert_eq!(during[0].ipv6_addresses, vec![prefix, link_local]);
+    assert_eq!(during[1], adapter("eth1", 2));
+    let started = rx.try_recv().unwrap();
+    assert!(started[0].prefix_only);
+
+    // The mode outlasts the storm
+    let after = fetcher.fetch().unwrap();
+    assert_eq!(after[0].ipv6_addresses, vec![prefix, link_local]);
+    assert!(rx.try_recv().unwrap()[0].resolved);
+}
+
+#[test]
+fn display_describes_storm() {
+    let mut started = change("eth0", 40, 16, false);
+    assert_eq!(
+        started.to_string(),
+        "eth0: 40 IPv6 addresses exceed the limit of 16"
+    );
+
+    started.prefix_only = true;
+    assert_eq!(
+        started.to_string(),
+        "eth0: 40 IPv6 addresses exceed the limit of 16 (reporting /64 prefixes only)"
+    );
+    assert_eq!(
+        change("eth0", 3, 16, true).to_string(),
+        "eth0: 3 IPv6 addresses, back within the limit of 16"
+    );
+}
@@ -0,0 +1,68 @@
+//! Human-readable summaries of change batches.
+//!
+//! Operators read these lines constantly, so [`summarize`] coalesces a batch
+//! into one line per adapter and shows an address swap within a family as a
+//! single replacement rather than an unrelated removal and addition.
+
+use super::change::IpChange;
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+
+/// Additions and removals of one address family on one adapter.
+#[derive(Default)]
+struct FamilyChanges {
+    added: Vec<IpAddr>,
+    removed: Vec<IpAddr>,
+}
+
+impl FamilyChanges {
+    /// Appends this family's parts to `parts`, pairing removals with additions.
+    fn describe(&self, family: &str, parts: &mut Vec<String>) {
+        let replaced = self.removed.len().min(self.added.len());
+
+        for (old, new) in self.removed.iter().zip(&self.added) {
+            parts.push(format!("{old} → {new} ({family})"));
+        }
+        for addr in &self.added[replaced..] {
+            parts.push(format!("+{addr} added"));
+        }
+        for addr in &self.removed[replaced..] {
+            parts.push(format!("-{addr} removed"));
+        }
+    }
+}
+
+/// Summarizes a batch of changes as one line per adapter, sorted by adapter name.
+///
+/// Within each adapter, a removed and an added address of the same family
+/// are shown as a replacement (`old → new (IPv4)`); unpaired changes are
+/// shown as `+addr added` or `-addr removed`. Parts are joined with `; `:
+///
+/// ```text
+/// eth0: 192.0.2.5 → 192.0.2.9 (IPv4); +2001:db8::1 added
+/// ```
+#[must_use]
+pub fn summarize(changes: &[IpChange]) -> Vec<String> {
+    let mut by_adapter: BTreeMap<&str, (FamilyChanges, FamilyChanges)> = BTreeMap::new();
+
+    for change in changes {
+        let (v4, v6) = by_adapter.entry(change.adapter.as_str()).or_default();
+        let family = if change.is_ipv4() { v4 } else { v6 };
+        if change.is_added() {
+            family.added.push(change.address);
+        } else {
+            family.removed.push(change.address);
+        }
+    }
+
+    by_adapter
+        .into_iter()
+        .map(|(adapter, (v4, v6))| {
+            let mut parts = Vec::new();
+            v4.describe("IPv4", &mut parts);
+            v6.describe("IPv6", &mut parts);
+
+            format!("{adapter}: {}", parts.join("; "))
+        })
+        .collect()
+}
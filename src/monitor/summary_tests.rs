@@ -0,0 +1,89 @@
+//! Tests for change batch summaries.
+
+use super::summary::summarize;
+use crate::monitor::IpChange;
+use std::net::IpAddr;
+use std::time::SystemTime;
+
+fn added(adapter: &str, addr: &str) -> IpChange {
+    IpChange::added(
+        adapter,
+        addr.parse::<IpAddr>().unwrap(),
+        SystemTime::UNIX_EPOCH,
+    )
+}
+
+fn removed(adapter: &str, addr: &str) -> IpChange {
+    IpChange::removed(
+        adapter,
+        addr.parse::<IpAddr>().unwrap(),
+        SystemTime::UNIX_EPOCH,
+    )
+}
+
+#[test]
+fn empty_batch_has_no_lines() {
+    assert!(summarize(&[]).is_empty());
+}
+
+#[test]
+fn replacement_and_addition_on_one_line() {
+    let changes = [
+        removed("eth0", "192.0.2.5"),
+        added("eth0", "2001:db8::1"),
+        added("eth0", "192.0.2.9"),
+    ];
+
+    assert_eq!(
+        summarize(&changes),
+        vec!["eth0: 192.0.2.5 → 192.0.2.9 (IPv4); +2001:db8::1 added"]
+    );
+}
+
+#[test]
+fn unpaired_removal() {
+    let changes = [removed("eth0", "2001:db8::1")];
+
+    assert_eq!(summarize(&changes), vec!["eth0: -2001:db8::1 removed"]);
+}
+
+#[test]
+fn families_are_not_paired_with_each_other() {
+    let changes = [removed("eth0", "192.0.2.5"), added("eth0", "2001:db8::1")];
+
+    assert_eq!(
+        summarize(&changes),
+        vec!["eth0: -192.0.2.5 removed; +2001:db8::1 added"]
+    );
+}
+
+#[test]
+fn surplus_additions_follow_replacement() {
+    let changes = [
+        removed("eth0", "2001:db8::1"),
+        added("eth0", "2001:db8::2"),
+        added("eth0", "2001:db8::3"),
+    ];
+
+    assert_eq!(
+        summarize(&changes),
+        vec!["eth0: 2001:db8::1 → 2001:db8::2 (IPv6); +2001:db8::3 added"]
+    );
+}
+
+#[test]
+fn one_line_per_adapter_sorted_by_name() {
+    let changes = [
+        added("wlan0", "192.0.2.7"),
+        added("eth0", "192.0.2.5"),
+        removed("wlan0", "192.0.2.6"),
+    ];
+
+    assert_eq!(
+        summarize(&changes),
+        vec![
+            "eth0: +192.0.2.5 added",
+            "wlan0: 192.0.2.6 → 192.0.2.7 (IPv4)",
+        ]
+    );
+}
@@ -0,0 +1,86 @@
+//! Minimum interval between change notifications.
+
+use std::time::{Duration, Instant};
+
+use super::change::IpChange;
+use super::poller::merge_changes;
+
+/// Holds change batches so notifications are at least an interval apart.
+///
+/// The first batch is passed through at once; batches arriving within the
+/// interval after a notification are held and coalesced with
+/// [`merge_changes`], so a flapping adapter costs one notification per
+/// interval, sent when it ends. Changes cancelling out send nothing.
+#[derive(Debug, Clone)]
+pub struct NotifyThrottle {
+    interval: Duration,
+    last_notified: Option<Instant>,
+    pending: Vec<IpChange>,
+}
+
+impl NotifyThrottle {
+    /// Creates a throttle allowing one notification per `interval`.
+    #[must_use]
+    pub const fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_notified: None,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Returns the minimum interval between notifications.
+    #[must_use]
+    pub const fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Offers `changes` detected at `now`, returning them if they can be
+    /// sent at once, or `None` if they are held until [`deadline`](Self::deadline).
+    pub fn offer(&mut self, changes: Vec<IpChange>, now: Instant) -> Option<Vec<IpChange>> {
+        let due = self
+            .last_notified
+            .is_none_or(|last| now >= last + self.interval);
+        if due && self.pending.is_empty() {
+            self.last_notified = Some(now);
+            return Some(changes);
+        }
+        self.pending.extend(changes);
+        None
+    }
+
+    /// Returns when the held changes are due, or `None` if none are held.
+    #[must_use]
+    pub fn deadline(&self) -> Option<Instant> {
+        // Changes are only held after a notification
+        self.last_notified
+            .filter(|_| !self.pending.is_empty())
+            .map(|last| last + self.interval)
+    }
+
+    /// Returns the held changes, coalesced, once they are due at `now`.
+    ///
+    /// Returns `None` before the deadline and when the held changes cancel
+    /// out; in the latter case no notification is counted.
+    pub fn take_due(&mut self, now: Instant) -> Option<Vec<IpChange>> {
+        if self.deadline().is_none_or(|deadline| now < deadline) {
+            return None;
+        }
+        let merged = self.take_pending();
+        if merged.is_empty() {
+            return None;
+        }
+        self.last_notified = Some(now);
+        Some(merged)
+    }
+
+    /// Returns the held changes, coalesced, regardless of the deadline
+    /// (e.g. to keep them on shutdown).
+    pub fn take_pending(&mut self) -> Vec<IpChange> {
+        let pending = std::mem::take(&mut self.pending);
+        let Some(timestamp) = pending.iter().map(|change| change.timestamp).max() else {
+            return Vec::new();
+        };
+        merge_changes(&pending, timestamp)
+    }
+}
@@ -0,0 +1,124 @@
+//! Tests for the minimum interval between notifications.
+
+use super::change::IpChange;
+use super::throttle::NotifyThrottle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const INTERVAL: Duration = Duration::from_secs(30);
+
+fn at(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+fn added(address: &str, secs: u64) -> IpChange {
+    IpChange::added("eth0", address.parse().unwrap(), at(secs))
+}
+
+fn removed(address: &str, secs: u64) -> IpChange {
+    IpChange::removed("eth0", address.parse().unwrap(), at(secs))
+}
+
+fn sorted(mut changes: Vec<IpChange>) -> Vec<IpChange> {
+    changes.sort_by_key(|change| change.address);
+    changes
+}
+
+#[test]
+fn first_batch_passes_at_once() {
+    let mut throttle = NotifyThrottle::new(INTERVAL);
+
+    let batch = throttle.offer(vec![added("10.0.0.1", 1)], Instant::now());
+
+    assert_eq!(batch, Some(vec![added("10.0.0.1", 1)]));
+    assert_eq!(throttle.deadline(), None);
+}
+
+#[test]
+fn batch_within_interval_is_held_until_deadline() {
+    let mut throttle = NotifyThrottle::new(INTERVAL);
+    let start = Instant::now();
+    throttle.offer(vec![added("10.0.0.1", 1)], start);
+
+    let held = throttle.offer(vec![added("10.0.0.2", 2)], start + Duration::from_secs(5));
+
+    assert_eq!(held, None);
+    assert_eq!(throttle.deadline(), Some(start + INTERVAL));
+    assert_eq!(throttle.take_due(start + Duration::from_secs(29)), None);
+    assert_eq!(
+        throttle.take_due(start + INTERVAL),
+        Some(vec![added("10.0.0.2", 2)])
+    );
+    assert_eq!(throttle.deadline(), None);
+}
+
+#[test]
+fn held_batches_are_coalesced() {
+    let mut throttle = NotifyThrottle::new(INTERVAL);
+    let start = Instant::now();
+    throttle.offer(vec![added("10.0.0.1", 1)], start);
+
+    throttle.offer(vec![added("10.0.0.2", 2)], start);
+    throttle.offer(vec![removed("10.0.0.2", 3), added("10.0.0.3", 3)], start);
+    throttle.offer(vec![removed("10.0.0.1", 4)], start);
+
+    let batch = throttle.take_due(start + INTERVAL).unwrap();
+    assert_eq!(
+        sorted(batch),
+        vec![removed("10.0.0.1", 4), added("10.0.0.3", 4)]
+    );
+}
+
+#[test]
+fn delivery_of_held_changes_starts_a_new_interval() {
+    let mut throttle = NotifyThrottle::new(INTERVAL);
+    let start = Instant::now();
+    throttle.offer(vec![added("10.0.0.1", 1)], start);
+    throttle.offer(vec![added("10.0.0.2", 2)], start);
+    let sent = start + INTERVAL + Duration::from_secs(1);
+    throttle.take_due(sent).unwrap();
+
+    let held = throttle.offer(vec![added("10.0.0.3", 3)], sent + Duration::from_secs(10));
+
+    assert_eq!(held, None);
+    assert_eq!(throttle.deadline(), Some(sent + INTERVAL));
+}
+
+#[test]
+fn cancelled_changes_send_nothing() {
+    let mut throttle = NotifyThrottle::new(INTERVAL);
+    let start = Instant::now();
+    throttle.offer(vec![added("10.0.0.1", 1)], start);
+    throttle.offer(vec![added("10.0.0.2", 2)], start);
+    throttle.offer(vec![removed("10.0.0.2", 3)], start);
+
+    assert_eq!(throttle.take_due(start + INTERVAL), None);
+    assert_eq!(throttle.deadline(), None);
+    // The interval still counts from the last notification
+    assert!(
+        throttle
+            .offer(vec![added("10.0.0.4", 4)], start + INTERVAL)
+            .is_some()
+    );
+}
+
+#[test]
+fn batch_after_interval_passes_at_once() {
+    let mut throttle = NotifyThrottle::new(INTERVAL);
+    let start = Instant::now();
+    throttle.offer(vec![added("10.0.0.1", 1)], start);
+
+    let batch = throttle.offer(vec![added("10.0.0.2", 2)], start + INTERVAL);
+
+    assert_eq!(batch, Some(vec![added("10.0.0.2", 2)]));
+}
+
+#[test]
+fn take_pending_ignores_deadline() {
+    let mut throttle = NotifyThrottle::new(INTERVAL);
+    let start = Instant::now();
+    throttle.offer(vec![added("10.0.0.1", 1)], start);
+    throttle.offer(vec![added("10.0.0.2", 2)], start);
+
+    assert_eq!(throttle.take_pending(), vec![added("10.0.0.2", 2)]);
+    assert!(throttle.take_pending().is_empty());
+}
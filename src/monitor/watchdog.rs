@@ -0,0 +1,213 @@
+//! Stall detection for the monitoring loop.
+//!
+//! Adapter fetches are synchronous OS calls; if one hangs, the monitor stream
+//! silently stops producing changes. [`HeartbeatFetcher`] records a
+//! [`Heartbeat`] each time a fetch returns, and [`Watchdog`] reports the loop
+//! as stalled once no fetch has completed within twice the poll interval.
+//! The interval is a [`SharedInterval`], so a reload that changes it moves
+//! the threshold with it.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::network::{AdapterSnapshot, AddressFetcher, FetchError};
+use crate::time::{Clock, SystemClock};
+
+/// Shared record of the last time the monitor loop made progress.
+///
+/// Clones share the same underlying timestamp, so one clone can be handed
+/// to the fetcher and another to the [`Watchdog`].
+#[derive(Debug, Clone, Default)]
+pub struct Heartbeat {
+    /// Milliseconds since `UNIX_EPOCH` of the last beat; 0 means no beat yet.
+    last_ms: Arc<AtomicU64>,
+}
+
+impl Heartbeat {
+    /// Creates a heartbeat that has not beaten yet.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records progress at `now`.
+    pub fn beat(&self, now: SystemTime) {
+        // Clamp to 1 so a pre-epoch clock still registers as a beat
+        let ms = now
+            .duration_since(UNIX_EPOCH)
+            .map_or(1, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX));
+        self.last_ms.store(ms.max(1), Ordering::Relaxed);
+    }
+
+    /// Returns the time of the last beat, if any.
+    #[must_use]
+    pub fn last(&self) -> Option<SystemTime> {
+        match self.last_ms.load(Ordering::Relaxed) {
+            0 => None,
+            ms => Some(UNIX_EPOCH + Duration::from_millis(ms)),
+        }
+    }
+}
+
+/// The longest interval between two polls, shared with a [`Watchdog`].
+///
+/// Clones share the same interval, so the monitor loop can update it when a
+/// reload changes the poll intervals.
+#[derive(Debug, Clone)]
+pub struct SharedInterval {
+    /// Milliseconds of the interval.
+    ms: Arc<AtomicU64>,
+}
+
+impl SharedInterval {
+    /// Creates a shared `interval`.
+    #[must_use]
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            ms: Arc::new(AtomicU64::new(Self::millis(interval))),
+        }
+    }
+
+    /// Returns the current interval.
+    #[must_use]
+    pub fn get(&self) -> Duration {
+        Duration::from_millis(self.ms.load(Ordering::Relaxed))
+    }
+
+    /// Replaces the interval for every clone.
+    pub fn set(&self, interval: Duration) {
+        self.ms.store(Self::millis(interval), Ordering::Relaxed);
+    }
+
+    fn millis(interval: Duration) -> u64 {
+        u64::try_from(interval.as_millis()).unwrap_or(u64::MAX)
+    }
+}
+
+/// An [`AddressFetcher`] decorator that records a [`Heartbeat`] after each fetch.
+///
+/// Failed fetches count as progress too: the loop is alive, merely unlucky.
+#[derive(Debug)]
+pub struct HeartbeatFetcher<F, C = SystemClock> {
+    inner: F,
+    heartbeat: Heartbeat,
+    clock: C,
+}
+
+impl<F> HeartbeatFetcher<F, SystemClock> {
+    /// Wraps `inner`, beating `heartbeat` whenever a fetch returns.
+    #[must_use]
+    pub const fn new(inner: F, heartbeat: Heartbeat) -> Self {
+        Self::with_clock(inner, heartbeat, SystemClock)
+    }
+}
+
+impl<F, C> HeartbeatFetcher<F, C> {
+    /// Wraps `inner` with a custom clock for heartbeat timestamps.
+    #[must_use]
+    pub const fn with_clock(inner: F, heartbeat: Heartbeat, clock: C) -> Self {
+        Self {
+            inner,
+            heartbeat,
+            clock,
+        }
+    }
+}
+
+impl<F: AddressFetcher, C: Clock> AddressFetcher for HeartbeatFetcher<F, C> {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        let result = self.inner.fetch();
+        self.heartbeat.beat(self.clock.now());
+        result
+    }
+}
+
+/// What to do when the monitor loop stalls.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WatchdogAction {
+    /// Do not watch the loop.
+    Disabled,
+    /// Log an error while stalled.
+    #[default]
+    Log,
+    /// Log an error and exit so a service manager can restart the process.
+    Exit,
+}
+
+/// Health of the monitor loop as seen by the [`Watchdog`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchdogStatus {
+    /// The loop made progress within the threshold.
+    Healthy,
+    /// No progress has been recorded for `silent_for`.
+    Stalled {
+        /// Time since the last recorded progress.
+        silent_for: Duration,
+    },
+}
+
+/// Detects a stalled monitor loop from its [`Heartbeat`].
+///
+/// Before the first beat, time is measured from the watchdog's creation,
+/// so a fetch that hangs on startup is detected too.
+#[derive(Debug)]
+pub struct Watchdog<C = SystemClock> {
+    heartbeat: Heartbeat,
+    poll_interval: SharedInterval,
+    clock: C,
+    started: SystemTime,
+}
+
+impl Watchdog<SystemClock> {
+    /// Creates a watchdog that flags a stall after twice the current
+    /// `poll_interval`.
+    #[must_use]
+    pub fn new(heartbeat: Heartbeat, poll_interval: SharedInterval) -> Self {
+        Self::with_clock(heartbeat, poll_interval, SystemClock)
+    }
+}
+
+impl<C: Clock> Watchdog<C> {
+    /// Creates a watchdog with a custom clock.
+    #[must_use]
+    pub fn with_clock(heartbeat: Heartbeat, poll_interval: SharedInterval, clock: C) -> Self {
+        let started = clock.now();
+        Self {
+            heartbeat,
+            poll_interval,
+            clock,
+            started,
+        }
+    }
+
+    /// Returns the poll interval the threshold follows.
+    #[must_use]
+    pub const fn poll_interval(&self) -> &SharedInterval {
+        &self.poll_interval
+    }
+
+    /// Returns how long the loop may go without progress before it is
+    /// stalled: twice the current poll interval.
+    #[must_use]
+    pub fn threshold(&self) -> Duration {
+        self.poll_interval.get().saturating_mul(2)
+    }
+
+    /// Checks the heartbeat against the threshold.
+    #[must_use]
+    pub fn check(&self) -> WatchdogStatus {
+        let reference = self.heartbeat.last().unwrap_or(self.started);
+        let silent_for = self
+            .clock
+            .now()
+            .duration_since(reference)
+            .unwrap_or_default();
+
+        if silent_for > self.threshold() {
+            WatchdogStatus::Stalled { silent_for }
+        } else {
+            WatchdogStatus::Healthy
+        }
+    }
+}
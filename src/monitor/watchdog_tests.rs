@@ -0,0 +1,199 @@
+//! Tests for monitor loop stall detection.
+
+use super::watchdog::{Heartbeat, HeartbeatFetcher, SharedInterval, Watchdog, WatchdogStatus};
+use crate::network::{AdapterSnapshot, AddressFetcher, FetchError};
+use crate::time::Clock;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Clock whose time is set manually; clones share the same time.
+#[derive(Clone, Default)]
+struct ManualClock {
+    secs: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    fn at(secs: u64) -> Self {
+        Self {
+            secs: Arc::new(AtomicU64::new(secs)),
+        }
+    }
+
+    fn set(&self, secs: u64) {
+        self.secs.store(secs, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.secs.load(Ordering::SeqCst))
+    }
+}
+
+/// Fetcher that always fails.
+struct FailingFetcher;
+
+impl AddressFetcher for FailingFetcher {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        Err(FetchError::Platform {
+            message: "boom".to_string(),
+        })
+    }
+}
+
+/// Fetcher that always succeeds with no adapters.
+struct EmptyFetcher;
+
+impl AddressFetcher for EmptyFetcher {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        Ok(vec![])
+    }
+}
+
+mod heartbeat {
+    use super::*;
+
+    #[test]
+    fn no_beat_initially() {
+        assert!(Heartbeat::new().last().is_none());
+    }
+
+    #[test]
+    fn beat_records_time() {
+        let heartbeat = Heartbeat::new();
+        let now = UNIX_EPOCH + Duration::from_secs(100);
+
+        heartbeat.beat(now);
+
+        assert_eq!(heartbeat.last(), Some(now));
+    }
+
+    #[test]
+    fn clones_share_state() {
+        let heartbeat = Heartbeat::new();
+        let observer = heartbeat.clone();
+
+        heartbeat.beat(UNIX_EPOCH + Duration::from_secs(5));
+
+        assert_eq!(observer.last(), Some(UNIX_EPOCH + Duration::from_secs(5)));
+    }
+}
+
+mod heartbeat_fetcher {
+    use super::*;
+
+    #[test]
+    fn successful_fetch_beats() {
+        let heartbeat = Heartbeat::new();
+        let fetcher =
+            HeartbeatFetcher::with_clock(EmptyFetcher, heartbeat.clone(), ManualClock::at(42));
+
+        assert!(fetcher.fetch().is_ok());
+        assert_eq!(heartbeat.last(), Some(UNIX_EPOCH + Duration::from_secs(42)));
+    }
+
+    #[test]
+    fn failed_fetch_beats_and_passes_error_through() {
+        let heartbeat = Heartbeat::new();
+        let fetcher =
+            HeartbeatFetcher::with_clock(FailingFetcher, heartbeat.clone(), ManualClock::at(42));
+
+        assert!(matches!(fetcher.fetch(), Err(FetchError::Platform { .. })));
+        assert!(heartbeat.last().is_some());
+    }
+}
+
+mod watchdog {
+    use super::*;
+
+    const POLL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn threshold_is_twice_poll_interval() {
+        let watchdog = Watchdog::with_clock(
+            Heartbeat::new(),
+            SharedInterval::new(POLL),
+            ManualClock::at(0),
+        );
+
+        assert_eq!(watchdog.threshold(), Duration::from_secs(120));
+    }
+
+    #[test]
+    fn healthy_within_threshold_of_last_beat() {
+        let clock = ManualClock::at(1000);
+        let heartbeat = Heartbeat::new();
+        let watchdog =
+            Watchdog::with_clock(heartbeat.clone(), SharedInterval::new(POLL), clock.clone());
+
+        heartbeat.beat(clock.now());
+        clock.set(1120);
+
+        assert_eq!(watchdog.check(), WatchdogStatus::Healthy);
+    }
+
+    #[test]
+    fn stalled_beyond_threshold() {
+        let clock = ManualClock::at(1000);
+        let heartbeat = Heartbeat::new();
+        let watchdog =
+            Watchdog::with_clock(heartbeat.clone(), SharedInterval::new(POLL), clock.clone());
+
+        heartbeat.beat(clock.now());
+        clock.set(1121);
+
+        assert_eq!(
+            watchdog.check(),
+            WatchdogStatus::Stalled {
+                silent_for: Duration::from_secs(121)
+            }
+        );
+    }
+
+    #[test]
+    fn stall_before_first_beat_measured_from_start() {
+        let clock = ManualClock::at(1000);
+        let watchdog =
+            Watchdog::with_clock(Heartbeat::new(), SharedInterval::new(POLL), clock.clone());
+
+        clock.set(1100);
+        assert_eq!(watchdog.check(), WatchdogStatus::Healthy);
+
+        clock.set(1200);
+        assert!(matches!(watchdog.check(), WatchdogStatus::Stalled { .. }));
+    }
+
+    #[test]
+    fn recovers_after_new_beat() {
+        let clock = ManualClock::at(1000);
+        let heartbeat = Heartbeat::new();
+        let watchdog =
+            Watchdog::with_clock(heartbeat.clone(), SharedInterval::new(POLL), clock.clone());
+
+        clock.set(1500);
+        assert!(matches!(watchdog.check(), WatchdogStatus::Stalled { .. }));
+
+        heartbeat.beat(clock.now());
+        assert_eq!(watchdog.check(), WatchdogStatus::Healthy);
+    }
+
+    #[test]
+    fn threshold_follows_interval_changes() {
+        let clock = ManualClock::at(1000);
+        let heartbeat = Heartbeat::new();
+        let interval = SharedInterval::new(POLL);
+        let watchdog = Watchdog::with_clock(heartbeat.clone(), interval.clone(), clock.clone());
+        heartbeat.beat(clock.now());
+
+        // A reload lengthening the interval to 5 minutes
+        interval.set(Duration::from_secs(300));
+        clock.set(1500);
+
+        assert_eq!(watchdog.threshold(), Duration::from_secs(600));
+        assert_eq!(watchdog.check(), WatchdogStatus::Healthy);
+
+        interval.set(POLL);
+        assert!(matches!(watchdog.check(), WatchdogStatus::Stalled { .. }));
+    }
+}
@@ -1,10 +1,13 @@
 //! Core network types for adapter representation.
 
+use std::collections::BTreeMap;
 use std::fmt;
-use std::net::{Ipv4Addr, Ipv6Addr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
 use serde::{Deserialize, Serialize};
 
+use super::AddressInfo;
+
 /// IP version to monitor (explicit specification required, no default).
 ///
 /// # Design Decision
@@ -49,6 +52,9 @@ impl fmt::Display for IpVersion {
 ///
 /// Used for logging, filtering, and debugging. The core logic does not
 /// depend on specific values, allowing platform-specific implementations.
+///
+/// Serialized by variant name (`"Ethernet"`, `{"Other": 71}`), as state
+/// files store it.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum AdapterKind {
     /// Physical Ethernet adapter.
@@ -59,6 +65,9 @@ pub enum AdapterKind {
     Loopback,
     /// Virtual adapter (`VMware`, `VirtualBox`, `Hyper-V`, WSL, etc.).
     Virtual,
+    /// Public address seen from the internet, not a local adapter
+    /// (see [`public`](super::public)).
+    Public,
     /// Unknown or other adapter type, preserving the original type code for debugging.
     Other(u32),
 }
@@ -77,11 +86,138 @@ impl AdapterKind {
     }
 }
 
+/// DNS configuration of an adapter.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DnsSettings {
+    /// Connection-specific DNS suffix (empty if none).
+    pub suffix: String,
+    /// DNS servers in resolver order.
+    pub servers: Vec<IpAddr>,
+}
+
+impl fmt::Display for DnsSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let suffix = if self.suffix.is_empty() {
+            "(none)"
+        } else {
+            &self.suffix
+        };
+        write!(f, "suffix {suffix}, servers [")?;
+        for (i, server) in self.servers.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{server}")?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// Operational state of an adapter (RFC 2863 `ifOperStatus`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperStatus {
+    /// Ready to pass packets.
+    Up,
+    /// Not ready to pass packets.
+    Down,
+    /// In a test mode.
+    Testing,
+    /// Waiting for an external event, e.g. a dial-up connection.
+    Dormant,
+    /// A component (typically hardware) is missing.
+    NotPresent,
+    /// Down because a lower-layer interface is down.
+    LowerLayerDown,
+    /// The state cannot be determined.
+    Unknown,
+}
+
+impl OperStatus {
+    /// Returns the status as rendered in payloads, e.g. `lower_layer_down`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Testing => "testing",
+            Self::Dormant => "dormant",
+            Self::NotPresent => "not_present",
+            Self::LowerLayerDown => "lower_layer_down",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for OperStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Link-layer details of an adapter; each is absent if the fetcher cannot
+/// tell.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdapterMetadata {
+    /// Hardware address, lowercase and colon-separated (see
+    /// [`format_mac`](Self::format_mac)).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mac: Option<String>,
+    /// Interface index.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<u32>,
+    /// Maximum transmission unit in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtu: Option<u32>,
+    /// Transmit link speed in bits per second.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_speed: Option<u64>,
+    /// Connection-specific DNS suffix; absent if empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dns_suffix: Option<String>,
+    /// Operational state.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oper_status: Option<OperStatus>,
+}
+
+impl AdapterMetadata {
+    /// Formats a hardware address as `aa:bb:cc:dd:ee:ff`.
+    ///
+    /// Returns `None` for an empty or all-zero address, which adapters
+    /// without one (loopback, most tunnels) report.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ddns_a::network::AdapterMetadata;
+    ///
+    /// let mac = AdapterMetadata::format_mac(&[0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]);
+    /// assert_eq!(mac.as_deref(), Some("00:1a:2b:3c:4d:5e"));
+    /// assert_eq!(AdapterMetadata::format_mac(&[0; 6]), None);
+    /// ```
+    #[must_use]
+    pub fn format_mac(bytes: &[u8]) -> Option<String> {
+        if bytes.iter().all(|&b| b == 0) {
+            return None;
+        }
+        let hex: Vec<String> = bytes.iter().map(|b| format!("{b:02x}")).collect();
+        Some(hex.join(":"))
+    }
+
+    /// Returns true if nothing is known about the adapter.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 /// A snapshot of a single network adapter's addresses at a point in time.
 ///
 /// # Equality
 ///
-/// Two snapshots are equal if they have the same name, kind, and addresses.
+/// Two snapshots are equal if they have the same name, kind, addresses
+/// (temporary ones included), address details, DNS settings, default route
+/// and metadata.
 /// Address order matters for equality comparison.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AdapterSnapshot {
@@ -93,6 +229,24 @@ pub struct AdapterSnapshot {
     pub ipv4_addresses: Vec<Ipv4Addr>,
     /// All IPv6 addresses assigned to this adapter.
     pub ipv6_addresses: Vec<Ipv6Addr>,
+    /// DNS settings, if the fetcher collects them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dns: Option<DnsSettings>,
+    /// The addresses of `ipv6_addresses` the system marks temporary (IPv6
+    /// privacy extensions), if the fetcher can tell.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub temporary_ipv6: Vec<Ipv6Addr>,
+    /// Whether a default route (IPv4 or IPv6) goes through this adapter,
+    /// if the fetcher can tell.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub default_route: bool,
+    /// Link-layer details, if the fetcher collects them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<AdapterMetadata>,
+    /// Details of the addresses the fetcher knows more about than the
+    /// address itself.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub address_info: BTreeMap<IpAddr, AddressInfo>,
 }
 
 impl AdapterSnapshot {
@@ -109,7 +263,56 @@ impl AdapterSnapshot {
             kind,
             ipv4_addresses,
             ipv6_addresses,
+            dns: None,
+            temporary_ipv6: Vec::new(),
+            default_route: false,
+            metadata: None,
+            address_info: BTreeMap::new(),
+        }
+    }
+
+    /// Attaches DNS settings to the snapshot.
+    #[must_use]
+    pub fn with_dns(mut self, dns: DnsSettings) -> Self {
+        self.dns = Some(dns);
+        self
+    }
+
+    /// Marks `addresses` of `ipv6_addresses` as temporary.
+    #[must_use]
+    pub fn with_temporary_ipv6(mut self, addresses: Vec<Ipv6Addr>) -> Self {
+        self.temporary_ipv6 = addresses;
+        self
+    }
+
+    /// Marks whether a default route goes through the adapter.
+    #[must_use]
+    pub const fn with_default_route(mut self, default_route: bool) -> Self {
+        self.default_route = default_route;
+        self
+    }
+
+    /// Attaches link-layer details to the snapshot; none if `metadata` is
+    /// empty.
+    #[must_use]
+    pub fn with_metadata(mut self, metadata: AdapterMetadata) -> Self {
+        self.metadata = (!metadata.is_empty()).then_some(metadata);
+        self
+    }
+
+    /// Records the details of `address`; nothing if `info` is empty.
+    #[must_use]
+    pub fn with_address_info(mut self, address: IpAddr, info: AddressInfo) -> Self {
+        if !info.is_empty() {
+            self.address_info.insert(address, info);
         }
+        self
+    }
+
+    /// Returns the details of `address`, empty if none are known.
+    #[must_use]
+    pub fn info_of(&self, address: IpAddr) -> AddressInfo {
+        self.address_info.get(&address).copied().unwrap_or_default()
     }
 
     /// Returns true if this adapter has any addresses (IPv4 or IPv6).
@@ -128,6 +331,7 @@ impl AdapterSnapshot {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::network::AddressState;
 
     mod ip_version {
         use super::*;
@@ -255,6 +459,34 @@ mod tests {
             assert_eq!(snapshot.address_count(), 0);
         }
 
+        #[test]
+        fn dns_is_omitted_from_json_when_absent() {
+            let json = serde_json::to_value(make_snapshot()).unwrap();
+            assert!(json.get("dns").is_none());
+
+            let restored: AdapterSnapshot = serde_json::from_value(json).unwrap();
+            assert_eq!(restored.dns, None);
+        }
+
+        #[test]
+        fn dns_display_lists_suffix_and_servers() {
+            let dns = DnsSettings {
+                suffix: "corp.example.com".to_string(),
+                servers: vec![
+                    "192.0.2.53".parse().unwrap(),
+                    "2001:db8::53".parse().unwrap(),
+                ],
+            };
+            assert_eq!(
+                dns.to_string(),
+                "suffix corp.example.com, servers [192.0.2.53, 2001:db8::53]"
+            );
+            assert_eq!(
+                DnsSettings::default().to_string(),
+                "suffix (none), servers []"
+            );
+        }
+
         #[test]
         fn equality_requires_same_name() {
             let snapshot1 = make_snapshot();
@@ -281,5 +513,117 @@ mod tests {
 
             assert_ne!(snapshot1, snapshot2);
         }
+
+        #[test]
+        fn empty_metadata_is_not_attached() {
+            let snapshot = make_snapshot().with_metadata(AdapterMetadata::default());
+            assert_eq!(snapshot.metadata, None);
+
+            let metadata = AdapterMetadata {
+                mtu: Some(1500),
+                ..AdapterMetadata::default()
+            };
+            let snapshot = make_snapshot().with_metadata(metadata.clone());
+            assert_eq!(snapshot.metadata, Some(metadata));
+        }
+
+        #[test]
+        fn metadata_round_trips_through_json_without_unknown_fields() {
+            let metadata = AdapterMetadata {
+                mac: Some("00:1a:2b:3c:4d:5e".to_string()),
+                oper_status: Some(OperStatus::LowerLayerDown),
+                ..AdapterMetadata::default()
+            };
+            let json =
+                serde_json::to_value(make_snapshot().with_metadata(metadata.clone())).unwrap();
+            assert_eq!(
+                json["metadata"],
+                serde_json::json!({"mac": "00:1a:2b:3c:4d:5e", "oper_status": "lower_layer_down"})
+            );
+
+            let restored: AdapterSnapshot = serde_json::from_value(json).unwrap();
+            assert_eq!(restored.metadata, Some(metadata));
+            assert!(
+                serde_json::to_value(make_snapshot())
+                    .unwrap()
+                    .get("metadata")
+                    .is_none()
+            );
+        }
+        #[test]
+        fn empty_address_info_is_not_recorded() {
+            let address: IpAddr = "192.168.1.100".parse().unwrap();
+            let snapshot = make_snapshot().with_address_info(address, AddressInfo::default());
+            assert!(snapshot.address_info.is_empty());
+
+            let info = AddressInfo {
+                prefix_len: Some(24),
+                state: Some(AddressState::Preferred),
+            };
+            let snapshot = make_snapshot().with_address_info(address, info);
+            assert_eq!(snapshot.info_of(address), info);
+            assert_eq!(
+                snapshot.info_of("10.0.0.1".parse().unwrap()),
+                AddressInfo::default()
+            );
+        }
+
+        #[test]
+        fn address_info_round_trips_through_json() {
+            let address: IpAddr = "2001:db8::1".parse().unwrap();
+            let info = AddressInfo {
+                prefix_len: Some(64),
+                state: Some(AddressState::Deprecated),
+            };
+            let json =
+                serde_json::to_value(make_snapshot().with_address_info(address, info)).unwrap();
+            assert_eq!(
+                json["address_info"],
+                serde_json::json!({"2001:db8::1": {"prefix_len": 64, "state": "deprecated"}})
+            );
+
+            let restored: AdapterSnapshot = serde_json::from_value(json).unwrap();
+            assert_eq!(restored.info_of(address), info);
+            assert!(
+                serde_json::to_value(make_snapshot())
+                    .unwrap()
+                    .get("address_info")
+                    .is_none()
+            );
+        }
+    }
+
+    mod adapter_metadata {
+        use super::*;
+
+        #[test]
+        fn format_mac_is_lowercase_and_colon_separated() {
+            assert_eq!(
+                AdapterMetadata::format_mac(&[0xAA, 0xBB, 0xCC, 0x01, 0x02, 0x03]).as_deref(),
+                Some("aa:bb:cc:01:02:03")
+            );
+        }
+
+        #[test]
+        fn format_mac_keeps_other_lengths() {
+            assert_eq!(
+                AdapterMetadata::format_mac(&[0x02, 0, 0, 0, 0, 0, 0, 0x01]).as_deref(),
+                Some("02:00:00:00:00:00:00:01")
+            );
+        }
+
+        #[test]
+        fn format_mac_skips_missing_addresses() {
+            assert_eq!(AdapterMetadata::format_mac(&[]), None);
+            assert_eq!(AdapterMetadata::format_mac(&[0; 6]), None);
+        }
+
+        #[test]
+        fn oper_status_displays_as_serialized() {
+            for status in [OperStatus::Up, OperStatus::NotPresent, OperStatus::Unknown] {
+                let json = serde_json::to_value(status).unwrap();
+                assert_eq!(json, status.to_string());
+            }
+        }
     }
 }
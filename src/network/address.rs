@@ -0,0 +1,149 @@
+//! Per-address classification: scope, preferred or deprecated state and
+//! prefix length.
+
+use std::fmt;
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+
+/// Reach of an address, judged by its prefix alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressScope {
+    /// Loopback (`127.0.0.0/8`, `::1`): never leaves the host.
+    Loopback,
+    /// Link-local (`169.254.0.0/16`, `fe80::/10`): never routed off the link.
+    LinkLocal,
+    /// Any other address, private and unique local ones included.
+    Global,
+}
+
+impl AddressScope {
+    /// Returns the scope of `address`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ddns_a::network::AddressScope;
+    ///
+    /// assert_eq!(AddressScope::of("fe80::1".parse().unwrap()), AddressScope::LinkLocal);
+    /// assert_eq!(AddressScope::of("192.168.1.5".parse().unwrap()), AddressScope::Global);
+    /// ```
+    #[must_use]
+    pub const fn of(address: IpAddr) -> Self {
+        match address {
+            IpAddr::V4(a) if a.is_loopback() => Self::Loopback,
+            IpAddr::V4(a) if a.is_link_local() => Self::LinkLocal,
+            IpAddr::V6(a) if a.is_loopback() => Self::Loopback,
+            IpAddr::V6(a) if a.is_unicast_link_local() => Self::LinkLocal,
+            _ => Self::Global,
+        }
+    }
+
+    /// Returns the scope as rendered in payloads, e.g. `link_local`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Loopback => "loopback",
+            Self::LinkLocal => "link_local",
+            Self::Global => "global",
+        }
+    }
+}
+
+impl fmt::Display for AddressScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Whether an address may be used for new connections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressState {
+    /// Valid and preferred for new connections.
+    Preferred,
+    /// Still valid, but its preferred lifetime ran out: kept for existing
+    /// connections only.
+    Deprecated,
+}
+
+impl AddressState {
+    /// Returns the state as rendered in payloads: `preferred` or
+    /// `deprecated`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Preferred => "preferred",
+            Self::Deprecated => "deprecated",
+        }
+    }
+}
+
+impl fmt::Display for AddressState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Per-address details; each is absent if the fetcher cannot tell.
+///
+/// The scope needs no fetcher: see [`AddressScope::of`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressInfo {
+    /// Length of the on-link prefix, e.g. 24 or 64.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix_len: Option<u8>,
+    /// Whether the address is preferred or deprecated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<AddressState>,
+}
+
+impl AddressInfo {
+    /// Returns true if nothing is known about the address.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod address_scope {
+        use super::*;
+
+        fn scope(address: &str) -> AddressScope {
+            AddressScope::of(address.parse().unwrap())
+        }
+
+        #[test]
+        fn loopback_addresses() {
+            assert_eq!(scope("127.0.0.1"), AddressScope::Loopback);
+            assert_eq!(scope("::1"), AddressScope::Loopback);
+        }
+
+        #[test]
+        fn link_local_addresses() {
+            assert_eq!(scope("169.254.10.1"), AddressScope::LinkLocal);
+            assert_eq!(scope("fe80::1"), AddressScope::LinkLocal);
+        }
+
+        #[test]
+        fn private_and_unique_local_addresses_are_global() {
+            assert_eq!(scope("10.0.0.1"), AddressScope::Global);
+            assert_eq!(scope("fd12::1"), AddressScope::Global);
+            assert_eq!(scope("2001:db8::1"), AddressScope::Global);
+        }
+
+        #[test]
+        fn displays_as_serialized() {
+            for scope in [AddressScope::Loopback, AddressScope::LinkLocal] {
+                assert_eq!(serde_json::to_value(scope).unwrap(), scope.to_string());
+            }
+            let state = AddressState::Deprecated;
+            assert_eq!(serde_json::to_value(state).unwrap(), state.to_string());
+        }
+    }
+}
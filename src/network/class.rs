@@ -0,0 +1,354 @@
+//! Address-class filtering.
+//!
+//! Some addresses are never useful to a DDNS record: link-local ones
+//! (`fe80::/10`, APIPA `169.254.0.0/16`), IPv6 unique local ones
+//! (`fc00::/7`, in practice `fd00::/8`) and temporary IPv6 privacy addresses,
+//! which rotate every day or so. An [`AddressClassFilter`] removes them from
+//! every snapshot before changes are detected, so they never produce an
+//! [`IpChange`](crate::monitor::IpChange).
+//!
+//! Temporary addresses are only known where the fetcher reports them (see
+//! [`AdapterSnapshot::temporary_ipv6`]).
+//!
+//! The filter also takes ranges of its own: addresses in an excluded
+//! [`Cidr`] are removed, and if any range is included, so is every address
+//! outside all of them.
+
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use super::{AdapterSnapshot, AddressFetcher, FetchError};
+
+/// An address range in CIDR notation, e.g. `203.0.113.0/24` or
+/// `2001:db8::/32`.
+///
+/// A bare address is a range of one; host bits below the prefix are
+/// ignored.
+///
+/// # Examples
+///
+/// ```
+/// use ddns_a::network::class::Cidr;
+///
+/// let range: Cidr = "2001:db8::/32".parse().unwrap();
+///
+/// assert!(range.contains("2001:db8:1::5".parse().unwrap()));
+/// assert!(!range.contains("2001:db9::5".parse().unwrap()));
+/// assert!(!range.contains("192.0.2.1".parse().unwrap()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    /// Creates the range of the `prefix` leading bits of `address`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `prefix` exceeds the address length.
+    pub fn new(address: IpAddr, prefix: u8) -> Result<Self, CidrError> {
+        let network = match address {
+            IpAddr::V4(a) if prefix <= 32 => IpAddr::V4((u32::from(a) & v4_mask(prefix)).into()),
+            IpAddr::V6(a) if prefix <= 128 => IpAddr::V6((u128::from(a) & v6_mask(prefix)).into()),
+            _ => return Err(CidrError::Prefix(prefix)),
+        };
+        Ok(Self { network, prefix })
+    }
+
+    /// Returns the first address of the range.
+    #[must_use]
+    pub const fn network(&self) -> IpAddr {
+        self.network
+    }
+
+    /// Returns the prefix length.
+    #[must_use]
+    pub const fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    /// Returns true if `address` lies in the range; never for the other
+    /// family.
+    #[must_use]
+    pub fn contains(&self, address: IpAddr) -> bool {
+        match (self.network, address) {
+            (IpAddr::V4(network), IpAddr::V4(a)) => {
+                u32::from(a) & v4_mask(self.prefix) == u32::from(network)
+            }
+            (IpAddr::V6(network), IpAddr::V6(a)) => {
+                u128::from(a) & v6_mask(self.prefix) == u128::from(network)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The netmask of an IPv4 `prefix` (at most 32).
+fn v4_mask(prefix: u8) -> u32 {
+    u32::MAX.checked_shl(32 - u32::from(prefix)).unwrap_or(0)
+}
+
+/// The netmask of an IPv6 `prefix` (at most 128).
+fn v6_mask(prefix: u8) -> u128 {
+    u128::MAX.checked_shl(128 - u32::from(prefix)).unwrap_or(0)
+}
+
+impl FromStr for Cidr {
+    type Err = CidrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address, prefix) = match s.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (s, None),
+        };
+        let address: IpAddr = address
+            .parse()
+            .map_err(|_| CidrError::Address(address.to_string()))?;
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .parse()
+                .map_err(|_| CidrError::PrefixText(prefix.to_string()))?,
+            None if address.is_ipv4() => 32,
+            None => 128,
+        };
+        Self::new(address, prefix)
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix)
+    }
+}
+
+/// Why a [`Cidr`] could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CidrError {
+    /// The part before the slash is not an IP address.
+    #[error("'{0}' is not an IP address")]
+    Address(String),
+
+    /// The part after the slash is not a number.
+    #[error("prefix '{0}' is not a number")]
+    PrefixText(String),
+
+    /// The prefix is longer than the address.
+    #[error("prefix /{0} is longer than the address")]
+    Prefix(u8),
+}
+
+/// Address classes and ranges removed from adapter snapshots.
+///
+/// The default removes link-local, unique local and temporary addresses and
+/// keeps private IPv4 ones. Excluded ranges are removed as well; included
+/// ranges, if any, are the only addresses kept of the rest.
+///
+/// # Examples
+///
+/// ```
+/// use ddns_a::network::class::AddressClassFilter;
+///
+/// let filter = AddressClassFilter::default();
+///
+/// assert!(!filter.allows("169.254.10.1".parse().unwrap()));
+/// assert!(!filter.allows("fd12::1".parse().unwrap()));
+/// assert!(filter.allows("192.168.1.5".parse().unwrap()));
+/// assert!(filter.allows("2001:db8::1".parse().unwrap()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)] // one switch per address class
+pub struct AddressClassFilter {
+    link_local: bool,
+    unique_local: bool,
+    temporary: bool,
+    private: bool,
+    include: Vec<Cidr>,
+    exclude: Vec<Cidr>,
+}
+
+impl Default for AddressClassFilter {
+    fn default() -> Self {
+        Self {
+            link_local: true,
+            unique_local: true,
+            temporary: true,
+            private: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+}
+
+impl AddressClassFilter {
+    /// Creates a filter removing no address.
+    #[must_use]
+    pub const fn none() -> Self {
+        Self {
+            link_local: false,
+            unique_local: false,
+            temporary: false,
+            private: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+
+    /// Sets whether link-local addresses (`fe80::/10`, `169.254.0.0/16`)
+    /// are removed.
+    #[must_use]
+    pub const fn with_link_local(mut self, exclude: bool) -> Self {
+        self.link_local = exclude;
+        self
+    }
+
+    /// Sets whether IPv6 unique local addresses (`fc00::/7`) are removed.
+    #[must_use]
+    pub const fn with_unique_local(mut self, exclude: bool) -> Self {
+        self.unique_local = exclude;
+        self
+    }
+
+    /// Sets whether temporary IPv6 addresses are removed.
+    #[must_use]
+    pub const fn with_temporary(mut self, exclude: bool) -> Self {
+        self.temporary = exclude;
+        self
+    }
+
+    /// Sets whether private IPv4 addresses (RFC 1918 and the shared
+    /// `100.64.0.0/10` of carrier-grade NAT) are removed.
+    #[must_use]
+    pub const fn with_private(mut self, exclude: bool) -> Self {
+        self.private = exclude;
+        self
+    }
+
+    /// Keeps only the addresses in one of `ranges`; every address if empty.
+    #[must_use]
+    pub fn with_include(mut self, ranges: Vec<Cidr>) -> Self {
+        self.include = ranges;
+        self
+    }
+
+    /// Removes the addresses in any of `ranges`.
+    #[must_use]
+    pub fn with_exclude(mut self, ranges: Vec<Cidr>) -> Self {
+        self.exclude = ranges;
+        self
+    }
+
+    /// Returns the included ranges.
+    #[must_use]
+    pub fn include(&self) -> &[Cidr] {
+        &self.include
+    }
+
+    /// Returns the excluded ranges.
+    #[must_use]
+    pub fn exclude(&self) -> &[Cidr] {
+        &self.exclude
+    }
+
+    /// Returns true if no address is removed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        !self.link_local
+            && !self.unique_local
+            && !self.temporary
+            && !self.private
+            && self.include.is_empty()
+            && self.exclude.is_empty()
+    }
+
+    /// Returns true if `address` is kept, judged by its prefix alone.
+    #[must_use]
+    pub fn allows(&self, address: IpAddr) -> bool {
+        let class_allowed = match address {
+            IpAddr::V4(address) => self.allows_v4(address),
+            IpAddr::V6(address) => self.allows_v6(address),
+        };
+        class_allowed
+            && !self.exclude.iter().any(|range| range.contains(address))
+            && (self.include.is_empty() || self.include.iter().any(|range| range.contains(address)))
+    }
+
+    const fn allows_v4(&self, address: Ipv4Addr) -> bool {
+        !(self.link_local && address.is_link_local()
+            || self.private && (address.is_private() || is_shared(address)))
+    }
+
+    const fn allows_v6(&self, address: Ipv6Addr) -> bool {
+        !(self.link_local && address.is_unicast_link_local()
+            || self.unique_local && address.is_unique_local())
+    }
+
+    /// Removes the addresses of the filtered classes and ranges from
+    /// `snapshot`.
+    #[must_use]
+    pub fn apply(&self, mut snapshot: AdapterSnapshot) -> AdapterSnapshot {
+        if self.is_empty() {
+            return snapshot;
+        }
+        snapshot
+            .ipv4_addresses
+            .retain(|&a| self.allows(IpAddr::V4(a)));
+        let temporary = std::mem::take(&mut snapshot.temporary_ipv6);
+        snapshot
+            .ipv6_addresses
+            .retain(|&a| self.allows(IpAddr::V6(a)) && !(self.temporary && temporary.contains(&a)));
+        if !self.temporary {
+            snapshot.temporary_ipv6 = temporary
+                .into_iter()
+                .filter(|a| snapshot.ipv6_addresses.contains(a))
+                .collect();
+        }
+        snapshot.address_info.retain(|address, _| match address {
+            IpAddr::V4(a) => snapshot.ipv4_addresses.contains(a),
+            IpAddr::V6(a) => snapshot.ipv6_addresses.contains(a),
+        });
+        snapshot
+    }
+}
+
+/// `100.64.0.0/10`, shared between the customers of a carrier-grade NAT.
+const fn is_shared(address: Ipv4Addr) -> bool {
+    let [a, b, ..] = address.octets();
+    a == 100 && b & 0xc0 == 64
+}
+
+/// A fetcher decorator removing the addresses an [`AddressClassFilter`]
+/// filters from every snapshot.
+#[derive(Debug)]
+pub struct ClassFilteredFetcher<F> {
+    inner: F,
+    filter: AddressClassFilter,
+}
+
+impl<F> ClassFilteredFetcher<F> {
+    /// Creates a fetcher applying `filter` to the snapshots of `inner`.
+    #[must_use]
+    pub const fn new(inner: F, filter: AddressClassFilter) -> Self {
+        Self { inner, filter }
+    }
+
+    /// Returns the address class filter.
+    #[must_use]
+    pub const fn filter(&self) -> &AddressClassFilter {
+        &self.filter
+    }
+}
+
+impl<F: AddressFetcher> AddressFetcher for ClassFilteredFetcher<F> {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        let snapshots = self.inner.fetch()?;
+        Ok(snapshots
+            .into_iter()
+            .map(|snapshot| self.filter.apply(snapshot))
+            .collect())
+    }
+}
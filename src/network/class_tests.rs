@@ -0,0 +1,303 @@
+//! Tests for the address-class filter.
+
+use std::net::{IpAddr, Ipv6Addr};
+
+use super::class::*;
+use super::{AdapterKind, AdapterSnapshot, AddressFetcher, AddressInfo, FetchError};
+
+fn ip(address: &str) -> IpAddr {
+    address.parse().unwrap()
+}
+
+fn v6(address: &str) -> Ipv6Addr {
+    address.parse().unwrap()
+}
+
+/// An adapter with an address of every class.
+fn adapter() -> AdapterSnapshot {
+    AdapterSnapshot::new(
+        "eth0",
+        AdapterKind::Ethernet,
+        vec![
+            "169.254.3.4".parse().unwrap(),
+            "192.168.1.5".parse().unwrap(),
+            "100.64.0.9".parse().unwrap(),
+            "203.0.113.5".parse().unwrap(),
+        ],
+        vec![
+            v6("fe80::1"),
+            v6("fd00::5"),
+            v6("2001:db8::1"),
+            v6("2001:db8::abcd"),
+        ],
+    )
+    .with_temporary_ipv6(vec![v6("2001:db8::abcd")])
+}
+
+mod cidr {
+    use super::*;
+
+    #[test]
+    fn parses_both_families() {
+        let v4: Cidr = "203.0.113.0/24".parse().unwrap();
+        let v6: Cidr = "2001:db8::/32".parse().unwrap();
+
+        assert_eq!((v4.network(), v4.prefix()), (ip("203.0.113.0"), 24));
+        assert_eq!((v6.network(), v6.prefix()), (ip("2001:db8::"), 32));
+    }
+
+    #[test]
+    fn bare_address_is_a_single_host() {
+        let v4: Cidr = "192.0.2.7".parse().unwrap();
+        let v6: Cidr = "2001:db8::7".parse().unwrap();
+
+        assert_eq!(v4.prefix(), 32);
+        assert_eq!(v6.prefix(), 128);
+        assert!(v4.contains(ip("192.0.2.7")));
+        assert!(!v4.contains(ip("192.0.2.8")));
+    }
+
+    #[test]
+    fn host_bits_are_ignored() {
+        let range: Cidr = "203.0.113.77/24".parse().unwrap();
+
+        assert_eq!(range.to_string(), "203.0.113.0/24");
+        assert!(range.contains(ip("203.0.113.1")));
+    }
+
+    #[test]
+    fn contains_only_its_range() {
+        let range: Cidr = "10.0.0.0/8".parse().unwrap();
+
+        assert!(range.contains(ip("10.255.0.1")));
+        assert!(!range.contains(ip("11.0.0.1")));
+        assert!(!range.contains(ip("::a00:1")));
+    }
+
+    #[test]
+    fn zero_prefix_contains_the_whole_family() {
+        let range: Cidr = "::/0".parse().unwrap();
+
+        assert!(range.contains(ip("2001:db8::1")));
+        assert!(!range.contains(ip("192.0.2.1")));
+    }
+
+    #[test]
+    fn invalid_ranges_are_rejected() {
+        assert_eq!(
+            "example.com/24".parse::<Cidr>(),
+            Err(CidrError::Address("example.com".to_string()))
+        );
+        assert_eq!(
+            "10.0.0.0/x".parse::<Cidr>(),
+            Err(CidrError::PrefixText("x".to_string()))
+        );
+        assert_eq!("10.0.0.0/33".parse::<Cidr>(), Err(CidrError::Prefix(33)));
+        assert_eq!("::/129".parse::<Cidr>(), Err(CidrError::Prefix(129)));
+    }
+}
+
+mod allows {
+    use super::*;
+
+    #[test]
+    fn default_drops_link_local_and_unique_local() {
+        let filter = AddressClassFilter::default();
+
+        assert!(!filter.allows(ip("169.254.0.1")));
+        assert!(!filter.allows(ip("fe80::1")));
+        assert!(!filter.allows(ip("fd00::1")));
+        assert!(!filter.allows(ip("fc00::1")));
+    }
+
+    #[test]
+    fn default_keeps_private_and_global() {
+        let filter = AddressClassFilter::default();
+
+        assert!(filter.allows(ip("10.0.0.1")));
+        assert!(filter.allows(ip("192.168.1.1")));
+        assert!(filter.allows(ip("203.0.113.5")));
+        assert!(filter.allows(ip("2001:db8::1")));
+    }
+
+    #[test]
+    fn private_drops_rfc1918_and_shared() {
+        let filter = AddressClassFilter::default().with_private(true);
+
+        assert!(!filter.allows(ip("10.0.0.1")));
+        assert!(!filter.allows(ip("172.16.0.1")));
+        assert!(!filter.allows(ip("192.168.1.1")));
+        assert!(!filter.allows(ip("100.127.255.1")));
+        assert!(filter.allows(ip("100.128.0.1")));
+        assert!(filter.allows(ip("203.0.113.5")));
+    }
+
+    #[test]
+    fn none_keeps_everything() {
+        let filter = AddressClassFilter::none();
+
+        assert!(filter.is_empty());
+        assert!(filter.allows(ip("169.254.0.1")));
+        assert!(filter.allows(ip("fe80::1")));
+        assert!(filter.allows(ip("fd00::1")));
+    }
+
+    #[test]
+    fn classes_switch_independently() {
+        let filter = AddressClassFilter::default().with_link_local(false);
+
+        assert!(filter.allows(ip("fe80::1")));
+        assert!(!filter.allows(ip("fd00::1")));
+
+        let filter = AddressClassFilter::default().with_unique_local(false);
+
+        assert!(!filter.allows(ip("fe80::1")));
+        assert!(filter.allows(ip("fd00::1")));
+    }
+}
+
+mod ranges {
+    use super::*;
+
+    fn cidrs(ranges: &[&str]) -> Vec<Cidr> {
+        ranges.iter().map(|r| r.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn includes_keep_only_their_addresses() {
+        let filter =
+            AddressClassFilter::default().with_include(cidrs(&["203.0.113.0/24", "2001:db8::/32"]));
+
+        assert!(filter.allows(ip("203.0.113.5")));
+        assert!(filter.allows(ip("2001:db8::1")));
+        assert!(!filter.allows(ip("192.168.1.5")));
+        assert!(!filter.allows(ip("2001:db9::1")));
+    }
+
+    #[test]
+    fn excludes_win_over_includes() {
+        let filter = AddressClassFilter::none()
+            .with_include(cidrs(&["2001:db8::/32"]))
+            .with_exclude(cidrs(&["2001:db8:ffff::/48"]));
+
+        assert!(filter.allows(ip("2001:db8:1::1")));
+        assert!(!filter.allows(ip("2001:db8:ffff::1")));
+    }
+
+    #[test]
+    fn classes_still_apply_inside_included_ranges() {
+        let filter = AddressClassFilter::default().with_include(cidrs(&["fd00::/8"]));
+
+        assert!(!filter.allows(ip("fd00::1")));
+    }
+
+    #[test]
+    fn ranges_are_applied_to_snapshots() {
+        let filter = AddressClassFilter::none().with_exclude(cidrs(&["2001:db8::abcd"]));
+        let snapshot = filter.apply(adapter());
+
+        assert!(!filter.is_empty());
+        assert!(!snapshot.ipv6_addresses.contains(&v6("2001:db8::abcd")));
+        assert!(snapshot.temporary_ipv6.is_empty());
+        assert_eq!(snapshot.ipv4_addresses, adapter().ipv4_addresses);
+    }
+}
+
+mod apply {
+    use super::*;
+
+    #[test]
+    fn default_keeps_stable_global_and_private() {
+        let snapshot = AddressClassFilter::default().apply(adapter());
+
+        assert_eq!(
+            snapshot.ipv4_addresses,
+            ["192.168.1.5", "100.64.0.9", "203.0.113.5"]
+                .map(|a| a.parse::<std::net::Ipv4Addr>().unwrap())
+        );
+        assert_eq!(snapshot.ipv6_addresses, [v6("2001:db8::1")]);
+        assert!(snapshot.temporary_ipv6.is_empty());
+    }
+
+    #[test]
+    fn temporary_addresses_can_be_kept() {
+        let snapshot = AddressClassFilter::default()
+            .with_temporary(false)
+            .apply(adapter());
+
+        assert_eq!(
+            snapshot.ipv6_addresses,
+            [v6("2001:db8::1"), v6("2001:db8::abcd")]
+        );
+        assert_eq!(snapshot.temporary_ipv6, [v6("2001:db8::abcd")]);
+    }
+
+    #[test]
+    fn details_of_removed_addresses_are_dropped() {
+        let info = AddressInfo {
+            prefix_len: Some(64),
+            state: None,
+        };
+        let snapshot = adapter()
+            .with_address_info(ip("fe80::1"), info)
+            .with_address_info(ip("2001:db8::1"), info);
+
+        let snapshot = AddressClassFilter::default().apply(snapshot);
+
+        assert_eq!(
+            snapshot.address_info.keys().collect::<Vec<_>>(),
+            [&ip("2001:db8::1")]
+        );
+    }
+
+    #[test]
+    fn none_leaves_the_snapshot_alone() {
+        assert_eq!(AddressClassFilter::none().apply(adapter()), adapter());
+    }
+}
+
+mod class_filtered_fetcher {
+    use super::*;
+
+    struct Fixed(Vec<AdapterSnapshot>);
+
+    impl AddressFetcher for Fixed {
+        fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    struct Failing;
+
+    impl AddressFetcher for Failing {
+        fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+            Err(FetchError::Platform {
+                message: "down".to_string(),
+            })
+        }
+    }
+
+    #[test]
+    fn filters_every_snapshot() {
+        let fetcher = ClassFilteredFetcher::new(
+            Fixed(vec![adapter(), adapter()]),
+            AddressClassFilter::default(),
+        );
+
+        let snapshots = fetcher.fetch().unwrap();
+
+        assert_eq!(snapshots.len(), 2);
+        assert!(
+            snapshots
+                .iter()
+                .all(|s| s.ipv6_addresses == [v6("2001:db8::1")])
+        );
+    }
+
+    #[test]
+    fn errors_pass_through() {
+        let fetcher = ClassFilteredFetcher::new(Failing, AddressClassFilter::default());
+
+        assert!(fetcher.fetch().is_err());
+    }
+}
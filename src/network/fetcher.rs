@@ -1,6 +1,7 @@
 //! Address fetching trait and error types.
 
 use super::AdapterSnapshot;
+use std::time::Duration;
 use thiserror::Error;
 
 /// Error type for address fetching operations.
@@ -21,6 +22,13 @@ pub enum FetchError {
         context: String,
     },
 
+    /// The fetch did not complete within the configured timeout.
+    #[error("Fetch timed out after {timeout:?}")]
+    Timeout {
+        /// The timeout that elapsed.
+        timeout: Duration,
+    },
+
     /// Platform-specific error with a generic message.
     #[error("Platform error: {message}")]
     Platform {
@@ -73,7 +81,8 @@ pub trait AddressFetcher: Send + Sync {
     ///
     /// - Implementations should return ALL adapters; filtering is done by the caller
     /// - Address order within each adapter should be stable across calls
-    /// - This is a synchronous operation; async wrappers can be added if needed
+    /// - This is a synchronous operation; monitor streams can run it on the
+    ///   blocking thread pool with a timeout (see `with_fetch_timeout`)
     fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError>;
 }
 
@@ -173,6 +182,14 @@ mod tests {
         assert!(error.to_string().contains("elevated privileges required"));
     }
 
+    #[test]
+    fn fetch_error_timeout_displays_duration() {
+        let error = FetchError::Timeout {
+            timeout: std::time::Duration::from_secs(30),
+        };
+        assert!(error.to_string().contains("30s"));
+    }
+
     #[test]
     fn fetch_error_platform_displays_message() {
         let error = FetchError::Platform {
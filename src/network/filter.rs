@@ -5,15 +5,18 @@
 //!
 //! # Design
 //!
-//! - **Pure Matchers**: [`KindFilter`] and [`NameRegexFilter`] only answer
-//!   "does this adapter match?" without include/exclude semantics.
+//! - **Pure Matchers**: [`KindFilter`], [`NameRegexFilter`] and
+//!   [`DefaultRouteFilter`] only answer "does this adapter match?" without
+//!   include/exclude semantics.
 //! - **Filter Chain**: [`FilterChain`] combines matchers with correct semantics:
 //!   - Exclude filters: AND logic (must pass ALL excludes)
+//!   - Required filters: AND logic (must match ALL requirements)
 //!   - Include filters: OR logic (pass ANY include, empty = match all)
 //! - **Decorator**: [`FilteredFetcher`] applies filtering transparently
 //!   to any [`AddressFetcher`] implementation.
 
 use std::collections::HashSet;
+use std::sync::{Arc, PoisonError, RwLock};
 
 use regex::Regex;
 
@@ -96,6 +99,37 @@ impl AdapterFilter for KindFilter {
     }
 }
 
+// ============================================================================
+// DefaultRouteFilter - Pure matcher by default route
+// ============================================================================
+
+/// Matches the adapters a default route goes through (pure matcher).
+///
+/// Relies on [`AdapterSnapshot::default_route`], which only the platform
+/// fetchers that read the routing table set.
+///
+/// # Examples
+///
+/// ```
+/// use ddns_a::network::filter::{AdapterFilter, DefaultRouteFilter};
+/// use ddns_a::network::{AdapterKind, AdapterSnapshot};
+///
+/// let uplink = AdapterSnapshot::new("eth0", AdapterKind::Ethernet, vec![], vec![])
+///     .with_default_route(true);
+/// let lan = AdapterSnapshot::new("eth1", AdapterKind::Ethernet, vec![], vec![]);
+///
+/// assert!(DefaultRouteFilter.matches(&uplink));
+/// assert!(!DefaultRouteFilter.matches(&lan));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultRouteFilter;
+
+impl AdapterFilter for DefaultRouteFilter {
+    fn matches(&self, adapter: &AdapterSnapshot) -> bool {
+        adapter.default_route
+    }
+}
+
 // ============================================================================
 // FilterChain - Include OR / Exclude AND semantics
 // ============================================================================
@@ -104,7 +138,8 @@ impl AdapterFilter for KindFilter {
 ///
 /// Evaluation order:
 /// 1. **Exclude filters (AND)**: Any match → reject. Adapter must pass ALL excludes.
-/// 2. **Include filters (OR)**: Any match → accept. Adapter needs to pass ANY include.
+/// 2. **Required filters (AND)**: Any mismatch → reject.
+/// 3. **Include filters (OR)**: Any match → accept. Adapter needs to pass ANY include.
 ///    Empty includes = match all (passthrough).
 ///
 /// # Examples
@@ -129,6 +164,7 @@ impl AdapterFilter for KindFilter {
 pub struct FilterChain {
     includes: Vec<Box<dyn AdapterFilter>>,
     excludes: Vec<Box<dyn AdapterFilter>>,
+    requires: Vec<Box<dyn AdapterFilter>>,
 }
 
 impl FilterChain {
@@ -158,6 +194,16 @@ impl FilterChain {
         self
     }
 
+    /// Adds a required filter (AND semantics - must match ALL).
+    ///
+    /// Adapters not matching a required filter will be rejected, whatever
+    /// include filters they match.
+    #[must_use]
+    pub fn require<F: AdapterFilter + 'static>(mut self, filter: F) -> Self {
+        self.requires.push(Box::new(filter));
+        self
+    }
+
     /// Returns the number of include filters.
     #[must_use]
     pub fn include_count(&self) -> usize {
@@ -170,10 +216,16 @@ impl FilterChain {
         self.excludes.len()
     }
 
+    /// Returns the number of required filters.
+    #[must_use]
+    pub fn require_count(&self) -> usize {
+        self.requires.len()
+    }
+
     /// Returns true if no filters are configured.
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.includes.is_empty() && self.excludes.is_empty()
+        self.includes.is_empty() && self.excludes.is_empty() && self.requires.is_empty()
     }
 }
 
@@ -184,7 +236,12 @@ impl AdapterFilter for FilterChain {
             return false;
         }
 
-        // 2. No includes = all pass; otherwise any include match → accept
+        // 2. Any required mismatch → reject
+        if !self.requires.iter().all(|f| f.matches(adapter)) {
+            return false;
+        }
+
+        // 3. No includes = all pass; otherwise any include match → accept
         self.includes.is_empty() || self.includes.iter().any(|f| f.matches(adapter))
     }
 }
@@ -194,6 +251,7 @@ impl std::fmt::Debug for FilterChain {
         f.debug_struct("FilterChain")
             .field("include_count", &self.includes.len())
             .field("exclude_count", &self.excludes.len())
+            .field("require_count", &self.requires.len())
             .finish()
     }
 }
@@ -252,6 +310,40 @@ impl AdapterFilter for NameRegexFilter {
     }
 }
 
+// ============================================================================
+// SharedFilter - Replaceable FilterChain
+// ============================================================================
+
+/// A [`FilterChain`] that can be replaced while a fetcher applies it, e.g.
+/// on a configuration reload.
+///
+/// Clones share the chain.
+#[derive(Clone, Default)]
+pub struct SharedFilter(Arc<RwLock<FilterChain>>);
+
+impl SharedFilter {
+    /// Creates a shared filter applying `chain`.
+    #[must_use]
+    pub fn new(chain: FilterChain) -> Self {
+        Self(Arc::new(RwLock::new(chain)))
+    }
+
+    /// Replaces the chain for every clone; fetches already filtering finish
+    /// with the old one.
+    pub fn replace(&self, chain: FilterChain) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = chain;
+    }
+}
+
+impl AdapterFilter for SharedFilter {
+    fn matches(&self, adapter: &AdapterSnapshot) -> bool {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .matches(adapter)
+    }
+}
+
 // ============================================================================
 // FilteredFetcher - Decorator for AddressFetcher
 // ============================================================================
@@ -137,6 +137,20 @@ mod kind_filter {
     }
 }
 
+// ============================================================================
+// DefaultRouteFilter Tests
+// ============================================================================
+
+mod default_route_filter {
+    use super::*;
+
+    #[test]
+    fn matches_only_the_default_route_adapter() {
+        assert!(DefaultRouteFilter.matches(&ethernet_adapter().with_default_route(true)));
+        assert!(!DefaultRouteFilter.matches(&ethernet_adapter()));
+    }
+}
+
 // ============================================================================
 // FilterChain Tests
 // ============================================================================
@@ -248,6 +262,24 @@ mod filter_chain {
         assert!(debug_str.contains("exclude_count"));
     }
 
+    #[test]
+    fn required_filters_must_all_match() {
+        let chain = FilterChain::new()
+            .require(DefaultRouteFilter)
+            .include(KindFilter::new([
+                AdapterKind::Ethernet,
+                AdapterKind::Virtual,
+            ]));
+
+        assert_eq!(chain.require_count(), 1);
+        assert!(!chain.is_empty());
+        assert!(chain.matches(&ethernet_adapter().with_default_route(true)));
+        // Matching an include is not enough without the default route
+        assert!(!chain.matches(&virtual_adapter()));
+        // Holding the default route is not enough without an include
+        assert!(!chain.matches(&wifi_adapter().with_default_route(true)));
+    }
+
     #[test]
     fn complex_real_world_scenario() {
         // Include physical adapters (Ethernet/Wireless), exclude loopback
@@ -380,6 +412,22 @@ mod name_regex_filter {
 // FilteredFetcher Tests
 // ============================================================================
 
+mod shared_filter {
+    use super::*;
+
+    #[test]
+    fn replacement_applies_to_every_clone() {
+        let filter = SharedFilter::new(FilterChain::new());
+        let fetcher_copy = filter.clone();
+        assert!(fetcher_copy.matches(&virtual_adapter()));
+
+        filter.replace(FilterChain::new().exclude(KindFilter::new([AdapterKind::Virtual])));
+
+        assert!(!fetcher_copy.matches(&virtual_adapter()));
+        assert!(fetcher_copy.matches(&ethernet_adapter()));
+    }
+}
+
 mod filtered_fetcher {
     use super::*;
     use crate::network::{AddressFetcher, FetchError};
@@ -1,20 +1,35 @@
 //! Network layer for fetching and representing adapter information.
 //!
 //! This module provides types and traits for:
-//! - Representing network adapter snapshots ([`AdapterSnapshot`])
+//! - Representing network adapter snapshots ([`AdapterSnapshot`], [`DnsSettings`],
+//!   [`AdapterMetadata`], [`AddressInfo`])
 //! - IP version filtering ([`IpVersion`])
 //! - Adapter type classification ([`AdapterKind`])
 //! - Fetching adapter information ([`AddressFetcher`])
 //! - Adapter filtering ([`filter`])
+//! - Address-class filtering of link-local, unique local and temporary addresses ([`class`])
+//! - Effective address selection by adapter priority ([`priority`])
+//! - Public address detection behind NAT ([`public`])
 //! - Platform-specific implementations ([`platform`])
 
 mod adapter;
+mod address;
+pub mod class;
 mod fetcher;
 pub mod filter;
 pub mod platform;
+pub mod priority;
+pub mod public;
 
+#[cfg(test)]
+mod class_tests;
 #[cfg(test)]
 mod filter_tests;
+#[cfg(test)]
+mod priority_tests;
 
-pub use adapter::{AdapterKind, AdapterSnapshot, IpVersion};
+pub use adapter::{
+    AdapterKind, AdapterMetadata, AdapterSnapshot, DnsSettings, IpVersion, OperStatus,
+};
+pub use address::{AddressInfo, AddressScope, AddressState};
 pub use fetcher::{AddressFetcher, FetchError};
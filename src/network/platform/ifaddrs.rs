@@ -0,0 +1,202 @@
+//! The `getifaddrs` walk shared by the macOS and Linux fetchers.
+//!
+//! `getifaddrs` returns one entry per address, plus one link-layer entry per
+//! interface (`AF_LINK` on macOS, `AF_PACKET` on Linux). The walk groups the
+//! entries by interface and leaves what differs between the platforms, the
+//! interface kind, the link-layer details and the default routes, to an
+//! [`Interfaces`] implementation.
+
+use crate::network::{AdapterKind, AdapterMetadata, AdapterSnapshot, AddressInfo, FetchError};
+use std::ffi::CStr;
+use std::io;
+use std::mem::offset_of;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// What a platform reads beyond the addresses `getifaddrs` reports.
+pub(super) trait Interfaces {
+    /// Address family of the link-layer entry of each interface.
+    const LINK_FAMILY: i32;
+
+    /// Returns the kind of the interface `name`.
+    fn kind(name: &str) -> AdapterKind;
+
+    /// Reads the link-layer details of an interface from its link-layer
+    /// entry, whose address is `sockaddr`.
+    fn link_metadata(entry: &libc::ifaddrs, sockaddr: &libc::sockaddr) -> AdapterMetadata;
+
+    /// Returns the names of the interfaces an unscoped default route (IPv4
+    /// or IPv6) goes through.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of reading the routing table.
+    fn default_route_interfaces() -> io::Result<Vec<String>>;
+}
+
+/// Fetches all network interfaces using `getifaddrs`, grouping the entries
+/// by interface name in the order the interfaces first appear.
+pub(super) fn fetch_adapters<I: Interfaces>() -> Result<Vec<AdapterSnapshot>, FetchError> {
+    let mut head: *mut libc::ifaddrs = std::ptr::null_mut();
+    // SAFETY: getifaddrs writes a list head we free below
+    if unsafe { libc::getifaddrs(&raw mut head) } != 0 {
+        return Err(FetchError::Platform {
+            message: format!("getifaddrs failed: {}", io::Error::last_os_error()),
+        });
+    }
+
+    let mut adapters: Vec<AdapterSnapshot> = Vec::new();
+    let mut current = head;
+
+    // SAFETY: We iterate through the linked list returned by getifaddrs,
+    // which stays valid until freeifaddrs
+    while !current.is_null() {
+        let entry = unsafe { &*current };
+        current = entry.ifa_next;
+
+        if entry.ifa_name.is_null() {
+            continue;
+        }
+        // SAFETY: ifa_name is a valid null-terminated string
+        let name = unsafe { CStr::from_ptr(entry.ifa_name) }.to_string_lossy();
+
+        let index = adapters
+            .iter()
+            .position(|a| a.name == name)
+            .unwrap_or_else(|| {
+                let kind = I::kind(&name);
+                adapters.push(AdapterSnapshot::new(name, kind, vec![], vec![]));
+                adapters.len() - 1
+            });
+
+        // SAFETY: ifa_addr is null or points to a sockaddr of its family
+        let Some(sockaddr) = (unsafe { entry.ifa_addr.as_ref() }) else {
+            continue;
+        };
+        let address = sockaddr_to_ip(sockaddr);
+        match address {
+            Some(IpAddr::V4(addr)) => adapters[index].ipv4_addresses.push(addr),
+            Some(IpAddr::V6(addr)) => adapters[index].ipv6_addresses.push(addr),
+            None if i32::from(sockaddr.sa_family) == I::LINK_FAMILY => {
+                adapters[index].metadata = Some(I::link_metadata(entry, sockaddr));
+            }
+            // Other families carry no IP address
+            None => {}
+        }
+        if let Some(address) = address {
+            let info = AddressInfo {
+                prefix_len: netmask_prefix(entry, address),
+                state: None,
+            };
+            if !info.is_empty() {
+                adapters[index].address_info.insert(address, info);
+            }
+        }
+    }
+
+    // SAFETY: head came from getifaddrs and is freed exactly once
+    unsafe { libc::freeifaddrs(head) };
+
+    mark_default_routes::<I>(&mut adapters);
+    Ok(adapters)
+}
+
+/// Marks the adapters an unscoped default route goes through.
+///
+/// A routing table that cannot be read leaves every adapter unmarked
+/// instead of failing the fetch.
+fn mark_default_routes<I: Interfaces>(adapters: &mut [AdapterSnapshot]) {
+    let names = match I::default_route_interfaces() {
+        Ok(names) => names,
+        Err(e) => {
+            tracing::debug!("Failed to read the routing table: {e}");
+            return;
+        }
+    };
+    for adapter in adapters.iter_mut().filter(|a| names.contains(&a.name)) {
+        adapter.default_route = true;
+    }
+}
+
+/// Returns the prefix length of the netmask of an address entry.
+fn netmask_prefix(entry: &libc::ifaddrs, address: IpAddr) -> Option<u8> {
+    // SAFETY: ifa_netmask is null or points to a sockaddr
+    let mask = unsafe { entry.ifa_netmask.as_ref() }?;
+    // SAFETY: a netmask sockaddr spans sockaddr_len bytes
+    let raw = unsafe {
+        std::slice::from_raw_parts(
+            std::ptr::from_ref(mask).cast::<u8>(),
+            sockaddr_len(mask, address),
+        )
+    };
+    let (at, len) = match address {
+        IpAddr::V4(_) => (offset_of!(libc::sockaddr_in, sin_addr), 4),
+        IpAddr::V6(_) => (offset_of!(libc::sockaddr_in6, sin6_addr), 16),
+    };
+    // The macOS kernel trims trailing zero bytes off netmasks
+    let mut bytes = [0; 16];
+    for (byte, &value) in bytes.iter_mut().zip(raw.iter().skip(at).take(len)) {
+        *byte = value;
+    }
+    prefix_len(&bytes[..len])
+}
+
+/// Returns the size of the netmask `sockaddr` of `address`, as declared in
+/// it.
+#[cfg(target_os = "macos")]
+fn sockaddr_len(sockaddr: &libc::sockaddr, _address: IpAddr) -> usize {
+    usize::from(sockaddr.sa_len)
+}
+
+/// Returns the size of the netmask `sockaddr` of `address`, the size of
+/// the address's own `sockaddr` since Linux does not declare it.
+#[cfg(target_os = "linux")]
+const fn sockaddr_len(_sockaddr: &libc::sockaddr, address: IpAddr) -> usize {
+    match address {
+        IpAddr::V4(_) => size_of::<libc::sockaddr_in>(),
+        IpAddr::V6(_) => size_of::<libc::sockaddr_in6>(),
+    }
+}
+
+/// Returns the number of leading one bits of a netmask, or `None` if they
+/// are not contiguous.
+pub(super) fn prefix_len(mask: &[u8]) -> Option<u8> {
+    let mut prefix = 0;
+    let mut ended = false;
+    for &byte in mask {
+        let ones = byte.leading_ones();
+        // Every bit after the first zero must be zero as well
+        if (ended && byte != 0) || byte.checked_shl(ones).unwrap_or(0) != 0 {
+            return None;
+        }
+        ended = ones < 8;
+        prefix += ones;
+    }
+    u8::try_from(prefix).ok()
+}
+
+/// Converts a `sockaddr` of family `AF_INET` or `AF_INET6` to an [`IpAddr`].
+///
+/// # Safety Note
+///
+/// The pointer casts to `sockaddr_in` and `sockaddr_in6` are allowed despite
+/// alignment concerns because `getifaddrs` returns properly aligned
+/// structures of the size their family implies.
+#[allow(clippy::cast_ptr_alignment)]
+fn sockaddr_to_ip(sockaddr: &libc::sockaddr) -> Option<IpAddr> {
+    match i32::from(sockaddr.sa_family) {
+        libc::AF_INET => {
+            // SAFETY: We verified the family is AF_INET, so this is a valid cast.
+            let sockaddr_in =
+                unsafe { &*(std::ptr::from_ref(sockaddr).cast::<libc::sockaddr_in>()) };
+            // s_addr holds the address bytes in network order
+            Some(Ipv4Addr::from(sockaddr_in.sin_addr.s_addr.to_ne_bytes()).into())
+        }
+        libc::AF_INET6 => {
+            // SAFETY: We verified the family is AF_INET6, so this is a valid cast.
+            let sockaddr_in6 =
+                unsafe { &*(std::ptr::from_ref(sockaddr).cast::<libc::sockaddr_in6>()) };
+            Some(Ipv6Addr::from(sockaddr_in6.sin6_addr.s6_addr).into())
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,17 @@
+//! Tests for the shared `getifaddrs` walk.
+
+use super::ifaddrs::prefix_len;
+
+#[test]
+fn prefix_len_counts_leading_ones() {
+    assert_eq!(prefix_len(&[255, 255, 255, 0]), Some(24));
+    assert_eq!(prefix_len(&[255, 255, 240, 0]), Some(20));
+    assert_eq!(prefix_len(&[0; 4]), Some(0));
+    assert_eq!(prefix_len(&[255; 16]), Some(128));
+}
+
+#[test]
+fn prefix_len_rejects_non_contiguous_masks() {
+    assert_eq!(prefix_len(&[255, 0, 255, 0]), None);
+    assert_eq!(prefix_len(&[255, 0b1010_0000, 0, 0]), None);
+}
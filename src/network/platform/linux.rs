@@ -0,0 +1,240 @@
+//! Linux-specific network adapter fetching using `getifaddrs`.
+//!
+//! The `getifaddrs` walk is shared with macOS (see [`super::ifaddrs`]); this
+//! module reads the `AF_PACKET` entries, classifies interfaces and reads
+//! their link details from sysfs (`/sys/class/net`), and finds the default
+//! routes in `/proc/net/route` and `/proc/net/ipv6_route`.
+
+use super::ifaddrs::{Interfaces, fetch_adapters};
+use crate::network::{
+    AdapterKind, AdapterMetadata, AdapterSnapshot, AddressFetcher, FetchError, OperStatus,
+};
+use std::ffi::CStr;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// `ARPHRD_ETHER`, the hardware type of Ethernet and most Ethernet-like
+/// interfaces.
+const ARPHRD_ETHER: u16 = 1;
+
+/// `ARPHRD_LOOPBACK`.
+const ARPHRD_LOOPBACK: u16 = 772;
+
+/// Hardware types of tunnels: `ppp`, `ipip`, `tunnel6`, `sit`, `ipgre`,
+/// `ip6gre` and `none`, which `tun` devices and `WireGuard` report.
+const TUNNEL_TYPES: &[u16] = &[512, 768, 769, 776, 778, 823, 65534];
+
+/// Route flag of a usable route (`RTF_UP`).
+const RTF_UP: u32 = 0x0001;
+
+/// Route flag of an unreachable route (`RTF_REJECT`).
+const RTF_REJECT: u32 = 0x0200;
+
+/// Linux implementation of [`AddressFetcher`] using `getifaddrs`.
+///
+/// This fetcher retrieves all network interfaces and their IPv4/IPv6
+/// addresses. Interfaces are classified from sysfs (see [`AdapterKind`]):
+/// devices under `/sys/devices/virtual` (bridges, `veth`, `docker0`,
+/// `WireGuard`...) and tunnels are virtual, devices with a `wireless`
+/// directory are wireless, and other Ethernet devices are Ethernet.
+///
+/// `getifaddrs` knows nothing about DNS, so snapshots carry no DNS settings
+/// (nor a DNS suffix in their metadata) even when [`with_dns`](Self::with_dns)
+/// asks for them. Addresses carry the prefix length of their netmask, but
+/// no preferred or deprecated state, which `getifaddrs` does not report.
+///
+/// # Example
+///
+/// ```no_run
+/// use ddns_a::network::{AddressFetcher, platform::LinuxFetcher};
+///
+/// let fetcher = LinuxFetcher::new();
+/// let adapters = fetcher.fetch().expect("Failed to fetch adapters");
+///
+/// for adapter in adapters {
+///     println!("{}: {:?}", adapter.name, adapter.ipv4_addresses);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LinuxFetcher;
+
+impl LinuxFetcher {
+    /// Creates a new Linux adapter fetcher.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Accepted for parity with the other platform fetchers.
+    ///
+    /// Has no effect: `getifaddrs` reports no DNS settings.
+    #[must_use]
+    pub const fn with_dns(self, _include_dns: bool) -> Self {
+        self
+    }
+}
+
+impl AddressFetcher for LinuxFetcher {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        fetch_adapters::<Self>()
+    }
+}
+
+impl Interfaces for LinuxFetcher {
+    const LINK_FAMILY: i32 = libc::AF_PACKET;
+
+    fn kind(name: &str) -> AdapterKind {
+        let hardware_type = sysfs(name, "type").and_then(|t| t.parse().ok());
+        let device = Path::new("/sys/class/net").join(name);
+        map_interface(
+            hardware_type,
+            device.join("wireless").exists() || device.join("phy80211").exists(),
+            Path::new("/sys/devices/virtual/net").join(name).exists(),
+        )
+    }
+
+    // getifaddrs returns properly aligned structures of their family's size
+    #[allow(clippy::cast_ptr_alignment)]
+    fn link_metadata(entry: &libc::ifaddrs, sockaddr: &libc::sockaddr) -> AdapterMetadata {
+        // SAFETY: an AF_PACKET sockaddr of getifaddrs is a sockaddr_ll
+        let link = unsafe { &*std::ptr::from_ref(sockaddr).cast::<libc::sockaddr_ll>() };
+        let address_len = usize::from(link.sll_halen).min(link.sll_addr.len());
+        // SAFETY: ifa_name was checked to be a valid null-terminated string
+        let name = unsafe { CStr::from_ptr(entry.ifa_name) }.to_string_lossy();
+        let flags = entry.ifa_flags;
+        #[allow(clippy::cast_sign_loss)] // Flags are a bit set
+        let running = (libc::IFF_UP | libc::IFF_RUNNING) as u32;
+
+        AdapterMetadata {
+            mac: AdapterMetadata::format_mac(&link.sll_addr[..address_len]),
+            index: u32::try_from(link.sll_ifindex).ok(),
+            mtu: sysfs(&name, "mtu").and_then(|mtu| mtu.parse().ok()),
+            link_speed: sysfs(&name, "speed").and_then(|speed| parse_speed(&speed)),
+            dns_suffix: None,
+            oper_status: sysfs(&name, "operstate")
+                .and_then(|state| parse_operstate(&state))
+                .or(Some(if flags & running == running {
+                    OperStatus::Up
+                } else {
+                    OperStatus::Down
+                })),
+        }
+    }
+
+    fn default_route_interfaces() -> io::Result<Vec<String>> {
+        let mut names = default_route_interfaces_v4(&fs::read_to_string("/proc/net/route")?);
+        // Without IPv6 the file does not exist
+        if let Ok(table) = fs::read_to_string("/proc/net/ipv6_route") {
+            for name in default_route_interfaces_v6(&table) {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        Ok(names)
+    }
+}
+
+/// Reads the sysfs `attribute` of the interface `name`, trimmed.
+fn sysfs(name: &str, attribute: &str) -> Option<String> {
+    let path = Path::new("/sys/class/net").join(name).join(attribute);
+    fs::read_to_string(path)
+        .ok()
+        .map(|value| value.trim().to_string())
+}
+
+/// Maps the sysfs details of an interface to [`AdapterKind`].
+///
+/// Unknown hardware types map to `AdapterKind::Other` with the type code.
+pub(super) fn map_interface(
+    hardware_type: Option<u16>,
+    wireless: bool,
+    virtual_device: bool,
+) -> AdapterKind {
+    match hardware_type {
+        Some(ARPHRD_LOOPBACK) => AdapterKind::Loopback,
+        _ if wireless => AdapterKind::Wireless,
+        _ if virtual_device => AdapterKind::Virtual,
+        Some(code) if TUNNEL_TYPES.contains(&code) => AdapterKind::Virtual,
+        Some(ARPHRD_ETHER) => AdapterKind::Ethernet,
+        Some(code) => AdapterKind::Other(u32::from(code)),
+        None => AdapterKind::Other(0),
+    }
+}
+
+/// Parses the sysfs `speed` of an interface, in Mbit/s, to bits per second.
+///
+/// Interfaces without a link report `-1`, or fail to read.
+pub(super) fn parse_speed(speed: &str) -> Option<u64> {
+    speed
+        .parse::<u64>()
+        .ok()
+        .filter(|&mbits| mbits != 0)
+        .map(|mbits| mbits * 1_000_000)
+}
+
+/// Parses the sysfs `operstate` of an interface (RFC 2863 states).
+pub(super) fn parse_operstate(state: &str) -> Option<OperStatus> {
+    match state {
+        "up" => Some(OperStatus::Up),
+        "down" => Some(OperStatus::Down),
+        "testing" => Some(OperStatus::Testing),
+        "dormant" => Some(OperStatus::Dormant),
+        "notpresent" => Some(OperStatus::NotPresent),
+        "lowerlayerdown" => Some(OperStatus::LowerLayerDown),
+        // Drivers that do not track the state report "unknown"
+        _ => None,
+    }
+}
+
+/// Returns the interfaces of the default routes in `/proc/net/route`.
+///
+/// Each line after the header is `Iface Destination Gateway Flags RefCnt
+/// Use Metric Mask ...`, addresses and flags in hex.
+pub(super) fn default_route_interfaces_v4(table: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for fields in table
+        .lines()
+        .skip(1)
+        .map(|l| l.split_whitespace().collect::<Vec<_>>())
+    {
+        let [name, destination, _, flags, _, _, _, mask, ..] = fields[..] else {
+            continue;
+        };
+        let up = u32::from_str_radix(flags, 16).is_ok_and(|f| f & RTF_UP != 0);
+        if up && destination == "00000000" && mask == "00000000" && !names.iter().any(|n| n == name)
+        {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Returns the interfaces of the default routes in `/proc/net/ipv6_route`.
+///
+/// Each line is `destination prefix source prefix next-hop metric refcnt
+/// use flags iface`, in hex. The kernel keeps an unreachable default route
+/// on `lo`, which is skipped.
+pub(super) fn default_route_interfaces_v6(table: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for fields in table
+        .lines()
+        .map(|l| l.split_whitespace().collect::<Vec<_>>())
+    {
+        let [destination, prefix, _, _, _, _, _, _, flags, name] = fields[..] else {
+            continue;
+        };
+        let flags = u32::from_str_radix(flags, 16).unwrap_or(0);
+        let default = prefix == "00" && destination.bytes().all(|b| b == b'0');
+        if default
+            && flags & RTF_UP != 0
+            && flags & RTF_REJECT == 0
+            && name != "lo"
+            && !names.iter().any(|n| n == name)
+        {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
@@ -0,0 +1,144 @@
+//! Tests for the Linux `getifaddrs` fetcher.
+
+use super::linux::{
+    LinuxFetcher, default_route_interfaces_v4, default_route_interfaces_v6, map_interface,
+    parse_operstate, parse_speed,
+};
+use crate::network::{AdapterKind, AddressFetcher, OperStatus};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+#[test]
+fn map_interface_loopback() {
+    assert_eq!(map_interface(Some(772), false, true), AdapterKind::Loopback);
+}
+
+#[test]
+fn map_interface_physical_ethernet() {
+    assert_eq!(map_interface(Some(1), false, false), AdapterKind::Ethernet);
+}
+
+#[test]
+fn map_interface_wireless_before_ethernet() {
+    assert_eq!(map_interface(Some(1), true, false), AdapterKind::Wireless);
+}
+
+#[test]
+fn map_interface_virtual_devices_and_tunnels() {
+    // docker0, veth, br0
+    assert_eq!(map_interface(Some(1), false, true), AdapterKind::Virtual);
+    // tun0 or wg0 outside /sys/devices/virtual
+    assert_eq!(
+        map_interface(Some(65534), false, false),
+        AdapterKind::Virtual
+    );
+}
+
+#[test]
+fn map_interface_unknown_keeps_type_code() {
+    assert_eq!(
+        map_interface(Some(32), false, false),
+        AdapterKind::Other(32)
+    );
+    assert_eq!(map_interface(None, false, false), AdapterKind::Other(0));
+}
+
+#[test]
+fn parse_speed_converts_to_bits() {
+    assert_eq!(parse_speed("1000"), Some(1_000_000_000));
+    assert_eq!(parse_speed("-1"), None);
+    assert_eq!(parse_speed("0"), None);
+}
+
+#[test]
+fn parse_operstate_maps_known_states() {
+    assert_eq!(parse_operstate("up"), Some(OperStatus::Up));
+    assert_eq!(
+        parse_operstate("lowerlayerdown"),
+        Some(OperStatus::LowerLayerDown)
+    );
+    assert_eq!(parse_operstate("unknown"), None);
+}
+
+#[test]
+fn default_route_interfaces_v4_finds_default_routes() {
+    let table = "\
+Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT
+eth0\t00000000\t0101A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0
+eth0\t0001A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0
+wlan0\t00000000\t0102A8C0\t0002\t0\t0\t600\t00000000\t0\t0\t0
+wg0\t00000000\t00000000\t0001\t0\t0\t50\t00000000\t0\t0\t0
+eth0\t00000000\t0101A8C0\t0003\t0\t0\t200\t00000000\t0\t0\t0
+";
+
+    assert_eq!(default_route_interfaces_v4(table), ["eth0", "wg0"]);
+}
+
+#[test]
+fn default_route_interfaces_v6_skips_unreachable_and_loopback() {
+    let zero = "00000000000000000000000000000000";
+    let table = format!(
+        "\
+{zero} 00 {zero} 00 fe800000000000000000000000000001 00000400 00000001 00000000 00000003 eth0
+20010db8000000000000000000000000 40 {zero} 00 {zero} 00000100 00000001 00000000 00000001 eth0
+{zero} 00 {zero} 00 {zero} ffffffff 00000001 00000000 00200200 lo
+{zero} 00 {zero} 00 {zero} 00000400 00000001 00000000 00000201 eth1
+"
+    );
+
+    assert_eq!(default_route_interfaces_v6(&table), ["eth0"]);
+}
+
+#[test]
+fn fetch_with_dns_leaves_settings_empty() {
+    let adapters = LinuxFetcher::new()
+        .with_dns(true)
+        .fetch()
+        .expect("fetch() failed");
+
+    assert!(adapters.iter().all(|a| a.dns.is_none()));
+}
+
+// Integration test: actually fetches adapters from the system
+#[test]
+fn fetch_adapters_returns_loopback_once() {
+    let adapters = LinuxFetcher.fetch().expect("fetch() failed");
+
+    let loopback: Vec<_> = adapters
+        .iter()
+        .filter(|a| a.kind == AdapterKind::Loopback)
+        .collect();
+    assert_eq!(loopback.len(), 1, "adapters: {adapters:?}");
+    assert!(
+        loopback[0].ipv4_addresses.contains(&Ipv4Addr::LOCALHOST)
+            || loopback[0].ipv6_addresses.contains(&Ipv6Addr::LOCALHOST),
+        "adapters: {adapters:?}"
+    );
+}
+
+#[test]
+fn fetch_adapters_reads_link_metadata() {
+    let adapters = LinuxFetcher.fetch().expect("fetch() failed");
+
+    let loopback = adapters
+        .iter()
+        .find(|a| a.kind == AdapterKind::Loopback)
+        .expect("no loopback adapter");
+    let metadata = loopback.metadata.as_ref().expect("no loopback metadata");
+    assert!(
+        metadata.index.is_some() && metadata.mtu.is_some(),
+        "{metadata:?}"
+    );
+    assert_eq!(metadata.mac, None);
+}
+
+#[test]
+fn fetch_adapters_reads_prefix_lengths() {
+    let adapters = LinuxFetcher.fetch().expect("fetch() failed");
+
+    let loopback = adapters
+        .iter()
+        .find(|a| a.ipv4_addresses.contains(&Ipv4Addr::LOCALHOST))
+        .expect("no adapter with 127.0.0.1");
+    let info = loopback.info_of(IpAddr::V4(Ipv4Addr::LOCALHOST));
+    assert_eq!(info.prefix_len, Some(8), "{loopback:?}");
+}
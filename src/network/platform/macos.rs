@@ -0,0 +1,278 @@
+//! macOS-specific network adapter fetching using `getifaddrs`.
+//!
+//! The `getifaddrs` walk is shared with Linux (see [`super::ifaddrs`]); this
+//! module reads the `AF_LINK` entries, classifies interfaces by name and
+//! finds the default routes in the routing table.
+
+use super::ifaddrs::{Interfaces, fetch_adapters};
+use crate::network::{
+    AdapterKind, AdapterMetadata, AdapterSnapshot, AddressFetcher, FetchError, OperStatus,
+};
+use std::ffi::CStr;
+use std::io;
+use std::mem::offset_of;
+
+/// Interface name prefixes of virtual adapters.
+///
+/// `utun` (VPNs, iCloud Private Relay), `ipsec` and `ppp` are tunnels;
+/// `bridge` is Internet Sharing and the VM bridges; `awdl` and `llw` are the
+/// Apple Wireless Direct Link used by `AirDrop`; `gif` and `stf` are the
+/// built-in 6in4/6to4 tunnels; `vmenet`, `feth` and `anpi` are created by
+/// hypervisors and the system itself.
+const VIRTUAL_PREFIXES: &[&str] = &[
+    "utun", "ipsec", "ppp", "bridge", "awdl", "llw", "gif", "stf", "vmenet", "feth", "anpi",
+    "vboxnet", "tap", "tun",
+];
+
+/// macOS implementation of [`AddressFetcher`] using `getifaddrs`.
+///
+/// This fetcher retrieves all network interfaces and their IPv4/IPv6
+/// addresses from the BSD networking stack. Interfaces are classified by
+/// name (see [`AdapterKind`]); macOS names Wi-Fi and Ethernet ports alike
+/// (`en0`, `en1`, ...), so both are reported as [`AdapterKind::Ethernet`].
+///
+/// `getifaddrs` knows nothing about DNS, so snapshots carry no DNS settings
+/// (nor a DNS suffix in their metadata) even when [`with_dns`](Self::with_dns)
+/// asks for them. The adapters holding the default route are read from the
+/// routing table (`sysctl`); the other metadata comes from each interface's
+/// `AF_LINK` entry. Addresses carry the prefix length of their netmask, but
+/// no preferred or deprecated state, which `getifaddrs` does not report.
+///
+/// # Example
+///
+/// ```no_run
+/// use ddns_a::network::{AddressFetcher, platform::MacFetcher};
+///
+/// let fetcher = MacFetcher::new();
+/// let adapters = fetcher.fetch().expect("Failed to fetch adapters");
+///
+/// for adapter in adapters {
+///     println!("{}: {:?}", adapter.name, adapter.ipv4_addresses);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MacFetcher;
+
+impl MacFetcher {
+    /// Creates a new macOS adapter fetcher.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Accepted for parity with the other platform fetchers.
+    ///
+    /// Has no effect: `getifaddrs` reports no DNS settings.
+    #[must_use]
+    pub const fn with_dns(self, _include_dns: bool) -> Self {
+        self
+    }
+}
+
+impl AddressFetcher for MacFetcher {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        fetch_adapters::<Self>()
+    }
+}
+
+impl Interfaces for MacFetcher {
+    const LINK_FAMILY: i32 = libc::AF_LINK;
+
+    fn kind(name: &str) -> AdapterKind {
+        map_interface_name(name)
+    }
+
+    fn link_metadata(entry: &libc::ifaddrs, sockaddr: &libc::sockaddr) -> AdapterMetadata {
+        link_metadata(entry, sockaddr)
+    }
+
+    fn default_route_interfaces() -> io::Result<Vec<String>> {
+        let table = routing_table()?;
+        Ok(default_route_indexes(&table)
+            .into_iter()
+            .filter_map(interface_name)
+            .collect())
+    }
+}
+
+/// Reads the link-layer details of an interface from its `AF_LINK` entry.
+fn link_metadata(entry: &libc::ifaddrs, sockaddr: &libc::sockaddr) -> AdapterMetadata {
+    // SAFETY: an AF_LINK sockaddr is a sockaddr_dl of sa_len bytes
+    let sdl = unsafe {
+        std::slice::from_raw_parts(
+            std::ptr::from_ref(sockaddr).cast::<u8>(),
+            usize::from(sockaddr.sa_len),
+        )
+    };
+    let (index, mac) = parse_sockaddr_dl(sdl).map_or((None, None), |(index, mac)| {
+        (Some(u32::from(index)), AdapterMetadata::format_mac(mac))
+    });
+    // SAFETY: ifa_data of an AF_LINK entry is null or points to its if_data
+    let data = unsafe { entry.ifa_data.cast::<libc::if_data>().as_ref() };
+    let flags = i64::from(entry.ifa_flags);
+    let running = i64::from(libc::IFF_UP | libc::IFF_RUNNING);
+
+    AdapterMetadata {
+        mac,
+        index,
+        mtu: data.map(|d| d.ifi_mtu),
+        // Zero when the driver does not report a speed
+        link_speed: data
+            .map(|d| u64::from(d.ifi_baudrate))
+            .filter(|&speed| speed != 0),
+        dns_suffix: None,
+        oper_status: Some(if flags & running == running {
+            OperStatus::Up
+        } else {
+            OperStatus::Down
+        }),
+    }
+}
+
+/// Returns the interface index and hardware address of a raw `sockaddr_dl`.
+///
+/// The address follows the interface name in `sdl_data`, which may run past
+/// the declared size of the struct.
+pub(super) fn parse_sockaddr_dl(sdl: &[u8]) -> Option<(u16, &[u8])> {
+    let at = offset_of!(libc::sockaddr_dl, sdl_index);
+    let index = u16::from_ne_bytes(sdl.get(at..at + 2)?.try_into().ok()?);
+    let name_len = usize::from(*sdl.get(offset_of!(libc::sockaddr_dl, sdl_nlen))?);
+    let address_len = usize::from(*sdl.get(offset_of!(libc::sockaddr_dl, sdl_alen))?);
+    let start = offset_of!(libc::sockaddr_dl, sdl_data) + name_len;
+    Some((index, sdl.get(start..start + address_len)?))
+}
+
+/// Dumps the routes through a gateway as routing messages.
+fn routing_table() -> io::Result<Vec<u8>> {
+    let mut mib = [
+        libc::CTL_NET,
+        libc::PF_ROUTE,
+        0,
+        0, // every address family
+        libc::NET_RT_FLAGS,
+        libc::RTF_GATEWAY,
+    ];
+    let mut len = 0;
+    // SAFETY: a null buffer only asks for the size of the table
+    let result = unsafe {
+        libc::sysctl(
+            mib.as_mut_ptr(),
+            6,
+            std::ptr::null_mut(),
+            &raw mut len,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // Leave room for routes added in between
+    let mut buf = vec![0u8; len + len / 4];
+    len = buf.len();
+    // SAFETY: buf is valid for writes of len bytes
+    let result = unsafe {
+        libc::sysctl(
+            mib.as_mut_ptr(),
+            6,
+            buf.as_mut_ptr().cast(),
+            &raw mut len,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    buf.truncate(len);
+    Ok(buf)
+}
+
+/// Returns the interface indexes of the unscoped default routes (IPv4 or
+/// IPv6) in a routing table dump, without duplicates.
+///
+/// Parsing stops at the first malformed message, keeping what was found.
+pub(super) fn default_route_indexes(buf: &[u8]) -> Vec<u16> {
+    const HDRLEN: usize = size_of::<libc::rt_msghdr>();
+
+    let mut indexes = Vec::new();
+    let mut rest = buf;
+    while rest.len() >= HDRLEN {
+        let len = usize::from(u16::from_ne_bytes([rest[0], rest[1]]));
+        if len < HDRLEN || len > rest.len() {
+            break;
+        }
+        let (header, addresses) = rest[..len].split_at(HDRLEN);
+        let flags = read_i32(header, offset_of!(libc::rt_msghdr, rtm_flags));
+        let addrs = read_i32(header, offset_of!(libc::rt_msghdr, rtm_addrs));
+        let at = offset_of!(libc::rt_msghdr, rtm_index);
+        let index = u16::from_ne_bytes([header[at], header[at + 1]]);
+        // Scoped routes only apply to traffic bound to their interface
+        if flags & libc::RTF_UP != 0
+            && flags & libc::RTF_IFSCOPE == 0
+            && addrs & libc::RTA_DST != 0
+            && is_unspecified(addresses)
+            && !indexes.contains(&index)
+        {
+            indexes.push(index);
+        }
+        rest = &rest[len..];
+    }
+    indexes
+}
+
+fn read_i32(bytes: &[u8], at: usize) -> i32 {
+    bytes
+        .get(at..at + 4)
+        .and_then(|b| b.try_into().ok())
+        .map_or(0, i32::from_ne_bytes)
+}
+
+/// Returns true if the raw `sockaddr` is `0.0.0.0` or `::`, the destination
+/// of a default route.
+fn is_unspecified(sockaddr: &[u8]) -> bool {
+    let address = match sockaddr.get(1).map(|&family| i32::from(family)) {
+        Some(libc::AF_INET) => sockaddr.get(4..8),
+        Some(libc::AF_INET6) => sockaddr.get(8..24),
+        _ => None,
+    };
+    address.is_some_and(|bytes| bytes.iter().all(|&b| b == 0))
+}
+
+/// Returns the name of the interface with `index`.
+fn interface_name(index: u16) -> Option<String> {
+    let mut buf = [0 as libc::c_char; libc::IF_NAMESIZE];
+    // SAFETY: buf holds IF_NAMESIZE bytes, as if_indextoname requires
+    let name = unsafe { libc::if_indextoname(u32::from(index), buf.as_mut_ptr()) };
+    if name.is_null() {
+        return None;
+    }
+    // SAFETY: on success buf holds a null-terminated name
+    Some(
+        unsafe { CStr::from_ptr(buf.as_ptr()) }
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
+
+/// Maps a BSD interface name to [`AdapterKind`].
+///
+/// Names without a known prefix map to `AdapterKind::Other(0)`: unlike
+/// Windows, there is no interface type code to preserve.
+pub(super) fn map_interface_name(name: &str) -> AdapterKind {
+    if has_unit(name, "lo") {
+        AdapterKind::Loopback
+    } else if VIRTUAL_PREFIXES.iter().any(|prefix| has_unit(name, prefix)) {
+        AdapterKind::Virtual
+    } else if has_unit(name, "en") {
+        AdapterKind::Ethernet
+    } else {
+        AdapterKind::Other(0)
+    }
+}
+
+/// Returns true if `name` is `prefix` followed by a unit number (`utun3`).
+fn has_unit(name: &str, prefix: &str) -> bool {
+    name.strip_prefix(prefix)
+        .is_some_and(|unit| !unit.is_empty() && unit.bytes().all(|b| b.is_ascii_digit()))
+}
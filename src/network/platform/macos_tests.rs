@@ -0,0 +1,176 @@
+//! Tests for the macOS `getifaddrs` fetcher.
+
+use super::macos::{MacFetcher, default_route_indexes, map_interface_name, parse_sockaddr_dl};
+use crate::network::{AdapterKind, AddressFetcher};
+use std::mem::offset_of;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+#[test]
+fn map_interface_name_loopback() {
+    assert_eq!(map_interface_name("lo0"), AdapterKind::Loopback);
+}
+
+#[test]
+fn map_interface_name_en_is_ethernet() {
+    assert_eq!(map_interface_name("en0"), AdapterKind::Ethernet);
+    assert_eq!(map_interface_name("en12"), AdapterKind::Ethernet);
+}
+
+#[test]
+fn map_interface_name_tunnels_and_bridges_are_virtual() {
+    for name in [
+        "utun0",
+        "utun4",
+        "bridge100",
+        "awdl0",
+        "llw0",
+        "gif0",
+        "stf0",
+        "ipsec0",
+    ] {
+        assert_eq!(map_interface_name(name), AdapterKind::Virtual, "{name}");
+    }
+}
+
+#[test]
+fn map_interface_name_requires_unit_number() {
+    assert_eq!(map_interface_name("utunnel"), AdapterKind::Other(0));
+    assert_eq!(map_interface_name("enx"), AdapterKind::Other(0));
+    assert_eq!(map_interface_name("en"), AdapterKind::Other(0));
+}
+
+#[test]
+fn map_interface_name_unknown_is_other() {
+    assert_eq!(map_interface_name("ap1"), AdapterKind::Other(0));
+}
+
+#[test]
+fn fetch_with_dns_leaves_settings_empty() {
+    let adapters = MacFetcher::new()
+        .with_dns(true)
+        .fetch()
+        .expect("fetch() failed");
+
+    assert!(adapters.iter().all(|a| a.dns.is_none()));
+}
+
+/// A routing message for interface `index` to the destination `sockaddr`.
+fn route(index: u16, flags: i32, sockaddr: &[u8]) -> Vec<u8> {
+    let mut message = vec![0u8; size_of::<libc::rt_msghdr>()];
+    let len = u16::try_from(message.len() + sockaddr.len()).unwrap();
+    message[..2].copy_from_slice(&len.to_ne_bytes());
+    let at = offset_of!(libc::rt_msghdr, rtm_index);
+    message[at..at + 2].copy_from_slice(&index.to_ne_bytes());
+    let at = offset_of!(libc::rt_msghdr, rtm_flags);
+    message[at..at + 4].copy_from_slice(&flags.to_ne_bytes());
+    let at = offset_of!(libc::rt_msghdr, rtm_addrs);
+    message[at..at + 4].copy_from_slice(&libc::RTA_DST.to_ne_bytes());
+    message.extend_from_slice(sockaddr);
+    message
+}
+
+/// A raw `sockaddr_in` for `address`.
+fn sockaddr_v4(address: [u8; 4]) -> Vec<u8> {
+    let mut sockaddr = vec![16, u8::try_from(libc::AF_INET).unwrap(), 0, 0];
+    sockaddr.extend_from_slice(&address);
+    sockaddr.resize(16, 0);
+    sockaddr
+}
+
+#[test]
+fn default_route_indexes_finds_unscoped_defaults() {
+    let up = libc::RTF_UP | libc::RTF_GATEWAY;
+    let mut sockaddr_v6 = vec![28, u8::try_from(libc::AF_INET6).unwrap()];
+    sockaddr_v6.resize(28, 0);
+    let table = [
+        route(4, up, &sockaddr_v4([0, 0, 0, 0])),
+        route(5, up, &sockaddr_v4([10, 0, 0, 0])),
+        route(6, up | libc::RTF_IFSCOPE, &sockaddr_v4([0, 0, 0, 0])),
+        route(7, libc::RTF_GATEWAY, &sockaddr_v4([0, 0, 0, 0])),
+        route(4, up, &sockaddr_v6),
+    ]
+    .concat();
+
+    assert_eq!(default_route_indexes(&table), [4]);
+}
+
+#[test]
+fn default_route_indexes_stops_at_truncated_message() {
+    let mut table = route(4, libc::RTF_UP, &sockaddr_v4([0, 0, 0, 0]));
+    table.truncate(table.len() - 1);
+
+    assert!(default_route_indexes(&table).is_empty());
+}
+
+/// A raw `sockaddr_dl` of interface `index` named `name` with `address`.
+fn sockaddr_dl(index: u16, name: &[u8], address: &[u8]) -> Vec<u8> {
+    let mut sdl = vec![0u8; offset_of!(libc::sockaddr_dl, sdl_data)];
+    let at = offset_of!(libc::sockaddr_dl, sdl_index);
+    sdl[at..at + 2].copy_from_slice(&index.to_ne_bytes());
+    sdl[offset_of!(libc::sockaddr_dl, sdl_nlen)] = u8::try_from(name.len()).unwrap();
+    sdl[offset_of!(libc::sockaddr_dl, sdl_alen)] = u8::try_from(address.len()).unwrap();
+    sdl.extend_from_slice(name);
+    sdl.extend_from_slice(address);
+    sdl
+}
+
+#[test]
+fn parse_sockaddr_dl_reads_index_and_address_after_name() {
+    let mac = [0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e];
+    let sdl = sockaddr_dl(4, b"en0", &mac);
+
+    assert_eq!(parse_sockaddr_dl(&sdl), Some((4, &mac[..])));
+}
+
+#[test]
+fn parse_sockaddr_dl_rejects_truncated_address() {
+    let mut sdl = sockaddr_dl(4, b"en0", &[0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]);
+    sdl.truncate(sdl.len() - 1);
+
+    assert_eq!(parse_sockaddr_dl(&sdl), None);
+}
+
+// Integration test: actually fetches adapters from the system
+#[test]
+fn fetch_adapters_returns_loopback_once() {
+    let adapters = MacFetcher.fetch().expect("fetch() failed");
+
+    let loopback: Vec<_> = adapters
+        .iter()
+        .filter(|a| a.kind == AdapterKind::Loopback)
+        .collect();
+    assert_eq!(loopback.len(), 1, "adapters: {adapters:?}");
+    assert!(
+        loopback[0].ipv4_addresses.contains(&Ipv4Addr::LOCALHOST)
+            || loopback[0].ipv6_addresses.contains(&Ipv6Addr::LOCALHOST),
+        "adapters: {adapters:?}"
+    );
+}
+
+#[test]
+fn fetch_adapters_reads_link_metadata() {
+    let adapters = MacFetcher.fetch().expect("fetch() failed");
+
+    let loopback = adapters
+        .iter()
+        .find(|a| a.kind == AdapterKind::Loopback)
+        .expect("no loopback adapter");
+    let metadata = loopback.metadata.as_ref().expect("no loopback metadata");
+    assert!(
+        metadata.index.is_some() && metadata.mtu.is_some(),
+        "{metadata:?}"
+    );
+    assert_eq!(metadata.mac, None);
+}
+
+#[test]
+fn fetch_adapters_reads_prefix_lengths() {
+    let adapters = MacFetcher.fetch().expect("fetch() failed");
+
+    let loopback = adapters
+        .iter()
+        .find(|a| a.ipv4_addresses.contains(&Ipv4Addr::LOCALHOST))
+        .expect("no adapter with 127.0.0.1");
+    let info = loopback.info_of(IpAddr::V4(Ipv4Addr::LOCALHOST));
+    assert_eq!(info.prefix_len, Some(8), "{loopback:?}");
+}
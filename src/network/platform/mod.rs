@@ -6,15 +6,44 @@
 //! # Platform Support
 //!
 //! - **Windows**: Uses `GetAdaptersAddresses` API via the `windows` crate.
-//! - **Linux**: Planned for future (netlink).
-//! - **macOS**: Planned for future (getifaddrs).
+//! - **macOS**: Uses `getifaddrs`, classifying interfaces by name.
+//! - **Linux**: Uses `getifaddrs`, classifying interfaces from sysfs.
+//!
+//! [`AddressFetcher`]: crate::network::AddressFetcher
 
 #[cfg(windows)]
 mod windows;
 
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+mod ifaddrs;
+#[cfg(all(any(target_os = "macos", target_os = "linux"), test))]
+mod ifaddrs_tests;
+
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(all(target_os = "macos", test))]
+mod macos_tests;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(all(target_os = "linux", test))]
+mod linux_tests;
+
 #[cfg(windows)]
 pub use windows::WindowsFetcher;
 
+#[cfg(target_os = "macos")]
+pub use macos::MacFetcher;
+
+#[cfg(target_os = "linux")]
+pub use linux::LinuxFetcher;
+
 // Re-export platform-specific fetcher as PlatformFetcher for convenience
 #[cfg(windows)]
 pub use windows::WindowsFetcher as PlatformFetcher;
+
+#[cfg(target_os = "macos")]
+pub use macos::MacFetcher as PlatformFetcher;
+
+#[cfg(target_os = "linux")]
+pub use linux::LinuxFetcher as PlatformFetcher;
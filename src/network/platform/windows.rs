@@ -1,14 +1,23 @@
 //! Windows-specific network adapter fetching using `GetAdaptersAddresses`.
 
-use crate::network::{AdapterKind, AdapterSnapshot, AddressFetcher, FetchError};
-use std::net::{Ipv4Addr, Ipv6Addr};
+use crate::network::{
+    AdapterKind, AdapterMetadata, AdapterSnapshot, AddressFetcher, AddressInfo, AddressState,
+    DnsSettings, FetchError, OperStatus,
+};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use windows::Win32::Foundation::WIN32_ERROR;
 use windows::Win32::NetworkManagement::IpHelper::{
-    GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_DNS_SERVER, GAA_FLAG_SKIP_MULTICAST, GetAdaptersAddresses,
+    GAA_FLAG_INCLUDE_GATEWAYS, GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_DNS_SERVER,
+    GAA_FLAG_SKIP_MULTICAST, GET_ADAPTERS_ADDRESSES_FLAGS, GetAdaptersAddresses,
     IF_TYPE_ETHERNET_CSMACD, IF_TYPE_IEEE80211, IF_TYPE_SOFTWARE_LOOPBACK, IP_ADAPTER_ADDRESSES_LH,
 };
+use windows::Win32::NetworkManagement::Ndis::{
+    IF_OPER_STATUS, IfOperStatusDormant, IfOperStatusDown, IfOperStatusLowerLayerDown,
+    IfOperStatusNotPresent, IfOperStatusTesting, IfOperStatusUp,
+};
 use windows::Win32::Networking::WinSock::{
-    AF_INET, AF_INET6, AF_UNSPEC, SOCKADDR_IN, SOCKADDR_IN6,
+    AF_INET, AF_INET6, AF_UNSPEC, IpDadStateDeprecated, IpDadStatePreferred, IpSuffixOriginRandom,
+    NL_DAD_STATE, SOCKADDR, SOCKADDR_IN, SOCKADDR_IN6,
 };
 
 /// Interface type for PPP (Point-to-Point Protocol) adapters.
@@ -42,27 +51,35 @@ const INITIAL_BUFFER_SIZE: u32 = 16384;
 /// ```
 #[derive(Debug, Clone, Default)]
 pub struct WindowsFetcher {
-    // Currently no configuration needed, but struct allows future extension
-    _private: (),
+    include_dns: bool,
 }
 
 impl WindowsFetcher {
     /// Creates a new Windows adapter fetcher.
     #[must_use]
     pub const fn new() -> Self {
-        Self { _private: () }
+        Self { include_dns: false }
+    }
+
+    /// Also collects each adapter's DNS suffix and DNS servers.
+    ///
+    /// Off by default, since most setups never look at them.
+    #[must_use]
+    pub const fn with_dns(mut self, include_dns: bool) -> Self {
+        self.include_dns = include_dns;
+        self
     }
 }
 
 impl AddressFetcher for WindowsFetcher {
     fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
-        fetch_adapters()
+        fetch_adapters(self.include_dns)
     }
 }
 
 /// Fetches all network adapters using `GetAdaptersAddresses`.
-fn fetch_adapters() -> Result<Vec<AdapterSnapshot>, FetchError> {
-    let raw_adapters = get_adapter_addresses()?;
+fn fetch_adapters(include_dns: bool) -> Result<Vec<AdapterSnapshot>, FetchError> {
+    let raw_adapters = get_adapter_addresses(include_dns)?;
 
     let mut adapters = Vec::new();
     // SAFETY: GetAdaptersAddresses returns a properly aligned buffer for IP_ADAPTER_ADDRESSES_LH.
@@ -75,7 +92,7 @@ fn fetch_adapters() -> Result<Vec<AdapterSnapshot>, FetchError> {
     while !current.is_null() {
         let adapter = unsafe { &*current };
 
-        if let Some(snapshot) = parse_adapter(adapter) {
+        if let Some(snapshot) = parse_adapter(adapter, include_dns) {
             adapters.push(snapshot);
         }
 
@@ -90,9 +107,13 @@ fn fetch_adapters() -> Result<Vec<AdapterSnapshot>, FetchError> {
 /// This function handles the two-call pattern:
 /// 1. First call with estimated buffer size
 /// 2. Retry with exact size if buffer was too small
-fn get_adapter_addresses() -> Result<Vec<u8>, FetchError> {
-    // Flags to skip data we don't need (anycast, multicast, DNS servers)
-    let flags = GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST | GAA_FLAG_SKIP_DNS_SERVER;
+fn get_adapter_addresses(include_dns: bool) -> Result<Vec<u8>, FetchError> {
+    // Flags to skip data we don't need (anycast, multicast, and DNS servers
+    // unless DNS tracking asked for them); gateways mark the default route
+    let mut flags = GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST | GAA_FLAG_INCLUDE_GATEWAYS;
+    if !include_dns {
+        flags |= GAA_FLAG_SKIP_DNS_SERVER;
+    }
     let family = u32::from(AF_UNSPEC.0); // Get both IPv4 and IPv6
 
     let mut buffer: Vec<u8> = vec![0u8; INITIAL_BUFFER_SIZE as usize];
@@ -128,7 +149,7 @@ fn handle_api_result(
     result: u32,
     buffer: &mut Vec<u8>,
     size: &mut u32,
-    flags: windows::Win32::NetworkManagement::IpHelper::GET_ADAPTERS_ADDRESSES_FLAGS,
+    flags: GET_ADAPTERS_ADDRESSES_FLAGS,
     family: u32,
 ) -> Result<(), FetchError> {
     use windows::Win32::Foundation::{ERROR_BUFFER_OVERFLOW, NO_ERROR};
@@ -160,22 +181,23 @@ fn handle_api_result(
 /// Parses a single `IP_ADAPTER_ADDRESSES_LH` structure into an [`AdapterSnapshot`].
 ///
 /// Returns `None` if the adapter name cannot be read.
-fn parse_adapter(adapter: &IP_ADAPTER_ADDRESSES_LH) -> Option<AdapterSnapshot> {
+fn parse_adapter(adapter: &IP_ADAPTER_ADDRESSES_LH, include_dns: bool) -> Option<AdapterSnapshot> {
     // Get the friendly name (wide string)
     let name = unsafe { adapter.FriendlyName.to_string().ok()? };
 
     // Map the adapter type
     let kind = map_adapter_type(adapter.IfType);
 
-    // Collect all unicast addresses
-    let (ipv4_addresses, ipv6_addresses) = collect_addresses(adapter);
-
-    Some(AdapterSnapshot::new(
-        name,
-        kind,
-        ipv4_addresses,
-        ipv6_addresses,
-    ))
+    // Windows lists an adapter's default gateways, i.e. its default routes
+    let mut snapshot = AdapterSnapshot::new(name, kind, vec![], vec![])
+        .with_default_route(!adapter.FirstGatewayAddress.is_null())
+        .with_metadata(collect_metadata(adapter));
+    collect_addresses(adapter, &mut snapshot);
+    Some(if include_dns {
+        snapshot.with_dns(collect_dns(adapter))
+    } else {
+        snapshot
+    })
 }
 
 /// Maps Windows `IF_TYPE_*` constants to [`AdapterKind`].
@@ -190,18 +212,10 @@ const fn map_adapter_type(if_type: u32) -> AdapterKind {
     }
 }
 
-/// Collects IPv4 and IPv6 unicast addresses from an adapter.
-///
-/// # Safety Note
-///
-/// The pointer casts to `SOCKADDR_IN` and `SOCKADDR_IN6` are allowed despite alignment
-/// concerns because Windows guarantees proper alignment of these structures when returned
-/// from the networking APIs.
-#[allow(clippy::cast_ptr_alignment)]
-fn collect_addresses(adapter: &IP_ADAPTER_ADDRESSES_LH) -> (Vec<Ipv4Addr>, Vec<Ipv6Addr>) {
-    let mut ipv4_addresses = Vec::new();
-    let mut ipv6_addresses = Vec::new();
-
+/// Collects the IPv4 and IPv6 unicast addresses of an adapter into
+/// `snapshot`, with their prefix length and state, and marks the IPv6 ones
+/// with a random suffix (temporary privacy addresses).
+fn collect_addresses(adapter: &IP_ADAPTER_ADDRESSES_LH, snapshot: &mut AdapterSnapshot) {
     let mut unicast = adapter.FirstUnicastAddress;
 
     // SAFETY: We iterate through a linked list of unicast addresses.
@@ -209,38 +223,138 @@ fn collect_addresses(adapter: &IP_ADAPTER_ADDRESSES_LH) -> (Vec<Ipv4Addr>, Vec<I
     while !unicast.is_null() {
         let addr_entry = unsafe { &*unicast };
 
-        // SAFETY: The Address field contains a valid SOCKET_ADDRESS structure
-        // pointing to either SOCKADDR_IN (IPv4) or SOCKADDR_IN6 (IPv6).
+        // SAFETY: The Address field contains a valid SOCKET_ADDRESS structure.
         if let Some(sockaddr) = unsafe { addr_entry.Address.lpSockaddr.as_ref() } {
-            match sockaddr.sa_family {
-                f if f == AF_INET => {
-                    // SAFETY: We verified the family is AF_INET, so this is a valid cast.
-                    let sockaddr_in =
-                        unsafe { &*(std::ptr::from_ref(sockaddr).cast::<SOCKADDR_IN>()) };
-                    // SAFETY: sin_addr contains the IPv4 address bytes in network order.
-                    let octets = unsafe { sockaddr_in.sin_addr.S_un.S_un_b };
-                    let addr = Ipv4Addr::new(octets.s_b1, octets.s_b2, octets.s_b3, octets.s_b4);
-                    ipv4_addresses.push(addr);
-                }
-                f if f == AF_INET6 => {
-                    // SAFETY: We verified the family is AF_INET6, so this is a valid cast.
-                    let sockaddr_in6 =
-                        unsafe { &*(std::ptr::from_ref(sockaddr).cast::<SOCKADDR_IN6>()) };
-                    // SAFETY: We verified this is an IPv6 address, so the union field is valid.
-                    let octets = unsafe { sockaddr_in6.sin6_addr.u.Byte };
-                    let addr = Ipv6Addr::from(octets);
-                    ipv6_addresses.push(addr);
+            let address = sockaddr_to_ip(sockaddr);
+            match address {
+                Some(IpAddr::V4(addr)) => snapshot.ipv4_addresses.push(addr),
+                Some(IpAddr::V6(addr)) => {
+                    if addr_entry.SuffixOrigin == IpSuffixOriginRandom {
+                        snapshot.temporary_ipv6.push(addr);
+                    }
+                    snapshot.ipv6_addresses.push(addr);
                 }
                 // Unknown address family, skip - Windows typically only returns
                 // AF_INET or AF_INET6 for unicast addresses
-                _ => {}
+                None => {}
+            }
+            if let Some(address) = address {
+                let info = AddressInfo {
+                    prefix_len: Some(addr_entry.OnLinkPrefixLength),
+                    state: map_dad_state(addr_entry.DadState),
+                };
+                snapshot.address_info.insert(address, info);
             }
         }
 
         unicast = unsafe { (*unicast).Next };
     }
+}
 
-    (ipv4_addresses, ipv6_addresses)
+/// Maps Windows `IpDadState*` constants to [`AddressState`]; tentative,
+/// duplicate and invalid addresses have neither state.
+const fn map_dad_state(state: NL_DAD_STATE) -> Option<AddressState> {
+    match state {
+        IpDadStatePreferred => Some(AddressState::Preferred),
+        IpDadStateDeprecated => Some(AddressState::Deprecated),
+        _ => None,
+    }
+}
+
+/// Collects the link-layer details of an adapter.
+fn collect_metadata(adapter: &IP_ADAPTER_ADDRESSES_LH) -> AdapterMetadata {
+    let length = usize::try_from(adapter.PhysicalAddressLength).unwrap_or(0);
+    let mac = adapter
+        .PhysicalAddress
+        .get(..length)
+        .and_then(AdapterMetadata::format_mac);
+    // SAFETY: the union's Length/IfIndex view is the one GetAdaptersAddresses fills
+    let if_index = unsafe { adapter.Anonymous1.Anonymous.IfIndex };
+    // Adapters without IPv4 report their index as Ipv6IfIndex only
+    let index = [if_index, adapter.Ipv6IfIndex]
+        .into_iter()
+        .find(|&index| index != 0);
+    // SAFETY: DnsSuffix is a valid (possibly empty) null-terminated wide string.
+    let dns_suffix = unsafe { adapter.DnsSuffix.to_string() }
+        .ok()
+        .filter(|suffix| !suffix.is_empty());
+
+    AdapterMetadata {
+        mac,
+        index,
+        mtu: (adapter.Mtu != u32::MAX).then_some(adapter.Mtu),
+        // u64::MAX means the speed is unknown
+        link_speed: (adapter.TransmitLinkSpeed != u64::MAX).then_some(adapter.TransmitLinkSpeed),
+        dns_suffix,
+        oper_status: Some(map_oper_status(adapter.OperStatus)),
+    }
+}
+
+/// Maps Windows `IfOperStatus*` constants to [`OperStatus`].
+const fn map_oper_status(status: IF_OPER_STATUS) -> OperStatus {
+    match status {
+        IfOperStatusUp => OperStatus::Up,
+        IfOperStatusDown => OperStatus::Down,
+        IfOperStatusTesting => OperStatus::Testing,
+        IfOperStatusDormant => OperStatus::Dormant,
+        IfOperStatusNotPresent => OperStatus::NotPresent,
+        IfOperStatusLowerLayerDown => OperStatus::LowerLayerDown,
+        _ => OperStatus::Unknown,
+    }
+}
+
+/// Collects the connection-specific DNS suffix and DNS servers of an adapter.
+///
+/// Only meaningful when the list was requested without `GAA_FLAG_SKIP_DNS_SERVER`.
+fn collect_dns(adapter: &IP_ADAPTER_ADDRESSES_LH) -> DnsSettings {
+    // SAFETY: DnsSuffix is a valid (possibly empty) null-terminated wide string.
+    let suffix = unsafe { adapter.DnsSuffix.to_string() }.unwrap_or_default();
+
+    let mut servers = Vec::new();
+    let mut server = adapter.FirstDnsServerAddress;
+
+    // SAFETY: We iterate through a linked list of DNS server addresses.
+    // Each address is valid as long as the parent adapter buffer is alive.
+    while !server.is_null() {
+        let entry = unsafe { &*server };
+
+        // SAFETY: The Address field contains a valid SOCKET_ADDRESS structure.
+        if let Some(addr) = unsafe { entry.Address.lpSockaddr.as_ref() }.and_then(sockaddr_to_ip) {
+            servers.push(addr);
+        }
+
+        server = entry.Next;
+    }
+
+    DnsSettings { suffix, servers }
+}
+
+/// Converts a `SOCKADDR` of family `AF_INET` or `AF_INET6` to an [`IpAddr`].
+///
+/// # Safety Note
+///
+/// The pointer casts to `SOCKADDR_IN` and `SOCKADDR_IN6` are allowed despite alignment
+/// concerns because Windows guarantees proper alignment of these structures when returned
+/// from the networking APIs.
+#[allow(clippy::cast_ptr_alignment)]
+fn sockaddr_to_ip(sockaddr: &SOCKADDR) -> Option<IpAddr> {
+    match sockaddr.sa_family {
+        f if f == AF_INET => {
+            // SAFETY: We verified the family is AF_INET, so this is a valid cast.
+            let sockaddr_in = unsafe { &*(std::ptr::from_ref(sockaddr).cast::<SOCKADDR_IN>()) };
+            // SAFETY: sin_addr contains the IPv4 address bytes in network order.
+            let octets = unsafe { sockaddr_in.sin_addr.S_un.S_un_b };
+            Some(Ipv4Addr::new(octets.s_b1, octets.s_b2, octets.s_b3, octets.s_b4).into())
+        }
+        f if f == AF_INET6 => {
+            // SAFETY: We verified the family is AF_INET6, so this is a valid cast.
+            let sockaddr_in6 = unsafe { &*(std::ptr::from_ref(sockaddr).cast::<SOCKADDR_IN6>()) };
+            // SAFETY: We verified this is an IPv6 address, so the union field is valid.
+            let octets = unsafe { sockaddr_in6.sin6_addr.u.Byte };
+            Some(Ipv6Addr::from(octets).into())
+        }
+        _ => None,
+    }
 }
 
 #[cfg(test)]
@@ -283,6 +397,31 @@ mod tests {
         assert_eq!(map_adapter_type(999), AdapterKind::Other(999));
     }
 
+    #[test]
+    fn map_oper_status_known_states() {
+        assert_eq!(map_oper_status(IfOperStatusUp), OperStatus::Up);
+        assert_eq!(
+            map_oper_status(IfOperStatusLowerLayerDown),
+            OperStatus::LowerLayerDown
+        );
+    }
+
+    #[test]
+    fn map_oper_status_unknown_code() {
+        assert_eq!(map_oper_status(IF_OPER_STATUS(99)), OperStatus::Unknown);
+    }
+
+    #[test]
+    fn fetch_collects_metadata() {
+        let adapters = WindowsFetcher::new().fetch().expect("fetch() failed");
+
+        assert!(adapters.iter().all(|a| {
+            a.metadata
+                .as_ref()
+                .is_some_and(|m| m.index.is_some() && m.oper_status.is_some())
+        }));
+    }
+
     #[test]
     fn windows_fetcher_new_creates_instance() {
         let _fetcher = WindowsFetcher::new();
@@ -295,6 +434,23 @@ mod tests {
         // Just verify it compiles and runs
     }
 
+    #[test]
+    fn fetch_without_dns_leaves_settings_empty() {
+        let adapters = WindowsFetcher::new().fetch().expect("fetch() failed");
+
+        assert!(adapters.iter().all(|a| a.dns.is_none()));
+    }
+
+    #[test]
+    fn fetch_with_dns_collects_settings() {
+        let adapters = WindowsFetcher::new()
+            .with_dns(true)
+            .fetch()
+            .expect("fetch() failed");
+
+        assert!(adapters.iter().all(|a| a.dns.is_some()));
+    }
+
     // Integration test: actually fetches adapters from the system
     // This test verifies the Windows API integration works end-to-end
     #[test]
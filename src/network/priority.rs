@@ -0,0 +1,139 @@
+//! Effective address selection across adapters.
+//!
+//! A host often has several usable addresses at once, e.g. Ethernet and Wi-Fi
+//! both connected. [`AdapterPriority`] picks one address per family from a
+//! snapshot: adapters listed by the user win in list order, the remaining
+//! adapters follow in fetch order.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use serde::{Deserialize, Serialize};
+
+use super::{AdapterSnapshot, IpVersion};
+
+/// An address chosen as the host's effective address in its family.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EffectiveAddress {
+    /// The adapter the address belongs to.
+    pub adapter: String,
+    /// The chosen address.
+    pub address: IpAddr,
+}
+
+/// Effective addresses per family; `None` when no adapter has a usable one.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EffectiveAddresses {
+    /// Effective IPv4 address.
+    pub ipv4: Option<EffectiveAddress>,
+    /// Effective IPv6 address.
+    pub ipv6: Option<EffectiveAddress>,
+}
+
+/// Ordered adapter names used to choose effective addresses.
+///
+/// Names match adapter names exactly. An empty list keeps fetch order.
+///
+/// # Example
+///
+/// ```
+/// use ddns_a::network::{AdapterKind, AdapterSnapshot, IpVersion};
+/// use ddns_a::network::priority::AdapterPriority;
+///
+/// let snapshot = [
+///     AdapterSnapshot::new("Wi-Fi", AdapterKind::Wireless, vec!["192.0.2.20".parse().unwrap()], vec![]),
+///     AdapterSnapshot::new("Ethernet", AdapterKind::Ethernet, vec!["192.0.2.10".parse().unwrap()], vec![]),
+/// ];
+/// let priority = AdapterPriority::new(["Ethernet", "Wi-Fi"]);
+///
+/// let effective = priority.resolve(&snapshot, IpVersion::V4);
+/// assert_eq!(effective.ipv4.unwrap().adapter, "Ethernet");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AdapterPriority {
+    names: Vec<String>,
+}
+
+impl AdapterPriority {
+    /// Creates a priority list, highest priority first.
+    #[must_use]
+    pub fn new<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            names: names.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Returns the adapter names, highest priority first.
+    #[must_use]
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Returns true if no adapter is prioritized.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Orders `snapshot` by priority: listed adapters first, in list
+    /// order, then the others in their original order.
+    #[must_use]
+    pub fn sort<'a>(&self, snapshot: &'a [AdapterSnapshot]) -> Vec<&'a AdapterSnapshot> {
+        let mut ordered: Vec<_> = snapshot.iter().collect();
+        // Stable sort keeps fetch order among equal ranks
+        ordered.sort_by_key(|adapter| self.rank(&adapter.name));
+        ordered
+    }
+
+    /// Chooses the effective address of each family included in `version`.
+    ///
+    /// The first usable address of the highest-ranked adapter having one
+    /// wins. Loopback, unspecified and link-local addresses are never
+    /// effective, since no remote peer can reach them.
+    #[must_use]
+    pub fn resolve(&self, snapshot: &[AdapterSnapshot], version: IpVersion) -> EffectiveAddresses {
+        let ordered = self.sort(snapshot);
+        let pick = |address: fn(&AdapterSnapshot) -> Option<IpAddr>| {
+            ordered.iter().find_map(|adapter| {
+                address(adapter).map(|address| EffectiveAddress {
+                    adapter: adapter.name.clone(),
+                    address,
+                })
+            })
+        };
+
+        EffectiveAddresses {
+            ipv4: version.includes_v4().then(|| pick(usable_v4)).flatten(),
+            ipv6: version.includes_v6().then(|| pick(usable_v6)).flatten(),
+        }
+    }
+
+    fn rank(&self, name: &str) -> usize {
+        self.names
+            .iter()
+            .position(|n| n == name)
+            .unwrap_or(self.names.len())
+    }
+}
+
+fn usable_v4(adapter: &AdapterSnapshot) -> Option<IpAddr> {
+    let usable = |a: &&Ipv4Addr| !a.is_loopback() && !a.is_unspecified() && !a.is_link_local();
+    adapter
+        .ipv4_addresses
+        .iter()
+        .find(usable)
+        .map(|&a| IpAddr::V4(a))
+}
+
+fn usable_v6(adapter: &AdapterSnapshot) -> Option<IpAddr> {
+    let usable =
+        |a: &&Ipv6Addr| !a.is_loopback() && !a.is_unspecified() && !a.is_unicast_link_local();
+    adapter
+        .ipv6_addresses
+        .iter()
+        .find(usable)
+        .map(|&a| IpAddr::V6(a))
+}
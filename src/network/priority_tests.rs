@@ -0,0 +1,118 @@
+//! Tests for effective address selection by adapter priority.
+
+use super::priority::{AdapterPriority, EffectiveAddress};
+use super::{AdapterKind, AdapterSnapshot, IpVersion};
+
+fn adapter(name: &str, v4: &[&str], v6: &[&str]) -> AdapterSnapshot {
+    AdapterSnapshot::new(
+        name,
+        AdapterKind::Ethernet,
+        v4.iter().map(|a| a.parse().unwrap()).collect(),
+        v6.iter().map(|a| a.parse().unwrap()).collect(),
+    )
+}
+
+fn effective(adapter: &str, address: &str) -> EffectiveAddress {
+    EffectiveAddress {
+        adapter: adapter.to_string(),
+        address: address.parse().unwrap(),
+    }
+}
+
+fn snapshot() -> Vec<AdapterSnapshot> {
+    vec![
+        adapter("vEthernet (WSL)", &["172.20.0.1"], &[]),
+        adapter("Wi-Fi", &["192.0.2.20"], &["2001:db8::20"]),
+        adapter("Ethernet", &["192.0.2.10"], &[]),
+    ]
+}
+
+#[test]
+fn listed_adapters_win_in_list_order() {
+    let priority = AdapterPriority::new(["Ethernet", "Wi-Fi"]);
+
+    let result = priority.resolve(&snapshot(), IpVersion::V4);
+
+    assert_eq!(result.ipv4, Some(effective("Ethernet", "192.0.2.10")));
+    assert_eq!(result.ipv6, None);
+}
+
+#[test]
+fn families_fall_back_independently() {
+    // Ethernet has no IPv6 address, so Wi-Fi provides it
+    let priority = AdapterPriority::new(["Ethernet", "Wi-Fi"]);
+
+    let result = priority.resolve(&snapshot(), IpVersion::Both);
+
+    assert_eq!(result.ipv4, Some(effective("Ethernet", "192.0.2.10")));
+    assert_eq!(result.ipv6, Some(effective("Wi-Fi", "2001:db8::20")));
+}
+
+#[test]
+fn empty_priority_keeps_fetch_order() {
+    let result = AdapterPriority::default().resolve(&snapshot(), IpVersion::V4);
+
+    assert_eq!(
+        result.ipv4,
+        Some(effective("vEthernet (WSL)", "172.20.0.1"))
+    );
+}
+
+#[test]
+fn unlisted_adapters_follow_in_fetch_order() {
+    let priority = AdapterPriority::new(["Wi-Fi"]);
+
+    let names: Vec<_> = priority
+        .sort(&snapshot())
+        .iter()
+        .map(|a| a.name.clone())
+        .collect();
+
+    assert_eq!(names, ["Wi-Fi", "vEthernet (WSL)", "Ethernet"]);
+}
+
+#[test]
+fn missing_priority_adapter_is_skipped() {
+    let priority = AdapterPriority::new(["Cellular", "Ethernet"]);
+
+    let result = priority.resolve(&snapshot(), IpVersion::V4);
+
+    assert_eq!(result.ipv4, Some(effective("Ethernet", "192.0.2.10")));
+}
+
+#[test]
+fn unusable_addresses_are_never_effective() {
+    let snapshot = [
+        adapter("Ethernet", &["169.254.1.1"], &["fe80::1"]),
+        adapter("Loopback", &["127.0.0.1"], &["::1"]),
+        adapter("Wi-Fi", &["169.254.2.2", "192.0.2.20"], &["2001:db8::20"]),
+    ];
+    let priority = AdapterPriority::new(["Ethernet", "Loopback"]);
+
+    let result = priority.resolve(&snapshot, IpVersion::Both);
+
+    assert_eq!(result.ipv4, Some(effective("Wi-Fi", "192.0.2.20")));
+    assert_eq!(result.ipv6, Some(effective("Wi-Fi", "2001:db8::20")));
+}
+
+#[test]
+fn no_usable_address_is_none() {
+    let snapshot = [adapter("Ethernet", &[], &["fe80::1"])];
+
+    let result = AdapterPriority::default().resolve(&snapshot, IpVersion::Both);
+
+    assert_eq!(result.ipv4, None);
+    assert_eq!(result.ipv6, None);
+}
+
+#[test]
+fn names_match_exactly() {
+    let priority = AdapterPriority::new(["ethernet"]);
+
+    let result = priority.resolve(&snapshot(), IpVersion::V4);
+
+    assert_eq!(
+        result.ipv4,
+        Some(effective("vEthernet (WSL)", "172.20.0.1"))
+    );
+}
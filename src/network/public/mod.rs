@@ -0,0 +1,382 @@
+//! Public address detection for hosts behind NAT.
+//!
+//! Adapters behind a NAT router only carry private addresses, which are of
+//! no use to a DNS record. [`PublicIpFetcher`] asks external services for
+//! the address the internet sees instead: HTTP endpoints answering with the
+//! address as plain text (e.g. `https://api4.ipify.org`), or STUN servers.
+//! It reports the result as the pseudo-adapter [`PUBLIC_ADAPTER`], so
+//! the monitors diff it like any other adapter and produce
+//! [`IpChange`](crate::monitor::IpChange) events for public address changes.
+//!
+//! [`CombinedFetcher`] joins it with the adapter fetcher for
+//! [`AddressSource::Both`].
+
+mod stun;
+
+#[cfg(test)]
+mod mod_tests;
+#[cfg(test)]
+mod stun_tests;
+
+use std::fmt;
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use tokio::runtime::{Handle, RuntimeFlavor};
+use url::Url;
+
+use crate::webhook::{HttpClient, HttpRequest};
+
+use super::{AdapterKind, AdapterSnapshot, AddressFetcher, FetchError, IpVersion};
+
+/// Name of the pseudo-adapter carrying the public addresses.
+pub const PUBLIC_ADAPTER: &str = "public";
+
+/// Default port of STUN servers.
+pub const STUN_PORT: u16 = 3478;
+
+/// Default timeout of a single lookup.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Where monitored addresses come from (`monitor.source`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AddressSource {
+    /// The addresses of local adapters (default).
+    #[default]
+    Adapter,
+    /// The public addresses reported by external services.
+    Public,
+    /// Both, the public addresses as the [`PUBLIC_ADAPTER`] pseudo-adapter.
+    Both,
+}
+
+impl AddressSource {
+    /// Returns true if local adapters are fetched.
+    #[must_use]
+    pub const fn includes_adapters(self) -> bool {
+        matches!(self, Self::Adapter | Self::Both)
+    }
+
+    /// Returns true if public addresses are looked up.
+    #[must_use]
+    pub const fn includes_public(self) -> bool {
+        matches!(self, Self::Public | Self::Both)
+    }
+}
+
+impl fmt::Display for AddressSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Adapter => write!(f, "adapter"),
+            Self::Public => write!(f, "public"),
+            Self::Both => write!(f, "both"),
+        }
+    }
+}
+
+/// A service reporting the public address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PublicEndpoint {
+    /// An HTTP(S) URL whose response body is the address as plain text.
+    Http(Url),
+    /// A STUN server, written `stun:host[:port]`.
+    Stun {
+        /// Server host name or address.
+        host: String,
+        /// Server UDP port (default: [`STUN_PORT`]).
+        port: u16,
+    },
+}
+
+impl PublicEndpoint {
+    /// Default endpoints for `version`: ipify's single-stack services.
+    #[must_use]
+    pub fn defaults(version: IpVersion) -> Vec<Self> {
+        [
+            (version.includes_v4(), "https://api4.ipify.org"),
+            (version.includes_v6(), "https://api6.ipify.org"),
+        ]
+        .into_iter()
+        .filter(|&(monitored, _)| monitored)
+        .filter_map(|(_, url)| Url::parse(url).ok())
+        .map(Self::Http)
+        .collect()
+    }
+}
+
+impl fmt::Display for PublicEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(url) => write!(f, "{url}"),
+            Self::Stun { host, port } if host.contains(':') => write!(f, "stun:[{host}]:{port}"),
+            Self::Stun { host, port } => write!(f, "stun:{host}:{port}"),
+        }
+    }
+}
+
+impl FromStr for PublicEndpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(server) = s.strip_prefix("stun:") {
+            return parse_stun_server(server)
+                .ok_or_else(|| format!("'{s}' must be stun:host or stun:host:port"));
+        }
+        let url = Url::parse(s).map_err(|e| format!("'{s}': {e}"))?;
+        match url.scheme() {
+            "http" | "https" => Ok(Self::Http(url)),
+            _ => Err(format!("'{s}' must be an http(s) URL or stun:host[:port]")),
+        }
+    }
+}
+
+/// Public address lookup settings (`[monitor.public]`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicIpSettings {
+    /// Services asked in order until every monitored family has an address
+    pub endpoints: Vec<PublicEndpoint>,
+
+    /// Timeout of a single lookup
+    pub timeout: Duration,
+}
+
+/// Parses `host`, `host:port`, `[v6]` or `[v6]:port`.
+fn parse_stun_server(server: &str) -> Option<PublicEndpoint> {
+    let (host, port) = if let Some(rest) = server.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']')?;
+        match rest.strip_prefix(':') {
+            Some(port) => (host, port.parse().ok()?),
+            None if rest.is_empty() => (host, STUN_PORT),
+            None => return None,
+        }
+    } else {
+        match server.split_once(':') {
+            Some((host, port)) => (host, port.parse().ok()?),
+            None => (server, STUN_PORT),
+        }
+    };
+    (!host.is_empty() && !host.contains(['/', ' '])).then(|| PublicEndpoint::Stun {
+        host: host.to_string(),
+        port,
+    })
+}
+
+/// Public addresses found by one round of lookups.
+#[derive(Debug, Default)]
+struct Lookup {
+    v4: Option<Ipv4Addr>,
+    v6: Option<Ipv6Addr>,
+    errors: Vec<String>,
+}
+
+impl Lookup {
+    /// Records `addr` if `version` monitors its family and the family has
+    /// no address yet.
+    fn record(&mut self, addr: IpAddr, version: IpVersion) {
+        match addr {
+            IpAddr::V4(addr) if version.includes_v4() => {
+                self.v4.get_or_insert(addr);
+            }
+            IpAddr::V6(addr) if version.includes_v6() => {
+                self.v6.get_or_insert(addr);
+            }
+            _ => {}
+        }
+    }
+
+    /// Families of `version` still lacking an address.
+    fn missing(&self, version: IpVersion) -> Vec<IpVersion> {
+        let mut missing = Vec::new();
+        if version.includes_v4() && self.v4.is_none() {
+            missing.push(IpVersion::V4);
+        }
+        if version.includes_v6() && self.v6.is_none() {
+            missing.push(IpVersion::V6);
+        }
+        missing
+    }
+}
+
+/// [`AddressFetcher`] reporting the public addresses as [`PUBLIC_ADAPTER`].
+///
+/// Endpoints are asked in order until every monitored family has an
+/// address; an HTTP endpoint counts for whichever family it answers in, a
+/// STUN server is asked once per missing family. A family whose lookups
+/// failed keeps its last known address, so an unreachable service is not
+/// mistaken for the address being removed; only if no address is known at
+/// all does `fetch` fail.
+///
+/// `fetch` is synchronous while lookups are not: it blocks on them, which
+/// needs a multi-threaded tokio runtime.
+#[derive(Debug)]
+pub struct PublicIpFetcher<H> {
+    client: H,
+    endpoints: Vec<PublicEndpoint>,
+    version: IpVersion,
+    timeout: Duration,
+    last: Mutex<(Option<Ipv4Addr>, Option<Ipv6Addr>)>,
+}
+
+impl<H: HttpClient> PublicIpFetcher<H> {
+    /// Creates a fetcher asking `endpoints` for the addresses of `version`.
+    #[must_use]
+    pub fn new(client: H, endpoints: Vec<PublicEndpoint>, version: IpVersion) -> Self {
+        Self {
+            client,
+            endpoints,
+            version,
+            timeout: DEFAULT_TIMEOUT,
+            last: Mutex::default(),
+        }
+    }
+
+    /// Sets the timeout of a single lookup.
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Asks the endpoints in order until every family has an address.
+    async fn lookup(&self) -> Lookup {
+        let mut lookup = Lookup::default();
+        for endpoint in &self.endpoints {
+            for family in lookup.missing(self.version) {
+                let result = match endpoint {
+                    PublicEndpoint::Http(url) => self.ask(self.lookup_http(url)).await,
+                    PublicEndpoint::Stun { host, port } => {
+                        self.ask(stun::lookup(host, *port, family)).await
+                    }
+                };
+                match result {
+                    Ok(addr) => lookup.record(addr, self.version),
+                    Err(e) => {
+                        tracing::debug!("Public IP lookup via {endpoint} failed: {e}");
+                        lookup.errors.push(format!("{endpoint}: {e}"));
+                    }
+                }
+                // An HTTP endpoint answers the same whatever family is missing
+                if matches!(endpoint, PublicEndpoint::Http(_)) {
+                    break;
+                }
+            }
+        }
+        lookup
+    }
+
+    /// Runs one lookup with the timeout.
+    async fn ask(
+        &self,
+        lookup: impl Future<Output = Result<IpAddr, String>>,
+    ) -> Result<IpAddr, String> {
+        tokio::time::timeout(self.timeout, lookup)
+            .await
+            .unwrap_or_else(|_| Err(format!("timed out after {:?}", self.timeout)))
+    }
+
+    async fn lookup_http(&self, url: &Url) -> Result<IpAddr, String> {
+        let response = self
+            .client
+            .request(HttpRequest::get(url.clone()))
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.is_success() {
+            return Err(format!("HTTP {}", response.status));
+        }
+        let text = response.body_text().unwrap_or_default().trim();
+        text.parse()
+            .map_err(|_| format!("response '{text}' is not an IP address"))
+    }
+}
+
+impl<H: HttpClient> AddressFetcher for PublicIpFetcher<H> {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        let mut lookup = block_on(self.lookup())?;
+
+        let mut last = self.last.lock().unwrap_or_else(PoisonError::into_inner);
+        if !lookup.errors.is_empty() {
+            let missing = lookup.missing(self.version);
+            if missing.contains(&IpVersion::V4) {
+                lookup.v4 = last.0;
+            }
+            if missing.contains(&IpVersion::V6) {
+                lookup.v6 = last.1;
+            }
+            if lookup.v4.is_none() && lookup.v6.is_none() {
+                return Err(FetchError::Platform {
+                    message: format!("public IP lookup failed: {}", lookup.errors.join("; ")),
+                });
+            }
+            if !missing.is_empty() {
+                tracing::warn!(
+                    "Public IP lookup failed, keeping the last known address: {}",
+                    lookup.errors.join("; ")
+                );
+            }
+        }
+        *last = (lookup.v4, lookup.v6);
+        drop(last);
+
+        Ok(vec![AdapterSnapshot::new(
+            PUBLIC_ADAPTER,
+            AdapterKind::Public,
+            lookup.v4.into_iter().collect(),
+            lookup.v6.into_iter().collect(),
+        )])
+    }
+}
+
+/// Runs `future` to completion from the synchronous [`AddressFetcher::fetch`].
+///
+/// Works both on runtime worker threads and on the blocking pool.
+fn block_on<F: Future>(future: F) -> Result<F::Output, FetchError> {
+    let handle = Handle::try_current().map_err(|e| FetchError::Platform {
+        message: format!("public IP lookups need a tokio runtime: {e}"),
+    })?;
+    if matches!(handle.runtime_flavor(), RuntimeFlavor::CurrentThread) {
+        return Err(FetchError::Platform {
+            message: "public IP lookups need a multi-threaded tokio runtime".to_string(),
+        });
+    }
+    Ok(tokio::task::block_in_place(|| handle.block_on(future)))
+}
+
+/// [`AddressFetcher`] joining local adapters and public addresses.
+///
+/// Either part may be absent (see [`AddressSource`]). With both present,
+/// a failed public lookup is logged and leaves out the public
+/// pseudo-adapter, so it never stops adapter monitoring.
+#[derive(Debug)]
+pub struct CombinedFetcher<A, P> {
+    adapters: Option<A>,
+    public: Option<P>,
+}
+
+impl<A: AddressFetcher, P: AddressFetcher> CombinedFetcher<A, P> {
+    /// Creates a fetcher concatenating the snapshots of both parts.
+    #[must_use]
+    pub const fn new(adapters: Option<A>, public: Option<P>) -> Self {
+        Self { adapters, public }
+    }
+}
+
+impl<A: AddressFetcher, P: AddressFetcher> AddressFetcher for CombinedFetcher<A, P> {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        let Some(adapters) = &self.adapters else {
+            return self
+                .public
+                .as_ref()
+                .map_or_else(|| Ok(Vec::new()), P::fetch);
+        };
+        let mut snapshots = adapters.fetch()?;
+        match self.public.as_ref().map(P::fetch) {
+            Some(Ok(public)) => snapshots.extend(public),
+            Some(Err(e)) => tracing::warn!("Skipping public addresses: {e}"),
+            None => {}
+        }
+        Ok(snapshots)
+    }
+}
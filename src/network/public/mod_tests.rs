@@ -0,0 +1,351 @@
+//! Tests for public address detection.
+
+use super::{AddressSource, CombinedFetcher, PUBLIC_ADAPTER, PublicEndpoint, PublicIpFetcher};
+use crate::network::{AdapterKind, AdapterSnapshot, AddressFetcher, FetchError, IpVersion};
+use crate::webhook::{HttpClient, HttpError, HttpRequest, HttpResponse};
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::sync::Mutex;
+
+/// Client answering each URL with its scripted body (`None`: connection error).
+#[derive(Debug, Default)]
+struct ScriptedClient {
+    bodies: Mutex<HashMap<String, Option<&'static str>>>,
+}
+
+impl ScriptedClient {
+    fn with(self, url: &str, body: Option<&'static str>) -> Self {
+        self.set(url, body);
+        self
+    }
+
+    fn set(&self, url: &str, body: Option<&'static str>) {
+        let url = url::Url::parse(url).unwrap().to_string();
+        self.bodies.lock().unwrap().insert(url, body);
+    }
+}
+
+impl HttpClient for ScriptedClient {
+    async fn request(&self, req: HttpRequest) -> Result<HttpResponse, HttpError> {
+        let body = self.bodies.lock().unwrap().get(req.url.as_str()).copied();
+        body.flatten().map_or_else(
+            || Err(HttpError::Connection("refused".into())),
+            |body| {
+                Ok(HttpResponse::new(
+                    http::StatusCode::OK,
+                    http::HeaderMap::new(),
+                    body.as_bytes().to_vec(),
+                ))
+            },
+        )
+    }
+}
+
+fn endpoints(urls: &[&str]) -> Vec<PublicEndpoint> {
+    urls.iter().map(|url| url.parse().unwrap()).collect()
+}
+
+fn v4(addr: &str) -> Vec<Ipv4Addr> {
+    vec![addr.parse().unwrap()]
+}
+
+fn v6(addr: &str) -> Vec<Ipv6Addr> {
+    vec![addr.parse().unwrap()]
+}
+
+mod endpoint {
+    use super::*;
+
+    #[test]
+    fn parses_http_urls() {
+        assert!(matches!(
+            "https://api4.ipify.org".parse(),
+            Ok(PublicEndpoint::Http(_))
+        ));
+        assert!(matches!(
+            "http://192.0.2.1/ip".parse(),
+            Ok(PublicEndpoint::Http(_))
+        ));
+    }
+
+    #[test]
+    fn parses_stun_servers() {
+        assert_eq!(
+            "stun:stun.example.com".parse(),
+            Ok(PublicEndpoint::Stun {
+                host: "stun.example.com".to_string(),
+                port: 3478,
+            })
+        );
+        assert_eq!(
+            "stun:stun.example.com:19302".parse(),
+            Ok(PublicEndpoint::Stun {
+                host: "stun.example.com".to_string(),
+                port: 19302,
+            })
+        );
+        assert_eq!(
+            "stun:[2001:db8::1]:3479".parse(),
+            Ok(PublicEndpoint::Stun {
+                host: "2001:db8::1".to_string(),
+                port: 3479,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_other_schemes_and_bad_servers() {
+        for s in [
+            "ftp://example.com",
+            "api.ipify.org",
+            "stun:",
+            "stun:host:port",
+            "stun:[2001:db8::1",
+            "stun:[::1]x",
+        ] {
+            assert!(s.parse::<PublicEndpoint>().is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn displays_as_parsed() {
+        for s in ["stun:stun.example.com:3478", "stun:[2001:db8::1]:3479"] {
+            assert_eq!(s.parse::<PublicEndpoint>().unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn defaults_follow_ip_version() {
+        assert_eq!(PublicEndpoint::defaults(IpVersion::V4).len(), 1);
+        assert_eq!(PublicEndpoint::defaults(IpVersion::V6).len(), 1);
+        assert_eq!(
+            PublicEndpoint::defaults(IpVersion::Both),
+            endpoints(&["https://api4.ipify.org", "https://api6.ipify.org"])
+        );
+    }
+}
+
+mod source {
+    use super::*;
+
+    #[test]
+    fn includes_its_parts() {
+        assert!(AddressSource::Adapter.includes_adapters());
+        assert!(!AddressSource::Adapter.includes_public());
+        assert!(!AddressSource::Public.includes_adapters());
+        assert!(AddressSource::Public.includes_public());
+        assert!(AddressSource::Both.includes_adapters());
+        assert!(AddressSource::Both.includes_public());
+    }
+
+    #[test]
+    fn defaults_to_adapter() {
+        assert_eq!(AddressSource::default(), AddressSource::Adapter);
+    }
+}
+
+mod fetcher {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn reports_public_pseudo_adapter() {
+        let client = ScriptedClient::default().with("https://a.example/", Some("192.0.2.7\n"));
+        let fetcher =
+            PublicIpFetcher::new(client, endpoints(&["https://a.example/"]), IpVersion::V4);
+
+        let snapshots = fetcher.fetch().unwrap();
+
+        assert_eq!(
+            snapshots,
+            vec![AdapterSnapshot::new(
+                PUBLIC_ADAPTER,
+                AdapterKind::Public,
+                v4("192.0.2.7"),
+                vec![]
+            )]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn fills_each_family_from_its_endpoint() {
+        let client = ScriptedClient::default()
+            .with("https://a.example/", Some("192.0.2.7"))
+            .with("https://b.example/", Some("2001:db8::7"));
+        let fetcher = PublicIpFetcher::new(
+            client,
+            endpoints(&["https://a.example/", "https://b.example/"]),
+            IpVersion::Both,
+        );
+
+        let snapshot = fetcher.fetch().unwrap().remove(0);
+
+        assert_eq!(snapshot.ipv4_addresses, v4("192.0.2.7"));
+        assert_eq!(snapshot.ipv6_addresses, v6("2001:db8::7"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ignores_answers_of_unmonitored_family() {
+        let client = ScriptedClient::default()
+            .with("https://a.example/", Some("192.0.2.7"))
+            .with("https://b.example/", Some("2001:db8::7"));
+        let fetcher = PublicIpFetcher::new(
+            client,
+            endpoints(&["https://a.example/", "https://b.example/"]),
+            IpVersion::V6,
+        );
+
+        let snapshot = fetcher.fetch().unwrap().remove(0);
+
+        assert!(snapshot.ipv4_addresses.is_empty());
+        assert_eq!(snapshot.ipv6_addresses, v6("2001:db8::7"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn tries_next_endpoint_on_failure() {
+        let client = ScriptedClient::default()
+            .with("https://a.example/", None)
+            .with("https://b.example/", Some("not an address"))
+            .with("https://c.example/", Some("192.0.2.7"));
+        let fetcher = PublicIpFetcher::new(
+            client,
+            endpoints(&[
+                "https://a.example/",
+                "https://b.example/",
+                "https://c.example/",
+            ]),
+            IpVersion::V4,
+        );
+
+        assert_eq!(fetcher.fetch().unwrap()[0].ipv4_addresses, v4("192.0.2.7"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn failure_keeps_last_known_address() {
+        let client = ScriptedClient::default().with("https://a.example/", Some("192.0.2.7"));
+        let fetcher =
+            PublicIpFetcher::new(client, endpoints(&["https://a.example/"]), IpVersion::V4);
+        fetcher.fetch().unwrap();
+
+        fetcher.client.set("https://a.example/", None);
+
+        assert_eq!(fetcher.fetch().unwrap()[0].ipv4_addresses, v4("192.0.2.7"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn failure_without_known_address_is_error() {
+        let client = ScriptedClient::default().with("https://a.example/", None);
+        let fetcher =
+            PublicIpFetcher::new(client, endpoints(&["https://a.example/"]), IpVersion::V4);
+
+        let error = fetcher.fetch().unwrap_err();
+
+        assert!(error.to_string().contains("https://a.example/"), "{error}");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn missing_family_without_failure_is_empty() {
+        let client = ScriptedClient::default().with("https://a.example/", Some("192.0.2.7"));
+        let fetcher =
+            PublicIpFetcher::new(client, endpoints(&["https://a.example/"]), IpVersion::Both);
+
+        let snapshot = fetcher.fetch().unwrap().remove(0);
+
+        assert_eq!(snapshot.ipv4_addresses, v4("192.0.2.7"));
+        assert!(snapshot.ipv6_addresses.is_empty());
+    }
+
+    #[tokio::test]
+    async fn current_thread_runtime_is_error() {
+        let fetcher = PublicIpFetcher::new(
+            ScriptedClient::default(),
+            endpoints(&["https://a.example/"]),
+            IpVersion::V4,
+        );
+
+        assert!(matches!(fetcher.fetch(), Err(FetchError::Platform { .. })));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn works_on_blocking_pool() {
+        let client = ScriptedClient::default().with("https://a.example/", Some("192.0.2.7"));
+        let fetcher =
+            PublicIpFetcher::new(client, endpoints(&["https://a.example/"]), IpVersion::V4);
+
+        let snapshots = tokio::task::spawn_blocking(move || fetcher.fetch())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(snapshots[0].ipv4_addresses, v4("192.0.2.7"));
+    }
+}
+
+mod combined {
+    use super::*;
+
+    /// Fetcher returning one adapter, or failing.
+    struct Fixed(Option<&'static str>);
+
+    impl AddressFetcher for Fixed {
+        fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+            self.0
+                .map(|name| {
+                    vec![AdapterSnapshot::new(
+                        name,
+                        AdapterKind::Ethernet,
+                        vec![],
+                        vec![],
+                    )]
+                })
+                .ok_or_else(|| FetchError::Platform {
+                    message: "down".to_string(),
+                })
+        }
+    }
+
+    fn names(fetcher: &impl AddressFetcher) -> Vec<String> {
+        fetcher
+            .fetch()
+            .unwrap()
+            .into_iter()
+            .map(|a| a.name)
+            .collect()
+    }
+
+    #[test]
+    fn concatenates_adapters_and_public() {
+        let fetcher = CombinedFetcher::new(Some(Fixed(Some("eth0"))), Some(Fixed(Some("public"))));
+
+        assert_eq!(names(&fetcher), ["eth0", "public"]);
+    }
+
+    #[test]
+    fn either_part_may_be_absent() {
+        let adapters = CombinedFetcher::new(Some(Fixed(Some("eth0"))), None::<Fixed>);
+        let public = CombinedFetcher::new(None::<Fixed>, Some(Fixed(Some("public"))));
+
+        assert_eq!(names(&adapters), ["eth0"]);
+        assert_eq!(names(&public), ["public"]);
+    }
+
+    #[test]
+    fn public_failure_is_skipped_alongside_adapters() {
+        let fetcher = CombinedFetcher::new(Some(Fixed(Some("eth0"))), Some(Fixed(None)));
+
+        assert_eq!(names(&fetcher), ["eth0"]);
+    }
+
+    #[test]
+    fn public_failure_alone_is_error() {
+        let fetcher = CombinedFetcher::new(None::<Fixed>, Some(Fixed(None)));
+
+        assert!(fetcher.fetch().is_err());
+    }
+
+    #[test]
+    fn adapter_failure_is_error() {
+        let fetcher = CombinedFetcher::new(Some(Fixed(None)), Some(Fixed(Some("public"))));
+
+        assert!(fetcher.fetch().is_err());
+    }
+}
@@ -0,0 +1,137 @@
+//! STUN binding requests (RFC 5389) revealing the public address.
+//!
+//! A binding request sent over UDP is answered with the address and port
+//! the server saw it coming from, i.e. the NAT's public mapping.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use tokio::net::UdpSocket;
+
+use crate::network::IpVersion;
+
+const BINDING_REQUEST: u16 = 0x0001;
+const BINDING_SUCCESS: u16 = 0x0101;
+const MAGIC_COOKIE: u32 = 0x2112_a442;
+const HEADER_LEN: usize = 20;
+const ATTR_MAPPED_ADDRESS: u16 = 0x0001;
+const ATTR_XOR_MAPPED_ADDRESS: u16 = 0x0020;
+const FAMILY_IPV4: u8 = 0x01;
+const FAMILY_IPV6: u8 = 0x02;
+/// Large enough for any binding response a server sends.
+const MAX_RESPONSE_LEN: usize = 1024;
+
+/// Transaction ID matching a response to its request.
+pub(super) type TransactionId = [u8; 12];
+
+/// Asks the STUN server `host:port` for the public address of `family`
+/// (`IpVersion::V4` or `IpVersion::V6`).
+pub(super) async fn lookup(host: &str, port: u16, family: IpVersion) -> Result<IpAddr, String> {
+    let server = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| format!("cannot resolve {host}: {e}"))?
+        .find(|addr| addr.is_ipv4() == (family == IpVersion::V4))
+        .ok_or_else(|| format!("{host} has no {family} address"))?;
+    let bind: SocketAddr = if server.is_ipv4() {
+        (Ipv4Addr::UNSPECIFIED, 0).into()
+    } else {
+        (Ipv6Addr::UNSPECIFIED, 0).into()
+    };
+
+    let id = transaction_id();
+    let socket = UdpSocket::bind(bind).await.map_err(|e| e.to_string())?;
+    socket.connect(server).await.map_err(|e| e.to_string())?;
+    socket
+        .send(&encode_request(id))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut buf = [0u8; MAX_RESPONSE_LEN];
+    loop {
+        let len = socket.recv(&mut buf).await.map_err(|e| e.to_string())?;
+        // A late answer to an earlier request: keep waiting for ours
+        if len >= HEADER_LEN && buf[8..HEADER_LEN] != id {
+            continue;
+        }
+        return decode_response(id, &buf[..len]);
+    }
+}
+
+/// Encodes an attribute-less binding request.
+pub(super) fn encode_request(id: TransactionId) -> Vec<u8> {
+    let mut msg = Vec::with_capacity(HEADER_LEN);
+    msg.extend_from_slice(&BINDING_REQUEST.to_be_bytes());
+    msg.extend_from_slice(&0u16.to_be_bytes());
+    msg.extend_from_slice(&MAGIC_COOKIE.to_be_bytes());
+    msg.extend_from_slice(&id);
+    msg
+}
+
+/// Decodes the mapped address of a binding success response to `id`.
+///
+/// `XOR-MAPPED-ADDRESS` is preferred; `MAPPED-ADDRESS` is accepted from
+/// servers predating RFC 5389.
+pub(super) fn decode_response(id: TransactionId, msg: &[u8]) -> Result<IpAddr, String> {
+    if msg.len() < HEADER_LEN {
+        return Err("response shorter than a STUN header".to_string());
+    }
+    if u16::from_be_bytes([msg[0], msg[1]]) != BINDING_SUCCESS {
+        return Err("not a binding success response".to_string());
+    }
+    if msg[8..HEADER_LEN] != id {
+        return Err("transaction id mismatch".to_string());
+    }
+
+    let len = usize::from(u16::from_be_bytes([msg[2], msg[3]]));
+    let mut attrs = msg
+        .get(HEADER_LEN..HEADER_LEN + len)
+        .ok_or("attributes out of bounds")?;
+    let mut mapped = None;
+    while attrs.len() >= 4 {
+        let kind = u16::from_be_bytes([attrs[0], attrs[1]]);
+        let len = usize::from(u16::from_be_bytes([attrs[2], attrs[3]]));
+        let value = attrs.get(4..4 + len).ok_or("attribute out of bounds")?;
+        match kind {
+            ATTR_XOR_MAPPED_ADDRESS => return decode_address(value, Some(id)),
+            ATTR_MAPPED_ADDRESS => mapped = Some(decode_address(value, None)?),
+            _ => {}
+        }
+        // Attributes are padded to 4 bytes
+        attrs = attrs.get(4 + len.next_multiple_of(4)..).unwrap_or_default();
+    }
+    mapped.ok_or_else(|| "response carries no mapped address".to_string())
+}
+
+/// Decodes a (XOR-)MAPPED-ADDRESS value; `xor` holds the transaction ID
+/// the address is obfuscated with.
+fn decode_address(value: &[u8], xor: Option<TransactionId>) -> Result<IpAddr, String> {
+    let mut mask = [0u8; 16];
+    if let Some(id) = xor {
+        mask[..4].copy_from_slice(&MAGIC_COOKIE.to_be_bytes());
+        mask[4..].copy_from_slice(&id);
+    }
+    let unmask = |bytes: &[u8]| -> Vec<u8> { bytes.iter().zip(mask).map(|(b, m)| b ^ m).collect() };
+
+    match (value.get(1), value.len()) {
+        (Some(&FAMILY_IPV4), 8) => {
+            let octets: [u8; 4] = unmask(&value[4..8]).try_into().unwrap_or_default();
+            Ok(Ipv4Addr::from(octets).into())
+        }
+        (Some(&FAMILY_IPV6), 20) => {
+            let octets: [u8; 16] = unmask(&value[4..20]).try_into().unwrap_or_default();
+            Ok(Ipv6Addr::from(octets).into())
+        }
+        _ => Err("malformed mapped address".to_string()),
+    }
+}
+
+/// Returns a random transaction ID.
+fn transaction_id() -> TransactionId {
+    // Every RandomState is seeded from OS randomness
+    let half = || RandomState::new().build_hasher().finish().to_ne_bytes();
+    let mut id = [0u8; 12];
+    id[..8].copy_from_slice(&half());
+    id[8..].copy_from_slice(&half()[..4]);
+    id
+}
@@ -0,0 +1,149 @@
+//! Tests for STUN binding requests.
+
+use super::stun::{TransactionId, decode_response, encode_request, lookup};
+use crate::network::IpVersion;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use tokio::net::UdpSocket;
+
+/// Transaction ID of the RFC 5769 sample responses.
+const ID: TransactionId = [
+    0xb7, 0xe7, 0xa7, 0x01, 0xbc, 0x34, 0xd6, 0x86, 0xfa, 0x87, 0xdf, 0xae,
+];
+
+/// A binding success response to `id` carrying `attrs` (type, value).
+fn response(id: TransactionId, attrs: &[(u16, Vec<u8>)]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for (kind, value) in attrs {
+        body.extend_from_slice(&kind.to_be_bytes());
+        body.extend_from_slice(&u16::try_from(value.len()).unwrap().to_be_bytes());
+        body.extend_from_slice(value);
+        body.resize(body.len().next_multiple_of(4), 0);
+    }
+    let mut msg = vec![0x01, 0x01];
+    msg.extend_from_slice(&u16::try_from(body.len()).unwrap().to_be_bytes());
+    msg.extend_from_slice(&[0x21, 0x12, 0xa4, 0x42]);
+    msg.extend_from_slice(&id);
+    msg.extend(body);
+    msg
+}
+
+/// XOR-MAPPED-ADDRESS of 192.0.2.1:32853 (RFC 5769, section 2.2).
+fn xor_mapped_v4() -> (u16, Vec<u8>) {
+    (0x0020, vec![0x00, 0x01, 0xa1, 0x47, 0xe1, 0x12, 0xa6, 0x43])
+}
+
+#[test]
+fn request_is_bare_binding_request() {
+    let msg = encode_request(ID);
+
+    assert_eq!(msg.len(), 20);
+    assert_eq!(msg[..8], [0x00, 0x01, 0x00, 0x00, 0x21, 0x12, 0xa4, 0x42]);
+    assert_eq!(msg[8..], ID);
+}
+
+#[test]
+fn decodes_xor_mapped_ipv4() {
+    let msg = response(ID, &[xor_mapped_v4()]);
+
+    assert_eq!(
+        decode_response(ID, &msg).unwrap(),
+        IpAddr::from([192, 0, 2, 1])
+    );
+}
+
+#[test]
+fn decodes_xor_mapped_ipv6() {
+    // RFC 5769, section 2.3: 2001:db8:1234:5678:11:2233:4455:6677 port 32853
+    let value = vec![
+        0x00, 0x02, 0xa1, 0x47, 0x01, 0x13, 0xa9, 0xfa, 0xa5, 0xd3, 0xf1, 0x79, 0xbc, 0x25, 0xf4,
+        0xb5, 0xbe, 0xd2, 0xb9, 0xd9,
+    ];
+    let msg = response(ID, &[(0x0020, value)]);
+
+    assert_eq!(
+        decode_response(ID, &msg).unwrap(),
+        "2001:db8:1234:5678:11:2233:4455:6677"
+            .parse::<IpAddr>()
+            .unwrap()
+    );
+}
+
+#[test]
+fn falls_back_to_plain_mapped_address() {
+    let software = (0x8022, b"legacy".to_vec());
+    let mapped = (0x0001, vec![0x00, 0x01, 0x80, 0x55, 198, 51, 100, 7]);
+    let msg = response(ID, &[software, mapped]);
+
+    assert_eq!(
+        decode_response(ID, &msg).unwrap(),
+        IpAddr::from([198, 51, 100, 7])
+    );
+}
+
+#[test]
+fn prefers_xor_mapped_address() {
+    let mapped = (0x0001, vec![0x00, 0x01, 0x80, 0x55, 198, 51, 100, 7]);
+    let msg = response(ID, &[mapped, xor_mapped_v4()]);
+
+    assert_eq!(
+        decode_response(ID, &msg).unwrap(),
+        IpAddr::from([192, 0, 2, 1])
+    );
+}
+
+#[test]
+fn rejects_other_transaction() {
+    let msg = response([0; 12], &[xor_mapped_v4()]);
+
+    assert!(decode_response(ID, &msg).is_err());
+}
+
+#[test]
+fn rejects_error_response_and_truncation() {
+    let mut error = response(ID, &[xor_mapped_v4()]);
+    error[..2].copy_from_slice(&[0x01, 0x11]);
+    let full = response(ID, &[xor_mapped_v4()]);
+
+    assert!(decode_response(ID, &error).is_err());
+    assert!(decode_response(ID, &full[..full.len() - 2]).is_err());
+    assert!(decode_response(ID, &full[..10]).is_err());
+}
+
+#[test]
+fn rejects_response_without_address() {
+    let msg = response(ID, &[(0x8022, b"nothing".to_vec())]);
+
+    assert!(decode_response(ID, &msg).is_err());
+}
+
+#[tokio::test]
+async fn lookup_returns_address_seen_by_server() {
+    let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let port = server.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let mut buf = [0u8; 64];
+        let (len, peer) = server.recv_from(&mut buf).await.unwrap();
+        assert_eq!(len, 20);
+        let id: TransactionId = buf[8..20].try_into().unwrap();
+        let SocketAddr::V4(peer) = peer else {
+            unreachable!()
+        };
+        let mut value = vec![0x00, 0x01];
+        value.extend_from_slice(&(peer.port() ^ 0x2112).to_be_bytes());
+        let cookie = [0x21, 0x12, 0xa4, 0x42];
+        value.extend(peer.ip().octets().iter().zip(cookie).map(|(b, m)| b ^ m));
+        let reply = response(id, &[(0x0020, value)]);
+        server.send_to(&reply, peer).await.unwrap();
+    });
+
+    let addr = lookup("127.0.0.1", port, IpVersion::V4).await.unwrap();
+
+    assert_eq!(addr, IpAddr::V4(Ipv4Addr::LOCALHOST));
+}
+
+#[tokio::test]
+async fn lookup_needs_server_address_of_family() {
+    let result = lookup("127.0.0.1", 3478, IpVersion::V6).await;
+
+    assert!(result.unwrap_err().contains("no IPv6 address"));
+}
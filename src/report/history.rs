@@ -0,0 +1,303 @@
+//! Append-only history of detected changes and webhook deliveries.
+//!
+//! [`HistoryLog`] appends one JSON line per detected change and one per
+//! delivery result to a file, as the [audit log](crate::webhook::AuditedClient)
+//! does for HTTP requests. `ddns-a history` reads it back, keeps the records
+//! a [`HistoryFilter`] matches and prints them with [`format_record`].
+
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::monitor::{IpChange, IpChangeKind};
+use crate::time::format_rfc3339;
+use crate::webhook::WebhookError;
+
+use super::DeliveryOutcome;
+
+/// A single history entry.
+///
+/// # Examples
+///
+/// ```
+/// use ddns_a::monitor::IpChange;
+/// use ddns_a::report::HistoryRecord;
+/// use std::time::{Duration, SystemTime};
+///
+/// let at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_714_564_800);
+/// let record = HistoryRecord::Detected(IpChange::added("eth0", "192.0.2.1".parse().unwrap(), at));
+///
+/// assert_eq!(
+///     serde_json::to_string(&record).unwrap(),
+///     r#"{"event":"detected","adapter":"eth0","address":"192.0.2.1","timestamp":"2024-05-01T12:00:00Z","kind":"added"}"#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum HistoryRecord {
+    /// An address change was detected.
+    Detected(IpChange),
+    /// A batch of changes was sent to the webhooks.
+    Delivery(DeliveryRecord),
+}
+
+impl HistoryRecord {
+    /// Returns when the change was detected or the delivery ended.
+    #[must_use]
+    pub const fn timestamp(&self) -> SystemTime {
+        match self {
+            Self::Detected(change) => change.timestamp,
+            Self::Delivery(delivery) => delivery.timestamp,
+        }
+    }
+
+    /// Returns true if the record concerns `adapter`.
+    #[must_use]
+    pub fn involves(&self, adapter: &str) -> bool {
+        match self {
+            Self::Detected(change) => change.adapter == adapter,
+            Self::Delivery(delivery) => delivery.adapters.iter().any(|a| a == adapter),
+        }
+    }
+}
+
+/// The result of one delivery of a change batch, retries included.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeliveryRecord {
+    /// When the delivery ended.
+    #[serde(with = "crate::time::rfc3339")]
+    pub timestamp: SystemTime,
+    /// Adapters whose changes were sent, sorted.
+    pub adapters: Vec<String>,
+    /// Number of changes in the batch.
+    pub changes: usize,
+    /// Whether the webhooks accepted the batch.
+    pub outcome: DeliveryOutcome,
+    /// Why the delivery failed or was postponed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl DeliveryRecord {
+    /// Describes the delivery of `changes`, which ended with `result` at
+    /// `timestamp`.
+    #[must_use]
+    pub fn new(
+        changes: &[IpChange],
+        result: &Result<(), WebhookError>,
+        timestamp: SystemTime,
+    ) -> Self {
+        let adapters: BTreeSet<&str> = changes.iter().map(|c| c.adapter.as_str()).collect();
+        Self {
+            timestamp,
+            adapters: adapters.into_iter().map(String::from).collect(),
+            changes: changes.len(),
+            outcome: DeliveryOutcome::of(result),
+            error: result.as_ref().err().map(ToString::to_string),
+        }
+    }
+}
+
+/// Appends history records as JSON lines to a file.
+///
+/// The file is opened in append mode for every write, so external log
+/// rotation (rename + recreate) is picked up without restarting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryLog {
+    path: PathBuf,
+}
+
+impl HistoryLog {
+    /// Creates a log writing to the given file.
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the path of the history file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Records each of `changes` as detected.
+    pub fn record_changes(&self, changes: &[IpChange]) {
+        let records: Vec<_> = changes
+            .iter()
+            .cloned()
+            .map(HistoryRecord::Detected)
+            .collect();
+        self.record(&records);
+    }
+
+    /// Records that the delivery of `changes` ended with `result` now.
+    pub fn record_delivery(&self, changes: &[IpChange], result: &Result<(), WebhookError>) {
+        let delivery = DeliveryRecord::new(changes, result, SystemTime::now());
+        self.record(&[HistoryRecord::Delivery(delivery)]);
+    }
+
+    /// Appends `records`, logging a failure rather than returning it.
+    pub fn record(&self, records: &[HistoryRecord]) {
+        if records.is_empty() {
+            return;
+        }
+        if let Err(e) = self.append(records) {
+            tracing::error!(
+                "Failed to write history record to {}: {e}",
+                self.path.display()
+            );
+        }
+    }
+
+    fn append(&self, records: &[HistoryRecord]) -> io::Result<()> {
+        let mut lines = String::new();
+        for record in records {
+            lines.push_str(&serde_json::to_string(record)?);
+            lines.push('\n');
+        }
+
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(lines.as_bytes())
+    }
+
+    /// Reads every record, in the order written.
+    ///
+    /// Malformed lines, e.g. one cut short by a crash, are skipped with a
+    /// warning.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read.
+    pub fn read(&self) -> io::Result<Vec<HistoryRecord>> {
+        let file = std::fs::File::open(&self.path)?;
+        let mut records = Vec::new();
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line) {
+                Ok(record) => records.push(record),
+                Err(e) => tracing::warn!(
+                    "Skipping malformed line {} of {}: {e}",
+                    index + 1,
+                    self.path.display()
+                ),
+            }
+        }
+        Ok(records)
+    }
+}
+
+/// Selects history records by age and adapter.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HistoryFilter {
+    /// Only records from this time on.
+    pub since: Option<SystemTime>,
+    /// Only records concerning this adapter.
+    pub adapter: Option<String>,
+}
+
+impl HistoryFilter {
+    /// Returns true if `record` passes the filter.
+    #[must_use]
+    pub fn matches(&self, record: &HistoryRecord) -> bool {
+        self.since.is_none_or(|since| record.timestamp() >= since)
+            && self
+                .adapter
+                .as_deref()
+                .is_none_or(|adapter| record.involves(adapter))
+    }
+}
+
+/// Formats `record` as one line for `ddns-a history`.
+///
+/// # Examples
+///
+/// ```
+/// use ddns_a::monitor::IpChange;
+/// use ddns_a::report::{HistoryRecord, format_record};
+/// use std::time::{Duration, SystemTime};
+///
+/// let at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_714_564_800);
+/// let change = IpChange::added("eth0", "192.0.2.1".parse().unwrap(), at);
+///
+/// assert_eq!(
+///     format_record(&HistoryRecord::Detected(change)),
+///     "2024-05-01T12:00:00Z  eth0  added 192.0.2.1"
+/// );
+/// ```
+#[must_use]
+pub fn format_record(record: &HistoryRecord) -> String {
+    let at = format_rfc3339(
+        record
+            .timestamp()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs()),
+    );
+    match record {
+        HistoryRecord::Detected(change) => {
+            let kind = match change.kind {
+                IpChangeKind::Added => "added",
+                IpChangeKind::Removed => "removed",
+            };
+            format!("{at}  {}  {kind} {}", change.adapter, change.address)
+        }
+        HistoryRecord::Delivery(delivery) => {
+            let mut line = format!(
+                "{at}  {}  delivery of {} change(s) {}",
+                delivery.adapters.join(", "),
+                delivery.changes,
+                delivery.outcome.as_str()
+            );
+            if let Some(ref error) = delivery.error {
+                line.push_str(": ");
+                line.push_str(error);
+            }
+            line
+        }
+    }
+}
+
+/// Parses an age such as `90s`, `30m`, `24h` or `7d`; a bare number is
+/// seconds.
+///
+/// # Errors
+///
+/// Returns a message naming the accepted units if `text` is not an age.
+///
+/// # Examples
+///
+/// ```
+/// use ddns_a::report::parse_age;
+/// use std::time::Duration;
+///
+/// assert_eq!(parse_age("24h"), Ok(Duration::from_secs(86_400)));
+/// assert!(parse_age("1w").is_err());
+/// ```
+pub fn parse_age(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let (number, unit) = text
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or((text, ""), |at| text.split_at(at));
+    let scale = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        _ => 0,
+    };
+    number
+        .parse::<u64>()
+        .ok()
+        .filter(|_| scale > 0)
+        .and_then(|n| n.checked_mul(scale))
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("invalid age '{text}': expected a number followed by s, m, h or d"))
+}
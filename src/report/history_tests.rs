@@ -0,0 +1,224 @@
+//! Tests for the change history.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tempfile::TempDir;
+
+use crate::monitor::IpChange;
+use crate::report::{
+    DeliveryOutcome, DeliveryRecord, HistoryFilter, HistoryLog, HistoryRecord, format_record,
+    parse_age,
+};
+use crate::webhook::{HttpError, RetryableError, WebhookError};
+
+fn at(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+fn added(adapter: &str, address: &str, secs: u64) -> IpChange {
+    IpChange::added(adapter, address.parse().unwrap(), at(secs))
+}
+
+fn failed() -> Result<(), WebhookError> {
+    Err(WebhookError::MaxRetriesExceeded {
+        attempts: 3,
+        last_error: RetryableError::Http(HttpError::Timeout),
+    })
+}
+
+mod delivery_record {
+    use super::*;
+
+    #[test]
+    fn names_each_adapter_once() {
+        let changes = [
+            added("wlan0", "192.0.2.1", 0),
+            added("eth0", "192.0.2.2", 0),
+            added("eth0", "2001:db8::2", 0),
+        ];
+
+        let record = DeliveryRecord::new(&changes, &Ok(()), at(5));
+
+        assert_eq!(record.adapters, ["eth0", "wlan0"]);
+        assert_eq!(record.changes, 3);
+        assert_eq!(record.outcome, DeliveryOutcome::Sent);
+        assert_eq!(record.error, None);
+    }
+
+    #[test]
+    fn keeps_the_error_of_a_failure() {
+        let record = DeliveryRecord::new(&[added("eth0", "192.0.2.1", 0)], &failed(), at(5));
+
+        assert_eq!(record.outcome, DeliveryOutcome::Failed);
+        assert_eq!(record.error.as_deref(), Some("Failed after 3 attempts"));
+    }
+}
+
+mod history_log {
+    use super::*;
+
+    #[test]
+    fn reads_back_what_was_recorded() {
+        let dir = TempDir::new().unwrap();
+        let log = HistoryLog::new(dir.path().join("history.jsonl"));
+        let changes = [added("eth0", "192.0.2.1", 1)];
+
+        log.record_changes(&changes);
+        log.record_delivery(&changes, &failed());
+
+        let records = log.read().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0], HistoryRecord::Detected(changes[0].clone()));
+        assert!(matches!(
+            &records[1],
+            HistoryRecord::Delivery(delivery) if delivery.outcome == DeliveryOutcome::Failed
+        ));
+    }
+
+    #[test]
+    fn appends_to_an_existing_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.jsonl");
+
+        HistoryLog::new(&path).record_changes(&[added("eth0", "192.0.2.1", 1)]);
+        HistoryLog::new(&path).record_changes(&[added("eth0", "192.0.2.2", 2)]);
+
+        assert_eq!(HistoryLog::new(&path).read().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn skips_malformed_lines() {
+        let dir = TempDir::new().unwrap();
+        let log = HistoryLog::new(dir.path().join("history.jsonl"));
+        log.record_changes(&[added("eth0", "192.0.2.1", 1)]);
+        let mut contents = std::fs::read_to_string(log.path()).unwrap();
+        contents.push_str("{\"event\":\"detec\n\n");
+        std::fs::write(log.path(), contents).unwrap();
+
+        assert_eq!(log.read().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        let log = HistoryLog::new(dir.path().join("missing.jsonl"));
+
+        assert!(log.read().is_err());
+    }
+
+    #[test]
+    fn unwritable_file_does_not_panic() {
+        let dir = TempDir::new().unwrap();
+        let log = HistoryLog::new(dir.path().join("missing").join("history.jsonl"));
+
+        log.record_changes(&[added("eth0", "192.0.2.1", 1)]);
+
+        assert!(!log.path().exists());
+    }
+}
+
+mod history_filter {
+    use super::*;
+
+    #[test]
+    fn default_matches_everything() {
+        let record = HistoryRecord::Detected(added("eth0", "192.0.2.1", 1));
+
+        assert!(HistoryFilter::default().matches(&record));
+    }
+
+    #[test]
+    fn since_drops_older_records() {
+        let filter = HistoryFilter {
+            since: Some(at(10)),
+            ..HistoryFilter::default()
+        };
+
+        assert!(!filter.matches(&HistoryRecord::Detected(added("eth0", "192.0.2.1", 9))));
+        assert!(filter.matches(&HistoryRecord::Detected(added("eth0", "192.0.2.1", 10))));
+    }
+
+    #[test]
+    fn adapter_keeps_deliveries_including_it() {
+        let filter = HistoryFilter {
+            adapter: Some("eth0".to_string()),
+            ..HistoryFilter::default()
+        };
+        let changes = [
+            added("wlan0", "192.0.2.1", 0),
+            added("eth0", "192.0.2.2", 0),
+        ];
+
+        assert!(filter.matches(&HistoryRecord::Delivery(DeliveryRecord::new(
+            &changes,
+            &Ok(()),
+            at(1)
+        ))));
+        assert!(!filter.matches(&HistoryRecord::Detected(changes[0].clone())));
+    }
+}
+
+#[test]
+fn formats_deliveries_with_their_error() {
+    let record = HistoryRecord::Delivery(DeliveryRecord::new(
+        &[
+            added("eth0", "192.0.2.1", 0),
+            added("wlan0", "192.0.2.2", 0),
+        ],
+        &failed(),
+        at(1_714_564_800),
+    ));
+
+    assert_eq!(
+        format_record(&record),
+        "2024-05-01T12:00:00Z  eth0, wlan0  delivery of 2 change(s) failed: Failed after 3 attempts"
+    );
+}
+
+#[test]
+fn delivery_serializes_without_missing_error() {
+    let record = HistoryRecord::Delivery(DeliveryRecord::new(
+        &[added("eth0", "192.0.2.1", 0)],
+        &Ok(()),
+        at(1_714_564_800),
+    ));
+
+    let json = serde_json::to_string(&record).unwrap();
+
+    assert_eq!(
+        json,
+        r#"{"event":"delivery","timestamp":"2024-05-01T12:00:00Z","adapters":["eth0"],"changes":1,"outcome":"sent"}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<HistoryRecord>(&json).unwrap(),
+        record
+    );
+}
+
+mod parse_age {
+    use super::*;
+
+    #[test]
+    fn accepts_each_unit() {
+        assert_eq!(parse_age("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_age("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_age("30m"), Ok(Duration::from_secs(1_800)));
+        assert_eq!(parse_age("24h"), Ok(Duration::from_secs(86_400)));
+        assert_eq!(parse_age("7d"), Ok(Duration::from_secs(604_800)));
+    }
+
+    #[test]
+    fn rejects_other_input() {
+        for text in [
+            "",
+            "h",
+            "1w",
+            "1.5h",
+            "-1h",
+            "24 h",
+            "99999999999999999999d",
+        ] {
+            assert!(parse_age(text).is_err(), "{text}");
+        }
+    }
+}
@@ -0,0 +1,366 @@
+//! Machine-readable run summary emitted on shutdown.
+//!
+//! [`RunStats`] accumulates counters while the monitor runs; on exit it is
+//! turned into a [`RunSummary`] and written as a single JSON line, so batch
+//! jobs and CI harnesses can consume the outcome of a run programmatically.
+//! With a [`HistoryLog`] attached, the detected changes and delivery results
+//! are also appended to a history file as they happen.
+
+mod history;
+
+#[cfg(test)]
+mod history_tests;
+#[cfg(test)]
+#[path = "mod_tests.rs"]
+mod tests;
+
+pub use history::{
+    DeliveryRecord, HistoryFilter, HistoryLog, HistoryRecord, format_record, parse_age,
+};
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::monitor::{IpChange, PollMetrics, PollSample, PollSummary};
+use crate::network::priority::{AdapterPriority, EffectiveAddresses};
+use crate::network::{AdapterSnapshot, IpVersion};
+use crate::webhook::{CircuitBreakers, CircuitState, WebhookError};
+
+/// Where the run summary is written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SummaryOutput {
+    /// Write to standard error.
+    Stderr,
+    /// Write to a file, replacing any previous summary.
+    File(PathBuf),
+}
+
+/// Outcome of a webhook delivery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryOutcome {
+    /// The delivery succeeded.
+    Sent,
+    /// The delivery failed after all retries.
+    Failed,
+    /// The delivery was postponed by the connectivity check.
+    Postponed,
+}
+
+impl DeliveryOutcome {
+    /// Returns the outcome of a delivery that ended with `result`.
+    #[must_use]
+    pub fn of(result: &Result<(), WebhookError>) -> Self {
+        match result {
+            Ok(()) => Self::Sent,
+            Err(e) if e.is_postponed() => Self::Postponed,
+            Err(_) => Self::Failed,
+        }
+    }
+
+    /// Returns the lowercase name used in the summary.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Sent => "sent",
+            Self::Failed => "failed",
+            Self::Postponed => "postponed",
+        }
+    }
+}
+
+/// Counters collected over the lifetime of a run.
+///
+/// All methods take `&self` so the stats can be shared by the startup check
+/// and the monitoring loop without threading `&mut` through both.
+#[derive(Debug)]
+pub struct RunStats {
+    started: SystemTime,
+    changes_detected: AtomicU64,
+    notifications_sent: AtomicU64,
+    notifications_failed: AtomicU64,
+    notifications_postponed: AtomicU64,
+    last_delivery: Mutex<Option<DeliveryOutcome>>,
+    last_sent: Mutex<Option<SystemTime>>,
+    last_change: Mutex<Option<SystemTime>>,
+    last_addresses: Mutex<BTreeMap<String, Vec<IpAddr>>>,
+    priority: AdapterPriority,
+    effective_addresses: Mutex<EffectiveAddresses>,
+    poll_metrics: Option<PollMetrics>,
+    circuits: Option<CircuitBreakers>,
+    history: Option<HistoryLog>,
+}
+
+impl RunStats {
+    /// Creates empty stats for a run that started at `started`.
+    #[must_use]
+    pub fn new(started: SystemTime) -> Self {
+        Self {
+            started,
+            changes_detected: AtomicU64::new(0),
+            notifications_sent: AtomicU64::new(0),
+            notifications_failed: AtomicU64::new(0),
+            notifications_postponed: AtomicU64::new(0),
+            last_delivery: Mutex::new(None),
+            last_sent: Mutex::new(None),
+            last_change: Mutex::new(None),
+            last_addresses: Mutex::new(BTreeMap::new()),
+            priority: AdapterPriority::default(),
+            effective_addresses: Mutex::new(EffectiveAddresses::default()),
+            poll_metrics: None,
+            circuits: None,
+            history: None,
+        }
+    }
+
+    /// Sets the adapter priority used to choose effective addresses.
+    #[must_use]
+    pub fn with_adapter_priority(mut self, priority: AdapterPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Includes the fetch metrics recorded in `metrics` in the summary.
+    #[must_use]
+    pub fn with_poll_metrics(mut self, metrics: PollMetrics) -> Self {
+        self.poll_metrics = Some(metrics);
+        self
+    }
+
+    /// Includes the state of the webhook circuits in the summary.
+    #[must_use]
+    pub fn with_circuit_breakers(mut self, circuits: CircuitBreakers) -> Self {
+        self.circuits = Some(circuits);
+        self
+    }
+
+    /// Appends detected changes and delivery results to `history`.
+    #[must_use]
+    pub fn with_history(mut self, history: HistoryLog) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Records a batch of detected changes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the change mutex is poisoned.
+    pub fn record_changes(&self, changes: &[IpChange]) {
+        self.changes_detected
+            .fetch_add(changes.len() as u64, Ordering::Relaxed);
+        if let Some(latest) = changes.iter().map(|c| c.timestamp).max() {
+            let mut last = self.last_change.lock().expect("change mutex poisoned");
+            *last = (*last).max(Some(latest));
+        }
+        if let Some(ref history) = self.history {
+            history.record_changes(changes);
+        }
+    }
+
+    /// Returns the timestamp of the latest recorded change, if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the change mutex is poisoned.
+    #[must_use]
+    pub fn last_change(&self) -> Option<SystemTime> {
+        *self.last_change.lock().expect("change mutex poisoned")
+    }
+
+    /// Records the outcome of one webhook delivery.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the delivery mutex is poisoned.
+    pub fn record_delivery(&self, result: &Result<(), WebhookError>) {
+        let outcome = DeliveryOutcome::of(result);
+        let counter = match outcome {
+            DeliveryOutcome::Sent => &self.notifications_sent,
+            DeliveryOutcome::Postponed => &self.notifications_postponed,
+            DeliveryOutcome::Failed => &self.notifications_failed,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        *self.last_delivery.lock().expect("delivery mutex poisoned") = Some(outcome);
+        if outcome == DeliveryOutcome::Sent {
+            *self.last_sent.lock().expect("delivery mutex poisoned") = Some(SystemTime::now());
+        }
+    }
+
+    /// Records the outcome of delivering `changes`, also in the history.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the delivery mutex is poisoned.
+    pub fn record_changes_delivery(&self, changes: &[IpChange], result: &Result<(), WebhookError>) {
+        self.record_delivery(result);
+        if let Some(ref history) = self.history {
+            history.record_delivery(changes, result);
+        }
+    }
+
+    /// Returns when the latest successful delivery was recorded, if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the delivery mutex is poisoned.
+    #[must_use]
+    pub fn last_sent(&self) -> Option<SystemTime> {
+        *self.last_sent.lock().expect("delivery mutex poisoned")
+    }
+
+    /// Returns the latest adapter fetch, if poll metrics are attached.
+    #[must_use]
+    pub fn last_poll(&self) -> Option<PollSample> {
+        self.poll_metrics.as_ref().and_then(PollMetrics::latest)
+    }
+
+    /// Records the latest adapter addresses in the monitored family.
+    ///
+    /// Adapters without any monitored address are left out. The effective
+    /// addresses are chosen by the adapter priority.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the address mutex is poisoned.
+    pub fn record_snapshot(&self, snapshot: &[AdapterSnapshot], version: IpVersion) {
+        let addresses = snapshot
+            .iter()
+            .map(|adapter| (adapter.name.clone(), monitored_addresses(adapter, version)))
+            .filter(|(_, addrs)| !addrs.is_empty())
+            .collect();
+
+        *self.last_addresses.lock().expect("address mutex poisoned") = addresses;
+        *self
+            .effective_addresses
+            .lock()
+            .expect("address mutex poisoned") = self.priority.resolve(snapshot, version);
+    }
+
+    /// Builds the summary as of `now`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the address or delivery mutex is poisoned.
+    #[must_use]
+    pub fn summary(&self, now: SystemTime) -> RunSummary {
+        RunSummary {
+            started_at: unix_secs(self.started),
+            uptime_secs: now
+                .duration_since(self.started)
+                .unwrap_or_default()
+                .as_secs(),
+            changes_detected: self.changes_detected.load(Ordering::Relaxed),
+            notifications_sent: self.notifications_sent.load(Ordering::Relaxed),
+            notifications_failed: self.notifications_failed.load(Ordering::Relaxed),
+            notifications_postponed: self.notifications_postponed.load(Ordering::Relaxed),
+            last_delivery: *self.last_delivery.lock().expect("delivery mutex poisoned"),
+            last_addresses: self
+                .last_addresses
+                .lock()
+                .expect("address mutex poisoned")
+                .clone(),
+            effective_addresses: self
+                .effective_addresses
+                .lock()
+                .expect("address mutex poisoned")
+                .clone(),
+            polls: self
+                .poll_metrics
+                .as_ref()
+                .map(PollMetrics::summary)
+                .unwrap_or_default(),
+            circuits: self
+                .circuits
+                .as_ref()
+                .map(|circuits| circuits.states(Instant::now()))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Final summary of a run, serialized as JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunSummary {
+    /// Unix timestamp (seconds) when the run started.
+    pub started_at: u64,
+    /// Seconds between start and shutdown.
+    pub uptime_secs: u64,
+    /// Number of individual address changes detected.
+    pub changes_detected: u64,
+    /// Webhook deliveries that succeeded.
+    pub notifications_sent: u64,
+    /// Webhook deliveries that failed after all retries.
+    pub notifications_failed: u64,
+    /// Webhook deliveries postponed by the connectivity check.
+    pub notifications_postponed: u64,
+    /// Outcome of the latest delivery; `None` before the first.
+    pub last_delivery: Option<DeliveryOutcome>,
+    /// Last known monitored addresses per adapter.
+    pub last_addresses: BTreeMap<String, Vec<IpAddr>>,
+    /// Effective address per monitored family, by adapter priority.
+    pub effective_addresses: EffectiveAddresses,
+    /// Adapter fetch metrics (all zero unless poll metrics are attached).
+    pub polls: PollSummary,
+    /// Circuit state per webhook target (empty unless circuit breaking is enabled).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub circuits: BTreeMap<String, CircuitState>,
+}
+
+impl RunSummary {
+    /// Serializes the summary as a single JSON line.
+    ///
+    /// # Panics
+    ///
+    /// Never panics in practice: the summary only holds numbers and
+    /// string-keyed maps, which always serialize.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("run summary is always serializable")
+    }
+
+    /// Writes the summary to `output`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the summary cannot be written.
+    pub fn write_to(&self, output: &SummaryOutput) -> io::Result<()> {
+        match output {
+            SummaryOutput::Stderr => writeln!(io::stderr().lock(), "{}", self.to_json()),
+            SummaryOutput::File(path) => write_file(path, &self.to_json()),
+        }
+    }
+}
+
+/// Writes `json` to `path`, creating parent directories as needed.
+fn write_file(path: &Path, json: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, format!("{json}\n"))
+}
+
+fn monitored_addresses(adapter: &AdapterSnapshot, version: IpVersion) -> Vec<IpAddr> {
+    let v4 = adapter
+        .ipv4_addresses
+        .iter()
+        .filter(|_| version.includes_v4())
+        .map(|&a| IpAddr::V4(a));
+    let v6 = adapter
+        .ipv6_addresses
+        .iter()
+        .filter(|_| version.includes_v6())
+        .map(|&a| IpAddr::V6(a));
+    v4.chain(v6).collect()
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
@@ -0,0 +1,224 @@
+//! Tests for the run summary.
+
+use std::net::IpAddr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tempfile::TempDir;
+
+use crate::monitor::{IpChange, PollMetrics, PollSample};
+use crate::network::priority::AdapterPriority;
+use crate::network::{AdapterKind, AdapterSnapshot, IpVersion};
+use crate::report::{DeliveryOutcome, HistoryLog, HistoryRecord, RunStats, SummaryOutput};
+use crate::webhook::{CircuitBreakers, CircuitPolicy, WebhookError};
+
+fn at(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+fn change(addr: &str) -> IpChange {
+    IpChange::added("eth0", addr.parse::<IpAddr>().unwrap(), at(0))
+}
+
+fn dual_stack(name: &str) -> AdapterSnapshot {
+    AdapterSnapshot::new(
+        name,
+        AdapterKind::Ethernet,
+        vec!["192.0.2.5".parse().unwrap()],
+        vec!["2001:db8::5".parse().unwrap()],
+    )
+}
+
+#[test]
+fn fresh_stats_are_zero() {
+    let summary = RunStats::new(at(1000)).summary(at(1000));
+
+    assert_eq!(summary.started_at, 1000);
+    assert_eq!(summary.uptime_secs, 0);
+    assert_eq!(summary.changes_detected, 0);
+    assert_eq!(summary.notifications_sent, 0);
+    assert!(summary.last_addresses.is_empty());
+    assert_eq!(summary.last_delivery, None);
+}
+
+#[test]
+fn counts_changes_and_deliveries() {
+    let stats = RunStats::new(at(1000));
+
+    stats.record_changes(&[change("192.0.2.1"), change("192.0.2.2")]);
+    stats.record_changes(&[change("192.0.2.3")]);
+    stats.record_delivery(&Ok(()));
+    stats.record_delivery(&Err(WebhookError::Postponed {
+        pending: 1,
+        reason: "offline".to_string(),
+    }));
+    stats.record_delivery(&Err(WebhookError::MaxRetriesExceeded {
+        attempts: 3,
+        last_error: crate::webhook::RetryableError::NonSuccessStatus {
+            status: http::StatusCode::BAD_GATEWAY,
+            body: None,
+            retry_after: None,
+        },
+    }));
+
+    let summary = stats.summary(at(1060));
+    assert_eq!(summary.uptime_secs, 60);
+    assert_eq!(summary.changes_detected, 3);
+    assert_eq!(summary.notifications_sent, 1);
+    assert_eq!(summary.notifications_postponed, 1);
+    assert_eq!(summary.notifications_failed, 1);
+    assert_eq!(summary.last_delivery, Some(DeliveryOutcome::Failed));
+}
+
+#[test]
+fn last_sent_only_after_success() {
+    let stats = RunStats::new(at(1000));
+    stats.record_delivery(&Err(WebhookError::Postponed {
+        pending: 1,
+        reason: "offline".to_string(),
+    }));
+    assert_eq!(stats.last_sent(), None);
+
+    let before = SystemTime::now();
+    stats.record_delivery(&Ok(()));
+
+    assert!(stats.last_sent().is_some_and(|sent| sent >= before));
+}
+
+#[test]
+fn last_poll_from_attached_metrics() {
+    let metrics = PollMetrics::default();
+    let stats = RunStats::new(at(0)).with_poll_metrics(metrics.clone());
+    assert_eq!(stats.last_poll(), None);
+    assert_eq!(RunStats::new(at(0)).last_poll(), None);
+
+    let sample = PollSample {
+        finished_at: at(5),
+        duration: Duration::from_millis(3),
+        adapters: 1,
+        addresses: 1,
+        succeeded: false,
+    };
+    metrics.record(sample.clone());
+
+    assert_eq!(stats.last_poll(), Some(sample));
+}
+
+#[test]
+fn last_addresses_follow_monitored_version() {
+    let stats = RunStats::new(at(0));
+    let empty = AdapterSnapshot::new("wlan0", AdapterKind::Wireless, vec![], vec![]);
+
+    stats.record_snapshot(&[dual_stack("eth0"), empty], IpVersion::V6);
+
+    let summary = stats.summary(at(0));
+    assert_eq!(summary.last_addresses.len(), 1);
+    assert_eq!(
+        summary.last_addresses["eth0"],
+        vec!["2001:db8::5".parse::<IpAddr>().unwrap()]
+    );
+}
+
+#[test]
+fn json_contains_all_fields() {
+    let stats = RunStats::new(at(100));
+    stats.record_snapshot(&[dual_stack("eth0")], IpVersion::Both);
+
+    let json: serde_json::Value = serde_json::from_str(&stats.summary(at(130)).to_json()).unwrap();
+
+    assert_eq!(json["started_at"], 100);
+    assert_eq!(json["uptime_secs"], 30);
+    assert_eq!(json["changes_detected"], 0);
+    assert_eq!(json["notifications_failed"], 0);
+    assert_eq!(json["last_delivery"], serde_json::Value::Null);
+    assert_eq!(
+        json["last_addresses"]["eth0"],
+        serde_json::json!(["192.0.2.5", "2001:db8::5"])
+    );
+}
+
+#[test]
+fn effective_addresses_follow_adapter_priority() {
+    let stats = RunStats::new(at(0)).with_adapter_priority(AdapterPriority::new(["wlan0"]));
+
+    stats.record_snapshot(&[dual_stack("eth0"), dual_stack("wlan0")], IpVersion::V4);
+
+    let json: serde_json::Value = serde_json::from_str(&stats.summary(at(0)).to_json()).unwrap();
+    assert_eq!(
+        json["effective_addresses"],
+        serde_json::json!({
+            "ipv4": {"adapter": "wlan0", "address": "192.0.2.5"},
+            "ipv6": null,
+        })
+    );
+}
+
+#[test]
+fn writes_file_creating_parent_dirs() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("out").join("summary.json");
+    let summary = RunStats::new(at(0)).summary(at(5));
+
+    summary
+        .write_to(&SummaryOutput::File(path.clone()))
+        .unwrap();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(content, format!("{}\n", summary.to_json()));
+}
+
+#[test]
+fn includes_poll_metrics() {
+    let metrics = PollMetrics::default();
+    let stats = RunStats::new(at(0)).with_poll_metrics(metrics.clone());
+
+    metrics.record(PollSample {
+        finished_at: at(1),
+        duration: Duration::from_millis(40),
+        adapters: 2,
+        addresses: 3,
+        succeeded: true,
+    });
+
+    let json: serde_json::Value = serde_json::from_str(&stats.summary(at(1)).to_json()).unwrap();
+    assert_eq!(json["polls"]["polls"], 1);
+    assert_eq!(json["polls"]["last_duration_ms"], 40);
+    assert_eq!(json["polls"]["adapters"], 2);
+}
+
+#[test]
+fn includes_circuit_states_if_attached() {
+    let circuits = CircuitBreakers::new(CircuitPolicy::default());
+    let _ = circuits.circuit("main");
+
+    let plain = RunStats::new(at(0)).summary(at(1)).to_json();
+    let tracked = RunStats::new(at(0))
+        .with_circuit_breakers(circuits)
+        .summary(at(1))
+        .to_json();
+
+    assert!(!plain.contains("circuits"), "{plain}");
+    let json: serde_json::Value = serde_json::from_str(&tracked).unwrap();
+    assert_eq!(json["circuits"]["main"]["state"], "closed");
+}
+
+#[test]
+fn appends_changes_and_deliveries_to_attached_history() {
+    let dir = TempDir::new().unwrap();
+    let history = HistoryLog::new(dir.path().join("history.jsonl"));
+    let stats = RunStats::new(at(0)).with_history(history.clone());
+    let changes = [change("192.0.2.1")];
+
+    stats.record_changes(&changes);
+    stats.record_changes_delivery(&changes, &Ok(()));
+    // Deliveries without changes only count
+    stats.record_delivery(&Ok(()));
+
+    let records = history.read().unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0], HistoryRecord::Detected(changes[0].clone()));
+    assert!(matches!(
+        &records[1],
+        HistoryRecord::Delivery(delivery) if delivery.outcome == DeliveryOutcome::Sent
+    ));
+    assert_eq!(stats.summary(at(1)).notifications_sent, 2);
+}
@@ -3,28 +3,157 @@
 //! This module contains the main async execution loop that monitors
 //! IP address changes and sends webhook notifications.
 
+use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
 use std::time::{Duration, SystemTime};
 
 use thiserror::Error;
-use tokio::signal;
-use tokio_stream::StreamExt;
+use tokio::sync::mpsc;
 
 use ddns_a::config::ValidatedConfig;
+#[cfg(unix)]
+use ddns_a::daemon::{Notifier, NotifyFetcher};
+use ddns_a::health::{self, HEALTH_PATH};
+use ddns_a::monitor::events::ChangeBus;
 use ddns_a::monitor::{
-    DebouncePolicy, HybridMonitor, IpChange, PollingMonitor, diff, filter_by_version,
+    AdaptivePolicy, AddressStormFetcher, DebouncePolicy, DnsTrackingFetcher, ExpectationFetcher,
+    Heartbeat, HeartbeatFetcher, HostnameTrackingFetcher, LinkTrackingFetcher, MetricsFetcher,
+    NotifyOn, PollMetrics, SharedInterval, SystemHostname, Watchdog, WatchdogAction,
+    WatchdogStatus,
 };
-use ddns_a::network::filter::{FilterChain, FilteredFetcher};
+use ddns_a::network::class::ClassFilteredFetcher;
+use ddns_a::network::filter::{FilteredFetcher, SharedFilter};
 use ddns_a::network::platform::PlatformFetcher;
-use ddns_a::network::{AdapterSnapshot, AddressFetcher, IpVersion};
-use ddns_a::state::{FileStateStore, LoadResult, StateStore};
-use ddns_a::webhook::{HttpWebhook, ReqwestClient, WebhookSender};
+use ddns_a::network::public::{CombinedFetcher, PublicIpFetcher};
+use ddns_a::network::{AddressFetcher, IpVersion};
+use ddns_a::report::{HistoryLog, RunStats, SummaryOutput};
+use ddns_a::state::{FileStateStore, QueueLimits, StateLock};
+use ddns_a::time::ShutdownToken;
+use ddns_a::tray::PauseSwitch;
+use ddns_a::webhook::{
+    AuditedClient, CircuitBreaker, CircuitBreakers, ConnectivityGate, FamilyProbe, FanOut,
+    FileAuditSink, MetadataClient, NameserverResolver, ReqwestClient,
+};
 
 /// Type alias for the application's filtered fetcher.
-type AppFetcher = FilteredFetcher<PlatformFetcher, FilterChain>;
+///
+/// Local adapters and the public address are fetched as `monitor.source`
+/// selects; the public address is not filtered by adapter name. Every
+/// completed fetch beats the watchdog's heartbeat, is timed for the
+/// poll metrics, is checked for IPv6 address storms (only counted with
+/// `[monitor.address_storm]`), for adapters going up or down (only with
+/// `monitor.track_link`), for DNS setting changes (only collected with
+/// `monitor.track_dns`), for drift from the `[expect]` addresses and for
+/// hostname changes (only read with `monitor.track_hostname`). Under systemd,
+/// fetches also report readiness and feed its watchdog.
+type AppFetcher = Notified<
+    HeartbeatFetcher<
+        MetricsFetcher<
+            HostnameTrackingFetcher<
+                ExpectationFetcher<
+                    DnsTrackingFetcher<LinkTrackingFetcher<AddressStormFetcher<SourceFetcher>>>,
+                >,
+                Option<SystemHostname>,
+            >,
+        >,
+    >,
+>;
+
+/// Type alias for the fetcher of the filtered local adapters, without the
+/// filtered address classes, and the public address, as `monitor.source`
+/// selects.
+type SourceFetcher = CombinedFetcher<
+    FilteredFetcher<ClassFilteredFetcher<PlatformFetcher>, SharedFilter>,
+    PublicIpFetcher<ReqwestClient>,
+>;
+
+/// Fetcher notifying systemd (see [`NotifyFetcher`]); unchanged elsewhere.
+#[cfg(unix)]
+type Notified<F> = NotifyFetcher<F>;
+#[cfg(not(unix))]
+type Notified<F> = F;
+
+/// Type alias for the application's HTTP client (auditing is a no-op when disabled).
+///
+/// Metadata headers are added outside the audit layer so audit records
+/// show the headers that were actually sent.
+type AppClient = MetadataClient<AuditedClient<ReqwestClient, Option<FileAuditSink>>>;
+
+/// Type alias for the connectivity probe of `webhook.connectivity_check`.
+type AppProbe = FamilyProbe<PlatformFetcher, Option<NameserverResolver>>;
 
-#[cfg(windows)]
-use ddns_a::monitor::platform::PlatformListener;
+/// Type alias for the application's webhook sender: every target at once,
+/// each gated on the tray pause and the connectivity to its host, then on
+/// its circuit breaker.
+type AppSender =
+    FanOut<ConnectivityGate<CircuitBreaker<AppTarget>, (PauseSwitch, Option<AppProbe>)>>;
+
+mod action;
+#[cfg(test)]
+mod action_tests;
+mod check;
+#[cfg(test)]
+mod check_tests;
+mod events;
+#[cfg(test)]
+mod events_tests;
+mod golden;
+#[cfg(all(
+    any(windows, target_os = "linux", target_os = "macos"),
+    feature = "hybrid"
+))]
+mod hybrid;
+mod outbox;
+mod polling;
+#[cfg(test)]
+mod polling_tests;
+mod queue;
+mod reload;
+#[cfg(test)]
+mod reload_tests;
+mod replay;
+mod send_test;
+mod senders;
+#[cfg(test)]
+mod senders_tests;
+mod signals;
+#[cfg(test)]
+mod signals_tests;
+mod smoke;
+#[cfg(test)]
+mod smoke_tests;
+mod startup;
+#[cfg(test)]
+mod startup_tests;
+mod throttle;
+#[cfg(test)]
+mod throttle_tests;
+mod tray;
+
+use action::AppTarget;
+pub use check::check;
+use events::Events;
+pub use golden::verify_goldens;
+#[cfg(all(
+    any(windows, target_os = "linux", target_os = "macos"),
+    feature = "hybrid"
+))]
+use hybrid::run_hybrid_loop;
+#[cfg(not(all(
+    any(windows, target_os = "linux", target_os = "macos"),
+    feature = "hybrid"
+)))]
+use polling::run_hybrid_loop;
+use polling::{longest_poll_interval, run_polling_loop};
+pub use replay::notify_replayed;
+pub use send_test::send_test;
+pub use senders::http_client;
+use senders::start_webhooks;
+use signals::spawn_signal_handler;
+pub use smoke::{TargetSelection, test_webhooks};
+use tray::Tray;
 
 #[cfg(test)]
 #[path = "run_tests.rs"]
@@ -34,6 +163,7 @@ mod tests;
 #[derive(Debug, Error)]
 pub enum RunError {
     /// Failed to create the API listener.
+    #[cfg(feature = "hybrid")]
     #[error("Failed to create API listener: {0}")]
     ApiListenerCreation(#[source] ddns_a::monitor::ApiError),
 
@@ -48,6 +178,23 @@ pub enum RunError {
     /// Failed to save state file.
     #[error("Failed to save state: {0}")]
     StateSave(#[source] ddns_a::state::StateError),
+
+    /// The state file is locked by another instance or cannot be locked.
+    #[error(transparent)]
+    StateLock(ddns_a::state::StateError),
+
+    /// Failed to start the health endpoint.
+    #[error(transparent)]
+    Health(#[from] ddns_a::health::HealthError),
+}
+
+/// How [`execute`] ended without error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    /// Stopped by Ctrl+C / SIGTERM (or the tray menu).
+    Shutdown,
+    /// Stopped by SIGUSR2 (`ddns-a ctl restart`) to run the binary again.
+    Restart,
 }
 
 /// Runtime options extracted from validated config.
@@ -56,24 +203,96 @@ pub enum RunError {
 /// allowing the config's `filter` field to be moved separately.
 struct RuntimeOptions {
     ip_version: IpVersion,
+    /// Kinds of address change delivered
+    notify_on: NotifyOn,
     poll_interval: Duration,
+    /// Separate IPv6 polling interval, if configured
+    poll_interval_v6: Option<Duration>,
+    /// Stretches the polling interval while stable, if adaptive
+    adaptive: Option<AdaptivePolicy>,
+    fetch_timeout: Duration,
+    /// Window merging rapid changes, if debouncing is enabled
+    debounce: Option<DebouncePolicy>,
+    /// Quiet period coalescing bursts of API notifications, if enabled
+    #[cfg_attr(
+        not(all(
+            any(windows, target_os = "linux", target_os = "macos"),
+            feature = "hybrid"
+        )),
+        allow(dead_code)
+    )]
+    event_quiet: Option<Duration>,
     poll_only: bool,
     dry_run: bool,
+    /// Minimum interval between address notifications, if limited
+    min_notify_interval: Option<Duration>,
     state_file: Option<PathBuf>,
+    /// Limits of the persistent retry queue, if enabled
+    retry_queue: Option<QueueLimits>,
+    /// Circuits of the webhook targets, if circuit breaking is enabled;
+    /// kept across reloads
+    circuits: Option<CircuitBreakers>,
+    /// Every version-filtered batch the monitor observes, before the
+    /// `notify_on` filter; kept across reloads
+    changes: ChangeBus,
+    /// Longest interval between two polls, which the watchdog allows twice
+    /// of; updated on reload
+    watchdog_interval: SharedInterval,
+    /// Triggered by Ctrl+C / SIGTERM; no new changes are taken after it
+    shutdown: ShutdownToken,
+    /// How long deliveries in progress may keep retrying after `shutdown`
+    shutdown_grace: Duration,
+    /// Triggered `shutdown_grace` after `shutdown` or by a second stop
+    /// signal; ends webhook retries in progress
+    abort: ShutdownToken,
+    /// Paused from the tray menu; postpones deliveries through the gate
+    pause: PauseSwitch,
+    tray: Tray,
 }
 
 impl From<&ValidatedConfig> for RuntimeOptions {
     fn from(config: &ValidatedConfig) -> Self {
         Self {
             ip_version: config.ip_version,
-            poll_interval: config.poll_interval,
-            poll_only: config.poll_only,
+            notify_on: config.webhook.notify_on,
+            poll_interval: config.monitor.poll_interval,
+            poll_interval_v6: config.monitor.poll_interval_v6,
+            adaptive: config.monitor.adaptive,
+            fetch_timeout: config.monitor.fetch_timeout,
+            debounce: config.monitor.debounce.clone(),
+            event_quiet: config.monitor.event_quiet,
+            poll_only: config.monitor.poll_only,
             dry_run: config.dry_run,
+            min_notify_interval: config.webhook.min_notify_interval,
             state_file: config.state_file.clone(),
+            retry_queue: config.webhook.retry_queue,
+            circuits: circuit_breakers(config),
+            changes: ChangeBus::default(),
+            watchdog_interval: SharedInterval::new(longest_poll_interval(
+                config.monitor.poll_interval,
+                config.monitor.adaptive,
+            )),
+            shutdown: ShutdownToken::new(),
+            shutdown_grace: config.webhook.shutdown_grace,
+            abort: ShutdownToken::new(),
+            pause: PauseSwitch::new(),
+            tray: Tray::default(),
         }
     }
 }
 
+/// Creates the circuits of the webhook targets if `[webhook.circuit_breaker]`
+/// or any target's own circuit breaker is set.
+fn circuit_breakers(config: &ValidatedConfig) -> Option<CircuitBreakers> {
+    let enabled = config.webhook.circuit_breaker.is_some()
+        || config
+            .webhook
+            .targets
+            .iter()
+            .any(|t| t.circuit_breaker.is_some());
+    enabled.then(|| CircuitBreakers::new(config.webhook.circuit_breaker.unwrap_or_default()))
+}
+
 /// Executes the main application loop.
 ///
 /// This function:
@@ -81,7 +300,12 @@ impl From<&ValidatedConfig> for RuntimeOptions {
 /// 2. Detects startup changes (if state file is configured)
 /// 3. Creates the monitor (hybrid or polling-only based on config)
 /// 4. Creates the webhook sender
-/// 5. Runs the monitoring loop until shutdown signal (Ctrl+C)
+/// 5. Runs the monitoring loop until shutdown signal (Ctrl+C), restart
+///    signal (SIGUSR2) or `shutdown` is triggered (a Windows service stop)
+///
+/// A restart stops like a shutdown: deliveries cut short are kept in the
+/// state file's outbox, and changes still being debounced are detected
+/// again against the saved snapshot by the restarted process.
 ///
 /// # Errors
 ///
@@ -96,15 +320,60 @@ impl From<&ValidatedConfig> for RuntimeOptions {
 /// - Platform-specific network APIs
 /// - Real async runtime with signal handling
 #[cfg(not(tarpaulin_include))]
-pub async fn execute(config: ValidatedConfig) -> Result<(), RunError> {
-    // Extract runtime options before consuming config fields
-    let options = RuntimeOptions::from(&config);
+pub async fn execute(
+    mut config: ValidatedConfig,
+    shutdown: ShutdownToken,
+) -> Result<Exit, RunError> {
+    let _lock = lock_state_file(&config)?;
 
-    // Create the webhook sender
-    let webhook = create_webhook(&config);
-
-    // Create the fetcher with filters (consumes config.filter)
-    let fetcher = FilteredFetcher::new(PlatformFetcher::default(), config.filter);
+    // Extract runtime options before consuming config fields
+    let mut options = RuntimeOptions::from(&config);
+    options.shutdown = shutdown;
+    let poll_metrics = PollMetrics::default();
+    let stats = Arc::new(create_stats(&config, &options, &poll_metrics));
+
+    let (signals, restart) = spawn_signal_handler(&options);
+
+    // Create the webhook senders and the filters, both replaced on reload (consumes config.monitor.filter)
+    let (certificate_tx, certificate) = mpsc::unbounded_channel();
+    let filter = SharedFilter::new(std::mem::take(&mut config.monitor.filter));
+    let (webhook, reloader) = start_webhooks(&config, &options, &filter, certificate_tx).await;
+
+    // Create the fetcher with filters
+    let heartbeat = Heartbeat::new();
+    let (dns_tx, dns) = mpsc::unbounded_channel();
+    let (drift_tx, drift) = mpsc::unbounded_channel();
+    let (hostname_tx, hostname) = mpsc::unbounded_channel();
+    let (storm_tx, storm) = mpsc::unbounded_channel();
+    let (link_tx, link) = mpsc::unbounded_channel();
+    let (tray_tx, tray) = mpsc::unbounded_channel();
+    log_reported_events(&config);
+    let source = source_fetcher(&config, filter);
+    let storms = AddressStormFetcher::new(source, config.monitor.address_storm, storm_tx);
+    let links = LinkTrackingFetcher::new(storms, config.monitor.track_link, link_tx);
+    let dns_tracking = DnsTrackingFetcher::new(links, dns_tx);
+    let expectation = ExpectationFetcher::new(dns_tracking, config.monitor.expect, drift_tx);
+    let hostname_source = config.monitor.track_hostname.then_some(SystemHostname);
+    let hostnames = HostnameTrackingFetcher::new(expectation, hostname_source, hostname_tx);
+    let events = Events {
+        dns,
+        drift,
+        hostname,
+        storm,
+        link,
+        certificate,
+        tray,
+    };
+    let fetcher = HeartbeatFetcher::new(
+        MetricsFetcher::new(hostnames, poll_metrics, options.poll_interval),
+        heartbeat.clone(),
+    );
+    let fetcher = notify_systemd(fetcher, options.watchdog_interval.get());
+    let watchdog = spawn_watchdog(heartbeat, &options, config.monitor.watchdog);
+    if config.tray {
+        options.tray = Tray::start(tray_tx, &fetcher, &options, &stats);
+    }
+    let health = start_health(config.health, &fetcher, &options, &stats).await?;
 
     // Log startup info
     if options.dry_run {
@@ -117,300 +386,266 @@ pub async fn execute(config: ValidatedConfig) -> Result<(), RunError> {
     // Perform startup change detection if state file is configured
     if let Some(ref store) = state_store {
         tracing::info!("State persistence enabled: {}", store.path().display());
-        startup_change_detection(store, &fetcher, &webhook, &options).await?;
+        startup::startup_change_detection(store, &fetcher, &webhook, &options, &stats).await?;
     }
 
-    if options.poll_only {
+    let result = if options.poll_only {
         tracing::info!(
             "Polling-only mode enabled (interval: {}s)",
             options.poll_interval.as_secs()
         );
-        run_polling_loop(fetcher, webhook, options, state_store).await
+        run_polling_loop(
+            fetcher,
+            events,
+            webhook,
+            reloader,
+            options,
+            state_store,
+            &stats,
+        )
+        .await
     } else {
         tracing::info!(
             "Hybrid mode enabled (API events + polling every {}s)",
             options.poll_interval.as_secs()
         );
-        run_hybrid_loop(fetcher, webhook, options, state_store).await
+        run_hybrid_loop(
+            fetcher,
+            events,
+            webhook,
+            reloader,
+            options,
+            state_store,
+            &stats,
+        )
+        .await
+    };
+
+    signals.abort();
+    notify_systemd_stopping(restart.load(Ordering::SeqCst));
+    for task in [watchdog, health].into_iter().flatten() {
+        task.abort();
+    }
+    if let Some(ref output) = config.outputs.summary {
+        write_summary(&stats, output);
+    }
+    result?;
+
+    if !restart.load(Ordering::SeqCst) {
+        return Ok(Exit::Shutdown);
+    }
+    if config.state_file.is_none() {
+        tracing::warn!("Restarting without --state-file: changes not yet delivered are lost");
     }
+    Ok(Exit::Restart)
 }
 
-/// Detects and handles IP changes that occurred while the program was stopped.
-///
-/// Compares the current network state with the previously saved state.
-/// If changes are detected, sends a webhook notification.
-///
-/// Excluded from coverage - requires platform APIs.
-#[cfg(not(tarpaulin_include))]
-async fn startup_change_detection<W: WebhookSender>(
-    store: &FileStateStore,
-    fetcher: &AppFetcher,
-    webhook: &W,
-    options: &RuntimeOptions,
-) -> Result<(), RunError> {
-    // Fetch current network state
-    let current = fetcher.fetch().map_err(RunError::InitialFetch)?;
+/// Locks the state file, if any, so that no other instance uses it, unless
+/// `--no-lock` is given.
+fn lock_state_file(config: &ValidatedConfig) -> Result<Option<StateLock>, RunError> {
+    let Some(ref state_file) = config.state_file else {
+        return Ok(None);
+    };
+    if config.no_lock {
+        tracing::warn!("State file lock disabled (--no-lock): other instances may overwrite it");
+        return Ok(None);
+    }
+    let lock = StateLock::acquire(state_file).map_err(RunError::StateLock)?;
+    tracing::debug!("Locked state file: {}", lock.path().display());
+    Ok(Some(lock))
+}
 
-    // Compare with saved state
-    let startup_changes = detect_startup_changes(store, &current, options.ip_version);
+/// Creates the fetcher of the adapters `filter` keeps and the public address
+/// `config` selects.
+fn source_fetcher(config: &ValidatedConfig, filter: SharedFilter) -> SourceFetcher {
+    let platform = ClassFilteredFetcher::new(
+        PlatformFetcher::new().with_dns(config.monitor.track_dns),
+        config.monitor.address_classes.clone(),
+    );
+    let adapters = config
+        .monitor
+        .address_source
+        .includes_adapters()
+        .then(|| FilteredFetcher::new(platform, filter));
+    let public = config.monitor.public_address.clone().map(|public| {
+        PublicIpFetcher::new(
+            http_client(&config.http, None),
+            public.endpoints,
+            config.ip_version,
+        )
+        .with_timeout(public.timeout)
+    });
+    CombinedFetcher::new(adapters, public)
+}
 
-    // Handle any detected changes
-    if startup_changes.is_empty() {
-        tracing::debug!("No IP changes detected since last run");
-    } else {
+/// Logs which events are reported beside address changes.
+fn log_reported_events(config: &ValidatedConfig) {
+    if config.monitor.track_dns {
+        tracing::info!("DNS setting changes are reported");
+    }
+    if config.monitor.track_hostname {
+        tracing::info!("Hostname changes are reported");
+    }
+    if config.monitor.address_storm.is_some() {
+        tracing::info!("IPv6 address storms are reported");
+    }
+    if config.monitor.track_link {
+        tracing::info!("Adapters going up or down are reported");
+    }
+    if !config.monitor.expect.is_empty() {
         tracing::info!(
-            "Detected {} change(s) since last run",
-            startup_changes.len()
+            "Expecting addresses: {:?}",
+            config.monitor.expect.addresses()
         );
-        handle_changes(&startup_changes, webhook, options.dry_run).await;
-    }
-
-    // Save current state (optimistic save - before webhook result matters)
-    // This ensures the state reflects the actual current IPs
-    if let Err(e) = store.save(&current).await {
-        tracing::error!("Failed to save state: {e}");
-        return Err(RunError::StateSave(e));
     }
-
-    Ok(())
 }
 
-/// Compares current network state with saved state and returns changes.
-fn detect_startup_changes(
-    store: &impl StateStore,
-    current: &[AdapterSnapshot],
-    ip_version: IpVersion,
-) -> Vec<IpChange> {
-    detect_startup_changes_with_timestamp(store, current, ip_version, SystemTime::now())
+/// Writes the JSON run summary, logging (not failing) on error.
+fn write_summary(stats: &RunStats, output: &SummaryOutput) {
+    if let Err(e) = stats.summary(SystemTime::now()).write_to(output) {
+        tracing::error!("Failed to write run summary: {e}");
+    }
 }
 
-/// Compares current network state with saved state and returns changes.
+/// Wraps `fetcher` to notify systemd, if started as a `Type=notify` service.
 ///
-/// This variant accepts a timestamp for testability.
-fn detect_startup_changes_with_timestamp(
-    store: &impl StateStore,
-    current: &[AdapterSnapshot],
-    ip_version: IpVersion,
-    timestamp: SystemTime,
-) -> Vec<IpChange> {
-    match store.load() {
-        LoadResult::Loaded(saved) => {
-            let changes = diff(&saved, current, timestamp);
-            filter_by_version(changes, ip_version)
-        }
-        LoadResult::NotFound => {
-            tracing::info!("No previous state found, starting fresh");
-            vec![]
-        }
-        LoadResult::Corrupted { reason } => {
-            tracing::warn!("State file corrupted ({reason}), will overwrite on next save");
-            vec![]
+/// A watchdog period not longer than `poll_interval` is warned about, since
+/// systemd would kill the service between two quiet polls.
+#[cfg(unix)]
+fn notify_systemd<F>(fetcher: F, poll_interval: Duration) -> Notified<F> {
+    let notifier = Notifier::from_env().unwrap_or_else(|e| {
+        tracing::warn!("{e}");
+        None
+    });
+    if let Some(period) = notifier.as_ref().and_then(Notifier::watchdog) {
+        tracing::debug!(
+            "Feeding the systemd watchdog (WatchdogSec={}s)",
+            period.as_secs()
+        );
+        if period <= poll_interval {
+            tracing::warn!(
+                "systemd WatchdogSec ({}s) should be longer than the poll interval ({}s)",
+                period.as_secs(),
+                poll_interval.as_secs()
+            );
         }
     }
+    NotifyFetcher::new(fetcher, notifier)
 }
 
-/// Creates the HTTP webhook sender from configuration.
-fn create_webhook(config: &ValidatedConfig) -> HttpWebhook<ReqwestClient> {
-    let mut webhook = HttpWebhook::new(ReqwestClient::new(), config.url.clone())
-        .with_method(config.method.clone())
-        .with_headers(config.headers.clone())
-        .with_retry_policy(config.retry_policy.clone());
-
-    if let Some(ref template) = config.body_template {
-        webhook = webhook.with_body_template(template);
-    }
-
-    webhook
+#[cfg(not(unix))]
+const fn notify_systemd<F>(fetcher: F, _poll_interval: Duration) -> Notified<F> {
+    fetcher
 }
 
-/// Runs the polling-only monitoring loop.
-///
-/// Excluded from coverage - requires platform APIs and signal handling.
-#[cfg(not(tarpaulin_include))]
-async fn run_polling_loop<W: WebhookSender>(
-    fetcher: AppFetcher,
-    webhook: W,
-    options: RuntimeOptions,
-    state_store: Option<FileStateStore>,
-) -> Result<(), RunError> {
-    let monitor = PollingMonitor::new(fetcher, options.poll_interval)
-        .with_debounce(DebouncePolicy::default());
-
-    let mut stream = monitor.into_stream();
-    let shutdown = shutdown_signal();
-    tokio::pin!(shutdown);
-
-    loop {
-        tokio::select! {
-            biased;
-
-            () = &mut shutdown => {
-                tracing::info!("Shutdown signal received, stopping...");
-                return Ok(());
-            }
-
-            changes = stream.next() => {
-                match changes {
-                    Some(changes) => {
-                        // Filter by IP version before processing
-                        let filtered = filter_by_version(changes, options.ip_version);
-                        if !filtered.is_empty() {
-                            save_state_if_configured(state_store.as_ref(), stream.current_snapshot()).await;
-                            handle_changes(&filtered, &webhook, options.dry_run).await;
-                        }
-                    }
-                    None => {
-                        // Stream ended unexpectedly
-                        return Err(RunError::StreamTerminated);
-                    }
-                }
-            }
+/// Tells systemd the service is stopping, or reloading if this process is
+/// about to exec its replacement (which reports `READY=1` again).
+#[cfg(unix)]
+fn notify_systemd_stopping(restart: bool) {
+    if let Ok(Some(notifier)) = Notifier::from_env() {
+        let state = if restart { "RELOADING=1" } else { "STOPPING=1" };
+        if let Err(e) = notifier.notify(state) {
+            tracing::debug!("{e}");
         }
     }
 }
 
-/// Saves state to the store if configured.
+#[cfg(not(unix))]
+const fn notify_systemd_stopping(_restart: bool) {}
+
+/// Serves the `/healthz` endpoint on `address`, if configured.
 ///
-/// Uses optimistic save strategy: state is saved before webhook delivery.
-/// This ensures the state reflects actual current IPs regardless of webhook success.
-/// On restart, previously notified changes won't re-trigger (by design).
-async fn save_state_if_configured(
-    store: Option<&FileStateStore>,
-    snapshot: Option<&[AdapterSnapshot]>,
-) {
-    if let (Some(store), Some(snapshot)) = (store, snapshot) {
-        if let Err(e) = store.save(snapshot).await {
-            tracing::error!("Failed to save state: {e}");
-        }
+/// The monitor only records addresses once they change, so the current ones
+/// are fetched first.
+///
+/// Excluded from coverage - binds a real socket.
+#[cfg(not(tarpaulin_include))]
+async fn start_health(
+    address: Option<SocketAddr>,
+    fetcher: &impl AddressFetcher,
+    options: &RuntimeOptions,
+    stats: &Arc<RunStats>,
+) -> Result<Option<tokio::task::JoinHandle<()>>, RunError> {
+    let Some(address) = address else {
+        return Ok(None);
+    };
+    let listener = health::bind(address).await?;
+    tracing::info!("Health endpoint listening on http://{address}{HEALTH_PATH}");
+    match fetcher.fetch() {
+        Ok(snapshot) => stats.record_snapshot(&snapshot, options.ip_version),
+        Err(e) => tracing::warn!("Failed to fetch addresses for the health endpoint: {e}"),
     }
+    Ok(Some(tokio::spawn(health::serve(
+        listener,
+        Arc::clone(stats),
+    ))))
 }
 
-/// Runs the hybrid (API + polling) monitoring loop.
+/// Spawns a task that reports when the monitor loop stops making progress,
+/// checking every `options.watchdog_interval` as it is at the time.
+///
+/// The check runs as its own task because a blocked fetch also blocks the
+/// monitor loop's task; the multi-threaded runtime keeps this one scheduled.
 ///
-/// Excluded from coverage - requires Windows API and signal handling.
+/// Excluded from coverage - runs for the lifetime of the process.
 #[cfg(not(tarpaulin_include))]
-#[cfg(windows)]
-async fn run_hybrid_loop<W: WebhookSender>(
-    fetcher: AppFetcher,
-    webhook: W,
-    options: RuntimeOptions,
-    state_store: Option<FileStateStore>,
-) -> Result<(), RunError> {
-    let listener = PlatformListener::new().map_err(RunError::ApiListenerCreation)?;
-
-    let monitor = HybridMonitor::new(fetcher, listener, options.poll_interval)
-        .with_debounce(DebouncePolicy::default());
-
-    let mut stream = monitor.into_stream();
-    let shutdown = shutdown_signal();
-    tokio::pin!(shutdown);
-
-    // Track if we've logged the degradation
-    let mut logged_degradation = false;
-
-    loop {
-        tokio::select! {
-            biased;
-
-            () = &mut shutdown => {
-                tracing::info!("Shutdown signal received, stopping...");
-                return Ok(());
-            }
+fn spawn_watchdog(
+    heartbeat: Heartbeat,
+    options: &RuntimeOptions,
+    action: WatchdogAction,
+) -> Option<tokio::task::JoinHandle<()>> {
+    if action == WatchdogAction::Disabled {
+        return None;
+    }
 
-            changes = stream.next() => {
-                // Check for degradation
-                if !logged_degradation && stream.is_polling_only() {
-                    tracing::warn!("API listener failed, degraded to polling-only mode");
-                    logged_degradation = true;
+    let watchdog = Watchdog::new(heartbeat, options.watchdog_interval.clone());
+    Some(tokio::spawn(async move {
+        let mut stalled = false;
+        loop {
+            tokio::time::sleep(watchdog.poll_interval().get()).await;
+            match watchdog.check() {
+                WatchdogStatus::Healthy => {
+                    if stalled {
+                        tracing::info!("Monitor loop recovered");
+                        stalled = false;
+                    }
                 }
-
-                match changes {
-                    Some(changes) => {
-                        // Filter by IP version before processing
-                        let filtered = filter_by_version(changes, options.ip_version);
-                        if !filtered.is_empty() {
-                            save_state_if_configured(state_store.as_ref(), stream.current_snapshot()).await;
-                            handle_changes(&filtered, &webhook, options.dry_run).await;
-                        }
+                WatchdogStatus::Stalled { silent_for } => {
+                    if !stalled {
+                        tracing::error!(
+                            "Monitor loop stalled: no adapter fetch completed for {}s (threshold {}s)",
+                            silent_for.as_secs(),
+                            watchdog.threshold().as_secs()
+                        );
+                        stalled = true;
                     }
-                    None => {
-                        // Stream ended unexpectedly
-                        return Err(RunError::StreamTerminated);
+                    if action == WatchdogAction::Exit {
+                        tracing::error!("Exiting so the service manager can restart ddns-a");
+                        // Same code as exit_code::runtime_error
+                        std::process::exit(2);
                     }
                 }
             }
         }
-    }
-}
-
-/// Non-Windows stub for hybrid loop.
-///
-/// Excluded from coverage - requires platform APIs and signal handling.
-#[cfg(not(tarpaulin_include))]
-#[cfg(not(windows))]
-async fn run_hybrid_loop<W: WebhookSender>(
-    fetcher: AppFetcher,
-    webhook: W,
-    options: RuntimeOptions,
-    state_store: Option<FileStateStore>,
-) -> Result<(), RunError> {
-    // On non-Windows platforms, fall back to polling-only
-    tracing::warn!("API listener not supported on this platform, using polling-only mode");
-    run_polling_loop(fetcher, webhook, options, state_store).await
+    }))
 }
 
-/// Handles a batch of IP changes.
-async fn handle_changes<W: WebhookSender>(changes: &[IpChange], webhook: &W, dry_run: bool) {
-    // Log the changes
-    for change in changes {
-        let action = if change.is_added() { "+" } else { "-" };
-        tracing::info!(
-            "{action} {address} on {adapter}",
-            address = change.address,
-            adapter = change.adapter,
-        );
-    }
-
-    // Send webhook (unless dry-run)
-    if dry_run {
-        tracing::debug!("Dry-run: skipping webhook for {} change(s)", changes.len());
-        return;
-    }
-
-    match webhook.send(changes).await {
-        Ok(()) => {
-            tracing::debug!("Webhook sent successfully for {} change(s)", changes.len());
-        }
-        Err(e) => {
-            tracing::error!("Webhook failed: {e}");
-        }
+/// Creates the run statistics, sharing `poll_metrics` and the webhook
+/// circuits with them, and appending to the history file if configured.
+fn create_stats(
+    config: &ValidatedConfig,
+    options: &RuntimeOptions,
+    poll_metrics: &PollMetrics,
+) -> RunStats {
+    let mut stats = RunStats::new(SystemTime::now())
+        .with_adapter_priority(config.monitor.adapter_priority.clone())
+        .with_poll_metrics(poll_metrics.clone());
+    if let Some(ref path) = config.outputs.history {
+        tracing::info!("History file enabled: {}", path.display());
+        stats = stats.with_history(HistoryLog::new(path));
     }
-}
-
-/// Returns a future that completes when a shutdown signal is received.
-///
-/// Excluded from coverage - requires OS signal handling.
-#[cfg(not(tarpaulin_include))]
-async fn shutdown_signal() {
-    let ctrl_c = async {
-        signal::ctrl_c()
-            .await
-            .expect("Failed to install Ctrl+C handler");
-    };
-
-    #[cfg(unix)]
-    let terminate = async {
-        signal::unix::signal(signal::unix::SignalKind::terminate())
-            .expect("Failed to install SIGTERM handler")
-            .recv()
-            .await;
-    };
-
-    #[cfg(not(unix))]
-    let terminate = std::future::pending::<()>();
-
-    tokio::select! {
-        () = ctrl_c => {}
-        () = terminate => {}
+    match &options.circuits {
+        Some(circuits) => stats.with_circuit_breakers(circuits.clone()),
+        None => stats,
     }
 }
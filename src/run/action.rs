@@ -0,0 +1,130 @@
+//! The `[action.exec]` command, `[mqtt]` publisher and `[email]` mailer
+//! beside the webhooks.
+//!
+//! [`AppTarget`] lets them join the webhooks in one `FanOut`: they are
+//! paused, guarded by their circuit and reported like any webhook, under
+//! [`EXEC_TARGET`], [`MQTT_TARGET`] and [`EMAIL_TARGET`]. Without a webhook
+//! URL to probe, they are never postponed by the connectivity check.
+
+use ddns_a::config::{EMAIL_TARGET, EXEC_TARGET, MQTT_TARGET, ValidatedConfig};
+use ddns_a::monitor::IpChange;
+use ddns_a::time::ShutdownToken;
+use ddns_a::webhook::{
+    EmailSender, Event, ExecSender, HttpWebhook, MqttSender, WebhookError, WebhookSender,
+};
+use url::Url;
+
+use super::AppClient;
+
+/// A target of the application's sender.
+#[derive(Debug)]
+pub(super) enum AppTarget {
+    /// A webhook, boxed as it is much larger than the command.
+    Webhook(Box<HttpWebhook<AppClient>>),
+    /// The `[action.exec]` command.
+    Exec(ExecSender),
+    /// The `[mqtt]` publisher, boxed like the webhook.
+    Mqtt(Box<MqttSender>),
+    /// The `[email]` mailer, boxed like the webhook.
+    Email(Box<EmailSender>),
+}
+
+impl AppTarget {
+    /// Stops retrying once `shutdown` is triggered.
+    pub(super) fn with_shutdown(self, shutdown: ShutdownToken) -> Self {
+        match self {
+            Self::Webhook(webhook) => Self::Webhook(Box::new(webhook.with_shutdown(shutdown))),
+            Self::Exec(exec) => Self::Exec(exec.with_shutdown(shutdown)),
+            Self::Mqtt(mqtt) => Self::Mqtt(Box::new(mqtt.with_shutdown(shutdown))),
+            Self::Email(email) => Self::Email(Box::new(email.with_shutdown(shutdown))),
+        }
+    }
+
+    /// Returns the webhook URL sent to; none for the other targets.
+    pub(super) fn url(&self) -> Option<Url> {
+        match self {
+            Self::Webhook(webhook) => Some(webhook.url_handle().get()),
+            Self::Exec(_) | Self::Mqtt(_) | Self::Email(_) => None,
+        }
+    }
+}
+
+impl WebhookSender for AppTarget {
+    async fn send(&self, changes: &[IpChange]) -> Result<(), WebhookError> {
+        match self {
+            Self::Webhook(webhook) => webhook.send(changes).await,
+            Self::Exec(exec) => exec.send(changes).await,
+            Self::Mqtt(mqtt) => mqtt.send(changes).await,
+            Self::Email(email) => email.send(changes).await,
+        }
+    }
+
+    async fn send_event(&self, event: &Event<'_>) -> Result<(), WebhookError> {
+        match self {
+            Self::Webhook(webhook) => webhook.send_event(event).await,
+            Self::Exec(exec) => exec.send_event(event).await,
+            Self::Mqtt(mqtt) => mqtt.send_event(event).await,
+            Self::Email(email) => email.send_event(event).await,
+        }
+    }
+}
+
+/// Turns `webhooks` into targets, followed by the `[action.exec]` command,
+/// the `[mqtt]` publisher and the `[email]` mailer if configured.
+///
+/// They are retried with the main webhook's policy. Recording payloads runs,
+/// publishes and emails nothing, as it sends nothing.
+pub(super) fn with_actions(
+    webhooks: Vec<(String, HttpWebhook<AppClient>)>,
+    config: &ValidatedConfig,
+) -> Vec<(String, AppTarget)> {
+    let recording = config.record_payloads.is_some();
+    let exec = config
+        .outputs
+        .exec
+        .clone()
+        .filter(|_| !recording)
+        .map(|action| {
+            tracing::info!("Also running {} on every delivery", action.program());
+            let exec =
+                ExecSender::new(action).with_retry_policy(config.webhook.retry_policy.clone());
+            (EXEC_TARGET.to_string(), AppTarget::Exec(exec))
+        });
+    let mqtt = config
+        .outputs
+        .mqtt
+        .clone()
+        .filter(|_| !recording)
+        .map(|publisher| {
+            tracing::info!(
+                "Also publishing to {} on {}",
+                publisher.topic(),
+                publisher.broker()
+            );
+            let mqtt =
+                MqttSender::new(publisher).with_retry_policy(config.webhook.retry_policy.clone());
+            (MQTT_TARGET.to_string(), AppTarget::Mqtt(Box::new(mqtt)))
+        });
+    let email = config
+        .outputs
+        .email
+        .clone()
+        .filter(|_| !recording)
+        .map(|mailer| {
+            tracing::info!(
+                "Also emailing {} through {}",
+                mailer.to().join(", "),
+                mailer.server()
+            );
+            let email =
+                EmailSender::new(mailer).with_retry_policy(config.webhook.retry_policy.clone());
+            (EMAIL_TARGET.to_string(), AppTarget::Email(Box::new(email)))
+        });
+    webhooks
+        .into_iter()
+        .map(|(name, webhook)| (name, AppTarget::Webhook(Box::new(webhook))))
+        .chain(exec)
+        .chain(mqtt)
+        .chain(email)
+        .collect()
+}
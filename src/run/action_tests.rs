@@ -0,0 +1,97 @@
+//! Tests for the sinks added beside the webhooks.
+
+use super::action::{AppTarget, with_actions};
+use super::senders::create_webhooks;
+use ddns_a::config::{Cli, TomlConfig, ValidatedConfig};
+
+fn config(extra: &[&str]) -> ValidatedConfig {
+    let mut args = vec!["ddns-a", "--url", "https://example.com/webhook"];
+    args.extend(["--ip-version", "ipv4", "--retry-max", "4"]);
+    args.extend(extra);
+    let toml = TomlConfig::parse(
+        r#"
+        [action.exec]
+        command = ["nsupdate", "-k", "key"]
+
+        [mqtt]
+        broker = "mqtt://broker.lan"
+        topic = "home/ddns"
+
+        [email]
+        smtp_server = "smtp://smtp.example.com"
+        from = "ddns@example.com"
+        to = ["admin@example.com"]
+    "#,
+    )
+    .unwrap();
+    ValidatedConfig::from_raw(&Cli::parse_from_iter(args), Some(&toml)).unwrap()
+}
+
+#[test]
+fn command_follows_the_webhooks() {
+    let config = config(&[]);
+
+    let targets = with_actions(create_webhooks(&config, None), &config);
+
+    let names: Vec<_> = targets.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["webhook", "action.exec", "mqtt", "email"]);
+    let AppTarget::Exec(exec) = &targets[1].1 else {
+        panic!("not the command");
+    };
+    assert_eq!(exec.transport().program(), "nsupdate");
+    assert_eq!(exec.retry_policy().max_attempts, 4);
+    assert_eq!(targets[1].1.url(), None);
+}
+
+#[test]
+fn publisher_follows_the_command() {
+    let config = config(&[]);
+
+    let targets = with_actions(create_webhooks(&config, None), &config);
+
+    let AppTarget::Mqtt(mqtt) = &targets[2].1 else {
+        panic!("not the publisher");
+    };
+    assert_eq!(mqtt.transport().topic(), "home/ddns");
+    assert_eq!(mqtt.retry_policy().max_attempts, 4);
+    assert_eq!(targets[2].1.url(), None);
+}
+
+#[test]
+fn mailer_follows_the_publisher() {
+    let config = config(&[]);
+
+    let targets = with_actions(create_webhooks(&config, None), &config);
+
+    let AppTarget::Email(email) = &targets[3].1 else {
+        panic!("not the mailer");
+    };
+    assert_eq!(email.transport().to(), ["admin@example.com"]);
+    assert_eq!(email.retry_policy().max_attempts, 4);
+    assert_eq!(targets[3].1.url(), None);
+}
+
+#[test]
+fn no_webhook_without_url() {
+    let cli = Cli::parse_from_iter(["ddns-a", "--ip-version", "ipv4"]);
+    let toml = TomlConfig::parse(
+        "[email]\nsmtp_server = \"smtp://mx.lan\"\nfrom = \"a@lan\"\nto = [\"b@lan\"]",
+    )
+    .unwrap();
+    let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+    let targets = with_actions(create_webhooks(&config, None), &config);
+
+    let names: Vec<_> = targets.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["email"]);
+}
+
+#[test]
+fn recording_runs_and_publishes_nothing() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(&["--record-payloads", dir.path().to_str().unwrap()]);
+
+    let targets = with_actions(create_webhooks(&config, None), &config);
+
+    assert_eq!(targets.len(), 1);
+}
@@ -0,0 +1,113 @@
+//! The one-shot `check` subcommand.
+//!
+//! Fetches the addresses once through the configured filters and compares
+//! them with the state file, for cron jobs and scripts that do not run the
+//! monitor. The state file is only read: saving it would drop the outbox a
+//! stopped instance left behind.
+
+use std::fmt::Write;
+use std::net::IpAddr;
+use std::time::SystemTime;
+
+use ddns_a::config::ValidatedConfig;
+use ddns_a::monitor::{IpChange, diff, filter_by_version, summarize};
+use ddns_a::network::filter::SharedFilter;
+use ddns_a::network::{AdapterSnapshot, AddressFetcher, IpVersion};
+use ddns_a::state::{FileStateStore, LoadResult, StateStore};
+
+use super::{RunError, source_fetcher};
+
+/// Fetches the addresses once, prints the adapters passing the filters and
+/// the changes since the state file; returns whether any were found.
+///
+/// Must be called within a multi-threaded Tokio runtime when the public
+/// address is fetched.
+///
+/// # Errors
+///
+/// Returns [`RunError::InitialFetch`] if the addresses cannot be fetched.
+///
+/// Excluded from coverage - requires platform APIs.
+#[cfg(not(tarpaulin_include))]
+pub fn check(mut config: ValidatedConfig) -> Result<bool, RunError> {
+    let filter = SharedFilter::new(std::mem::take(&mut config.monitor.filter));
+    let current = source_fetcher(&config, filter)
+        .fetch()
+        .map_err(RunError::InitialFetch)?;
+    print!("{}", format_adapters(&current, config.ip_version));
+
+    let Some(path) = config.state_file else {
+        println!("\nNo --state-file configured, nothing to compare");
+        return Ok(false);
+    };
+    let saved = FileStateStore::new(&path).load();
+    match compare(saved, &current, config.ip_version, SystemTime::now()) {
+        Err(reason) => {
+            println!("\nNothing to compare in {}: {reason}", path.display());
+            Ok(false)
+        }
+        Ok(changes) if changes.is_empty() => {
+            println!("\nNo changes since {}", path.display());
+            Ok(false)
+        }
+        Ok(changes) => {
+            println!("\n{} change(s) since {}:", changes.len(), path.display());
+            for line in summarize(&changes) {
+                println!("{line}");
+            }
+            Ok(true)
+        }
+    }
+}
+
+/// Returns the changes from the `saved` snapshots to `current` in the
+/// monitored family, or why there is nothing to compare with.
+pub(super) fn compare(
+    saved: LoadResult,
+    current: &[AdapterSnapshot],
+    ip_version: IpVersion,
+    timestamp: SystemTime,
+) -> Result<Vec<IpChange>, String> {
+    match saved {
+        LoadResult::Loaded(saved) => Ok(filter_by_version(
+            diff(&saved, current, timestamp),
+            ip_version,
+        )),
+        LoadResult::NotFound => Err("no state saved yet".to_string()),
+        LoadResult::Corrupted { reason } => Err(format!("state file is corrupted ({reason})")),
+    }
+}
+
+/// Formats `adapters` and their addresses in the monitored family as an
+/// aligned table; `-` marks an adapter without any.
+pub(super) fn format_adapters(adapters: &[AdapterSnapshot], ip_version: IpVersion) -> String {
+    const ADAPTER_HEADER: &str = "ADAPTER";
+
+    let width = adapters
+        .iter()
+        .map(|adapter| adapter.name.len())
+        .max()
+        .unwrap_or(0)
+        .max(ADAPTER_HEADER.len());
+    let mut table = format!("{ADAPTER_HEADER:<width$}  ADDRESSES\n");
+    for adapter in adapters {
+        let v4 = adapter
+            .ipv4_addresses
+            .iter()
+            .filter(|_| ip_version.includes_v4())
+            .map(|&a| IpAddr::V4(a));
+        let v6 = adapter
+            .ipv6_addresses
+            .iter()
+            .filter(|_| ip_version.includes_v6())
+            .map(|&a| IpAddr::V6(a));
+        let addresses: Vec<_> = v4.chain(v6).map(|a| a.to_string()).collect();
+        let addresses = if addresses.is_empty() {
+            "-".to_string()
+        } else {
+            addresses.join(", ")
+        };
+        let _ = writeln!(table, "{:<width$}  {addresses}", adapter.name);
+    }
+    table
+}
@@ -0,0 +1,76 @@
+//! Tests for the one-shot `check`.
+
+use super::check::{compare, format_adapters};
+use ddns_a::network::{AdapterKind, AdapterSnapshot, IpVersion};
+use ddns_a::state::LoadResult;
+use std::time::SystemTime;
+
+fn eth0(ipv4: &str) -> AdapterSnapshot {
+    AdapterSnapshot::new(
+        "eth0",
+        AdapterKind::Ethernet,
+        vec![ipv4.parse().unwrap()],
+        vec!["2001:db8::5".parse().unwrap()],
+    )
+}
+
+#[test]
+fn table_lists_addresses_in_monitored_family() {
+    let wireless = AdapterSnapshot::new("wlan-home", AdapterKind::Wireless, vec![], vec![]);
+
+    let table = format_adapters(&[eth0("192.0.2.5"), wireless], IpVersion::V4);
+
+    assert_eq!(
+        table,
+        "ADAPTER    ADDRESSES\neth0       192.0.2.5\nwlan-home  -\n"
+    );
+}
+
+#[test]
+fn changes_since_saved_state() {
+    let saved = LoadResult::Loaded(vec![eth0("192.0.2.5")]);
+
+    let changes = compare(
+        saved,
+        &[eth0("192.0.2.6")],
+        IpVersion::V4,
+        SystemTime::UNIX_EPOCH,
+    )
+    .unwrap();
+
+    assert_eq!(changes.len(), 2);
+    assert!(changes.iter().all(|change| change.address.is_ipv4()));
+}
+
+#[test]
+fn unchanged_state_has_no_changes() {
+    let saved = LoadResult::Loaded(vec![eth0("192.0.2.5")]);
+
+    let changes = compare(
+        saved,
+        &[eth0("192.0.2.5")],
+        IpVersion::Both,
+        SystemTime::UNIX_EPOCH,
+    );
+
+    assert_eq!(changes, Ok(vec![]));
+}
+
+#[test]
+fn missing_or_corrupted_state_is_nothing_to_compare() {
+    let current = [eth0("192.0.2.5")];
+    let corrupted = LoadResult::Corrupted {
+        reason: "bad json".to_string(),
+    };
+
+    let missing = compare(
+        LoadResult::NotFound,
+        &current,
+        IpVersion::Both,
+        SystemTime::UNIX_EPOCH,
+    );
+    let corrupted = compare(corrupted, &current, IpVersion::Both, SystemTime::UNIX_EPOCH);
+
+    assert!(missing.is_err());
+    assert!(corrupted.unwrap_err().contains("bad json"));
+}
@@ -0,0 +1,152 @@
+//! Events reported beside address changes: DNS setting changes, drift
+//! from the expected addresses, hostname changes, IPv6 address storms,
+//! adapters going up or down, webhook certificate changes and tray menu
+//! commands.
+//!
+//! The fetcher decorators, the certificate watch and the tray icon send them
+//! on channels the monitor loops select on next to the change stream.
+
+use ddns_a::monitor::{DnsChange, DriftChange, HostnameChange, LinkChange, LinkState, StormChange};
+use ddns_a::network::AdapterSnapshot;
+use ddns_a::report::RunStats;
+use ddns_a::state::StateStore;
+use ddns_a::tray::TrayCommand;
+use ddns_a::webhook::{CertificateChange, Event, WebhookSender};
+use std::fmt::Display;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use super::RuntimeOptions;
+use super::tray::handle_tray_command;
+
+/// Receivers of the events reported beside address changes.
+#[derive(Debug)]
+pub(super) struct Events {
+    pub(super) dns: UnboundedReceiver<Vec<DnsChange>>,
+    pub(super) drift: UnboundedReceiver<Vec<DriftChange>>,
+    pub(super) hostname: UnboundedReceiver<Vec<HostnameChange>>,
+    pub(super) storm: UnboundedReceiver<Vec<StormChange>>,
+    pub(super) link: UnboundedReceiver<Vec<LinkChange>>,
+    /// Closed unless `[webhook.certificate]` is configured
+    pub(super) certificate: UnboundedReceiver<Vec<CertificateChange>>,
+    /// Closed unless the tray icon is shown
+    pub(super) tray: UnboundedReceiver<TrayCommand>,
+}
+
+/// An event received by [`Events::recv`].
+#[derive(Debug)]
+pub(super) enum Incoming {
+    Dns(Vec<DnsChange>),
+    Drift(Vec<DriftChange>),
+    Hostname(Vec<HostnameChange>),
+    Storm(Vec<StormChange>),
+    Link(Vec<LinkChange>),
+    Certificate(Vec<CertificateChange>),
+    Tray(TrayCommand),
+}
+
+impl Events {
+    /// Waits for the next event; `None` once every channel is closed.
+    pub(super) async fn recv(&mut self) -> Option<Incoming> {
+        tokio::select! {
+            Some(changes) = self.dns.recv() => Some(Incoming::Dns(changes)),
+            Some(changes) = self.drift.recv() => Some(Incoming::Drift(changes)),
+            Some(changes) = self.hostname.recv() => Some(Incoming::Hostname(changes)),
+            Some(changes) = self.storm.recv() => Some(Incoming::Storm(changes)),
+            Some(changes) = self.link.recv() => Some(Incoming::Link(changes)),
+            Some(changes) = self.certificate.recv() => Some(Incoming::Certificate(changes)),
+            Some(command) = self.tray.recv() => Some(Incoming::Tray(command)),
+            else => None,
+        }
+    }
+}
+
+/// Handles an event; tray commands may notify the addresses of `snapshot`,
+/// certificate fingerprints are persisted in `store`.
+pub(super) async fn handle_event<W: WebhookSender, S: StateStore>(
+    event: Incoming,
+    snapshot: Option<&[AdapterSnapshot]>,
+    webhook: &W,
+    options: &RuntimeOptions,
+    store: Option<&S>,
+    stats: &RunStats,
+) {
+    let dry_run = options.dry_run;
+    let delivery = match &event {
+        Incoming::Dns(changes) => Event::Dns(changes),
+        Incoming::Drift(changes) => Event::Drift(changes),
+        Incoming::Hostname(changes) => Event::Hostname(changes),
+        Incoming::Storm(changes) => Event::Storm(changes),
+        Incoming::Link(changes) => Event::Link(changes),
+        Incoming::Certificate(changes) => return handle_certificate(changes, store).await,
+        Incoming::Tray(command) => {
+            return handle_tray_command(*command, snapshot, webhook, options, stats).await;
+        }
+    };
+    notify(&delivery, webhook, dry_run, stats).await;
+}
+
+/// Logs and sends the changes of an event, recording the delivery in
+/// `stats`.
+///
+/// Drift and storms that start and adapters going down are logged as
+/// warnings, so they stand out from the address changes of a dynamic setup;
+/// everything else as info.
+async fn notify<W: WebhookSender>(event: &Event<'_>, webhook: &W, dry_run: bool, stats: &RunStats) {
+    match *event {
+        Event::Dns(changes) => log_each(changes, |_| false),
+        Event::Drift(changes) => log_each(changes, |change| !change.resolved),
+        Event::Hostname(changes) => log_each(changes, |_| false),
+        Event::Storm(changes) => log_each(changes, |change| !change.resolved),
+        Event::Link(changes) => log_each(changes, |change| change.state == LinkState::Down),
+    }
+
+    if dry_run {
+        tracing::debug!(
+            "Dry-run: skipping webhook for {} {} change(s)",
+            event.len(),
+            event.name()
+        );
+        return;
+    }
+
+    let result = webhook.send_event(event).await;
+    stats.record_delivery(&result);
+    if let Err(e) = result {
+        tracing::error!("Webhook for {} change(s) failed: {e}", event.name());
+    }
+}
+
+/// Logs each of `changes`, as a warning if `warn` says so.
+fn log_each<T: Display>(changes: &[T], warn: impl Fn(&T) -> bool) {
+    for change in changes {
+        if warn(change) {
+            tracing::warn!("{change}");
+        } else {
+            tracing::info!("{change}");
+        }
+    }
+}
+
+/// Handles certificate fingerprints not recorded before, persisting them in
+/// `store`.
+///
+/// A changed certificate is logged as a warning, since it may mean the
+/// connection is intercepted; a host's first certificate as info.
+pub(super) async fn handle_certificate<S: StateStore>(
+    changes: &[CertificateChange],
+    store: Option<&S>,
+) {
+    for change in changes {
+        if change.old.is_some() {
+            tracing::warn!("{change}");
+        } else {
+            tracing::info!("{change}");
+        }
+
+        if let Some(store) = store {
+            if let Err(e) = store.record_certificate(&change.host, change.new).await {
+                tracing::error!("Failed to save certificate fingerprint: {e}");
+            }
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Tests for delivering the events beside address changes.
+
+use super::RuntimeOptions;
+use super::events::{Incoming, handle_certificate, handle_event};
+use ddns_a::config::{Cli, ValidatedConfig};
+use ddns_a::monitor::{
+    AddressDrift, AddressStorm, DnsChange, DriftChange, DriftKind, HostnameChange, IpChange,
+    LinkChange, LinkState, StormChange,
+};
+use ddns_a::network::{DnsSettings, OperStatus};
+use ddns_a::report::RunStats;
+use ddns_a::state::{FileStateStore, StateStore};
+use ddns_a::webhook::{CertificateChange, Event, Fingerprint, WebhookError, WebhookSender};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Sender recording the name of every event; address deliveries are
+/// unexpected.
+#[derive(Default)]
+struct EventRecorder(Mutex<Vec<&'static str>>);
+
+impl WebhookSender for EventRecorder {
+    async fn send(&self, _changes: &[IpChange]) -> Result<(), WebhookError> {
+        unreachable!("only events are handled")
+    }
+
+    async fn send_event(&self, event: &Event<'_>) -> Result<(), WebhookError> {
+        self.0.lock().unwrap().push(event.name());
+        Ok(())
+    }
+}
+
+fn options(dry_run: bool) -> RuntimeOptions {
+    let mut args = vec![
+        "ddns-a",
+        "--url",
+        "https://example.com/hook",
+        "--ip-version",
+        "ipv4",
+    ];
+    if dry_run {
+        args.push("--dry-run");
+    }
+    let cli = Cli::parse_from_iter(args);
+    RuntimeOptions::from(&ValidatedConfig::from_raw(&cli, None).unwrap())
+}
+
+/// One event of each kind, with the name it is sent under.
+fn events() -> Vec<(Incoming, &'static str)> {
+    let timestamp = SystemTime::UNIX_EPOCH;
+    vec![
+        (
+            Incoming::Dns(vec![DnsChange {
+                adapter: "vpn".to_string(),
+                old: DnsSettings::default(),
+                new: DnsSettings {
+                    suffix: "corp.example.com".to_string(),
+                    servers: vec!["10.0.0.53".parse().unwrap()],
+                },
+                timestamp,
+            }]),
+            "dns",
+        ),
+        (
+            Incoming::Drift(vec![DriftChange {
+                drift: AddressDrift {
+                    kind: DriftKind::Missing,
+                    address: "203.0.113.10".parse().unwrap(),
+                    adapter: None,
+                },
+                resolved: false,
+                timestamp,
+            }]),
+            "drift",
+        ),
+        (
+            Incoming::Hostname(vec![HostnameChange {
+                old: "desk-01".to_string(),
+                new: "desk-02".to_string(),
+                timestamp,
+            }]),
+            "hostname",
+        ),
+        (
+            Incoming::Storm(vec![StormChange {
+                storm: AddressStorm {
+                    adapter: "eth0".to_string(),
+                    count: 40,
+                    limit: 16,
+                },
+                resolved: false,
+                prefix_only: false,
+                timestamp,
+            }]),
+            "storm",
+        ),
+        (
+            Incoming::Link(vec![LinkChange {
+                adapter: "eth0".to_string(),
+                state: LinkState::Down,
+                status: OperStatus::LowerLayerDown,
+                timestamp,
+            }]),
+            "link",
+        ),
+    ]
+}
+
+#[tokio::test]
+async fn sends_each_event_unless_dry_run() {
+    for dry_run in [false, true] {
+        let options = options(dry_run);
+        for (event, name) in events() {
+            let webhook = EventRecorder::default();
+            let stats = RunStats::new(SystemTime::UNIX_EPOCH);
+
+            handle_event(
+                event,
+                None,
+                &webhook,
+                &options,
+                None::<&FileStateStore>,
+                &stats,
+            )
+            .await;
+
+            let sent = webhook.0.lock().unwrap().clone();
+            let summary = stats.summary(SystemTime::UNIX_EPOCH);
+            if dry_run {
+                assert!(sent.is_empty(), "{name}: {sent:?}");
+                assert_eq!(summary.notifications_sent, 0, "{name}");
+            } else {
+                assert_eq!(sent, [name]);
+                assert_eq!(summary.notifications_sent, 1, "{name}");
+            }
+            assert_eq!(summary.changes_detected, 0, "{name}");
+        }
+    }
+}
+
+fn certificate(old: Option<&[u8]>, new: &[u8]) -> CertificateChange {
+    CertificateChange {
+        host: "example.com".to_string(),
+        old: old.map(Fingerprint::of),
+        new: Fingerprint::of(new),
+        timestamp: SystemTime::UNIX_EPOCH,
+    }
+}
+
+#[tokio::test]
+async fn certificate_fingerprints_persist_in_state_file() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let store = FileStateStore::new(dir.path().join("state.json"));
+    store.save(&[]).await.unwrap();
+
+    handle_certificate(&[certificate(None, b"a")], Some(&store)).await;
+    handle_certificate(&[certificate(Some(b"a"), b"b")], Some(&store)).await;
+
+    assert_eq!(
+        store.certificates().get("example.com"),
+        Some(&Fingerprint::of(b"b"))
+    );
+}
+
+#[tokio::test]
+async fn certificate_without_store_only_logs() {
+    handle_certificate::<FileStateStore>(&[certificate(Some(b"a"), b"b")], None).await;
+}
@@ -0,0 +1,23 @@
+//! Checking recorded golden files against the current configuration.
+
+use ddns_a::config::ValidatedConfig;
+use ddns_a::webhook::GoldenCase;
+
+use super::senders::create_webhook;
+
+/// Renders every case with the configured webhook and compares the payload
+/// with its golden.
+///
+/// Returns one result per case, in order; an `Err` holds the diff or why
+/// the payload could not be rendered. Nothing is sent.
+pub fn verify_goldens(config: &ValidatedConfig, cases: &[GoldenCase]) -> Vec<Result<(), String>> {
+    let webhook = create_webhook(config, None);
+    cases
+        .iter()
+        .map(|case| {
+            let webhook = webhook.as_ref().ok_or("no webhook URL is set")?;
+            let rendered = webhook.render(&case.changes).map_err(|e| e.to_string())?;
+            case.compare(&rendered)
+        })
+        .collect()
+}
@@ -0,0 +1,138 @@
+//! The hybrid monitoring loop: platform change notifications plus polling.
+//!
+//! Only built on platforms with a listener and with the `hybrid` feature;
+//! elsewhere the polling loop stands in for it. When the listener fails,
+//! the monitor degrades to polling at the shortest poll interval.
+
+use std::time::Duration;
+
+use tokio_stream::StreamExt;
+
+use ddns_a::monitor::platform::PlatformListener;
+use ddns_a::monitor::{HybridMonitor, NotifyThrottle, filter_by_version};
+use ddns_a::report::RunStats;
+use ddns_a::state::FileStateStore;
+
+use super::events::{Events, handle_event};
+use super::outbox::deliver;
+use super::polling::{
+    deliver_observed, log_adaptive, longest_poll_interval, save_state_if_configured,
+};
+use super::queue::{QueueTimer, retry_queued};
+use super::reload::Reloader;
+use super::throttle;
+use super::{AppFetcher, AppSender, RunError, RuntimeOptions};
+
+/// Runs the hybrid (API + polling) monitoring loop.
+///
+/// Excluded from coverage - requires platform APIs and signal handling.
+#[cfg(not(tarpaulin_include))]
+pub(super) async fn run_hybrid_loop(
+    fetcher: AppFetcher,
+    mut events: Events,
+    mut webhook: AppSender,
+    mut reloader: Reloader,
+    options: RuntimeOptions,
+    state_store: Option<FileStateStore>,
+    stats: &RunStats,
+) -> Result<(), RunError> {
+    let listener = PlatformListener::new().map_err(RunError::ApiListenerCreation)?;
+
+    let interval = fallback_interval(options.poll_interval, options.poll_interval_v6);
+    let mut stream = hybrid_monitor(fetcher, listener, interval, &options).into_stream();
+
+    let mut queue_timer = QueueTimer::new(options.retry_queue.is_some(), interval);
+    let mut throttle = options.min_notify_interval.map(NotifyThrottle::new);
+
+    // Track if we've logged the degradation
+    let mut logged_degradation = false;
+
+    loop {
+        tokio::select! {
+            biased;
+
+            () = options.shutdown.triggered() => {
+                tracing::info!("Shutdown signal received, stopping...");
+                throttle::keep_held(throttle.as_mut(), state_store.as_ref()).await;
+                return Ok(());
+            }
+
+            Some(event) = events.recv() => {
+                handle_event(event, stream.current_snapshot(), &webhook, &options, state_store.as_ref(), stats).await;
+            }
+
+            () = queue_timer.tick() => {
+                retry_queued(&webhook, &options, state_store.as_ref(), stats).await;
+            }
+
+            () = throttle::until(throttle.as_ref().and_then(NotifyThrottle::deadline)) => {
+                if let Some(held) = throttle::take_due(throttle.as_mut()) {
+                    deliver(&held, &webhook, &options, state_store.as_ref(), stats).await;
+                }
+            }
+
+            () = reloader.requested() => {
+                if let Some(reloaded) = reloader.reload(&options).await {
+                    stream.set_adaptive(reloaded.adaptive);
+                    stream.set_poll_interval(fallback_interval(reloaded.poll_interval, reloaded.poll_interval_v6));
+                    options.watchdog_interval.set(longest_poll_interval(reloaded.poll_interval, reloaded.adaptive));
+                    webhook = reloaded.webhook;
+                }
+            }
+
+            changes = stream.next() => {
+                // Check for degradation
+                if !logged_degradation && stream.is_polling_only() {
+                    tracing::warn!("API listener failed, degraded to polling-only mode");
+                    logged_degradation = true;
+                }
+
+                match changes {
+                    Some(changes) => {
+                        // Filter by IP version before processing
+                        let filtered = filter_by_version(changes, options.ip_version);
+                        if !filtered.is_empty() {
+                            save_state_if_configured(state_store.as_ref(), stream.current_snapshot()).await;
+                            if let Some(snapshot) = stream.current_snapshot() {
+                                stats.record_snapshot(snapshot, options.ip_version);
+                            }
+                            deliver_observed(filtered, throttle.as_mut(), &webhook, &options, state_store.as_ref(), stats).await;
+                        }
+                    }
+                    None => {
+                        // Stream ended unexpectedly
+                        return Err(RunError::StreamTerminated);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the poll interval of the hybrid loop: API events report changes
+/// at once, so the fallback poll uses the shorter interval.
+fn fallback_interval(poll_interval: Duration, poll_interval_v6: Option<Duration>) -> Duration {
+    poll_interval_v6.map_or(poll_interval, |v6| v6.min(poll_interval))
+}
+
+/// Configures the hybrid monitor of [`run_hybrid_loop`] from `options`.
+fn hybrid_monitor(
+    fetcher: AppFetcher,
+    listener: PlatformListener,
+    interval: Duration,
+    options: &RuntimeOptions,
+) -> HybridMonitor<AppFetcher, PlatformListener> {
+    let mut monitor =
+        HybridMonitor::new(fetcher, listener, interval).with_fetch_timeout(options.fetch_timeout);
+    if let Some(ref policy) = options.debounce {
+        monitor = monitor.with_debounce(policy.clone());
+    }
+    if let Some(quiet_period) = options.event_quiet {
+        monitor = monitor.with_quiet_period(quiet_period);
+    }
+    if let Some(policy) = options.adaptive {
+        log_adaptive(&policy);
+        monitor = monitor.with_adaptive(policy);
+    }
+    monitor
+}
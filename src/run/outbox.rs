@@ -0,0 +1,149 @@
+//! Delivery outcomes and the outbox of batches shutdown interrupted.
+//!
+//! Snapshots are saved before delivery, so a batch whose delivery shutdown
+//! cut short would not be detected again. Such batches are kept in the
+//! state file instead and delivered first on the next start. Failed batches
+//! go to the retry [`queue`](super::queue), if enabled.
+//!
+//! The state file also records the addresses the webhooks acknowledged;
+//! changes that would leave them unchanged are not delivered again.
+
+use ddns_a::monitor::{IpChange, NotifyOn};
+use ddns_a::report::RunStats;
+use ddns_a::state::{Acknowledged, StateStore};
+use ddns_a::webhook::WebhookSender;
+
+use super::RuntimeOptions;
+use super::polling::{handle_changes, notified_changes, record_changes};
+use super::queue::{enqueue, retry_queued};
+
+/// Outcome of handling a change batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Delivery {
+    /// The webhook accepted the batch.
+    Delivered,
+    /// The batch was not sent (dry run) or was postponed.
+    NotDelivered,
+    /// The webhook failed, retries included.
+    Failed,
+    /// Shutdown interrupted the delivery.
+    Interrupted,
+}
+
+/// Handles `changes`, keeping them in the outbox if shutdown interrupts
+/// their delivery and in the retry queue if it fails, then refreshes the
+/// tray icon.
+///
+/// Queued changes are retried first; while some remain, `changes` join
+/// them instead of overtaking them. Changes the webhooks already
+/// acknowledged are skipped.
+pub(super) async fn deliver<W: WebhookSender>(
+    changes: &[IpChange],
+    webhook: &W,
+    options: &RuntimeOptions,
+    store: Option<&impl StateStore>,
+    stats: &RunStats,
+) {
+    let changes = unacknowledged(store, changes.to_vec());
+    if changes.is_empty() {
+        return;
+    }
+    if retry_queued(webhook, options, store, stats).await {
+        match handle_changes(&changes, webhook, options.dry_run, stats).await {
+            Delivery::Delivered => acknowledge(store, &changes).await,
+            Delivery::Interrupted => keep_undelivered(store, &changes).await,
+            Delivery::Failed => enqueue(options, store, &changes).await,
+            Delivery::NotDelivered => {}
+        }
+    } else {
+        record_changes(&changes, stats);
+        enqueue(options, store, &changes).await;
+    }
+    options.tray.show(stats, &options.pause);
+}
+
+/// Returns the `changes` of the kinds `notify_on` delivers, or `None` if
+/// none is left, after recording the others as acknowledged.
+///
+/// The webhooks are never told of suppressed changes, so they must not
+/// count as pending: an address whose suppressed removal was left
+/// acknowledged would be skipped as already delivered when it comes back.
+pub(super) async fn notified_batch(
+    store: Option<&impl StateStore>,
+    changes: Vec<IpChange>,
+    notify_on: NotifyOn,
+) -> Option<Vec<IpChange>> {
+    let suppressed = suppressed_changes(&changes, notify_on);
+    if !suppressed.is_empty() {
+        acknowledge(store, &suppressed).await;
+    }
+    notified_changes(changes, notify_on)
+}
+
+/// Returns the `changes` of the kinds `notify_on` suppresses.
+pub(super) fn suppressed_changes(changes: &[IpChange], notify_on: NotifyOn) -> Vec<IpChange> {
+    changes
+        .iter()
+        .filter(|change| !notify_on.includes(change.kind))
+        .cloned()
+        .collect()
+}
+
+/// Returns the `changes` the webhooks have not acknowledged, logging how
+/// many were skipped; all of them without acknowledged addresses.
+pub(super) fn unacknowledged(
+    store: Option<&impl StateStore>,
+    changes: Vec<IpChange>,
+) -> Vec<IpChange> {
+    let Some(acknowledged) = store.and_then(StateStore::acknowledged) else {
+        return changes;
+    };
+    let count = changes.len();
+    let pending = acknowledged.pending(changes);
+    if pending.len() < count {
+        tracing::info!(
+            "Skipping {} change(s): the webhooks already acknowledged them",
+            count - pending.len()
+        );
+    }
+    pending
+}
+
+/// Records that `changes` were delivered in the acknowledged addresses of
+/// `store`, if it has any.
+pub(super) async fn acknowledge(store: Option<&impl StateStore>, changes: &[IpChange]) {
+    let Some(store) = store else {
+        return;
+    };
+    let Some(mut acknowledged) = store.acknowledged() else {
+        return;
+    };
+    acknowledged.record(changes);
+    set_acknowledged(store, &acknowledged).await;
+}
+
+/// Saves `acknowledged` in `store`, logging a failure.
+pub(super) async fn set_acknowledged(store: &impl StateStore, acknowledged: &Acknowledged) {
+    if let Err(e) = store.set_acknowledged(acknowledged).await {
+        tracing::error!("Failed to record the acknowledged addresses: {e}");
+    }
+}
+
+/// Adds `changes` to the outbox of `store` for the next run to deliver.
+pub(super) async fn keep_undelivered(store: Option<&impl StateStore>, changes: &[IpChange]) {
+    let Some(store) = store else {
+        tracing::warn!(
+            "Dropping {} undelivered change(s): no state file to keep them in",
+            changes.len()
+        );
+        return;
+    };
+
+    match store.record_undelivered(changes).await {
+        Ok(()) => tracing::info!(
+            "Kept {} undelivered change(s) for delivery on the next start",
+            changes.len()
+        ),
+        Err(e) => tracing::error!("Failed to keep undelivered changes: {e}"),
+    }
+}
@@ -0,0 +1,272 @@
+//! The polling-only monitoring loop, and the delivery of the changes both
+//! monitoring loops observe.
+//!
+//! Observed changes are filtered by `webhook.notify_on`, throttled by
+//! `webhook.min_notify_interval`, sent behind the `[retry.queue]`, recorded
+//! in the run stats and history, and acknowledged in the state file.
+
+use std::time::Duration;
+
+use tokio_stream::StreamExt;
+
+use ddns_a::monitor::{
+    AdaptivePolicy, IpChange, NotifyOn, NotifyThrottle, PollingMonitor, filter_by_kind,
+    filter_by_version, summarize,
+};
+use ddns_a::network::AdapterSnapshot;
+use ddns_a::report::RunStats;
+use ddns_a::state::{FileStateStore, StateStore};
+use ddns_a::webhook::WebhookSender;
+
+use super::events::{Events, handle_event};
+use super::outbox::{Delivery, deliver, notified_batch};
+use super::queue::{QueueTimer, retry_queued};
+use super::reload::Reloader;
+use super::throttle;
+use super::{AppFetcher, AppSender, RunError, RuntimeOptions};
+
+/// Runs the polling-only monitoring loop.
+///
+/// Excluded from coverage - requires platform APIs and signal handling.
+#[cfg(not(tarpaulin_include))]
+pub(super) async fn run_polling_loop(
+    fetcher: AppFetcher,
+    mut events: Events,
+    mut webhook: AppSender,
+    mut reloader: Reloader,
+    options: RuntimeOptions,
+    state_store: Option<FileStateStore>,
+    stats: &RunStats,
+) -> Result<(), RunError> {
+    let mut monitor = PollingMonitor::new(fetcher, options.poll_interval)
+        .with_fetch_timeout(options.fetch_timeout);
+    if let Some(ref policy) = options.debounce {
+        monitor = monitor.with_debounce(policy.clone());
+    }
+    if let Some(interval) = options.poll_interval_v6 {
+        tracing::info!("IPv6 addresses polled every {}s", interval.as_secs());
+        monitor = monitor.with_ipv6_interval(interval);
+    }
+    if let Some(policy) = options.adaptive {
+        log_adaptive(&policy);
+        monitor = monitor.with_adaptive(policy);
+    }
+
+    let mut stream = monitor.into_stream();
+    let mut queue_timer = QueueTimer::new(options.retry_queue.is_some(), options.poll_interval);
+    let mut throttle = options.min_notify_interval.map(NotifyThrottle::new);
+
+    loop {
+        tokio::select! {
+            biased;
+
+            () = options.shutdown.triggered() => {
+                tracing::info!("Shutdown signal received, stopping...");
+                throttle::keep_held(throttle.as_mut(), state_store.as_ref()).await;
+                return Ok(());
+            }
+
+            Some(event) = events.recv() => {
+                handle_event(event, stream.current_snapshot(), &webhook, &options, state_store.as_ref(), stats).await;
+            }
+
+            () = queue_timer.tick() => {
+                retry_queued(&webhook, &options, state_store.as_ref(), stats).await;
+            }
+
+            () = throttle::until(throttle.as_ref().and_then(NotifyThrottle::deadline)) => {
+                if let Some(held) = throttle::take_due(throttle.as_mut()) {
+                    deliver(&held, &webhook, &options, state_store.as_ref(), stats).await;
+                }
+            }
+
+            () = reloader.requested() => {
+                if let Some(reloaded) = reloader.reload(&options).await {
+                    stream.set_adaptive(reloaded.adaptive);
+                    stream.set_poll_interval(reloaded.poll_interval);
+                    options.watchdog_interval.set(longest_poll_interval(reloaded.poll_interval, reloaded.adaptive));
+                    stream.set_ipv6_interval(reloaded.poll_interval_v6);
+                    webhook = reloaded.webhook;
+                }
+            }
+
+            changes = stream.next() => {
+                match changes {
+                    Some(changes) => {
+                        // Filter by IP version before processing
+                        let filtered = filter_by_version(changes, options.ip_version);
+                        if !filtered.is_empty() {
+                            save_state_if_configured(state_store.as_ref(), stream.current_snapshot()).await;
+                            if let Some(snapshot) = stream.current_snapshot() {
+                                stats.record_snapshot(snapshot, options.ip_version);
+                            }
+                            deliver_observed(filtered, throttle.as_mut(), &webhook, &options, state_store.as_ref(), stats).await;
+                        }
+                    }
+                    None => {
+                        // Stream ended unexpectedly
+                        return Err(RunError::StreamTerminated);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Publishes an observed batch on the change bus, then delivers the changes
+/// `notify_on` covers, unless the throttle holds them.
+pub(super) async fn deliver_observed<W: WebhookSender>(
+    changes: Vec<IpChange>,
+    throttle: Option<&mut NotifyThrottle>,
+    webhook: &W,
+    options: &RuntimeOptions,
+    store: Option<&FileStateStore>,
+    stats: &RunStats,
+) {
+    options.changes.publish(&changes);
+    let notified = notified_batch(store, changes, options.notify_on).await;
+    if let Some(batch) = notified.and_then(|changes| throttle::admit(throttle, changes)) {
+        deliver(&batch, webhook, options, store, stats).await;
+    }
+}
+
+/// Returns the `changes` of the kinds `notify_on` delivers, logging how
+/// many were suppressed; `None` if none is left.
+pub(super) fn notified_changes(
+    changes: Vec<IpChange>,
+    notify_on: NotifyOn,
+) -> Option<Vec<IpChange>> {
+    let count = changes.len();
+    let notified = filter_by_kind(changes, notify_on);
+    if notified.len() < count {
+        tracing::info!(
+            "Suppressing {} change(s): not in webhook.notify_on",
+            count - notified.len()
+        );
+    }
+    (!notified.is_empty()).then_some(notified)
+}
+
+/// Saves state to the store if configured.
+///
+/// Uses optimistic save strategy: state is saved before webhook delivery.
+/// This ensures the state reflects actual current IPs regardless of webhook success.
+/// On restart, previously notified changes won't re-trigger (by design).
+pub(super) async fn save_state_if_configured(
+    store: Option<&FileStateStore>,
+    snapshot: Option<&[AdapterSnapshot]>,
+) {
+    if let (Some(store), Some(snapshot)) = (store, snapshot) {
+        if let Err(e) = store.save(snapshot).await {
+            tracing::error!("Failed to save state: {e}");
+        }
+    }
+}
+
+/// Returns the longest interval between two polls: the `adaptive`
+/// maximum, or `poll_interval`.
+///
+/// Stall detection must wait this long, as quiet stretches are expected.
+pub(super) fn longest_poll_interval(
+    poll_interval: Duration,
+    adaptive: Option<AdaptivePolicy>,
+) -> Duration {
+    adaptive.map_or(poll_interval, |policy| policy.max())
+}
+
+/// Logs the adaptive polling range at startup.
+pub(super) fn log_adaptive(policy: &AdaptivePolicy) {
+    tracing::info!(
+        "Adaptive polling between {}s and {}s",
+        policy.min().as_secs(),
+        policy.max().as_secs()
+    );
+}
+
+/// Handles a batch of IP changes, recording the outcome in `stats`.
+pub(super) async fn handle_changes<W: WebhookSender>(
+    changes: &[IpChange],
+    webhook: &W,
+    dry_run: bool,
+    stats: &RunStats,
+) -> Delivery {
+    record_changes(changes, stats);
+
+    // Send webhook (unless dry-run)
+    if dry_run {
+        tracing::debug!("Dry-run: skipping webhook for {} change(s)", changes.len());
+        return Delivery::NotDelivered;
+    }
+
+    let result = webhook.send(changes).await;
+    stats.record_changes_delivery(changes, &result);
+    match result {
+        Ok(()) => {
+            tracing::debug!("Webhook sent successfully for {} change(s)", changes.len());
+            Delivery::Delivered
+        }
+        Err(e) if e.is_interrupted() => {
+            tracing::warn!("{e}");
+            Delivery::Interrupted
+        }
+        Err(e) if e.is_postponed() => {
+            tracing::warn!("{e}");
+            Delivery::NotDelivered
+        }
+        // The circuit warned once when it opened
+        Err(e) if e.is_circuit_open() => {
+            tracing::debug!("Webhook skipped: {e}");
+            Delivery::Failed
+        }
+        Err(e) => {
+            tracing::error!("Webhook failed: {e}");
+            Delivery::Failed
+        }
+    }
+}
+
+/// Records `changes` in `stats` and logs them.
+pub(super) fn record_changes(changes: &[IpChange], stats: &RunStats) {
+    stats.record_changes(changes);
+
+    // Log a coalesced summary, one line per adapter
+    for line in summarize(changes) {
+        tracing::info!("{line}");
+    }
+    tracing::debug!("Change details: {changes:?}");
+}
+
+/// Fallback hybrid loop for platforms without a listener and builds without
+/// the `hybrid` feature.
+///
+/// Excluded from coverage - requires platform APIs and signal handling.
+#[cfg(not(tarpaulin_include))]
+#[cfg(not(all(
+    any(windows, target_os = "linux", target_os = "macos"),
+    feature = "hybrid"
+)))]
+pub(super) async fn run_hybrid_loop(
+    fetcher: AppFetcher,
+    events: Events,
+    webhook: AppSender,
+    reloader: Reloader,
+    options: RuntimeOptions,
+    state_store: Option<FileStateStore>,
+    stats: &RunStats,
+) -> Result<(), RunError> {
+    if cfg!(feature = "hybrid") {
+        tracing::warn!("API listener not supported on this platform, using polling-only mode");
+    } else {
+        tracing::warn!("Built without the 'hybrid' feature, using polling-only mode");
+    }
+    run_polling_loop(
+        fetcher,
+        events,
+        webhook,
+        reloader,
+        options,
+        state_store,
+        stats,
+    )
+    .await
+}
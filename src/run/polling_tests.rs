@@ -0,0 +1,509 @@
+//! Tests for delivering the observed changes.
+
+use super::RuntimeOptions;
+use super::outbox::{Delivery, deliver, notified_batch};
+use super::polling::{deliver_observed, handle_changes, notified_changes};
+use ddns_a::config::{Cli, ValidatedConfig};
+use ddns_a::monitor::{IpChange, NotifyOn};
+use ddns_a::report::{DeliveryOutcome, HistoryLog, HistoryRecord, RunStats};
+use ddns_a::state::{Acknowledged, FileStateStore, QueueLimits, QueuedChange, StateStore};
+use ddns_a::webhook::{
+    Circuit, CircuitBreaker, CircuitPolicy, Event, HttpError, RetryableError, WebhookError,
+    WebhookSender,
+};
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
+
+/// Mock webhook sender for testing.
+struct MockWebhook {
+    send_count: AtomicUsize,
+    outcome: fn() -> Result<(), WebhookError>,
+}
+
+impl MockWebhook {
+    fn new() -> Self {
+        Self::answering(|| Ok(()))
+    }
+
+    fn failing() -> Self {
+        Self::answering(|| {
+            Err(WebhookError::Retryable(RetryableError::Http(
+                HttpError::Timeout,
+            )))
+        })
+    }
+
+    fn interrupted() -> Self {
+        Self::answering(|| Err(WebhookError::Interrupted { attempts: 1 }))
+    }
+
+    fn answering(outcome: fn() -> Result<(), WebhookError>) -> Self {
+        Self {
+            send_count: AtomicUsize::new(0),
+            outcome,
+        }
+    }
+
+    fn send_count(&self) -> usize {
+        self.send_count.load(Ordering::SeqCst)
+    }
+}
+
+impl WebhookSender for MockWebhook {
+    async fn send(&self, _changes: &[IpChange]) -> Result<(), WebhookError> {
+        self.send_count.fetch_add(1, Ordering::SeqCst);
+        (self.outcome)()
+    }
+
+    async fn send_event(&self, _event: &Event<'_>) -> Result<(), WebhookError> {
+        unreachable!("only address changes are handled")
+    }
+}
+
+fn make_change() -> IpChange {
+    IpChange::added(
+        "eth0",
+        "192.168.1.100".parse::<IpAddr>().unwrap(),
+        SystemTime::UNIX_EPOCH,
+    )
+}
+
+fn stats() -> RunStats {
+    RunStats::new(SystemTime::UNIX_EPOCH)
+}
+
+#[tokio::test]
+async fn sends_webhook_when_not_dry_run() {
+    let webhook = MockWebhook::new();
+    let changes = vec![make_change()];
+    let stats = stats();
+
+    let delivery = handle_changes(&changes, &webhook, false, &stats).await;
+
+    assert_eq!(delivery, Delivery::Delivered);
+    assert_eq!(webhook.send_count(), 1);
+    let summary = stats.summary(SystemTime::UNIX_EPOCH);
+    assert_eq!(summary.changes_detected, 1);
+    assert_eq!(summary.notifications_sent, 1);
+}
+
+#[tokio::test]
+async fn skips_webhook_in_dry_run() {
+    let webhook = MockWebhook::new();
+    let changes = vec![make_change()];
+    let stats = stats();
+
+    let delivery = handle_changes(&changes, &webhook, true, &stats).await;
+
+    assert_eq!(delivery, Delivery::NotDelivered);
+    assert_eq!(webhook.send_count(), 0);
+    let summary = stats.summary(SystemTime::UNIX_EPOCH);
+    assert_eq!(summary.changes_detected, 1);
+    assert_eq!(summary.notifications_sent, 0);
+}
+
+#[tokio::test]
+async fn handles_webhook_failure_gracefully() {
+    let webhook = MockWebhook::failing();
+    let changes = vec![make_change()];
+    let stats = stats();
+
+    // Should not panic
+    let delivery = handle_changes(&changes, &webhook, false, &stats).await;
+
+    assert_eq!(delivery, Delivery::Failed);
+    assert_eq!(webhook.send_count(), 1);
+    assert_eq!(
+        stats.summary(SystemTime::UNIX_EPOCH).notifications_failed,
+        1
+    );
+}
+
+#[tokio::test]
+async fn handles_multiple_changes() {
+    let webhook = MockWebhook::new();
+    let changes = vec![
+        IpChange::added(
+            "eth0",
+            "192.168.1.1".parse().unwrap(),
+            SystemTime::UNIX_EPOCH,
+        ),
+        IpChange::removed(
+            "eth0",
+            "192.168.1.2".parse().unwrap(),
+            SystemTime::UNIX_EPOCH,
+        ),
+        IpChange::added("wlan0", "10.0.0.1".parse().unwrap(), SystemTime::UNIX_EPOCH),
+    ];
+
+    handle_changes(&changes, &webhook, false, &stats()).await;
+
+    // All changes sent in single batch
+    assert_eq!(webhook.send_count(), 1);
+}
+
+#[tokio::test]
+async fn reports_interrupted_delivery() {
+    let webhook = MockWebhook::interrupted();
+
+    let delivery = handle_changes(&[make_change()], &webhook, false, &stats()).await;
+
+    assert_eq!(delivery, Delivery::Interrupted);
+}
+
+#[tokio::test]
+async fn open_circuit_fails_without_sending() {
+    let policy = CircuitPolicy {
+        failure_threshold: 1,
+        ..CircuitPolicy::default()
+    };
+    let webhook = CircuitBreaker::new(MockWebhook::failing(), Circuit::new("main", policy));
+    handle_changes(&[make_change()], &webhook, false, &stats()).await;
+
+    let delivery = handle_changes(&[make_change()], &webhook, false, &stats()).await;
+
+    assert_eq!(delivery, Delivery::Failed);
+    assert_eq!(webhook.inner().send_count(), 1);
+}
+
+fn options() -> RuntimeOptions {
+    let cli = Cli::parse_from_iter([
+        "ddns-a",
+        "--url",
+        "https://example.com/hook",
+        "--ip-version",
+        "ipv4",
+    ]);
+    RuntimeOptions::from(&ValidatedConfig::from_raw(&cli, None).unwrap())
+}
+
+fn queue_options() -> RuntimeOptions {
+    RuntimeOptions {
+        retry_queue: Some(QueueLimits::default()),
+        ..options()
+    }
+}
+
+async fn queue_store(dir: &tempfile::TempDir, queued: &[IpChange]) -> FileStateStore {
+    let store = FileStateStore::new(dir.path().join("state.json"));
+    store.save(&[]).await.unwrap();
+    let queued: Vec<_> = queued
+        .iter()
+        .map(|change| QueuedChange::new(change.clone(), SystemTime::now()))
+        .collect();
+    store.set_queued(&queued).await.unwrap();
+    store
+}
+
+fn queued_changes(store: &FileStateStore) -> Vec<IpChange> {
+    store
+        .queued()
+        .into_iter()
+        .map(|queued| queued.change)
+        .collect()
+}
+
+#[tokio::test]
+async fn interrupted_batch_goes_to_outbox() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let store = FileStateStore::new(dir.path().join("state.json"));
+    store.save(&[]).await.unwrap();
+    let changes = vec![make_change()];
+
+    let webhook = MockWebhook::interrupted();
+    deliver(&changes, &webhook, &options(), Some(&store), &stats()).await;
+
+    assert_eq!(store.undelivered(), changes);
+}
+
+#[tokio::test]
+async fn failed_batch_stays_out_of_outbox() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let store = FileStateStore::new(dir.path().join("state.json"));
+    store.save(&[]).await.unwrap();
+
+    let webhook = MockWebhook::failing();
+    deliver(
+        &[make_change()],
+        &webhook,
+        &options(),
+        Some(&store),
+        &stats(),
+    )
+    .await;
+
+    assert!(store.undelivered().is_empty());
+}
+
+#[tokio::test]
+async fn failed_batch_goes_to_retry_queue() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let store = queue_store(&dir, &[]).await;
+    let changes = vec![make_change()];
+
+    let webhook = MockWebhook::failing();
+    deliver(&changes, &webhook, &queue_options(), Some(&store), &stats()).await;
+
+    assert_eq!(queued_changes(&store), changes);
+    assert!(store.undelivered().is_empty());
+}
+
+#[tokio::test]
+async fn queued_changes_are_sent_first() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let store = queue_store(&dir, &[make_change()]).await;
+
+    let webhook = MockWebhook::new();
+    deliver(
+        &[make_change()],
+        &webhook,
+        &queue_options(),
+        Some(&store),
+        &stats(),
+    )
+    .await;
+
+    assert_eq!(webhook.send_count(), 2);
+    assert!(store.queued().is_empty());
+}
+
+#[tokio::test]
+async fn new_changes_wait_behind_queued_ones() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let older = IpChange::added(
+        "eth0",
+        "192.168.1.1".parse().unwrap(),
+        SystemTime::UNIX_EPOCH,
+    );
+    let store = queue_store(&dir, std::slice::from_ref(&older)).await;
+    let stats = stats();
+
+    let webhook = MockWebhook::failing();
+    deliver(
+        &[make_change()],
+        &webhook,
+        &queue_options(),
+        Some(&store),
+        &stats,
+    )
+    .await;
+
+    // Only the queued change was attempted
+    assert_eq!(webhook.send_count(), 1);
+    assert_eq!(queued_changes(&store), vec![older, make_change()]);
+    assert_eq!(stats.summary(SystemTime::UNIX_EPOCH).changes_detected, 1);
+}
+
+#[tokio::test]
+async fn dry_run_leaves_queue_alone() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let store = queue_store(&dir, &[make_change()]).await;
+    let options = RuntimeOptions {
+        dry_run: true,
+        ..queue_options()
+    };
+
+    let webhook = MockWebhook::new();
+    deliver(&[make_change()], &webhook, &options, Some(&store), &stats()).await;
+
+    assert_eq!(webhook.send_count(), 0);
+    assert_eq!(queued_changes(&store), vec![make_change()]);
+}
+
+#[tokio::test]
+async fn deliveries_are_appended_to_history() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let history = HistoryLog::new(dir.path().join("history.jsonl"));
+    let stats = stats().with_history(history.clone());
+
+    let webhook = MockWebhook::failing();
+    handle_changes(&[make_change()], &webhook, false, &stats).await;
+
+    let records = history.read().unwrap();
+    assert_eq!(records[0], HistoryRecord::Detected(make_change()));
+    assert!(matches!(
+        &records[1],
+        HistoryRecord::Delivery(delivery) if delivery.outcome == DeliveryOutcome::Failed
+    ));
+}
+
+async fn acknowledged_store(
+    dir: &tempfile::TempDir,
+    queued: &[IpChange],
+    delivered: &[IpChange],
+) -> FileStateStore {
+    let store = queue_store(dir, queued).await;
+    let mut acknowledged = Acknowledged::default();
+    acknowledged.record(delivered);
+    store.set_acknowledged(&acknowledged).await.unwrap();
+    store
+}
+
+#[tokio::test]
+async fn acknowledged_changes_are_not_sent_again() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let store = acknowledged_store(&dir, &[], &[make_change()]).await;
+
+    let webhook = MockWebhook::new();
+    deliver(
+        &[make_change()],
+        &webhook,
+        &options(),
+        Some(&store),
+        &stats(),
+    )
+    .await;
+
+    assert_eq!(webhook.send_count(), 0);
+}
+
+#[tokio::test]
+async fn delivered_changes_are_acknowledged() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let store = acknowledged_store(&dir, &[], &[]).await;
+
+    let webhook = MockWebhook::new();
+    deliver(
+        &[make_change()],
+        &webhook,
+        &options(),
+        Some(&store),
+        &stats(),
+    )
+    .await;
+
+    let acknowledged = store.acknowledged().unwrap();
+    assert_eq!(
+        acknowledged.addresses("eth0").collect::<Vec<_>>(),
+        [make_change().address]
+    );
+}
+
+#[tokio::test]
+async fn failed_changes_are_not_acknowledged() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let store = acknowledged_store(&dir, &[], &[]).await;
+
+    let webhook = MockWebhook::failing();
+    deliver(
+        &[make_change()],
+        &webhook,
+        &options(),
+        Some(&store),
+        &stats(),
+    )
+    .await;
+
+    assert!(store.acknowledged().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn suppressed_removal_does_not_hide_a_readded_address() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let a = make_change();
+    let b = IpChange::added(
+        "eth0",
+        "192.168.1.101".parse().unwrap(),
+        SystemTime::UNIX_EPOCH,
+    );
+    let store = acknowledged_store(&dir, &[], std::slice::from_ref(&a)).await;
+    let removed =
+        |change: &IpChange| IpChange::removed("eth0", change.address, SystemTime::UNIX_EPOCH);
+    let options = options();
+    let webhook = MockWebhook::new();
+
+    // notify_on = ["added"]: A -> B, then B -> A
+    for batch in [vec![removed(&a), b.clone()], vec![removed(&b), a.clone()]] {
+        let notified = notified_batch(Some(&store), batch, NotifyOn::Added).await;
+        deliver(
+            &notified.unwrap(),
+            &webhook,
+            &options,
+            Some(&store),
+            &stats(),
+        )
+        .await;
+    }
+
+    // Both additions were sent, and the receiver holds A only
+    assert_eq!(webhook.send_count(), 2);
+    assert_eq!(
+        store
+            .acknowledged()
+            .unwrap()
+            .addresses("eth0")
+            .collect::<Vec<_>>(),
+        [a.address]
+    );
+}
+
+#[tokio::test]
+async fn observed_batches_are_published_on_the_change_bus() {
+    let options = RuntimeOptions {
+        notify_on: NotifyOn::Added,
+        ..options()
+    };
+    let mut published = options.changes.subscribe();
+    let webhook = MockWebhook::new();
+    let removed = IpChange::removed(
+        "eth0",
+        "192.168.1.101".parse().unwrap(),
+        SystemTime::UNIX_EPOCH,
+    );
+    let batch = vec![make_change(), removed];
+
+    deliver_observed(batch.clone(), None, &webhook, &options, None, &stats()).await;
+
+    // Published whole, though notify_on leaves the removal out
+    assert_eq!(&*published.try_recv().unwrap(), batch.as_slice());
+    assert_eq!(webhook.send_count(), 1);
+}
+
+#[tokio::test]
+async fn queued_changes_already_acknowledged_are_dropped() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let store = acknowledged_store(&dir, &[make_change()], &[make_change()]).await;
+    let removal = IpChange::removed("eth0", make_change().address, SystemTime::UNIX_EPOCH);
+
+    let webhook = MockWebhook::new();
+    deliver(
+        std::slice::from_ref(&removal),
+        &webhook,
+        &queue_options(),
+        Some(&store),
+        &stats(),
+    )
+    .await;
+
+    // Only the removal was sent
+    assert_eq!(webhook.send_count(), 1);
+    assert!(store.queued().is_empty());
+    assert!(store.acknowledged().unwrap().is_empty());
+}
+
+fn changes() -> Vec<IpChange> {
+    vec![
+        IpChange::added("eth0", "10.0.0.2".parse().unwrap(), SystemTime::UNIX_EPOCH),
+        IpChange::removed("eth0", "10.0.0.1".parse().unwrap(), SystemTime::UNIX_EPOCH),
+    ]
+}
+
+#[test]
+fn suppresses_the_other_kind() {
+    let notified = notified_changes(changes(), NotifyOn::Added).unwrap();
+
+    assert_eq!(notified, changes()[..1]);
+}
+
+#[test]
+fn nothing_left_is_none() {
+    let removed = changes().split_off(1);
+
+    assert_eq!(notified_changes(removed, NotifyOn::Added), None);
+}
+
+#[test]
+fn both_keeps_everything() {
+    assert_eq!(notified_changes(changes(), NotifyOn::Both), Some(changes()));
+}
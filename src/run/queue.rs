@@ -0,0 +1,132 @@
+//! The persistent retry queue of changes whose delivery failed.
+//!
+//! With `[retry.queue]`, changes whose retries ran out are kept in the state
+//! file and sent again every poll interval and on the next start. Newer
+//! changes wait behind them, so the webhook never sees an older address
+//! after a newer one.
+
+use std::time::{Duration, SystemTime};
+
+use ddns_a::monitor::IpChange;
+use ddns_a::report::RunStats;
+use ddns_a::state::{QueueLimits, QueuedChange, StateStore};
+use ddns_a::webhook::WebhookSender;
+use tokio::time::{Interval, MissedTickBehavior};
+
+use super::RuntimeOptions;
+use super::outbox::acknowledge;
+
+/// Sends the queued changes again, returning whether the queue is empty
+/// afterwards (always if the queue is disabled).
+pub(super) async fn retry_queued<W: WebhookSender>(
+    webhook: &W,
+    options: &RuntimeOptions,
+    store: Option<&impl StateStore>,
+    stats: &RunStats,
+) -> bool {
+    let (Some(limits), Some(store)) = (options.retry_queue, store) else {
+        return true;
+    };
+    if options.dry_run {
+        return true;
+    }
+
+    let mut queue = store.queued();
+    let mut updated = prune(&limits, &mut queue) > 0;
+    if let Some(acknowledged) = store.acknowledged() {
+        let count = queue.len();
+        queue.retain(|queued| !acknowledged.contains(&queued.change));
+        if queue.len() < count {
+            tracing::info!(
+                "Dropped {} queued change(s): the webhooks already acknowledged them",
+                count - queue.len()
+            );
+            updated = true;
+        }
+    }
+    if !queue.is_empty() {
+        let changes: Vec<IpChange> = queue.iter().map(|queued| queued.change.clone()).collect();
+        tracing::info!("Retrying {} queued change(s)", changes.len());
+        let result = webhook.send(&changes).await;
+        stats.record_changes_delivery(&changes, &result);
+        match result {
+            Ok(()) => {
+                tracing::info!("Delivered {} queued change(s)", changes.len());
+                acknowledge(Some(store), &changes).await;
+                queue.clear();
+                updated = true;
+            }
+            Err(e) => tracing::warn!("Queued changes still undelivered: {e}"),
+        }
+    }
+
+    if updated {
+        if let Err(e) = store.set_queued(&queue).await {
+            tracing::error!("Failed to update the retry queue: {e}");
+        }
+    }
+    queue.is_empty()
+}
+
+/// Appends `changes` to the retry queue of `store`, if enabled.
+pub(super) async fn enqueue(
+    options: &RuntimeOptions,
+    store: Option<&impl StateStore>,
+    changes: &[IpChange],
+) {
+    let (Some(limits), Some(store)) = (options.retry_queue, store) else {
+        return;
+    };
+
+    let now = SystemTime::now();
+    let mut queue = store.queued();
+    queue.extend(
+        changes
+            .iter()
+            .map(|change| QueuedChange::new(change.clone(), now)),
+    );
+    prune(&limits, &mut queue);
+    match store.set_queued(&queue).await {
+        Ok(()) => tracing::info!(
+            "Queued {} change(s) for another delivery attempt ({} waiting)",
+            changes.len(),
+            queue.len()
+        ),
+        Err(e) => tracing::error!("Failed to queue undelivered changes: {e}"),
+    }
+}
+
+/// Drops the queued changes beyond `limits`, logging how many.
+fn prune(limits: &QueueLimits, queue: &mut Vec<QueuedChange>) -> usize {
+    let dropped = limits.prune(queue, SystemTime::now());
+    if dropped > 0 {
+        tracing::warn!("Dropped {dropped} queued change(s) past the retry queue limits");
+    }
+    dropped
+}
+
+/// Ticks every poll interval while the retry queue is enabled.
+pub(super) struct QueueTimer(Option<Interval>);
+
+impl QueueTimer {
+    /// Creates a timer ticking every `period`, first after one period, if
+    /// the queue is `enabled`.
+    pub(super) fn new(enabled: bool, period: Duration) -> Self {
+        Self(enabled.then(|| {
+            let mut interval =
+                tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            interval
+        }))
+    }
+
+    /// Completes on the next tick; never if the queue is disabled.
+    pub(super) async fn tick(&mut self) {
+        match &mut self.0 {
+            Some(interval) => {
+                interval.tick().await;
+            }
+            None => std::future::pending().await,
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! Configuration reload on SIGHUP or when the configuration file changes.
+//!
+//! The configuration is loaded again from the command line and environment
+//! this process was started with, as a restart would. The adapter filters,
+//! the webhook senders and the poll intervals (with the adaptive range) are
+//! replaced in the running loop, which keeps its snapshot, debounce window,
+//! outbox and statistics; other settings only apply after a restart
+//! (`ddns-a ctl restart`). An invalid configuration is logged and the
+//! running one kept.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use ddns_a::config::{Cli, ValidatedConfig};
+use ddns_a::monitor::AdaptivePolicy;
+use ddns_a::network::filter::SharedFilter;
+use ddns_a::webhook::CertificateChange;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+
+use super::senders::{
+    certificate_watch, create_webhooks, gate_webhooks, main_url, start_endpoint_discovery,
+};
+use super::{AppSender, RuntimeOptions};
+
+/// How often the configuration file is checked for changes.
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Settings a reload replaces in the running loop.
+pub(super) struct Reloaded {
+    pub(super) webhook: AppSender,
+    pub(super) poll_interval: Duration,
+    pub(super) poll_interval_v6: Option<Duration>,
+    pub(super) adaptive: Option<AdaptivePolicy>,
+}
+
+/// Reloads the configuration when asked to, owning what the webhook
+/// senders need besides it.
+pub(super) struct Reloader {
+    requests: UnboundedReceiver<()>,
+    trigger: JoinHandle<()>,
+    filter: SharedFilter,
+    certificates: UnboundedSender<Vec<CertificateChange>>,
+    /// Refreshes the main webhook's URL; restarted for the new sender
+    discovery: Option<JoinHandle<()>>,
+}
+
+impl Reloader {
+    /// Starts watching `config_file` and, on Unix, listening for SIGHUP.
+    pub(super) fn start(
+        config_file: Option<PathBuf>,
+        filter: SharedFilter,
+        certificates: UnboundedSender<Vec<CertificateChange>>,
+        discovery: Option<JoinHandle<()>>,
+    ) -> Self {
+        let (tx, requests) = mpsc::unbounded_channel();
+        let trigger = tokio::spawn(async move {
+            tokio::join!(hangups(tx.clone()), watch(config_file, tx));
+        });
+        Self {
+            requests,
+            trigger,
+            filter,
+            certificates,
+            discovery,
+        }
+    }
+
+    /// Waits for the next reload request; never completes without triggers.
+    pub(super) async fn requested(&mut self) {
+        if self.requests.recv().await.is_none() {
+            std::future::pending::<()>().await;
+        }
+    }
+
+    /// Loads the configuration again and applies its adapter filters;
+    /// returns the webhook and poll settings for the loop to replace, or
+    /// `None` if the configuration is invalid.
+    ///
+    /// Excluded from coverage - reads the process arguments.
+    #[cfg(not(tarpaulin_include))]
+    pub(super) async fn reload(&mut self, options: &RuntimeOptions) -> Option<Reloaded> {
+        let mut cli = Cli::parse_from_iter(std::env::args_os());
+        let mut config = match cli.apply_env().and_then(|()| ValidatedConfig::load(&cli)) {
+            Ok(config) => config,
+            Err(e) => {
+                tracing::error!(
+                    "Keeping the running configuration, the reloaded one is invalid: {e}"
+                );
+                return None;
+            }
+        };
+
+        self.filter
+            .replace(std::mem::take(&mut config.monitor.filter));
+        let certificates = certificate_watch(&config, self.certificates.clone());
+        let webhooks = create_webhooks(&config, certificates);
+        if let Some(discovery) = self.discovery.take() {
+            discovery.abort();
+        }
+        self.discovery = start_endpoint_discovery(&config, main_url(&webhooks), true).await;
+
+        tracing::info!(
+            "Configuration reloaded: adapter filters, webhook settings and poll interval ({}s) \
+             applied; other settings apply after a restart",
+            config.monitor.poll_interval.as_secs()
+        );
+        Some(Reloaded {
+            webhook: gate_webhooks(webhooks, &config, options),
+            poll_interval: config.monitor.poll_interval,
+            poll_interval_v6: config.monitor.poll_interval_v6,
+            adaptive: config.monitor.adaptive,
+        })
+    }
+}
+
+impl Drop for Reloader {
+    fn drop(&mut self) {
+        self.trigger.abort();
+        if let Some(discovery) = &self.discovery {
+            discovery.abort();
+        }
+    }
+}
+
+/// Requests a reload on every SIGHUP.
+#[cfg(unix)]
+async fn hangups(tx: UnboundedSender<()>) {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangup = signal(SignalKind::hangup()).expect("Failed to install SIGHUP handler");
+    while hangup.recv().await.is_some() {
+        tracing::info!("Reload signal received");
+        if tx.send(()).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn hangups(_tx: UnboundedSender<()>) {}
+
+/// Requests a reload whenever the file at `path` is modified.
+async fn watch(path: Option<PathBuf>, tx: UnboundedSender<()>) {
+    let Some(path) = path else {
+        return;
+    };
+    let mut file = FileWatch::new(path);
+    let mut ticks = tokio::time::interval(WATCH_INTERVAL);
+    loop {
+        ticks.tick().await;
+        if file.changed() {
+            tracing::info!("{} changed, reloading", file.path.display());
+            if tx.send(()).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Detects modifications of a file by its modification time.
+pub(super) struct FileWatch {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl FileWatch {
+    /// Starts watching `path` as it is now.
+    pub(super) fn new(path: PathBuf) -> Self {
+        let modified = modified(&path);
+        Self { path, modified }
+    }
+
+    /// Returns true if the file was modified since the last call; a removed
+    /// file is not a change, but its replacement is.
+    pub(super) fn changed(&mut self) -> bool {
+        let modified = modified(&self.path);
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        modified.is_some()
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
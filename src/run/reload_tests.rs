@@ -0,0 +1,37 @@
+//! Tests for watching the config file.
+
+use super::reload::FileWatch;
+use std::fs::File;
+use std::time::{Duration, SystemTime};
+
+fn touch(file: &File, secs: u64) {
+    file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+        .unwrap();
+}
+
+#[test]
+fn modification_is_a_change_once() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("ddns-a.toml");
+    let file = File::create(&path).unwrap();
+    touch(&file, 1_000);
+    let mut watch = FileWatch::new(path);
+
+    assert!(!watch.changed());
+    touch(&file, 2_000);
+    assert!(watch.changed());
+    assert!(!watch.changed());
+}
+
+#[test]
+fn removal_is_not_a_change_but_replacement_is() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("ddns-a.toml");
+    touch(&File::create(&path).unwrap(), 1_000);
+    let mut watch = FileWatch::new(path.clone());
+
+    std::fs::remove_file(&path).unwrap();
+    assert!(!watch.changed());
+    touch(&File::create(&path).unwrap(), 1_000);
+    assert!(watch.changed());
+}
@@ -0,0 +1,32 @@
+//! Re-sending the changes between two saved state files.
+
+use std::time::SystemTime;
+
+use ddns_a::config::ValidatedConfig;
+use ddns_a::monitor::IpChange;
+use ddns_a::report::RunStats;
+use ddns_a::webhook::FanOut;
+
+use super::action::with_actions;
+use super::outbox::Delivery;
+use super::polling::handle_changes;
+use super::senders::{create_webhooks, main_url, route_webhooks, start_endpoint_discovery};
+
+/// Sends replayed `changes` to the configured webhook targets, as a startup
+/// detection would have.
+///
+/// Connectivity gating is bypassed, as for `test-webhook`. Returns true if
+/// every target accepted the changes (or `--dry-run` skipped sending).
+///
+/// Excluded from coverage - performs real HTTP requests.
+#[cfg(not(tarpaulin_include))]
+pub async fn notify_replayed(config: &ValidatedConfig, changes: &[IpChange]) -> bool {
+    let webhooks = create_webhooks(config, None);
+    start_endpoint_discovery(config, main_url(&webhooks), false).await;
+    let webhooks = with_actions(webhooks, config);
+    let webhook = route_webhooks(webhooks.into_iter().collect::<FanOut<_>>(), config);
+    let stats = RunStats::new(SystemTime::now());
+
+    let delivery = handle_changes(changes, &webhook, config.dry_run, &stats).await;
+    delivery == Delivery::Delivered || config.dry_run
+}
@@ -0,0 +1,61 @@
+//! Sending a synthetic change to the main webhook with a request trace.
+
+use std::time::SystemTime;
+
+use ddns_a::config::ValidatedConfig;
+use ddns_a::webhook::{
+    ExchangeLog, LoggingClient, RetryPolicy, RetryableError, WebhookError, WebhookSender,
+    format_request, format_response, synthetic_changes,
+};
+
+use super::senders::{
+    create_client_with, create_webhook_with, http_client, start_endpoint_discovery,
+};
+
+/// Sends a synthetic change to the main webhook once, printing every
+/// request with its response; with `--dry-run` only prints the requests.
+///
+/// Unlike `test-webhook`, a failed attempt is not retried, so the printed
+/// response is the one the error describes.
+///
+/// # Errors
+///
+/// Returns the error of the delivery, or of rendering it with `--dry-run`.
+///
+/// Excluded from coverage - performs real HTTP requests.
+#[cfg(not(tarpaulin_include))]
+pub async fn send_test(config: &ValidatedConfig) -> Result<(), WebhookError> {
+    let changes = synthetic_changes(config.ip_version, SystemTime::now());
+    let log = ExchangeLog::new();
+    // Logged as sent, with the metadata headers
+    let client = create_client_with(
+        config,
+        LoggingClient::new(http_client(&config.http, None), log.clone()),
+    );
+    let Some(webhook) = create_webhook_with(config, client) else {
+        return Err(RetryableError::Transport {
+            reason: "no webhook URL is set".to_string(),
+            retryable: false,
+        }
+        .into());
+    };
+    let webhook = webhook.with_retry_policy(RetryPolicy::new().with_max_attempts(1));
+    start_endpoint_discovery(config, Some(webhook.url_handle()), false).await;
+
+    if config.dry_run {
+        for request in webhook.preview(&changes)? {
+            println!("{}", format_request(&request));
+        }
+        return Ok(());
+    }
+
+    let result = webhook.send(&changes).await;
+    for exchange in log.take() {
+        println!("{}", format_request(&exchange.request));
+        match exchange.response {
+            Ok(response) => println!("{}", format_response(&response)),
+            Err(e) => println!("No response: {e}\n"),
+        }
+    }
+    result
+}
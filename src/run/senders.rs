@@ -0,0 +1,451 @@
+//! Construction of the webhook senders.
+//!
+//! Every target (the main webhook, the `[[webhook.targets]]`, the IP version
+//! URLs and the other sinks) gets its own HTTP client stack, is gated on the
+//! tray pause, its connectivity and its circuit breaker, and is routed by
+//! adapter in one [`FanOut`]. The reloader rebuilds them the same way.
+
+use tokio::sync::mpsc;
+use url::Url;
+
+use ddns_a::config::{HttpSettings, MAIN_TARGET, ValidatedConfig};
+use ddns_a::dns::{NameserverList, UdpResolver};
+use ddns_a::network::filter::SharedFilter;
+use ddns_a::network::platform::PlatformFetcher;
+use ddns_a::state::{FileStateStore, StateStore};
+use ddns_a::webhook::{
+    AuditedClient, CertificateChange, CertificateWatch, CircuitBreaker, ConnectivityCheck,
+    ConnectivityGate, FamilyProbe, FanOut, FileAuditSink, GoldenDir, HttpClient, HttpWebhook,
+    MetadataClient, NameserverResolver, Redaction, RequestMetadata, ReqwestClient, SharedUrl,
+    refresh_endpoint,
+};
+
+use super::action::with_actions;
+use super::reload::Reloader;
+use super::{AppClient, AppProbe, AppSender, RuntimeOptions};
+
+/// Creates the webhook senders, each gated on the tray pause and connectivity
+/// if configured, and the reloader replacing them and `filter`.
+pub(super) async fn start_webhooks(
+    config: &ValidatedConfig,
+    options: &RuntimeOptions,
+    filter: &SharedFilter,
+    certificate_tx: mpsc::UnboundedSender<Vec<CertificateChange>>,
+) -> (AppSender, Reloader) {
+    let certificates = certificate_watch(config, certificate_tx.clone());
+    let webhooks = create_webhooks(config, certificates);
+    let discovery = start_endpoint_discovery(config, main_url(&webhooks), true).await;
+    let webhook = gate_webhooks(webhooks, config, options);
+    let reloader = Reloader::start(
+        config.config_file.clone(),
+        filter.clone(),
+        certificate_tx,
+        discovery,
+    );
+    (webhook, reloader)
+}
+
+/// Returns the URL of the main webhook among `webhooks`, if there is one.
+pub(super) fn main_url(webhooks: &[(String, HttpWebhook<AppClient>)]) -> Option<SharedUrl> {
+    webhooks
+        .iter()
+        .find(|(name, _)| name == MAIN_TARGET)
+        .map(|(_, webhook)| webhook.url_handle())
+}
+
+/// Resolves the webhook URL from DNS, if configured, and stores it in `url`.
+///
+/// With `keep_refreshing` and a non-zero refresh interval, spawns a task
+/// repeating the lookup. Lookup failures are logged and keep the current URL.
+///
+/// Excluded from coverage - performs real DNS queries.
+#[cfg(not(tarpaulin_include))]
+pub(super) async fn start_endpoint_discovery(
+    config: &ValidatedConfig,
+    url: Option<SharedUrl>,
+    keep_refreshing: bool,
+) -> Option<tokio::task::JoinHandle<()>> {
+    let discovery = config.webhook.endpoint_discovery.clone()?;
+    let url = url?;
+    let resolver = match discovery.nameserver {
+        Some(server) => NameserverList::new([server]),
+        None if !config.http.dns_servers.is_empty() => {
+            NameserverList::new(config.http.dns_servers.iter().copied())
+        }
+        None => match UdpResolver::from_system() {
+            Ok(resolver) => NameserverList::new([resolver.server()]),
+            Err(e) => {
+                tracing::warn!("Webhook URL discovery disabled: {e}");
+                return None;
+            }
+        },
+    };
+    tracing::info!(
+        "Discovering webhook URL from DNS record '{}' via {:?}",
+        discovery.record.name(),
+        resolver.servers().collect::<Vec<_>>()
+    );
+
+    if let Err(e) = refresh_endpoint(&resolver, &discovery.record, &url).await {
+        tracing::warn!("{e}; using {}", url.get());
+    }
+    if !keep_refreshing || discovery.refresh.is_zero() {
+        return None;
+    }
+
+    Some(tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(discovery.refresh).await;
+            if let Err(e) = refresh_endpoint(&resolver, &discovery.record, &url).await {
+                tracing::warn!("{e}; keeping {}", url.get());
+            }
+        }
+    }))
+}
+
+/// Creates the sender of every webhook target, the main `[webhook]` first,
+/// passing its server certificates to `certificates` if given.
+///
+/// The per-version URLs follow with the main webhook's settings. Recording
+/// payloads only records the main webhook's: the other targets would send
+/// theirs.
+pub(super) fn create_webhooks(
+    config: &ValidatedConfig,
+    certificates: Option<CertificateWatch>,
+) -> Vec<(String, HttpWebhook<AppClient>)> {
+    let mut webhooks: Vec<_> = create_webhook(config, certificates)
+        .map(|webhook| (MAIN_TARGET.to_string(), webhook))
+        .into_iter()
+        .collect();
+    if config.record_payloads.is_some() {
+        if !config.webhook.targets.is_empty()
+            || !config.webhook.version_urls.is_empty()
+            || !config.outputs.chats.is_empty()
+        {
+            tracing::info!("Not sending to other webhooks while recording payloads");
+        }
+        return webhooks;
+    }
+
+    for version_url in &config.webhook.version_urls {
+        tracing::info!(
+            "Sending {} address changes to {}",
+            version_url.version,
+            version_url.url
+        );
+        let client = create_client(config, None);
+        let webhook = create_webhook_to(config, client, version_url.url.clone());
+        webhooks.push((version_url.name().to_string(), webhook));
+    }
+
+    for target in &config.webhook.targets {
+        tracing::info!("Also sending changes to {} ({})", target.name, target.url);
+        let http = HttpSettings {
+            connection: target.connection.clone(),
+            ..config.http.clone()
+        };
+        let client = create_client_with(config, http_client(&http, None));
+        let mut webhook = HttpWebhook::new(client, target.url.clone())
+            .with_method(target.method.clone())
+            .with_headers(target.headers.clone())
+            .with_payload_format(config.webhook.payload_format)
+            .with_retry_policy(target.retry_policy.clone())
+            .with_delivery_mode(target.delivery_mode);
+        if let Some(ref template) = target.body_template {
+            webhook = webhook.with_body_template(template);
+        }
+        webhooks.push((target.name.clone(), webhook));
+    }
+
+    // The URLs hold the chat credentials, so only the service is logged
+    for chat in &config.outputs.chats {
+        tracing::info!("Also posting changes to {}", chat.service());
+        let webhook = HttpWebhook::new(create_client(config, None), chat.url().clone())
+            .with_chat(chat.clone())
+            .with_retry_policy(config.webhook.retry_policy.clone());
+        webhooks.push((chat.service().target_name().to_string(), webhook));
+    }
+    webhooks
+}
+
+/// Combines `webhooks` and the exec action into one sender delivering to
+/// all of them at once, each gated on the tray pause and its own host's
+/// connectivity, and guarded by its circuit if circuit breaking is enabled
+/// for it.
+pub(super) fn gate_webhooks(
+    webhooks: Vec<(String, HttpWebhook<AppClient>)>,
+    config: &ValidatedConfig,
+    options: &RuntimeOptions,
+) -> AppSender {
+    let gated = with_actions(webhooks, config)
+        .into_iter()
+        .map(|(name, webhook)| {
+            let probe = (
+                options.pause.clone(),
+                webhook.url().and_then(|url| create_probe(config, url)),
+            );
+            let webhook = webhook.with_shutdown(options.abort.clone());
+            let policy = config
+                .webhook
+                .targets
+                .iter()
+                .find(|t| t.name == name)
+                .map_or(config.webhook.circuit_breaker, |t| t.circuit_breaker);
+            let webhook = match (&options.circuits, policy) {
+                (Some(circuits), Some(policy)) => {
+                    CircuitBreaker::new(webhook, circuits.circuit_with(&name, policy))
+                }
+                _ => CircuitBreaker::unguarded(webhook),
+            };
+            (name, ConnectivityGate::new(webhook, probe))
+        })
+        .collect();
+    route_webhooks(gated, config)
+}
+
+/// Limits each target of `sender` to the changes of its configured adapters,
+/// IP version and added addresses, splitting the main webhook's address
+/// changes by IP version if per-version URLs are set.
+pub(super) fn route_webhooks<W>(sender: FanOut<W>, config: &ValidatedConfig) -> FanOut<W> {
+    let main_route = config
+        .webhook
+        .version_urls
+        .iter()
+        .fold(config.webhook.route.clone(), |route, v| {
+            route.excluding(v.version)
+        });
+    let version_routes = config.webhook.version_urls.iter().map(|v| {
+        (
+            v.name(),
+            config.webhook.route.clone().for_version(v.version),
+        )
+    });
+    let routes = std::iter::once((MAIN_TARGET, main_route))
+        .chain(version_routes)
+        .chain(
+            config
+                .webhook
+                .targets
+                .iter()
+                .map(|t| (t.name.as_str(), t.route.clone())),
+        )
+        .chain(
+            config
+                .outputs
+                .sink_routes
+                .iter()
+                .map(|(name, route)| (*name, route.clone())),
+        );
+    routes
+        .filter(|(_, route)| !route.is_all() || route.is_version_split() || route.is_added_only())
+        .fold(sender, |sender, (name, route)| {
+            if !route.is_all() {
+                let patterns: Vec<_> = route.patterns().collect();
+                tracing::info!(
+                    "Sending {name} only changes of adapters matching {}",
+                    patterns.join(", ")
+                );
+            }
+            if route.is_added_only() {
+                tracing::info!("Sending {name} no removed addresses");
+            }
+            sender.with_route(name, route)
+        })
+}
+
+/// Creates the HTTP webhook sender of the main `[webhook]` from
+/// configuration, passing server certificates to `certificates` if given;
+/// `None` without a webhook URL.
+pub(super) fn create_webhook(
+    config: &ValidatedConfig,
+    certificates: Option<CertificateWatch>,
+) -> Option<HttpWebhook<AppClient>> {
+    create_webhook_with(config, create_client(config, certificates))
+}
+
+/// Creates the HTTP webhook sender of the main `[webhook]` from
+/// configuration, sending through `client`; `None` without a webhook URL.
+pub(super) fn create_webhook_with<H: HttpClient>(
+    config: &ValidatedConfig,
+    client: H,
+) -> Option<HttpWebhook<H>> {
+    let webhook = create_webhook_to(config, client, config.webhook.url.clone()?);
+    Some(match config.webhook.url_template {
+        Some(ref template) => webhook.with_url_template(template),
+        None => webhook,
+    })
+}
+
+/// Creates an HTTP webhook sender with the main `[webhook]` settings
+/// sending to `url` through `client`.
+fn create_webhook_to<H: HttpClient>(
+    config: &ValidatedConfig,
+    client: H,
+    url: Url,
+) -> HttpWebhook<H> {
+    let mut webhook = HttpWebhook::new(client, url)
+        .with_method(config.webhook.method.clone())
+        .with_headers(config.webhook.headers.clone())
+        .with_echo_check(config.webhook.echo_check)
+        .with_payload_format(config.webhook.payload_format)
+        .with_delivery_mode(config.webhook.delivery_mode)
+        .with_retry_policy(config.webhook.retry_policy.clone())
+        .with_retry_overrides(config.webhook.retry_overrides.clone());
+
+    if let Some(ref template) = config.webhook.body_template {
+        webhook = webhook.with_body_template(template);
+    }
+    if let Some(ref template) = config.webhook.dns_body_template {
+        webhook = webhook.with_dns_body_template(template);
+    }
+    if let Some(encoding) = config.webhook.payload_encoding {
+        webhook = webhook.with_payload_encoding(encoding);
+    }
+    if let Some(ref assertion) = config.webhook.json_assertion {
+        webhook = webhook.with_json_assertion(assertion.clone());
+    }
+    if let Some(ref assertion) = config.webhook.body_assertion {
+        webhook = webhook.with_body_assertion(assertion.clone());
+    }
+    if let Some(ttl) = config.webhook.ttl {
+        webhook = webhook.with_ttl(ttl);
+    }
+    if let Some(ref provider) = config.webhook.provider {
+        tracing::info!(
+            "Updating {} records of {}",
+            provider.kind(),
+            provider.hostname()
+        );
+        webhook = webhook.with_provider(provider.clone());
+    }
+    if let Some(ref pre_request) = config.webhook.pre_request {
+        webhook = webhook.with_pre_request(pre_request.clone());
+    }
+    if let Some(ref oauth2) = config.webhook.oauth2 {
+        webhook = webhook.with_oauth2(oauth2.clone());
+    }
+    if let Some(ref dir) = config.record_payloads {
+        tracing::info!(
+            "Recording payloads to {} instead of sending them",
+            dir.display()
+        );
+        webhook = webhook.with_recorder(GoldenDir::new(dir));
+    }
+
+    webhook
+}
+
+/// Creates the HTTP client, wrapped with metadata headers and auditing if configured.
+fn create_client(config: &ValidatedConfig, certificates: Option<CertificateWatch>) -> AppClient {
+    create_client_with(config, http_client(&config.http, certificates))
+}
+
+/// Wraps `client` with metadata headers and auditing if configured.
+pub(super) fn create_client_with<H: HttpClient>(
+    config: &ValidatedConfig,
+    client: H,
+) -> MetadataClient<AuditedClient<H, Option<FileAuditSink>>> {
+    let (sink, redaction) = config.outputs.audit.as_ref().map_or_else(
+        || (None, Redaction::new()),
+        |audit| {
+            tracing::info!("Audit log enabled: {}", audit.path.display());
+            (
+                Some(FileAuditSink::new(&audit.path)),
+                audit.redaction.clone(),
+            )
+        },
+    );
+
+    let audited = AuditedClient::new(client, sink, redaction);
+    MetadataClient::new(audited, create_metadata(config))
+}
+
+/// Creates the reqwest client, resolving hosts through `[http] dns_servers`
+/// if configured, applying `[http.tls]`, the `[http]` timeouts and source
+/// binding, and passing server certificates to `certificates`.
+pub fn http_client(http: &HttpSettings, certificates: Option<CertificateWatch>) -> ReqwestClient {
+    if http.tls.accept_invalid_certs {
+        tracing::warn!(
+            "TLS certificate verification is disabled (http.tls.danger_accept_invalid_certs)"
+        );
+    }
+    ReqwestClient::with_options(
+        nameserver_resolver(http),
+        &http.tls,
+        &http.connection,
+        certificates,
+    )
+}
+
+/// Creates the certificate watch of `[webhook.certificate]`, starting from
+/// the fingerprints in the state file; `None` if not configured.
+pub(super) fn certificate_watch(
+    config: &ValidatedConfig,
+    events: mpsc::UnboundedSender<Vec<CertificateChange>>,
+) -> Option<CertificateWatch> {
+    let pins = config.webhook.certificate_pins.clone()?;
+    let known = config
+        .state_file
+        .as_ref()
+        .map(|path| FileStateStore::new(path).certificates())
+        .unwrap_or_default();
+    if config.state_file.is_none() {
+        tracing::warn!(
+            "Certificate tracking without --state-file: fingerprints are not kept across runs"
+        );
+    }
+
+    let mut watch = CertificateWatch::new(events).with_known(known);
+    if let Some(host) = config.webhook.url.as_ref().and_then(Url::host_str) {
+        if !pins.is_empty() {
+            tracing::info!("Pinning {} TLS certificate(s) for {host}", pins.len());
+        }
+        watch = watch.with_pins(host, pins);
+    }
+    Some(watch)
+}
+
+/// Returns the resolver for `[http] dns_servers`; `None` uses the system's.
+fn nameserver_resolver(http: &HttpSettings) -> Option<NameserverResolver> {
+    if http.dns_servers.is_empty() {
+        return None;
+    }
+    let servers = NameserverList::new(http.dns_servers.iter().copied());
+    Some(NameserverResolver::new(servers))
+}
+
+/// Creates the connectivity probe consulted before each delivery to `url`,
+/// if enabled.
+fn create_probe(config: &ValidatedConfig, url: Url) -> Option<AppProbe> {
+    // Recording sends nothing, so there is no host to wait for
+    if config.record_payloads.is_some() {
+        return None;
+    }
+    match config.webhook.connectivity_check {
+        ConnectivityCheck::Disabled => None,
+        ConnectivityCheck::AddressFamily => {
+            tracing::info!(
+                "Deliveries postponed while {} cannot reach {}",
+                config.ip_version,
+                url.host_str().unwrap_or("the webhook host")
+            );
+            Some(FamilyProbe::with_resolver(
+                PlatformFetcher::new(),
+                nameserver_resolver(&config.http),
+                url,
+                config.ip_version,
+            ))
+        }
+    }
+}
+
+/// Builds identifying request headers from configuration.
+fn create_metadata(config: &ValidatedConfig) -> RequestMetadata {
+    let metadata = RequestMetadata::new(config.http.user_agent.clone());
+    if !config.http.metadata_headers {
+        return metadata;
+    }
+
+    let hostname = gethostname::gethostname();
+    metadata.with_identity(&hostname.to_string_lossy())
+}
@@ -0,0 +1,190 @@
+//! Tests for constructing the webhook senders.
+
+use super::senders::{create_webhook, create_webhooks};
+use ddns_a::config::{Cli, TomlConfig, ValidatedConfig};
+use ddns_a::monitor::IpChange;
+
+#[test]
+fn creates_webhook_with_url() {
+    let cli = Cli::parse_from_iter([
+        "ddns-a",
+        "--url",
+        "https://example.com/webhook",
+        "--ip-version",
+        "ipv4",
+    ]);
+    let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+    let webhook = create_webhook(&config, None).unwrap();
+
+    assert_eq!(webhook.url().as_str(), "https://example.com/webhook");
+}
+
+#[test]
+fn creates_webhook_with_method() {
+    let cli = Cli::parse_from_iter([
+        "ddns-a",
+        "--url",
+        "https://example.com/webhook",
+        "--ip-version",
+        "ipv4",
+        "--method",
+        "PUT",
+    ]);
+    let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+    let webhook = create_webhook(&config, None).unwrap();
+
+    assert_eq!(webhook.method(), http::Method::PUT);
+}
+
+#[test]
+fn creates_webhook_with_retry_policy() {
+    let cli = Cli::parse_from_iter([
+        "ddns-a",
+        "--url",
+        "https://example.com/webhook",
+        "--ip-version",
+        "ipv4",
+        "--retry-max",
+        "5",
+        "--retry-delay",
+        "10",
+    ]);
+    let config = ValidatedConfig::from_raw(&cli, None).unwrap();
+    let webhook = create_webhook(&config, None).unwrap();
+
+    assert_eq!(webhook.retry_policy().max_attempts, 5);
+}
+
+#[test]
+fn main_webhook_renders_templated_url() {
+    let cli = Cli::parse_from_iter([
+        "ddns-a",
+        "--url",
+        "https://example.com/update?ip={{changes.0.address}}",
+        "--ip-version",
+        "ipv6",
+    ]);
+    let toml = TomlConfig::parse(
+        r#"
+        [webhook.ipv6]
+        url = "https://example.com/aaaa"
+    "#,
+    )
+    .unwrap();
+    let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+    let changes = [IpChange::added(
+        "eth0",
+        "2001:db8::1".parse().unwrap(),
+        std::time::SystemTime::UNIX_EPOCH,
+    )];
+
+    let webhooks = create_webhooks(&config, None);
+
+    let urls: Vec<String> = webhooks
+        .iter()
+        .map(|(_, webhook)| webhook.preview(&changes).unwrap()[0].url.to_string())
+        .collect();
+    // Version URLs replace the main URL, template included
+    assert_eq!(
+        urls,
+        [
+            "https://example.com/update?ip=2001%3Adb8%3A%3A1",
+            "https://example.com/aaaa"
+        ]
+    );
+}
+
+fn config(extra: &[&str]) -> ValidatedConfig {
+    let mut args = vec!["ddns-a", "--url", "https://example.com/webhook"];
+    args.extend(["--ip-version", "ipv4"]);
+    args.extend(extra);
+    let toml = TomlConfig::parse(
+        r#"
+        [[webhook.targets]]
+        name = "backup"
+        url = "https://backup.example.com/"
+        method = "PUT"
+        retry = { max_attempts = 7 }
+    "#,
+    )
+    .unwrap();
+    ValidatedConfig::from_raw(&Cli::parse_from_iter(args), Some(&toml)).unwrap()
+}
+
+#[test]
+fn main_webhook_comes_first() {
+    let webhooks = create_webhooks(&config(&[]), None);
+
+    let names: Vec<_> = webhooks.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["webhook", "backup"]);
+    let backup = &webhooks[1].1;
+    assert_eq!(backup.url().as_str(), "https://backup.example.com/");
+    assert_eq!(backup.method(), http::Method::PUT);
+    assert_eq!(backup.retry_policy().max_attempts, 7);
+}
+
+#[test]
+fn version_urls_share_the_main_settings() {
+    let cli = Cli::parse_from_iter([
+        "ddns-a",
+        "--url",
+        "https://example.com/webhook",
+        "--ip-version",
+        "both",
+        "--method",
+        "PUT",
+    ]);
+    let toml = TomlConfig::parse(
+        r#"
+        [webhook.ipv6]
+        url = "https://example.com/aaaa"
+        [[webhook.targets]]
+        url = "https://backup.example.com/"
+    "#,
+    )
+    .unwrap();
+    let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+    let webhooks = create_webhooks(&config, None);
+
+    let names: Vec<_> = webhooks.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["webhook", "webhook.ipv6", "backup.example.com"]);
+    let ipv6 = &webhooks[1].1;
+    assert_eq!(ipv6.url().as_str(), "https://example.com/aaaa");
+    assert_eq!(ipv6.method(), http::Method::PUT);
+}
+
+#[test]
+fn chats_follow_the_targets() {
+    let cli = Cli::parse_from_iter(["ddns-a", "--ip-version", "ipv4"]);
+    let toml = TomlConfig::parse(
+        r#"
+        [notify.telegram]
+        bot_token = "123:abc"
+        chat_id = "42"
+        [notify.slack]
+        webhook_url = "https://hooks.slack.com/services/T0/B0/x"
+    "#,
+    )
+    .unwrap();
+    let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+
+    let webhooks = create_webhooks(&config, None);
+
+    let names: Vec<_> = webhooks.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["notify.telegram", "notify.slack"]);
+    assert_eq!(
+        webhooks[0].1.url().as_str(),
+        "https://api.telegram.org/bot123:abc/sendMessage"
+    );
+}
+
+#[test]
+fn recording_skips_other_targets() {
+    let dir = tempfile::tempdir().unwrap();
+    let dir = dir.path().to_str().unwrap();
+
+    let webhooks = create_webhooks(&config(&["--record-payloads", dir]), None);
+
+    assert_eq!(webhooks.len(), 1);
+}
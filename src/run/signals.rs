@@ -0,0 +1,114 @@
+//! Stop signals: Ctrl+C and SIGTERM shut down, SIGUSR2 restarts, and a
+//! second signal cuts the delivery grace period short.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use tokio::signal;
+
+use super::RuntimeOptions;
+
+/// Signals that stop the monitoring loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StopSignal {
+    Shutdown,
+    Restart,
+}
+
+/// Spawns the task that triggers `shutdown` on a stop signal, then `abort`
+/// once the grace period for deliveries in progress has passed.
+///
+/// Signals trigger the token from their own task, so that a delivery in
+/// progress sees the shutdown too instead of delaying it. Shutdown requested
+/// otherwise (a service stop, the tray menu) starts the grace period as well.
+/// The returned flag is set first if the signal asked for a restart.
+///
+/// Excluded from coverage - requires OS signal handling.
+#[cfg(not(tarpaulin_include))]
+pub(super) fn spawn_signal_handler(
+    options: &RuntimeOptions,
+) -> (tokio::task::JoinHandle<()>, Arc<AtomicBool>) {
+    let restart = Arc::new(AtomicBool::new(false));
+    let (shutdown, abort) = (options.shutdown.clone(), options.abort.clone());
+    let grace = options.shutdown_grace;
+    let handle = tokio::spawn({
+        let restart = Arc::clone(&restart);
+        async move {
+            tokio::select! {
+                signal = stop_signal() => {
+                    if signal == StopSignal::Restart {
+                        restart.store(true, Ordering::SeqCst);
+                    }
+                    shutdown.trigger();
+                }
+                () = shutdown.triggered() => {}
+            }
+            if !grace.is_zero() {
+                tracing::info!(
+                    "Deliveries in progress may retry for up to {}s; stop again to cut them short",
+                    grace.as_secs()
+                );
+            }
+            grace_period(grace, stop_signal()).await;
+            abort.trigger();
+        }
+    });
+    (handle, restart)
+}
+
+/// Completes when `grace` has passed or `force` completes, whichever is
+/// first; at once if `grace` is zero.
+pub(super) async fn grace_period(grace: Duration, force: impl Future) {
+    if grace.is_zero() {
+        return;
+    }
+    tokio::select! {
+        () = tokio::time::sleep(grace) => {}
+        _ = force => tracing::info!("Stop signal received again, cutting deliveries short"),
+    }
+}
+
+/// Returns a future that completes when a shutdown or restart signal is
+/// received.
+///
+/// Excluded from coverage - requires OS signal handling.
+#[cfg(not(tarpaulin_include))]
+async fn stop_signal() -> StopSignal {
+    let ctrl_c = async {
+        signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    #[cfg(unix)]
+    let restart = async {
+        signal::unix::signal(signal::unix::SignalKind::user_defined2())
+            .expect("Failed to install SIGUSR2 handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let restart = std::future::pending::<()>();
+
+    tokio::select! {
+        () = ctrl_c => StopSignal::Shutdown,
+        () = terminate => StopSignal::Shutdown,
+        () = restart => {
+            tracing::info!("Restart signal received, handing over to the new process...");
+            StopSignal::Restart
+        }
+    }
+}
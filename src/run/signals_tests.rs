@@ -0,0 +1,36 @@
+//! Tests for the stop signal handling.
+
+use std::time::Duration;
+
+use super::signals::grace_period;
+
+#[tokio::test(start_paused = true)]
+async fn zero_grace_ends_at_once() {
+    let start = tokio::time::Instant::now();
+
+    grace_period(Duration::ZERO, std::future::pending::<()>()).await;
+
+    assert_eq!(start.elapsed(), Duration::ZERO);
+}
+
+#[tokio::test(start_paused = true)]
+async fn waits_for_the_grace_period() {
+    let start = tokio::time::Instant::now();
+
+    grace_period(Duration::from_secs(30), std::future::pending::<()>()).await;
+
+    assert_eq!(start.elapsed(), Duration::from_secs(30));
+}
+
+#[tokio::test(start_paused = true)]
+async fn second_signal_cuts_it_short() {
+    let start = tokio::time::Instant::now();
+
+    grace_period(
+        Duration::from_secs(30),
+        tokio::time::sleep(Duration::from_secs(5)),
+    )
+    .await;
+
+    assert_eq!(start.elapsed(), Duration::from_secs(5));
+}
@@ -0,0 +1,101 @@
+//! The `test-webhook` smoke test of the configured targets.
+
+use std::time::SystemTime;
+
+use ddns_a::config::{MAIN_TARGET, ValidatedConfig};
+use ddns_a::webhook::{TargetReport, format_report_table, run_smoke_test, synthetic_changes};
+
+use super::action::with_actions;
+use super::senders::{create_webhooks, main_url, start_endpoint_discovery};
+
+/// The targets `test-webhook` sends to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TargetSelection {
+    /// The main `[webhook]` only.
+    Main,
+    /// Every configured target (`--all`).
+    All,
+    /// The targets with these names (`--target`), in configuration order.
+    Named(Vec<String>),
+}
+
+impl TargetSelection {
+    /// Selects from the `--all` and `--target` flags: the main webhook
+    /// unless either is given.
+    pub fn new(all: bool, targets: &[String]) -> Self {
+        if all {
+            Self::All
+        } else if targets.is_empty() {
+            Self::Main
+        } else {
+            Self::Named(targets.to_vec())
+        }
+    }
+}
+
+/// Keeps the `targets` that `selection` names, in their order.
+///
+/// # Errors
+///
+/// Returns a message if a named target (or the main webhook) is not
+/// configured, listing the configured names.
+pub(super) fn select_targets<T>(
+    targets: Vec<(String, T)>,
+    selection: &TargetSelection,
+) -> Result<Vec<(String, T)>, String> {
+    let configured = || {
+        let names: Vec<_> = targets.iter().map(|(name, _)| name.as_str()).collect();
+        if names.is_empty() {
+            "none".to_string()
+        } else {
+            names.join(", ")
+        }
+    };
+    let wanted: Vec<&str> = match selection {
+        TargetSelection::All => return Ok(targets),
+        TargetSelection::Main => vec![MAIN_TARGET],
+        TargetSelection::Named(names) => names.iter().map(String::as_str).collect(),
+    };
+    if let Some(missing) = wanted
+        .iter()
+        .find(|name| !targets.iter().any(|(target, _)| target == *name))
+    {
+        return Err(format!(
+            "No target named '{missing}' is configured (configured: {}); use --all to test every target",
+            configured()
+        ));
+    }
+    Ok(targets
+        .into_iter()
+        .filter(|(name, _)| wanted.contains(&name.as_str()))
+        .collect())
+}
+
+/// Sends a synthetic change to the `selection` of the configured targets
+/// and prints a report table.
+///
+/// Connectivity gating is bypassed so the report reflects an actual delivery.
+/// Returns true if every selected target succeeded.
+///
+/// # Errors
+///
+/// Returns a message if a selected target is not configured; nothing is
+/// sent then.
+///
+/// Excluded from coverage - performs real HTTP requests.
+#[cfg(not(tarpaulin_include))]
+pub async fn test_webhooks(
+    config: &ValidatedConfig,
+    selection: &TargetSelection,
+) -> Result<bool, String> {
+    let webhooks = create_webhooks(config, None);
+    start_endpoint_discovery(config, main_url(&webhooks), false).await;
+    let targets = select_targets(with_actions(webhooks, config), selection)?;
+    let changes = synthetic_changes(config.ip_version, SystemTime::now());
+
+    let targets: Vec<_> = targets.iter().map(|(name, t)| (name.as_str(), t)).collect();
+    let reports = run_smoke_test(&targets, &changes).await;
+    print!("{}", format_report_table(&reports));
+
+    Ok(reports.iter().all(TargetReport::is_success))
+}
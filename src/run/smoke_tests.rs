@@ -0,0 +1,75 @@
+//! Tests for selecting the targets of `test-webhook`.
+
+use super::smoke::{TargetSelection, select_targets};
+
+fn targets() -> Vec<(String, u8)> {
+    ["webhook", "backup", "telegram", "action.exec"]
+        .into_iter()
+        .zip(0..)
+        .map(|(name, id)| (name.to_string(), id))
+        .collect()
+}
+
+fn names(selected: &[(String, u8)]) -> Vec<&str> {
+    selected.iter().map(|(name, _)| name.as_str()).collect()
+}
+
+#[test]
+fn flags_select_main_all_or_named() {
+    let named = vec!["backup".to_string()];
+
+    assert_eq!(TargetSelection::new(false, &[]), TargetSelection::Main);
+    assert_eq!(TargetSelection::new(true, &[]), TargetSelection::All);
+    assert_eq!(
+        TargetSelection::new(false, &named),
+        TargetSelection::Named(named)
+    );
+}
+
+#[test]
+fn main_selects_only_the_main_webhook() {
+    let selected = select_targets(targets(), &TargetSelection::Main).unwrap();
+
+    assert_eq!(selected, [("webhook".to_string(), 0)]);
+}
+
+#[test]
+fn all_selects_every_target() {
+    let selected = select_targets(targets(), &TargetSelection::All).unwrap();
+
+    assert_eq!(selected, targets());
+}
+
+#[test]
+fn named_targets_keep_configuration_order() {
+    let selection = TargetSelection::Named(vec!["action.exec".into(), "backup".into()]);
+
+    let selected = select_targets(targets(), &selection).unwrap();
+
+    assert_eq!(names(&selected), ["backup", "action.exec"]);
+}
+
+#[test]
+fn unknown_target_lists_configured_names() {
+    let selection = TargetSelection::Named(vec!["backup".into(), "missing".into()]);
+
+    let error = select_targets(targets(), &selection).unwrap_err();
+
+    assert!(error.contains("'missing'"), "{error}");
+    assert!(
+        error.contains("webhook, backup, telegram, action.exec"),
+        "{error}"
+    );
+}
+
+#[test]
+fn missing_main_webhook_suggests_all() {
+    let only_mqtt = vec![("mqtt".to_string(), 0)];
+
+    let error = select_targets(only_mqtt, &TargetSelection::Main).unwrap_err();
+
+    assert!(
+        error.contains("'webhook'") && error.contains("--all"),
+        "{error}"
+    );
+}
@@ -0,0 +1,181 @@
+//! Change detection across restarts.
+//!
+//! On startup the current addresses are compared with the saved snapshots,
+//! and changes made while ddns-a was stopped are delivered, after the retry
+//! queue and those the last shutdown interrupted, before the new snapshots
+//! are saved. Changes the webhooks already acknowledged are skipped.
+//!
+//! A state file without acknowledged addresses (written before they were
+//! recorded, or new) starts from the saved snapshots, which the last run
+//! notified, or else from the current addresses.
+
+use std::time::SystemTime;
+
+use ddns_a::monitor::{IpChange, diff, filter_by_version};
+use ddns_a::network::{AdapterSnapshot, AddressFetcher, IpVersion};
+use ddns_a::report::RunStats;
+use ddns_a::state::{Acknowledged, BatchId, FileStateStore, LoadResult, StateStore};
+use ddns_a::webhook::WebhookSender;
+
+use super::outbox::{Delivery, keep_undelivered, set_acknowledged, suppressed_changes};
+use super::polling::{handle_changes, notified_changes, record_changes};
+use super::queue::{enqueue, retry_queued};
+use super::{AppFetcher, RunError, RuntimeOptions};
+
+/// Detects and handles IP changes that occurred while the program was stopped.
+///
+/// Compares the current network state with the previously saved state.
+/// If changes are detected, sends a webhook notification.
+///
+/// Excluded from coverage - requires platform APIs.
+#[cfg(not(tarpaulin_include))]
+pub(super) async fn startup_change_detection<W: WebhookSender>(
+    store: &FileStateStore,
+    fetcher: &AppFetcher,
+    webhook: &W,
+    options: &RuntimeOptions,
+    stats: &RunStats,
+) -> Result<(), RunError> {
+    // Fetch current network state
+    let current = fetcher.fetch().map_err(RunError::InitialFetch)?;
+    stats.record_snapshot(&current, options.ip_version);
+
+    // Changes the last shutdown interrupted come first, then those since
+    let mut batch = store.undelivered();
+    if !batch.is_empty() {
+        tracing::info!(
+            "Delivering {} change(s) interrupted by the last shutdown",
+            batch.len()
+        );
+    }
+    let detected = detect_startup_changes(store, &current, options.ip_version);
+    options.changes.publish(&detected);
+    let mut acknowledged = acknowledged_or_seeded(store, &current);
+    // The webhooks are never told of suppressed changes
+    acknowledged.record(&suppressed_changes(&detected, options.notify_on));
+    batch.extend(notified_changes(detected, options.notify_on).unwrap_or_default());
+    batch = unacknowledged_since_last_run(&acknowledged, batch);
+    // Recorded before the queue is retried, which acknowledges its changes
+    set_acknowledged(store, &acknowledged).await;
+    let queue_empty = retry_queued(webhook, options, Some(store), stats).await;
+
+    // Handle any detected changes
+    let mut delivery = Delivery::NotDelivered;
+    if batch.is_empty() {
+        tracing::debug!("No IP changes detected since last run");
+    } else if let Some(id) = undelivered_batch(store, &batch) {
+        tracing::info!("Detected {} change(s) since last run", batch.len());
+        delivery = if queue_empty {
+            handle_changes(&batch, webhook, options.dry_run, stats).await
+        } else {
+            // Queued changes come first
+            record_changes(&batch, stats);
+            Delivery::Failed
+        };
+        if delivery == Delivery::Delivered {
+            // Lets a restart before the save below recognize this batch
+            if let Err(e) = store.record_delivered(&id).await {
+                tracing::warn!("Failed to record delivered batch: {e}");
+            }
+            acknowledged = store.acknowledged().unwrap_or(acknowledged);
+            acknowledged.record(&batch);
+            set_acknowledged(store, &acknowledged).await;
+        }
+    } else {
+        tracing::info!(
+            "Skipping {} change(s) since last run: already delivered before the restart",
+            batch.len()
+        );
+    }
+
+    // Save current state (optimistic save - before webhook result matters)
+    // This ensures the state reflects the actual current IPs
+    if let Err(e) = store.save(&current).await {
+        tracing::error!("Failed to save state: {e}");
+        return Err(RunError::StateSave(e));
+    }
+    // A new or unreadable state file could not take them before the save
+    if store.acknowledged().is_none() {
+        set_acknowledged(store, &acknowledged).await;
+    }
+
+    // Saving emptied the outbox
+    match delivery {
+        Delivery::Interrupted => keep_undelivered(Some(store), &batch).await,
+        Delivery::Failed => enqueue(options, Some(store), &batch).await,
+        Delivery::Delivered | Delivery::NotDelivered => {}
+    }
+
+    Ok(())
+}
+
+/// Returns the addresses the webhooks acknowledged, starting from the
+/// saved snapshots, or else `current`, if none were recorded.
+pub(super) fn acknowledged_or_seeded(
+    store: &impl StateStore,
+    current: &[AdapterSnapshot],
+) -> Acknowledged {
+    store.acknowledged().unwrap_or_else(|| match store.load() {
+        LoadResult::Loaded(saved) => Acknowledged::from_snapshots(&saved),
+        LoadResult::NotFound | LoadResult::Corrupted { .. } => {
+            Acknowledged::from_snapshots(current)
+        }
+    })
+}
+
+/// Returns the `changes` the webhooks have not acknowledged yet.
+fn unacknowledged_since_last_run(
+    acknowledged: &Acknowledged,
+    changes: Vec<IpChange>,
+) -> Vec<IpChange> {
+    let count = changes.len();
+    let pending = acknowledged.pending(changes);
+    if pending.len() < count {
+        tracing::info!(
+            "Skipping {} change(s) since last run: the webhooks already acknowledged them",
+            count - pending.len()
+        );
+    }
+    pending
+}
+
+/// Returns the id of the `changes` batch, or `None` if it was delivered
+/// since the saved snapshots, i.e. just before a restart.
+pub(super) fn undelivered_batch(store: &impl StateStore, changes: &[IpChange]) -> Option<BatchId> {
+    let id = BatchId::of(changes);
+    (!store.delivered_batches().contains(&id)).then_some(id)
+}
+
+/// Compares current network state with saved state and returns changes.
+fn detect_startup_changes(
+    store: &impl StateStore,
+    current: &[AdapterSnapshot],
+    ip_version: IpVersion,
+) -> Vec<IpChange> {
+    detect_startup_changes_with_timestamp(store, current, ip_version, SystemTime::now())
+}
+
+/// Compares current network state with saved state and returns changes.
+///
+/// This variant accepts a timestamp for testability.
+pub(super) fn detect_startup_changes_with_timestamp(
+    store: &impl StateStore,
+    current: &[AdapterSnapshot],
+    ip_version: IpVersion,
+    timestamp: SystemTime,
+) -> Vec<IpChange> {
+    match store.load() {
+        LoadResult::Loaded(saved) => {
+            let changes = diff(&saved, current, timestamp);
+            filter_by_version(changes, ip_version)
+        }
+        LoadResult::NotFound => {
+            tracing::info!("No previous state found, starting fresh");
+            vec![]
+        }
+        LoadResult::Corrupted { reason } => {
+            tracing::warn!("State file corrupted ({reason}), will overwrite on next save");
+            vec![]
+        }
+    }
+}
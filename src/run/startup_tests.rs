@@ -0,0 +1,262 @@
+//! Tests for change detection across restarts.
+
+use super::startup::{
+    acknowledged_or_seeded, detect_startup_changes_with_timestamp, undelivered_batch,
+};
+use ddns_a::monitor::IpChange;
+use ddns_a::network::{AdapterKind, AdapterSnapshot, IpVersion};
+use ddns_a::state::{Acknowledged, BatchId, LoadResult, StateError, StateStore};
+use std::net::Ipv4Addr;
+use std::time::SystemTime;
+
+/// Local mock for testing `detect_startup_changes`.
+struct MockStateStore {
+    load_result: LoadResult,
+    delivered: Vec<BatchId>,
+    acknowledged: Option<Acknowledged>,
+}
+
+impl MockStateStore {
+    fn with_loaded(snapshots: Vec<AdapterSnapshot>) -> Self {
+        Self {
+            load_result: LoadResult::Loaded(snapshots),
+            delivered: Vec::new(),
+            acknowledged: None,
+        }
+    }
+
+    fn not_found() -> Self {
+        Self {
+            load_result: LoadResult::NotFound,
+            delivered: Vec::new(),
+            acknowledged: None,
+        }
+    }
+
+    fn corrupted(reason: impl Into<String>) -> Self {
+        Self {
+            load_result: LoadResult::Corrupted {
+                reason: reason.into(),
+            },
+            delivered: Vec::new(),
+            acknowledged: None,
+        }
+    }
+}
+
+impl StateStore for MockStateStore {
+    fn load(&self) -> LoadResult {
+        self.load_result.clone()
+    }
+
+    async fn save(&self, _snapshots: &[AdapterSnapshot]) -> Result<(), StateError> {
+        Ok(())
+    }
+
+    fn delivered_batches(&self) -> Vec<BatchId> {
+        self.delivered.clone()
+    }
+
+    fn acknowledged(&self) -> Option<Acknowledged> {
+        self.acknowledged.clone()
+    }
+}
+
+fn snapshot_with_ipv4(name: &str, ip: &str) -> AdapterSnapshot {
+    AdapterSnapshot::new(
+        name,
+        AdapterKind::Ethernet,
+        vec![ip.parse::<Ipv4Addr>().unwrap()],
+        vec![],
+    )
+}
+
+#[test]
+fn returns_empty_when_no_previous_state() {
+    let store = MockStateStore::not_found();
+    let current = vec![snapshot_with_ipv4("eth0", "192.168.1.1")];
+
+    let changes = detect_startup_changes_with_timestamp(
+        &store,
+        &current,
+        IpVersion::Both,
+        SystemTime::UNIX_EPOCH,
+    );
+
+    assert!(changes.is_empty());
+}
+
+#[test]
+fn returns_empty_when_state_corrupted() {
+    let store = MockStateStore::corrupted("test corruption");
+    let current = vec![snapshot_with_ipv4("eth0", "192.168.1.1")];
+
+    let changes = detect_startup_changes_with_timestamp(
+        &store,
+        &current,
+        IpVersion::Both,
+        SystemTime::UNIX_EPOCH,
+    );
+
+    assert!(changes.is_empty());
+}
+
+#[test]
+fn returns_empty_when_no_changes() {
+    let snapshots = vec![snapshot_with_ipv4("eth0", "192.168.1.1")];
+    let store = MockStateStore::with_loaded(snapshots.clone());
+
+    let changes = detect_startup_changes_with_timestamp(
+        &store,
+        &snapshots,
+        IpVersion::Both,
+        SystemTime::UNIX_EPOCH,
+    );
+
+    assert!(changes.is_empty());
+}
+
+#[test]
+fn detects_added_address() {
+    let saved = vec![snapshot_with_ipv4("eth0", "192.168.1.1")];
+    let current = vec![
+        snapshot_with_ipv4("eth0", "192.168.1.1"),
+        snapshot_with_ipv4("eth1", "10.0.0.1"),
+    ];
+    let store = MockStateStore::with_loaded(saved);
+
+    let changes = detect_startup_changes_with_timestamp(
+        &store,
+        &current,
+        IpVersion::Both,
+        SystemTime::UNIX_EPOCH,
+    );
+
+    assert_eq!(changes.len(), 1);
+    assert!(changes[0].is_added());
+    assert_eq!(changes[0].address.to_string(), "10.0.0.1");
+}
+
+#[test]
+fn detects_removed_address() {
+    let saved = vec![
+        snapshot_with_ipv4("eth0", "192.168.1.1"),
+        snapshot_with_ipv4("eth1", "10.0.0.1"),
+    ];
+    let current = vec![snapshot_with_ipv4("eth0", "192.168.1.1")];
+    let store = MockStateStore::with_loaded(saved);
+
+    let changes = detect_startup_changes_with_timestamp(
+        &store,
+        &current,
+        IpVersion::Both,
+        SystemTime::UNIX_EPOCH,
+    );
+
+    assert_eq!(changes.len(), 1);
+    assert!(changes[0].is_removed());
+    assert_eq!(changes[0].address.to_string(), "10.0.0.1");
+}
+
+#[test]
+fn filters_by_ip_version() {
+    use std::net::Ipv6Addr;
+
+    let saved = vec![];
+    let current = vec![AdapterSnapshot::new(
+        "eth0",
+        AdapterKind::Ethernet,
+        vec!["192.168.1.1".parse::<Ipv4Addr>().unwrap()],
+        vec!["fe80::1".parse::<Ipv6Addr>().unwrap()],
+    )];
+    let store = MockStateStore::with_loaded(saved);
+
+    // V4 only
+    let changes = detect_startup_changes_with_timestamp(
+        &store,
+        &current,
+        IpVersion::V4,
+        SystemTime::UNIX_EPOCH,
+    );
+    assert_eq!(changes.len(), 1);
+    assert!(changes[0].address.is_ipv4());
+
+    // V6 only
+    let store = MockStateStore::with_loaded(vec![]);
+    let changes = detect_startup_changes_with_timestamp(
+        &store,
+        &current,
+        IpVersion::V6,
+        SystemTime::UNIX_EPOCH,
+    );
+    assert_eq!(changes.len(), 1);
+    assert!(changes[0].address.is_ipv6());
+}
+
+#[test]
+fn undelivered_batch_returns_id_of_new_batch() {
+    let store = MockStateStore::not_found();
+    let changes = vec![IpChange::added(
+        "eth0",
+        "192.168.1.1".parse().unwrap(),
+        SystemTime::UNIX_EPOCH,
+    )];
+
+    assert_eq!(
+        undelivered_batch(&store, &changes),
+        Some(BatchId::of(&changes))
+    );
+}
+
+#[test]
+fn undelivered_batch_skips_batch_delivered_before_restart() {
+    let changes = vec![IpChange::added(
+        "eth0",
+        "192.168.1.1".parse().unwrap(),
+        SystemTime::UNIX_EPOCH,
+    )];
+    let mut store = MockStateStore::not_found();
+    store.delivered.push(BatchId::of(&changes));
+
+    // Re-detected after the restart, with a new timestamp
+    let redetected = vec![IpChange::added(
+        "eth0",
+        "192.168.1.1".parse().unwrap(),
+        SystemTime::now(),
+    )];
+    assert_eq!(undelivered_batch(&store, &redetected), None);
+}
+
+#[test]
+fn acknowledged_addresses_are_kept_when_recorded() {
+    let recorded = Acknowledged::from_snapshots(&[snapshot_with_ipv4("eth0", "10.0.0.1")]);
+    let mut store = MockStateStore::with_loaded(vec![snapshot_with_ipv4("eth0", "192.168.1.1")]);
+    store.acknowledged = Some(recorded.clone());
+
+    let current = [snapshot_with_ipv4("eth0", "192.168.1.2")];
+    assert_eq!(acknowledged_or_seeded(&store, &current), recorded);
+}
+
+#[test]
+fn acknowledged_addresses_start_from_saved_snapshots() {
+    let saved = vec![snapshot_with_ipv4("eth0", "192.168.1.1")];
+    let store = MockStateStore::with_loaded(saved.clone());
+
+    let current = [snapshot_with_ipv4("eth0", "192.168.1.2")];
+    assert_eq!(
+        acknowledged_or_seeded(&store, &current),
+        Acknowledged::from_snapshots(&saved)
+    );
+}
+
+#[test]
+fn acknowledged_addresses_start_from_current_without_saved_state() {
+    let current = [snapshot_with_ipv4("eth0", "192.168.1.2")];
+
+    for store in [MockStateStore::not_found(), MockStateStore::corrupted("x")] {
+        assert_eq!(
+            acknowledged_or_seeded(&store, &current),
+            Acknowledged::from_snapshots(&current)
+        );
+    }
+}
@@ -0,0 +1,62 @@
+//! Minimum interval between address notifications (`webhook.min_notify_interval`).
+//!
+//! Batches detected within the interval after a notification are held by a
+//! [`NotifyThrottle`] and delivered, coalesced, when it ends. Held changes
+//! still pending at shutdown go to the outbox, since their snapshot was
+//! already saved.
+
+use std::time::Instant;
+
+use ddns_a::monitor::{IpChange, NotifyThrottle};
+use ddns_a::state::StateStore;
+
+use super::outbox::keep_undelivered;
+
+/// Returns `changes` if they can be delivered now, holding them otherwise.
+pub(super) fn admit(
+    throttle: Option<&mut NotifyThrottle>,
+    changes: Vec<IpChange>,
+) -> Option<Vec<IpChange>> {
+    let Some(throttle) = throttle else {
+        return Some(changes);
+    };
+    let count = changes.len();
+    let admitted = throttle.offer(changes, Instant::now());
+    if admitted.is_none() {
+        tracing::info!(
+            "Holding {count} change(s): notifications are at least {}s apart",
+            throttle.interval().as_secs()
+        );
+    }
+    admitted
+}
+
+/// Completes at `deadline`; never if there is none.
+pub(super) async fn until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Returns the held changes once due, logging if they cancelled out.
+pub(super) fn take_due(throttle: Option<&mut NotifyThrottle>) -> Option<Vec<IpChange>> {
+    let batch = throttle?.take_due(Instant::now());
+    if batch.is_none() {
+        tracing::info!("Held changes cancelled out, nothing to notify");
+    }
+    batch
+}
+
+/// Keeps the held changes in the outbox for the next run.
+pub(super) async fn keep_held(
+    throttle: Option<&mut NotifyThrottle>,
+    store: Option<&impl StateStore>,
+) {
+    let held = throttle
+        .map(NotifyThrottle::take_pending)
+        .unwrap_or_default();
+    if !held.is_empty() {
+        keep_undelivered(store, &held).await;
+    }
+}
@@ -0,0 +1,41 @@
+//! Tests for throttling address notifications.
+
+use super::throttle::{admit, keep_held};
+use ddns_a::monitor::{IpChange, NotifyThrottle};
+use ddns_a::state::{FileStateStore, StateStore};
+use std::time::{Duration, SystemTime};
+
+fn change(address: &str) -> IpChange {
+    IpChange::added("eth0", address.parse().unwrap(), SystemTime::UNIX_EPOCH)
+}
+
+#[test]
+fn admits_everything_without_throttle() {
+    let changes = vec![change("10.0.0.1")];
+
+    assert_eq!(admit(None, changes.clone()), Some(changes));
+}
+
+#[test]
+fn holds_batch_within_interval() {
+    let mut throttle = NotifyThrottle::new(Duration::from_secs(60));
+
+    assert!(admit(Some(&mut throttle), vec![change("10.0.0.1")]).is_some());
+    assert_eq!(admit(Some(&mut throttle), vec![change("10.0.0.2")]), None);
+    assert!(throttle.deadline().is_some());
+}
+
+#[tokio::test]
+async fn held_changes_go_to_outbox_on_shutdown() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let store = FileStateStore::new(dir.path().join("state.json"));
+    store.save(&[]).await.unwrap();
+    let mut throttle = NotifyThrottle::new(Duration::from_secs(60));
+    admit(Some(&mut throttle), vec![change("10.0.0.1")]);
+    admit(Some(&mut throttle), vec![change("10.0.0.2")]);
+
+    keep_held(Some(&mut throttle), Some(&store)).await;
+
+    assert_eq!(store.undelivered(), vec![change("10.0.0.2")]);
+    assert_eq!(throttle.deadline(), None);
+}
@@ -0,0 +1,119 @@
+//! Tray icon mode (`--tray`, needs Windows and the `tray` feature).
+//!
+//! The icon shows the effective addresses and the latest delivery, and is
+//! refreshed after every address delivery. Its menu commands arrive on
+//! [`Events`](super::Events) and are handled between change batches.
+
+use std::time::SystemTime;
+
+use ddns_a::monitor::{diff, filter_by_version};
+use ddns_a::network::AdapterSnapshot;
+use ddns_a::report::RunStats;
+use ddns_a::tray::{PauseSwitch, TrayCommand};
+use ddns_a::webhook::WebhookSender;
+
+use super::RuntimeOptions;
+use super::polling::handle_changes;
+
+/// The tray icon, if one is shown.
+#[derive(Debug, Default)]
+pub(super) struct Tray {
+    #[cfg(all(windows, feature = "tray"))]
+    icon: Option<ddns_a::tray::TrayIcon>,
+}
+
+#[cfg(all(windows, feature = "tray"))]
+impl Tray {
+    /// Shows the icon with the current addresses of `fetcher`.
+    ///
+    /// A failure is logged and leaves ddns-a running without the icon.
+    pub(super) fn start(
+        commands: tokio::sync::mpsc::UnboundedSender<TrayCommand>,
+        fetcher: &impl ddns_a::network::AddressFetcher,
+        options: &RuntimeOptions,
+        stats: &RunStats,
+    ) -> Self {
+        let icon = match ddns_a::tray::TrayIcon::spawn(commands) {
+            Ok(icon) => icon,
+            Err(e) => {
+                tracing::error!("{e}");
+                return Self::default();
+            }
+        };
+        tracing::info!("Tray icon shown");
+
+        // The monitor only records addresses once they change
+        match fetcher.fetch() {
+            Ok(snapshot) => stats.record_snapshot(&snapshot, options.ip_version),
+            Err(e) => tracing::warn!("Failed to fetch addresses for the tray icon: {e}"),
+        }
+        let tray = Self { icon: Some(icon) };
+        tray.show(stats, &options.pause);
+        tray
+    }
+
+    /// Shows the current state of `stats` and `pause`.
+    pub(super) fn show(&self, stats: &RunStats, pause: &PauseSwitch) {
+        if let Some(icon) = &self.icon {
+            let summary = stats.summary(SystemTime::now());
+            icon.show(ddns_a::tray::TrayStatus::new(&summary, pause.is_paused()));
+        }
+    }
+}
+
+/// Builds without the `tray` feature, or not for Windows, reject `--tray`,
+/// so nothing is shown.
+#[cfg(not(all(windows, feature = "tray")))]
+impl Tray {
+    pub(super) fn start(
+        _commands: tokio::sync::mpsc::UnboundedSender<TrayCommand>,
+        _fetcher: &impl ddns_a::network::AddressFetcher,
+        _options: &RuntimeOptions,
+        _stats: &RunStats,
+    ) -> Self {
+        Self::default()
+    }
+
+    #[allow(clippy::unused_self)] // Same signature as with the feature
+    pub(super) const fn show(&self, _stats: &RunStats, _pause: &PauseSwitch) {}
+}
+
+/// Handles a command from the tray menu.
+///
+/// Notifying sends every monitored address of `snapshot` as added, like a
+/// first start; while paused, it is postponed like any other delivery.
+pub(super) async fn handle_tray_command<W: WebhookSender>(
+    command: TrayCommand,
+    snapshot: Option<&[AdapterSnapshot]>,
+    webhook: &W,
+    options: &RuntimeOptions,
+    stats: &RunStats,
+) {
+    match command {
+        TrayCommand::ForceNotify => {
+            let changes = snapshot.map_or_else(Vec::new, |snapshot| {
+                filter_by_version(diff(&[], snapshot, SystemTime::now()), options.ip_version)
+            });
+            if changes.is_empty() {
+                tracing::info!("Tray: no monitored address to notify");
+            } else {
+                tracing::info!("Tray: notifying {} address(es)", changes.len());
+                handle_changes(&changes, webhook, options.dry_run, stats).await;
+            }
+        }
+        TrayCommand::TogglePause => {
+            if options.pause.toggle() {
+                tracing::info!("Tray: notifications paused");
+            } else {
+                tracing::info!(
+                    "Tray: notifications resumed; postponed changes go out with the next delivery"
+                );
+            }
+        }
+        TrayCommand::Exit => {
+            tracing::info!("Tray: exit requested");
+            options.shutdown.trigger();
+        }
+    }
+    options.tray.show(stats, &options.pause);
+}
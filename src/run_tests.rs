@@ -11,6 +11,7 @@ mod run_error {
         assert_eq!(error.to_string(), "Monitor stream terminated unexpectedly");
     }
 
+    #[cfg(feature = "hybrid")]
     #[test]
     fn api_listener_creation_displays_source() {
         let api_error = ddns_a::monitor::ApiError::Stopped;
@@ -28,7 +29,7 @@ mod run_error {
 
 mod runtime_options {
     use super::*;
-    use ddns_a::config::{Cli, ValidatedConfig};
+    use ddns_a::config::{Cli, TomlConfig, ValidatedConfig};
 
     fn make_test_config() -> ValidatedConfig {
         let cli = Cli::parse_from_iter([
@@ -52,6 +53,23 @@ mod runtime_options {
         assert_eq!(options.poll_interval, std::time::Duration::from_secs(120));
     }
 
+    #[test]
+    fn from_config_extracts_debounce() {
+        let config = make_test_config();
+        let options = RuntimeOptions::from(&config);
+        assert_eq!(options.debounce, Some(DebouncePolicy::default()));
+    }
+
+    #[test]
+    fn from_config_extracts_event_quiet() {
+        let config = make_test_config();
+        let options = RuntimeOptions::from(&config);
+        assert_eq!(
+            options.event_quiet,
+            Some(std::time::Duration::from_millis(250))
+        );
+    }
+
     #[test]
     fn from_config_extracts_poll_only() {
         let config = make_test_config();
@@ -89,345 +107,152 @@ mod runtime_options {
         let options = RuntimeOptions::from(&config);
         assert_eq!(options.ip_version, ddns_a::network::IpVersion::V4);
     }
-}
-
-mod create_webhook {
-    use super::*;
-    use ddns_a::config::Cli;
-
-    #[test]
-    fn creates_webhook_with_url() {
-        let cli = Cli::parse_from_iter([
-            "ddns-a",
-            "--url",
-            "https://example.com/webhook",
-            "--ip-version",
-            "ipv4",
-        ]);
-        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
-        let webhook = create_webhook(&config);
-
-        assert_eq!(webhook.url().as_str(), "https://example.com/webhook");
-    }
-
-    #[test]
-    fn creates_webhook_with_method() {
-        let cli = Cli::parse_from_iter([
-            "ddns-a",
-            "--url",
-            "https://example.com/webhook",
-            "--ip-version",
-            "ipv4",
-            "--method",
-            "PUT",
-        ]);
-        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
-        let webhook = create_webhook(&config);
-
-        assert_eq!(webhook.method(), http::Method::PUT);
-    }
 
     #[test]
-    fn creates_webhook_with_retry_policy() {
-        let cli = Cli::parse_from_iter([
-            "ddns-a",
-            "--url",
-            "https://example.com/webhook",
-            "--ip-version",
-            "ipv4",
-            "--retry-max",
-            "5",
-            "--retry-delay",
-            "10",
-        ]);
-        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
-        let webhook = create_webhook(&config);
-
-        assert_eq!(webhook.retry_policy().max_attempts, 5);
-    }
-}
-
-mod detect_startup_changes {
-    use super::*;
-    use ddns_a::network::{AdapterKind, AdapterSnapshot, IpVersion};
-    use ddns_a::state::{LoadResult, StateError, StateStore};
-    use std::net::Ipv4Addr;
-    use std::time::SystemTime;
-
-    /// Local mock for testing `detect_startup_changes`.
-    struct MockStateStore {
-        load_result: LoadResult,
-    }
-
-    impl MockStateStore {
-        fn with_loaded(snapshots: Vec<AdapterSnapshot>) -> Self {
-            Self {
-                load_result: LoadResult::Loaded(snapshots),
-            }
-        }
-
-        fn not_found() -> Self {
-            Self {
-                load_result: LoadResult::NotFound,
-            }
-        }
-
-        fn corrupted(reason: impl Into<String>) -> Self {
-            Self {
-                load_result: LoadResult::Corrupted {
-                    reason: reason.into(),
-                },
-            }
-        }
-    }
-
-    impl StateStore for MockStateStore {
-        fn load(&self) -> LoadResult {
-            self.load_result.clone()
-        }
-
-        async fn save(&self, _snapshots: &[AdapterSnapshot]) -> Result<(), StateError> {
-            Ok(())
-        }
-    }
-
-    fn snapshot_with_ipv4(name: &str, ip: &str) -> AdapterSnapshot {
-        AdapterSnapshot::new(
-            name,
-            AdapterKind::Ethernet,
-            vec![ip.parse::<Ipv4Addr>().unwrap()],
-            vec![],
-        )
-    }
-
-    #[test]
-    fn returns_empty_when_no_previous_state() {
-        let store = MockStateStore::not_found();
-        let current = vec![snapshot_with_ipv4("eth0", "192.168.1.1")];
-
-        let changes = detect_startup_changes_with_timestamp(
-            &store,
-            &current,
-            IpVersion::Both,
-            SystemTime::UNIX_EPOCH,
+    fn from_config_extracts_notify_on() {
+        assert_eq!(
+            RuntimeOptions::from(&make_test_config()).notify_on,
+            NotifyOn::Both
         );
 
-        assert!(changes.is_empty());
+        let cli = Cli::parse_from_iter(["ddns-a", "--url", "https://example.com/hook"]);
+        let toml = TomlConfig::parse("[webhook]\nip_version = \"ipv4\"\nnotify_on = [\"removed\"]")
+            .unwrap();
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+        assert_eq!(RuntimeOptions::from(&config).notify_on, NotifyOn::Removed);
     }
 
     #[test]
-    fn returns_empty_when_state_corrupted() {
-        let store = MockStateStore::corrupted("test corruption");
-        let current = vec![snapshot_with_ipv4("eth0", "192.168.1.1")];
-
-        let changes = detect_startup_changes_with_timestamp(
-            &store,
-            &current,
-            IpVersion::Both,
-            SystemTime::UNIX_EPOCH,
+    fn adaptive_stretches_the_watchdog_interval() {
+        let options = RuntimeOptions::from(&make_test_config());
+        assert_eq!(options.adaptive, None);
+        assert_eq!(
+            options.watchdog_interval.get(),
+            std::time::Duration::from_secs(120)
         );
 
-        assert!(changes.is_empty());
-    }
-
-    #[test]
-    fn returns_empty_when_no_changes() {
-        let snapshots = vec![snapshot_with_ipv4("eth0", "192.168.1.1")];
-        let store = MockStateStore::with_loaded(snapshots.clone());
-
-        let changes = detect_startup_changes_with_timestamp(
-            &store,
-            &snapshots,
-            IpVersion::Both,
-            SystemTime::UNIX_EPOCH,
+        let cli = Cli::parse_from_iter(["ddns-a", "--url", "https://example.com/hook"]);
+        let toml = TomlConfig::parse(
+            "[webhook]\nip_version = \"ipv4\"\n[monitor]\nadaptive = true\npoll_interval_min = 30",
+        )
+        .unwrap();
+        let options = RuntimeOptions::from(&ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap());
+        assert_eq!(options.poll_interval, std::time::Duration::from_secs(30));
+        assert_eq!(
+            options.watchdog_interval.get(),
+            std::time::Duration::from_secs(300)
         );
-
-        assert!(changes.is_empty());
     }
 
     #[test]
-    fn detects_added_address() {
-        let saved = vec![snapshot_with_ipv4("eth0", "192.168.1.1")];
-        let current = vec![
-            snapshot_with_ipv4("eth0", "192.168.1.1"),
-            snapshot_with_ipv4("eth1", "10.0.0.1"),
-        ];
-        let store = MockStateStore::with_loaded(saved);
-
-        let changes = detect_startup_changes_with_timestamp(
-            &store,
-            &current,
-            IpVersion::Both,
-            SystemTime::UNIX_EPOCH,
+    fn longest_poll_interval_is_the_adaptive_maximum() {
+        let poll = std::time::Duration::from_secs(30);
+        let max = std::time::Duration::from_secs(600);
+
+        assert_eq!(longest_poll_interval(poll, None), poll);
+        assert_eq!(
+            longest_poll_interval(poll, Some(AdaptivePolicy::new(poll, max))),
+            max
         );
-
-        assert_eq!(changes.len(), 1);
-        assert!(changes[0].is_added());
-        assert_eq!(changes[0].address.to_string(), "10.0.0.1");
     }
 
     #[test]
-    fn detects_removed_address() {
-        let saved = vec![
-            snapshot_with_ipv4("eth0", "192.168.1.1"),
-            snapshot_with_ipv4("eth1", "10.0.0.1"),
-        ];
-        let current = vec![snapshot_with_ipv4("eth0", "192.168.1.1")];
-        let store = MockStateStore::with_loaded(saved);
-
-        let changes = detect_startup_changes_with_timestamp(
-            &store,
-            &current,
-            IpVersion::Both,
-            SystemTime::UNIX_EPOCH,
-        );
+    fn circuits_only_if_configured() {
+        let config = make_test_config();
+        assert!(RuntimeOptions::from(&config).circuits.is_none());
 
-        assert_eq!(changes.len(), 1);
-        assert!(changes[0].is_removed());
-        assert_eq!(changes[0].address.to_string(), "10.0.0.1");
-    }
+        let cli = Cli::parse_from_iter(["ddns-a", "--url", "https://example.com/hook"]);
+        let toml = TomlConfig::parse("[webhook]\nip_version = \"ipv4\"\n[webhook.circuit_breaker]")
+            .unwrap();
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+        assert!(RuntimeOptions::from(&config).circuits.is_some());
 
-    #[test]
-    fn filters_by_ip_version() {
-        use std::net::Ipv6Addr;
-
-        let saved = vec![];
-        let current = vec![AdapterSnapshot::new(
-            "eth0",
-            AdapterKind::Ethernet,
-            vec!["192.168.1.1".parse::<Ipv4Addr>().unwrap()],
-            vec!["fe80::1".parse::<Ipv6Addr>().unwrap()],
-        )];
-        let store = MockStateStore::with_loaded(saved);
-
-        // V4 only
-        let changes = detect_startup_changes_with_timestamp(
-            &store,
-            &current,
-            IpVersion::V4,
-            SystemTime::UNIX_EPOCH,
-        );
-        assert_eq!(changes.len(), 1);
-        assert!(changes[0].address.is_ipv4());
-
-        // V6 only
-        let store = MockStateStore::with_loaded(vec![]);
-        let changes = detect_startup_changes_with_timestamp(
-            &store,
-            &current,
-            IpVersion::V6,
-            SystemTime::UNIX_EPOCH,
-        );
-        assert_eq!(changes.len(), 1);
-        assert!(changes[0].address.is_ipv6());
+        let toml = TomlConfig::parse(
+            "[webhook]\nip_version = \"ipv4\"\n[[webhook.targets]]\nurl = \"https://a.example/\"\n[webhook.targets.circuit_breaker]",
+        )
+        .unwrap();
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
+        assert!(RuntimeOptions::from(&config).circuits.is_some());
     }
 }
 
-mod handle_changes {
+mod shutdown_grace {
     use super::*;
-    use ddns_a::monitor::IpChange;
-    use std::net::IpAddr;
-    use std::sync::atomic::{AtomicUsize, Ordering};
-    use std::time::SystemTime;
-
-    /// Mock webhook sender for testing.
-    struct MockWebhook {
-        send_count: AtomicUsize,
-        should_fail: bool,
-    }
+    use ddns_a::config::{Cli, TomlConfig, ValidatedConfig};
+    use std::time::Duration;
 
-    impl MockWebhook {
-        fn new() -> Self {
-            Self {
-                send_count: AtomicUsize::new(0),
-                should_fail: false,
-            }
-        }
-
-        fn failing() -> Self {
-            Self {
-                send_count: AtomicUsize::new(0),
-                should_fail: true,
-            }
-        }
-
-        fn send_count(&self) -> usize {
-            self.send_count.load(Ordering::SeqCst)
-        }
-    }
+    #[test]
+    fn options_carry_the_grace_and_a_separate_abort_token() {
+        let cli = Cli::parse_from_iter(["ddns-a", "--url", "https://example.com/hook"]);
+        let toml =
+            TomlConfig::parse("[webhook]\nip_version = \"ipv4\"\n[retry]\nshutdown_grace = 20")
+                .unwrap();
+        let config = ValidatedConfig::from_raw(&cli, Some(&toml)).unwrap();
 
-    impl ddns_a::webhook::WebhookSender for MockWebhook {
-        async fn send(&self, _changes: &[IpChange]) -> Result<(), ddns_a::webhook::WebhookError> {
-            self.send_count.fetch_add(1, Ordering::SeqCst);
-            if self.should_fail {
-                Err(ddns_a::webhook::WebhookError::Retryable(
-                    ddns_a::webhook::RetryableError::Http(ddns_a::webhook::HttpError::Timeout),
-                ))
-            } else {
-                Ok(())
-            }
-        }
-    }
+        let options = RuntimeOptions::from(&config);
+        options.shutdown.trigger();
 
-    fn make_change() -> IpChange {
-        IpChange::added(
-            "eth0",
-            "192.168.1.100".parse::<IpAddr>().unwrap(),
-            SystemTime::UNIX_EPOCH,
-        )
+        assert_eq!(options.shutdown_grace, Duration::from_secs(20));
+        assert!(!options.abort.is_triggered());
     }
+}
 
-    #[tokio::test]
-    async fn sends_webhook_when_not_dry_run() {
-        let webhook = MockWebhook::new();
-        let changes = vec![make_change()];
-
-        handle_changes(&changes, &webhook, false).await;
+mod lock_state_file {
+    use super::*;
+    use ddns_a::config::{Cli, ValidatedConfig};
+    use ddns_a::state::StateError;
 
-        assert_eq!(webhook.send_count(), 1);
+    fn config(state_file: &std::path::Path, extra: &[&str]) -> ValidatedConfig {
+        let mut args = vec![
+            "ddns-a",
+            "--url",
+            "https://example.com/hook",
+            "--ip-version",
+            "ipv4",
+            "--state-file",
+            state_file.to_str().unwrap(),
+        ];
+        args.extend_from_slice(extra);
+        ValidatedConfig::from_raw(&Cli::parse_from_iter(args), None).unwrap()
     }
 
-    #[tokio::test]
-    async fn skips_webhook_in_dry_run() {
-        let webhook = MockWebhook::new();
-        let changes = vec![make_change()];
-
-        handle_changes(&changes, &webhook, true).await;
+    #[test]
+    fn no_lock_without_state_file() {
+        let cli = Cli::parse_from_iter([
+            "ddns-a",
+            "--url",
+            "https://example.com/hook",
+            "--ip-version",
+            "ipv4",
+        ]);
+        let config = ValidatedConfig::from_raw(&cli, None).unwrap();
 
-        assert_eq!(webhook.send_count(), 0);
+        assert!(lock_state_file(&config).unwrap().is_none());
     }
 
-    #[tokio::test]
-    async fn handles_webhook_failure_gracefully() {
-        let webhook = MockWebhook::failing();
-        let changes = vec![make_change()];
-
-        // Should not panic
-        handle_changes(&changes, &webhook, false).await;
-
-        assert_eq!(webhook.send_count(), 1);
+    #[test]
+    fn second_instance_is_rejected() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = config(&dir.path().join("state.json"), &[]);
+        let _lock = lock_state_file(&config).unwrap();
+
+        let error = lock_state_file(&config).unwrap_err();
+
+        assert!(matches!(
+            error,
+            RunError::StateLock(StateError::Locked { .. })
+        ));
+        assert!(error.to_string().contains("--no-lock"));
     }
 
-    #[tokio::test]
-    async fn handles_multiple_changes() {
-        let webhook = MockWebhook::new();
-        let changes = vec![
-            IpChange::added(
-                "eth0",
-                "192.168.1.1".parse().unwrap(),
-                SystemTime::UNIX_EPOCH,
-            ),
-            IpChange::removed(
-                "eth0",
-                "192.168.1.2".parse().unwrap(),
-                SystemTime::UNIX_EPOCH,
-            ),
-            IpChange::added("wlan0", "10.0.0.1".parse().unwrap(), SystemTime::UNIX_EPOCH),
-        ];
+    #[test]
+    fn no_lock_skips_the_lock() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let state_file = dir.path().join("state.json");
+        let _lock = lock_state_file(&config(&state_file, &[])).unwrap();
 
-        handle_changes(&changes, &webhook, false).await;
+        let lock = lock_state_file(&config(&state_file, &["--no-lock"])).unwrap();
 
-        // All changes sent in single batch
-        assert_eq!(webhook.send_count(), 1);
+        assert!(lock.is_none());
     }
 }
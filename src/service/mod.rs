@@ -0,0 +1,65 @@
+//! Native Windows service integration (`ddns-a service`).
+//!
+//! `service install` registers the running executable with the service
+//! control manager (SCM), to be started as `ddns-a --config <file> service
+//! run`; [`launch_arguments`] builds that command line. Under the SCM,
+//! [`run`] reports the service state and turns stop and system shutdown
+//! requests into the application's [`ShutdownToken`](crate::time::ShutdownToken),
+//! so pending changes are saved as on Ctrl+C.
+//!
+//! The SCM calls only need Windows; the rest is shared so it can be tested
+//! everywhere.
+
+#[cfg(windows)]
+mod windows;
+
+#[cfg(test)]
+#[path = "mod_tests.rs"]
+mod tests;
+
+#[cfg(windows)]
+pub use windows::{install, run, uninstall};
+
+use std::ffi::OsString;
+use std::path::Path;
+
+use thiserror::Error;
+
+/// Name of the service in the SCM (`sc start ddns-a`).
+pub const SERVICE_NAME: &str = "ddns-a";
+
+/// Name shown in the Services console.
+pub const DISPLAY_NAME: &str = "DDNS-A Address Monitor";
+
+/// Description shown in the Services console.
+pub const DESCRIPTION: &str =
+    "Monitors IP address changes on network adapters and notifies webhooks.";
+
+/// Errors that can occur while installing, removing or running the service.
+#[derive(Debug, Error)]
+pub enum ServiceError {
+    /// The service could not be registered.
+    #[error("Failed to install the service: {0}")]
+    Install(String),
+
+    /// The service could not be stopped or removed.
+    #[error("Failed to uninstall the service: {0}")]
+    Uninstall(String),
+
+    /// The process was not started by the SCM, or could not report to it.
+    #[error("Failed to run as a service: {0}")]
+    Run(String),
+}
+
+/// Returns the arguments the SCM starts the service with: the absolute
+/// configuration file, since services start in the system directory, and
+/// `service run`.
+#[must_use]
+pub fn launch_arguments(config_file: &Path) -> Vec<OsString> {
+    vec![
+        OsString::from("--config"),
+        config_file.as_os_str().to_owned(),
+        OsString::from("service"),
+        OsString::from("run"),
+    ]
+}
@@ -0,0 +1,51 @@
+//! Tests for the service command line.
+
+use super::*;
+use std::path::PathBuf;
+
+#[test]
+fn launch_arguments_pass_config_before_subcommand() {
+    let config = PathBuf::from("C:\\ProgramData\\ddns-a\\config.toml");
+
+    let args = launch_arguments(&config);
+
+    assert_eq!(
+        args,
+        [
+            "--config",
+            "C:\\ProgramData\\ddns-a\\config.toml",
+            "service",
+            "run"
+        ]
+    );
+}
+
+#[test]
+fn launch_arguments_parse_as_service_run() {
+    use crate::config::{Cli, Command, ServiceCommand};
+
+    let mut args = vec![OsString::from("ddns-a")];
+    args.extend(launch_arguments(Path::new("/etc/ddns-a/config.toml")));
+    let cli = Cli::parse_from_iter(args);
+
+    assert_eq!(
+        cli.config.as_deref(),
+        Some(Path::new("/etc/ddns-a/config.toml"))
+    );
+    assert!(matches!(
+        cli.command,
+        Some(Command::Service {
+            command: ServiceCommand::Run
+        })
+    ));
+}
+
+#[test]
+fn errors_name_the_step() {
+    let error = ServiceError::Install("access denied".to_string());
+
+    assert_eq!(
+        error.to_string(),
+        "Failed to install the service: access denied"
+    );
+}
@@ -0,0 +1,181 @@
+//! Windows SCM calls through the `windows-service` crate.
+
+use std::ffi::OsString;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use windows_service::service::{
+    ServiceAccess, ServiceControl, ServiceControlAccept, ServiceErrorControl, ServiceExitCode,
+    ServiceInfo, ServiceStartType, ServiceState, ServiceStatus, ServiceType,
+};
+use windows_service::service_control_handler::{
+    self, ServiceControlHandlerResult, ServiceStatusHandle,
+};
+use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+use windows_service::{define_windows_service, service_dispatcher};
+
+use crate::time::ShutdownToken;
+
+use super::{DESCRIPTION, DISPLAY_NAME, SERVICE_NAME, ServiceError, launch_arguments};
+
+/// How long the SCM waits after a stop request before giving up on the
+/// service; covers saving the state file and cutting deliveries short.
+const STOP_WAIT_HINT: Duration = Duration::from_secs(15);
+
+/// Exit code reported when the application failed.
+const FAILURE_EXIT_CODE: u32 = 2;
+
+/// The application the SCM's service thread runs; returns an error message
+/// on failure.
+type App = Box<dyn FnOnce(ShutdownToken) -> Result<(), String> + Send>;
+
+/// Handed from [`run`] to the service thread, which the SCM starts itself.
+static APP: Mutex<Option<App>> = Mutex::new(None);
+
+define_windows_service!(ffi_service_main, service_main);
+
+/// Registers `executable` as an automatically started service reading
+/// `config_file`.
+///
+/// # Errors
+///
+/// Returns [`ServiceError::Install`] if the SCM refuses, e.g. without
+/// administrator rights or if the service already exists.
+pub fn install(executable: &Path, config_file: &Path) -> Result<(), ServiceError> {
+    let install_error = |e: windows_service::Error| ServiceError::Install(e.to_string());
+
+    let access = ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE;
+    let manager = ServiceManager::local_computer(None::<&str>, access).map_err(install_error)?;
+    let info = ServiceInfo {
+        name: OsString::from(SERVICE_NAME),
+        display_name: OsString::from(DISPLAY_NAME),
+        service_type: ServiceType::OWN_PROCESS,
+        start_type: ServiceStartType::AutoStart,
+        error_control: ServiceErrorControl::Normal,
+        executable_path: executable.to_path_buf(),
+        launch_arguments: launch_arguments(config_file),
+        dependencies: Vec::new(),
+        account_name: None,
+        account_password: None,
+    };
+    let service = manager
+        .create_service(&info, ServiceAccess::CHANGE_CONFIG)
+        .map_err(install_error)?;
+    service.set_description(DESCRIPTION).map_err(install_error)
+}
+
+/// Stops the service if it is running and removes it.
+///
+/// # Errors
+///
+/// Returns [`ServiceError::Uninstall`] if the service does not exist or
+/// the SCM refuses.
+pub fn uninstall() -> Result<(), ServiceError> {
+    let uninstall_error = |e: windows_service::Error| ServiceError::Uninstall(e.to_string());
+
+    let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)
+        .map_err(uninstall_error)?;
+    let access = ServiceAccess::QUERY_STATUS | ServiceAccess::STOP | ServiceAccess::DELETE;
+    let service = manager
+        .open_service(SERVICE_NAME, access)
+        .map_err(uninstall_error)?;
+    // Deletion completes once the service has stopped
+    let status = service.query_status().map_err(uninstall_error)?;
+    if status.current_state != ServiceState::Stopped {
+        service.stop().map_err(uninstall_error)?;
+    }
+    service.delete().map_err(uninstall_error)
+}
+
+/// Runs `app` as the service, blocking until it returns.
+///
+/// `app` receives a token triggered when the SCM asks the service to stop
+/// or the system shuts down; the service is reported stopped, with a
+/// service-specific exit code on failure, once `app` returns.
+///
+/// # Errors
+///
+/// Returns [`ServiceError::Run`] if the process was not started by the SCM.
+pub fn run<F>(app: F) -> Result<(), ServiceError>
+where
+    F: FnOnce(ShutdownToken) -> Result<(), String> + Send + 'static,
+{
+    if let Ok(mut slot) = APP.lock() {
+        *slot = Some(Box::new(app));
+    }
+    service_dispatcher::start(SERVICE_NAME, ffi_service_main)
+        .map_err(|e| ServiceError::Run(e.to_string()))
+}
+
+/// Entry point of the service thread started by the SCM.
+fn service_main(_arguments: Vec<OsString>) {
+    let Some(app) = APP.lock().ok().and_then(|mut slot| slot.take()) else {
+        return;
+    };
+    if let Err(e) = run_app(app) {
+        tracing::error!("{}", ServiceError::Run(e.to_string()));
+    }
+}
+
+fn run_app(app: App) -> windows_service::Result<()> {
+    let shutdown = ShutdownToken::new();
+    // Set once registered, for the handler to report stopping
+    let handle: Arc<Mutex<Option<ServiceStatusHandle>>> = Arc::new(Mutex::new(None));
+
+    let control = {
+        let shutdown = shutdown.clone();
+        let handle = Arc::clone(&handle);
+        move |event| match event {
+            ServiceControl::Stop | ServiceControl::Shutdown => {
+                tracing::info!("Stop requested by the service control manager");
+                if let Some(handle) = handle.lock().ok().and_then(|h| *h) {
+                    let _ = handle.set_service_status(status(
+                        ServiceState::StopPending,
+                        ServiceExitCode::NO_ERROR,
+                    ));
+                }
+                shutdown.trigger();
+                ServiceControlHandlerResult::NoError
+            }
+            ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+            _ => ServiceControlHandlerResult::NotImplemented,
+        }
+    };
+    let status_handle = service_control_handler::register(SERVICE_NAME, control)?;
+    if let Ok(mut slot) = handle.lock() {
+        *slot = Some(status_handle);
+    }
+    status_handle.set_service_status(status(ServiceState::Running, ServiceExitCode::NO_ERROR))?;
+
+    let exit_code = match app(shutdown) {
+        Ok(()) => ServiceExitCode::NO_ERROR,
+        Err(e) => {
+            tracing::error!("Application error: {e}");
+            ServiceExitCode::ServiceSpecific(FAILURE_EXIT_CODE)
+        }
+    };
+    status_handle.set_service_status(status(ServiceState::Stopped, exit_code))
+}
+
+/// The status reported in `state`; a running service accepts stop and
+/// shutdown requests.
+fn status(state: ServiceState, exit_code: ServiceExitCode) -> ServiceStatus {
+    let (controls_accepted, wait_hint) = match state {
+        ServiceState::Running => (
+            ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+            Duration::ZERO,
+        ),
+        ServiceState::StopPending => (ServiceControlAccept::empty(), STOP_WAIT_HINT),
+        _ => (ServiceControlAccept::empty(), Duration::ZERO),
+    };
+    ServiceStatus {
+        service_type: ServiceType::OWN_PROCESS,
+        current_state: state,
+        controls_accepted,
+        exit_code,
+        checkpoint: 0,
+        wait_hint,
+        process_id: None,
+    }
+}
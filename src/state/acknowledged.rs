@@ -0,0 +1,111 @@
+//! Addresses the webhooks last acknowledged.
+//!
+//! Snapshots are saved whether or not a delivery succeeds, so they tell
+//! what the adapters held, not what the receiver was told. [`Acknowledged`]
+//! records the latter: the addresses per adapter as of the last successful
+//! delivery. A change that would leave the receiver where it already is,
+//! e.g. an address re-added before its failed removal was delivered, or a
+//! batch delivered just before a restart, is not sent again.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::monitor::{IpChange, IpChangeKind};
+use crate::network::AdapterSnapshot;
+
+/// The addresses per adapter the webhooks last acknowledged.
+///
+/// # Examples
+///
+/// ```
+/// use ddns_a::monitor::IpChange;
+/// use ddns_a::state::Acknowledged;
+/// use std::time::SystemTime;
+///
+/// let added = IpChange::added("eth0", "192.0.2.1".parse().unwrap(), SystemTime::UNIX_EPOCH);
+/// let mut acknowledged = Acknowledged::default();
+///
+/// assert_eq!(acknowledged.pending(vec![added.clone()]), [added.clone()]);
+/// acknowledged.record(&[added.clone()]);
+/// assert!(acknowledged.pending(vec![added]).is_empty());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Acknowledged(BTreeMap<String, BTreeSet<IpAddr>>);
+
+impl Acknowledged {
+    /// Creates the record of a receiver told every address of `snapshots`.
+    #[must_use]
+    pub fn from_snapshots(snapshots: &[AdapterSnapshot]) -> Self {
+        let mut acknowledged = Self::default();
+        for snapshot in snapshots {
+            let addresses: BTreeSet<IpAddr> = snapshot
+                .ipv4_addresses
+                .iter()
+                .copied()
+                .map(IpAddr::V4)
+                .chain(snapshot.ipv6_addresses.iter().copied().map(IpAddr::V6))
+                .collect();
+            if !addresses.is_empty() {
+                acknowledged.0.insert(snapshot.name.clone(), addresses);
+            }
+        }
+        acknowledged
+    }
+
+    /// Returns true if the receiver already holds the outcome of `change`:
+    /// the address for an addition, its absence for a removal.
+    #[must_use]
+    pub fn contains(&self, change: &IpChange) -> bool {
+        let held = self
+            .0
+            .get(&change.adapter)
+            .is_some_and(|addresses| addresses.contains(&change.address));
+        match change.kind {
+            IpChangeKind::Added => held,
+            IpChangeKind::Removed => !held,
+        }
+    }
+
+    /// Returns the `changes` the receiver does not hold yet.
+    #[must_use]
+    pub fn pending(&self, mut changes: Vec<IpChange>) -> Vec<IpChange> {
+        changes.retain(|change| !self.contains(change));
+        changes
+    }
+
+    /// Records that `changes` were delivered, in order.
+    pub fn record(&mut self, changes: &[IpChange]) {
+        for change in changes {
+            match change.kind {
+                IpChangeKind::Added => {
+                    self.0
+                        .entry(change.adapter.clone())
+                        .or_default()
+                        .insert(change.address);
+                }
+                IpChangeKind::Removed => {
+                    if let Some(addresses) = self.0.get_mut(&change.adapter) {
+                        addresses.remove(&change.address);
+                        if addresses.is_empty() {
+                            self.0.remove(&change.adapter);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the acknowledged addresses of `adapter`.
+    pub fn addresses(&self, adapter: &str) -> impl Iterator<Item = IpAddr> + '_ {
+        self.0.get(adapter).into_iter().flatten().copied()
+    }
+
+    /// Returns true if no address is acknowledged.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
@@ -0,0 +1,139 @@
+//! Tests for the acknowledged addresses.
+
+use std::net::IpAddr;
+use std::time::SystemTime;
+
+use crate::monitor::IpChange;
+use crate::network::{AdapterKind, AdapterSnapshot};
+use crate::state::Acknowledged;
+
+fn addr(s: &str) -> IpAddr {
+    s.parse().unwrap()
+}
+
+fn added(adapter: &str, address: &str) -> IpChange {
+    IpChange::added(adapter, addr(address), SystemTime::UNIX_EPOCH)
+}
+
+fn removed(adapter: &str, address: &str) -> IpChange {
+    IpChange::removed(adapter, addr(address), SystemTime::UNIX_EPOCH)
+}
+
+fn eth0(ipv4: &str, ipv6: &str) -> AdapterSnapshot {
+    AdapterSnapshot::new(
+        "eth0",
+        AdapterKind::Ethernet,
+        vec![ipv4.parse().unwrap()],
+        vec![ipv6.parse().unwrap()],
+    )
+}
+
+mod from_snapshots {
+    use super::*;
+
+    #[test]
+    fn holds_every_address() {
+        let acknowledged = Acknowledged::from_snapshots(&[eth0("192.0.2.1", "2001:db8::1")]);
+
+        assert_eq!(
+            acknowledged.addresses("eth0").collect::<Vec<_>>(),
+            [addr("192.0.2.1"), addr("2001:db8::1")]
+        );
+    }
+
+    #[test]
+    fn skips_adapters_without_addresses() {
+        let empty = AdapterSnapshot::new("wlan0", AdapterKind::Wireless, vec![], vec![]);
+
+        assert!(Acknowledged::from_snapshots(&[empty]).is_empty());
+    }
+}
+
+mod contains {
+    use super::*;
+
+    #[test]
+    fn addition_of_a_held_address() {
+        let acknowledged = Acknowledged::from_snapshots(&[eth0("192.0.2.1", "2001:db8::1")]);
+
+        assert!(acknowledged.contains(&added("eth0", "192.0.2.1")));
+        assert!(!acknowledged.contains(&added("eth0", "192.0.2.2")));
+        assert!(!acknowledged.contains(&added("eth1", "192.0.2.1")));
+    }
+
+    #[test]
+    fn removal_of_an_address_not_held() {
+        let acknowledged = Acknowledged::from_snapshots(&[eth0("192.0.2.1", "2001:db8::1")]);
+
+        assert!(acknowledged.contains(&removed("eth0", "192.0.2.2")));
+        assert!(!acknowledged.contains(&removed("eth0", "192.0.2.1")));
+    }
+}
+
+mod record {
+    use super::*;
+
+    #[test]
+    fn applies_changes_in_order() {
+        let mut acknowledged = Acknowledged::default();
+
+        acknowledged.record(&[
+            added("eth0", "192.0.2.1"),
+            removed("eth0", "192.0.2.1"),
+            added("eth0", "192.0.2.2"),
+        ]);
+
+        assert_eq!(
+            acknowledged.addresses("eth0").collect::<Vec<_>>(),
+            [addr("192.0.2.2")]
+        );
+    }
+
+    #[test]
+    fn drops_adapters_left_without_addresses() {
+        let mut acknowledged = Acknowledged::default();
+        acknowledged.record(&[added("eth0", "192.0.2.1")]);
+
+        acknowledged.record(&[removed("eth0", "192.0.2.1")]);
+
+        assert!(acknowledged.is_empty());
+        assert_eq!(acknowledged, Acknowledged::default());
+    }
+}
+
+mod pending {
+    use super::*;
+
+    #[test]
+    fn keeps_changes_the_receiver_does_not_hold() {
+        let acknowledged = Acknowledged::from_snapshots(&[eth0("192.0.2.1", "2001:db8::1")]);
+
+        let pending = acknowledged.pending(vec![
+            added("eth0", "192.0.2.1"),
+            added("eth0", "192.0.2.2"),
+            removed("eth0", "2001:db8::1"),
+            removed("eth0", "2001:db8::2"),
+        ]);
+
+        assert_eq!(
+            pending,
+            [added("eth0", "192.0.2.2"), removed("eth0", "2001:db8::1")]
+        );
+    }
+}
+
+#[test]
+fn serializes_as_a_map_of_addresses() {
+    let acknowledged = Acknowledged::from_snapshots(&[eth0("192.0.2.1", "2001:db8::1")]);
+
+    let json = serde_json::to_value(&acknowledged).unwrap();
+
+    assert_eq!(
+        json,
+        serde_json::json!({ "eth0": ["192.0.2.1", "2001:db8::1"] })
+    );
+    assert_eq!(
+        serde_json::from_value::<Acknowledged>(json).unwrap(),
+        acknowledged
+    );
+}
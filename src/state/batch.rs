@@ -0,0 +1,56 @@
+//! Content-derived identifiers for delivered change batches.
+//!
+//! Startup change detection delivers before it saves the new snapshots, so a
+//! restart between the two diffs the same old state again. Recording the
+//! [`BatchId`] of each delivery lets the next run recognize that batch.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::monitor::IpChange;
+
+/// Maximum number of delivered batch ids kept in the state file.
+pub const MAX_DELIVERED_BATCHES: usize = 16;
+
+/// Identifier of a change batch, derived from its content.
+///
+/// Two batches with the same changes (adapter, address and kind, in any
+/// order) have the same id; detection timestamps are ignored, since a
+/// re-detected batch gets new ones.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct BatchId(String);
+
+impl BatchId {
+    /// Computes the id of `changes`.
+    #[must_use]
+    pub fn of(changes: &[IpChange]) -> Self {
+        let mut entries: Vec<String> = changes
+            .iter()
+            .map(|c| {
+                let kind = if c.is_added() { '+' } else { '-' };
+                format!("{kind}{}\t{}\n", c.address, c.adapter)
+            })
+            .collect();
+        entries.sort_unstable();
+
+        let mut hex = format!("{:x}", Sha256::digest(entries.concat()));
+        // 64 bits are plenty to tell apart the handful of ids kept
+        hex.truncate(16);
+        Self(hex)
+    }
+
+    /// Returns the id as a hex string.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for BatchId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
@@ -1,13 +1,20 @@
 //! File-based state persistence implementation.
 
+use std::collections::BTreeMap;
 use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use crate::monitor::IpChange;
 use crate::network::AdapterSnapshot;
+use crate::webhook::Fingerprint;
 
-use super::{LoadResult, StateError, StateStore};
+use super::outbox::OutboxEntry;
+use super::queue::QueueEntry;
+use super::{
+    Acknowledged, BatchId, LoadResult, MAX_DELIVERED_BATCHES, QueuedChange, StateError, StateStore,
+};
 
 /// Current state file format version.
 ///
@@ -31,6 +38,28 @@ struct StateFile {
 
     /// The saved adapter snapshots.
     snapshots: Vec<AdapterSnapshot>,
+
+    /// Ids of batches delivered since the snapshots were saved, oldest first.
+    /// Absent in files written before delivery tracking, hence the default.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    delivered: Vec<BatchId>,
+
+    /// Changes whose delivery shutdown interrupted, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    outbox: Vec<OutboxEntry>,
+
+    /// Changes whose delivery failed, oldest first; kept across saves.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    queue: Vec<QueueEntry>,
+
+    /// TLS certificate fingerprints by host; kept across saves.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    certificates: BTreeMap<String, Fingerprint>,
+
+    /// Addresses per adapter as of the last successful delivery; kept
+    /// across saves. Absent until first recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    acknowledged: Option<Acknowledged>,
 }
 
 impl StateFile {
@@ -40,6 +69,11 @@ impl StateFile {
             version: STATE_FILE_VERSION,
             saved_at: Some(unix_timestamp_now()),
             snapshots: snapshots.to_vec(),
+            delivered: Vec::new(),
+            outbox: Vec::new(),
+            queue: Vec::new(),
+            certificates: BTreeMap::new(),
+            acknowledged: None,
         }
     }
 }
@@ -58,9 +92,21 @@ fn unix_timestamp_now() -> String {
     format!("{}", duration.as_secs())
 }
 
+/// Creates `dir` and its missing parents; on Unix, those created are only
+/// accessible to their owner.
+pub(super) fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(dir)
+}
+
 /// File-based implementation of [`StateStore`].
 ///
 /// Stores adapter snapshots as JSON files with atomic write semantics.
+/// Missing parent directories are created, on Unix accessible only to the
+/// owner; existing ones are left as they are.
 ///
 /// # Atomic Writes
 ///
@@ -96,7 +142,7 @@ impl FileStateStore {
         // Create parent directory if it doesn't exist
         if let Some(parent) = path.parent() {
             if !parent.as_os_str().is_empty() {
-                std::fs::create_dir_all(parent).map_err(StateError::Write)?;
+                create_private_dir(parent).map_err(StateError::Write)?;
             }
         }
 
@@ -112,46 +158,166 @@ impl FileStateStore {
 
         Ok(())
     }
-}
 
-impl StateStore for FileStateStore {
-    fn load(&self) -> LoadResult {
+    /// Reads the state file; `Err` holds the reason it is unusable, `None`
+    /// that it does not exist.
+    fn read(&self) -> Result<Option<StateFile>, String> {
         let content = match std::fs::read_to_string(&self.path) {
             Ok(c) => c,
-            Err(e) if e.kind() == ErrorKind::NotFound => return LoadResult::NotFound,
-            Err(e) => {
-                return LoadResult::Corrupted {
-                    reason: format!("Failed to read file: {e}"),
-                };
-            }
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Failed to read file: {e}")),
         };
 
-        match serde_json::from_str::<StateFile>(&content) {
-            Ok(state) => {
-                // Check version compatibility
-                if state.version != STATE_FILE_VERSION {
-                    return LoadResult::Corrupted {
-                        reason: format!(
-                            "Incompatible version: expected {STATE_FILE_VERSION}, got {}",
-                            state.version
-                        ),
-                    };
-                }
-                LoadResult::Loaded(state.snapshots)
-            }
-            Err(e) => LoadResult::Corrupted {
-                reason: format!("Invalid JSON: {e}"),
-            },
+        let state = serde_json::from_str::<StateFile>(&content)
+            .map_err(|e| format!("Invalid JSON: {e}"))?;
+        // Check version compatibility
+        if state.version != STATE_FILE_VERSION {
+            return Err(format!(
+                "Incompatible version: expected {STATE_FILE_VERSION}, got {}",
+                state.version
+            ));
+        }
+        Ok(Some(state))
+    }
+}
+
+impl StateStore for FileStateStore {
+    fn load(&self) -> LoadResult {
+        match self.read() {
+            Ok(Some(state)) => LoadResult::Loaded(state.snapshots),
+            Ok(None) => LoadResult::NotFound,
+            Err(reason) => LoadResult::Corrupted { reason },
         }
     }
 
     async fn save(&self, snapshots: &[AdapterSnapshot]) -> Result<(), StateError> {
-        let path = self.path.clone();
-        let state = StateFile::new(snapshots);
+        let store = self.clone();
+        let mut state = StateFile::new(snapshots);
 
         // Use spawn_blocking to avoid blocking the async runtime
-        tokio::task::spawn_blocking(move || Self::save_blocking(&path, &state))
-            .await
-            .expect("spawn_blocking task panicked")
+        tokio::task::spawn_blocking(move || {
+            if let Ok(Some(previous)) = store.read() {
+                state.queue = previous.queue;
+                state.certificates = previous.certificates;
+                state.acknowledged = previous.acknowledged;
+            }
+            Self::save_blocking(&store.path, &state)
+        })
+        .await
+        .expect("spawn_blocking task panicked")
+    }
+
+    fn delivered_batches(&self) -> Vec<BatchId> {
+        self.read()
+            .ok()
+            .flatten()
+            .map_or_else(Vec::new, |state| state.delivered)
+    }
+
+    async fn record_delivered(&self, id: &BatchId) -> Result<(), StateError> {
+        let store = self.clone();
+        let id = id.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let Ok(Some(mut state)) = store.read() else {
+                return Ok(());
+            };
+            state.delivered.push(id);
+            let excess = state.delivered.len().saturating_sub(MAX_DELIVERED_BATCHES);
+            state.delivered.drain(..excess);
+            Self::save_blocking(&store.path, &state)
+        })
+        .await
+        .expect("spawn_blocking task panicked")
+    }
+
+    fn undelivered(&self) -> Vec<IpChange> {
+        self.read().ok().flatten().map_or_else(Vec::new, |state| {
+            state.outbox.into_iter().map(IpChange::from).collect()
+        })
+    }
+
+    async fn record_undelivered(&self, changes: &[IpChange]) -> Result<(), StateError> {
+        let store = self.clone();
+        let entries: Vec<OutboxEntry> = changes.iter().map(OutboxEntry::from).collect();
+
+        tokio::task::spawn_blocking(move || {
+            let Ok(Some(mut state)) = store.read() else {
+                return Ok(());
+            };
+            state.outbox.extend(entries);
+            Self::save_blocking(&store.path, &state)
+        })
+        .await
+        .expect("spawn_blocking task panicked")
+    }
+
+    fn queued(&self) -> Vec<QueuedChange> {
+        self.read().ok().flatten().map_or_else(Vec::new, |state| {
+            state.queue.into_iter().map(QueuedChange::from).collect()
+        })
+    }
+
+    async fn set_queued(&self, queue: &[QueuedChange]) -> Result<(), StateError> {
+        let store = self.clone();
+        let entries: Vec<QueueEntry> = queue.iter().map(QueueEntry::from).collect();
+
+        tokio::task::spawn_blocking(move || {
+            let Ok(Some(mut state)) = store.read() else {
+                return Ok(());
+            };
+            state.queue = entries;
+            Self::save_blocking(&store.path, &state)
+        })
+        .await
+        .expect("spawn_blocking task panicked")
+    }
+
+    fn certificates(&self) -> BTreeMap<String, Fingerprint> {
+        self.read()
+            .ok()
+            .flatten()
+            .map_or_else(BTreeMap::new, |state| state.certificates)
+    }
+
+    async fn record_certificate(
+        &self,
+        host: &str,
+        fingerprint: Fingerprint,
+    ) -> Result<(), StateError> {
+        let store = self.clone();
+        let host = host.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let Ok(Some(mut state)) = store.read() else {
+                return Ok(());
+            };
+            state.certificates.insert(host, fingerprint);
+            Self::save_blocking(&store.path, &state)
+        })
+        .await
+        .expect("spawn_blocking task panicked")
+    }
+
+    fn acknowledged(&self) -> Option<Acknowledged> {
+        self.read()
+            .ok()
+            .flatten()
+            .and_then(|state| state.acknowledged)
+    }
+
+    async fn set_acknowledged(&self, acknowledged: &Acknowledged) -> Result<(), StateError> {
+        let store = self.clone();
+        let acknowledged = acknowledged.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let Ok(Some(mut state)) = store.read() else {
+                return Ok(());
+            };
+            state.acknowledged = Some(acknowledged);
+            Self::save_blocking(&store.path, &state)
+        })
+        .await
+        .expect("spawn_blocking task panicked")
     }
 }
@@ -0,0 +1,96 @@
+//! Exclusive lock on a state file.
+
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::StateError;
+
+/// Exclusive lock on a state file, held until dropped.
+///
+/// Two instances sharing a state file race on its writes, so the instance
+/// holding the lock is the only one using it. The lock is taken on
+/// `{state_file}.lock`: with `flock` on Unix, and on Windows by opening the
+/// file without sharing. The operating system releases it when the process
+/// exits, so a crash leaves no stale lock behind. Other platforms do not
+/// lock.
+///
+/// The lock file itself is left in place: removing it on drop would let a
+/// starting instance lock a file that is about to disappear.
+#[derive(Debug)]
+pub struct StateLock {
+    path: PathBuf,
+    _file: File,
+}
+
+impl StateLock {
+    /// Locks `state_file`, creating the lock file and its parent directories.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StateError::Locked`] if another process holds the lock, or
+    /// [`StateError::Lock`] if the lock file cannot be created or locked.
+    pub fn acquire(state_file: &Path) -> Result<Self, StateError> {
+        let mut path = state_file.as_os_str().to_owned();
+        path.push(".lock");
+        let path = PathBuf::from(path);
+
+        match open_locked(&path) {
+            Ok(file) => Ok(Self { path, _file: file }),
+            Err(e) if is_contended(&e) => Err(StateError::Locked { path }),
+            Err(source) => Err(StateError::Lock { path, source }),
+        }
+    }
+
+    /// Returns the path of the lock file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Opens `path`, creating it if needed, and locks it.
+fn open_locked(path: &Path) -> io::Result<File> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        super::file::create_private_dir(parent)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.read(true).write(true).create(true).truncate(false);
+    #[cfg(windows)]
+    std::os::windows::fs::OpenOptionsExt::share_mode(&mut options, 0);
+    let file = options.open(path)?;
+    lock(&file)?;
+    Ok(file)
+}
+
+#[cfg(unix)]
+fn lock(file: &File) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    // SAFETY: the descriptor is open for the duration of the call.
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Opening the file without sharing already locked it.
+#[cfg(not(unix))]
+#[allow(clippy::unnecessary_wraps)]
+const fn lock(_file: &File) -> io::Result<()> {
+    Ok(())
+}
+
+/// Returns true if `error` means another process holds the lock.
+fn is_contended(error: &io::Error) -> bool {
+    #[cfg(windows)]
+    {
+        // ERROR_SHARING_VIOLATION, ERROR_LOCK_VIOLATION
+        matches!(error.raw_os_error(), Some(32 | 33))
+    }
+    #[cfg(not(windows))]
+    {
+        error.kind() == io::ErrorKind::WouldBlock
+    }
+}
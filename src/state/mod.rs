@@ -1,21 +1,49 @@
 //! IP state persistence for detecting changes across restarts.
 //!
 //! This module provides abstractions for storing and retrieving
-//! adapter snapshot state between program executions.
+//! adapter snapshot state between program executions, plus the ids of
+//! change batches delivered since the snapshots were saved ([`BatchId`])
+//! and an outbox of changes whose delivery shutdown interrupted. It also
+//! keeps the retry [`queue`] of changes whose delivery failed, the TLS
+//! certificate fingerprints seen per host and the addresses the webhooks
+//! last acknowledged ([`Acknowledged`]). [`StateLock`] keeps a second
+//! instance from using the same state file, and [`replay`] diffs two saved
+//! state files, for postmortems of missed updates.
 
+mod acknowledged;
+mod batch;
 mod file;
+mod lock;
+mod outbox;
+pub mod queue;
+mod replay;
 
+#[cfg(test)]
+mod acknowledged_tests;
+#[cfg(test)]
+mod queue_tests;
+#[cfg(test)]
+mod replay_tests;
 #[cfg(test)]
 #[path = "mod_tests.rs"]
 mod tests;
 
+pub use acknowledged::Acknowledged;
+pub use batch::{BatchId, MAX_DELIVERED_BATCHES};
 pub use file::FileStateStore;
+pub use lock::StateLock;
+pub use queue::{QueueLimits, QueuedChange};
+pub use replay::{ReplayError, load_snapshots, replay};
 
+use std::collections::BTreeMap;
 use std::io;
+use std::path::PathBuf;
 
 use thiserror::Error;
 
+use crate::monitor::IpChange;
 use crate::network::AdapterSnapshot;
+use crate::webhook::Fingerprint;
 
 /// Result of loading state from persistent storage.
 ///
@@ -58,8 +86,8 @@ impl LoadResult {
 
 /// Errors that can occur during state persistence operations.
 ///
-/// Only covers write-side errors; read-side issues are modeled
-/// as [`LoadResult`] variants to allow graceful degradation.
+/// Only covers write-side errors and locking; read-side issues are
+/// modeled as [`LoadResult`] variants to allow graceful degradation.
 #[derive(Debug, Error)]
 pub enum StateError {
     /// Failed to write the state file.
@@ -69,6 +97,26 @@ pub enum StateError {
     /// Failed to serialize state to JSON.
     #[error("Failed to serialize state: {0}")]
     Serialize(#[source] serde_json::Error),
+
+    /// Another instance holds the lock on the state file.
+    #[error(
+        "Another instance is already using this state file (lock file '{}'); stop it or pass --no-lock",
+        path.display()
+    )]
+    Locked {
+        /// Path to the lock file
+        path: PathBuf,
+    },
+
+    /// Failed to create or lock the lock file.
+    #[error("Failed to lock state file ('{}'): {source}", path.display())]
+    Lock {
+        /// Path to the lock file
+        path: PathBuf,
+        /// Underlying I/O error
+        #[source]
+        source: io::Error,
+    },
 }
 
 /// Abstraction for persisting adapter state between program runs.
@@ -94,6 +142,10 @@ pub trait StateStore: Send + Sync {
     ///
     /// Implementations should use atomic write semantics (write to temp file,
     /// then rename) to prevent corruption if the program crashes mid-write.
+    /// Saving clears the delivered batch ids, which describe deliveries made
+    /// since the saved snapshots, and the undelivered changes, which the
+    /// caller delivers before saving. The retry queue, certificate
+    /// fingerprints and acknowledged addresses are kept.
     ///
     /// # Errors
     ///
@@ -102,6 +154,125 @@ pub trait StateStore: Send + Sync {
         &self,
         snapshots: &[AdapterSnapshot],
     ) -> impl std::future::Future<Output = Result<(), StateError>> + Send;
+
+    /// Returns the ids of batches delivered since the snapshots were saved,
+    /// oldest first.
+    ///
+    /// The default implementation records nothing and returns no ids.
+    fn delivered_batches(&self) -> Vec<BatchId> {
+        Vec::new()
+    }
+
+    /// Records that the batch `id` was delivered, keeping at most
+    /// [`MAX_DELIVERED_BATCHES`] ids.
+    ///
+    /// Does nothing if no snapshots were saved, since a batch can only be
+    /// detected again against saved snapshots.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state cannot be written.
+    fn record_delivered(
+        &self,
+        id: &BatchId,
+    ) -> impl std::future::Future<Output = Result<(), StateError>> + Send {
+        let _ = id;
+        async { Ok(()) }
+    }
+
+    /// Returns the changes whose delivery shutdown interrupted, oldest first.
+    ///
+    /// The default implementation records nothing and returns no changes.
+    fn undelivered(&self) -> Vec<IpChange> {
+        Vec::new()
+    }
+
+    /// Adds `changes` to the undelivered changes, for the next run to deliver.
+    ///
+    /// Does nothing if no snapshots were saved, like
+    /// [`record_delivered`](Self::record_delivered).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state cannot be written.
+    fn record_undelivered(
+        &self,
+        changes: &[IpChange],
+    ) -> impl std::future::Future<Output = Result<(), StateError>> + Send {
+        let _ = changes;
+        async { Ok(()) }
+    }
+
+    /// Returns the retry queue, oldest first.
+    ///
+    /// The default implementation records nothing and returns no changes.
+    fn queued(&self) -> Vec<QueuedChange> {
+        Vec::new()
+    }
+
+    /// Replaces the retry queue with `queue`.
+    ///
+    /// Does nothing if no snapshots were saved, like
+    /// [`record_delivered`](Self::record_delivered).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state cannot be written.
+    fn set_queued(
+        &self,
+        queue: &[QueuedChange],
+    ) -> impl std::future::Future<Output = Result<(), StateError>> + Send {
+        let _ = queue;
+        async { Ok(()) }
+    }
+
+    /// Returns the TLS certificate fingerprints recorded per host.
+    ///
+    /// The default implementation records nothing and returns no fingerprints.
+    fn certificates(&self) -> BTreeMap<String, Fingerprint> {
+        BTreeMap::new()
+    }
+
+    /// Records `fingerprint` as the certificate `host` presents.
+    ///
+    /// Does nothing if no snapshots were saved, like
+    /// [`record_delivered`](Self::record_delivered).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state cannot be written.
+    fn record_certificate(
+        &self,
+        host: &str,
+        fingerprint: Fingerprint,
+    ) -> impl std::future::Future<Output = Result<(), StateError>> + Send {
+        let _ = (host, fingerprint);
+        async { Ok(()) }
+    }
+
+    /// Returns the addresses the webhooks last acknowledged, or `None` if
+    /// none were recorded yet.
+    ///
+    /// The default implementation records nothing and returns `None`.
+    fn acknowledged(&self) -> Option<Acknowledged> {
+        None
+    }
+
+    /// Replaces the acknowledged addresses with `acknowledged`.
+    ///
+    /// Does nothing if no snapshots were saved, like
+    /// [`record_delivered`](Self::record_delivered).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state cannot be written.
+    fn set_acknowledged(
+        &self,
+        acknowledged: &Acknowledged,
+    ) -> impl std::future::Future<Output = Result<(), StateError>> + Send {
+        let _ = acknowledged;
+        async { Ok(()) }
+    }
 }
 
 /// Mock state store for testing.
@@ -5,7 +5,7 @@ use std::net::{Ipv4Addr, Ipv6Addr};
 use tempfile::TempDir;
 
 use crate::network::{AdapterKind, AdapterSnapshot};
-use crate::state::{FileStateStore, LoadResult, StateStore};
+use crate::state::{BatchId, FileStateStore, LoadResult, MAX_DELIVERED_BATCHES, StateStore};
 
 /// Creates a test adapter snapshot with the given IPv4 address.
 fn snapshot_with_ipv4(name: &str, ip: &str) -> AdapterSnapshot {
@@ -244,6 +244,359 @@ mod file_state_store {
         let result = store.load();
         assert!(result.is_loaded());
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn created_directories_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let nested = dir.path().join("nested").join("deep");
+        let store = FileStateStore::new(nested.join("state.json"));
+
+        store.save(&[]).await.unwrap();
+
+        for created in [&nested, &dir.path().join("nested")] {
+            let mode = std::fs::metadata(created).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700, "{}", created.display());
+        }
+    }
+}
+
+mod batch_id {
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+    use crate::monitor::IpChange;
+
+    fn added(adapter: &str, ip: &str, at: SystemTime) -> IpChange {
+        IpChange::added(adapter, ip.parse().unwrap(), at)
+    }
+
+    #[test]
+    fn ignores_order_and_timestamps() {
+        let earlier = SystemTime::UNIX_EPOCH;
+        let later = earlier + Duration::from_secs(60);
+        let a = vec![
+            added("eth0", "192.168.1.1", earlier),
+            added("eth1", "10.0.0.1", earlier),
+        ];
+        let b = vec![
+            added("eth1", "10.0.0.1", later),
+            added("eth0", "192.168.1.1", later),
+        ];
+
+        assert_eq!(BatchId::of(&a), BatchId::of(&b));
+    }
+
+    #[test]
+    fn differs_by_kind_adapter_and_address() {
+        let at = SystemTime::UNIX_EPOCH;
+        let base = BatchId::of(&[added("eth0", "192.168.1.1", at)]);
+
+        let removed = IpChange::removed("eth0", "192.168.1.1".parse().unwrap(), at);
+        assert_ne!(BatchId::of(&[removed]), base);
+        assert_ne!(BatchId::of(&[added("eth1", "192.168.1.1", at)]), base);
+        assert_ne!(BatchId::of(&[added("eth0", "192.168.1.2", at)]), base);
+    }
+
+    #[test]
+    fn is_sixteen_hex_digits() {
+        let id = BatchId::of(&[added("eth0", "192.168.1.1", SystemTime::UNIX_EPOCH)]);
+
+        assert_eq!(id.as_str().len(), 16);
+        assert!(id.as_str().chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(id.to_string(), id.as_str());
+    }
+}
+
+mod delivered_batches {
+    use std::time::SystemTime;
+
+    use super::*;
+    use crate::monitor::IpChange;
+
+    fn batch(n: u8) -> BatchId {
+        let address = Ipv4Addr::new(10, 0, 0, n).into();
+        BatchId::of(&[IpChange::added("eth0", address, SystemTime::UNIX_EPOCH)])
+    }
+
+    #[tokio::test]
+    async fn record_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let store = FileStateStore::new(dir.path().join("state.json"));
+        store.save(&[]).await.unwrap();
+
+        store.record_delivered(&batch(1)).await.unwrap();
+        store.record_delivered(&batch(2)).await.unwrap();
+
+        assert_eq!(store.delivered_batches(), vec![batch(1), batch(2)]);
+        assert!(store.load().is_loaded());
+    }
+
+    #[tokio::test]
+    async fn keeps_only_most_recent() {
+        let dir = TempDir::new().unwrap();
+        let store = FileStateStore::new(dir.path().join("state.json"));
+        store.save(&[]).await.unwrap();
+
+        let count = u8::try_from(MAX_DELIVERED_BATCHES).unwrap() + 2;
+        for n in 0..count {
+            store.record_delivered(&batch(n)).await.unwrap();
+        }
+
+        let delivered = store.delivered_batches();
+        assert_eq!(delivered.len(), MAX_DELIVERED_BATCHES);
+        assert_eq!(delivered[0], batch(2));
+        assert_eq!(delivered.last(), Some(&batch(count - 1)));
+    }
+
+    #[tokio::test]
+    async fn save_clears_delivered() {
+        let dir = TempDir::new().unwrap();
+        let store = FileStateStore::new(dir.path().join("state.json"));
+        store.save(&[]).await.unwrap();
+        store.record_delivered(&batch(1)).await.unwrap();
+
+        store
+            .save(&[snapshot_with_ipv4("eth0", "10.0.0.1")])
+            .await
+            .unwrap();
+
+        assert!(store.delivered_batches().is_empty());
+    }
+
+    #[tokio::test]
+    async fn record_without_state_file_is_noop() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("state.json");
+        let store = FileStateStore::new(&path);
+
+        store.record_delivered(&batch(1)).await.unwrap();
+
+        assert!(!path.exists());
+        assert!(store.delivered_batches().is_empty());
+    }
+
+    #[test]
+    fn file_without_delivered_field_loads() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("state.json");
+        std::fs::write(&path, r#"{"version": 1, "snapshots": []}"#).unwrap();
+
+        let store = FileStateStore::new(&path);
+
+        assert!(store.load().is_loaded());
+        assert!(store.delivered_batches().is_empty());
+    }
+}
+
+mod undelivered_changes {
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+    use crate::monitor::IpChange;
+
+    fn changes() -> Vec<IpChange> {
+        let at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        vec![
+            IpChange::added("eth0", Ipv4Addr::new(10, 0, 0, 2).into(), at),
+            IpChange::removed("eth0", Ipv6Addr::LOCALHOST.into(), at),
+        ]
+    }
+
+    #[tokio::test]
+    async fn record_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let store = FileStateStore::new(dir.path().join("state.json"));
+        store.save(&[]).await.unwrap();
+
+        store.record_undelivered(&changes()).await.unwrap();
+
+        assert_eq!(store.undelivered(), changes());
+        assert!(store.load().is_loaded());
+    }
+
+    #[tokio::test]
+    async fn records_accumulate() {
+        let dir = TempDir::new().unwrap();
+        let store = FileStateStore::new(dir.path().join("state.json"));
+        store.save(&[]).await.unwrap();
+
+        store.record_undelivered(&changes()[..1]).await.unwrap();
+        store.record_undelivered(&changes()[1..]).await.unwrap();
+
+        assert_eq!(store.undelivered(), changes());
+    }
+
+    #[tokio::test]
+    async fn save_clears_undelivered() {
+        let dir = TempDir::new().unwrap();
+        let store = FileStateStore::new(dir.path().join("state.json"));
+        store.save(&[]).await.unwrap();
+        store.record_undelivered(&changes()).await.unwrap();
+
+        store.save(&[]).await.unwrap();
+
+        assert!(store.undelivered().is_empty());
+    }
+
+    #[tokio::test]
+    async fn record_without_state_file_is_noop() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("state.json");
+        let store = FileStateStore::new(&path);
+
+        store.record_undelivered(&changes()).await.unwrap();
+
+        assert!(!path.exists());
+        assert!(store.undelivered().is_empty());
+    }
+}
+
+mod certificates {
+    use super::*;
+    use crate::webhook::Fingerprint;
+
+    #[tokio::test]
+    async fn record_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let store = FileStateStore::new(dir.path().join("state.json"));
+        store.save(&[]).await.unwrap();
+
+        store
+            .record_certificate("example.com", Fingerprint::of(b"a"))
+            .await
+            .unwrap();
+        store
+            .record_certificate("example.com", Fingerprint::of(b"b"))
+            .await
+            .unwrap();
+
+        let certificates = store.certificates();
+        assert_eq!(certificates.len(), 1);
+        assert_eq!(certificates["example.com"], Fingerprint::of(b"b"));
+        assert!(store.load().is_loaded());
+    }
+
+    #[tokio::test]
+    async fn save_keeps_certificates() {
+        let dir = TempDir::new().unwrap();
+        let store = FileStateStore::new(dir.path().join("state.json"));
+        store.save(&[]).await.unwrap();
+        store
+            .record_certificate("example.com", Fingerprint::of(b"a"))
+            .await
+            .unwrap();
+
+        store
+            .save(&[snapshot_with_ipv4("eth0", "10.0.0.1")])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.certificates().get("example.com"),
+            Some(&Fingerprint::of(b"a"))
+        );
+    }
+
+    #[tokio::test]
+    async fn record_without_state_file_is_noop() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("state.json");
+        let store = FileStateStore::new(&path);
+
+        store
+            .record_certificate("example.com", Fingerprint::of(b"a"))
+            .await
+            .unwrap();
+
+        assert!(!path.exists());
+        assert!(store.certificates().is_empty());
+    }
+}
+
+mod acknowledged {
+    use super::*;
+    use crate::state::Acknowledged;
+
+    #[tokio::test]
+    async fn absent_until_recorded() {
+        let dir = TempDir::new().unwrap();
+        let store = FileStateStore::new(dir.path().join("state.json"));
+        store.save(&[]).await.unwrap();
+
+        assert_eq!(store.acknowledged(), None);
+    }
+
+    #[tokio::test]
+    async fn set_roundtrip_and_kept_by_save() {
+        let dir = TempDir::new().unwrap();
+        let store = FileStateStore::new(dir.path().join("state.json"));
+        store.save(&[]).await.unwrap();
+        let acknowledged = Acknowledged::from_snapshots(&[snapshot_with_ipv4("eth0", "10.0.0.1")]);
+
+        store.set_acknowledged(&acknowledged).await.unwrap();
+        store
+            .save(&[snapshot_with_ipv4("eth0", "10.0.0.2")])
+            .await
+            .unwrap();
+
+        assert_eq!(store.acknowledged(), Some(acknowledged));
+        assert!(store.load().is_loaded());
+    }
+
+    #[tokio::test]
+    async fn set_without_state_file_is_noop() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("state.json");
+        let store = FileStateStore::new(&path);
+
+        store
+            .set_acknowledged(&Acknowledged::default())
+            .await
+            .unwrap();
+
+        assert!(!path.exists());
+        assert_eq!(store.acknowledged(), None);
+    }
+}
+
+mod state_lock {
+    use super::*;
+    use crate::state::{StateError, StateLock};
+
+    #[test]
+    fn locks_next_to_the_state_file() {
+        let dir = TempDir::new().unwrap();
+        let state_file = dir.path().join("run").join("state.json");
+
+        let lock = StateLock::acquire(&state_file).unwrap();
+
+        assert_eq!(lock.path(), dir.path().join("run").join("state.json.lock"));
+        assert!(lock.path().exists());
+    }
+
+    #[cfg(any(unix, windows))]
+    #[test]
+    fn second_lock_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let state_file = dir.path().join("state.json");
+        let _lock = StateLock::acquire(&state_file).unwrap();
+
+        let result = StateLock::acquire(&state_file);
+
+        assert!(matches!(result, Err(StateError::Locked { .. })));
+    }
+
+    #[test]
+    fn released_when_dropped() {
+        let dir = TempDir::new().unwrap();
+        let state_file = dir.path().join("state.json");
+        drop(StateLock::acquire(&state_file).unwrap());
+
+        assert!(StateLock::acquire(&state_file).is_ok());
+    }
 }
 
 mod mock_state_store {
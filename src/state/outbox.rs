@@ -0,0 +1,43 @@
+//! Changes whose delivery was interrupted by shutdown.
+//!
+//! Snapshots are saved before delivery, so a batch cut short by shutdown is
+//! not detected again on the next start. The state file's outbox keeps such
+//! changes until the next run delivers them.
+
+use std::net::IpAddr;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::monitor::{IpChange, IpChangeKind};
+
+/// An undelivered change as stored in the state file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct OutboxEntry {
+    adapter: String,
+    address: IpAddr,
+    kind: IpChangeKind,
+    /// Detection time as Unix seconds.
+    timestamp: u64,
+}
+
+impl From<&IpChange> for OutboxEntry {
+    fn from(change: &IpChange) -> Self {
+        Self {
+            adapter: change.adapter.clone(),
+            address: change.address,
+            kind: change.kind,
+            timestamp: change
+                .timestamp
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+        }
+    }
+}
+
+impl From<OutboxEntry> for IpChange {
+    fn from(entry: OutboxEntry) -> Self {
+        let timestamp = SystemTime::UNIX_EPOCH + Duration::from_secs(entry.timestamp);
+        Self::new(entry.adapter, entry.address, timestamp, entry.kind)
+    }
+}
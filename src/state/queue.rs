@@ -0,0 +1,100 @@
+//! Changes whose delivery failed, kept for later attempts.
+//!
+//! When the webhook stays unreachable past its retry budget, the retry
+//! queue keeps the failed changes in the state file. They are sent again on
+//! later poll cycles and on the next start, ahead of newer changes, until
+//! delivered or dropped by the [`QueueLimits`].
+
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::monitor::IpChange;
+
+use super::outbox::OutboxEntry;
+
+/// Default time a change is kept in the retry queue.
+pub const DEFAULT_QUEUE_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Default number of changes kept in the retry queue.
+pub const DEFAULT_QUEUE_MAX_SIZE: usize = 1000;
+
+/// A change waiting in the retry queue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedChange {
+    /// The undelivered change.
+    pub change: IpChange,
+    /// When the change entered the queue.
+    pub queued_at: SystemTime,
+}
+
+impl QueuedChange {
+    /// Queues `change` at `queued_at`.
+    #[must_use]
+    pub const fn new(change: IpChange, queued_at: SystemTime) -> Self {
+        Self { change, queued_at }
+    }
+}
+
+/// Bounds of the retry queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueLimits {
+    /// Changes queued longer than this are dropped.
+    pub max_age: Duration,
+    /// The oldest changes beyond this many are dropped.
+    pub max_size: usize,
+}
+
+impl Default for QueueLimits {
+    fn default() -> Self {
+        Self {
+            max_age: DEFAULT_QUEUE_MAX_AGE,
+            max_size: DEFAULT_QUEUE_MAX_SIZE,
+        }
+    }
+}
+
+impl QueueLimits {
+    /// Drops the changes of `queue` (oldest first) that exceed the limits
+    /// at `now`, returning how many were dropped.
+    pub fn prune(&self, queue: &mut Vec<QueuedChange>, now: SystemTime) -> usize {
+        let before = queue.len();
+        queue.retain(|queued| {
+            now.duration_since(queued.queued_at)
+                .map_or(true, |age| age <= self.max_age)
+        });
+        let excess = queue.len().saturating_sub(self.max_size);
+        queue.drain(..excess);
+        before - queue.len()
+    }
+}
+
+/// A queued change as stored in the state file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct QueueEntry {
+    #[serde(flatten)]
+    change: OutboxEntry,
+    /// Queueing time as Unix seconds.
+    queued_at: u64,
+}
+
+impl From<&QueuedChange> for QueueEntry {
+    fn from(queued: &QueuedChange) -> Self {
+        Self {
+            change: OutboxEntry::from(&queued.change),
+            queued_at: queued
+                .queued_at
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+        }
+    }
+}
+
+impl From<QueueEntry> for QueuedChange {
+    fn from(entry: QueueEntry) -> Self {
+        Self {
+            change: IpChange::from(entry.change),
+            queued_at: SystemTime::UNIX_EPOCH + Duration::from_secs(entry.queued_at),
+        }
+    }
+}
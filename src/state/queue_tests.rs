@@ -0,0 +1,114 @@
+//! Tests for the retry queue of failed deliveries.
+
+use std::net::Ipv4Addr;
+use std::time::{Duration, SystemTime};
+
+use tempfile::TempDir;
+
+use crate::monitor::IpChange;
+use crate::state::{FileStateStore, QueueLimits, QueuedChange, StateStore};
+
+fn queued(last_octet: u8, queued_at: u64) -> QueuedChange {
+    let at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    QueuedChange::new(
+        IpChange::added("eth0", Ipv4Addr::new(10, 0, 0, last_octet).into(), at),
+        SystemTime::UNIX_EPOCH + Duration::from_secs(queued_at),
+    )
+}
+
+mod prune {
+    use super::*;
+
+    fn limits(max_age: u64, max_size: usize) -> QueueLimits {
+        QueueLimits {
+            max_age: Duration::from_secs(max_age),
+            max_size,
+        }
+    }
+
+    fn now() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1000)
+    }
+
+    #[test]
+    fn drops_changes_older_than_max_age() {
+        let mut queue = vec![queued(1, 100), queued(2, 900), queued(3, 950)];
+
+        let dropped = limits(100, 10).prune(&mut queue, now());
+
+        assert_eq!(dropped, 1);
+        assert_eq!(queue, vec![queued(2, 900), queued(3, 950)]);
+    }
+
+    #[test]
+    fn drops_oldest_changes_beyond_max_size() {
+        let mut queue = vec![queued(1, 900), queued(2, 910), queued(3, 920)];
+
+        let dropped = limits(3600, 2).prune(&mut queue, now());
+
+        assert_eq!(dropped, 1);
+        assert_eq!(queue, vec![queued(2, 910), queued(3, 920)]);
+    }
+
+    #[test]
+    fn keeps_changes_queued_in_the_future() {
+        // The clock went back since queueing
+        let mut queue = vec![queued(1, 5000)];
+
+        assert_eq!(limits(100, 10).prune(&mut queue, now()), 0);
+        assert_eq!(queue.len(), 1);
+    }
+}
+
+mod file_store {
+    use super::*;
+
+    #[tokio::test]
+    async fn set_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let store = FileStateStore::new(dir.path().join("state.json"));
+        store.save(&[]).await.unwrap();
+        let queue = vec![queued(1, 100), queued(2, 200)];
+
+        store.set_queued(&queue).await.unwrap();
+
+        assert_eq!(store.queued(), queue);
+        assert!(store.load().is_loaded());
+    }
+
+    #[tokio::test]
+    async fn set_replaces_queue() {
+        let dir = TempDir::new().unwrap();
+        let store = FileStateStore::new(dir.path().join("state.json"));
+        store.save(&[]).await.unwrap();
+        store.set_queued(&[queued(1, 100)]).await.unwrap();
+
+        store.set_queued(&[]).await.unwrap();
+
+        assert!(store.queued().is_empty());
+    }
+
+    #[tokio::test]
+    async fn save_keeps_queue() {
+        let dir = TempDir::new().unwrap();
+        let store = FileStateStore::new(dir.path().join("state.json"));
+        store.save(&[]).await.unwrap();
+        store.set_queued(&[queued(1, 100)]).await.unwrap();
+
+        store.save(&[]).await.unwrap();
+
+        assert_eq!(store.queued(), vec![queued(1, 100)]);
+    }
+
+    #[tokio::test]
+    async fn set_without_state_file_is_noop() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("state.json");
+        let store = FileStateStore::new(&path);
+
+        store.set_queued(&[queued(1, 100)]).await.unwrap();
+
+        assert!(!path.exists());
+        assert!(store.queued().is_empty());
+    }
+}
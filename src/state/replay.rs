@@ -0,0 +1,81 @@
+//! Replaying the changes between two saved state files.
+//!
+//! `ddns-a replay --from state.bak --to state.json` runs the monitor's diff
+//! and filter pipeline on two saved states instead of live fetches, so the
+//! changes that (should have) triggered a notification can be reviewed, or
+//! sent again, after the fact.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use thiserror::Error;
+
+use crate::monitor::{IpChange, diff, filter_by_version};
+use crate::network::filter::AdapterFilter;
+use crate::network::{AdapterSnapshot, IpVersion};
+
+use super::{FileStateStore, LoadResult, StateStore};
+
+/// Errors that can occur while loading a state file to replay.
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    /// The state file does not exist.
+    #[error("State file '{}' not found", path.display())]
+    NotFound {
+        /// Path to the state file
+        path: PathBuf,
+    },
+
+    /// The state file exists but could not be parsed.
+    #[error("State file '{}' is unusable: {reason}", path.display())]
+    Corrupted {
+        /// Path to the state file
+        path: PathBuf,
+        /// Why the file could not be loaded
+        reason: String,
+    },
+}
+
+/// Loads the adapter snapshots saved in the state file at `path`.
+///
+/// # Errors
+///
+/// Returns an error if the file is missing or not a state file; unlike a
+/// startup load, neither is treated as an empty state.
+pub fn load_snapshots(path: &Path) -> Result<Vec<AdapterSnapshot>, ReplayError> {
+    match FileStateStore::new(path).load() {
+        LoadResult::Loaded(snapshots) => Ok(snapshots),
+        LoadResult::NotFound => Err(ReplayError::NotFound {
+            path: path.to_path_buf(),
+        }),
+        LoadResult::Corrupted { reason } => Err(ReplayError::Corrupted {
+            path: path.to_path_buf(),
+            reason,
+        }),
+    }
+}
+
+/// Returns the changes from the `from` to the `to` snapshots, as the
+/// monitor would have reported them.
+///
+/// Adapters not matching `filter` are dropped from both sides first, like
+/// fetches are filtered, and changes are then limited to `version`.
+#[must_use]
+pub fn replay(
+    from: &[AdapterSnapshot],
+    to: &[AdapterSnapshot],
+    filter: &impl AdapterFilter,
+    version: IpVersion,
+    timestamp: SystemTime,
+) -> Vec<IpChange> {
+    let matching = |snapshots: &[AdapterSnapshot]| {
+        snapshots
+            .iter()
+            .filter(|adapter| filter.matches(adapter))
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    let changes = diff(&matching(from), &matching(to), timestamp);
+    filter_by_version(changes, version)
+}
@@ -0,0 +1,136 @@
+//! Tests for replaying the changes between saved state files.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::SystemTime;
+
+use tempfile::TempDir;
+
+use crate::network::filter::{FilterChain, KindFilter};
+use crate::network::{AdapterKind, AdapterSnapshot, IpVersion};
+use crate::state::{FileStateStore, ReplayError, StateStore, load_snapshots, replay};
+
+fn adapter(name: &str, kind: AdapterKind, ipv4: &str, ipv6: &str) -> AdapterSnapshot {
+    AdapterSnapshot::new(
+        name,
+        kind,
+        vec![ipv4.parse::<Ipv4Addr>().unwrap()],
+        vec![ipv6.parse::<Ipv6Addr>().unwrap()],
+    )
+}
+
+mod load {
+    use super::*;
+
+    #[tokio::test]
+    async fn returns_saved_snapshots() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("state.json");
+        let snapshots = vec![adapter(
+            "eth0",
+            AdapterKind::Ethernet,
+            "192.0.2.1",
+            "2001:db8::1",
+        )];
+        FileStateStore::new(&path).save(&snapshots).await.unwrap();
+
+        assert_eq!(load_snapshots(&path).unwrap(), snapshots);
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        let result = load_snapshots(&dir.path().join("missing.json"));
+
+        assert!(matches!(result, Err(ReplayError::NotFound { .. })));
+    }
+
+    #[test]
+    fn invalid_file_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("state.json");
+        std::fs::write(&path, "not json").unwrap();
+
+        let result = load_snapshots(&path);
+
+        assert!(matches!(result, Err(ReplayError::Corrupted { .. })));
+    }
+}
+
+mod diff {
+    use super::*;
+
+    fn from() -> Vec<AdapterSnapshot> {
+        vec![
+            adapter("eth0", AdapterKind::Ethernet, "192.0.2.1", "2001:db8::1"),
+            adapter("vEthernet", AdapterKind::Virtual, "10.0.0.1", "fd00::1"),
+        ]
+    }
+
+    fn to() -> Vec<AdapterSnapshot> {
+        vec![
+            adapter("eth0", AdapterKind::Ethernet, "192.0.2.2", "2001:db8::2"),
+            adapter("vEthernet", AdapterKind::Virtual, "10.0.0.2", "fd00::2"),
+        ]
+    }
+
+    fn addresses(changes: &[crate::monitor::IpChange]) -> Vec<(&str, IpAddr, bool)> {
+        changes
+            .iter()
+            .map(|c| (c.adapter.as_str(), c.address, c.is_added()))
+            .collect()
+    }
+
+    #[test]
+    fn reports_changes_between_states() {
+        let changes = replay(
+            &from(),
+            &to(),
+            &FilterChain::new(),
+            IpVersion::Both,
+            SystemTime::UNIX_EPOCH,
+        );
+
+        assert_eq!(changes.len(), 8);
+        assert!(
+            changes
+                .iter()
+                .all(|c| c.timestamp == SystemTime::UNIX_EPOCH)
+        );
+    }
+
+    #[test]
+    fn filtered_adapters_are_ignored() {
+        let filter = FilterChain::new().exclude(KindFilter::new([AdapterKind::Virtual]));
+
+        let changes = replay(
+            &from(),
+            &to(),
+            &filter,
+            IpVersion::V4,
+            SystemTime::UNIX_EPOCH,
+        );
+
+        let mut found = addresses(&changes);
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                ("eth0", "192.0.2.1".parse().unwrap(), false),
+                ("eth0", "192.0.2.2".parse().unwrap(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn identical_states_have_no_changes() {
+        let changes = replay(
+            &to(),
+            &to(),
+            &FilterChain::new(),
+            IpVersion::Both,
+            SystemTime::UNIX_EPOCH,
+        );
+
+        assert!(changes.is_empty());
+    }
+}
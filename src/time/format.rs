@@ -0,0 +1,280 @@
+//! RFC 3339 and RFC 2822 date-times, formatted and parsed without a date
+//! crate.
+
+use std::time::{Duration, SystemTime};
+
+/// Formats Unix seconds as an RFC 3339 UTC date-time, e.g.
+/// `2024-05-01T12:00:00Z`.
+#[must_use]
+pub fn format_rfc3339(secs: u64) -> String {
+    let (year, month, day) = civil_date(secs / 86_400);
+    format!("{year:04}-{month:02}-{day:02}T{}Z", time_of_day(secs))
+}
+
+/// Parses an RFC 3339 date-time, e.g. `2024-05-01T12:00:00Z` or
+/// `2024-05-01T14:00:00.25+02:00`.
+///
+/// Fractions beyond nanoseconds are truncated. Returns `None` if `text` is
+/// malformed or before the epoch.
+///
+/// # Examples
+///
+/// ```
+/// use ddns_a::time::parse_rfc3339;
+/// use std::time::{Duration, SystemTime};
+///
+/// let at = parse_rfc3339("2024-05-01T14:00:00+02:00").unwrap();
+/// assert_eq!(at, SystemTime::UNIX_EPOCH + Duration::from_secs(1_714_564_800));
+/// assert_eq!(parse_rfc3339("2024-02-30T00:00:00Z"), None);
+/// ```
+#[must_use]
+pub fn parse_rfc3339(text: &str) -> Option<SystemTime> {
+    let (date, time) = text.split_once(['T', 't', ' '])?;
+    let mut fields = date.splitn(3, '-');
+    let year = digits(fields.next()?, 4)?;
+    let month = digits(fields.next()?, 2)?;
+    let day = digits(fields.next()?, 2)?;
+    let days = days_from_civil(year, month, day)?;
+
+    let (time, offset) = if let Some(time) = time.strip_suffix(['Z', 'z']) {
+        (time, 0)
+    } else {
+        let (time, offset) = time.split_at(time.rfind(['+', '-'])?);
+        let (sign, offset) = offset.split_at(1);
+        let (hours, minutes) = offset.split_once(':')?;
+        let offset =
+            i64::try_from(two_digits(hours, 24)? * 3_600 + two_digits(minutes, 60)? * 60).ok()?;
+        (time, if sign == "-" { -offset } else { offset })
+    };
+    let (time, nanos) = match time.split_once('.') {
+        Some((time, fraction)) => (time, parse_fraction(fraction)?),
+        None => (time, 0),
+    };
+    let mut parts = time.splitn(3, ':');
+    let hours = two_digits(parts.next()?, 24)?;
+    let minutes = two_digits(parts.next()?, 60)?;
+    // 60 is a leap second
+    let seconds = two_digits(parts.next()?, 61)?;
+
+    let local = days * 86_400 + hours * 3_600 + minutes * 60 + seconds;
+    let secs = u64::try_from(i64::try_from(local).ok()? - offset).ok()?;
+    Some(SystemTime::UNIX_EPOCH + Duration::new(secs, nanos))
+}
+
+/// Formats Unix seconds as an RFC 2822 date-time in UTC, as email `Date`
+/// headers carry it, e.g. `Wed, 01 May 2024 12:00:00 +0000`.
+#[must_use]
+pub fn format_rfc2822(secs: u64) -> String {
+    const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let days = secs / 86_400;
+    let (year, month, day) = civil_date(days);
+    // The epoch was a Thursday; both indexes are below the array lengths
+    #[allow(clippy::cast_possible_truncation)]
+    let (weekday, month) = (WEEKDAYS[(days % 7) as usize], MONTHS[(month - 1) as usize]);
+    format!(
+        "{weekday}, {day:02} {month} {year:04} {} +0000",
+        time_of_day(secs)
+    )
+}
+
+/// Proleptic Gregorian year, month and day of a day since the epoch
+/// (Howard Hinnant's algorithm).
+fn civil_date(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Day since the epoch of a proleptic Gregorian date (the inverse of
+/// [`civil_date`]); `None` if the date does not exist or precedes the epoch.
+fn days_from_civil(year: u64, month: u64, day: u64) -> Option<u64> {
+    if year < 1970 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    // Years start in March, so February's leap day comes last
+    let march_year = year - u64::from(month <= 2);
+    let era = march_year / 400;
+    let year_of_era = march_year % 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    // Rejects days past the end of the month, e.g. February 30
+    (civil_date(days) == (year, month, day)).then_some(days)
+}
+
+/// A field of exactly `len` decimal digits.
+fn digits(text: &str, len: usize) -> Option<u64> {
+    (text.len() == len && text.bytes().all(|b| b.is_ascii_digit()))
+        .then(|| text.parse().ok())
+        .flatten()
+}
+
+/// A two-digit field below `limit`.
+fn two_digits(text: &str, limit: u64) -> Option<u64> {
+    digits(text, 2).filter(|&value| value < limit)
+}
+
+/// Nanoseconds of the digits after a decimal point.
+fn parse_fraction(fraction: &str) -> Option<u32> {
+    if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    format!("{:0<9}", &fraction[..fraction.len().min(9)])
+        .parse()
+        .ok()
+}
+
+/// `HH:MM:SS` of Unix seconds.
+fn time_of_day(secs: u64) -> String {
+    let secs_of_day = secs % 86_400;
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs_of_day / 3_600,
+        secs_of_day % 3_600 / 60,
+        secs_of_day % 60
+    )
+}
+
+/// Serde adapter storing a [`SystemTime`] as an RFC 3339 UTC date-time, for
+/// `#[serde(with = "ddns_a::time::rfc3339")]`.
+///
+/// Whole seconds are written as [`format_rfc3339`] writes them, sub-second
+/// precision as a fraction (e.g. `2024-05-01T12:00:00.25Z`); times before
+/// the epoch as the epoch. Any [`parse_rfc3339`] input is read back.
+pub mod rfc3339 {
+    use std::time::SystemTime;
+
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    /// Serializes `time` as an RFC 3339 UTC date-time.
+    ///
+    /// # Errors
+    ///
+    /// Returns the serializer's error.
+    pub fn serialize<S: Serializer>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+        let since = time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        let mut text = super::format_rfc3339(since.as_secs());
+        if since.subsec_nanos() != 0 {
+            let fraction = format!("{:09}", since.subsec_nanos());
+            // Before the trailing `Z`
+            text.insert_str(
+                text.len() - 1,
+                &format!(".{}", fraction.trim_end_matches('0')),
+            );
+        }
+        serializer.serialize_str(&text)
+    }
+
+    /// Deserializes an RFC 3339 date-time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not a string holding a date-time
+    /// [`parse_rfc3339`](super::parse_rfc3339) accepts.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SystemTime, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        super::parse_rfc3339(&text)
+            .ok_or_else(|| D::Error::custom(format!("invalid RFC 3339 date-time '{text}'")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_rfc3339_dates() {
+        assert_eq!(format_rfc3339(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_rfc3339(951_868_799), "2000-02-29T23:59:59Z");
+        assert_eq!(format_rfc3339(1_714_564_800), "2024-05-01T12:00:00Z");
+    }
+
+    #[test]
+    fn parses_rfc3339_dates() {
+        let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+
+        assert_eq!(parse_rfc3339("1970-01-01T00:00:00Z"), Some(at(0)));
+        assert_eq!(parse_rfc3339("2000-02-29T23:59:59Z"), Some(at(951_868_799)));
+        assert_eq!(
+            parse_rfc3339("2024-05-01t10:00:00-02:00"),
+            Some(at(1_714_564_800))
+        );
+        assert_eq!(
+            parse_rfc3339("2024-05-01T12:00:00.25Z"),
+            Some(at(1_714_564_800) + Duration::from_millis(250))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_rfc3339_dates() {
+        for text in [
+            "",
+            "2024-05-01",
+            "2024-05-01T12:00:00",
+            "2024-5-01T12:00:00Z",
+            "2023-02-29T12:00:00Z",
+            "2024-05-01T24:00:00Z",
+            "2024-05-01T12:00:00.Z",
+            "2024-05-01T12:00:00+2:00",
+            "1969-12-31T23:59:59Z",
+            "1970-01-01T00:30:00+01:00",
+        ] {
+            assert_eq!(parse_rfc3339(text), None, "{text}");
+        }
+    }
+
+    #[test]
+    fn rfc3339_serde_round_trips() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Stamped {
+            #[serde(with = "rfc3339")]
+            at: SystemTime,
+        }
+
+        for (at, text) in [
+            (Duration::from_secs(1_714_564_800), "2024-05-01T12:00:00Z"),
+            (
+                Duration::new(1_714_564_800, 250_000_000),
+                "2024-05-01T12:00:00.25Z",
+            ),
+            (Duration::new(0, 1), "1970-01-01T00:00:00.000000001Z"),
+        ] {
+            let stamped = Stamped {
+                at: SystemTime::UNIX_EPOCH + at,
+            };
+            let json = serde_json::to_value(&stamped).unwrap();
+            assert_eq!(json["at"], text);
+
+            let restored: Stamped = serde_json::from_value(json).unwrap();
+            assert_eq!(restored.at, stamped.at);
+        }
+        assert!(serde_json::from_str::<Stamped>(r#"{"at": 0}"#).is_err());
+    }
+
+    #[test]
+    fn formats_rfc2822_dates() {
+        assert_eq!(format_rfc2822(0), "Thu, 01 Jan 1970 00:00:00 +0000");
+        assert_eq!(
+            format_rfc2822(951_868_799),
+            "Tue, 29 Feb 2000 23:59:59 +0000"
+        );
+        assert_eq!(
+            format_rfc2822(1_714_564_800),
+            "Wed, 01 May 2024 12:00:00 +0000"
+        );
+    }
+}
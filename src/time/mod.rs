@@ -0,0 +1,528 @@
+//! Time abstraction for testability.
+//!
+//! This module provides a [`Clock`] trait that allows injecting mock clocks
+//! in tests while using the real system clock in production, a [`Sleeper`]
+//! trait for injectable async delays, a [`ShutdownToken`] that cuts such
+//! delays short on shutdown, and a [`TimeJumpDetector`] that notices when
+//! the wall clock is stepped relative to the monotonic clock.
+//! [`format_rfc3339`] formats timestamps for payloads without a date crate;
+//! [`parse_rfc3339`] and the [`rfc3339`] serde adapter read them back.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+mod format;
+
+pub use format::{format_rfc2822, format_rfc3339, parse_rfc3339, rfc3339};
+
+/// Abstraction over system time for testability.
+///
+/// Implementations provide the current time, allowing tests to inject
+/// controlled time values instead of relying on actual system time.
+///
+/// # Example
+///
+/// ```
+/// use ddns_a::time::{Clock, SystemClock};
+///
+/// let clock = SystemClock;
+/// let now = clock.now();
+/// assert!(now >= std::time::SystemTime::UNIX_EPOCH);
+/// ```
+pub trait Clock: Send + Sync {
+    /// Returns the current time.
+    fn now(&self) -> SystemTime;
+}
+
+/// Production clock using actual system time.
+///
+/// This is the default clock implementation that delegates to
+/// [`SystemTime::now()`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Abstraction over async sleep for testability.
+///
+/// Implementations provide async delay functionality, allowing tests to
+/// inject instant/mock sleeps instead of waiting for real time.
+///
+/// # Example
+///
+/// ```
+/// use ddns_a::time::{Sleeper, TokioSleeper};
+/// use std::time::Duration;
+///
+/// async fn example() {
+///     let sleeper = TokioSleeper;
+///     sleeper.sleep(Duration::from_millis(100)).await;
+/// }
+/// ```
+pub trait Sleeper: Send + Sync {
+    /// Sleeps for the specified duration.
+    fn sleep(&self, duration: Duration) -> impl std::future::Future<Output = ()> + Send;
+}
+
+/// Production sleeper using tokio's sleep.
+///
+/// This is the default sleeper implementation that delegates to
+/// [`tokio::time::sleep`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioSleeper;
+
+impl Sleeper for TokioSleeper {
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+/// Mock sleeper that returns immediately without waiting.
+///
+/// Useful for testing retry logic without actual delays.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InstantSleeper;
+
+impl Sleeper for InstantSleeper {
+    async fn sleep(&self, _duration: Duration) {
+        // Return immediately - no actual sleep
+    }
+}
+
+/// A shutdown signal shared by the tasks that stop on it.
+///
+/// Clones observe the same signal, and once [`trigger`](Self::trigger)ed it
+/// stays triggered. Long waits such as retry backoffs race against
+/// [`triggered`](Self::triggered) so that shutdown does not wait for them.
+///
+/// # Example
+///
+/// ```
+/// use ddns_a::time::ShutdownToken;
+///
+/// let token = ShutdownToken::new();
+/// let observer = token.clone();
+///
+/// token.trigger();
+/// assert!(observer.is_triggered());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ShutdownToken {
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl ShutdownToken {
+    /// Creates a token that has not been triggered.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            sender: Arc::new(watch::Sender::new(false)),
+        }
+    }
+
+    /// Signals shutdown to every clone of this token.
+    pub fn trigger(&self) {
+        self.sender.send_replace(true);
+    }
+
+    /// Returns true once shutdown was signalled.
+    #[must_use]
+    pub fn is_triggered(&self) -> bool {
+        *self.sender.borrow()
+    }
+
+    /// Completes once shutdown is signalled (immediately if it already was).
+    pub async fn triggered(&self) {
+        let mut receiver = self.sender.subscribe();
+        // The sender lives in `self`, so the channel cannot close while waiting
+        let _ = receiver.wait_for(|&triggered| triggered).await;
+    }
+}
+
+impl Default for ShutdownToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A step of the wall clock relative to the monotonic clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeJump {
+    /// The wall clock moved ahead (e.g. NTP step forward, resume from suspend).
+    Forward(Duration),
+    /// The wall clock moved back (e.g. NTP step backward).
+    Backward(Duration),
+}
+
+impl fmt::Display for TimeJump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Forward(d) => write!(f, "wall clock jumped forward by {}s", d.as_secs()),
+            Self::Backward(d) => write!(f, "wall clock jumped backward by {}s", d.as_secs()),
+        }
+    }
+}
+
+/// Detects wall-clock jumps by comparing [`Clock`] time with monotonic time.
+///
+/// Debounce windows and retry delays run on tokio's monotonic clock while
+/// change timestamps come from the wall clock, so a large step (NTP, DST on
+/// some platforms) makes the two disagree. Each [`observe`](Self::observe)
+/// compares how far both clocks moved since the previous observation and
+/// reports a [`TimeJump`] when they differ by more than the tolerance.
+#[derive(Debug, Clone)]
+pub struct TimeJumpDetector {
+    tolerance: Duration,
+    baseline: Option<(SystemTime, Instant)>,
+}
+
+impl TimeJumpDetector {
+    /// Default drift tolerated between observations.
+    pub const DEFAULT_TOLERANCE: Duration = Duration::from_secs(10);
+
+    /// Creates a detector reporting drift larger than `tolerance`.
+    #[must_use]
+    pub const fn new(tolerance: Duration) -> Self {
+        Self {
+            tolerance,
+            baseline: None,
+        }
+    }
+
+    /// Returns the drift tolerated between observations.
+    #[must_use]
+    pub const fn tolerance(&self) -> Duration {
+        self.tolerance
+    }
+
+    /// Records the current time of both clocks and reports any jump since
+    /// the previous observation.
+    ///
+    /// The baseline is re-taken on every call, so a jump is reported once.
+    /// The first observation only establishes the baseline.
+    pub fn observe(&mut self, clock: &impl Clock, now: Instant) -> Option<TimeJump> {
+        let wall = clock.now();
+        let previous = self.baseline.replace((wall, now));
+        let (prev_wall, prev_mono) = previous?;
+
+        let monotonic = now.saturating_duration_since(prev_mono);
+        let jump = match wall.duration_since(prev_wall) {
+            Ok(elapsed) if elapsed > monotonic => {
+                TimeJump::Forward(elapsed.saturating_sub(monotonic))
+            }
+            Ok(elapsed) => TimeJump::Backward(monotonic.saturating_sub(elapsed)),
+            Err(e) => TimeJump::Backward(monotonic + e.duration()),
+        };
+
+        let (TimeJump::Forward(drift) | TimeJump::Backward(drift)) = jump;
+        (drift > self.tolerance).then_some(jump)
+    }
+}
+
+impl Default for TimeJumpDetector {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TOLERANCE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::Duration;
+
+    /// A mock clock for testing that returns controlled time values.
+    struct MockClock {
+        /// Seconds since `UNIX_EPOCH`, atomically updated.
+        secs: AtomicU64,
+    }
+
+    impl MockClock {
+        fn new(initial_secs: u64) -> Self {
+            Self {
+                secs: AtomicU64::new(initial_secs),
+            }
+        }
+
+        fn advance(&self, secs: u64) {
+            self.secs.fetch_add(secs, Ordering::SeqCst);
+        }
+
+        fn rewind(&self, secs: u64) {
+            self.secs.fetch_sub(secs, Ordering::SeqCst);
+        }
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> SystemTime {
+            SystemTime::UNIX_EPOCH + Duration::from_secs(self.secs.load(Ordering::SeqCst))
+        }
+    }
+
+    #[test]
+    fn system_clock_returns_current_time() {
+        let clock = SystemClock;
+        let before = SystemTime::now();
+        let result = clock.now();
+        let after = SystemTime::now();
+
+        assert!(result >= before);
+        assert!(result <= after);
+    }
+
+    #[test]
+    fn system_clock_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<SystemClock>();
+    }
+
+    fn assert_default<T: Default>() {}
+
+    #[test]
+    fn system_clock_is_default() {
+        assert_default::<SystemClock>();
+    }
+
+    #[test]
+    fn system_clock_is_copy() {
+        let clock1 = SystemClock;
+        let clock2 = clock1;
+        // Both are usable (Copy semantics)
+        let _ = clock1.now();
+        let _ = clock2.now();
+    }
+
+    #[test]
+    fn mock_clock_returns_controlled_time() {
+        let clock = MockClock::new(1_000_000);
+        let expected = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+        assert_eq!(clock.now(), expected);
+    }
+
+    #[test]
+    fn mock_clock_can_advance() {
+        let clock = MockClock::new(0);
+
+        assert_eq!(clock.now(), SystemTime::UNIX_EPOCH);
+
+        clock.advance(100);
+        assert_eq!(
+            clock.now(),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(100)
+        );
+
+        clock.advance(50);
+        assert_eq!(
+            clock.now(),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(150)
+        );
+    }
+
+    #[test]
+    fn mock_clock_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<MockClock>();
+    }
+
+    // Sleeper tests
+
+    #[tokio::test]
+    async fn tokio_sleeper_completes() {
+        let sleeper = TokioSleeper;
+        // Very short sleep to verify it works
+        sleeper.sleep(Duration::from_millis(1)).await;
+    }
+
+    #[test]
+    fn tokio_sleeper_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<TokioSleeper>();
+    }
+
+    #[test]
+    fn tokio_sleeper_is_default() {
+        assert_default::<TokioSleeper>();
+    }
+
+    #[test]
+    fn tokio_sleeper_is_copy() {
+        let sleeper1 = TokioSleeper;
+        let sleeper2 = sleeper1;
+        // Both are usable (Copy semantics)
+        let _ = sleeper1;
+        let _ = sleeper2;
+    }
+
+    #[tokio::test]
+    async fn instant_sleeper_returns_immediately() {
+        let sleeper = InstantSleeper;
+        let start = std::time::Instant::now();
+        sleeper.sleep(Duration::from_secs(1000)).await;
+        // Should complete almost instantly
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+
+    #[test]
+    fn instant_sleeper_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<InstantSleeper>();
+    }
+
+    #[test]
+    fn instant_sleeper_is_default() {
+        assert_default::<InstantSleeper>();
+    }
+
+    #[test]
+    fn instant_sleeper_is_copy() {
+        let sleeper1 = InstantSleeper;
+        let sleeper2 = sleeper1;
+        // Both are usable (Copy semantics)
+        let _ = sleeper1;
+        let _ = sleeper2;
+    }
+
+    // TimeJumpDetector tests
+
+    fn detector() -> TimeJumpDetector {
+        TimeJumpDetector::new(Duration::from_secs(10))
+    }
+
+    #[test]
+    fn first_observation_sets_baseline() {
+        let clock = MockClock::new(1000);
+
+        assert_eq!(detector().observe(&clock, Instant::now()), None);
+    }
+
+    #[test]
+    fn clocks_moving_together_are_not_a_jump() {
+        let clock = MockClock::new(1000);
+        let start = Instant::now();
+        let mut detector = detector();
+        detector.observe(&clock, start);
+
+        clock.advance(60);
+        let jump = detector.observe(&clock, start + Duration::from_secs(60));
+
+        assert_eq!(jump, None);
+    }
+
+    #[test]
+    fn drift_within_tolerance_is_ignored() {
+        let clock = MockClock::new(1000);
+        let start = Instant::now();
+        let mut detector = detector();
+        detector.observe(&clock, start);
+
+        clock.advance(70);
+        let jump = detector.observe(&clock, start + Duration::from_secs(60));
+
+        assert_eq!(jump, None);
+    }
+
+    #[test]
+    fn detects_forward_jump() {
+        let clock = MockClock::new(1000);
+        let start = Instant::now();
+        let mut detector = detector();
+        detector.observe(&clock, start);
+
+        clock.advance(3660);
+        let jump = detector.observe(&clock, start + Duration::from_secs(60));
+
+        assert_eq!(jump, Some(TimeJump::Forward(Duration::from_secs(3600))));
+    }
+
+    #[test]
+    fn detects_backward_jump() {
+        let clock = MockClock::new(10_000);
+        let start = Instant::now();
+        let mut detector = detector();
+        detector.observe(&clock, start);
+
+        clock.rewind(3540);
+        let jump = detector.observe(&clock, start + Duration::from_secs(60));
+
+        assert_eq!(jump, Some(TimeJump::Backward(Duration::from_secs(3600))));
+    }
+
+    #[test]
+    fn frozen_wall_clock_is_backward_jump() {
+        let clock = MockClock::new(1000);
+        let start = Instant::now();
+        let mut detector = detector();
+        detector.observe(&clock, start);
+
+        let jump = detector.observe(&clock, start + Duration::from_secs(30));
+
+        assert_eq!(jump, Some(TimeJump::Backward(Duration::from_secs(30))));
+    }
+
+    #[test]
+    fn jump_is_reported_once() {
+        let clock = MockClock::new(1000);
+        let start = Instant::now();
+        let mut detector = detector();
+        detector.observe(&clock, start);
+
+        clock.advance(3600);
+        assert!(detector.observe(&clock, start).is_some());
+
+        clock.advance(60);
+        let jump = detector.observe(&clock, start + Duration::from_secs(60));
+        assert_eq!(jump, None);
+    }
+
+    #[test]
+    fn time_jump_display() {
+        assert_eq!(
+            TimeJump::Forward(Duration::from_secs(3600)).to_string(),
+            "wall clock jumped forward by 3600s"
+        );
+        assert_eq!(
+            TimeJump::Backward(Duration::from_secs(5)).to_string(),
+            "wall clock jumped backward by 5s"
+        );
+    }
+
+    #[tokio::test]
+    async fn shutdown_token_reaches_clones() {
+        let token = ShutdownToken::new();
+        let observer = token.clone();
+        assert!(!observer.is_triggered());
+
+        let waiter = tokio::spawn(async move { observer.triggered().await });
+        token.trigger();
+
+        waiter.await.unwrap();
+        assert!(token.is_triggered());
+    }
+
+    #[tokio::test]
+    async fn triggered_token_completes_immediately() {
+        let token = ShutdownToken::default();
+        token.trigger();
+
+        tokio::time::timeout(Duration::from_secs(1), token.triggered())
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn default_detector_uses_default_tolerance() {
+        assert_eq!(
+            TimeJumpDetector::default().tolerance(),
+            TimeJumpDetector::DEFAULT_TOLERANCE
+        );
+    }
+}
@@ -0,0 +1,33 @@
+//! Tray icon companion mode for desktops (`--tray`).
+//!
+//! [`TrayStatus`] is what the icon shows: the effective addresses, the
+//! outcome of the latest delivery and whether notifications are paused.
+//! Menu actions arrive as [`TrayCommand`]s. Pausing flips a [`PauseSwitch`],
+//! which postpones deliveries as a connectivity probe, so changes detected
+//! while paused are sent after resuming instead of being lost.
+//!
+//! The icon itself, `TrayIcon`, needs Windows and the `tray` feature: it
+//! runs the Win32 message loop on a thread of its own, beside the tokio
+//! runtime.
+
+mod status;
+#[cfg(all(windows, feature = "tray"))]
+mod windows;
+
+#[cfg(test)]
+#[path = "mod_tests.rs"]
+mod tests;
+
+pub use status::{PauseSwitch, TrayCommand, TrayStatus};
+#[cfg(all(windows, feature = "tray"))]
+pub use windows::TrayIcon;
+
+use thiserror::Error;
+
+/// Errors that can occur while showing the tray icon.
+#[derive(Debug, Error)]
+pub enum TrayError {
+    /// The hidden window, the icon or its thread could not be created.
+    #[error("Failed to create the tray icon: {0}")]
+    Create(String),
+}
@@ -0,0 +1,118 @@
+//! Tests for the tray module.
+
+use std::time::SystemTime;
+
+use super::{PauseSwitch, TrayStatus};
+use crate::network::{AdapterKind, AdapterSnapshot, IpVersion};
+use crate::report::{DeliveryOutcome, RunStats};
+use crate::webhook::{ConnectivityProbe, Reachability};
+
+fn status(ipv4: Option<&str>, ipv6: Option<&str>) -> TrayStatus {
+    TrayStatus {
+        ipv4: ipv4.map(|a| a.parse().unwrap()),
+        ipv6: ipv6.map(|a| a.parse().unwrap()),
+        ..TrayStatus::default()
+    }
+}
+
+mod tray_status {
+    use super::*;
+
+    #[test]
+    fn built_from_effective_addresses_and_last_delivery() {
+        let stats = RunStats::new(SystemTime::UNIX_EPOCH);
+        stats.record_snapshot(
+            &[AdapterSnapshot::new(
+                "eth0",
+                AdapterKind::Ethernet,
+                vec!["192.0.2.5".parse().unwrap()],
+                vec!["2001:db8::5".parse().unwrap()],
+            )],
+            IpVersion::Both,
+        );
+        stats.record_delivery(&Ok(()));
+
+        let shown = TrayStatus::new(&stats.summary(SystemTime::UNIX_EPOCH), true);
+
+        assert_eq!(
+            shown,
+            TrayStatus {
+                last_delivery: Some(DeliveryOutcome::Sent),
+                paused: true,
+                ..status(Some("192.0.2.5"), Some("2001:db8::5"))
+            }
+        );
+    }
+
+    #[test]
+    fn tooltip_lists_addresses_and_last_delivery() {
+        let status = TrayStatus {
+            last_delivery: Some(DeliveryOutcome::Failed),
+            ..status(Some("192.0.2.5"), Some("2001:db8::5"))
+        };
+
+        assert_eq!(
+            status.tooltip(),
+            "ddns-a: 192.0.2.5, 2001:db8::5\nLast update: failed"
+        );
+    }
+
+    #[test]
+    fn tooltip_before_first_delivery() {
+        assert_eq!(
+            status(None, None).tooltip(),
+            "ddns-a: no address\nLast update: none yet"
+        );
+    }
+
+    #[test]
+    fn tooltip_marks_pause() {
+        let status = TrayStatus {
+            paused: true,
+            ..status(Some("192.0.2.5"), None)
+        };
+
+        assert!(status.tooltip().ends_with("none yet (paused)"));
+        assert_eq!(status.pause_label(), "Resume notifications");
+    }
+
+    #[test]
+    fn longest_tooltip_fits() {
+        let status = TrayStatus {
+            last_delivery: Some(DeliveryOutcome::Postponed),
+            paused: true,
+            ..status(
+                Some("255.255.255.255"),
+                Some("ffff:ffff:ffff:ffff:ffff:ffff:255.255.255.255"),
+            )
+        };
+
+        assert!(status.tooltip().encode_utf16().count() <= 127);
+    }
+}
+
+mod pause_switch {
+    use super::*;
+
+    #[test]
+    fn toggle_reports_new_state() {
+        let switch = PauseSwitch::new();
+        let shared = switch.clone();
+
+        assert!(!switch.is_paused());
+        assert!(switch.toggle());
+        assert!(shared.is_paused());
+        assert!(!switch.toggle());
+        assert!(!switch.is_paused());
+    }
+
+    #[tokio::test]
+    async fn postpones_deliveries_while_paused() {
+        let switch = PauseSwitch::new();
+        assert_eq!(switch.probe().await, Reachability::Reachable);
+
+        let _ = switch.toggle();
+
+        assert!(matches!(switch.probe().await, Reachability::Unreachable(_)));
+    }
+}
@@ -0,0 +1,119 @@
+//! What the tray icon shows and the actions its menu sends.
+
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::report::{DeliveryOutcome, RunSummary};
+use crate::webhook::{ConnectivityProbe, Reachability};
+
+/// An action chosen from the tray menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayCommand {
+    /// Send the current addresses now, as if they had just been added.
+    ForceNotify,
+    /// Pause notifications, or resume them.
+    TogglePause,
+    /// Shut down, as Ctrl+C would.
+    Exit,
+}
+
+/// The state shown by the tray icon.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrayStatus {
+    /// Effective IPv4 address.
+    pub ipv4: Option<IpAddr>,
+    /// Effective IPv6 address.
+    pub ipv6: Option<IpAddr>,
+    /// Outcome of the latest delivery; `None` before the first.
+    pub last_delivery: Option<DeliveryOutcome>,
+    /// Whether notifications are paused.
+    pub paused: bool,
+}
+
+impl TrayStatus {
+    /// Builds the status from a run summary.
+    #[must_use]
+    pub fn new(summary: &RunSummary, paused: bool) -> Self {
+        let effective = &summary.effective_addresses;
+        Self {
+            ipv4: effective.ipv4.as_ref().map(|e| e.address),
+            ipv6: effective.ipv6.as_ref().map(|e| e.address),
+            last_delivery: summary.last_delivery,
+            paused,
+        }
+    }
+
+    /// Returns the tooltip: the addresses on the first line, the latest
+    /// delivery on the second.
+    ///
+    /// It always fits the 127 characters the notification area shows.
+    #[must_use]
+    pub fn tooltip(&self) -> String {
+        let addresses: Vec<String> = [self.ipv4, self.ipv6]
+            .iter()
+            .flatten()
+            .map(ToString::to_string)
+            .collect();
+        let addresses = if addresses.is_empty() {
+            "no address".to_string()
+        } else {
+            addresses.join(", ")
+        };
+        let delivery = self
+            .last_delivery
+            .map_or("none yet", DeliveryOutcome::as_str);
+        let paused = if self.paused { " (paused)" } else { "" };
+
+        format!("ddns-a: {addresses}\nLast update: {delivery}{paused}")
+    }
+
+    /// Returns the label of the pause menu item.
+    #[must_use]
+    pub const fn pause_label(&self) -> &'static str {
+        if self.paused {
+            "Resume notifications"
+        } else {
+            "Pause notifications"
+        }
+    }
+}
+
+/// Pause state shared by the tray menu and the delivery gate.
+///
+/// As the probe of a [`ConnectivityGate`](crate::webhook::ConnectivityGate),
+/// it postpones every delivery while paused; the postponed changes go out
+/// with the first delivery after resuming.
+#[derive(Debug, Clone, Default)]
+pub struct PauseSwitch(Arc<AtomicBool>);
+
+impl PauseSwitch {
+    /// Creates a switch with notifications running.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if notifications are paused.
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Pauses running notifications or resumes paused ones; returns true if
+    /// they are paused now.
+    #[must_use]
+    pub fn toggle(&self) -> bool {
+        !self.0.fetch_xor(true, Ordering::SeqCst)
+    }
+}
+
+impl ConnectivityProbe for PauseSwitch {
+    async fn probe(&self) -> Reachability {
+        if self.is_paused() {
+            Reachability::Unreachable("notifications paused from the tray".to_string())
+        } else {
+            Reachability::Reachable
+        }
+    }
+}
@@ -0,0 +1,324 @@
+//! Windows notification area icon.
+
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex, PoisonError, mpsc};
+use std::thread::JoinHandle;
+
+use tokio::sync::mpsc::UnboundedSender;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, POINT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::Shell::{
+    NIF_ICON, NIF_MESSAGE, NIF_TIP, NIM_ADD, NIM_DELETE, NIM_MODIFY, NOTIFYICONDATAW,
+    Shell_NotifyIconW,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    AppendMenuW, CreatePopupMenu, CreateWindowExW, DefWindowProcW, DestroyMenu, DestroyWindow,
+    DispatchMessageW, GetCursorPos, GetMessageW, IDI_APPLICATION, LoadIconW, MF_SEPARATOR,
+    MF_STRING, MSG, PostMessageW, PostQuitMessage, RegisterClassW, SetForegroundWindow,
+    TPM_RETURNCMD, TPM_RIGHTBUTTON, TrackPopupMenu, TranslateMessage, WINDOW_EX_STYLE, WM_APP,
+    WM_CLOSE, WM_CONTEXTMENU, WM_DESTROY, WM_NULL, WM_RBUTTONUP, WNDCLASSW, WS_OVERLAPPED,
+};
+use windows::core::{HSTRING, PCWSTR, w};
+
+use super::{TrayCommand, TrayError, TrayStatus};
+
+/// Callback message for mouse events on the icon.
+const WM_TRAY: u32 = WM_APP + 1;
+/// Posted by [`TrayIcon::show`] to update the tooltip.
+const WM_REFRESH: u32 = WM_APP + 2;
+
+const CLASS_NAME: PCWSTR = w!("ddns-a-tray");
+
+/// Menu items, in menu order; the separator sits before [`TrayCommand::Exit`].
+const MENU: [TrayCommand; 3] = [
+    TrayCommand::ForceNotify,
+    TrayCommand::TogglePause,
+    TrayCommand::Exit,
+];
+
+/// A notification area icon showing a [`TrayStatus`].
+///
+/// Win32 windows belong to the thread that creates them, so the icon's
+/// hidden window and its message loop run on a dedicated thread. Menu
+/// actions are sent to the monitor loop as [`TrayCommand`]s. Dropping the
+/// icon removes it and ends the thread.
+#[derive(Debug)]
+pub struct TrayIcon {
+    /// The hidden window's address, as `HWND` is not `Send`
+    window: usize,
+    status: Arc<Mutex<TrayStatus>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl TrayIcon {
+    /// Shows the icon, sending menu actions on `commands`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TrayError::Create`] if the thread, the window or the icon
+    /// cannot be created.
+    pub fn spawn(commands: UnboundedSender<TrayCommand>) -> Result<Self, TrayError> {
+        let status = Arc::new(Mutex::new(TrayStatus::default()));
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let shared = Arc::clone(&status);
+        let thread = std::thread::Builder::new()
+            .name("tray".to_string())
+            .spawn(move || run(commands, shared, &ready_tx))
+            .map_err(|e| TrayError::Create(e.to_string()))?;
+
+        match ready_rx.recv() {
+            Ok(Ok(window)) => Ok(Self {
+                window,
+                status,
+                thread: Some(thread),
+            }),
+            Ok(Err(e)) => {
+                let _ = thread.join();
+                Err(e)
+            }
+            Err(_) => Err(TrayError::Create("tray thread exited".to_string())),
+        }
+    }
+
+    /// Shows `status`, updating the tooltip and menu if it changed.
+    pub fn show(&self, status: TrayStatus) {
+        let mut current = self.status.lock().unwrap_or_else(PoisonError::into_inner);
+        if *current == status {
+            return;
+        }
+        *current = status;
+        drop(current);
+        self.post(WM_REFRESH);
+    }
+
+    fn post(&self, message: u32) {
+        let window = HWND(std::ptr::with_exposed_provenance_mut(self.window));
+        // SAFETY: any thread may post to a window; messages to a window that
+        // is already destroyed are dropped.
+        let _ = unsafe { PostMessageW(Some(window), message, WPARAM(0), LPARAM(0)) };
+    }
+}
+
+impl Drop for TrayIcon {
+    fn drop(&mut self) {
+        // Closing destroys the window, which removes the icon and ends the loop
+        self.post(WM_CLOSE);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// State of the window procedure, owned by the tray thread.
+struct WindowState {
+    commands: UnboundedSender<TrayCommand>,
+    status: Arc<Mutex<TrayStatus>>,
+    icon: NOTIFYICONDATAW,
+}
+
+thread_local! {
+    static STATE: RefCell<Option<WindowState>> = const { RefCell::new(None) };
+}
+
+/// Body of the tray thread: creates the icon, then runs the message loop.
+fn run(
+    commands: UnboundedSender<TrayCommand>,
+    status: Arc<Mutex<TrayStatus>>,
+    ready: &mpsc::Sender<Result<usize, TrayError>>,
+) {
+    let window = match create(commands, status) {
+        Ok(window) => window,
+        Err(e) => {
+            let _ = ready.send(Err(e));
+            return;
+        }
+    };
+    let _ = ready.send(Ok(window.0.expose_provenance()));
+
+    let mut message = MSG::default();
+    // SAFETY: `message` outlives the loop; GetMessageW returns 0 on WM_QUIT
+    // and -1 on error, both of which end it.
+    while unsafe { GetMessageW(&raw mut message, None, 0, 0) }.0 > 0 {
+        // SAFETY: the message was filled in by GetMessageW.
+        unsafe {
+            let _ = TranslateMessage(&raw const message);
+            DispatchMessageW(&raw const message);
+        }
+    }
+}
+
+/// Creates the hidden window and adds its icon.
+fn create(
+    commands: UnboundedSender<TrayCommand>,
+    status: Arc<Mutex<TrayStatus>>,
+) -> Result<HWND, TrayError> {
+    let error = |e: windows::core::Error| TrayError::Create(e.message());
+
+    // SAFETY: every pointer passed refers to a live local or a static string.
+    unsafe {
+        let instance = GetModuleHandleW(None).map_err(error)?.into();
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(window_proc),
+            hInstance: instance,
+            lpszClassName: CLASS_NAME,
+            ..Default::default()
+        };
+        if RegisterClassW(&raw const class) == 0 {
+            return Err(error(windows::core::Error::from_thread()));
+        }
+        let window = CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            CLASS_NAME,
+            w!("ddns-a"),
+            WS_OVERLAPPED,
+            0,
+            0,
+            0,
+            0,
+            None,
+            None,
+            Some(instance),
+            None,
+        )
+        .map_err(error)?;
+
+        let mut icon = NOTIFYICONDATAW {
+            cbSize: u32::try_from(size_of::<NOTIFYICONDATAW>()).unwrap_or(u32::MAX),
+            hWnd: window,
+            uID: 1,
+            uFlags: NIF_ICON | NIF_MESSAGE | NIF_TIP,
+            uCallbackMessage: WM_TRAY,
+            hIcon: LoadIconW(None, IDI_APPLICATION).map_err(error)?,
+            ..Default::default()
+        };
+        set_tooltip(&mut icon, &TrayStatus::default());
+        if !Shell_NotifyIconW(NIM_ADD, &raw const icon).as_bool() {
+            let _ = DestroyWindow(window);
+            return Err(TrayError::Create(
+                "the notification area refused the icon".to_string(),
+            ));
+        }
+
+        STATE.set(Some(WindowState {
+            commands,
+            status,
+            icon,
+        }));
+        Ok(window)
+    }
+}
+
+/// Copies the tooltip of `status` into `icon`, cut to the field's size.
+fn set_tooltip(icon: &mut NOTIFYICONDATAW, status: &TrayStatus) {
+    let tooltip: Vec<u16> = status.tooltip().encode_utf16().collect();
+    let len = tooltip.len().min(icon.szTip.len() - 1);
+    icon.szTip = [0; 128];
+    icon.szTip[..len].copy_from_slice(&tooltip[..len]);
+}
+
+extern "system" fn window_proc(
+    window: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    match message {
+        WM_TRAY => {
+            // The low word of `lparam` holds the mouse message
+            let event = u32::try_from(lparam.0 & 0xFFFF).unwrap_or_default();
+            if event == WM_RBUTTONUP || event == WM_CONTEXTMENU {
+                show_menu(window);
+            }
+            LRESULT(0)
+        }
+        WM_REFRESH => {
+            STATE.with_borrow_mut(|state| {
+                if let Some(state) = state {
+                    let status = state
+                        .status
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .clone();
+                    set_tooltip(&mut state.icon, &status);
+                    // SAFETY: the icon data is live and names our own icon.
+                    let _ = unsafe { Shell_NotifyIconW(NIM_MODIFY, &raw const state.icon) };
+                }
+            });
+            LRESULT(0)
+        }
+        WM_DESTROY => {
+            if let Some(state) = STATE.take() {
+                // SAFETY: as above; the icon is removed before its window goes.
+                let _ = unsafe { Shell_NotifyIconW(NIM_DELETE, &raw const state.icon) };
+            }
+            // SAFETY: ends the message loop of this thread.
+            unsafe { PostQuitMessage(0) };
+            LRESULT(0)
+        }
+        // SAFETY: the default handling of every other message.
+        _ => unsafe { DefWindowProcW(window, message, wparam, lparam) },
+    }
+}
+
+/// Shows the menu at the cursor and sends the chosen command.
+fn show_menu(window: HWND) {
+    // Copied out: the menu runs a nested message loop that re-enters the
+    // window procedure, so the state must not stay borrowed
+    let Some((commands, status)) = STATE.with_borrow(|state| {
+        state.as_ref().map(|state| {
+            let status = state
+                .status
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone();
+            (state.commands.clone(), status)
+        })
+    }) else {
+        return;
+    };
+
+    // SAFETY: the menu is created, shown and destroyed within this call, and
+    // every string outlives the call that reads it.
+    let chosen = unsafe {
+        let Ok(menu) = CreatePopupMenu() else {
+            return;
+        };
+        for (id, command) in (1..).zip(MENU) {
+            let label = HSTRING::from(match command {
+                TrayCommand::ForceNotify => "Notify now",
+                TrayCommand::TogglePause => status.pause_label(),
+                TrayCommand::Exit => {
+                    let _ = AppendMenuW(menu, MF_SEPARATOR, 0, PCWSTR::null());
+                    "Exit"
+                }
+            });
+            let _ = AppendMenuW(menu, MF_STRING, id, &label);
+        }
+
+        let mut cursor = POINT::default();
+        let _ = GetCursorPos(&raw mut cursor);
+        // Without the foreground, the menu would not close on a click elsewhere
+        let _ = SetForegroundWindow(window);
+        let chosen = TrackPopupMenu(
+            menu,
+            TPM_RETURNCMD | TPM_RIGHTBUTTON,
+            cursor.x,
+            cursor.y,
+            None,
+            window,
+            None,
+        );
+        let _ = PostMessageW(Some(window), WM_NULL, WPARAM(0), LPARAM(0));
+        let _ = DestroyMenu(menu);
+        chosen.0
+    };
+
+    let command = usize::try_from(chosen)
+        .ok()
+        .and_then(|id| id.checked_sub(1))
+        .and_then(|index| MENU.get(index));
+    if let Some(&command) = command {
+        // A closed receiver means the monitor loop has ended
+        let _ = commands.send(command);
+    }
+}
@@ -0,0 +1,80 @@
+//! Replacing the running executable with a downloaded one.
+//!
+//! The new binary is first written next to the executable, so the final
+//! step is a rename within one directory. Unix renames over the running
+//! file; Windows cannot, but lets a running executable be renamed, so the
+//! old binary is moved aside to `<exe>.old` first and removed by the next
+//! update, once no process runs it anymore.
+
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::UpdateError;
+
+/// Returns `path` with `suffix` appended to its file name.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Path the previous executable is moved to on Windows.
+#[must_use]
+pub fn previous_path(exe: &Path) -> PathBuf {
+    with_suffix(exe, ".old")
+}
+
+/// Removes the executable left behind by a previous update, if any.
+pub fn remove_previous(exe: &Path) {
+    let path = previous_path(exe);
+    if let Err(e) = std::fs::remove_file(&path) {
+        if e.kind() != io::ErrorKind::NotFound {
+            tracing::debug!("Failed to remove {}: {e}", path.display());
+        }
+    }
+}
+
+/// Replaces the executable at `exe` with `binary`.
+///
+/// # Errors
+///
+/// Returns [`UpdateError::Install`] if the new binary cannot be written or
+/// moved into place; `exe` is then left unchanged.
+pub fn replace_executable(exe: &Path, binary: &[u8]) -> Result<(), UpdateError> {
+    let staged = with_suffix(exe, ".new");
+    let fail = |source| UpdateError::Install {
+        path: exe.to_path_buf(),
+        source,
+    };
+
+    write_staged(exe, &staged, binary).map_err(fail)?;
+    swap(exe, &staged).map_err(|e| {
+        let _ = std::fs::remove_file(&staged);
+        fail(e)
+    })
+}
+
+/// Writes `binary` to `staged` with the permissions of `exe`.
+fn write_staged(exe: &Path, staged: &Path, binary: &[u8]) -> io::Result<()> {
+    std::fs::write(staged, binary)?;
+    let permissions = std::fs::metadata(exe)?.permissions();
+    std::fs::set_permissions(staged, permissions)
+}
+
+#[cfg(not(windows))]
+fn swap(exe: &Path, staged: &Path) -> io::Result<()> {
+    std::fs::rename(staged, exe)
+}
+
+#[cfg(windows)]
+fn swap(exe: &Path, staged: &Path) -> io::Result<()> {
+    let previous = previous_path(exe);
+    remove_previous(exe);
+
+    std::fs::rename(exe, &previous)?;
+    std::fs::rename(staged, exe).inspect_err(|_| {
+        // Put the running binary back so the next start still finds it
+        let _ = std::fs::rename(&previous, exe);
+    })
+}
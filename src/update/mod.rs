@@ -0,0 +1,345 @@
+//! Self-update from the project's release feed.
+//!
+//! `ddns-a self-update` asks the release feed for the latest release,
+//! downloads the binary for this platform and replaces the running
+//! executable with it. Updates are opt-in (`[update] enabled = true`) and
+//! every binary must carry a valid Ed25519 signature (`<asset>.sig`, hex)
+//! by the configured [`PublicKey`], so neither the feed nor a mirror can
+//! install a binary its publisher did not sign.
+
+mod install;
+mod release;
+
+#[cfg(test)]
+mod release_tests;
+#[cfg(test)]
+#[path = "mod_tests.rs"]
+mod tests;
+
+pub use install::{previous_path, remove_previous, replace_executable};
+pub use release::{Asset, Release, Version, platform_asset_name, signature_asset_name};
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use aws_lc_rs::signature::{ED25519, UnparsedPublicKey};
+use http::header::{ACCEPT, USER_AGENT};
+use http::{HeaderValue, StatusCode};
+use thiserror::Error;
+use url::Url;
+
+use crate::webhook::{DEFAULT_USER_AGENT, HttpClient, HttpError, HttpRequest};
+
+/// Release feed used unless configured: the project's latest GitHub release.
+pub const DEFAULT_RELEASE_FEED: &str =
+    "https://api.github.com/repos/doraemonkeys/ddns-a/releases/latest";
+
+const OCTET_STREAM: &str = "application/octet-stream";
+
+/// Largest release binary accepted by default (64 MiB).
+pub const MAX_DOWNLOAD_LEN: usize = 64 * 1024 * 1024;
+
+/// Largest signature file accepted: 128 hex digits plus some whitespace.
+const MAX_SIGNATURE_LEN: usize = 1024;
+
+/// Largest release feed accepted (4 MiB).
+const MAX_FEED_LEN: usize = 4 * 1024 * 1024;
+
+/// Errors that can occur during a self-update.
+#[derive(Debug, Error)]
+pub enum UpdateError {
+    /// The feed or a download could not be fetched.
+    #[error("Failed to fetch {url}: {source}")]
+    Http {
+        /// Requested URL
+        url: Url,
+        /// Underlying HTTP error
+        #[source]
+        source: HttpError,
+    },
+
+    /// The feed or a download answered with a non-success status.
+    #[error("{url} answered {status}")]
+    Status {
+        /// Requested URL
+        url: Url,
+        /// Response status
+        status: StatusCode,
+    },
+
+    /// The feed is not a release.
+    #[error("Invalid release feed: {0}")]
+    Feed(String),
+
+    /// The feed or a download is larger than allowed.
+    #[error("{url} is larger than {limit} bytes")]
+    TooLarge {
+        /// Requested URL
+        url: Url,
+        /// Limit in bytes
+        limit: usize,
+    },
+
+    /// The release has no binary (or no signature) for this platform.
+    #[error("Release {version} has no asset named '{name}'")]
+    MissingAsset {
+        /// Release version
+        version: Version,
+        /// Missing asset name
+        name: String,
+    },
+
+    /// The downloaded binary is not signed by the configured key.
+    #[error("Signature of '{name}' does not match the configured public key")]
+    BadSignature {
+        /// Asset name
+        name: String,
+    },
+
+    /// The running executable could not be replaced.
+    #[error("Failed to replace '{}': {source}", path.display())]
+    Install {
+        /// Path to the executable
+        path: PathBuf,
+        /// Underlying I/O error
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// An Ed25519 public key that release binaries must be signed with.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct PublicKey([u8; 32]);
+
+impl PublicKey {
+    /// Parses a key from 64 hex digits.
+    #[must_use]
+    pub fn from_hex(s: &str) -> Option<Self> {
+        decode_hex(s.trim())?.try_into().ok().map(Self)
+    }
+
+    /// Checks that `signature` (hex) signs `message`.
+    #[must_use]
+    pub fn verifies(&self, message: &[u8], signature: &str) -> bool {
+        decode_hex(signature.trim()).is_some_and(|signature| {
+            UnparsedPublicKey::new(&ED25519, &self.0)
+                .verify(message, &signature)
+                .is_ok()
+        })
+    }
+}
+
+impl fmt::Debug for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PublicKey(")?;
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        f.write_str(")")
+    }
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 || !s.is_ascii() {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Self-update settings from the `[update]` section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateSettings {
+    /// URL of the latest release, in GitHub's release API format
+    pub feed: Url,
+    /// Key release binaries must be signed with
+    pub public_key: PublicKey,
+}
+
+/// Outcome of [`Updater::run`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpdateOutcome {
+    /// The latest release is not newer than this build.
+    UpToDate(Version),
+    /// A newer release exists; nothing was installed (check only).
+    Available(Version),
+    /// The executable was replaced with this release.
+    Installed(Version),
+}
+
+/// Fetches, verifies and installs releases.
+#[derive(Debug)]
+pub struct Updater<H> {
+    client: H,
+    settings: UpdateSettings,
+    current: Version,
+    asset: String,
+    user_agent: HeaderValue,
+    max_download: usize,
+}
+
+impl<H: HttpClient> Updater<H> {
+    /// Creates an updater for this build and platform.
+    #[must_use]
+    pub fn new(client: H, settings: UpdateSettings) -> Self {
+        Self {
+            client,
+            settings,
+            current: Version::current(),
+            asset: platform_asset_name(),
+            user_agent: HeaderValue::from_static(DEFAULT_USER_AGENT),
+            max_download: MAX_DOWNLOAD_LEN,
+        }
+    }
+
+    /// Sets the version updates are compared with (default: this build).
+    #[must_use]
+    pub const fn with_current_version(mut self, version: Version) -> Self {
+        self.current = version;
+        self
+    }
+
+    /// Sets the release asset to install (default: [`platform_asset_name`]).
+    #[must_use]
+    pub fn with_asset_name(mut self, name: impl Into<String>) -> Self {
+        self.asset = name.into();
+        self
+    }
+
+    /// Sets the `User-Agent` of feed and download requests.
+    #[must_use]
+    pub fn with_user_agent(mut self, user_agent: HeaderValue) -> Self {
+        self.user_agent = user_agent;
+        self
+    }
+
+    /// Sets the largest binary to download (default: [`MAX_DOWNLOAD_LEN`]).
+    #[must_use]
+    pub const fn with_max_download_len(mut self, limit: usize) -> Self {
+        self.max_download = limit;
+        self
+    }
+
+    /// Fetches the latest release from the feed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the feed cannot be fetched or parsed.
+    pub async fn latest(&self) -> Result<Release, UpdateError> {
+        let feed = self
+            .fetch(
+                &self.settings.feed,
+                "application/vnd.github+json",
+                MAX_FEED_LEN,
+            )
+            .await?;
+        Release::parse(&feed).map_err(UpdateError::Feed)
+    }
+
+    /// Downloads the platform binary of `release` and checks its signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an asset is missing, cannot be downloaded, is
+    /// larger than allowed or the signature does not verify. An oversized
+    /// signature fails before the binary is downloaded.
+    pub async fn download(&self, release: &Release) -> Result<Vec<u8>, UpdateError> {
+        let asset = |name: String| {
+            release
+                .asset(&name)
+                .cloned()
+                .ok_or(UpdateError::MissingAsset {
+                    version: release.version,
+                    name,
+                })
+        };
+        let binary = asset(self.asset.clone())?;
+        let signature = asset(signature_asset_name(&self.asset))?;
+
+        let signature = self
+            .fetch(&signature.url, OCTET_STREAM, MAX_SIGNATURE_LEN)
+            .await?;
+        let bytes = self
+            .fetch(&binary.url, OCTET_STREAM, self.max_download)
+            .await?;
+        if !self
+            .settings
+            .public_key
+            .verifies(&bytes, &String::from_utf8_lossy(&signature))
+        {
+            return Err(UpdateError::BadSignature { name: binary.name });
+        }
+        Ok(bytes)
+    }
+
+    /// Updates the executable at `exe` to the latest release.
+    ///
+    /// Nothing is installed if the release is not newer (unless `force`) or
+    /// with `check_only`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the release cannot be fetched, verified or
+    /// installed; `exe` is only replaced by a verified binary.
+    pub async fn run(
+        &self,
+        exe: &Path,
+        check_only: bool,
+        force: bool,
+    ) -> Result<UpdateOutcome, UpdateError> {
+        let release = self.latest().await?;
+        if !force && !release.is_newer_than(self.current) {
+            return Ok(UpdateOutcome::UpToDate(self.current));
+        }
+        if check_only {
+            return Ok(UpdateOutcome::Available(release.version));
+        }
+
+        let binary = self.download(&release).await?;
+        remove_previous(exe);
+        replace_executable(exe, &binary)?;
+        Ok(UpdateOutcome::Installed(release.version))
+    }
+
+    /// Fetches `url`, refusing bodies over `limit` bytes.
+    async fn fetch(
+        &self,
+        url: &Url,
+        accept: &'static str,
+        limit: usize,
+    ) -> Result<Vec<u8>, UpdateError> {
+        let too_large = || UpdateError::TooLarge {
+            url: url.clone(),
+            limit,
+        };
+        let request = HttpRequest::get(url.clone())
+            .with_header(USER_AGENT, self.user_agent.clone())
+            .with_header(ACCEPT, HeaderValue::from_static(accept))
+            .with_max_response_len(limit);
+        let response = self
+            .client
+            .request(request)
+            .await
+            .map_err(|source| match source {
+                HttpError::ResponseTooLarge { .. } => too_large(),
+                source => UpdateError::Http {
+                    url: url.clone(),
+                    source,
+                },
+            })?;
+
+        if !response.is_success() {
+            return Err(UpdateError::Status {
+                url: url.clone(),
+                status: response.status,
+            });
+        }
+        // Clients that ignore the request's limit are held to it as well
+        if response.body.len() > limit {
+            return Err(too_large());
+        }
+        Ok(response.body)
+    }
+}
@@ -0,0 +1,365 @@
+//! Tests for signature checks and the self-update flow.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+use aws_lc_rs::signature::{Ed25519KeyPair, KeyPair};
+use http::header::USER_AGENT;
+use http::{HeaderMap, HeaderValue, StatusCode};
+use tempfile::TempDir;
+use url::Url;
+
+use super::{
+    PublicKey, UpdateError, UpdateOutcome, UpdateSettings, Updater, Version, previous_path,
+    replace_executable,
+};
+use crate::webhook::{HttpClient, HttpError, HttpRequest, HttpResponse};
+
+const ASSET: &str = "ddns-a-test";
+const BINARY: &[u8] = b"new ddns-a binary";
+
+fn key_pair() -> Ed25519KeyPair {
+    Ed25519KeyPair::from_seed_unchecked(&[7; 32]).unwrap()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, b| {
+        let _ = write!(hex, "{b:02x}");
+        hex
+    })
+}
+
+fn public_key() -> PublicKey {
+    PublicKey::from_hex(&hex(key_pair().public_key().as_ref())).unwrap()
+}
+
+fn sign(message: &[u8]) -> String {
+    hex(key_pair().sign(message).as_ref())
+}
+
+mod public_key {
+    use super::*;
+
+    #[test]
+    fn parses_64_hex_digits() {
+        let hex = hex(key_pair().public_key().as_ref());
+
+        assert_eq!(
+            PublicKey::from_hex(&format!(" {hex}\n")),
+            Some(public_key())
+        );
+        assert_eq!(format!("{:?}", public_key()), format!("PublicKey({hex})"));
+    }
+
+    #[test]
+    fn rejects_other_input() {
+        for s in ["", "abc", &"0".repeat(62), &"g".repeat(64), &"é".repeat(32)] {
+            assert_eq!(PublicKey::from_hex(s), None, "{s}");
+        }
+    }
+
+    #[test]
+    fn verifies_signature() {
+        assert!(public_key().verifies(BINARY, &sign(BINARY)));
+        assert!(public_key().verifies(BINARY, &format!("{}\n", sign(BINARY))));
+    }
+
+    #[test]
+    fn rejects_other_message_or_garbage() {
+        assert!(!public_key().verifies(b"tampered", &sign(BINARY)));
+        assert!(!public_key().verifies(BINARY, "not hex"));
+        assert!(!public_key().verifies(BINARY, &"00".repeat(64)));
+    }
+}
+
+/// Serves canned responses by URL and records the requests.
+#[derive(Default)]
+struct ReleaseServer {
+    responses: HashMap<String, (StatusCode, Vec<u8>)>,
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+impl ReleaseServer {
+    const FEED: &str = "https://releases.example.com/latest";
+
+    /// A server publishing `tag` with `binary` signed by `signature`.
+    fn publishing(tag: &str, binary: &[u8], signature: &str) -> Self {
+        let feed = format!(
+            r#"{{"tag_name": "{tag}", "assets": [
+                {{"name": "{ASSET}", "browser_download_url": "https://releases.example.com/bin"}},
+                {{"name": "{ASSET}.sig", "browser_download_url": "https://releases.example.com/sig"}}
+            ]}}"#
+        );
+        Self::default()
+            .with(Self::FEED, StatusCode::OK, feed.as_bytes())
+            .with("https://releases.example.com/bin", StatusCode::OK, binary)
+            .with(
+                "https://releases.example.com/sig",
+                StatusCode::OK,
+                signature.as_bytes(),
+            )
+    }
+
+    fn with(mut self, url: &str, status: StatusCode, body: &[u8]) -> Self {
+        self.responses
+            .insert(url.to_string(), (status, body.to_vec()));
+        self
+    }
+
+    fn requested(&self) -> Vec<String> {
+        let requests = self.requests.lock().unwrap();
+        requests.iter().map(|r| r.url.to_string()).collect()
+    }
+}
+
+impl HttpClient for &ReleaseServer {
+    async fn request(&self, req: HttpRequest) -> Result<HttpResponse, HttpError> {
+        let (status, body) = self
+            .responses
+            .get(req.url.as_str())
+            .cloned()
+            .unwrap_or((StatusCode::NOT_FOUND, Vec::new()));
+        self.requests.lock().unwrap().push(req);
+        Ok(HttpResponse::new(status, HeaderMap::new(), body))
+    }
+}
+
+fn updater(server: &ReleaseServer) -> Updater<&ReleaseServer> {
+    let settings = UpdateSettings {
+        feed: Url::parse(ReleaseServer::FEED).unwrap(),
+        public_key: public_key(),
+    };
+    Updater::new(server, settings)
+        .with_current_version(Version::parse("0.1.0").unwrap())
+        .with_asset_name(ASSET)
+}
+
+/// A fake installed executable in a temporary directory.
+fn installed_exe() -> (TempDir, std::path::PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let exe = dir.path().join("ddns-a");
+    std::fs::write(&exe, b"old ddns-a binary").unwrap();
+    (dir, exe)
+}
+
+fn contents(path: &Path) -> Vec<u8> {
+    std::fs::read(path).unwrap()
+}
+
+mod run {
+    use super::*;
+
+    #[tokio::test]
+    async fn installs_signed_newer_release() {
+        let server = ReleaseServer::publishing("v0.2.0", BINARY, &sign(BINARY));
+        let (dir, exe) = installed_exe();
+
+        let outcome = updater(&server).run(&exe, false, false).await.unwrap();
+
+        assert_eq!(
+            outcome,
+            UpdateOutcome::Installed(Version::parse("0.2.0").unwrap())
+        );
+        assert_eq!(contents(&exe), BINARY);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn keeps_up_to_date_binary() {
+        let server = ReleaseServer::publishing("v0.1.0", BINARY, &sign(BINARY));
+        let (_dir, exe) = installed_exe();
+
+        let outcome = updater(&server).run(&exe, false, false).await.unwrap();
+
+        assert_eq!(
+            outcome,
+            UpdateOutcome::UpToDate(Version::parse("0.1.0").unwrap())
+        );
+        assert_eq!(server.requested(), vec![ReleaseServer::FEED]);
+        assert_eq!(contents(&exe), b"old ddns-a binary");
+    }
+
+    #[tokio::test]
+    async fn force_reinstalls_same_version() {
+        let server = ReleaseServer::publishing("v0.1.0", BINARY, &sign(BINARY));
+        let (_dir, exe) = installed_exe();
+
+        let outcome = updater(&server).run(&exe, false, true).await.unwrap();
+
+        assert!(matches!(outcome, UpdateOutcome::Installed(_)));
+        assert_eq!(contents(&exe), BINARY);
+    }
+
+    #[tokio::test]
+    async fn check_only_downloads_nothing() {
+        let server = ReleaseServer::publishing("v0.2.0", BINARY, &sign(BINARY));
+        let (_dir, exe) = installed_exe();
+
+        let outcome = updater(&server).run(&exe, true, false).await.unwrap();
+
+        assert_eq!(
+            outcome,
+            UpdateOutcome::Available(Version::parse("0.2.0").unwrap())
+        );
+        assert_eq!(server.requested().len(), 1);
+        assert_eq!(contents(&exe), b"old ddns-a binary");
+    }
+
+    #[tokio::test]
+    async fn refuses_badly_signed_binary() {
+        let server = ReleaseServer::publishing("v0.2.0", b"tampered", &sign(BINARY));
+        let (dir, exe) = installed_exe();
+
+        let result = updater(&server).run(&exe, false, false).await;
+
+        assert!(matches!(result, Err(UpdateError::BadSignature { name }) if name == ASSET));
+        assert_eq!(contents(&exe), b"old ddns-a binary");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn refuses_oversized_binary() {
+        let server = ReleaseServer::publishing("v0.2.0", BINARY, &sign(BINARY));
+        let (_dir, exe) = installed_exe();
+
+        let result = updater(&server)
+            .with_max_download_len(BINARY.len() - 1)
+            .run(&exe, false, false)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(UpdateError::TooLarge { url, limit })
+                if url.as_str() == "https://releases.example.com/bin" && limit == BINARY.len() - 1
+        ));
+        assert_eq!(contents(&exe), b"old ddns-a binary");
+    }
+
+    #[tokio::test]
+    async fn refuses_oversized_signature_before_downloading_binary() {
+        let server = ReleaseServer::publishing("v0.2.0", BINARY, &"0".repeat(4096));
+        let (_dir, exe) = installed_exe();
+
+        let result = updater(&server).run(&exe, false, false).await;
+
+        assert!(matches!(result, Err(UpdateError::TooLarge { url, .. })
+            if url.as_str() == "https://releases.example.com/sig"));
+        assert!(
+            !server
+                .requested()
+                .contains(&"https://releases.example.com/bin".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn limits_every_request() {
+        let server = ReleaseServer::publishing("v0.2.0", BINARY, &sign(BINARY));
+
+        updater(&server)
+            .download(&updater(&server).latest().await.unwrap())
+            .await
+            .unwrap();
+
+        let limited = server
+            .requests
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|r| r.max_response_len.is_some());
+        assert!(limited);
+    }
+
+    #[tokio::test]
+    async fn requires_platform_asset() {
+        let server = ReleaseServer::publishing("v0.2.0", BINARY, &sign(BINARY));
+        let (_dir, exe) = installed_exe();
+
+        let result = updater(&server)
+            .with_asset_name("ddns-a-other")
+            .run(&exe, false, false)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(UpdateError::MissingAsset { name, .. }) if name == "ddns-a-other"
+        ));
+    }
+
+    #[tokio::test]
+    async fn reports_feed_status() {
+        let server = ReleaseServer::default();
+        let (_dir, exe) = installed_exe();
+
+        let result = updater(&server).run(&exe, false, false).await;
+
+        assert!(matches!(
+            result,
+            Err(UpdateError::Status {
+                status: StatusCode::NOT_FOUND,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn sends_user_agent() {
+        let server = ReleaseServer::publishing("v0.1.0", BINARY, &sign(BINARY));
+
+        updater(&server)
+            .with_user_agent(HeaderValue::from_static("custom/1"))
+            .latest()
+            .await
+            .unwrap();
+
+        let user_agent = server.requests.lock().unwrap()[0].headers[USER_AGENT].clone();
+        assert_eq!(user_agent, "custom/1");
+    }
+}
+
+mod install {
+    use super::*;
+
+    #[test]
+    fn replaces_executable() {
+        let (dir, exe) = installed_exe();
+
+        replace_executable(&exe, BINARY).unwrap();
+
+        assert_eq!(contents(&exe), BINARY);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (_dir, exe) = installed_exe();
+        std::fs::set_permissions(&exe, std::fs::Permissions::from_mode(0o750)).unwrap();
+
+        replace_executable(&exe, BINARY).unwrap();
+
+        let mode = std::fs::metadata(&exe).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o750);
+    }
+
+    #[test]
+    fn missing_executable_fails() {
+        let dir = TempDir::new().unwrap();
+        let exe = dir.path().join("missing");
+
+        let result = replace_executable(&exe, BINARY);
+
+        assert!(matches!(result, Err(UpdateError::Install { path, .. }) if path == exe));
+    }
+
+    #[test]
+    fn previous_binary_sits_next_to_executable() {
+        assert_eq!(
+            previous_path(Path::new("/opt/ddns-a")),
+            Path::new("/opt/ddns-a.old")
+        );
+    }
+}
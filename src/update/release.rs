@@ -0,0 +1,134 @@
+//! Release feed parsing and version comparison.
+
+use std::fmt;
+
+use serde::Deserialize;
+use url::Url;
+
+/// A release version: `major.minor.patch`, optionally prefixed with `v`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    major: u64,
+    minor: u64,
+    patch: u64,
+}
+
+impl Version {
+    /// Parses `1.2.3` or `v1.2.3`.
+    ///
+    /// Pre-release and build suffixes (`1.2.3-rc.1`) are not accepted, so a
+    /// feed can never offer one as an update.
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.strip_prefix('v').unwrap_or(s).split('.');
+        let mut next = || parts.next()?.parse().ok();
+        let version = Self {
+            major: next()?,
+            minor: next()?,
+            patch: next()?,
+        };
+        parts.next().is_none().then_some(version)
+    }
+
+    /// Returns the version of this build.
+    ///
+    /// # Panics
+    ///
+    /// Never: the crate version is plain `major.minor.patch`.
+    #[must_use]
+    pub fn current() -> Self {
+        Self::parse(env!("CARGO_PKG_VERSION")).expect("crate version is major.minor.patch")
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// A downloadable file of a release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Asset {
+    /// File name, e.g. `ddns-a-windows-x86_64.exe`
+    pub name: String,
+    /// Download URL
+    pub url: Url,
+}
+
+/// A published release, as listed by the release feed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    /// Release version, from the tag name
+    pub version: Version,
+    /// Files attached to the release
+    pub assets: Vec<Asset>,
+}
+
+/// The fields of a GitHub release that updates use.
+#[derive(Deserialize)]
+struct FeedRelease {
+    tag_name: String,
+    #[serde(default)]
+    assets: Vec<FeedAsset>,
+}
+
+#[derive(Deserialize)]
+struct FeedAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+impl Release {
+    /// Parses a release in the format of GitHub's "latest release" API.
+    ///
+    /// # Errors
+    ///
+    /// Returns why the feed is not a release with a `vX.Y.Z` tag.
+    pub fn parse(json: &[u8]) -> Result<Self, String> {
+        let feed: FeedRelease = serde_json::from_slice(json).map_err(|e| e.to_string())?;
+        let version = Version::parse(&feed.tag_name)
+            .ok_or_else(|| format!("tag '{}' is not a vX.Y.Z version", feed.tag_name))?;
+
+        let assets = feed
+            .assets
+            .into_iter()
+            .map(|asset| {
+                let url = Url::parse(&asset.browser_download_url)
+                    .map_err(|e| format!("asset '{}' has an invalid URL: {e}", asset.name))?;
+                Ok(Asset {
+                    name: asset.name,
+                    url,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self { version, assets })
+    }
+
+    /// Returns the asset named `name`.
+    #[must_use]
+    pub fn asset(&self, name: &str) -> Option<&Asset> {
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+
+    /// Returns true if this release is newer than `version`.
+    #[must_use]
+    pub fn is_newer_than(&self, version: Version) -> bool {
+        self.version > version
+    }
+}
+
+/// Name of the release binary for this platform, e.g.
+/// `ddns-a-windows-x86_64.exe` or `ddns-a-linux-aarch64`.
+#[must_use]
+pub fn platform_asset_name() -> String {
+    use std::env::consts::{ARCH, EXE_SUFFIX, OS};
+    format!("ddns-a-{OS}-{ARCH}{EXE_SUFFIX}")
+}
+
+/// Name of the signature published next to the asset `name`.
+#[must_use]
+pub fn signature_asset_name(name: &str) -> String {
+    format!("{name}.sig")
+}
@@ -0,0 +1,102 @@
+//! Tests for release feed parsing and version comparison.
+
+use super::{Release, Version, platform_asset_name, signature_asset_name};
+
+fn version(s: &str) -> Version {
+    Version::parse(s).unwrap()
+}
+
+mod version {
+    use super::*;
+
+    #[test]
+    fn parses_with_or_without_prefix() {
+        assert_eq!(version("v1.2.3"), version("1.2.3"));
+        assert_eq!(version("v1.2.3").to_string(), "1.2.3");
+    }
+
+    #[test]
+    fn rejects_other_forms() {
+        for s in ["1.2", "1.2.3.4", "1.2.3-rc.1", "v", "1.x.3", ""] {
+            assert_eq!(Version::parse(s), None, "{s}");
+        }
+    }
+
+    #[test]
+    fn compares_numerically() {
+        assert!(version("0.10.0") > version("0.9.9"));
+        assert!(version("1.0.0") > version("0.99.99"));
+        assert!(version("0.1.2") > version("0.1.1"));
+    }
+
+    #[test]
+    fn current_is_crate_version() {
+        assert_eq!(Version::current().to_string(), env!("CARGO_PKG_VERSION"));
+    }
+}
+
+mod release {
+    use super::*;
+
+    const FEED: &str = r#"{
+        "tag_name": "v0.2.0",
+        "name": "ddns-a 0.2.0",
+        "assets": [
+            {"name": "ddns-a-linux-x86_64", "browser_download_url": "https://example.com/bin", "size": 1},
+            {"name": "ddns-a-linux-x86_64.sig", "browser_download_url": "https://example.com/sig"}
+        ]
+    }"#;
+
+    #[test]
+    fn parses_github_release() {
+        let release = Release::parse(FEED.as_bytes()).unwrap();
+
+        assert_eq!(release.version, version("0.2.0"));
+        assert_eq!(release.assets.len(), 2);
+        let asset = release.asset("ddns-a-linux-x86_64.sig").unwrap();
+        assert_eq!(asset.url.as_str(), "https://example.com/sig");
+        assert!(release.asset("ddns-a-windows-x86_64.exe").is_none());
+    }
+
+    #[test]
+    fn compares_with_running_version() {
+        let release = Release::parse(FEED.as_bytes()).unwrap();
+
+        assert!(release.is_newer_than(version("0.1.9")));
+        assert!(!release.is_newer_than(version("0.2.0")));
+        assert!(!release.is_newer_than(version("0.3.0")));
+    }
+
+    #[test]
+    fn rejects_non_version_tag() {
+        let error = Release::parse(br#"{"tag_name": "nightly"}"#).unwrap_err();
+
+        assert_eq!(error, "tag 'nightly' is not a vX.Y.Z version");
+    }
+
+    #[test]
+    fn rejects_invalid_asset_url() {
+        let feed = br#"{"tag_name": "v1.0.0", "assets": [{"name": "a", "browser_download_url": "not a url"}]}"#;
+
+        assert!(Release::parse(feed).unwrap_err().starts_with("asset 'a'"));
+    }
+
+    #[test]
+    fn rejects_non_release() {
+        assert!(Release::parse(br#"{"message": "Not Found"}"#).is_err());
+        assert!(Release::parse(b"<html>").is_err());
+    }
+}
+
+mod asset_names {
+    use super::*;
+
+    #[test]
+    fn names_platform_binary() {
+        let name = platform_asset_name();
+
+        assert!(name.starts_with(&format!("ddns-a-{}-", std::env::consts::OS)));
+        assert!(name.ends_with(std::env::consts::EXE_SUFFIX));
+        assert_eq!(signature_asset_name(&name), format!("{name}.sig"));
+    }
+}
@@ -0,0 +1,220 @@
+//! Assertions on the bodies of delivery responses.
+//!
+//! Many APIs answer `200 OK` with an error in the body, e.g.
+//! `{"status": "error", "message": "bad token"}` or `KO`. A [`JsonAssertion`]
+//! picks one field out of every successful delivery response with a
+//! [`JsonPath`] and fails the attempt unless it equals the expected value; a
+//! [`BodyAssertion`] fails it unless the body matches a regex.
+
+use std::fmt;
+use std::str::FromStr;
+
+use regex::Regex;
+use serde_json::Value;
+
+use super::HttpResponse;
+
+/// Characters of a mismatching body quoted in the failure reason.
+const MAX_QUOTED_BODY: usize = 200;
+
+/// One step of a [`JsonPath`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    /// A member of an object.
+    Key(String),
+    /// An element of an array.
+    Index(usize),
+}
+
+/// A `JSONPath` selecting a single value, e.g. `$.data.records[0].status`.
+///
+/// Only the subset naming one value is supported: the root `$`, followed by
+/// `.key`, `['key']` (or `["key"]`) and `[index]` steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPath {
+    text: String,
+    segments: Vec<Segment>,
+}
+
+impl JsonPath {
+    /// Returns the value the path selects in `json`, if any.
+    #[must_use]
+    pub fn select<'a>(&self, json: &'a Value) -> Option<&'a Value> {
+        self.segments
+            .iter()
+            .try_fold(json, |value, segment| match segment {
+                Segment::Key(key) => value.get(key),
+                Segment::Index(index) => value.get(index),
+            })
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| format!("JSONPath '{s}' {reason}");
+        let mut rest = s
+            .trim()
+            .strip_prefix('$')
+            .ok_or_else(|| invalid("must start with '$'"))?;
+
+        let mut segments = Vec::new();
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('.') {
+                let end = after.find(['.', '[']).unwrap_or(after.len());
+                let key = &after[..end];
+                if key.is_empty() || key == "*" {
+                    return Err(invalid("has an empty or wildcard key"));
+                }
+                segments.push(Segment::Key(key.to_string()));
+                rest = &after[end..];
+            } else if let Some(after) = rest.strip_prefix('[') {
+                let (inner, after) = after
+                    .split_once(']')
+                    .ok_or_else(|| invalid("has an unclosed '['"))?;
+                let segment = bracket(inner)
+                    .ok_or_else(|| invalid("has a bracket that is neither a key nor an index"))?;
+                segments.push(segment);
+                rest = after;
+            } else {
+                return Err(invalid("must continue with '.' or '['"));
+            }
+        }
+
+        Ok(Self {
+            text: s.trim().to_string(),
+            segments,
+        })
+    }
+}
+
+/// Parses the inside of a `['key']`, `["key"]` or `[index]` step.
+fn bracket(inner: &str) -> Option<Segment> {
+    let quoted = |q: char| inner.strip_prefix(q).and_then(|k| k.strip_suffix(q));
+    quoted('\'')
+        .or_else(|| quoted('"'))
+        .map(|key| Segment::Key(key.to_string()))
+        .or_else(|| inner.trim().parse().ok().map(Segment::Index))
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// Requires a field of every successful delivery response to hold a value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonAssertion {
+    path: JsonPath,
+    equals: Value,
+    retryable: bool,
+}
+
+impl JsonAssertion {
+    /// Creates an assertion that `path` selects a value equal to `equals`.
+    ///
+    /// Mismatches are not retried unless [`retried`](Self::retried).
+    #[must_use]
+    pub const fn new(path: JsonPath, equals: Value) -> Self {
+        Self {
+            path,
+            equals,
+            retryable: false,
+        }
+    }
+
+    /// Sets whether a mismatch is retried like a transient failure.
+    #[must_use]
+    pub const fn retried(mut self, retryable: bool) -> Self {
+        self.retryable = retryable;
+        self
+    }
+
+    /// Returns true if a mismatch is retried.
+    #[must_use]
+    pub const fn is_retryable(&self) -> bool {
+        self.retryable
+    }
+
+    /// Checks the value the path selects in `response`'s body.
+    ///
+    /// # Errors
+    ///
+    /// Returns the reason if the body is not JSON, or the value is missing
+    /// or different.
+    pub fn verify(&self, response: &HttpResponse) -> Result<(), String> {
+        let json: Value = serde_json::from_slice(&response.body)
+            .map_err(|e| format!("response body is not JSON: {e}"))?;
+        match self.path.select(&json) {
+            Some(value) if *value == self.equals => Ok(()),
+            Some(value) => Err(format!(
+                "response {} is {value}, expected {}",
+                self.path, self.equals
+            )),
+            None => Err(format!("response has no {}", self.path)),
+        }
+    }
+}
+
+/// Requires the body of every successful delivery response to match a regex,
+/// e.g. `good|nochg` for dyndns2-style answers.
+#[derive(Debug, Clone)]
+pub struct BodyAssertion {
+    pattern: Regex,
+    retryable: bool,
+}
+
+impl BodyAssertion {
+    /// Creates an assertion that bodies match `pattern` (anywhere, unless
+    /// anchored).
+    ///
+    /// Mismatches are not retried unless [`retried`](Self::retried).
+    #[must_use]
+    pub const fn new(pattern: Regex) -> Self {
+        Self {
+            pattern,
+            retryable: false,
+        }
+    }
+
+    /// Sets whether a mismatch is retried like a transient failure.
+    #[must_use]
+    pub const fn retried(mut self, retryable: bool) -> Self {
+        self.retryable = retryable;
+        self
+    }
+
+    /// Returns true if a mismatch is retried.
+    #[must_use]
+    pub const fn is_retryable(&self) -> bool {
+        self.retryable
+    }
+
+    /// Returns the pattern bodies must match.
+    #[must_use]
+    pub fn pattern(&self) -> &str {
+        self.pattern.as_str()
+    }
+
+    /// Checks `response`'s body against the pattern.
+    ///
+    /// # Errors
+    ///
+    /// Returns the reason if the body is not text or does not match.
+    pub fn verify(&self, response: &HttpResponse) -> Result<(), String> {
+        let body = response
+            .body_text()
+            .ok_or_else(|| "response body is not text".to_string())?;
+        if self.pattern.is_match(body) {
+            return Ok(());
+        }
+        let body = body.trim();
+        Err(format!(
+            "response body '{}' does not match '{}'",
+            body.chars().take(MAX_QUOTED_BODY).collect::<String>(),
+            self.pattern
+        ))
+    }
+}
@@ -0,0 +1,296 @@
+//! Tests for JSON and body regex response assertions.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use http::{HeaderMap, StatusCode};
+use serde_json::{Value, json};
+
+use super::{
+    BodyAssertion, HttpClient, HttpError, HttpRequest, HttpResponse, HttpWebhook, IsRetryable,
+    JsonAssertion, JsonPath, RetryableError, WebhookError, WebhookSender,
+};
+use crate::monitor::IpChange;
+use crate::time::InstantSleeper;
+
+fn path(s: &str) -> JsonPath {
+    s.parse().unwrap()
+}
+
+fn response(body: &str) -> HttpResponse {
+    HttpResponse::new(StatusCode::OK, HeaderMap::new(), body.as_bytes().to_vec())
+}
+
+fn status_ok() -> JsonAssertion {
+    JsonAssertion::new(path("$.status"), json!("ok"))
+}
+
+mod json_path {
+    use super::*;
+
+    #[test]
+    fn selects_keys_and_indices() {
+        let doc = json!({"data": {"records": [{"id": 1}, {"id": 2, "a.b": true}]}});
+
+        assert_eq!(path("$").select(&doc), Some(&doc));
+        assert_eq!(path("$.data.records[1].id").select(&doc), Some(&json!(2)));
+        assert_eq!(
+            path("$['data'][\"records\"][1]['a.b']").select(&doc),
+            Some(&json!(true))
+        );
+        assert_eq!(path("$.data.missing").select(&doc), None);
+        assert_eq!(path("$.data.records[5]").select(&doc), None);
+    }
+
+    #[test]
+    fn displays_as_written() {
+        assert_eq!(path(" $.result[0] ").to_string(), "$.result[0]");
+    }
+
+    #[test]
+    fn rejects_unsupported_syntax() {
+        for invalid in ["status", "$.", "$.*", "$..a", "$[", "$[x]", "$a"] {
+            assert!(invalid.parse::<JsonPath>().is_err(), "{invalid}");
+        }
+    }
+}
+
+mod verify {
+    use super::*;
+
+    #[test]
+    fn accepts_matching_value() {
+        assert!(status_ok().verify(&response(r#"{"status": "ok"}"#)).is_ok());
+    }
+
+    #[test]
+    fn compares_values_by_type() {
+        let assertion = JsonAssertion::new(path("$.code"), json!(0));
+
+        assert!(assertion.verify(&response(r#"{"code": 0}"#)).is_ok());
+        assert!(assertion.verify(&response(r#"{"code": "0"}"#)).is_err());
+    }
+
+    #[test]
+    fn reports_mismatch_missing_field_and_non_json() {
+        let mismatch = status_ok()
+            .verify(&response(r#"{"status": "error"}"#))
+            .unwrap_err();
+        assert_eq!(mismatch, r#"response $.status is "error", expected "ok""#);
+
+        let missing = status_ok().verify(&response("{}")).unwrap_err();
+        assert_eq!(missing, "response has no $.status");
+
+        let text = status_ok().verify(&response("OK")).unwrap_err();
+        assert!(text.starts_with("response body is not JSON"), "{text}");
+    }
+
+    #[test]
+    fn failure_is_retried_only_if_configured() {
+        let error = |retryable| RetryableError::AssertionFailed {
+            reason: "x".to_string(),
+            retryable,
+        };
+
+        assert!(!status_ok().is_retryable());
+        assert!(status_ok().retried(true).is_retryable());
+        assert!(!error(false).is_retryable());
+        assert!(error(true).is_retryable());
+    }
+}
+
+mod body_regex {
+    use super::*;
+
+    fn good() -> BodyAssertion {
+        BodyAssertion::new(regex::Regex::new("^(good|nochg)").unwrap())
+    }
+
+    #[test]
+    fn accepts_matching_body() {
+        assert!(good().verify(&response("good 192.0.2.1")).is_ok());
+        assert!(good().verify(&response("nochg 192.0.2.1\n")).is_ok());
+    }
+
+    #[test]
+    fn reports_mismatch_with_body() {
+        let reason = good().verify(&response("KO\n")).unwrap_err();
+
+        assert_eq!(reason, "response body 'KO' does not match '^(good|nochg)'");
+    }
+
+    #[test]
+    fn quotes_long_bodies_truncated() {
+        let body = "x".repeat(1000);
+
+        let reason = good().verify(&response(&body)).unwrap_err();
+
+        assert!(reason.len() < 300, "{reason}");
+    }
+
+    #[test]
+    fn binary_body_does_not_match() {
+        let response = HttpResponse::new(StatusCode::OK, HeaderMap::new(), vec![0xff, 0xfe]);
+
+        assert_eq!(
+            good().verify(&response).unwrap_err(),
+            "response body is not text"
+        );
+    }
+
+    #[test]
+    fn retried_only_if_configured() {
+        assert!(!good().is_retryable());
+        assert!(good().retried(true).is_retryable());
+    }
+}
+
+mod webhook {
+    use super::*;
+
+    /// Answers requests with the queued bodies, then with the last one.
+    struct BodyClient {
+        bodies: Mutex<VecDeque<&'static str>>,
+        requests: Mutex<usize>,
+    }
+
+    impl BodyClient {
+        fn new(bodies: &[&'static str]) -> Self {
+            Self {
+                bodies: Mutex::new(bodies.iter().copied().collect()),
+                requests: Mutex::new(0),
+            }
+        }
+
+        fn requests(&self) -> usize {
+            *self.requests.lock().unwrap()
+        }
+    }
+
+    impl HttpClient for &BodyClient {
+        async fn request(&self, _req: HttpRequest) -> Result<HttpResponse, HttpError> {
+            *self.requests.lock().unwrap() += 1;
+            let mut bodies = self.bodies.lock().unwrap();
+            let body = if bodies.len() > 1 {
+                bodies.pop_front().unwrap()
+            } else {
+                bodies[0]
+            };
+            Ok(response(body))
+        }
+    }
+
+    fn webhook(
+        client: &BodyClient,
+        assertion: JsonAssertion,
+    ) -> HttpWebhook<&BodyClient, InstantSleeper> {
+        HttpWebhook::new(client, url::Url::parse("https://example.com/hook").unwrap())
+            .with_json_assertion(assertion)
+            .with_sleeper(InstantSleeper)
+    }
+
+    fn changes() -> Vec<IpChange> {
+        vec![IpChange::added(
+            "eth0",
+            "192.168.1.1".parse().unwrap(),
+            SystemTime::UNIX_EPOCH,
+        )]
+    }
+
+    #[tokio::test]
+    async fn succeeds_when_assertion_passes() {
+        let client = BodyClient::new(&[r#"{"status": "ok"}"#]);
+
+        webhook(&client, status_ok())
+            .send(&changes())
+            .await
+            .unwrap();
+
+        assert_eq!(client.requests(), 1);
+    }
+
+    #[tokio::test]
+    async fn mismatch_fails_without_retry_by_default() {
+        let client = BodyClient::new(&[r#"{"status": "error"}"#]);
+
+        let result = webhook(&client, status_ok()).send(&changes()).await;
+
+        assert!(matches!(
+            result,
+            Err(WebhookError::Retryable(RetryableError::AssertionFailed {
+                retryable: false,
+                ..
+            }))
+        ));
+        assert_eq!(client.requests(), 1);
+    }
+
+    #[tokio::test]
+    async fn retried_mismatch_is_sent_again() {
+        let client = BodyClient::new(&[r#"{"status": "busy"}"#, r#"{"status": "ok"}"#]);
+
+        webhook(&client, status_ok().retried(true))
+            .send(&changes())
+            .await
+            .unwrap();
+
+        assert_eq!(client.requests(), 2);
+    }
+
+    #[tokio::test]
+    async fn non_success_status_is_not_asserted() {
+        struct Failing;
+        impl HttpClient for Failing {
+            async fn request(&self, _req: HttpRequest) -> Result<HttpResponse, HttpError> {
+                Ok(HttpResponse::new(
+                    StatusCode::BAD_REQUEST,
+                    HeaderMap::new(),
+                    b"{}".to_vec(),
+                ))
+            }
+        }
+
+        let result = HttpWebhook::new(Failing, url::Url::parse("https://example.com").unwrap())
+            .with_json_assertion(JsonAssertion::new(path("$.status"), Value::Bool(true)))
+            .with_sleeper(InstantSleeper)
+            .send(&changes())
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(WebhookError::Retryable(
+                RetryableError::NonSuccessStatus { .. }
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn body_mismatch_fails_and_retried_mismatch_is_sent_again() {
+        let client = BodyClient::new(&["KO"]);
+        let result = HttpWebhook::new(&client, url::Url::parse("https://example.com").unwrap())
+            .with_body_assertion(BodyAssertion::new(regex::Regex::new("^(OK|good)").unwrap()))
+            .with_sleeper(InstantSleeper)
+            .send(&changes())
+            .await;
+        assert!(matches!(
+            result,
+            Err(WebhookError::Retryable(RetryableError::AssertionFailed {
+                retryable: false,
+                ..
+            }))
+        ));
+        assert_eq!(client.requests(), 1);
+
+        let client = BodyClient::new(&["KO", "OK"]);
+        HttpWebhook::new(&client, url::Url::parse("https://example.com").unwrap())
+            .with_body_assertion(
+                BodyAssertion::new(regex::Regex::new("^(OK|good)").unwrap()).retried(true),
+            )
+            .with_sleeper(InstantSleeper)
+            .send(&changes())
+            .await
+            .unwrap();
+        assert_eq!(client.requests(), 2);
+    }
+}
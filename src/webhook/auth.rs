@@ -0,0 +1,205 @@
+//! `OAuth2` client-credentials authentication of webhook requests.
+//!
+//! With [`HttpWebhook::with_oauth2`](super::HttpWebhook::with_oauth2), every
+//! request carries `Authorization: Bearer <token>`, the token coming from the
+//! [`OAuth2`] client-credentials flow (RFC 6749, section 4.4). Tokens are
+//! cached until shortly before they expire and fetched again when the
+//! webhook answers 401, so an hourly token costs one token request per hour.
+
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use http::header::{AUTHORIZATION, CONTENT_TYPE};
+use http::{HeaderValue, Method};
+use serde::Deserialize;
+use url::Url;
+use url::form_urlencoded;
+
+use super::provider::base64;
+use super::{HttpRequest, HttpResponse};
+
+/// Time before a token's expiry at which it is no longer used.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(30);
+
+/// How the client authenticates to the token endpoint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ClientAuth {
+    /// HTTP basic authentication (`client_secret_basic`).
+    #[default]
+    Basic,
+    /// `client_id` and `client_secret` in the form body (`client_secret_post`).
+    Body,
+}
+
+/// An access token and when it stops being used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CachedToken {
+    value: String,
+    /// `None` if the token endpoint gave no lifetime
+    renew_at: Option<Instant>,
+}
+
+/// Answer of a token endpoint.
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+/// An `OAuth2` client-credentials grant, caching its access token.
+///
+/// Clones share the cached token.
+#[derive(Clone)]
+pub struct OAuth2 {
+    token_url: Url,
+    client_id: String,
+    client_secret: String,
+    scope: Option<String>,
+    audience: Option<String>,
+    client_auth: ClientAuth,
+    token: Arc<Mutex<Option<CachedToken>>>,
+}
+
+impl OAuth2 {
+    /// Creates a grant for `client_id` at `token_url`, authenticating with
+    /// HTTP basic authentication and requesting no particular scope.
+    #[must_use]
+    pub fn new(
+        token_url: Url,
+        client_id: impl Into<String>,
+        client_secret: impl Into<String>,
+    ) -> Self {
+        Self {
+            token_url,
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            scope: None,
+            audience: None,
+            client_auth: ClientAuth::default(),
+            token: Arc::default(),
+        }
+    }
+
+    /// Requests tokens for `scope` (space-separated scopes).
+    #[must_use]
+    pub fn with_scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = Some(scope.into());
+        self
+    }
+
+    /// Requests tokens for `audience`, as some providers (e.g. Auth0) require.
+    #[must_use]
+    pub fn with_audience(mut self, audience: impl Into<String>) -> Self {
+        self.audience = Some(audience.into());
+        self
+    }
+
+    /// Sets how the client authenticates to the token endpoint.
+    #[must_use]
+    pub const fn with_client_auth(mut self, client_auth: ClientAuth) -> Self {
+        self.client_auth = client_auth;
+        self
+    }
+
+    /// Builds the token request.
+    #[must_use]
+    pub fn token_request(&self) -> HttpRequest {
+        let mut form = form_urlencoded::Serializer::new(String::new());
+        form.append_pair("grant_type", "client_credentials");
+        if let Some(scope) = &self.scope {
+            form.append_pair("scope", scope);
+        }
+        if let Some(audience) = &self.audience {
+            form.append_pair("audience", audience);
+        }
+
+        let mut request = HttpRequest::new(Method::POST, self.token_url.clone());
+        match self.client_auth {
+            ClientAuth::Basic => {
+                // RFC 6749 2.3.1: both parts are form-encoded first
+                let encode =
+                    |s: &str| form_urlencoded::byte_serialize(s.as_bytes()).collect::<String>();
+                let credentials: String =
+                    [encode(&self.client_id), encode(&self.client_secret)].join(":");
+                let basic = format!("Basic {}", base64(credentials.as_bytes()));
+                if let Ok(value) = HeaderValue::from_str(&basic) {
+                    request.headers.insert(AUTHORIZATION, value);
+                }
+            }
+            ClientAuth::Body => {
+                form.append_pair("client_id", &self.client_id);
+                form.append_pair("client_secret", &self.client_secret);
+            }
+        }
+        request.headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/x-www-form-urlencoded"),
+        );
+        request.body = Some(form.finish().into_bytes());
+        request
+    }
+
+    /// Returns the cached token, unless it is about to expire at `now`.
+    #[must_use]
+    pub fn cached(&self, now: Instant) -> Option<String> {
+        let token = self.token.lock().unwrap_or_else(PoisonError::into_inner);
+        token
+            .as_ref()
+            .filter(|token| token.renew_at.is_none_or(|at| now < at))
+            .map(|token| token.value.clone())
+    }
+
+    /// Caches the token of a successful token `response` received at `now`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the reason if the response holds no access token.
+    pub fn store(&self, response: &HttpResponse, now: Instant) -> Result<String, String> {
+        let answer: TokenResponse = serde_json::from_slice(&response.body)
+            .map_err(|e| format!("token response has no access_token: {e}"))?;
+        if HeaderValue::from_str(&format!("Bearer {}", answer.access_token)).is_err() {
+            return Err("access_token is not valid in a header".to_string());
+        }
+        let renew_at = answer.expires_in.map(|secs| {
+            let lifetime = Duration::from_secs(secs);
+            // Short-lived tokens are used for half their lifetime instead
+            now + lifetime.saturating_sub(EXPIRY_MARGIN.min(lifetime / 2))
+        });
+        let value = answer.access_token;
+        *self.token.lock().unwrap_or_else(PoisonError::into_inner) = Some(CachedToken {
+            value: value.clone(),
+            renew_at,
+        });
+        Ok(value)
+    }
+
+    /// Drops the cached token if it is still `rejected`, e.g. after a 401.
+    pub fn invalidate(&self, rejected: &str) {
+        let mut token = self.token.lock().unwrap_or_else(PoisonError::into_inner);
+        if token.as_ref().is_some_and(|token| token.value == rejected) {
+            *token = None;
+        }
+    }
+}
+
+impl fmt::Debug for OAuth2 {
+    /// Leaves out the client secret and the token.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OAuth2")
+            .field("token_url", &self.token_url.as_str())
+            .field("client_id", &self.client_id)
+            .field("scope", &self.scope)
+            .field("audience", &self.audience)
+            .field("client_auth", &self.client_auth)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Sets `request`'s `Authorization` header to the bearer `token`.
+pub(super) fn authorize(request: &mut HttpRequest, token: &str) {
+    // Tokens were checked to be valid header values when stored
+    if let Ok(value) = HeaderValue::from_str(&format!("Bearer {token}")) {
+        request.headers.insert(AUTHORIZATION, value);
+    }
+}
@@ -0,0 +1,280 @@
+//! Tests for `OAuth2` client-credentials authentication.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use http::{HeaderMap, StatusCode};
+
+use super::{
+    ClientAuth, HttpClient, HttpError, HttpRequest, HttpResponse, HttpWebhook, OAuth2, RetryPolicy,
+    RetryableError, WebhookError, WebhookSender,
+};
+use crate::monitor::IpChange;
+use crate::time::InstantSleeper;
+
+fn response(status: u16, body: &str) -> HttpResponse {
+    HttpResponse::new(
+        StatusCode::from_u16(status).unwrap(),
+        HeaderMap::new(),
+        body.as_bytes().to_vec(),
+    )
+}
+
+fn token(value: &str, expires_in: u64) -> HttpResponse {
+    response(
+        200,
+        &format!(
+            r#"{{"access_token": "{value}", "token_type": "Bearer", "expires_in": {expires_in}}}"#
+        ),
+    )
+}
+
+fn grant() -> OAuth2 {
+    OAuth2::new(
+        url::Url::parse("https://auth.example.com/oauth/token").unwrap(),
+        "ddns client",
+        "s3cr:t",
+    )
+}
+
+fn body(request: &HttpRequest) -> &str {
+    std::str::from_utf8(request.body.as_deref().unwrap()).unwrap()
+}
+
+mod token_request {
+    use super::*;
+
+    #[test]
+    fn basic_auth_encodes_credentials() {
+        let request = grant().with_scope("ddns.write").token_request();
+
+        assert_eq!(request.method, http::Method::POST);
+        assert_eq!(request.url.path(), "/oauth/token");
+        assert_eq!(
+            request.headers["content-type"],
+            "application/x-www-form-urlencoded"
+        );
+        // base64("ddns+client:s3cr%3At")
+        assert_eq!(
+            request.headers["authorization"],
+            "Basic ZGRucytjbGllbnQ6czNjciUzQXQ="
+        );
+        assert_eq!(
+            body(&request),
+            "grant_type=client_credentials&scope=ddns.write"
+        );
+    }
+
+    #[test]
+    fn body_auth_sends_credentials_in_form() {
+        let request = grant()
+            .with_audience("https://api.example.com")
+            .with_client_auth(ClientAuth::Body)
+            .token_request();
+
+        assert!(!request.headers.contains_key("authorization"));
+        assert_eq!(
+            body(&request),
+            "grant_type=client_credentials&audience=https%3A%2F%2Fapi.example.com\
+             &client_id=ddns+client&client_secret=s3cr%3At"
+        );
+    }
+
+    #[test]
+    fn debug_leaves_out_secret() {
+        let debug = format!("{:?}", grant());
+
+        assert!(debug.contains("ddns client"), "{debug}");
+        assert!(!debug.contains("s3cr"), "{debug}");
+    }
+}
+
+mod cache {
+    use super::*;
+
+    #[test]
+    fn token_is_used_until_shortly_before_expiry() {
+        let oauth2 = grant();
+        let now = Instant::now();
+
+        assert_eq!(oauth2.store(&token("t1", 3600), now).unwrap(), "t1");
+
+        assert_eq!(oauth2.cached(now).as_deref(), Some("t1"));
+        assert_eq!(
+            oauth2.cached(now + Duration::from_secs(3569)).as_deref(),
+            Some("t1")
+        );
+        assert_eq!(oauth2.cached(now + Duration::from_secs(3570)), None);
+    }
+
+    #[test]
+    fn short_lived_token_is_used_for_half_its_lifetime() {
+        let oauth2 = grant();
+        let now = Instant::now();
+        oauth2.store(&token("t1", 20), now).unwrap();
+
+        assert!(oauth2.cached(now + Duration::from_secs(9)).is_some());
+        assert!(oauth2.cached(now + Duration::from_secs(10)).is_none());
+    }
+
+    #[test]
+    fn token_without_lifetime_is_kept() {
+        let oauth2 = grant();
+        let now = Instant::now();
+        oauth2
+            .store(&response(200, r#"{"access_token": "t1"}"#), now)
+            .unwrap();
+
+        assert!(oauth2.cached(now + Duration::from_secs(86_400)).is_some());
+    }
+
+    #[test]
+    fn clones_share_the_token_and_invalidation_spares_newer_ones() {
+        let oauth2 = grant();
+        let clone = oauth2.clone();
+        let now = Instant::now();
+        oauth2.store(&token("t1", 3600), now).unwrap();
+
+        clone.invalidate("t0");
+        assert_eq!(clone.cached(now).as_deref(), Some("t1"));
+        clone.invalidate("t1");
+        assert_eq!(oauth2.cached(now), None);
+    }
+
+    #[test]
+    fn answer_without_token_is_rejected() {
+        let now = Instant::now();
+
+        assert!(
+            grant()
+                .store(&response(200, r#"{"error": "x"}"#), now)
+                .is_err()
+        );
+        assert!(grant().store(&token("bad\ntoken", 60), now).is_err());
+    }
+}
+
+/// Client answering from a script and recording every request.
+struct ScriptedClient {
+    responses: Mutex<VecDeque<HttpResponse>>,
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+impl ScriptedClient {
+    fn new(responses: impl IntoIterator<Item = HttpResponse>) -> Self {
+        Self {
+            responses: Mutex::new(responses.into_iter().collect()),
+            requests: Mutex::default(),
+        }
+    }
+
+    fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl HttpClient for &ScriptedClient {
+    async fn request(&self, req: HttpRequest) -> Result<HttpResponse, HttpError> {
+        self.requests.lock().unwrap().push(req);
+        Ok(self
+            .responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .expect("unexpected request"))
+    }
+}
+
+mod authorized_delivery {
+    use super::*;
+
+    fn changes() -> Vec<IpChange> {
+        vec![IpChange::added(
+            "eth0",
+            "192.0.2.1".parse().unwrap(),
+            SystemTime::UNIX_EPOCH,
+        )]
+    }
+
+    fn webhook(
+        client: &ScriptedClient,
+        oauth2: OAuth2,
+    ) -> HttpWebhook<&ScriptedClient, InstantSleeper> {
+        HttpWebhook::new(client, url::Url::parse("https://example.com/hook").unwrap())
+            .with_sleeper(InstantSleeper)
+            .with_retry_policy(RetryPolicy::new().with_max_attempts(2))
+            .with_oauth2(oauth2)
+    }
+
+    #[tokio::test]
+    async fn token_is_fetched_once_and_reused() {
+        let client = ScriptedClient::new([token("t1", 3600), response(200, ""), response(200, "")]);
+        let webhook = webhook(&client, grant());
+
+        webhook.send(&changes()).await.unwrap();
+        webhook.send(&changes()).await.unwrap();
+
+        let requests = client.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].url.path(), "/oauth/token");
+        assert_eq!(requests[1].headers["authorization"], "Bearer t1");
+        assert_eq!(requests[2].headers["authorization"], "Bearer t1");
+    }
+
+    #[tokio::test]
+    async fn unauthorized_answer_fetches_a_new_token() {
+        let client = ScriptedClient::new([
+            token("stale", 3600),
+            response(401, ""),
+            token("fresh", 3600),
+            response(200, ""),
+        ]);
+
+        webhook(&client, grant()).send(&changes()).await.unwrap();
+
+        let requests = client.requests();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[3].headers["authorization"], "Bearer fresh");
+    }
+
+    #[tokio::test]
+    async fn second_rejection_fails_the_attempt() {
+        let client = ScriptedClient::new([
+            token("t1", 3600),
+            response(401, ""),
+            token("t2", 3600),
+            response(401, ""),
+        ]);
+
+        let result = webhook(&client, grant()).send(&changes()).await;
+
+        assert!(matches!(
+            result,
+            Err(WebhookError::Retryable(RetryableError::NonSuccessStatus { status, .. }))
+                if status == StatusCode::UNAUTHORIZED
+        ));
+        assert_eq!(client.requests().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn failed_token_request_is_retried() {
+        let client = ScriptedClient::new([response(503, ""), token("t1", 3600), response(200, "")]);
+
+        webhook(&client, grant()).send(&changes()).await.unwrap();
+
+        assert_eq!(client.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn answer_without_token_fails_the_delivery() {
+        let client = ScriptedClient::new([response(200, "{}")]);
+
+        let result = webhook(&client, grant()).send(&changes()).await;
+
+        assert!(matches!(
+            result,
+            Err(WebhookError::Retryable(RetryableError::Token { .. }))
+        ));
+    }
+}
@@ -0,0 +1,233 @@
+//! Pre-requests whose responses feed the main request.
+//!
+//! Some DDNS panels require a fresh session or CSRF token for every update.
+//! With [`HttpWebhook::with_pre_request`](super::HttpWebhook::with_pre_request),
+//! each attempt first sends a [`PreRequest`] (usually a login or token
+//! fetch) and picks values out of its response with [`Capture`]s. The
+//! attempt's body template sees them as `pre.<name>`, and header values
+//! may use `{{pre.<name>}}` placeholders.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use http::{HeaderMap, HeaderName, HeaderValue};
+use serde::Serialize;
+
+use super::{HttpRequest, HttpResponse};
+
+/// Where a [`Capture`] takes its value from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaptureSource {
+    /// A response header.
+    Header(HeaderName),
+    /// A JSON pointer (RFC 6901, e.g. `/data/token`) into a JSON body.
+    Json(String),
+}
+
+impl CaptureSource {
+    /// Parses `header:<name>` or `json:<pointer>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns why `s` is not a valid source.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        if let Some(name) = s.strip_prefix("header:") {
+            return HeaderName::try_from(name.trim())
+                .map(Self::Header)
+                .map_err(|_| format!("'{name}' is not a valid header name"));
+        }
+        if let Some(pointer) = s.strip_prefix("json:") {
+            if !pointer.is_empty() && !pointer.starts_with('/') {
+                return Err(format!("JSON pointer '{pointer}' must start with '/'"));
+            }
+            return Ok(Self::Json(pointer.to_string()));
+        }
+        Err(format!(
+            "'{s}' must be \"header:<name>\" or \"json:<pointer>\""
+        ))
+    }
+}
+
+impl fmt::Display for CaptureSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Header(name) => write!(f, "header:{name}"),
+            Self::Json(pointer) => write!(f, "json:{pointer}"),
+        }
+    }
+}
+
+/// A named value taken from the pre-request's response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capture {
+    /// Variable name, available as `pre.<name>`
+    pub name: String,
+    /// Where the value comes from
+    pub source: CaptureSource,
+}
+
+/// A request sent before every attempt of a delivery.
+#[derive(Debug, Clone)]
+pub struct PreRequest {
+    method: http::Method,
+    url: url::Url,
+    headers: HeaderMap,
+    body: Option<String>,
+    captures: Vec<Capture>,
+}
+
+impl PreRequest {
+    /// Creates a pre-request without headers, body or captures.
+    #[must_use]
+    pub fn new(method: http::Method, url: url::Url) -> Self {
+        Self {
+            method,
+            url,
+            headers: HeaderMap::new(),
+            body: None,
+            captures: Vec::new(),
+        }
+    }
+
+    /// Sets the request headers.
+    #[must_use]
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Sets the request body, sent as is.
+    #[must_use]
+    pub fn with_body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Adds a capture.
+    #[must_use]
+    pub fn with_capture(mut self, name: impl Into<String>, source: CaptureSource) -> Self {
+        self.captures.push(Capture {
+            name: name.into(),
+            source,
+        });
+        self
+    }
+
+    /// Returns the captures, in the order they were added.
+    #[must_use]
+    pub fn captures(&self) -> &[Capture] {
+        &self.captures
+    }
+
+    /// Builds the HTTP request.
+    #[must_use]
+    pub fn request(&self) -> HttpRequest {
+        let mut request = HttpRequest::new(self.method.clone(), self.url.clone());
+        request.headers.clone_from(&self.headers);
+        request.body = self.body.clone().map(String::into_bytes);
+        request
+    }
+
+    /// Takes every capture's value from `response`.
+    ///
+    /// # Errors
+    ///
+    /// Returns which capture is missing from the response.
+    pub fn capture(&self, response: &HttpResponse) -> Result<Captured, String> {
+        let json = || serde_json::from_slice::<serde_json::Value>(&response.body).ok();
+        let mut body = None;
+
+        let mut values = BTreeMap::new();
+        for capture in &self.captures {
+            let value = match &capture.source {
+                CaptureSource::Header(name) => response
+                    .headers
+                    .get(name)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string),
+                CaptureSource::Json(pointer) => body
+                    .get_or_insert_with(json)
+                    .as_ref()
+                    .and_then(|json| json.pointer(pointer))
+                    .and_then(scalar),
+            };
+            let value = value.ok_or_else(|| {
+                format!(
+                    "pre-request response has no {} for '{}'",
+                    capture.source, capture.name
+                )
+            })?;
+            values.insert(capture.name.clone(), value);
+        }
+        Ok(Captured(values))
+    }
+}
+
+/// A JSON scalar as text; `None` for null, arrays and objects.
+fn scalar(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Values captured from a pre-request's response, by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct Captured(BTreeMap<String, String>);
+
+impl Captured {
+    /// Returns the value captured as `name`.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+
+    /// Returns true if nothing was captured.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Replaces the `{{pre.<name>}}` placeholders of every header value.
+    ///
+    /// Placeholders of names that were not captured are left as they are.
+    ///
+    /// # Errors
+    ///
+    /// Returns the header whose value is no longer valid, e.g. because a
+    /// captured value holds a line break.
+    pub fn apply(&self, headers: &mut HeaderMap) -> Result<(), String> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        for (name, value) in headers.iter_mut() {
+            let Ok(text) = value.to_str() else {
+                continue;
+            };
+            if !text.contains("{{pre.") {
+                continue;
+            }
+            let replaced = self
+                .0
+                .iter()
+                .fold(text.to_string(), |text, (key, captured)| {
+                    text.replace(&format!("{{{{pre.{key}}}}}"), captured)
+                });
+            *value = HeaderValue::from_str(&replaced)
+                .map_err(|_| format!("captured value is not valid in header '{name}'"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Names of the `{{pre.<name>}}` placeholders in `text`.
+#[must_use]
+pub fn placeholders(text: &str) -> Vec<&str> {
+    text.split("{{pre.")
+        .skip(1)
+        .filter_map(|rest| rest.split_once("}}").map(|(name, _)| name.trim()))
+        .collect()
+}
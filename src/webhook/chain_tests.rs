@@ -0,0 +1,284 @@
+//! Tests for pre-requests and their captures.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use http::{HeaderMap, HeaderValue, StatusCode};
+
+use super::chain::placeholders;
+use super::{
+    CaptureSource, HttpClient, HttpError, HttpRequest, HttpResponse, HttpWebhook, PreRequest,
+    RetryPolicy, RetryableError, WebhookError, WebhookSender,
+};
+use crate::monitor::IpChange;
+use crate::time::InstantSleeper;
+
+fn response(status: u16, headers: &[(&'static str, &'static str)], body: &str) -> HttpResponse {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        map.insert(*name, HeaderValue::from_static(value));
+    }
+    HttpResponse {
+        status: StatusCode::from_u16(status).unwrap(),
+        headers: map,
+        body: body.as_bytes().to_vec(),
+    }
+}
+
+fn login() -> PreRequest {
+    PreRequest::new(
+        http::Method::POST,
+        url::Url::parse("https://panel.example.com/login").unwrap(),
+    )
+}
+
+fn header(name: &'static str) -> CaptureSource {
+    CaptureSource::Header(http::HeaderName::from_static(name))
+}
+
+fn json(pointer: &str) -> CaptureSource {
+    CaptureSource::Json(pointer.to_string())
+}
+
+mod capture_source {
+    use super::*;
+
+    #[test]
+    fn parses_header_and_json() {
+        assert_eq!(
+            CaptureSource::parse("header:X-CSRF-Token"),
+            Ok(header("x-csrf-token"))
+        );
+        assert_eq!(
+            CaptureSource::parse("json:/data/token"),
+            Ok(json("/data/token"))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_kind_and_relative_pointer() {
+        assert!(CaptureSource::parse("cookie:session").is_err());
+        assert!(CaptureSource::parse("json:data/token").is_err());
+        assert!(CaptureSource::parse("header:bad header").is_err());
+    }
+
+    #[test]
+    fn displays_as_parsed() {
+        assert_eq!(json("/token").to_string(), "json:/token");
+    }
+}
+
+mod capture {
+    use super::*;
+
+    #[test]
+    fn takes_headers_and_json_scalars() {
+        let pre = login()
+            .with_capture("csrf", header("x-csrf-token"))
+            .with_capture("session", json("/data/session"))
+            .with_capture("expires", json("/data/expires"));
+        let answer = response(
+            200,
+            &[("x-csrf-token", "abc")],
+            r#"{"data": {"session": "s1", "expires": 3600}}"#,
+        );
+
+        let captured = pre.capture(&answer).unwrap();
+
+        assert_eq!(captured.get("csrf"), Some("abc"));
+        assert_eq!(captured.get("session"), Some("s1"));
+        assert_eq!(captured.get("expires"), Some("3600"));
+    }
+
+    #[test]
+    fn missing_value_is_an_error() {
+        let pre = login().with_capture("token", json("/token"));
+
+        let error = pre.capture(&response(200, &[], "not json")).unwrap_err();
+
+        assert!(error.contains("json:/token"), "{error}");
+        assert!(error.contains("'token'"), "{error}");
+    }
+
+    #[test]
+    fn objects_are_not_captured() {
+        let pre = login().with_capture("data", json("/data"));
+
+        assert!(pre.capture(&response(200, &[], r#"{"data": {}}"#)).is_err());
+    }
+
+    #[test]
+    fn request_carries_headers_and_body() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+
+        let request = login()
+            .with_headers(headers)
+            .with_body(r#"{"user": "me"}"#)
+            .request();
+
+        assert_eq!(request.method, http::Method::POST);
+        assert_eq!(request.headers["content-type"], "application/json");
+        assert_eq!(
+            request.body.as_deref(),
+            Some(br#"{"user": "me"}"#.as_slice())
+        );
+    }
+}
+
+mod captured {
+    use super::*;
+
+    #[test]
+    fn apply_replaces_known_placeholders() {
+        let pre = login().with_capture("csrf", header("x-csrf-token"));
+        let captured = pre
+            .capture(&response(200, &[("x-csrf-token", "abc")], ""))
+            .unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-token", HeaderValue::from_static("t={{pre.csrf}}"));
+        headers.insert("x-other", HeaderValue::from_static("{{pre.unknown}}"));
+
+        captured.apply(&mut headers).unwrap();
+
+        assert_eq!(headers["x-token"], "t=abc");
+        assert_eq!(headers["x-other"], "{{pre.unknown}}");
+    }
+
+    #[test]
+    fn placeholders_lists_names() {
+        assert_eq!(
+            placeholders("{{pre.a}} and {{ pre.b }} or {{pre.c}}"),
+            ["a", "c"]
+        );
+        assert!(placeholders("Bearer abc").is_empty());
+    }
+}
+
+/// Client answering from a script and recording every request.
+struct ScriptedClient {
+    responses: Mutex<VecDeque<Result<HttpResponse, HttpError>>>,
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+impl ScriptedClient {
+    fn new(responses: impl IntoIterator<Item = Result<HttpResponse, HttpError>>) -> Self {
+        Self {
+            responses: Mutex::new(responses.into_iter().collect()),
+            requests: Mutex::default(),
+        }
+    }
+}
+
+impl HttpClient for &ScriptedClient {
+    async fn request(&self, req: HttpRequest) -> Result<HttpResponse, HttpError> {
+        self.requests.lock().unwrap().push(req);
+        self.responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .expect("unexpected request")
+    }
+}
+
+mod chained_delivery {
+    use super::*;
+
+    fn changes() -> Vec<IpChange> {
+        vec![IpChange::added(
+            "eth0",
+            "192.0.2.1".parse().unwrap(),
+            SystemTime::UNIX_EPOCH,
+        )]
+    }
+
+    /// Sends `changes()` through a webhook with a login pre-request and
+    /// returns every request made.
+    async fn deliver(client: &ScriptedClient) -> (Result<(), WebhookError>, Vec<HttpRequest>) {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-csrf-token", HeaderValue::from_static("{{pre.csrf}}"));
+        let webhook = HttpWebhook::new(
+            client,
+            url::Url::parse("https://panel.example.com/update").unwrap(),
+        )
+        .with_sleeper(InstantSleeper)
+        .with_retry_policy(RetryPolicy::new().with_max_attempts(2))
+        .with_headers(headers)
+        .with_body_template("{{pre.session}} {{#each changes}}{{address}}{{/each}}")
+        .with_pre_request(
+            login()
+                .with_capture("csrf", header("x-csrf-token"))
+                .with_capture("session", json("/session")),
+        );
+
+        let result = webhook.send(&changes()).await;
+        (result, client.requests.lock().unwrap().clone())
+    }
+
+    #[cfg(feature = "templates")]
+    fn token(value: &'static str) -> HttpResponse {
+        response(200, &[("x-csrf-token", value)], r#"{"session": "s1"}"#)
+    }
+
+    #[cfg(feature = "templates")]
+    #[tokio::test]
+    async fn captures_feed_headers_and_template() {
+        let client = ScriptedClient::new([Ok(token("abc")), Ok(response(200, &[], ""))]);
+
+        let (result, requests) = deliver(&client).await;
+
+        result.unwrap();
+        assert_eq!(requests[0].url.path(), "/login");
+        assert_eq!(requests[1].headers["x-csrf-token"], "abc");
+        assert_eq!(
+            requests[1].body.as_deref(),
+            Some(b"s1 192.0.2.1".as_slice())
+        );
+    }
+
+    #[cfg(feature = "templates")]
+    #[tokio::test]
+    async fn every_attempt_fetches_a_fresh_token() {
+        let client = ScriptedClient::new([
+            Ok(token("first")),
+            Ok(response(503, &[], "")),
+            Ok(token("second")),
+            Ok(response(200, &[], "")),
+        ]);
+
+        let (result, requests) = deliver(&client).await;
+
+        result.unwrap();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[3].headers["x-csrf-token"], "second");
+    }
+
+    #[cfg(feature = "templates")]
+    #[tokio::test]
+    async fn failed_pre_request_is_retried() {
+        let client = ScriptedClient::new([
+            Ok(response(502, &[], "")),
+            Ok(token("abc")),
+            Ok(response(200, &[], "")),
+        ]);
+
+        let (result, requests) = deliver(&client).await;
+
+        result.unwrap();
+        assert_eq!(requests.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn missing_capture_fails_without_retry() {
+        let client = ScriptedClient::new([Ok(response(200, &[], "{}"))]);
+
+        let (result, requests) = deliver(&client).await;
+
+        assert!(matches!(
+            result,
+            Err(WebhookError::Retryable(RetryableError::Capture { .. }))
+        ));
+        assert_eq!(requests.len(), 1);
+    }
+}
@@ -0,0 +1,285 @@
+//! Circuit breaking for webhooks that keep failing.
+//!
+//! After [`CircuitPolicy::failure_threshold`] consecutive failed deliveries,
+//! a [`CircuitBreaker`] opens its [`Circuit`]: deliveries fail at once with
+//! [`WebhookError::CircuitOpen`] instead of burning through retries against a
+//! dead endpoint. Once the cooldown has passed the circuit is half-open, and
+//! the next delivery probes the endpoint: success closes the circuit, failure
+//! opens it for another cooldown.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::monitor::IpChange;
+
+use super::{Event, WebhookError, WebhookSender};
+
+/// Default number of consecutive failures opening the circuit.
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+
+/// Default time an open circuit rejects deliveries.
+pub const DEFAULT_CIRCUIT_COOLDOWN: Duration = Duration::from_secs(300);
+
+/// When a circuit opens and for how long.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitPolicy {
+    /// Consecutive failed deliveries opening the circuit.
+    pub failure_threshold: u32,
+    /// Time the open circuit rejects deliveries before probing.
+    pub cooldown: Duration,
+}
+
+impl Default for CircuitPolicy {
+    fn default() -> Self {
+        Self {
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            cooldown: DEFAULT_CIRCUIT_COOLDOWN,
+        }
+    }
+}
+
+/// State of a circuit, as reported in the run summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "state")]
+pub enum CircuitState {
+    /// Deliveries are attempted.
+    Closed,
+    /// Deliveries are rejected until the cooldown ends.
+    Open {
+        /// Seconds until the next delivery probes the endpoint
+        retry_in_secs: u64,
+    },
+    /// The next delivery probes the endpoint.
+    HalfOpen,
+}
+
+impl CircuitState {
+    /// Returns the `snake_case` name of the state.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Closed => "closed",
+            Self::Open { .. } => "open",
+            Self::HalfOpen => "half_open",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct CircuitInner {
+    failures: u32,
+    /// Set while the circuit is open or half-open
+    open_until: Option<Instant>,
+    /// Whether a half-open probe is in flight
+    probing: bool,
+}
+
+/// The shared state of one webhook's circuit breaker; clones share it.
+#[derive(Debug, Clone)]
+pub struct Circuit {
+    name: String,
+    policy: CircuitPolicy,
+    inner: Arc<Mutex<CircuitInner>>,
+}
+
+impl Circuit {
+    /// Creates a closed circuit for the webhook `name`.
+    #[must_use]
+    pub fn new(name: impl Into<String>, policy: CircuitPolicy) -> Self {
+        Self {
+            name: name.into(),
+            policy,
+            inner: Arc::default(),
+        }
+    }
+
+    /// Returns the state of the circuit at `now`.
+    #[must_use]
+    pub fn state(&self, now: Instant) -> CircuitState {
+        let inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        match inner.open_until {
+            None => CircuitState::Closed,
+            Some(until) if now < until => CircuitState::Open {
+                retry_in_secs: until.saturating_duration_since(now).as_secs().max(1),
+            },
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Lets a delivery through at `now`, or rejects it while the circuit is
+    /// open or another delivery is probing the endpoint.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WebhookError::CircuitOpen`] if the delivery is rejected.
+    pub fn admit(&self, now: Instant) -> Result<(), WebhookError> {
+        let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(until) = inner.open_until else {
+            return Ok(());
+        };
+        if now < until || inner.probing {
+            drop(inner);
+            return Err(WebhookError::CircuitOpen {
+                retry_in_secs: until.saturating_duration_since(now).as_secs(),
+            });
+        }
+        inner.probing = true;
+        drop(inner);
+        tracing::debug!("Probing {} with the next delivery", self.name);
+        Ok(())
+    }
+
+    /// Records the `result` of an admitted delivery finished at `now`.
+    ///
+    /// Postponed and interrupted deliveries count neither way.
+    pub fn record(&self, result: &Result<(), WebhookError>, now: Instant) {
+        let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        let probing = std::mem::take(&mut inner.probing);
+        match result {
+            Ok(()) => {
+                if inner.open_until.is_some() {
+                    tracing::info!("Circuit of {} closed: delivery succeeded", self.name);
+                }
+                *inner = CircuitInner::default();
+            }
+            Err(e) if e.is_postponed() || e.is_interrupted() => {}
+            Err(_) => {
+                inner.failures = inner.failures.saturating_add(1);
+                if probing {
+                    tracing::debug!("Probe of {} failed, circuit stays open", self.name);
+                } else if inner.failures >= self.policy.failure_threshold
+                    && inner.open_until.is_none()
+                {
+                    tracing::warn!(
+                        "Circuit of {} opened after {} consecutive failures: \
+                         skipping deliveries for {}s",
+                        self.name,
+                        inner.failures,
+                        self.policy.cooldown.as_secs()
+                    );
+                } else {
+                    return;
+                }
+                inner.open_until = Some(now + self.policy.cooldown);
+            }
+        }
+    }
+}
+
+/// The circuits of all webhook targets by name, shared with the run stats.
+///
+/// Clones share the circuits, so a reloaded sender keeps the state of the
+/// targets it still has.
+#[derive(Debug, Clone)]
+pub struct CircuitBreakers {
+    policy: CircuitPolicy,
+    circuits: Arc<Mutex<BTreeMap<String, Circuit>>>,
+}
+
+impl CircuitBreakers {
+    /// Creates an empty set of circuits opening by `policy`.
+    #[must_use]
+    pub fn new(policy: CircuitPolicy) -> Self {
+        Self {
+            policy,
+            circuits: Arc::default(),
+        }
+    }
+
+    /// Returns the circuit of the webhook `name`, creating it if needed.
+    #[must_use]
+    pub fn circuit(&self, name: &str) -> Circuit {
+        self.circuit_with(name, self.policy)
+    }
+
+    /// Returns the circuit of the webhook `name`, creating it with its own
+    /// `policy` if needed.
+    #[must_use]
+    pub fn circuit_with(&self, name: &str, policy: CircuitPolicy) -> Circuit {
+        let mut circuits = self.circuits.lock().unwrap_or_else(PoisonError::into_inner);
+        circuits
+            .entry(name.to_string())
+            .or_insert_with(|| Circuit::new(name, policy))
+            .clone()
+    }
+
+    /// Returns the state of every circuit at `now`, by webhook name.
+    #[must_use]
+    pub fn states(&self, now: Instant) -> BTreeMap<String, CircuitState> {
+        let circuits = self.circuits.lock().unwrap_or_else(PoisonError::into_inner);
+        circuits
+            .iter()
+            .map(|(name, circuit)| (name.clone(), circuit.state(now)))
+            .collect()
+    }
+}
+
+/// A [`WebhookSender`] decorator rejecting deliveries while its
+/// [`Circuit`] is open.
+///
+/// Without a circuit every delivery is passed through, so senders keep one
+/// type whether circuit breaking is enabled or not.
+#[derive(Debug)]
+pub struct CircuitBreaker<W> {
+    inner: W,
+    circuit: Option<Circuit>,
+}
+
+impl<W> CircuitBreaker<W> {
+    /// Wraps `inner`, guarding its deliveries with `circuit`.
+    #[must_use]
+    pub const fn new(inner: W, circuit: Circuit) -> Self {
+        Self {
+            inner,
+            circuit: Some(circuit),
+        }
+    }
+
+    /// Wraps `inner` without a circuit, passing every delivery through.
+    #[must_use]
+    pub const fn unguarded(inner: W) -> Self {
+        Self {
+            inner,
+            circuit: None,
+        }
+    }
+
+    /// Returns a reference to the wrapped sender.
+    pub const fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns the guarding circuit, if any.
+    pub const fn circuit(&self) -> Option<&Circuit> {
+        self.circuit.as_ref()
+    }
+}
+
+impl<W: Sync> CircuitBreaker<W> {
+    async fn guard(
+        &self,
+        delivery: impl Future<Output = Result<(), WebhookError>> + Send,
+    ) -> Result<(), WebhookError> {
+        let Some(circuit) = &self.circuit else {
+            return delivery.await;
+        };
+        circuit.admit(Instant::now())?;
+        let result = delivery.await;
+        circuit.record(&result, Instant::now());
+        result
+    }
+}
+
+impl<W: WebhookSender> WebhookSender for CircuitBreaker<W> {
+    async fn send(&self, changes: &[IpChange]) -> Result<(), WebhookError> {
+        self.guard(self.inner.send(changes)).await
+    }
+
+    async fn send_event(&self, event: &Event<'_>) -> Result<(), WebhookError> {
+        self.guard(self.inner.send_event(event)).await
+    }
+}
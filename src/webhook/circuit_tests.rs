@@ -0,0 +1,233 @@
+//! Tests for the webhook circuit breaker.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+
+use super::circuit::{Circuit, CircuitBreaker, CircuitBreakers, CircuitPolicy, CircuitState};
+use super::{Event, HttpError, RetryableError, WebhookError, WebhookSender};
+use crate::monitor::IpChange;
+
+const COOLDOWN: Duration = Duration::from_secs(60);
+
+fn policy() -> CircuitPolicy {
+    CircuitPolicy {
+        failure_threshold: 2,
+        cooldown: COOLDOWN,
+    }
+}
+
+fn failure() -> Result<(), WebhookError> {
+    Err(WebhookError::MaxRetriesExceeded {
+        attempts: 3,
+        last_error: RetryableError::Http(HttpError::Timeout),
+    })
+}
+
+mod circuit {
+    use super::*;
+
+    /// Opens a circuit with two failures at `now`.
+    fn opened(now: Instant) -> Circuit {
+        let circuit = Circuit::new("main", policy());
+        for _ in 0..2 {
+            circuit.admit(now).unwrap();
+            circuit.record(&failure(), now);
+        }
+        circuit
+    }
+
+    #[test]
+    fn opens_after_consecutive_failures() {
+        let circuit = Circuit::new("main", policy());
+        let now = Instant::now();
+
+        circuit.record(&failure(), now);
+        assert_eq!(circuit.state(now), CircuitState::Closed);
+        circuit.record(&failure(), now);
+
+        assert_eq!(circuit.state(now), CircuitState::Open { retry_in_secs: 60 });
+        assert!(matches!(
+            circuit.admit(now + Duration::from_secs(10)),
+            Err(WebhookError::CircuitOpen { retry_in_secs: 50 })
+        ));
+    }
+
+    #[test]
+    fn success_resets_the_failure_count() {
+        let circuit = Circuit::new("main", policy());
+        let now = Instant::now();
+
+        circuit.record(&failure(), now);
+        circuit.record(&Ok(()), now);
+        circuit.record(&failure(), now);
+
+        assert_eq!(circuit.state(now), CircuitState::Closed);
+    }
+
+    #[test]
+    fn postponed_and_interrupted_deliveries_do_not_count() {
+        let circuit = Circuit::new("main", policy());
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            circuit.record(
+                &Err(WebhookError::Postponed {
+                    pending: 1,
+                    reason: "offline".to_string(),
+                }),
+                now,
+            );
+            circuit.record(&Err(WebhookError::Interrupted { attempts: 1 }), now);
+        }
+
+        assert_eq!(circuit.state(now), CircuitState::Closed);
+    }
+
+    #[test]
+    fn half_open_admits_a_single_probe() {
+        let now = Instant::now();
+        let circuit = opened(now);
+        let later = now + COOLDOWN;
+
+        assert_eq!(circuit.state(later), CircuitState::HalfOpen);
+        circuit.admit(later).unwrap();
+        assert!(circuit.admit(later).is_err());
+    }
+
+    #[test]
+    fn successful_probe_closes_the_circuit() {
+        let now = Instant::now();
+        let circuit = opened(now);
+        let later = now + COOLDOWN;
+
+        circuit.admit(later).unwrap();
+        circuit.record(&Ok(()), later);
+
+        assert_eq!(circuit.state(later), CircuitState::Closed);
+        circuit.admit(later).unwrap();
+    }
+
+    #[test]
+    fn failed_probe_opens_the_circuit_again() {
+        let now = Instant::now();
+        let circuit = opened(now);
+        let later = now + COOLDOWN;
+
+        circuit.admit(later).unwrap();
+        circuit.record(&failure(), later);
+
+        assert_eq!(
+            circuit.state(later),
+            CircuitState::Open { retry_in_secs: 60 }
+        );
+    }
+
+    #[test]
+    fn state_serializes_with_a_tag() {
+        let json = serde_json::to_string(&CircuitState::Open { retry_in_secs: 5 }).unwrap();
+
+        assert_eq!(json, r#"{"state":"open","retry_in_secs":5}"#);
+        assert_eq!(CircuitState::HalfOpen.as_str(), "half_open");
+    }
+}
+
+mod breakers {
+    use super::*;
+
+    #[test]
+    fn circuits_are_shared_by_name() {
+        let breakers = CircuitBreakers::new(policy());
+        let now = Instant::now();
+        let circuit = breakers.circuit("main");
+        circuit.record(&failure(), now);
+        circuit.record(&failure(), now);
+
+        assert!(breakers.circuit("main").admit(now).is_err());
+        assert!(breakers.circuit("backup").admit(now).is_ok());
+        let states = breakers.states(now);
+        assert_eq!(states.len(), 2);
+        assert_eq!(states["backup"], CircuitState::Closed);
+    }
+
+    #[test]
+    fn circuit_keeps_its_own_policy() {
+        let breakers = CircuitBreakers::new(policy());
+        let now = Instant::now();
+        let own = CircuitPolicy {
+            failure_threshold: 1,
+            ..policy()
+        };
+        breakers.circuit_with("backup", own).record(&failure(), now);
+
+        assert!(breakers.circuit("backup").admit(now).is_err());
+    }
+}
+
+/// Sender answering from a script and counting deliveries.
+struct ScriptedSender {
+    results: Mutex<VecDeque<Result<(), WebhookError>>>,
+    sent: AtomicUsize,
+}
+
+impl ScriptedSender {
+    fn new(results: impl IntoIterator<Item = Result<(), WebhookError>>) -> Self {
+        Self {
+            results: Mutex::new(results.into_iter().collect()),
+            sent: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl WebhookSender for ScriptedSender {
+    async fn send(&self, _changes: &[IpChange]) -> Result<(), WebhookError> {
+        self.sent.fetch_add(1, Ordering::SeqCst);
+        self.results
+            .lock()
+            .unwrap()
+            .pop_front()
+            .expect("unexpected delivery")
+    }
+
+    async fn send_event(&self, _event: &Event<'_>) -> Result<(), WebhookError> {
+        unreachable!("only address changes are sent")
+    }
+}
+
+mod breaker {
+    use super::*;
+
+    fn changes() -> Vec<IpChange> {
+        vec![IpChange::added(
+            "eth0",
+            "192.0.2.1".parse().unwrap(),
+            SystemTime::UNIX_EPOCH,
+        )]
+    }
+
+    #[tokio::test]
+    async fn open_circuit_skips_the_endpoint() {
+        let sender = ScriptedSender::new([failure(), failure()]);
+        let breaker = CircuitBreaker::new(sender, Circuit::new("main", policy()));
+
+        assert!(breaker.send(&changes()).await.is_err());
+        assert!(breaker.send(&changes()).await.is_err());
+        let result = breaker.send(&changes()).await;
+
+        assert!(matches!(result, Err(WebhookError::CircuitOpen { .. })));
+        assert!(result.unwrap_err().is_circuit_open());
+        assert_eq!(breaker.inner().sent.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn unguarded_passes_every_delivery() {
+        let sender = ScriptedSender::new([failure(), failure(), Ok(())]);
+        let breaker = CircuitBreaker::unguarded(sender);
+
+        assert!(breaker.send(&changes()).await.is_err());
+        assert!(breaker.send(&changes()).await.is_err());
+        assert!(breaker.send(&changes()).await.is_ok());
+        assert!(breaker.circuit().is_none());
+    }
+}
@@ -0,0 +1,307 @@
+//! Audit logging of outbound HTTP requests.
+//!
+//! Provides an [`HttpClient`] decorator ([`AuditedClient`]) that records every
+//! request leaving the process: method, URL, header names, a hash of the body,
+//! and the response status (or transport error). Sensitive values are redacted
+//! according to a [`Redaction`] allow-list before anything is written.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use http::{HeaderMap, HeaderName};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::time::{Clock, SystemClock};
+
+use crate::webhook::{HttpClient, HttpError, HttpRequest, HttpResponse};
+
+/// Placeholder written in place of redacted values.
+pub const REDACTED: &str = "<redacted>";
+
+/// Redaction rules applied to audit records.
+///
+/// Everything that may carry a secret is redacted by default:
+/// - Header values (header names are always kept)
+/// - Query parameter values (parameter names are always kept)
+/// - URL user-info passwords (never allow-listable)
+///
+/// Individual headers and query parameters can be allow-listed by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Redaction {
+    allowed_headers: HashSet<HeaderName>,
+    allowed_query_params: HashSet<String>,
+}
+
+impl Redaction {
+    /// Creates redaction rules that redact all values.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps the value of the given header in audit records.
+    #[must_use]
+    pub fn allow_header(mut self, name: HeaderName) -> Self {
+        self.allowed_headers.insert(name);
+        self
+    }
+
+    /// Keeps the value of the given query parameter in audit records.
+    #[must_use]
+    pub fn allow_query_param(mut self, name: impl Into<String>) -> Self {
+        self.allowed_query_params.insert(name.into());
+        self
+    }
+
+    /// Returns header names mapped to their (possibly redacted) values.
+    ///
+    /// Multiple values for the same header are joined with `", "`.
+    #[must_use]
+    pub fn redact_headers(&self, headers: &HeaderMap) -> BTreeMap<String, String> {
+        let mut redacted: BTreeMap<String, String> = BTreeMap::new();
+
+        for (name, value) in headers {
+            let shown = if self.allowed_headers.contains(name) {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            } else {
+                REDACTED.to_string()
+            };
+
+            redacted
+                .entry(name.as_str().to_string())
+                .and_modify(|existing| {
+                    existing.push_str(", ");
+                    existing.push_str(&shown);
+                })
+                .or_insert(shown);
+        }
+
+        redacted
+    }
+
+    /// Returns the URL with credentials and non-allow-listed query values redacted.
+    #[must_use]
+    pub fn redact_url(&self, url: &url::Url) -> String {
+        let mut redacted = url.clone();
+
+        if redacted.password().is_some() {
+            // Only fails for cannot-be-a-base URLs, which never carry credentials
+            let _ = redacted.set_password(Some(REDACTED));
+        }
+
+        if url.query().is_some() {
+            let pairs: Vec<(String, String)> = url
+                .query_pairs()
+                .map(|(name, value)| {
+                    let shown = if self.allowed_query_params.contains(name.as_ref()) {
+                        value.into_owned()
+                    } else {
+                        REDACTED.to_string()
+                    };
+                    (name.into_owned(), shown)
+                })
+                .collect();
+            redacted.query_pairs_mut().clear().extend_pairs(pairs);
+        }
+
+        redacted.to_string()
+    }
+}
+
+/// Result of an audited request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    /// The server responded with this HTTP status code.
+    Status(u16),
+    /// The request failed before a response was received.
+    Error(String),
+}
+
+/// A single audit log entry describing one outbound request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Unix timestamp (seconds) when the request was sent.
+    pub timestamp: u64,
+    /// HTTP method.
+    pub method: String,
+    /// Target URL after redaction.
+    pub url: String,
+    /// Header names with values redacted unless allow-listed.
+    pub headers: BTreeMap<String, String>,
+    /// Hex-encoded SHA-256 of the request body, if a body was sent.
+    pub body_sha256: Option<String>,
+    /// Response status or transport error.
+    #[serde(flatten)]
+    pub outcome: AuditOutcome,
+}
+
+impl AuditRecord {
+    /// Builds a record from a request and its result.
+    #[must_use]
+    pub fn new(
+        request: &HttpRequest,
+        result: &Result<HttpResponse, HttpError>,
+        redaction: &Redaction,
+        timestamp: u64,
+    ) -> Self {
+        let outcome = match result {
+            Ok(response) => AuditOutcome::Status(response.status.as_u16()),
+            Err(e) => AuditOutcome::Error(e.to_string()),
+        };
+
+        Self {
+            timestamp,
+            method: request.method.to_string(),
+            url: redaction.redact_url(&request.url),
+            headers: redaction.redact_headers(&request.headers),
+            body_sha256: request.body.as_deref().map(sha256_hex),
+            outcome,
+        }
+    }
+}
+
+/// Returns the lowercase hex SHA-256 digest of `data`.
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Destination for audit records.
+///
+/// Sinks must not fail the request being audited: write errors are
+/// reported via logging and otherwise swallowed.
+pub trait AuditSink: Send + Sync {
+    /// Persists a single audit record.
+    fn record(&self, record: &AuditRecord);
+}
+
+/// `None` disables auditing, allowing the decorator to be applied unconditionally.
+impl<S: AuditSink> AuditSink for Option<S> {
+    fn record(&self, record: &AuditRecord) {
+        if let Some(sink) = self {
+            sink.record(record);
+        }
+    }
+}
+
+/// Appends audit records as JSON lines to a file.
+///
+/// The file is opened in append mode for every record, so external log
+/// rotation (rename + recreate) is picked up without restarting.
+#[derive(Debug, Clone)]
+pub struct FileAuditSink {
+    path: PathBuf,
+}
+
+impl FileAuditSink {
+    /// Creates a sink writing to the given file.
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the path of the audit file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn append(&self, record: &AuditRecord) -> std::io::Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(line.as_bytes())
+    }
+}
+
+impl AuditSink for FileAuditSink {
+    fn record(&self, record: &AuditRecord) {
+        if let Err(e) = self.append(record) {
+            tracing::error!(
+                "Failed to write audit record to {}: {e}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+/// Audit settings resolved from configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditConfig {
+    /// File receiving JSON-lines audit records.
+    pub path: PathBuf,
+    /// Redaction rules for recorded requests.
+    pub redaction: Redaction,
+}
+
+/// An [`HttpClient`] decorator that records every request to an [`AuditSink`].
+///
+/// # Type Parameters
+///
+/// - `H`: The wrapped HTTP client
+/// - `A`: The audit sink
+/// - `C`: The clock used for record timestamps (defaults to [`SystemClock`])
+#[derive(Debug)]
+pub struct AuditedClient<H, A, C = SystemClock> {
+    inner: H,
+    sink: A,
+    redaction: Redaction,
+    clock: C,
+}
+
+impl<H, A> AuditedClient<H, A, SystemClock> {
+    /// Wraps `inner`, recording its requests to `sink`.
+    #[must_use]
+    pub const fn new(inner: H, sink: A, redaction: Redaction) -> Self {
+        Self::with_clock(inner, sink, redaction, SystemClock)
+    }
+}
+
+impl<H, A, C> AuditedClient<H, A, C> {
+    /// Wraps `inner` with a custom clock for record timestamps.
+    #[must_use]
+    pub const fn with_clock(inner: H, sink: A, redaction: Redaction, clock: C) -> Self {
+        Self {
+            inner,
+            sink,
+            redaction,
+            clock,
+        }
+    }
+
+    /// Returns a reference to the audit sink.
+    pub const fn sink(&self) -> &A {
+        &self.sink
+    }
+}
+
+impl<H: HttpClient, A: AuditSink, C: Clock> HttpClient for AuditedClient<H, A, C> {
+    async fn request(&self, req: HttpRequest) -> Result<HttpResponse, HttpError> {
+        // Pre-epoch clocks shouldn't occur in practice; record 0 rather than failing
+        let timestamp = self
+            .clock
+            .now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+
+        // The request is moved into the inner client, so keep a copy for the record
+        let audited = req.clone();
+        let result = self.inner.request(req).await;
+
+        self.sink.record(&AuditRecord::new(
+            &audited,
+            &result,
+            &self.redaction,
+            timestamp,
+        ));
+
+        result
+    }
+}
@@ -0,0 +1,263 @@
+//! TLS certificate fingerprints of the hosts the client connects to.
+//!
+//! Edge devices send credentials to their DDNS endpoint, which makes them a
+//! target for interception. [`CertificateWatch`] records the SHA-256
+//! fingerprint of the certificate each host presents and reports a
+//! [`CertificateChange`] when it differs from the one seen before. With
+//! pinned fingerprints, a host presenting any other certificate fails the
+//! handshake, before a request (and its credentials) is sent.
+//!
+//! The watch hooks into certificate verification via
+//! [`tls_config`](crate::webhook::tls_config): the platform verifier checks the chain
+//! first, then the watch checks the leaf.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::SystemTime;
+
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, SignatureScheme};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// SHA-256 fingerprint of a DER-encoded certificate.
+///
+/// Displayed and parsed as 64 hex digits; parsing also accepts the
+/// colon-separated form that browsers and `openssl x509 -fingerprint` print.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Fingerprint([u8; 32]);
+
+impl Fingerprint {
+    /// Returns the fingerprint of the DER-encoded certificate `der`.
+    #[must_use]
+    pub fn of(der: &[u8]) -> Self {
+        Self(Sha256::digest(der).into())
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+impl FromStr for Fingerprint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("'{s}' is not a SHA-256 fingerprint (64 hex digits)");
+        let digits: Vec<u8> = s.bytes().filter(|&b| b != b':').collect();
+        if digits.len() != 64 {
+            return Err(invalid());
+        }
+        let mut bytes = [0u8; 32];
+        for (byte, pair) in bytes.iter_mut().zip(digits.chunks(2)) {
+            let pair = std::str::from_utf8(pair).map_err(|_| invalid())?;
+            *byte = u8::from_str_radix(pair, 16).map_err(|_| invalid())?;
+        }
+        Ok(Self(bytes))
+    }
+}
+
+impl TryFrom<String> for Fingerprint {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Fingerprint> for String {
+    fn from(fingerprint: Fingerprint) -> Self {
+        fingerprint.to_string()
+    }
+}
+
+/// A host presenting a certificate other than the one recorded for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateChange {
+    /// The host concerned.
+    pub host: String,
+    /// The recorded fingerprint; `None` the first time the host is seen.
+    pub old: Option<Fingerprint>,
+    /// The fingerprint of the certificate just presented.
+    pub new: Fingerprint,
+    /// The timestamp when the change was detected.
+    pub timestamp: SystemTime,
+}
+
+impl fmt::Display for CertificateChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.old {
+            Some(old) => write!(
+                f,
+                "{}: TLS certificate changed from {old} to {}",
+                self.host, self.new
+            ),
+            None => write!(f, "{}: recorded TLS certificate {}", self.host, self.new),
+        }
+    }
+}
+
+/// Fingerprints a host must present one of.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Pins {
+    host: String,
+    fingerprints: Vec<Fingerprint>,
+}
+
+/// Records certificate fingerprints per host and checks pins.
+///
+/// Clones share the records. Every fingerprint not recorded before is sent
+/// on `events`, including a host's first one, so the receiver can persist
+/// them; only a differing fingerprint is a change worth a warning.
+#[derive(Debug, Clone)]
+pub struct CertificateWatch {
+    known: Arc<Mutex<BTreeMap<String, Fingerprint>>>,
+    pins: Option<Arc<Pins>>,
+    events: UnboundedSender<Vec<CertificateChange>>,
+}
+
+impl CertificateWatch {
+    /// Creates a watch without recorded fingerprints or pins.
+    #[must_use]
+    pub fn new(events: UnboundedSender<Vec<CertificateChange>>) -> Self {
+        Self {
+            known: Arc::default(),
+            pins: None,
+            events,
+        }
+    }
+
+    /// Starts from fingerprints recorded earlier, e.g. by a previous run.
+    #[must_use]
+    pub fn with_known(mut self, known: BTreeMap<String, Fingerprint>) -> Self {
+        self.known = Arc::new(Mutex::new(known));
+        self
+    }
+
+    /// Requires `host` to present one of `fingerprints`.
+    ///
+    /// An empty list pins nothing.
+    #[must_use]
+    pub fn with_pins(mut self, host: impl Into<String>, fingerprints: Vec<Fingerprint>) -> Self {
+        self.pins = (!fingerprints.is_empty()).then(|| {
+            Arc::new(Pins {
+                host: host.into(),
+                fingerprints,
+            })
+        });
+        self
+    }
+
+    /// Returns the fingerprint recorded for `host`.
+    #[must_use]
+    pub fn fingerprint(&self, host: &str) -> Option<Fingerprint> {
+        self.known
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(host)
+            .copied()
+    }
+
+    /// Checks the certificate `host` presented, recording its fingerprint.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the mismatch if `host` is pinned and
+    /// `fingerprint` is not one of its pins; nothing is recorded then.
+    pub fn observe(&self, host: &str, fingerprint: Fingerprint) -> Result<(), String> {
+        if let Some(pins) = &self.pins {
+            if pins.host.eq_ignore_ascii_case(host) && !pins.fingerprints.contains(&fingerprint) {
+                return Err(format!(
+                    "{host} presented TLS certificate {fingerprint}, which is not pinned"
+                ));
+            }
+        }
+
+        let old = self
+            .known
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(host.to_string(), fingerprint);
+        if old == Some(fingerprint) {
+            return Ok(());
+        }
+        let change = CertificateChange {
+            host: host.to_string(),
+            old,
+            new: fingerprint,
+            timestamp: SystemTime::now(),
+        };
+        // A closed receiver means nobody listens any more; drop the change
+        let _ = self.events.send(vec![change]);
+        Ok(())
+    }
+}
+
+/// Verifier passing certificates accepted by `inner` to a [`CertificateWatch`].
+#[derive(Debug)]
+pub(super) struct WatchingVerifier {
+    inner: Arc<dyn ServerCertVerifier>,
+    watch: CertificateWatch,
+}
+
+impl WatchingVerifier {
+    pub(super) fn new(inner: Arc<dyn ServerCertVerifier>, watch: CertificateWatch) -> Self {
+        Self { inner, watch }
+    }
+}
+
+impl ServerCertVerifier for WatchingVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let verified = self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        )?;
+        let host = server_name.to_str();
+        self.watch
+            .observe(&host, Fingerprint::of(end_entity))
+            .map_err(|reason| {
+                tracing::error!("{reason}");
+                rustls::Error::General(reason)
+            })?;
+        Ok(verified)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
@@ -0,0 +1,206 @@
+//! Tests for certificate fingerprints and the certificate watch.
+
+use super::certificate::{CertificateWatch, Fingerprint, WatchingVerifier};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, SignatureScheme};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+const HEX: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+/// Verifier accepting every certificate.
+#[derive(Debug)]
+struct AcceptAll;
+
+impl ServerCertVerifier for AcceptAll {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        vec![SignatureScheme::ED25519]
+    }
+}
+
+mod fingerprint {
+    use super::*;
+
+    #[test]
+    fn is_sha256_of_der() {
+        assert_eq!(Fingerprint::of(b"test").to_string(), HEX);
+    }
+
+    #[test]
+    fn parses_plain_and_colon_separated_hex() {
+        let colons = HEX
+            .as_bytes()
+            .chunks(2)
+            .map(|pair| std::str::from_utf8(pair).unwrap().to_uppercase())
+            .collect::<Vec<_>>()
+            .join(":");
+
+        assert_eq!(
+            HEX.parse::<Fingerprint>().unwrap(),
+            Fingerprint::of(b"test")
+        );
+        assert_eq!(
+            colons.parse::<Fingerprint>().unwrap(),
+            Fingerprint::of(b"test")
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_length_and_non_hex() {
+        assert!("abcd".parse::<Fingerprint>().is_err());
+        assert!(HEX.replace('9', "z").parse::<Fingerprint>().is_err());
+    }
+
+    #[test]
+    fn serializes_as_hex_string() {
+        let json = serde_json::to_string(&Fingerprint::of(b"test")).unwrap();
+
+        assert_eq!(json, format!("\"{HEX}\""));
+        assert_eq!(
+            serde_json::from_str::<Fingerprint>(&json).unwrap(),
+            Fingerprint::of(b"test")
+        );
+    }
+}
+
+mod watch {
+    use super::*;
+
+    #[test]
+    fn first_fingerprint_is_recorded_without_old() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let watch = CertificateWatch::new(tx);
+
+        watch.observe("example.com", Fingerprint::of(b"a")).unwrap();
+
+        let changes = rx.try_recv().unwrap();
+        assert_eq!(changes[0].host, "example.com");
+        assert_eq!(changes[0].old, None);
+        assert_eq!(
+            watch.fingerprint("example.com"),
+            Some(Fingerprint::of(b"a"))
+        );
+    }
+
+    #[test]
+    fn same_fingerprint_is_not_reported() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let known = BTreeMap::from([("example.com".to_string(), Fingerprint::of(b"a"))]);
+        let watch = CertificateWatch::new(tx).with_known(known);
+
+        watch.observe("example.com", Fingerprint::of(b"a")).unwrap();
+
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn changed_fingerprint_is_reported() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let known = BTreeMap::from([("example.com".to_string(), Fingerprint::of(b"a"))]);
+        let watch = CertificateWatch::new(tx).with_known(known);
+
+        watch.observe("example.com", Fingerprint::of(b"b")).unwrap();
+
+        let change = rx.try_recv().unwrap().remove(0);
+        assert_eq!(change.old, Some(Fingerprint::of(b"a")));
+        assert_eq!(change.new, Fingerprint::of(b"b"));
+        assert!(change.to_string().contains("TLS certificate changed from"));
+    }
+
+    #[test]
+    fn unpinned_fingerprint_is_rejected_and_not_recorded() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let watch = CertificateWatch::new(tx).with_pins("example.com", vec![Fingerprint::of(b"a")]);
+
+        assert!(watch.observe("Example.com", Fingerprint::of(b"b")).is_err());
+        assert!(rx.try_recv().is_err());
+        assert_eq!(watch.fingerprint("Example.com"), None);
+
+        watch.observe("example.com", Fingerprint::of(b"a")).unwrap();
+    }
+
+    #[test]
+    fn pins_apply_only_to_their_host() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let watch = CertificateWatch::new(tx).with_pins("example.com", vec![Fingerprint::of(b"a")]);
+
+        watch
+            .observe("auth.example.com", Fingerprint::of(b"b"))
+            .unwrap();
+    }
+}
+
+mod verifier {
+    use super::*;
+
+    fn verify(
+        verifier: &WatchingVerifier,
+        der: &[u8],
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let name = ServerName::try_from("example.com").unwrap();
+        verifier.verify_server_cert(
+            &CertificateDer::from(der.to_vec()),
+            &[],
+            &name,
+            &[],
+            UnixTime::now(),
+        )
+    }
+
+    #[test]
+    fn records_leaf_of_verified_chain() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let watch = CertificateWatch::new(tx);
+        let verifier = WatchingVerifier::new(Arc::new(AcceptAll), watch.clone());
+
+        verify(&verifier, b"leaf").unwrap();
+
+        assert_eq!(
+            watch.fingerprint("example.com"),
+            Some(Fingerprint::of(b"leaf"))
+        );
+        assert!(rx.try_recv().is_ok());
+    }
+
+    #[test]
+    fn pin_mismatch_fails_handshake() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let watch = CertificateWatch::new(tx).with_pins("example.com", vec![Fingerprint::of(b"a")]);
+        let verifier = WatchingVerifier::new(Arc::new(AcceptAll), watch);
+
+        let result = verify(&verifier, b"leaf");
+
+        assert!(matches!(result, Err(rustls::Error::General(_))));
+    }
+}
@@ -0,0 +1,107 @@
+//! Identifying headers attached to every outbound request.
+//!
+//! Some providers reject requests without a descriptive `User-Agent` (or block
+//! generic HTTP library defaults). [`MetadataClient`] stamps each request with
+//! the configured user agent and, optionally, `X-DDNS-A-Version` /
+//! `X-DDNS-A-Host` headers so operators can tell which machine sent an update.
+
+use http::header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT};
+
+use crate::webhook::{HttpClient, HttpError, HttpRequest, HttpResponse};
+
+/// User agent sent when none is configured.
+pub const DEFAULT_USER_AGENT: &str = concat!("ddns-a/", env!("CARGO_PKG_VERSION"));
+
+/// Header carrying the ddns-a version.
+pub const VERSION_HEADER: HeaderName = HeaderName::from_static("x-ddns-a-version");
+
+/// Header carrying the hostname of the machine running ddns-a.
+pub const HOST_HEADER: HeaderName = HeaderName::from_static("x-ddns-a-host");
+
+/// Headers that identify this client to the receiving service.
+///
+/// Metadata headers never override headers already present on a request,
+/// so explicitly configured webhook headers (including `User-Agent`) win.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestMetadata {
+    headers: HeaderMap,
+}
+
+impl RequestMetadata {
+    /// Creates metadata containing only the given user agent.
+    #[must_use]
+    pub fn new(user_agent: HeaderValue) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, user_agent);
+        Self { headers }
+    }
+
+    /// Adds the `X-DDNS-A-Version` and `X-DDNS-A-Host` headers.
+    ///
+    /// Hostnames that are not valid header values (e.g. containing control
+    /// characters) are skipped with a warning rather than failing startup.
+    #[must_use]
+    pub fn with_identity(mut self, host: &str) -> Self {
+        self.headers.insert(
+            VERSION_HEADER,
+            HeaderValue::from_static(env!("CARGO_PKG_VERSION")),
+        );
+
+        if let Ok(value) = HeaderValue::from_str(host) {
+            self.headers.insert(HOST_HEADER, value);
+        } else {
+            tracing::warn!("Hostname {host:?} is not a valid header value, omitting");
+        }
+
+        self
+    }
+
+    /// Returns the headers that will be added to requests.
+    #[must_use]
+    pub const fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Inserts metadata headers that are not already present in `headers`.
+    pub fn apply(&self, headers: &mut HeaderMap) {
+        for (name, value) in &self.headers {
+            if !headers.contains_key(name) {
+                headers.insert(name, value.clone());
+            }
+        }
+    }
+}
+
+impl Default for RequestMetadata {
+    fn default() -> Self {
+        Self::new(HeaderValue::from_static(DEFAULT_USER_AGENT))
+    }
+}
+
+/// An [`HttpClient`] decorator that adds [`RequestMetadata`] headers to each request.
+#[derive(Debug)]
+pub struct MetadataClient<H> {
+    inner: H,
+    metadata: RequestMetadata,
+}
+
+impl<H> MetadataClient<H> {
+    /// Wraps `inner`, stamping its requests with `metadata`.
+    #[must_use]
+    pub const fn new(inner: H, metadata: RequestMetadata) -> Self {
+        Self { inner, metadata }
+    }
+
+    /// Returns the metadata added to requests.
+    #[must_use]
+    pub const fn metadata(&self) -> &RequestMetadata {
+        &self.metadata
+    }
+}
+
+impl<H: HttpClient> HttpClient for MetadataClient<H> {
+    async fn request(&self, mut req: HttpRequest) -> Result<HttpResponse, HttpError> {
+        self.metadata.apply(&mut req.headers);
+        self.inner.request(req).await
+    }
+}
@@ -0,0 +1,133 @@
+//! Tests for request metadata headers.
+
+use super::metadata::{
+    DEFAULT_USER_AGENT, HOST_HEADER, MetadataClient, RequestMetadata, VERSION_HEADER,
+};
+use crate::webhook::{HttpClient, HttpError, HttpRequest, HttpResponse};
+use http::header::{HeaderMap, HeaderValue, USER_AGENT};
+use std::sync::{Arc, Mutex};
+
+/// Client that records the request it receives; clones share the recording.
+#[derive(Clone, Default)]
+struct CapturingClient {
+    received: Arc<Mutex<Option<HttpRequest>>>,
+}
+
+impl CapturingClient {
+    fn received(&self) -> HttpRequest {
+        self.received.lock().unwrap().clone().expect("request sent")
+    }
+}
+
+impl HttpClient for CapturingClient {
+    async fn request(&self, req: HttpRequest) -> Result<HttpResponse, HttpError> {
+        *self.received.lock().unwrap() = Some(req);
+        Ok(HttpResponse::new(
+            http::StatusCode::OK,
+            HeaderMap::new(),
+            vec![],
+        ))
+    }
+}
+
+fn request() -> HttpRequest {
+    HttpRequest::post(url::Url::parse("https://example.com/hook").unwrap())
+}
+
+mod request_metadata {
+    use super::*;
+
+    #[test]
+    fn default_user_agent_identifies_ddns_a() {
+        let metadata = RequestMetadata::default();
+
+        assert_eq!(metadata.headers()[USER_AGENT], DEFAULT_USER_AGENT);
+        assert!(DEFAULT_USER_AGENT.starts_with("ddns-a/"));
+    }
+
+    #[test]
+    fn default_has_no_identity_headers() {
+        let metadata = RequestMetadata::default();
+
+        assert!(!metadata.headers().contains_key(VERSION_HEADER));
+        assert!(!metadata.headers().contains_key(HOST_HEADER));
+    }
+
+    #[test]
+    fn custom_user_agent() {
+        let metadata = RequestMetadata::new(HeaderValue::from_static("my-router/1.0"));
+
+        assert_eq!(metadata.headers()[USER_AGENT], "my-router/1.0");
+    }
+
+    #[test]
+    fn with_identity_adds_version_and_host() {
+        let metadata = RequestMetadata::default().with_identity("office-pc");
+
+        assert_eq!(
+            metadata.headers()[VERSION_HEADER],
+            env!("CARGO_PKG_VERSION")
+        );
+        assert_eq!(metadata.headers()[HOST_HEADER], "office-pc");
+    }
+
+    #[test]
+    fn invalid_hostname_is_omitted() {
+        let metadata = RequestMetadata::default().with_identity("bad\nhost");
+
+        assert!(metadata.headers().contains_key(VERSION_HEADER));
+        assert!(!metadata.headers().contains_key(HOST_HEADER));
+    }
+
+    #[test]
+    fn apply_does_not_override_existing_headers() {
+        let metadata = RequestMetadata::default();
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static("explicit"));
+
+        metadata.apply(&mut headers);
+
+        assert_eq!(headers[USER_AGENT], "explicit");
+        assert_eq!(headers.get_all(USER_AGENT).iter().count(), 1);
+    }
+}
+
+mod metadata_client {
+    use super::*;
+
+    #[tokio::test]
+    async fn adds_metadata_headers_to_request() {
+        let inner = CapturingClient::default();
+        let client = MetadataClient::new(
+            inner.clone(),
+            RequestMetadata::default().with_identity("office-pc"),
+        );
+
+        client.request(request()).await.unwrap();
+
+        let received = inner.received();
+        assert_eq!(received.headers[USER_AGENT], DEFAULT_USER_AGENT);
+        assert_eq!(received.headers[HOST_HEADER], "office-pc");
+    }
+
+    #[tokio::test]
+    async fn keeps_request_user_agent() {
+        let inner = CapturingClient::default();
+        let client = MetadataClient::new(inner.clone(), RequestMetadata::default());
+
+        client
+            .request(request().with_header(USER_AGENT, HeaderValue::from_static("explicit")))
+            .await
+            .unwrap();
+
+        assert_eq!(inner.received().headers[USER_AGENT], "explicit");
+    }
+
+    #[test]
+    fn metadata_accessor() {
+        let metadata = RequestMetadata::default().with_identity("office-pc");
+        let client = MetadataClient::new(CapturingClient::default(), metadata.clone());
+
+        assert_eq!(client.metadata(), &metadata);
+    }
+}
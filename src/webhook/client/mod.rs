@@ -0,0 +1,292 @@
+//! Production HTTP client implementation using reqwest.
+//!
+//! The submodules configure how it connects: TLS roots and identities
+//! ([`TlsSettings`]), certificate fingerprints ([`CertificateWatch`]) and
+//! nameservers ([`NameserverResolver`]), and wrap it with audit records
+//! ([`AuditedClient`]) and identifying headers ([`MetadataClient`]).
+
+use std::net::IpAddr;
+use std::time::Duration;
+
+use super::{HttpClient, HttpError, HttpRequest, HttpResponse};
+
+mod audit;
+mod certificate;
+mod metadata;
+mod resolve;
+mod tls;
+
+#[cfg(test)]
+mod audit_tests;
+#[cfg(test)]
+mod certificate_tests;
+#[cfg(test)]
+mod metadata_tests;
+#[cfg(test)]
+mod resolve_tests;
+#[cfg(test)]
+mod tls_tests;
+
+pub use audit::{
+    AuditConfig, AuditOutcome, AuditRecord, AuditSink, AuditedClient, FileAuditSink, REDACTED,
+    Redaction,
+};
+pub use certificate::{CertificateChange, CertificateWatch, Fingerprint};
+pub use metadata::{
+    DEFAULT_USER_AGENT, HOST_HEADER, MetadataClient, RequestMetadata, VERSION_HEADER,
+};
+pub use resolve::NameserverResolver;
+pub use tls::{ClientIdentity, TlsSettings, tls_config};
+
+/// Default time allowed to establish a connection.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default time allowed for a whole request, from connecting to reading the
+/// last byte of the response.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Default time an unused pooled connection is kept open.
+pub const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Default number of unused connections kept open per host.
+pub const DEFAULT_POOL_MAX_IDLE_PER_HOST: usize = 8;
+
+/// Whether connections can be bound to a network interface by name on this
+/// platform ([`ConnectionSettings::interface`]).
+pub const INTERFACE_BINDING_SUPPORTED: bool = cfg!(any(
+    target_os = "android",
+    target_os = "fuchsia",
+    target_os = "illumos",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "solaris",
+    target_os = "tvos",
+    target_os = "visionos",
+    target_os = "watchos",
+));
+
+/// Timeouts, connection pool limits and source binding of a
+/// [`ReqwestClient`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionSettings {
+    /// Time allowed to establish a connection, TLS handshake included
+    pub connect_timeout: Duration,
+    /// Time allowed for a whole request; a request exceeding it fails with
+    /// [`HttpError::Timeout`]
+    pub request_timeout: Duration,
+    /// Time an unused pooled connection is kept open
+    pub pool_idle_timeout: Duration,
+    /// Unused connections kept open per host (0 disables pooling)
+    pub pool_max_idle_per_host: usize,
+    /// Local address connections are made from; an unspecified address
+    /// (`0.0.0.0` or `::`) only pins the address family
+    pub local_address: Option<IpAddr>,
+    /// Network interface connections go out through, where
+    /// [supported](INTERFACE_BINDING_SUPPORTED)
+    pub interface: Option<String>,
+}
+
+impl Default for ConnectionSettings {
+    fn default() -> Self {
+        Self {
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            pool_idle_timeout: DEFAULT_POOL_IDLE_TIMEOUT,
+            pool_max_idle_per_host: DEFAULT_POOL_MAX_IDLE_PER_HOST,
+            local_address: None,
+            interface: None,
+        }
+    }
+}
+
+/// Production HTTP client using reqwest.
+///
+/// This is a thin wrapper around `reqwest::Client` that implements
+/// the [`HttpClient`] trait. [`ReqwestClient::new`] inherits reqwest's
+/// default configuration, which has no timeouts; [`ReqwestClient::with_options`]
+/// applies [`ConnectionSettings`].
+///
+/// # Example
+///
+/// ```no_run
+/// use ddns_a::webhook::{ReqwestClient, HttpClient, HttpRequest};
+/// use url::Url;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = ReqwestClient::new();
+/// let url = Url::parse("https://api.example.com/webhook")?;
+/// let request = HttpRequest::post(url).with_body(b"hello".to_vec());
+/// let response = client.request(request).await?;
+/// println!("Status: {}", response.status);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReqwestClient {
+    inner: reqwest::Client,
+}
+
+impl ReqwestClient {
+    /// Creates a new HTTP client with default configuration.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            inner: reqwest::Client::new(),
+        }
+    }
+
+    /// Creates an HTTP client resolving hosts with `resolver` instead of
+    /// the system resolver, using the `tls` and `connection` settings, and
+    /// passing server certificates to `certificates`, if given.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the TLS backend cannot be initialized, like
+    /// [`reqwest::Client::new`], or a file of `tls` became unusable since
+    /// it was [checked](TlsSettings::check).
+    #[must_use]
+    pub fn with_options(
+        resolver: Option<NameserverResolver>,
+        tls: &TlsSettings,
+        connection: &ConnectionSettings,
+        certificates: Option<CertificateWatch>,
+    ) -> Self {
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(connection.connect_timeout)
+            .timeout(connection.request_timeout)
+            .pool_idle_timeout(connection.pool_idle_timeout)
+            .pool_max_idle_per_host(connection.pool_max_idle_per_host)
+            .local_address(connection.local_address);
+        if let Some(interface) = &connection.interface {
+            builder = bind_interface(builder, interface);
+        }
+        if let Some(resolver) = resolver {
+            builder = builder.dns_resolver(resolver);
+        }
+        if certificates.is_some() || !tls.is_default() {
+            let tls = tls_config(tls, certificates).expect("Failed to initialize TLS");
+            builder = builder.tls_backend_preconfigured(tls);
+        }
+        let client = builder
+            .build()
+            .expect("Failed to initialize the HTTP client");
+        Self { inner: client }
+    }
+
+    /// Creates an HTTP client from an existing reqwest client.
+    ///
+    /// Useful when you need custom configuration (timeouts, TLS, etc.).
+    #[must_use]
+    pub const fn from_client(client: reqwest::Client) -> Self {
+        Self { inner: client }
+    }
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "fuchsia",
+    target_os = "illumos",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "solaris",
+    target_os = "tvos",
+    target_os = "visionos",
+    target_os = "watchos",
+))]
+fn bind_interface(builder: reqwest::ClientBuilder, interface: &str) -> reqwest::ClientBuilder {
+    builder.interface(interface)
+}
+
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "fuchsia",
+    target_os = "illumos",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "solaris",
+    target_os = "tvos",
+    target_os = "visionos",
+    target_os = "watchos",
+)))]
+fn bind_interface(builder: reqwest::ClientBuilder, interface: &str) -> reqwest::ClientBuilder {
+    tracing::warn!("Binding to interface {interface} is not supported on this platform");
+    builder
+}
+
+impl Default for ReqwestClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpClient for ReqwestClient {
+    async fn request(&self, req: HttpRequest) -> Result<HttpResponse, HttpError> {
+        // Build the reqwest request
+        let mut builder = self.inner.request(req.method, req.url.as_str());
+
+        // Add headers
+        for (name, value) in &req.headers {
+            builder = builder.header(name, value);
+        }
+
+        // Add body if present
+        if let Some(body) = req.body {
+            builder = builder.body(body);
+        }
+
+        // Send the request
+        let response = builder.send().await.map_err(|e| {
+            if e.is_timeout() {
+                HttpError::Timeout
+            } else if e.is_builder() {
+                HttpError::InvalidUrl(e.to_string())
+            } else {
+                HttpError::Connection(Box::new(e))
+            }
+        })?;
+
+        // Extract response parts
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = read_body(response, req.max_response_len).await?;
+
+        Ok(HttpResponse::new(status, headers, body))
+    }
+}
+
+/// Reads a response body, failing once it grows past `limit` bytes.
+async fn read_body(
+    mut response: reqwest::Response,
+    limit: Option<usize>,
+) -> Result<Vec<u8>, HttpError> {
+    let Some(limit) = limit else {
+        return Ok(response
+            .bytes()
+            .await
+            .map_err(|e| HttpError::Connection(Box::new(e)))?
+            .to_vec());
+    };
+    // A declared length over the limit fails before anything is read
+    if response
+        .content_length()
+        .is_some_and(|len| len > limit as u64)
+    {
+        return Err(HttpError::ResponseTooLarge { limit });
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| HttpError::Connection(Box::new(e)))?
+    {
+        if body.len() + chunk.len() > limit {
+            return Err(HttpError::ResponseTooLarge { limit });
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
@@ -0,0 +1,73 @@
+//! Host resolution through configured nameservers.
+//!
+//! A local resolver that is still catching up after a reconnect can fail
+//! the very lookup an address change triggers. [`NameserverResolver`]
+//! resolves outbound hosts with a [`NameserverList`] instead, both for the
+//! connections of [`ReqwestClient`](crate::webhook::ReqwestClient) and for the
+//! connectivity probe.
+
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+
+use crate::dns::{DnsResolver, NameserverList};
+use crate::webhook::HostResolver;
+
+/// Resolves host names with the `[http] dns_servers`.
+#[derive(Debug, Clone)]
+pub struct NameserverResolver {
+    servers: Arc<NameserverList>,
+}
+
+impl NameserverResolver {
+    /// Creates a resolver asking `servers` in order.
+    #[must_use]
+    pub fn new(servers: NameserverList) -> Self {
+        Self {
+            servers: Arc::new(servers),
+        }
+    }
+
+    /// Returns the nameservers.
+    #[must_use]
+    pub fn servers(&self) -> &NameserverList {
+        &self.servers
+    }
+
+    /// Returns the addresses of `host`, failing if it has none.
+    async fn lookup(&self, host: &str) -> io::Result<Vec<IpAddr>> {
+        let addresses = self
+            .servers
+            .lookup_ip(host)
+            .await
+            .map_err(io::Error::other)?;
+        if addresses.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no A or AAAA records for {host}"),
+            ));
+        }
+        Ok(addresses)
+    }
+}
+
+impl Resolve for NameserverResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.clone();
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addresses = resolver.lookup(&host).await?;
+            // Port 0 is replaced by the URL's port
+            let addrs: Addrs = Box::new(addresses.into_iter().map(|ip| SocketAddr::new(ip, 0)));
+            Ok(addrs)
+        })
+    }
+}
+
+impl HostResolver for NameserverResolver {
+    async fn resolve(&self, host: &str, _port: u16) -> io::Result<Vec<IpAddr>> {
+        self.lookup(host).await
+    }
+}
@@ -0,0 +1,73 @@
+//! Tests for resolving hosts through configured nameservers.
+
+use std::net::{IpAddr, SocketAddr};
+
+use reqwest::dns::Resolve;
+use tokio::net::UdpSocket;
+
+use crate::dns::NameserverList;
+use crate::webhook::{HostResolver, NameserverResolver};
+
+/// Spawns a nameserver answering A queries with `v4` (no records if
+/// `None`) and AAAA queries without records.
+async fn nameserver(v4: Option<[u8; 4]>) -> SocketAddr {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = socket.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let mut buf = [0u8; 512];
+        loop {
+            let (len, peer) = socket.recv_from(&mut buf).await.unwrap();
+            let qtype = u16::from_be_bytes([buf[len - 4], buf[len - 3]]);
+
+            let mut reply = buf[..len].to_vec();
+            reply[2..4].copy_from_slice(&0x8180u16.to_be_bytes());
+            if let (1, Some(octets)) = (qtype, v4) {
+                reply[6..8].copy_from_slice(&1u16.to_be_bytes());
+                reply.extend_from_slice(&[0xc0, 12, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4]);
+                reply.extend_from_slice(&octets);
+            }
+            socket.send_to(&reply, peer).await.unwrap();
+        }
+    });
+
+    addr
+}
+
+async fn resolver(v4: Option<[u8; 4]>) -> NameserverResolver {
+    NameserverResolver::new(NameserverList::new([nameserver(v4).await]))
+}
+
+#[tokio::test]
+async fn resolves_host_for_probe() {
+    let resolver = resolver(Some([192, 0, 2, 1])).await;
+
+    let addresses = HostResolver::resolve(&resolver, "hooks.example.com", 443)
+        .await
+        .unwrap();
+
+    assert_eq!(addresses, ["192.0.2.1".parse::<IpAddr>().unwrap()]);
+}
+
+#[tokio::test]
+async fn host_without_records_is_error() {
+    let resolver = resolver(None).await;
+
+    let error = HostResolver::resolve(&resolver, "missing.example.com", 443)
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[tokio::test]
+async fn resolves_host_for_reqwest() {
+    let resolver = resolver(Some([192, 0, 2, 1])).await;
+
+    let addrs: Vec<SocketAddr> = Resolve::resolve(&resolver, "hooks.example.com".parse().unwrap())
+        .await
+        .unwrap()
+        .collect();
+
+    assert_eq!(addrs, ["192.0.2.1:0".parse::<SocketAddr>().unwrap()]);
+}
@@ -0,0 +1,184 @@
+//! Custom TLS settings of the HTTP client (`[http.tls]`).
+//!
+//! By default the client trusts the platform's root certificates. A
+//! [`TlsSettings`] adds the roots of a private CA, presents a client
+//! certificate to endpoints requiring mutual TLS, or, for lab setups only,
+//! accepts any server certificate. [`tls_config`] builds the rustls
+//! configuration from them, with a [`CertificateWatch`] checking the
+//! certificates the verifier accepted.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{CryptoProvider, verify_tls12_signature, verify_tls13_signature};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, SignatureScheme};
+
+use super::certificate::WatchingVerifier;
+use crate::webhook::CertificateWatch;
+
+/// Client certificate and private key presented for mutual TLS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientIdentity {
+    /// PEM file with the certificate chain, leaf first
+    pub cert_file: PathBuf,
+    /// PEM file with the private key (PKCS#8, PKCS#1 or SEC1)
+    pub key_file: PathBuf,
+}
+
+/// TLS settings of the HTTP client; the default trusts the platform roots
+/// and presents no client certificate.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TlsSettings {
+    /// PEM file with root certificates trusted besides the platform's
+    pub ca_file: Option<PathBuf>,
+    /// Client certificate for mutual TLS
+    pub client_identity: Option<ClientIdentity>,
+    /// Accept any server certificate (lab environments only)
+    pub accept_invalid_certs: bool,
+}
+
+impl TlsSettings {
+    /// Returns true if the settings leave reqwest's TLS defaults unchanged.
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Reads the configured files, so unusable ones are reported before
+    /// the client is built.
+    ///
+    /// # Errors
+    ///
+    /// Returns the reason if a file cannot be read or holds no usable
+    /// certificate or key.
+    pub fn check(&self) -> Result<(), String> {
+        self.roots()?;
+        self.identity()?;
+        Ok(())
+    }
+
+    fn roots(&self) -> Result<Vec<CertificateDer<'static>>, String> {
+        self.ca_file.as_deref().map_or(Ok(Vec::new()), read_certs)
+    }
+
+    fn identity(
+        &self,
+    ) -> Result<Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>, String> {
+        let Some(identity) = &self.client_identity else {
+            return Ok(None);
+        };
+        let certs = read_certs(&identity.cert_file)?;
+        let key = PrivateKeyDer::from_pem_file(&identity.key_file).map_err(|e| {
+            format!(
+                "no private key read from '{}': {e}",
+                identity.key_file.display()
+            )
+        })?;
+        Ok(Some((certs, key)))
+    }
+}
+
+/// Reads the PEM certificates of `path`, failing if there are none.
+fn read_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, String> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .and_then(Iterator::collect::<Result<Vec<_>, _>>)
+        .map_err(|e| format!("cannot read certificates from '{}': {e}", path.display()))?;
+    if certs.is_empty() {
+        return Err(format!("no certificate found in '{}'", path.display()));
+    }
+    Ok(certs)
+}
+
+/// Builds a TLS configuration like reqwest's default (platform roots,
+/// HTTP/2 and HTTP/1.1) adjusted by `settings`, whose accepted server
+/// certificates go through `watch`, if given.
+///
+/// # Errors
+///
+/// Returns an error if the platform verifier cannot be initialized or a
+/// file of `settings` is unusable.
+pub fn tls_config(
+    settings: &TlsSettings,
+    watch: Option<CertificateWatch>,
+) -> Result<ClientConfig, rustls::Error> {
+    let provider = CryptoProvider::get_default().map_or_else(
+        || Arc::new(rustls::crypto::aws_lc_rs::default_provider()),
+        Arc::clone,
+    );
+    let mut verifier: Arc<dyn ServerCertVerifier> = if settings.accept_invalid_certs {
+        Arc::new(AcceptAnyCertificate(Arc::clone(&provider)))
+    } else {
+        let roots = settings.roots().map_err(rustls::Error::General)?;
+        Arc::new(rustls_platform_verifier::Verifier::new_with_extra_roots(
+            roots,
+            Arc::clone(&provider),
+        )?)
+    };
+    if let Some(watch) = watch {
+        verifier = Arc::new(WatchingVerifier::new(verifier, watch));
+    }
+
+    let builder = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()?
+        .dangerous()
+        .with_custom_certificate_verifier(verifier);
+    let mut config = match settings.identity().map_err(rustls::Error::General)? {
+        Some((certs, key)) => builder.with_client_auth_cert(certs, key)?,
+        None => builder.with_no_client_auth(),
+    };
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+/// Verifier accepting any server certificate, for
+/// `danger_accept_invalid_certs`; handshake signatures are still checked.
+#[derive(Debug)]
+struct AcceptAnyCertificate(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
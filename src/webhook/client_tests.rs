@@ -38,6 +38,32 @@ mod reqwest_client {
         let _ = format!("{client:?}");
     }
 
+    #[test]
+    fn with_resolver_creates_client() {
+        let servers = crate::dns::NameserverList::new(["192.0.2.53:53".parse().unwrap()]);
+        let client = ReqwestClient::with_options(
+            Some(NameserverResolver::new(servers)),
+            &TlsSettings::default(),
+            &ConnectionSettings::default(),
+            None,
+        );
+
+        let _ = format!("{client:?}");
+    }
+
+    #[test]
+    fn with_certificate_watch_creates_client() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let client = ReqwestClient::with_options(
+            None,
+            &TlsSettings::default(),
+            &ConnectionSettings::default(),
+            Some(CertificateWatch::new(tx)),
+        );
+
+        let _ = format!("{client:?}");
+    }
+
     #[test]
     fn clone_creates_independent_client() {
         let client1 = ReqwestClient::new();
@@ -70,6 +96,104 @@ mod reqwest_client {
     // the client can be constructed. The actual HTTP behavior is
     // tested via the reqwest library's own tests.
 
+    // Linux routes all of 127.0.0.0/8 to the loopback interface
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn local_address_is_the_source_of_connections() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move { listener.accept().await.unwrap().1 });
+        let connection = ConnectionSettings {
+            local_address: Some("127.0.0.2".parse().unwrap()),
+            ..ConnectionSettings::default()
+        };
+        let client = ReqwestClient::with_options(None, &TlsSettings::default(), &connection, None);
+        let url = url::Url::parse(&format!("http://{addr}/")).unwrap();
+
+        // The server never answers; only the connection matters
+        let _ = tokio::time::timeout(
+            std::time::Duration::from_millis(200),
+            client.request(HttpRequest::get(url)),
+        )
+        .await;
+
+        let peer = server.await.unwrap();
+        assert_eq!(peer.ip(), connection.local_address.unwrap());
+    }
+
+    #[tokio::test]
+    async fn silent_server_hits_the_request_timeout() {
+        // Accepts connections but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (_stream, _) = listener.accept().await.unwrap();
+            std::future::pending::<()>().await;
+        });
+        let connection = ConnectionSettings {
+            request_timeout: std::time::Duration::from_millis(100),
+            ..ConnectionSettings::default()
+        };
+        let client = ReqwestClient::with_options(None, &TlsSettings::default(), &connection, None);
+        let url = url::Url::parse(&format!("http://{addr}/")).unwrap();
+
+        let result = client.request(HttpRequest::get(url)).await;
+
+        assert!(matches!(result, Err(HttpError::Timeout)), "{result:?}");
+        server.abort();
+    }
+
+    /// Serves one response with `body`, declaring its length or not.
+    async fn serve_once(body: &'static [u8], declare_length: bool) -> url::Url {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let _ = stream.read(&mut [0; 1024]).await;
+            let head = if declare_length {
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                )
+            } else {
+                "HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n".to_string()
+            };
+            let _ = stream.write_all(head.as_bytes()).await;
+            let _ = stream.write_all(body).await;
+        });
+        url::Url::parse(&format!("http://{addr}/")).unwrap()
+    }
+
+    #[tokio::test]
+    async fn response_within_limit_is_read() {
+        let url = serve_once(b"0123456789", false).await;
+
+        let response = ReqwestClient::new()
+            .request(HttpRequest::get(url).with_max_response_len(10))
+            .await
+            .unwrap();
+
+        assert_eq!(response.body, b"0123456789");
+    }
+
+    #[tokio::test]
+    async fn response_over_limit_fails() {
+        for declare_length in [true, false] {
+            let url = serve_once(b"0123456789", declare_length).await;
+
+            let result = ReqwestClient::new()
+                .request(HttpRequest::get(url).with_max_response_len(9))
+                .await;
+
+            assert!(
+                matches!(result, Err(HttpError::ResponseTooLarge { limit: 9 })),
+                "declare_length={declare_length}: {result:?}"
+            );
+        }
+    }
+
     #[tokio::test]
     async fn request_to_invalid_host_returns_error_or_proxy_response() {
         let client = ReqwestClient::new();
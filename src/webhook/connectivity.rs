@@ -0,0 +1,312 @@
+//! Address-family connectivity checks ahead of webhook delivery.
+//!
+//! On hosts that temporarily lack connectivity in the monitored address
+//! family (e.g. `ip_version = ipv6` while no global IPv6 address is assigned),
+//! webhook attempts can only fail and burn through retries. [`ConnectivityGate`]
+//! consults a [`ConnectivityProbe`] before each delivery and postpones changes
+//! until the probe reports the webhook host reachable again.
+
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::Mutex;
+
+use url::{Host, Url};
+
+use crate::monitor::IpChange;
+use crate::network::{AddressFetcher, IpVersion};
+
+use super::{Event, WebhookError, WebhookSender};
+
+/// Whether the webhook host can currently be reached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reachability {
+    /// Delivery can be attempted.
+    Reachable,
+    /// Delivery cannot succeed right now, with a human-readable reason.
+    Unreachable(String),
+}
+
+impl fmt::Display for Reachability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Reachable => write!(f, "reachable"),
+            Self::Unreachable(reason) => write!(f, "unreachable: {reason}"),
+        }
+    }
+}
+
+/// Checks whether webhook delivery can currently succeed.
+pub trait ConnectivityProbe: Send + Sync {
+    /// Probes current connectivity to the webhook host.
+    fn probe(&self) -> impl Future<Output = Reachability> + Send;
+}
+
+/// `None` disables probing, allowing the gate to be applied unconditionally.
+impl<P: ConnectivityProbe> ConnectivityProbe for Option<P> {
+    async fn probe(&self) -> Reachability {
+        match self {
+            Some(probe) => probe.probe().await,
+            None => Reachability::Reachable,
+        }
+    }
+}
+
+/// Combines two probes: the first that reports unreachable wins, and the
+/// second is only consulted while the first reports reachable.
+impl<A: ConnectivityProbe, B: ConnectivityProbe> ConnectivityProbe for (A, B) {
+    async fn probe(&self) -> Reachability {
+        match self.0.probe().await {
+            Reachability::Reachable => self.1.probe().await,
+            unreachable @ Reachability::Unreachable(_) => unreachable,
+        }
+    }
+}
+
+/// Resolves host names to IP addresses.
+pub trait HostResolver: Send + Sync {
+    /// Resolves `host` to all of its addresses.
+    fn resolve(
+        &self,
+        host: &str,
+        port: u16,
+    ) -> impl Future<Output = io::Result<Vec<IpAddr>>> + Send;
+}
+
+/// [`HostResolver`] backed by the operating system resolver.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemResolver;
+
+impl HostResolver for SystemResolver {
+    async fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<IpAddr>> {
+        let addrs = tokio::net::lookup_host((host, port)).await?;
+        Ok(addrs.map(|addr| addr.ip()).collect())
+    }
+}
+
+/// Uses the system resolver when `None`.
+impl<R: HostResolver> HostResolver for Option<R> {
+    async fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<IpAddr>> {
+        match self {
+            Some(resolver) => resolver.resolve(host, port).await,
+            None => SystemResolver.resolve(host, port).await,
+        }
+    }
+}
+
+/// Connectivity setting resolved from configuration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConnectivityCheck {
+    /// Always attempt delivery.
+    #[default]
+    Disabled,
+    /// Postpone delivery while the monitored address family cannot reach the webhook host.
+    AddressFamily,
+}
+
+/// Probe requiring a shared address family between this host and the webhook host.
+///
+/// A family is usable when this host has a routable address in it and the
+/// webhook host resolves to an address in it. With [`IpVersion::Both`], either
+/// family suffices.
+///
+/// Probe failures (adapter enumeration or DNS errors) report [`Reachability::Reachable`]
+/// so that delivery proceeds and the HTTP layer reports the actual error.
+#[derive(Debug)]
+pub struct FamilyProbe<F, R = SystemResolver> {
+    fetcher: F,
+    resolver: R,
+    url: Url,
+    version: IpVersion,
+}
+
+impl<F> FamilyProbe<F, SystemResolver> {
+    /// Creates a probe for `url` using the system resolver.
+    #[must_use]
+    pub const fn new(fetcher: F, url: Url, version: IpVersion) -> Self {
+        Self::with_resolver(fetcher, SystemResolver, url, version)
+    }
+}
+
+impl<F, R> FamilyProbe<F, R> {
+    /// Creates a probe with a custom host resolver.
+    #[must_use]
+    pub const fn with_resolver(fetcher: F, resolver: R, url: Url, version: IpVersion) -> Self {
+        Self {
+            fetcher,
+            resolver,
+            url,
+            version,
+        }
+    }
+}
+
+impl<F: AddressFetcher, R: HostResolver> FamilyProbe<F, R> {
+    /// Returns which families this host has routable addresses in, as (v4, v6).
+    fn local_families(&self) -> Option<(bool, bool)> {
+        let adapters = match self.fetcher.fetch() {
+            Ok(adapters) => adapters,
+            Err(e) => {
+                tracing::debug!("Connectivity probe could not list adapters: {e}");
+                return None;
+            }
+        };
+
+        let usable = adapters.iter().filter(|a| !a.kind.is_loopback());
+        let mut v4 = false;
+        let mut v6 = false;
+        for adapter in usable {
+            v4 |= adapter.ipv4_addresses.iter().copied().any(is_routable_v4);
+            v6 |= adapter.ipv6_addresses.iter().copied().any(is_global_v6);
+        }
+
+        Some((v4, v6))
+    }
+
+    /// Returns which families the webhook host has addresses in, as (v4, v6).
+    async fn remote_families(&self) -> Option<(bool, bool)> {
+        let addrs = match self.url.host()? {
+            Host::Ipv4(_) => return Some((true, false)),
+            Host::Ipv6(_) => return Some((false, true)),
+            Host::Domain(domain) => {
+                let port = self.url.port_or_known_default().unwrap_or(443);
+                match self.resolver.resolve(domain, port).await {
+                    Ok(addrs) => addrs,
+                    Err(e) => {
+                        tracing::debug!("Connectivity probe could not resolve {domain}: {e}");
+                        return None;
+                    }
+                }
+            }
+        };
+
+        Some((
+            addrs.iter().any(IpAddr::is_ipv4),
+            addrs.iter().any(IpAddr::is_ipv6),
+        ))
+    }
+}
+
+impl<F: AddressFetcher, R: HostResolver> ConnectivityProbe for FamilyProbe<F, R> {
+    async fn probe(&self) -> Reachability {
+        let Some((local_v4, local_v6)) = self.local_families() else {
+            return Reachability::Reachable;
+        };
+        let Some((remote_v4, remote_v6)) = self.remote_families().await else {
+            return Reachability::Reachable;
+        };
+
+        let v4_ok = self.version.includes_v4() && local_v4 && remote_v4;
+        let v6_ok = self.version.includes_v6() && local_v6 && remote_v6;
+        if v4_ok || v6_ok {
+            return Reachability::Reachable;
+        }
+
+        let host = self.url.host_str().unwrap_or_default();
+        let reason = match self.version {
+            IpVersion::V4 if !local_v4 => "no routable IPv4 address on this host".to_string(),
+            IpVersion::V6 if !local_v6 => "no global IPv6 address on this host".to_string(),
+            IpVersion::V4 | IpVersion::V6 => {
+                format!("webhook host {host} has no {} address", self.version)
+            }
+            IpVersion::Both => format!("no address family shared with webhook host {host}"),
+        };
+        Reachability::Unreachable(reason)
+    }
+}
+
+/// Returns true for IPv4 addresses that can carry traffic off-link.
+///
+/// Private ranges count as routable since they are typically translated by a NAT.
+const fn is_routable_v4(addr: Ipv4Addr) -> bool {
+    !addr.is_loopback() && !addr.is_link_local() && !addr.is_unspecified()
+}
+
+/// Returns true for IPv6 addresses with global scope.
+const fn is_global_v6(addr: Ipv6Addr) -> bool {
+    !addr.is_loopback()
+        && !addr.is_unspecified()
+        && !addr.is_unicast_link_local()
+        && !addr.is_unique_local()
+}
+
+/// A [`WebhookSender`] decorator that postpones delivery while unreachable.
+///
+/// Postponed changes are kept in order and delivered together with the next
+/// batch sent once the probe reports the host reachable. Connectivity loss
+/// usually coincides with address changes, so restoration tends to trigger
+/// that next send on its own.
+#[derive(Debug)]
+pub struct ConnectivityGate<W, P> {
+    inner: W,
+    probe: P,
+    pending: Mutex<Vec<IpChange>>,
+}
+
+impl<W, P> ConnectivityGate<W, P> {
+    /// Wraps `inner`, consulting `probe` before each delivery.
+    #[must_use]
+    pub const fn new(inner: W, probe: P) -> Self {
+        Self {
+            inner,
+            probe,
+            pending: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns a reference to the wrapped sender.
+    pub const fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns the number of changes awaiting delivery.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pending-changes mutex is poisoned.
+    #[must_use]
+    pub fn pending_count(&self) -> usize {
+        self.pending.lock().expect("pending mutex poisoned").len()
+    }
+}
+
+impl<W: WebhookSender, P: ConnectivityProbe> WebhookSender for ConnectivityGate<W, P> {
+    async fn send(&self, changes: &[IpChange]) -> Result<(), WebhookError> {
+        let reachability = self.probe.probe().await;
+
+        let batch = {
+            let mut pending = self.pending.lock().expect("pending mutex poisoned");
+
+            if let Reachability::Unreachable(reason) = reachability {
+                pending.extend_from_slice(changes);
+                return Err(WebhookError::Postponed {
+                    pending: pending.len(),
+                    reason,
+                });
+            }
+
+            std::mem::take(&mut *pending)
+        };
+
+        if batch.is_empty() {
+            return self.inner.send(changes).await;
+        }
+
+        tracing::info!(
+            "Connectivity restored, delivering {} postponed change(s)",
+            batch.len()
+        );
+        let mut batch = batch;
+        batch.extend_from_slice(changes);
+        self.inner.send(&batch).await
+    }
+
+    /// Events are never postponed: DNS settings describe the current setup,
+    /// which a later change would supersede anyway, and receivers should
+    /// learn of drift, renames, storms and adapters going down even if the
+    /// address changes cannot be delivered yet.
+    async fn send_event(&self, event: &Event<'_>) -> Result<(), WebhookError> {
+        self.inner.send_event(event).await
+    }
+}
@@ -0,0 +1,375 @@
+//! Tests for connectivity probing and delivery postponement.
+
+use super::connectivity::{
+    ConnectivityGate, ConnectivityProbe, FamilyProbe, HostResolver, Reachability,
+};
+use super::{Event, WebhookError, WebhookSender};
+use crate::monitor::{
+    AddressDrift, AddressStorm, DnsChange, DriftChange, DriftKind, HostnameChange, IpChange,
+    StormChange,
+};
+use crate::network::DnsSettings;
+use crate::network::{AdapterKind, AdapterSnapshot, AddressFetcher, FetchError, IpVersion};
+use std::collections::VecDeque;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Fetcher returning a fixed adapter list, or an error when `None`.
+struct StaticFetcher(Option<Vec<AdapterSnapshot>>);
+
+impl AddressFetcher for StaticFetcher {
+    fn fetch(&self) -> Result<Vec<AdapterSnapshot>, FetchError> {
+        self.0.clone().ok_or_else(|| FetchError::Platform {
+            message: "unavailable".to_string(),
+        })
+    }
+}
+
+/// Resolver returning fixed addresses, or an error when `None`.
+struct StaticResolver(Option<Vec<IpAddr>>);
+
+impl HostResolver for StaticResolver {
+    async fn resolve(&self, _host: &str, _port: u16) -> io::Result<Vec<IpAddr>> {
+        self.0
+            .clone()
+            .ok_or_else(|| io::Error::other("lookup failed"))
+    }
+}
+
+/// Probe returning scripted results in order.
+struct ScriptedProbe(Mutex<VecDeque<Reachability>>);
+
+impl ScriptedProbe {
+    fn new(results: impl IntoIterator<Item = Reachability>) -> Self {
+        Self(Mutex::new(results.into_iter().collect()))
+    }
+}
+
+impl ConnectivityProbe for ScriptedProbe {
+    async fn probe(&self) -> Reachability {
+        self.0.lock().unwrap().pop_front().expect("scripted result")
+    }
+}
+
+/// Sender recording every delivered batch, and the name and size of every
+/// event.
+#[derive(Default)]
+struct RecordingSender {
+    batches: Mutex<Vec<Vec<IpChange>>>,
+    events: Mutex<Vec<(&'static str, usize)>>,
+}
+
+impl WebhookSender for RecordingSender {
+    async fn send(&self, changes: &[IpChange]) -> Result<(), WebhookError> {
+        self.batches.lock().unwrap().push(changes.to_vec());
+        Ok(())
+    }
+
+    async fn send_event(&self, event: &Event<'_>) -> Result<(), WebhookError> {
+        self.events
+            .lock()
+            .unwrap()
+            .push((event.name(), event.len()));
+        Ok(())
+    }
+}
+
+const GLOBAL_V6: Ipv6Addr = Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1);
+const LINK_LOCAL_V6: Ipv6Addr = Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1);
+const PRIVATE_V4: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 10);
+
+fn adapter(v4: Vec<Ipv4Addr>, v6: Vec<Ipv6Addr>) -> AdapterSnapshot {
+    AdapterSnapshot::new("eth0", AdapterKind::Ethernet, v4, v6)
+}
+
+fn probe(
+    adapters: Option<Vec<AdapterSnapshot>>,
+    resolved: Option<Vec<IpAddr>>,
+    url: &str,
+    version: IpVersion,
+) -> FamilyProbe<StaticFetcher, StaticResolver> {
+    FamilyProbe::with_resolver(
+        StaticFetcher(adapters),
+        StaticResolver(resolved),
+        url::Url::parse(url).unwrap(),
+        version,
+    )
+}
+
+fn dual_stack_host() -> Vec<IpAddr> {
+    vec![
+        IpAddr::V4(Ipv4Addr::new(203, 0, 113, 1)),
+        IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 1, 0, 0, 0, 0, 1)),
+    ]
+}
+
+fn change(n: u8) -> IpChange {
+    IpChange::added(
+        "eth0",
+        IpAddr::V4(Ipv4Addr::new(10, 0, 0, n)),
+        SystemTime::UNIX_EPOCH,
+    )
+}
+
+mod family_probe {
+    use super::*;
+
+    #[tokio::test]
+    async fn v6_reachable_with_global_address() {
+        let probe = probe(
+            Some(vec![adapter(vec![], vec![GLOBAL_V6])]),
+            Some(dual_stack_host()),
+            "https://example.com/",
+            IpVersion::V6,
+        );
+
+        assert_eq!(probe.probe().await, Reachability::Reachable);
+    }
+
+    #[tokio::test]
+    async fn v6_unreachable_with_only_link_local() {
+        let probe = probe(
+            Some(vec![adapter(vec![PRIVATE_V4], vec![LINK_LOCAL_V6])]),
+            Some(dual_stack_host()),
+            "https://example.com/",
+            IpVersion::V6,
+        );
+
+        let Reachability::Unreachable(reason) = probe.probe().await else {
+            panic!("expected unreachable");
+        };
+        assert!(reason.contains("IPv6"));
+    }
+
+    #[tokio::test]
+    async fn loopback_adapter_not_counted() {
+        let loopback = AdapterSnapshot::new(
+            "lo",
+            AdapterKind::Loopback,
+            vec![Ipv4Addr::LOCALHOST],
+            vec![GLOBAL_V6],
+        );
+        let probe = probe(
+            Some(vec![loopback]),
+            Some(dual_stack_host()),
+            "https://example.com/",
+            IpVersion::V6,
+        );
+
+        assert!(matches!(probe.probe().await, Reachability::Unreachable(_)));
+    }
+
+    #[tokio::test]
+    async fn v6_unreachable_when_host_has_no_v6_address() {
+        let probe = probe(
+            Some(vec![adapter(vec![], vec![GLOBAL_V6])]),
+            Some(vec![IpAddr::V4(Ipv4Addr::new(203, 0, 113, 1))]),
+            "https://example.com/",
+            IpVersion::V6,
+        );
+
+        let Reachability::Unreachable(reason) = probe.probe().await else {
+            panic!("expected unreachable");
+        };
+        assert!(reason.contains("example.com"));
+    }
+
+    #[tokio::test]
+    async fn v4_private_address_is_routable() {
+        let probe = probe(
+            Some(vec![adapter(vec![PRIVATE_V4], vec![])]),
+            Some(dual_stack_host()),
+            "https://example.com/",
+            IpVersion::V4,
+        );
+
+        assert_eq!(probe.probe().await, Reachability::Reachable);
+    }
+
+    #[tokio::test]
+    async fn both_needs_only_one_family() {
+        let probe = probe(
+            Some(vec![adapter(vec![PRIVATE_V4], vec![])]),
+            Some(dual_stack_host()),
+            "https://example.com/",
+            IpVersion::Both,
+        );
+
+        assert_eq!(probe.probe().await, Reachability::Reachable);
+    }
+
+    #[tokio::test]
+    async fn ip_literal_host_skips_resolution() {
+        let probe = probe(
+            Some(vec![adapter(vec![PRIVATE_V4], vec![GLOBAL_V6])]),
+            None,
+            "http://[2001:db8::2]:8080/",
+            IpVersion::V6,
+        );
+
+        assert_eq!(probe.probe().await, Reachability::Reachable);
+    }
+
+    #[tokio::test]
+    async fn resolution_failure_allows_delivery() {
+        let probe = probe(
+            Some(vec![adapter(vec![], vec![])]),
+            None,
+            "https://example.com/",
+            IpVersion::V6,
+        );
+
+        assert_eq!(probe.probe().await, Reachability::Reachable);
+    }
+
+    #[tokio::test]
+    async fn fetch_failure_allows_delivery() {
+        let probe = probe(
+            None,
+            Some(dual_stack_host()),
+            "https://example.com/",
+            IpVersion::V6,
+        );
+
+        assert_eq!(probe.probe().await, Reachability::Reachable);
+    }
+
+    #[tokio::test]
+    async fn none_probe_is_always_reachable() {
+        let probe: Option<ScriptedProbe> = None;
+
+        assert_eq!(probe.probe().await, Reachability::Reachable);
+    }
+
+    #[tokio::test]
+    async fn pair_reports_first_unreachable() {
+        let down = || Reachability::Unreachable("down".to_string());
+        let first_down = (ScriptedProbe::new([down()]), ScriptedProbe::new([]));
+        let second_down = (
+            ScriptedProbe::new([Reachability::Reachable]),
+            ScriptedProbe::new([down()]),
+        );
+
+        // The second probe of the first pair is never consulted
+        assert_eq!(first_down.probe().await, down());
+        assert_eq!(second_down.probe().await, down());
+    }
+}
+
+mod gate {
+    use super::*;
+
+    fn unreachable() -> Reachability {
+        Reachability::Unreachable("no global IPv6 address on this host".to_string())
+    }
+
+    #[tokio::test]
+    async fn reachable_sends_immediately() {
+        let gate = ConnectivityGate::new(
+            RecordingSender::default(),
+            ScriptedProbe::new([Reachability::Reachable]),
+        );
+
+        gate.send(&[change(1)]).await.unwrap();
+
+        assert_eq!(gate.pending_count(), 0);
+        assert_eq!(gate.inner().batches.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn unreachable_postpones_with_reason() {
+        let gate = ConnectivityGate::new(
+            RecordingSender::default(),
+            ScriptedProbe::new([unreachable()]),
+        );
+
+        let result = gate.send(&[change(1)]).await;
+
+        match result {
+            Err(WebhookError::Postponed { pending, reason }) => {
+                assert_eq!(pending, 1);
+                assert!(reason.contains("IPv6"));
+            }
+            other => panic!("expected Postponed, got {other:?}"),
+        }
+        assert_eq!(gate.pending_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn postponed_changes_delivered_first_when_reachable() {
+        let gate = ConnectivityGate::new(
+            RecordingSender::default(),
+            ScriptedProbe::new([unreachable(), unreachable(), Reachability::Reachable]),
+        );
+
+        let _ = gate.send(&[change(1)]).await;
+        let _ = gate.send(&[change(2)]).await;
+        gate.send(&[change(3)]).await.unwrap();
+
+        assert_eq!(gate.pending_count(), 0);
+        let batches = gate.inner().batches.lock().unwrap().clone();
+        assert_eq!(batches, vec![vec![change(1), change(2), change(3)]]);
+    }
+
+    #[tokio::test]
+    async fn events_bypass_probe() {
+        // An empty script panics if the probe is consulted
+        let gate = ConnectivityGate::new(RecordingSender::default(), ScriptedProbe::new([]));
+        let dns = DnsChange {
+            adapter: "vpn".to_string(),
+            old: DnsSettings::default(),
+            new: DnsSettings {
+                suffix: "corp.example.com".to_string(),
+                servers: vec![],
+            },
+            timestamp: SystemTime::UNIX_EPOCH,
+        };
+        let drift = DriftChange {
+            drift: AddressDrift {
+                kind: DriftKind::Missing,
+                address: "203.0.113.10".parse().unwrap(),
+                adapter: None,
+            },
+            resolved: false,
+            timestamp: SystemTime::UNIX_EPOCH,
+        };
+        let rename = HostnameChange {
+            old: "old-pc".to_string(),
+            new: "new-pc".to_string(),
+            timestamp: SystemTime::UNIX_EPOCH,
+        };
+        let storm = StormChange {
+            storm: AddressStorm {
+                adapter: "eth0".to_string(),
+                count: 40,
+                limit: 16,
+            },
+            resolved: false,
+            prefix_only: false,
+            timestamp: SystemTime::UNIX_EPOCH,
+        };
+
+        for event in [
+            Event::Dns(&[dns]),
+            Event::Drift(&[drift]),
+            Event::Hostname(&[rename]),
+            Event::Storm(&[storm]),
+            Event::Link(&[]),
+        ] {
+            gate.send_event(&event).await.unwrap();
+        }
+
+        assert_eq!(
+            *gate.inner().events.lock().unwrap(),
+            [
+                ("dns", 1),
+                ("drift", 1),
+                ("hostname", 1),
+                ("storm", 1),
+                ("link", 0)
+            ]
+        );
+    }
+}
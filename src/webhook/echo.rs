@@ -0,0 +1,64 @@
+//! Echo checks that a delivery actually reached the receiver.
+//!
+//! A proxy or CDN in front of the receiver may answer with a cached `200 OK`
+//! without forwarding the request. With an [`EchoCheck`], every attempt
+//! carries a fresh nonce, in [`NONCE_HEADER`] and as the payload's `nonce`,
+//! and only counts as delivered if the response echoes that nonce back.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+use http::HeaderName;
+
+use super::HttpResponse;
+
+/// Header carrying the attempt's nonce, on requests and echoed responses.
+pub const NONCE_HEADER: HeaderName = HeaderName::from_static("x-ddns-a-nonce");
+
+/// Where the receiver must echo the nonce of each attempt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EchoCheck {
+    /// No nonce is sent or expected (default).
+    #[default]
+    Disabled,
+    /// The response must carry the nonce in [`NONCE_HEADER`].
+    Header,
+    /// The response body must contain the nonce.
+    Body,
+}
+
+impl EchoCheck {
+    /// Returns true if attempts carry a nonce.
+    #[must_use]
+    pub const fn is_enabled(self) -> bool {
+        !matches!(self, Self::Disabled)
+    }
+
+    /// Checks that `response` echoes `nonce`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the reason if the nonce is missing or different.
+    pub fn verify(self, nonce: &str, response: &HttpResponse) -> Result<(), String> {
+        match self {
+            Self::Disabled => Ok(()),
+            Self::Header => match response.headers.get(NONCE_HEADER) {
+                Some(echoed) if echoed == nonce => Ok(()),
+                Some(echoed) => Err(format!(
+                    "response {NONCE_HEADER} is '{}', expected '{nonce}'",
+                    String::from_utf8_lossy(echoed.as_bytes())
+                )),
+                None => Err(format!("response has no {NONCE_HEADER} header")),
+            },
+            Self::Body if response.body_text().is_some_and(|b| b.contains(nonce)) => Ok(()),
+            Self::Body => Err(format!("response body does not contain nonce '{nonce}'")),
+        }
+    }
+}
+
+/// Returns a fresh, unpredictable 128-bit nonce as 32 hex digits.
+pub(super) fn nonce() -> String {
+    // Every RandomState is seeded from OS randomness
+    let half = || RandomState::new().build_hasher().finish();
+    format!("{:016x}{:016x}", half(), half())
+}
@@ -0,0 +1,233 @@
+//! Tests for the webhook echo check.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
+
+use http::{HeaderMap, HeaderValue, StatusCode};
+
+use super::echo::{EchoCheck, NONCE_HEADER, nonce};
+use super::{
+    HttpClient, HttpError, HttpRequest, HttpResponse, HttpWebhook, IsRetryable, RetryableError,
+    WebhookError, WebhookSender,
+};
+use crate::monitor::IpChange;
+use crate::time::InstantSleeper;
+
+fn response_with_header(value: &str) -> HttpResponse {
+    let mut headers = HeaderMap::new();
+    headers.insert(NONCE_HEADER, HeaderValue::from_str(value).unwrap());
+    HttpResponse::new(StatusCode::OK, headers, Vec::new())
+}
+
+fn response_with_body(body: &str) -> HttpResponse {
+    HttpResponse::new(StatusCode::OK, HeaderMap::new(), body.as_bytes().to_vec())
+}
+
+mod verify {
+    use super::*;
+
+    #[test]
+    fn disabled_accepts_anything() {
+        assert!(!EchoCheck::Disabled.is_enabled());
+        assert!(
+            EchoCheck::Disabled
+                .verify("abc", &response_with_body(""))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn header_must_match() {
+        assert!(
+            EchoCheck::Header
+                .verify("abc", &response_with_header("abc"))
+                .is_ok()
+        );
+        assert_eq!(
+            EchoCheck::Header.verify("abc", &response_with_header("old")),
+            Err("response x-ddns-a-nonce is 'old', expected 'abc'".to_string())
+        );
+        assert_eq!(
+            EchoCheck::Header.verify("abc", &response_with_body("abc")),
+            Err("response has no x-ddns-a-nonce header".to_string())
+        );
+    }
+
+    #[test]
+    fn body_must_contain_nonce() {
+        assert!(
+            EchoCheck::Body
+                .verify("abc", &response_with_body(r#"{"nonce":"abc"}"#))
+                .is_ok()
+        );
+        assert!(
+            EchoCheck::Body
+                .verify("abc", &response_with_body("cached"))
+                .is_err()
+        );
+        assert!(
+            EchoCheck::Body
+                .verify("abc", &response_with_header("abc"))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn nonces_are_unique_hex() {
+        let (a, b) = (nonce(), nonce());
+
+        assert_eq!(a.len(), 32);
+        assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn mismatch_is_retryable() {
+        let error = RetryableError::EchoMismatch {
+            reason: "x".to_string(),
+        };
+        assert!(error.is_retryable());
+    }
+}
+
+mod webhook {
+    use super::*;
+
+    /// Answers each request in `mode`, with an old nonce while stale answers remain.
+    struct EchoClient {
+        mode: EchoCheck,
+        stale_answers: AtomicUsize,
+        requests: Mutex<Vec<HttpRequest>>,
+    }
+
+    impl EchoClient {
+        /// Echoes every nonce after answering `stale` requests with an old one.
+        fn new(mode: EchoCheck, stale: usize) -> Self {
+            Self {
+                mode,
+                stale_answers: AtomicUsize::new(stale),
+                requests: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn nonces(&self) -> Vec<Option<String>> {
+            self.requests
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|r| {
+                    r.headers
+                        .get(NONCE_HEADER)
+                        .map(|v| v.to_str().unwrap().to_string())
+                })
+                .collect()
+        }
+    }
+
+    impl HttpClient for &EchoClient {
+        async fn request(&self, req: HttpRequest) -> Result<HttpResponse, HttpError> {
+            let stale = self
+                .stale_answers
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok();
+            let echoed = if stale {
+                "0123456789abcdef0123456789abcdef".to_string()
+            } else {
+                req.headers
+                    .get(NONCE_HEADER)
+                    .map_or("", |v| v.to_str().unwrap())
+                    .to_string()
+            };
+            self.requests.lock().unwrap().push(req);
+
+            Ok(if self.mode == EchoCheck::Header {
+                response_with_header(&echoed)
+            } else {
+                response_with_body(&echoed)
+            })
+        }
+    }
+
+    fn webhook(client: &EchoClient, check: EchoCheck) -> HttpWebhook<&EchoClient, InstantSleeper> {
+        HttpWebhook::new(client, url::Url::parse("https://example.com/hook").unwrap())
+            .with_echo_check(check)
+            .with_sleeper(InstantSleeper)
+    }
+
+    fn changes() -> Vec<IpChange> {
+        vec![IpChange::added(
+            "eth0",
+            "192.168.1.1".parse().unwrap(),
+            SystemTime::UNIX_EPOCH,
+        )]
+    }
+
+    #[tokio::test]
+    async fn succeeds_when_receiver_echoes() {
+        for check in [EchoCheck::Header, EchoCheck::Body] {
+            let client = EchoClient::new(check, 0);
+
+            webhook(&client, check).send(&changes()).await.unwrap();
+
+            assert!(client.nonces()[0].is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_stale_answer_with_new_nonce() {
+        let client = EchoClient::new(EchoCheck::Header, 1);
+
+        webhook(&client, EchoCheck::Header)
+            .send(&changes())
+            .await
+            .unwrap();
+
+        let nonces = client.nonces();
+        assert_eq!(nonces.len(), 2);
+        assert_ne!(nonces[0], nonces[1]);
+    }
+
+    #[tokio::test]
+    async fn fails_when_never_echoed() {
+        let client = EchoClient::new(EchoCheck::Body, usize::MAX);
+
+        let result = webhook(&client, EchoCheck::Body).send(&changes()).await;
+
+        assert!(matches!(
+            result,
+            Err(WebhookError::MaxRetriesExceeded {
+                last_error: RetryableError::EchoMismatch { .. },
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn disabled_sends_no_nonce() {
+        let client = EchoClient::new(EchoCheck::Header, 0);
+
+        webhook(&client, EchoCheck::Disabled)
+            .send(&changes())
+            .await
+            .unwrap();
+
+        assert_eq!(client.nonces(), vec![None]);
+    }
+
+    #[cfg(feature = "templates")]
+    #[tokio::test]
+    async fn template_sees_nonce() {
+        let client = EchoClient::new(EchoCheck::Header, 0);
+
+        webhook(&client, EchoCheck::Header)
+            .with_body_template("{{nonce}}")
+            .send(&changes())
+            .await
+            .unwrap();
+
+        let request = client.requests.lock().unwrap()[0].clone();
+        let body = String::from_utf8(request.body.unwrap()).unwrap();
+        assert_eq!(Some(body), client.nonces()[0]);
+    }
+}
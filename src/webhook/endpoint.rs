@@ -0,0 +1,167 @@
+//! Webhook endpoint discovery from DNS.
+//!
+//! Instead of baking the webhook URL into every device's config, a fleet can
+//! publish it in DNS: a TXT record holding the URL, or an SRV record naming
+//! the host and port. The configured URL is used until the first successful
+//! lookup and whenever a lookup fails, so a DNS outage never stops delivery.
+
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::Duration;
+
+use thiserror::Error;
+use url::Url;
+
+use crate::dns::{DnsError, DnsResolver, SrvRecord};
+
+/// DNS record the webhook URL is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EndpointRecord {
+    /// TXT record whose text is the full webhook URL.
+    Txt {
+        /// Record name, e.g. `_ddns-a.example.com`
+        name: String,
+    },
+    /// SRV record; the URL is `https://<target>:<port><path>`.
+    Srv {
+        /// Record name, e.g. `_ddns-a._tcp.example.com`
+        name: String,
+        /// Path appended to the discovered host (starts with `/`)
+        path: String,
+    },
+}
+
+impl EndpointRecord {
+    /// Returns the record name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Txt { name } | Self::Srv { name, .. } => name,
+        }
+    }
+}
+
+/// Endpoint discovery settings resolved from configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointDiscovery {
+    /// Record to read the URL from.
+    pub record: EndpointRecord,
+    /// Nameserver to query (`None`: the first nameserver in `/etc/resolv.conf`).
+    pub nameserver: Option<std::net::SocketAddr>,
+    /// Time between lookups after startup (`Duration::ZERO`: startup only).
+    pub refresh: Duration,
+}
+
+/// Error type for endpoint discovery.
+#[derive(Debug, Error)]
+pub enum EndpointError {
+    /// The DNS lookup failed.
+    #[error("Endpoint lookup failed: {0}")]
+    Lookup(#[from] DnsError),
+
+    /// The record exists but holds no usable URL.
+    #[error("No usable webhook URL in DNS record '{name}'")]
+    NoUsableRecord {
+        /// Record name
+        name: String,
+    },
+}
+
+/// Webhook URL that can be replaced while senders are using it.
+///
+/// Clones share the same URL, so a refresh task can update the URL of a
+/// sender it does not own.
+#[derive(Debug, Clone)]
+pub struct SharedUrl(Arc<RwLock<Url>>);
+
+impl SharedUrl {
+    /// Wraps `url`.
+    #[must_use]
+    pub fn new(url: Url) -> Self {
+        Self(Arc::new(RwLock::new(url)))
+    }
+
+    /// Returns the current URL.
+    #[must_use]
+    pub fn get(&self) -> Url {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Replaces the URL, returning true if it changed.
+    pub fn set(&self, url: Url) -> bool {
+        let mut current = self.0.write().unwrap_or_else(PoisonError::into_inner);
+        if *current == url {
+            return false;
+        }
+        *current = url;
+        true
+    }
+}
+
+/// Looks up the webhook URL published in `record`.
+///
+/// Only `https` URLs are accepted, since whoever controls the record
+/// receives the webhook's headers and bearer token. For TXT records the
+/// first record that is such a URL wins. For SRV records the lowest
+/// priority wins, then the highest weight; a target of `.` ("service not
+/// available") is skipped.
+///
+/// # Errors
+///
+/// Returns an error if the lookup fails or yields no usable URL.
+pub async fn resolve_endpoint<R: DnsResolver>(
+    resolver: &R,
+    record: &EndpointRecord,
+) -> Result<Url, EndpointError> {
+    let url = match record {
+        EndpointRecord::Txt { name } => resolver
+            .lookup_txt(name)
+            .await?
+            .iter()
+            .find_map(|text| Url::parse(text.trim()).ok().filter(is_https)),
+        EndpointRecord::Srv { name, path } => srv_url(&resolver.lookup_srv(name).await?, path),
+    };
+
+    url.ok_or_else(|| EndpointError::NoUsableRecord {
+        name: record.name().to_string(),
+    })
+}
+
+/// Looks up `record` and stores the result in `url`.
+///
+/// Returns true if the URL changed. On error `url` is left untouched.
+///
+/// # Errors
+///
+/// Returns an error if the lookup fails or yields no usable URL.
+pub async fn refresh_endpoint<R: DnsResolver>(
+    resolver: &R,
+    record: &EndpointRecord,
+    url: &SharedUrl,
+) -> Result<bool, EndpointError> {
+    let discovered = resolve_endpoint(resolver, record).await?;
+    let changed = url.set(discovered.clone());
+    if changed {
+        tracing::info!(
+            "Webhook URL from DNS record '{}': {discovered}",
+            record.name()
+        );
+    }
+    Ok(changed)
+}
+
+fn is_https(url: &Url) -> bool {
+    url.scheme() == "https" && url.host().is_some()
+}
+
+fn srv_url(records: &[SrvRecord], path: &str) -> Option<Url> {
+    let best = records
+        .iter()
+        .filter(|srv| srv.target != ".")
+        .min_by_key(|srv| (srv.priority, std::cmp::Reverse(srv.weight)))?;
+
+    let host = best.target.trim_end_matches('.');
+    Url::parse(&format!("https://{host}:{}{path}", best.port)).ok()
+}
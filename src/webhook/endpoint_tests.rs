@@ -0,0 +1,219 @@
+//! Tests for webhook endpoint discovery.
+
+use std::net::IpAddr;
+
+use url::Url;
+
+use super::endpoint::{
+    EndpointError, EndpointRecord, SharedUrl, refresh_endpoint, resolve_endpoint,
+};
+use crate::dns::{DnsError, DnsResolver, SrvRecord};
+
+/// Resolver returning fixed records, or failing when `fail` is set.
+#[derive(Default)]
+struct MockResolver {
+    txt: Vec<String>,
+    srv: Vec<SrvRecord>,
+    fail: bool,
+}
+
+impl DnsResolver for MockResolver {
+    async fn lookup_txt(&self, _name: &str) -> Result<Vec<String>, DnsError> {
+        if self.fail {
+            return Err(DnsError::Timeout);
+        }
+        Ok(self.txt.clone())
+    }
+
+    async fn lookup_srv(&self, _name: &str) -> Result<Vec<SrvRecord>, DnsError> {
+        if self.fail {
+            return Err(DnsError::Timeout);
+        }
+        Ok(self.srv.clone())
+    }
+
+    async fn lookup_ip(&self, _name: &str) -> Result<Vec<IpAddr>, DnsError> {
+        unreachable!("discovery only looks up TXT and SRV records")
+    }
+}
+
+fn txt(records: &[&str]) -> MockResolver {
+    MockResolver {
+        txt: records.iter().map(ToString::to_string).collect(),
+        ..MockResolver::default()
+    }
+}
+
+fn srv(priority: u16, weight: u16, port: u16, target: &str) -> SrvRecord {
+    SrvRecord {
+        priority,
+        weight,
+        port,
+        target: target.to_string(),
+    }
+}
+
+fn txt_record() -> EndpointRecord {
+    EndpointRecord::Txt {
+        name: "_ddns-a.example.com".to_string(),
+    }
+}
+
+fn srv_record(path: &str) -> EndpointRecord {
+    EndpointRecord::Srv {
+        name: "_ddns-a._tcp.example.com".to_string(),
+        path: path.to_string(),
+    }
+}
+
+fn url(s: &str) -> Url {
+    Url::parse(s).unwrap()
+}
+
+mod txt_records {
+    use super::*;
+
+    #[tokio::test]
+    async fn first_https_url_wins() {
+        let resolver = txt(&[
+            "v=spf1 -all",
+            "http://insecure.example.com/hook",
+            " https://hooks.example.com/ddns ",
+            "https://other.example.com/",
+        ]);
+
+        let found = resolve_endpoint(&resolver, &txt_record()).await.unwrap();
+
+        assert_eq!(found, url("https://hooks.example.com/ddns"));
+    }
+
+    #[tokio::test]
+    async fn no_url_is_error() {
+        let resolver = txt(&["not a url"]);
+
+        let result = resolve_endpoint(&resolver, &txt_record()).await;
+
+        assert!(matches!(
+            result,
+            Err(EndpointError::NoUsableRecord { ref name }) if name == "_ddns-a.example.com"
+        ));
+    }
+
+    #[tokio::test]
+    async fn missing_record_is_error() {
+        let result = resolve_endpoint(&txt(&[]), &txt_record()).await;
+
+        assert!(matches!(result, Err(EndpointError::NoUsableRecord { .. })));
+    }
+}
+
+mod srv_records {
+    use super::*;
+
+    #[tokio::test]
+    async fn builds_https_url_with_path() {
+        let resolver = MockResolver {
+            srv: vec![srv(10, 0, 8443, "hooks.example.com.")],
+            ..MockResolver::default()
+        };
+
+        let found = resolve_endpoint(&resolver, &srv_record("/ddns"))
+            .await
+            .unwrap();
+
+        assert_eq!(found, url("https://hooks.example.com:8443/ddns"));
+    }
+
+    #[tokio::test]
+    async fn lowest_priority_then_highest_weight() {
+        let resolver = MockResolver {
+            srv: vec![
+                srv(20, 100, 443, "backup.example.com"),
+                srv(10, 1, 443, "light.example.com"),
+                srv(10, 50, 443, "heavy.example.com"),
+            ],
+            ..MockResolver::default()
+        };
+
+        let found = resolve_endpoint(&resolver, &srv_record("/")).await.unwrap();
+
+        assert_eq!(found.host_str(), Some("heavy.example.com"));
+    }
+
+    #[tokio::test]
+    async fn unavailable_target_is_skipped() {
+        let resolver = MockResolver {
+            srv: vec![srv(0, 0, 0, "."), srv(10, 0, 443, "hooks.example.com")],
+            ..MockResolver::default()
+        };
+
+        let found = resolve_endpoint(&resolver, &srv_record("/")).await.unwrap();
+
+        assert_eq!(found.host_str(), Some("hooks.example.com"));
+    }
+
+    #[tokio::test]
+    async fn only_unavailable_target_is_error() {
+        let resolver = MockResolver {
+            srv: vec![srv(0, 0, 0, ".")],
+            ..MockResolver::default()
+        };
+
+        let result = resolve_endpoint(&resolver, &srv_record("/")).await;
+
+        assert!(matches!(result, Err(EndpointError::NoUsableRecord { .. })));
+    }
+}
+
+mod refresh {
+    use super::*;
+
+    #[tokio::test]
+    async fn updates_shared_url() {
+        let shared = SharedUrl::new(url("https://fallback.example.com/"));
+        let other = shared.clone();
+
+        let changed = refresh_endpoint(
+            &txt(&["https://hooks.example.com/"]),
+            &txt_record(),
+            &shared,
+        )
+        .await
+        .unwrap();
+
+        assert!(changed);
+        assert_eq!(other.get(), url("https://hooks.example.com/"));
+    }
+
+    #[tokio::test]
+    async fn unchanged_url_reports_false() {
+        let shared = SharedUrl::new(url("https://hooks.example.com/"));
+
+        let changed = refresh_endpoint(
+            &txt(&["https://hooks.example.com/"]),
+            &txt_record(),
+            &shared,
+        )
+        .await
+        .unwrap();
+
+        assert!(!changed);
+    }
+
+    #[tokio::test]
+    async fn failure_keeps_current_url() {
+        let shared = SharedUrl::new(url("https://fallback.example.com/"));
+        let resolver = MockResolver {
+            fail: true,
+            ..MockResolver::default()
+        };
+
+        let result = refresh_endpoint(&resolver, &txt_record(), &shared).await;
+
+        assert!(matches!(
+            result,
+            Err(EndpointError::Lookup(DnsError::Timeout))
+        ));
+        assert_eq!(shared.get(), url("https://fallback.example.com/"));
+    }
+}
@@ -1,7 +1,11 @@
 //! Error types for HTTP and webhook operations.
 
+use std::time::Duration;
+
 use thiserror::Error;
 
+use super::GoldenError;
+
 /// Error type for HTTP operations.
 ///
 /// Describes what went wrong without dictating recovery strategy.
@@ -27,6 +31,15 @@ pub enum HttpError {
     /// a transient failure.
     #[error("Invalid URL: {0}")]
     InvalidUrl(String),
+
+    /// The response body exceeded the request's limit.
+    ///
+    /// See [`HttpRequest::with_max_response_len`](super::HttpRequest::with_max_response_len).
+    #[error("Response body exceeds {limit} bytes")]
+    ResponseTooLarge {
+        /// Limit in bytes
+        limit: usize,
+    },
 }
 
 /// Error type for operations that may be retried.
@@ -59,6 +72,8 @@ pub enum RetryableError {
         status: http::StatusCode,
         /// Optional response body for diagnostics
         body: Option<String>,
+        /// Wait the server asked for in `Retry-After` (429 and 503 only)
+        retry_after: Option<Duration>,
     },
 
     /// Template rendering failed.
@@ -66,6 +81,64 @@ pub enum RetryableError {
     /// The body template could not be rendered with the provided data.
     #[error("Template error: {0}")]
     Template(String),
+
+    /// A 2xx response did not echo the attempt's nonce.
+    ///
+    /// Retryable: a cache may have answered; every attempt has a new nonce.
+    #[error("Echo check failed: {reason}")]
+    EchoMismatch {
+        /// What was missing or different
+        reason: String,
+    },
+
+    /// A DNS provider answered with success status but rejected the update.
+    ///
+    /// Not retryable: providers reject updates for bad credentials or names.
+    #[error("Update rejected: {reason}")]
+    Rejected {
+        /// The provider's answer
+        reason: String,
+    },
+
+    /// A 2xx response failed the [`JsonAssertion`](super::JsonAssertion).
+    ///
+    /// Retried only if the assertion was configured to be.
+    #[error("Response assertion failed: {reason}")]
+    AssertionFailed {
+        /// What was missing or different
+        reason: String,
+        /// Whether another attempt may succeed
+        retryable: bool,
+    },
+
+    /// A value could not be taken from the pre-request's response.
+    ///
+    /// Not retryable: the panel answered, just not as configured.
+    #[error("Capture failed: {reason}")]
+    Capture {
+        /// Which capture failed
+        reason: String,
+    },
+
+    /// The `OAuth2` token endpoint answered without a usable access token.
+    ///
+    /// Not retryable: the endpoint answered, just not with a token.
+    #[error("OAuth2 token request failed: {reason}")]
+    Token {
+        /// What was wrong with the answer
+        reason: String,
+    },
+
+    /// An attempt of a [`Transport`](super::Transport) other than HTTP failed.
+    ///
+    /// Retried only if the transport marked the failure as transient.
+    #[error("Transport error: {reason}")]
+    Transport {
+        /// Why the attempt failed
+        reason: String,
+        /// Whether another attempt may succeed
+        retryable: bool,
+    },
 }
 
 /// High-level error type for webhook operations.
@@ -87,4 +160,124 @@ pub enum WebhookError {
         #[source]
         last_error: RetryableError,
     },
+
+    /// Shutdown was signalled before the delivery succeeded.
+    ///
+    /// The changes were not (or not known to be) delivered.
+    #[error("Delivery interrupted by shutdown after {attempts} attempt(s)")]
+    Interrupted {
+        /// Number of attempts made before shutdown
+        attempts: u32,
+    },
+
+    /// Delivery was postponed because the webhook host is currently unreachable.
+    ///
+    /// The changes are kept and delivered with a later batch.
+    #[error("Delivery postponed ({pending} change(s) pending): {reason}")]
+    Postponed {
+        /// Number of changes now awaiting delivery
+        pending: usize,
+        /// Why the host is unreachable
+        reason: String,
+    },
+
+    /// The webhook's circuit is open after repeated failures, so the
+    /// delivery was not attempted (see [`CircuitBreaker`](super::CircuitBreaker)).
+    #[error("Circuit open after repeated failures, next attempt in {retry_in_secs}s")]
+    CircuitOpen {
+        /// Seconds until a delivery probes the endpoint again
+        retry_in_secs: u64,
+    },
+
+    /// The delivery could not be recorded as a golden case.
+    #[error("Failed to record payload: {0}")]
+    Record(#[from] GoldenError),
+
+    /// Delivery to some of several [`FanOut`](super::FanOut) targets failed.
+    ///
+    /// The other targets accepted the changes.
+    #[error("{} of {total} targets failed: {}", failures.len(), describe_failures(failures))]
+    Targets {
+        /// Number of targets the changes were sent to
+        total: usize,
+        /// Name and error of each failed target
+        failures: Vec<(String, Self)>,
+    },
+
+    /// Delivery of some changes sent one by one failed (see
+    /// [`DeliveryMode::PerChange`](super::DeliveryMode::PerChange)).
+    ///
+    /// The other changes were delivered, except after an interruption.
+    #[error("{} of {total} changes failed: {}", failures.len(), describe_failures(failures))]
+    Changes {
+        /// Number of changes in the batch
+        total: usize,
+        /// Summary and error of each failed change, in batch order
+        failures: Vec<(String, Self)>,
+    },
+}
+
+impl RetryableError {
+    /// Returns the wait the server asked for before the next attempt, if any.
+    #[must_use]
+    pub const fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::NonSuccessStatus { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
+impl WebhookError {
+    /// Returns true if shutdown interrupted the delivery, to any target.
+    #[must_use]
+    pub fn is_interrupted(&self) -> bool {
+        match self {
+            Self::Interrupted { .. } => true,
+            Self::Targets { failures, .. } | Self::Changes { failures, .. } => {
+                failures.iter().any(|(_, e)| e.is_interrupted())
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if an open circuit rejected the delivery, to every
+    /// failed target.
+    #[must_use]
+    pub fn is_circuit_open(&self) -> bool {
+        match self {
+            Self::CircuitOpen { .. } => true,
+            Self::Targets { failures, .. } | Self::Changes { failures, .. } => {
+                failures.iter().all(|(_, e)| e.is_circuit_open())
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if the delivery was postponed, to every failed target.
+    #[must_use]
+    pub fn is_postponed(&self) -> bool {
+        match self {
+            Self::Postponed { .. } => true,
+            Self::Targets { failures, .. } | Self::Changes { failures, .. } => {
+                failures.iter().all(|(_, e)| e.is_postponed())
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Joins target or change failures as `name: error[: cause]; ...`, so the
+/// cause of a retry summary is not lost.
+fn describe_failures(failures: &[(String, WebhookError)]) -> String {
+    failures
+        .iter()
+        .map(|(name, error)| {
+            std::error::Error::source(error).map_or_else(
+                || format!("{name}: {error}"),
+                |cause| format!("{name}: {error}: {cause}"),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
 }
@@ -0,0 +1,158 @@
+//! Tests for the webhook errors and their retryability.
+
+use super::{HttpError, IsRetryable, RetryableError};
+
+mod is_retryable_trait {
+    use super::*;
+
+    #[test]
+    fn connection_error_is_retryable() {
+        let error = HttpError::Connection(Box::new(std::io::Error::other("network")));
+        assert!(error.is_retryable());
+    }
+
+    #[test]
+    fn timeout_is_retryable() {
+        let error = HttpError::Timeout;
+        assert!(error.is_retryable());
+    }
+
+    #[test]
+    fn invalid_url_is_not_retryable() {
+        let error = HttpError::InvalidUrl("bad url".to_string());
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn oversized_response_is_not_retryable() {
+        let error = HttpError::ResponseTooLarge { limit: 1024 };
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn retryable_error_http_delegates() {
+        let error = RetryableError::Http(HttpError::Timeout);
+        assert!(error.is_retryable());
+
+        let error = RetryableError::Http(HttpError::InvalidUrl("bad".to_string()));
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn status_500_is_retryable() {
+        let error = RetryableError::NonSuccessStatus {
+            status: http::StatusCode::INTERNAL_SERVER_ERROR,
+            body: None,
+            retry_after: None,
+        };
+        assert!(error.is_retryable());
+    }
+
+    #[test]
+    fn status_503_is_retryable() {
+        let error = RetryableError::NonSuccessStatus {
+            status: http::StatusCode::SERVICE_UNAVAILABLE,
+            body: None,
+            retry_after: None,
+        };
+        assert!(error.is_retryable());
+    }
+
+    #[test]
+    fn status_429_is_retryable() {
+        let error = RetryableError::NonSuccessStatus {
+            status: http::StatusCode::TOO_MANY_REQUESTS,
+            body: None,
+            retry_after: None,
+        };
+        assert!(error.is_retryable());
+    }
+
+    #[test]
+    fn status_408_is_retryable() {
+        let error = RetryableError::NonSuccessStatus {
+            status: http::StatusCode::REQUEST_TIMEOUT,
+            body: None,
+            retry_after: None,
+        };
+        assert!(error.is_retryable());
+    }
+
+    #[test]
+    fn status_400_is_not_retryable() {
+        let error = RetryableError::NonSuccessStatus {
+            status: http::StatusCode::BAD_REQUEST,
+            body: None,
+            retry_after: None,
+        };
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn status_404_is_not_retryable() {
+        let error = RetryableError::NonSuccessStatus {
+            status: http::StatusCode::NOT_FOUND,
+            body: None,
+            retry_after: None,
+        };
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn template_error_is_not_retryable() {
+        let error = RetryableError::Template("bad template".to_string());
+        assert!(!error.is_retryable());
+    }
+}
+
+mod error_display {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn retryable_error_http_displays_source() {
+        let error = RetryableError::Http(HttpError::Timeout);
+        assert!(error.to_string().contains("timed out"));
+    }
+
+    #[test]
+    fn retryable_error_non_success_status_displays_code_and_body() {
+        let error = RetryableError::NonSuccessStatus {
+            status: http::StatusCode::INTERNAL_SERVER_ERROR,
+            body: Some("Internal error".to_string()),
+            retry_after: None,
+        };
+        assert!(error.to_string().contains("500"));
+        assert!(error.to_string().contains("Internal error"));
+    }
+
+    #[test]
+    fn retryable_error_non_success_status_without_body() {
+        let error = RetryableError::NonSuccessStatus {
+            status: http::StatusCode::INTERNAL_SERVER_ERROR,
+            body: None,
+            retry_after: None,
+        };
+        let display = error.to_string();
+        assert!(display.contains("500"));
+        assert!(display.contains("<no body>"));
+    }
+
+    #[test]
+    fn webhook_error_max_retries_displays_attempts() {
+        let error = super::super::WebhookError::MaxRetriesExceeded {
+            attempts: 5,
+            last_error: RetryableError::Http(HttpError::Timeout),
+        };
+        assert!(error.to_string().contains("5 attempts"));
+    }
+
+    #[test]
+    fn webhook_error_max_retries_has_source() {
+        let error = super::super::WebhookError::MaxRetriesExceeded {
+            attempts: 3,
+            last_error: RetryableError::Http(HttpError::Timeout),
+        };
+        assert!(error.source().is_some());
+    }
+}
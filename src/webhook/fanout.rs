@@ -0,0 +1,410 @@
+//! Delivering every change to several webhook targets.
+//!
+//! [`FanOut`] sends each batch to all of its named targets concurrently, so
+//! a slow or retrying target does not hold up the others. Each target keeps
+//! its own retry policy; failures are combined into one
+//! [`WebhookError::Targets`].
+//!
+//! A target can be limited to some adapters with an [`AdapterRoute`]: it then
+//! only receives their changes, and is skipped for batches without any. A
+//! route can also split address changes by IP version, so A and AAAA updates
+//! go to different endpoints, and leave out removed addresses, so a chat
+//! only hears about new ones while the DNS updater sees every change.
+
+use std::borrow::Cow;
+use std::future::{Future, poll_fn};
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::task::Poll;
+
+use regex::Regex;
+
+use crate::monitor::{DnsChange, DriftChange, HostnameChange, IpChange, LinkChange, StormChange};
+use crate::network::IpVersion;
+
+use super::{Event, WebhookError, WebhookSender};
+
+/// Adapters whose changes a target receives.
+///
+/// A change is routed if its adapter matches any of the patterns. Changes
+/// without an adapter (hostname changes, missing expected addresses) go to
+/// every target. Without patterns, everything is routed.
+///
+/// Changes of an address (address changes, drifts) can further be limited
+/// by IP version: [`for_version`](Self::for_version) routes only the
+/// addresses of one version, [`excluding`](Self::excluding) all but those.
+/// With [`with_added_only`](Self::with_added_only), removed addresses are
+/// not routed either.
+#[derive(Debug, Clone, Default)]
+pub struct AdapterRoute {
+    patterns: Vec<Regex>,
+    /// IP versions whose addresses are not routed
+    excluded: Option<IpVersion>,
+    /// Whether changes without an address are not routed
+    address_only: bool,
+    /// Whether removed addresses are not routed
+    added_only: bool,
+}
+
+impl AdapterRoute {
+    /// Creates a route for adapters matching any of `patterns`.
+    #[must_use]
+    pub const fn new(patterns: Vec<Regex>) -> Self {
+        Self {
+            patterns,
+            excluded: None,
+            address_only: false,
+            added_only: false,
+        }
+    }
+
+    /// Limits the route to the addresses of `version`; changes without an
+    /// address are no longer routed.
+    #[must_use]
+    pub const fn for_version(mut self, version: IpVersion) -> Self {
+        self.excluded = match version {
+            IpVersion::V4 => Some(IpVersion::V6),
+            IpVersion::V6 => Some(IpVersion::V4),
+            IpVersion::Both => None,
+        };
+        self.address_only = true;
+        self
+    }
+
+    /// Stops routing the addresses of `version`, which go elsewhere.
+    #[must_use]
+    pub const fn excluding(mut self, version: IpVersion) -> Self {
+        self.excluded = match (self.excluded, version) {
+            (None, version) => Some(version),
+            (Some(IpVersion::V4), IpVersion::V4) => Some(IpVersion::V4),
+            (Some(IpVersion::V6), IpVersion::V6) => Some(IpVersion::V6),
+            _ => Some(IpVersion::Both),
+        };
+        self
+    }
+
+    /// Stops routing removed addresses if `added_only` is set.
+    #[must_use]
+    pub const fn with_added_only(mut self, added_only: bool) -> Self {
+        self.added_only = added_only;
+        self
+    }
+
+    /// Returns true if every adapter is routed.
+    #[must_use]
+    pub fn is_all(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Returns true if changes of `adapter` are routed.
+    #[must_use]
+    pub fn matches(&self, adapter: &str) -> bool {
+        self.is_all() || self.patterns.iter().any(|p| p.is_match(adapter))
+    }
+
+    /// Returns true if changes of `address` are routed; `None` stands for
+    /// changes without an address.
+    #[must_use]
+    pub const fn matches_address(&self, address: Option<IpAddr>) -> bool {
+        match (address, self.excluded) {
+            (None, _) => !self.address_only,
+            (Some(_), None) => true,
+            (Some(address), Some(excluded)) => !match address {
+                IpAddr::V4(_) => excluded.includes_v4(),
+                IpAddr::V6(_) => excluded.includes_v6(),
+            },
+        }
+    }
+
+    /// Returns true if the route splits changes by IP version.
+    #[must_use]
+    pub const fn is_version_split(&self) -> bool {
+        self.excluded.is_some() || self.address_only
+    }
+
+    /// Returns true if removed addresses are not routed.
+    #[must_use]
+    pub const fn is_added_only(&self) -> bool {
+        self.added_only
+    }
+
+    /// Returns the patterns as written.
+    pub fn patterns(&self) -> impl Iterator<Item = &str> {
+        self.patterns.iter().map(Regex::as_str)
+    }
+
+    /// The part of `changes` routed here; `None` if a non-empty batch has
+    /// nothing for this target.
+    fn select<'c, T: Routed + Clone>(&self, changes: &'c [T]) -> Option<Cow<'c, [T]>> {
+        let routed = |change: &T| {
+            change.adapter().is_none_or(|a| self.matches(a))
+                && self.matches_address(change.address())
+                && !(self.added_only && change.is_removal())
+        };
+        if changes.iter().all(routed) {
+            return Some(Cow::Borrowed(changes));
+        }
+        let selected: Vec<T> = changes.iter().filter(|c| routed(c)).cloned().collect();
+        (!selected.is_empty()).then_some(Cow::Owned(selected))
+    }
+}
+
+/// A change concerning a single adapter and address, if any.
+trait Routed {
+    fn adapter(&self) -> Option<&str>;
+
+    fn address(&self) -> Option<IpAddr> {
+        None
+    }
+
+    fn is_removal(&self) -> bool {
+        false
+    }
+}
+
+impl Routed for IpChange {
+    fn adapter(&self) -> Option<&str> {
+        Some(&self.adapter)
+    }
+
+    fn address(&self) -> Option<IpAddr> {
+        Some(self.address)
+    }
+
+    fn is_removal(&self) -> bool {
+        !self.is_added()
+    }
+}
+
+impl Routed for DnsChange {
+    fn adapter(&self) -> Option<&str> {
+        Some(&self.adapter)
+    }
+}
+
+impl Routed for DriftChange {
+    fn adapter(&self) -> Option<&str> {
+        self.drift.adapter.as_deref()
+    }
+
+    fn address(&self) -> Option<IpAddr> {
+        Some(self.drift.address)
+    }
+}
+
+impl Routed for HostnameChange {
+    fn adapter(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl Routed for StormChange {
+    fn adapter(&self) -> Option<&str> {
+        Some(&self.storm.adapter)
+    }
+}
+
+impl Routed for LinkChange {
+    fn adapter(&self) -> Option<&str> {
+        Some(&self.adapter)
+    }
+}
+
+/// A named target and the adapters it receives.
+#[derive(Debug)]
+struct Target<W> {
+    name: String,
+    sender: W,
+    route: AdapterRoute,
+}
+
+/// Sender delivering to several named targets at once.
+///
+/// Succeeds only if every target a batch was routed to accepted the
+/// changes. With a single such target, its error is returned as is.
+#[derive(Debug)]
+pub struct FanOut<W> {
+    targets: Vec<Target<W>>,
+}
+
+impl<W> FanOut<W> {
+    /// Creates a sender without targets, which accepts everything.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            targets: Vec::new(),
+        }
+    }
+
+    /// Adds a target, named in logs and errors, receiving every change.
+    #[must_use]
+    pub fn with_target(mut self, name: impl Into<String>, sender: W) -> Self {
+        self.targets.push(Target {
+            name: name.into(),
+            sender,
+            route: AdapterRoute::default(),
+        });
+        self
+    }
+
+    /// Limits the target named `name` to the changes `route` matches.
+    /// Unknown names are ignored.
+    #[must_use]
+    pub fn with_route(mut self, name: &str, route: AdapterRoute) -> Self {
+        if let Some(target) = self.targets.iter_mut().find(|t| t.name == name) {
+            target.route = route;
+        }
+        self
+    }
+
+    /// Returns the targets with their names, in the order they were added.
+    pub fn targets(&self) -> impl Iterator<Item = (&str, &W)> {
+        self.targets
+            .iter()
+            .map(|target| (target.name.as_str(), &target.sender))
+    }
+
+    /// Splits `changes` by target route.
+    fn route<'c, T: Routed + Clone>(&self, changes: &'c [T]) -> Vec<Option<Cow<'c, [T]>>> {
+        self.targets
+            .iter()
+            .map(|target| target.route.select(changes))
+            .collect()
+    }
+}
+
+impl<W> Default for FanOut<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W> FromIterator<(String, W)> for FanOut<W> {
+    fn from_iter<I: IntoIterator<Item = (String, W)>>(iter: I) -> Self {
+        Self {
+            targets: iter
+                .into_iter()
+                .map(|(name, sender)| Target {
+                    name,
+                    sender,
+                    route: AdapterRoute::default(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<W: WebhookSender> FanOut<W> {
+    /// Runs `send` concurrently against every target with its batch (see
+    /// [`route`](Self::route)) and combines the outcomes.
+    async fn deliver<'a, T, F, Fut>(
+        &'a self,
+        batches: &'a [Option<Cow<'a, [T]>>],
+        send: F,
+    ) -> Result<(), WebhookError>
+    where
+        T: Clone + Sync,
+        F: Fn(&'a W, &'a [T]) -> Fut + Send,
+        Fut: Future<Output = Result<(), WebhookError>> + Send + 'a,
+    {
+        let (names, deliveries): (Vec<&str>, Vec<_>) = self
+            .targets
+            .iter()
+            .zip(batches)
+            .filter_map(|(target, batch)| {
+                let delivery = send(&target.sender, batch.as_deref()?);
+                Some((target.name.as_str(), delivery))
+            })
+            .unzip();
+        let outcomes = join_all(deliveries).await;
+
+        let total = outcomes.len();
+        let mut failures: Vec<_> = names
+            .into_iter()
+            .zip(outcomes)
+            .filter_map(|(name, outcome)| outcome.err().map(|e| (name.to_string(), e)))
+            .collect();
+        match failures.pop() {
+            None => Ok(()),
+            Some((_, error)) if total == 1 => Err(error),
+            Some(last) => {
+                failures.push(last);
+                Err(WebhookError::Targets { total, failures })
+            }
+        }
+    }
+
+    /// Routes `changes` and sends each target its batch as the event
+    /// `wrap` makes of it.
+    async fn deliver_event<T>(
+        &self,
+        changes: &[T],
+        wrap: for<'c> fn(&'c [T]) -> Event<'c>,
+    ) -> Result<(), WebhookError>
+    where
+        T: Routed + Clone + Sync,
+    {
+        let batches = self.route(changes);
+        self.deliver(&batches, |sender, batch| async move {
+            sender.send_event(&wrap(batch)).await
+        })
+        .await
+    }
+}
+
+impl<W: WebhookSender> WebhookSender for FanOut<W> {
+    async fn send(&self, changes: &[IpChange]) -> Result<(), WebhookError> {
+        let batches = self.route(changes);
+        self.deliver(&batches, W::send).await
+    }
+
+    async fn send_event(&self, event: &Event<'_>) -> Result<(), WebhookError> {
+        match *event {
+            Event::Dns(changes) => self.deliver_event(changes, |batch| Event::Dns(batch)).await,
+            Event::Drift(changes) => {
+                self.deliver_event(changes, |batch| Event::Drift(batch))
+                    .await
+            }
+            Event::Hostname(changes) => {
+                self.deliver_event(changes, |batch| Event::Hostname(batch))
+                    .await
+            }
+            Event::Storm(changes) => {
+                self.deliver_event(changes, |batch| Event::Storm(batch))
+                    .await
+            }
+            Event::Link(changes) => {
+                self.deliver_event(changes, |batch| Event::Link(batch))
+                    .await
+            }
+        }
+    }
+}
+
+/// Polls `futures` together until all are done; outputs keep their order.
+async fn join_all<F: Future>(futures: impl IntoIterator<Item = F>) -> Vec<F::Output> {
+    let mut pending: Vec<Option<Pin<Box<F>>>> =
+        futures.into_iter().map(|f| Some(Box::pin(f))).collect();
+    let mut outputs: Vec<Option<F::Output>> = pending.iter().map(|_| None).collect();
+
+    poll_fn(|cx| {
+        let mut done = true;
+        for (slot, output) in pending.iter_mut().zip(&mut outputs) {
+            if let Some(future) = slot {
+                match future.as_mut().poll(cx) {
+                    Poll::Ready(value) => {
+                        *output = Some(value);
+                        *slot = None;
+                    }
+                    Poll::Pending => done = false,
+                }
+            }
+        }
+        if done {
+            Poll::Ready(std::mem::take(&mut outputs).into_iter().flatten().collect())
+        } else {
+            Poll::Pending
+        }
+    })
+    .await
+}
@@ -0,0 +1,430 @@
+//! Tests for delivering to several targets.
+
+use super::{AdapterRoute, Event, FanOut, RetryableError, WebhookError, WebhookSender};
+use crate::monitor::{HostnameChange, IpChange};
+use regex::Regex;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tokio::sync::Barrier;
+
+/// How a [`Target`] answers.
+#[derive(Debug, Clone, Copy)]
+enum Answer {
+    Accept,
+    Fail,
+    Postpone,
+    Interrupt,
+}
+
+/// Sender counting its deliveries and answering as configured.
+#[derive(Debug)]
+struct Target {
+    answer: Answer,
+    sent: AtomicUsize,
+    /// Adapters of the changes received by `send`
+    adapters: Mutex<Vec<String>>,
+    /// Waited on before answering, to prove targets run concurrently
+    barrier: Option<Arc<Barrier>>,
+}
+
+impl Target {
+    fn new(answer: Answer) -> Self {
+        Self {
+            answer,
+            sent: AtomicUsize::new(0),
+            adapters: Mutex::new(Vec::new()),
+            barrier: None,
+        }
+    }
+
+    fn waiting_on(barrier: &Arc<Barrier>) -> Self {
+        Self {
+            barrier: Some(Arc::clone(barrier)),
+            ..Self::new(Answer::Accept)
+        }
+    }
+
+    async fn answer(&self) -> Result<(), WebhookError> {
+        self.sent.fetch_add(1, Ordering::SeqCst);
+        if let Some(ref barrier) = self.barrier {
+            barrier.wait().await;
+        }
+        match self.answer {
+            Answer::Accept => Ok(()),
+            Answer::Fail => Err(WebhookError::MaxRetriesExceeded {
+                attempts: 3,
+                last_error: RetryableError::NonSuccessStatus {
+                    status: http::StatusCode::BAD_GATEWAY,
+                    body: None,
+                    retry_after: None,
+                },
+            }),
+            Answer::Postpone => Err(WebhookError::Postponed {
+                pending: 1,
+                reason: "offline".to_string(),
+            }),
+            Answer::Interrupt => Err(WebhookError::Interrupted { attempts: 1 }),
+        }
+    }
+}
+
+impl WebhookSender for Target {
+    async fn send(&self, changes: &[IpChange]) -> Result<(), WebhookError> {
+        let adapters = changes.iter().map(|c| c.adapter.clone());
+        self.adapters.lock().unwrap().extend(adapters);
+        self.answer().await
+    }
+
+    async fn send_event(&self, _event: &Event<'_>) -> Result<(), WebhookError> {
+        self.answer().await
+    }
+}
+
+fn changes() -> Vec<IpChange> {
+    let address: IpAddr = "192.0.2.1".parse().unwrap();
+    vec![IpChange::added("eth0", address, SystemTime::UNIX_EPOCH)]
+}
+
+fn change(adapter: &str) -> IpChange {
+    let address: IpAddr = "192.0.2.1".parse().unwrap();
+    IpChange::added(adapter, address, SystemTime::UNIX_EPOCH)
+}
+
+fn route(patterns: &[&str]) -> AdapterRoute {
+    AdapterRoute::new(patterns.iter().map(|p| Regex::new(p).unwrap()).collect())
+}
+
+fn received(sender: &FanOut<Target>, name: &str) -> Vec<String> {
+    let (_, target) = sender.targets().find(|(n, _)| *n == name).unwrap();
+    target.adapters.lock().unwrap().clone()
+}
+
+fn fan_out(answers: &[Answer]) -> FanOut<Target> {
+    answers
+        .iter()
+        .enumerate()
+        .map(|(i, &answer)| (format!("t{i}"), Target::new(answer)))
+        .collect()
+}
+
+#[tokio::test]
+async fn sends_to_every_target() {
+    let sender = fan_out(&[Answer::Accept, Answer::Accept]);
+
+    sender.send(&changes()).await.unwrap();
+    sender.send_event(&Event::Dns(&[])).await.unwrap();
+
+    assert!(
+        sender
+            .targets()
+            .all(|(_, t)| t.sent.load(Ordering::SeqCst) == 2)
+    );
+}
+
+#[tokio::test]
+async fn sends_concurrently() {
+    // Neither target can finish before the other has started
+    let barrier = Arc::new(Barrier::new(2));
+    let sender = FanOut::new()
+        .with_target("a", Target::waiting_on(&barrier))
+        .with_target("b", Target::waiting_on(&barrier));
+
+    let result = tokio::time::timeout(Duration::from_secs(5), sender.send(&changes())).await;
+
+    assert!(matches!(result, Ok(Ok(()))));
+}
+
+#[tokio::test]
+async fn without_targets_accepts() {
+    let sender = FanOut::<Target>::new();
+
+    assert!(sender.send(&changes()).await.is_ok());
+}
+
+#[tokio::test]
+async fn single_target_error_is_returned_as_is() {
+    let sender = fan_out(&[Answer::Fail]);
+
+    let error = sender.send(&changes()).await.unwrap_err();
+
+    assert!(matches!(error, WebhookError::MaxRetriesExceeded { .. }));
+}
+
+#[tokio::test]
+async fn failures_are_combined_with_target_names() {
+    let sender = fan_out(&[Answer::Fail, Answer::Accept, Answer::Postpone]);
+
+    let error = sender.send(&changes()).await.unwrap_err();
+
+    let WebhookError::Targets {
+        total,
+        ref failures,
+    } = error
+    else {
+        panic!("unexpected error: {error:?}");
+    };
+    assert_eq!(total, 3);
+    let names: Vec<_> = failures.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["t0", "t2"]);
+    let message = error.to_string();
+    assert!(message.starts_with("2 of 3 targets failed"), "{message}");
+    // The cause of the retry summary is kept
+    assert!(
+        message.contains("t0: Failed after 3 attempts: HTTP 502"),
+        "{message}"
+    );
+    assert!(message.contains("t2: Delivery postponed"), "{message}");
+}
+
+#[tokio::test]
+async fn interrupted_if_any_target_was() {
+    let sender = fan_out(&[Answer::Accept, Answer::Interrupt]);
+
+    let error = sender.send(&changes()).await.unwrap_err();
+
+    assert!(error.is_interrupted());
+    assert!(!error.is_postponed());
+}
+
+#[tokio::test]
+async fn postponed_only_if_every_failure_was() {
+    let postponed = fan_out(&[Answer::Accept, Answer::Postpone, Answer::Postpone]);
+    let mixed = fan_out(&[Answer::Postpone, Answer::Fail]);
+
+    assert!(postponed.send(&changes()).await.unwrap_err().is_postponed());
+    assert!(!mixed.send(&changes()).await.unwrap_err().is_postponed());
+}
+
+mod routing {
+    use super::*;
+
+    fn routed() -> FanOut<Target> {
+        FanOut::new()
+            .with_target("lan", Target::new(Answer::Accept))
+            .with_target("vpn", Target::new(Answer::Fail))
+            .with_target("all", Target::new(Answer::Accept))
+            .with_route("lan", route(&["^eth"]))
+            .with_route("vpn", route(&["^wg", "^tun"]))
+    }
+
+    #[test]
+    fn route_matches_any_pattern() {
+        let route = route(&["^wg", "^tun"]);
+
+        assert!(route.matches("wg0"));
+        assert!(route.matches("tun1"));
+        assert!(!route.matches("eth0"));
+        assert!(AdapterRoute::default().matches("eth0"));
+        assert!(AdapterRoute::default().is_all());
+    }
+
+    #[tokio::test]
+    async fn targets_receive_only_matching_adapters() {
+        let sender = routed();
+
+        sender
+            .send(&[change("eth0"), change("eth1"), change("wlan0")])
+            .await
+            .unwrap();
+
+        assert_eq!(received(&sender, "lan"), ["eth0", "eth1"]);
+        assert_eq!(received(&sender, "all"), ["eth0", "eth1", "wlan0"]);
+    }
+
+    #[tokio::test]
+    async fn unmatched_targets_are_skipped() {
+        let sender = routed();
+
+        // The failing vpn target is not sent the eth0 change
+        assert!(sender.send(&[change("eth0")]).await.is_ok());
+        let sent = |name: &str| {
+            let (_, target) = sender.targets().find(|(n, _)| *n == name).unwrap();
+            target.sent.load(Ordering::SeqCst)
+        };
+        assert_eq!((sent("lan"), sent("vpn"), sent("all")), (1, 0, 1));
+    }
+
+    #[tokio::test]
+    async fn failures_count_routed_targets_only() {
+        let sender = routed();
+
+        let error = sender
+            .send(&[change("wg0"), change("eth0")])
+            .await
+            .unwrap_err();
+
+        let WebhookError::Targets { total, failures } = error else {
+            panic!("unexpected error: {error:?}");
+        };
+        assert_eq!(total, 3);
+        assert_eq!(failures[0].0, "vpn");
+        assert_eq!(received(&sender, "vpn"), ["wg0"]);
+    }
+
+    #[tokio::test]
+    async fn changes_without_adapter_go_everywhere() {
+        let sender = FanOut::new()
+            .with_target("lan", Target::new(Answer::Accept))
+            .with_route("lan", route(&["^eth"]));
+        let change = HostnameChange {
+            old: "a".to_string(),
+            new: "b".to_string(),
+            timestamp: SystemTime::UNIX_EPOCH,
+        };
+
+        sender
+            .send_event(&Event::Hostname(&[change]))
+            .await
+            .unwrap();
+
+        let (_, target) = sender.targets().next().unwrap();
+        assert_eq!(target.sent.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn unknown_route_names_are_ignored() {
+        let sender = fan_out(&[Answer::Accept]).with_route("missing", route(&["^wg"]));
+
+        sender.send(&[change("eth0")]).await.unwrap();
+
+        assert_eq!(received(&sender, "t0"), ["eth0"]);
+    }
+}
+
+mod version_routing {
+    use super::*;
+    use crate::network::IpVersion;
+
+    fn change_of(adapter: &str, address: &str) -> IpChange {
+        IpChange::added(adapter, address.parse().unwrap(), SystemTime::UNIX_EPOCH)
+    }
+
+    /// The main webhook with a dedicated IPv6 endpoint.
+    fn split() -> FanOut<Target> {
+        FanOut::new()
+            .with_target("main", Target::new(Answer::Accept))
+            .with_target("v6", Target::new(Answer::Accept))
+            .with_route("main", AdapterRoute::default().excluding(IpVersion::V6))
+            .with_route("v6", AdapterRoute::default().for_version(IpVersion::V6))
+    }
+
+    #[test]
+    fn version_routes_match_addresses() {
+        let v4 = Some("192.0.2.1".parse().unwrap());
+        let v6 = Some("2001:db8::1".parse().unwrap());
+        let only_v4 = AdapterRoute::default().for_version(IpVersion::V4);
+        let neither = AdapterRoute::default()
+            .excluding(IpVersion::V4)
+            .excluding(IpVersion::V6);
+
+        assert!(only_v4.matches_address(v4));
+        assert!(!only_v4.matches_address(v6));
+        assert!(!only_v4.matches_address(None));
+        assert!(!neither.matches_address(v4) && !neither.matches_address(v6));
+        assert!(neither.matches_address(None));
+        assert!(AdapterRoute::default().matches_address(v6));
+        assert!(only_v4.is_version_split() && !AdapterRoute::default().is_version_split());
+    }
+
+    #[tokio::test]
+    async fn address_changes_are_split_by_version() {
+        let sender = split();
+
+        sender
+            .send(&[
+                change_of("v4-a", "192.0.2.1"),
+                change_of("v6-a", "2001:db8::1"),
+                change_of("v4-b", "192.0.2.2"),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(received(&sender, "main"), ["v4-a", "v4-b"]);
+        assert_eq!(received(&sender, "v6"), ["v6-a"]);
+    }
+
+    #[tokio::test]
+    async fn changes_without_address_skip_version_targets() {
+        let sender = split();
+        let change = HostnameChange {
+            old: "a".to_string(),
+            new: "b".to_string(),
+            timestamp: SystemTime::UNIX_EPOCH,
+        };
+
+        sender
+            .send_event(&Event::Hostname(&[change]))
+            .await
+            .unwrap();
+
+        let sent = |name: &str| {
+            let (_, target) = sender.targets().find(|(n, _)| *n == name).unwrap();
+            target.sent.load(Ordering::SeqCst)
+        };
+        assert_eq!((sent("main"), sent("v6")), (1, 0));
+    }
+
+    #[tokio::test]
+    async fn version_and_adapter_routes_combine() {
+        let sender = FanOut::new()
+            .with_target("v4", Target::new(Answer::Accept))
+            .with_route("v4", route(&["^eth"]).for_version(IpVersion::V4));
+
+        sender
+            .send(&[
+                change_of("eth0", "192.0.2.1"),
+                change_of("eth0", "2001:db8::1"),
+                change_of("wg0", "192.0.2.2"),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(received(&sender, "v4"), ["eth0"]);
+    }
+}
+
+mod added_only {
+    use super::*;
+
+    fn removal(adapter: &str) -> IpChange {
+        let address: IpAddr = "192.0.2.9".parse().unwrap();
+        IpChange::removed(adapter, address, SystemTime::UNIX_EPOCH)
+    }
+
+    fn sender() -> FanOut<Target> {
+        FanOut::new()
+            .with_target("dns", Target::new(Answer::Accept))
+            .with_target("chat", Target::new(Answer::Accept))
+            .with_route("chat", AdapterRoute::default().with_added_only(true))
+    }
+
+    #[tokio::test]
+    async fn removals_are_left_out() {
+        let sender = sender();
+
+        sender
+            .send(&[change("eth0"), removal("eth1")])
+            .await
+            .unwrap();
+
+        assert_eq!(received(&sender, "dns"), ["eth0", "eth1"]);
+        assert_eq!(received(&sender, "chat"), ["eth0"]);
+    }
+
+    #[tokio::test]
+    async fn batches_of_removals_skip_the_target() {
+        let sender = sender();
+
+        sender.send(&[removal("eth0")]).await.unwrap();
+
+        let (_, chat) = sender.targets().find(|(n, _)| *n == "chat").unwrap();
+        assert_eq!(chat.sent.load(Ordering::SeqCst), 0);
+        assert!(
+            AdapterRoute::default()
+                .with_added_only(true)
+                .is_added_only()
+        );
+    }
+}
@@ -1,5 +1,7 @@
 //! HTTP request/response types and client trait.
 
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use super::HttpError;
 
 /// An HTTP request to be sent.
@@ -17,6 +19,8 @@ pub struct HttpRequest {
     pub headers: http::HeaderMap,
     /// Optional request body
     pub body: Option<Vec<u8>>,
+    /// Largest response body to accept, in bytes (`None`: unlimited)
+    pub max_response_len: Option<usize>,
 }
 
 impl HttpRequest {
@@ -30,6 +34,7 @@ impl HttpRequest {
             url,
             headers: http::HeaderMap::new(),
             body: None,
+            max_response_len: None,
         }
     }
 
@@ -52,6 +57,16 @@ impl HttpRequest {
         self
     }
 
+    /// Limits the response body to `limit` bytes.
+    ///
+    /// Larger responses fail with [`HttpError::ResponseTooLarge`] instead of
+    /// being buffered.
+    #[must_use]
+    pub const fn with_max_response_len(mut self, limit: usize) -> Self {
+        self.max_response_len = Some(limit);
+        self
+    }
+
     /// Adds a header to the request.
     ///
     /// If the header name already exists, the value is appended
@@ -99,6 +114,70 @@ impl HttpResponse {
     pub fn body_text(&self) -> Option<&str> {
         std::str::from_utf8(&self.body).ok()
     }
+
+    /// Returns the wait the `Retry-After` header asks for, counted from
+    /// `now` for an HTTP date; `None` if the header is absent or invalid.
+    #[must_use]
+    pub fn retry_after(&self, now: SystemTime) -> Option<Duration> {
+        let value = self
+            .headers
+            .get(http::header::RETRY_AFTER)?
+            .to_str()
+            .ok()?
+            .trim();
+        if let Ok(seconds) = value.parse::<u64>() {
+            return Some(Duration::from_secs(seconds));
+        }
+        // A date in the past asks for no wait
+        Some(
+            parse_http_date(value)?
+                .duration_since(now)
+                .unwrap_or_default(),
+        )
+    }
+}
+
+/// Parses an IMF-fixdate such as `Sun, 06 Nov 1994 08:49:37 GMT`, the
+/// HTTP date format servers must send.
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let mut parts = value.split_whitespace();
+    let (_weekday, day, month, year, time, zone) = (
+        parts.next()?,
+        parts.next()?,
+        parts.next()?,
+        parts.next()?,
+        parts.next()?,
+        parts.next()?,
+    );
+    if zone != "GMT" || parts.next().is_some() {
+        return None;
+    }
+    let day: u64 = day.parse().ok()?;
+    let month = MONTHS.iter().position(|m| *m == month)? + 1;
+    let year: u64 = year.parse().ok()?;
+    let mut clock = time.split(':').map(|part| part.parse::<u64>().ok());
+    let (hours, minutes, seconds) = (clock.next()??, clock.next()??, clock.next()??);
+    if !(1..=31).contains(&day) || year < 1970 || hours > 23 || minutes > 59 || seconds > 60 {
+        return None;
+    }
+
+    // Days since the epoch of a proleptic Gregorian date (Howard Hinnant's algorithm)
+    let (year, month) = if month <= 2 {
+        (year - 1, month as u64 + 9)
+    } else {
+        (year, month as u64 - 3)
+    };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = (era * 146_097 + day_of_era).checked_sub(719_468)?;
+
+    let secs = days * 86_400 + hours * 3_600 + minutes * 60 + seconds;
+    Some(UNIX_EPOCH + Duration::from_secs(secs))
 }
 
 /// Trait for making HTTP requests.
@@ -142,6 +221,8 @@ pub trait HttpClient: Send + Sync {
     /// - Network connection fails ([`HttpError::Connection`])
     /// - Request times out ([`HttpError::Timeout`])
     /// - URL is invalid ([`HttpError::InvalidUrl`])
+    /// - The response body exceeds the request's limit
+    ///   ([`HttpError::ResponseTooLarge`])
     fn request(
         &self,
         req: HttpRequest,
@@ -1,5 +1,7 @@
 //! Tests for HTTP request/response types.
 
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use super::{HttpClient, HttpError, HttpRequest, HttpResponse};
 
 mod http_request {
@@ -14,6 +16,7 @@ mod http_request {
         assert_eq!(req.url, url);
         assert!(req.headers.is_empty());
         assert!(req.body.is_none());
+        assert_eq!(req.max_response_len, None);
     }
 
     #[test]
@@ -41,6 +44,14 @@ mod http_request {
         assert_eq!(req.body, Some(body));
     }
 
+    #[test]
+    fn with_max_response_len_sets_limit() {
+        let url = url::Url::parse("https://example.com/").unwrap();
+        let req = HttpRequest::get(url).with_max_response_len(1024);
+
+        assert_eq!(req.max_response_len, Some(1024));
+    }
+
     #[test]
     fn with_header_adds_single_header() {
         let url = url::Url::parse("https://example.com/").unwrap();
@@ -185,6 +196,62 @@ mod http_response {
         assert_eq!(resp1.body, resp2.body);
     }
 
+    fn with_retry_after(value: &str) -> HttpResponse {
+        let mut headers = http::HeaderMap::new();
+        headers.insert(http::header::RETRY_AFTER, value.parse().unwrap());
+        HttpResponse::new(http::StatusCode::TOO_MANY_REQUESTS, headers, vec![])
+    }
+
+    /// 1994-11-06T08:49:37Z, the example date of RFC 9110.
+    fn rfc_example() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(784_111_777)
+    }
+
+    #[test]
+    fn retry_after_in_seconds() {
+        let resp = with_retry_after("120");
+
+        assert_eq!(
+            resp.retry_after(SystemTime::now()),
+            Some(Duration::from_secs(120))
+        );
+    }
+
+    #[test]
+    fn retry_after_as_http_date() {
+        let resp = with_retry_after("Sun, 06 Nov 1994 08:49:37 GMT");
+        let now = rfc_example() - Duration::from_secs(90);
+
+        assert_eq!(resp.retry_after(now), Some(Duration::from_secs(90)));
+    }
+
+    #[test]
+    fn retry_after_date_in_the_past_waits_nothing() {
+        let resp = with_retry_after("Sun, 06 Nov 1994 08:49:37 GMT");
+        let now = rfc_example() + Duration::from_secs(10);
+
+        assert_eq!(resp.retry_after(now), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn retry_after_absent_or_invalid() {
+        let absent = HttpResponse::new(http::StatusCode::OK, http::HeaderMap::new(), vec![]);
+
+        assert_eq!(absent.retry_after(SystemTime::now()), None);
+        for value in [
+            "soon",
+            "-5",
+            "Sunday, 06-Nov-94 08:49:37 GMT",
+            "Sun, 06 Nov 1994 08:49:37 CET",
+        ] {
+            assert_eq!(
+                with_retry_after(value).retry_after(SystemTime::now()),
+                None,
+                "{value}"
+            );
+        }
+    }
+
     #[test]
     fn debug_format_is_readable() {
         let resp = HttpResponse::new(http::StatusCode::OK, http::HeaderMap::new(), vec![]);
@@ -248,6 +315,13 @@ mod http_error {
         assert!(error.source().is_none());
     }
 
+    #[test]
+    fn response_too_large_displays_limit() {
+        let error = HttpError::ResponseTooLarge { limit: 1024 };
+
+        assert_eq!(error.to_string(), "Response body exceeds 1024 bytes");
+    }
+
     #[test]
     fn error_is_send_sync() {
         fn assert_send_sync<T: Send + Sync>() {}
@@ -0,0 +1,847 @@
+//! The HTTP implementation of [`WebhookSender`].
+
+use crate::monitor::{HostnameSource, IpChange, SystemHostname};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::time::{Clock, ShutdownToken, Sleeper, SystemClock, TokioSleeper};
+
+use super::auth::authorize;
+use super::echo;
+use super::payload::{Attempt, Event, Payload, PayloadData, render_headers};
+use super::sender::WebhookSender;
+use super::{
+    BodyAssertion, Captured, ChatPreset, DeliveryMode, EchoCheck, GoldenDir, HttpClient,
+    HttpRequest, HttpResponse, JsonAssertion, NONCE_HEADER, OAuth2, PayloadEncoding, PayloadFormat,
+    PreRequest, Provider, RetryOverrides, RetryPolicy, RetryableError, RetryingSender,
+    SAMPLE_NONCE, SCHEMA_HEADER, SCHEMA_VERSION, SharedUrl, Transport, WebhookError,
+};
+use http::header::CONTENT_TYPE;
+use http::{HeaderName, HeaderValue};
+
+/// Header carrying the 1-based attempt number of a delivery.
+pub const ATTEMPT_HEADER: HeaderName = HeaderName::from_static("x-attempt");
+
+/// Header carrying milliseconds elapsed since the first attempt of a delivery.
+pub const ELAPSED_HEADER: HeaderName = HeaderName::from_static("x-attempt-elapsed-ms");
+
+/// Header naming the event category of deliveries other than address changes.
+///
+/// Set to `dns` on DNS setting deliveries, `drift` on expected address
+/// drift deliveries, `hostname` on hostname deliveries, `storm` on IPv6
+/// address storm deliveries and `link` on link state deliveries; absent on
+/// address deliveries.
+pub const EVENT_HEADER: HeaderName = HeaderName::from_static("x-ddns-a-event");
+
+/// HTTP-based webhook sender with retry support.
+///
+/// Sends IP change notifications via HTTP requests, with configurable
+/// retry behavior using exponential backoff: its requests are a
+/// [`Transport`] under a [`RetryingSender`].
+///
+/// # Template Support
+///
+/// With the `templates` feature (on by default), the body can be templated
+/// using Handlebars syntax. Available variables:
+/// - `schema_version`: Payload schema version (see [`SCHEMA_VERSION`])
+/// - `changes`: Array of change objects, each with:
+///   - `adapter`: Adapter name
+///   - `address`: IP address string
+///   - `kind`: "added" or "removed"
+///   - `timestamp`: Unix timestamp (seconds)
+///   - `prefix_len`: On-link prefix length, only if known
+///   - `scope`: `global`, `link_local` or `loopback`
+///   - `state`: `preferred` or `deprecated`, only if known
+///   - `metadata`: The adapter's `mac`, `index`, `mtu`, `link_speed`,
+///     `dns_suffix` and `oper_status`, each only if known; absent if none is
+/// - `attempt`: 1-based attempt number
+/// - `elapsed_ms`: Milliseconds since the first attempt
+/// - `is_retry`: Whether this is a retry attempt
+/// - `nonce`: The attempt's nonce, only with an [`EchoCheck`]
+/// - `ttl`: The TTL hint in seconds, only with [`HttpWebhook::with_ttl`]
+///
+/// Templates can also use the helpers registered by `template_registry`,
+/// such as `{{json changes}}` and `{{first_added_v4 changes}}`.
+///
+/// # Header Templates
+///
+/// Header values holding Handlebars expressions, e.g. `X-Current-IP:
+/// {{first_added changes}}`, are rendered for every request with the same
+/// data as the body, values inserted as they are. A value that renders to
+/// an invalid header value fails the attempt.
+///
+/// # URL Templates
+///
+/// With [`HttpWebhook::with_url_template`], the URL of every request is
+/// rendered from a template with the same data as the body, e.g.
+/// `https://dyn.example.com/update?myip={{changes.0.address}}`. Values are
+/// percent-encoded as they are inserted (`{{{...}}}` inserts them as is);
+/// a template that does not render to a valid URL fails the attempt. The
+/// template replaces the URL given to [`HttpWebhook::new`] and any URL set
+/// through [`HttpWebhook::url_handle`].
+///
+/// # DNS Events
+///
+/// DNS setting changes are sent with [`EVENT_HEADER`] set to `dns` and a
+/// separate template (see [`HttpWebhook::with_dns_body_template`]) whose
+/// `dns_changes` array holds objects with `adapter`, `suffix`, `old_suffix`,
+/// `servers`, `old_servers` and `timestamp`; `attempt`, `elapsed_ms` and
+/// `is_retry` are available as above. Without that template the body is the
+/// same data as JSON, including `schema_version`.
+///
+/// # TTL Hint
+///
+/// With [`HttpWebhook::with_ttl`], address payloads carry `ttl`, and each
+/// added change also `expires`: its `timestamp` plus the TTL, after which
+/// downstream caches should consider the address stale. DNS and drift
+/// payloads carry neither.
+///
+/// # Drift Events
+///
+/// Drift from the expected addresses is sent with [`EVENT_HEADER`] set to
+/// `drift` and always as a default body: its `drift` array holds objects
+/// with `kind` (`missing` or `unexpected`), `address`, `adapter` (only for
+/// unexpected addresses), `resolved` and `timestamp`.
+///
+/// # Hostname Events
+///
+/// A renamed machine is reported with [`EVENT_HEADER`] set to `hostname`,
+/// also as a default body: its `hostname_changes` array holds objects with
+/// `hostname`, `old_hostname` and `timestamp`.
+///
+/// # Storm Events
+///
+/// An adapter with more IPv6 addresses than its limit is reported with
+/// [`EVENT_HEADER`] set to `storm`, also as a default body: its `storms`
+/// array holds objects with `adapter`, `count`, `limit`, `resolved`,
+/// `prefix_only` and `timestamp`.
+///
+/// # Link Events
+///
+/// An adapter going up or down is reported with [`EVENT_HEADER`] set to
+/// `link`, also as a default body: its `link_changes` array holds objects
+/// with `adapter`, `state` (`up` or `down`), `status` (the operational
+/// status, e.g. `lower_layer_down`) and `timestamp`.
+///
+/// # Default Bodies
+///
+/// Without a template, address deliveries carry the [`PayloadFormat`]
+/// document (`json-v1` unless set with
+/// [`HttpWebhook::with_payload_format`]). With
+/// [`HttpWebhook::with_payload_encoding`] they instead carry, like DNS
+/// deliveries, the template data in that [`PayloadEncoding`]. Either way
+/// the body gets a matching `Content-Type` (unless one is configured).
+///
+/// # Attempt Headers
+///
+/// Every attempt carries [`ATTEMPT_HEADER`] (`X-Attempt`) and [`ELAPSED_HEADER`]
+/// (`X-Attempt-Elapsed-Ms`) so receivers can deduplicate and diagnose retries,
+/// and [`SCHEMA_HEADER`] (`X-DDNS-A-Schema`) so they can check the payload
+/// schema before parsing it.
+/// [`RetryOverrides`] can further change the body template or URL on retries.
+///
+/// # Echo Check
+///
+/// With [`HttpWebhook::with_echo_check`], every attempt also carries a fresh
+/// nonce in [`NONCE_HEADER`] (and the payload's `nonce`); a 2xx response that
+/// does not echo it fails the attempt, which is then retried like a 5xx.
+///
+/// # Response Assertion
+///
+/// With [`HttpWebhook::with_json_assertion`], a 2xx response only succeeds
+/// if its JSON body passes the [`JsonAssertion`], catching APIs that report
+/// errors with a success status; with [`HttpWebhook::with_body_assertion`],
+/// only if its body matches the [`BodyAssertion`]'s regex. A failed
+/// assertion fails the delivery, unless the assertion is
+/// [retried](JsonAssertion::retried).
+///
+/// # Provider Presets
+///
+/// With [`HttpWebhook::with_provider`], address deliveries are sent as the
+/// [`Provider`]'s update requests instead (the URL being the API base), and
+/// a 2xx response only succeeds if the provider reports the update as
+/// accepted. DNS deliveries are dropped, as are batches without added
+/// addresses. Headers are applied as above.
+///
+/// # Chat Presets
+///
+/// With [`HttpWebhook::with_chat`], every delivery is sent as the
+/// [`ChatPreset`]'s readable message instead of a templated or default
+/// body, the URL usually being the preset's own [`url`](ChatPreset::url).
+///
+/// # Pre-Requests
+///
+/// With [`HttpWebhook::with_pre_request`], every attempt first sends a
+/// [`PreRequest`] and captures values from its response: templates see them
+/// as `pre.<name>`, and `{{pre.<name>}}` in a header value is replaced.
+/// A failed pre-request fails the attempt like a failed request; a missing
+/// capture fails the delivery. [Rendering](HttpWebhook::render) skips the
+/// pre-request, so captured values are empty there.
+///
+/// # `OAuth2`
+///
+/// With [`HttpWebhook::with_oauth2`], every request carries the [`OAuth2`]
+/// grant's bearer token, fetched before the first request and again once it
+/// expires. A 401 answer drops the token and the request is sent once more
+/// with a new one, within the same attempt. A failed token request fails
+/// the attempt like a failed request; an answer without a token fails the
+/// delivery. [Rendering](HttpWebhook::render) leaves the token out.
+///
+/// # Delivery Mode
+///
+/// With [`HttpWebhook::with_delivery_mode`] and [`DeliveryMode::PerChange`],
+/// every address change is delivered on its own, in batch order, for
+/// endpoints accepting one address per request; failures are combined into
+/// [`WebhookError::Changes`]. Other deliveries and [recording](Self#recording)
+/// keep their batches.
+///
+/// # Recording
+///
+/// With [`HttpWebhook::with_recorder`], nothing is sent: each address
+/// delivery is [rendered](HttpWebhook::render) and recorded as a golden
+/// case (see [`GoldenDir`]), and DNS and drift deliveries are dropped.
+///
+/// # Shutdown
+///
+/// With [`HttpWebhook::with_shutdown`], a triggered [`ShutdownToken`] ends a
+/// delivery between attempts, including during a retry delay, with
+/// [`WebhookError::Interrupted`]; no new attempt starts after shutdown.
+///
+/// # Type Parameters
+///
+/// - `H`: The HTTP client implementation
+/// - `S`: The sleeper implementation for retry delays (defaults to [`TokioSleeper`])
+/// - `C`: The clock used to measure elapsed time (defaults to [`SystemClock`])
+///
+/// # Example
+///
+/// ```
+/// use ddns_a::webhook::{HttpWebhook, ReqwestClient, RetryPolicy};
+/// use url::Url;
+///
+/// let webhook = HttpWebhook::new(
+///     ReqwestClient::new(),
+///     Url::parse("https://api.example.com/ddns").unwrap(),
+/// );
+/// ```
+#[derive(Debug)]
+pub struct HttpWebhook<H, S = TokioSleeper, C = SystemClock> {
+    inner: RetryingSender<HttpTransport<H>, S, C>,
+}
+
+/// The HTTP [`Transport`] of an [`HttpWebhook`].
+#[derive(Debug)]
+struct HttpTransport<H> {
+    client: H,
+    url: SharedUrl,
+    url_template: Option<String>,
+    method: http::Method,
+    headers: http::HeaderMap,
+    body_template: Option<String>,
+    dns_body_template: Option<String>,
+    payload_encoding: Option<PayloadEncoding>,
+    payload_format: PayloadFormat,
+    host: String,
+    provider: Option<Provider>,
+    chat: Option<ChatPreset>,
+    echo_check: EchoCheck,
+    json_assertion: Option<JsonAssertion>,
+    body_assertion: Option<BodyAssertion>,
+    retry_overrides: RetryOverrides,
+    recorder: Option<GoldenDir>,
+    ttl: Option<u32>,
+    pre_request: Option<PreRequest>,
+    oauth2: Option<OAuth2>,
+}
+
+impl<H> HttpWebhook<H, TokioSleeper, SystemClock> {
+    /// Creates a new HTTP webhook with default settings.
+    ///
+    /// Uses POST method, no custom headers, no body template,
+    /// default retry policy, and [`TokioSleeper`] for delays.
+    #[must_use]
+    pub fn new(client: H, url: url::Url) -> Self {
+        Self {
+            inner: RetryingSender::new(HttpTransport {
+                client,
+                url: SharedUrl::new(url),
+                url_template: None,
+                method: http::Method::POST,
+                headers: http::HeaderMap::new(),
+                body_template: None,
+                dns_body_template: None,
+                payload_encoding: None,
+                payload_format: PayloadFormat::default(),
+                host: SystemHostname.hostname().unwrap_or_default(),
+                provider: None,
+                chat: None,
+                echo_check: EchoCheck::Disabled,
+                json_assertion: None,
+                body_assertion: None,
+                retry_overrides: RetryOverrides::default(),
+                recorder: None,
+                ttl: None,
+                pre_request: None,
+                oauth2: None,
+            }),
+        }
+    }
+}
+
+impl<H, S, C> HttpWebhook<H, S, C> {
+    /// Sets a custom sleeper for retry delays.
+    ///
+    /// This is primarily useful for testing to avoid actual delays.
+    #[must_use]
+    pub fn with_sleeper<S2>(self, sleeper: S2) -> HttpWebhook<H, S2, C> {
+        HttpWebhook {
+            inner: self.inner.with_sleeper(sleeper),
+        }
+    }
+
+    /// Sets a custom clock for measuring elapsed time across attempts.
+    #[must_use]
+    pub fn with_clock<C2>(self, clock: C2) -> HttpWebhook<H, S, C2> {
+        HttpWebhook {
+            inner: self.inner.with_clock(clock),
+        }
+    }
+
+    /// Renders the URL of every request from `template` (see
+    /// [URL Templates](Self#url-templates)) instead of using the URL.
+    #[must_use]
+    pub fn with_url_template(mut self, template: impl Into<String>) -> Self {
+        self.inner.transport_mut().url_template = Some(template.into());
+        self
+    }
+
+    /// Sets the HTTP method.
+    #[must_use]
+    pub fn with_method(mut self, method: http::Method) -> Self {
+        self.inner.transport_mut().method = method;
+        self
+    }
+
+    /// Sets the HTTP headers.
+    #[must_use]
+    pub fn with_headers(mut self, headers: http::HeaderMap) -> Self {
+        self.inner.transport_mut().headers = headers;
+        self
+    }
+
+    /// Sets the body template (Handlebars syntax).
+    #[must_use]
+    pub fn with_body_template(mut self, template: impl Into<String>) -> Self {
+        self.inner.transport_mut().body_template = Some(template.into());
+        self
+    }
+
+    /// Sets the body template for DNS setting changes (Handlebars syntax).
+    #[must_use]
+    pub fn with_dns_body_template(mut self, template: impl Into<String>) -> Self {
+        self.inner.transport_mut().dns_body_template = Some(template.into());
+        self
+    }
+
+    /// Sets the encoding of default (non-templated) bodies.
+    ///
+    /// Address deliveries without a template carry no body unless an
+    /// encoding is set; DNS deliveries default to JSON.
+    #[must_use]
+    pub const fn with_payload_encoding(mut self, encoding: PayloadEncoding) -> Self {
+        self.inner.transport_mut().payload_encoding = Some(encoding);
+        self
+    }
+
+    /// Sets the default body of address deliveries without a template or
+    /// payload encoding (see [Default Bodies](Self#default-bodies)).
+    #[must_use]
+    pub const fn with_payload_format(mut self, format: PayloadFormat) -> Self {
+        self.inner.transport_mut().payload_format = format;
+        self
+    }
+
+    /// Sets the host name reported by [`PayloadFormat`] documents (default:
+    /// the system hostname).
+    #[must_use]
+    pub fn with_host(mut self, host: impl Into<String>) -> Self {
+        self.inner.transport_mut().host = host.into();
+        self
+    }
+
+    /// Sends address changes through a DNS provider's update API.
+    ///
+    /// The URL should be the provider's API base, usually
+    /// [`ProviderKind::endpoint`](super::ProviderKind::endpoint).
+    #[must_use]
+    pub fn with_provider(mut self, provider: Provider) -> Self {
+        self.inner.transport_mut().provider = Some(provider);
+        self
+    }
+
+    /// Sends every delivery as a chat message (see
+    /// [Chat Presets](Self#chat-presets)).
+    #[must_use]
+    pub fn with_chat(mut self, chat: ChatPreset) -> Self {
+        self.inner.transport_mut().chat = Some(chat);
+        self
+    }
+
+    /// Requires responses to echo a per-attempt nonce (see [Echo Check](Self#echo-check)).
+    #[must_use]
+    pub const fn with_echo_check(mut self, check: EchoCheck) -> Self {
+        self.inner.transport_mut().echo_check = check;
+        self
+    }
+
+    /// Requires 2xx responses to pass `assertion` (see
+    /// [Response Assertion](Self#response-assertion)).
+    #[must_use]
+    pub fn with_json_assertion(mut self, assertion: JsonAssertion) -> Self {
+        self.inner.transport_mut().json_assertion = Some(assertion);
+        self
+    }
+
+    /// Requires the bodies of 2xx responses to match `assertion` (see
+    /// [Response Assertion](Self#response-assertion)).
+    #[must_use]
+    pub fn with_body_assertion(mut self, assertion: BodyAssertion) -> Self {
+        self.inner.transport_mut().body_assertion = Some(assertion);
+        self
+    }
+
+    /// Sets the retry policy.
+    #[must_use]
+    pub fn with_retry_policy(self, policy: RetryPolicy) -> Self {
+        Self {
+            inner: self.inner.with_retry_policy(policy),
+        }
+    }
+
+    /// Sets request changes applied to retry attempts.
+    #[must_use]
+    pub fn with_retry_overrides(mut self, overrides: RetryOverrides) -> Self {
+        self.inner.transport_mut().retry_overrides = overrides;
+        self
+    }
+
+    /// Sets how address changes are split into deliveries (see
+    /// [Delivery Mode](Self#delivery-mode)).
+    #[must_use]
+    pub fn with_delivery_mode(self, mode: DeliveryMode) -> Self {
+        Self {
+            inner: self.inner.with_delivery_mode(mode),
+        }
+    }
+
+    /// Stops retrying once `shutdown` is triggered (see [Shutdown](Self#shutdown)).
+    #[must_use]
+    pub fn with_shutdown(self, shutdown: ShutdownToken) -> Self {
+        Self {
+            inner: self.inner.with_shutdown(shutdown),
+        }
+    }
+
+    /// Adds a TTL hint in seconds to address payloads (see
+    /// [TTL Hint](Self#ttl-hint)).
+    #[must_use]
+    pub const fn with_ttl(mut self, ttl: u32) -> Self {
+        self.inner.transport_mut().ttl = Some(ttl);
+        self
+    }
+
+    /// Sends `pre_request` before every attempt (see
+    /// [Pre-Requests](Self#pre-requests)).
+    #[must_use]
+    pub fn with_pre_request(mut self, pre_request: PreRequest) -> Self {
+        self.inner.transport_mut().pre_request = Some(pre_request);
+        self
+    }
+
+    /// Authorizes every request with a token of `oauth2` (see
+    /// [`OAuth2`](Self#oauth2)).
+    #[must_use]
+    pub fn with_oauth2(mut self, oauth2: OAuth2) -> Self {
+        self.inner.transport_mut().oauth2 = Some(oauth2);
+        self
+    }
+
+    /// Records address deliveries to `dir` instead of sending them (see
+    /// [Recording](Self#recording)).
+    #[must_use]
+    pub fn with_recorder(mut self, dir: GoldenDir) -> Self {
+        self.inner.transport_mut().recorder = Some(dir);
+        self
+    }
+
+    /// Returns the current URL.
+    #[must_use]
+    pub fn url(&self) -> url::Url {
+        self.inner.transport().url.get()
+    }
+
+    /// Returns a handle that replaces the URL of this sender (and its
+    /// subsequent requests) when set, e.g. after endpoint discovery.
+    #[must_use]
+    pub fn url_handle(&self) -> SharedUrl {
+        self.inner.transport().url.clone()
+    }
+
+    /// Returns the configured HTTP method.
+    #[must_use]
+    pub const fn method(&self) -> &http::Method {
+        &self.inner.transport().method
+    }
+
+    /// Returns the configured retry policy.
+    #[must_use]
+    pub const fn retry_policy(&self) -> &RetryPolicy {
+        self.inner.retry_policy()
+    }
+}
+
+impl<H: HttpClient, S: Sleeper, C: Clock> HttpWebhook<H, S, C> {
+    /// Renders the payload of a first attempt for `changes` without
+    /// sending it: the bodies of its requests, separated by newlines.
+    ///
+    /// Like `ddns-a template check`, the attempt has no elapsed time and an
+    /// echo check's nonce is [`SAMPLE_NONCE`], so the payload only depends
+    /// on the configuration and the changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a body cannot be rendered.
+    pub fn render(&self, changes: &[IpChange]) -> Result<Vec<u8>, WebhookError> {
+        let requests = self.preview(changes)?;
+        let bodies: Vec<&[u8]> = requests
+            .iter()
+            .map(|r| r.body.as_deref().unwrap_or_default())
+            .collect();
+        Ok(bodies.join(&b'\n'))
+    }
+
+    /// Builds the requests of a first attempt for `changes` without
+    /// sending them, as [`render`](Self::render) does.
+    ///
+    /// The pre-request is not sent, so `{{pre.*}}` placeholders render
+    /// empty (or stay as they are without the `templates` feature).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a body cannot be rendered.
+    pub fn preview(&self, changes: &[IpChange]) -> Result<Vec<HttpRequest>, WebhookError> {
+        let transport = self.inner.transport();
+        let attempt = Attempt {
+            number: 1,
+            elapsed: Duration::ZERO,
+        };
+        let nonce = transport.echo_check.is_enabled().then_some(SAMPLE_NONCE);
+        Ok(transport.build_requests(Payload::Ip(changes), attempt, nonce, &Captured::default())?)
+    }
+
+    async fn deliver(&self, payload: Payload<'_>) -> Result<(), WebhookError> {
+        if let Some(recorder) = &self.inner.transport().recorder {
+            // Recording replaces sending; other deliveries are dropped
+            if let Payload::Ip(changes) = payload {
+                let name = recorder.record(changes, &self.render(changes)?)?;
+                tracing::info!("Recorded payload {name} in {}", recorder.path().display());
+            }
+            return Ok(());
+        }
+        match payload {
+            Payload::Ip(changes) => self.inner.deliver_changes(changes).await,
+            _ => self.inner.deliver(payload).await,
+        }
+    }
+}
+
+impl<H> HttpTransport<H> {
+    /// Returns the body template for the given payload and attempt.
+    fn template_for(&self, payload: Payload<'_>, attempt: Attempt) -> Option<&String> {
+        match payload {
+            Payload::Ip(_) => self
+                .retry_overrides
+                .body_template
+                .as_ref()
+                .filter(|_| attempt.is_retry())
+                .or(self.body_template.as_ref()),
+            Payload::Dns(_) => self.dns_body_template.as_ref(),
+            Payload::Drift(_) | Payload::Hostname(_) | Payload::Storm(_) | Payload::Link(_) => None,
+        }
+    }
+
+    /// Returns the encoding of the default body, if the payload has one.
+    ///
+    /// Deliveries other than address changes always have one (JSON unless
+    /// configured otherwise); address deliveries only once an encoding is
+    /// configured, and otherwise carry their [`PayloadFormat`] document.
+    fn default_encoding(&self, payload: Payload<'_>) -> Option<PayloadEncoding> {
+        match payload {
+            Payload::Ip(_) => self.payload_encoding,
+            Payload::Dns(_)
+            | Payload::Drift(_)
+            | Payload::Hostname(_)
+            | Payload::Storm(_)
+            | Payload::Link(_) => Some(self.payload_encoding.unwrap_or_default()),
+        }
+    }
+
+    /// Builds the data templates render for the given payload and attempt.
+    fn data<'a>(
+        &self,
+        payload: Payload<'a>,
+        attempt: Attempt,
+        nonce: Option<&'a str>,
+        captured: &'a Captured,
+    ) -> PayloadData<'a> {
+        payload
+            .data(attempt)
+            .with_nonce(nonce)
+            .with_ttl(self.ttl)
+            .with_captured(captured)
+    }
+
+    /// Sets the templated body, or else the default body, on `request`.
+    fn set_body(
+        &self,
+        request: &mut HttpRequest,
+        payload: Payload<'_>,
+        attempt: Attempt,
+        nonce: Option<&str>,
+        captured: &Captured,
+    ) -> Result<(), RetryableError> {
+        let data = || self.data(payload, attempt, nonce, captured);
+        if let Some(chat) = &self.chat {
+            set_default_body(request, chat.body(payload, &self.host), "application/json");
+        } else if let Some(template) = self.template_for(payload, attempt) {
+            let body = data().render(template)?;
+            request.body = Some(body.into_bytes());
+        } else if let Some(encoding) = self.default_encoding(payload) {
+            let body = encoding.encode(&data()).map_err(RetryableError::Template)?;
+            set_default_body(request, body, encoding.content_type());
+        } else if let Payload::Ip(changes) = payload {
+            if let Some(body) = self.payload_format.document(changes, &self.host) {
+                set_default_body(request, body, "application/json");
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds the HTTP requests for the given payload and attempt: one
+    /// without a provider, and none or more with one.
+    fn build_requests(
+        &self,
+        payload: Payload<'_>,
+        attempt: Attempt,
+        nonce: Option<&str>,
+        captured: &Captured,
+    ) -> Result<Vec<HttpRequest>, RetryableError> {
+        let mut url = match &self.url_template {
+            Some(template) => self
+                .data(payload, attempt, nonce, captured)
+                .render_url(template)?,
+            None => self.url.get(),
+        };
+        if let Some((name, value)) = self.retry_overrides.query.as_ref() {
+            if attempt.is_retry() {
+                url.query_pairs_mut().append_pair(name, value);
+            }
+        }
+
+        let mut request = HttpRequest::new(self.method.clone(), url);
+
+        // Copy headers, rendering templated values
+        for (name, value) in &self.headers {
+            request.headers.append(name, value.clone());
+        }
+        render_headers(
+            &mut request.headers,
+            &self.data(payload, attempt, nonce, captured),
+        )?;
+        captured
+            .apply(&mut request.headers)
+            .map_err(|reason| RetryableError::Capture { reason })?;
+
+        // Attempt headers reflect this attempt, replacing any configured values
+        request
+            .headers
+            .insert(ATTEMPT_HEADER, HeaderValue::from(attempt.number));
+        request
+            .headers
+            .insert(ELAPSED_HEADER, HeaderValue::from(attempt.elapsed_ms()));
+        request
+            .headers
+            .insert(SCHEMA_HEADER, HeaderValue::from(SCHEMA_VERSION));
+        if let Some(event) = payload.event() {
+            request
+                .headers
+                .insert(EVENT_HEADER, HeaderValue::from_static(event));
+        }
+        if let Some(nonce) = nonce.and_then(|n| HeaderValue::from_str(n).ok()) {
+            request.headers.insert(NONCE_HEADER, nonce);
+        }
+
+        match (&self.provider, payload) {
+            (Some(provider), Payload::Ip(changes)) => Ok(provider.requests(&request, changes)),
+            // Providers only hold address records
+            (
+                Some(_),
+                Payload::Dns(_)
+                | Payload::Drift(_)
+                | Payload::Hostname(_)
+                | Payload::Storm(_)
+                | Payload::Link(_),
+            ) => Ok(Vec::new()),
+            (None, _) => {
+                self.set_body(&mut request, payload, attempt, nonce, captured)?;
+                Ok(vec![request])
+            }
+        }
+    }
+}
+
+/// Sets a default (non-templated) `body` of `content_type` on `request`.
+fn set_default_body(request: &mut HttpRequest, body: Vec<u8>, content_type: &'static str) {
+    request.body = Some(body);
+    // A configured Content-Type wins
+    request
+        .headers
+        .entry(CONTENT_TYPE)
+        .or_insert(HeaderValue::from_static(content_type));
+}
+
+impl<H: HttpClient> HttpTransport<H> {
+    /// Sends the pre-request, if any, and captures its values.
+    async fn run_pre_request(&self) -> Result<Captured, RetryableError> {
+        let Some(pre_request) = &self.pre_request else {
+            return Ok(Captured::default());
+        };
+        let response = self.client.request(pre_request.request()).await?;
+        if !response.is_success() {
+            return Err(status_error(&response));
+        }
+        pre_request
+            .capture(&response)
+            .map_err(|reason| RetryableError::Capture { reason })
+    }
+
+    /// Returns the cached `OAuth2` token, fetching a new one if there is none.
+    async fn oauth2_token(&self, oauth2: &OAuth2) -> Result<String, RetryableError> {
+        if let Some(token) = oauth2.cached(Instant::now()) {
+            return Ok(token);
+        }
+        let response = self.client.request(oauth2.token_request()).await?;
+        if !response.is_success() {
+            return Err(status_error(&response));
+        }
+        oauth2
+            .store(&response, Instant::now())
+            .map_err(|reason| RetryableError::Token { reason })
+    }
+
+    /// Executes `request` with the `OAuth2` token, if configured; a 401 drops
+    /// the token and sends the request once more with a new one.
+    async fn execute_authorized(
+        &self,
+        request: &HttpRequest,
+        nonce: Option<&str>,
+    ) -> Result<(), RetryableError> {
+        let Some(oauth2) = &self.oauth2 else {
+            return self.execute_request(request, nonce).await;
+        };
+        let mut request = request.clone();
+        let token = self.oauth2_token(oauth2).await?;
+        authorize(&mut request, &token);
+        match self.execute_request(&request, nonce).await {
+            Err(RetryableError::NonSuccessStatus { status, .. })
+                if status == http::StatusCode::UNAUTHORIZED =>
+            {
+                tracing::debug!("Webhook rejected the OAuth2 token, fetching a new one");
+                oauth2.invalidate(&token);
+                authorize(&mut request, &self.oauth2_token(oauth2).await?);
+                self.execute_request(&request, nonce).await
+            }
+            result => result,
+        }
+    }
+
+    /// Executes a single request attempt.
+    async fn execute_request(
+        &self,
+        request: &HttpRequest,
+        nonce: Option<&str>,
+    ) -> Result<(), RetryableError> {
+        let response = self.client.request(request.clone()).await?;
+
+        if response.is_success() {
+            if let Some(nonce) = nonce {
+                self.echo_check
+                    .verify(nonce, &response)
+                    .map_err(|reason| RetryableError::EchoMismatch { reason })?;
+            }
+            if let Some(assertion) = &self.json_assertion {
+                assertion
+                    .verify(&response)
+                    .map_err(|reason| RetryableError::AssertionFailed {
+                        reason,
+                        retryable: assertion.is_retryable(),
+                    })?;
+            }
+            if let Some(assertion) = &self.body_assertion {
+                assertion
+                    .verify(&response)
+                    .map_err(|reason| RetryableError::AssertionFailed {
+                        reason,
+                        retryable: assertion.is_retryable(),
+                    })?;
+            }
+            return self
+                .provider
+                .as_ref()
+                .map_or(Ok(()), |provider| provider.check(&response))
+                .map_err(|reason| RetryableError::Rejected { reason });
+        }
+
+        Err(status_error(&response))
+    }
+}
+
+/// The error of a non-2xx `response`, with the wait a 429 or 503 asks for
+/// in `Retry-After`.
+fn status_error(response: &HttpResponse) -> RetryableError {
+    let throttled = matches!(
+        response.status,
+        http::StatusCode::TOO_MANY_REQUESTS | http::StatusCode::SERVICE_UNAVAILABLE
+    );
+    RetryableError::NonSuccessStatus {
+        status: response.status,
+        body: response.body_text().map(ToString::to_string),
+        retry_after: throttled
+            .then(|| response.retry_after(SystemTime::now()))
+            .flatten(),
+    }
+}
+
+impl<H: HttpClient> Transport for HttpTransport<H> {
+    /// Sends the requests of one attempt in order, stopping at the first
+    /// failure; a retry sends all of them again.
+    async fn attempt(&self, payload: Payload<'_>, attempt: Attempt) -> Result<(), RetryableError> {
+        // Fresh per attempt, so a cached answer to an earlier one never matches
+        let nonce = self.echo_check.is_enabled().then(echo::nonce);
+        let captured = self.run_pre_request().await?;
+        let requests = self.build_requests(payload, attempt, nonce.as_deref(), &captured)?;
+        for request in &requests {
+            self.execute_authorized(request, nonce.as_deref()).await?;
+        }
+        Ok(())
+    }
+}
+
+impl<H: HttpClient, S: Sleeper, C: Clock> WebhookSender for HttpWebhook<H, S, C> {
+    async fn send(&self, changes: &[IpChange]) -> Result<(), WebhookError> {
+        self.deliver(Payload::Ip(changes)).await
+    }
+
+    async fn send_event(&self, event: &Event<'_>) -> Result<(), WebhookError> {
+        self.deliver(Payload::from(*event)).await
+    }
+}
@@ -1,9 +1,10 @@
-//! Tests for `WebhookSender` and `HttpWebhook`.
+//! Tests for delivering changes through `HttpWebhook`.
 
-use super::sender::{HttpWebhook, IsRetryable, WebhookSender};
-use super::{HttpClient, HttpError, HttpRequest, HttpResponse, RetryPolicy, RetryableError};
+use super::{
+    HttpClient, HttpError, HttpRequest, HttpResponse, HttpWebhook, RetryPolicy, WebhookSender,
+};
 use crate::monitor::IpChange;
-use crate::time::InstantSleeper;
+use crate::time::{InstantSleeper, Sleeper};
 use std::net::IpAddr;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -11,14 +12,14 @@ use std::time::{Duration, SystemTime};
 
 /// Mock HTTP client that returns a configurable sequence of responses.
 #[derive(Debug)]
-struct MockClient {
+pub(super) struct MockClient {
     responses: std::sync::Mutex<Vec<Result<HttpResponse, HttpError>>>,
     requests: std::sync::Mutex<Vec<HttpRequest>>,
     call_count: AtomicUsize,
 }
 
 impl MockClient {
-    fn new(responses: Vec<Result<HttpResponse, HttpError>>) -> Self {
+    pub(super) fn new(responses: Vec<Result<HttpResponse, HttpError>>) -> Self {
         Self {
             responses: std::sync::Mutex::new(responses),
             requests: std::sync::Mutex::new(Vec::new()),
@@ -26,7 +27,7 @@ impl MockClient {
         }
     }
 
-    fn success() -> Self {
+    pub(super) fn success() -> Self {
         Self::new(vec![Ok(HttpResponse::new(
             http::StatusCode::OK,
             http::HeaderMap::new(),
@@ -34,7 +35,7 @@ impl MockClient {
         ))])
     }
 
-    fn failing_then_success(failures: usize) -> Self {
+    pub(super) fn failing_then_success(failures: usize) -> Self {
         let mut responses = Vec::new();
         for _ in 0..failures {
             responses.push(Err(HttpError::Timeout));
@@ -47,7 +48,7 @@ impl MockClient {
         Self::new(responses)
     }
 
-    fn always_failing() -> Self {
+    pub(super) fn always_failing() -> Self {
         Self::new(vec![
             Err(HttpError::Timeout),
             Err(HttpError::Timeout),
@@ -57,11 +58,11 @@ impl MockClient {
         ])
     }
 
-    fn calls(&self) -> usize {
+    pub(super) fn calls(&self) -> usize {
         self.call_count.load(Ordering::SeqCst)
     }
 
-    fn captured_requests(&self) -> Vec<HttpRequest> {
+    pub(super) fn captured_requests(&self) -> Vec<HttpRequest> {
         self.requests.lock().unwrap().clone()
     }
 }
@@ -80,11 +81,11 @@ impl HttpClient for Arc<MockClient> {
     }
 }
 
-fn test_url() -> url::Url {
+pub(super) fn test_url() -> url::Url {
     url::Url::parse("https://example.com/webhook").unwrap()
 }
 
-fn test_changes() -> Vec<IpChange> {
+pub(super) fn test_changes() -> Vec<IpChange> {
     vec![IpChange::added(
         "eth0",
         "192.168.1.1".parse::<IpAddr>().unwrap(),
@@ -174,6 +175,23 @@ mod http_webhook_send {
         assert_eq!(requests[0].url.as_str(), "https://example.com/webhook");
     }
 
+    #[tokio::test]
+    async fn url_handle_redirects_later_requests() {
+        let client = Arc::new(MockClient::success());
+        let webhook = HttpWebhook::new(client.clone(), test_url());
+
+        webhook
+            .url_handle()
+            .set(url::Url::parse("https://discovered.example.com/hook").unwrap());
+        webhook.send(&test_changes()).await.unwrap();
+
+        let requests = client.captured_requests();
+        assert_eq!(
+            requests[0].url.as_str(),
+            "https://discovered.example.com/hook"
+        );
+    }
+
     #[tokio::test]
     async fn uses_configured_method() {
         let client = Arc::new(MockClient::success());
@@ -204,6 +222,7 @@ mod http_webhook_send {
         );
     }
 
+    #[cfg(feature = "templates")]
     #[tokio::test]
     async fn renders_body_template() {
         let client = Arc::new(MockClient::success());
@@ -283,6 +302,66 @@ mod http_webhook_retry {
         assert_eq!(client.calls(), 2);
     }
 
+    /// Records the delays it is asked to sleep, without sleeping.
+    #[derive(Default)]
+    struct RecordingSleeper(std::sync::Mutex<Vec<Duration>>);
+
+    impl Sleeper for &RecordingSleeper {
+        async fn sleep(&self, duration: Duration) {
+            self.0.lock().unwrap().push(duration);
+        }
+    }
+
+    fn answer(status: http::StatusCode, retry_after: &str) -> HttpResponse {
+        let mut headers = http::HeaderMap::new();
+        headers.insert(http::header::RETRY_AFTER, retry_after.parse().unwrap());
+        HttpResponse::new(status, headers, vec![])
+    }
+
+    #[tokio::test]
+    async fn waits_as_long_as_retry_after_asks() {
+        let client = MockClient::new(vec![
+            Ok(answer(http::StatusCode::TOO_MANY_REQUESTS, "30")),
+            Ok(answer(http::StatusCode::SERVICE_UNAVAILABLE, "3600")),
+            Ok(answer(http::StatusCode::OK, "30")),
+        ]);
+        let sleeper = RecordingSleeper::default();
+        let policy = RetryPolicy::new()
+            .with_max_attempts(3)
+            .with_initial_delay(Duration::from_secs(1))
+            .with_max_delay(Duration::from_secs(120));
+
+        let webhook = HttpWebhook::new(client, test_url())
+            .with_sleeper(&sleeper)
+            .with_retry_policy(policy);
+        webhook.send(&test_changes()).await.unwrap();
+
+        // The second wait is capped at max_delay
+        assert_eq!(
+            *sleeper.0.lock().unwrap(),
+            [Duration::from_secs(30), Duration::from_secs(120)]
+        );
+    }
+
+    #[tokio::test]
+    async fn retry_after_of_other_statuses_is_ignored() {
+        let client = MockClient::new(vec![
+            Ok(answer(http::StatusCode::INTERNAL_SERVER_ERROR, "30")),
+            Ok(answer(http::StatusCode::OK, "30")),
+        ]);
+        let sleeper = RecordingSleeper::default();
+        let policy = RetryPolicy::new()
+            .with_max_attempts(2)
+            .with_initial_delay(Duration::from_secs(1));
+
+        let webhook = HttpWebhook::new(client, test_url())
+            .with_sleeper(&sleeper)
+            .with_retry_policy(policy);
+        webhook.send(&test_changes()).await.unwrap();
+
+        assert_eq!(*sleeper.0.lock().unwrap(), [Duration::from_secs(1)]);
+    }
+
     #[tokio::test]
     async fn fails_after_max_retries() {
         let client = Arc::new(MockClient::always_failing());
@@ -439,224 +518,191 @@ mod http_webhook_error_handling {
     }
 }
 
-mod template_rendering {
+mod traits {
     use super::*;
 
-    #[tokio::test]
-    async fn renders_changes_array() {
-        let client = Arc::new(MockClient::success());
-        let template =
-            r#"[{{#each changes}}{"addr":"{{address}}"}{{#unless @last}},{{/unless}}{{/each}}]"#;
-
-        let changes = vec![
-            IpChange::added(
-                "eth0",
-                "192.168.1.1".parse().unwrap(),
-                SystemTime::UNIX_EPOCH,
-            ),
-            IpChange::removed("eth1", "10.0.0.1".parse().unwrap(), SystemTime::UNIX_EPOCH),
-        ];
-
-        let webhook = HttpWebhook::new(client.clone(), test_url()).with_body_template(template);
-        webhook.send(&changes).await.unwrap();
-
-        let requests = client.captured_requests();
-        let body = String::from_utf8(requests[0].body.clone().unwrap()).unwrap();
-        assert!(body.contains("192.168.1.1"));
-        assert!(body.contains("10.0.0.1"));
-    }
-
-    #[tokio::test]
-    async fn renders_change_kind() {
-        let client = Arc::new(MockClient::success());
-        let template = r"{{#each changes}}{{kind}}{{/each}}";
-
-        let changes = vec![
-            IpChange::added(
-                "eth0",
-                "192.168.1.1".parse().unwrap(),
-                SystemTime::UNIX_EPOCH,
-            ),
-            IpChange::removed("eth1", "10.0.0.1".parse().unwrap(), SystemTime::UNIX_EPOCH),
-        ];
-
-        let webhook = HttpWebhook::new(client.clone(), test_url()).with_body_template(template);
-        webhook.send(&changes).await.unwrap();
-
-        let requests = client.captured_requests();
-        let body = String::from_utf8(requests[0].body.clone().unwrap()).unwrap();
-        assert!(body.contains("added"));
-        assert!(body.contains("removed"));
+    #[test]
+    fn webhook_sender_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<HttpWebhook<MockClient>>();
     }
 
-    #[tokio::test]
-    async fn invalid_template_returns_error() {
+    #[test]
+    fn http_webhook_debug_is_readable() {
         let client = MockClient::success();
-        let template = "{{#if}}"; // Invalid Handlebars
-
-        let webhook = HttpWebhook::new(client, test_url()).with_body_template(template);
-        let result = webhook.send(&test_changes()).await;
+        let webhook = HttpWebhook::new(client, test_url());
+        let debug = format!("{webhook:?}");
 
-        assert!(result.is_err());
+        assert!(debug.contains("HttpWebhook"));
     }
 }
 
-mod is_retryable_trait {
+mod attempt_context {
     use super::*;
+    use crate::time::Clock;
+    use crate::webhook::{ATTEMPT_HEADER, ELAPSED_HEADER, RetryOverrides};
+    use std::sync::atomic::AtomicU64;
 
-    #[test]
-    fn connection_error_is_retryable() {
-        let error = HttpError::Connection(Box::new(std::io::Error::other("network")));
-        assert!(error.is_retryable());
+    /// Clock that advances by 1.5 seconds on every read.
+    #[derive(Default)]
+    struct SteppingClock {
+        reads: AtomicU64,
     }
 
-    #[test]
-    fn timeout_is_retryable() {
-        let error = HttpError::Timeout;
-        assert!(error.is_retryable());
+    impl Clock for SteppingClock {
+        fn now(&self) -> SystemTime {
+            let reads = self.reads.fetch_add(1, Ordering::SeqCst);
+            SystemTime::UNIX_EPOCH + Duration::from_millis(reads * 1500)
+        }
     }
 
-    #[test]
-    fn invalid_url_is_not_retryable() {
-        let error = HttpError::InvalidUrl("bad url".to_string());
-        assert!(!error.is_retryable());
+    fn header(request: &HttpRequest, name: &http::HeaderName) -> String {
+        request.headers[name].to_str().unwrap().to_string()
     }
 
-    #[test]
-    fn retryable_error_http_delegates() {
-        let error = RetryableError::Http(HttpError::Timeout);
-        assert!(error.is_retryable());
-
-        let error = RetryableError::Http(HttpError::InvalidUrl("bad".to_string()));
-        assert!(!error.is_retryable());
+    #[cfg(feature = "templates")]
+    fn body(request: &HttpRequest) -> String {
+        String::from_utf8(request.body.clone().unwrap()).unwrap()
     }
 
-    #[test]
-    fn status_500_is_retryable() {
-        let error = RetryableError::NonSuccessStatus {
-            status: http::StatusCode::INTERNAL_SERVER_ERROR,
-            body: None,
-        };
-        assert!(error.is_retryable());
+    fn retrying_webhook(
+        client: &Arc<MockClient>,
+    ) -> HttpWebhook<Arc<MockClient>, InstantSleeper, SteppingClock> {
+        HttpWebhook::new(client.clone(), test_url())
+            .with_sleeper(InstantSleeper)
+            .with_clock(SteppingClock::default())
+            .with_retry_policy(RetryPolicy::new().with_max_attempts(3))
     }
 
-    #[test]
-    fn status_503_is_retryable() {
-        let error = RetryableError::NonSuccessStatus {
-            status: http::StatusCode::SERVICE_UNAVAILABLE,
-            body: None,
-        };
-        assert!(error.is_retryable());
-    }
+    #[tokio::test]
+    async fn first_attempt_headers() {
+        let client = Arc::new(MockClient::success());
+        let webhook = HttpWebhook::new(client.clone(), test_url());
 
-    #[test]
-    fn status_429_is_retryable() {
-        let error = RetryableError::NonSuccessStatus {
-            status: http::StatusCode::TOO_MANY_REQUESTS,
-            body: None,
-        };
-        assert!(error.is_retryable());
-    }
+        webhook.send(&test_changes()).await.unwrap();
 
-    #[test]
-    fn status_408_is_retryable() {
-        let error = RetryableError::NonSuccessStatus {
-            status: http::StatusCode::REQUEST_TIMEOUT,
-            body: None,
-        };
-        assert!(error.is_retryable());
+        let requests = client.captured_requests();
+        assert_eq!(header(&requests[0], &ATTEMPT_HEADER), "1");
+        assert!(requests[0].headers.contains_key(ELAPSED_HEADER));
     }
 
-    #[test]
-    fn status_400_is_not_retryable() {
-        let error = RetryableError::NonSuccessStatus {
-            status: http::StatusCode::BAD_REQUEST,
-            body: None,
-        };
-        assert!(!error.is_retryable());
-    }
+    #[tokio::test]
+    async fn headers_track_each_attempt() {
+        let client = Arc::new(MockClient::failing_then_success(2));
+        let webhook = retrying_webhook(&client);
 
-    #[test]
-    fn status_404_is_not_retryable() {
-        let error = RetryableError::NonSuccessStatus {
-            status: http::StatusCode::NOT_FOUND,
-            body: None,
-        };
-        assert!(!error.is_retryable());
-    }
+        webhook.send(&test_changes()).await.unwrap();
 
-    #[test]
-    fn template_error_is_not_retryable() {
-        let error = RetryableError::Template("bad template".to_string());
-        assert!(!error.is_retryable());
+        let requests = client.captured_requests();
+        let attempts: Vec<String> = requests
+            .iter()
+            .map(|r| header(r, &ATTEMPT_HEADER))
+            .collect();
+        let elapsed: Vec<String> = requests
+            .iter()
+            .map(|r| header(r, &ELAPSED_HEADER))
+            .collect();
+        assert_eq!(attempts, ["1", "2", "3"]);
+        assert_eq!(elapsed, ["1500", "3000", "4500"]);
     }
-}
 
-mod error_display {
-    use super::*;
-    use std::error::Error;
+    #[tokio::test]
+    async fn attempt_header_replaces_configured_value() {
+        let client = Arc::new(MockClient::success());
+        let mut headers = http::HeaderMap::new();
+        headers.insert(ATTEMPT_HEADER, http::HeaderValue::from_static("stale"));
+        let webhook = HttpWebhook::new(client.clone(), test_url()).with_headers(headers);
 
-    #[test]
-    fn retryable_error_http_displays_source() {
-        let error = RetryableError::Http(HttpError::Timeout);
-        assert!(error.to_string().contains("timed out"));
-    }
+        webhook.send(&test_changes()).await.unwrap();
 
-    #[test]
-    fn retryable_error_non_success_status_displays_code_and_body() {
-        let error = RetryableError::NonSuccessStatus {
-            status: http::StatusCode::INTERNAL_SERVER_ERROR,
-            body: Some("Internal error".to_string()),
-        };
-        assert!(error.to_string().contains("500"));
-        assert!(error.to_string().contains("Internal error"));
+        let requests = client.captured_requests();
+        assert_eq!(
+            requests[0].headers.get_all(ATTEMPT_HEADER).iter().count(),
+            1
+        );
+        assert_eq!(header(&requests[0], &ATTEMPT_HEADER), "1");
     }
 
-    #[test]
-    fn retryable_error_non_success_status_without_body() {
-        let error = RetryableError::NonSuccessStatus {
-            status: http::StatusCode::INTERNAL_SERVER_ERROR,
-            body: None,
-        };
-        let display = error.to_string();
-        assert!(display.contains("500"));
-        assert!(display.contains("<no body>"));
+    #[cfg(feature = "templates")]
+    #[tokio::test]
+    async fn template_exposes_attempt_variables() {
+        let client = Arc::new(MockClient::failing_then_success(1));
+        let webhook = retrying_webhook(&client).with_body_template(
+            "{{attempt}}/{{elapsed_ms}}/{{#if is_retry}}retry{{else}}first{{/if}}",
+        );
+
+        webhook.send(&test_changes()).await.unwrap();
+
+        let requests = client.captured_requests();
+        assert_eq!(body(&requests[0]), "1/1500/first");
+        assert_eq!(body(&requests[1]), "2/3000/retry");
     }
 
-    #[test]
-    fn webhook_error_max_retries_displays_attempts() {
-        let error = super::super::WebhookError::MaxRetriesExceeded {
-            attempts: 5,
-            last_error: RetryableError::Http(HttpError::Timeout),
-        };
-        assert!(error.to_string().contains("5 attempts"));
+    #[cfg(feature = "templates")]
+    #[tokio::test]
+    async fn retry_template_used_only_on_retries() {
+        let client = Arc::new(MockClient::failing_then_success(2));
+        let webhook = retrying_webhook(&client)
+            .with_body_template("first")
+            .with_retry_overrides(RetryOverrides::new().with_body_template("retry {{attempt}}"));
+
+        webhook.send(&test_changes()).await.unwrap();
+
+        let bodies: Vec<String> = client.captured_requests().iter().map(body).collect();
+        assert_eq!(bodies, ["first", "retry 2", "retry 3"]);
     }
 
-    #[test]
-    fn webhook_error_max_retries_has_source() {
-        let error = super::super::WebhookError::MaxRetriesExceeded {
-            attempts: 3,
-            last_error: RetryableError::Http(HttpError::Timeout),
-        };
-        assert!(error.source().is_some());
+    #[cfg(feature = "templates")]
+    #[tokio::test]
+    async fn retry_template_without_regular_template() {
+        let client = Arc::new(MockClient::failing_then_success(1));
+        let webhook = retrying_webhook(&client)
+            .with_retry_overrides(RetryOverrides::new().with_body_template("retry"));
+
+        webhook.send(&test_changes()).await.unwrap();
+
+        let requests = client.captured_requests();
+        assert!(body(&requests[0]).starts_with(r#"{"version":1,"#));
+        assert_eq!(body(&requests[1]), "retry");
     }
-}
 
-mod traits {
-    use super::*;
+    #[tokio::test]
+    async fn retry_query_appended_only_on_retries() {
+        let client = Arc::new(MockClient::failing_then_success(2));
+        let webhook = retrying_webhook(&client)
+            .with_retry_overrides(RetryOverrides::new().with_query("retry", "true"));
 
-    #[test]
-    fn webhook_sender_is_send_sync() {
-        fn assert_send_sync<T: Send + Sync>() {}
-        assert_send_sync::<HttpWebhook<MockClient>>();
+        webhook.send(&test_changes()).await.unwrap();
+
+        let urls: Vec<String> = client
+            .captured_requests()
+            .iter()
+            .map(|r| r.url.to_string())
+            .collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/webhook",
+                "https://example.com/webhook?retry=true",
+                "https://example.com/webhook?retry=true",
+            ]
+        );
     }
 
-    #[test]
-    fn http_webhook_debug_is_readable() {
-        let client = MockClient::success();
-        let webhook = HttpWebhook::new(client, test_url());
-        let debug = format!("{webhook:?}");
+    #[tokio::test]
+    async fn retry_query_keeps_existing_query() {
+        let client = Arc::new(MockClient::failing_then_success(1));
+        let url = url::Url::parse("https://example.com/update?host=home").unwrap();
+        let webhook = HttpWebhook::new(client.clone(), url)
+            .with_sleeper(InstantSleeper)
+            .with_retry_overrides(RetryOverrides::new().with_query("retry", "true"));
 
-        assert!(debug.contains("HttpWebhook"));
+        webhook.send(&test_changes()).await.unwrap();
+
+        let requests = client.captured_requests();
+        assert_eq!(
+            requests[1].url.as_str(),
+            "https://example.com/update?host=home&retry=true"
+        );
     }
 }
@@ -6,25 +6,133 @@
 //! - Abstracting HTTP clients ([`HttpClient`])
 //! - Production HTTP client implementation ([`ReqwestClient`])
 //! - Webhook sending with retries ([`WebhookSender`], [`HttpWebhook`])
-//! - Retry policy configuration ([`RetryPolicy`])
+//! - Pre-requests whose responses feed the main request ([`PreRequest`])
+//! - Delivering to several targets at once, routed by adapter ([`FanOut`])
+//! - Retrying deliveries over any backend ([`Transport`], [`RetryingSender`])
+//! - Running a local command on every delivery ([`ExecSender`])
+//! - Publishing deliveries to an MQTT broker ([`MqttSender`])
+//! - Emailing deliveries through an SMTP server ([`EmailSender`])
+//! - A versioned payload schema ([`SCHEMA_VERSION`], [`SCHEMA_HEADER`])
+//! - JSON, YAML or CBOR default bodies ([`PayloadEncoding`])
+//! - A frozen, versioned default address document ([`PayloadFormat`])
+//! - Presets for DNS provider update APIs ([`Provider`])
+//! - Readable chat messages for Telegram, Discord and Slack ([`ChatPreset`])
+//! - Checking that receivers echo a per-attempt nonce ([`EchoCheck`])
+//! - Asserting a field of JSON delivery responses ([`JsonAssertion`]) or a
+//!   regex on their bodies ([`BodyAssertion`])
+//! - Retry policy configuration ([`RetryPolicy`], with [`JitterMode`] delays)
+//! - Audit logging of outbound requests ([`AuditedClient`])
+//! - Postponing delivery while the webhook host is unreachable ([`ConnectivityGate`])
+//! - Skipping deliveries to a failing webhook for a cooldown ([`CircuitBreaker`])
+//! - Recording and pinning webhook TLS certificates ([`CertificateWatch`])
+//! - Custom CA roots, client certificates and insecure mode ([`TlsSettings`])
+//! - Authenticating with `OAuth2` client-credentials tokens ([`OAuth2`])
+//! - Identifying request headers such as `User-Agent` ([`MetadataClient`])
+//! - Discovering the webhook URL from a DNS TXT/SRV record ([`resolve_endpoint`])
+//! - Resolving outbound hosts through configured nameservers ([`NameserverResolver`])
+//! - Smoke-testing configured targets with a synthetic change ([`run_smoke_test`])
+//! - Checking body templates against sample changes ([`check_template`])
+//! - Recording payloads as golden files and verifying them ([`GoldenDir`])
 
+mod assertion;
+mod auth;
+mod chain;
+mod circuit;
 mod client;
+mod connectivity;
+mod echo;
+mod endpoint;
 mod error;
+mod fanout;
 mod http;
+mod http_webhook;
+mod payload;
+mod provider;
 mod retry;
 mod sender;
+mod smoke;
+mod transport;
 
+#[cfg(test)]
+mod assertion_tests;
+#[cfg(test)]
+mod auth_tests;
+#[cfg(test)]
+mod chain_tests;
+#[cfg(test)]
+mod circuit_tests;
 #[cfg(test)]
 mod client_tests;
 #[cfg(test)]
+mod connectivity_tests;
+#[cfg(test)]
+mod echo_tests;
+#[cfg(test)]
+mod endpoint_tests;
+#[cfg(test)]
+mod error_tests;
+#[cfg(test)]
+mod fanout_tests;
+#[cfg(test)]
 mod http_tests;
 #[cfg(test)]
+mod http_webhook_tests;
+#[cfg(test)]
+mod payload_tests;
+#[cfg(test)]
+mod provider_tests;
+#[cfg(test)]
+mod rendering_tests;
+#[cfg(test)]
 mod retry_tests;
 #[cfg(test)]
-mod sender_tests;
+mod smoke_tests;
+#[cfg(test)]
+mod transport_tests;
 
-pub use client::ReqwestClient;
+pub use assertion::{BodyAssertion, JsonAssertion, JsonPath};
+pub use auth::{ClientAuth, OAuth2};
+pub use chain::{Capture, CaptureSource, Captured, PreRequest, placeholders};
+pub use circuit::{
+    Circuit, CircuitBreaker, CircuitBreakers, CircuitPolicy, CircuitState,
+    DEFAULT_CIRCUIT_COOLDOWN, DEFAULT_FAILURE_THRESHOLD,
+};
+pub use client::{
+    AuditConfig, AuditOutcome, AuditRecord, AuditSink, AuditedClient, CertificateChange,
+    CertificateWatch, ClientIdentity, ConnectionSettings, DEFAULT_CONNECT_TIMEOUT,
+    DEFAULT_POOL_IDLE_TIMEOUT, DEFAULT_POOL_MAX_IDLE_PER_HOST, DEFAULT_REQUEST_TIMEOUT,
+    DEFAULT_USER_AGENT, FileAuditSink, Fingerprint, HOST_HEADER, INTERFACE_BINDING_SUPPORTED,
+    MetadataClient, NameserverResolver, REDACTED, Redaction, RequestMetadata, ReqwestClient,
+    TlsSettings, VERSION_HEADER, tls_config,
+};
+pub use connectivity::{
+    ConnectivityCheck, ConnectivityGate, ConnectivityProbe, FamilyProbe, HostResolver,
+    Reachability, SystemResolver,
+};
+pub use echo::{EchoCheck, NONCE_HEADER};
+pub use endpoint::{
+    EndpointDiscovery, EndpointError, EndpointRecord, SharedUrl, refresh_endpoint, resolve_endpoint,
+};
 pub use error::{HttpError, RetryableError, WebhookError};
+pub use fanout::{AdapterRoute, FanOut};
 pub use http::{HttpClient, HttpRequest, HttpResponse};
-pub use retry::RetryPolicy;
-pub use sender::{HttpWebhook, IsRetryable, WebhookSender};
+pub use http_webhook::{ATTEMPT_HEADER, ELAPSED_HEADER, EVENT_HEADER, HttpWebhook};
+pub use payload::{
+    Attempt, CHANGES_SUFFIX, Event, GOLDEN_SUFFIX, GoldenCase, GoldenDir, GoldenError, Payload,
+    PayloadData, PayloadEncoding, PayloadFormat, SAMPLE_NONCE, SAMPLE_TTL, SCHEMA_HEADER,
+    SCHEMA_VERSION, check_template, sample_changes, sample_json,
+};
+#[cfg(feature = "templates")]
+pub use payload::{template_registry, url_template_registry};
+pub use provider::{DEFAULT_PROVIDER_TTL, Provider, ProviderKind};
+pub use retry::{JitterMode, OverlapCheck, RetryOverlap, RetryOverrides, RetryPolicy};
+pub use sender::WebhookSender;
+pub use smoke::{
+    Exchange, ExchangeLog, LoggingClient, SMOKE_TEST_ADAPTER, TargetReport, format_report_table,
+    format_request, format_response, run_smoke_test, synthetic_changes,
+};
+pub use transport::{
+    ChatPreset, ChatService, DEFAULT_EMAIL_TIMEOUT, DEFAULT_EXEC_TIMEOUT, DEFAULT_MQTT_TIMEOUT,
+    DeliveryMode, EmailSender, ExecAction, ExecInput, ExecSender, IsRetryable, MqttPublisher,
+    MqttQos, MqttSender, RetryingSender, SmtpMailer, Transport,
+};
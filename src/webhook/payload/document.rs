@@ -0,0 +1,96 @@
+//! The structured default body of address deliveries (`webhook.payload_format`).
+//!
+//! Without a body template or [`PayloadEncoding`](crate::webhook::PayloadEncoding),
+//! address deliveries carry a [`PayloadFormat`] document:
+//!
+//! ```json
+//! {"version":1,"changes":[{"adapter":"eth0","address":"192.0.2.1","kind":"added","timestamp_rfc3339":"2024-05-01T12:00:00Z"}],"host":"nas"}
+//! ```
+//!
+//! Unlike the template variables, whose schema only grows, a format is
+//! frozen once released: a changed document will be a new format that
+//! configurations opt into, so receivers written for `json-v1` keep working.
+
+use std::time::SystemTime;
+
+use serde::Serialize;
+
+use crate::monitor::IpChange;
+use crate::time::format_rfc3339;
+
+/// Default body of address deliveries without a template.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PayloadFormat {
+    /// No body.
+    None,
+    /// The version 1 JSON document described in the module docs.
+    #[default]
+    JsonV1,
+}
+
+impl PayloadFormat {
+    /// Returns the configuration name of the format.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::JsonV1 => "json-v1",
+        }
+    }
+
+    /// Builds the document for `changes` detected on `host`, or none for
+    /// [`PayloadFormat::None`].
+    ///
+    /// # Panics
+    ///
+    /// Never panics: documents hold only strings and numbers.
+    #[must_use]
+    pub fn document(self, changes: &[IpChange], host: &str) -> Option<Vec<u8>> {
+        match self {
+            Self::None => None,
+            Self::JsonV1 => {
+                let document = DocumentV1 {
+                    version: 1,
+                    changes: changes.iter().map(ChangeV1::from).collect(),
+                    host,
+                };
+                Some(serde_json::to_vec(&document).expect("documents always serialize"))
+            }
+        }
+    }
+}
+
+/// The `json-v1` document; its fields must never change.
+#[derive(Debug, Serialize)]
+struct DocumentV1<'a> {
+    version: u32,
+    changes: Vec<ChangeV1<'a>>,
+    host: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct ChangeV1<'a> {
+    adapter: &'a str,
+    address: String,
+    kind: &'static str,
+    timestamp_rfc3339: String,
+}
+
+impl<'a> From<&'a IpChange> for ChangeV1<'a> {
+    fn from(change: &'a IpChange) -> Self {
+        let secs = change
+            .timestamp
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Self {
+            adapter: &change.adapter,
+            address: change.address.to_string(),
+            kind: if change.is_added() {
+                "added"
+            } else {
+                "removed"
+            },
+            timestamp_rfc3339: format_rfc3339(secs),
+        }
+    }
+}
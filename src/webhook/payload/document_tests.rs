@@ -0,0 +1,68 @@
+//! Tests for the structured default body.
+
+use std::time::{Duration, SystemTime};
+
+use serde_json::{Value, json};
+
+use crate::monitor::IpChange;
+use crate::webhook::PayloadFormat;
+
+fn changes() -> Vec<IpChange> {
+    let at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_714_564_800);
+    vec![
+        IpChange::added("eth0", "192.0.2.1".parse().unwrap(), at),
+        IpChange::removed("eth0", "2001:db8::1".parse().unwrap(), at),
+    ]
+}
+
+#[test]
+fn json_v1_is_the_default() {
+    assert_eq!(PayloadFormat::default(), PayloadFormat::JsonV1);
+    assert_eq!(PayloadFormat::JsonV1.as_str(), "json-v1");
+    assert_eq!(PayloadFormat::None.as_str(), "none");
+}
+
+#[test]
+fn json_v1_document_is_frozen() {
+    let body = PayloadFormat::JsonV1.document(&changes(), "nas").unwrap();
+
+    let document: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        document,
+        json!({
+            "version": 1,
+            "changes": [
+                {
+                    "adapter": "eth0",
+                    "address": "192.0.2.1",
+                    "kind": "added",
+                    "timestamp_rfc3339": "2024-05-01T12:00:00Z"
+                },
+                {
+                    "adapter": "eth0",
+                    "address": "2001:db8::1",
+                    "kind": "removed",
+                    "timestamp_rfc3339": "2024-05-01T12:00:00Z"
+                }
+            ],
+            "host": "nas"
+        })
+    );
+}
+
+#[test]
+fn json_v1_keeps_the_field_order() {
+    let body = PayloadFormat::JsonV1
+        .document(&changes()[..1], "nas")
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8(body).unwrap(),
+        r#"{"version":1,"changes":[{"adapter":"eth0","address":"192.0.2.1","kind":"added","timestamp_rfc3339":"2024-05-01T12:00:00Z"}],"host":"nas"}"#
+    );
+}
+
+#[test]
+fn none_has_no_body() {
+    assert_eq!(PayloadFormat::None.document(&changes(), "nas"), None);
+}
@@ -0,0 +1,187 @@
+//! Encodings for default (non-templated) webhook bodies.
+//!
+//! Embedded receivers often prefer CBOR and configuration-management systems
+//! YAML. [`PayloadEncoding`] writes the payload model in either, or JSON.
+//! Both are produced from the payload's JSON value (keys in sorted order) by
+//! small writers covering what JSON can hold.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Encoding of default webhook bodies.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PayloadEncoding {
+    /// JSON (`application/json`).
+    #[default]
+    Json,
+    /// YAML (`application/yaml`); strings are always double-quoted.
+    Yaml,
+    /// CBOR (`application/cbor`, RFC 8949) with definite-length items.
+    Cbor,
+}
+
+impl PayloadEncoding {
+    /// Returns the `Content-Type` of bodies in this encoding.
+    #[must_use]
+    pub const fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Yaml => "application/yaml",
+            Self::Cbor => "application/cbor",
+        }
+    }
+
+    /// Encodes `data`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the serializer's message if `data` cannot be represented as
+    /// JSON (e.g. a map with non-string keys).
+    pub fn encode(self, data: &impl Serialize) -> Result<Vec<u8>, String> {
+        if self == Self::Json {
+            // Serialized directly to keep the model's field order
+            return serde_json::to_vec(data).map_err(|e| e.to_string());
+        }
+
+        let value = serde_json::to_value(data).map_err(|e| e.to_string())?;
+        if self == Self::Yaml {
+            let mut yaml = yaml_lines(&value).join("\n");
+            yaml.push('\n');
+            return Ok(yaml.into_bytes());
+        }
+
+        let mut cbor = Vec::new();
+        write_cbor(&value, &mut cbor);
+        Ok(cbor)
+    }
+}
+
+/// Lines of the YAML block for `value`, without leading indentation.
+fn yaml_lines(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) if !items.is_empty() => items
+            .iter()
+            .flat_map(|item| prefixed(yaml_lines(item), "- "))
+            .collect(),
+        Value::Object(map) if !map.is_empty() => yaml_mapping(map),
+        scalar => vec![yaml_scalar(scalar)],
+    }
+}
+
+fn yaml_mapping(map: &Map<String, Value>) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (key, value) in map {
+        let key = yaml_key(key);
+        if is_block(value) {
+            lines.push(format!("{key}:"));
+            lines.extend(
+                yaml_lines(value)
+                    .into_iter()
+                    .map(|line| format!("  {line}")),
+            );
+        } else {
+            lines.push(format!("{key}: {}", yaml_scalar(value)));
+        }
+    }
+    lines
+}
+
+/// Prefixes the first line with `first` and indents the rest to match.
+fn prefixed(lines: Vec<String>, first: &str) -> Vec<String> {
+    let indent = " ".repeat(first.len());
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let prefix = if i == 0 { first } else { indent.as_str() };
+            format!("{prefix}{line}")
+        })
+        .collect()
+}
+
+fn is_block(value: &Value) -> bool {
+    match value {
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+        _ => false,
+    }
+}
+
+/// Plain scalar for numbers, booleans and null; JSON (a YAML subset) otherwise.
+fn yaml_scalar(value: &Value) -> String {
+    match value {
+        Value::Array(_) => "[]".to_string(),
+        Value::Object(_) => "{}".to_string(),
+        // Double-quoted JSON strings are valid YAML and never ambiguous
+        other => other.to_string(),
+    }
+}
+
+fn yaml_key(key: &str) -> String {
+    let plain = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if plain {
+        key.to_string()
+    } else {
+        Value::String(key.to_string()).to_string()
+    }
+}
+
+/// Appends the CBOR encoding of `value` to `out`.
+fn write_cbor(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.push(0xf6),
+        Value::Bool(b) => out.push(if *b { 0xf5 } else { 0xf4 }),
+        Value::Number(n) => {
+            if let Some(u) = n.as_u64() {
+                cbor_head(0, u, out);
+            } else if let Some(i) = n.as_i64() {
+                // Negative integers encode -1 - n; as_u64 failed, so i < 0
+                cbor_head(1, i.unsigned_abs() - 1, out);
+            } else {
+                out.push(0xfb);
+                out.extend_from_slice(&n.as_f64().unwrap_or(f64::NAN).to_be_bytes());
+            }
+        }
+        Value::String(s) => cbor_text(s, out),
+        Value::Array(items) => {
+            cbor_head(4, items.len() as u64, out);
+            for item in items {
+                write_cbor(item, out);
+            }
+        }
+        Value::Object(map) => {
+            cbor_head(5, map.len() as u64, out);
+            for (key, value) in map {
+                cbor_text(key, out);
+                write_cbor(value, out);
+            }
+        }
+    }
+}
+
+fn cbor_text(s: &str, out: &mut Vec<u8>) {
+    cbor_head(3, s.len() as u64, out);
+    out.extend_from_slice(s.as_bytes());
+}
+
+/// Writes an item head: major type plus argument in the shortest form.
+fn cbor_head(major: u8, argument: u64, out: &mut Vec<u8>) {
+    let major = major << 5;
+    if let Ok(small) = u8::try_from(argument) {
+        if small < 24 {
+            out.push(major | small);
+        } else {
+            out.extend_from_slice(&[major | 0x18, small]);
+        }
+    } else if let Ok(short) = u16::try_from(argument) {
+        out.push(major | 0x19);
+        out.extend_from_slice(&short.to_be_bytes());
+    } else if let Ok(word) = u32::try_from(argument) {
+        out.push(major | 0x1a);
+        out.extend_from_slice(&word.to_be_bytes());
+    } else {
+        out.push(major | 0x1b);
+        out.extend_from_slice(&argument.to_be_bytes());
+    }
+}
@@ -0,0 +1,143 @@
+//! Tests for default body encodings.
+
+use crate::webhook::PayloadEncoding;
+use serde_json::json;
+
+fn text(encoding: PayloadEncoding, value: &serde_json::Value) -> String {
+    String::from_utf8(encoding.encode(value).unwrap()).unwrap()
+}
+
+mod json {
+    use super::*;
+
+    #[test]
+    fn keeps_field_order() {
+        #[derive(serde::Serialize)]
+        struct Data {
+            b: u8,
+            a: u8,
+        }
+
+        let body = PayloadEncoding::Json.encode(&Data { b: 1, a: 2 }).unwrap();
+
+        assert_eq!(body, br#"{"b":1,"a":2}"#);
+    }
+
+    #[test]
+    fn content_types() {
+        assert_eq!(PayloadEncoding::Json.content_type(), "application/json");
+        assert_eq!(PayloadEncoding::Yaml.content_type(), "application/yaml");
+        assert_eq!(PayloadEncoding::Cbor.content_type(), "application/cbor");
+    }
+}
+
+mod yaml {
+    use super::*;
+
+    #[test]
+    fn nested_mappings_and_sequences() {
+        let value = json!({
+            "schema_version": 1,
+            "changes": [
+                {"adapter": "eth0", "timestamp": 100},
+                {"adapter": "wlan0", "timestamp": 200},
+            ],
+            "is_retry": false,
+        });
+
+        assert_eq!(
+            text(PayloadEncoding::Yaml, &value),
+            "changes:\n  - adapter: \"eth0\"\n    timestamp: 100\n  - adapter: \"wlan0\"\n    timestamp: 200\nis_retry: false\nschema_version: 1\n"
+        );
+    }
+
+    #[test]
+    fn empty_collections_are_flow_style() {
+        let value = json!({"servers": [], "extra": {}});
+
+        assert_eq!(
+            text(PayloadEncoding::Yaml, &value),
+            "extra: {}\nservers: []\n"
+        );
+    }
+
+    #[test]
+    fn sequences_of_scalars_and_sequences() {
+        let value = json!({"servers": ["10.0.0.53", null], "nested": [[1, 2]]});
+
+        assert_eq!(
+            text(PayloadEncoding::Yaml, &value),
+            "nested:\n  - - 1\n    - 2\nservers:\n  - \"10.0.0.53\"\n  - null\n"
+        );
+    }
+
+    #[test]
+    fn strings_and_unusual_keys_are_quoted() {
+        let value = json!({"a key": "yes: \"no\"\n", "x1": "true"});
+
+        assert_eq!(
+            text(PayloadEncoding::Yaml, &value),
+            "\"a key\": \"yes: \\\"no\\\"\\n\"\nx1: \"true\"\n"
+        );
+    }
+}
+
+mod cbor {
+    use super::*;
+
+    fn cbor(value: &serde_json::Value) -> Vec<u8> {
+        PayloadEncoding::Cbor.encode(value).unwrap()
+    }
+
+    // Expected bytes from the examples in RFC 8949 appendix A
+
+    #[test]
+    fn integers() {
+        assert_eq!(cbor(&json!(0)), [0x00]);
+        assert_eq!(cbor(&json!(23)), [0x17]);
+        assert_eq!(cbor(&json!(24)), [0x18, 0x18]);
+        assert_eq!(cbor(&json!(1000)), [0x19, 0x03, 0xe8]);
+        assert_eq!(cbor(&json!(1_000_000)), [0x1a, 0x00, 0x0f, 0x42, 0x40]);
+        assert_eq!(
+            cbor(&json!(1_000_000_000_000_u64)),
+            [0x1b, 0x00, 0x00, 0x00, 0xe8, 0xd4, 0xa5, 0x10, 0x00]
+        );
+        assert_eq!(cbor(&json!(-1)), [0x20]);
+        assert_eq!(cbor(&json!(-1000)), [0x39, 0x03, 0xe7]);
+        assert_eq!(
+            cbor(&json!(i64::MIN)),
+            [0x3b, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]
+        );
+    }
+
+    #[test]
+    fn simple_values_and_floats() {
+        assert_eq!(cbor(&json!(false)), [0xf4]);
+        assert_eq!(cbor(&json!(true)), [0xf5]);
+        assert_eq!(cbor(&json!(null)), [0xf6]);
+        assert_eq!(
+            cbor(&json!(1.1)),
+            [0xfb, 0x3f, 0xf1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a]
+        );
+    }
+
+    #[test]
+    fn strings_arrays_and_maps() {
+        assert_eq!(cbor(&json!("")), [0x60]);
+        assert_eq!(cbor(&json!("IETF")), [0x64, 0x49, 0x45, 0x54, 0x46]);
+        assert_eq!(cbor(&json!([])), [0x80]);
+        assert_eq!(cbor(&json!([1, [2, 3]])), [0x82, 0x01, 0x82, 0x02, 0x03]);
+        assert_eq!(
+            cbor(&json!({"a": 1, "b": [2, 3]})),
+            [0xa2, 0x61, 0x61, 0x01, 0x61, 0x62, 0x82, 0x02, 0x03]
+        );
+    }
+
+    #[test]
+    fn long_string_uses_length_byte() {
+        let body = cbor(&json!("x".repeat(30)));
+
+        assert_eq!(body[..2], [0x78, 30]);
+        assert_eq!(body.len(), 32);
+    }
+}
@@ -0,0 +1,209 @@
+//! Golden files of rendered payloads.
+//!
+//! With `--record-payloads <DIR>`, [`HttpWebhook`](crate::webhook::HttpWebhook)
+//! writes each address delivery to a [`GoldenDir`] instead of sending it:
+//! the changes as a sample file (`NNNN.changes.json`, the format of
+//! `template check --sample`) and the rendered payload (`NNNN.golden`).
+//! `ddns-a template verify` renders every sample again with the current
+//! configuration and compares it with its golden, so an upgrade that
+//! changes what receivers get is caught before it is deployed.
+
+use std::fmt::Write;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::monitor::IpChange;
+
+use super::template::{sample_changes, sample_json};
+
+/// File name suffix of the recorded changes of a case.
+pub const CHANGES_SUFFIX: &str = ".changes.json";
+
+/// File name suffix of the recorded payload of a case.
+pub const GOLDEN_SUFFIX: &str = ".golden";
+
+/// Errors that can occur while recording or loading golden files.
+#[derive(Debug, Error)]
+pub enum GoldenError {
+    /// A file or the directory could not be read or written.
+    #[error("Cannot access '{}': {source}", path.display())]
+    Io {
+        /// Path of the file or directory
+        path: PathBuf,
+        /// Underlying I/O error
+        #[source]
+        source: io::Error,
+    },
+
+    /// A changes file is not a valid sample.
+    #[error("Invalid changes file '{}': {reason}", path.display())]
+    InvalidChanges {
+        /// Path of the changes file
+        path: PathBuf,
+        /// Why the sample could not be parsed
+        reason: String,
+    },
+}
+
+impl GoldenError {
+    fn io(path: &Path) -> impl FnOnce(io::Error) -> Self + '_ {
+        move |source| Self::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+}
+
+/// A directory of recorded cases.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldenDir {
+    path: PathBuf,
+}
+
+impl GoldenDir {
+    /// Uses the directory at `path`; it is created by the first recording.
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the directory path.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Records `changes` and their rendered `payload` as the next case.
+    ///
+    /// Cases are numbered in recording order, continuing after the highest
+    /// existing number. Returns the name of the new case.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or a file cannot be written.
+    pub fn record(&self, changes: &[IpChange], payload: &[u8]) -> Result<String, GoldenError> {
+        std::fs::create_dir_all(&self.path).map_err(GoldenError::io(&self.path))?;
+        let next = self
+            .names()?
+            .iter()
+            .filter_map(|n| n.parse::<u32>().ok())
+            .max();
+        let name = format!("{:04}", next.unwrap_or(0) + 1);
+
+        let golden = self.file(&name, GOLDEN_SUFFIX);
+        std::fs::write(&golden, payload).map_err(GoldenError::io(&golden))?;
+        // Written last: a case only exists once its golden does
+        let sample = self.file(&name, CHANGES_SUFFIX);
+        std::fs::write(&sample, sample_json(changes)).map_err(GoldenError::io(&sample))?;
+        Ok(name)
+    }
+
+    /// Loads every case, sorted by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be listed, or a case's
+    /// changes or golden file cannot be read or parsed.
+    pub fn cases(&self) -> Result<Vec<GoldenCase>, GoldenError> {
+        let mut names = self.names()?;
+        names.sort();
+
+        names
+            .into_iter()
+            .map(|name| {
+                let sample = self.file(&name, CHANGES_SUFFIX);
+                let json = std::fs::read_to_string(&sample).map_err(GoldenError::io(&sample))?;
+                let changes =
+                    sample_changes(&json).map_err(|reason| GoldenError::InvalidChanges {
+                        path: sample.clone(),
+                        reason,
+                    })?;
+                let golden = self.file(&name, GOLDEN_SUFFIX);
+                let golden = std::fs::read(&golden).map_err(GoldenError::io(&golden))?;
+                Ok(GoldenCase {
+                    name,
+                    changes,
+                    golden,
+                })
+            })
+            .collect()
+    }
+
+    /// Names of the cases with a changes file; none if the directory is missing.
+    fn names(&self) -> Result<Vec<String>, GoldenError> {
+        let entries = match std::fs::read_dir(&self.path) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(GoldenError::io(&self.path)(e)),
+        };
+
+        Ok(entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                name.strip_suffix(CHANGES_SUFFIX).map(str::to_string)
+            })
+            .collect())
+    }
+
+    fn file(&self, name: &str, suffix: &str) -> PathBuf {
+        self.path.join(format!("{name}{suffix}"))
+    }
+}
+
+/// A recorded case: changes and the payload rendered for them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldenCase {
+    /// Case name, the file name without suffix
+    pub name: String,
+    /// The recorded changes
+    pub changes: Vec<IpChange>,
+    /// The payload recorded for them
+    pub golden: Vec<u8>,
+}
+
+impl GoldenCase {
+    /// Compares a fresh render of the changes with the golden.
+    ///
+    /// # Errors
+    ///
+    /// Returns a line-by-line diff (`-` golden, `+` rendered) if they
+    /// differ, or the sizes for payloads that are not UTF-8 (CBOR).
+    pub fn compare(&self, rendered: &[u8]) -> Result<(), String> {
+        if rendered == self.golden {
+            return Ok(());
+        }
+        let (Ok(golden), Ok(rendered)) = (
+            std::str::from_utf8(&self.golden),
+            std::str::from_utf8(rendered),
+        ) else {
+            return Err(format!(
+                "binary payload differs ({} bytes, golden {} bytes)",
+                rendered.len(),
+                self.golden.len()
+            ));
+        };
+
+        let golden: Vec<_> = golden.lines().collect();
+        let rendered: Vec<_> = rendered.lines().collect();
+        let mut diff = String::new();
+        for line in 0..golden.len().max(rendered.len()) {
+            let (before, after) = (golden.get(line), rendered.get(line));
+            if before == after {
+                continue;
+            }
+            if let Some(before) = before {
+                let _ = writeln!(diff, "{:>4} - {before}", line + 1);
+            }
+            if let Some(after) = after {
+                let _ = writeln!(diff, "{:>4} + {after}", line + 1);
+            }
+        }
+        if diff.is_empty() {
+            diff.push_str("only line endings or the final newline differ\n");
+        }
+        Err(diff)
+    }
+}
@@ -0,0 +1,240 @@
+//! Tests for golden files of rendered payloads.
+
+use std::time::{Duration, SystemTime};
+
+use tempfile::TempDir;
+
+use super::golden::{CHANGES_SUFFIX, GOLDEN_SUFFIX, GoldenDir, GoldenError};
+use crate::monitor::{DnsChange, IpChange};
+use crate::network::DnsSettings;
+use crate::webhook::{
+    Event, HttpClient, HttpError, HttpRequest, HttpResponse, HttpWebhook, WebhookSender,
+};
+
+/// Client failing the test if a request is sent.
+struct NoClient;
+
+impl HttpClient for NoClient {
+    async fn request(&self, _req: HttpRequest) -> Result<HttpResponse, HttpError> {
+        panic!("recording must not send requests")
+    }
+}
+
+fn changes() -> Vec<IpChange> {
+    vec![IpChange::added(
+        "eth0",
+        "192.0.2.1".parse().unwrap(),
+        SystemTime::UNIX_EPOCH + Duration::from_secs(100),
+    )]
+}
+
+fn webhook() -> HttpWebhook<NoClient> {
+    HttpWebhook::new(
+        NoClient,
+        url::Url::parse("https://example.com/hook").unwrap(),
+    )
+}
+
+mod golden_dir {
+    use super::*;
+
+    #[test]
+    fn records_numbered_cases() {
+        let dir = TempDir::new().unwrap();
+        let golden = GoldenDir::new(dir.path().join("goldens"));
+
+        assert_eq!(golden.record(&changes(), b"one").unwrap(), "0001");
+        assert_eq!(golden.record(&changes(), b"two").unwrap(), "0002");
+
+        let cases = golden.cases().unwrap();
+        assert_eq!(cases.len(), 2);
+        assert_eq!(cases[0].name, "0001");
+        assert_eq!(cases[0].changes, changes());
+        assert_eq!(cases[1].golden, b"two");
+    }
+
+    #[test]
+    fn numbering_continues_after_highest_case() {
+        let dir = TempDir::new().unwrap();
+        let golden = GoldenDir::new(dir.path());
+        std::fs::write(dir.path().join(format!("0007{CHANGES_SUFFIX}")), "[]").unwrap();
+        std::fs::write(dir.path().join(format!("0007{GOLDEN_SUFFIX}")), "").unwrap();
+
+        assert_eq!(golden.record(&changes(), b"").unwrap(), "0008");
+    }
+
+    #[test]
+    fn hand_written_cases_are_loaded() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join(format!("removal{CHANGES_SUFFIX}")),
+            r#"[{"adapter": "eth0", "address": "192.0.2.1", "kind": "removed"}]"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join(format!("removal{GOLDEN_SUFFIX}")), "gone").unwrap();
+        std::fs::write(dir.path().join("README.md"), "ignored").unwrap();
+
+        let cases = GoldenDir::new(dir.path()).cases().unwrap();
+
+        assert_eq!(cases.len(), 1);
+        assert!(!cases[0].changes[0].is_added());
+    }
+
+    #[test]
+    fn missing_directory_has_no_cases() {
+        let dir = TempDir::new().unwrap();
+
+        assert!(
+            GoldenDir::new(dir.path().join("missing"))
+                .cases()
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn missing_golden_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join(format!("0001{CHANGES_SUFFIX}")), "[]").unwrap();
+
+        let result = GoldenDir::new(dir.path()).cases();
+
+        assert!(matches!(result, Err(GoldenError::Io { .. })));
+    }
+
+    #[test]
+    fn invalid_changes_are_an_error() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join(format!("0001{CHANGES_SUFFIX}")), "{}").unwrap();
+        std::fs::write(dir.path().join(format!("0001{GOLDEN_SUFFIX}")), "").unwrap();
+
+        let result = GoldenDir::new(dir.path()).cases();
+
+        assert!(matches!(result, Err(GoldenError::InvalidChanges { .. })));
+    }
+}
+
+mod compare {
+    use super::*;
+    use crate::webhook::GoldenCase;
+
+    fn case(golden: &[u8]) -> GoldenCase {
+        GoldenCase {
+            name: "0001".to_string(),
+            changes: changes(),
+            golden: golden.to_vec(),
+        }
+    }
+
+    #[test]
+    fn identical_payload_matches() {
+        assert_eq!(case(b"{\"ip\": 1}").compare(b"{\"ip\": 1}"), Ok(()));
+    }
+
+    #[test]
+    fn changed_lines_are_listed() {
+        let diff = case(b"{\n  \"ip\": 1\n}")
+            .compare(b"{\n  \"ip\": 2\n}\n")
+            .unwrap_err();
+
+        assert_eq!(diff, "   2 -   \"ip\": 1\n   2 +   \"ip\": 2\n");
+    }
+
+    #[test]
+    fn removed_and_added_lines_are_listed() {
+        assert_eq!(case(b"a\nb").compare(b"a").unwrap_err(), "   2 - b\n");
+        assert_eq!(case(b"a").compare(b"a\nb").unwrap_err(), "   2 + b\n");
+    }
+
+    #[test]
+    fn line_ending_change_is_reported() {
+        let diff = case(b"a\nb").compare(b"a\r\nb").unwrap_err();
+
+        assert_eq!(diff, "only line endings or the final newline differ\n");
+    }
+
+    #[test]
+    fn binary_payloads_report_sizes() {
+        let diff = case(&[0xa1, 0xff])
+            .compare(&[0xa1, 0xfe, 0x00])
+            .unwrap_err();
+
+        assert_eq!(diff, "binary payload differs (3 bytes, golden 2 bytes)");
+    }
+}
+
+mod recording {
+    use super::*;
+    use crate::webhook::{PayloadEncoding, PayloadFormat};
+
+    #[tokio::test]
+    async fn records_instead_of_sending() {
+        let dir = TempDir::new().unwrap();
+        let webhook = webhook()
+            .with_payload_encoding(PayloadEncoding::Json)
+            .with_recorder(GoldenDir::new(dir.path()));
+
+        webhook.send(&changes()).await.unwrap();
+
+        let cases = GoldenDir::new(dir.path()).cases().unwrap();
+        assert_eq!(cases.len(), 1);
+        assert_eq!(cases[0].changes, changes());
+        assert_eq!(
+            cases[0].compare(&webhook.render(&changes()).unwrap()),
+            Ok(())
+        );
+        let body: serde_json::Value = serde_json::from_slice(&cases[0].golden).unwrap();
+        assert_eq!(body["changes"][0]["address"], "192.0.2.1");
+    }
+
+    #[tokio::test]
+    async fn dns_deliveries_are_dropped() {
+        let dir = TempDir::new().unwrap();
+        let webhook = webhook().with_recorder(GoldenDir::new(dir.path()));
+        let change = DnsChange {
+            adapter: "vpn".to_string(),
+            old: DnsSettings::default(),
+            new: DnsSettings::default(),
+            timestamp: SystemTime::UNIX_EPOCH,
+        };
+
+        webhook.send_event(&Event::Dns(&[change])).await.unwrap();
+
+        assert!(GoldenDir::new(dir.path()).cases().unwrap().is_empty());
+    }
+
+    #[test]
+    fn render_includes_ttl_hint() {
+        let webhook = webhook()
+            .with_payload_encoding(PayloadEncoding::Json)
+            .with_ttl(300);
+
+        let body: serde_json::Value =
+            serde_json::from_slice(&webhook.render(&changes()).unwrap()).unwrap();
+
+        assert_eq!(body["ttl"], 300);
+        assert_eq!(body["changes"][0]["expires"], 400);
+    }
+
+    #[test]
+    fn render_without_body_is_empty() {
+        let webhook = webhook().with_payload_format(PayloadFormat::None);
+
+        assert!(webhook.render(&changes()).unwrap().is_empty());
+    }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn render_is_deterministic() {
+        use crate::webhook::{EchoCheck, SAMPLE_NONCE};
+
+        let webhook = webhook()
+            .with_echo_check(EchoCheck::Body)
+            .with_body_template("{{attempt}} {{elapsed_ms}} {{nonce}}");
+
+        assert_eq!(
+            webhook.render(&changes()).unwrap(),
+            format!("1 0 {SAMPLE_NONCE}").into_bytes()
+        );
+    }
+}
@@ -0,0 +1,462 @@
+//! The payload model shared by body templates and default bodies.
+//!
+//! Every delivery is rendered from one [`PayloadData`] value, so templates,
+//! default bodies and custom [`Transport`](super::Transport)s always see the
+//! same fields.
+//!
+//! # Compatibility Policy
+//!
+//! The payload carries `schema_version` (also sent as [`SCHEMA_HEADER`]).
+//! Within a schema version fields may be added, but never removed, renamed
+//! or given a different type; any such change bumps [`SCHEMA_VERSION`].
+//! `payload_tests` freezes the fields of every released version.
+//!
+//! The submodules render payloads: default bodies ([`PayloadFormat`],
+//! [`PayloadEncoding`]), body templates ([`check_template`]) and golden files
+//! ([`GoldenDir`]).
+
+use std::time::{Duration, SystemTime};
+
+use http::HeaderName;
+use serde::Serialize;
+
+use crate::monitor::{DnsChange, DriftChange, HostnameChange, IpChange, LinkChange, StormChange};
+use crate::network::{AdapterMetadata, AddressState, DnsSettings};
+
+use super::{Captured, RetryableError};
+
+mod document;
+mod encoding;
+mod golden;
+mod template;
+#[cfg(feature = "templates")]
+mod template_helpers;
+
+#[cfg(test)]
+mod document_tests;
+#[cfg(test)]
+mod encoding_tests;
+#[cfg(test)]
+mod golden_tests;
+#[cfg(all(test, feature = "templates"))]
+mod template_helpers_tests;
+#[cfg(test)]
+mod template_tests;
+
+pub use document::PayloadFormat;
+pub use encoding::PayloadEncoding;
+pub use golden::{CHANGES_SUFFIX, GOLDEN_SUFFIX, GoldenCase, GoldenDir, GoldenError};
+pub use template::{SAMPLE_NONCE, SAMPLE_TTL, check_template, sample_changes, sample_json};
+pub(super) use template::{render_headers, render_template, render_url_template};
+#[cfg(feature = "templates")]
+pub use template::{template_registry, url_template_registry};
+
+/// Version of the payload schema described in the module docs.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Header carrying [`SCHEMA_VERSION`] on every delivery.
+pub const SCHEMA_HEADER: HeaderName = HeaderName::from_static("x-ddns-a-schema");
+
+/// Position of a single attempt within a delivery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Attempt {
+    /// 1-based attempt number.
+    pub number: u32,
+    /// Time since the first attempt started.
+    pub elapsed: Duration,
+}
+
+impl Attempt {
+    /// Returns true for every attempt after the first.
+    #[must_use]
+    pub const fn is_retry(self) -> bool {
+        self.number > 1
+    }
+
+    /// Elapsed time in whole milliseconds, saturating on overflow.
+    #[must_use]
+    pub fn elapsed_ms(self) -> u64 {
+        u64::try_from(self.elapsed.as_millis()).unwrap_or(u64::MAX)
+    }
+}
+
+/// Changes carried by a single delivery.
+#[derive(Debug, Clone, Copy)]
+pub enum Payload<'a> {
+    /// Address changes.
+    Ip(&'a [IpChange]),
+    /// DNS setting changes.
+    Dns(&'a [DnsChange]),
+    /// Drift from the expected addresses.
+    Drift(&'a [DriftChange]),
+    /// Hostname changes.
+    Hostname(&'a [HostnameChange]),
+    /// IPv6 address storms that started or ended.
+    Storm(&'a [StormChange]),
+    /// Adapters that went up or down.
+    Link(&'a [LinkChange]),
+}
+
+/// Changes other than address changes, sent with
+/// [`WebhookSender::send_event`](super::WebhookSender::send_event).
+#[derive(Debug, Clone, Copy)]
+pub enum Event<'a> {
+    /// DNS setting changes.
+    Dns(&'a [DnsChange]),
+    /// Drift from the expected addresses.
+    Drift(&'a [DriftChange]),
+    /// Hostname changes.
+    Hostname(&'a [HostnameChange]),
+    /// IPv6 address storms that started or ended.
+    Storm(&'a [StormChange]),
+    /// Adapters that went up or down.
+    Link(&'a [LinkChange]),
+}
+
+impl Event<'_> {
+    /// Returns the event category, as sent in
+    /// [`EVENT_HEADER`](super::EVENT_HEADER).
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Dns(_) => "dns",
+            Self::Drift(_) => "drift",
+            Self::Hostname(_) => "hostname",
+            Self::Storm(_) => "storm",
+            Self::Link(_) => "link",
+        }
+    }
+
+    /// Returns the number of changes.
+    #[must_use]
+    pub const fn len(self) -> usize {
+        match self {
+            Self::Dns(changes) => changes.len(),
+            Self::Drift(changes) => changes.len(),
+            Self::Hostname(changes) => changes.len(),
+            Self::Storm(changes) => changes.len(),
+            Self::Link(changes) => changes.len(),
+        }
+    }
+
+    /// Returns true if there are no changes.
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<'a> From<Event<'a>> for Payload<'a> {
+    fn from(event: Event<'a>) -> Self {
+        match event {
+            Event::Dns(changes) => Self::Dns(changes),
+            Event::Drift(changes) => Self::Drift(changes),
+            Event::Hostname(changes) => Self::Hostname(changes),
+            Event::Storm(changes) => Self::Storm(changes),
+            Event::Link(changes) => Self::Link(changes),
+        }
+    }
+}
+
+impl<'a> Payload<'a> {
+    /// Returns the event category of deliveries other than address changes,
+    /// as sent in [`EVENT_HEADER`](super::EVENT_HEADER).
+    #[must_use]
+    pub const fn event(self) -> Option<&'static str> {
+        match self {
+            Self::Ip(_) => None,
+            Self::Dns(_) => Some("dns"),
+            Self::Drift(_) => Some("drift"),
+            Self::Hostname(_) => Some("hostname"),
+            Self::Storm(_) => Some("storm"),
+            Self::Link(_) => Some("link"),
+        }
+    }
+
+    /// Builds the data rendered for `attempt` of this delivery.
+    #[must_use]
+    pub fn data(self, attempt: Attempt) -> PayloadData<'a> {
+        let changes = match self {
+            Self::Ip(changes) => ChangesData::Ip(changes.iter().map(ChangeData::from).collect()),
+            Self::Dns(changes) => {
+                ChangesData::Dns(changes.iter().map(DnsChangeData::from).collect())
+            }
+            Self::Drift(changes) => {
+                ChangesData::Drift(changes.iter().map(DriftData::from).collect())
+            }
+            Self::Hostname(changes) => {
+                ChangesData::Hostname(changes.iter().map(HostnameData::from).collect())
+            }
+            Self::Storm(changes) => {
+                ChangesData::Storm(changes.iter().map(StormData::from).collect())
+            }
+            Self::Link(changes) => ChangesData::Link(changes.iter().map(LinkData::from).collect()),
+        };
+
+        PayloadData {
+            schema_version: SCHEMA_VERSION,
+            changes,
+            attempt: attempt.number,
+            elapsed_ms: attempt.elapsed_ms(),
+            is_retry: attempt.is_retry(),
+            nonce: None,
+            ttl: None,
+            pre: None,
+        }
+    }
+}
+
+/// Data for rendering a delivery body.
+///
+/// Serializes to the payload schema described in the module docs.
+#[derive(Debug, Serialize)]
+pub struct PayloadData<'a> {
+    schema_version: u32,
+    #[serde(flatten)]
+    changes: ChangesData<'a>,
+    attempt: u32,
+    elapsed_ms: u64,
+    is_retry: bool,
+    /// Echo check nonce of the attempt; absent without an echo check.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nonce: Option<&'a str>,
+    /// TTL hint in seconds of address deliveries; absent unless configured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ttl: Option<u32>,
+    /// Values captured from the pre-request; absent without one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pre: Option<&'a Captured>,
+}
+
+impl<'a> PayloadData<'a> {
+    /// Adds the attempt's echo check nonce, if any.
+    #[must_use]
+    pub const fn with_nonce(mut self, nonce: Option<&'a str>) -> Self {
+        self.nonce = nonce;
+        self
+    }
+
+    /// Adds the values captured from the pre-request, if any.
+    #[must_use]
+    pub fn with_captured(mut self, captured: &'a Captured) -> Self {
+        self.pre = (!captured.is_empty()).then_some(captured);
+        self
+    }
+
+    /// Adds the TTL hint, if any, to an address delivery, and to each added
+    /// address the time it expires.
+    ///
+    /// Other deliveries are left unchanged: they describe no records.
+    #[must_use]
+    pub fn with_ttl(mut self, ttl: Option<u32>) -> Self {
+        let (Some(ttl), ChangesData::Ip(changes)) = (ttl, &mut self.changes) else {
+            return self;
+        };
+        for change in changes.iter_mut().filter(|c| c.kind == "added") {
+            change.expires = Some(change.timestamp.saturating_add(u64::from(ttl)));
+        }
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Renders a body template (Handlebars syntax) with this data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RetryableError::Template`] if the template does not render,
+    /// or without the `templates` feature.
+    pub fn render(&self, template: &str) -> Result<String, RetryableError> {
+        render_template(template, self)
+    }
+
+    /// Renders a URL template (Handlebars syntax) with this data,
+    /// percent-encoding the values it inserts.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RetryableError::Template`] if the template does not render
+    /// to a valid URL, or without the `templates` feature.
+    pub fn render_url(&self, template: &str) -> Result<url::Url, RetryableError> {
+        render_url_template(template, self)
+    }
+}
+
+/// The changes of a delivery, keyed by event category.
+#[derive(Debug, Serialize)]
+enum ChangesData<'a> {
+    #[serde(rename = "changes")]
+    Ip(Vec<ChangeData<'a>>),
+    #[serde(rename = "dns_changes")]
+    Dns(Vec<DnsChangeData<'a>>),
+    #[serde(rename = "drift")]
+    Drift(Vec<DriftData<'a>>),
+    #[serde(rename = "hostname_changes")]
+    Hostname(Vec<HostnameData<'a>>),
+    #[serde(rename = "storms")]
+    Storm(Vec<StormData<'a>>),
+    #[serde(rename = "link_changes")]
+    Link(Vec<LinkData<'a>>),
+}
+
+/// Individual address change data.
+#[derive(Debug, Serialize)]
+struct ChangeData<'a> {
+    adapter: &'a str,
+    address: String,
+    kind: &'static str,
+    timestamp: u64,
+    /// Unix time the TTL hint runs out; absent without one or on removals.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires: Option<u64>,
+    /// Link-layer details of the adapter; absent if the fetcher has none.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<&'a AdapterMetadata>,
+    /// Length of the on-link prefix; absent if the fetcher cannot tell.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prefix_len: Option<u8>,
+    scope: &'static str,
+    /// `preferred` or `deprecated`; absent if the fetcher cannot tell.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state: Option<&'static str>,
+}
+
+impl<'a> From<&'a IpChange> for ChangeData<'a> {
+    fn from(change: &'a IpChange) -> Self {
+        let kind = if change.is_added() {
+            "added"
+        } else {
+            "removed"
+        };
+        Self {
+            adapter: &change.adapter,
+            address: change.address.to_string(),
+            kind,
+            timestamp: unix_secs(change.timestamp),
+            expires: None,
+            metadata: change.metadata.as_ref(),
+            prefix_len: change.info.prefix_len,
+            scope: change.scope().as_str(),
+            state: change.info.state.map(AddressState::as_str),
+        }
+    }
+}
+
+/// Individual DNS change data.
+#[derive(Debug, Serialize)]
+struct DnsChangeData<'a> {
+    adapter: &'a str,
+    suffix: &'a str,
+    old_suffix: &'a str,
+    servers: Vec<String>,
+    old_servers: Vec<String>,
+    timestamp: u64,
+}
+
+impl<'a> From<&'a DnsChange> for DnsChangeData<'a> {
+    fn from(change: &'a DnsChange) -> Self {
+        let servers = |dns: &DnsSettings| dns.servers.iter().map(ToString::to_string).collect();
+
+        Self {
+            adapter: &change.adapter,
+            suffix: &change.new.suffix,
+            old_suffix: &change.old.suffix,
+            servers: servers(&change.new),
+            old_servers: servers(&change.old),
+            timestamp: unix_secs(change.timestamp),
+        }
+    }
+}
+
+/// Individual expected address drift data.
+#[derive(Debug, Serialize)]
+struct DriftData<'a> {
+    kind: &'static str,
+    address: String,
+    /// Adapter of an unexpected address; absent for a missing one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    adapter: Option<&'a str>,
+    resolved: bool,
+    timestamp: u64,
+}
+
+impl<'a> From<&'a DriftChange> for DriftData<'a> {
+    fn from(change: &'a DriftChange) -> Self {
+        Self {
+            kind: change.drift.kind.as_str(),
+            address: change.drift.address.to_string(),
+            adapter: change.drift.adapter.as_deref(),
+            resolved: change.resolved,
+            timestamp: unix_secs(change.timestamp),
+        }
+    }
+}
+
+/// Individual hostname change data.
+#[derive(Debug, Serialize)]
+struct HostnameData<'a> {
+    hostname: &'a str,
+    old_hostname: &'a str,
+    timestamp: u64,
+}
+
+impl<'a> From<&'a HostnameChange> for HostnameData<'a> {
+    fn from(change: &'a HostnameChange) -> Self {
+        Self {
+            hostname: &change.new,
+            old_hostname: &change.old,
+            timestamp: unix_secs(change.timestamp),
+        }
+    }
+}
+
+/// Individual IPv6 address storm data.
+#[derive(Debug, Serialize)]
+struct StormData<'a> {
+    adapter: &'a str,
+    count: usize,
+    limit: usize,
+    resolved: bool,
+    prefix_only: bool,
+    timestamp: u64,
+}
+
+impl<'a> From<&'a StormChange> for StormData<'a> {
+    fn from(change: &'a StormChange) -> Self {
+        Self {
+            adapter: &change.storm.adapter,
+            count: change.storm.count,
+            limit: change.storm.limit,
+            resolved: change.resolved,
+            prefix_only: change.prefix_only,
+            timestamp: unix_secs(change.timestamp),
+        }
+    }
+}
+
+/// Individual link state change data.
+#[derive(Debug, Serialize)]
+struct LinkData<'a> {
+    adapter: &'a str,
+    state: &'static str,
+    status: &'static str,
+    timestamp: u64,
+}
+
+impl<'a> From<&'a LinkChange> for LinkData<'a> {
+    fn from(change: &'a LinkChange) -> Self {
+        Self {
+            adapter: &change.adapter,
+            state: change.state.as_str(),
+            status: change.status.as_str(),
+            timestamp: unix_secs(change.timestamp),
+        }
+    }
+}
+
+/// Seconds since the Unix epoch; pre-epoch timestamps (shouldn't occur in
+/// practice for DDNS events) default to 0.
+fn unix_secs(timestamp: SystemTime) -> u64 {
+    timestamp
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
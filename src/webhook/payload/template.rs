@@ -0,0 +1,268 @@
+//! Body template rendering and offline checks.
+//!
+//! Deliveries render templates with [`render_template`]; `ddns-a template
+//! check` uses [`check_template`] to render one against sample changes, so
+//! templates can be linted in CI without a configuration or a receiver.
+//! Both render with the custom helpers of [`template_registry`]; templated
+//! webhook URLs render with [`url_template_registry`].
+//! Recorded golden files store their changes in the same sample format
+//! ([`sample_json`]).
+
+use std::net::IpAddr;
+use std::time::{Duration, SystemTime};
+
+use http::HeaderMap;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::monitor::{IpChange, IpChangeKind};
+use crate::network::{AdapterMetadata, AddressInfo, AddressScope, AddressState};
+
+use super::{Attempt, Payload};
+use crate::webhook::RetryableError;
+
+/// Creates the Handlebars registry body templates are rendered with,
+/// including the custom helpers such as `{{json changes}}` (see
+/// [`HttpWebhook`](crate::webhook::HttpWebhook)).
+#[cfg(feature = "templates")]
+#[must_use]
+pub fn template_registry() -> handlebars::Handlebars<'static> {
+    let mut hbs = handlebars::Handlebars::new();
+    super::template_helpers::register(&mut hbs);
+    hbs
+}
+
+/// Creates the Handlebars registry URL templates are rendered with: that
+/// of [`template_registry`], percent-encoding values instead of escaping
+/// them for HTML.
+///
+/// `{{changes.0.address}}` thus renders `2001:db8::1` as `2001%3Adb8%3A%3A1`;
+/// `{{{...}}}` inserts a value as is, e.g. a path segment with slashes.
+#[cfg(feature = "templates")]
+#[must_use]
+pub fn url_template_registry() -> handlebars::Handlebars<'static> {
+    let mut hbs = template_registry();
+    hbs.register_escape_fn(super::template_helpers::percent_encode);
+    hbs
+}
+
+/// Renders a URL template with `data` and parses the result.
+#[cfg(feature = "templates")]
+pub fn render_url_template(template: &str, data: &impl Serialize) -> Result<Url, RetryableError> {
+    let rendered = url_template_registry()
+        .render_template(template, data)
+        .map_err(|e| RetryableError::Template(e.to_string()))?;
+    Url::parse(&rendered)
+        .map_err(|e| RetryableError::Template(format!("rendered URL '{rendered}': {e}")))
+}
+
+/// Fails every render: templating is not compiled in.
+#[cfg(not(feature = "templates"))]
+pub fn render_url_template(_template: &str, _data: &impl Serialize) -> Result<Url, RetryableError> {
+    Err(RetryableError::Template(
+        "URL templates require the 'templates' feature".to_string(),
+    ))
+}
+
+/// Renders the header values holding Handlebars expressions with `data`,
+/// inserting values as they are.
+#[cfg(feature = "templates")]
+pub fn render_headers(
+    headers: &mut HeaderMap,
+    data: &impl Serialize,
+) -> Result<(), RetryableError> {
+    let templated =
+        |value: &http::HeaderValue| value.to_str().is_ok_and(|text| text.contains("{{"));
+    if !headers.values().any(templated) {
+        return Ok(());
+    }
+    let mut hbs = template_registry();
+    hbs.register_escape_fn(handlebars::no_escape);
+    for (name, value) in headers.iter_mut().filter(|(_, value)| templated(value)) {
+        let rendered = hbs
+            .render_template(value.to_str().unwrap_or_default(), data)
+            .map_err(|e| RetryableError::Template(format!("header '{name}': {e}")))?;
+        *value = http::HeaderValue::from_str(&rendered).map_err(|_| {
+            RetryableError::Template(format!("header '{name}': rendered value is not valid"))
+        })?;
+    }
+    Ok(())
+}
+
+/// Leaves header values as they are: templating is not compiled in, and
+/// configurations only pass `{{pre.<name>}}` placeholders, which
+/// [`Captured::apply`](crate::webhook::Captured::apply) fills.
+#[cfg(not(feature = "templates"))]
+#[allow(clippy::unnecessary_wraps)] // Same signature as with templates
+pub const fn render_headers(
+    _headers: &mut HeaderMap,
+    _data: &impl Serialize,
+) -> Result<(), RetryableError> {
+    Ok(())
+}
+
+/// Renders a Handlebars template with `data`.
+#[cfg(feature = "templates")]
+pub fn render_template(template: &str, data: &impl Serialize) -> Result<String, RetryableError> {
+    template_registry()
+        .render_template(template, data)
+        .map_err(|e| RetryableError::Template(e.to_string()))
+}
+
+/// Fails every render: templating is not compiled in.
+#[cfg(not(feature = "templates"))]
+pub fn render_template(_template: &str, _data: &impl Serialize) -> Result<String, RetryableError> {
+    Err(RetryableError::Template(
+        "body templates require the 'templates' feature".to_string(),
+    ))
+}
+
+/// Nonce rendered by checks, so templates written for an echo check pass.
+pub const SAMPLE_NONCE: &str = "00000000000000000000000000000000";
+
+/// TTL hint rendered by checks, so templates using `ttl` or `expires` pass.
+pub const SAMPLE_TTL: u32 = 300;
+
+/// Renders `template` for `changes` as the first attempt of a delivery.
+///
+/// Unlike deliveries, the check is strict: a variable missing from the
+/// payload (usually a typo) is an error rather than an empty string. The
+/// `nonce` is [`SAMPLE_NONCE`] and the TTL hint [`SAMPLE_TTL`]. With `expect_json`, the output must also
+/// parse as JSON.
+///
+/// # Errors
+///
+/// Returns why the template failed to compile or render, or why its output
+/// is not JSON.
+pub fn check_template(
+    template: &str,
+    changes: &[IpChange],
+    expect_json: bool,
+) -> Result<String, String> {
+    let data = Payload::Ip(changes)
+        .data(Attempt {
+            number: 1,
+            elapsed: Duration::ZERO,
+        })
+        .with_nonce(Some(SAMPLE_NONCE))
+        .with_ttl(Some(SAMPLE_TTL));
+    let rendered = render_strict(template, &data)?;
+
+    if expect_json {
+        serde_json::from_str::<serde_json::Value>(&rendered)
+            .map_err(|e| format!("rendered output is not valid JSON: {e}"))?;
+    }
+    Ok(rendered)
+}
+
+#[cfg(feature = "templates")]
+fn render_strict(template: &str, data: &impl Serialize) -> Result<String, String> {
+    let mut hbs = template_registry();
+    hbs.set_strict_mode(true);
+    hbs.render_template(template, data)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "templates"))]
+fn render_strict(template: &str, data: &impl Serialize) -> Result<String, String> {
+    render_template(template, data).map_err(|e| e.to_string())
+}
+
+/// One change in a sample file.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct SampleChange {
+    adapter: String,
+    address: IpAddr,
+    #[serde(default = "added")]
+    kind: IpChangeKind,
+    /// Unix timestamp in seconds (default: 0)
+    #[serde(default)]
+    timestamp: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<AdapterMetadata>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    prefix_len: Option<u8>,
+    /// Derived from the address; accepted so captured payloads read back
+    #[serde(rename = "scope", default, skip_serializing)]
+    _scope: Option<AddressScope>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    state: Option<AddressState>,
+}
+
+/// Samples default to added addresses.
+const fn added() -> IpChangeKind {
+    IpChangeKind::Added
+}
+
+impl From<&IpChange> for SampleChange {
+    fn from(change: &IpChange) -> Self {
+        Self {
+            adapter: change.adapter.clone(),
+            address: change.address,
+            kind: change.kind,
+            timestamp: change
+                .timestamp
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            metadata: change.metadata.clone(),
+            prefix_len: change.info.prefix_len,
+            _scope: None,
+            state: change.info.state,
+        }
+    }
+}
+
+/// A sample file: a change array, or a payload holding one.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Sample {
+    Changes(Vec<SampleChange>),
+    Payload { changes: Vec<SampleChange> },
+}
+
+/// Parses sample changes from JSON.
+///
+/// Accepts an array of `{adapter, address, kind, timestamp}` objects, where
+/// `kind` defaults to `added` and `timestamp` to 0, an optional `metadata`
+/// object holds the adapter's details and optional `prefix_len` and `state`
+/// the address details, or a captured payload
+/// with such a `changes` array (other payload fields are ignored).
+///
+/// # Errors
+///
+/// Returns the parse error if `json` is neither form.
+pub fn sample_changes(json: &str) -> Result<Vec<IpChange>, String> {
+    let sample: Sample = serde_json::from_str(json).map_err(|_| {
+        "expected an array of {adapter, address, kind, timestamp} objects \
+         or an object with such a \"changes\" array"
+            .to_string()
+    })?;
+    let (Sample::Changes(changes) | Sample::Payload { changes }) = sample;
+
+    Ok(changes
+        .into_iter()
+        .map(|c| {
+            let at = SystemTime::UNIX_EPOCH + Duration::from_secs(c.timestamp);
+            IpChange::new(c.adapter, c.address, at, c.kind)
+                .with_metadata(c.metadata)
+                .with_info(AddressInfo {
+                    prefix_len: c.prefix_len,
+                    state: c.state,
+                })
+        })
+        .collect())
+}
+
+/// Formats `changes` as a sample array that [`sample_changes`] reads back.
+///
+/// Timestamps are truncated to whole seconds, as payloads carry them.
+///
+/// # Panics
+///
+/// Never panics: a sample holds only strings and addresses.
+#[must_use]
+pub fn sample_json(changes: &[IpChange]) -> String {
+    let sample: Vec<SampleChange> = changes.iter().map(SampleChange::from).collect();
+    serde_json::to_string_pretty(&sample).expect("sample changes always serialize")
+}
@@ -0,0 +1,138 @@
+//! Custom Handlebars helpers available to every body template.
+//!
+//! - `{{first_added changes}}`: address of the first added change, empty if
+//!   there is none
+//! - `{{first_added_v4 changes}}` / `{{first_added_v6 changes}}`: the same,
+//!   limited to IPv4 / IPv6 addresses
+//! - `{{#each (only_added changes)}}`: the added changes only
+//! - `{{json value}}`: `value` as JSON, e.g. `{{json changes}}`
+//! - `{{iso8601 timestamp}}`: a Unix timestamp as `2024-05-01T12:00:00Z`
+//! - `{{urlencode value}}`: `value` percent-encoded for a URL or form body
+//!
+//! Helpers take exactly one parameter and compose as subexpressions, e.g.
+//! `{{urlencode (first_added_v6 changes)}}`. Their output is written as is,
+//! without HTML escaping, so `{{json changes}}` yields valid JSON. A missing
+//! value renders empty, so templates can be validated without data.
+
+use std::net::IpAddr;
+
+use handlebars::{
+    Context, Handlebars, Helper, HelperDef, HelperResult, Output, RenderContext, RenderError,
+    RenderErrorReason, ScopedJson,
+};
+use serde_json::Value as Json;
+use url::form_urlencoded;
+
+use crate::time::format_rfc3339;
+
+/// Computes a helper's value from its parameter.
+type Compute = fn(&Json) -> Result<Json, String>;
+
+/// A helper computing its value from a single parameter.
+struct ValueHelper(Compute);
+
+impl HelperDef for ValueHelper {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        _: &'reg Handlebars<'reg>,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<ScopedJson<'rc>, RenderError> {
+        let fail = |reason: String| RenderErrorReason::Other(format!("{}: {reason}", h.name()));
+        let [param] = h.params().as_slice() else {
+            return Err(fail(format!("expected 1 parameter, got {}", h.params().len())).into());
+        };
+        (self.0)(param.value())
+            .map(ScopedJson::Derived)
+            .map_err(|reason| fail(reason).into())
+    }
+
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let value = self.call_inner(h, r, ctx, rc)?;
+        out.write(&value.render())?;
+        Ok(())
+    }
+}
+
+/// Registers the helpers on `hbs`.
+pub(super) fn register(hbs: &mut Handlebars<'_>) {
+    let helpers: [(&str, Compute); 7] = [
+        ("first_added", |changes| first_added(changes, |_| true)),
+        ("first_added_v4", |changes| {
+            first_added(changes, IpAddr::is_ipv4)
+        }),
+        ("first_added_v6", |changes| {
+            first_added(changes, IpAddr::is_ipv6)
+        }),
+        ("only_added", only_added),
+        ("json", |value| Ok(Json::String(value.to_string()))),
+        ("iso8601", iso8601),
+        ("urlencode", urlencode),
+    ];
+    for (name, helper) in helpers {
+        hbs.register_helper(name, Box::new(ValueHelper(helper)));
+    }
+}
+
+/// The elements of a changes array; none for a missing value.
+fn changes_of(value: &Json) -> Result<&[Json], String> {
+    match value {
+        Json::Null => Ok(&[]),
+        Json::Array(changes) => Ok(changes),
+        _ => Err("expected a changes array".to_string()),
+    }
+}
+
+fn is_added(change: &Json) -> bool {
+    change.get("kind").and_then(Json::as_str) == Some("added")
+}
+
+fn first_added(changes: &Json, family: fn(&IpAddr) -> bool) -> Result<Json, String> {
+    Ok(changes_of(changes)?
+        .iter()
+        .filter(|change| is_added(change))
+        .filter_map(|change| change.get("address")?.as_str())
+        .find(|address| address.parse().is_ok_and(|a| family(&a)))
+        .map_or(Json::Null, |address| Json::String(address.to_string())))
+}
+
+fn only_added(changes: &Json) -> Result<Json, String> {
+    let added = changes_of(changes)?.iter().filter(|c| is_added(c));
+    Ok(Json::Array(added.cloned().collect()))
+}
+
+fn iso8601(timestamp: &Json) -> Result<Json, String> {
+    match timestamp {
+        Json::Null => Ok(Json::Null),
+        Json::Number(n) => n
+            .as_u64()
+            .map(|secs| Json::String(format_rfc3339(secs)))
+            .ok_or_else(|| format!("expected a Unix timestamp, got {n}")),
+        other => Err(format!("expected a Unix timestamp, got {other}")),
+    }
+}
+
+fn urlencode(value: &Json) -> Result<Json, String> {
+    let text = match value {
+        Json::Null => return Ok(Json::Null),
+        Json::String(s) => s.clone(),
+        Json::Number(_) | Json::Bool(_) => value.to_string(),
+        _ => return Err("expected a string or number".to_string()),
+    };
+    Ok(Json::String(percent_encode(&text)))
+}
+
+/// Percent-encodes `text` for any part of a URL or a form body.
+pub(super) fn percent_encode(text: &str) -> String {
+    // Spaces as %20 rather than '+', which only form bodies read as a space
+    let encoded: String = form_urlencoded::byte_serialize(text.as_bytes()).collect();
+    encoded.replace('+', "%20")
+}
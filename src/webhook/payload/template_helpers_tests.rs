@@ -0,0 +1,174 @@
+//! Tests for the custom template helpers.
+
+use serde_json::json;
+
+use crate::webhook::template_registry;
+
+fn render(template: &str, data: &serde_json::Value) -> Result<String, String> {
+    template_registry()
+        .render_template(template, data)
+        .map_err(|e| e.to_string())
+}
+
+fn changes() -> serde_json::Value {
+    json!({
+        "changes": [
+            {"adapter": "eth0", "address": "192.0.2.1", "kind": "removed", "timestamp": 0},
+            {"adapter": "eth0", "address": "2001:db8::1", "kind": "added", "timestamp": 0},
+            {"adapter": "eth0", "address": "192.0.2.2", "kind": "added", "timestamp": 0},
+            {"adapter": "eth0", "address": "192.0.2.3", "kind": "added", "timestamp": 0}
+        ]
+    })
+}
+
+mod first_added {
+    use super::*;
+
+    #[test]
+    fn picks_the_first_added_address_of_the_version() {
+        let rendered = render(
+            "{{first_added_v4 changes}} {{first_added_v6 changes}}",
+            &changes(),
+        )
+        .unwrap();
+
+        assert_eq!(rendered, "192.0.2.2 2001:db8::1");
+    }
+
+    #[test]
+    fn first_added_ignores_the_version() {
+        let rendered = render("{{first_added changes}}", &changes()).unwrap();
+
+        assert_eq!(rendered, "2001:db8::1");
+    }
+
+    #[test]
+    fn renders_empty_without_such_address() {
+        let data = json!({"changes": [
+            {"adapter": "eth0", "address": "2001:db8::1", "kind": "removed", "timestamp": 0}
+        ]});
+
+        assert_eq!(render("[{{first_added_v6 changes}}]", &data).unwrap(), "[]");
+        assert_eq!(
+            render("[{{first_added_v4 changes}}]", &json!({})).unwrap(),
+            "[]"
+        );
+    }
+
+    #[test]
+    fn rejects_other_values() {
+        let error = render("{{first_added_v4 \"x\"}}", &json!({})).unwrap_err();
+
+        assert!(
+            error.contains("first_added_v4: expected a changes array"),
+            "{error}"
+        );
+    }
+}
+
+mod only_added {
+    use super::*;
+
+    #[test]
+    fn filters_added_changes() {
+        let rendered = render(
+            "{{#each (only_added changes)}}{{address}};{{/each}}",
+            &changes(),
+        )
+        .unwrap();
+
+        assert_eq!(rendered, "2001:db8::1;192.0.2.2;192.0.2.3;");
+    }
+}
+
+mod json {
+    use super::*;
+
+    #[test]
+    fn renders_values_as_unescaped_json() {
+        let data = json!({"changes": [{"adapter": "a\"b", "address": "192.0.2.1"}]});
+
+        let rendered = render(r#"{"items": {{json changes}}}"#, &data).unwrap();
+
+        let parsed: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(parsed["items"], data["changes"]);
+    }
+
+    #[test]
+    fn composes_with_other_helpers() {
+        let rendered = render("{{json (first_added_v4 changes)}}", &changes()).unwrap();
+
+        assert_eq!(rendered, r#""192.0.2.2""#);
+    }
+}
+
+mod iso8601 {
+    use super::*;
+
+    #[test]
+    fn formats_unix_timestamps_in_utc() {
+        for (timestamp, expected) in [
+            (0_u64, "1970-01-01T00:00:00Z"),
+            (951_782_400, "2000-02-29T00:00:00Z"),
+            (1_714_564_800, "2024-05-01T12:00:00Z"),
+            (4_107_542_399, "2100-02-28T23:59:59Z"),
+        ] {
+            let data = json!({ "timestamp": timestamp });
+
+            assert_eq!(render("{{iso8601 timestamp}}", &data).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn rejects_non_timestamps() {
+        let error = render("{{iso8601 \"yesterday\"}}", &json!({})).unwrap_err();
+
+        assert!(
+            error.contains("iso8601: expected a Unix timestamp"),
+            "{error}"
+        );
+    }
+}
+
+mod urlencode {
+    use super::*;
+
+    #[test]
+    fn percent_encodes_values() {
+        let data = json!({"host": "my host/a+b", "port": 8080});
+
+        let rendered = render("{{urlencode host}}:{{urlencode port}}", &data).unwrap();
+
+        assert_eq!(rendered, "my%20host%2Fa%2Bb:8080");
+    }
+
+    #[test]
+    fn encodes_ipv6_addresses() {
+        let rendered = render("ip={{urlencode (first_added_v6 changes)}}", &changes()).unwrap();
+
+        assert_eq!(rendered, "ip=2001%3Adb8%3A%3A1");
+    }
+}
+
+#[test]
+fn helpers_take_one_parameter() {
+    for template in ["{{json}}", "{{urlencode a b}}", "{{iso8601}}"] {
+        let error = render(template, &json!({})).unwrap_err();
+
+        assert!(
+            error.contains("expected 1 parameter"),
+            "{template}: {error}"
+        );
+    }
+}
+
+#[test]
+fn missing_values_render_empty() {
+    let rendered = render(
+        "[{{iso8601 timestamp}}{{urlencode host}}{{first_added_v6 changes}}]",
+        &json!({}),
+    )
+    .unwrap();
+
+    assert_eq!(rendered, "[]");
+}
@@ -0,0 +1,206 @@
+//! Tests for body template checks.
+
+use std::time::{Duration, SystemTime};
+
+use super::template::{check_template, sample_changes, sample_json};
+use crate::monitor::IpChange;
+
+fn changes() -> Vec<IpChange> {
+    vec![IpChange::added(
+        "eth0",
+        "192.168.1.1".parse().unwrap(),
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+    )]
+}
+
+#[cfg(feature = "templates")]
+mod check {
+    use super::*;
+    use crate::webhook::{SAMPLE_NONCE, SAMPLE_TTL};
+
+    #[test]
+    fn renders_payload_variables() {
+        let rendered = check_template(
+            "{{#each changes}}{{adapter}} {{address}} {{kind}} {{timestamp}}{{/each}} {{attempt}}",
+            &changes(),
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(rendered, "eth0 192.168.1.1 added 1700000000 1");
+    }
+
+    #[test]
+    fn renders_sample_ttl() {
+        let rendered = check_template(
+            "{{ttl}} {{#each changes}}{{expires}}{{/each}}",
+            &changes(),
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rendered,
+            format!("{SAMPLE_TTL} {}", 1_700_000_000 + SAMPLE_TTL)
+        );
+    }
+
+    #[test]
+    fn renders_sample_nonce() {
+        let rendered = check_template("{{nonce}}", &changes(), false).unwrap();
+
+        assert_eq!(rendered, SAMPLE_NONCE);
+    }
+
+    #[test]
+    fn missing_variable_is_error() {
+        let result = check_template("{{#each changes}}{{adress}}{{/each}}", &changes(), false);
+
+        assert!(result.unwrap_err().contains("adress"));
+    }
+
+    #[test]
+    fn syntax_error_is_error() {
+        assert!(check_template("{{#each changes}}", &changes(), false).is_err());
+    }
+
+    #[test]
+    fn json_mode_accepts_json() {
+        let template = r#"{"ip": "{{changes.0.address}}"}"#;
+
+        let rendered = check_template(template, &changes(), true).unwrap();
+
+        assert_eq!(rendered, r#"{"ip": "192.168.1.1"}"#);
+    }
+
+    #[test]
+    fn json_mode_rejects_invalid_json() {
+        let template = r#"{"ip": {{changes.0.address}}}"#;
+
+        let error = check_template(template, &changes(), true).unwrap_err();
+
+        assert!(error.starts_with("rendered output is not valid JSON"));
+        assert!(check_template(template, &changes(), false).is_ok());
+    }
+}
+
+#[cfg(not(feature = "templates"))]
+#[test]
+fn check_requires_templates_feature() {
+    let error = check_template("{{attempt}}", &changes(), false).unwrap_err();
+
+    assert!(error.contains("'templates' feature"));
+}
+
+mod samples {
+    use super::*;
+
+    #[test]
+    fn parses_change_array() {
+        let parsed = sample_changes(
+            r#"[{"adapter": "eth0", "address": "192.168.1.1", "kind": "added", "timestamp": 1700000000},
+                {"adapter": "wlan0", "address": "fe80::1", "kind": "removed", "timestamp": 0}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0], changes()[0]);
+        assert!(!parsed[1].is_added());
+        assert_eq!(
+            parsed[1].address,
+            "fe80::1".parse::<std::net::IpAddr>().unwrap()
+        );
+    }
+
+    #[test]
+    fn parses_captured_payload() {
+        let parsed = sample_changes(
+            r#"{"schema_version": 1, "attempt": 2,
+                "changes": [{"adapter": "eth0", "address": "192.168.1.1", "kind": "added", "timestamp": 1700000000}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(parsed, changes());
+    }
+
+    #[test]
+    fn kind_and_timestamp_default() {
+        let parsed = sample_changes(r#"[{"adapter": "eth0", "address": "10.0.0.1"}]"#).unwrap();
+
+        assert!(parsed[0].is_added());
+        assert_eq!(parsed[0].timestamp, SystemTime::UNIX_EPOCH);
+    }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn metadata_is_rendered() {
+        let parsed = sample_changes(
+            r#"[{"adapter": "eth0", "address": "10.0.0.1",
+                 "metadata": {"mac": "00:1a:2b:3c:4d:5e", "mtu": 1500}}]"#,
+        )
+        .unwrap();
+
+        let rendered = check_template(
+            "{{#each changes}}{{metadata.mac}} {{metadata.mtu}}{{/each}}",
+            &parsed,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(rendered, "00:1a:2b:3c:4d:5e 1500");
+        assert_eq!(sample_changes(&sample_json(&parsed)).unwrap(), parsed);
+    }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn address_details_are_rendered() {
+        let parsed = sample_changes(
+            r#"{"changes": [{"adapter": "eth0", "address": "2001:db8::5", "scope": "global",
+                             "prefix_len": 64, "state": "deprecated"}]}"#,
+        )
+        .unwrap();
+
+        let rendered = check_template(
+            "{{#each changes}}{{address}}/{{prefix_len}} {{scope}} {{state}}{{/each}}",
+            &parsed,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(rendered, "2001:db8::5/64 global deprecated");
+        assert_eq!(sample_changes(&sample_json(&parsed)).unwrap(), parsed);
+    }
+
+    #[test]
+    fn rejects_invalid_samples() {
+        for json in [
+            "not json",
+            r#"[{"adapter": "eth0"}]"#,
+            r#"[{"adapter": "eth0", "address": "not-an-ip"}]"#,
+            r#"[{"adapter": "eth0", "address": "10.0.0.1", "kind": "changed"}]"#,
+            r#"[{"adapter": "eth0", "address": "10.0.0.1", "extra": 1}]"#,
+            r#"{"dns_changes": []}"#,
+        ] {
+            assert!(sample_changes(json).is_err(), "{json}");
+        }
+    }
+
+    #[test]
+    fn sample_json_reads_back() {
+        let mut changes = changes();
+        changes.push(IpChange::removed(
+            "wlan0",
+            "fe80::1".parse().unwrap(),
+            SystemTime::UNIX_EPOCH + Duration::from_millis(2_500),
+        ));
+
+        let parsed = sample_changes(&sample_json(&changes)).unwrap();
+
+        assert_eq!(parsed[0], changes[0]);
+        assert!(!parsed[1].is_added());
+        assert_eq!(
+            parsed[1].timestamp,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(2)
+        );
+    }
+}
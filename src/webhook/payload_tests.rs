@@ -0,0 +1,486 @@
+//! Tests for the payload model and its compatibility policy.
+
+use super::payload::{Attempt, Payload};
+use super::{SCHEMA_HEADER, SCHEMA_VERSION};
+use crate::monitor::{
+    AddressDrift, AddressStorm, DnsChange, DriftChange, DriftKind, HostnameChange, IpChange,
+    LinkChange, LinkState, StormChange,
+};
+use crate::network::{AdapterMetadata, AddressInfo, AddressState, DnsSettings, OperStatus};
+use serde_json::Value;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn at(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+fn ip_changes() -> Vec<IpChange> {
+    vec![IpChange::added(
+        "eth0",
+        "192.0.2.1".parse().unwrap(),
+        at(100),
+    )]
+}
+
+fn dns_changes() -> Vec<DnsChange> {
+    vec![DnsChange {
+        adapter: "vpn".to_string(),
+        old: DnsSettings {
+            suffix: String::new(),
+            servers: vec![],
+        },
+        new: DnsSettings {
+            suffix: "corp.example.com".to_string(),
+            servers: vec!["10.0.0.53".parse().unwrap()],
+        },
+        timestamp: at(100),
+    }]
+}
+
+fn drift_changes() -> Vec<DriftChange> {
+    vec![
+        DriftChange {
+            drift: AddressDrift {
+                kind: DriftKind::Missing,
+                address: "203.0.113.10".parse().unwrap(),
+                adapter: None,
+            },
+            resolved: false,
+            timestamp: at(100),
+        },
+        DriftChange {
+            drift: AddressDrift {
+                kind: DriftKind::Unexpected,
+                address: "198.51.100.7".parse().unwrap(),
+                adapter: Some("eth0".to_string()),
+            },
+            resolved: true,
+            timestamp: at(100),
+        },
+    ]
+}
+
+fn hostname_changes() -> Vec<HostnameChange> {
+    vec![HostnameChange {
+        old: "old-pc".to_string(),
+        new: "new-pc".to_string(),
+        timestamp: at(100),
+    }]
+}
+
+fn storm_changes() -> Vec<StormChange> {
+    vec![StormChange {
+        storm: AddressStorm {
+            adapter: "eth0".to_string(),
+            count: 40,
+            limit: 16,
+        },
+        resolved: false,
+        prefix_only: true,
+        timestamp: at(100),
+    }]
+}
+
+fn link_changes() -> Vec<LinkChange> {
+    vec![LinkChange {
+        adapter: "eth0".to_string(),
+        state: LinkState::Down,
+        status: OperStatus::LowerLayerDown,
+        timestamp: at(100),
+    }]
+}
+
+fn render(payload: Payload<'_>, attempt: u32) -> Value {
+    let data = payload.data(Attempt {
+        number: attempt,
+        elapsed: Duration::from_millis(1500),
+    });
+    serde_json::to_value(data).unwrap()
+}
+
+/// Compact JSON type name, for comparing against the frozen field lists.
+fn type_of(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Asserts every frozen field is present in `object` with its frozen type.
+///
+/// Extra fields are allowed: adding fields is compatible.
+fn assert_fields(object: &Value, frozen: &[(&str, &str)]) {
+    for (name, ty) in frozen {
+        let value = object
+            .get(name)
+            .unwrap_or_else(|| panic!("field '{name}' removed without a schema bump"));
+        assert_eq!(type_of(value), *ty, "field '{name}' changed type");
+    }
+}
+
+mod schema_v1 {
+    use super::*;
+
+    /// Fields of every delivery in schema version 1.
+    const DELIVERY: &[(&str, &str)] = &[
+        ("schema_version", "number"),
+        ("attempt", "number"),
+        ("elapsed_ms", "number"),
+        ("is_retry", "bool"),
+    ];
+
+    #[test]
+    fn current_version() {
+        // Bumping the version requires freezing its fields in a new module
+        assert_eq!(SCHEMA_VERSION, 1);
+        assert_eq!(SCHEMA_HEADER.as_str(), "x-ddns-a-schema");
+    }
+
+    #[test]
+    fn address_payload_fields() {
+        let changes = ip_changes();
+        let json = render(Payload::Ip(&changes), 1);
+
+        assert_fields(&json, DELIVERY);
+        assert_fields(&json, &[("changes", "array")]);
+        assert_fields(
+            &json["changes"][0],
+            &[
+                ("adapter", "string"),
+                ("address", "string"),
+                ("kind", "string"),
+                ("timestamp", "number"),
+                ("scope", "string"),
+            ],
+        );
+    }
+
+    #[test]
+    fn dns_payload_fields() {
+        let changes = dns_changes();
+        let json = render(Payload::Dns(&changes), 1);
+
+        assert_fields(&json, DELIVERY);
+        assert_fields(&json, &[("dns_changes", "array")]);
+        assert_fields(
+            &json["dns_changes"][0],
+            &[
+                ("adapter", "string"),
+                ("suffix", "string"),
+                ("old_suffix", "string"),
+                ("servers", "array"),
+                ("old_servers", "array"),
+                ("timestamp", "number"),
+            ],
+        );
+    }
+
+    #[test]
+    fn drift_payload_fields() {
+        let changes = drift_changes();
+        let json = render(Payload::Drift(&changes), 1);
+
+        assert_fields(&json, DELIVERY);
+        assert_fields(&json, &[("drift", "array")]);
+        assert_fields(
+            &json["drift"][1],
+            &[
+                ("kind", "string"),
+                ("address", "string"),
+                ("adapter", "string"),
+                ("resolved", "bool"),
+                ("timestamp", "number"),
+            ],
+        );
+    }
+
+    #[test]
+    fn hostname_payload_fields() {
+        let changes = hostname_changes();
+        let json = render(Payload::Hostname(&changes), 1);
+
+        assert_fields(&json, DELIVERY);
+        assert_fields(&json, &[("hostname_changes", "array")]);
+        assert_fields(
+            &json["hostname_changes"][0],
+            &[
+                ("hostname", "string"),
+                ("old_hostname", "string"),
+                ("timestamp", "number"),
+            ],
+        );
+    }
+
+    #[test]
+    fn storm_payload_fields() {
+        let changes = storm_changes();
+        let json = render(Payload::Storm(&changes), 1);
+
+        assert_fields(&json, DELIVERY);
+        assert_fields(&json, &[("storms", "array")]);
+        assert_fields(
+            &json["storms"][0],
+            &[
+                ("adapter", "string"),
+                ("count", "number"),
+                ("limit", "number"),
+                ("resolved", "bool"),
+                ("prefix_only", "bool"),
+                ("timestamp", "number"),
+            ],
+        );
+    }
+
+    #[test]
+    fn link_payload_fields() {
+        let changes = link_changes();
+        let json = render(Payload::Link(&changes), 1);
+
+        assert_fields(&json, DELIVERY);
+        assert_fields(&json, &[("link_changes", "array")]);
+        assert_fields(
+            &json["link_changes"][0],
+            &[
+                ("adapter", "string"),
+                ("state", "string"),
+                ("status", "string"),
+                ("timestamp", "number"),
+            ],
+        );
+        assert_eq!(json["link_changes"][0]["status"], "lower_layer_down");
+    }
+
+    #[test]
+    fn address_payload_values() {
+        let changes = ip_changes();
+
+        assert_eq!(
+            render(Payload::Ip(&changes), 2),
+            serde_json::json!({
+                "schema_version": 1,
+                "changes": [{
+                    "adapter": "eth0",
+                    "address": "192.0.2.1",
+                    "kind": "added",
+                    "timestamp": 100,
+                    "scope": "global",
+                }],
+                "attempt": 2,
+                "elapsed_ms": 1500,
+                "is_retry": true,
+            })
+        );
+    }
+
+    #[test]
+    fn dns_payload_values() {
+        let changes = dns_changes();
+
+        assert_eq!(
+            render(Payload::Dns(&changes), 1),
+            serde_json::json!({
+                "schema_version": 1,
+                "dns_changes": [{
+                    "adapter": "vpn",
+                    "suffix": "corp.example.com",
+                    "old_suffix": "",
+                    "servers": ["10.0.0.53"],
+                    "old_servers": [],
+                    "timestamp": 100,
+                }],
+                "attempt": 1,
+                "elapsed_ms": 1500,
+                "is_retry": false,
+            })
+        );
+    }
+}
+
+mod drift {
+    use super::*;
+
+    #[test]
+    fn missing_address_has_no_adapter() {
+        let changes = drift_changes();
+
+        assert_eq!(
+            render(Payload::Drift(&changes), 1)["drift"],
+            serde_json::json!([
+                {
+                    "kind": "missing",
+                    "address": "203.0.113.10",
+                    "resolved": false,
+                    "timestamp": 100,
+                },
+                {
+                    "kind": "unexpected",
+                    "address": "198.51.100.7",
+                    "adapter": "eth0",
+                    "resolved": true,
+                    "timestamp": 100,
+                },
+            ])
+        );
+    }
+}
+
+mod nonce {
+    use super::*;
+
+    #[test]
+    fn absent_by_default() {
+        let changes = ip_changes();
+        let json = render(Payload::Ip(&changes), 1);
+
+        assert!(json.get("nonce").is_none());
+    }
+
+    #[test]
+    fn added_with_echo_check() {
+        let changes = ip_changes();
+        let attempt = Attempt {
+            number: 1,
+            elapsed: Duration::ZERO,
+        };
+        let data = Payload::Ip(&changes).data(attempt).with_nonce(Some("abc"));
+        let json = serde_json::to_value(data).unwrap();
+
+        assert_eq!(json["nonce"], "abc");
+    }
+}
+
+mod ttl {
+    use super::*;
+
+    fn render_with_ttl(payload: Payload<'_>, ttl: Option<u32>) -> Value {
+        let attempt = Attempt {
+            number: 1,
+            elapsed: Duration::ZERO,
+        };
+        serde_json::to_value(payload.data(attempt).with_ttl(ttl)).unwrap()
+    }
+
+    #[test]
+    fn absent_by_default() {
+        let changes = ip_changes();
+        let json = render_with_ttl(Payload::Ip(&changes), None);
+
+        assert!(json.get("ttl").is_none());
+        assert!(json["changes"][0].get("expires").is_none());
+    }
+
+    #[test]
+    fn added_addresses_expire() {
+        let changes = vec![
+            IpChange::added("eth0", "192.0.2.1".parse().unwrap(), at(100)),
+            IpChange::removed("eth0", "192.0.2.2".parse().unwrap(), at(100)),
+        ];
+        let json = render_with_ttl(Payload::Ip(&changes), Some(300));
+
+        assert_eq!(json["ttl"], 300);
+        assert_eq!(json["changes"][0]["expires"], 400);
+        assert!(json["changes"][1].get("expires").is_none());
+    }
+
+    #[test]
+    fn other_deliveries_are_unchanged() {
+        let dns = dns_changes();
+        let drift = drift_changes();
+
+        for payload in [Payload::Dns(&dns), Payload::Drift(&drift)] {
+            assert_eq!(
+                render_with_ttl(payload, Some(300)),
+                render_with_ttl(payload, None)
+            );
+        }
+    }
+}
+
+mod metadata {
+    use super::*;
+
+    #[test]
+    fn absent_without_adapter_details() {
+        let changes = ip_changes();
+        let json = render(Payload::Ip(&changes), 1);
+
+        assert!(json["changes"][0].get("metadata").is_none());
+    }
+
+    #[test]
+    fn carries_known_adapter_details() {
+        let metadata = AdapterMetadata {
+            mac: Some("00:1a:2b:3c:4d:5e".to_string()),
+            index: Some(7),
+            mtu: Some(1500),
+            link_speed: Some(1_000_000_000),
+            dns_suffix: Some("lan".to_string()),
+            oper_status: Some(OperStatus::Up),
+        };
+        let changes: Vec<IpChange> = ip_changes()
+            .into_iter()
+            .map(|c| c.with_metadata(Some(metadata.clone())))
+            .collect();
+        let json = render(Payload::Ip(&changes), 1);
+
+        assert_eq!(
+            json["changes"][0]["metadata"],
+            serde_json::json!({
+                "mac": "00:1a:2b:3c:4d:5e",
+                "index": 7,
+                "mtu": 1500,
+                "link_speed": 1_000_000_000,
+                "dns_suffix": "lan",
+                "oper_status": "up",
+            })
+        );
+    }
+}
+
+mod address_info {
+    use super::*;
+
+    #[test]
+    fn absent_without_address_details() {
+        let changes = ip_changes();
+        let json = render(Payload::Ip(&changes), 1);
+
+        assert!(json["changes"][0].get("prefix_len").is_none());
+        assert!(json["changes"][0].get("state").is_none());
+    }
+
+    #[test]
+    fn carries_known_address_details() {
+        let changes = vec![
+            IpChange::removed("eth0", "fe80::1".parse().unwrap(), at(100)).with_info(AddressInfo {
+                prefix_len: Some(64),
+                state: Some(AddressState::Deprecated),
+            }),
+        ];
+        let json = render(Payload::Ip(&changes), 1);
+
+        let change = &json["changes"][0];
+        assert_eq!(change["prefix_len"], 64);
+        assert_eq!(change["scope"], "link_local");
+        assert_eq!(change["state"], "deprecated");
+    }
+}
+
+mod attempt {
+    use super::*;
+
+    #[test]
+    fn elapsed_saturates() {
+        let attempt = Attempt {
+            number: 1,
+            elapsed: Duration::MAX,
+        };
+
+        assert_eq!(attempt.elapsed_ms(), u64::MAX);
+        assert!(!attempt.is_retry());
+    }
+}
@@ -0,0 +1,464 @@
+//! Built-in presets for popular DNS providers.
+//!
+//! A [`Provider`] replaces the generic webhook request with the provider's
+//! own update API: it builds the update request(s) for a batch of changes
+//! and checks the answer, since most of these APIs report failures with a
+//! `200 OK` status. Providers are therefore selectable by name and
+//! credentials alone, without describing their API with URLs and templates.
+
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use http::HeaderValue;
+use http::header::{AUTHORIZATION, CONTENT_TYPE};
+use serde_json::{Value, json};
+use url::Url;
+
+use crate::monitor::IpChange;
+
+use super::{HttpRequest, HttpResponse, REDACTED};
+
+/// Record TTL in seconds used unless configured (Porkbun's minimum).
+pub const DEFAULT_PROVIDER_TTL: u32 = 600;
+
+/// A supported DNS provider API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderKind {
+    /// `DuckDNS`: `GET /update` with the token in the query.
+    DuckDns,
+    /// Porkbun JSON API v3: `POST /dns/editByNameType` with API keys in the body.
+    Porkbun,
+    /// Gandi `LiveDNS`: `PUT` of the record set with a personal access token.
+    Gandi,
+    /// deSEC dynDNS: `GET /` on `update.dedyn.io` with a token header.
+    Desec,
+    /// No-IP: `GET /nic/update` with HTTP basic authentication.
+    NoIp,
+    /// Dynu: `GET /nic/update` with the IP update password in the query.
+    Dynu,
+    /// `FreeDNS` (afraid.org) v2: `GET /u/<token>/` with the address in the query.
+    FreeDns,
+}
+
+impl ProviderKind {
+    /// All supported providers.
+    pub const ALL: [Self; 7] = [
+        Self::DuckDns,
+        Self::Porkbun,
+        Self::Gandi,
+        Self::Desec,
+        Self::NoIp,
+        Self::Dynu,
+        Self::FreeDns,
+    ];
+
+    /// Returns the name used to select this provider in configuration.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::DuckDns => "duckdns",
+            Self::Porkbun => "porkbun",
+            Self::Gandi => "gandi",
+            Self::Desec => "desec",
+            Self::NoIp => "noip",
+            Self::Dynu => "dynu",
+            Self::FreeDns => "freedns",
+        }
+    }
+
+    /// Returns the provider with the given configuration name.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the base URL of the provider's update API.
+    ///
+    /// Request paths and queries are appended to it, so a different base
+    /// (e.g. a test server) can be used as the webhook URL.
+    ///
+    /// # Panics
+    ///
+    /// Never: the endpoints are valid URL literals.
+    #[must_use]
+    pub fn endpoint(self) -> Url {
+        let url = match self {
+            Self::DuckDns => "https://www.duckdns.org/update",
+            Self::Porkbun => "https://api.porkbun.com/api/json/v3/dns/editByNameType",
+            Self::Gandi => "https://api.gandi.net/v5/livedns/domains",
+            Self::Desec => "https://update.dedyn.io/",
+            Self::NoIp => "https://dynupdate.no-ip.com/nic/update",
+            Self::Dynu => "https://api.dynu.com/nic/update",
+            Self::FreeDns => "https://sync.afraid.org/u/",
+        };
+        Url::parse(url).expect("provider endpoints are valid URLs")
+    }
+
+    /// Returns true if the provider needs a secret in addition to the token.
+    #[must_use]
+    pub const fn needs_secret(self) -> bool {
+        matches!(self, Self::Porkbun | Self::NoIp)
+    }
+
+    /// Returns true if a record name within the domain can be set.
+    #[must_use]
+    pub const fn has_records(self) -> bool {
+        matches!(self, Self::Porkbun | Self::Gandi | Self::Desec)
+    }
+}
+
+impl fmt::Display for ProviderKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A configured DNS provider preset.
+///
+/// Only added addresses are sent: for each family, the newest one in the
+/// batch. A batch without added addresses leaves the records as they are.
+///
+/// # Records
+///
+/// - `DuckDNS`: `domain` is the subdomain (`myhost` for `myhost.duckdns.org`);
+///   without an IPv4 address `DuckDNS` uses the request's source address.
+/// - Porkbun and Gandi: `domain` is the zone and `record` the name within it
+///   (the apex if unset); one request is sent per record type.
+/// - deSEC: `domain` is the hostname, prefixed with `record` if set; the
+///   family without an address is kept (`preserve`).
+/// - No-IP: `domain` is the hostname, `token` the username and `secret` the
+///   password; both families are sent in one `myip` list.
+/// - Dynu: `domain` is the hostname and `token` the IP update password; the
+///   family without an address is kept (`no`).
+/// - `FreeDNS`: `token` is the record's update token, which selects the
+///   record, so `domain` is only shown in logs. A token updates a single A
+///   or AAAA record: the IPv4 address is sent if there is one, else the
+///   IPv6 address.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Provider {
+    kind: ProviderKind,
+    domain: String,
+    record: Option<String>,
+    token: String,
+    secret: Option<String>,
+    ttl: u32,
+}
+
+impl Provider {
+    /// Creates a preset for `domain`, authenticated with `token`.
+    #[must_use]
+    pub fn new(kind: ProviderKind, domain: impl Into<String>, token: impl Into<String>) -> Self {
+        Self {
+            kind,
+            domain: domain.into(),
+            record: None,
+            token: token.into(),
+            secret: None,
+            ttl: DEFAULT_PROVIDER_TTL,
+        }
+    }
+
+    /// Sets the record name within the domain (see [Records](Self#records)).
+    #[must_use]
+    pub fn with_record(mut self, record: impl Into<String>) -> Self {
+        self.record = Some(record.into());
+        self
+    }
+
+    /// Sets the secret API key (Porkbun) or password (No-IP).
+    #[must_use]
+    pub fn with_secret(mut self, secret: impl Into<String>) -> Self {
+        self.secret = Some(secret.into());
+        self
+    }
+
+    /// Sets the record TTL in seconds (Porkbun and Gandi).
+    #[must_use]
+    pub const fn with_ttl(mut self, ttl: u32) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Returns the provider API.
+    #[must_use]
+    pub const fn kind(&self) -> ProviderKind {
+        self.kind
+    }
+
+    /// Returns the fully qualified name of the updated record.
+    #[must_use]
+    pub fn hostname(&self) -> String {
+        match (&self.record, self.kind) {
+            (_, ProviderKind::DuckDns) => format!("{}.duckdns.org", self.domain),
+            (Some(record), _) => format!("{record}.{}", self.domain),
+            (None, _) => self.domain.clone(),
+        }
+    }
+
+    /// Builds the update requests for `changes`.
+    ///
+    /// Each request starts as a copy of `base`, whose URL is the API base
+    /// and whose headers are kept unless the provider sets them.
+    #[must_use]
+    pub fn requests(&self, base: &HttpRequest, changes: &[IpChange]) -> Vec<HttpRequest> {
+        let (v4, v6) = latest_addresses(changes);
+        if v4.is_none() && v6.is_none() {
+            return Vec::new();
+        }
+
+        match self.kind {
+            ProviderKind::DuckDns => vec![self.duckdns(base, v4, v6)],
+            ProviderKind::Desec => vec![self.desec(base, v4, v6)],
+            ProviderKind::NoIp => vec![self.noip(base, v4, v6)],
+            ProviderKind::Dynu => vec![self.dynu(base, v4, v6)],
+            ProviderKind::FreeDns => {
+                let ip = v4.map_or_else(|| v6.map(IpAddr::V6), |v4| Some(IpAddr::V4(v4)));
+                ip.map(|ip| self.freedns(base, ip)).into_iter().collect()
+            }
+            ProviderKind::Porkbun | ProviderKind::Gandi => {
+                let addresses = v4.map(IpAddr::V4).into_iter().chain(v6.map(IpAddr::V6));
+                addresses.map(|ip| self.record_update(base, ip)).collect()
+            }
+        }
+    }
+
+    /// Checks a successful (2xx) response for a provider-reported failure.
+    ///
+    /// # Errors
+    ///
+    /// Returns the reason if the provider did not accept the update.
+    pub fn check(&self, response: &HttpResponse) -> Result<(), String> {
+        let answer = response.body_text().unwrap_or_default().trim();
+        let rejected = || format!("{} answered '{answer}'", self.kind);
+
+        match self.kind {
+            ProviderKind::DuckDns if answer.starts_with("OK") => Ok(()),
+            ProviderKind::Desec | ProviderKind::NoIp | ProviderKind::Dynu
+                if answer.starts_with("good") || answer.starts_with("nochg") =>
+            {
+                Ok(())
+            }
+            ProviderKind::FreeDns
+                if answer.starts_with("Updated") || answer.starts_with("No IP change") =>
+            {
+                Ok(())
+            }
+            ProviderKind::DuckDns
+            | ProviderKind::Desec
+            | ProviderKind::NoIp
+            | ProviderKind::Dynu
+            | ProviderKind::FreeDns => Err(rejected()),
+            ProviderKind::Porkbun => match serde_json::from_str::<Value>(answer) {
+                Ok(json) if json["status"] == "SUCCESS" => Ok(()),
+                Ok(json) if json["message"].is_string() => Err(format!(
+                    "porkbun answered '{}'",
+                    json["message"].as_str().unwrap_or_default()
+                )),
+                _ => Err(rejected()),
+            },
+            // `LiveDNS` answers with a JSON message; anything else is not Gandi
+            ProviderKind::Gandi => match serde_json::from_str::<Value>(answer) {
+                Ok(Value::Object(_)) => Ok(()),
+                _ => Err(rejected()),
+            },
+        }
+    }
+
+    fn duckdns(
+        &self,
+        base: &HttpRequest,
+        v4: Option<Ipv4Addr>,
+        v6: Option<Ipv6Addr>,
+    ) -> HttpRequest {
+        let mut request = get(base);
+        {
+            let mut query = request.url.query_pairs_mut();
+            query
+                .append_pair("domains", &self.domain)
+                .append_pair("token", &self.token);
+            if let Some(v4) = v4 {
+                query.append_pair("ip", &v4.to_string());
+            }
+            if let Some(v6) = v6 {
+                query.append_pair("ipv6", &v6.to_string());
+            }
+        }
+        request
+    }
+
+    fn desec(&self, base: &HttpRequest, v4: Option<Ipv4Addr>, v6: Option<Ipv6Addr>) -> HttpRequest {
+        let address = |ip: Option<String>| ip.unwrap_or_else(|| "preserve".to_string());
+
+        let mut request = get(base);
+        request
+            .url
+            .query_pairs_mut()
+            .append_pair("hostname", &self.hostname())
+            .append_pair("myipv4", &address(v4.map(|ip| ip.to_string())))
+            .append_pair("myipv6", &address(v6.map(|ip| ip.to_string())));
+        set_header(
+            &mut request,
+            AUTHORIZATION,
+            &format!("Token {}", self.token),
+        );
+        request
+    }
+
+    fn noip(&self, base: &HttpRequest, v4: Option<Ipv4Addr>, v6: Option<Ipv6Addr>) -> HttpRequest {
+        let addresses: Vec<String> = v4
+            .map(IpAddr::V4)
+            .into_iter()
+            .chain(v6.map(IpAddr::V6))
+            .map(|ip| ip.to_string())
+            .collect();
+
+        let mut request = get(base);
+        request
+            .url
+            .query_pairs_mut()
+            .append_pair("hostname", &self.domain)
+            .append_pair("myip", &addresses.join(","));
+        let credentials = format!(
+            "{}:{}",
+            self.token,
+            self.secret.as_deref().unwrap_or_default()
+        );
+        set_header(
+            &mut request,
+            AUTHORIZATION,
+            &format!("Basic {}", base64(credentials.as_bytes())),
+        );
+        request
+    }
+
+    fn dynu(&self, base: &HttpRequest, v4: Option<Ipv4Addr>, v6: Option<Ipv6Addr>) -> HttpRequest {
+        let address = |ip: Option<String>| ip.unwrap_or_else(|| "no".to_string());
+
+        let mut request = get(base);
+        request
+            .url
+            .query_pairs_mut()
+            .append_pair("hostname", &self.domain)
+            .append_pair("myip", &address(v4.map(|ip| ip.to_string())))
+            .append_pair("myipv6", &address(v6.map(|ip| ip.to_string())))
+            .append_pair("password", &self.token);
+        request
+    }
+
+    fn freedns(&self, base: &HttpRequest, ip: IpAddr) -> HttpRequest {
+        let mut request = get(base);
+        if let Ok(mut path) = request.url.path_segments_mut() {
+            // The trailing empty segment keeps the API's `/u/<token>/` form
+            path.pop_if_empty().extend([self.token.as_str(), ""]);
+        }
+        request
+            .url
+            .query_pairs_mut()
+            .append_pair("ip", &ip.to_string());
+        request
+    }
+
+    /// Porkbun or Gandi update of the A or AAAA record holding `ip`.
+    fn record_update(&self, base: &HttpRequest, ip: IpAddr) -> HttpRequest {
+        let record_type = if ip.is_ipv4() { "A" } else { "AAAA" };
+        let mut request = base.clone();
+        request.body = None;
+
+        let (segments, body): (Vec<&str>, Value) = if self.kind == ProviderKind::Porkbun {
+            request.method = http::Method::POST;
+            let mut segments = vec![self.domain.as_str(), record_type];
+            segments.extend(self.record.as_deref());
+            let body = json!({
+                "apikey": self.token,
+                "secretapikey": self.secret.as_deref().unwrap_or_default(),
+                "content": ip.to_string(),
+                // Porkbun takes the TTL as a string
+                "ttl": self.ttl.to_string(),
+            });
+            (segments, body)
+        } else {
+            request.method = http::Method::PUT;
+            let name = self.record.as_deref().unwrap_or("@");
+            let segments = vec![self.domain.as_str(), "records", name, record_type];
+            set_header(
+                &mut request,
+                AUTHORIZATION,
+                &format!("Bearer {}", self.token),
+            );
+            let body = json!({ "rrset_values": [ip.to_string()], "rrset_ttl": self.ttl });
+            (segments, body)
+        };
+
+        if let Ok(mut path) = request.url.path_segments_mut() {
+            path.pop_if_empty().extend(segments);
+        }
+        request.body = Some(body.to_string().into_bytes());
+        set_header(&mut request, CONTENT_TYPE, "application/json");
+        request
+    }
+}
+
+impl fmt::Debug for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Provider")
+            .field("kind", &self.kind)
+            .field("domain", &self.domain)
+            .field("record", &self.record)
+            .field("token", &REDACTED)
+            .field("secret", &self.secret.as_ref().map(|_| REDACTED))
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
+
+/// The newest added address of each family in `changes`.
+fn latest_addresses(changes: &[IpChange]) -> (Option<Ipv4Addr>, Option<Ipv6Addr>) {
+    let mut latest = (None, None);
+    for change in changes.iter().filter(|c| c.is_added()) {
+        match change.address {
+            IpAddr::V4(v4) => latest.0 = Some(v4),
+            IpAddr::V6(v6) => latest.1 = Some(v6),
+        }
+    }
+    latest
+}
+
+/// A bodiless GET copy of `base`.
+fn get(base: &HttpRequest) -> HttpRequest {
+    let mut request = base.clone();
+    request.method = http::Method::GET;
+    request.body = None;
+    request.headers.remove(CONTENT_TYPE);
+    request
+}
+
+/// Standard base64 with padding, as HTTP basic authentication needs it.
+pub(super) fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let triple = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, &b)| acc | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (triple >> (18 - 6 * i)) & 0x3f;
+                encoded.push(char::from(ALPHABET[index as usize]));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+fn set_header(request: &mut HttpRequest, name: http::HeaderName, value: &str) {
+    // Credentials were validated as header values when the preset was configured
+    if let Ok(value) = HeaderValue::from_str(value) {
+        request.headers.insert(name, value);
+    }
+}
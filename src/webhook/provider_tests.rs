@@ -0,0 +1,507 @@
+//! Tests for DNS provider presets.
+
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use http::header::{AUTHORIZATION, CONTENT_TYPE};
+use http::{HeaderMap, HeaderValue, Method, StatusCode};
+use serde_json::{Value, json};
+
+use super::{
+    Event, HttpClient, HttpError, HttpRequest, HttpResponse, HttpWebhook, Provider, ProviderKind,
+    RetryableError, WebhookError, WebhookSender,
+};
+use crate::monitor::{DnsChange, IpChange};
+use crate::network::DnsSettings;
+use crate::time::InstantSleeper;
+
+fn added(ip: &str) -> IpChange {
+    IpChange::added("eth0", ip.parse().unwrap(), SystemTime::UNIX_EPOCH)
+}
+
+fn removed(ip: &str) -> IpChange {
+    IpChange::removed("eth0", ip.parse().unwrap(), SystemTime::UNIX_EPOCH)
+}
+
+fn base(kind: ProviderKind) -> HttpRequest {
+    HttpRequest::post(kind.endpoint())
+}
+
+fn response(status: StatusCode, body: &str) -> HttpResponse {
+    HttpResponse::new(status, HeaderMap::new(), body.as_bytes().to_vec())
+}
+
+fn body_json(request: &HttpRequest) -> Value {
+    serde_json::from_slice(request.body.as_deref().unwrap()).unwrap()
+}
+
+mod kind {
+    use super::*;
+
+    #[test]
+    fn from_name_is_case_insensitive() {
+        assert_eq!(
+            ProviderKind::from_name("DuckDNS"),
+            Some(ProviderKind::DuckDns)
+        );
+        assert_eq!(ProviderKind::from_name("desec"), Some(ProviderKind::Desec));
+        assert_eq!(ProviderKind::from_name("cloudflare"), None);
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for kind in ProviderKind::ALL {
+            assert_eq!(ProviderKind::from_name(kind.name()), Some(kind));
+            assert_eq!(kind.to_string(), kind.name());
+        }
+    }
+
+    #[test]
+    fn endpoints_are_https() {
+        for kind in ProviderKind::ALL {
+            assert_eq!(kind.endpoint().scheme(), "https");
+        }
+    }
+}
+
+mod requests {
+    use super::*;
+
+    #[test]
+    fn duckdns_sends_token_and_addresses_in_query() {
+        let provider = Provider::new(ProviderKind::DuckDns, "myhost", "tok");
+        let requests = provider.requests(
+            &base(ProviderKind::DuckDns),
+            &[added("203.0.113.7"), added("2001:db8::7")],
+        );
+
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, Method::GET);
+        assert_eq!(
+            requests[0].url.as_str(),
+            "https://www.duckdns.org/update?domains=myhost&token=tok&ip=203.0.113.7&ipv6=2001%3Adb8%3A%3A7"
+        );
+        assert!(requests[0].body.is_none());
+    }
+
+    #[test]
+    fn duckdns_omits_missing_family() {
+        let provider = Provider::new(ProviderKind::DuckDns, "myhost", "tok");
+        let requests = provider.requests(&base(ProviderKind::DuckDns), &[added("2001:db8::7")]);
+
+        let query = requests[0].url.query().unwrap();
+        assert!(query.contains("ipv6="));
+        assert!(!query.contains("ip=2"));
+        assert!(!query.contains("&ip="));
+    }
+
+    #[test]
+    fn porkbun_sends_one_request_per_record_type() {
+        let provider = Provider::new(ProviderKind::Porkbun, "example.com", "pk1_key")
+            .with_secret("sk1_secret")
+            .with_record("home");
+        let requests = provider.requests(
+            &base(ProviderKind::Porkbun),
+            &[added("203.0.113.7"), added("2001:db8::7")],
+        );
+
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, Method::POST);
+        assert_eq!(
+            requests[0].url.path(),
+            "/api/json/v3/dns/editByNameType/example.com/A/home"
+        );
+        assert_eq!(
+            requests[1].url.path(),
+            "/api/json/v3/dns/editByNameType/example.com/AAAA/home"
+        );
+        assert_eq!(
+            body_json(&requests[0]),
+            json!({
+                "apikey": "pk1_key",
+                "secretapikey": "sk1_secret",
+                "content": "203.0.113.7",
+                "ttl": "600",
+            })
+        );
+        assert_eq!(requests[1].headers[CONTENT_TYPE], "application/json");
+    }
+
+    #[test]
+    fn porkbun_apex_has_no_record_segment() {
+        let provider =
+            Provider::new(ProviderKind::Porkbun, "example.com", "pk1").with_secret("sk1");
+        let requests = provider.requests(&base(ProviderKind::Porkbun), &[added("203.0.113.7")]);
+
+        assert_eq!(
+            requests[0].url.path(),
+            "/api/json/v3/dns/editByNameType/example.com/A"
+        );
+    }
+
+    #[test]
+    fn gandi_puts_record_set_with_bearer_token() {
+        let provider = Provider::new(ProviderKind::Gandi, "example.com", "pat")
+            .with_record("home")
+            .with_ttl(300);
+        let requests = provider.requests(&base(ProviderKind::Gandi), &[added("2001:db8::7")]);
+
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, Method::PUT);
+        assert_eq!(
+            requests[0].url.as_str(),
+            "https://api.gandi.net/v5/livedns/domains/example.com/records/home/AAAA"
+        );
+        assert_eq!(requests[0].headers[AUTHORIZATION], "Bearer pat");
+        assert_eq!(
+            body_json(&requests[0]),
+            json!({ "rrset_values": ["2001:db8::7"], "rrset_ttl": 300 })
+        );
+    }
+
+    #[test]
+    fn gandi_apex_is_at_sign() {
+        let provider = Provider::new(ProviderKind::Gandi, "example.com", "pat");
+        let requests = provider.requests(&base(ProviderKind::Gandi), &[added("203.0.113.7")]);
+
+        assert!(requests[0].url.path().ends_with("/records/@/A"));
+    }
+
+    #[test]
+    fn desec_preserves_missing_family() {
+        let provider = Provider::new(ProviderKind::Desec, "myhost.dedyn.io", "tok");
+        let requests = provider.requests(&base(ProviderKind::Desec), &[added("203.0.113.7")]);
+
+        assert_eq!(requests[0].method, Method::GET);
+        assert_eq!(
+            requests[0].url.as_str(),
+            "https://update.dedyn.io/?hostname=myhost.dedyn.io&myipv4=203.0.113.7&myipv6=preserve"
+        );
+        assert_eq!(requests[0].headers[AUTHORIZATION], "Token tok");
+    }
+
+    #[test]
+    fn noip_sends_both_families_with_basic_auth() {
+        let provider =
+            Provider::new(ProviderKind::NoIp, "myhost.ddns.net", "user").with_secret("pass");
+        let requests = provider.requests(
+            &base(ProviderKind::NoIp),
+            &[added("203.0.113.7"), added("2001:db8::7")],
+        );
+
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, Method::GET);
+        assert_eq!(
+            requests[0].url.as_str(),
+            "https://dynupdate.no-ip.com/nic/update?hostname=myhost.ddns.net&myip=203.0.113.7%2C2001%3Adb8%3A%3A7"
+        );
+        assert_eq!(requests[0].headers[AUTHORIZATION], "Basic dXNlcjpwYXNz");
+    }
+
+    #[test]
+    fn dynu_keeps_missing_family() {
+        let provider = Provider::new(ProviderKind::Dynu, "myhost.dynu.net", "pw");
+        let requests = provider.requests(&base(ProviderKind::Dynu), &[added("2001:db8::7")]);
+
+        assert_eq!(requests[0].method, Method::GET);
+        assert_eq!(
+            requests[0].url.as_str(),
+            "https://api.dynu.com/nic/update?hostname=myhost.dynu.net&myip=no&myipv6=2001%3Adb8%3A%3A7&password=pw"
+        );
+    }
+
+    #[test]
+    fn freedns_puts_token_in_path_and_prefers_ipv4() {
+        let provider = Provider::new(ProviderKind::FreeDns, "myhost.mooo.com", "abc123");
+        let both = provider.requests(
+            &base(ProviderKind::FreeDns),
+            &[added("2001:db8::7"), added("203.0.113.7")],
+        );
+        let v6 = provider.requests(&base(ProviderKind::FreeDns), &[added("2001:db8::7")]);
+
+        assert_eq!(both.len(), 1);
+        assert_eq!(both[0].method, Method::GET);
+        assert_eq!(
+            both[0].url.as_str(),
+            "https://sync.afraid.org/u/abc123/?ip=203.0.113.7"
+        );
+        assert_eq!(
+            v6[0].url.as_str(),
+            "https://sync.afraid.org/u/abc123/?ip=2001%3Adb8%3A%3A7"
+        );
+    }
+
+    #[test]
+    fn basic_auth_pads_credentials() {
+        let provider = |user: &str, pass: &str| {
+            let requests = Provider::new(ProviderKind::NoIp, "h", user)
+                .with_secret(pass)
+                .requests(&base(ProviderKind::NoIp), &[added("203.0.113.7")]);
+            requests[0].headers[AUTHORIZATION].clone()
+        };
+
+        assert_eq!(provider("a", "b"), "Basic YTpi");
+        assert_eq!(provider("ab", "c"), "Basic YWI6Yw==");
+        assert_eq!(provider("ab", "cd"), "Basic YWI6Y2Q=");
+    }
+
+    #[test]
+    fn uses_newest_added_address_of_each_family() {
+        let provider = Provider::new(ProviderKind::Gandi, "example.com", "pat");
+        let requests = provider.requests(
+            &base(ProviderKind::Gandi),
+            &[
+                added("203.0.113.7"),
+                removed("203.0.113.7"),
+                added("203.0.113.8"),
+            ],
+        );
+
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            body_json(&requests[0])["rrset_values"],
+            json!(["203.0.113.8"])
+        );
+    }
+
+    #[test]
+    fn removals_only_send_nothing() {
+        for kind in ProviderKind::ALL {
+            let provider = Provider::new(kind, "example.com", "tok").with_secret("sk");
+            assert!(
+                provider
+                    .requests(&base(kind), &[removed("203.0.113.7")])
+                    .is_empty()
+            );
+        }
+    }
+
+    #[test]
+    fn keeps_base_headers_and_replaces_provider_headers() {
+        let base = base(ProviderKind::Desec)
+            .with_header(AUTHORIZATION, HeaderValue::from_static("Bearer other"))
+            .with_header(
+                http::HeaderName::from_static("x-custom"),
+                HeaderValue::from_static("1"),
+            );
+        let provider = Provider::new(ProviderKind::Desec, "myhost.dedyn.io", "tok");
+        let requests = provider.requests(&base, &[added("203.0.113.7")]);
+
+        assert_eq!(requests[0].headers[AUTHORIZATION], "Token tok");
+        assert_eq!(requests[0].headers["x-custom"], "1");
+    }
+
+    #[test]
+    fn hostname_combines_record_and_domain() {
+        let desec = Provider::new(ProviderKind::Desec, "dedyn.io", "t").with_record("myhost");
+        let duckdns = Provider::new(ProviderKind::DuckDns, "myhost", "t");
+
+        assert_eq!(desec.hostname(), "myhost.dedyn.io");
+        assert_eq!(duckdns.hostname(), "myhost.duckdns.org");
+    }
+
+    #[test]
+    fn debug_redacts_credentials() {
+        let provider =
+            Provider::new(ProviderKind::Porkbun, "example.com", "pk1_key").with_secret("sk1_key");
+        let debug = format!("{provider:?}");
+
+        assert!(!debug.contains("pk1_key"));
+        assert!(!debug.contains("sk1_key"));
+        assert!(debug.contains("example.com"));
+    }
+}
+
+mod check {
+    use super::*;
+
+    fn check(kind: ProviderKind, body: &str) -> Result<(), String> {
+        Provider::new(kind, "example.com", "tok").check(&response(StatusCode::OK, body))
+    }
+
+    #[test]
+    fn duckdns_accepts_ok_only() {
+        assert!(check(ProviderKind::DuckDns, "OK").is_ok());
+        assert!(check(ProviderKind::DuckDns, "OK\n203.0.113.7\n\nUPDATED").is_ok());
+        assert_eq!(
+            check(ProviderKind::DuckDns, "KO"),
+            Err("duckdns answered 'KO'".to_string())
+        );
+        assert!(check(ProviderKind::DuckDns, "<html>cached</html>").is_err());
+    }
+
+    #[test]
+    fn porkbun_requires_success_status() {
+        assert!(check(ProviderKind::Porkbun, r#"{"status":"SUCCESS"}"#).is_ok());
+        assert_eq!(
+            check(
+                ProviderKind::Porkbun,
+                r#"{"status":"ERROR","message":"Invalid API key."}"#
+            ),
+            Err("porkbun answered 'Invalid API key.'".to_string())
+        );
+        assert!(check(ProviderKind::Porkbun, "").is_err());
+    }
+
+    #[test]
+    fn gandi_requires_json_object() {
+        assert!(check(ProviderKind::Gandi, r#"{"message":"DNS Record Created"}"#).is_ok());
+        assert!(check(ProviderKind::Gandi, "<html></html>").is_err());
+    }
+
+    #[test]
+    fn desec_accepts_good_and_nochg() {
+        assert!(check(ProviderKind::Desec, "good").is_ok());
+        assert!(check(ProviderKind::Desec, "nochg 203.0.113.7").is_ok());
+        assert!(check(ProviderKind::Desec, "badauth").is_err());
+    }
+
+    #[test]
+    fn noip_and_dynu_accept_good_and_nochg() {
+        for kind in [ProviderKind::NoIp, ProviderKind::Dynu] {
+            assert!(check(kind, "good 203.0.113.7").is_ok());
+            assert!(check(kind, "nochg 203.0.113.7").is_ok());
+            assert!(check(kind, "nohost").is_err());
+            assert!(check(kind, "badauth").is_err());
+        }
+    }
+
+    #[test]
+    fn freedns_accepts_updates_and_unchanged() {
+        assert!(
+            check(
+                ProviderKind::FreeDns,
+                "Updated 1 host(s) myhost.mooo.com to 203.0.113.7"
+            )
+            .is_ok()
+        );
+        assert!(
+            check(
+                ProviderKind::FreeDns,
+                "No IP change detected for myhost.mooo.com with IP 203.0.113.7"
+            )
+            .is_ok()
+        );
+        assert!(check(ProviderKind::FreeDns, "ERROR: Unable to locate this record").is_err());
+    }
+}
+
+mod webhook {
+    use super::*;
+
+    /// Records requests and answers each with the next queued response.
+    struct QueueClient {
+        responses: Mutex<Vec<HttpResponse>>,
+        requests: Mutex<Vec<HttpRequest>>,
+    }
+
+    impl QueueClient {
+        fn new(mut responses: Vec<HttpResponse>) -> Self {
+            responses.reverse();
+            Self {
+                responses: Mutex::new(responses),
+                requests: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn requests(&self) -> Vec<HttpRequest> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    impl HttpClient for &QueueClient {
+        async fn request(&self, req: HttpRequest) -> Result<HttpResponse, HttpError> {
+            self.requests.lock().unwrap().push(req);
+            Ok(self
+                .responses
+                .lock()
+                .unwrap()
+                .pop()
+                .expect("unexpected request"))
+        }
+    }
+
+    fn webhook(
+        client: &QueueClient,
+        provider: Provider,
+    ) -> HttpWebhook<&QueueClient, InstantSleeper> {
+        HttpWebhook::new(client, provider.kind().endpoint())
+            .with_provider(provider)
+            .with_body_template("unused")
+            .with_sleeper(InstantSleeper)
+    }
+
+    #[tokio::test]
+    async fn sends_every_record_update() {
+        let ok = || response(StatusCode::OK, r#"{"status":"SUCCESS"}"#);
+        let client = QueueClient::new(vec![ok(), ok()]);
+        let provider =
+            Provider::new(ProviderKind::Porkbun, "example.com", "pk1").with_secret("sk1");
+
+        webhook(&client, provider)
+            .send(&[added("203.0.113.7"), added("2001:db8::7")])
+            .await
+            .unwrap();
+
+        let requests = client.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests.iter().all(|r| r.headers.contains_key("x-attempt")));
+    }
+
+    #[tokio::test]
+    async fn rejected_update_fails_without_retry() {
+        let client = QueueClient::new(vec![response(StatusCode::OK, "KO")]);
+        let provider = Provider::new(ProviderKind::DuckDns, "myhost", "bad");
+
+        let result = webhook(&client, provider)
+            .send(&[added("203.0.113.7")])
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(WebhookError::Retryable(RetryableError::Rejected { .. }))
+        ));
+        assert_eq!(client.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn retries_all_updates_after_server_error() {
+        let ok = || response(StatusCode::CREATED, r#"{"message":"DNS Record Created"}"#);
+        let client = QueueClient::new(vec![
+            ok(),
+            response(StatusCode::BAD_GATEWAY, ""),
+            ok(),
+            ok(),
+        ]);
+        let provider = Provider::new(ProviderKind::Gandi, "example.com", "pat");
+
+        webhook(&client, provider)
+            .send(&[added("203.0.113.7"), added("2001:db8::7")])
+            .await
+            .unwrap();
+
+        let requests = client.requests();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[3].headers["x-attempt"], "2");
+    }
+
+    #[tokio::test]
+    async fn drops_dns_changes_and_removals() {
+        let client = QueueClient::new(Vec::new());
+        let webhook = webhook(
+            &client,
+            Provider::new(ProviderKind::Desec, "h.dedyn.io", "t"),
+        );
+        let dns = DnsChange {
+            adapter: "eth0".to_string(),
+            old: DnsSettings::default(),
+            new: DnsSettings::default(),
+            timestamp: SystemTime::UNIX_EPOCH,
+        };
+
+        webhook.send(&[removed("203.0.113.7")]).await.unwrap();
+        webhook.send_event(&Event::Dns(&[dns])).await.unwrap();
+
+        assert!(client.requests().is_empty());
+    }
+}
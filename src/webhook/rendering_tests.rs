@@ -0,0 +1,593 @@
+//! Tests for the requests `HttpWebhook` renders: templates, payloads and
+//! event bodies.
+
+use super::http_webhook_tests::{MockClient, test_changes, test_url};
+use super::{Event, HttpWebhook, RetryPolicy, WebhookSender};
+use crate::monitor::IpChange;
+use crate::time::InstantSleeper;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+mod template_rendering {
+    use super::*;
+
+    #[tokio::test]
+    async fn renders_changes_array() {
+        let client = Arc::new(MockClient::success());
+        let template =
+            r#"[{{#each changes}}{"addr":"{{address}}"}{{#unless @last}},{{/unless}}{{/each}}]"#;
+
+        let changes = vec![
+            IpChange::added(
+                "eth0",
+                "192.168.1.1".parse().unwrap(),
+                SystemTime::UNIX_EPOCH,
+            ),
+            IpChange::removed("eth1", "10.0.0.1".parse().unwrap(), SystemTime::UNIX_EPOCH),
+        ];
+
+        let webhook = HttpWebhook::new(client.clone(), test_url()).with_body_template(template);
+        webhook.send(&changes).await.unwrap();
+
+        let requests = client.captured_requests();
+        let body = String::from_utf8(requests[0].body.clone().unwrap()).unwrap();
+        assert!(body.contains("192.168.1.1"));
+        assert!(body.contains("10.0.0.1"));
+    }
+
+    #[tokio::test]
+    async fn renders_change_kind() {
+        let client = Arc::new(MockClient::success());
+        let template = r"{{#each changes}}{{kind}}{{/each}}";
+
+        let changes = vec![
+            IpChange::added(
+                "eth0",
+                "192.168.1.1".parse().unwrap(),
+                SystemTime::UNIX_EPOCH,
+            ),
+            IpChange::removed("eth1", "10.0.0.1".parse().unwrap(), SystemTime::UNIX_EPOCH),
+        ];
+
+        let webhook = HttpWebhook::new(client.clone(), test_url()).with_body_template(template);
+        webhook.send(&changes).await.unwrap();
+
+        let requests = client.captured_requests();
+        let body = String::from_utf8(requests[0].body.clone().unwrap()).unwrap();
+        assert!(body.contains("added"));
+        assert!(body.contains("removed"));
+    }
+
+    #[tokio::test]
+    async fn invalid_template_returns_error() {
+        let client = MockClient::success();
+        let template = "{{#if}}"; // Invalid Handlebars
+
+        let webhook = HttpWebhook::new(client, test_url()).with_body_template(template);
+        let result = webhook.send(&test_changes()).await;
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn preview_builds_requests_without_sending() {
+        let client = Arc::new(MockClient::success());
+        let webhook = HttpWebhook::new(client.clone(), test_url())
+            .with_body_template("{{#each changes}}{{address}}{{/each}}");
+
+        let requests = webhook.preview(&test_changes()).unwrap();
+
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].url, test_url());
+        assert_eq!(requests[0].body.as_deref(), Some(&b"192.168.1.1"[..]));
+        assert_eq!(requests[0].headers[crate::webhook::ATTEMPT_HEADER], "1");
+        assert_eq!(client.calls(), 0);
+    }
+}
+
+mod header_templates {
+    use super::*;
+
+    fn webhook(client: Arc<MockClient>, value: &'static str) -> HttpWebhook<Arc<MockClient>> {
+        let mut headers = http::HeaderMap::new();
+        headers.insert("x-current-ip", http::HeaderValue::from_static(value));
+        headers.insert("x-static", http::HeaderValue::from_static("plain"));
+        HttpWebhook::new(client, test_url()).with_headers(headers)
+    }
+
+    #[tokio::test]
+    async fn renders_header_values_per_request() {
+        let client = Arc::new(MockClient::success());
+
+        webhook(client.clone(), "{{first_added changes}} #{{attempt}}")
+            .send(&test_changes())
+            .await
+            .unwrap();
+
+        let requests = client.captured_requests();
+        assert_eq!(requests[0].headers["x-current-ip"], "192.168.1.1 #1");
+        assert_eq!(requests[0].headers["x-static"], "plain");
+    }
+
+    #[test]
+    fn inserts_values_unescaped() {
+        let changes = [IpChange::added(
+            "A&B <lan>",
+            "192.0.2.1".parse().unwrap(),
+            SystemTime::UNIX_EPOCH,
+        )];
+
+        let requests = webhook(Arc::new(MockClient::success()), "{{changes.0.adapter}}")
+            .preview(&changes)
+            .unwrap();
+
+        assert_eq!(requests[0].headers["x-current-ip"], "A&B <lan>");
+    }
+
+    #[tokio::test]
+    async fn invalid_rendered_value_fails_without_sending() {
+        let client = Arc::new(MockClient::success());
+        let changes = [IpChange::added(
+            "eth0\r\nX-Injected: 1",
+            "192.0.2.1".parse().unwrap(),
+            SystemTime::UNIX_EPOCH,
+        )];
+
+        let result = webhook(client.clone(), "{{changes.0.adapter}}")
+            .send(&changes)
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(client.calls(), 0);
+    }
+}
+
+mod url_template {
+    use super::*;
+    use crate::webhook::RetryOverrides;
+
+    const TEMPLATE: &str =
+        "https://example.com/update?myip={{changes.0.address}}&attempt={{attempt}}";
+
+    fn changes(address: &str) -> Vec<IpChange> {
+        vec![IpChange::added(
+            "eth0",
+            address.parse().unwrap(),
+            SystemTime::UNIX_EPOCH,
+        )]
+    }
+
+    #[tokio::test]
+    async fn renders_the_url_per_delivery() {
+        let client = Arc::new(MockClient::success());
+        let webhook = HttpWebhook::new(client.clone(), test_url()).with_url_template(TEMPLATE);
+
+        webhook.send(&changes("192.0.2.1")).await.unwrap();
+
+        let requests = client.captured_requests();
+        assert_eq!(
+            requests[0].url.as_str(),
+            "https://example.com/update?myip=192.0.2.1&attempt=1"
+        );
+        assert_eq!(webhook.url(), test_url());
+    }
+
+    #[test]
+    fn percent_encodes_inserted_values() {
+        let webhook = HttpWebhook::new(MockClient::success(), test_url()).with_url_template(
+            "https://example.com/{{changes.0.adapter}}?ip={{changes.0.address}}",
+        );
+        let changes = vec![IpChange::added(
+            "Wi-Fi 2/a",
+            "2001:db8::1".parse().unwrap(),
+            SystemTime::UNIX_EPOCH,
+        )];
+
+        let requests = webhook.preview(&changes).unwrap();
+
+        assert_eq!(
+            requests[0].url.as_str(),
+            "https://example.com/Wi-Fi%202%2Fa?ip=2001%3Adb8%3A%3A1"
+        );
+    }
+
+    #[tokio::test]
+    async fn retry_query_is_appended_to_the_rendered_url() {
+        let client = Arc::new(MockClient::failing_then_success(1));
+        let webhook = HttpWebhook::new(client.clone(), test_url())
+            .with_sleeper(InstantSleeper)
+            .with_retry_policy(RetryPolicy::new().with_max_attempts(2))
+            .with_url_template(TEMPLATE)
+            .with_retry_overrides(RetryOverrides::new().with_query("retry", "true"));
+
+        webhook.send(&changes("192.0.2.1")).await.unwrap();
+
+        let urls: Vec<String> = client
+            .captured_requests()
+            .iter()
+            .map(|r| r.url.to_string())
+            .collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/update?myip=192.0.2.1&attempt=1",
+                "https://example.com/update?myip=192.0.2.1&attempt=2&retry=true"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_rendered_url_fails_without_sending() {
+        let client = Arc::new(MockClient::success());
+        let webhook = HttpWebhook::new(client.clone(), test_url())
+            .with_url_template("https://[{{changes.0.adapter}}]/update");
+
+        let result = webhook.send(&changes("192.0.2.1")).await;
+
+        assert!(result.is_err());
+        assert_eq!(client.calls(), 0);
+    }
+}
+
+mod dns_delivery {
+    use super::*;
+    use crate::monitor::DnsChange;
+    use crate::network::DnsSettings;
+    use crate::webhook::{ATTEMPT_HEADER, EVENT_HEADER, SCHEMA_HEADER, SCHEMA_VERSION};
+
+    pub(super) fn dns_changes() -> Vec<DnsChange> {
+        vec![DnsChange {
+            adapter: "vpn".to_string(),
+            old: DnsSettings {
+                suffix: String::new(),
+                servers: vec!["192.0.2.53".parse().unwrap()],
+            },
+            new: DnsSettings {
+                suffix: "corp.example.com".to_string(),
+                servers: vec!["10.0.0.53".parse().unwrap(), "10.0.0.54".parse().unwrap()],
+            },
+            timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000),
+        }]
+    }
+
+    #[tokio::test]
+    async fn marks_event_header() {
+        let client = Arc::new(MockClient::success());
+        let webhook = HttpWebhook::new(client.clone(), test_url());
+
+        webhook
+            .send_event(&Event::Dns(&dns_changes()))
+            .await
+            .unwrap();
+
+        let request = &client.captured_requests()[0];
+        assert_eq!(request.headers[EVENT_HEADER], "dns");
+        assert_eq!(request.headers[ATTEMPT_HEADER], "1");
+    }
+
+    #[tokio::test]
+    async fn every_delivery_carries_schema_header() {
+        let ip_client = Arc::new(MockClient::success());
+        let dns_client = Arc::new(MockClient::success());
+
+        HttpWebhook::new(ip_client.clone(), test_url())
+            .send(&test_changes())
+            .await
+            .unwrap();
+        HttpWebhook::new(dns_client.clone(), test_url())
+            .send_event(&Event::Dns(&dns_changes()))
+            .await
+            .unwrap();
+
+        for client in [ip_client, dns_client] {
+            let request = &client.captured_requests()[0];
+            assert_eq!(request.headers[SCHEMA_HEADER], SCHEMA_VERSION.to_string());
+        }
+    }
+
+    #[tokio::test]
+    async fn address_delivery_has_no_event_header() {
+        let client = Arc::new(MockClient::success());
+        let webhook = HttpWebhook::new(client.clone(), test_url());
+
+        webhook.send(&test_changes()).await.unwrap();
+
+        assert!(
+            !client.captured_requests()[0]
+                .headers
+                .contains_key(EVENT_HEADER)
+        );
+    }
+
+    #[tokio::test]
+    async fn body_defaults_to_json() {
+        let client = Arc::new(MockClient::success());
+        let webhook = HttpWebhook::new(client.clone(), test_url())
+            .with_body_template("{{#each changes}}x{{/each}}");
+
+        webhook
+            .send_event(&Event::Dns(&dns_changes()))
+            .await
+            .unwrap();
+
+        let body: serde_json::Value =
+            serde_json::from_slice(client.captured_requests()[0].body.as_ref().unwrap()).unwrap();
+        assert_eq!(
+            body["dns_changes"][0],
+            serde_json::json!({
+                "adapter": "vpn",
+                "suffix": "corp.example.com",
+                "old_suffix": "",
+                "servers": ["10.0.0.53", "10.0.0.54"],
+                "old_servers": ["192.0.2.53"],
+                "timestamp": 1_000_000,
+            })
+        );
+        assert_eq!(body["attempt"], 1);
+        assert_eq!(body["schema_version"], SCHEMA_VERSION);
+    }
+
+    #[cfg(feature = "templates")]
+    #[tokio::test]
+    async fn renders_dns_template() {
+        let client = Arc::new(MockClient::success());
+        let webhook = HttpWebhook::new(client.clone(), test_url()).with_dns_body_template(
+            "{{#each dns_changes}}{{adapter}}={{suffix}}:{{#each servers}}{{this}};{{/each}}{{/each}}",
+        );
+
+        webhook
+            .send_event(&Event::Dns(&dns_changes()))
+            .await
+            .unwrap();
+
+        let body = client.captured_requests()[0].body.clone().unwrap();
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            "vpn=corp.example.com:10.0.0.53;10.0.0.54;"
+        );
+    }
+
+    #[tokio::test]
+    async fn retries_like_address_deliveries() {
+        let client = Arc::new(MockClient::failing_then_success(1));
+        let webhook = HttpWebhook::new(client.clone(), test_url()).with_sleeper(InstantSleeper);
+
+        webhook
+            .send_event(&Event::Dns(&dns_changes()))
+            .await
+            .unwrap();
+
+        assert_eq!(client.calls(), 2);
+        assert_eq!(client.captured_requests()[1].headers[ATTEMPT_HEADER], "2");
+    }
+}
+
+mod event_delivery {
+    use super::*;
+    use crate::monitor::{
+        AddressDrift, AddressStorm, DriftChange, DriftKind, HostnameChange, LinkChange, LinkState,
+        StormChange,
+    };
+    use crate::network::OperStatus;
+    use crate::webhook::EVENT_HEADER;
+
+    fn drift_changes() -> Vec<DriftChange> {
+        vec![DriftChange {
+            drift: AddressDrift {
+                kind: DriftKind::Missing,
+                address: "203.0.113.10".parse().unwrap(),
+                adapter: None,
+            },
+            resolved: false,
+            timestamp: SystemTime::UNIX_EPOCH,
+        }]
+    }
+
+    #[tokio::test]
+    async fn sends_json_body_with_event_header() {
+        let client = Arc::new(MockClient::success());
+        let webhook = HttpWebhook::new(client.clone(), test_url())
+            .with_body_template("{{#each changes}}x{{/each}}");
+
+        webhook
+            .send_event(&Event::Drift(&drift_changes()))
+            .await
+            .unwrap();
+
+        let request = &client.captured_requests()[0];
+        assert_eq!(request.headers[EVENT_HEADER], "drift");
+        let body: serde_json::Value =
+            serde_json::from_slice(request.body.as_ref().unwrap()).unwrap();
+        assert_eq!(body["drift"][0]["kind"], "missing");
+    }
+
+    #[tokio::test]
+    async fn hostname_sends_event_header() {
+        let client = Arc::new(MockClient::success());
+        let webhook = HttpWebhook::new(client.clone(), test_url());
+        let change = HostnameChange {
+            old: "desk-01".to_string(),
+            new: "desk-02".to_string(),
+            timestamp: SystemTime::UNIX_EPOCH,
+        };
+
+        webhook
+            .send_event(&Event::Hostname(&[change]))
+            .await
+            .unwrap();
+
+        let request = &client.captured_requests()[0];
+        assert_eq!(request.headers[EVENT_HEADER], "hostname");
+        assert!(request.body.is_some());
+    }
+
+    #[tokio::test]
+    async fn storm_sends_event_header() {
+        let client = Arc::new(MockClient::success());
+        let webhook = HttpWebhook::new(client.clone(), test_url());
+        let change = StormChange {
+            storm: AddressStorm {
+                adapter: "eth0".to_string(),
+                count: 40,
+                limit: 16,
+            },
+            resolved: false,
+            prefix_only: false,
+            timestamp: SystemTime::UNIX_EPOCH,
+        };
+
+        webhook.send_event(&Event::Storm(&[change])).await.unwrap();
+
+        let request = &client.captured_requests()[0];
+        assert_eq!(request.headers[EVENT_HEADER], "storm");
+        let body: serde_json::Value =
+            serde_json::from_slice(request.body.as_ref().unwrap()).unwrap();
+        assert_eq!(body["storms"][0]["count"], 40);
+    }
+
+    #[tokio::test]
+    async fn link_sends_event_header() {
+        let client = Arc::new(MockClient::success());
+        let webhook = HttpWebhook::new(client.clone(), test_url());
+        let change = LinkChange {
+            adapter: "eth0".to_string(),
+            state: LinkState::Up,
+            status: OperStatus::Up,
+            timestamp: SystemTime::UNIX_EPOCH,
+        };
+
+        webhook.send_event(&Event::Link(&[change])).await.unwrap();
+
+        let request = &client.captured_requests()[0];
+        assert_eq!(request.headers[EVENT_HEADER], "link");
+        let body: serde_json::Value =
+            serde_json::from_slice(request.body.as_ref().unwrap()).unwrap();
+        assert_eq!(body["link_changes"][0]["state"], "up");
+    }
+}
+
+mod payload_format {
+    use super::*;
+    use crate::webhook::{PayloadEncoding, PayloadFormat};
+    use http::header::CONTENT_TYPE;
+
+    #[tokio::test]
+    async fn address_delivery_defaults_to_json_v1() {
+        let client = Arc::new(MockClient::success());
+        let webhook = HttpWebhook::new(client.clone(), test_url()).with_host("nas");
+
+        webhook.send(&test_changes()).await.unwrap();
+
+        let request = &client.captured_requests()[0];
+        assert_eq!(request.headers[CONTENT_TYPE], "application/json");
+        let body: serde_json::Value =
+            serde_json::from_slice(request.body.as_ref().unwrap()).unwrap();
+        assert_eq!(body["version"], 1);
+        assert_eq!(body["host"], "nas");
+        assert_eq!(body["changes"][0]["address"], "192.168.1.1");
+        assert_eq!(
+            body["changes"][0]["timestamp_rfc3339"],
+            "1970-01-12T13:46:40Z"
+        );
+    }
+
+    #[tokio::test]
+    async fn none_sends_no_body() {
+        let client = Arc::new(MockClient::success());
+        let webhook =
+            HttpWebhook::new(client.clone(), test_url()).with_payload_format(PayloadFormat::None);
+
+        webhook.send(&test_changes()).await.unwrap();
+
+        let request = &client.captured_requests()[0];
+        assert!(request.body.is_none());
+        assert!(!request.headers.contains_key(CONTENT_TYPE));
+    }
+
+    #[tokio::test]
+    async fn payload_encoding_takes_precedence() {
+        let client = Arc::new(MockClient::success());
+        let webhook = HttpWebhook::new(client.clone(), test_url())
+            .with_payload_encoding(PayloadEncoding::Json)
+            .with_payload_format(PayloadFormat::JsonV1);
+
+        webhook.send(&test_changes()).await.unwrap();
+
+        let body: serde_json::Value =
+            serde_json::from_slice(client.captured_requests()[0].body.as_ref().unwrap()).unwrap();
+        assert_eq!(body["schema_version"], 1);
+        assert!(body.get("version").is_none());
+    }
+}
+
+mod payload_encoding {
+    use super::*;
+    use crate::webhook::PayloadEncoding;
+    use http::header::CONTENT_TYPE;
+
+    #[tokio::test]
+    async fn address_delivery_gets_default_body_once_set() {
+        let client = Arc::new(MockClient::success());
+        let webhook = HttpWebhook::new(client.clone(), test_url())
+            .with_payload_encoding(PayloadEncoding::Yaml);
+
+        webhook.send(&test_changes()).await.unwrap();
+
+        let request = &client.captured_requests()[0];
+        assert_eq!(request.headers[CONTENT_TYPE], "application/yaml");
+        let body = String::from_utf8(request.body.clone().unwrap()).unwrap();
+        assert!(body.starts_with(
+            "attempt: 1\nchanges:\n  - adapter: \"eth0\"\n    address: \"192.168.1.1\"\n"
+        ));
+    }
+
+    #[tokio::test]
+    async fn configured_content_type_wins() {
+        let client = Arc::new(MockClient::success());
+        let mut headers = http::HeaderMap::new();
+        headers.insert(CONTENT_TYPE, http::HeaderValue::from_static("text/plain"));
+        let webhook = HttpWebhook::new(client.clone(), test_url())
+            .with_headers(headers)
+            .with_payload_encoding(PayloadEncoding::Json);
+
+        webhook.send(&test_changes()).await.unwrap();
+
+        let request = &client.captured_requests()[0];
+        assert_eq!(request.headers[CONTENT_TYPE], "text/plain");
+        let body: serde_json::Value =
+            serde_json::from_slice(request.body.as_ref().unwrap()).unwrap();
+        assert_eq!(body["changes"][0]["adapter"], "eth0");
+    }
+
+    #[cfg(feature = "templates")]
+    #[tokio::test]
+    async fn template_takes_precedence() {
+        let client = Arc::new(MockClient::success());
+        let webhook = HttpWebhook::new(client.clone(), test_url())
+            .with_body_template("plain")
+            .with_payload_encoding(PayloadEncoding::Cbor);
+
+        webhook.send(&test_changes()).await.unwrap();
+
+        let request = &client.captured_requests()[0];
+        assert_eq!(request.body.as_deref(), Some(&b"plain"[..]));
+        assert!(!request.headers.contains_key(CONTENT_TYPE));
+    }
+
+    #[tokio::test]
+    async fn dns_delivery_uses_encoding() {
+        let client = Arc::new(MockClient::success());
+        let webhook = HttpWebhook::new(client.clone(), test_url())
+            .with_payload_encoding(PayloadEncoding::Cbor);
+
+        webhook
+            .send_event(&Event::Dns(&dns_delivery::dns_changes()))
+            .await
+            .unwrap();
+
+        let request = &client.captured_requests()[0];
+        assert_eq!(request.headers[CONTENT_TYPE], "application/cbor");
+        // Map of five entries: attempt, dns_changes, elapsed_ms, is_retry, schema_version
+        assert_eq!(request.body.as_ref().unwrap()[0], 0xa5);
+    }
+}
@@ -1,5 +1,8 @@
 //! Retry policy configuration for webhook operations.
 
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
 use std::time::Duration;
 
 /// Configuration for exponential backoff retry behavior.
@@ -14,11 +17,12 @@ use std::time::Duration;
 /// - `initial_delay`: 5 seconds
 /// - `max_delay`: 60 seconds
 /// - `multiplier`: 2.0
+/// - `jitter`: [`JitterMode::None`]
 ///
 /// # Example
 ///
 /// ```
-/// use ddns_a::webhook::RetryPolicy;
+/// use ddns_a::webhook::{JitterMode, RetryPolicy};
 /// use std::time::Duration;
 ///
 /// // Create with defaults
@@ -29,7 +33,8 @@ use std::time::Duration;
 ///     .with_max_attempts(5)
 ///     .with_initial_delay(Duration::from_secs(1))
 ///     .with_max_delay(Duration::from_secs(30))
-///     .with_multiplier(1.5);
+///     .with_multiplier(1.5)
+///     .with_jitter(JitterMode::Full);
 /// ```
 #[derive(Debug, Clone, PartialEq)]
 pub struct RetryPolicy {
@@ -53,6 +58,9 @@ pub struct RetryPolicy {
     ///
     /// A value of 2.0 doubles the delay each time.
     pub multiplier: f64,
+
+    /// Randomization of the backoff delays.
+    pub jitter: JitterMode,
 }
 
 impl RetryPolicy {
@@ -76,6 +84,7 @@ impl RetryPolicy {
             initial_delay: Self::DEFAULT_INITIAL_DELAY,
             max_delay: Self::DEFAULT_MAX_DELAY,
             multiplier: Self::DEFAULT_MULTIPLIER,
+            jitter: JitterMode::None,
         }
     }
 
@@ -128,8 +137,17 @@ impl RetryPolicy {
         self
     }
 
+    /// Sets the randomization of the backoff delays.
+    #[must_use]
+    pub const fn with_jitter(mut self, jitter: JitterMode) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
     /// Computes the delay for a given retry number (0-indexed).
     ///
+    /// This is the delay before jitter, which only ever shortens it.
+    ///
     /// # Arguments
     ///
     /// * `retry` - The retry number (0 = delay before first retry, 1 = delay before second retry, etc.)
@@ -147,6 +165,18 @@ impl RetryPolicy {
         Duration::from_secs_f64(capped)
     }
 
+    /// Computes the delay for a given retry number (0-indexed) with jitter
+    /// applied, waiting at least `retry_after` if the server asked for it.
+    ///
+    /// The result is capped at `max_delay`, even if the server asked for more.
+    #[must_use]
+    pub fn delay_honoring(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        let backoff = self
+            .jitter
+            .apply(self.delay_for_retry(retry), random_fraction());
+        retry_after.map_or(backoff, |wait| backoff.max(wait).min(self.max_delay))
+    }
+
     /// Returns true if the given attempt number should be retried.
     ///
     /// # Arguments
@@ -160,6 +190,17 @@ impl RetryPolicy {
     pub const fn should_retry(&self, attempt: u32) -> bool {
         attempt < self.max_attempts
     }
+
+    /// Returns the longest time a delivery spends waiting between attempts:
+    /// the sum of the delays before every retry.
+    ///
+    /// The time the attempts themselves take is not included.
+    #[must_use]
+    pub fn worst_case_delay(&self) -> Duration {
+        (0..self.max_attempts.saturating_sub(1))
+            .map(|retry| self.delay_for_retry(retry))
+            .sum()
+    }
 }
 
 impl Default for RetryPolicy {
@@ -167,3 +208,131 @@ impl Default for RetryPolicy {
         Self::new()
     }
 }
+
+/// Request changes applied to retry attempts (attempt 2 onwards).
+///
+/// Lets receivers distinguish retries from first deliveries, e.g. to
+/// deduplicate updates they already processed before a timeout.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetryOverrides {
+    /// Body template used instead of the regular template on retries.
+    pub body_template: Option<String>,
+
+    /// Query parameter (name, value) appended to the URL on retries.
+    pub query: Option<(String, String)>,
+}
+
+impl RetryOverrides {
+    /// Creates overrides that leave retry attempts unchanged.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses a different body template on retry attempts.
+    #[must_use]
+    pub fn with_body_template(mut self, template: impl Into<String>) -> Self {
+        self.body_template = Some(template.into());
+        self
+    }
+
+    /// Appends a query parameter to the URL on retry attempts.
+    #[must_use]
+    pub fn with_query(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.query = Some((name.into(), value.into()));
+        self
+    }
+}
+
+/// Randomization of retry delays, so instances failing together do not
+/// retry in lockstep against the same endpoint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JitterMode {
+    /// Wait the exact backoff delay.
+    #[default]
+    None,
+    /// Wait a random time between zero and the backoff delay.
+    Full,
+    /// Wait half the backoff delay plus a random time up to the other half.
+    Equal,
+}
+
+impl JitterMode {
+    /// Applies the jitter to `delay`, given a `fraction` drawn from `[0, 1)`.
+    #[must_use]
+    pub fn apply(self, delay: Duration, fraction: f64) -> Duration {
+        match self {
+            Self::None => delay,
+            Self::Full => delay.mul_f64(fraction),
+            Self::Equal => delay / 2 + (delay / 2).mul_f64(fraction),
+        }
+    }
+}
+
+impl fmt::Display for JitterMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::None => "none",
+            Self::Full => "full",
+            Self::Equal => "equal",
+        })
+    }
+}
+
+/// Returns a random number from `[0, 1)`.
+#[allow(clippy::cast_precision_loss)]
+fn random_fraction() -> f64 {
+    // Every RandomState is seeded from OS randomness; 53 bits fill an f64 mantissa exactly
+    let bits = RandomState::new().build_hasher().finish() >> 11;
+    bits as f64 / (1_u64 << 53) as f64
+}
+
+/// What to do when retries can outlast the poll interval.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverlapCheck {
+    /// Do not compare retries with the poll interval.
+    Off,
+    /// Log a warning on startup.
+    #[default]
+    Warn,
+    /// Reject the configuration.
+    Error,
+}
+
+/// A retry policy whose retries can outlast the poll interval.
+///
+/// Deliveries never overlap: the monitor loop waits for a delivery,
+/// retries included, before it looks for changes again. Changes made while
+/// a delivery retries are therefore queued and reported late.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryOverlap {
+    /// Longest wait between the attempts of one delivery
+    pub worst_case: Duration,
+    /// Shortest configured poll interval
+    pub poll_interval: Duration,
+}
+
+impl RetryOverlap {
+    /// Returns the overlap if retries under `policy` can outlast
+    /// `poll_interval`.
+    #[must_use]
+    pub fn check(policy: &RetryPolicy, poll_interval: Duration) -> Option<Self> {
+        let worst_case = policy.worst_case_delay();
+        (worst_case > poll_interval).then_some(Self {
+            worst_case,
+            poll_interval,
+        })
+    }
+}
+
+impl fmt::Display for RetryOverlap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "retries can wait up to {}s, longer than the {}s poll interval; \
+             changes made meanwhile are delivered after the retries end",
+            self.worst_case.as_secs(),
+            self.poll_interval.as_secs()
+        )
+    }
+}
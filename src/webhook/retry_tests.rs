@@ -1,6 +1,6 @@
-//! Tests for `RetryPolicy`.
+//! Tests for `RetryPolicy` and retries cut short by shutdown.
 
-use super::RetryPolicy;
+use super::{JitterMode, RetryOverlap, RetryPolicy};
 use std::time::Duration;
 
 mod retry_policy_defaults {
@@ -187,6 +187,97 @@ mod delay_for_retry {
     }
 }
 
+mod delay_honoring {
+    use super::*;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy::new()
+            .with_initial_delay(Duration::from_secs(5))
+            .with_max_delay(Duration::from_secs(60))
+    }
+
+    #[test]
+    fn backoff_without_retry_after() {
+        assert_eq!(policy().delay_honoring(0, None), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn longer_retry_after_wins() {
+        let delay = policy().delay_honoring(0, Some(Duration::from_secs(30)));
+
+        assert_eq!(delay, Duration::from_secs(30));
+    }
+
+    #[test]
+    fn shorter_retry_after_keeps_backoff() {
+        let delay = policy().delay_honoring(1, Some(Duration::from_secs(1)));
+
+        assert_eq!(delay, Duration::from_secs(10));
+    }
+
+    #[test]
+    fn retry_after_is_capped_at_max_delay() {
+        let delay = policy().delay_honoring(0, Some(Duration::from_secs(3600)));
+
+        assert_eq!(delay, Duration::from_secs(60));
+    }
+}
+
+mod jitter {
+    use super::*;
+
+    const DELAY: Duration = Duration::from_secs(10);
+
+    #[test]
+    fn none_keeps_the_delay() {
+        assert_eq!(JitterMode::None.apply(DELAY, 0.3), DELAY);
+    }
+
+    #[test]
+    fn full_spans_zero_to_the_delay() {
+        assert_eq!(JitterMode::Full.apply(DELAY, 0.0), Duration::ZERO);
+        assert_eq!(JitterMode::Full.apply(DELAY, 0.5), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn equal_keeps_half_the_delay() {
+        assert_eq!(JitterMode::Equal.apply(DELAY, 0.0), Duration::from_secs(5));
+        assert_eq!(
+            JitterMode::Equal.apply(DELAY, 0.5),
+            Duration::from_millis(7500)
+        );
+    }
+
+    #[test]
+    fn jittered_delays_stay_within_the_backoff() {
+        let policy = RetryPolicy::new()
+            .with_initial_delay(DELAY)
+            .with_jitter(JitterMode::Equal);
+
+        for _ in 0..100 {
+            let delay = policy.delay_honoring(0, None);
+            assert!(delay >= DELAY / 2 && delay <= DELAY, "{delay:?}");
+        }
+    }
+
+    #[test]
+    fn retry_after_still_sets_the_minimum() {
+        let policy = RetryPolicy::new()
+            .with_initial_delay(DELAY)
+            .with_jitter(JitterMode::Full);
+
+        let delay = policy.delay_honoring(0, Some(Duration::from_secs(20)));
+
+        assert_eq!(delay, Duration::from_secs(20));
+    }
+
+    #[test]
+    fn defaults_to_none() {
+        assert_eq!(RetryPolicy::new().jitter, JitterMode::None);
+        assert_eq!(JitterMode::Full.to_string(), "full");
+    }
+}
+
 mod should_retry {
     use super::*;
 
@@ -221,6 +312,60 @@ mod should_retry {
     }
 }
 
+mod worst_case_delay {
+    use super::*;
+
+    #[test]
+    fn sums_delays_before_each_retry() {
+        // Defaults: 3 attempts, retries after 5s and 10s
+        let policy = RetryPolicy::new();
+
+        assert_eq!(policy.worst_case_delay(), Duration::from_secs(15));
+    }
+
+    #[test]
+    fn delays_are_capped() {
+        let policy = RetryPolicy::new()
+            .with_max_attempts(5)
+            .with_max_delay(Duration::from_secs(12));
+
+        // 5s, 10s, 12s, 12s
+        assert_eq!(policy.worst_case_delay(), Duration::from_secs(39));
+    }
+
+    #[test]
+    fn single_attempt_never_waits() {
+        let policy = RetryPolicy::new().with_max_attempts(1);
+
+        assert_eq!(policy.worst_case_delay(), Duration::ZERO);
+    }
+}
+
+mod retry_overlap {
+    use super::*;
+
+    #[test]
+    fn reports_retries_longer_than_poll_interval() {
+        let overlap = RetryOverlap::check(&RetryPolicy::new(), Duration::from_secs(10));
+
+        assert_eq!(
+            overlap,
+            Some(RetryOverlap {
+                worst_case: Duration::from_secs(15),
+                poll_interval: Duration::from_secs(10),
+            })
+        );
+        assert!(overlap.unwrap().to_string().contains("up to 15s"));
+    }
+
+    #[test]
+    fn retries_within_poll_interval_are_fine() {
+        let overlap = RetryOverlap::check(&RetryPolicy::new(), Duration::from_secs(15));
+
+        assert_eq!(overlap, None);
+    }
+}
+
 mod traits {
     use super::*;
 
@@ -253,3 +398,103 @@ mod traits {
         assert!(debug.contains("initial_delay"));
     }
 }
+
+mod shutdown {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::SystemTime;
+
+    use http::{HeaderMap, StatusCode};
+
+    use super::*;
+    use crate::monitor::IpChange;
+    use crate::time::{ShutdownToken, Sleeper};
+    use crate::webhook::{
+        HttpClient, HttpError, HttpRequest, HttpResponse, HttpWebhook, WebhookError, WebhookSender,
+    };
+
+    /// Answers every request with 503 Service Unavailable.
+    #[derive(Default)]
+    struct Unavailable {
+        requests: AtomicUsize,
+    }
+
+    impl HttpClient for &Unavailable {
+        async fn request(&self, _req: HttpRequest) -> Result<HttpResponse, HttpError> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            Ok(HttpResponse::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                HeaderMap::new(),
+                Vec::new(),
+            ))
+        }
+    }
+
+    /// Signals shutdown when a retry delay starts, then never wakes up.
+    struct ShutdownDuringDelay(ShutdownToken);
+
+    impl Sleeper for ShutdownDuringDelay {
+        async fn sleep(&self, _duration: Duration) {
+            self.0.trigger();
+            std::future::pending::<()>().await;
+        }
+    }
+
+    fn changes() -> Vec<IpChange> {
+        vec![IpChange::added(
+            "eth0",
+            "192.168.1.1".parse().unwrap(),
+            SystemTime::UNIX_EPOCH,
+        )]
+    }
+
+    fn webhook(client: &Unavailable) -> HttpWebhook<&Unavailable> {
+        HttpWebhook::new(client, url::Url::parse("https://example.com/hook").unwrap())
+            .with_retry_policy(RetryPolicy::new().with_max_attempts(5))
+    }
+
+    #[tokio::test]
+    async fn shutdown_cuts_retry_delay_short() {
+        let client = Unavailable::default();
+        let shutdown = ShutdownToken::new();
+
+        let result = webhook(&client)
+            .with_shutdown(shutdown.clone())
+            .with_sleeper(ShutdownDuringDelay(shutdown))
+            .send(&changes())
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(WebhookError::Interrupted { attempts: 1 })
+        ));
+        assert_eq!(client.requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn no_attempt_after_shutdown() {
+        let client = Unavailable::default();
+        let shutdown = ShutdownToken::new();
+        shutdown.trigger();
+
+        let result = webhook(&client)
+            .with_shutdown(shutdown)
+            .send(&changes())
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(WebhookError::Interrupted { attempts: 0 })
+        ));
+        assert_eq!(client.requests.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn interrupted_displays_attempts() {
+        let error = WebhookError::Interrupted { attempts: 2 };
+
+        assert_eq!(
+            error.to_string(),
+            "Delivery interrupted by shutdown after 2 attempt(s)"
+        );
+    }
+}
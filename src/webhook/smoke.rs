@@ -0,0 +1,217 @@
+//! Deployment smoke tests against configured webhook targets.
+//!
+//! `ddns-a test-webhook` sends a synthetic change to every configured target
+//! and reports the outcome per target, so a deployment can be verified
+//! without waiting for a real address change. `ddns-a send-test` sends it
+//! to the main webhook through a [`LoggingClient`] and prints every request
+//! with its response.
+
+use std::error::Error;
+use std::fmt::Write;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::SystemTime;
+
+use crate::monitor::IpChange;
+use crate::network::IpVersion;
+
+use super::{HttpClient, HttpError, HttpRequest, HttpResponse, WebhookError, WebhookSender};
+
+/// Adapter name used for synthetic changes.
+pub const SMOKE_TEST_ADAPTER: &str = "ddns-a-test";
+
+/// IPv4 address used for synthetic changes (TEST-NET-1, RFC 5737).
+const TEST_V4: Ipv4Addr = Ipv4Addr::new(192, 0, 2, 1);
+
+/// IPv6 address used for synthetic changes (documentation prefix, RFC 3849).
+const TEST_V6: Ipv6Addr = Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1);
+
+/// Builds a synthetic "added" change for each monitored address family.
+///
+/// Addresses come from documentation ranges so they can never be mistaken
+/// for a real host address by the receiving service.
+#[must_use]
+pub fn synthetic_changes(version: IpVersion, timestamp: SystemTime) -> Vec<IpChange> {
+    let mut changes = Vec::new();
+    if version.includes_v4() {
+        changes.push(IpChange::added(
+            SMOKE_TEST_ADAPTER,
+            IpAddr::V4(TEST_V4),
+            timestamp,
+        ));
+    }
+    if version.includes_v6() {
+        changes.push(IpChange::added(
+            SMOKE_TEST_ADAPTER,
+            IpAddr::V6(TEST_V6),
+            timestamp,
+        ));
+    }
+    changes
+}
+
+/// Outcome of sending the synthetic change to one target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetReport {
+    /// Target name shown in the report.
+    pub name: String,
+    /// `Err` holds the delivery error message.
+    pub outcome: Result<(), String>,
+}
+
+impl TargetReport {
+    /// Returns true if delivery to this target succeeded.
+    #[must_use]
+    pub const fn is_success(&self) -> bool {
+        self.outcome.is_ok()
+    }
+}
+
+/// Sends `changes` to each named target in turn and collects the outcomes.
+pub async fn run_smoke_test<W: WebhookSender>(
+    targets: &[(&str, &W)],
+    changes: &[IpChange],
+) -> Vec<TargetReport> {
+    let mut reports = Vec::with_capacity(targets.len());
+    for (name, sender) in targets {
+        let outcome = sender.send(changes).await.map_err(|e| describe(&e));
+        reports.push(TargetReport {
+            name: (*name).to_string(),
+            outcome,
+        });
+    }
+    reports
+}
+
+/// Describes an error together with its immediate cause.
+fn describe(error: &WebhookError) -> String {
+    error
+        .source()
+        .map_or_else(|| error.to_string(), |cause| format!("{error}: {cause}"))
+}
+
+/// Formats reports as an aligned two-column table.
+#[must_use]
+pub fn format_report_table(reports: &[TargetReport]) -> String {
+    const NAME_HEADER: &str = "TARGET";
+
+    let width = reports
+        .iter()
+        .map(|r| r.name.len())
+        .max()
+        .unwrap_or(0)
+        .max(NAME_HEADER.len());
+
+    let mut table = format!("{NAME_HEADER:<width$}  RESULT\n");
+    for report in reports {
+        let result = match &report.outcome {
+            Ok(()) => "ok".to_string(),
+            Err(e) => format!("FAILED: {e}"),
+        };
+        let _ = writeln!(table, "{:<width$}  {result}", report.name);
+    }
+    table
+}
+
+/// A request sent by a [`LoggingClient`] and what came back.
+#[derive(Debug, Clone)]
+pub struct Exchange {
+    /// The request as sent.
+    pub request: HttpRequest,
+    /// The response, or the error message if none arrived.
+    pub response: Result<HttpResponse, String>,
+}
+
+/// Exchanges recorded by a [`LoggingClient`], in the order they were sent.
+///
+/// Clones share the log.
+#[derive(Debug, Clone, Default)]
+pub struct ExchangeLog(Arc<Mutex<Vec<Exchange>>>);
+
+impl ExchangeLog {
+    /// Creates an empty log.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Removes and returns the recorded exchanges.
+    #[must_use]
+    pub fn take(&self) -> Vec<Exchange> {
+        std::mem::take(&mut *self.0.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    fn push(&self, exchange: Exchange) {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(exchange);
+    }
+}
+
+/// HTTP client decorator recording every exchange in an [`ExchangeLog`].
+#[derive(Debug)]
+pub struct LoggingClient<H> {
+    inner: H,
+    log: ExchangeLog,
+}
+
+impl<H> LoggingClient<H> {
+    /// Wraps `inner`, recording its exchanges in `log`.
+    #[must_use]
+    pub const fn new(inner: H, log: ExchangeLog) -> Self {
+        Self { inner, log }
+    }
+}
+
+impl<H: HttpClient> HttpClient for LoggingClient<H> {
+    async fn request(&self, req: HttpRequest) -> Result<HttpResponse, HttpError> {
+        let request = req.clone();
+        let result = self.inner.request(req).await;
+        self.log.push(Exchange {
+            request,
+            response: result
+                .as_ref()
+                .map(Clone::clone)
+                .map_err(ToString::to_string),
+        });
+        result
+    }
+}
+
+/// Formats `request` like an HTTP/1.1 request: the request line, the
+/// headers and, after a blank line, the body.
+#[must_use]
+pub fn format_request(request: &HttpRequest) -> String {
+    let mut text = format!("{} {}\n", request.method, request.url);
+    write_headers(&mut text, &request.headers);
+    write_body(&mut text, request.body.as_deref().unwrap_or_default());
+    text
+}
+
+/// Formats `response` like an HTTP/1.1 response: the status line, the
+/// headers and, after a blank line, the body.
+#[must_use]
+pub fn format_response(response: &HttpResponse) -> String {
+    let mut text = format!(
+        "HTTP {} {}\n",
+        response.status.as_u16(),
+        response.status.canonical_reason().unwrap_or_default()
+    );
+    write_headers(&mut text, &response.headers);
+    write_body(&mut text, &response.body);
+    text
+}
+
+fn write_headers(text: &mut String, headers: &http::HeaderMap) {
+    for (name, value) in headers {
+        let value = String::from_utf8_lossy(value.as_bytes());
+        let _ = writeln!(text, "{name}: {value}");
+    }
+}
+
+fn write_body(text: &mut String, body: &[u8]) {
+    if !body.is_empty() {
+        let _ = writeln!(text, "\n{}", String::from_utf8_lossy(body));
+    }
+}
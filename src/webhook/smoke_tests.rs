@@ -0,0 +1,184 @@
+//! Tests for webhook smoke testing.
+
+use super::smoke::{
+    ExchangeLog, LoggingClient, SMOKE_TEST_ADAPTER, TargetReport, format_report_table,
+    format_request, format_response, run_smoke_test, synthetic_changes,
+};
+use super::{
+    Event, HttpClient, HttpError, HttpRequest, HttpResponse, RetryableError, WebhookError,
+    WebhookSender,
+};
+use crate::monitor::IpChange;
+use crate::network::IpVersion;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Sender recording batches and optionally failing.
+#[derive(Default)]
+struct StubSender {
+    fail: bool,
+    received: Mutex<Vec<Vec<IpChange>>>,
+}
+
+impl WebhookSender for StubSender {
+    async fn send(&self, changes: &[IpChange]) -> Result<(), WebhookError> {
+        self.received.lock().unwrap().push(changes.to_vec());
+        if self.fail {
+            Err(WebhookError::MaxRetriesExceeded {
+                attempts: 1,
+                last_error: RetryableError::NonSuccessStatus {
+                    status: http::StatusCode::SERVICE_UNAVAILABLE,
+                    body: None,
+                    retry_after: None,
+                },
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn send_event(&self, _event: &Event<'_>) -> Result<(), WebhookError> {
+        unreachable!("only address changes are sent")
+    }
+}
+
+mod synthetic {
+    use super::*;
+
+    #[test]
+    fn both_yields_one_change_per_family() {
+        let changes = synthetic_changes(IpVersion::Both, SystemTime::UNIX_EPOCH);
+
+        assert_eq!(changes.len(), 2);
+        assert!(
+            changes
+                .iter()
+                .all(|c| c.adapter == SMOKE_TEST_ADAPTER && c.is_added())
+        );
+        assert!(changes.iter().any(IpChange::is_ipv4));
+        assert!(changes.iter().any(IpChange::is_ipv6));
+    }
+
+    #[test]
+    fn v6_yields_documentation_address() {
+        let changes = synthetic_changes(IpVersion::V6, SystemTime::UNIX_EPOCH);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].address.to_string(), "2001:db8::1");
+    }
+}
+
+mod smoke_test {
+    use super::*;
+
+    #[tokio::test]
+    async fn reports_each_target() {
+        let ok = StubSender::default();
+        let failing = StubSender {
+            fail: true,
+            ..StubSender::default()
+        };
+        let changes = synthetic_changes(IpVersion::V4, SystemTime::UNIX_EPOCH);
+
+        let reports = run_smoke_test(&[("primary", &ok), ("backup", &failing)], &changes).await;
+
+        assert_eq!(reports.len(), 2);
+        assert!(reports[0].is_success());
+        assert!(!reports[1].is_success());
+        // The underlying cause is included, not just the retry summary
+        let error = reports[1].outcome.as_ref().unwrap_err();
+        assert!(
+            error.contains("1 attempts") && error.contains("503"),
+            "{error}"
+        );
+        assert_eq!(ok.received.lock().unwrap().as_slice(), &[changes]);
+    }
+}
+
+mod table {
+    use super::*;
+
+    #[test]
+    fn aligns_columns_and_shows_errors() {
+        let reports = [
+            TargetReport {
+                name: "webhook".to_string(),
+                outcome: Ok(()),
+            },
+            TargetReport {
+                name: "a".to_string(),
+                outcome: Err("HTTP 503".to_string()),
+            },
+        ];
+
+        assert_eq!(
+            format_report_table(&reports),
+            "TARGET   RESULT\nwebhook  ok\na        FAILED: HTTP 503\n"
+        );
+    }
+}
+
+mod exchange_log {
+    use super::*;
+
+    /// Client answering 200 to `/ok` and timing out elsewhere.
+    struct PathClient;
+
+    impl HttpClient for PathClient {
+        async fn request(&self, req: HttpRequest) -> Result<HttpResponse, HttpError> {
+            if req.url.path() == "/ok" {
+                Ok(HttpResponse::new(
+                    http::StatusCode::OK,
+                    http::HeaderMap::new(),
+                    b"done".to_vec(),
+                ))
+            } else {
+                Err(HttpError::Timeout)
+            }
+        }
+    }
+
+    fn get(path: &str) -> HttpRequest {
+        HttpRequest::get(format!("https://example.com{path}").parse().unwrap())
+    }
+
+    #[tokio::test]
+    async fn records_responses_and_errors_in_order() {
+        let log = ExchangeLog::new();
+        let client = LoggingClient::new(PathClient, log.clone());
+
+        client.request(get("/ok")).await.unwrap();
+        client.request(get("/slow")).await.unwrap_err();
+        let exchanges = log.take();
+
+        assert_eq!(exchanges.len(), 2);
+        assert_eq!(exchanges[0].request.url.path(), "/ok");
+        assert_eq!(exchanges[0].response.as_ref().unwrap().body, b"done");
+        assert!(exchanges[1].response.is_err());
+        assert!(log.take().is_empty());
+    }
+
+    #[test]
+    fn formats_request_and_response_like_http() {
+        let request = HttpRequest::post("https://example.com/ddns".parse().unwrap())
+            .with_header(
+                http::header::CONTENT_TYPE,
+                http::HeaderValue::from_static("application/json"),
+            )
+            .with_body(b"{\"ip\":\"192.0.2.1\"}".to_vec());
+        let response = HttpResponse::new(
+            http::StatusCode::NOT_FOUND,
+            http::HeaderMap::new(),
+            b"no such record".to_vec(),
+        );
+
+        assert_eq!(
+            format_request(&request),
+            "POST https://example.com/ddns\ncontent-type: application/json\n\n{\"ip\":\"192.0.2.1\"}\n"
+        );
+        assert_eq!(
+            format_response(&response),
+            "HTTP 404 Not Found\n\nno such record\n"
+        );
+    }
+}
@@ -0,0 +1,175 @@
+//! Built-in presets for chat services.
+//!
+//! A [`ChatPreset`] replaces the webhook body with a readable message (e.g.
+//! `eth0 got new IPv4 203.0.113.5`) in the service's own message format,
+//! so chat notifications need a bot token or webhook URL but no templates.
+
+use std::fmt;
+use std::net::IpAddr;
+
+use serde_json::json;
+use url::Url;
+
+use crate::monitor::IpChange;
+
+use crate::webhook::{Payload, REDACTED};
+
+/// A supported chat service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatService {
+    /// Telegram Bot API: `POST /bot<token>/sendMessage` with a chat ID.
+    Telegram,
+    /// Discord: `POST` to a channel webhook URL.
+    Discord,
+    /// Slack: `POST` to an incoming webhook URL.
+    Slack,
+}
+
+impl ChatService {
+    /// All supported services.
+    pub const ALL: [Self; 3] = [Self::Telegram, Self::Discord, Self::Slack];
+
+    /// Returns the name of the service's `[notify]` section.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Telegram => "telegram",
+            Self::Discord => "discord",
+            Self::Slack => "slack",
+        }
+    }
+
+    /// Returns the name of the service's target in logs and reports.
+    #[must_use]
+    pub const fn target_name(self) -> &'static str {
+        match self {
+            Self::Telegram => "notify.telegram",
+            Self::Discord => "notify.discord",
+            Self::Slack => "notify.slack",
+        }
+    }
+}
+
+impl fmt::Display for ChatService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A configured chat service preset.
+///
+/// Every delivery becomes one message: a line naming the host, then one
+/// line per change. Address changes read `eth0 got new IPv4 203.0.113.5`
+/// or `eth0 lost IPv6 2001:db8::1`; other events read as they are logged.
+///
+/// The URL holds the credentials (the bot token or the webhook's secret
+/// path), so it is left out of [`Debug`](fmt::Debug) output.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ChatPreset {
+    service: ChatService,
+    url: Url,
+    chat_id: Option<String>,
+}
+
+impl ChatPreset {
+    /// Creates a Telegram preset sending to `chat_id` as the bot `bot_token`.
+    ///
+    /// # Panics
+    ///
+    /// Never: the Bot API base is a valid URL literal and the token is
+    /// appended as a single path segment.
+    #[must_use]
+    pub fn telegram(bot_token: &str, chat_id: impl Into<String>) -> Self {
+        let mut url = Url::parse("https://api.telegram.org/").expect("valid Bot API URL");
+        url.path_segments_mut()
+            .expect("https URLs have a path")
+            .push(&format!("bot{bot_token}"))
+            .push("sendMessage");
+        Self {
+            service: ChatService::Telegram,
+            url,
+            chat_id: Some(chat_id.into()),
+        }
+    }
+
+    /// Creates a Discord preset posting to the channel webhook `url`.
+    #[must_use]
+    pub const fn discord(url: Url) -> Self {
+        Self {
+            service: ChatService::Discord,
+            url,
+            chat_id: None,
+        }
+    }
+
+    /// Creates a Slack preset posting to the incoming webhook `url`.
+    #[must_use]
+    pub const fn slack(url: Url) -> Self {
+        Self {
+            service: ChatService::Slack,
+            url,
+            chat_id: None,
+        }
+    }
+
+    /// Returns the chat service.
+    #[must_use]
+    pub const fn service(&self) -> ChatService {
+        self.service
+    }
+
+    /// Returns the URL messages are posted to.
+    #[must_use]
+    pub const fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Returns the JSON request body carrying the message for `payload`.
+    #[must_use]
+    pub fn body(&self, payload: Payload<'_>, host: &str) -> Vec<u8> {
+        let text = message(payload, host);
+        let body = match self.service {
+            ChatService::Telegram => json!({ "chat_id": self.chat_id, "text": text }),
+            ChatService::Discord => json!({ "content": text }),
+            ChatService::Slack => json!({ "text": text }),
+        };
+        body.to_string().into_bytes()
+    }
+}
+
+impl fmt::Debug for ChatPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChatPreset")
+            .field("service", &self.service)
+            .field("url", &REDACTED)
+            .field("chat_id", &self.chat_id)
+            .finish()
+    }
+}
+
+/// The readable message of `payload`, headed by `host` unless it is empty.
+fn message(payload: Payload<'_>, host: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    if !host.is_empty() {
+        lines.push(format!("ddns-a on {host}"));
+    }
+    match payload {
+        Payload::Ip(changes) => lines.extend(changes.iter().map(describe)),
+        Payload::Dns(changes) => lines.extend(changes.iter().map(ToString::to_string)),
+        Payload::Drift(changes) => lines.extend(changes.iter().map(ToString::to_string)),
+        Payload::Hostname(changes) => lines.extend(changes.iter().map(ToString::to_string)),
+        Payload::Storm(changes) => lines.extend(changes.iter().map(ToString::to_string)),
+        Payload::Link(changes) => lines.extend(changes.iter().map(ToString::to_string)),
+    }
+    lines.join("\n")
+}
+
+/// `eth0 got new IPv4 203.0.113.5` or `eth0 lost IPv4 203.0.113.5`.
+fn describe(change: &IpChange) -> String {
+    let verb = if change.is_added() { "got new" } else { "lost" };
+    let family = match change.address {
+        IpAddr::V4(_) => "IPv4",
+        IpAddr::V6(_) => "IPv6",
+    };
+    format!("{} {verb} {family} {}", change.adapter, change.address)
+}
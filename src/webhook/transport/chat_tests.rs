@@ -0,0 +1,142 @@
+//! Tests for chat service presets.
+
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use http::header::CONTENT_TYPE;
+use http::{HeaderMap, StatusCode};
+use serde_json::{Value, json};
+use url::Url;
+
+use crate::monitor::{HostnameChange, IpChange};
+use crate::webhook::{
+    ChatPreset, ChatService, HttpClient, HttpError, HttpRequest, HttpResponse, HttpWebhook,
+    Payload, WebhookSender,
+};
+
+fn changes() -> Vec<IpChange> {
+    vec![
+        IpChange::added(
+            "eth0",
+            "203.0.113.5".parse().unwrap(),
+            SystemTime::UNIX_EPOCH,
+        ),
+        IpChange::removed(
+            "eth0",
+            "2001:db8::1".parse().unwrap(),
+            SystemTime::UNIX_EPOCH,
+        ),
+    ]
+}
+
+fn body(preset: &ChatPreset, payload: Payload<'_>) -> Value {
+    serde_json::from_slice(&preset.body(payload, "nas")).unwrap()
+}
+
+fn hook() -> Url {
+    Url::parse("https://hooks.example.com/services/T0/B0/secret").unwrap()
+}
+
+#[test]
+fn names_are_distinct() {
+    for service in ChatService::ALL {
+        assert_eq!(service.to_string(), service.name());
+        assert_eq!(service.target_name(), format!("notify.{}", service.name()));
+    }
+}
+
+#[test]
+fn telegram_posts_to_the_bot_api() {
+    let preset = ChatPreset::telegram("123:ABC-def", "-1001");
+
+    assert_eq!(
+        preset.url().as_str(),
+        "https://api.telegram.org/bot123:ABC-def/sendMessage"
+    );
+    assert_eq!(
+        body(&preset, Payload::Ip(&changes())),
+        json!({
+            "chat_id": "-1001",
+            "text": "ddns-a on nas\neth0 got new IPv4 203.0.113.5\neth0 lost IPv6 2001:db8::1",
+        })
+    );
+}
+
+#[test]
+fn discord_and_slack_carry_the_message() {
+    let discord = body(&ChatPreset::discord(hook()), Payload::Ip(&changes()));
+    let slack = body(&ChatPreset::slack(hook()), Payload::Ip(&changes()));
+
+    assert!(
+        discord["content"]
+            .as_str()
+            .unwrap()
+            .contains("got new IPv4")
+    );
+    assert!(slack["text"].as_str().unwrap().contains("lost IPv6"));
+}
+
+#[test]
+fn other_events_read_as_logged() {
+    let change = HostnameChange {
+        old: "old".to_string(),
+        new: "new".to_string(),
+        timestamp: SystemTime::UNIX_EPOCH,
+    };
+
+    let body = body(&ChatPreset::slack(hook()), Payload::Hostname(&[change]));
+
+    assert_eq!(body["text"], "ddns-a on nas\nHostname old → new");
+}
+
+#[test]
+fn empty_host_is_left_out() {
+    let body: Value =
+        serde_json::from_slice(&ChatPreset::slack(hook()).body(Payload::Ip(&changes()[..1]), ""))
+            .unwrap();
+
+    assert_eq!(body["text"], "eth0 got new IPv4 203.0.113.5");
+}
+
+#[test]
+fn debug_hides_the_url() {
+    let debug = format!("{:?}", ChatPreset::telegram("123:secret", "42"));
+
+    assert!(!debug.contains("secret"), "{debug}");
+    assert!(debug.contains("42"), "{debug}");
+}
+
+#[derive(Debug, Default)]
+struct Recorder(Mutex<Vec<HttpRequest>>);
+
+impl HttpClient for &Recorder {
+    async fn request(&self, req: HttpRequest) -> Result<HttpResponse, HttpError> {
+        self.0.lock().unwrap().push(req);
+        Ok(HttpResponse::new(
+            StatusCode::NO_CONTENT,
+            HeaderMap::new(),
+            vec![],
+        ))
+    }
+}
+
+#[tokio::test]
+async fn webhook_sends_the_message_instead_of_a_template() {
+    let recorder = Recorder::default();
+    let preset = ChatPreset::discord(hook());
+    let webhook = HttpWebhook::new(&recorder, preset.url().clone())
+        .with_chat(preset)
+        .with_host("nas")
+        .with_body_template("{{json changes}}");
+
+    webhook.send(&changes()[..1]).await.unwrap();
+
+    let requests = recorder.0.lock().unwrap().clone();
+    assert_eq!(requests[0].url, hook());
+    assert_eq!(requests[0].headers[CONTENT_TYPE], "application/json");
+    let body: Value = serde_json::from_slice(requests[0].body.as_deref().unwrap()).unwrap();
+    assert_eq!(
+        body,
+        json!({ "content": "ddns-a on nas\neth0 got new IPv4 203.0.113.5" })
+    );
+}
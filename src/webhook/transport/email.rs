@@ -0,0 +1,437 @@
+//! Sending deliveries by email over SMTP (`[email]`).
+//!
+//! [`SmtpMailer`] is a [`Transport`] sending each attempt as one plain-text
+//! email, so an [`EmailSender`] retries failed sends like an
+//! [`HttpWebhook`](crate::webhook::HttpWebhook) retries failed requests. Every
+//! attempt opens a new SMTP session: changes are rare, and no connection is
+//! held open between them.
+//!
+//! An `smtp://` server (default port 587) is upgraded with STARTTLS unless
+//! that is turned off; an `smtps://` server (default port 465) is spoken to
+//! over TLS from the start. Credentials are sent with `AUTH PLAIN`, and only
+//! over TLS.
+//!
+//! The subject and body are the rendered templates if set; otherwise the
+//! subject names the host and event, and the body lists address changes one
+//! per line or holds the template variables of other events as JSON.
+//!
+//! An attempt fails if the server cannot be reached, does not answer within
+//! the timeout or rejects a command. Permanent rejections (5xx replies, e.g.
+//! refused credentials or recipients) are not retried.
+
+use std::fmt::Write as _;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rustls::pki_types::ServerName;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+use url::Url;
+
+use crate::monitor::{HostnameSource, SystemHostname};
+use crate::time::{SystemClock, TokioSleeper, format_rfc2822, format_rfc3339};
+
+use crate::webhook::payload::render_template;
+use crate::webhook::payload::{Attempt, Payload};
+use crate::webhook::provider::base64;
+use crate::webhook::{RetryableError, RetryingSender, TlsSettings, Transport, tls_config};
+
+/// Default time an attempt may take from connecting to the server's
+/// acceptance of the message.
+pub const DEFAULT_EMAIL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A [`RetryingSender`] sending with an [`SmtpMailer`].
+pub type EmailSender<S = TokioSleeper, C = SystemClock> = RetryingSender<SmtpMailer, S, C>;
+
+/// Emails every delivery attempt through an SMTP server.
+#[derive(Debug, Clone)]
+pub struct SmtpMailer {
+    server: Url,
+    from: String,
+    to: Vec<String>,
+    starttls: bool,
+    credentials: Option<(String, String)>,
+    tls: TlsSettings,
+    subject_template: Option<String>,
+    body_template: Option<String>,
+    host: String,
+    timeout: Duration,
+}
+
+impl SmtpMailer {
+    /// Creates a mailer sending from `from` to every address of `to`
+    /// through `server`, an `smtp://` or `smtps://` URL.
+    #[must_use]
+    pub fn new<I>(server: Url, from: impl Into<String>, to: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        Self {
+            server,
+            from: from.into(),
+            to: to.into_iter().map(Into::into).collect(),
+            starttls: true,
+            credentials: None,
+            tls: TlsSettings::default(),
+            subject_template: None,
+            body_template: None,
+            host: SystemHostname.hostname().unwrap_or_default(),
+            timeout: DEFAULT_EMAIL_TIMEOUT,
+        }
+    }
+
+    /// Sets whether an `smtp://` session must be upgraded with STARTTLS
+    /// (default: true).
+    #[must_use]
+    pub const fn with_starttls(mut self, starttls: bool) -> Self {
+        self.starttls = starttls;
+        self
+    }
+
+    /// Sets the user name and password sent with `AUTH PLAIN`.
+    #[must_use]
+    pub fn with_credentials(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.credentials = Some((username.into(), password.into()));
+        self
+    }
+
+    /// Sets the CA bundle, client certificate and insecure mode of TLS.
+    #[must_use]
+    pub fn with_tls(mut self, tls: TlsSettings) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Sets the Handlebars template of the subject.
+    #[must_use]
+    pub fn with_subject_template(mut self, template: impl Into<String>) -> Self {
+        self.subject_template = Some(template.into());
+        self
+    }
+
+    /// Sets the Handlebars template of the body.
+    #[must_use]
+    pub fn with_body_template(mut self, template: impl Into<String>) -> Self {
+        self.body_template = Some(template.into());
+        self
+    }
+
+    /// Sets the host named in the default subject and the greeting
+    /// (default: the machine's hostname).
+    #[must_use]
+    pub fn with_host(mut self, host: impl Into<String>) -> Self {
+        self.host = host.into();
+        self
+    }
+
+    /// Sets the time an attempt may take until the message is accepted.
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the server sent through.
+    #[must_use]
+    pub const fn server(&self) -> &Url {
+        &self.server
+    }
+
+    /// Returns the sender address.
+    #[must_use]
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+
+    /// Returns the recipient addresses.
+    #[must_use]
+    pub fn to(&self) -> &[String] {
+        &self.to
+    }
+
+    /// Returns whether an `smtp://` session is upgraded with STARTTLS.
+    #[must_use]
+    pub const fn starttls(&self) -> bool {
+        self.starttls
+    }
+
+    /// Returns the time an attempt may take.
+    #[must_use]
+    pub const fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Builds the message of an attempt, headers included.
+    fn message(&self, payload: Payload<'_>, attempt: Attempt) -> Result<String, RetryableError> {
+        let data = payload.data(attempt);
+        let subject = match &self.subject_template {
+            Some(template) => render_template(template, &data)?,
+            None => payload.event().map_or_else(
+                || format!("ddns-a on {}: address change", self.host),
+                |event| format!("ddns-a on {}: {event} event", self.host),
+            ),
+        };
+        let body = match (&self.body_template, payload) {
+            (Some(template), _) => render_template(template, &data)?,
+            (None, Payload::Ip(changes)) => changes.iter().fold(String::new(), |mut body, c| {
+                let kind = if c.is_added() { "added" } else { "removed" };
+                let at = c.timestamp.duration_since(UNIX_EPOCH).unwrap_or_default();
+                let _ = writeln!(
+                    body,
+                    "{kind} {} on {} at {}",
+                    c.address,
+                    c.adapter,
+                    format_rfc3339(at.as_secs())
+                );
+                body
+            }),
+            (None, _) => {
+                serde_json::to_string_pretty(&data).map_err(|e| failure(e.to_string(), false))?
+            }
+        };
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let mut message = format!(
+            "Date: {}\r\nFrom: {}\r\nTo: {}\r\nSubject: {}\r\nMIME-Version: 1.0\r\n\
+             Content-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: 8bit\r\n\r\n",
+            format_rfc2822(now.as_secs()),
+            self.from,
+            self.to.join(", "),
+            encode_subject(&subject)
+        );
+        for line in body.lines() {
+            // A leading dot is doubled so no line ends the data early
+            if line.starts_with('.') {
+                message.push('.');
+            }
+            message.push_str(line);
+            message.push_str("\r\n");
+        }
+        Ok(message)
+    }
+
+    /// Connects to the server and sends `message` in one session.
+    async fn send_to_server(&self, message: &str) -> Result<(), RetryableError> {
+        let implicit_tls = self.server.scheme() == "smtps";
+        let host = self
+            .server
+            .host_str()
+            .unwrap_or_default()
+            .trim_start_matches('[')
+            .trim_end_matches(']');
+        let port = self
+            .server
+            .port()
+            .unwrap_or(if implicit_tls { 465 } else { 587 });
+
+        let stream = TcpStream::connect((host, port))
+            .await
+            .map_err(|e| failure(format!("cannot connect to {host}:{port}: {e}"), true))?;
+        if implicit_tls {
+            let mut session = Session::new(self.secure(stream, host).await?);
+            session.reply(220).await?;
+            session.ehlo(&self.helo_name()).await?;
+            return self.transact(session, message).await;
+        }
+
+        let mut session = Session::new(stream);
+        session.reply(220).await?;
+        let extensions = session.ehlo(&self.helo_name()).await?;
+        if !self.starttls {
+            return self.transact(session, message).await;
+        }
+        if !extensions
+            .iter()
+            .any(|e| e.eq_ignore_ascii_case("STARTTLS"))
+        {
+            return Err(failure(
+                format!("{host}:{port} does not offer STARTTLS"),
+                false,
+            ));
+        }
+        session.command("STARTTLS", 220).await?;
+        let mut session = Session::new(self.secure(session.into_inner(), host).await?);
+        session.ehlo(&self.helo_name()).await?;
+        self.transact(session, message).await
+    }
+
+    /// Wraps `stream` in TLS to `host`.
+    async fn secure(
+        &self,
+        stream: TcpStream,
+        host: &str,
+    ) -> Result<tokio_rustls::client::TlsStream<TcpStream>, RetryableError> {
+        let mut config = tls_config(&self.tls, None)
+            .map_err(|e| failure(format!("TLS configuration: {e}"), false))?;
+        config.alpn_protocols.clear();
+        let name = ServerName::try_from(host.to_string())
+            .map_err(|e| failure(format!("invalid TLS server name '{host}': {e}"), false))?;
+        TlsConnector::from(Arc::new(config))
+            .connect(name, stream)
+            .await
+            .map_err(|e| failure(format!("TLS handshake with {host} failed: {e}"), true))
+    }
+
+    /// Authenticates if configured and sends `message` to every recipient.
+    async fn transact<S>(
+        &self,
+        mut session: Session<S>,
+        message: &str,
+    ) -> Result<(), RetryableError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        if let Some((username, password)) = &self.credentials {
+            let token = base64(format!("\0{username}\0{password}").as_bytes());
+            session.command(&format!("AUTH PLAIN {token}"), 235).await?;
+        }
+        session
+            .command(&format!("MAIL FROM:<{}>", self.from), 250)
+            .await?;
+        for recipient in &self.to {
+            session
+                .command(&format!("RCPT TO:<{recipient}>"), 250)
+                .await?;
+        }
+        session.command("DATA", 354).await?;
+        session.write(message).await?;
+        session.command(".", 250).await?;
+
+        // The message is accepted; a failed goodbye changes nothing
+        let _ = session.command("QUIT", 221).await;
+        Ok(())
+    }
+
+    /// The name the client greets the server with.
+    fn helo_name(&self) -> String {
+        if self.host.is_empty() {
+            "localhost".to_string()
+        } else {
+            self.host.clone()
+        }
+    }
+}
+
+impl Transport for SmtpMailer {
+    async fn attempt(&self, payload: Payload<'_>, attempt: Attempt) -> Result<(), RetryableError> {
+        let message = self.message(payload, attempt)?;
+        tokio::time::timeout(self.timeout, self.send_to_server(&message))
+            .await
+            .unwrap_or_else(|_| {
+                Err(failure(
+                    format!(
+                        "no answer from {} within {}s",
+                        self.server,
+                        self.timeout.as_secs()
+                    ),
+                    true,
+                ))
+            })
+    }
+}
+
+/// An SMTP session over `S`, reading replies line by line.
+struct Session<S> {
+    stream: BufReader<S>,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Session<S> {
+    fn new(stream: S) -> Self {
+        Self {
+            stream: BufReader::new(stream),
+        }
+    }
+
+    fn into_inner(self) -> S {
+        self.stream.into_inner()
+    }
+
+    async fn write(&mut self, text: &str) -> Result<(), RetryableError> {
+        let stream = self.stream.get_mut();
+        stream.write_all(text.as_bytes()).await.map_err(io)?;
+        stream.flush().await.map_err(io)
+    }
+
+    /// Sends `command` and reads a reply with code `expected`.
+    async fn command(
+        &mut self,
+        command: &str,
+        expected: u16,
+    ) -> Result<Vec<String>, RetryableError> {
+        self.write(&format!("{command}\r\n")).await?;
+        self.reply(expected).await.map_err(|e| match e {
+            RetryableError::Transport { reason, retryable } => {
+                // Never echo the credentials
+                let verb = command.split(' ').next().unwrap_or_default();
+                failure(format!("{verb}: {reason}"), retryable)
+            }
+            other => other,
+        })
+    }
+
+    /// Greets the server, returning the extensions it offers.
+    async fn ehlo(&mut self, name: &str) -> Result<Vec<String>, RetryableError> {
+        let lines = self.command(&format!("EHLO {name}"), 250).await?;
+        Ok(lines
+            .into_iter()
+            .skip(1)
+            .filter_map(|line| line.split_whitespace().next().map(str::to_string))
+            .collect())
+    }
+
+    /// Reads a reply, failing unless its code is `expected`; returns the
+    /// text of its lines.
+    async fn reply(&mut self, expected: u16) -> Result<Vec<String>, RetryableError> {
+        let mut lines = Vec::new();
+        loop {
+            let mut line = String::new();
+            if self.stream.read_line(&mut line).await.map_err(io)? == 0 {
+                return Err(failure("server closed the connection".to_string(), true));
+            }
+            let line = line.trim_end();
+            let code = line.get(..3).and_then(|c| c.parse::<u16>().ok());
+            let Some(code) = code else {
+                return Err(failure(format!("malformed reply '{line}'"), true));
+            };
+            lines.push(line.get(4..).unwrap_or_default().to_string());
+            if line.as_bytes().get(3) == Some(&b'-') {
+                continue;
+            }
+            if code == expected || (expected == 250 && code == 251) {
+                return Ok(lines);
+            }
+            // 5xx replies are permanent, anything else may pass
+            return Err(failure(
+                format!("server replied {code} {}", lines.join(" ")),
+                code < 500,
+            ));
+        }
+    }
+}
+
+const fn failure(reason: String, retryable: bool) -> RetryableError {
+    RetryableError::Transport { reason, retryable }
+}
+
+#[allow(clippy::needless_pass_by_value)] // Used with map_err
+fn io(error: std::io::Error) -> RetryableError {
+    failure(format!("connection to the server failed: {error}"), true)
+}
+
+/// The subject as a header value: ASCII as is, anything else encoded.
+fn encode_subject(subject: &str) -> String {
+    let subject = subject.replace(['\r', '\n'], " ");
+    if subject.is_ascii() {
+        subject
+    } else {
+        format!("=?UTF-8?B?{}?=", base64(subject.as_bytes()))
+    }
+}
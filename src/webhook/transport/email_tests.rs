@@ -0,0 +1,246 @@
+//! Tests for the SMTP mailer against a scripted local server.
+
+use std::time::{Duration, SystemTime};
+
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use url::Url;
+
+use crate::monitor::{HostnameChange, IpChange};
+use crate::time::InstantSleeper;
+use crate::webhook::{
+    Attempt, EmailSender, IsRetryable, Payload, RetryPolicy, SmtpMailer, Transport, WebhookError,
+    WebhookSender,
+};
+
+const FIRST: Attempt = Attempt {
+    number: 1,
+    elapsed: Duration::ZERO,
+};
+
+fn changes() -> Vec<IpChange> {
+    vec![IpChange::added(
+        "eth0",
+        "192.0.2.1".parse().unwrap(),
+        SystemTime::UNIX_EPOCH,
+    )]
+}
+
+/// Serves one session, offering `extensions` and answering recipients with
+/// `rcpt_reply`; returns the lines received.
+async fn server(
+    extensions: &'static str,
+    rcpt_reply: &'static str,
+) -> (Url, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = Url::parse(&format!("smtp://{}", listener.local_addr().unwrap())).unwrap();
+    let session = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut stream = BufReader::new(stream);
+        stream
+            .get_mut()
+            .write_all(b"220 mx ready\r\n")
+            .await
+            .unwrap();
+        let (mut received, mut in_data) = (Vec::new(), false);
+        loop {
+            let mut line = String::new();
+            if stream.read_line(&mut line).await.unwrap() == 0 {
+                break;
+            }
+            let line = line.trim_end_matches("\r\n").to_string();
+            let reply = if in_data {
+                in_data = line != ".";
+                (!in_data).then(|| "250 queued\r\n".to_string())
+            } else {
+                match line.split(' ').next().unwrap() {
+                    "EHLO" => Some(format!("250-mx\r\n{extensions}250 8BITMIME\r\n")),
+                    "AUTH" => Some("235 ok\r\n".to_string()),
+                    "MAIL" => Some("250 ok\r\n".to_string()),
+                    "RCPT" => Some(format!("{rcpt_reply}\r\n")),
+                    "DATA" => {
+                        in_data = true;
+                        Some("354 go ahead\r\n".to_string())
+                    }
+                    "QUIT" => Some("221 bye\r\n".to_string()),
+                    _ => Some("502 unknown\r\n".to_string()),
+                }
+            };
+            let quit = line == "QUIT";
+            received.push(line);
+            if let Some(reply) = reply {
+                stream.get_mut().write_all(reply.as_bytes()).await.unwrap();
+            }
+            if quit {
+                break;
+            }
+        }
+        received
+    });
+    (url, session)
+}
+
+/// The lines of the message between `DATA` and the final dot.
+fn message(received: &[String]) -> Vec<String> {
+    let start = received.iter().position(|l| l == "DATA").unwrap() + 1;
+    let end = received.iter().rposition(|l| l == ".").unwrap();
+    received[start..end].to_vec()
+}
+
+#[tokio::test]
+async fn sends_the_changes_to_every_recipient() {
+    let (url, session) = server("", "250 ok").await;
+    let mailer = SmtpMailer::new(url, "ddns@example.com", ["a@example.com", "b@example.com"])
+        .with_starttls(false)
+        .with_host("nas");
+
+    mailer
+        .attempt(Payload::Ip(&changes()), FIRST)
+        .await
+        .unwrap();
+
+    let received = session.await.unwrap();
+    assert_eq!(received[0], "EHLO nas");
+    assert_eq!(received[1], "MAIL FROM:<ddns@example.com>");
+    assert_eq!(received[2], "RCPT TO:<a@example.com>");
+    assert_eq!(received[3], "RCPT TO:<b@example.com>");
+    assert_eq!(received.last().unwrap(), "QUIT");
+    let message = message(&received);
+    assert!(message.contains(&"From: ddns@example.com".to_string()));
+    assert!(message.contains(&"To: a@example.com, b@example.com".to_string()));
+    assert!(message.contains(&"Subject: ddns-a on nas: address change".to_string()));
+    assert!(message.iter().any(|l| l.starts_with("Date: ")));
+    assert_eq!(
+        message.last().unwrap(),
+        "added 192.0.2.1 on eth0 at 1970-01-01T00:00:00Z"
+    );
+}
+
+#[tokio::test]
+async fn credentials_are_sent_with_auth_plain() {
+    let (url, session) = server("250-AUTH PLAIN\r\n", "250 ok").await;
+    let mailer = SmtpMailer::new(url, "d@example.com", ["a@example.com"])
+        .with_starttls(false)
+        .with_credentials("user", "secret");
+
+    mailer
+        .attempt(Payload::Ip(&changes()), FIRST)
+        .await
+        .unwrap();
+
+    let received = session.await.unwrap();
+    // base64("\0user\0secret")
+    assert_eq!(received[1], "AUTH PLAIN AHVzZXIAc2VjcmV0");
+}
+
+#[tokio::test]
+async fn other_events_carry_their_variables() {
+    let (url, session) = server("", "250 ok").await;
+    let mailer = SmtpMailer::new(url, "d@example.com", ["a@example.com"])
+        .with_starttls(false)
+        .with_host("nas");
+    let change = HostnameChange {
+        old: "old".to_string(),
+        new: ".new".to_string(),
+        timestamp: SystemTime::UNIX_EPOCH,
+    };
+
+    mailer
+        .attempt(Payload::Hostname(&[change]), FIRST)
+        .await
+        .unwrap();
+
+    let message = message(&session.await.unwrap());
+    assert!(message.contains(&"Subject: ddns-a on nas: hostname event".to_string()));
+    assert!(message.iter().any(|l| l.contains(r#""hostname": ".new""#)));
+}
+
+#[cfg(feature = "templates")]
+#[tokio::test]
+async fn templates_are_rendered_and_dots_doubled() {
+    let (url, session) = server("", "250 ok").await;
+    let mailer = SmtpMailer::new(url, "d@example.com", ["a@example.com"])
+        .with_starttls(false)
+        .with_subject_template("IP → {{first_added_v4 changes}}")
+        .with_body_template(".{{first_added_v4 changes}}\n.");
+
+    mailer
+        .attempt(Payload::Ip(&changes()), FIRST)
+        .await
+        .unwrap();
+
+    let message = message(&session.await.unwrap());
+    // Non-ASCII subjects are encoded
+    assert!(message.contains(&"Subject: =?UTF-8?B?SVAg4oaSIDE5Mi4wLjIuMQ==?=".to_string()));
+    assert_eq!(message[message.len() - 2..], ["..192.0.2.1", ".."]);
+}
+
+#[tokio::test]
+async fn missing_starttls_is_not_retried() {
+    let (url, _session) = server("", "250 ok").await;
+    let mailer = SmtpMailer::new(url, "d@example.com", ["a@example.com"]);
+
+    let error = mailer
+        .attempt(Payload::Ip(&changes()), FIRST)
+        .await
+        .unwrap_err();
+
+    assert!(!error.is_retryable());
+    assert!(
+        error.to_string().contains("does not offer STARTTLS"),
+        "{error}"
+    );
+}
+
+#[tokio::test]
+async fn permanent_rejection_is_not_retried() {
+    let (url, _session) = server("", "550 no such user").await;
+    let mailer = SmtpMailer::new(url, "d@example.com", ["a@example.com"]).with_starttls(false);
+
+    let error = mailer
+        .attempt(Payload::Ip(&changes()), FIRST)
+        .await
+        .unwrap_err();
+
+    assert!(!error.is_retryable());
+    assert!(
+        error
+            .to_string()
+            .contains("RCPT: server replied 550 no such user"),
+        "{error}"
+    );
+}
+
+#[tokio::test]
+async fn temporary_rejection_is_retried() {
+    let (url, _session) = server("", "451 try later").await;
+    let sender = EmailSender::new(
+        SmtpMailer::new(url, "d@example.com", ["a@example.com"]).with_starttls(false),
+    )
+    .with_sleeper(InstantSleeper)
+    .with_retry_policy(RetryPolicy::new().with_max_attempts(1));
+
+    let error = sender.send(&changes()).await.unwrap_err();
+
+    let WebhookError::MaxRetriesExceeded { last_error, .. } = error else {
+        panic!("unexpected error: {error:?}");
+    };
+    assert!(last_error.is_retryable());
+}
+
+#[tokio::test]
+async fn silent_server_times_out() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = Url::parse(&format!("smtp://{}", listener.local_addr().unwrap())).unwrap();
+    let mailer = SmtpMailer::new(url, "d@example.com", ["a@example.com"])
+        .with_timeout(Duration::from_millis(100));
+
+    let error = mailer
+        .attempt(Payload::Ip(&changes()), FIRST)
+        .await
+        .unwrap_err();
+
+    assert!(error.is_retryable());
+    assert!(error.to_string().contains("no answer from"), "{error}");
+}
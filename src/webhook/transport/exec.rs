@@ -0,0 +1,250 @@
+//! Running a local command on every delivery (`[action.exec]`).
+//!
+//! [`ExecAction`] is a [`Transport`] running a command once per attempt, so
+//! an [`ExecSender`] retries failed runs like an
+//! [`HttpWebhook`](crate::webhook::HttpWebhook) retries failed requests. The command
+//! is started directly, without a shell, and learns about the delivery from
+//! environment variables:
+//!
+//! - `DDNS_A_EVENT`: `address`, `dns`, `drift`, `hostname` or `storm`
+//! - `DDNS_A_ATTEMPT`: the 1-based attempt number
+//! - `DDNS_A_ADDED` / `DDNS_A_REMOVED`: the added / removed addresses,
+//!   space-separated (address changes only)
+//! - `DDNS_A_ADDED_V4` / `DDNS_A_ADDED_V6`: the first added IPv4 / IPv6
+//!   address, empty if there is none (address changes only)
+//! - `DDNS_A_PAYLOAD`: the template variables as JSON
+//!
+//! With [`ExecInput::Stdin`], the JSON is written to the command's standard
+//! input instead of `DDNS_A_PAYLOAD`. Inherited `DDNS_A_*` configuration
+//! variables are removed.
+//!
+//! A run fails if the command cannot be started (not retried), exits with a
+//! non-zero status or outlives its timeout and is killed (both retried). Its
+//! standard output is logged line by line at info level, its standard error
+//! at warn level.
+
+use std::process::{Output, Stdio};
+use std::time::Duration;
+
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::monitor::IpChange;
+use crate::time::{SystemClock, TokioSleeper};
+
+use crate::webhook::payload::{Attempt, Payload};
+use crate::webhook::{RetryableError, RetryingSender, Transport};
+
+/// Default time a command may run before it is killed.
+pub const DEFAULT_EXEC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Prefix of the variables set for the command, shared with the
+/// configuration variables.
+const VAR_PREFIX: &str = "DDNS_A_";
+
+/// Environment variable holding the template variables as JSON.
+const PAYLOAD_VAR: &str = "DDNS_A_PAYLOAD";
+
+/// A [`RetryingSender`] running an [`ExecAction`].
+pub type ExecSender<S = TokioSleeper, C = SystemClock> = RetryingSender<ExecAction, S, C>;
+
+/// How the template variables are passed to the command.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExecInput {
+    /// In the `DDNS_A_PAYLOAD` environment variable (default).
+    #[default]
+    Env,
+    /// On standard input.
+    Stdin,
+}
+
+/// A command run on every delivery attempt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecAction {
+    program: String,
+    args: Vec<String>,
+    timeout: Duration,
+    input: ExecInput,
+}
+
+impl ExecAction {
+    /// Creates an action running `program` without arguments.
+    #[must_use]
+    pub fn new(program: impl Into<String>) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+            timeout: DEFAULT_EXEC_TIMEOUT,
+            input: ExecInput::default(),
+        }
+    }
+
+    /// Sets the arguments of the command.
+    #[must_use]
+    pub fn with_args<I>(mut self, args: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the time a run may take before the command is killed.
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets how the template variables are passed to the command.
+    #[must_use]
+    pub const fn with_input(mut self, input: ExecInput) -> Self {
+        self.input = input;
+        self
+    }
+
+    /// Returns the program run.
+    #[must_use]
+    pub fn program(&self) -> &str {
+        &self.program
+    }
+
+    /// Returns the arguments of the command.
+    #[must_use]
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// Returns the time a run may take.
+    #[must_use]
+    pub const fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Returns how the template variables are passed to the command.
+    #[must_use]
+    pub const fn input(&self) -> ExecInput {
+        self.input
+    }
+
+    fn command(&self, payload: Payload<'_>, attempt: Attempt, json: &str) -> Command {
+        let mut command = Command::new(&self.program);
+        command
+            .args(&self.args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        for (name, _) in std::env::vars_os() {
+            if name.to_string_lossy().starts_with(VAR_PREFIX) {
+                command.env_remove(name);
+            }
+        }
+        command.envs(variables(payload, attempt));
+        match self.input {
+            ExecInput::Env => command.env(PAYLOAD_VAR, json).stdin(Stdio::null()),
+            ExecInput::Stdin => command.stdin(Stdio::piped()),
+        };
+        command
+    }
+
+    /// Runs the command to completion, killing it after the timeout.
+    async fn run(&self, mut command: Command, json: &str) -> Result<Output, RetryableError> {
+        let mut child = command
+            .spawn()
+            .map_err(|e| failure(format!("cannot run '{}': {e}", self.program), false))?;
+        let stdin = child.stdin.take();
+        let write = async move {
+            if let Some(mut stdin) = stdin {
+                // A command not reading its input has not failed; dropping
+                // the pipe closes it
+                let _ = stdin.write_all(json.as_bytes()).await;
+            }
+        };
+        // Dropping the child on timeout kills it
+        let run = async { tokio::join!(write, child.wait_with_output()).1 };
+        tokio::time::timeout(self.timeout, run)
+            .await
+            .map_err(|_| {
+                failure(
+                    format!(
+                        "'{}' killed after running for {}s",
+                        self.program,
+                        self.timeout.as_secs()
+                    ),
+                    true,
+                )
+            })?
+            .map_err(|e| failure(format!("'{}' failed: {e}", self.program), true))
+    }
+}
+
+impl Transport for ExecAction {
+    async fn attempt(&self, payload: Payload<'_>, attempt: Attempt) -> Result<(), RetryableError> {
+        let json = serde_json::to_string(&payload.data(attempt))
+            .map_err(|e| failure(e.to_string(), false))?;
+        let output = self
+            .run(self.command(payload, attempt, &json), &json)
+            .await?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            tracing::info!("{}: {line}", self.program);
+        }
+        for line in stderr.lines() {
+            tracing::warn!("{}: {line}", self.program);
+        }
+        if output.status.success() {
+            return Ok(());
+        }
+        let reason = format!("'{}' failed with {}", self.program, output.status);
+        Err(failure(
+            match stderr.lines().rfind(|line| !line.trim().is_empty()) {
+                Some(line) => format!("{reason}: {}", line.trim()),
+                None => reason,
+            },
+            true,
+        ))
+    }
+}
+
+const fn failure(reason: String, retryable: bool) -> RetryableError {
+    RetryableError::Transport { reason, retryable }
+}
+
+/// The environment variables describing a delivery, except the payload.
+fn variables(payload: Payload<'_>, attempt: Attempt) -> Vec<(&'static str, String)> {
+    let mut variables = vec![
+        (
+            "DDNS_A_EVENT",
+            payload.event().unwrap_or("address").to_string(),
+        ),
+        ("DDNS_A_ATTEMPT", attempt.number.to_string()),
+    ];
+    if let Payload::Ip(changes) = payload {
+        let added = changes.iter().filter(|c| c.is_added());
+        let first = |v6: bool| {
+            added
+                .clone()
+                .find(|c| c.address.is_ipv6() == v6)
+                .map_or_else(String::new, |c| c.address.to_string())
+        };
+        variables.extend([
+            ("DDNS_A_ADDED", addresses(added.clone())),
+            (
+                "DDNS_A_REMOVED",
+                addresses(changes.iter().filter(|c| !c.is_added())),
+            ),
+            ("DDNS_A_ADDED_V4", first(false)),
+            ("DDNS_A_ADDED_V6", first(true)),
+        ]);
+    }
+    variables
+}
+
+fn addresses<'a>(changes: impl Iterator<Item = &'a IpChange>) -> String {
+    changes
+        .map(|c| c.address.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,197 @@
+//! Tests for the exec action, running `sh` scripts.
+
+use std::time::{Duration, SystemTime};
+
+use tempfile::TempDir;
+
+use crate::monitor::{HostnameChange, IpChange};
+use crate::time::InstantSleeper;
+use crate::webhook::{
+    Attempt, ExecAction, ExecInput, ExecSender, IsRetryable, Payload, RetryPolicy, RetryableError,
+    Transport, WebhookError, WebhookSender,
+};
+
+const FIRST: Attempt = Attempt {
+    number: 1,
+    elapsed: Duration::ZERO,
+};
+
+fn changes() -> Vec<IpChange> {
+    let at = SystemTime::UNIX_EPOCH;
+    vec![
+        IpChange::removed("eth0", "192.0.2.1".parse().unwrap(), at),
+        IpChange::added("eth0", "2001:db8::1".parse().unwrap(), at),
+        IpChange::added("eth0", "192.0.2.2".parse().unwrap(), at),
+        IpChange::added("eth0", "192.0.2.3".parse().unwrap(), at),
+    ]
+}
+
+/// An action running `script` with `sh`, writing what it prints to `out`.
+fn script(dir: &TempDir, script: &str) -> ExecAction {
+    let out = dir.path().join("out");
+    ExecAction::new("sh").with_args(["-c", &format!("{{ {script} ; }} > '{}'", out.display())])
+}
+
+fn output(dir: &TempDir) -> String {
+    std::fs::read_to_string(dir.path().join("out")).unwrap()
+}
+
+#[tokio::test]
+async fn passes_addresses_in_variables() {
+    let dir = TempDir::new().unwrap();
+    let action = script(
+        &dir,
+        r#"echo "$DDNS_A_EVENT|$DDNS_A_ATTEMPT|$DDNS_A_ADDED|$DDNS_A_REMOVED|$DDNS_A_ADDED_V4|$DDNS_A_ADDED_V6""#,
+    );
+
+    action
+        .attempt(Payload::Ip(&changes()), FIRST)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        output(&dir),
+        "address|1|2001:db8::1 192.0.2.2 192.0.2.3|192.0.2.1|192.0.2.2|2001:db8::1\n"
+    );
+}
+
+#[tokio::test]
+async fn passes_payload_in_variable() {
+    let dir = TempDir::new().unwrap();
+    let action = script(&dir, r#"printf '%s' "$DDNS_A_PAYLOAD""#);
+
+    action
+        .attempt(Payload::Ip(&changes()), FIRST)
+        .await
+        .unwrap();
+
+    let payload: serde_json::Value = serde_json::from_str(&output(&dir)).unwrap();
+    assert_eq!(payload["changes"][1]["address"], "2001:db8::1");
+    assert_eq!(payload["attempt"], 1);
+}
+
+#[tokio::test]
+async fn stdin_input_passes_payload_on_stdin() {
+    let dir = TempDir::new().unwrap();
+    let action =
+        script(&dir, r#"cat; printf '[%s]' "$DDNS_A_PAYLOAD""#).with_input(ExecInput::Stdin);
+
+    action
+        .attempt(Payload::Ip(&changes()), FIRST)
+        .await
+        .unwrap();
+
+    let printed = output(&dir);
+    let (json, variable) = printed.split_at(printed.len() - 2);
+    assert_eq!(variable, "[]");
+    let payload: serde_json::Value = serde_json::from_str(json).unwrap();
+    assert_eq!(payload["changes"][0]["kind"], "removed");
+}
+
+#[tokio::test]
+async fn other_events_name_their_event() {
+    let dir = TempDir::new().unwrap();
+    let action = script(&dir, r#"echo "$DDNS_A_EVENT|${DDNS_A_ADDED-unset}""#);
+    let change = HostnameChange {
+        old: "old".to_string(),
+        new: "new".to_string(),
+        timestamp: SystemTime::UNIX_EPOCH,
+    };
+
+    action
+        .attempt(Payload::Hostname(&[change]), FIRST)
+        .await
+        .unwrap();
+
+    assert_eq!(output(&dir), "hostname|unset\n");
+}
+
+#[tokio::test]
+async fn non_zero_exit_is_retryable_and_reports_stderr() {
+    let action =
+        ExecAction::new("sh").with_args(["-c", "echo 'first' >&2; echo 'REFUSED' >&2; exit 3"]);
+
+    let error = action
+        .attempt(Payload::Ip(&changes()), FIRST)
+        .await
+        .unwrap_err();
+
+    assert!(error.is_retryable());
+    let message = error.to_string();
+    assert!(
+        message.contains("'sh' failed with exit status: 3: REFUSED"),
+        "{message}"
+    );
+}
+
+#[tokio::test]
+async fn missing_program_is_not_retryable() {
+    let action = ExecAction::new("/nonexistent/ddns-a-hook");
+
+    let error = action
+        .attempt(Payload::Ip(&changes()), FIRST)
+        .await
+        .unwrap_err();
+
+    assert!(!error.is_retryable());
+    assert!(
+        error
+            .to_string()
+            .contains("cannot run '/nonexistent/ddns-a-hook'")
+    );
+}
+
+#[tokio::test]
+async fn slow_command_is_killed() {
+    let action = ExecAction::new("sh")
+        .with_args(["-c", "sleep 5"])
+        .with_timeout(Duration::from_millis(100));
+
+    let error = action
+        .attempt(Payload::Ip(&changes()), FIRST)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        RetryableError::Transport {
+            retryable: true,
+            ..
+        }
+    ));
+    assert!(
+        error.to_string().contains("killed after running"),
+        "{error}"
+    );
+}
+
+#[tokio::test]
+async fn sender_retries_failed_runs() {
+    let dir = TempDir::new().unwrap();
+    // Fails until the second attempt
+    let action = script(
+        &dir,
+        r#"echo "$DDNS_A_ATTEMPT"; test "$DDNS_A_ATTEMPT" -ge 2"#,
+    );
+    let sender = ExecSender::new(action)
+        .with_sleeper(InstantSleeper)
+        .with_retry_policy(RetryPolicy::new().with_max_attempts(3));
+
+    sender.send(&changes()).await.unwrap();
+
+    assert_eq!(output(&dir), "2\n");
+}
+
+#[tokio::test]
+async fn sender_gives_up_after_max_attempts() {
+    let sender = ExecSender::new(ExecAction::new("false"))
+        .with_sleeper(InstantSleeper)
+        .with_retry_policy(RetryPolicy::new().with_max_attempts(2));
+
+    let error = sender.send(&changes()).await.unwrap_err();
+
+    assert!(matches!(
+        error,
+        WebhookError::MaxRetriesExceeded { attempts: 2, .. }
+    ));
+}
@@ -0,0 +1,375 @@
+//! Transport-agnostic delivery with retries.
+//!
+//! A [`Transport`] makes single delivery attempts: it turns a [`Payload`]
+//! into whatever its backend sends and reports the outcome.
+//! [`RetryingSender`] makes any transport a [`WebhookSender`] that retries
+//! with exponential backoff and stops on shutdown, so every backend shares
+//! one retry loop. [`HttpWebhook`](super::HttpWebhook) is the HTTP transport
+//! under the same loop, and library users can plug in their own:
+//!
+//! ```
+//! use ddns_a::webhook::{Attempt, Payload, RetryableError, RetryingSender, Transport};
+//!
+//! /// Prints every delivery as JSON.
+//! struct Stdout;
+//!
+//! impl Transport for Stdout {
+//!     async fn attempt(&self, payload: Payload<'_>, attempt: Attempt) -> Result<(), RetryableError> {
+//!         let body = serde_json::to_string(&payload.data(attempt)).map_err(|e| {
+//!             RetryableError::Transport { reason: e.to_string(), retryable: false }
+//!         })?;
+//!         println!("{body}");
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let sender = RetryingSender::new(Stdout);
+//! ```
+//!
+//! Address changes are delivered as one batch per [`send`](WebhookSender::send)
+//! unless the sender's [`DeliveryMode`] is [`PerChange`](DeliveryMode::PerChange).
+//!
+//! The built-in transports beside HTTP live in submodules: commands
+//! ([`ExecAction`]), MQTT ([`MqttPublisher`]), email ([`SmtpMailer`]) and the
+//! chat presets ([`ChatPreset`]) sent through [`HttpWebhook`](super::HttpWebhook).
+
+use std::time::Duration;
+
+use crate::monitor::{IpChange, summarize};
+use crate::time::{Clock, ShutdownToken, Sleeper, SystemClock, TokioSleeper};
+
+use super::payload::{Attempt, Event, Payload};
+use super::{HttpError, RetryPolicy, RetryableError, WebhookError, WebhookSender};
+
+mod chat;
+mod email;
+mod exec;
+mod mqtt;
+
+#[cfg(test)]
+mod chat_tests;
+#[cfg(test)]
+mod email_tests;
+#[cfg(all(test, unix))]
+mod exec_tests;
+#[cfg(test)]
+mod mqtt_tests;
+
+pub use chat::{ChatPreset, ChatService};
+pub use email::{DEFAULT_EMAIL_TIMEOUT, EmailSender, SmtpMailer};
+pub use exec::{DEFAULT_EXEC_TIMEOUT, ExecAction, ExecInput, ExecSender};
+pub use mqtt::{DEFAULT_MQTT_TIMEOUT, MqttPublisher, MqttQos, MqttSender};
+
+/// A notification backend making single delivery attempts.
+///
+/// Retries, backoff and shutdown are left to [`RetryingSender`]; a
+/// transport only sends one attempt and says whether it failed, and how.
+pub trait Transport: Send + Sync {
+    /// Makes one attempt to deliver `payload`.
+    ///
+    /// A transport with nothing to send for a payload (e.g. an event its
+    /// backend has no place for) returns `Ok(())`.
+    ///
+    /// # Errors
+    ///
+    /// Returns why the attempt failed; [`IsRetryable`] decides whether
+    /// another attempt follows.
+    fn attempt(
+        &self,
+        payload: Payload<'_>,
+        attempt: Attempt,
+    ) -> impl std::future::Future<Output = Result<(), RetryableError>> + Send;
+}
+
+/// How address changes are split into deliveries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DeliveryMode {
+    /// Every batch is one delivery (default).
+    #[default]
+    Batched,
+    /// Every change is its own delivery, for endpoints accepting one
+    /// address per request.
+    ///
+    /// Changes are delivered one after another in batch order, each with
+    /// its own retries; a failed change does not hold back the next one.
+    /// Failures are combined into one [`WebhookError::Changes`], and
+    /// shutdown leaves the rest of the batch unsent.
+    PerChange,
+}
+
+/// A [`WebhookSender`] retrying the attempts of a [`Transport`].
+///
+/// Failed attempts are retried per the [`RetryPolicy`] while their error
+/// [is retryable](IsRetryable); the first other error ends the delivery.
+/// With [`RetryingSender::with_shutdown`], a triggered [`ShutdownToken`]
+/// ends a delivery between attempts, including during a retry delay, with
+/// [`WebhookError::Interrupted`].
+///
+/// # Type Parameters
+///
+/// - `T`: The transport
+/// - `S`: The sleeper implementation for retry delays (defaults to [`TokioSleeper`])
+/// - `C`: The clock used to measure elapsed time (defaults to [`SystemClock`])
+#[derive(Debug)]
+pub struct RetryingSender<T, S = TokioSleeper, C = SystemClock> {
+    transport: T,
+    sleeper: S,
+    clock: C,
+    retry_policy: RetryPolicy,
+    shutdown: Option<ShutdownToken>,
+    delivery_mode: DeliveryMode,
+}
+
+impl<T> RetryingSender<T, TokioSleeper, SystemClock> {
+    /// Wraps `transport` with the default retry policy.
+    #[must_use]
+    pub fn new(transport: T) -> Self {
+        Self {
+            transport,
+            sleeper: TokioSleeper,
+            clock: SystemClock,
+            retry_policy: RetryPolicy::default(),
+            shutdown: None,
+            delivery_mode: DeliveryMode::default(),
+        }
+    }
+}
+
+impl<T, S, C> RetryingSender<T, S, C> {
+    /// Sets a custom sleeper for retry delays.
+    ///
+    /// This is primarily useful for testing to avoid actual delays.
+    #[must_use]
+    pub fn with_sleeper<S2>(self, sleeper: S2) -> RetryingSender<T, S2, C> {
+        RetryingSender {
+            transport: self.transport,
+            sleeper,
+            clock: self.clock,
+            retry_policy: self.retry_policy,
+            shutdown: self.shutdown,
+            delivery_mode: self.delivery_mode,
+        }
+    }
+
+    /// Sets a custom clock for measuring elapsed time across attempts.
+    #[must_use]
+    pub fn with_clock<C2>(self, clock: C2) -> RetryingSender<T, S, C2> {
+        RetryingSender {
+            transport: self.transport,
+            sleeper: self.sleeper,
+            clock,
+            retry_policy: self.retry_policy,
+            shutdown: self.shutdown,
+            delivery_mode: self.delivery_mode,
+        }
+    }
+
+    /// Sets the retry policy.
+    #[must_use]
+    pub const fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Stops retrying once `shutdown` is triggered.
+    #[must_use]
+    pub fn with_shutdown(mut self, shutdown: ShutdownToken) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    /// Sets how address changes are split into deliveries.
+    #[must_use]
+    pub const fn with_delivery_mode(mut self, mode: DeliveryMode) -> Self {
+        self.delivery_mode = mode;
+        self
+    }
+
+    /// Returns the transport.
+    #[must_use]
+    pub const fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Returns the transport for configuration by a wrapping sender.
+    pub(super) const fn transport_mut(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
+    /// Returns the configured retry policy.
+    #[must_use]
+    pub const fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+}
+
+impl<T: Transport, S: Sleeper, C: Clock> RetryingSender<T, S, C> {
+    /// Sleeps for `delay`, returning false if shutdown cut the sleep short.
+    async fn backoff(&self, delay: Duration) -> bool {
+        let Some(shutdown) = &self.shutdown else {
+            self.sleeper.sleep(delay).await;
+            return true;
+        };
+        tokio::select! {
+            () = self.sleeper.sleep(delay) => true,
+            () = shutdown.triggered() => false,
+        }
+    }
+
+    /// Delivers `payload`, retrying failed attempts.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WebhookError::MaxRetriesExceeded`] once every attempt
+    /// failed, [`WebhookError::Retryable`] for an error that is not
+    /// retried, and [`WebhookError::Interrupted`] on shutdown.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the retry policy allows no attempt, which
+    /// [`RetryPolicy::with_max_attempts`] rules out.
+    pub async fn deliver(&self, payload: Payload<'_>) -> Result<(), WebhookError> {
+        let started = self.clock.now();
+        let mut last_error: Option<RetryableError> = None;
+
+        for attempt in 1..=self.retry_policy.max_attempts {
+            if self
+                .shutdown
+                .as_ref()
+                .is_some_and(ShutdownToken::is_triggered)
+            {
+                return Err(WebhookError::Interrupted {
+                    attempts: attempt - 1,
+                });
+            }
+            // A clock stepping backwards reports zero rather than failing the delivery
+            let elapsed = self.clock.now().duration_since(started).unwrap_or_default();
+            let attempt_at = Attempt {
+                number: attempt,
+                elapsed,
+            };
+
+            match self.transport.attempt(payload, attempt_at).await {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    // Non-retryable errors fail immediately
+                    if !e.is_retryable() {
+                        return Err(e.into());
+                    }
+
+                    let retry_after = e.retry_after();
+                    last_error = Some(e);
+
+                    // Don't sleep after the last attempt
+                    if self.retry_policy.should_retry(attempt) {
+                        let delay = self.retry_policy.delay_honoring(attempt - 1, retry_after);
+                        if retry_after.is_some() {
+                            tracing::debug!(
+                                "Server asked to retry after {retry_after:?}, waiting {delay:?}"
+                            );
+                        }
+                        if !self.backoff(delay).await {
+                            return Err(WebhookError::Interrupted { attempts: attempt });
+                        }
+                    }
+                }
+            }
+        }
+
+        Err(WebhookError::MaxRetriesExceeded {
+            attempts: self.retry_policy.max_attempts,
+            last_error: last_error.expect("max_attempts >= 1 ensures at least one attempt"),
+        })
+    }
+
+    /// Delivers address `changes` as the [`DeliveryMode`] splits them.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of a batch, or of the only change, as
+    /// [`deliver`](Self::deliver) does; with several changes delivered
+    /// one by one, [`WebhookError::Changes`] for those that failed.
+    pub async fn deliver_changes(&self, changes: &[IpChange]) -> Result<(), WebhookError> {
+        if self.delivery_mode == DeliveryMode::Batched || changes.len() == 1 {
+            return self.deliver(Payload::Ip(changes)).await;
+        }
+        let mut failures = Vec::new();
+        for change in changes {
+            let single = std::slice::from_ref(change);
+            if let Err(error) = self.deliver(Payload::Ip(single)).await {
+                let interrupted = error.is_interrupted();
+                failures.push((summarize(single).concat(), error));
+                // Later changes must not overtake this one
+                if interrupted {
+                    break;
+                }
+            }
+        }
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(WebhookError::Changes {
+                total: changes.len(),
+                failures,
+            })
+        }
+    }
+}
+
+impl<T: Transport, S: Sleeper, C: Clock> WebhookSender for RetryingSender<T, S, C> {
+    async fn send(&self, changes: &[IpChange]) -> Result<(), WebhookError> {
+        self.deliver_changes(changes).await
+    }
+
+    async fn send_event(&self, event: &Event<'_>) -> Result<(), WebhookError> {
+        self.deliver(Payload::from(*event)).await
+    }
+}
+
+/// Extension trait for checking if an error is retryable.
+///
+/// Determines whether an error represents a transient failure that
+/// warrants a retry attempt. Used by [`RetryingSender`] to decide whether
+/// to continue retrying after a failure.
+pub trait IsRetryable {
+    /// Returns true if the error is potentially transient and should be retried.
+    fn is_retryable(&self) -> bool;
+}
+
+impl IsRetryable for HttpError {
+    fn is_retryable(&self) -> bool {
+        match self {
+            // Network errors are typically transient
+            Self::Connection(_) | Self::Timeout => true,
+            // URL errors are configuration issues, not transient; an
+            // oversized body will be as large next time
+            Self::InvalidUrl(_) | Self::ResponseTooLarge { .. } => false,
+        }
+    }
+}
+
+impl IsRetryable for RetryableError {
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::Http(e) => e.is_retryable(),
+            // A wrong echo may be a stale cached answer; the retry has a new nonce
+            Self::EchoMismatch { .. } => true,
+            // Server errors (5xx) are typically transient
+            // Rate limiting (429) is retryable
+            // Some 4xx (408 Request Timeout) are retryable
+            Self::NonSuccessStatus { status, .. } => {
+                status.is_server_error()
+                    || *status == http::StatusCode::TOO_MANY_REQUESTS
+                    || *status == http::StatusCode::REQUEST_TIMEOUT
+            }
+            // Template errors and rejected updates are configuration issues
+            Self::Template(_)
+            | Self::Rejected { .. }
+            | Self::Capture { .. }
+            | Self::Token { .. } => false,
+            Self::AssertionFailed { retryable, .. } | Self::Transport { retryable, .. } => {
+                *retryable
+            }
+        }
+    }
+}
@@ -0,0 +1,494 @@
+//! Publishing deliveries to an MQTT broker (`[mqtt]`).
+//!
+//! [`MqttPublisher`] is a [`Transport`] publishing each attempt as one MQTT
+//! 3.1.1 message, so an [`MqttSender`] retries failed publishes like an
+//! [`HttpWebhook`](crate::webhook::HttpWebhook) retries failed requests. Every attempt
+//! opens a clean session, publishes and disconnects: changes are rare, and
+//! no connection is held open between them.
+//!
+//! Address changes are published to the configured topic, other events to
+//! a subtopic named after them (e.g. `ddns-a/dns`). The message is the
+//! rendered body template if set; otherwise address changes carry the
+//! [`PayloadFormat`] document and other events the template variables as
+//! JSON.
+//!
+//! An attempt fails if the broker cannot be reached, refuses the connection
+//! or does not acknowledge the message within the timeout. Refusals for
+//! credentials, authorization or the client identifier are not retried.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use rustls::pki_types::ServerName;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+use url::Url;
+
+use crate::monitor::{HostnameSource, SystemHostname};
+use crate::time::{SystemClock, TokioSleeper};
+
+use crate::webhook::payload::render_template;
+use crate::webhook::payload::{Attempt, Payload};
+use crate::webhook::{
+    PayloadFormat, RetryableError, RetryingSender, TlsSettings, Transport, tls_config,
+};
+
+/// Default time an attempt may take from connecting to the acknowledgement.
+pub const DEFAULT_MQTT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Keep-alive announced to the broker, in seconds; sessions are far shorter.
+const KEEP_ALIVE_SECS: u16 = 30;
+
+/// Identifier of the published message; every session is new.
+const PACKET_ID: u16 = 1;
+
+/// A [`RetryingSender`] publishing with an [`MqttPublisher`].
+pub type MqttSender<S = TokioSleeper, C = SystemClock> = RetryingSender<MqttPublisher, S, C>;
+
+/// MQTT delivery guarantee of published messages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MqttQos {
+    /// Fire and forget (level 0).
+    AtMostOnce,
+    /// Acknowledged by the broker (level 1, default).
+    #[default]
+    AtLeastOnce,
+    /// Acknowledged in a four-way handshake (level 2).
+    ExactlyOnce,
+}
+
+impl MqttQos {
+    /// Returns the guarantee of numeric `level`, if valid.
+    #[must_use]
+    pub const fn from_level(level: u8) -> Option<Self> {
+        match level {
+            0 => Some(Self::AtMostOnce),
+            1 => Some(Self::AtLeastOnce),
+            2 => Some(Self::ExactlyOnce),
+            _ => None,
+        }
+    }
+
+    /// Returns the numeric level.
+    #[must_use]
+    pub const fn level(self) -> u8 {
+        match self {
+            Self::AtMostOnce => 0,
+            Self::AtLeastOnce => 1,
+            Self::ExactlyOnce => 2,
+        }
+    }
+}
+
+/// Publishes every delivery attempt to an MQTT broker.
+#[derive(Debug, Clone)]
+pub struct MqttPublisher {
+    broker: Url,
+    topic: String,
+    qos: MqttQos,
+    retain: bool,
+    client_id: String,
+    credentials: Option<(String, String)>,
+    tls: TlsSettings,
+    body_template: Option<String>,
+    payload_format: PayloadFormat,
+    host: String,
+    timeout: Duration,
+}
+
+impl MqttPublisher {
+    /// Creates a publisher to `topic` on `broker`, an `mqtt://` or (with
+    /// TLS) `mqtts://` URL; the port defaults to 1883 or 8883.
+    #[must_use]
+    pub fn new(broker: Url, topic: impl Into<String>) -> Self {
+        let host = SystemHostname.hostname().unwrap_or_default();
+        Self {
+            broker,
+            topic: topic.into(),
+            qos: MqttQos::default(),
+            retain: false,
+            client_id: format!("ddns-a-{host}"),
+            credentials: None,
+            tls: TlsSettings::default(),
+            body_template: None,
+            payload_format: PayloadFormat::default(),
+            host,
+            timeout: DEFAULT_MQTT_TIMEOUT,
+        }
+    }
+
+    /// Sets the delivery guarantee.
+    #[must_use]
+    pub const fn with_qos(mut self, qos: MqttQos) -> Self {
+        self.qos = qos;
+        self
+    }
+
+    /// Asks the broker to keep the last message for new subscribers.
+    #[must_use]
+    pub const fn with_retain(mut self, retain: bool) -> Self {
+        self.retain = retain;
+        self
+    }
+
+    /// Sets the client identifier (default: `ddns-a-<hostname>`).
+    #[must_use]
+    pub fn with_client_id(mut self, client_id: impl Into<String>) -> Self {
+        self.client_id = client_id.into();
+        self
+    }
+
+    /// Authenticates with a username and password.
+    #[must_use]
+    pub fn with_credentials(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.credentials = Some((username.into(), password.into()));
+        self
+    }
+
+    /// Sets the TLS settings of `mqtts://` brokers.
+    #[must_use]
+    pub fn with_tls(mut self, tls: TlsSettings) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Publishes address changes as `template`, rendered with the template
+    /// variables.
+    #[must_use]
+    pub fn with_body_template(mut self, template: impl Into<String>) -> Self {
+        self.body_template = Some(template.into());
+        self
+    }
+
+    /// Sets the document published for address changes without a template.
+    #[must_use]
+    pub const fn with_payload_format(mut self, format: PayloadFormat) -> Self {
+        self.payload_format = format;
+        self
+    }
+
+    /// Sets the hostname reported in the default document.
+    #[must_use]
+    pub fn with_host(mut self, host: impl Into<String>) -> Self {
+        self.host = host.into();
+        self
+    }
+
+    /// Sets the time an attempt may take.
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the broker URL.
+    #[must_use]
+    pub const fn broker(&self) -> &Url {
+        &self.broker
+    }
+
+    /// Returns the topic of address changes.
+    #[must_use]
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// Returns the delivery guarantee.
+    #[must_use]
+    pub const fn qos(&self) -> MqttQos {
+        self.qos
+    }
+
+    /// Returns whether messages are retained.
+    #[must_use]
+    pub const fn retain(&self) -> bool {
+        self.retain
+    }
+
+    /// Returns the client identifier.
+    #[must_use]
+    pub fn client_id(&self) -> &str {
+        &self.client_id
+    }
+
+    /// Returns the time an attempt may take.
+    #[must_use]
+    pub const fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Builds the topic and message of an attempt.
+    fn message(
+        &self,
+        payload: Payload<'_>,
+        attempt: Attempt,
+    ) -> Result<(String, Vec<u8>), RetryableError> {
+        let data = payload.data(attempt);
+        let json = || {
+            serde_json::to_vec(&data).map_err(|e| RetryableError::Transport {
+                reason: e.to_string(),
+                retryable: false,
+            })
+        };
+        match (payload, payload.event()) {
+            (Payload::Ip(changes), _) => {
+                let body = match &self.body_template {
+                    Some(template) => render_template(template, &data)?.into_bytes(),
+                    None => match self.payload_format.document(changes, &self.host) {
+                        Some(document) => document,
+                        None => json()?,
+                    },
+                };
+                Ok((self.topic.clone(), body))
+            }
+            (_, event) => Ok((
+                format!("{}/{}", self.topic, event.unwrap_or_default()),
+                json()?,
+            )),
+        }
+    }
+
+    /// Connects to the broker and publishes `message` to `topic`.
+    async fn publish_to_broker(&self, topic: &str, message: &[u8]) -> Result<(), RetryableError> {
+        let tls = self.broker.scheme() == "mqtts";
+        let host = self
+            .broker
+            .host_str()
+            .unwrap_or_default()
+            .trim_start_matches('[')
+            .trim_end_matches(']');
+        let port = self.broker.port().unwrap_or(if tls { 8883 } else { 1883 });
+
+        let stream = TcpStream::connect((host, port))
+            .await
+            .map_err(|e| failure(format!("cannot connect to {host}:{port}: {e}"), true))?;
+        if !tls {
+            return self.publish(stream, topic, message).await;
+        }
+
+        let mut config = tls_config(&self.tls, None)
+            .map_err(|e| failure(format!("TLS configuration: {e}"), false))?;
+        config.alpn_protocols.clear();
+        let name = ServerName::try_from(host.to_string())
+            .map_err(|e| failure(format!("invalid TLS server name '{host}': {e}"), false))?;
+        let stream = TlsConnector::from(Arc::new(config))
+            .connect(name, stream)
+            .await
+            .map_err(|e| {
+                failure(
+                    format!("TLS handshake with {host}:{port} failed: {e}"),
+                    true,
+                )
+            })?;
+        self.publish(stream, topic, message).await
+    }
+
+    /// Publishes `message` over `stream` in one session.
+    async fn publish<S>(
+        &self,
+        mut stream: S,
+        topic: &str,
+        message: &[u8],
+    ) -> Result<(), RetryableError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let credentials = self
+            .credentials
+            .as_ref()
+            .map(|(username, password)| (username.as_str(), password.as_str()));
+        write(&mut stream, &connect_packet(&self.client_id, credentials)).await?;
+        let (kind, body) = read_packet(&mut stream).await?;
+        if kind != CONNACK {
+            return Err(unexpected(kind, "CONNACK"));
+        }
+        check_connack(&body)?;
+
+        write(
+            &mut stream,
+            &publish_packet(topic, message, self.qos, self.retain),
+        )
+        .await?;
+        match self.qos {
+            MqttQos::AtMostOnce => {}
+            MqttQos::AtLeastOnce => expect_ack(&mut stream, PUBACK, "PUBACK").await?,
+            MqttQos::ExactlyOnce => {
+                expect_ack(&mut stream, PUBREC, "PUBREC").await?;
+                write(&mut stream, &ack_packet(PUBREL | 0x02)).await?;
+                expect_ack(&mut stream, PUBCOMP, "PUBCOMP").await?;
+            }
+        }
+
+        // The message is delivered; a failed goodbye changes nothing
+        let _ = write(&mut stream, &[DISCONNECT, 0]).await;
+        let _ = stream.shutdown().await;
+        Ok(())
+    }
+}
+
+impl Transport for MqttPublisher {
+    async fn attempt(&self, payload: Payload<'_>, attempt: Attempt) -> Result<(), RetryableError> {
+        let (topic, message) = self.message(payload, attempt)?;
+        tokio::time::timeout(self.timeout, self.publish_to_broker(&topic, &message))
+            .await
+            .unwrap_or_else(|_| {
+                Err(failure(
+                    format!(
+                        "no answer from {} within {}s",
+                        self.broker,
+                        self.timeout.as_secs()
+                    ),
+                    true,
+                ))
+            })
+    }
+}
+
+const CONNECT: u8 = 0x10;
+const CONNACK: u8 = 0x20;
+const PUBLISH: u8 = 0x30;
+const PUBACK: u8 = 0x40;
+const PUBREC: u8 = 0x50;
+const PUBREL: u8 = 0x60;
+const PUBCOMP: u8 = 0x70;
+const DISCONNECT: u8 = 0xE0;
+
+const fn failure(reason: String, retryable: bool) -> RetryableError {
+    RetryableError::Transport { reason, retryable }
+}
+
+#[allow(clippy::needless_pass_by_value)] // Used with map_err
+fn io(error: std::io::Error) -> RetryableError {
+    failure(format!("connection to the broker failed: {error}"), true)
+}
+
+fn unexpected(kind: u8, expected: &str) -> RetryableError {
+    failure(
+        format!(
+            "broker sent packet type {} instead of {expected}",
+            kind >> 4
+        ),
+        true,
+    )
+}
+
+/// The CONNECT packet of a clean session.
+fn connect_packet(client_id: &str, credentials: Option<(&str, &str)>) -> Vec<u8> {
+    let mut body = Vec::new();
+    put_bytes(&mut body, b"MQTT");
+    // Protocol level 4 (3.1.1), clean session
+    let mut flags = 0x02;
+    if credentials.is_some() {
+        flags |= 0xC0;
+    }
+    body.extend([4, flags]);
+    body.extend(KEEP_ALIVE_SECS.to_be_bytes());
+    put_bytes(&mut body, client_id.as_bytes());
+    if let Some((username, password)) = credentials {
+        put_bytes(&mut body, username.as_bytes());
+        put_bytes(&mut body, password.as_bytes());
+    }
+    packet(CONNECT, &body)
+}
+
+/// The PUBLISH packet of `message`.
+fn publish_packet(topic: &str, message: &[u8], qos: MqttQos, retain: bool) -> Vec<u8> {
+    let mut body = Vec::with_capacity(topic.len() + message.len() + 4);
+    put_bytes(&mut body, topic.as_bytes());
+    if qos != MqttQos::AtMostOnce {
+        body.extend(PACKET_ID.to_be_bytes());
+    }
+    body.extend_from_slice(message);
+    packet(PUBLISH | (qos.level() << 1) | u8::from(retain), &body)
+}
+
+/// An acknowledgement packet of [`PACKET_ID`].
+fn ack_packet(header: u8) -> Vec<u8> {
+    packet(header, &PACKET_ID.to_be_bytes())
+}
+
+fn packet(header: u8, body: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(body.len() + 5);
+    packet.push(header);
+    // Remaining length: 7 bits per byte, least significant first
+    let mut remaining = body.len();
+    loop {
+        #[allow(clippy::cast_possible_truncation)] // Below 128
+        let byte = (remaining % 128) as u8;
+        remaining /= 128;
+        if remaining == 0 {
+            packet.push(byte);
+            break;
+        }
+        packet.push(byte | 0x80);
+    }
+    packet.extend_from_slice(body);
+    packet
+}
+
+/// Appends `bytes` with their two-byte length.
+fn put_bytes(buffer: &mut Vec<u8>, bytes: &[u8]) {
+    let len = u16::try_from(bytes.len()).unwrap_or(u16::MAX);
+    buffer.extend(len.to_be_bytes());
+    buffer.extend_from_slice(&bytes[..usize::from(len)]);
+}
+
+async fn write<S: AsyncWrite + Unpin>(stream: &mut S, bytes: &[u8]) -> Result<(), RetryableError> {
+    stream.write_all(bytes).await.map_err(io)?;
+    stream.flush().await.map_err(io)
+}
+
+/// Reads a packet, returning its type (the high nibble) and body.
+async fn read_packet<S: AsyncRead + Unpin>(
+    stream: &mut S,
+) -> Result<(u8, Vec<u8>), RetryableError> {
+    let header = stream.read_u8().await.map_err(io)?;
+    let mut len = 0_usize;
+    for shift in (0..28).step_by(7) {
+        let byte = stream.read_u8().await.map_err(io)?;
+        len |= usize::from(byte & 0x7F) << shift;
+        if byte & 0x80 == 0 {
+            let mut body = vec![0; len];
+            stream.read_exact(&mut body).await.map_err(io)?;
+            return Ok((header & 0xF0, body));
+        }
+    }
+    Err(failure("malformed packet length".to_string(), true))
+}
+
+async fn expect_ack<S: AsyncRead + Unpin>(
+    stream: &mut S,
+    kind: u8,
+    name: &str,
+) -> Result<(), RetryableError> {
+    let (received, body) = read_packet(stream).await?;
+    if received != kind {
+        return Err(unexpected(received, name));
+    }
+    if body.get(..2) != Some(&PACKET_ID.to_be_bytes()[..]) {
+        return Err(failure(format!("{name} for another message"), true));
+    }
+    Ok(())
+}
+
+/// Fails unless the CONNACK accepted the connection.
+fn check_connack(body: &[u8]) -> Result<(), RetryableError> {
+    let (reason, retryable) = match body.get(1) {
+        Some(0) => return Ok(()),
+        Some(1) => ("unacceptable protocol version", false),
+        Some(2) => ("client identifier rejected", false),
+        Some(3) => ("server unavailable", true),
+        Some(4) => ("bad username or password", false),
+        Some(5) => ("not authorized", false),
+        _ => ("malformed CONNACK", true),
+    };
+    Err(failure(
+        format!("broker refused the connection: {reason}"),
+        retryable,
+    ))
+}
@@ -0,0 +1,246 @@
+//! Tests for the MQTT publisher against a scripted local broker.
+
+use std::time::{Duration, SystemTime};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use url::Url;
+
+use crate::monitor::{HostnameChange, IpChange};
+use crate::time::InstantSleeper;
+use crate::webhook::{
+    Attempt, IsRetryable, MqttPublisher, MqttQos, MqttSender, Payload, PayloadFormat, RetryPolicy,
+    Transport, WebhookError, WebhookSender,
+};
+
+const FIRST: Attempt = Attempt {
+    number: 1,
+    elapsed: Duration::ZERO,
+};
+
+fn changes() -> Vec<IpChange> {
+    vec![IpChange::added(
+        "eth0",
+        "192.0.2.1".parse().unwrap(),
+        SystemTime::UNIX_EPOCH,
+    )]
+}
+
+/// A packet as received by the broker: first header byte and body.
+type Packet = (u8, Vec<u8>);
+
+async fn read_packet(stream: &mut TcpStream) -> Option<Packet> {
+    let header = stream.read_u8().await.ok()?;
+    let (mut len, mut shift) = (0_usize, 0);
+    loop {
+        let byte = stream.read_u8().await.ok()?;
+        len |= usize::from(byte & 0x7F) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            break;
+        }
+    }
+    let mut body = vec![0; len];
+    stream.read_exact(&mut body).await.ok()?;
+    Some((header, body))
+}
+
+/// Serves one session, answering CONNECT with `return_code` and every
+/// PUBLISH or PUBREL as its delivery guarantee asks; returns the packets
+/// received.
+async fn broker(return_code: u8) -> (Url, JoinHandle<Vec<Packet>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = Url::parse(&format!("mqtt://{}", listener.local_addr().unwrap())).unwrap();
+    let session = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut received = Vec::new();
+        while let Some((header, body)) = read_packet(&mut stream).await {
+            let answer = match header & 0xF0 {
+                0x10 => Some(vec![0x20, 2, 0, return_code]),
+                0x30 => match (header >> 1) & 0x03 {
+                    1 => Some(vec![0x40, 2, 0, 1]),
+                    2 => Some(vec![0x50, 2, 0, 1]),
+                    _ => None,
+                },
+                0x60 => Some(vec![0x70, 2, 0, 1]),
+                _ => None,
+            };
+            received.push((header, body));
+            if let Some(answer) = answer {
+                stream.write_all(&answer).await.unwrap();
+            }
+        }
+        received
+    });
+    (url, session)
+}
+
+/// Topic and message of a PUBLISH body.
+fn split_publish(header: u8, body: &[u8]) -> (String, Vec<u8>) {
+    let len = usize::from(u16::from_be_bytes([body[0], body[1]]));
+    let topic = String::from_utf8(body[2..2 + len].to_vec()).unwrap();
+    let id_len = if header & 0x06 == 0 { 0 } else { 2 };
+    (topic, body[2 + len + id_len..].to_vec())
+}
+
+#[tokio::test]
+async fn publishes_the_address_document() {
+    let (url, session) = broker(0).await;
+    let publisher = MqttPublisher::new(url, "home/ddns")
+        .with_host("nas")
+        .with_retain(true);
+
+    publisher
+        .attempt(Payload::Ip(&changes()), FIRST)
+        .await
+        .unwrap();
+
+    let packets = session.await.unwrap();
+    let kinds: Vec<_> = packets.iter().map(|(header, _)| header & 0xF0).collect();
+    assert_eq!(kinds, [0x10, 0x30, 0xE0]);
+    let (header, body) = &packets[1];
+    // QoS 1, retained
+    assert_eq!(header & 0x0F, 0b0011);
+    let (topic, message) = split_publish(*header, body);
+    assert_eq!(topic, "home/ddns");
+    let message: serde_json::Value = serde_json::from_slice(&message).unwrap();
+    assert_eq!(message["version"], 1);
+    assert_eq!(message["host"], "nas");
+    assert_eq!(message["changes"][0]["address"], "192.0.2.1");
+}
+
+#[tokio::test]
+async fn connect_carries_client_id_and_credentials() {
+    let (url, session) = broker(0).await;
+    let publisher = MqttPublisher::new(url, "t")
+        .with_client_id("router")
+        .with_credentials("ha", "secret");
+
+    publisher
+        .attempt(Payload::Ip(&changes()), FIRST)
+        .await
+        .unwrap();
+
+    let packets = session.await.unwrap();
+    let connect = &packets[0].1;
+    // Protocol name, level 4, username + password + clean session
+    assert_eq!(&connect[..8], b"\x00\x04MQTT\x04\xC2");
+    assert_eq!(&connect[10..], b"\x00\x06router\x00\x02ha\x00\x06secret");
+}
+
+#[tokio::test]
+async fn other_events_go_to_a_subtopic() {
+    let (url, session) = broker(0).await;
+    let publisher = MqttPublisher::new(url, "home/ddns").with_qos(MqttQos::AtMostOnce);
+    let change = HostnameChange {
+        old: "old".to_string(),
+        new: "new".to_string(),
+        timestamp: SystemTime::UNIX_EPOCH,
+    };
+
+    publisher
+        .attempt(Payload::Hostname(&[change]), FIRST)
+        .await
+        .unwrap();
+
+    let packets = session.await.unwrap();
+    let (header, body) = &packets[1];
+    let (topic, message) = split_publish(*header, body);
+    assert_eq!(topic, "home/ddns/hostname");
+    let message: serde_json::Value = serde_json::from_slice(&message).unwrap();
+    assert_eq!(message["hostname_changes"][0]["hostname"], "new");
+}
+
+#[tokio::test]
+async fn none_format_publishes_the_variables() {
+    let (url, session) = broker(0).await;
+    let publisher = MqttPublisher::new(url, "t").with_payload_format(PayloadFormat::None);
+
+    publisher
+        .attempt(Payload::Ip(&changes()), FIRST)
+        .await
+        .unwrap();
+
+    let packets = session.await.unwrap();
+    let (_, message) = split_publish(packets[1].0, &packets[1].1);
+    let message: serde_json::Value = serde_json::from_slice(&message).unwrap();
+    assert_eq!(message["schema_version"], 1);
+}
+
+#[cfg(feature = "templates")]
+#[tokio::test]
+async fn body_template_is_rendered() {
+    let (url, session) = broker(0).await;
+    let publisher = MqttPublisher::new(url, "t").with_body_template("{{first_added_v4 changes}}");
+
+    publisher
+        .attempt(Payload::Ip(&changes()), FIRST)
+        .await
+        .unwrap();
+
+    let packets = session.await.unwrap();
+    let (_, message) = split_publish(packets[1].0, &packets[1].1);
+    assert_eq!(message, b"192.0.2.1");
+}
+
+#[tokio::test]
+async fn exactly_once_completes_the_handshake() {
+    let (url, session) = broker(0).await;
+    let publisher = MqttPublisher::new(url, "t").with_qos(MqttQos::ExactlyOnce);
+
+    publisher
+        .attempt(Payload::Ip(&changes()), FIRST)
+        .await
+        .unwrap();
+
+    let headers: Vec<_> = session.await.unwrap().iter().map(|(h, _)| *h).collect();
+    assert_eq!(headers, [0x10, 0x34, 0x62, 0xE0]);
+}
+
+#[tokio::test]
+async fn refused_credentials_are_not_retried() {
+    let (url, _session) = broker(4).await;
+    let publisher = MqttPublisher::new(url, "t");
+
+    let error = publisher
+        .attempt(Payload::Ip(&changes()), FIRST)
+        .await
+        .unwrap_err();
+
+    assert!(!error.is_retryable());
+    assert!(
+        error.to_string().contains("bad username or password"),
+        "{error}"
+    );
+}
+
+#[tokio::test]
+async fn unavailable_broker_is_retried() {
+    let (url, _session) = broker(3).await;
+    let sender = MqttSender::new(MqttPublisher::new(url, "t"))
+        .with_sleeper(InstantSleeper)
+        .with_retry_policy(RetryPolicy::new().with_max_attempts(1));
+
+    let error = sender.send(&changes()).await.unwrap_err();
+
+    let WebhookError::MaxRetriesExceeded { last_error, .. } = error else {
+        panic!("unexpected error: {error:?}");
+    };
+    assert!(last_error.is_retryable());
+}
+
+#[tokio::test]
+async fn silent_broker_times_out() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = Url::parse(&format!("mqtt://{}", listener.local_addr().unwrap())).unwrap();
+    let publisher = MqttPublisher::new(url, "t").with_timeout(Duration::from_millis(100));
+
+    let error = publisher
+        .attempt(Payload::Ip(&changes()), FIRST)
+        .await
+        .unwrap_err();
+
+    assert!(error.is_retryable());
+    assert!(error.to_string().contains("no answer from"), "{error}");
+}
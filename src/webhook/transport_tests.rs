@@ -0,0 +1,326 @@
+//! Tests for `RetryingSender` over a scripted transport.
+
+use super::{
+    Attempt, DeliveryMode, Event, IsRetryable, Payload, RetryPolicy, RetryableError,
+    RetryingSender, Transport, WebhookError, WebhookSender,
+};
+use crate::monitor::{HostnameChange, IpChange};
+use crate::time::{Clock, InstantSleeper, ShutdownToken};
+use std::collections::VecDeque;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+/// Transport answering attempts from a script (success once it runs out)
+/// and recording each attempt with its event.
+#[derive(Default)]
+struct ScriptedTransport {
+    results: Mutex<VecDeque<Result<(), RetryableError>>>,
+    attempts: Mutex<Vec<(Option<&'static str>, Attempt)>>,
+    addresses: Mutex<Vec<Vec<IpAddr>>>,
+}
+
+impl ScriptedTransport {
+    fn new(results: impl IntoIterator<Item = Result<(), RetryableError>>) -> Self {
+        Self {
+            results: Mutex::new(results.into_iter().collect()),
+            attempts: Mutex::default(),
+            addresses: Mutex::default(),
+        }
+    }
+
+    fn attempts(&self) -> Vec<(Option<&'static str>, Attempt)> {
+        self.attempts.lock().unwrap().clone()
+    }
+
+    /// Addresses of each address attempt.
+    fn addresses(&self) -> Vec<Vec<IpAddr>> {
+        self.addresses.lock().unwrap().clone()
+    }
+}
+
+impl Transport for ScriptedTransport {
+    async fn attempt(&self, payload: Payload<'_>, attempt: Attempt) -> Result<(), RetryableError> {
+        self.attempts
+            .lock()
+            .unwrap()
+            .push((payload.event(), attempt));
+        if let Payload::Ip(changes) = payload {
+            let addresses = changes.iter().map(|c| c.address).collect();
+            self.addresses.lock().unwrap().push(addresses);
+        }
+        self.results.lock().unwrap().pop_front().unwrap_or(Ok(()))
+    }
+}
+
+/// Clock advancing one second on every read.
+#[derive(Default)]
+struct SteppingClock(AtomicU64);
+
+impl Clock for SteppingClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(self.0.fetch_add(1, Ordering::SeqCst))
+    }
+}
+
+fn failure(retryable: bool) -> Result<(), RetryableError> {
+    Err(RetryableError::Transport {
+        reason: "broker unavailable".to_string(),
+        retryable,
+    })
+}
+
+fn sender(transport: ScriptedTransport) -> RetryingSender<ScriptedTransport, InstantSleeper> {
+    RetryingSender::new(transport)
+        .with_sleeper(InstantSleeper)
+        .with_retry_policy(RetryPolicy::new().with_max_attempts(3))
+}
+
+fn changes() -> Vec<IpChange> {
+    vec![IpChange::added(
+        "eth0",
+        "192.0.2.1".parse().unwrap(),
+        SystemTime::UNIX_EPOCH,
+    )]
+}
+
+mod retries {
+    use super::*;
+
+    #[tokio::test]
+    async fn first_success_ends_delivery() {
+        let sender = sender(ScriptedTransport::default());
+
+        sender.send(&changes()).await.unwrap();
+
+        assert_eq!(sender.transport().attempts().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn retryable_failure_is_retried() {
+        let sender = sender(ScriptedTransport::new([failure(true), failure(true)]))
+            .with_clock(SteppingClock::default());
+
+        sender.send(&changes()).await.unwrap();
+
+        let attempts = sender.transport().attempts();
+        let numbers: Vec<_> = attempts.iter().map(|(_, a)| a.number).collect();
+        assert_eq!(numbers, [1, 2, 3]);
+        // The clock is read once at the start and once per attempt
+        assert_eq!(attempts[2].1.elapsed, Duration::from_secs(3));
+    }
+
+    #[tokio::test]
+    async fn non_retryable_failure_fails_at_once() {
+        let sender = sender(ScriptedTransport::new([failure(false)]));
+
+        let result = sender.send(&changes()).await;
+
+        assert!(matches!(
+            result,
+            Err(WebhookError::Retryable(RetryableError::Transport {
+                retryable: false,
+                ..
+            }))
+        ));
+        assert_eq!(sender.transport().attempts().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn exhausted_attempts_return_last_error() {
+        let sender = sender(ScriptedTransport::new([
+            failure(true),
+            failure(true),
+            failure(true),
+        ]));
+
+        let result = sender.send(&changes()).await;
+
+        assert!(matches!(
+            result,
+            Err(WebhookError::MaxRetriesExceeded {
+                attempts: 3,
+                last_error: RetryableError::Transport { .. },
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn shutdown_stops_before_first_attempt() {
+        let shutdown = ShutdownToken::new();
+        shutdown.trigger();
+        let sender = sender(ScriptedTransport::default()).with_shutdown(shutdown);
+
+        let result = sender.send(&changes()).await;
+
+        assert!(matches!(
+            result,
+            Err(WebhookError::Interrupted { attempts: 0 })
+        ));
+        assert!(sender.transport().attempts().is_empty());
+    }
+}
+
+mod events {
+    use super::*;
+
+    #[tokio::test]
+    async fn each_event_reaches_the_transport() {
+        let sender = sender(ScriptedTransport::default());
+        let rename = HostnameChange {
+            old: "old-pc".to_string(),
+            new: "new-pc".to_string(),
+            timestamp: SystemTime::UNIX_EPOCH,
+        };
+
+        sender.send(&changes()).await.unwrap();
+        sender.send_event(&Event::Dns(&[])).await.unwrap();
+        sender.send_event(&Event::Drift(&[])).await.unwrap();
+        sender
+            .send_event(&Event::Hostname(&[rename]))
+            .await
+            .unwrap();
+
+        let events: Vec<_> = sender
+            .transport()
+            .attempts()
+            .into_iter()
+            .map(|(event, _)| event)
+            .collect();
+        assert_eq!(events, [None, Some("dns"), Some("drift"), Some("hostname")]);
+    }
+
+    #[test]
+    fn transport_errors_follow_their_flag() {
+        assert!(failure(true).unwrap_err().is_retryable());
+        assert!(!failure(false).unwrap_err().is_retryable());
+    }
+}
+
+mod per_change {
+    use super::*;
+
+    fn batch() -> Vec<IpChange> {
+        ["192.0.2.1", "192.0.2.2", "192.0.2.3"]
+            .into_iter()
+            .map(|address| {
+                IpChange::added("eth0", address.parse().unwrap(), SystemTime::UNIX_EPOCH)
+            })
+            .collect()
+    }
+
+    fn addresses(list: &[&[&str]]) -> Vec<Vec<IpAddr>> {
+        list.iter()
+            .map(|batch| batch.iter().map(|a| a.parse().unwrap()).collect())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn batched_by_default() {
+        let sender = sender(ScriptedTransport::default());
+
+        sender.send(&batch()).await.unwrap();
+
+        assert_eq!(
+            sender.transport().addresses(),
+            addresses(&[&["192.0.2.1", "192.0.2.2", "192.0.2.3"]])
+        );
+    }
+
+    #[tokio::test]
+    async fn changes_are_sent_one_by_one_in_order() {
+        let sender =
+            sender(ScriptedTransport::default()).with_delivery_mode(DeliveryMode::PerChange);
+
+        sender.send(&batch()).await.unwrap();
+
+        assert_eq!(
+            sender.transport().addresses(),
+            addresses(&[&["192.0.2.1"], &["192.0.2.2"], &["192.0.2.3"]])
+        );
+    }
+
+    #[tokio::test]
+    async fn failures_are_combined_and_do_not_stop_the_batch() {
+        // The first change is retried once, the second fails for good
+        let transport = ScriptedTransport::new([failure(true), Ok(()), failure(false)]);
+        let sender = sender(transport).with_delivery_mode(DeliveryMode::PerChange);
+
+        let error = sender.send(&batch()).await.unwrap_err();
+
+        let WebhookError::Changes {
+            total,
+            ref failures,
+        } = error
+        else {
+            panic!("unexpected error: {error:?}");
+        };
+        assert_eq!(total, 3);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, "eth0: +192.0.2.2 added");
+        assert!(
+            error
+                .to_string()
+                .starts_with("1 of 3 changes failed: eth0: +192.0.2.2 added: "),
+            "{error}"
+        );
+        assert_eq!(
+            sender.transport().addresses(),
+            addresses(&[
+                &["192.0.2.1"],
+                &["192.0.2.1"],
+                &["192.0.2.2"],
+                &["192.0.2.3"]
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn single_change_error_is_returned_as_is() {
+        let sender = sender(ScriptedTransport::new([failure(false)]))
+            .with_delivery_mode(DeliveryMode::PerChange);
+
+        let result = sender.send(&changes()).await;
+
+        assert!(matches!(result, Err(WebhookError::Retryable(_))));
+    }
+
+    /// Transport triggering shutdown while failing its second attempt.
+    struct ShuttingDown {
+        shutdown: ShutdownToken,
+        calls: AtomicU64,
+    }
+
+    impl Transport for ShuttingDown {
+        async fn attempt(&self, _: Payload<'_>, _: Attempt) -> Result<(), RetryableError> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Ok(());
+            }
+            self.shutdown.trigger();
+            failure(true)
+        }
+    }
+
+    #[tokio::test]
+    async fn shutdown_leaves_the_rest_unsent() {
+        let shutdown = ShutdownToken::new();
+        let transport = ShuttingDown {
+            shutdown: shutdown.clone(),
+            calls: AtomicU64::default(),
+        };
+        let sender = RetryingSender::new(transport)
+            .with_sleeper(InstantSleeper)
+            .with_delivery_mode(DeliveryMode::PerChange)
+            .with_shutdown(shutdown);
+
+        let error = sender.send(&batch()).await.unwrap_err();
+
+        assert!(error.is_interrupted(), "{error:?}");
+        let WebhookError::Changes { failures, .. } = error else {
+            panic!("unexpected error: {error:?}");
+        };
+        assert_eq!(failures.len(), 1);
+        assert_eq!(sender.transport().calls.load(Ordering::SeqCst), 2);
+    }
+}